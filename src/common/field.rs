@@ -0,0 +1,81 @@
+//! Word field instructions shared by .doc, .docx and RTF.
+//!
+//! All three formats store fields as an instruction (`HYPERLINK "url"`)
+//! followed by the text Word last displayed for it.
+
+/// Get the target of a `HYPERLINK` field instruction.
+///
+/// Returns the URL, `#bookmark` for a `\l` link to a bookmark, or both
+/// (`url#bookmark`). Returns `None` for other fields.
+///
+/// # Examples
+/// ```
+/// use litchi::common::field::hyperlink_target;
+///
+/// assert_eq!(
+///     hyperlink_target(r#" HYPERLINK "https://example.com" \o "Tip" "#).as_deref(),
+///     Some("https://example.com")
+/// );
+/// assert_eq!(hyperlink_target(r#"HYPERLINK \l "_Toc1""#).as_deref(), Some("#_Toc1"));
+/// assert_eq!(hyperlink_target("PAGE"), None);
+/// ```
+pub fn hyperlink_target(instruction: &str) -> Option<String> {
+    let mut args = arguments(instruction.trim_start()).into_iter();
+    if !args.next()?.eq_ignore_ascii_case("HYPERLINK") {
+        return None;
+    }
+
+    let mut url = None;
+    let mut anchor = None;
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "\\l" => anchor = args.next(),
+            // Switches taking an argument: tooltip, target frame
+            "\\o" | "\\t" => {
+                args.next();
+            },
+            switch if switch.starts_with('\\') => {},
+            _ if url.is_none() => url = Some(arg),
+            _ => {},
+        }
+    }
+
+    match (url, anchor) {
+        (Some(url), Some(anchor)) => Some(format!("{}#{}", url, anchor)),
+        (Some(url), None) => Some(url),
+        (None, Some(anchor)) => Some(format!("#{}", anchor)),
+        (None, None) => None,
+    }
+}
+
+/// Split an instruction into words and quoted arguments.
+pub(crate) fn arguments(instruction: &str) -> Vec<String> {
+    let mut args = Vec::new();
+    let mut chars = instruction.chars().peekable();
+    while let Some(&c) = chars.peek() {
+        if c.is_whitespace() {
+            chars.next();
+        } else if c == '"' {
+            chars.next();
+            let mut arg = String::new();
+            for c in chars.by_ref() {
+                if c == '"' {
+                    break;
+                }
+                arg.push(c);
+            }
+            args.push(arg);
+        } else {
+            let mut arg = String::new();
+            while let Some(&c) = chars.peek() {
+                if c.is_whitespace() || c == '"' {
+                    break;
+                }
+                arg.push(c);
+                chars.next();
+            }
+            args.push(arg);
+        }
+    }
+    args
+}
@@ -0,0 +1,327 @@
+//! Format-independent representation of pictures embedded in documents.
+//!
+//! Every container stores its pictures differently: BLIP records in OLE
+//! streams, media parts in OOXML packages, `Pictures/` in ODF packages,
+//! `\pict` groups in RTF and `Data/` in iWork bundles. [`EmbeddedImage`] is
+//! the common shape returned by the `images()` methods on
+//! [`Document`](crate::Document), [`Presentation`](crate::Presentation) and
+//! [`sheet::Workbook`](crate::sheet::Workbook), whatever the source format.
+
+/// Where an embedded image is attached in its document.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ImageAnchor {
+    /// Inside the paragraph with this 0-based index (documents)
+    Paragraph(usize),
+    /// On the slide with this 0-based index (presentations)
+    Slide(usize),
+    /// Anchored at a cell, given as 0-based row and column (workbooks)
+    Cell {
+        /// Worksheet name
+        sheet: String,
+        /// 0-based row index
+        row: u32,
+        /// 0-based column index
+        column: u32,
+    },
+    /// The format does not record where the image is placed
+    Unanchored,
+}
+
+/// A picture extracted from a document, presentation or workbook.
+///
+/// The raw bytes are kept exactly as stored in the file (after any container
+/// level decompression), so `data` can be written straight to disk using
+/// [`extension()`](Self::extension). MIME type and pixel dimensions are
+/// sniffed from the image header.
+#[derive(Debug, Clone)]
+pub struct EmbeddedImage {
+    /// Name of the image inside the container (e.g. `image1.png`)
+    pub name: String,
+    /// MIME type detected from the image data (e.g. `image/png`)
+    pub mime_type: &'static str,
+    /// Encoded image bytes
+    pub data: Vec<u8>,
+    /// Width in pixels, when it can be determined
+    pub width: Option<u32>,
+    /// Height in pixels, when it can be determined
+    pub height: Option<u32>,
+    /// Where the image is placed in the document
+    pub anchor: ImageAnchor,
+    /// Alternative text (description or title) attached to the picture
+    pub alt_text: Option<String>,
+}
+
+impl EmbeddedImage {
+    /// Create an image, detecting MIME type and dimensions from `data`.
+    ///
+    /// When the header is not recognized the MIME type falls back to the
+    /// extension of `name`, then to `application/octet-stream`.
+    pub fn new(name: impl Into<String>, data: Vec<u8>, anchor: ImageAnchor) -> Self {
+        let name = name.into();
+        let mime_type = sniff_mime_type(&data)
+            .or_else(|| mime_type_from_name(&name))
+            .unwrap_or("application/octet-stream");
+        let (width, height) = match sniff_dimensions(&data) {
+            Some((w, h)) => (Some(w), Some(h)),
+            None => (None, None),
+        };
+
+        Self {
+            name,
+            mime_type,
+            data,
+            width,
+            height,
+            anchor,
+            alt_text: None,
+        }
+    }
+
+    /// Set the alternative text, ignoring empty strings.
+    pub fn with_alt_text(mut self, alt_text: Option<String>) -> Self {
+        self.alt_text = alt_text.filter(|text| !text.trim().is_empty());
+        self
+    }
+
+    /// File extension matching the MIME type (without the dot).
+    pub fn extension(&self) -> &'static str {
+        match self.mime_type {
+            "image/png" => "png",
+            "image/jpeg" => "jpg",
+            "image/gif" => "gif",
+            "image/bmp" => "bmp",
+            "image/tiff" => "tiff",
+            "image/webp" => "webp",
+            "image/svg+xml" => "svg",
+            "image/x-emf" => "emf",
+            "image/x-wmf" => "wmf",
+            "image/x-pict" => "pict",
+            "image/heic" => "heic",
+            "application/pdf" => "pdf",
+            _ => "bin",
+        }
+    }
+
+    /// Whether the image is a vector metafile (EMF, WMF or PICT).
+    pub fn is_metafile(&self) -> bool {
+        matches!(
+            self.mime_type,
+            "image/x-emf" | "image/x-wmf" | "image/x-pict"
+        )
+    }
+
+    /// Convert the image to another raster format.
+    ///
+    /// Metafiles are rendered with the EMF/WMF/PICT converters; bitmaps are
+    /// decoded and re-encoded. When only one of `width` and `height` is given
+    /// the aspect ratio is preserved.
+    #[cfg(feature = "imgconv")]
+    pub fn convert(
+        &self,
+        format: image::ImageFormat,
+        width: Option<u32>,
+        height: Option<u32>,
+    ) -> super::Result<Vec<u8>> {
+        use crate::images;
+
+        match self.mime_type {
+            "image/x-emf" => images::emf::convert_emf(&self.data, format, width, height),
+            "image/x-wmf" => images::wmf::convert_wmf(&self.data, format, width, height),
+            "image/x-pict" => images::pict::convert_pict(&self.data, format, width, height),
+            "image/svg+xml" | "application/pdf" | "application/octet-stream" => Err(
+                super::Error::Unsupported(format!("Cannot rasterize {}", self.mime_type)),
+            ),
+            _ => images::convert_bitmap(&self.data, format, width, height),
+        }
+    }
+
+    /// Convert the image to PNG.
+    #[cfg(feature = "imgconv")]
+    pub fn to_png(&self, width: Option<u32>, height: Option<u32>) -> super::Result<Vec<u8>> {
+        self.convert(image::ImageFormat::Png, width, height)
+    }
+}
+
+/// Detect the MIME type of encoded image data from its signature.
+pub fn sniff_mime_type(data: &[u8]) -> Option<&'static str> {
+    if data.starts_with(b"\x89PNG\r\n\x1a\n") {
+        Some("image/png")
+    } else if data.starts_with(&[0xFF, 0xD8, 0xFF]) {
+        Some("image/jpeg")
+    } else if data.starts_with(b"GIF87a") || data.starts_with(b"GIF89a") {
+        Some("image/gif")
+    } else if data.starts_with(b"BM") && data.len() >= 26 {
+        Some("image/bmp")
+    } else if data.starts_with(b"II*\0") || data.starts_with(b"MM\0*") {
+        Some("image/tiff")
+    } else if data.len() >= 12 && &data[0..4] == b"RIFF" && &data[8..12] == b"WEBP" {
+        Some("image/webp")
+    } else if data.len() >= 44 && data[0..4] == [1, 0, 0, 0] && &data[40..44] == b" EMF" {
+        Some("image/x-emf")
+    } else if data.starts_with(&[0xD7, 0xCD, 0xC6, 0x9A])
+        || (data.len() >= 4 && matches!(data[0..4], [1, 0, 9, 0] | [2, 0, 9, 0]))
+    {
+        Some("image/x-wmf")
+    } else if data.starts_with(b"%PDF") {
+        Some("application/pdf")
+    } else if is_svg(data) {
+        Some("image/svg+xml")
+    } else if data.len() >= 12
+        && &data[4..8] == b"ftyp"
+        && matches!(&data[8..12], b"heic" | b"heix" | b"mif1")
+    {
+        Some("image/heic")
+    } else {
+        None
+    }
+}
+
+/// Guess a MIME type from a file name extension.
+pub fn mime_type_from_name(name: &str) -> Option<&'static str> {
+    let extension = name.rsplit_once('.')?.1.to_ascii_lowercase();
+    Some(match extension.as_str() {
+        "png" => "image/png",
+        "jpg" | "jpeg" | "jpe" => "image/jpeg",
+        "gif" => "image/gif",
+        "bmp" | "dib" => "image/bmp",
+        "tif" | "tiff" => "image/tiff",
+        "webp" => "image/webp",
+        "svg" => "image/svg+xml",
+        "emf" | "emz" => "image/x-emf",
+        "wmf" | "wmz" => "image/x-wmf",
+        "pct" | "pict" => "image/x-pict",
+        "heic" | "heif" => "image/heic",
+        "pdf" => "application/pdf",
+        _ => return None,
+    })
+}
+
+/// Read the pixel dimensions from an image header without decoding it.
+///
+/// Metafile dimensions are derived from their bounds: EMF bounds are in
+/// device pixels, placeable WMF bounds are scaled from logical units to
+/// 96 DPI.
+pub fn sniff_dimensions(data: &[u8]) -> Option<(u32, u32)> {
+    let be32 = |at: usize| -> Option<u32> {
+        Some(u32::from_be_bytes(data.get(at..at + 4)?.try_into().ok()?))
+    };
+    let le32 = |at: usize| -> Option<i32> {
+        Some(i32::from_le_bytes(data.get(at..at + 4)?.try_into().ok()?))
+    };
+    let le16 = |at: usize| -> Option<i16> {
+        Some(i16::from_le_bytes(data.get(at..at + 2)?.try_into().ok()?))
+    };
+
+    match sniff_mime_type(data)? {
+        "image/png" => Some((be32(16)?, be32(20)?)),
+        "image/jpeg" => jpeg_dimensions(data),
+        "image/gif" => Some((le16(6)? as u16 as u32, le16(8)? as u16 as u32)),
+        "image/bmp" => Some((le32(18)?.unsigned_abs(), le32(22)?.unsigned_abs())),
+        "image/x-emf" => {
+            let width = le32(16)?.checked_sub(le32(8)?)?.checked_add(1)?;
+            let height = le32(20)?.checked_sub(le32(12)?)?.checked_add(1)?;
+            (width > 0 && height > 0).then_some((width as u32, height as u32))
+        },
+        "image/x-wmf" if data.starts_with(&[0xD7, 0xCD, 0xC6, 0x9A]) => {
+            let units_per_inch = le16(14)? as u16 as i32;
+            if units_per_inch == 0 {
+                return None;
+            }
+            let width = (le16(10)? as i32 - le16(6)? as i32).abs() * 96 / units_per_inch;
+            let height = (le16(12)? as i32 - le16(8)? as i32).abs() * 96 / units_per_inch;
+            (width > 0 && height > 0).then_some((width as u32, height as u32))
+        },
+        _ => None,
+    }
+}
+
+/// Walk JPEG markers to the first start-of-frame segment.
+fn jpeg_dimensions(data: &[u8]) -> Option<(u32, u32)> {
+    let mut pos = 2;
+    while pos + 4 <= data.len() {
+        if data[pos] != 0xFF {
+            return None;
+        }
+        let marker = data[pos + 1];
+        // Fill bytes and standalone markers carry no length
+        if marker == 0xFF {
+            pos += 1;
+            continue;
+        }
+        if matches!(marker, 0x01 | 0xD0..=0xD7) {
+            pos += 2;
+            continue;
+        }
+        let length = u16::from_be_bytes([data[pos + 2], data[pos + 3]]) as usize;
+        let is_frame = matches!(marker, 0xC0..=0xCF) && !matches!(marker, 0xC4 | 0xC8 | 0xCC);
+        if is_frame {
+            let segment = data.get(pos + 5..pos + 9)?;
+            let height = u16::from_be_bytes([segment[0], segment[1]]) as u32;
+            let width = u16::from_be_bytes([segment[2], segment[3]]) as u32;
+            return Some((width, height));
+        }
+        pos += 2 + length;
+    }
+    None
+}
+
+fn is_svg(data: &[u8]) -> bool {
+    let head = &data[..data.len().min(512)];
+    let text = String::from_utf8_lossy(head);
+    let trimmed = text.trim_start_matches('\u{feff}').trim_start();
+    (trimmed.starts_with("<?xml") || trimmed.starts_with("<svg")) && text.contains("<svg")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_png_header() {
+        let mut png = b"\x89PNG\r\n\x1a\n\0\0\0\x0dIHDR".to_vec();
+        png.extend_from_slice(&640u32.to_be_bytes());
+        png.extend_from_slice(&480u32.to_be_bytes());
+
+        let image = EmbeddedImage::new("image1.png", png, ImageAnchor::Slide(2));
+        assert_eq!(image.mime_type, "image/png");
+        assert_eq!(image.extension(), "png");
+        assert_eq!((image.width, image.height), (Some(640), Some(480)));
+        assert_eq!(image.anchor, ImageAnchor::Slide(2));
+    }
+
+    #[test]
+    fn test_jpeg_frame_after_app_segment() {
+        let jpeg = [
+            0xFF, 0xD8, // SOI
+            0xFF, 0xE0, 0x00, 0x04, 0x00, 0x00, // APP0 with two payload bytes
+            0xFF, 0xC0, 0x00, 0x0B, 0x08, 0x00, 0x20, 0x00, 0x40, // SOF0 64x32
+        ];
+        assert_eq!(sniff_mime_type(&jpeg), Some("image/jpeg"));
+        assert_eq!(sniff_dimensions(&jpeg), Some((64, 32)));
+    }
+
+    #[test]
+    fn test_placeable_wmf_scaled_to_96_dpi() {
+        let mut wmf = vec![0xD7, 0xCD, 0xC6, 0x9A, 0, 0];
+        for value in [0i16, 0, 1440, 720, 1440] {
+            wmf.extend_from_slice(&value.to_le_bytes());
+        }
+        assert_eq!(sniff_mime_type(&wmf), Some("image/x-wmf"));
+        assert_eq!(sniff_dimensions(&wmf), Some((96, 48)));
+    }
+
+    #[test]
+    fn test_fallback_to_name_and_alt_text() {
+        let image = EmbeddedImage::new("picture.pct", vec![0; 16], ImageAnchor::Unanchored)
+            .with_alt_text(Some("  ".to_string()));
+        assert_eq!(image.mime_type, "image/x-pict");
+        assert!(image.is_metafile());
+        assert_eq!(image.alt_text, None);
+
+        let unknown = EmbeddedImage::new("blob", vec![0; 4], ImageAnchor::Paragraph(0))
+            .with_alt_text(Some("Chart".to_string()));
+        assert_eq!(unknown.mime_type, "application/octet-stream");
+        assert_eq!(unknown.extension(), "bin");
+        assert_eq!(unknown.alt_text.as_deref(), Some("Chart"));
+    }
+}
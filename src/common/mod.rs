@@ -9,6 +9,9 @@ pub mod detection;
 #[cfg(any(feature = "ole", feature = "rtf"))]
 pub mod encoding;
 pub mod error;
+#[cfg(any(feature = "ole", feature = "ooxml", feature = "rtf"))]
+pub mod field;
+pub mod image;
 pub mod metadata;
 pub mod shapes;
 pub mod simd;
@@ -17,6 +20,7 @@ pub mod style;
 // Re-exports for convenience
 pub use detection::{FileFormat, detect_file_format, detect_file_format_from_bytes};
 pub use error::{Error, Result};
+pub use image::{EmbeddedImage, ImageAnchor};
 pub use metadata::Metadata;
 pub use shapes::{PlaceholderType, ShapeType};
-pub use style::{Length, RGBColor, VerticalPosition};
+pub use style::{Length, ListFormat, ListInfo, RGBColor, TextAlignment, VerticalPosition};
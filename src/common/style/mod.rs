@@ -10,4 +10,4 @@ pub mod text;
 // Re-exports
 pub use color::RGBColor;
 pub use len::Length;
-pub use text::VerticalPosition;
+pub use text::{ListFormat, ListInfo, TextAlignment, VerticalPosition};
@@ -0,0 +1,31 @@
+//! Paragraph alignment shared across formats.
+
+/// Horizontal alignment of a paragraph.
+///
+/// Used to report paragraph alignment uniformly for OLE (.doc),
+/// OOXML (.docx) and RTF documents.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum TextAlignment {
+    /// Left aligned (start of line)
+    #[default]
+    Left,
+    /// Centered
+    Center,
+    /// Right aligned (end of line)
+    Right,
+    /// Justified (full width)
+    Justify,
+}
+
+impl TextAlignment {
+    /// Get the CSS `text-align` value for this alignment.
+    #[inline]
+    pub fn as_css(&self) -> &'static str {
+        match self {
+            TextAlignment::Left => "left",
+            TextAlignment::Center => "center",
+            TextAlignment::Right => "right",
+            TextAlignment::Justify => "justify",
+        }
+    }
+}
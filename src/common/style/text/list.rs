@@ -0,0 +1,201 @@
+//! List membership shared across formats.
+//!
+//! Word processors store list membership as a reference into a numbering
+//! definition (`w:numPr` in OOXML, `sprmPIlfo` in .doc, `\ls` in RTF,
+//! `text:list` in ODF). This module holds the resolved result: the nesting
+//! level and the numbering format of that level.
+
+/// Numbering format of a list level.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ListFormat {
+    /// Bullet character
+    #[default]
+    Bullet,
+    /// 1, 2, 3
+    Decimal,
+    /// a, b, c
+    LowerLetter,
+    /// A, B, C
+    UpperLetter,
+    /// i, ii, iii
+    LowerRoman,
+    /// I, II, III
+    UpperRoman,
+    /// No marker
+    None,
+}
+
+impl ListFormat {
+    /// Map a number format code (`nfc`) as used by .doc and RTF.
+    pub fn from_nfc(nfc: u8) -> Self {
+        match nfc {
+            0 | 22 => ListFormat::Decimal,
+            1 => ListFormat::UpperRoman,
+            2 => ListFormat::LowerRoman,
+            3 => ListFormat::UpperLetter,
+            4 => ListFormat::LowerLetter,
+            23 => ListFormat::Bullet,
+            255 => ListFormat::None,
+            // Ordinals, cardinal text and the East Asian formats are numbered
+            _ => ListFormat::Decimal,
+        }
+    }
+
+    /// Map an OOXML `w:numFmt` value.
+    pub fn from_ooxml(num_fmt: &str) -> Self {
+        match num_fmt {
+            "bullet" => ListFormat::Bullet,
+            "lowerLetter" => ListFormat::LowerLetter,
+            "upperLetter" => ListFormat::UpperLetter,
+            "lowerRoman" => ListFormat::LowerRoman,
+            "upperRoman" => ListFormat::UpperRoman,
+            "none" => ListFormat::None,
+            _ => ListFormat::Decimal,
+        }
+    }
+
+    /// Get the OOXML `w:numFmt` value.
+    pub fn to_ooxml(&self) -> &'static str {
+        match self {
+            ListFormat::Bullet => "bullet",
+            ListFormat::Decimal => "decimal",
+            ListFormat::LowerLetter => "lowerLetter",
+            ListFormat::UpperLetter => "upperLetter",
+            ListFormat::LowerRoman => "lowerRoman",
+            ListFormat::UpperRoman => "upperRoman",
+            ListFormat::None => "none",
+        }
+    }
+
+    /// Map an ODF `style:num-format` value; an empty format means no marker.
+    pub fn from_odf(num_format: &str) -> Self {
+        match num_format {
+            "a" => ListFormat::LowerLetter,
+            "A" => ListFormat::UpperLetter,
+            "i" => ListFormat::LowerRoman,
+            "I" => ListFormat::UpperRoman,
+            "" => ListFormat::None,
+            _ => ListFormat::Decimal,
+        }
+    }
+
+    /// Whether items are numbered rather than bulleted.
+    #[inline]
+    pub fn is_ordered(&self) -> bool {
+        !matches!(self, ListFormat::Bullet | ListFormat::None)
+    }
+
+    /// Format an item number as its label, such as "3", "c" or "iii".
+    ///
+    /// Letters repeat past "z" ("aa", "bb") as in Word, and Roman numerals
+    /// above 3999 fall back to decimal. Returns `None` for bullets and lists
+    /// without a marker.
+    pub fn label(&self, number: u32) -> Option<String> {
+        let label = match self {
+            ListFormat::Bullet | ListFormat::None => return None,
+            ListFormat::Decimal => number.to_string(),
+            ListFormat::LowerLetter => letter_label(number),
+            ListFormat::UpperLetter => letter_label(number).to_ascii_uppercase(),
+            ListFormat::LowerRoman => roman_label(number).to_ascii_lowercase(),
+            ListFormat::UpperRoman => roman_label(number),
+        };
+        Some(label)
+    }
+}
+
+fn letter_label(number: u32) -> String {
+    if number == 0 {
+        return number.to_string();
+    }
+    let letter = char::from(b'a' + ((number - 1) % 26) as u8);
+    std::iter::repeat_n(letter, ((number - 1) / 26 + 1) as usize).collect()
+}
+
+fn roman_label(number: u32) -> String {
+    const NUMERALS: [(u32, &str); 13] = [
+        (1000, "M"),
+        (900, "CM"),
+        (500, "D"),
+        (400, "CD"),
+        (100, "C"),
+        (90, "XC"),
+        (50, "L"),
+        (40, "XL"),
+        (10, "X"),
+        (9, "IX"),
+        (5, "V"),
+        (4, "IV"),
+        (1, "I"),
+    ];
+    if number == 0 || number > 3999 {
+        return number.to_string();
+    }
+    let mut rest = number;
+    let mut label = String::new();
+    for (value, numeral) in NUMERALS {
+        while rest >= value {
+            label.push_str(numeral);
+            rest -= value;
+        }
+    }
+    label
+}
+
+/// A paragraph's membership in a list.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ListInfo {
+    /// Nesting level (0 = top level)
+    pub level: u8,
+    /// Numbering format of that level
+    pub format: ListFormat,
+    /// Number of the first item at that level
+    pub start: u32,
+}
+
+impl ListInfo {
+    /// Create list membership at a level, numbered from 1.
+    pub fn new(level: u8, format: ListFormat) -> Self {
+        Self {
+            level,
+            format,
+            start: 1,
+        }
+    }
+
+    /// Set the number of the first item.
+    pub fn with_start(mut self, start: u32) -> Self {
+        self.start = start;
+        self
+    }
+
+    /// Whether items are numbered rather than bulleted.
+    #[inline]
+    pub fn is_ordered(&self) -> bool {
+        self.format.is_ordered()
+    }
+}
+
+impl Default for ListInfo {
+    fn default() -> Self {
+        Self::new(0, ListFormat::default())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_labels() {
+        assert_eq!(ListFormat::Decimal.label(12).as_deref(), Some("12"));
+        assert_eq!(ListFormat::LowerLetter.label(3).as_deref(), Some("c"));
+        assert_eq!(ListFormat::UpperLetter.label(28).as_deref(), Some("BB"));
+        assert_eq!(ListFormat::LowerRoman.label(14).as_deref(), Some("xiv"));
+        assert_eq!(
+            ListFormat::UpperRoman.label(1994).as_deref(),
+            Some("MCMXCIV")
+        );
+        assert_eq!(ListFormat::UpperRoman.label(4000).as_deref(), Some("4000"));
+        assert_eq!(ListFormat::Bullet.label(1), None);
+    }
+}
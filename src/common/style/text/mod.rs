@@ -1,7 +1,11 @@
 //! Text formatting types.
 
 // Submodule declarations
+pub mod align;
+pub mod list;
 pub mod pos;
 
 // Re-exports
+pub use align::TextAlignment;
+pub use list::{ListFormat, ListInfo};
 pub use pos::VerticalPosition;
@@ -272,13 +272,10 @@ impl Document {
             #[cfg(feature = "ooxml")]
             DocumentImpl::Docx(doc, _) => doc.paragraph_count().map_err(Error::from),
             #[cfg(feature = "iwa")]
-            DocumentImpl::Pages(doc) => {
-                // Pages documents are organized by sections
-                let sections = doc
-                    .sections()
-                    .map_err(|e| Error::ParseError(format!("Failed to get sections: {}", e)))?;
-                Ok(sections.iter().map(|s| s.paragraphs.len()).sum())
-            },
+            DocumentImpl::Pages(doc) => doc
+                .paragraphs()
+                .map(|paragraphs| paragraphs.len())
+                .map_err(|e| Error::ParseError(format!("Failed to get paragraphs: {}", e))),
             #[cfg(feature = "rtf")]
             DocumentImpl::Rtf(doc) => Ok(doc.paragraph_count()),
             #[cfg(feature = "odf")]
@@ -315,20 +312,10 @@ impl Document {
             },
             #[cfg(feature = "iwa")]
             DocumentImpl::Pages(doc) => {
-                // Pages documents have sections, each with paragraphs
-                let sections = doc
-                    .sections()
-                    .map_err(|e| Error::ParseError(format!("Failed to get sections: {}", e)))?;
-                let paragraphs: Vec<_> = sections
-                    .iter()
-                    .flat_map(|section| {
-                        section
-                            .paragraphs
-                            .iter()
-                            .map(|text| Paragraph::Pages(text.clone()))
-                    })
-                    .collect();
-                Ok(paragraphs)
+                let paras = doc
+                    .paragraphs()
+                    .map_err(|e| Error::ParseError(format!("Failed to get paragraphs: {}", e)))?;
+                Ok(paras.into_iter().map(Paragraph::Pages).collect())
             },
             #[cfg(feature = "rtf")]
             DocumentImpl::Rtf(doc) => {
@@ -341,12 +328,7 @@ impl Document {
                             p.properties,
                             p.runs
                                 .into_iter()
-                                .map(|r| {
-                                    crate::rtf::Run::new(
-                                        std::borrow::Cow::Owned(r.text.into_owned()),
-                                        r.formatting,
-                                    )
-                                })
+                                .map(crate::rtf::Run::into_owned)
                                 .collect(),
                         )
                     })
@@ -468,21 +450,14 @@ impl Document {
             #[cfg(feature = "iwa")]
             DocumentImpl::Pages(doc) => {
                 use super::DocumentElement;
-                // Pages documents have sections with paragraphs
                 // Tables are not currently supported in the extraction API
-                let sections = doc
-                    .sections()
-                    .map_err(|e| Error::ParseError(format!("Failed to get sections: {}", e)))?;
-                let elements: Vec<_> = sections
-                    .iter()
-                    .flat_map(|section| {
-                        section
-                            .paragraphs
-                            .iter()
-                            .map(|text| DocumentElement::Paragraph(Paragraph::Pages(text.clone())))
-                    })
-                    .collect();
-                Ok(elements)
+                let paras = doc
+                    .paragraphs()
+                    .map_err(|e| Error::ParseError(format!("Failed to get paragraphs: {}", e)))?;
+                Ok(paras
+                    .into_iter()
+                    .map(|para| DocumentElement::Paragraph(Paragraph::Pages(para)))
+                    .collect())
             },
             #[cfg(feature = "rtf")]
             DocumentImpl::Rtf(doc) => {
@@ -500,12 +475,7 @@ impl Document {
                                 para.properties,
                                 para.runs
                                     .into_iter()
-                                    .map(|r| {
-                                        crate::rtf::Run::new(
-                                            std::borrow::Cow::Owned(r.text.into_owned()),
-                                            r.formatting,
-                                        )
-                                    })
+                                    .map(crate::rtf::Run::into_owned)
                                     .collect(),
                             );
                             elements.push(DocumentElement::Paragraph(Paragraph::Rtf(owned_para)));
@@ -595,4 +565,59 @@ impl Document {
                 .map_err(|e| Error::ParseError(format!("Failed to get metadata: {}", e))),
         }
     }
+
+    /// Get all images embedded in the document.
+    ///
+    /// Images come back in a common [`EmbeddedImage`](crate::EmbeddedImage)
+    /// form whatever the source format, with MIME type, pixel dimensions
+    /// (when the header reveals them) and alt text. Anchors use the same
+    /// paragraph indices as [`paragraphs()`](Self::paragraphs) where the
+    /// format records placement:
+    ///
+    /// - **DOCX / ODT**: anchored to the containing paragraph
+    /// - **DOC**: pictures from the Data stream, unanchored
+    /// - **RTF / Pages**: unanchored
+    ///
+    /// Use [`EmbeddedImage::to_png`](crate::EmbeddedImage::to_png) (with the
+    /// `imgconv` feature) to rasterize metafiles or re-encode bitmaps.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use litchi::Document;
+    ///
+    /// let doc = Document::open("document.docx")?;
+    /// for image in doc.images()? {
+    ///     println!("{} ({}) at {:?}", image.name, image.mime_type, image.anchor);
+    ///     std::fs::write(&image.name, &image.data)?;
+    /// }
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn images(&self) -> Result<Vec<crate::common::EmbeddedImage>> {
+        match &self.inner {
+            #[cfg(feature = "ole")]
+            DocumentImpl::Doc(doc, _) => doc
+                .extract_all_images()?
+                .iter()
+                .map(|image| image.to_embedded_image(crate::common::ImageAnchor::Unanchored))
+                .collect(),
+            #[cfg(feature = "ooxml")]
+            DocumentImpl::Docx(doc, _) => doc.images().map_err(Error::from),
+            #[cfg(feature = "iwa")]
+            DocumentImpl::Pages(doc) => doc.bundle().media().embedded_images().map_err(|e| {
+                Error::ParseError(format!("Failed to extract images from Pages: {}", e))
+            }),
+            #[cfg(feature = "rtf")]
+            DocumentImpl::Rtf(doc) => Ok(doc
+                .pictures()
+                .iter()
+                .enumerate()
+                .map(|(index, picture)| picture.to_embedded_image(index))
+                .collect()),
+            #[cfg(feature = "odf")]
+            DocumentImpl::Odt(doc) => doc.images().map_err(|e| {
+                Error::ParseError(format!("Failed to extract images from ODT: {}", e))
+            }),
+        }
+    }
 }
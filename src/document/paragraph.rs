@@ -1,7 +1,7 @@
 //! Paragraph implementation for Word documents.
 
 use super::Run;
-use crate::common::{Error, Result};
+use crate::common::{Error, ListInfo, Result, TextAlignment};
 
 #[cfg(feature = "ole")]
 use crate::ole;
@@ -17,7 +17,7 @@ pub enum Paragraph {
     #[cfg(feature = "ooxml")]
     Docx(ooxml::docx::Paragraph),
     #[cfg(feature = "iwa")]
-    Pages(String),
+    Pages(crate::iwa::text::TextParagraph),
     #[cfg(feature = "rtf")]
     Rtf(crate::rtf::ParagraphContent<'static>),
     #[cfg(feature = "odf")]
@@ -33,7 +33,7 @@ impl Paragraph {
             #[cfg(feature = "ooxml")]
             Paragraph::Docx(p) => p.text().map(|s| s.to_string()).map_err(Error::from),
             #[cfg(feature = "iwa")]
-            Paragraph::Pages(text) => Ok(text.clone()),
+            Paragraph::Pages(p) => Ok(p.text()),
             #[cfg(feature = "rtf")]
             Paragraph::Rtf(p) => Ok(p.text()),
             #[cfg(feature = "odf")]
@@ -57,11 +57,7 @@ impl Paragraph {
                 Ok(runs.into_iter().map(Run::Docx).collect())
             },
             #[cfg(feature = "iwa")]
-            Paragraph::Pages(text) => {
-                // Pages paragraphs are simple strings without run-level formatting
-                // Return a single run with the entire text
-                Ok(vec![Run::Pages(text.clone())])
-            },
+            Paragraph::Pages(p) => Ok(p.spans.iter().cloned().map(Run::Pages).collect()),
             #[cfg(feature = "rtf")]
             Paragraph::Rtf(p) => Ok(p.runs().iter().map(|r| Run::Rtf(r.clone())).collect()),
             #[cfg(feature = "odf")]
@@ -73,4 +69,94 @@ impl Paragraph {
             },
         }
     }
+
+    /// Get the display name of the paragraph style, if the format names it.
+    pub fn style_name(&self) -> Result<Option<String>> {
+        match self {
+            #[cfg(feature = "ole")]
+            Paragraph::Doc(_) => Ok(None),
+            #[cfg(feature = "ooxml")]
+            Paragraph::Docx(p) => p.style_name().map_err(Error::from),
+            #[cfg(feature = "iwa")]
+            Paragraph::Pages(p) => Ok(p.style_name.clone()),
+            #[cfg(feature = "rtf")]
+            Paragraph::Rtf(_) => Ok(None),
+            #[cfg(feature = "odf")]
+            Paragraph::Odt(p) => Ok(p.style_name().map(str::to_string)),
+        }
+    }
+
+    /// Get the heading level of the paragraph (1 for a top-level heading).
+    ///
+    /// Returns `None` for body text.
+    pub fn heading_level(&self) -> Result<Option<u8>> {
+        match self {
+            #[cfg(feature = "ole")]
+            Paragraph::Doc(p) => Ok(p.heading_level()),
+            #[cfg(feature = "ooxml")]
+            Paragraph::Docx(p) => p.heading_level().map_err(Error::from),
+            #[cfg(feature = "iwa")]
+            Paragraph::Pages(p) => Ok(p.heading_level),
+            #[cfg(feature = "rtf")]
+            Paragraph::Rtf(p) => Ok(p.properties.heading_level()),
+            #[cfg(feature = "odf")]
+            Paragraph::Odt(p) => Ok(p.heading_level()),
+        }
+    }
+
+    /// Get the list membership of the paragraph: its nesting level and the
+    /// number format of that level.
+    ///
+    /// Returns `None` if the paragraph is not in a list.
+    pub fn list_info(&self) -> Result<Option<ListInfo>> {
+        match self {
+            #[cfg(feature = "ole")]
+            Paragraph::Doc(p) => Ok(p.list_info()),
+            #[cfg(feature = "ooxml")]
+            Paragraph::Docx(p) => p.list_info().map_err(Error::from),
+            #[cfg(feature = "iwa")]
+            Paragraph::Pages(p) => Ok(p.list.clone()),
+            #[cfg(feature = "rtf")]
+            Paragraph::Rtf(p) => Ok(p.properties.list_info()),
+            #[cfg(feature = "odf")]
+            Paragraph::Odt(p) => Ok(p.list_info()),
+        }
+    }
+
+    /// Get the horizontal alignment of the paragraph.
+    ///
+    /// Returns `None` if the alignment is not known, such as when a DOCX
+    /// paragraph inherits it from its style.
+    pub fn alignment(&self) -> Result<Option<TextAlignment>> {
+        match self {
+            #[cfg(feature = "ole")]
+            Paragraph::Doc(p) => {
+                use crate::ole::doc::parts::pap::Justification;
+
+                Ok(Some(match p.properties().justification {
+                    Justification::Left => TextAlignment::Left,
+                    Justification::Center => TextAlignment::Center,
+                    Justification::Right => TextAlignment::Right,
+                    Justification::Justified | Justification::Distributed => TextAlignment::Justify,
+                }))
+            },
+            #[cfg(feature = "ooxml")]
+            Paragraph::Docx(p) => p.alignment().map_err(Error::from),
+            #[cfg(feature = "iwa")]
+            Paragraph::Pages(p) => Ok(p.alignment),
+            #[cfg(feature = "rtf")]
+            Paragraph::Rtf(p) => {
+                use crate::rtf::Alignment;
+
+                Ok(Some(match p.properties.alignment {
+                    Alignment::Left => TextAlignment::Left,
+                    Alignment::Center => TextAlignment::Center,
+                    Alignment::Right => TextAlignment::Right,
+                    Alignment::Justify => TextAlignment::Justify,
+                }))
+            },
+            #[cfg(feature = "odf")]
+            Paragraph::Odt(_) => Ok(None),
+        }
+    }
 }
@@ -1,6 +1,6 @@
 //! Text run implementation for Word documents.
 
-use crate::common::{Error, Result};
+use crate::common::{Error, RGBColor, Result};
 
 #[cfg(feature = "ole")]
 use crate::ole;
@@ -16,7 +16,7 @@ pub enum Run {
     #[cfg(feature = "ooxml")]
     Docx(ooxml::docx::Run),
     #[cfg(feature = "iwa")]
-    Pages(String),
+    Pages(crate::iwa::text::TextSpan),
     #[cfg(feature = "rtf")]
     Rtf(crate::rtf::Run<'static>),
     #[cfg(feature = "odf")]
@@ -32,7 +32,7 @@ impl Run {
             #[cfg(feature = "ooxml")]
             Run::Docx(r) => r.text().map(|s| s.to_string()).map_err(Error::from),
             #[cfg(feature = "iwa")]
-            Run::Pages(text) => Ok(text.clone()),
+            Run::Pages(r) => Ok(r.text.clone()),
             #[cfg(feature = "rtf")]
             Run::Rtf(r) => Ok(r.text().to_string()),
             #[cfg(feature = "odf")]
@@ -50,7 +50,7 @@ impl Run {
             #[cfg(feature = "ooxml")]
             Run::Docx(r) => r.bold().map_err(Error::from),
             #[cfg(feature = "iwa")]
-            Run::Pages(_) => Ok(None), // Pages doesn't support run-level formatting in the current API
+            Run::Pages(r) => Ok(r.style.bold.then_some(true)),
             #[cfg(feature = "rtf")]
             Run::Rtf(r) => Ok(r.bold()),
             #[cfg(feature = "odf")]
@@ -66,7 +66,7 @@ impl Run {
             #[cfg(feature = "ooxml")]
             Run::Docx(r) => r.italic().map_err(Error::from),
             #[cfg(feature = "iwa")]
-            Run::Pages(_) => Ok(None), // Pages doesn't support run-level formatting in the current API
+            Run::Pages(r) => Ok(r.style.italic.then_some(true)),
             #[cfg(feature = "rtf")]
             Run::Rtf(r) => Ok(r.italic()),
             #[cfg(feature = "odf")]
@@ -82,7 +82,7 @@ impl Run {
             #[cfg(feature = "ooxml")]
             Run::Docx(r) => r.strikethrough().map_err(Error::from),
             #[cfg(feature = "iwa")]
-            Run::Pages(_) => Ok(None), // Pages doesn't support run-level formatting in the current API
+            Run::Pages(r) => Ok(r.style.strikethrough.then_some(true)),
             #[cfg(feature = "rtf")]
             Run::Rtf(r) => Ok(r.strikethrough()),
             #[cfg(feature = "odf")]
@@ -118,11 +118,90 @@ impl Run {
                 }
             },
             #[cfg(feature = "iwa")]
-            Run::Pages(_) => Ok(None), // Pages doesn't support run-level formatting in the current API
+            Run::Pages(r) => {
+                let pos = match r.style.vertical_position {
+                    VerticalPosition::Normal => None,
+                    pos => Some(pos),
+                };
+                Ok(pos)
+            },
             #[cfg(feature = "rtf")]
             Run::Rtf(r) => Ok(r.vertical_position()),
             #[cfg(feature = "odf")]
             Run::Odt(r) => Ok(r.vertical_position()),
         }
     }
+
+    /// Check if the run is underlined.
+    pub fn underline(&self) -> Result<Option<bool>> {
+        match self {
+            #[cfg(feature = "ole")]
+            Run::Doc(r) => Ok(r.underline()),
+            #[cfg(feature = "ooxml")]
+            Run::Docx(r) => r.underline().map_err(Error::from),
+            #[cfg(feature = "iwa")]
+            Run::Pages(r) => Ok(r.style.underline.then_some(true)),
+            #[cfg(feature = "rtf")]
+            Run::Rtf(r) => Ok(r.underline()),
+            #[cfg(feature = "odf")]
+            Run::Odt(_) => Ok(None),
+        }
+    }
+
+    /// Get the font size of the run in points.
+    ///
+    /// Returns `None` if the size is inherited from the style.
+    pub fn font_size(&self) -> Result<Option<f32>> {
+        match self {
+            #[cfg(feature = "ole")]
+            Run::Doc(r) => Ok(r.font_size().map(|size| f32::from(size) / 2.0)),
+            #[cfg(feature = "ooxml")]
+            Run::Docx(r) => Ok(r
+                .font_size()
+                .map_err(Error::from)?
+                .map(|size| size as f32 / 2.0)),
+            #[cfg(feature = "iwa")]
+            Run::Pages(r) => Ok(r.style.font_size),
+            #[cfg(feature = "rtf")]
+            Run::Rtf(r) => Ok(Some(f32::from(r.font_size()) / 2.0)),
+            #[cfg(feature = "odf")]
+            Run::Odt(_) => Ok(None),
+        }
+    }
+
+    /// Get the text color of the run.
+    ///
+    /// Returns `None` if the color is inherited or automatic. RTF colors
+    /// refer to the document's color table and are not resolved here.
+    pub fn color(&self) -> Result<Option<RGBColor>> {
+        match self {
+            #[cfg(feature = "ole")]
+            Run::Doc(r) => Ok(r.color().map(|(r, g, b)| RGBColor::new(r, g, b))),
+            #[cfg(feature = "ooxml")]
+            Run::Docx(r) => r.color().map_err(Error::from),
+            #[cfg(feature = "iwa")]
+            Run::Pages(r) => Ok(r.style.color.map(|(r, g, b)| RGBColor::new(r, g, b))),
+            #[cfg(feature = "rtf")]
+            Run::Rtf(_) => Ok(None),
+            #[cfg(feature = "odf")]
+            Run::Odt(_) => Ok(None),
+        }
+    }
+
+    /// Get the hyperlink target of the run: a URL, or `#name` for a link to
+    /// a bookmark in the same document.
+    pub fn hyperlink(&self) -> Result<Option<String>> {
+        match self {
+            #[cfg(feature = "ole")]
+            Run::Doc(r) => Ok(r.hyperlink().map(str::to_string)),
+            #[cfg(feature = "ooxml")]
+            Run::Docx(r) => Ok(r.hyperlink().map(str::to_string)),
+            #[cfg(feature = "iwa")]
+            Run::Pages(r) => Ok(r.hyperlink.clone()),
+            #[cfg(feature = "rtf")]
+            Run::Rtf(r) => Ok(r.hyperlink().map(str::to_string)),
+            #[cfg(feature = "odf")]
+            Run::Odt(r) => Ok(r.hyperlink().map(str::to_string)),
+        }
+    }
 }
@@ -69,6 +69,30 @@ impl Table {
         }
     }
 
+    /// Get the number of paragraphs in the table's cells.
+    ///
+    /// Keeps paragraph indices, which count table paragraphs, in step when
+    /// walking [`Document::elements()`](crate::Document::elements). Only
+    /// formats that anchor images to paragraphs count them.
+    pub(crate) fn paragraph_count(&self) -> Result<usize> {
+        match self {
+            #[cfg(feature = "ooxml")]
+            Table::Docx(t) => {
+                let mut count = 0;
+                for row in t.rows().map_err(Error::from)? {
+                    for cell in row.cells().map_err(Error::from)? {
+                        count += cell.paragraphs().map_err(Error::from)?.len();
+                    }
+                }
+                Ok(count)
+            },
+            #[cfg(feature = "odf")]
+            Table::Odt(t) => Ok(t.paragraph_count()),
+            #[allow(unreachable_patterns)]
+            _ => Ok(0),
+        }
+    }
+
     /// Get a specific row by index without allocating a collection.
     ///
     /// This is more efficient than calling `rows()` and then indexing,
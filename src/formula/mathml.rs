@@ -0,0 +1,733 @@
+// MathML Converter Implementation
+//
+// This module converts the formula AST to Presentation MathML, as understood
+// by browsers (MathML Core). Every node maps onto a MathML element, so the
+// conversion cannot fail: unknown content falls back to `<mtext>`.
+
+use crate::formula::ast::*;
+use std::fmt::Write;
+
+/// Function application, placed between a function name and its argument
+const FUNCTION_APPLICATION: &str = "\u{2061}";
+
+/// MathML converter that converts formula AST to `<math>` elements
+pub struct MathmlConverter {
+    /// Buffer for building the MathML output
+    buffer: String,
+}
+
+impl MathmlConverter {
+    /// Create a new MathML converter
+    pub fn new() -> Self {
+        Self {
+            buffer: String::with_capacity(1024),
+        }
+    }
+
+    /// Convert a formula to a `<math>` element
+    ///
+    /// Display formulas get `display="block"`.
+    pub fn convert(&mut self, formula: &Formula) -> &str {
+        self.convert_nodes(formula.root(), formula.display_style())
+    }
+
+    /// Convert nodes to a `<math>` element
+    pub fn convert_nodes(&mut self, nodes: &[MathNode], display: bool) -> &str {
+        self.buffer.clear();
+        self.buffer
+            .push_str(r#"<math xmlns="http://www.w3.org/1998/Math/MathML""#);
+        if display {
+            self.buffer.push_str(r#" display="block""#);
+        }
+        self.buffer.push('>');
+        self.write_row(nodes);
+        self.buffer.push_str("</math>");
+        &self.buffer
+    }
+
+    /// Write nodes as a single `<mrow>`, or the node itself if there is one
+    ///
+    /// Text of more than one character may become several tokens, so it
+    /// always gets a row.
+    fn write_row(&mut self, nodes: &[MathNode]) {
+        if let [node] = nodes
+            && !matches!(node, MathNode::Text(text) if text.chars().nth(1).is_some())
+        {
+            self.write_node(node);
+        } else {
+            self.buffer.push_str("<mrow>");
+            for node in nodes {
+                self.write_node(node);
+            }
+            self.buffer.push_str("</mrow>");
+        }
+    }
+
+    /// Write an element with the given children, each as one argument
+    fn write_element(&mut self, tag: &str, attributes: &str, children: &[&[MathNode]]) {
+        let _ = write!(self.buffer, "<{}{}>", tag, attributes);
+        for child in children {
+            self.write_row(child);
+        }
+        let _ = write!(self.buffer, "</{}>", tag);
+    }
+
+    /// Write a token element (`mi`, `mn`, `mo`, `mtext`)
+    fn write_token(&mut self, tag: &str, text: &str) {
+        let _ = write!(self.buffer, "<{}>", tag);
+        escape_into(&mut self.buffer, text);
+        let _ = write!(self.buffer, "</{}>", tag);
+    }
+
+    fn write_node(&mut self, node: &MathNode) {
+        match node {
+            MathNode::Text(text) => self.write_text(text),
+            MathNode::Number(number) => self.write_token("mn", number),
+            MathNode::Operator(op) => self.write_token("mo", operator_char(*op)),
+            MathNode::Symbol(symbol) => match symbol.unicode {
+                Some(ch) => self.write_token("mi", ch.encode_utf8(&mut [0; 4])),
+                None => self.write_token("mi", &symbol.name),
+            },
+            MathNode::PredefinedSymbol(symbol) => {
+                self.write_token("mi", predefined_symbol_char(*symbol))
+            },
+            MathNode::Frac {
+                numerator,
+                denominator,
+                line_thickness,
+                frac_type,
+            } => {
+                let attributes =
+                    if *line_thickness == Some(0.0) || *frac_type == Some(FractionType::NoBar) {
+                        r#" linethickness="0""#
+                    } else {
+                        ""
+                    };
+                self.write_element("mfrac", attributes, &[numerator, denominator]);
+            },
+            MathNode::Root { base, index } => match index {
+                Some(index) => self.write_element("mroot", "", &[base, index]),
+                None => self.write_element("msqrt", "", &[base]),
+            },
+            MathNode::Power { base, exponent } => self.write_element("msup", "", &[base, exponent]),
+            MathNode::Sub { base, subscript } => self.write_element("msub", "", &[base, subscript]),
+            MathNode::SubSup {
+                base,
+                subscript,
+                superscript,
+            } => self.write_element("msubsup", "", &[base, subscript, superscript]),
+            MathNode::PreSub {
+                base,
+                pre_subscript,
+            } => self.write_prescripts(base, Some(pre_subscript), None),
+            MathNode::PreSup {
+                base,
+                pre_superscript,
+            } => self.write_prescripts(base, None, Some(pre_superscript)),
+            MathNode::PreSubSup {
+                base,
+                pre_subscript,
+                pre_superscript,
+            } => self.write_prescripts(base, Some(pre_subscript), Some(pre_superscript)),
+            MathNode::Under { base, under, .. } => self.write_element("munder", "", &[base, under]),
+            MathNode::Over { base, over, .. } => self.write_element("mover", "", &[base, over]),
+            MathNode::UnderOver {
+                base, under, over, ..
+            } => self.write_element("munderover", "", &[base, under, over]),
+            MathNode::Fenced {
+                open,
+                content,
+                close,
+                separator,
+            } => {
+                self.buffer.push_str("<mrow>");
+                let (open, _) = fence_chars(*open);
+                let (_, close) = fence_chars(*close);
+                if !open.is_empty() {
+                    self.write_token("mo", open);
+                }
+                for (i, node) in content.iter().enumerate() {
+                    if i > 0
+                        && let Some(separator) = separator
+                    {
+                        self.write_token("mo", separator);
+                    }
+                    self.write_node(node);
+                }
+                if !close.is_empty() {
+                    self.write_token("mo", close);
+                }
+                self.buffer.push_str("</mrow>");
+            },
+            MathNode::LargeOp {
+                operator,
+                lower_limit,
+                upper_limit,
+                integrand,
+                hide_lower,
+                hide_upper,
+            } => {
+                self.buffer.push_str("<mrow>");
+                let lower = lower_limit.as_deref().filter(|_| !hide_lower);
+                let upper = upper_limit.as_deref().filter(|_| !hide_upper);
+                let (symbol, is_word) = large_operator(*operator);
+                // Integrals take their limits as scripts, others above and below
+                let (under_over, sub_sup) = if is_integral(*operator) {
+                    ("msub", "msubsup")
+                } else {
+                    ("munder", "munderover")
+                };
+                let tag = match (lower, upper) {
+                    (Some(_), Some(_)) => Some(sub_sup),
+                    (Some(_), None) => Some(under_over),
+                    (None, Some(_)) => Some(if is_integral(*operator) {
+                        "msup"
+                    } else {
+                        "mover"
+                    }),
+                    (None, None) => None,
+                };
+                if let Some(tag) = tag {
+                    let _ = write!(self.buffer, "<{}>", tag);
+                }
+                self.write_token(if is_word { "mi" } else { "mo" }, symbol);
+                if let Some(lower) = lower {
+                    self.write_row(lower);
+                }
+                if let Some(upper) = upper {
+                    self.write_row(upper);
+                }
+                if let Some(tag) = tag {
+                    let _ = write!(self.buffer, "</{}>", tag);
+                }
+                if let Some(integrand) = integrand {
+                    self.write_row(integrand);
+                }
+                self.buffer.push_str("</mrow>");
+            },
+            MathNode::Function { name, argument } => self.write_function(name, argument),
+            MathNode::PredefinedFunction { function, argument } => {
+                self.write_function(function_name(*function), argument)
+            },
+            MathNode::Matrix {
+                rows, fence_type, ..
+            } => {
+                let (open, close) = matrix_fence_chars(*fence_type);
+                self.buffer.push_str("<mrow>");
+                if !open.is_empty() {
+                    self.write_token("mo", open);
+                }
+                self.write_table(rows.iter().map(Vec::as_slice));
+                if !close.is_empty() {
+                    self.write_token("mo", close);
+                }
+                self.buffer.push_str("</mrow>");
+            },
+            MathNode::EqArray { rows, .. } => {
+                self.buffer.push_str("<mtable>");
+                for row in rows {
+                    self.buffer.push_str("<mtr><mtd>");
+                    self.write_row(row);
+                    self.buffer.push_str("</mtd></mtr>");
+                }
+                self.buffer.push_str("</mtable>");
+            },
+            MathNode::Accent {
+                base,
+                accent,
+                position,
+            } => {
+                let tag = if *position == Some(Position::Bottom) {
+                    "munder"
+                } else {
+                    "mover"
+                };
+                let _ = write!(self.buffer, r#"<{} accent="true">"#, tag);
+                self.write_row(base);
+                self.write_token("mo", accent_char(*accent));
+                let _ = write!(self.buffer, "</{}>", tag);
+            },
+            MathNode::Bar { base, position } => {
+                let (tag, mark) = if *position == Some(Position::Bottom) {
+                    ("munder", "\u{2015}")
+                } else {
+                    ("mover", "\u{203E}")
+                };
+                let _ = write!(self.buffer, r#"<{} accent="true">"#, tag);
+                self.write_row(base);
+                self.write_token("mo", mark);
+                let _ = write!(self.buffer, "</{}>", tag);
+            },
+            MathNode::BorderBox { content, .. } => {
+                self.write_element("menclose", r#" notation="box""#, &[content])
+            },
+            MathNode::GroupChar {
+                base,
+                character,
+                position,
+                ..
+            } => {
+                let bottom = *position != Some(Position::Top);
+                let (tag, default) = if bottom {
+                    ("munder", "\u{23DF}")
+                } else {
+                    ("mover", "\u{23DE}")
+                };
+                let _ = write!(self.buffer, "<{}>", tag);
+                self.write_row(base);
+                self.write_token("mo", character.as_deref().unwrap_or(default));
+                let _ = write!(self.buffer, "</{}>", tag);
+            },
+            MathNode::Space(space) => {
+                let _ = write!(self.buffer, r#"<mspace width="{}"/>"#, space_width(*space));
+            },
+            MathNode::LineBreak => self.buffer.push_str(r#"<mspace linebreak="newline"/>"#),
+            MathNode::Style { style, content } => self.write_styled(Some(*style), content),
+            MathNode::Run { content, style, .. } => self.write_styled(*style, content),
+            MathNode::Row(nodes) => self.write_row(nodes),
+            MathNode::Phantom(content) => self.write_element("mphantom", "", &[content]),
+            MathNode::Limit { content, .. }
+            | MathNode::Degree(content)
+            | MathNode::Base(content)
+            | MathNode::Argument(content)
+            | MathNode::Numerator(content)
+            | MathNode::Denominator(content)
+            | MathNode::Integrand(content)
+            | MathNode::LowerLimit(content)
+            | MathNode::UpperLimit(content) => self.write_row(content),
+            MathNode::Error(message) => {
+                self.buffer.push_str("<merror>");
+                self.write_token("mtext", message);
+                self.buffer.push_str("</merror>");
+            },
+        }
+    }
+
+    /// Write text, splitting it into identifiers, numbers and operators
+    fn write_text(&mut self, text: &str) {
+        let mut chars = text.char_indices().peekable();
+        while let Some((start, ch)) = chars.next() {
+            if ch.is_ascii_digit() {
+                let mut end = start + ch.len_utf8();
+                while let Some(&(i, next)) = chars.peek() {
+                    if !(next.is_ascii_digit() || next == '.') {
+                        break;
+                    }
+                    end = i + next.len_utf8();
+                    chars.next();
+                }
+                self.write_token("mn", &text[start..end]);
+            } else if ch.is_alphabetic() {
+                self.write_token("mi", ch.encode_utf8(&mut [0; 4]));
+            } else if ch.is_whitespace() {
+                self.buffer.push_str(r#"<mspace width="0.25em"/>"#);
+            } else {
+                self.write_token("mo", ch.encode_utf8(&mut [0; 4]));
+            }
+        }
+    }
+
+    /// Write a named function applied to its argument
+    fn write_function(&mut self, name: &str, argument: &[MathNode]) {
+        self.buffer.push_str("<mrow>");
+        self.write_token("mi", name);
+        self.write_token("mo", FUNCTION_APPLICATION);
+        self.write_row(argument);
+        self.buffer.push_str("</mrow>");
+    }
+
+    /// Write a base with scripts before it
+    fn write_prescripts(
+        &mut self,
+        base: &[MathNode],
+        subscript: Option<&Vec<MathNode>>,
+        superscript: Option<&Vec<MathNode>>,
+    ) {
+        self.buffer.push_str("<mmultiscripts>");
+        self.write_row(base);
+        self.buffer.push_str("<mprescripts/>");
+        for script in [subscript, superscript] {
+            match script {
+                Some(script) => self.write_row(script),
+                None => self.buffer.push_str("<none/>"),
+            }
+        }
+        self.buffer.push_str("</mmultiscripts>");
+    }
+
+    /// Write matrix rows as an `<mtable>`
+    fn write_table<'n, 'a: 'n>(&mut self, rows: impl Iterator<Item = &'n [Vec<MathNode<'a>>]>) {
+        self.buffer.push_str("<mtable>");
+        for row in rows {
+            self.buffer.push_str("<mtr>");
+            for cell in row {
+                self.buffer.push_str("<mtd>");
+                self.write_row(cell);
+                self.buffer.push_str("</mtd>");
+            }
+            self.buffer.push_str("</mtr>");
+        }
+        self.buffer.push_str("</mtable>");
+    }
+
+    /// Write content in a math variant
+    fn write_styled(&mut self, style: Option<StyleType>, content: &[MathNode]) {
+        match style.map(math_variant) {
+            Some(variant) => {
+                let _ = write!(self.buffer, r#"<mstyle mathvariant="{}">"#, variant);
+                self.write_row(content);
+                self.buffer.push_str("</mstyle>");
+            },
+            None => self.write_row(content),
+        }
+    }
+}
+
+impl Default for MathmlConverter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn escape_into(buffer: &mut String, text: &str) {
+    for ch in text.chars() {
+        match ch {
+            '&' => buffer.push_str("&amp;"),
+            '<' => buffer.push_str("&lt;"),
+            '>' => buffer.push_str("&gt;"),
+            '"' => buffer.push_str("&quot;"),
+            _ => buffer.push(ch),
+        }
+    }
+}
+
+fn operator_char(op: Operator) -> &'static str {
+    match op {
+        Operator::Plus => "+",
+        Operator::Minus => "\u{2212}",
+        Operator::Multiply | Operator::Times | Operator::Cross => "\u{00D7}",
+        Operator::Divide => "\u{00F7}",
+        Operator::PlusMinus => "\u{00B1}",
+        Operator::MinusPlus => "\u{2213}",
+        Operator::Equals => "=",
+        Operator::NotEquals => "\u{2260}",
+        Operator::LessThan => "<",
+        Operator::GreaterThan => ">",
+        Operator::LessThanOrEqual => "\u{2264}",
+        Operator::GreaterThanOrEqual => "\u{2265}",
+        Operator::Dot => "\u{22C5}",
+        Operator::Star => "\u{22C6}",
+        Operator::Circle | Operator::Circ => "\u{2218}",
+        Operator::Bullet => "\u{2219}",
+        Operator::Wedge | Operator::And => "\u{2227}",
+        Operator::Vee | Operator::Or => "\u{2228}",
+        Operator::Cap | Operator::Intersection => "\u{2229}",
+        Operator::Cup | Operator::Union => "\u{222A}",
+        Operator::In => "\u{2208}",
+        Operator::NotIn => "\u{2209}",
+        Operator::Subset => "\u{2282}",
+        Operator::Superset => "\u{2283}",
+        Operator::SubsetEq => "\u{2286}",
+        Operator::SupersetEq => "\u{2287}",
+        Operator::EmptySet => "\u{2205}",
+        Operator::Approx => "\u{2248}",
+        Operator::Cong => "\u{2245}",
+        Operator::Equiv => "\u{2261}",
+        Operator::Propto => "\u{221D}",
+        Operator::Sim => "\u{223C}",
+        Operator::Simeq => "\u{2243}",
+        Operator::Asymp => "\u{224D}",
+        Operator::Parallel => "\u{2225}",
+        Operator::Perpendicular => "\u{22A5}",
+        Operator::Angle => "\u{2220}",
+        Operator::Nabla => "\u{2207}",
+        Operator::Partial => "\u{2202}",
+        Operator::Differential => "d",
+        Operator::Infinity => "\u{221E}",
+        Operator::Aleph => "\u{2135}",
+        Operator::Prime => "\u{2032}",
+        Operator::DoublePrime => "\u{2033}",
+        Operator::TriplePrime => "\u{2034}",
+        Operator::Ellipsis | Operator::Ldots => "\u{2026}",
+        Operator::CDots => "\u{22EF}",
+        Operator::VDots => "\u{22EE}",
+        Operator::DDots => "\u{22F1}",
+        Operator::LeftArrow => "\u{2190}",
+        Operator::RightArrow => "\u{2192}",
+        Operator::UpArrow => "\u{2191}",
+        Operator::DownArrow => "\u{2193}",
+        Operator::LeftRightArrow => "\u{2194}",
+        Operator::UpDownArrow => "\u{2195}",
+        Operator::ForAll => "\u{2200}",
+        Operator::Exists => "\u{2203}",
+        Operator::Not => "\u{00AC}",
+        Operator::Implies => "\u{21D2}",
+        Operator::Iff => "\u{21D4}",
+        Operator::Therefore => "\u{2234}",
+        Operator::Because => "\u{2235}",
+        Operator::Box | Operator::Square => "\u{25A1}",
+        Operator::Diamond => "\u{22C4}",
+    }
+}
+
+fn predefined_symbol_char(symbol: PredefinedSymbol) -> &'static str {
+    use PredefinedSymbol::*;
+    match symbol {
+        Alpha => "\u{03B1}",
+        Beta => "\u{03B2}",
+        Gamma => "\u{03B3}",
+        Delta => "\u{03B4}",
+        Epsilon => "\u{03B5}",
+        Zeta => "\u{03B6}",
+        Eta => "\u{03B7}",
+        Theta => "\u{03B8}",
+        Iota => "\u{03B9}",
+        Kappa => "\u{03BA}",
+        Lambda => "\u{03BB}",
+        Mu => "\u{03BC}",
+        Nu => "\u{03BD}",
+        Xi => "\u{03BE}",
+        Omicron => "\u{03BF}",
+        Pi => "\u{03C0}",
+        Rho => "\u{03C1}",
+        Sigma => "\u{03C3}",
+        Tau => "\u{03C4}",
+        Upsilon => "\u{03C5}",
+        Phi => "\u{03C6}",
+        Chi => "\u{03C7}",
+        Psi => "\u{03C8}",
+        Omega => "\u{03C9}",
+        AlphaCap => "\u{0391}",
+        BetaCap => "\u{0392}",
+        GammaCap => "\u{0393}",
+        DeltaCap => "\u{0394}",
+        EpsilonCap => "\u{0395}",
+        ZetaCap => "\u{0396}",
+        EtaCap => "\u{0397}",
+        ThetaCap => "\u{0398}",
+        IotaCap => "\u{0399}",
+        KappaCap => "\u{039A}",
+        LambdaCap => "\u{039B}",
+        MuCap => "\u{039C}",
+        NuCap => "\u{039D}",
+        XiCap => "\u{039E}",
+        OmicronCap => "\u{039F}",
+        PiCap => "\u{03A0}",
+        RhoCap => "\u{03A1}",
+        SigmaCap => "\u{03A3}",
+        TauCap => "\u{03A4}",
+        UpsilonCap => "\u{03A5}",
+        PhiCap => "\u{03A6}",
+        ChiCap => "\u{03A7}",
+        PsiCap => "\u{03A8}",
+        OmegaCap => "\u{03A9}",
+        Aleph => "\u{2135}",
+        EulerGamma => "\u{03B3}",
+        ExponentialE => "\u{2147}",
+        ImaginaryI => "\u{2148}",
+        Infinity => "\u{221E}",
+    }
+}
+
+fn function_name(function: FunctionName) -> &'static str {
+    use FunctionName::*;
+    match function {
+        Sin => "sin",
+        Cos => "cos",
+        Tan => "tan",
+        Sec => "sec",
+        Csc => "csc",
+        Cot => "cot",
+        ArcSin => "arcsin",
+        ArcCos => "arccos",
+        ArcTan => "arctan",
+        ArcSec => "arcsec",
+        ArcCsc => "arccsc",
+        ArcCot => "arccot",
+        Sinh => "sinh",
+        Cosh => "cosh",
+        Tanh => "tanh",
+        Sech => "sech",
+        Csch => "csch",
+        Coth => "coth",
+        Log => "log",
+        Ln => "ln",
+        Exp => "exp",
+        Sqrt => "sqrt",
+        Min => "min",
+        Max => "max",
+        Sup => "sup",
+        Inf => "inf",
+        Lim => "lim",
+        Det => "det",
+        Trace => "tr",
+        Dim => "dim",
+        Ker => "ker",
+        Im => "Im",
+        Re => "Re",
+        Arg => "arg",
+        Mod => "mod",
+        Gcd => "gcd",
+        Lcm => "lcm",
+    }
+}
+
+/// Symbol of a large operator, and whether it is written as a word
+fn large_operator(operator: LargeOperator) -> (&'static str, bool) {
+    use LargeOperator::*;
+    match operator {
+        Sum => ("\u{2211}", false),
+        Product => ("\u{220F}", false),
+        Coproduct => ("\u{2210}", false),
+        Integral => ("\u{222B}", false),
+        DoubleIntegral => ("\u{222C}", false),
+        TripleIntegral => ("\u{222D}", false),
+        ContourIntegral => ("\u{222E}", false),
+        SurfaceIntegral => ("\u{222F}", false),
+        VolumeIntegral => ("\u{2230}", false),
+        Union | BigUnion => ("\u{22C3}", false),
+        Intersection | BigIntersection => ("\u{22C2}", false),
+        Limit => ("lim", true),
+        Max => ("max", true),
+        Min => ("min", true),
+        Supremum => ("sup", true),
+        Infimum => ("inf", true),
+        ArgMax => ("argmax", true),
+        ArgMin => ("argmin", true),
+    }
+}
+
+fn is_integral(operator: LargeOperator) -> bool {
+    use LargeOperator::*;
+    matches!(
+        operator,
+        Integral
+            | DoubleIntegral
+            | TripleIntegral
+            | ContourIntegral
+            | SurfaceIntegral
+            | VolumeIntegral
+    )
+}
+
+fn fence_chars(fence: Fence) -> (&'static str, &'static str) {
+    match fence {
+        Fence::Paren => ("(", ")"),
+        Fence::Bracket => ("[", "]"),
+        Fence::Brace => ("{", "}"),
+        Fence::Angle => ("\u{27E8}", "\u{27E9}"),
+        Fence::Pipe => ("|", "|"),
+        Fence::DoublePipe => ("\u{2016}", "\u{2016}"),
+        Fence::Floor => ("\u{230A}", "\u{230B}"),
+        Fence::Ceiling => ("\u{2308}", "\u{2309}"),
+        Fence::AngleBracket => ("\u{3008}", "\u{3009}"),
+        Fence::SquareBracket => ("\u{27E6}", "\u{27E7}"),
+        Fence::CurlyBrace => ("\u{2983}", "\u{2984}"),
+        Fence::None => ("", ""),
+    }
+}
+
+fn matrix_fence_chars(fence: MatrixFence) -> (&'static str, &'static str) {
+    match fence {
+        MatrixFence::None => ("", ""),
+        MatrixFence::Paren => ("(", ")"),
+        MatrixFence::Bracket => ("[", "]"),
+        MatrixFence::Brace => ("{", "}"),
+        MatrixFence::Pipe => ("|", "|"),
+        MatrixFence::DoublePipe => ("\u{2016}", "\u{2016}"),
+    }
+}
+
+fn accent_char(accent: AccentType) -> &'static str {
+    match accent {
+        AccentType::Hat => "^",
+        AccentType::Check => "\u{02C7}",
+        AccentType::Tilde => "~",
+        AccentType::Acute => "\u{00B4}",
+        AccentType::Grave => "`",
+        AccentType::Dot => "\u{02D9}",
+        AccentType::DoubleDot => "\u{00A8}",
+        AccentType::TripleDot => "\u{20DB}",
+        AccentType::Bar => "\u{00AF}",
+        AccentType::Breve => "\u{02D8}",
+        AccentType::Vec => "\u{2192}",
+    }
+}
+
+fn space_width(space: SpaceType) -> &'static str {
+    match space {
+        SpaceType::Thin => "0.1667em",
+        SpaceType::Medium => "0.2222em",
+        SpaceType::Thick => "0.2778em",
+        SpaceType::Quad => "1em",
+        SpaceType::QQuad => "2em",
+        SpaceType::Negative => "-0.1667em",
+    }
+}
+
+fn math_variant(style: StyleType) -> &'static str {
+    match style {
+        StyleType::Normal => "normal",
+        StyleType::Bold => "bold",
+        StyleType::Italic => "italic",
+        StyleType::BoldItalic => "bold-italic",
+        StyleType::SansSerif => "sans-serif",
+        StyleType::SansSerifBold => "bold-sans-serif",
+        StyleType::SansSerifItalic => "sans-serif-italic",
+        StyleType::SansSerifBoldItalic => "sans-serif-bold-italic",
+        StyleType::Monospace => "monospace",
+        StyleType::Script => "script",
+        StyleType::BoldScript => "bold-script",
+        StyleType::Fraktur => "fraktur",
+        StyleType::BoldFraktur => "bold-fraktur",
+        StyleType::DoubleStruck => "double-struck",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::borrow::Cow;
+
+    #[test]
+    fn test_fraction_and_scripts() {
+        let nodes = vec![
+            MathNode::Power {
+                base: vec![MathNode::Text(Cow::Borrowed("x"))],
+                exponent: vec![MathNode::Number(Cow::Borrowed("2"))],
+            },
+            MathNode::Operator(Operator::Plus),
+            MathNode::Frac {
+                numerator: vec![MathNode::Number(Cow::Borrowed("1"))],
+                denominator: vec![MathNode::Text(Cow::Borrowed("n<m"))],
+                line_thickness: None,
+                frac_type: None,
+            },
+        ];
+        let mut converter = MathmlConverter::new();
+        assert_eq!(
+            converter.convert_nodes(&nodes, false),
+            r#"<math xmlns="http://www.w3.org/1998/Math/MathML"><mrow><msup><mi>x</mi><mn>2</mn></msup><mo>+</mo><mfrac><mn>1</mn><mrow><mi>n</mi><mo>&lt;</mo><mi>m</mi></mrow></mfrac></mrow></math>"#
+        );
+    }
+
+    #[test]
+    fn test_large_operator_and_function() {
+        let nodes = vec![MathNode::LargeOp {
+            operator: LargeOperator::Sum,
+            lower_limit: Some(vec![MathNode::Text(Cow::Borrowed("i"))]),
+            upper_limit: Some(vec![MathNode::Text(Cow::Borrowed("n"))]),
+            integrand: Some(vec![MathNode::PredefinedFunction {
+                function: FunctionName::Sin,
+                argument: vec![MathNode::PredefinedSymbol(PredefinedSymbol::Theta)],
+            }]),
+            hide_lower: false,
+            hide_upper: false,
+        }];
+        let mut converter = MathmlConverter::new();
+        assert_eq!(
+            converter.convert_nodes(&nodes, true),
+            "<math xmlns=\"http://www.w3.org/1998/Math/MathML\" display=\"block\"><mrow><munderover><mo>\u{2211}</mo><mi>i</mi><mi>n</mi></munderover><mrow><mi>sin</mi><mo>\u{2061}</mo><mi>\u{03B8}</mi></mrow></mrow></math>"
+        );
+    }
+}
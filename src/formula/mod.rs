@@ -40,6 +40,11 @@ pub mod ast;
 /// This module converts our formula AST to LaTeX format.
 /// LaTeX is a widely-used typesetting system for mathematical formulas.
 pub mod latex;
+/// MathML Converter
+///
+/// This module converts our formula AST to Presentation MathML, which
+/// browsers render natively in HTML.
+pub mod mathml;
 /// MTEF (MathType Equation Format) Parser
 ///
 /// This module parses the binary MathType Equation Format (MTEF) used in
@@ -74,6 +79,7 @@ pub use ast::{
     SpaceType, StyleType, Symbol,
 };
 pub use latex::{LatexConverter, LatexError};
+pub use mathml::MathmlConverter;
 pub use mtef::{MtefError, MtefParser};
 pub use omml::{OmmlError, OmmlParser};
 
@@ -154,6 +160,36 @@ pub fn mtef_to_latex(mtef_data: &[u8]) -> Result<String, FormulaError> {
     Ok(converter.convert(&formula)?.to_string())
 }
 
+/// Convert OMML to a MathML `<math>` element
+///
+/// # Example
+/// ```ignore
+/// let mathml = omml_to_mathml("<m:oMath><m:r><m:t>x</m:t></m:r></m:oMath>", false)?;
+/// ```
+pub fn omml_to_mathml(omml: &str, display: bool) -> Result<String, FormulaError> {
+    let formula = Formula::new();
+    let parser = OmmlParser::new(formula.arena());
+    let nodes = parser.parse(omml)?;
+
+    let mut converter = MathmlConverter::new();
+    Ok(converter.convert_nodes(&nodes, display).to_string())
+}
+
+/// Convert MTEF binary data to a MathML `<math>` element
+///
+/// # Example
+/// ```ignore
+/// let mathml = mtef_to_mathml(mtef_data, false)?;
+/// ```
+pub fn mtef_to_mathml(mtef_data: &[u8], display: bool) -> Result<String, FormulaError> {
+    let formula = Formula::new();
+    let mut parser = MtefParser::new(formula.arena(), mtef_data);
+    let nodes = parser.parse()?;
+
+    let mut converter = MathmlConverter::new();
+    Ok(converter.convert_nodes(&nodes, display).to_string())
+}
+
 /// Convert OMML to MTEF (not yet implemented)
 ///
 /// This function is planned for future implementation.
@@ -172,6 +208,14 @@ pub fn mtef_to_omml(_mtef_data: &[u8]) -> Result<String, FormulaError> {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_omml_to_mathml() {
+        let omml = r#"<m:oMath><m:r><m:t>x</m:t></m:r></m:oMath>"#;
+        let mathml = omml_to_mathml(omml, false).unwrap();
+        assert!(mathml.starts_with("<math"));
+        assert!(mathml.contains("<mi>x</mi>"));
+    }
+
     #[test]
     fn test_omml_to_latex() {
         let omml = r#"<m:oMath><m:r><m:t>x</m:t></m:r></m:oMath>"#;
@@ -0,0 +1,206 @@
+/// Configuration types for HTML conversion.
+///
+/// This module defines the options used to customize HTML output. They
+/// mirror [`MarkdownOptions`](crate::markdown::MarkdownOptions) where the
+/// two formats overlap.
+use crate::markdown::{ImageSink, MetafileStyle};
+
+/// Configuration options for HTML conversion.
+///
+/// # Examples
+///
+/// ```rust
+/// use litchi::html::HtmlOptions;
+/// use litchi::markdown::ImageSink;
+///
+/// // Create with defaults: a fragment with inline styles and embedded images
+/// let options = HtmlOptions::default();
+///
+/// // Or customize
+/// let options = HtmlOptions::new()
+///     .with_standalone(true)
+///     .with_metadata(true)
+///     .with_image_sink(ImageSink::directory("images"));
+/// ```
+#[derive(Debug, Clone)]
+pub struct HtmlOptions {
+    /// Whether to include text styles (bold, italic, color, alignment, etc.)
+    pub include_styles: bool,
+    /// Whether to write document metadata as `<meta>` elements
+    ///
+    /// Metadata lives in the `<head>`, so it is only written for
+    /// standalone documents.
+    pub include_metadata: bool,
+    /// Whether to write a complete HTML5 document rather than a fragment
+    pub standalone: bool,
+    /// Whether to use parallel processing for large documents (default: true)
+    pub use_parallel: bool,
+    /// Where to put images; `None` leaves images out
+    ///
+    /// Defaults to [`ImageSink::DataUri`], so the output is self-contained.
+    pub image_sink: Option<ImageSink>,
+    /// How to convert EMF/WMF/PICT images before they reach the sink
+    pub metafile_style: MetafileStyle,
+}
+
+impl Default for HtmlOptions {
+    fn default() -> Self {
+        Self {
+            include_styles: true,
+            include_metadata: false,
+            standalone: false,
+            use_parallel: true,
+            image_sink: Some(ImageSink::DataUri),
+            metafile_style: MetafileStyle::Png,
+        }
+    }
+}
+
+impl HtmlOptions {
+    /// Create a new `HtmlOptions` with default values.
+    #[inline]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set whether to include text styles.
+    ///
+    /// When enabled, runs are wrapped in `<strong>`, `<em>`, `<u>`, `<s>`,
+    /// `<sup>` and `<sub>`, colors and font sizes become inline styles, and
+    /// paragraphs carry their alignment.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use litchi::html::HtmlOptions;
+    ///
+    /// let options = HtmlOptions::new().with_styles(false);
+    /// ```
+    #[inline]
+    pub fn with_styles(mut self, include: bool) -> Self {
+        self.include_styles = include;
+        self
+    }
+
+    /// Set whether to include document metadata in the `<head>`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use litchi::html::HtmlOptions;
+    ///
+    /// let options = HtmlOptions::new().with_standalone(true).with_metadata(true);
+    /// ```
+    #[inline]
+    pub fn with_metadata(mut self, include: bool) -> Self {
+        self.include_metadata = include;
+        self
+    }
+
+    /// Set whether to write a complete HTML5 document.
+    ///
+    /// A standalone document has a doctype, a `<head>` with the character
+    /// set and title, and the content in its `<body>`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use litchi::html::HtmlOptions;
+    ///
+    /// let options = HtmlOptions::new().with_standalone(true);
+    /// ```
+    #[inline]
+    pub fn with_standalone(mut self, standalone: bool) -> Self {
+        self.standalone = standalone;
+        self
+    }
+
+    /// Set whether to use parallel processing.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use litchi::html::HtmlOptions;
+    ///
+    /// let options = HtmlOptions::new().with_parallel(false);
+    /// ```
+    #[inline]
+    pub fn with_parallel(mut self, use_parallel: bool) -> Self {
+        self.use_parallel = use_parallel;
+        self
+    }
+
+    /// Set where images are stored.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use litchi::html::HtmlOptions;
+    /// use litchi::markdown::ImageSink;
+    ///
+    /// let options = HtmlOptions::new().with_image_sink(ImageSink::directory("images"));
+    /// ```
+    #[inline]
+    pub fn with_image_sink(mut self, sink: ImageSink) -> Self {
+        self.image_sink = Some(sink);
+        self
+    }
+
+    /// Leave images out of the output.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use litchi::html::HtmlOptions;
+    ///
+    /// let options = HtmlOptions::new().without_images();
+    /// ```
+    #[inline]
+    pub fn without_images(mut self) -> Self {
+        self.image_sink = None;
+        self
+    }
+
+    /// Set how metafile images (EMF, WMF, PICT) are converted.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use litchi::html::HtmlOptions;
+    /// use litchi::markdown::MetafileStyle;
+    ///
+    /// let options = HtmlOptions::new().with_metafile_style(MetafileStyle::Svg);
+    /// ```
+    #[inline]
+    pub fn with_metafile_style(mut self, style: MetafileStyle) -> Self {
+        self.metafile_style = style;
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_html_options() {
+        let options = HtmlOptions::default();
+        assert!(options.include_styles);
+        assert!(!options.standalone);
+        assert!(matches!(options.image_sink, Some(ImageSink::DataUri)));
+
+        let options = HtmlOptions::new()
+            .with_styles(false)
+            .with_metadata(true)
+            .with_standalone(true)
+            .with_parallel(false)
+            .without_images()
+            .with_metafile_style(MetafileStyle::Keep);
+        assert!(!options.include_styles);
+        assert!(options.include_metadata);
+        assert!(options.standalone);
+        assert!(!options.use_parallel);
+        assert!(options.image_sink.is_none());
+        assert_eq!(options.metafile_style, MetafileStyle::Keep);
+    }
+}
@@ -0,0 +1,138 @@
+use super::config::HtmlOptions;
+use super::traits::ToHtml;
+use super::writer::{Block, HtmlWriter, render_paragraph, render_run, render_table};
+/// ToHtml implementations for Document types.
+///
+/// This module implements the `ToHtml` trait for Word document types,
+/// including Document, Paragraph, Run, and Table.
+use crate::common::Result;
+use crate::document::{Document, DocumentElement, Paragraph, Run, Table};
+use crate::markdown::{PlacedImages, paragraph_ranges};
+use rayon::prelude::*;
+
+/// Minimum number of elements to justify parallel processing overhead.
+const PARALLEL_THRESHOLD: usize = 50;
+
+impl ToHtml for Document {
+    fn to_html_with_options(&self, options: &HtmlOptions) -> Result<String> {
+        let mut writer = HtmlWriter::new(options.clone());
+        let metadata = if options.standalone {
+            Some(self.metadata()?)
+        } else {
+            None
+        };
+        writer.begin(metadata.as_ref());
+
+        // Store images up front (sequentially) so their link targets are known
+        let images = PlacedImages::store_in(
+            self.images()?,
+            options.image_sink.as_ref(),
+            options.metafile_style,
+        )?;
+
+        let elements = self.elements()?;
+        let paragraphs = paragraph_ranges(&elements, &images)?;
+
+        // Render elements independently, then assemble them in order so
+        // list items can share their <ul> and <ol> elements
+        let blocks: Vec<Option<Block>> =
+            if options.use_parallel && elements.len() >= PARALLEL_THRESHOLD {
+                elements
+                    .par_iter()
+                    .map(|element| render_element(element, options))
+                    .collect::<Result<_>>()?
+            } else {
+                elements
+                    .iter()
+                    .map(|element| render_element(element, options))
+                    .collect::<Result<_>>()?
+            };
+
+        for (block, paragraphs) in blocks.into_iter().zip(paragraphs) {
+            if let Some(block) = block {
+                writer.push_block(block);
+            }
+            writer.write_images(&images.within(paragraphs));
+        }
+        writer.write_images(images.trailing());
+
+        Ok(writer.finish())
+    }
+}
+
+/// Render a paragraph or table.
+fn render_element(element: &DocumentElement, options: &HtmlOptions) -> Result<Option<Block>> {
+    match element {
+        DocumentElement::Paragraph(para) => render_paragraph(para, options),
+        DocumentElement::Table(table) => Ok(Some(Block::Html(render_table(table, options)?))),
+    }
+}
+
+impl ToHtml for Paragraph {
+    fn to_html_with_options(&self, options: &HtmlOptions) -> Result<String> {
+        let mut writer = HtmlWriter::new(options.clone());
+        if let Some(block) = render_paragraph(self, options)? {
+            writer.push_block(block);
+        }
+        Ok(writer.finish().trim_end().to_string())
+    }
+}
+
+impl ToHtml for Run {
+    fn to_html_with_options(&self, options: &HtmlOptions) -> Result<String> {
+        let mut html = String::new();
+        render_run(&mut html, self, options)?;
+        Ok(html)
+    }
+}
+
+impl ToHtml for Table {
+    fn to_html_with_options(&self, options: &HtmlOptions) -> Result<String> {
+        Ok(render_table(self, options)?.trim_end().to_string())
+    }
+}
+
+#[cfg(all(test, feature = "rtf"))]
+mod tests {
+    use super::*;
+    use crate::rtf::{Alignment, Formatting, Paragraph as RtfParagraph, ParagraphContent};
+    use std::borrow::Cow;
+
+    fn rtf_paragraph(alignment: Alignment, runs: Vec<(&str, Formatting)>) -> Paragraph {
+        let properties = RtfParagraph {
+            alignment,
+            ..Default::default()
+        };
+        let runs = runs
+            .into_iter()
+            .map(|(text, formatting)| {
+                crate::rtf::Run::new(Cow::Owned(text.to_string()), formatting)
+            })
+            .collect();
+        Paragraph::Rtf(ParagraphContent::new(properties, runs))
+    }
+
+    #[test]
+    fn test_paragraph_styles() {
+        let bold = Formatting {
+            bold: true,
+            underline: true,
+            font_size: std::num::NonZeroU16::new(24).unwrap(),
+            ..Default::default()
+        };
+        let para = rtf_paragraph(
+            Alignment::Center,
+            vec![("a < b", bold), (" and c", Formatting::default())],
+        );
+
+        assert_eq!(
+            para.to_html().unwrap(),
+            "<p style=\"text-align:center\"><span style=\"font-size:12pt\"><strong><u>a &lt; b</u></strong></span><span style=\"font-size:12pt\"> and c</span></p>"
+        );
+        assert_eq!(
+            para.to_html_with_options(&HtmlOptions::new().with_styles(false))
+                .unwrap(),
+            "<p>a &lt; b and c</p>"
+        );
+    }
+}
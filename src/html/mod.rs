@@ -0,0 +1,61 @@
+/// HTML conversion functionality for Office documents, presentations and workbooks.
+///
+/// This module converts documents to semantic HTML5, in parallel with the
+/// [`markdown`](crate::markdown) module. Where Markdown has to approximate,
+/// HTML keeps the structure: tables keep their merged cells, runs keep their
+/// colors and sizes, and formulas become MathML.
+///
+/// # Features
+///
+/// - **Semantic output**: Headings, nested lists, tables and sections
+/// - **Style preservation**: Bold, italic, underline, colors, font sizes and
+///   paragraph alignment as elements and inline styles
+/// - **Merged cells**: Table cells keep their `colspan` and `rowspan`
+/// - **Formulas**: OMML and MathType equations as MathML (with the `formula` feature)
+/// - **Self-contained**: Images embedded as data URIs by default
+///
+/// # Quick Start
+///
+/// ```rust,no_run
+/// use litchi::{Document, html::ToHtml};
+///
+/// # fn main() -> Result<(), litchi::Error> {
+/// // Convert a document to an HTML fragment
+/// let doc = Document::open("report.docx")?;
+/// let html = doc.to_html()?;
+/// println!("{}", html);
+///
+/// // Or to a complete page
+/// use litchi::html::HtmlOptions;
+/// let options = HtmlOptions::new()
+///     .with_standalone(true)
+///     .with_metadata(true);
+/// let html = doc.to_html_with_options(&options)?;
+/// # Ok(())
+/// # }
+/// ```
+///
+/// # Output Structure
+///
+/// - **Documents**: Paragraphs, headings, lists and tables in document order
+/// - **Presentations**: One `<section class="slide">` per slide, titled by its first line
+/// - **Workbooks**: One `<section class="sheet">` per worksheet, holding a table
+///   of the displayed cell text
+// Module declarations
+mod config;
+mod traits;
+mod writer;
+
+// Document and presentation implementations are only available when their respective features are enabled
+#[cfg(any(feature = "ole", feature = "ooxml"))]
+mod document;
+
+#[cfg(any(feature = "ole", feature = "ooxml"))]
+mod presentation;
+
+#[cfg(any(feature = "ole", feature = "ooxml", feature = "odf", feature = "iwa"))]
+mod workbook;
+
+// Re-export public API
+pub use config::HtmlOptions;
+pub use traits::ToHtml;
@@ -0,0 +1,92 @@
+use super::config::HtmlOptions;
+use super::traits::ToHtml;
+use super::writer::{HtmlWriter, escape_text};
+/// ToHtml implementations for Presentation types.
+///
+/// This module implements the `ToHtml` trait for PowerPoint presentation types,
+/// including Presentation and Slide.
+///
+/// **Note**: This module is only available when the `ole` or `ooxml` feature is enabled.
+use crate::common::Result;
+use crate::markdown::PlacedImages;
+use crate::presentation::{Presentation, Slide};
+
+impl ToHtml for Presentation {
+    fn to_html_with_options(&self, options: &HtmlOptions) -> Result<String> {
+        let mut writer = HtmlWriter::new(options.clone());
+        let metadata = if options.standalone {
+            self.metadata()?
+        } else {
+            None
+        };
+        writer.begin(metadata.as_ref());
+
+        // Store images up front so each slide can link its own
+        let images = PlacedImages::store_in(
+            self.images()?,
+            options.image_sink.as_ref(),
+            options.metafile_style,
+        )?;
+
+        // Use the same fast text path as the Markdown writer
+        for (slide_num, text) in self.extract_text_for_markdown()? {
+            writer.push_str(&render_slide(slide_num, &text));
+            writer.write_images(images.at(slide_num.saturating_sub(1)));
+            writer.push_str("</section>\n");
+        }
+        writer.write_images(images.trailing());
+
+        Ok(writer.finish())
+    }
+}
+
+/// Render the opening of a slide section: its title and text.
+///
+/// The first line of the text is the title; slides without text are
+/// titled by number.
+fn render_slide(slide_num: usize, text: &str) -> String {
+    let mut html = format!("<section class=\"slide\" id=\"slide-{}\">\n<h2>", slide_num);
+    let mut lines = text.lines();
+    match lines.next().map(str::trim) {
+        Some(title) if !title.is_empty() => escape_text(&mut html, title),
+        _ => html.push_str(&format!("Slide {}", slide_num)),
+    }
+    html.push_str("</h2>\n");
+    render_lines(&mut html, lines);
+    html
+}
+
+/// Write each non-empty line as a paragraph.
+fn render_lines<'a>(html: &mut String, lines: impl Iterator<Item = &'a str>) {
+    for line in lines.map(str::trim).filter(|line| !line.is_empty()) {
+        html.push_str("<p>");
+        escape_text(html, line);
+        html.push_str("</p>\n");
+    }
+}
+
+impl ToHtml for Slide {
+    fn to_html_with_options(&self, _options: &HtmlOptions) -> Result<String> {
+        // Slides carry no run formatting through the unified API
+        let mut html = String::new();
+        render_lines(&mut html, self.text()?.lines());
+        Ok(html)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render_slide() {
+        assert_eq!(
+            render_slide(2, "Results\n\nUp <10%>\n"),
+            "<section class=\"slide\" id=\"slide-2\">\n<h2>Results</h2>\n<p>Up &lt;10%&gt;</p>\n"
+        );
+        assert_eq!(
+            render_slide(3, ""),
+            "<section class=\"slide\" id=\"slide-3\">\n<h2>Slide 3</h2>\n"
+        );
+    }
+}
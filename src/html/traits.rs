@@ -0,0 +1,52 @@
+use super::config::HtmlOptions;
+/// Core trait for HTML conversion.
+///
+/// This module defines the `ToHtml` trait that enables types to be
+/// converted to HTML5.
+use crate::common::Result;
+
+/// Core trait for types that can be converted to HTML.
+///
+/// This trait is implemented for Document, Presentation, Workbook and the
+/// parts of a document (paragraphs, runs, tables).
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// use litchi::{Document, html::ToHtml};
+///
+/// # fn main() -> Result<(), litchi::Error> {
+/// let doc = Document::open("document.docx")?;
+///
+/// // Convert entire document
+/// let html = doc.to_html()?;
+///
+/// // Or convert individual parts
+/// for para in doc.paragraphs()? {
+///     println!("{}", para.to_html()?);
+/// }
+/// # Ok(())
+/// # }
+/// ```
+pub trait ToHtml {
+    /// Convert this item to HTML with default options.
+    fn to_html(&self) -> Result<String> {
+        self.to_html_with_options(&HtmlOptions::default())
+    }
+
+    /// Convert this item to HTML with custom options.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use litchi::{Document, html::{HtmlOptions, ToHtml}};
+    ///
+    /// # fn main() -> Result<(), litchi::Error> {
+    /// let doc = Document::open("document.docx")?;
+    /// let options = HtmlOptions::new().with_standalone(true);
+    /// let html = doc.to_html_with_options(&options)?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    fn to_html_with_options(&self, options: &HtmlOptions) -> Result<String>;
+}
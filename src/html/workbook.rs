@@ -0,0 +1,46 @@
+use super::config::HtmlOptions;
+use super::traits::ToHtml;
+use super::writer::{HtmlWriter, escape_text, render_text_table};
+/// ToHtml implementation for workbooks.
+///
+/// Each worksheet becomes a section holding its title and a table of the
+/// displayed cell text.
+use crate::common::{Error, Result};
+use crate::markdown::PlacedImages;
+use crate::sheet::Workbook;
+
+impl ToHtml for Workbook {
+    fn to_html_with_options(&self, options: &HtmlOptions) -> Result<String> {
+        let mut writer = HtmlWriter::new(options.clone());
+        let metadata = if options.standalone {
+            Some(self.metadata().map_err(sheet_error)?)
+        } else {
+            None
+        };
+        writer.begin(metadata.as_ref());
+
+        for (name, rows) in self.worksheet_rows().map_err(sheet_error)? {
+            let mut html = String::from("<section class=\"sheet\">\n<h2>");
+            escape_text(&mut html, &name);
+            html.push_str("</h2>\n");
+            html.push_str(&render_text_table(&rows));
+            html.push_str("</section>\n");
+            writer.push_str(&html);
+        }
+
+        // Cell-anchored images have no place in a plain table, so they follow it
+        let images = PlacedImages::store_in(
+            self.images().map_err(sheet_error)?,
+            options.image_sink.as_ref(),
+            options.metafile_style,
+        )?;
+        writer.write_images(images.trailing());
+
+        Ok(writer.finish())
+    }
+}
+
+/// Convert a sheet API error into the crate error type.
+fn sheet_error(error: Box<dyn std::error::Error>) -> Error {
+    Error::Other(error.to_string())
+}
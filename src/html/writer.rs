@@ -0,0 +1,522 @@
+use super::config::HtmlOptions;
+/// Low-level writer for HTML generation.
+///
+/// Paragraphs and tables are rendered independently into [`Block`]s, which
+/// lets large documents be rendered in parallel. The [`HtmlWriter`] then
+/// assembles blocks in document order, opening and closing the `<ul>` and
+/// `<ol>` elements that list items need.
+use crate::common::{Metadata, Result, TextAlignment, VerticalPosition};
+#[cfg(any(feature = "ole", feature = "ooxml"))]
+use crate::document::{Paragraph, Run, Table};
+use crate::markdown::StoredImage;
+#[cfg(any(feature = "ole", feature = "ooxml"))]
+use crate::markdown::{CellSpan, analyze_table_spans};
+use std::fmt::Write as FmtWrite;
+
+/// A rendered block of content.
+#[derive(Debug, Clone)]
+pub(crate) enum Block {
+    /// The content of a list item, at a 0-based nesting level
+    ListItem {
+        level: usize,
+        ordered: bool,
+        html: String,
+    },
+    /// Any other block-level HTML
+    Html(String),
+}
+
+/// Writer that assembles blocks into an HTML document or fragment.
+pub(crate) struct HtmlWriter {
+    /// The output buffer
+    buffer: String,
+    /// Current options
+    options: HtmlOptions,
+    /// Open lists, outermost first; `true` for ordered lists
+    lists: Vec<bool>,
+}
+
+impl HtmlWriter {
+    /// Create a new writer with the given options.
+    pub fn new(options: HtmlOptions) -> Self {
+        Self {
+            buffer: String::with_capacity(4096),
+            options,
+            lists: Vec::new(),
+        }
+    }
+
+    /// Start the output: the doctype and `<head>` of a standalone document.
+    ///
+    /// Does nothing for fragments.
+    pub fn begin(&mut self, metadata: Option<&Metadata>) {
+        if !self.options.standalone {
+            return;
+        }
+
+        self.buffer
+            .push_str("<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n");
+        if let Some(metadata) = metadata {
+            if let Some(title) = &metadata.title {
+                self.buffer.push_str("<title>");
+                escape_text(&mut self.buffer, title);
+                self.buffer.push_str("</title>\n");
+            }
+            if self.options.include_metadata {
+                let fields = [
+                    ("author", &metadata.author),
+                    ("description", &metadata.description),
+                    ("keywords", &metadata.keywords),
+                    ("generator", &metadata.application),
+                ];
+                for (name, value) in fields {
+                    if let Some(value) = value {
+                        let _ = write!(self.buffer, "<meta name=\"{}\" content=\"", name);
+                        escape_attribute(&mut self.buffer, value);
+                        self.buffer.push_str("\">\n");
+                    }
+                }
+            }
+        }
+        self.buffer.push_str("</head>\n<body>\n");
+    }
+
+    /// Append a rendered block, opening or closing lists as needed.
+    pub fn push_block(&mut self, block: Block) {
+        match block {
+            Block::ListItem {
+                level,
+                ordered,
+                html,
+            } => {
+                let depth = level + 1;
+                while self.lists.len() > depth {
+                    self.close_list();
+                }
+                if self.lists.len() == depth {
+                    if self.lists.last() == Some(&ordered) {
+                        self.buffer.push_str("</li>\n");
+                    } else {
+                        self.close_list();
+                    }
+                }
+                while self.lists.len() < depth {
+                    // Nested lists go inside the open item
+                    if !self.lists.is_empty() {
+                        self.buffer.push('\n');
+                    }
+                    self.buffer
+                        .push_str(if ordered { "<ol>\n" } else { "<ul>\n" });
+                    self.lists.push(ordered);
+                }
+                self.buffer.push_str("<li>");
+                self.buffer.push_str(&html);
+            },
+            Block::Html(html) => {
+                self.close_lists();
+                self.buffer.push_str(&html);
+            },
+        }
+    }
+
+    /// Close the innermost open list and the item holding it.
+    fn close_list(&mut self) {
+        if let Some(ordered) = self.lists.pop() {
+            self.buffer.push_str(if ordered {
+                "</li>\n</ol>\n"
+            } else {
+                "</li>\n</ul>\n"
+            });
+        }
+    }
+
+    /// Close all open lists.
+    pub fn close_lists(&mut self) {
+        while !self.lists.is_empty() {
+            self.close_list();
+        }
+    }
+
+    /// Write images as `<img>` elements, each in its own paragraph.
+    pub fn write_images(&mut self, images: &[StoredImage]) {
+        if images.is_empty() {
+            return;
+        }
+        self.close_lists();
+        for image in images {
+            self.buffer.push_str("<p><img src=\"");
+            escape_attribute(&mut self.buffer, &image.target);
+            self.buffer.push_str("\" alt=\"");
+            escape_attribute(&mut self.buffer, &image.alt_text);
+            self.buffer.push_str("\"></p>\n");
+        }
+    }
+
+    /// Append raw HTML.
+    pub fn push_str(&mut self, html: &str) {
+        self.close_lists();
+        self.buffer.push_str(html);
+    }
+
+    /// Close open elements and get the final HTML output.
+    pub fn finish(mut self) -> String {
+        self.close_lists();
+        if self.options.standalone {
+            self.buffer.push_str("</body>\n</html>\n");
+        }
+        self.buffer
+    }
+}
+
+/// Render a paragraph as a heading, list item or `<p>`.
+///
+/// Returns `None` for paragraphs without content.
+#[cfg(any(feature = "ole", feature = "ooxml"))]
+pub(crate) fn render_paragraph(para: &Paragraph, options: &HtmlOptions) -> Result<Option<Block>> {
+    // Display formulas follow the paragraph's text
+    #[cfg(feature = "ooxml")]
+    {
+        let display_formulas = match para {
+            Paragraph::Docx(docx_para) => docx_para.paragraph_level_formulas()?,
+            #[allow(unreachable_patterns)]
+            _ => Vec::new(),
+        };
+        if !display_formulas.is_empty() {
+            let mut html = String::new();
+            let runs = para.runs()?;
+            let text = para.text()?;
+            if !text.trim().is_empty() {
+                html.push_str("<p>");
+                render_runs(&mut html, &runs, options)?;
+                html.push_str("</p>\n");
+            }
+            for omml in display_formulas {
+                html.push_str(&omml_to_math(&omml, true));
+                html.push('\n');
+            }
+            return Ok(Some(Block::Html(html)));
+        }
+    }
+
+    let runs = para.runs()?;
+    let mut content = String::new();
+    if runs.is_empty() {
+        escape_text(&mut content, para.text()?.trim());
+    } else {
+        render_runs(&mut content, &runs, options)?;
+    }
+    if content.trim().is_empty() {
+        return Ok(None);
+    }
+
+    let style = if options.include_styles {
+        match para.alignment()? {
+            Some(alignment) if alignment != TextAlignment::Left => {
+                format!(" style=\"text-align:{}\"", alignment.as_css())
+            },
+            _ => String::new(),
+        }
+    } else {
+        String::new()
+    };
+
+    if let Some(level) = para.heading_level()? {
+        let level = level.clamp(1, 6);
+        return Ok(Some(Block::Html(format!(
+            "<h{level}{style}>{content}</h{level}>\n"
+        ))));
+    }
+    if let Some(info) = para.list_info()? {
+        return Ok(Some(Block::ListItem {
+            level: info.level as usize,
+            ordered: info.is_ordered(),
+            html: content,
+        }));
+    }
+    Ok(Some(Block::Html(format!("<p{style}>{content}</p>\n"))))
+}
+
+/// Render runs, grouping consecutive runs with the same hyperlink target
+/// into one `<a>` element.
+#[cfg(any(feature = "ole", feature = "ooxml"))]
+fn render_runs(out: &mut String, runs: &[Run], options: &HtmlOptions) -> Result<()> {
+    let mut open_link: Option<String> = None;
+
+    for run in runs {
+        let link = run.hyperlink()?;
+        if link != open_link {
+            if open_link.is_some() {
+                out.push_str("</a>");
+            }
+            if let Some(url) = &link {
+                out.push_str("<a href=\"");
+                escape_attribute(out, url);
+                out.push_str("\">");
+            }
+            open_link = link;
+        }
+        render_run(out, run, options)?;
+    }
+
+    if open_link.is_some() {
+        out.push_str("</a>");
+    }
+    Ok(())
+}
+
+/// Render a run: a formula as MathML, or text wrapped in its formatting.
+#[cfg(any(feature = "ole", feature = "ooxml"))]
+pub(crate) fn render_run(out: &mut String, run: &Run, options: &HtmlOptions) -> Result<()> {
+    if render_formula(out, run)? {
+        return Ok(());
+    }
+
+    let text = run.text()?;
+    if text.is_empty() {
+        return Ok(());
+    }
+    if !options.include_styles {
+        escape_text(out, &text);
+        return Ok(());
+    }
+
+    let mut style = String::new();
+    if let Some(color) = run.color()? {
+        let _ = write!(style, "color:{};", color);
+    }
+    if let Some(size) = run.font_size()? {
+        let _ = write!(style, "font-size:{}pt;", size);
+    }
+
+    let mut closing: Vec<&str> = Vec::new();
+    if !style.is_empty() {
+        let _ = write!(out, "<span style=\"{}\">", style.trim_end_matches(';'));
+        closing.push("</span>");
+    }
+    let tags = [
+        (run.bold()?, "<strong>", "</strong>"),
+        (run.italic()?, "<em>", "</em>"),
+        (run.underline()?, "<u>", "</u>"),
+        (run.strikethrough()?, "<s>", "</s>"),
+    ];
+    for (enabled, open, close) in tags {
+        if enabled.unwrap_or(false) {
+            out.push_str(open);
+            closing.push(close);
+        }
+    }
+    match run.vertical_position()? {
+        Some(VerticalPosition::Superscript) => {
+            out.push_str("<sup>");
+            closing.push("</sup>");
+        },
+        Some(VerticalPosition::Subscript) => {
+            out.push_str("<sub>");
+            closing.push("</sub>");
+        },
+        _ => {},
+    }
+
+    escape_text(out, &text);
+    for close in closing.iter().rev() {
+        out.push_str(close);
+    }
+    Ok(())
+}
+
+/// Render the formula in a run as inline MathML.
+///
+/// Returns `false` if the run holds no formula.
+#[cfg(any(feature = "ole", feature = "ooxml"))]
+fn render_formula(out: &mut String, run: &Run) -> Result<bool> {
+    match run {
+        #[cfg(feature = "ooxml")]
+        Run::Docx(docx_run) => {
+            if let Some(omml) = docx_run.omml_formula()? {
+                out.push_str(&omml_to_math(&omml, false));
+                return Ok(true);
+            }
+        },
+        #[cfg(feature = "ole")]
+        Run::Doc(doc_run) if doc_run.has_mtef_formula() => {
+            #[cfg(feature = "formula")]
+            match doc_run.mtef_formula_ast() {
+                Some(nodes) => {
+                    let mut converter = crate::formula::MathmlConverter::new();
+                    out.push_str(converter.convert_nodes(nodes, false));
+                },
+                None => out.push_str(&formula_error()),
+            }
+            #[cfg(not(feature = "formula"))]
+            out.push_str(FORMULA_DISABLED);
+            return Ok(true);
+        },
+        #[allow(unreachable_patterns)]
+        _ => {},
+    }
+    Ok(false)
+}
+
+/// Placeholder for formulas when the `formula` feature is disabled.
+#[cfg(not(feature = "formula"))]
+const FORMULA_DISABLED: &str =
+    "<span class=\"formula\">[Formula - enable 'formula' feature]</span>";
+
+/// Convert OMML to a `<math>` element.
+#[cfg(all(feature = "ooxml", feature = "formula"))]
+fn omml_to_math(omml: &str, display: bool) -> String {
+    crate::formula::omml_to_mathml(omml, display).unwrap_or_else(|_| formula_error())
+}
+
+/// Convert OMML to a `<math>` element (placeholder when the formula
+/// feature is disabled).
+#[cfg(all(feature = "ooxml", not(feature = "formula")))]
+fn omml_to_math(_omml: &str, _display: bool) -> String {
+    FORMULA_DISABLED.to_string()
+}
+
+/// A `<math>` element reporting a formula that could not be converted.
+#[cfg(feature = "formula")]
+fn formula_error() -> String {
+    "<math><merror><mtext>Formula conversion error</mtext></merror></math>".to_string()
+}
+
+/// Render a table, with `colspan` and `rowspan` for merged cells.
+#[cfg(any(feature = "ole", feature = "ooxml"))]
+pub(crate) fn render_table(table: &Table, options: &HtmlOptions) -> Result<String> {
+    let rows = table.rows()?;
+    if rows.is_empty() {
+        return Ok(String::new());
+    }
+    let spans = analyze_table_spans(table, options.use_parallel)?;
+
+    let mut html = String::with_capacity(rows.len() * 100);
+    html.push_str("<table>\n");
+    for (row_idx, row) in rows.iter().enumerate() {
+        html.push_str("<tr>");
+        let mut grid_col = 0;
+        for cell in row.cells()? {
+            let span = spans
+                .get(row_idx)
+                .and_then(|row| row.get(grid_col))
+                .copied()
+                .unwrap_or_else(CellSpan::new);
+            grid_col += cell.grid_span().unwrap_or(1);
+
+            // Cells continuing a vertical merge are covered by the cell above
+            if span.skip {
+                continue;
+            }
+            html.push_str("<td");
+            if span.colspan > 1 {
+                let _ = write!(html, " colspan=\"{}\"", span.colspan);
+            }
+            if span.rowspan > 1 {
+                let _ = write!(html, " rowspan=\"{}\"", span.rowspan);
+            }
+            html.push('>');
+            escape_text(&mut html, cell.text()?.trim());
+            html.push_str("</td>");
+        }
+        html.push_str("</tr>\n");
+    }
+    html.push_str("</table>\n");
+    Ok(html)
+}
+
+/// Render rows of cell text as a table.
+pub(crate) fn render_text_table(rows: &[Vec<String>]) -> String {
+    let mut html = String::with_capacity(rows.len() * 100);
+    html.push_str("<table>\n");
+    for row in rows {
+        html.push_str("<tr>");
+        for cell in row {
+            html.push_str("<td>");
+            escape_text(&mut html, cell);
+            html.push_str("</td>");
+        }
+        html.push_str("</tr>\n");
+    }
+    html.push_str("</table>\n");
+    html
+}
+
+/// Escape text content, turning line breaks into `<br>`.
+pub(crate) fn escape_text(out: &mut String, text: &str) {
+    for ch in text.chars() {
+        match ch {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '\n' => out.push_str("<br>"),
+            '\r' => {},
+            _ => out.push(ch),
+        }
+    }
+}
+
+/// Escape a double-quoted attribute value.
+pub(crate) fn escape_attribute(out: &mut String, value: &str) {
+    for ch in value.chars() {
+        match ch {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            _ => out.push(ch),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn item(level: usize, ordered: bool, html: &str) -> Block {
+        Block::ListItem {
+            level,
+            ordered,
+            html: html.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_nested_lists() {
+        let mut writer = HtmlWriter::new(HtmlOptions::default());
+        writer.push_block(item(0, false, "a"));
+        writer.push_block(item(1, true, "b"));
+        writer.push_block(item(1, true, "c"));
+        writer.push_block(item(0, false, "d"));
+        writer.push_block(Block::Html("<p>end</p>\n".to_string()));
+
+        assert_eq!(
+            writer.finish(),
+            "<ul>\n<li>a\n<ol>\n<li>b</li>\n<li>c</li>\n</ol>\n</li>\n<li>d</li>\n</ul>\n<p>end</p>\n"
+        );
+    }
+
+    #[test]
+    fn test_standalone_and_escaping() {
+        let options = HtmlOptions::new().with_standalone(true).with_metadata(true);
+        let metadata = Metadata {
+            title: Some("A & B".to_string()),
+            author: Some("\"Quoted\"".to_string()),
+            ..Default::default()
+        };
+        let mut writer = HtmlWriter::new(options);
+        writer.begin(Some(&metadata));
+        writer.push_str(&render_text_table(&[vec!["<1>".to_string()]]));
+        writer.write_images(&[StoredImage {
+            alt_text: "Logo".to_string(),
+            target: "img/a.png".to_string(),
+        }]);
+        let html = writer.finish();
+
+        assert!(html.starts_with("<!DOCTYPE html>\n<html>\n<head>\n"));
+        assert!(html.contains("<title>A &amp; B</title>"));
+        assert!(html.contains("<meta name=\"author\" content=\"&quot;Quoted&quot;\">"));
+        assert!(html.contains("<table>\n<tr><td>&lt;1&gt;</td></tr>\n</table>\n"));
+        assert!(html.contains("<p><img src=\"img/a.png\" alt=\"Logo\"></p>"));
+        assert!(html.ends_with("</body>\n</html>\n"));
+    }
+}
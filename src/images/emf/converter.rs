@@ -2,15 +2,14 @@
 //
 // Converts EMF metafiles to modern raster formats (PNG, JPEG, WebP).
 //
-// Note: Full EMF rendering would require implementing a complete GDI rendering engine,
-// which is extremely complex. This implementation provides:
-// 1. Extraction of embedded bitmaps from EMF records
-// 2. Placeholder generation with proper dimensions
-// 3. A foundation for future full rendering support
+// Records are played back by the anti-aliased software renderer in the
+// `raster` module, which implements the GDI drawing model: mapping modes and
+// world transforms, pens and brushes, paths, clipping, bitmaps and text.
 
 use super::parser::EmfParser;
+use super::raster::EmfRenderer;
 use crate::common::error::{Error, Result};
-use image::{DynamicImage, ImageBuffer, ImageFormat, Rgba, RgbaImage};
+use image::{DynamicImage, ImageFormat, Rgba};
 use std::io::Cursor;
 
 /// Options for EMF to raster conversion
@@ -79,144 +78,16 @@ impl EmfConverter {
         }
     }
 
-    /// Try to extract embedded bitmap from EMF records
-    ///
-    /// EMF files can contain embedded bitmaps via various record types:
-    /// - EMR_STRETCHDIBITS (0x00000051)
-    /// - EMR_BITBLT (0x00000040)
-    /// - EMR_STRETCHBLT (0x00000041)
-    fn extract_embedded_bitmap(&self) -> Option<DynamicImage> {
-        // Scan through records looking for bitmap data
-        for record in &self.parser.records {
-            match record.record_type {
-                0x00000051 => {
-                    // EMR_STRETCHDIBITS - contains DIB data
-                    if let Some(img) = self.parse_dib_from_record(&record.data) {
-                        return Some(img);
-                    }
-                },
-                0x00000040 | 0x00000041 => {
-                    // EMR_BITBLT or EMR_STRETCHBLT
-                    if let Some(img) = self.parse_bitmap_from_bitblt(&record.data) {
-                        return Some(img);
-                    }
-                },
-                _ => {},
-            }
-        }
-        None
-    }
-
-    /// Parse DIB (Device Independent Bitmap) data from record
-    fn parse_dib_from_record(&self, data: &[u8]) -> Option<DynamicImage> {
-        // DIB data structure is complex; for now, try to detect standard formats
-        // that image crate can handle directly
-        if data.len() < 40 {
-            return None;
-        }
-
-        // Try to load as BMP (DIB is essentially BMP without file header)
-        // We need to construct a proper BMP file header
-        if let Ok(img) = self.construct_bmp_from_dib(data) {
-            return Some(img);
-        }
-
-        None
-    }
-
-    /// Construct a BMP image from DIB data
-    fn construct_bmp_from_dib(&self, dib_data: &[u8]) -> Result<DynamicImage> {
-        // BMP file header is 14 bytes
-        // BITMAPFILEHEADER structure
-        let file_size = 14u32 + dib_data.len() as u32;
-        let pixel_data_offset = 14u32 + 40u32; // header + BITMAPINFOHEADER
-
-        let mut bmp_data = Vec::with_capacity(file_size as usize);
-
-        // BMP file header
-        bmp_data.extend_from_slice(b"BM"); // Signature
-        bmp_data.extend_from_slice(&file_size.to_le_bytes()); // File size
-        bmp_data.extend_from_slice(&[0u8; 4]); // Reserved
-        bmp_data.extend_from_slice(&pixel_data_offset.to_le_bytes()); // Pixel data offset
-
-        // Append DIB data
-        bmp_data.extend_from_slice(dib_data);
-
-        // Try to load the constructed BMP
-        match image::load_from_memory(&bmp_data) {
-            Ok(img) => Ok(img),
-            Err(_) => Err(Error::ParseError("Failed to load DIB as BMP".into())),
-        }
-    }
-
-    /// Parse bitmap from BITBLT record
-    fn parse_bitmap_from_bitblt(&self, _data: &[u8]) -> Option<DynamicImage> {
-        // BITBLT records are complex and may not always contain embedded bitmap data
-        // This is a placeholder for future implementation
-        None
-    }
-
-    /// Create a placeholder image with EMF metadata
-    ///
-    /// This generates a simple placeholder when full rendering isn't available.
-    /// The placeholder includes dimensional information.
-    fn create_placeholder(&self, width: u32, height: u32) -> RgbaImage {
-        let mut img = ImageBuffer::from_pixel(width, height, self.options.background_color);
-
-        // Draw a simple border and diagonal lines to indicate this is a placeholder
-        let border_color = Rgba([128, 128, 128, 255]);
-
-        // Draw border
-        for x in 0..width {
-            if x < height {
-                img.put_pixel(x, 0, border_color);
-                img.put_pixel(x, height - 1, border_color);
-            }
-        }
-        for y in 0..height {
-            if y < width {
-                img.put_pixel(0, y, border_color);
-                img.put_pixel(width - 1, y, border_color);
-            }
-        }
-
-        // Draw diagonals
-        let min_dim = width.min(height);
-        for i in 0..min_dim {
-            img.put_pixel(i, i, border_color);
-            img.put_pixel(i, height - 1 - i, border_color);
-        }
-
-        img
-    }
-
     /// Convert EMF to a raster image
     ///
-    /// This first attempts to extract any embedded bitmaps from the EMF.
-    /// If no bitmaps are found, it creates a placeholder image.
-    ///
-    /// TODO: Implement full EMF rendering engine for complete vector-to-raster conversion
+    /// All records are played back by the software renderer, so vector
+    /// graphics, text and embedded bitmaps appear as they would in GDI.
     pub fn convert_to_image(&self) -> Result<DynamicImage> {
-        let (target_width, target_height) = self.calculate_dimensions();
-
-        // Try to extract embedded bitmap first
-        if let Some(embedded) = self.extract_embedded_bitmap() {
-            // Resize if necessary
-            if embedded.width() != target_width || embedded.height() != target_height {
-                return Ok(DynamicImage::ImageRgba8(image::imageops::resize(
-                    &embedded,
-                    target_width,
-                    target_height,
-                    image::imageops::FilterType::Lanczos3,
-                )));
-            }
-            return Ok(embedded);
-        }
-
-        // No embedded bitmap found - create placeholder
-        // TODO: Full EMF rendering would go here
-        let placeholder = self.create_placeholder(target_width, target_height);
-        Ok(DynamicImage::ImageRgba8(placeholder))
+        let (width, height) = self.calculate_dimensions();
+        let renderer = EmfRenderer::new(&self.parser, width.max(1), height.max(1));
+        Ok(DynamicImage::ImageRgba8(
+            renderer.render(self.options.background_color),
+        ))
     }
 
     /// Convert EMF to specified image format
@@ -255,9 +126,91 @@ impl EmfConverter {
 
 #[cfg(test)]
 mod tests {
+    use super::*;
+
+    /// Minimal EMF with the given bounds, a red rectangle and EOF
+    fn sample_emf(right: i32, bottom: i32) -> Vec<u8> {
+        let mut data = Vec::new();
+        let header: [i32; 22] = [
+            1,
+            88,
+            0,
+            0,
+            right,
+            bottom,
+            0,
+            0,
+            2646,
+            2646,
+            0x464D_4520,
+            0x0001_0000,
+            0,
+            0,
+            0,
+            0,
+            0,
+            96,
+            96,
+            25,
+            25,
+            0,
+        ];
+        let brush = [0x27, 24, 1, 0, 0x0000_00FF, 0];
+        let select = [0x25, 12, 1];
+        let rectangle = [0x2B, 24, 0, 0, right / 2, bottom / 2];
+        let eof = [0x0E, 20, 0, 0, 20];
+        for value in header
+            .iter()
+            .chain(&brush)
+            .chain(&select)
+            .chain(&rectangle)
+            .chain(&eof)
+        {
+            data.extend_from_slice(&value.to_le_bytes());
+        }
+        data
+    }
+
+    fn converter(right: i32, bottom: i32, width: Option<u32>, height: Option<u32>) -> EmfConverter {
+        let parser = EmfParser::new(&sample_emf(right, bottom)).unwrap();
+        EmfConverter::new(
+            parser,
+            EmfToRasterOptions {
+                width,
+                height,
+                ..Default::default()
+            },
+        )
+    }
+
     #[test]
     fn test_dimension_calculation() {
-        // This would require creating a valid EMF parser
-        // Placeholder for future tests
+        assert_eq!(
+            converter(200, 100, None, None).calculate_dimensions(),
+            (200, 100)
+        );
+        assert_eq!(
+            converter(200, 100, Some(100), None).calculate_dimensions(),
+            (100, 50)
+        );
+        assert_eq!(
+            converter(200, 100, None, Some(200)).calculate_dimensions(),
+            (400, 200)
+        );
+        assert_eq!(
+            converter(200, 100, Some(30), Some(40)).calculate_dimensions(),
+            (30, 40)
+        );
+    }
+
+    #[test]
+    fn test_convert_renders_records() {
+        let image = converter(100, 100, Some(50), None)
+            .convert_to_image()
+            .unwrap()
+            .to_rgba8();
+        assert_eq!(image.dimensions(), (50, 50));
+        assert_eq!(image.get_pixel(10, 10), &Rgba([255, 0, 0, 255]));
+        assert_eq!(image.get_pixel(40, 40), &Rgba([255, 255, 255, 255]));
     }
 }
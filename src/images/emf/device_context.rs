@@ -4,7 +4,9 @@
 // objects, transforms, colors, and modes.
 
 use super::gdi_objects::{Brush, Font, Pen};
+use super::raster::Mask;
 use crate::images::svg::color::colorref_to_hex;
+use std::sync::Arc;
 
 /// Text alignment modes
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -187,8 +189,14 @@ pub struct DeviceContext {
     // Map mode
     pub map_mode: u32,
 
+    // Line drawing
+    pub miter_limit: f64,
+    pub clockwise_arcs: bool,
+
     // Clipping
     pub clip_id: Option<String>,
+    /// Clipping region rasterized at output resolution (None = no clipping)
+    pub clip_mask: Option<Arc<Mask>>,
 }
 
 impl Default for DeviceContext {
@@ -214,7 +222,10 @@ impl Default for DeviceContext {
             window_ext_x: 1,
             window_ext_y: 1,
             map_mode: 1, // MM_TEXT
+            miter_limit: 10.0,
+            clockwise_arcs: false,
             clip_id: None,
+            clip_mask: None,
         }
     }
 }
@@ -222,7 +233,10 @@ impl Default for DeviceContext {
 impl DeviceContext {
     /// Transform point from logical to device coordinates
     pub fn transform_point(&self, x: f64, y: f64) -> (f64, f64) {
-        // First apply window-to-viewport mapping
+        // World space to page space first
+        let (x, y) = self.world_transform.transform_point(x, y);
+
+        // Then the window-to-viewport mapping from page space to device space
         let vp_x = if self.window_ext_x != 0 {
             (x - self.window_org_x as f64) * (self.viewport_ext_x as f64 / self.window_ext_x as f64)
                 + self.viewport_org_x as f64
@@ -237,8 +251,7 @@ impl DeviceContext {
             y
         };
 
-        // Then apply world transform
-        self.world_transform.transform_point(vp_x, vp_y)
+        (vp_x, vp_y)
     }
 
     /// Set text color from COLORREF
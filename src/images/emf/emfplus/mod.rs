@@ -0,0 +1,18 @@
+/// EMF+ (GDI+) Record Support
+///
+/// Office stores most charts and SmartArt fallbacks as EMF+ records embedded
+/// in `EMR_COMMENT` records, with a GDI fallback that is often empty. This
+/// module parses those records and plays them back onto an `EmfPlusDevice`,
+/// which both the SVG converter and the raster renderer implement.
+///
+/// References:
+/// - [MS-EMFPLUS]: Enhanced Metafile Format Plus Extensions
+pub mod objects;
+pub mod player;
+pub mod records;
+
+pub use objects::{Brush, Font, Image, Pen, Region, StringFormat};
+pub use player::{EmfPlusDevice, EmfPlusPlayer, Text, TextLayout};
+pub use records::{
+    Color, EmfPlusRecord, EmfPlusRecordType, RectF, emf_plus_payload, parse_records,
+};
@@ -0,0 +1,1053 @@
+/// EMF+ Graphics Objects
+///
+/// Objects are defined by `EmfPlusObject` records and referenced by their
+/// 8-bit ID from drawing records. Large objects are split across several
+/// records with the continuation flag set.
+use super::records::{Color, Reader, RectF};
+use crate::images::emf::gdi_objects::{LineCap, LineJoin};
+use crate::images::emf::parser::EmfParser;
+use crate::images::emf::raster::{EmfRenderer, Path, Point, Transform};
+use image::{Rgba, RgbaImage};
+use std::sync::Arc;
+
+/// Object type stored in bits 8-14 of the object record flags
+pub mod object_type {
+    pub const BRUSH: u16 = 1;
+    pub const PEN: u16 = 2;
+    pub const PATH: u16 = 3;
+    pub const REGION: u16 = 4;
+    pub const IMAGE: u16 = 5;
+    pub const FONT: u16 = 6;
+    pub const STRING_FORMAT: u16 = 7;
+    pub const IMAGE_ATTRIBUTES: u16 = 8;
+    pub const CUSTOM_LINE_CAP: u16 = 9;
+}
+
+/// Largest decoded bitmap dimension, to guard against corrupt headers
+const MAX_DIMENSION: u32 = 16384;
+
+/// Units of measure (UnitType)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Unit {
+    #[default]
+    World,
+    Display,
+    Pixel,
+    Point,
+    Inch,
+    Document,
+    Millimeter,
+}
+
+impl Unit {
+    pub fn from_u32(value: u32) -> Self {
+        match value {
+            1 => Unit::Display,
+            2 => Unit::Pixel,
+            3 => Unit::Point,
+            4 => Unit::Inch,
+            5 => Unit::Document,
+            6 => Unit::Millimeter,
+            _ => Unit::World,
+        }
+    }
+
+    /// Device pixels per unit at the given resolution
+    ///
+    /// World, display and pixel units map one to one onto device pixels.
+    pub fn pixels(self, dpi: f64) -> f64 {
+        match self {
+            Unit::World | Unit::Display | Unit::Pixel => 1.0,
+            Unit::Point => dpi / 72.0,
+            Unit::Inch => dpi,
+            Unit::Document => dpi / 300.0,
+            Unit::Millimeter => dpi / 25.4,
+        }
+    }
+}
+
+/// How a brush is repeated outside its defining area (WrapMode)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum WrapMode {
+    #[default]
+    Tile,
+    TileFlipX,
+    TileFlipY,
+    TileFlipXY,
+    Clamp,
+}
+
+impl WrapMode {
+    pub fn from_u32(value: u32) -> Self {
+        match value {
+            1 => WrapMode::TileFlipX,
+            2 => WrapMode::TileFlipY,
+            3 => WrapMode::TileFlipXY,
+            4 => WrapMode::Clamp,
+            _ => WrapMode::Tile,
+        }
+    }
+
+    /// Map a gradient parameter into [0, 1] along the gradient axis
+    pub fn wrap(self, t: f64) -> f64 {
+        match self {
+            WrapMode::Clamp => t.clamp(0.0, 1.0),
+            WrapMode::Tile | WrapMode::TileFlipY => t.rem_euclid(1.0),
+            WrapMode::TileFlipX | WrapMode::TileFlipXY => {
+                let t = t.rem_euclid(2.0);
+                if t > 1.0 { 2.0 - t } else { t }
+            },
+        }
+    }
+}
+
+/// A brush (EmfPlusBrush)
+#[derive(Debug, Clone)]
+pub enum Brush {
+    Solid(Color),
+    Hatch {
+        style: u32,
+        foreground: Color,
+        background: Color,
+    },
+    Texture {
+        image: Arc<Image>,
+        /// Image pixels to world units
+        transform: Transform,
+        wrap: WrapMode,
+    },
+    LinearGradient {
+        /// The gradient runs from the left to the right edge of this rectangle
+        rect: RectF,
+        /// Gradient stops as (position, color), sorted by position
+        stops: Vec<(f64, Color)>,
+        /// Brush space to world units
+        transform: Transform,
+        wrap: WrapMode,
+    },
+    PathGradient {
+        center: Point,
+        center_color: Color,
+        /// Closed boundary in brush space
+        boundary: Vec<Point>,
+        /// Colors at the boundary points (the last one repeats)
+        surround_colors: Vec<Color>,
+        /// Gradient stops from the boundary (0) to the center (1)
+        stops: Vec<(f64, Color)>,
+        transform: Transform,
+    },
+}
+
+impl Brush {
+    /// Parse an EmfPlusBrush object
+    pub fn parse(reader: &mut Reader) -> Option<Self> {
+        let _version = reader.u32()?;
+        let brush_type = reader.u32()?;
+        match brush_type {
+            0 => Some(Brush::Solid(reader.color()?)),
+            1 => Some(Brush::Hatch {
+                style: reader.u32()?,
+                foreground: reader.color()?,
+                background: reader.color()?,
+            }),
+            2 => {
+                let flags = reader.u32()?;
+                let wrap = WrapMode::from_u32(reader.u32()?);
+                let transform = if flags & brush_data::TRANSFORM != 0 {
+                    reader.transform()?
+                } else {
+                    Transform::IDENTITY
+                };
+                let image = Image::parse(reader)?;
+                Some(Brush::Texture {
+                    image: Arc::new(image),
+                    transform,
+                    wrap,
+                })
+            },
+            3 => Self::parse_path_gradient(reader),
+            4 => Self::parse_linear_gradient(reader),
+            _ => None,
+        }
+    }
+
+    fn parse_linear_gradient(reader: &mut Reader) -> Option<Self> {
+        let flags = reader.u32()?;
+        let wrap = WrapMode::from_u32(reader.u32()?);
+        let rect = reader.rect_f()?;
+        let start = reader.color()?;
+        let end = reader.color()?;
+        reader.skip(8)?;
+
+        let transform = if flags & brush_data::TRANSFORM != 0 {
+            reader.transform()?
+        } else {
+            Transform::IDENTITY
+        };
+
+        let stops = if flags & brush_data::PRESET_COLORS != 0 {
+            read_preset_colors(reader)?
+        } else if flags & (brush_data::BLEND_FACTORS_H | brush_data::BLEND_FACTORS_V) != 0 {
+            read_blend_factors(reader, start, end)?
+        } else {
+            vec![(0.0, start), (1.0, end)]
+        };
+
+        Some(Brush::LinearGradient {
+            rect,
+            stops,
+            transform,
+            wrap,
+        })
+    }
+
+    fn parse_path_gradient(reader: &mut Reader) -> Option<Self> {
+        let flags = reader.u32()?;
+        let _wrap = reader.u32()?;
+        let center_color = reader.color()?;
+        let center = reader.point_f()?;
+        let count = reader.u32()? as usize;
+        if count > reader.remaining().len() / 4 {
+            return None;
+        }
+        let surround_colors = (0..count)
+            .map(|_| reader.color())
+            .collect::<Option<Vec<_>>>()?;
+
+        let boundary = if flags & brush_data::PATH != 0 {
+            let size = reader.u32()? as usize;
+            let figures = parse_path_points(&mut Reader::new(reader.bytes(size)?))?;
+            figures
+                .into_iter()
+                .flat_map(|figure| figure.points.into_iter().map(|(point, _)| point))
+                .collect()
+        } else {
+            let count = reader.u32()? as usize;
+            reader.points(count, false, false)?
+        };
+
+        let transform = if flags & brush_data::TRANSFORM != 0 {
+            reader.transform()?
+        } else {
+            Transform::IDENTITY
+        };
+
+        let outer = surround_colors.first().copied().unwrap_or(center_color);
+        let stops = if flags & brush_data::PRESET_COLORS != 0 {
+            read_preset_colors(reader)?
+        } else if flags & brush_data::BLEND_FACTORS_H != 0 {
+            read_blend_factors(reader, outer, center_color)?
+        } else {
+            vec![(0.0, outer), (1.0, center_color)]
+        };
+
+        Some(Brush::PathGradient {
+            center,
+            center_color,
+            boundary,
+            surround_colors,
+            stops,
+            transform,
+        })
+    }
+
+    /// A representative solid color, used where gradients are unsupported
+    pub fn average_color(&self) -> Color {
+        match self {
+            Brush::Solid(color) => *color,
+            Brush::Hatch { foreground, .. } => *foreground,
+            Brush::Texture { .. } => Color {
+                r: 128,
+                g: 128,
+                b: 128,
+                a: 255,
+            },
+            Brush::LinearGradient { stops, .. } | Brush::PathGradient { stops, .. } => {
+                match (stops.first(), stops.last()) {
+                    (Some(first), Some(last)) => first.1.lerp(last.1, 0.5),
+                    _ => Color::default(),
+                }
+            },
+        }
+    }
+}
+
+/// Flags of the optional brush data (BrushData)
+pub mod brush_data {
+    pub const PATH: u32 = 0x0000_0001;
+    pub const TRANSFORM: u32 = 0x0000_0002;
+    pub const PRESET_COLORS: u32 = 0x0000_0004;
+    pub const BLEND_FACTORS_H: u32 = 0x0000_0008;
+    pub const BLEND_FACTORS_V: u32 = 0x0000_0010;
+}
+
+fn read_preset_colors(reader: &mut Reader) -> Option<Vec<(f64, Color)>> {
+    let count = reader.u32()? as usize;
+    if count > reader.remaining().len() / 8 {
+        return None;
+    }
+    let positions = (0..count)
+        .map(|_| reader.f32())
+        .collect::<Option<Vec<_>>>()?;
+    let colors = (0..count)
+        .map(|_| reader.color())
+        .collect::<Option<Vec<_>>>()?;
+    Some(positions.into_iter().zip(colors).collect())
+}
+
+fn read_blend_factors(reader: &mut Reader, start: Color, end: Color) -> Option<Vec<(f64, Color)>> {
+    let count = reader.u32()? as usize;
+    if count > reader.remaining().len() / 8 {
+        return None;
+    }
+    let positions = (0..count)
+        .map(|_| reader.f32())
+        .collect::<Option<Vec<_>>>()?;
+    let factors = (0..count)
+        .map(|_| reader.f32())
+        .collect::<Option<Vec<_>>>()?;
+    Some(
+        positions
+            .into_iter()
+            .zip(factors)
+            .map(|(position, factor)| (position, start.lerp(end, factor)))
+            .collect(),
+    )
+}
+
+/// A pen (EmfPlusPen)
+#[derive(Debug, Clone)]
+pub struct Pen {
+    pub width: f64,
+    pub unit: Unit,
+    pub start_cap: LineCap,
+    pub end_cap: LineCap,
+    pub join: LineJoin,
+    pub miter_limit: f64,
+    /// Dash and gap lengths in multiples of the pen width (empty = solid)
+    pub dashes: Vec<f64>,
+    pub dash_offset: f64,
+    pub brush: Brush,
+}
+
+/// Flags of the optional pen data (PenData)
+pub mod pen_data {
+    pub const TRANSFORM: u32 = 0x0001;
+    pub const START_CAP: u32 = 0x0002;
+    pub const END_CAP: u32 = 0x0004;
+    pub const JOIN: u32 = 0x0008;
+    pub const MITER_LIMIT: u32 = 0x0010;
+    pub const LINE_STYLE: u32 = 0x0020;
+    pub const DASHED_LINE_CAP: u32 = 0x0040;
+    pub const DASHED_LINE_OFFSET: u32 = 0x0080;
+    pub const DASHED_LINE: u32 = 0x0100;
+    pub const NON_CENTER: u32 = 0x0200;
+    pub const COMPOUND_LINE: u32 = 0x0400;
+    pub const CUSTOM_START_CAP: u32 = 0x0800;
+    pub const CUSTOM_END_CAP: u32 = 0x1000;
+}
+
+impl Pen {
+    /// Parse an EmfPlusPen object
+    pub fn parse(reader: &mut Reader) -> Option<Self> {
+        let _version = reader.u32()?;
+        let _pen_type = reader.u32()?;
+        let flags = reader.u32()?;
+        let unit = Unit::from_u32(reader.u32()?);
+        let width = reader.f32()?;
+
+        let mut pen = Pen {
+            width,
+            unit,
+            start_cap: LineCap::Flat,
+            end_cap: LineCap::Flat,
+            join: LineJoin::Miter,
+            miter_limit: 10.0,
+            dashes: Vec::new(),
+            dash_offset: 0.0,
+            brush: Brush::Solid(Color {
+                a: 255,
+                ..Default::default()
+            }),
+        };
+
+        if flags & pen_data::TRANSFORM != 0 {
+            reader.skip(24)?;
+        }
+        if flags & pen_data::START_CAP != 0 {
+            pen.start_cap = line_cap(reader.u32()?);
+        }
+        if flags & pen_data::END_CAP != 0 {
+            pen.end_cap = line_cap(reader.u32()?);
+        }
+        if flags & pen_data::JOIN != 0 {
+            pen.join = match reader.u32()? {
+                1 => LineJoin::Bevel,
+                2 => LineJoin::Round,
+                _ => LineJoin::Miter,
+            };
+        }
+        if flags & pen_data::MITER_LIMIT != 0 {
+            pen.miter_limit = reader.f32()?;
+        }
+        if flags & pen_data::LINE_STYLE != 0 {
+            pen.dashes = match reader.u32()? {
+                1 => vec![3.0, 1.0],
+                2 => vec![1.0, 1.0],
+                3 => vec![3.0, 1.0, 1.0, 1.0],
+                4 => vec![3.0, 1.0, 1.0, 1.0, 1.0, 1.0],
+                _ => Vec::new(),
+            };
+        }
+        if flags & pen_data::DASHED_LINE_CAP != 0 {
+            reader.skip(4)?;
+        }
+        if flags & pen_data::DASHED_LINE_OFFSET != 0 {
+            pen.dash_offset = reader.f32()?;
+        }
+        if flags & pen_data::DASHED_LINE != 0 {
+            let count = reader.u32()? as usize;
+            if count > reader.remaining().len() / 4 {
+                return None;
+            }
+            pen.dashes = (0..count)
+                .map(|_| reader.f32())
+                .collect::<Option<Vec<_>>>()?;
+        }
+        if flags & pen_data::NON_CENTER != 0 {
+            reader.skip(4)?;
+        }
+        if flags & pen_data::COMPOUND_LINE != 0 {
+            let count = reader.u32()? as usize;
+            reader.skip(count.checked_mul(4)?)?;
+        }
+        for flag in [pen_data::CUSTOM_START_CAP, pen_data::CUSTOM_END_CAP] {
+            if flags & flag != 0 {
+                let size = reader.u32()? as usize;
+                reader.skip(size)?;
+            }
+        }
+
+        pen.brush = Brush::parse(reader)?;
+        Some(pen)
+    }
+}
+
+fn line_cap(value: u32) -> LineCap {
+    match value {
+        1 | 0x11 => LineCap::Square,
+        2 | 0x12 => LineCap::Round,
+        _ => LineCap::Flat,
+    }
+}
+
+/// Path point type flags (PathPointType)
+mod point_type {
+    pub const START: u8 = 0x00;
+    pub const BEZIER: u8 = 0x03;
+    pub const TYPE_MASK: u8 = 0x0F;
+    pub const CLOSE_SUBPATH: u8 = 0x80;
+}
+
+/// Parse an EmfPlusPath object into a path
+pub fn parse_path(reader: &mut Reader) -> Option<Path> {
+    let figures = parse_path_points(reader)?;
+    let mut path = Path::new();
+    for figure in figures {
+        path.append(&figure.into_path());
+    }
+    Some(path)
+}
+
+/// A figure of a path with its point types
+struct Figure {
+    points: Vec<(Point, u8)>,
+}
+
+impl Figure {
+    fn into_path(self) -> Path {
+        let mut path = Path::new();
+        let mut i = 0;
+        while i < self.points.len() {
+            let (point, kind) = self.points[i];
+            match kind & point_type::TYPE_MASK {
+                point_type::START => path.move_to(point),
+                point_type::BEZIER if i + 2 < self.points.len() => {
+                    let (c2, _) = self.points[i + 1];
+                    let (end, end_kind) = self.points[i + 2];
+                    path.cubic_to(point, c2, end);
+                    i += 2;
+                    if end_kind & point_type::CLOSE_SUBPATH != 0 {
+                        path.close();
+                    }
+                    i += 1;
+                    continue;
+                },
+                _ => path.line_to(point),
+            }
+            if kind & point_type::CLOSE_SUBPATH != 0 {
+                path.close();
+            }
+            i += 1;
+        }
+        path
+    }
+}
+
+/// Path flags (EmfPlusPath PathPointFlags)
+mod path_flags {
+    pub const RLE: u32 = 0x1000;
+    pub const COMPRESSED: u32 = 0x4000;
+    pub const RELATIVE: u32 = 0x0800;
+}
+
+fn parse_path_points(reader: &mut Reader) -> Option<Vec<Figure>> {
+    let _version = reader.u32()?;
+    let count = reader.u32()? as usize;
+    let flags = reader.u32()?;
+    let points = reader.points(
+        count,
+        flags & path_flags::COMPRESSED != 0,
+        flags & path_flags::RELATIVE != 0,
+    )?;
+
+    let types = if flags & path_flags::RLE != 0 {
+        // Run-length encoded point types: run count, then the type
+        let mut types = Vec::with_capacity(count);
+        while types.len() < count {
+            let run = (reader.u8()? & 0x3F) as usize;
+            let kind = reader.u8()?;
+            types.extend(std::iter::repeat_n(kind, run.max(1)));
+        }
+        types.truncate(count);
+        types
+    } else {
+        reader.bytes(count)?.to_vec()
+    };
+
+    let mut figures: Vec<Figure> = Vec::new();
+    for (point, kind) in points.into_iter().zip(types) {
+        if kind & point_type::TYPE_MASK == point_type::START || figures.is_empty() {
+            figures.push(Figure { points: Vec::new() });
+        }
+        if let Some(figure) = figures.last_mut() {
+            figure.points.push((point, kind));
+        }
+    }
+    // The first point of each figure always starts it
+    for figure in &mut figures {
+        if let Some(first) = figure.points.first_mut() {
+            first.1 &= !point_type::TYPE_MASK;
+        }
+    }
+    Some(figures)
+}
+
+/// Region combine mode (CombineMode)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CombineMode {
+    Replace,
+    Intersect,
+    Union,
+    Xor,
+    Exclude,
+    Complement,
+}
+
+impl CombineMode {
+    pub fn from_u32(value: u32) -> Self {
+        match value {
+            1 => CombineMode::Intersect,
+            2 => CombineMode::Union,
+            3 => CombineMode::Xor,
+            4 => CombineMode::Exclude,
+            5 => CombineMode::Complement,
+            _ => CombineMode::Replace,
+        }
+    }
+}
+
+/// A region (EmfPlusRegion) as a tree of combined areas
+#[derive(Debug, Clone)]
+pub enum Region {
+    Empty,
+    Infinite,
+    Path(Path),
+    Combine(CombineMode, Box<Region>, Box<Region>),
+}
+
+impl Region {
+    /// Parse an EmfPlusRegion object
+    pub fn parse(reader: &mut Reader) -> Option<Self> {
+        let _version = reader.u32()?;
+        let _node_count = reader.u32()?;
+        Self::parse_node(reader, 0)
+    }
+
+    fn parse_node(reader: &mut Reader, depth: usize) -> Option<Self> {
+        if depth > 64 {
+            return None;
+        }
+        let node_type = reader.u32()?;
+        match node_type {
+            1..=5 => {
+                let left = Self::parse_node(reader, depth + 1)?;
+                let right = Self::parse_node(reader, depth + 1)?;
+                Some(Region::Combine(
+                    CombineMode::from_u32(node_type),
+                    Box::new(left),
+                    Box::new(right),
+                ))
+            },
+            0x1000_0000 => {
+                let rect = reader.rect_f()?;
+                Some(Region::Path(rect_path(&rect)))
+            },
+            0x1000_0001 => {
+                let size = reader.u32()? as usize;
+                parse_path(&mut Reader::new(reader.bytes(size)?)).map(Region::Path)
+            },
+            0x1000_0002 => Some(Region::Empty),
+            0x1000_0003 => Some(Region::Infinite),
+            _ => None,
+        }
+    }
+
+    /// The region mapped through a transform
+    pub fn transform(&self, transform: &Transform) -> Region {
+        match self {
+            Region::Path(path) => Region::Path(path.transform(transform)),
+            Region::Combine(mode, left, right) => Region::Combine(
+                *mode,
+                Box::new(left.transform(transform)),
+                Box::new(right.transform(transform)),
+            ),
+            other => other.clone(),
+        }
+    }
+
+    /// Combine `other` into this region
+    pub fn combine(self, mode: CombineMode, other: Region) -> Region {
+        match mode {
+            CombineMode::Replace => other,
+            _ => Region::Combine(mode, Box::new(self), Box::new(other)),
+        }
+    }
+}
+
+/// A closed rectangle path
+pub fn rect_path(rect: &RectF) -> Path {
+    Path::rect(rect.x, rect.y, rect.right(), rect.bottom())
+}
+
+/// Font style flags (FontStyle)
+pub mod font_style {
+    pub const BOLD: u32 = 0x01;
+    pub const ITALIC: u32 = 0x02;
+    pub const UNDERLINE: u32 = 0x04;
+    pub const STRIKEOUT: u32 = 0x08;
+}
+
+/// A font (EmfPlusFont)
+#[derive(Debug, Clone)]
+pub struct Font {
+    pub em_size: f64,
+    pub unit: Unit,
+    pub style: u32,
+    pub family: String,
+}
+
+impl Font {
+    /// Parse an EmfPlusFont object
+    pub fn parse(reader: &mut Reader) -> Option<Self> {
+        let _version = reader.u32()?;
+        let em_size = reader.f32()?;
+        let unit = Unit::from_u32(reader.u32()?);
+        let style = reader.u32()?;
+        let _reserved = reader.u32()?;
+        let length = reader.u32()? as usize;
+        let family = reader.utf16(length)?;
+        Some(Font {
+            em_size,
+            unit,
+            style,
+            family: family.trim_end_matches('\0').to_string(),
+        })
+    }
+
+    pub fn is_bold(&self) -> bool {
+        self.style & font_style::BOLD != 0
+    }
+
+    pub fn is_italic(&self) -> bool {
+        self.style & font_style::ITALIC != 0
+    }
+
+    pub fn is_underline(&self) -> bool {
+        self.style & font_style::UNDERLINE != 0
+    }
+
+    pub fn is_strikeout(&self) -> bool {
+        self.style & font_style::STRIKEOUT != 0
+    }
+}
+
+/// Text alignment along one axis (StringAlignment)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum StringAlignment {
+    #[default]
+    Near,
+    Center,
+    Far,
+}
+
+impl StringAlignment {
+    pub fn from_u32(value: u32) -> Self {
+        match value {
+            1 => StringAlignment::Center,
+            2 => StringAlignment::Far,
+            _ => StringAlignment::Near,
+        }
+    }
+}
+
+/// String format flags (StringFormatFlags)
+pub mod string_format_flags {
+    pub const DIRECTION_RIGHT_TO_LEFT: u32 = 0x0000_0001;
+    pub const DIRECTION_VERTICAL: u32 = 0x0000_0002;
+    pub const NO_WRAP: u32 = 0x0000_1000;
+    pub const NO_CLIP: u32 = 0x0000_4000;
+}
+
+/// Text layout options (EmfPlusStringFormat)
+#[derive(Debug, Clone, Default)]
+pub struct StringFormat {
+    pub flags: u32,
+    pub alignment: StringAlignment,
+    pub line_alignment: StringAlignment,
+}
+
+impl StringFormat {
+    /// Parse an EmfPlusStringFormat object
+    pub fn parse(reader: &mut Reader) -> Option<Self> {
+        let _version = reader.u32()?;
+        let flags = reader.u32()?;
+        let _language = reader.u32()?;
+        let alignment = StringAlignment::from_u32(reader.u32()?);
+        let line_alignment = StringAlignment::from_u32(reader.u32()?);
+        Some(StringFormat {
+            flags,
+            alignment,
+            line_alignment,
+        })
+    }
+}
+
+/// Metafile kinds embedded in image objects (MetafileDataType)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MetafileType {
+    Wmf,
+    WmfPlaceable,
+    Emf,
+    EmfPlusOnly,
+    EmfPlusDual,
+}
+
+/// An image (EmfPlusImage)
+#[derive(Debug, Clone)]
+pub enum Image {
+    Bitmap(RgbaImage),
+    Metafile { kind: MetafileType, data: Vec<u8> },
+}
+
+/// Pixel formats of uncompressed bitmaps (PixelFormat)
+mod pixel_format {
+    pub const INDEXED_1BPP: u32 = 0x0003_0101;
+    pub const INDEXED_4BPP: u32 = 0x0003_0402;
+    pub const INDEXED_8BPP: u32 = 0x0003_0803;
+    pub const RGB555_16BPP: u32 = 0x0002_1005;
+    pub const RGB565_16BPP: u32 = 0x0002_1006;
+    pub const RGB_24BPP: u32 = 0x0002_1808;
+    pub const RGB_32BPP: u32 = 0x0002_2009;
+    pub const ARGB_32BPP: u32 = 0x0026_200A;
+    pub const PARGB_32BPP: u32 = 0x000E_200B;
+    /// Format flag: the bitmap has a palette
+    pub const INDEXED: u32 = 0x0001_0000;
+}
+
+impl Image {
+    /// Parse an EmfPlusImage object
+    pub fn parse(reader: &mut Reader) -> Option<Self> {
+        let _version = reader.u32()?;
+        match reader.u32()? {
+            1 => Self::parse_bitmap(reader).map(Image::Bitmap),
+            2 => {
+                let kind = match reader.u32()? {
+                    1 => MetafileType::Wmf,
+                    2 => MetafileType::WmfPlaceable,
+                    3 => MetafileType::Emf,
+                    4 => MetafileType::EmfPlusOnly,
+                    _ => MetafileType::EmfPlusDual,
+                };
+                let size = reader.u32()? as usize;
+                let data = reader.bytes(size.min(reader.remaining().len()))?.to_vec();
+                Some(Image::Metafile { kind, data })
+            },
+            _ => None,
+        }
+    }
+
+    fn parse_bitmap(reader: &mut Reader) -> Option<RgbaImage> {
+        let width = reader.i32()?;
+        let height = reader.i32()?;
+        let stride = reader.i32()?;
+        let format = reader.u32()?;
+        let bitmap_type = reader.u32()?;
+
+        if bitmap_type == 1 {
+            // Compressed: PNG, JPEG, GIF, TIFF or BMP file data
+            return image::load_from_memory(reader.remaining())
+                .ok()
+                .map(|img| img.to_rgba8());
+        }
+
+        let (width, height) = (u32::try_from(width).ok()?, u32::try_from(height).ok()?);
+        if width == 0 || height == 0 || width > MAX_DIMENSION || height > MAX_DIMENSION {
+            return None;
+        }
+
+        let palette = if format & pixel_format::INDEXED != 0 {
+            let _flags = reader.u32()?;
+            let count = reader.u32()? as usize;
+            if count > 256 {
+                return None;
+            }
+            (0..count)
+                .map(|_| reader.color().map(|c| Rgba([c.r, c.g, c.b, c.a])))
+                .collect::<Option<Vec<_>>>()?
+        } else {
+            Vec::new()
+        };
+
+        let bits = reader.remaining();
+        let bits_per_pixel = (format >> 8) & 0xFF;
+        let stride = if stride > 0 {
+            stride as usize
+        } else {
+            (width as usize * bits_per_pixel as usize).div_ceil(32) * 4
+        };
+        if bits.len()
+            < stride * (height as usize - 1)
+                + (width as usize * bits_per_pixel as usize).div_ceil(8)
+        {
+            return None;
+        }
+
+        let mut image = RgbaImage::new(width, height);
+        for y in 0..height {
+            let row = &bits[y as usize * stride..];
+            for x in 0..width {
+                let i = x as usize;
+                let pixel = match format {
+                    pixel_format::INDEXED_1BPP
+                    | pixel_format::INDEXED_4BPP
+                    | pixel_format::INDEXED_8BPP => {
+                        let bpp = bits_per_pixel as usize;
+                        let bit = i * bpp;
+                        let index =
+                            (row[bit / 8] >> (8 - bpp - bit % 8)) as usize & ((1 << bpp) - 1);
+                        palette.get(index).copied().unwrap_or(Rgba([0, 0, 0, 255]))
+                    },
+                    pixel_format::RGB555_16BPP | pixel_format::RGB565_16BPP => {
+                        let v = u16::from_le_bytes([row[i * 2], row[i * 2 + 1]]) as u32;
+                        let (r, g, b) = if format == pixel_format::RGB565_16BPP {
+                            ((v >> 11) & 0x1F, (v >> 5) & 0x3F, v & 0x1F)
+                        } else {
+                            ((v >> 10) & 0x1F, (v >> 5) & 0x1F, v & 0x1F)
+                        };
+                        let g_max = if format == pixel_format::RGB565_16BPP {
+                            63
+                        } else {
+                            31
+                        };
+                        Rgba([
+                            (r * 255 / 31) as u8,
+                            (g * 255 / g_max) as u8,
+                            (b * 255 / 31) as u8,
+                            255,
+                        ])
+                    },
+                    pixel_format::RGB_24BPP => {
+                        Rgba([row[i * 3 + 2], row[i * 3 + 1], row[i * 3], 255])
+                    },
+                    pixel_format::RGB_32BPP => {
+                        Rgba([row[i * 4 + 2], row[i * 4 + 1], row[i * 4], 255])
+                    },
+                    pixel_format::ARGB_32BPP => {
+                        Rgba([row[i * 4 + 2], row[i * 4 + 1], row[i * 4], row[i * 4 + 3]])
+                    },
+                    pixel_format::PARGB_32BPP => {
+                        let alpha = row[i * 4 + 3] as u32;
+                        let unpremultiply = |c: u8| {
+                            (c as u32 * 255)
+                                .checked_div(alpha)
+                                .map_or(0, |v| v.min(255) as u8)
+                        };
+                        Rgba([
+                            unpremultiply(row[i * 4 + 2]),
+                            unpremultiply(row[i * 4 + 1]),
+                            unpremultiply(row[i * 4]),
+                            alpha as u8,
+                        ])
+                    },
+                    _ => return None,
+                };
+                image.put_pixel(x, y, pixel);
+            }
+        }
+        Some(image)
+    }
+
+    /// Size in pixels (metafiles use their bounds)
+    pub fn size(&self) -> (f64, f64) {
+        match self {
+            Image::Bitmap(image) => (image.width() as f64, image.height() as f64),
+            Image::Metafile { kind, data } => match kind {
+                MetafileType::Emf | MetafileType::EmfPlusOnly | MetafileType::EmfPlusDual => {
+                    EmfParser::new(data)
+                        .map(|parser| (parser.width().max(1) as f64, parser.height().max(1) as f64))
+                        .unwrap_or((1.0, 1.0))
+                },
+                _ => self.to_rgba().map_or((1.0, 1.0), |image| {
+                    (image.width() as f64, image.height() as f64)
+                }),
+            },
+        }
+    }
+
+    /// Decode to RGBA pixels, rendering metafiles at their natural size
+    pub fn to_rgba(&self) -> Option<RgbaImage> {
+        match self {
+            Image::Bitmap(image) => Some(image.clone()),
+            Image::Metafile { kind, data } => match kind {
+                MetafileType::Emf | MetafileType::EmfPlusOnly | MetafileType::EmfPlusDual => {
+                    let parser = EmfParser::new(data).ok()?;
+                    let width = (parser.width().max(1) as u32).min(MAX_DIMENSION / 4);
+                    let height = (parser.height().max(1) as u32).min(MAX_DIMENSION / 4);
+                    Some(EmfRenderer::new(&parser, width, height).render(Rgba([0, 0, 0, 0])))
+                },
+                MetafileType::Wmf | MetafileType::WmfPlaceable => {
+                    let png = crate::images::wmf::convert_wmf_to_png(data, None, None).ok()?;
+                    image::load_from_memory(&png).ok().map(|img| img.to_rgba8())
+                },
+            },
+        }
+    }
+}
+
+/// Any object stored in the object table
+#[derive(Debug, Clone)]
+pub enum Object {
+    Brush(Brush),
+    Pen(Pen),
+    Path(Path),
+    Region(Region),
+    Image(Arc<Image>),
+    Font(Font),
+    StringFormat(StringFormat),
+}
+
+impl Object {
+    /// Parse object data of the given type; unsupported types yield `None`
+    pub fn parse(object_type: u16, data: &[u8]) -> Option<Self> {
+        let reader = &mut Reader::new(data);
+        match object_type {
+            object_type::BRUSH => Brush::parse(reader).map(Object::Brush),
+            object_type::PEN => Pen::parse(reader).map(Object::Pen),
+            object_type::PATH => parse_path(reader).map(Object::Path),
+            object_type::REGION => Region::parse(reader).map(Object::Region),
+            object_type::IMAGE => Image::parse(reader).map(|image| Object::Image(Arc::new(image))),
+            object_type::FONT => Font::parse(reader).map(Object::Font),
+            object_type::STRING_FORMAT => StringFormat::parse(reader).map(Object::StringFormat),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn words(values: &[u32]) -> Vec<u8> {
+        values.iter().flat_map(|v| v.to_le_bytes()).collect()
+    }
+
+    #[test]
+    fn test_parse_path_object() {
+        // Triangle with a closed last point, float coordinates
+        let mut data = words(&[0xDBC0_1002, 3, 0]);
+        for v in [0.0f32, 0.0, 10.0, 0.0, 10.0, 10.0] {
+            data.extend_from_slice(&v.to_le_bytes());
+        }
+        data.extend_from_slice(&[0x00, 0x01, 0x81]);
+
+        let Some(Object::Path(path)) = Object::parse(object_type::PATH, &data) else {
+            panic!("expected a path");
+        };
+        let polylines = path.flatten(0.1);
+        assert_eq!(polylines.len(), 1);
+        assert!(polylines[0].closed);
+        assert_eq!(polylines[0].points.len(), 3);
+    }
+
+    #[test]
+    fn test_parse_linear_gradient_brush() {
+        let mut data = words(&[0xDBC0_1002, 4, 0, 0]);
+        for v in [0.0f32, 0.0, 100.0, 50.0] {
+            data.extend_from_slice(&v.to_le_bytes());
+        }
+        data.extend_from_slice(&words(&[0xFFFF_0000, 0xFF00_00FF, 0, 0]));
+
+        let Some(Object::Brush(Brush::LinearGradient {
+            rect, stops, wrap, ..
+        })) = Object::parse(object_type::BRUSH, &data)
+        else {
+            panic!("expected a linear gradient");
+        };
+        assert_eq!(rect.width, 100.0);
+        assert_eq!(wrap, WrapMode::Tile);
+        assert_eq!(stops.len(), 2);
+        assert_eq!(stops[0].1.r, 255);
+        assert_eq!(stops[1].1.b, 255);
+    }
+
+    #[test]
+    fn test_parse_pen_with_dash_style() {
+        let mut data = words(&[0xDBC0_1002, 0, pen_data::LINE_STYLE, 2]);
+        data.extend_from_slice(&2.5f32.to_le_bytes());
+        data.extend_from_slice(&words(&[1]));
+        data.extend_from_slice(&words(&[0xDBC0_1002, 0, 0xFF00_8000]));
+
+        let Some(Object::Pen(pen)) = Object::parse(object_type::PEN, &data) else {
+            panic!("expected a pen");
+        };
+        assert_eq!(pen.width, 2.5);
+        assert_eq!(pen.unit, Unit::Pixel);
+        assert_eq!(pen.dashes, vec![3.0, 1.0]);
+        assert!(matches!(
+            pen.brush,
+            Brush::Solid(Color {
+                g: 0x80,
+                a: 255,
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn test_wrap_modes() {
+        assert_eq!(WrapMode::Tile.wrap(1.25), 0.25);
+        assert_eq!(WrapMode::TileFlipX.wrap(1.25), 0.75);
+        assert_eq!(WrapMode::Clamp.wrap(-0.5), 0.0);
+    }
+}
@@ -0,0 +1,1069 @@
+/// EMF+ Record Playback
+///
+/// Interprets EMF+ records and forwards the resulting drawing operations to
+/// an output device. Geometry is passed in world units together with the
+/// world-to-device transform, so devices can stroke and paint exactly like
+/// GDI+ does under arbitrary transforms.
+use super::objects::*;
+use super::records::*;
+use crate::images::emf::raster::{Path, Point, Transform};
+use std::f64::consts::PI;
+
+/// Number of slots in the EMF+ object table
+const OBJECT_TABLE_SIZE: usize = 64;
+
+/// Default resolution until the EMF+ header provides one
+const DEFAULT_DPI: f64 = 96.0;
+
+/// Text to draw, either laid out in a rectangle or at explicit positions
+pub enum TextLayout<'a> {
+    /// EmfPlusDrawString: a string laid out inside a rectangle
+    Rect {
+        text: &'a str,
+        rect: RectF,
+        format: Option<&'a StringFormat>,
+    },
+    /// EmfPlusDrawDriverString: characters or glyph indices on baselines
+    ///
+    /// `positions` holds one origin per glyph, or only the first one when
+    /// the following glyphs are placed by their natural advance.
+    Positioned {
+        codes: &'a [u16],
+        glyph_indices: bool,
+        positions: &'a [Point],
+    },
+}
+
+/// A text drawing operation
+pub struct Text<'a> {
+    pub font: &'a Font,
+    /// Em height in world units
+    pub em_size: f64,
+    pub brush: &'a Brush,
+    pub layout: TextLayout<'a>,
+}
+
+/// Output device for EMF+ playback
+///
+/// All geometry is in world units; `to_device` maps it to the reference
+/// device pixels of the metafile (the same space as EMF device units).
+pub trait EmfPlusDevice {
+    /// Fill a path with a brush
+    fn fill_path(&mut self, path: &Path, winding: bool, brush: &Brush, to_device: &Transform);
+
+    /// Stroke a path with a pen of the given width in world units
+    fn stroke_path(&mut self, path: &Path, pen: &Pen, width: f64, to_device: &Transform);
+
+    /// Draw text
+    fn draw_text(&mut self, text: &Text, to_device: &Transform);
+
+    /// Draw the `src` part of an image; `to_device` maps image pixels
+    fn draw_image(&mut self, image: &Image, src: RectF, to_device: &Transform);
+
+    /// Replace the clipping region (in device units, `None` = no clipping)
+    fn set_clip(&mut self, clip: Option<&Region>);
+
+    /// Fill the whole clipping area with a color
+    fn clear(&mut self, color: Color);
+}
+
+/// Graphics state saved by Save and BeginContainer
+#[derive(Debug, Clone)]
+struct GraphicsState {
+    world: Transform,
+    page_unit: Unit,
+    page_scale: f64,
+    /// Clipping region in device units
+    clip: Option<Region>,
+}
+
+impl Default for GraphicsState {
+    fn default() -> Self {
+        Self {
+            world: Transform::IDENTITY,
+            page_unit: Unit::Display,
+            page_scale: 1.0,
+            clip: None,
+        }
+    }
+}
+
+/// An object split across several object records
+struct PartialObject {
+    id: u8,
+    object_type: u16,
+    total_size: usize,
+    data: Vec<u8>,
+}
+
+/// EMF+ playback state
+pub struct EmfPlusPlayer {
+    objects: Vec<Option<Object>>,
+    partial: Option<PartialObject>,
+    state: GraphicsState,
+    saved: Vec<(u32, GraphicsState)>,
+    dpi: (f64, f64),
+    /// Whether an EMF+ header has been seen
+    active: bool,
+    /// Whether the last EMF+ record was GetDC
+    get_dc: bool,
+    /// Picture bounds in device units
+    bounds: RectF,
+}
+
+impl EmfPlusPlayer {
+    /// Create a player for a metafile with the given device bounds
+    pub fn new(bounds: RectF) -> Self {
+        Self {
+            objects: vec![None; OBJECT_TABLE_SIZE],
+            partial: None,
+            state: GraphicsState::default(),
+            saved: Vec::new(),
+            dpi: (DEFAULT_DPI, DEFAULT_DPI),
+            active: false,
+            get_dc: false,
+            bounds,
+        }
+    }
+
+    /// Whether GDI records should be drawn
+    ///
+    /// Once EMF+ records are present, the GDI records of a dual metafile are
+    /// only a fallback and are skipped, except those following EmfPlusGetDC.
+    pub fn gdi_enabled(&self) -> bool {
+        !self.active || self.get_dc
+    }
+
+    /// Play the EMF+ records in an `EMR_COMMENT` record
+    ///
+    /// `comment` is the record data without its type and size. Returns
+    /// false if the comment does not hold EMF+ records.
+    pub fn play_comment(&mut self, comment: &[u8], device: &mut impl EmfPlusDevice) -> bool {
+        let Some(payload) = emf_plus_payload(comment) else {
+            return false;
+        };
+        for record in parse_records(payload) {
+            self.play(&record, device);
+        }
+        true
+    }
+
+    /// World units to device pixels
+    fn to_device(&self) -> Transform {
+        let scale = self.state.page_scale;
+        let sx = self.state.page_unit.pixels(self.dpi.0) * scale;
+        let sy = self.state.page_unit.pixels(self.dpi.1) * scale;
+        self.state.world.then(&Transform::scale(sx, sy))
+    }
+
+    /// Convert a length in `unit` to world units
+    fn world_length(&self, value: f64, unit: Unit) -> f64 {
+        if unit == Unit::World {
+            return value;
+        }
+        let page = self.state.page_unit.pixels(self.dpi.0) * self.state.page_scale;
+        if page == 0.0 {
+            value
+        } else {
+            value * unit.pixels(self.dpi.0) / page
+        }
+    }
+
+    fn object(&self, id: u32) -> Option<&Object> {
+        self.objects.get(id as usize & 0xFF)?.as_ref()
+    }
+
+    /// Brush given inline as a color or by object ID
+    fn brush(&self, flags: u16, value: u32) -> Option<Brush> {
+        if flags & record_flags::SOLID_COLOR != 0 {
+            return Some(Brush::Solid(Color::from_argb(value)));
+        }
+        match self.object(value)? {
+            Object::Brush(brush) => Some(brush.clone()),
+            _ => None,
+        }
+    }
+
+    fn pen(&self, id: u32) -> Option<&Pen> {
+        match self.object(id)? {
+            Object::Pen(pen) => Some(pen),
+            _ => None,
+        }
+    }
+
+    fn fill(
+        &self,
+        device: &mut impl EmfPlusDevice,
+        path: &Path,
+        winding: bool,
+        brush: Option<Brush>,
+    ) {
+        if let Some(brush) = brush {
+            device.fill_path(path, winding, &brush, &self.to_device());
+        }
+    }
+
+    fn stroke(&self, device: &mut impl EmfPlusDevice, path: &Path, pen_id: u32) {
+        let Some(pen) = self.pen(pen_id) else {
+            return;
+        };
+        let to_device = self.to_device();
+        let mut width = self.world_length(pen.width, pen.unit);
+        if width <= 0.0 {
+            // Zero-width pens draw one device pixel wide lines
+            width = 1.0 / to_device.mean_scale().max(1e-9);
+        }
+        device.stroke_path(path, pen, width, &to_device);
+    }
+
+    fn update_clip(&mut self, mode: CombineMode, region: Region, device: &mut impl EmfPlusDevice) {
+        let current = self.state.clip.take().unwrap_or(Region::Infinite);
+        let clip = match (mode, current) {
+            (CombineMode::Replace, _) | (CombineMode::Intersect, Region::Infinite) => region,
+            (mode, current) => current.combine(mode, region),
+        };
+        self.state.clip = match clip {
+            Region::Infinite => None,
+            clip => Some(clip),
+        };
+        device.set_clip(self.state.clip.as_ref());
+    }
+
+    fn restore(&mut self, index: u32, device: &mut impl EmfPlusDevice) {
+        if let Some(position) = self.saved.iter().rposition(|(i, _)| *i == index) {
+            let (_, state) = self.saved.swap_remove(position);
+            self.saved.truncate(position);
+            self.state = state;
+            device.set_clip(self.state.clip.as_ref());
+        }
+    }
+
+    fn play(&mut self, record: &EmfPlusRecord, device: &mut impl EmfPlusDevice) {
+        let Some(kind) = EmfPlusRecordType::from_u16(record.record_type) else {
+            return;
+        };
+        self.get_dc = kind == EmfPlusRecordType::GetDc;
+
+        let flags = record.flags;
+        let compressed = flags & record_flags::COMPRESSED != 0;
+        let relative = flags & record_flags::RELATIVE != 0;
+        let id = (flags & 0xFF) as u32;
+        let reader = &mut Reader::new(record.data);
+
+        use EmfPlusRecordType as R;
+        match kind {
+            R::Header => {
+                let _version = reader.u32();
+                let _flags = reader.u32();
+                if let (Some(x), Some(y)) = (reader.u32(), reader.u32())
+                    && x > 0
+                    && y > 0
+                {
+                    self.dpi = (x as f64, y as f64);
+                }
+                self.active = true;
+            },
+            R::Object => self.define_object(flags, record.data),
+            R::Clear => {
+                if let Some(color) = reader.color() {
+                    device.clear(color);
+                }
+            },
+
+            // Filled shapes
+            R::FillRects => {
+                let (Some(brush), Some(count)) = (reader.u32(), reader.u32()) else {
+                    return;
+                };
+                let mut path = Path::new();
+                for _ in 0..count.min(record.data.len() as u32) {
+                    let Some(rect) = reader.rect(compressed) else {
+                        break;
+                    };
+                    path.append(&rect_path(&rect));
+                }
+                self.fill(device, &path, true, self.brush(flags, brush));
+            },
+            R::FillPolygon => {
+                let (Some(brush), Some(count)) = (reader.u32(), reader.u32()) else {
+                    return;
+                };
+                if let Some(points) = reader.points(count as usize, compressed, relative) {
+                    let path = polygon_path(&points, true);
+                    let winding = flags & record_flags::WINDING != 0;
+                    self.fill(device, &path, winding, self.brush(flags, brush));
+                }
+            },
+            R::FillEllipse => {
+                if let (Some(brush), Some(rect)) = (reader.u32(), reader.rect(compressed)) {
+                    let path = Path::ellipse(rect.x, rect.y, rect.right(), rect.bottom());
+                    self.fill(device, &path, false, self.brush(flags, brush));
+                }
+            },
+            R::FillPie => {
+                if let (Some(brush), Some(start), Some(sweep), Some(rect)) = (
+                    reader.u32(),
+                    reader.f32(),
+                    reader.f32(),
+                    reader.rect(compressed),
+                ) {
+                    let path = arc_path(&rect, start, sweep, ArcKind::Pie);
+                    self.fill(device, &path, false, self.brush(flags, brush));
+                }
+            },
+            R::FillPath => {
+                if let (Some(brush), Some(Object::Path(path))) = (reader.u32(), self.object(id)) {
+                    self.fill(device, path, false, self.brush(flags, brush));
+                }
+            },
+            R::FillClosedCurve => {
+                let (Some(brush), Some(tension), Some(count)) =
+                    (reader.u32(), reader.f32(), reader.u32())
+                else {
+                    return;
+                };
+                if let Some(points) = reader.points(count as usize, compressed, relative) {
+                    let path = cardinal_spline(&points, tension, true);
+                    let winding = flags & record_flags::WINDING != 0;
+                    self.fill(device, &path, winding, self.brush(flags, brush));
+                }
+            },
+            R::FillRegion => {
+                if let (Some(brush), Some(Object::Region(region))) = (reader.u32(), self.object(id))
+                    && let Some(brush) = self.brush(flags, brush)
+                {
+                    self.fill_region(device, region.clone(), &brush);
+                }
+            },
+
+            // Outlines
+            R::DrawRects => {
+                let Some(count) = reader.u32() else {
+                    return;
+                };
+                let mut path = Path::new();
+                for _ in 0..count.min(record.data.len() as u32) {
+                    let Some(rect) = reader.rect(compressed) else {
+                        break;
+                    };
+                    path.append(&rect_path(&rect));
+                }
+                self.stroke(device, &path, id);
+            },
+            R::DrawLines => {
+                let Some(count) = reader.u32() else {
+                    return;
+                };
+                if let Some(points) = reader.points(count as usize, compressed, relative) {
+                    let path = polygon_path(&points, flags & record_flags::CLOSED != 0);
+                    self.stroke(device, &path, id);
+                }
+            },
+            R::DrawEllipse => {
+                if let Some(rect) = reader.rect(compressed) {
+                    let path = Path::ellipse(rect.x, rect.y, rect.right(), rect.bottom());
+                    self.stroke(device, &path, id);
+                }
+            },
+            R::DrawPie | R::DrawArc => {
+                if let (Some(start), Some(sweep), Some(rect)) =
+                    (reader.f32(), reader.f32(), reader.rect(compressed))
+                {
+                    let arc = if kind == R::DrawPie {
+                        ArcKind::Pie
+                    } else {
+                        ArcKind::Open
+                    };
+                    self.stroke(device, &arc_path(&rect, start, sweep, arc), id);
+                }
+            },
+            R::DrawPath => {
+                if let (Some(pen), Some(Object::Path(path))) = (reader.u32(), self.object(id)) {
+                    self.stroke(device, path, pen);
+                }
+            },
+            R::DrawClosedCurve => {
+                let (Some(tension), Some(count)) = (reader.f32(), reader.u32()) else {
+                    return;
+                };
+                if let Some(points) = reader.points(count as usize, compressed, relative) {
+                    self.stroke(device, &cardinal_spline(&points, tension, true), id);
+                }
+            },
+            R::DrawCurve => {
+                let (Some(tension), Some(offset), Some(segments), Some(count)) =
+                    (reader.f32(), reader.u32(), reader.u32(), reader.u32())
+                else {
+                    return;
+                };
+                if let Some(points) = reader.points(count as usize, compressed, false) {
+                    let path = cardinal_spline(&points, tension, false);
+                    // Only segments [offset, offset + segments) are drawn
+                    let path = if offset == 0 && segments as usize + 1 >= points.len() {
+                        path
+                    } else {
+                        let start = (offset as usize).min(points.len());
+                        let end = (start + segments as usize + 1).min(points.len());
+                        spline_segment(&points, tension, start, end)
+                    };
+                    self.stroke(device, &path, id);
+                }
+            },
+            R::DrawBeziers => {
+                let Some(count) = reader.u32() else {
+                    return;
+                };
+                if let Some(points) = reader.points(count as usize, compressed, relative)
+                    && let Some(&first) = points.first()
+                {
+                    let mut path = Path::new();
+                    path.move_to(first);
+                    for chunk in points[1..].chunks_exact(3) {
+                        path.cubic_to(chunk[0], chunk[1], chunk[2]);
+                    }
+                    self.stroke(device, &path, id);
+                }
+            },
+
+            // Images
+            R::DrawImage => {
+                let (Some(_attributes), Some(_unit), Some(src), Some(dest)) = (
+                    reader.u32(),
+                    reader.u32(),
+                    reader.rect_f(),
+                    reader.rect(compressed),
+                ) else {
+                    return;
+                };
+                if let Some(Object::Image(image)) = self.object(id)
+                    && src.width != 0.0
+                    && src.height != 0.0
+                {
+                    let to_dest = Transform::translate(-src.x, -src.y)
+                        .then(&Transform::scale(
+                            dest.width / src.width,
+                            dest.height / src.height,
+                        ))
+                        .then(&Transform::translate(dest.x, dest.y));
+                    device.draw_image(image, src, &to_dest.then(&self.to_device()));
+                }
+            },
+            R::DrawImagePoints => {
+                let (Some(_attributes), Some(_unit), Some(src), Some(count)) =
+                    (reader.u32(), reader.u32(), reader.rect_f(), reader.u32())
+                else {
+                    return;
+                };
+                if count != 3 || src.width == 0.0 || src.height == 0.0 {
+                    return;
+                }
+                if let Some(points) = reader.points(3, compressed, relative)
+                    && let Some(Object::Image(image)) = self.object(id)
+                {
+                    // Map the source rectangle onto the parallelogram
+                    let (p0, p1, p2) = (points[0], points[1], points[2]);
+                    let a = (p1.x - p0.x) / src.width;
+                    let b = (p1.y - p0.y) / src.width;
+                    let c = (p2.x - p0.x) / src.height;
+                    let d = (p2.y - p0.y) / src.height;
+                    let to_dest = Transform {
+                        a,
+                        b,
+                        c,
+                        d,
+                        e: p0.x - a * src.x - c * src.y,
+                        f: p0.y - b * src.x - d * src.y,
+                    };
+                    device.draw_image(image, src, &to_dest.then(&self.to_device()));
+                }
+            },
+
+            // Text
+            R::DrawString => {
+                let (Some(brush), Some(format), Some(length), Some(rect)) =
+                    (reader.u32(), reader.u32(), reader.u32(), reader.rect_f())
+                else {
+                    return;
+                };
+                let Some(text) = reader.utf16(length as usize) else {
+                    return;
+                };
+                let (Some(Object::Font(font)), Some(brush)) =
+                    (self.object(id), self.brush(flags, brush))
+                else {
+                    return;
+                };
+                let format = match self.object(format) {
+                    Some(Object::StringFormat(format)) => Some(format),
+                    _ => None,
+                };
+                let text = Text {
+                    font,
+                    em_size: self.world_length(font.em_size, font.unit),
+                    brush: &brush,
+                    layout: TextLayout::Rect {
+                        text: &text,
+                        rect,
+                        format,
+                    },
+                };
+                device.draw_text(&text, &self.to_device());
+            },
+            R::DrawDriverString => self.draw_driver_string(flags, reader, device),
+
+            // Graphics state
+            R::Save | R::BeginContainerNoParams => {
+                if let Some(index) = reader.u32() {
+                    self.saved.push((index, self.state.clone()));
+                }
+            },
+            R::Restore | R::EndContainer => {
+                if let Some(index) = reader.u32() {
+                    self.restore(index, device);
+                }
+            },
+            R::BeginContainer => {
+                let (Some(dest), Some(src), Some(index)) =
+                    (reader.rect_f(), reader.rect_f(), reader.u32())
+                else {
+                    return;
+                };
+                self.saved.push((index, self.state.clone()));
+                if src.width != 0.0 && src.height != 0.0 {
+                    let unit = Unit::from_u32(id);
+                    let scale = self.world_length(1.0, unit);
+                    let container = Transform::translate(-src.x, -src.y)
+                        .then(&Transform::scale(
+                            dest.width / src.width * scale,
+                            dest.height / src.height * scale,
+                        ))
+                        .then(&Transform::translate(dest.x * scale, dest.y * scale));
+                    self.state.world = container.then(&self.state.world);
+                }
+            },
+
+            // Transforms
+            R::SetWorldTransform => {
+                if let Some(transform) = reader.transform() {
+                    self.state.world = transform;
+                }
+            },
+            R::ResetWorldTransform => self.state.world = Transform::IDENTITY,
+            R::MultiplyWorldTransform => {
+                if let Some(transform) = reader.transform() {
+                    self.multiply_world(flags, transform);
+                }
+            },
+            R::TranslateWorldTransform => {
+                if let (Some(dx), Some(dy)) = (reader.f32(), reader.f32()) {
+                    self.multiply_world(flags, Transform::translate(dx, dy));
+                }
+            },
+            R::ScaleWorldTransform => {
+                if let (Some(sx), Some(sy)) = (reader.f32(), reader.f32()) {
+                    self.multiply_world(flags, Transform::scale(sx, sy));
+                }
+            },
+            R::RotateWorldTransform => {
+                if let Some(angle) = reader.f32() {
+                    // GDI+ angles run clockwise on the y-down device
+                    self.multiply_world(flags, Transform::rotate(-angle.to_radians()));
+                }
+            },
+            R::SetPageTransform => {
+                if let Some(scale) = reader.f32() {
+                    self.state.page_unit = Unit::from_u32(id);
+                    self.state.page_scale = if scale > 0.0 { scale } else { 1.0 };
+                }
+            },
+
+            // Clipping
+            R::ResetClip => {
+                self.state.clip = None;
+                device.set_clip(None);
+            },
+            R::SetClipRect => {
+                if let Some(rect) = reader.rect_f() {
+                    let region = Region::Path(rect_path(&rect).transform(&self.to_device()));
+                    self.update_clip(combine_mode(flags), region, device);
+                }
+            },
+            R::SetClipPath => {
+                if let Some(Object::Path(path)) = self.object(id) {
+                    let region = Region::Path(path.transform(&self.to_device()));
+                    self.update_clip(combine_mode(flags), region, device);
+                }
+            },
+            R::SetClipRegion => {
+                if let Some(Object::Region(region)) = self.object(id) {
+                    let region = region.transform(&self.to_device());
+                    self.update_clip(combine_mode(flags), region, device);
+                }
+            },
+            R::OffsetClip => {
+                if let (Some(dx), Some(dy)) = (reader.f32(), reader.f32())
+                    && let Some(clip) = &self.state.clip
+                {
+                    let offset = self.to_device().apply_vector(Point::new(dx, dy));
+                    self.state.clip =
+                        Some(clip.transform(&Transform::translate(offset.x, offset.y)));
+                    device.set_clip(self.state.clip.as_ref());
+                }
+            },
+
+            _ => {},
+        }
+    }
+
+    fn multiply_world(&mut self, flags: u16, transform: Transform) {
+        self.state.world = if flags & record_flags::APPEND != 0 {
+            self.state.world.then(&transform)
+        } else {
+            transform.then(&self.state.world)
+        };
+    }
+
+    fn define_object(&mut self, flags: u16, data: &[u8]) {
+        let id = (flags & 0xFF) as u8;
+        let object_type = (flags >> 8) & 0x7F;
+
+        let complete = if flags & record_flags::CONTINUED != 0 {
+            // Fragment of a large object, preceded by the total size
+            let Some(total_size) = data
+                .get(..4)
+                .map(|b| u32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+            else {
+                return;
+            };
+            let fragment = &data[4..];
+            let partial = match self.partial.take() {
+                Some(partial) if partial.id == id && partial.object_type == object_type => partial,
+                _ => PartialObject {
+                    id,
+                    object_type,
+                    total_size: total_size as usize,
+                    data: Vec::with_capacity((total_size as usize).min(1 << 24)),
+                },
+            };
+            let mut partial = partial;
+            partial.data.extend_from_slice(fragment);
+            if partial.data.len() < partial.total_size {
+                self.partial = Some(partial);
+                return;
+            }
+            partial.data
+        } else {
+            match self.partial.take() {
+                Some(mut partial) if partial.id == id && partial.object_type == object_type => {
+                    partial.data.extend_from_slice(data);
+                    partial.data
+                },
+                _ => data.to_vec(),
+            }
+        };
+
+        if let Some(slot) = self.objects.get_mut(id as usize) {
+            *slot = Object::parse(object_type, &complete);
+        }
+    }
+
+    fn draw_driver_string(
+        &mut self,
+        flags: u16,
+        reader: &mut Reader,
+        device: &mut impl EmfPlusDevice,
+    ) {
+        let (Some(brush), Some(options), Some(matrix_present), Some(count)) =
+            (reader.u32(), reader.u32(), reader.u32(), reader.u32())
+        else {
+            return;
+        };
+        let count = count as usize;
+        if count > reader.remaining().len() / 2 {
+            return;
+        }
+        let Some(codes) = (0..count).map(|_| reader.u16()).collect::<Option<Vec<_>>>() else {
+            return;
+        };
+        let realized_advance = options & driver_string_options::REALIZED_ADVANCE != 0;
+        let Some(positions) = reader.points(if realized_advance { 1 } else { count }, false, false)
+        else {
+            return;
+        };
+        let matrix = if matrix_present != 0 {
+            reader.transform()
+        } else {
+            None
+        };
+
+        let font_id = (flags & 0xFF) as u32;
+        let (Some(Object::Font(font)), Some(brush)) =
+            (self.object(font_id), self.brush(flags, brush))
+        else {
+            return;
+        };
+        let text = Text {
+            font,
+            em_size: self.world_length(font.em_size, font.unit),
+            brush: &brush,
+            layout: TextLayout::Positioned {
+                codes: &codes,
+                glyph_indices: options & driver_string_options::CMAP_LOOKUP == 0,
+                positions: &positions,
+            },
+        };
+        let to_device = match matrix {
+            Some(matrix) => matrix.then(&self.to_device()),
+            None => self.to_device(),
+        };
+        device.draw_text(&text, &to_device);
+    }
+
+    /// Fill a region by clipping a rectangle that covers the picture
+    fn fill_region(&mut self, device: &mut impl EmfPlusDevice, region: Region, brush: &Brush) {
+        let to_device = self.to_device();
+        if let Region::Path(path) = &region {
+            device.fill_path(path, false, brush, &to_device);
+            return;
+        }
+        let Some(to_world) = to_device.invert() else {
+            return;
+        };
+        let saved = self.state.clip.clone();
+        self.update_clip(CombineMode::Intersect, region.transform(&to_device), device);
+        let cover = rect_path(&self.bounds).transform(&to_world);
+        device.fill_path(&cover, false, brush, &to_device);
+        self.state.clip = saved;
+        device.set_clip(self.state.clip.as_ref());
+    }
+}
+
+/// DrawDriverString option flags (DriverStringOptionsFlags)
+pub mod driver_string_options {
+    pub const CMAP_LOOKUP: u32 = 0x0001;
+    pub const VERTICAL: u32 = 0x0002;
+    pub const REALIZED_ADVANCE: u32 = 0x0004;
+    pub const LIMIT_SUBPIXEL: u32 = 0x0008;
+}
+
+/// Combine mode in bits 8-11 of the clipping record flags
+fn combine_mode(flags: u16) -> CombineMode {
+    CombineMode::from_u32(((flags >> 8) & 0x0F) as u32)
+}
+
+fn polygon_path(points: &[Point], closed: bool) -> Path {
+    let mut path = Path::new();
+    for (i, &point) in points.iter().enumerate() {
+        if i == 0 {
+            path.move_to(point);
+        } else {
+            path.line_to(point);
+        }
+    }
+    if closed && !points.is_empty() {
+        path.close();
+    }
+    path
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum ArcKind {
+    Open,
+    Pie,
+}
+
+/// Elliptical arc with GDI+ angles (degrees, clockwise from the x axis)
+fn arc_path(rect: &RectF, start: f64, sweep: f64, kind: ArcKind) -> Path {
+    let center = Point::new(rect.x + rect.width / 2.0, rect.y + rect.height / 2.0);
+    let (rx, ry) = (rect.width.abs() / 2.0, rect.height.abs() / 2.0);
+    let mut path = Path::new();
+    if rx == 0.0 || ry == 0.0 {
+        return path;
+    }
+
+    // Angles are measured on the ellipse, not on the unit circle
+    let parametric = |degrees: f64| {
+        let (sin, cos) = degrees.to_radians().sin_cos();
+        (sin / ry).atan2(cos / rx)
+    };
+    let t0 = parametric(start);
+    let mut delta = if sweep.abs() >= 360.0 {
+        2.0 * PI * sweep.signum()
+    } else {
+        parametric(start + sweep) - t0
+    };
+    if sweep > 0.0 && delta < 0.0 {
+        delta += 2.0 * PI;
+    } else if sweep < 0.0 && delta > 0.0 {
+        delta -= 2.0 * PI;
+    }
+
+    if kind == ArcKind::Pie {
+        path.move_to(center);
+    }
+    // Path arcs run counter-clockwise on a y-down surface
+    path.arc(center, rx, ry, -t0, -delta);
+    if kind == ArcKind::Pie {
+        path.close();
+    }
+    path
+}
+
+/// Cardinal spline through the points, as GDI+ draws curves
+fn cardinal_spline(points: &[Point], tension: f64, closed: bool) -> Path {
+    if closed {
+        let mut path = Path::new();
+        let n = points.len();
+        if n < 2 {
+            return path;
+        }
+        let k = tension * 0.3;
+        path.move_to(points[0]);
+        for i in 0..n {
+            let p0 = points[(i + n - 1) % n];
+            let p1 = points[i];
+            let p2 = points[(i + 1) % n];
+            let p3 = points[(i + 2) % n];
+            path.cubic_to(
+                Point::new(p1.x + k * (p2.x - p0.x), p1.y + k * (p2.y - p0.y)),
+                Point::new(p2.x - k * (p3.x - p1.x), p2.y - k * (p3.y - p1.y)),
+                p2,
+            );
+        }
+        path.close();
+        path
+    } else {
+        spline_segment(points, tension, 0, points.len())
+    }
+}
+
+/// Open cardinal spline through `points[start..end]`, using the neighbours
+/// outside the range for the end tangents
+fn spline_segment(points: &[Point], tension: f64, start: usize, end: usize) -> Path {
+    let mut path = Path::new();
+    if end <= start + 1 || end > points.len() {
+        return path;
+    }
+    let k = tension * 0.3;
+    let at = |i: isize| points[i.clamp(0, points.len() as isize - 1) as usize];
+    path.move_to(points[start]);
+    for i in start..end - 1 {
+        let i = i as isize;
+        let (p0, p1, p2, p3) = (at(i - 1), at(i), at(i + 1), at(i + 2));
+        path.cubic_to(
+            Point::new(p1.x + k * (p2.x - p0.x), p1.y + k * (p2.y - p0.y)),
+            Point::new(p2.x - k * (p3.x - p1.x), p2.y - k * (p3.y - p1.y)),
+            p2,
+        );
+    }
+    path
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Records every call for inspection
+    #[derive(Default)]
+    struct Recorder {
+        fills: Vec<(Path, Brush, Transform)>,
+        strokes: Vec<f64>,
+        texts: Vec<String>,
+        clips: usize,
+    }
+
+    impl EmfPlusDevice for Recorder {
+        fn fill_path(&mut self, path: &Path, _winding: bool, brush: &Brush, to_device: &Transform) {
+            self.fills.push((path.clone(), brush.clone(), *to_device));
+        }
+
+        fn stroke_path(&mut self, _path: &Path, _pen: &Pen, width: f64, _to_device: &Transform) {
+            self.strokes.push(width);
+        }
+
+        fn draw_text(&mut self, text: &Text, _to_device: &Transform) {
+            if let TextLayout::Rect { text, .. } = text.layout {
+                self.texts.push(text.to_string());
+            }
+        }
+
+        fn draw_image(&mut self, _image: &Image, _src: RectF, _to_device: &Transform) {}
+
+        fn set_clip(&mut self, _clip: Option<&Region>) {
+            self.clips += 1;
+        }
+
+        fn clear(&mut self, _color: Color) {}
+    }
+
+    fn record(record_type: EmfPlusRecordType, flags: u16, data: &[u8]) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        bytes.extend_from_slice(&(record_type as u16).to_le_bytes());
+        bytes.extend_from_slice(&flags.to_le_bytes());
+        bytes.extend_from_slice(&(12 + data.len() as u32).to_le_bytes());
+        bytes.extend_from_slice(&(data.len() as u32).to_le_bytes());
+        bytes.extend_from_slice(data);
+        bytes
+    }
+
+    fn comment(records: &[Vec<u8>]) -> Vec<u8> {
+        let body: Vec<u8> = records.concat();
+        let mut data = Vec::new();
+        data.extend_from_slice(&(4 + body.len() as u32).to_le_bytes());
+        data.extend_from_slice(&EMF_PLUS_SIGNATURE.to_le_bytes());
+        data.extend_from_slice(&body);
+        data
+    }
+
+    fn floats(values: &[f32]) -> Vec<u8> {
+        values.iter().flat_map(|v| v.to_le_bytes()).collect()
+    }
+
+    fn words(values: &[u32]) -> Vec<u8> {
+        values.iter().flat_map(|v| v.to_le_bytes()).collect()
+    }
+
+    #[test]
+    fn test_header_switches_off_gdi() {
+        let mut player = EmfPlusPlayer::new(RectF::default());
+        let mut device = Recorder::default();
+        assert!(player.gdi_enabled());
+
+        let header = record(
+            EmfPlusRecordType::Header,
+            1,
+            &words(&[0xDBC0_1002, 1, 96, 96]),
+        );
+        assert!(player.play_comment(&comment(&[header]), &mut device));
+        assert!(!player.gdi_enabled());
+
+        let get_dc = record(EmfPlusRecordType::GetDc, 0, &[]);
+        player.play_comment(&comment(&[get_dc]), &mut device);
+        assert!(player.gdi_enabled());
+    }
+
+    #[test]
+    fn test_fill_rects_with_transform() {
+        let mut player = EmfPlusPlayer::new(RectF::default());
+        let mut device = Recorder::default();
+
+        let mut rects = words(&[0xFF00_FF00, 1]);
+        rects.extend(floats(&[1.0, 2.0, 3.0, 4.0]));
+        let records = [
+            record(
+                EmfPlusRecordType::Header,
+                1,
+                &words(&[0xDBC0_1002, 1, 96, 96]),
+            ),
+            record(EmfPlusRecordType::SetPageTransform, 2, &floats(&[1.0])),
+            record(
+                EmfPlusRecordType::ScaleWorldTransform,
+                0,
+                &floats(&[2.0, 3.0]),
+            ),
+            record(EmfPlusRecordType::FillRects, 0x8000, &rects),
+        ];
+        player.play_comment(&comment(&records), &mut device);
+
+        assert_eq!(device.fills.len(), 1);
+        let (_, brush, to_device) = &device.fills[0];
+        assert!(matches!(brush, Brush::Solid(Color { g: 255, a: 255, .. })));
+        assert_eq!(to_device.apply(Point::new(1.0, 1.0)), Point::new(2.0, 3.0));
+    }
+
+    #[test]
+    fn test_objects_and_save_restore() {
+        let mut player = EmfPlusPlayer::new(RectF::default());
+        let mut device = Recorder::default();
+
+        // Font object 1: 12pt "Arial"
+        let mut font = words(&[0xDBC0_1002]);
+        font.extend(floats(&[12.0]));
+        font.extend(words(&[3, 0, 0, 5]));
+        font.extend("Arial".encode_utf16().flat_map(|c| c.to_le_bytes()));
+
+        let mut string = words(&[0xFF00_0000, 0xFFFF_FFFF, 2]);
+        string.extend(floats(&[0.0, 0.0, 100.0, 20.0]));
+        string.extend("Hi".encode_utf16().flat_map(|c| c.to_le_bytes()));
+
+        // Pen object 2: width 2 pixels, solid black brush
+        let mut pen = words(&[0xDBC0_1002, 0, 0, 2]);
+        pen.extend(floats(&[2.0]));
+        pen.extend(words(&[0xDBC0_1002, 0, 0xFF00_0000]));
+
+        let mut ellipse = Vec::new();
+        ellipse.extend(floats(&[0.0, 0.0, 10.0, 10.0]));
+
+        let records = [
+            record(EmfPlusRecordType::Object, 0x0601, &font),
+            record(EmfPlusRecordType::DrawString, 0x8001, &string),
+            record(EmfPlusRecordType::Object, 0x0202, &pen),
+            record(EmfPlusRecordType::Save, 0, &words(&[7])),
+            record(
+                EmfPlusRecordType::SetClipRect,
+                0,
+                &floats(&[0.0, 0.0, 5.0, 5.0]),
+            ),
+            record(EmfPlusRecordType::SetPageTransform, 2, &floats(&[2.0])),
+            record(EmfPlusRecordType::DrawEllipse, 0x0002, &ellipse),
+            record(EmfPlusRecordType::Restore, 0, &words(&[7])),
+            record(EmfPlusRecordType::DrawEllipse, 0x0002, &ellipse),
+        ];
+        player.play_comment(&comment(&records), &mut device);
+
+        assert_eq!(device.texts, vec!["Hi".to_string()]);
+        // Pixel-unit pen widths are converted to world units
+        assert_eq!(device.strokes, vec![1.0, 2.0]);
+        // SetClipRect and Restore both update the clip
+        assert_eq!(device.clips, 2);
+    }
+
+    #[test]
+    fn test_continued_object() {
+        let mut player = EmfPlusPlayer::new(RectF::default());
+        let mut device = Recorder::default();
+
+        let brush = words(&[0xDBC0_1002, 0, 0xFF12_3456]);
+        let mut first = words(&[brush.len() as u32]);
+        first.extend_from_slice(&brush[..6]);
+        let mut second = words(&[brush.len() as u32]);
+        second.extend_from_slice(&brush[6..]);
+
+        let mut fill = words(&[3, 1]);
+        fill.extend(floats(&[0.0, 0.0, 1.0, 1.0]));
+        let records = [
+            record(EmfPlusRecordType::Object, 0x8103, &first),
+            record(EmfPlusRecordType::Object, 0x8103, &second),
+            record(EmfPlusRecordType::FillRects, 0, &fill),
+        ];
+        player.play_comment(&comment(&records), &mut device);
+
+        assert_eq!(device.fills.len(), 1);
+        assert!(matches!(
+            device.fills[0].1,
+            Brush::Solid(Color {
+                r: 0x12,
+                g: 0x34,
+                b: 0x56,
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn test_arc_angles() {
+        let rect = RectF {
+            x: 0.0,
+            y: 0.0,
+            width: 20.0,
+            height: 10.0,
+        };
+        // A quarter from 0° clockwise ends at the bottom of the ellipse
+        let path = arc_path(&rect, 0.0, 90.0, ArcKind::Open);
+        let polyline = &path.flatten(0.01)[0];
+        let first = polyline.points[0];
+        let last = *polyline.points.last().unwrap();
+        assert!(first.distance(Point::new(20.0, 5.0)) < 1e-6);
+        assert!(last.distance(Point::new(10.0, 10.0)) < 1e-6);
+    }
+}
@@ -0,0 +1,490 @@
+/// EMF+ Record Types and Stream Parsing
+///
+/// EMF+ records are stored inside `EMR_COMMENT` records tagged with the
+/// `EMF+` identifier. Each comment carries one or more EMF+ records with a
+/// 12-byte header (type, flags, size, data size).
+///
+/// References:
+/// - [MS-EMFPLUS]: Enhanced Metafile Format Plus Extensions
+use super::super::raster::{Point, Transform};
+
+/// Identifier at the start of an `EMR_COMMENT` payload holding EMF+ records
+pub const EMF_PLUS_SIGNATURE: u32 = 0x2B46_4D45;
+
+/// EMF+ record types
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[repr(u16)]
+pub enum EmfPlusRecordType {
+    // Control records
+    Header = 0x4001,
+    EndOfFile = 0x4002,
+    Comment = 0x4003,
+    GetDc = 0x4004,
+    MultiFormatStart = 0x4005,
+    MultiFormatSection = 0x4006,
+    MultiFormatEnd = 0x4007,
+
+    // Object record
+    Object = 0x4008,
+
+    // Drawing records
+    Clear = 0x4009,
+    FillRects = 0x400A,
+    DrawRects = 0x400B,
+    FillPolygon = 0x400C,
+    DrawLines = 0x400D,
+    FillEllipse = 0x400E,
+    DrawEllipse = 0x400F,
+    FillPie = 0x4010,
+    DrawPie = 0x4011,
+    DrawArc = 0x4012,
+    FillRegion = 0x4013,
+    FillPath = 0x4014,
+    DrawPath = 0x4015,
+    FillClosedCurve = 0x4016,
+    DrawClosedCurve = 0x4017,
+    DrawCurve = 0x4018,
+    DrawBeziers = 0x4019,
+    DrawImage = 0x401A,
+    DrawImagePoints = 0x401B,
+    DrawString = 0x401C,
+
+    // Property records
+    SetRenderingOrigin = 0x401D,
+    SetAntiAliasMode = 0x401E,
+    SetTextRenderingHint = 0x401F,
+    SetTextContrast = 0x4020,
+    SetInterpolationMode = 0x4021,
+    SetPixelOffsetMode = 0x4022,
+    SetCompositingMode = 0x4023,
+    SetCompositingQuality = 0x4024,
+
+    // State records
+    Save = 0x4025,
+    Restore = 0x4026,
+    BeginContainer = 0x4027,
+    BeginContainerNoParams = 0x4028,
+    EndContainer = 0x4029,
+
+    // Transform records
+    SetWorldTransform = 0x402A,
+    ResetWorldTransform = 0x402B,
+    MultiplyWorldTransform = 0x402C,
+    TranslateWorldTransform = 0x402D,
+    ScaleWorldTransform = 0x402E,
+    RotateWorldTransform = 0x402F,
+    SetPageTransform = 0x4030,
+
+    // Clipping records
+    ResetClip = 0x4031,
+    SetClipRect = 0x4032,
+    SetClipPath = 0x4033,
+    SetClipRegion = 0x4034,
+    OffsetClip = 0x4035,
+
+    // More drawing records
+    DrawDriverString = 0x4036,
+    StrokeFillPath = 0x4037,
+
+    // Terminal server records
+    SerializableObject = 0x4038,
+    SetTsGraphics = 0x4039,
+    SetTsClip = 0x403A,
+}
+
+impl EmfPlusRecordType {
+    /// Convert from the u16 record type
+    pub fn from_u16(value: u16) -> Option<Self> {
+        use EmfPlusRecordType::*;
+        let record_type = match value {
+            0x4001 => Header,
+            0x4002 => EndOfFile,
+            0x4003 => Comment,
+            0x4004 => GetDc,
+            0x4005 => MultiFormatStart,
+            0x4006 => MultiFormatSection,
+            0x4007 => MultiFormatEnd,
+            0x4008 => Object,
+            0x4009 => Clear,
+            0x400A => FillRects,
+            0x400B => DrawRects,
+            0x400C => FillPolygon,
+            0x400D => DrawLines,
+            0x400E => FillEllipse,
+            0x400F => DrawEllipse,
+            0x4010 => FillPie,
+            0x4011 => DrawPie,
+            0x4012 => DrawArc,
+            0x4013 => FillRegion,
+            0x4014 => FillPath,
+            0x4015 => DrawPath,
+            0x4016 => FillClosedCurve,
+            0x4017 => DrawClosedCurve,
+            0x4018 => DrawCurve,
+            0x4019 => DrawBeziers,
+            0x401A => DrawImage,
+            0x401B => DrawImagePoints,
+            0x401C => DrawString,
+            0x401D => SetRenderingOrigin,
+            0x401E => SetAntiAliasMode,
+            0x401F => SetTextRenderingHint,
+            0x4020 => SetTextContrast,
+            0x4021 => SetInterpolationMode,
+            0x4022 => SetPixelOffsetMode,
+            0x4023 => SetCompositingMode,
+            0x4024 => SetCompositingQuality,
+            0x4025 => Save,
+            0x4026 => Restore,
+            0x4027 => BeginContainer,
+            0x4028 => BeginContainerNoParams,
+            0x4029 => EndContainer,
+            0x402A => SetWorldTransform,
+            0x402B => ResetWorldTransform,
+            0x402C => MultiplyWorldTransform,
+            0x402D => TranslateWorldTransform,
+            0x402E => ScaleWorldTransform,
+            0x402F => RotateWorldTransform,
+            0x4030 => SetPageTransform,
+            0x4031 => ResetClip,
+            0x4032 => SetClipRect,
+            0x4033 => SetClipPath,
+            0x4034 => SetClipRegion,
+            0x4035 => OffsetClip,
+            0x4036 => DrawDriverString,
+            0x4037 => StrokeFillPath,
+            0x4038 => SerializableObject,
+            0x4039 => SetTsGraphics,
+            0x403A => SetTsClip,
+            _ => return None,
+        };
+        Some(record_type)
+    }
+}
+
+/// Record flag bits shared by several drawing records
+pub mod record_flags {
+    /// Brush is an inline ARGB color instead of an object ID (fill records)
+    pub const SOLID_COLOR: u16 = 0x8000;
+    /// Coordinates are 16-bit integers instead of floats
+    pub const COMPRESSED: u16 = 0x4000;
+    /// Fill mode is winding instead of alternate
+    pub const WINDING: u16 = 0x2000;
+    /// DrawLines: the line is closed
+    pub const CLOSED: u16 = 0x2000;
+    /// Transform records: append instead of prepend
+    pub const APPEND: u16 = 0x2000;
+    /// Coordinates are relative (EmfPlusPointR)
+    pub const RELATIVE: u16 = 0x0800;
+    /// Object record: the object continues in the next record
+    pub const CONTINUED: u16 = 0x8000;
+}
+
+/// A single EMF+ record borrowed from a comment payload
+#[derive(Debug, Clone, Copy)]
+pub struct EmfPlusRecord<'a> {
+    pub record_type: u16,
+    pub flags: u16,
+    pub data: &'a [u8],
+}
+
+/// Extract the EMF+ record stream from `EMR_COMMENT` data
+///
+/// `data` is the comment record without its type and size header. Returns
+/// `None` for comments that do not carry EMF+ records.
+pub fn emf_plus_payload(data: &[u8]) -> Option<&[u8]> {
+    let size = read_u32(data, 0)? as usize;
+    if read_u32(data, 4)? != EMF_PLUS_SIGNATURE {
+        return None;
+    }
+    data.get(8..(4 + size).min(data.len()))
+}
+
+/// Iterate over the EMF+ records in a comment payload
+pub fn parse_records(payload: &[u8]) -> impl Iterator<Item = EmfPlusRecord<'_>> {
+    let mut offset = 0;
+    std::iter::from_fn(move || {
+        let record_type = read_u16(payload, offset)?;
+        let flags = read_u16(payload, offset + 2)?;
+        let size = read_u32(payload, offset + 4)? as usize;
+        let data_size = read_u32(payload, offset + 8)? as usize;
+        if size < 12 {
+            return None;
+        }
+        let start = offset + 12;
+        let data = payload.get(start..start + data_size.min(size - 12))?;
+        offset += size;
+        Some(EmfPlusRecord {
+            record_type,
+            flags,
+            data,
+        })
+    })
+}
+
+/// A rectangle in floating point coordinates
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct RectF {
+    pub x: f64,
+    pub y: f64,
+    pub width: f64,
+    pub height: f64,
+}
+
+impl RectF {
+    pub fn right(&self) -> f64 {
+        self.x + self.width
+    }
+
+    pub fn bottom(&self) -> f64 {
+        self.y + self.height
+    }
+}
+
+/// An ARGB color
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Color {
+    pub r: u8,
+    pub g: u8,
+    pub b: u8,
+    pub a: u8,
+}
+
+impl Color {
+    /// Decode an EmfPlusARGB value (stored as blue, green, red, alpha)
+    pub fn from_argb(value: u32) -> Self {
+        Self {
+            a: (value >> 24) as u8,
+            r: (value >> 16) as u8,
+            g: (value >> 8) as u8,
+            b: value as u8,
+        }
+    }
+
+    /// Linear interpolation between two colors
+    pub fn lerp(self, other: Color, t: f64) -> Color {
+        let mix = |a: u8, b: u8| {
+            (a as f64 + (b as f64 - a as f64) * t)
+                .round()
+                .clamp(0.0, 255.0) as u8
+        };
+        Color {
+            r: mix(self.r, other.r),
+            g: mix(self.g, other.g),
+            b: mix(self.b, other.b),
+            a: mix(self.a, other.a),
+        }
+    }
+}
+
+/// Little-endian reader over record data
+#[derive(Debug, Clone)]
+pub struct Reader<'a> {
+    data: &'a [u8],
+    offset: usize,
+}
+
+impl<'a> Reader<'a> {
+    pub fn new(data: &'a [u8]) -> Self {
+        Self { data, offset: 0 }
+    }
+
+    /// Bytes not yet consumed
+    pub fn remaining(&self) -> &'a [u8] {
+        self.data.get(self.offset..).unwrap_or_default()
+    }
+
+    pub fn skip(&mut self, count: usize) -> Option<()> {
+        if self.offset + count > self.data.len() {
+            return None;
+        }
+        self.offset += count;
+        Some(())
+    }
+
+    pub fn bytes(&mut self, count: usize) -> Option<&'a [u8]> {
+        let bytes = self
+            .data
+            .get(self.offset..self.offset.checked_add(count)?)?;
+        self.offset += count;
+        Some(bytes)
+    }
+
+    pub fn u8(&mut self) -> Option<u8> {
+        self.bytes(1).map(|b| b[0])
+    }
+
+    pub fn u16(&mut self) -> Option<u16> {
+        self.bytes(2).map(|b| u16::from_le_bytes([b[0], b[1]]))
+    }
+
+    pub fn i16(&mut self) -> Option<i16> {
+        self.u16().map(|v| v as i16)
+    }
+
+    pub fn u32(&mut self) -> Option<u32> {
+        self.bytes(4)
+            .map(|b| u32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+    }
+
+    pub fn i32(&mut self) -> Option<i32> {
+        self.u32().map(|v| v as i32)
+    }
+
+    pub fn f32(&mut self) -> Option<f64> {
+        self.u32().map(|v| f32::from_bits(v) as f64)
+    }
+
+    pub fn color(&mut self) -> Option<Color> {
+        self.u32().map(Color::from_argb)
+    }
+
+    pub fn point_f(&mut self) -> Option<Point> {
+        Some(Point::new(self.f32()?, self.f32()?))
+    }
+
+    pub fn rect_f(&mut self) -> Option<RectF> {
+        Some(RectF {
+            x: self.f32()?,
+            y: self.f32()?,
+            width: self.f32()?,
+            height: self.f32()?,
+        })
+    }
+
+    /// EmfPlusRect (16-bit) or EmfPlusRectF depending on the compressed flag
+    pub fn rect(&mut self, compressed: bool) -> Option<RectF> {
+        if compressed {
+            Some(RectF {
+                x: self.i16()? as f64,
+                y: self.i16()? as f64,
+                width: self.i16()? as f64,
+                height: self.i16()? as f64,
+            })
+        } else {
+            self.rect_f()
+        }
+    }
+
+    /// EmfPlusTransformMatrix
+    pub fn transform(&mut self) -> Option<Transform> {
+        Some(Transform {
+            a: self.f32()?,
+            b: self.f32()?,
+            c: self.f32()?,
+            d: self.f32()?,
+            e: self.f32()?,
+            f: self.f32()?,
+        })
+    }
+
+    /// A UTF-16LE string of `count` code units
+    pub fn utf16(&mut self, count: usize) -> Option<String> {
+        let bytes = self.bytes(count.checked_mul(2)?)?;
+        let units: Vec<u16> = bytes
+            .chunks_exact(2)
+            .map(|c| u16::from_le_bytes([c[0], c[1]]))
+            .collect();
+        Some(String::from_utf16_lossy(&units))
+    }
+
+    /// Points in the encoding selected by the record or path flags
+    ///
+    /// Relative points (EmfPlusPointR) are offsets from the previous point
+    /// stored as 7-bit or 15-bit integers.
+    pub fn points(&mut self, count: usize, compressed: bool, relative: bool) -> Option<Vec<Point>> {
+        if count > self.remaining().len() {
+            return None;
+        }
+        let mut points = Vec::with_capacity(count);
+        let mut last = Point::default();
+        for _ in 0..count {
+            let point = if relative {
+                let dx = self.integer_7_15()?;
+                let dy = self.integer_7_15()?;
+                Point::new(last.x + dx, last.y + dy)
+            } else if compressed {
+                Point::new(self.i16()? as f64, self.i16()? as f64)
+            } else {
+                self.point_f()?
+            };
+            last = point;
+            points.push(point);
+        }
+        Some(points)
+    }
+
+    /// EmfPlusInteger7 or EmfPlusInteger15
+    fn integer_7_15(&mut self) -> Option<f64> {
+        let first = self.u8()?;
+        if first & 0x80 == 0 {
+            // 7-bit signed value
+            Some(((first << 1) as i8 >> 1) as f64)
+        } else {
+            let second = self.u8()?;
+            let value = (((first & 0x7F) as u16) << 8) | second as u16;
+            // 15-bit signed value
+            Some((((value << 1) as i16) >> 1) as f64)
+        }
+    }
+}
+
+fn read_u16(data: &[u8], offset: usize) -> Option<u16> {
+    data.get(offset..offset + 2)
+        .map(|b| u16::from_le_bytes([b[0], b[1]]))
+}
+
+fn read_u32(data: &[u8], offset: usize) -> Option<u32> {
+    data.get(offset..offset + 4)
+        .map(|b| u32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_records() {
+        let mut comment = Vec::new();
+        comment.extend_from_slice(&32u32.to_le_bytes());
+        comment.extend_from_slice(&EMF_PLUS_SIGNATURE.to_le_bytes());
+        // EmfPlusClear with one ARGB value
+        comment.extend_from_slice(&0x4009u16.to_le_bytes());
+        comment.extend_from_slice(&0u16.to_le_bytes());
+        comment.extend_from_slice(&16u32.to_le_bytes());
+        comment.extend_from_slice(&4u32.to_le_bytes());
+        comment.extend_from_slice(&0xFF11_2233u32.to_le_bytes());
+        // EmfPlusGetDC
+        comment.extend_from_slice(&0x4004u16.to_le_bytes());
+        comment.extend_from_slice(&0u16.to_le_bytes());
+        comment.extend_from_slice(&12u32.to_le_bytes());
+        comment.extend_from_slice(&0u32.to_le_bytes());
+
+        let payload = emf_plus_payload(&comment).unwrap();
+        let records: Vec<_> = parse_records(payload).collect();
+        assert_eq!(records.len(), 2);
+        assert_eq!(
+            EmfPlusRecordType::from_u16(records[0].record_type),
+            Some(EmfPlusRecordType::Clear)
+        );
+        let color = Reader::new(records[0].data).color().unwrap();
+        assert_eq!(
+            (color.r, color.g, color.b, color.a),
+            (0x11, 0x22, 0x33, 0xFF)
+        );
+        assert_eq!(
+            EmfPlusRecordType::from_u16(records[1].record_type),
+            Some(EmfPlusRecordType::GetDc)
+        );
+
+        assert!(emf_plus_payload(&[4, 0, 0, 0, b'G', b'D', b'I', b'C']).is_none());
+    }
+
+    #[test]
+    fn test_relative_points() {
+        // (5, -3), then (+200, +1) using a 15-bit x offset
+        let data = [0x05, 0x7D, 0x80, 0xC8, 0x01];
+        let points = Reader::new(&data).points(2, false, true).unwrap();
+        assert_eq!(points[0], Point::new(5.0, -3.0));
+        assert_eq!(points[1], Point::new(205.0, -2.0));
+    }
+}
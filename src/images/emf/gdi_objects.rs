@@ -3,8 +3,11 @@
 // This module manages GDI objects (pens, brushes, fonts) that are created,
 // selected, and deleted during EMF playback.
 
+use super::records::LogFontW;
 use crate::images::svg::color::colorref_to_hex;
+use image::RgbaImage;
 use std::collections::HashMap;
+use std::sync::Arc;
 
 /// Pen styles from GDI
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -53,6 +56,42 @@ impl PenStyle {
     }
 }
 
+/// Line end cap from the `PS_ENDCAP_*` bits of a pen style
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LineCap {
+    Round,
+    Square,
+    Flat,
+}
+
+impl LineCap {
+    pub fn from_pen_style(style: u32) -> Self {
+        match style & 0x0F00 {
+            0x0100 => Self::Square,
+            0x0200 => Self::Flat,
+            _ => Self::Round,
+        }
+    }
+}
+
+/// Line join from the `PS_JOIN_*` bits of a pen style
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LineJoin {
+    Round,
+    Bevel,
+    Miter,
+}
+
+impl LineJoin {
+    pub fn from_pen_style(style: u32) -> Self {
+        match style & 0xF000 {
+            0x1000 => Self::Bevel,
+            0x2000 => Self::Miter,
+            _ => Self::Round,
+        }
+    }
+}
+
 /// Brush styles from GDI
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 #[repr(u32)]
@@ -93,6 +132,12 @@ pub struct Pen {
     pub style: PenStyle,
     pub width: f64,
     pub color: String, // RGB hex color
+    pub end_cap: LineCap,
+    pub line_join: LineJoin,
+    /// Geometric pen (`PS_GEOMETRIC`); cosmetic pens are always one device pixel wide
+    pub geometric: bool,
+    /// Dash and gap lengths for `PS_USERSTYLE` pens
+    pub user_style: Vec<f64>,
 }
 
 impl Default for Pen {
@@ -101,6 +146,10 @@ impl Default for Pen {
             style: PenStyle::Solid,
             width: 1.0,
             color: "#000000".to_string(),
+            end_cap: LineCap::Round,
+            line_join: LineJoin::Round,
+            geometric: false,
+            user_style: Vec::new(),
         }
     }
 }
@@ -112,6 +161,29 @@ impl Pen {
             style: PenStyle::from_u32(style).unwrap_or(PenStyle::Solid),
             width: width.max(1) as f64,
             color: colorref_to_hex(colorref),
+            end_cap: LineCap::from_pen_style(style),
+            line_join: LineJoin::from_pen_style(style),
+            // Old-style pens wider than one unit behave like geometric pens
+            geometric: width > 1,
+            user_style: Vec::new(),
+        }
+    }
+
+    /// Create pen from EMR_EXTCREATEPEN data
+    pub fn from_ext_emr_data(style: u32, width: u32, colorref: u32, user_style: &[u32]) -> Self {
+        let geometric = style & 0x0001_0000 != 0;
+        Self {
+            style: PenStyle::from_u32(style).unwrap_or(PenStyle::Solid),
+            width: if geometric { width as f64 } else { 1.0 },
+            color: colorref_to_hex(colorref),
+            end_cap: LineCap::from_pen_style(style),
+            line_join: LineJoin::from_pen_style(style),
+            geometric,
+            user_style: if style & 0xFF == 7 {
+                user_style.iter().map(|&v| v as f64).collect()
+            } else {
+                Vec::new()
+            },
         }
     }
 
@@ -136,6 +208,8 @@ pub struct Brush {
     pub style: BrushStyle,
     pub color: String, // RGB hex color
     pub hatch: u32,    // Hatch pattern if hatched
+    /// Pattern bitmap for DIB and mono pattern brushes
+    pub pattern: Option<Arc<RgbaImage>>,
 }
 
 impl Default for Brush {
@@ -144,6 +218,7 @@ impl Default for Brush {
             style: BrushStyle::Solid,
             color: "#FFFFFF".to_string(),
             hatch: 0,
+            pattern: None,
         }
     }
 }
@@ -155,6 +230,16 @@ impl Brush {
             style: BrushStyle::from_u32(style).unwrap_or(BrushStyle::Solid),
             color: colorref_to_hex(colorref),
             hatch,
+            pattern: None,
+        }
+    }
+
+    /// Create a pattern brush from a decoded bitmap
+    pub fn from_pattern(style: BrushStyle, pattern: RgbaImage) -> Self {
+        Self {
+            style,
+            pattern: Some(Arc::new(pattern)),
+            ..Default::default()
         }
     }
 
@@ -201,6 +286,27 @@ impl Default for Font {
 }
 
 impl Font {
+    /// Create font from a LOGFONTW and the UTF-16LE face name that follows it
+    pub fn from_logfont(logfont: &LogFontW, face_name: &[u8]) -> Self {
+        let units: Vec<u16> = face_name
+            .chunks_exact(2)
+            .map(|c| u16::from_le_bytes([c[0], c[1]]))
+            .take_while(|&c| c != 0)
+            .collect();
+        Self {
+            height: logfont.height,
+            width: logfont.width,
+            escapement: logfont.escapement,
+            orientation: logfont.orientation,
+            weight: logfont.weight,
+            italic: logfont.italic != 0,
+            underline: logfont.underline != 0,
+            strike_out: logfont.strike_out != 0,
+            charset: logfont.char_set,
+            face_name: String::from_utf16_lossy(&units),
+        }
+    }
+
     /// Calculate font size for SVG
     pub fn svg_font_size(&self) -> f64 {
         self.height.abs() as f64 * 0.75 // Convert from logical units
@@ -279,6 +385,12 @@ impl ObjectTable {
         }
     }
 
+    /// Store object at a handle chosen by the metafile (EMF object index)
+    pub fn insert(&mut self, handle: u32, object: GdiObject) {
+        self.objects.insert(handle, object);
+        self.next_free = self.next_free.max(handle.saturating_add(1));
+    }
+
     /// Create object and return its handle
     pub fn create_object(&mut self, object: GdiObject) -> u32 {
         let handle = self.next_free;
@@ -348,6 +460,7 @@ mod tests {
             style: PenStyle::Dash,
             width: 2.0,
             color: "#000000".to_string(),
+            ..Default::default()
         };
         assert_eq!(dash_pen.style.to_dasharray(2.0), Some("6,2".to_string()));
 
@@ -355,6 +468,7 @@ mod tests {
             style: PenStyle::Dot,
             width: 1.0,
             color: "#000000".to_string(),
+            ..Default::default()
         };
         assert_eq!(dot_pen.style.to_dasharray(1.0), Some("1,1".to_string()));
     }
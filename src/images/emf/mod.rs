@@ -12,8 +12,10 @@
 
 pub mod converter;
 pub mod device_context;
+pub mod emfplus;
 pub mod gdi_objects;
 pub mod parser;
+pub mod raster;
 pub mod record_parser;
 pub mod records;
 pub mod simd_ops;
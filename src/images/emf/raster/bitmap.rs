@@ -0,0 +1,262 @@
+// Device-independent bitmap (DIB) decoding
+//
+// EMF bitmap records carry a BITMAPINFO header and the pixel bits at
+// separate offsets. Uncompressed formats are decoded here directly so the
+// alpha channel of 32-bit bitmaps is available for AlphaBlend; RLE, JPEG and
+// PNG payloads are handed to the `image` crate.
+
+use image::{Rgba, RgbaImage};
+
+const BI_RGB: u32 = 0;
+const BI_RLE8: u32 = 1;
+const BI_RLE4: u32 = 2;
+const BI_BITFIELDS: u32 = 3;
+const BI_JPEG: u32 = 4;
+const BI_PNG: u32 = 5;
+const BI_ALPHABITFIELDS: u32 = 6;
+
+/// Largest bitmap dimension accepted, to guard against corrupt headers
+const MAX_DIMENSION: u32 = 16384;
+
+/// Decode a DIB from its BITMAPINFO and pixel bits
+///
+/// With `keep_alpha`, the fourth byte of 32-bit `BI_RGB` pixels is used as
+/// alpha; otherwise such pixels are opaque, matching GDI.
+pub fn decode_dib(bmi: &[u8], bits: &[u8], keep_alpha: bool) -> Option<RgbaImage> {
+    let header_size = read_u32(bmi, 0)? as usize;
+
+    let (width, height, bit_count, compression, colors_used) = if header_size == 12 {
+        // BITMAPCOREHEADER
+        (
+            read_u16(bmi, 4)? as i32,
+            read_u16(bmi, 6)? as i16 as i32,
+            read_u16(bmi, 10)?,
+            BI_RGB,
+            0,
+        )
+    } else if header_size >= 40 {
+        (
+            read_u32(bmi, 4)? as i32,
+            read_u32(bmi, 8)? as i32,
+            read_u16(bmi, 14)?,
+            read_u32(bmi, 16)?,
+            read_u32(bmi, 32)?,
+        )
+    } else {
+        return None;
+    };
+
+    if compression == BI_JPEG || compression == BI_PNG {
+        return image::load_from_memory(bits).ok().map(|img| img.to_rgba8());
+    }
+    if compression == BI_RLE8 || compression == BI_RLE4 {
+        return decode_with_bmp_wrapper(bmi, bits);
+    }
+
+    let top_down = height < 0;
+    let width = width.unsigned_abs();
+    let height = height.unsigned_abs();
+    if width == 0 || height == 0 || width > MAX_DIMENSION || height > MAX_DIMENSION {
+        return None;
+    }
+
+    // Color masks for bitfield formats
+    let masks = if compression == BI_BITFIELDS || compression == BI_ALPHABITFIELDS {
+        let base = if header_size >= 52 { 40 } else { header_size };
+        let alpha = if compression == BI_ALPHABITFIELDS || header_size >= 56 {
+            read_u32(bmi, base + 12).unwrap_or(0)
+        } else {
+            0
+        };
+        Some([
+            read_u32(bmi, base)?,
+            read_u32(bmi, base + 4)?,
+            read_u32(bmi, base + 8)?,
+            alpha,
+        ])
+    } else {
+        None
+    };
+
+    // Color table follows the header (and the masks of a plain BITMAPINFOHEADER)
+    let mut table_offset = header_size;
+    if header_size == 40 {
+        match compression {
+            BI_BITFIELDS => table_offset += 12,
+            BI_ALPHABITFIELDS => table_offset += 16,
+            _ => {},
+        }
+    }
+    let entry_size = if header_size == 12 { 3 } else { 4 };
+    let palette: Vec<Rgba<u8>> = if bit_count <= 8 {
+        let count = if colors_used > 0 {
+            colors_used as usize
+        } else {
+            1usize << bit_count
+        };
+        (0..count.min(256))
+            .filter_map(|i| {
+                let offset = table_offset + i * entry_size;
+                let entry = bmi.get(offset..offset + 3)?;
+                Some(Rgba([entry[2], entry[1], entry[0], 255]))
+            })
+            .collect()
+    } else {
+        Vec::new()
+    };
+
+    let stride = (width as usize * bit_count as usize).div_ceil(32) * 4;
+    if bits.len()
+        < stride * (height as usize - 1) + (width as usize * bit_count as usize).div_ceil(8)
+    {
+        return None;
+    }
+
+    let mut image = RgbaImage::new(width, height);
+    for row in 0..height {
+        let src_row = if top_down { row } else { height - 1 - row };
+        let line = &bits[src_row as usize * stride..];
+        for x in 0..width {
+            let pixel = match bit_count {
+                1 | 2 | 4 | 8 => {
+                    let bpp = bit_count as usize;
+                    let bit_offset = x as usize * bpp;
+                    let byte = line[bit_offset / 8];
+                    let shift = 8 - bpp - (bit_offset % 8);
+                    let index = (byte >> shift) as usize & ((1 << bpp) - 1);
+                    palette.get(index).copied().unwrap_or(Rgba([0, 0, 0, 255]))
+                },
+                16 => {
+                    let v =
+                        u16::from_le_bytes([line[x as usize * 2], line[x as usize * 2 + 1]]) as u32;
+                    match masks {
+                        Some(m) => from_masks(v, m),
+                        None => from_masks(v, [0x7C00, 0x03E0, 0x001F, 0]),
+                    }
+                },
+                24 => {
+                    let i = x as usize * 3;
+                    Rgba([line[i + 2], line[i + 1], line[i], 255])
+                },
+                32 => {
+                    let i = x as usize * 4;
+                    let v = u32::from_le_bytes([line[i], line[i + 1], line[i + 2], line[i + 3]]);
+                    match masks {
+                        Some(m) => from_masks(v, m),
+                        None => Rgba([
+                            line[i + 2],
+                            line[i + 1],
+                            line[i],
+                            if keep_alpha { line[i + 3] } else { 255 },
+                        ]),
+                    }
+                },
+                _ => return None,
+            };
+            image.put_pixel(x, row, pixel);
+        }
+    }
+    Some(image)
+}
+
+/// Extract a channel with a bit mask, scaled to 8 bits
+fn channel(value: u32, mask: u32) -> Option<u8> {
+    if mask == 0 {
+        return None;
+    }
+    let shift = mask.trailing_zeros();
+    let max = mask >> shift;
+    Some((((value & mask) >> shift) * 255 / max) as u8)
+}
+
+fn from_masks(value: u32, masks: [u32; 4]) -> Rgba<u8> {
+    Rgba([
+        channel(value, masks[0]).unwrap_or(0),
+        channel(value, masks[1]).unwrap_or(0),
+        channel(value, masks[2]).unwrap_or(0),
+        channel(value, masks[3]).unwrap_or(255),
+    ])
+}
+
+/// Decode formats we don't handle natively by wrapping them as a BMP file
+fn decode_with_bmp_wrapper(bmi: &[u8], bits: &[u8]) -> Option<RgbaImage> {
+    let pixel_offset = 14 + bmi.len();
+    let file_size = pixel_offset + bits.len();
+    let mut bmp = Vec::with_capacity(file_size);
+    bmp.extend_from_slice(b"BM");
+    bmp.extend_from_slice(&(file_size as u32).to_le_bytes());
+    bmp.extend_from_slice(&[0u8; 4]);
+    bmp.extend_from_slice(&(pixel_offset as u32).to_le_bytes());
+    bmp.extend_from_slice(bmi);
+    bmp.extend_from_slice(bits);
+    image::load_from_memory_with_format(&bmp, image::ImageFormat::Bmp)
+        .ok()
+        .map(|img| img.to_rgba8())
+}
+
+fn read_u16(data: &[u8], offset: usize) -> Option<u16> {
+    data.get(offset..offset + 2)
+        .map(|b| u16::from_le_bytes([b[0], b[1]]))
+}
+
+fn read_u32(data: &[u8], offset: usize) -> Option<u32> {
+    data.get(offset..offset + 4)
+        .map(|b| u32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn info_header(width: i32, height: i32, bit_count: u16, colors: u32) -> Vec<u8> {
+        let mut bmi = Vec::new();
+        bmi.extend_from_slice(&40u32.to_le_bytes());
+        bmi.extend_from_slice(&width.to_le_bytes());
+        bmi.extend_from_slice(&height.to_le_bytes());
+        bmi.extend_from_slice(&1u16.to_le_bytes());
+        bmi.extend_from_slice(&bit_count.to_le_bytes());
+        bmi.extend_from_slice(&[0u8; 16]);
+        bmi.extend_from_slice(&colors.to_le_bytes());
+        bmi.extend_from_slice(&0u32.to_le_bytes());
+        bmi
+    }
+
+    #[test]
+    fn test_decode_24bit_bottom_up() {
+        let bmi = info_header(2, 2, 24, 0);
+        // Bottom row first, rows padded to 4 bytes
+        let bits = [
+            0, 0, 255, 0, 255, 0, 0, 0, // red, green
+            255, 0, 0, 255, 255, 255, 0, 0, // blue, white
+        ];
+        let image = decode_dib(&bmi, &bits, false).unwrap();
+        assert_eq!(image.get_pixel(0, 0), &Rgba([0, 0, 255, 255]));
+        assert_eq!(image.get_pixel(1, 0), &Rgba([255, 255, 255, 255]));
+        assert_eq!(image.get_pixel(0, 1), &Rgba([255, 0, 0, 255]));
+        assert_eq!(image.get_pixel(1, 1), &Rgba([0, 255, 0, 255]));
+    }
+
+    #[test]
+    fn test_decode_monochrome_palette() {
+        let mut bmi = info_header(8, -1, 1, 2);
+        bmi.extend_from_slice(&[0, 0, 0, 0, 255, 255, 255, 0]);
+        let image = decode_dib(&bmi, &[0b1010_0000, 0, 0, 0], false).unwrap();
+        assert_eq!(image.get_pixel(0, 0), &Rgba([255, 255, 255, 255]));
+        assert_eq!(image.get_pixel(1, 0), &Rgba([0, 0, 0, 255]));
+        assert_eq!(image.get_pixel(2, 0), &Rgba([255, 255, 255, 255]));
+    }
+
+    #[test]
+    fn test_decode_32bit_alpha() {
+        let bmi = info_header(1, 1, 32, 0);
+        let bits = [10, 20, 30, 40];
+        assert_eq!(
+            decode_dib(&bmi, &bits, true).unwrap().get_pixel(0, 0),
+            &Rgba([30, 20, 10, 40])
+        );
+        assert_eq!(
+            decode_dib(&bmi, &bits, false).unwrap().get_pixel(0, 0),
+            &Rgba([30, 20, 10, 255])
+        );
+    }
+}
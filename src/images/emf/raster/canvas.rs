@@ -0,0 +1,341 @@
+// Pixel canvas with paint sources and compositing
+//
+// All drawing ends up here: a coverage mask is combined with the clip mask
+// and a paint source, then blended onto the RGBA surface with source-over
+// compositing. Bitmap blits support GDI ternary raster operations.
+
+use super::path::{Point, Transform};
+use super::rasterizer::Mask;
+use crate::images::emf::records::{hatch_style, rop};
+use image::{Rgba, RgbaImage};
+
+/// Source of colors for a fill
+pub enum Paint<'a> {
+    /// Uniform color
+    Solid(Rgba<u8>),
+    /// GDI hatch pattern with lines `spacing` pixels apart
+    Hatch {
+        style: u32,
+        color: Rgba<u8>,
+        background: Option<Rgba<u8>>,
+        spacing: f64,
+    },
+    /// Tiled bitmap, each bitmap pixel covering `scale` canvas pixels
+    Pattern { image: &'a RgbaImage, scale: f64 },
+    /// Color computed per pixel center
+    Shader(&'a dyn Fn(f64, f64) -> Rgba<u8>),
+}
+
+impl Paint<'_> {
+    fn color_at(&self, x: u32, y: u32) -> Option<Rgba<u8>> {
+        match self {
+            Paint::Solid(color) => Some(*color),
+            Paint::Hatch {
+                style,
+                color,
+                background,
+                spacing,
+            } => {
+                let spacing = spacing.max(1.0);
+                let line = (spacing / 8.0).max(1.0);
+                let on = |v: f64| v.rem_euclid(spacing) < line;
+                let (fx, fy) = (x as f64, y as f64);
+                let hit = match *style {
+                    hatch_style::HORIZONTAL => on(fy),
+                    hatch_style::VERTICAL => on(fx),
+                    hatch_style::FDIAGONAL => on(fx - fy),
+                    hatch_style::BDIAGONAL => on(fx + fy),
+                    hatch_style::CROSS => on(fx) || on(fy),
+                    hatch_style::DIAGCROSS => on(fx - fy) || on(fx + fy),
+                    _ => true,
+                };
+                if hit { Some(*color) } else { *background }
+            },
+            Paint::Pattern { image, scale } => {
+                if image.width() == 0 || image.height() == 0 {
+                    return None;
+                }
+                let scale = scale.max(1e-6);
+                let px = ((x as f64 / scale) as u64 % image.width() as u64) as u32;
+                let py = ((y as f64 / scale) as u64 % image.height() as u64) as u32;
+                Some(*image.get_pixel(px, py))
+            },
+            Paint::Shader(shader) => Some(shader(x as f64 + 0.5, y as f64 + 0.5)),
+        }
+    }
+}
+
+/// RGBA drawing surface
+pub struct Canvas {
+    image: RgbaImage,
+}
+
+impl Canvas {
+    pub fn new(width: u32, height: u32, background: Rgba<u8>) -> Self {
+        Self {
+            image: RgbaImage::from_pixel(width, height, background),
+        }
+    }
+
+    pub fn width(&self) -> u32 {
+        self.image.width()
+    }
+
+    pub fn height(&self) -> u32 {
+        self.image.height()
+    }
+
+    pub fn into_image(self) -> RgbaImage {
+        self.image
+    }
+
+    /// Fill the area covered by `mask` with `paint`
+    pub fn fill_mask(&mut self, mask: &Mask, paint: &Paint, clip: Option<&Mask>) {
+        let x0 = mask.x().max(0);
+        let y0 = mask.y().max(0);
+        let x1 = (mask.x() + mask.width() as i32).min(self.width() as i32);
+        let y1 = (mask.y() + mask.height() as i32).min(self.height() as i32);
+
+        for y in y0..y1 {
+            for x in x0..x1 {
+                let mut coverage = mask.get(x, y) as u32;
+                if let Some(clip) = clip {
+                    coverage = coverage * clip.get(x, y) as u32 / 255;
+                }
+                if coverage == 0 {
+                    continue;
+                }
+                if let Some(color) = paint.color_at(x as u32, y as u32) {
+                    self.blend(x as u32, y as u32, color, coverage);
+                }
+            }
+        }
+    }
+
+    /// Source-over blend of `color` with the given coverage (0-255)
+    #[inline]
+    fn blend(&mut self, x: u32, y: u32, color: Rgba<u8>, coverage: u32) {
+        let alpha = color[3] as u32 * coverage / 255;
+        if alpha == 0 {
+            return;
+        }
+        let dst = self.image.get_pixel_mut(x, y);
+        if alpha == 255 {
+            *dst = color;
+            dst[3] = 255;
+            return;
+        }
+        let inv = 255 - alpha;
+        for c in 0..3 {
+            dst[c] = ((color[c] as u32 * alpha + dst[c] as u32 * inv + 127) / 255) as u8;
+        }
+        dst[3] = (alpha + dst[3] as u32 * inv / 255).min(255) as u8;
+    }
+
+    /// Draw a bitmap through a transform from bitmap pixels to canvas pixels
+    ///
+    /// `src` selects the source rectangle in bitmap pixels. Standard raster
+    /// operations are evaluated per channel with `brush` as the pattern;
+    /// `SRCCOPY` honours the bitmap's alpha channel.
+    pub fn draw_image(
+        &mut self,
+        image: &RgbaImage,
+        src: (f64, f64, f64, f64),
+        to_canvas: &Transform,
+        raster_op: u32,
+        brush: Rgba<u8>,
+        clip: Option<&Mask>,
+    ) {
+        let Some(inverse) = to_canvas.invert() else {
+            return;
+        };
+        let (sx, sy, sw, sh) = src;
+        let corners = [
+            to_canvas.apply(Point::new(sx, sy)),
+            to_canvas.apply(Point::new(sx + sw, sy)),
+            to_canvas.apply(Point::new(sx, sy + sh)),
+            to_canvas.apply(Point::new(sx + sw, sy + sh)),
+        ];
+        let min_x = corners.iter().map(|p| p.x).fold(f64::INFINITY, f64::min);
+        let max_x = corners
+            .iter()
+            .map(|p| p.x)
+            .fold(f64::NEG_INFINITY, f64::max);
+        let min_y = corners.iter().map(|p| p.y).fold(f64::INFINITY, f64::min);
+        let max_y = corners
+            .iter()
+            .map(|p| p.y)
+            .fold(f64::NEG_INFINITY, f64::max);
+        if !(min_x.is_finite() && max_x.is_finite() && min_y.is_finite() && max_y.is_finite()) {
+            return;
+        }
+
+        let x0 = min_x.floor().max(0.0) as u32;
+        let x1 = max_x.ceil().min(self.width() as f64).max(0.0) as u32;
+        let y0 = min_y.floor().max(0.0) as u32;
+        let y1 = max_y.ceil().min(self.height() as f64).max(0.0) as u32;
+
+        // Upscaled bitmaps are interpolated, downscaled ones sampled
+        let smooth = to_canvas.mean_scale() > 1.0;
+
+        for y in y0..y1 {
+            for x in x0..x1 {
+                let clip_coverage = clip.map_or(255, |c| c.get(x as i32, y as i32)) as u32;
+                if clip_coverage == 0 {
+                    continue;
+                }
+                let p = inverse.apply(Point::new(x as f64 + 0.5, y as f64 + 0.5));
+                if p.x < sx || p.y < sy || p.x >= sx + sw || p.y >= sy + sh {
+                    continue;
+                }
+                let Some(source) = sample(image, p, smooth) else {
+                    continue;
+                };
+
+                if raster_op == rop::SRCCOPY {
+                    self.blend(x, y, source, clip_coverage);
+                    continue;
+                }
+                let dst = *self.image.get_pixel(x, y);
+                let code = ((raster_op >> 16) & 0xFF) as u8;
+                let mut result = Rgba([0, 0, 0, 255]);
+                for c in 0..3 {
+                    result[c] = rop3(code, brush[c], source[c], dst[c]);
+                }
+                self.blend(x, y, result, clip_coverage * source[3] as u32 / 255);
+            }
+        }
+    }
+
+    /// Apply a raster operation that needs no source bitmap over a mask
+    pub fn pattern_blt(
+        &mut self,
+        mask: &Mask,
+        raster_op: u32,
+        brush: Rgba<u8>,
+        clip: Option<&Mask>,
+    ) {
+        let code = ((raster_op >> 16) & 0xFF) as u8;
+        let x0 = mask.x().max(0);
+        let y0 = mask.y().max(0);
+        let x1 = (mask.x() + mask.width() as i32).min(self.width() as i32);
+        let y1 = (mask.y() + mask.height() as i32).min(self.height() as i32);
+        for y in y0..y1 {
+            for x in x0..x1 {
+                let mut coverage = mask.get(x, y) as u32;
+                if let Some(clip) = clip {
+                    coverage = coverage * clip.get(x, y) as u32 / 255;
+                }
+                if coverage == 0 {
+                    continue;
+                }
+                let dst = *self.image.get_pixel(x as u32, y as u32);
+                let mut result = Rgba([0, 0, 0, 255]);
+                for c in 0..3 {
+                    result[c] = rop3(code, brush[c], 0, dst[c]);
+                }
+                self.blend(x as u32, y as u32, result, coverage);
+            }
+        }
+    }
+}
+
+/// Sample a bitmap at a position in pixel coordinates
+fn sample(image: &RgbaImage, p: Point, smooth: bool) -> Option<Rgba<u8>> {
+    let (w, h) = (image.width() as i64, image.height() as i64);
+    if w == 0 || h == 0 {
+        return None;
+    }
+    if !smooth {
+        let x = (p.x.floor() as i64).clamp(0, w - 1) as u32;
+        let y = (p.y.floor() as i64).clamp(0, h - 1) as u32;
+        return Some(*image.get_pixel(x, y));
+    }
+
+    // Bilinear interpolation between pixel centers
+    let fx = p.x - 0.5;
+    let fy = p.y - 0.5;
+    let x0 = fx.floor();
+    let y0 = fy.floor();
+    let tx = fx - x0;
+    let ty = fy - y0;
+    let at = |x: f64, y: f64| {
+        let x = (x as i64).clamp(0, w - 1) as u32;
+        let y = (y as i64).clamp(0, h - 1) as u32;
+        *image.get_pixel(x, y)
+    };
+    let (p00, p10, p01, p11) = (
+        at(x0, y0),
+        at(x0 + 1.0, y0),
+        at(x0, y0 + 1.0),
+        at(x0 + 1.0, y0 + 1.0),
+    );
+    let mut out = Rgba([0, 0, 0, 0]);
+    for c in 0..4 {
+        let top = p00[c] as f64 * (1.0 - tx) + p10[c] as f64 * tx;
+        let bottom = p01[c] as f64 * (1.0 - tx) + p11[c] as f64 * tx;
+        out[c] = (top * (1.0 - ty) + bottom * ty).round().clamp(0.0, 255.0) as u8;
+    }
+    Some(out)
+}
+
+/// Evaluate a ternary raster operation on one channel
+///
+/// Bit `(p << 2) | (s << 1) | d` of `code` gives the result for each
+/// combination of pattern, source and destination bits.
+pub fn rop3(code: u8, p: u8, s: u8, d: u8) -> u8 {
+    let mut result = 0u8;
+    for bit in 0..8 {
+        if code & (1 << bit) == 0 {
+            continue;
+        }
+        let pm = if bit & 4 != 0 { p } else { !p };
+        let sm = if bit & 2 != 0 { s } else { !s };
+        let dm = if bit & 1 != 0 { d } else { !d };
+        result |= pm & sm & dm;
+    }
+    result
+}
+
+/// Parse a `#RRGGBB` color as used by the GDI object state
+pub fn parse_hex_color(hex: &str) -> Rgba<u8> {
+    let digits = hex.trim_start_matches('#');
+    let value = u32::from_str_radix(digits, 16).unwrap_or(0);
+    Rgba([
+        ((value >> 16) & 0xFF) as u8,
+        ((value >> 8) & 0xFF) as u8,
+        (value & 0xFF) as u8,
+        255,
+    ])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rop3() {
+        let (p, s, d) = (0b1100_1100, 0b1010_1010, 0b1111_0000);
+        assert_eq!(rop3((rop::SRCCOPY >> 16) as u8, p, s, d), s);
+        assert_eq!(rop3((rop::SRCAND >> 16) as u8, p, s, d), s & d);
+        assert_eq!(rop3((rop::SRCPAINT >> 16) as u8, p, s, d), s | d);
+        assert_eq!(rop3((rop::SRCINVERT >> 16) as u8, p, s, d), s ^ d);
+        assert_eq!(rop3((rop::PATCOPY >> 16) as u8, p, s, d), p);
+        assert_eq!(rop3((rop::DSTINVERT >> 16) as u8, p, s, d), !d);
+        assert_eq!(rop3((rop::BLACKNESS >> 16) as u8, p, s, d), 0);
+    }
+
+    #[test]
+    fn test_blend_partial_coverage() {
+        let mut canvas = Canvas::new(2, 1, Rgba([255, 255, 255, 255]));
+        let mask = Mask::filled(0, 0, 2, 1, 128);
+        canvas.fill_mask(&mask, &Paint::Solid(Rgba([0, 0, 0, 255])), None);
+        let image = canvas.into_image();
+        assert_eq!(image.get_pixel(0, 0), &Rgba([127, 127, 127, 255]));
+    }
+
+    #[test]
+    fn test_parse_hex_color() {
+        assert_eq!(parse_hex_color("#FF8000"), Rgba([255, 128, 0, 255]));
+    }
+}
@@ -0,0 +1,459 @@
+// EMF+ output onto the raster canvas
+//
+// The playback state doubles as an EMF+ device: shapes are transformed from
+// world units to output pixels and go through the same rasterizer and canvas
+// as GDI records. Gradient and texture brushes are evaluated per pixel by
+// mapping each pixel back into brush space. EMF+ keeps its own clipping
+// region, independent of the GDI clip of the device context.
+
+use super::canvas::Paint;
+use super::font::{self, TextRun};
+use super::path::{Path, Point, Transform};
+use super::rasterizer::{FillRule, Mask, MaskOp, rasterize};
+use super::renderer::{Playback, TOLERANCE};
+use super::stroke::{StrokeStyle, stroke};
+use crate::images::emf::emfplus::objects::{CombineMode, StringAlignment, WrapMode};
+use crate::images::emf::emfplus::{
+    Brush, Color, EmfPlusDevice, Image, Pen, RectF, Region, Text, TextLayout,
+};
+use crate::images::emf::gdi_objects::Font;
+use crate::images::emf::records::rop;
+use image::{Rgba, RgbaImage};
+use std::sync::Arc;
+
+/// Hatch styles with a GDI equivalent (HatchStyleHorizontal to HatchStyleDiagonalCross)
+const GDI_HATCH_STYLES: u32 = 6;
+
+impl EmfPlusDevice for Playback {
+    fn fill_path(&mut self, path: &Path, winding: bool, brush: &Brush, to_device: &Transform) {
+        let to_output = to_device.then(&self.device_to_output);
+        let rule = if winding {
+            FillRule::NonZero
+        } else {
+            FillRule::EvenOdd
+        };
+        if let Some(mask) = self.rasterize(&path.transform(&to_output), rule) {
+            self.paint(&mask, brush, &to_output);
+        }
+    }
+
+    fn stroke_path(&mut self, path: &Path, pen: &Pen, width: f64, to_device: &Transform) {
+        // Stroke in world units so the pen follows the transform
+        let to_output = to_device.then(&self.device_to_output);
+        let style = StrokeStyle {
+            width,
+            cap: pen.end_cap,
+            join: pen.join,
+            miter_limit: pen.miter_limit,
+            dashes: pen.dashes.iter().map(|dash| dash * width).collect(),
+        };
+        let tolerance = TOLERANCE / to_output.mean_scale().max(1e-9);
+        let mut outline = stroke(&path.flatten(tolerance), &style);
+        for polyline in &mut outline {
+            for p in &mut polyline.points {
+                *p = to_output.apply(*p);
+            }
+        }
+        if let Some(mask) = rasterize(
+            &outline,
+            FillRule::NonZero,
+            self.canvas.width(),
+            self.canvas.height(),
+        ) {
+            self.paint(&mask, &pen.brush, &to_output);
+        }
+    }
+
+    fn draw_text(&mut self, text: &Text, to_device: &Transform) {
+        let to_output = to_device.then(&self.device_to_output);
+        let font = Font {
+            weight: if text.font.is_bold() { 700 } else { 400 },
+            italic: text.font.is_italic(),
+            underline: text.font.is_underline(),
+            strike_out: text.font.is_strikeout(),
+            face_name: text.font.family.clone(),
+            ..Default::default()
+        };
+        let em = text.em_size;
+
+        let mut outlines = Path::new();
+        match &text.layout {
+            TextLayout::Rect {
+                text: string,
+                rect,
+                format,
+            } => {
+                let lines: Vec<Vec<char>> = string
+                    .split('\n')
+                    .map(|line| line.trim_end_matches('\r').chars().collect())
+                    .collect();
+                let shaped: Vec<_> = lines
+                    .iter()
+                    .filter_map(|line| font::shape(&font, TextRun::Chars(line)))
+                    .collect();
+                let Some(metrics) = shaped.first().map(|s| s.metrics) else {
+                    return;
+                };
+                let line_height = (metrics.descent - metrics.ascent) * em;
+                let (alignment, line_alignment) = format
+                    .map_or((StringAlignment::Near, StringAlignment::Near), |format| {
+                        (format.alignment, format.line_alignment)
+                    });
+                let top = rect.y
+                    + (rect.height - line_height * shaped.len() as f64)
+                        * alignment_factor(line_alignment);
+
+                for (i, line) in shaped.iter().enumerate() {
+                    let width: f64 = line.glyphs.iter().map(|g| g.advance * em).sum();
+                    let mut x = rect.x + (rect.width - width) * alignment_factor(alignment);
+                    let baseline = top + i as f64 * line_height - metrics.ascent * em;
+                    let start = x;
+                    for glyph in &line.glyphs {
+                        let place =
+                            Transform::scale(em, em).then(&Transform::translate(x, baseline));
+                        outlines.append(&glyph.outline.transform(&place));
+                        x += glyph.advance * em;
+                    }
+                    append_decorations(&mut outlines, &font, &metrics, em, start, x, baseline);
+                }
+            },
+            TextLayout::Positioned {
+                codes,
+                glyph_indices,
+                positions,
+            } => {
+                let chars: Vec<char>;
+                let run = if *glyph_indices {
+                    TextRun::Glyphs(codes)
+                } else {
+                    chars = char::decode_utf16(codes.iter().copied())
+                        .map(|c| c.unwrap_or(char::REPLACEMENT_CHARACTER))
+                        .collect();
+                    TextRun::Chars(&chars)
+                };
+                let Some(shaped) = font::shape(&font, run) else {
+                    return;
+                };
+                let Some(&first) = positions.first() else {
+                    return;
+                };
+                let mut origin = first;
+                let start = origin.x;
+                for (i, glyph) in shaped.glyphs.iter().enumerate() {
+                    if let Some(&p) = positions.get(i) {
+                        origin = p;
+                    }
+                    let place =
+                        Transform::scale(em, em).then(&Transform::translate(origin.x, origin.y));
+                    outlines.append(&glyph.outline.transform(&place));
+                    origin.x += glyph.advance * em;
+                }
+                append_decorations(
+                    &mut outlines,
+                    &font,
+                    &shaped.metrics,
+                    em,
+                    start,
+                    origin.x,
+                    first.y,
+                );
+            },
+        }
+
+        if let Some(mask) = self.rasterize(&outlines.transform(&to_output), FillRule::NonZero) {
+            self.paint(&mask, text.brush, &to_output);
+        }
+    }
+
+    fn draw_image(&mut self, image: &Image, src: RectF, to_device: &Transform) {
+        let Some(pixels) = image.to_rgba() else {
+            return;
+        };
+        let to_output = to_device.then(&self.device_to_output);
+        self.canvas.draw_image(
+            &pixels,
+            (src.x, src.y, src.width, src.height),
+            &to_output,
+            rop::SRCCOPY,
+            Rgba([0, 0, 0, 255]),
+            self.plus_clip.as_deref(),
+        );
+    }
+
+    fn set_clip(&mut self, clip: Option<&Region>) {
+        self.plus_clip = clip.map(|region| Arc::new(self.region_mask(region)));
+    }
+
+    fn clear(&mut self, color: Color) {
+        let mask = Mask::filled(0, 0, self.canvas.width(), self.canvas.height(), 255);
+        self.canvas
+            .fill_mask(&mask, &Paint::Solid(rgba(color)), self.plus_clip.as_deref());
+    }
+}
+
+impl Playback {
+    /// Paint a coverage mask with an EMF+ brush defined in the space that
+    /// `to_output` maps to output pixels
+    fn paint(&mut self, mask: &Mask, brush: &Brush, to_output: &Transform) {
+        let clip = self.plus_clip.clone();
+        let clip = clip.as_deref();
+        match brush {
+            Brush::Solid(color) => self
+                .canvas
+                .fill_mask(mask, &Paint::Solid(rgba(*color)), clip),
+            Brush::Hatch {
+                style,
+                foreground,
+                background,
+            } => {
+                let paint = if *style < GDI_HATCH_STYLES {
+                    Paint::Hatch {
+                        style: *style,
+                        color: rgba(*foreground),
+                        background: Some(rgba(*background)),
+                        spacing: 8.0 * self.scale,
+                    }
+                } else {
+                    Paint::Solid(rgba(foreground.lerp(*background, 0.5)))
+                };
+                self.canvas.fill_mask(mask, &paint, clip);
+            },
+            Brush::LinearGradient {
+                rect,
+                stops,
+                transform,
+                wrap,
+            } => {
+                let Some(to_brush) = transform.then(to_output).invert() else {
+                    return;
+                };
+                let width = if rect.width == 0.0 { 1.0 } else { rect.width };
+                let shader = |x: f64, y: f64| {
+                    let p = to_brush.apply(Point::new(x, y));
+                    rgba(gradient_color(stops, wrap.wrap((p.x - rect.x) / width)))
+                };
+                self.canvas.fill_mask(mask, &Paint::Shader(&shader), clip);
+            },
+            Brush::PathGradient {
+                center,
+                boundary,
+                stops,
+                transform,
+                ..
+            } => {
+                let Some(to_brush) = transform.then(to_output).invert() else {
+                    return;
+                };
+                let shader = |x: f64, y: f64| {
+                    let p = to_brush.apply(Point::new(x, y));
+                    let t = 1.0 - boundary_ratio(*center, boundary, p).min(1.0);
+                    rgba(gradient_color(stops, t))
+                };
+                self.canvas.fill_mask(mask, &Paint::Shader(&shader), clip);
+            },
+            Brush::Texture {
+                image,
+                transform,
+                wrap,
+            } => {
+                let (Some(pixels), Some(to_image)) =
+                    (image.to_rgba(), transform.then(to_output).invert())
+                else {
+                    return;
+                };
+                if pixels.width() == 0 || pixels.height() == 0 {
+                    return;
+                }
+                let transparent = Rgba([0, 0, 0, 0]);
+                let shader = |x: f64, y: f64| {
+                    let p = to_image.apply(Point::new(x, y));
+                    texel(&pixels, p, *wrap).unwrap_or(transparent)
+                };
+                self.canvas.fill_mask(mask, &Paint::Shader(&shader), clip);
+            },
+        }
+    }
+
+    /// Coverage of a region given in reference device units
+    fn region_mask(&self, region: &Region) -> Mask {
+        let (width, height) = (self.canvas.width(), self.canvas.height());
+        match region {
+            Region::Empty => Mask::filled(0, 0, 0, 0, 0),
+            Region::Infinite => Mask::filled(0, 0, width, height, 255),
+            Region::Path(path) => self
+                .rasterize(&path.transform(&self.device_to_output), FillRule::EvenOdd)
+                .unwrap_or_else(|| Mask::filled(0, 0, 0, 0, 0)),
+            Region::Combine(mode, left, right) => {
+                let (left, right) = (self.region_mask(left), self.region_mask(right));
+                match mode {
+                    CombineMode::Replace => right,
+                    CombineMode::Intersect => left.combine(&right, MaskOp::And, width, height),
+                    CombineMode::Union => left.combine(&right, MaskOp::Or, width, height),
+                    CombineMode::Xor => left.combine(&right, MaskOp::Xor, width, height),
+                    CombineMode::Exclude => left.combine(&right, MaskOp::Diff, width, height),
+                    CombineMode::Complement => right.combine(&left, MaskOp::Diff, width, height),
+                }
+            },
+        }
+    }
+}
+
+fn rgba(color: Color) -> Rgba<u8> {
+    Rgba([color.r, color.g, color.b, color.a])
+}
+
+/// Offset of a line or block within the available space
+fn alignment_factor(alignment: StringAlignment) -> f64 {
+    match alignment {
+        StringAlignment::Near => 0.0,
+        StringAlignment::Center => 0.5,
+        StringAlignment::Far => 1.0,
+    }
+}
+
+/// Add underline and strikeout bars for a run of text
+fn append_decorations(
+    outlines: &mut Path,
+    font: &Font,
+    metrics: &font::FontMetrics,
+    em: f64,
+    start: f64,
+    end: f64,
+    baseline: f64,
+) {
+    let mut decorations = Vec::new();
+    if font.underline {
+        decorations.push(metrics.underline);
+    }
+    if font.strike_out {
+        decorations.push(metrics.strikeout);
+    }
+    for (center, thickness) in decorations {
+        outlines.append(&Path::rect(
+            start,
+            baseline + (center - thickness / 2.0) * em,
+            end,
+            baseline + (center + thickness / 2.0) * em,
+        ));
+    }
+}
+
+/// Color at position `t` of a sorted list of gradient stops
+fn gradient_color(stops: &[(f64, Color)], t: f64) -> Color {
+    let Some(&(first_position, first)) = stops.first() else {
+        return Color::default();
+    };
+    if t <= first_position {
+        return first;
+    }
+    for pair in stops.windows(2) {
+        let ((p0, c0), (p1, c1)) = (pair[0], pair[1]);
+        if t <= p1 {
+            let span = p1 - p0;
+            return if span <= 0.0 {
+                c1
+            } else {
+                c0.lerp(c1, (t - p0) / span)
+            };
+        }
+    }
+    stops.last().map_or(first, |&(_, color)| color)
+}
+
+/// Distance of `p` from `center` relative to the boundary along the same ray
+///
+/// 0 at the center, 1 on the boundary and above 1 outside of it.
+fn boundary_ratio(center: Point, boundary: &[Point], p: Point) -> f64 {
+    let (dx, dy) = (p.x - center.x, p.y - center.y);
+    if dx == 0.0 && dy == 0.0 {
+        return 0.0;
+    }
+
+    // Nearest intersection of the ray center + s * (p - center) with an edge
+    let mut nearest = f64::INFINITY;
+    for (i, &a) in boundary.iter().enumerate() {
+        let b = boundary[(i + 1) % boundary.len()];
+        let (ex, ey) = (b.x - a.x, b.y - a.y);
+        let denom = dx * ey - dy * ex;
+        if denom.abs() < 1e-12 {
+            continue;
+        }
+        let (ax, ay) = (a.x - center.x, a.y - center.y);
+        let s = (ax * ey - ay * ex) / denom;
+        let u = (ax * dy - ay * dx) / denom;
+        if s > 0.0 && (0.0..=1.0).contains(&u) {
+            nearest = nearest.min(s);
+        }
+    }
+    if nearest.is_finite() {
+        1.0 / nearest
+    } else {
+        1.0
+    }
+}
+
+/// Nearest texel of a texture brush, repeated according to the wrap mode
+fn texel(image: &RgbaImage, p: Point, wrap: WrapMode) -> Option<Rgba<u8>> {
+    let (width, height) = (image.width() as f64, image.height() as f64);
+    let (flip_x, flip_y) = match wrap {
+        WrapMode::Clamp => {
+            if p.x < 0.0 || p.y < 0.0 || p.x >= width || p.y >= height {
+                return None;
+            }
+            (false, false)
+        },
+        WrapMode::Tile => (false, false),
+        WrapMode::TileFlipX => (true, false),
+        WrapMode::TileFlipY => (false, true),
+        WrapMode::TileFlipXY => (true, true),
+    };
+    let x = wrap_coordinate(p.x, width, flip_x) as u32;
+    let y = wrap_coordinate(p.y, height, flip_y) as u32;
+    Some(*image.get_pixel(x.min(image.width() - 1), y.min(image.height() - 1)))
+}
+
+fn wrap_coordinate(value: f64, size: f64, flip: bool) -> f64 {
+    let tile = (value / size).floor();
+    let offset = value - tile * size;
+    if flip && tile.rem_euclid(2.0) == 1.0 {
+        size - 1.0 - offset.floor()
+    } else {
+        offset
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn color(r: u8, g: u8, b: u8) -> Color {
+        Color { r, g, b, a: 255 }
+    }
+
+    #[test]
+    fn test_gradient_color() {
+        let stops = [
+            (0.0, color(0, 0, 0)),
+            (0.5, color(200, 0, 0)),
+            (1.0, color(200, 100, 0)),
+        ];
+        assert_eq!(gradient_color(&stops, -1.0), color(0, 0, 0));
+        assert_eq!(gradient_color(&stops, 0.25), color(100, 0, 0));
+        assert_eq!(gradient_color(&stops, 0.75), color(200, 50, 0));
+        assert_eq!(gradient_color(&stops, 2.0), color(200, 100, 0));
+    }
+
+    #[test]
+    fn test_boundary_ratio() {
+        let square = [
+            Point::new(-10.0, -10.0),
+            Point::new(10.0, -10.0),
+            Point::new(10.0, 10.0),
+            Point::new(-10.0, 10.0),
+        ];
+        let center = Point::new(0.0, 0.0);
+        assert!((boundary_ratio(center, &square, Point::new(5.0, 0.0)) - 0.5).abs() < 1e-9);
+        assert!((boundary_ratio(center, &square, Point::new(10.0, 10.0)) - 1.0).abs() < 1e-9);
+        assert!((boundary_ratio(center, &square, Point::new(0.0, -20.0)) - 2.0).abs() < 1e-9);
+    }
+}
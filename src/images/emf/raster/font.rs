@@ -0,0 +1,204 @@
+// Glyph outlines for text rendering
+//
+// Fonts are looked up among the system fonts by the LOGFONT face name, with
+// metric-compatible substitutes for the common Windows faces. Glyphs are
+// returned as outlines in em units so text goes through the same path
+// rasterizer as every other shape.
+
+use super::path::{Path, Point};
+use crate::images::emf::gdi_objects::Font;
+use fontdb::{Database, Family, Query, Style, Weight};
+use once_cell::sync::Lazy;
+
+/// System font database, loaded on first use
+static FONTS: Lazy<Database> = Lazy::new(|| {
+    let mut db = Database::new();
+    db.load_system_fonts();
+    db
+});
+
+const SANS_SUBSTITUTES: &[&str] = &["Liberation Sans", "Arimo", "DejaVu Sans", "Noto Sans"];
+const SERIF_SUBSTITUTES: &[&str] = &["Liberation Serif", "Tinos", "DejaVu Serif", "Noto Serif"];
+const MONO_SUBSTITUTES: &[&str] = &[
+    "Liberation Mono",
+    "Cousine",
+    "DejaVu Sans Mono",
+    "Noto Sans Mono",
+];
+
+/// Text to lay out: characters or glyph indices (`ETO_GLYPH_INDEX`)
+pub enum TextRun<'a> {
+    Chars(&'a [char]),
+    Glyphs(&'a [u16]),
+}
+
+/// Vertical metrics in em units, measured downwards from the baseline
+#[derive(Debug, Clone, Copy)]
+pub struct FontMetrics {
+    /// Distance from the baseline to the top of the cell (negative)
+    pub ascent: f64,
+    /// Distance from the baseline to the bottom of the cell (positive)
+    pub descent: f64,
+    /// Underline center and thickness
+    pub underline: (f64, f64),
+    /// Strikeout center and thickness
+    pub strikeout: (f64, f64),
+}
+
+/// A glyph outline in em units with the origin on the baseline
+pub struct Glyph {
+    pub outline: Path,
+    /// Horizontal advance in em units
+    pub advance: f64,
+}
+
+/// Laid out glyphs of a text run
+pub struct ShapedText {
+    pub glyphs: Vec<Glyph>,
+    pub metrics: FontMetrics,
+}
+
+/// Outline and measure a text run with the best available system font
+///
+/// Returns `None` if no usable font is installed.
+pub fn shape(font: &Font, text: TextRun) -> Option<ShapedText> {
+    let db = &*FONTS;
+    let id = find_face(db, font)?;
+    db.with_face_data(id, |data, index| {
+        let face = ttf_parser::Face::parse(data, index).ok()?;
+        let upem = face.units_per_em() as f64;
+        if upem <= 0.0 {
+            return None;
+        }
+
+        let line = |metrics: Option<ttf_parser::LineMetrics>, fallback: (f64, f64)| {
+            metrics.map_or(fallback, |m| {
+                let thickness = (m.thickness as f64 / upem).max(0.02);
+                (-(m.position as f64) / upem + thickness / 2.0, thickness)
+            })
+        };
+        let metrics = FontMetrics {
+            ascent: -(face.ascender() as f64) / upem,
+            descent: -(face.descender() as f64) / upem,
+            underline: line(face.underline_metrics(), (0.1, 0.05)),
+            strikeout: line(face.strikeout_metrics(), (-0.3, 0.05)),
+        };
+
+        let ids: Vec<ttf_parser::GlyphId> = match text {
+            TextRun::Chars(chars) => chars
+                .iter()
+                .map(|&c| face.glyph_index(c).unwrap_or(ttf_parser::GlyphId(0)))
+                .collect(),
+            TextRun::Glyphs(glyphs) => glyphs.iter().map(|&g| ttf_parser::GlyphId(g)).collect(),
+        };
+
+        let glyphs = ids
+            .into_iter()
+            .map(|id| {
+                let mut builder = OutlineSink {
+                    path: Path::new(),
+                    scale: 1.0 / upem,
+                    last: Point::default(),
+                };
+                face.outline_glyph(id, &mut builder);
+                Glyph {
+                    outline: builder.path,
+                    advance: face.glyph_hor_advance(id).unwrap_or(0) as f64 / upem,
+                }
+            })
+            .collect();
+
+        Some(ShapedText { glyphs, metrics })
+    })?
+}
+
+/// Pick a face for a LOGFONT, falling back to substitutes and then any font
+fn find_face(db: &Database, font: &Font) -> Option<fontdb::ID> {
+    let name = font.face_name.trim();
+    let lower = name.to_ascii_lowercase();
+    let substitutes = if ["courier", "consolas", "mono", "lucida console", "fixedsys"]
+        .iter()
+        .any(|k| lower.contains(k))
+    {
+        MONO_SUBSTITUTES
+    } else if [
+        "times", "georgia", "garamond", "cambria", "serif", "roman", "book", "palatino",
+    ]
+    .iter()
+    .any(|k| lower.contains(k) && !lower.contains("sans"))
+    {
+        SERIF_SUBSTITUTES
+    } else {
+        SANS_SUBSTITUTES
+    };
+
+    let mut families = Vec::with_capacity(substitutes.len() + 1);
+    if !name.is_empty() {
+        families.push(Family::Name(name));
+    }
+    families.extend(substitutes.iter().map(|&s| Family::Name(s)));
+
+    let weight = if font.weight <= 0 {
+        400
+    } else {
+        font.weight.clamp(100, 900) as u16
+    };
+    let query = Query {
+        families: &families,
+        weight: Weight(weight),
+        style: if font.italic {
+            Style::Italic
+        } else {
+            Style::Normal
+        },
+        ..Default::default()
+    };
+    db.query(&query)
+        .or_else(|| db.faces().next().map(|face| face.id))
+}
+
+/// Collects glyph outlines into a y-down path in em units
+struct OutlineSink {
+    path: Path,
+    scale: f64,
+    last: Point,
+}
+
+impl OutlineSink {
+    fn point(&self, x: f32, y: f32) -> Point {
+        Point::new(x as f64 * self.scale, -(y as f64) * self.scale)
+    }
+}
+
+impl ttf_parser::OutlineBuilder for OutlineSink {
+    fn move_to(&mut self, x: f32, y: f32) {
+        self.last = self.point(x, y);
+        self.path.move_to(self.last);
+    }
+
+    fn line_to(&mut self, x: f32, y: f32) {
+        self.last = self.point(x, y);
+        self.path.line_to(self.last);
+    }
+
+    fn quad_to(&mut self, x1: f32, y1: f32, x: f32, y: f32) {
+        // Raise the quadratic to a cubic
+        let control = self.point(x1, y1);
+        let end = self.point(x, y);
+        let c1 = self.last.lerp(control, 2.0 / 3.0);
+        let c2 = end.lerp(control, 2.0 / 3.0);
+        self.path.cubic_to(c1, c2, end);
+        self.last = end;
+    }
+
+    fn curve_to(&mut self, x1: f32, y1: f32, x2: f32, y2: f32, x: f32, y: f32) {
+        let end = self.point(x, y);
+        self.path
+            .cubic_to(self.point(x1, y1), self.point(x2, y2), end);
+        self.last = end;
+    }
+
+    fn close(&mut self) {
+        self.path.close();
+    }
+}
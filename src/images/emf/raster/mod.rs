@@ -0,0 +1,19 @@
+// EMF rasterization
+//
+// A small software renderer that plays back EMF records onto an RGBA canvas:
+// paths are flattened to polylines, strokes are expanded into polygons, and
+// everything is filled through a single anti-aliased scanline rasterizer so
+// clipping, raster operations and text all share one code path.
+
+mod bitmap;
+mod canvas;
+mod emfplus;
+mod font;
+mod path;
+mod rasterizer;
+mod renderer;
+mod stroke;
+
+pub use path::{Path, Point, Segment, Transform};
+pub use rasterizer::Mask;
+pub use renderer::EmfRenderer;
@@ -0,0 +1,488 @@
+// Path geometry for the EMF rasterizer
+//
+// Paths are stored with cubic Bézier segments so they can be transformed
+// exactly (affine transforms map Béziers to Béziers) and are only flattened
+// to polylines right before rasterization, in output pixel space.
+
+use crate::images::emf::records::XForm;
+use std::f64::consts::{FRAC_PI_2, PI};
+
+/// Bézier approximation constant for a quarter circle
+const KAPPA: f64 = 0.552_284_749_830_793_4;
+
+/// A point in 2D space
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct Point {
+    pub x: f64,
+    pub y: f64,
+}
+
+impl Point {
+    pub const fn new(x: f64, y: f64) -> Self {
+        Self { x, y }
+    }
+
+    pub fn distance(self, other: Point) -> f64 {
+        (self.x - other.x).hypot(self.y - other.y)
+    }
+
+    pub fn lerp(self, other: Point, t: f64) -> Point {
+        Point::new(
+            self.x + (other.x - self.x) * t,
+            self.y + (other.y - self.y) * t,
+        )
+    }
+}
+
+/// 2D affine transform
+///
+/// Maps `(x, y)` to `(a*x + c*y + e, b*x + d*y + f)`, the same layout as the
+/// GDI `XFORM` structure (`m11`, `m12`, `m21`, `m22`, `dx`, `dy`).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Transform {
+    pub a: f64,
+    pub b: f64,
+    pub c: f64,
+    pub d: f64,
+    pub e: f64,
+    pub f: f64,
+}
+
+impl Default for Transform {
+    fn default() -> Self {
+        Self::IDENTITY
+    }
+}
+
+impl Transform {
+    pub const IDENTITY: Transform = Transform {
+        a: 1.0,
+        b: 0.0,
+        c: 0.0,
+        d: 1.0,
+        e: 0.0,
+        f: 0.0,
+    };
+
+    pub const fn translate(x: f64, y: f64) -> Self {
+        Self {
+            a: 1.0,
+            b: 0.0,
+            c: 0.0,
+            d: 1.0,
+            e: x,
+            f: y,
+        }
+    }
+
+    pub const fn scale(sx: f64, sy: f64) -> Self {
+        Self {
+            a: sx,
+            b: 0.0,
+            c: 0.0,
+            d: sy,
+            e: 0.0,
+            f: 0.0,
+        }
+    }
+
+    /// Rotation by `angle` radians (counter-clockwise on a y-down surface)
+    pub fn rotate(angle: f64) -> Self {
+        let (sin, cos) = angle.sin_cos();
+        Self {
+            a: cos,
+            b: -sin,
+            c: sin,
+            d: cos,
+            e: 0.0,
+            f: 0.0,
+        }
+    }
+
+    pub fn from_xform(xform: &XForm) -> Self {
+        Self {
+            a: xform.m11 as f64,
+            b: xform.m12 as f64,
+            c: xform.m21 as f64,
+            d: xform.m22 as f64,
+            e: xform.dx as f64,
+            f: xform.dy as f64,
+        }
+    }
+
+    /// Transform that applies `self` first and then `next`
+    pub fn then(&self, next: &Transform) -> Transform {
+        Transform {
+            a: next.a * self.a + next.c * self.b,
+            b: next.b * self.a + next.d * self.b,
+            c: next.a * self.c + next.c * self.d,
+            d: next.b * self.c + next.d * self.d,
+            e: next.a * self.e + next.c * self.f + next.e,
+            f: next.b * self.e + next.d * self.f + next.f,
+        }
+    }
+
+    pub fn apply(&self, p: Point) -> Point {
+        Point::new(
+            self.a * p.x + self.c * p.y + self.e,
+            self.b * p.x + self.d * p.y + self.f,
+        )
+    }
+
+    /// Apply only the linear part (no translation)
+    pub fn apply_vector(&self, p: Point) -> Point {
+        Point::new(self.a * p.x + self.c * p.y, self.b * p.x + self.d * p.y)
+    }
+
+    pub fn determinant(&self) -> f64 {
+        self.a * self.d - self.b * self.c
+    }
+
+    pub fn invert(&self) -> Option<Transform> {
+        let det = self.determinant();
+        if det.abs() < 1e-12 || !det.is_finite() {
+            return None;
+        }
+        let a = self.d / det;
+        let b = -self.b / det;
+        let c = -self.c / det;
+        let d = self.a / det;
+        Some(Transform {
+            a,
+            b,
+            c,
+            d,
+            e: -(a * self.e + c * self.f),
+            f: -(b * self.e + d * self.f),
+        })
+    }
+
+    /// Average scale factor (square root of the absolute determinant)
+    pub fn mean_scale(&self) -> f64 {
+        self.determinant().abs().sqrt()
+    }
+}
+
+/// A single path segment
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Segment {
+    MoveTo(Point),
+    LineTo(Point),
+    CubicTo(Point, Point, Point),
+    Close,
+}
+
+/// A flattened sub-path
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Polyline {
+    pub points: Vec<Point>,
+    pub closed: bool,
+}
+
+/// A path made of straight lines and cubic Béziers
+#[derive(Debug, Clone, Default)]
+pub struct Path {
+    segments: Vec<Segment>,
+    start: Option<Point>,
+    current: Option<Point>,
+}
+
+impl Path {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.segments.is_empty()
+    }
+
+    pub fn segments(&self) -> &[Segment] {
+        &self.segments
+    }
+
+    pub fn current_point(&self) -> Option<Point> {
+        self.current
+    }
+
+    pub fn move_to(&mut self, p: Point) {
+        // Collapse consecutive moves
+        if let Some(Segment::MoveTo(last)) = self.segments.last_mut() {
+            *last = p;
+        } else {
+            self.segments.push(Segment::MoveTo(p));
+        }
+        self.start = Some(p);
+        self.current = Some(p);
+    }
+
+    pub fn line_to(&mut self, p: Point) {
+        if self.current.is_none() {
+            self.move_to(p);
+            return;
+        }
+        self.segments.push(Segment::LineTo(p));
+        self.current = Some(p);
+    }
+
+    pub fn cubic_to(&mut self, c1: Point, c2: Point, p: Point) {
+        if self.current.is_none() {
+            self.move_to(c1);
+        }
+        self.segments.push(Segment::CubicTo(c1, c2, p));
+        self.current = Some(p);
+    }
+
+    pub fn close(&mut self) {
+        if self.current.is_some() && !matches!(self.segments.last(), Some(Segment::Close)) {
+            self.segments.push(Segment::Close);
+            self.current = self.start;
+        }
+    }
+
+    /// Append all figures of another path
+    pub fn append(&mut self, other: &Path) {
+        for segment in &other.segments {
+            match *segment {
+                Segment::MoveTo(p) => self.move_to(p),
+                Segment::LineTo(p) => self.line_to(p),
+                Segment::CubicTo(c1, c2, p) => self.cubic_to(c1, c2, p),
+                Segment::Close => self.close(),
+            }
+        }
+    }
+
+    /// Append another path, continuing the current figure
+    ///
+    /// The leading move of `other` becomes a line from the current point, as
+    /// GDI does for `*To` records drawn inside a path bracket.
+    pub fn connect(&mut self, other: &Path) {
+        let mut segments = other.segments.iter();
+        if let Some(&Segment::MoveTo(p)) = other.segments.first() {
+            segments.next();
+            match self.current {
+                Some(current) if current.distance(p) > 1e-9 => self.line_to(p),
+                Some(_) => {},
+                None => self.move_to(p),
+            }
+        }
+        for segment in segments {
+            match *segment {
+                Segment::MoveTo(p) => self.move_to(p),
+                Segment::LineTo(p) => self.line_to(p),
+                Segment::CubicTo(c1, c2, p) => self.cubic_to(c1, c2, p),
+                Segment::Close => self.close(),
+            }
+        }
+    }
+
+    pub fn transform(&self, t: &Transform) -> Path {
+        let segments = self
+            .segments
+            .iter()
+            .map(|segment| match *segment {
+                Segment::MoveTo(p) => Segment::MoveTo(t.apply(p)),
+                Segment::LineTo(p) => Segment::LineTo(t.apply(p)),
+                Segment::CubicTo(c1, c2, p) => {
+                    Segment::CubicTo(t.apply(c1), t.apply(c2), t.apply(p))
+                },
+                Segment::Close => Segment::Close,
+            })
+            .collect();
+        Path {
+            segments,
+            start: self.start.map(|p| t.apply(p)),
+            current: self.current.map(|p| t.apply(p)),
+        }
+    }
+
+    /// Flatten curves into polylines with the given maximum deviation
+    pub fn flatten(&self, tolerance: f64) -> Vec<Polyline> {
+        let mut result = Vec::new();
+        let mut current = Polyline::default();
+
+        for segment in &self.segments {
+            match *segment {
+                Segment::MoveTo(p) => {
+                    if current.points.len() > 1 {
+                        result.push(std::mem::take(&mut current));
+                    }
+                    current.points.clear();
+                    current.closed = false;
+                    current.points.push(p);
+                },
+                Segment::LineTo(p) => current.points.push(p),
+                Segment::CubicTo(c1, c2, p) => {
+                    let p0 = current.points.last().copied().unwrap_or(c1);
+                    flatten_cubic(p0, c1, c2, p, tolerance, &mut current.points);
+                },
+                Segment::Close => {
+                    if let Some(&first) = current.points.first() {
+                        current.closed = true;
+                        result.push(std::mem::take(&mut current));
+                        // A new figure continues from the start point
+                        current.points.push(first);
+                    }
+                },
+            }
+        }
+        if current.points.len() > 1 {
+            result.push(current);
+        }
+        result
+    }
+
+    /// Axis-aligned rectangle
+    pub fn rect(left: f64, top: f64, right: f64, bottom: f64) -> Path {
+        let mut path = Path::new();
+        path.move_to(Point::new(left, top));
+        path.line_to(Point::new(right, top));
+        path.line_to(Point::new(right, bottom));
+        path.line_to(Point::new(left, bottom));
+        path.close();
+        path
+    }
+
+    /// Ellipse inscribed in a rectangle
+    pub fn ellipse(left: f64, top: f64, right: f64, bottom: f64) -> Path {
+        let cx = (left + right) / 2.0;
+        let cy = (top + bottom) / 2.0;
+        let mut path = Path::new();
+        path.move_to(Point::new(right, cy));
+        path.arc(
+            Point::new(cx, cy),
+            (right - left).abs() / 2.0,
+            (bottom - top).abs() / 2.0,
+            0.0,
+            2.0 * PI,
+        );
+        path.close();
+        path
+    }
+
+    /// Rectangle with elliptical corners of the given radii
+    pub fn round_rect(left: f64, top: f64, right: f64, bottom: f64, rx: f64, ry: f64) -> Path {
+        let (left, right) = (left.min(right), left.max(right));
+        let (top, bottom) = (top.min(bottom), top.max(bottom));
+        let rx = rx.abs().min((right - left) / 2.0);
+        let ry = ry.abs().min((bottom - top) / 2.0);
+        if rx <= 0.0 || ry <= 0.0 {
+            return Path::rect(left, top, right, bottom);
+        }
+
+        let kx = rx * KAPPA;
+        let ky = ry * KAPPA;
+        let mut path = Path::new();
+        path.move_to(Point::new(left + rx, top));
+        path.line_to(Point::new(right - rx, top));
+        path.cubic_to(
+            Point::new(right - rx + kx, top),
+            Point::new(right, top + ry - ky),
+            Point::new(right, top + ry),
+        );
+        path.line_to(Point::new(right, bottom - ry));
+        path.cubic_to(
+            Point::new(right, bottom - ry + ky),
+            Point::new(right - rx + kx, bottom),
+            Point::new(right - rx, bottom),
+        );
+        path.line_to(Point::new(left + rx, bottom));
+        path.cubic_to(
+            Point::new(left + rx - kx, bottom),
+            Point::new(left, bottom - ry + ky),
+            Point::new(left, bottom - ry),
+        );
+        path.line_to(Point::new(left, top + ry));
+        path.cubic_to(
+            Point::new(left, top + ry - ky),
+            Point::new(left + rx - kx, top),
+            Point::new(left + rx, top),
+        );
+        path.close();
+        path
+    }
+
+    /// Append an elliptical arc
+    ///
+    /// Angles are in radians, measured counter-clockwise on a y-down surface
+    /// (the GDI convention); a negative sweep runs clockwise. The arc is
+    /// connected to the current point with a straight line.
+    pub fn arc(&mut self, center: Point, rx: f64, ry: f64, start: f64, sweep: f64) {
+        let point_at =
+            |angle: f64| Point::new(center.x + rx * angle.cos(), center.y - ry * angle.sin());
+        let tangent_at = |angle: f64| Point::new(-rx * angle.sin(), -ry * angle.cos());
+
+        let start_point = point_at(start);
+        self.line_to(start_point);
+
+        let pieces = (sweep.abs() / FRAC_PI_2).ceil().max(1.0) as usize;
+        let step = sweep / pieces as f64;
+        let k = 4.0 / 3.0 * (step / 4.0).tan();
+        let mut angle = start;
+        for _ in 0..pieces {
+            let next = angle + step;
+            let p0 = point_at(angle);
+            let p1 = point_at(next);
+            let t0 = tangent_at(angle);
+            let t1 = tangent_at(next);
+            self.cubic_to(
+                Point::new(p0.x + k * t0.x, p0.y + k * t0.y),
+                Point::new(p1.x - k * t1.x, p1.y - k * t1.y),
+                p1,
+            );
+            angle = next;
+        }
+    }
+}
+
+/// Flatten a cubic Bézier, appending points after `p0`
+fn flatten_cubic(p0: Point, c1: Point, c2: Point, p3: Point, tolerance: f64, out: &mut Vec<Point>) {
+    // Bound on the deviation from the chord using the second differences
+    let dd1 = (p0.x - 2.0 * c1.x + c2.x).hypot(p0.y - 2.0 * c1.y + c2.y);
+    let dd2 = (c1.x - 2.0 * c2.x + p3.x).hypot(c1.y - 2.0 * c2.y + p3.y);
+    let deviation = 0.75 * dd1.max(dd2);
+    let steps = ((deviation / tolerance.max(1e-3)).sqrt().ceil() as usize).clamp(1, 1000);
+
+    for i in 1..=steps {
+        let t = i as f64 / steps as f64;
+        let a = p0.lerp(c1, t);
+        let b = c1.lerp(c2, t);
+        let c = c2.lerp(p3, t);
+        let d = a.lerp(b, t);
+        let e = b.lerp(c, t);
+        out.push(d.lerp(e, t));
+    }
+}
+
+/// Normalize an angle into `[0, 2π)`
+pub fn normalize_angle(angle: f64) -> f64 {
+    let a = angle % (2.0 * PI);
+    if a < 0.0 { a + 2.0 * PI } else { a }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_transform_composition() {
+        let t = Transform::scale(2.0, 3.0).then(&Transform::translate(10.0, 20.0));
+        assert_eq!(t.apply(Point::new(1.0, 1.0)), Point::new(12.0, 23.0));
+
+        let inverse = t.invert().unwrap();
+        let p = inverse.apply(Point::new(12.0, 23.0));
+        assert!((p.x - 1.0).abs() < 1e-9 && (p.y - 1.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_flatten_ellipse() {
+        let path = Path::ellipse(0.0, 0.0, 100.0, 50.0);
+        let polylines = path.flatten(0.1);
+        assert_eq!(polylines.len(), 1);
+        assert!(polylines[0].closed);
+        for p in &polylines[0].points {
+            let nx = (p.x - 50.0) / 50.0;
+            let ny = (p.y - 25.0) / 25.0;
+            assert!((nx * nx + ny * ny - 1.0).abs() < 0.01);
+        }
+    }
+}
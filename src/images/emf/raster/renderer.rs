@@ -0,0 +1,2089 @@
+// EMF playback onto a raster canvas
+//
+// Records are executed in order against a device context, like GDI playing
+// back a metafile. Geometry is converted from logical units through the world
+// transform and the window/viewport mapping into reference device units, and
+// from there into output pixels.
+
+use super::bitmap::decode_dib;
+use super::canvas::{Canvas, Paint, parse_hex_color};
+use super::font::{self, TextRun};
+use super::path::{Path, Point, Polyline, Transform, normalize_angle};
+use super::rasterizer::{FillRule, Mask, MaskOp, rasterize};
+use super::stroke::{StrokeStyle, stroke};
+use crate::images::emf::device_context::{
+    BackgroundMode, DeviceContext, DeviceContextStack, PolyFillMode, TextAlign, WorldTransform,
+};
+use crate::images::emf::emfplus::{EmfPlusPlayer, RectF};
+use crate::images::emf::gdi_objects::{
+    Brush, BrushStyle, Font, GdiObject, LineCap, LineJoin, ObjectTable, Pen, PenStyle,
+};
+use crate::images::emf::parser::{EmfParser, EmfRecordRef};
+use crate::images::emf::records::*;
+use image::{Rgba, RgbaImage};
+use std::f64::consts::PI;
+use std::sync::Arc;
+use zerocopy::FromBytes;
+
+/// Maximum deviation when flattening curves, in output pixels
+pub(super) const TOLERANCE: f64 = 0.2;
+
+/// Software renderer for EMF metafiles
+///
+/// Executes drawing, path, clipping, bitmap and text records with
+/// anti-aliasing and returns the result as an RGBA image.
+pub struct EmfRenderer<'a> {
+    parser: &'a EmfParser,
+    width: u32,
+    height: u32,
+}
+
+impl<'a> EmfRenderer<'a> {
+    /// Create a renderer producing an image of the given size
+    pub fn new(parser: &'a EmfParser, width: u32, height: u32) -> Self {
+        Self {
+            parser,
+            width: width.max(1),
+            height: height.max(1),
+        }
+    }
+
+    /// Play back all records over the given background
+    pub fn render(&self, background: Rgba<u8>) -> RgbaImage {
+        let mut playback = Playback::new(self.parser, self.width, self.height, background);
+        let mut plus = EmfPlusPlayer::new(playback.reference_bounds());
+
+        let data = self.parser.data();
+        let mut offset = data
+            .get(4..8)
+            .map_or(0, |b| u32::from_le_bytes([b[0], b[1], b[2], b[3]]) as usize);
+        while let Ok((record, consumed)) = EmfRecordRef::parse_ref(data, offset) {
+            if record.record_type == EmrType::Eof as u32 {
+                break;
+            }
+            let bytes = &data[offset..offset + consumed];
+            offset += consumed;
+
+            // EMF+ records travel in comments; once they are present the GDI
+            // records are only a fallback for older readers
+            if record.record_type == EmrType::Comment as u32
+                && plus.play_comment(&bytes[8..], &mut playback)
+            {
+                continue;
+            }
+            if plus.gdi_enabled() {
+                playback.execute(record.record_type, bytes);
+            }
+        }
+
+        playback.canvas.into_image()
+    }
+}
+
+/// Playback state
+pub(super) struct Playback {
+    pub(super) canvas: Canvas,
+    dc: DeviceContext,
+    saved: DeviceContextStack,
+    objects: ObjectTable,
+    /// Path being built or completed, in output pixels
+    path: Option<Path>,
+    /// Whether a path bracket is open
+    path_open: bool,
+    /// Whether the current path came from WidenPath (always filled non-zero)
+    path_widened: bool,
+    /// Reference device units to output pixels
+    pub(super) device_to_output: Transform,
+    /// Output pixels per reference device pixel
+    pub(super) scale: f64,
+    /// Reference device pixels per millimeter
+    px_per_mm: (f64, f64),
+    /// Clipping region of EMF+ records, kept apart from the GDI clip
+    pub(super) plus_clip: Option<Arc<Mask>>,
+}
+
+impl Playback {
+    fn new(parser: &EmfParser, width: u32, height: u32, background: Rgba<u8>) -> Self {
+        let header = &parser.header;
+        let px_per_mm = (
+            ratio(header.device_width, header.device_width_mm),
+            ratio(header.device_height, header.device_height_mm),
+        );
+
+        // Reference area from the bounds, or the frame if the bounds are empty
+        let (mut left, mut top, mut right, mut bottom) = (
+            header.bounds.0 as f64,
+            header.bounds.1 as f64,
+            header.bounds.2 as f64,
+            header.bounds.3 as f64,
+        );
+        if right <= left || bottom <= top {
+            left = header.frame.0 as f64 / 100.0 * px_per_mm.0;
+            top = header.frame.1 as f64 / 100.0 * px_per_mm.1;
+            right = header.frame.2 as f64 / 100.0 * px_per_mm.0;
+            bottom = header.frame.3 as f64 / 100.0 * px_per_mm.1;
+        }
+        let sx = width as f64 / (right - left).max(1.0);
+        let sy = height as f64 / (bottom - top).max(1.0);
+
+        Self {
+            canvas: Canvas::new(width, height, background),
+            dc: DeviceContext::default(),
+            saved: DeviceContextStack::new(),
+            objects: ObjectTable::new(),
+            path: None,
+            path_open: false,
+            path_widened: false,
+            device_to_output: Transform::translate(-left, -top).then(&Transform::scale(sx, sy)),
+            scale: (sx * sy).sqrt(),
+            px_per_mm,
+            plus_clip: None,
+        }
+    }
+
+    /// The area of reference device units covered by the canvas
+    fn reference_bounds(&self) -> RectF {
+        let Some(to_device) = self.device_to_output.invert() else {
+            return RectF::default();
+        };
+        let top_left = to_device.apply(Point::new(0.0, 0.0));
+        let bottom_right = to_device.apply(Point::new(
+            self.canvas.width() as f64,
+            self.canvas.height() as f64,
+        ));
+        RectF {
+            x: top_left.x,
+            y: top_left.y,
+            width: bottom_right.x - top_left.x,
+            height: bottom_right.y - top_left.y,
+        }
+    }
+
+    fn execute(&mut self, record_type: u32, record: &[u8]) {
+        let Some(kind) = EmrType::from_u32(record_type) else {
+            return;
+        };
+
+        match kind {
+            // Device context state
+            EmrType::SaveDc => self.saved.push(self.dc.clone()),
+            EmrType::RestoreDc => {
+                if let Some(r) = read::<EmrRestoreDc>(record)
+                    && let Some(dc) = self.saved.pop_to(r.saved_dc as isize)
+                {
+                    self.dc = dc;
+                }
+            },
+            EmrType::SetWorldTransform => {
+                if let Some(r) = read::<EmrSetWorldTransform>(record) {
+                    self.dc.world_transform = to_world(&Transform::from_xform(&r.xform));
+                }
+            },
+            EmrType::ModifyWorldTransform => {
+                if let Some(r) = read::<EmrModifyWorldTransform>(record) {
+                    let xform = Transform::from_xform(&r.xform);
+                    let current = from_world(&self.dc.world_transform);
+                    let combined = match r.mode {
+                        1 => Transform::IDENTITY,
+                        2 => xform.then(&current),
+                        3 => current.then(&xform),
+                        4 => xform,
+                        _ => current,
+                    };
+                    self.dc.world_transform = to_world(&combined);
+                }
+            },
+            EmrType::SetMapMode => {
+                if let Some(r) = read::<EmrSetMapMode>(record) {
+                    self.dc.map_mode = r.mode;
+                }
+            },
+            EmrType::SetWindowExtEx => {
+                if let Some(r) = read::<EmrSetExtEx>(record) {
+                    self.dc.window_ext_x = r.extent.cx;
+                    self.dc.window_ext_y = r.extent.cy;
+                }
+            },
+            EmrType::SetWindowOrgEx => {
+                if let Some(r) = read::<EmrSetOrgEx>(record) {
+                    self.dc.window_org_x = r.origin.x;
+                    self.dc.window_org_y = r.origin.y;
+                }
+            },
+            EmrType::SetViewportExtEx => {
+                if let Some(r) = read::<EmrSetExtEx>(record) {
+                    self.dc.viewport_ext_x = r.extent.cx;
+                    self.dc.viewport_ext_y = r.extent.cy;
+                }
+            },
+            EmrType::SetViewportOrgEx => {
+                if let Some(r) = read::<EmrSetOrgEx>(record) {
+                    self.dc.viewport_org_x = r.origin.x;
+                    self.dc.viewport_org_y = r.origin.y;
+                }
+            },
+            EmrType::ScaleViewportExtEx | EmrType::ScaleWindowExtEx => {
+                if let Some(r) = read::<EmrScaleExtEx>(record) {
+                    let scale = |ext: i32, num: i32, denom: i32| {
+                        if denom == 0 {
+                            ext
+                        } else {
+                            (ext as i64 * num as i64 / denom as i64) as i32
+                        }
+                    };
+                    if kind == EmrType::ScaleViewportExtEx {
+                        self.dc.viewport_ext_x = scale(self.dc.viewport_ext_x, r.x_num, r.x_denom);
+                        self.dc.viewport_ext_y = scale(self.dc.viewport_ext_y, r.y_num, r.y_denom);
+                    } else {
+                        self.dc.window_ext_x = scale(self.dc.window_ext_x, r.x_num, r.x_denom);
+                        self.dc.window_ext_y = scale(self.dc.window_ext_y, r.y_num, r.y_denom);
+                    }
+                }
+            },
+            EmrType::SetBkMode => {
+                if let Some(mode) =
+                    read::<EmrSetBkMode>(record).and_then(|r| BackgroundMode::from_u32(r.mode))
+                {
+                    self.dc.background_mode = mode;
+                }
+            },
+            EmrType::SetBkColor => {
+                if let Some(r) = read::<EmrSetColorRef>(record) {
+                    self.dc.set_background_color(r.color.value);
+                }
+            },
+            EmrType::SetTextColor => {
+                if let Some(r) = read::<EmrSetColorRef>(record) {
+                    self.dc.set_text_color(r.color.value);
+                }
+            },
+            EmrType::SetPolyFillMode => {
+                if let Some(mode) =
+                    read::<EmrSetPolyFillMode>(record).and_then(|r| PolyFillMode::from_u32(r.mode))
+                {
+                    self.dc.poly_fill_mode = mode;
+                }
+            },
+            EmrType::SetTextAlign => {
+                if let Some(r) = read::<EmrSetTextAlign>(record) {
+                    self.dc.text_align = TextAlign(r.mode as u16);
+                }
+            },
+            EmrType::SetMiterLimit => {
+                if let Some(r) = read::<EmrSetMiterLimit>(record) {
+                    // Some writers store the limit as a float
+                    let limit = if r.limit > 0x0010_0000 {
+                        f32::from_bits(r.limit) as f64
+                    } else {
+                        r.limit as f64
+                    };
+                    if limit.is_finite() && limit >= 1.0 {
+                        self.dc.miter_limit = limit;
+                    }
+                }
+            },
+            EmrType::SetArcDirection => {
+                if let Some(r) = read::<EmrSetArcDirection>(record) {
+                    self.dc.clockwise_arcs = r.direction == 2;
+                }
+            },
+
+            // Objects
+            EmrType::CreatePen => {
+                if let Some(r) = read::<EmrCreatePen>(record) {
+                    let pen = Pen::from_emr_data(r.pen_style, r.width as i32, r.color.value);
+                    self.objects.insert(r.object_index, GdiObject::Pen(pen));
+                }
+            },
+            EmrType::ExtCreatePen => self.ext_create_pen(record),
+            EmrType::CreateBrushIndirect => {
+                if let Some(r) = read::<EmrCreateBrushIndirect>(record) {
+                    let brush = Brush::from_emr_data(r.brush_style, r.color.value, r.brush_hatch);
+                    self.objects.insert(r.object_index, GdiObject::Brush(brush));
+                }
+            },
+            EmrType::CreateDIBPatternBrushPt => {
+                if let Some(r) = read::<EmrCreateDIBPatternBrushPtHeader>(record)
+                    && let Some(image) =
+                        dib(record, r.off_bmi, r.cb_bmi, r.off_bits, r.cb_bits, false)
+                {
+                    let brush = Brush::from_pattern(BrushStyle::DibPatternPt, image);
+                    self.objects.insert(r.object_index, GdiObject::Brush(brush));
+                }
+            },
+            EmrType::CreateMonoBrush => {
+                if let Some(r) = read::<EmrCreateMonoBrushHeader>(record)
+                    && let Some(image) =
+                        dib(record, r.off_bmi, r.cb_bmi, r.off_bits, r.cb_bits, false)
+                {
+                    let brush = Brush::from_pattern(BrushStyle::MonoPattern, image);
+                    self.objects.insert(r.object_index, GdiObject::Brush(brush));
+                }
+            },
+            EmrType::ExtCreateFontIndirectW => {
+                if let Some(header) = read::<EmrExtCreateFontIndirectWHeader>(record)
+                    && let Some(logfont) = record.get(12..).and_then(read::<LogFontW>)
+                {
+                    let face = record.get(40..record.len().min(104)).unwrap_or_default();
+                    let font = Font::from_logfont(&logfont, face);
+                    self.objects
+                        .insert(header.object_index, GdiObject::Font(font));
+                }
+            },
+            EmrType::SelectObject => {
+                if let Some(r) = read::<EmrSelectObject>(record) {
+                    match self.object(r.object_index) {
+                        Some(GdiObject::Pen(pen)) => self.dc.pen = pen,
+                        Some(GdiObject::Brush(brush)) => self.dc.brush = brush,
+                        Some(GdiObject::Font(font)) => self.dc.font = font,
+                        _ => {},
+                    }
+                }
+            },
+            EmrType::DeleteObject => {
+                if let Some(r) = read::<EmrDeleteObject>(record) {
+                    self.objects.delete(r.object_index);
+                }
+            },
+
+            // Lines and shapes
+            EmrType::MoveToEx => {
+                if let Some(r) = read::<EmrLineTo>(record) {
+                    let p = Point::new(r.point.x as f64, r.point.y as f64);
+                    self.set_current(p);
+                    if self.path_open {
+                        let out = self.logical_to_output().apply(p);
+                        if let Some(path) = &mut self.path {
+                            path.move_to(out);
+                        }
+                    }
+                }
+            },
+            EmrType::LineTo => {
+                if let Some(r) = read::<EmrLineTo>(record) {
+                    let p = Point::new(r.point.x as f64, r.point.y as f64);
+                    let mut figure = self.figure_start();
+                    figure.line_to(p);
+                    self.continue_figure(&figure);
+                    self.set_current(p);
+                }
+            },
+            EmrType::Rectangle => {
+                if let Some(r) = read::<EmrRectangle>(record) {
+                    let (l, t, rr, b) = rect(&r.rect);
+                    self.emit(&Path::rect(l, t, rr, b), true, true);
+                }
+            },
+            EmrType::RoundRect => {
+                if let Some(r) = read::<EmrRoundRect>(record) {
+                    let (l, t, rr, b) = rect(&r.rect);
+                    let path = Path::round_rect(
+                        l,
+                        t,
+                        rr,
+                        b,
+                        r.corner.cx as f64 / 2.0,
+                        r.corner.cy as f64 / 2.0,
+                    );
+                    self.emit(&path, true, true);
+                }
+            },
+            EmrType::Ellipse => {
+                if let Some(r) = read::<EmrRectangle>(record) {
+                    let (l, t, rr, b) = rect(&r.rect);
+                    self.emit(&Path::ellipse(l, t, rr, b), true, true);
+                }
+            },
+            EmrType::Arc | EmrType::ArcTo | EmrType::Chord | EmrType::Pie => {
+                if let Some(r) = read::<EmrArc>(record) {
+                    self.arc(kind, &r);
+                }
+            },
+            EmrType::AngleArc => {
+                if let Some(r) = read::<EmrAngleArc>(record) {
+                    let center = Point::new(r.center.x as f64, r.center.y as f64);
+                    let radius = r.radius as f64;
+                    let start = (r.start_angle as f64).to_radians();
+                    let sweep = (r.sweep_angle as f64).to_radians();
+                    let mut figure = self.figure_start();
+                    figure.arc(center, radius, radius, start, sweep);
+                    let end = figure.current_point();
+                    self.continue_figure(&figure);
+                    if let Some(end) = end {
+                        self.set_current(end);
+                    }
+                }
+            },
+            EmrType::Polygon
+            | EmrType::Polyline
+            | EmrType::PolyBezier
+            | EmrType::PolyLineTo
+            | EmrType::PolyBezierTo
+            | EmrType::Polygon16
+            | EmrType::Polyline16
+            | EmrType::PolyBezier16
+            | EmrType::PolyLineTo16
+            | EmrType::PolyBezierTo16 => self.poly(kind, record),
+            EmrType::PolyPolygon
+            | EmrType::PolyPolyline
+            | EmrType::PolyPolygon16
+            | EmrType::PolyPolyline16 => self.poly_poly(kind, record),
+            EmrType::PolyDraw | EmrType::PolyDraw16 => self.poly_draw(kind, record),
+            EmrType::SetPixelV => {
+                if let Some(r) = read::<EmrSetPixelV>(record) {
+                    let p = self
+                        .logical_to_output()
+                        .apply(Point::new(r.point.x as f64, r.point.y as f64));
+                    let size = self.scale.max(1.0);
+                    let path = Path::rect(p.x, p.y, p.x + size, p.y + size);
+                    self.fill_solid(&path, FillRule::NonZero, colorref(r.color));
+                }
+            },
+            EmrType::GradientFill => self.gradient_fill(record),
+
+            // Regions
+            EmrType::FillRgn => {
+                if let (Some(size), Some(handle)) = (u32_at(record, 24), u32_at(record, 28))
+                    && let Some(region) = region_path(record.get(32..).unwrap_or_default(), size)
+                    && let Some(GdiObject::Brush(brush)) = self.object(handle)
+                {
+                    let out = region.transform(&self.logical_to_output());
+                    let saved = std::mem::replace(&mut self.dc.brush, brush);
+                    self.fill_output(&out, FillRule::NonZero);
+                    self.dc.brush = saved;
+                }
+            },
+            EmrType::PaintRgn | EmrType::InvertRgn => {
+                if let Some(size) = u32_at(record, 24)
+                    && let Some(region) = region_path(record.get(28..).unwrap_or_default(), size)
+                {
+                    let out = region.transform(&self.logical_to_output());
+                    if kind == EmrType::PaintRgn {
+                        self.fill_output(&out, FillRule::NonZero);
+                    } else if let Some(mask) = self.rasterize(&out, FillRule::NonZero) {
+                        self.canvas.pattern_blt(
+                            &mask,
+                            rop::DSTINVERT,
+                            Rgba([0, 0, 0, 255]),
+                            self.dc.clip_mask.as_deref(),
+                        );
+                    }
+                }
+            },
+
+            // Paths
+            EmrType::BeginPath => {
+                self.path = Some(Path::new());
+                self.path_open = true;
+                self.path_widened = false;
+                let start = self.logical_to_output().apply(self.current());
+                if let Some(path) = &mut self.path {
+                    path.move_to(start);
+                }
+            },
+            EmrType::EndPath => self.path_open = false,
+            EmrType::AbortPath => {
+                self.path = None;
+                self.path_open = false;
+            },
+            EmrType::CloseFigure => {
+                if let Some(path) = &mut self.path {
+                    path.close();
+                }
+            },
+            EmrType::FillPath | EmrType::StrokePath | EmrType::StrokeAndFillPath => {
+                if let Some(path) = self.path.take() {
+                    self.path_open = false;
+                    if kind != EmrType::StrokePath {
+                        let rule = if self.path_widened {
+                            FillRule::NonZero
+                        } else {
+                            self.fill_rule()
+                        };
+                        self.fill_output(&path, rule);
+                    }
+                    if kind != EmrType::FillPath {
+                        self.stroke_output(&path);
+                    }
+                }
+            },
+            EmrType::WidenPath => {
+                if let Some(path) = self.path.take() {
+                    let mut widened = Path::new();
+                    for polygon in self.stroke_outline(&path) {
+                        append_polyline(&mut widened, &polygon);
+                    }
+                    self.path = Some(widened);
+                    self.path_widened = true;
+                }
+            },
+            EmrType::SelectClipPath => {
+                if let Some(r) = read::<EmrSelectClipPath>(record)
+                    && let Some(path) = self.path.take()
+                    && let Some(op) = MaskOp::from_region_mode(r.mode)
+                {
+                    let mask = self.rasterize(&path, self.fill_rule());
+                    self.combine_clip(mask, op);
+                }
+            },
+
+            // Clipping
+            EmrType::IntersectClipRect | EmrType::ExcludeClipRect => {
+                if let Some(r) = read::<EmrRectangle>(record) {
+                    let (l, t, rr, b) = rect(&r.rect);
+                    let out = Path::rect(l, t, rr, b).transform(&self.logical_to_output());
+                    let mask = self.rasterize(&out, FillRule::NonZero);
+                    let op = if kind == EmrType::IntersectClipRect {
+                        MaskOp::And
+                    } else {
+                        MaskOp::Diff
+                    };
+                    self.combine_clip(mask, op);
+                }
+            },
+            EmrType::ExtSelectClipRgn => {
+                if let (Some(size), Some(mode)) = (u32_at(record, 8), u32_at(record, 12)) {
+                    let Some(op) = MaskOp::from_region_mode(mode) else {
+                        return;
+                    };
+                    if size == 0 {
+                        // An empty region with RGN_COPY restores the default clipping
+                        if op == MaskOp::Copy {
+                            self.dc.clip_mask = None;
+                        }
+                        return;
+                    }
+                    if let Some(region) = region_path(record.get(16..).unwrap_or_default(), size) {
+                        let out = region.transform(&self.device_to_output);
+                        let mask = self.rasterize(&out, FillRule::NonZero);
+                        self.combine_clip(mask, op);
+                    }
+                }
+            },
+            EmrType::OffsetClipRgn => {
+                if let Some(r) = read::<EmrOffsetClipRgn>(record)
+                    && let Some(clip) = &self.dc.clip_mask
+                {
+                    let delta = self
+                        .logical_to_output()
+                        .apply_vector(Point::new(r.offset.x as f64, r.offset.y as f64));
+                    let moved = clip.offset(delta.x.round() as i32, delta.y.round() as i32);
+                    self.dc.clip_mask = Some(Arc::new(moved));
+                }
+            },
+
+            // Bitmaps
+            EmrType::BitBlt => self.bit_blt(record),
+            EmrType::StretchBlt => self.stretch_blt(record),
+            EmrType::StretchDIBits => self.stretch_dibits(record),
+            EmrType::SetDIBitsToDevice => self.set_dibits_to_device(record),
+            EmrType::AlphaBlend => self.alpha_blend(record),
+            EmrType::TransparentBlt => self.transparent_blt(record),
+
+            // Text
+            EmrType::ExtTextOutA | EmrType::ExtTextOutW => {
+                if let Some(header) = record.get(8..).and_then(read::<EmrExtTextOutHeader>) {
+                    self.text_out(record, &header.text, kind == EmrType::ExtTextOutW);
+                }
+            },
+            EmrType::PolyTextOutA | EmrType::PolyTextOutW => {
+                if let Some(header) = record.get(8..).and_then(read::<EmrPolyTextOutHeader>) {
+                    let base = 8 + std::mem::size_of::<EmrPolyTextOutHeader>();
+                    let entry = std::mem::size_of::<EmrTextInfo>();
+                    for i in 0..header.num_strings as usize {
+                        match record.get(base + i * entry..).and_then(read::<EmrTextInfo>) {
+                            Some(text) => {
+                                self.text_out(record, &text, kind == EmrType::PolyTextOutW)
+                            },
+                            None => break,
+                        }
+                    }
+                }
+            },
+            EmrType::SmallTextOut => self.small_text_out(record),
+
+            _ => {},
+        }
+    }
+
+    // Coordinate spaces
+
+    /// Page space (after the world transform) to reference device units
+    fn page_to_device(&self) -> Transform {
+        let dc = &self.dc;
+        let window_org = Transform::translate(-dc.window_org_x as f64, -dc.window_org_y as f64);
+        let viewport_org = Transform::translate(dc.viewport_org_x as f64, dc.viewport_org_y as f64);
+
+        let metric_mm = match dc.map_mode {
+            2 => Some(0.1),           // MM_LOMETRIC
+            3 => Some(0.01),          // MM_HIMETRIC
+            4 => Some(0.254),         // MM_LOENGLISH
+            5 => Some(0.0254),        // MM_HIENGLISH
+            6 => Some(25.4 / 1440.0), // MM_TWIPS
+            _ => None,
+        };
+
+        let (sx, sy) = if let Some(mm) = metric_mm {
+            (mm * self.px_per_mm.0, -mm * self.px_per_mm.1)
+        } else if dc.map_mode == 7 || dc.map_mode == 8 {
+            let sx = ratio(dc.viewport_ext_x, dc.window_ext_x);
+            let sy = ratio(dc.viewport_ext_y, dc.window_ext_y);
+            if dc.map_mode == 7 {
+                // MM_ISOTROPIC keeps the aspect ratio
+                let m = sx.abs().min(sy.abs());
+                (m.copysign(sx), m.copysign(sy))
+            } else {
+                (sx, sy)
+            }
+        } else {
+            (1.0, 1.0)
+        };
+
+        window_org
+            .then(&Transform::scale(sx, sy))
+            .then(&viewport_org)
+    }
+
+    /// Logical units to output pixels
+    fn logical_to_output(&self) -> Transform {
+        from_world(&self.dc.world_transform)
+            .then(&self.page_to_device())
+            .then(&self.device_to_output)
+    }
+
+    fn current(&self) -> Point {
+        Point::new(self.dc.current_x, self.dc.current_y)
+    }
+
+    fn set_current(&mut self, p: Point) {
+        self.dc.current_x = p.x;
+        self.dc.current_y = p.y;
+    }
+
+    fn fill_rule(&self) -> FillRule {
+        match self.dc.poly_fill_mode {
+            PolyFillMode::Alternate => FillRule::EvenOdd,
+            PolyFillMode::Winding => FillRule::NonZero,
+        }
+    }
+
+    fn object(&self, handle: u32) -> Option<GdiObject> {
+        if handle & 0x8000_0000 != 0 {
+            return stock_object(handle);
+        }
+        self.objects.get(handle).cloned()
+    }
+
+    // Drawing
+
+    /// Draw a closed or open shape given in logical units
+    ///
+    /// Inside a path bracket the shape is added to the path instead.
+    fn emit(&mut self, logical: &Path, fill: bool, stroke: bool) {
+        let out = logical.transform(&self.logical_to_output());
+        if self.path_open {
+            if let Some(path) = &mut self.path {
+                path.append(&out);
+            }
+            return;
+        }
+        if fill {
+            self.fill_output(&out, self.fill_rule());
+        }
+        if stroke {
+            self.stroke_output(&out);
+        }
+    }
+
+    /// A path in logical units starting at the current position
+    fn figure_start(&self) -> Path {
+        let mut path = Path::new();
+        path.move_to(self.current());
+        path
+    }
+
+    /// Stroke a figure that continues from the current position
+    fn continue_figure(&mut self, logical: &Path) {
+        let out = logical.transform(&self.logical_to_output());
+        if self.path_open {
+            if let Some(path) = &mut self.path {
+                path.connect(&out);
+            }
+        } else {
+            self.stroke_output(&out);
+        }
+    }
+
+    pub(super) fn rasterize(&self, path: &Path, rule: FillRule) -> Option<Mask> {
+        if path.is_empty() {
+            return None;
+        }
+        rasterize(
+            &path.flatten(TOLERANCE),
+            rule,
+            self.canvas.width(),
+            self.canvas.height(),
+        )
+    }
+
+    /// Fill a path in output pixels with the selected brush
+    fn fill_output(&mut self, path: &Path, rule: FillRule) {
+        if self.dc.brush.style == BrushStyle::Null {
+            return;
+        }
+        if let Some(mask) = self.rasterize(path, rule) {
+            self.fill_mask(&mask);
+        }
+    }
+
+    /// Fill a path in output pixels with a solid color
+    fn fill_solid(&mut self, path: &Path, rule: FillRule, color: Rgba<u8>) {
+        if let Some(mask) = self.rasterize(path, rule) {
+            self.canvas
+                .fill_mask(&mask, &Paint::Solid(color), self.dc.clip_mask.as_deref());
+        }
+    }
+
+    /// Paint a coverage mask with the selected brush
+    fn fill_mask(&mut self, mask: &Mask) {
+        let brush = &self.dc.brush;
+        let color = parse_hex_color(&brush.color);
+        let clip = self.dc.clip_mask.as_deref();
+        let background = parse_hex_color(&self.dc.background_color);
+
+        match (brush.style, &brush.pattern) {
+            (BrushStyle::Null, _) => {},
+            (BrushStyle::Hatched, _) => {
+                let paint = Paint::Hatch {
+                    style: brush.hatch,
+                    color,
+                    background: (self.dc.background_mode == BackgroundMode::Opaque)
+                        .then_some(background),
+                    spacing: 8.0 * self.scale,
+                };
+                self.canvas.fill_mask(mask, &paint, clip);
+            },
+            (BrushStyle::MonoPattern, Some(pattern)) => {
+                // Monochrome patterns take the text and background colors
+                let foreground = parse_hex_color(&self.dc.text_color);
+                let mut image = (**pattern).clone();
+                for pixel in image.pixels_mut() {
+                    let luma = (pixel[0] as u32 * 3 + pixel[1] as u32 * 6 + pixel[2] as u32) / 10;
+                    *pixel = if luma < 128 { foreground } else { background };
+                }
+                let paint = Paint::Pattern {
+                    image: &image,
+                    scale: self.scale,
+                };
+                self.canvas.fill_mask(mask, &paint, clip);
+            },
+            (_, Some(pattern)) => {
+                let paint = Paint::Pattern {
+                    image: pattern,
+                    scale: self.scale,
+                };
+                self.canvas.fill_mask(mask, &paint, clip);
+            },
+            _ => self.canvas.fill_mask(mask, &Paint::Solid(color), clip),
+        }
+    }
+
+    /// Outline of a path in output pixels stroked with the selected pen
+    fn stroke_outline(&self, path: &Path) -> Vec<Polyline> {
+        let pen = &self.dc.pen;
+        if pen.style == PenStyle::Null {
+            return Vec::new();
+        }
+
+        let to_output = self.logical_to_output();
+        let device_width = pen.width * to_output.mean_scale();
+
+        if pen.geometric
+            && device_width >= 1.0
+            && let Some(to_logical) = to_output.invert()
+        {
+            // Stroke in logical units so the pen follows the mapping
+            let logical = path.transform(&to_logical);
+            let style = StrokeStyle {
+                width: pen.width,
+                cap: pen.end_cap,
+                join: pen.line_join,
+                miter_limit: self.dc.miter_limit,
+                dashes: dash_pattern(pen, pen.width),
+            };
+            let tolerance = TOLERANCE / to_output.mean_scale().max(1e-9);
+            let mut outline = stroke(&logical.flatten(tolerance), &style);
+            for polyline in &mut outline {
+                for p in &mut polyline.points {
+                    *p = to_output.apply(*p);
+                }
+            }
+            return outline;
+        }
+
+        // Cosmetic pens are one pixel wide regardless of the mapping
+        let style = StrokeStyle {
+            width: 1.0,
+            cap: LineCap::Flat,
+            join: LineJoin::Miter,
+            miter_limit: self.dc.miter_limit,
+            dashes: dash_pattern(pen, self.scale),
+        };
+        stroke(&path.flatten(TOLERANCE), &style)
+    }
+
+    /// Stroke a path in output pixels with the selected pen
+    fn stroke_output(&mut self, path: &Path) {
+        let outline = self.stroke_outline(path);
+        if outline.is_empty() {
+            return;
+        }
+        let color = parse_hex_color(&self.dc.pen.color);
+        if let Some(mask) = rasterize(
+            &outline,
+            FillRule::NonZero,
+            self.canvas.width(),
+            self.canvas.height(),
+        ) {
+            self.canvas
+                .fill_mask(&mask, &Paint::Solid(color), self.dc.clip_mask.as_deref());
+        }
+    }
+
+    /// Combine the clipping region with a mask (None = empty region)
+    fn combine_clip(&mut self, mask: Option<Mask>, op: MaskOp) {
+        let (width, height) = (self.canvas.width(), self.canvas.height());
+        let mask = mask.unwrap_or_else(|| Mask::filled(0, 0, 0, 0, 0));
+        let combined = match &self.dc.clip_mask {
+            Some(current) => current.combine(&mask, op, width, height),
+            None => Mask::filled(0, 0, width, height, 255).combine(&mask, op, width, height),
+        };
+        self.dc.clip_mask = Some(Arc::new(combined));
+    }
+
+    fn arc(&mut self, kind: EmrType, r: &EmrArc) {
+        let (l, t, rr, b) = rect(&r.rect);
+        let center = Point::new((l + rr) / 2.0, (t + b) / 2.0);
+        let rx = (rr - l).abs() / 2.0;
+        let ry = (b - t).abs() / 2.0;
+        if rx <= 0.0 || ry <= 0.0 {
+            return;
+        }
+
+        // Angles of the radials through the start and end points
+        let angle =
+            |p: &PointL| (-(p.y as f64 - center.y) / ry).atan2((p.x as f64 - center.x) / rx);
+        let start = angle(&r.start);
+        let end = angle(&r.end);
+        let sweep = if self.dc.clockwise_arcs {
+            let s = normalize_angle(start - end);
+            -(if s == 0.0 { 2.0 * PI } else { s })
+        } else {
+            let s = normalize_angle(end - start);
+            if s == 0.0 { 2.0 * PI } else { s }
+        };
+
+        match kind {
+            EmrType::ArcTo => {
+                let mut figure = self.figure_start();
+                figure.arc(center, rx, ry, start, sweep);
+                let end_point = figure.current_point();
+                self.continue_figure(&figure);
+                if let Some(p) = end_point {
+                    self.set_current(p);
+                }
+            },
+            EmrType::Arc => {
+                let mut path = Path::new();
+                path.arc(center, rx, ry, start, sweep);
+                self.emit(&path, false, true);
+            },
+            EmrType::Chord => {
+                let mut path = Path::new();
+                path.arc(center, rx, ry, start, sweep);
+                path.close();
+                self.emit(&path, true, true);
+            },
+            _ => {
+                let mut path = Path::new();
+                path.move_to(center);
+                path.arc(center, rx, ry, start, sweep);
+                path.close();
+                self.emit(&path, true, true);
+            },
+        }
+    }
+
+    fn poly(&mut self, kind: EmrType, record: &[u8]) {
+        let small = matches!(
+            kind,
+            EmrType::Polygon16
+                | EmrType::Polyline16
+                | EmrType::PolyBezier16
+                | EmrType::PolyLineTo16
+                | EmrType::PolyBezierTo16
+        );
+        let Some(count) = u32_at(record, 24) else {
+            return;
+        };
+        let Some(points) = read_points(record, 28, count as usize, small) else {
+            return;
+        };
+        let Some(&last) = points.last() else {
+            return;
+        };
+
+        match kind {
+            EmrType::Polygon | EmrType::Polygon16 => {
+                let mut path = polyline_path(&points);
+                path.close();
+                self.emit(&path, true, true);
+            },
+            EmrType::Polyline | EmrType::Polyline16 => {
+                self.emit(&polyline_path(&points), false, true);
+            },
+            EmrType::PolyBezier | EmrType::PolyBezier16 => {
+                let mut path = Path::new();
+                path.move_to(points[0]);
+                bezier_segments(&mut path, &points[1..]);
+                self.emit(&path, false, true);
+            },
+            EmrType::PolyLineTo | EmrType::PolyLineTo16 => {
+                let mut figure = self.figure_start();
+                for &p in &points {
+                    figure.line_to(p);
+                }
+                self.continue_figure(&figure);
+                self.set_current(last);
+            },
+            _ => {
+                let mut figure = self.figure_start();
+                bezier_segments(&mut figure, &points);
+                self.continue_figure(&figure);
+                self.set_current(last);
+            },
+        }
+    }
+
+    fn poly_poly(&mut self, kind: EmrType, record: &[u8]) {
+        let small = matches!(kind, EmrType::PolyPolygon16 | EmrType::PolyPolyline16);
+        let closed = matches!(kind, EmrType::PolyPolygon | EmrType::PolyPolygon16);
+        let (Some(num_polys), Some(total)) = (u32_at(record, 24), u32_at(record, 28)) else {
+            return;
+        };
+        let counts: Vec<usize> = (0..num_polys as usize)
+            .map_while(|i| u32_at(record, 32 + i * 4).map(|c| c as usize))
+            .collect();
+        if counts.len() != num_polys as usize {
+            return;
+        }
+        let Some(points) = read_points(record, 32 + counts.len() * 4, total as usize, small) else {
+            return;
+        };
+
+        let mut path = Path::new();
+        let mut start = 0;
+        for count in counts {
+            let Some(figure) = points.get(start..start + count) else {
+                break;
+            };
+            path.append(&polyline_path(figure));
+            if closed {
+                path.close();
+            }
+            start += count;
+        }
+        self.emit(&path, closed, true);
+    }
+
+    fn poly_draw(&mut self, kind: EmrType, record: &[u8]) {
+        let small = kind == EmrType::PolyDraw16;
+        let Some(count) = u32_at(record, 24).map(|c| c as usize) else {
+            return;
+        };
+        let Some(points) = read_points(record, 28, count, small) else {
+            return;
+        };
+        let types_offset = 28 + count * if small { 4 } else { 8 };
+        let Some(types) = record.get(types_offset..types_offset + count) else {
+            return;
+        };
+
+        let mut figure = self.figure_start();
+        let mut i = 0;
+        while i < count {
+            let kind = types[i] & !point_type::CLOSEFIGURE;
+            let mut close = types[i] & point_type::CLOSEFIGURE != 0;
+            match kind {
+                point_type::MOVETO => figure.move_to(points[i]),
+                point_type::BEZIERTO if i + 2 < count => {
+                    figure.cubic_to(points[i], points[i + 1], points[i + 2]);
+                    close = types[i + 2] & point_type::CLOSEFIGURE != 0;
+                    i += 2;
+                },
+                _ => figure.line_to(points[i]),
+            }
+            if close {
+                figure.close();
+            }
+            i += 1;
+        }
+        self.continue_figure(&figure);
+        if let Some(&last) = points.last() {
+            self.set_current(last);
+        }
+    }
+
+    fn gradient_fill(&mut self, record: &[u8]) {
+        let Some(header) = read::<EmrGradientFillHeader>(record) else {
+            return;
+        };
+        let vertex_size = std::mem::size_of::<TriVertex>();
+        let base = std::mem::size_of::<EmrGradientFillHeader>();
+        let vertices: Vec<TriVertex> = (0..header.num_vertices as usize)
+            .map_while(|i| {
+                record
+                    .get(base + i * vertex_size..)
+                    .and_then(read::<TriVertex>)
+            })
+            .collect();
+        if vertices.len() != header.num_vertices as usize {
+            return;
+        }
+        let color = |v: &TriVertex| {
+            [
+                (v.red >> 8) as f64,
+                (v.green >> 8) as f64,
+                (v.blue >> 8) as f64,
+            ]
+        };
+        let mix = |c: [f64; 3]| {
+            Rgba([
+                c[0].round() as u8,
+                c[1].round() as u8,
+                c[2].round() as u8,
+                255,
+            ])
+        };
+
+        let to_output = self.logical_to_output();
+        let Some(to_logical) = to_output.invert() else {
+            return;
+        };
+        let mut offset = base + vertices.len() * vertex_size;
+
+        for _ in 0..header.num_triangles {
+            if header.mode == 2 {
+                let (Some(a), Some(b), Some(c)) = (
+                    u32_at(record, offset),
+                    u32_at(record, offset + 4),
+                    u32_at(record, offset + 8),
+                ) else {
+                    return;
+                };
+                offset += 12;
+                let (Some(va), Some(vb), Some(vc)) = (
+                    vertices.get(a as usize),
+                    vertices.get(b as usize),
+                    vertices.get(c as usize),
+                ) else {
+                    continue;
+                };
+                let pa = to_output.apply(Point::new(va.x as f64, va.y as f64));
+                let pb = to_output.apply(Point::new(vb.x as f64, vb.y as f64));
+                let pc = to_output.apply(Point::new(vc.x as f64, vc.y as f64));
+                let area = (pb.x - pa.x) * (pc.y - pa.y) - (pc.x - pa.x) * (pb.y - pa.y);
+                if area.abs() < 1e-9 {
+                    continue;
+                }
+                let (ca, cb, cc) = (color(va), color(vb), color(vc));
+                let shader = |x: f64, y: f64| {
+                    let wa = ((pb.x - x) * (pc.y - y) - (pc.x - x) * (pb.y - y)) / area;
+                    let wb = ((pc.x - x) * (pa.y - y) - (pa.x - x) * (pc.y - y)) / area;
+                    let wa = wa.clamp(0.0, 1.0);
+                    let wb = wb.clamp(0.0, 1.0 - wa);
+                    let wc = 1.0 - wa - wb;
+                    mix([0, 1, 2].map(|i| ca[i] * wa + cb[i] * wb + cc[i] * wc))
+                };
+                let mut path = Path::new();
+                path.move_to(pa);
+                path.line_to(pb);
+                path.line_to(pc);
+                path.close();
+                if let Some(mask) = self.rasterize(&path, FillRule::NonZero) {
+                    self.canvas.fill_mask(
+                        &mask,
+                        &Paint::Shader(&shader),
+                        self.dc.clip_mask.as_deref(),
+                    );
+                }
+            } else {
+                let (Some(a), Some(b)) = (u32_at(record, offset), u32_at(record, offset + 4))
+                else {
+                    return;
+                };
+                offset += 8;
+                let (Some(va), Some(vb)) = (vertices.get(a as usize), vertices.get(b as usize))
+                else {
+                    continue;
+                };
+                let (ca, cb) = (color(va), color(vb));
+                let horizontal = header.mode == 0;
+                let (start, end) = if horizontal {
+                    (va.x as f64, vb.x as f64)
+                } else {
+                    (va.y as f64, vb.y as f64)
+                };
+                let shader = |x: f64, y: f64| {
+                    let p = to_logical.apply(Point::new(x, y));
+                    let v = if horizontal { p.x } else { p.y };
+                    let t = if end == start {
+                        0.0
+                    } else {
+                        ((v - start) / (end - start)).clamp(0.0, 1.0)
+                    };
+                    mix([0, 1, 2].map(|i| ca[i] + (cb[i] - ca[i]) * t))
+                };
+                let path = Path::rect(va.x as f64, va.y as f64, vb.x as f64, vb.y as f64)
+                    .transform(&to_output);
+                if let Some(mask) = self.rasterize(&path, FillRule::NonZero) {
+                    self.canvas.fill_mask(
+                        &mask,
+                        &Paint::Shader(&shader),
+                        self.dc.clip_mask.as_deref(),
+                    );
+                }
+            }
+        }
+    }
+
+    fn ext_create_pen(&mut self, record: &[u8]) {
+        let Some(r) = read::<EmrExtCreatePenHeader>(record) else {
+            return;
+        };
+        let base = std::mem::size_of::<EmrExtCreatePenHeader>();
+        let style_entries: Vec<u32> = (0..r.num_style_entries.min(16) as usize)
+            .map_while(|i| u32_at(record, base + i * 4))
+            .collect();
+        let mut pen = Pen::from_ext_emr_data(r.pen_style, r.width, r.color.value, &style_entries);
+        if r.brush_style == brush_style::NULL {
+            pen.style = PenStyle::Null;
+        }
+        self.objects.insert(r.object_index, GdiObject::Pen(pen));
+    }
+
+    // Bitmaps
+
+    /// Draw a bitmap from source pixels onto a logical destination rectangle
+    fn blit(
+        &mut self,
+        image: &RgbaImage,
+        src: (f64, f64, f64, f64),
+        dest: (f64, f64, f64, f64),
+        raster_op: u32,
+    ) {
+        let (mut sx, mut sy, mut sw, mut sh) = src;
+        let (mut dx, mut dy, mut dw, mut dh) = dest;
+        if sw < 0.0 {
+            sx += sw;
+            sw = -sw;
+            dx += dw;
+            dw = -dw;
+        }
+        if sh < 0.0 {
+            sy += sh;
+            sh = -sh;
+            dy += dh;
+            dh = -dh;
+        }
+        if sw == 0.0 || sh == 0.0 || dw == 0.0 || dh == 0.0 {
+            return;
+        }
+
+        let to_canvas = Transform::translate(-sx, -sy)
+            .then(&Transform::scale(dw / sw, dh / sh))
+            .then(&Transform::translate(dx, dy))
+            .then(&self.logical_to_output());
+        let brush = parse_hex_color(&self.dc.brush.color);
+        self.canvas.draw_image(
+            image,
+            (sx, sy, sw, sh),
+            &to_canvas,
+            raster_op,
+            brush,
+            self.dc.clip_mask.as_deref(),
+        );
+    }
+
+    /// Apply a raster operation without a source bitmap to a logical rectangle
+    fn pattern_rect(&mut self, dest: (f64, f64, f64, f64), raster_op: u32) {
+        let (x, y, w, h) = dest;
+        let path = Path::rect(x, y, x + w, y + h).transform(&self.logical_to_output());
+        let Some(mask) = self.rasterize(&path, FillRule::NonZero) else {
+            return;
+        };
+        if raster_op == rop::PATCOPY {
+            self.fill_mask(&mask);
+        } else {
+            let brush = parse_hex_color(&self.dc.brush.color);
+            self.canvas
+                .pattern_blt(&mask, raster_op, brush, self.dc.clip_mask.as_deref());
+        }
+    }
+
+    fn bit_blt(&mut self, record: &[u8]) {
+        let Some(r) = read::<EmrBitBltHeader>(record) else {
+            return;
+        };
+        let dest = (
+            r.x_dest as f64,
+            r.y_dest as f64,
+            r.cx_dest as f64,
+            r.cy_dest as f64,
+        );
+        if r.cb_bmi_src == 0 {
+            self.pattern_rect(dest, r.rop);
+            return;
+        }
+        if let Some(image) = dib(
+            record,
+            r.off_bmi_src,
+            r.cb_bmi_src,
+            r.off_bits_src,
+            r.cb_bits_src,
+            false,
+        ) {
+            let src = (
+                r.x_src as f64,
+                r.y_src as f64,
+                r.cx_dest as f64,
+                r.cy_dest as f64,
+            );
+            self.blit(&image, src, dest, r.rop);
+        }
+    }
+
+    fn stretch_blt(&mut self, record: &[u8]) {
+        let Some(r) = read::<EmrStretchBltHeader>(record) else {
+            return;
+        };
+        let dest = (
+            r.x_dest as f64,
+            r.y_dest as f64,
+            r.cx_dest as f64,
+            r.cy_dest as f64,
+        );
+        if r.cb_bmi_src == 0 {
+            self.pattern_rect(dest, r.rop);
+            return;
+        }
+        if let Some(image) = dib(
+            record,
+            r.off_bmi_src,
+            r.cb_bmi_src,
+            r.off_bits_src,
+            r.cb_bits_src,
+            false,
+        ) {
+            let src = (
+                r.x_src as f64,
+                r.y_src as f64,
+                r.cx_src as f64,
+                r.cy_src as f64,
+            );
+            self.blit(&image, src, dest, r.rop);
+        }
+    }
+
+    fn stretch_dibits(&mut self, record: &[u8]) {
+        let Some(r) = read::<EmrStretchDiBitsHeader>(record) else {
+            return;
+        };
+        let dest = (
+            r.x_dest as f64,
+            r.y_dest as f64,
+            r.cx_dest as f64,
+            r.cy_dest as f64,
+        );
+        if r.cb_bmi_src == 0 {
+            self.pattern_rect(dest, r.rop);
+            return;
+        }
+        let Some(image) = dib(
+            record,
+            r.off_bmi_src,
+            r.cb_bmi_src,
+            r.off_bits_src,
+            r.cb_bits_src,
+            false,
+        ) else {
+            return;
+        };
+        // Source coordinates of bottom-up DIBs start at the bottom row
+        let y = if dib_is_bottom_up(record, r.off_bmi_src) {
+            image.height() as f64 - r.y_src as f64 - r.cy_src as f64
+        } else {
+            r.y_src as f64
+        };
+        let src = (r.x_src as f64, y, r.cx_src as f64, r.cy_src as f64);
+        self.blit(&image, src, dest, r.rop);
+    }
+
+    fn set_dibits_to_device(&mut self, record: &[u8]) {
+        let Some(r) = read::<EmrSetDiBitsToDeviceHeader>(record) else {
+            return;
+        };
+        let Some(mut bmi) = slice(record, r.off_bmi_src, r.cb_bmi_src).map(<[u8]>::to_vec) else {
+            return;
+        };
+        let Some(bits) = slice(record, r.off_bits_src, r.cb_bits_src) else {
+            return;
+        };
+
+        // The bits only hold `num_scans` scanlines starting at `scan_start`
+        let bottom_up = dib_is_bottom_up(record, r.off_bmi_src);
+        if bmi.len() >= 12 && u32_at(&bmi, 0).is_some_and(|size| size >= 40) {
+            let height = i32::from_le_bytes([bmi[8], bmi[9], bmi[10], bmi[11]]);
+            if (r.num_scans as i64) < height.unsigned_abs() as i64 && r.num_scans > 0 {
+                let scans = r.num_scans as i32 * height.signum();
+                bmi[8..12].copy_from_slice(&scans.to_le_bytes());
+            }
+        }
+        let Some(image) = decode_dib(&bmi, bits, false) else {
+            return;
+        };
+        let y_src = r.y_src as f64 - if bottom_up { r.scan_start as f64 } else { 0.0 };
+        let y = if bottom_up {
+            image.height() as f64 - y_src - r.cy_src as f64
+        } else {
+            y_src
+        };
+        let src = (r.x_src as f64, y, r.cx_src as f64, r.cy_src as f64);
+        let dest = (
+            r.x_dest as f64,
+            r.y_dest as f64,
+            r.cx_src as f64,
+            r.cy_src as f64,
+        );
+        self.blit(&image, src, dest, rop::SRCCOPY);
+    }
+
+    fn alpha_blend(&mut self, record: &[u8]) {
+        let Some(r) = read::<EmrAlphaBlendHeader>(record) else {
+            return;
+        };
+        let per_pixel = r.blend_function.alpha_format & 1 != 0;
+        let Some(mut image) = dib(
+            record,
+            r.off_bmi_src,
+            r.cb_bmi_src,
+            r.off_bits_src,
+            r.cb_bits_src,
+            per_pixel,
+        ) else {
+            return;
+        };
+        let constant = r.blend_function.src_constant_alpha as u32;
+        for pixel in image.pixels_mut() {
+            let alpha = pixel[3] as u32;
+            if per_pixel && alpha > 0 {
+                // Source colors are premultiplied
+                for c in 0..3 {
+                    pixel[c] = (pixel[c] as u32 * 255 / alpha).min(255) as u8;
+                }
+            }
+            pixel[3] = (alpha * constant / 255) as u8;
+        }
+        let src = (
+            r.x_src as f64,
+            r.y_src as f64,
+            r.cx_src as f64,
+            r.cy_src as f64,
+        );
+        let dest = (
+            r.x_dest as f64,
+            r.y_dest as f64,
+            r.cx_dest as f64,
+            r.cy_dest as f64,
+        );
+        self.blit(&image, src, dest, rop::SRCCOPY);
+    }
+
+    fn transparent_blt(&mut self, record: &[u8]) {
+        let Some(r) = read::<EmrTransparentBltHeader>(record) else {
+            return;
+        };
+        let Some(mut image) = dib(
+            record,
+            r.off_bmi_src,
+            r.cb_bmi_src,
+            r.off_bits_src,
+            r.cb_bits_src,
+            false,
+        ) else {
+            return;
+        };
+        let key = colorref(ColorRef {
+            value: r.transparent_color,
+        });
+        for pixel in image.pixels_mut() {
+            if pixel[0] == key[0] && pixel[1] == key[1] && pixel[2] == key[2] {
+                pixel[3] = 0;
+            }
+        }
+        let src = (
+            r.x_src as f64,
+            r.y_src as f64,
+            r.cx_src as f64,
+            r.cy_src as f64,
+        );
+        let dest = (
+            r.x_dest as f64,
+            r.y_dest as f64,
+            r.cx_dest as f64,
+            r.cy_dest as f64,
+        );
+        self.blit(&image, src, dest, rop::SRCCOPY);
+    }
+
+    // Text
+
+    fn text_out(&mut self, record: &[u8], text: &EmrTextInfo, wide: bool) {
+        let options = text.options;
+        let has_rect = options & 0x0100 == 0; // ETO_NO_RECT
+        // Without a rectangle the dx offset takes its place
+        let off_dx = if has_rect {
+            text.off_dx
+        } else {
+            text.rectangle.left as u32
+        };
+        let count = text.num_chars as usize;
+
+        let glyphs: Vec<u16>;
+        let chars: Vec<char>;
+        let run = if wide {
+            let Some(units) = slice(record, text.off_string, (count * 2) as u32) else {
+                return;
+            };
+            let units: Vec<u16> = units
+                .chunks_exact(2)
+                .map(|c| u16::from_le_bytes([c[0], c[1]]))
+                .collect();
+            if options & text_options::GLYPH_INDEX != 0 {
+                glyphs = units;
+                TextRun::Glyphs(&glyphs)
+            } else {
+                chars = char::decode_utf16(units.iter().copied())
+                    .map(|c| c.unwrap_or(char::REPLACEMENT_CHARACTER))
+                    .collect();
+                TextRun::Chars(&chars)
+            }
+        } else {
+            let Some(bytes) = slice(record, text.off_string, count as u32) else {
+                return;
+            };
+            chars = bytes.iter().map(|&b| b as char).collect();
+            TextRun::Chars(&chars)
+        };
+
+        let pdy = options & text_options::PDY != 0;
+        let dx = if off_dx != 0 {
+            let entries = if pdy { count * 2 } else { count };
+            (0..entries)
+                .map(|i| i32_at(record, off_dx as usize + i * 4).map(|v| v as f64))
+                .collect::<Option<Vec<f64>>>()
+        } else {
+            None
+        };
+
+        let reference = Point::new(text.reference.x as f64, text.reference.y as f64);
+        let rectangle = has_rect.then(|| rect(&text.rectangle));
+        self.draw_text(reference, run, dx.as_deref(), pdy, options, rectangle);
+    }
+
+    fn small_text_out(&mut self, record: &[u8]) {
+        let Some(header) = record.get(8..).and_then(read::<EmrSmallTextOutHeader>) else {
+            return;
+        };
+        let options = header.fu_options;
+        let no_rect = options & 0x0100 != 0;
+        let mut offset = 8 + std::mem::size_of::<EmrSmallTextOutHeader>();
+        if no_rect {
+            offset -= std::mem::size_of::<RectL>();
+        }
+        let count = header.num_chars as usize;
+        // ETO_SMALL_CHARS stores 8-bit characters
+        let chars: Vec<char> = if options & 0x0200 != 0 {
+            let Some(bytes) = record.get(offset..offset + count) else {
+                return;
+            };
+            bytes.iter().map(|&b| b as char).collect()
+        } else {
+            let Some(bytes) = record.get(offset..offset + count * 2) else {
+                return;
+            };
+            char::decode_utf16(
+                bytes
+                    .chunks_exact(2)
+                    .map(|c| u16::from_le_bytes([c[0], c[1]])),
+            )
+            .map(|c| c.unwrap_or(char::REPLACEMENT_CHARACTER))
+            .collect()
+        };
+        let reference = Point::new(header.x as f64, header.y as f64);
+        let rectangle = (!no_rect).then(|| rect(&header.bounds));
+        self.draw_text(
+            reference,
+            TextRun::Chars(&chars),
+            None,
+            false,
+            options,
+            rectangle,
+        );
+    }
+
+    fn draw_text(
+        &mut self,
+        reference: Point,
+        run: TextRun,
+        dx: Option<&[f64]>,
+        pdy: bool,
+        options: u32,
+        rectangle: Option<(f64, f64, f64, f64)>,
+    ) {
+        let to_output = self.logical_to_output();
+        let background = parse_hex_color(&self.dc.background_color);
+
+        if options & text_options::OPAQUE != 0
+            && let Some((l, t, r, b)) = rectangle
+        {
+            self.fill_solid(
+                &Path::rect(l, t, r, b).transform(&to_output),
+                FillRule::NonZero,
+                background,
+            );
+        }
+
+        let font = self.dc.font.clone();
+        let Some(shaped) = font::shape(&font, run) else {
+            return;
+        };
+        let metrics = shaped.metrics;
+
+        // Em size in logical units
+        let cell = (metrics.descent - metrics.ascent).max(0.1);
+        let em = match font.height {
+            h if h < 0 => -h as f64,
+            0 => 16.0 * self.scale / to_output.mean_scale().max(1e-9),
+            h => h as f64 / cell,
+        };
+
+        // Glyph origins along the baseline, in logical units
+        let mut positions = Vec::with_capacity(shaped.glyphs.len());
+        let (mut x, mut y) = (0.0, 0.0);
+        for (i, glyph) in shaped.glyphs.iter().enumerate() {
+            positions.push((x, y));
+            match dx {
+                Some(dx) if pdy => {
+                    x += dx.get(i * 2).copied().unwrap_or(glyph.advance * em);
+                    y -= dx.get(i * 2 + 1).copied().unwrap_or(0.0);
+                },
+                Some(dx) => x += dx.get(i).copied().unwrap_or(glyph.advance * em),
+                None => x += glyph.advance * em,
+            }
+        }
+        let width = x;
+
+        let align = self.dc.text_align;
+        let update_cp = align.0 & TextAlign::UPDATECP != 0;
+        let reference = if update_cp { self.current() } else { reference };
+        let origin_x = if align.is_center() {
+            -width / 2.0
+        } else if align.is_right() {
+            -width
+        } else {
+            0.0
+        };
+        let baseline = if align.is_baseline() {
+            0.0
+        } else if align.is_bottom() {
+            -metrics.descent * em
+        } else {
+            -metrics.ascent * em
+        };
+
+        // Text space has x along the baseline and y down the page; keep
+        // glyphs upright when the mapping flips the y axis
+        let flip = if to_output.determinant() < 0.0 {
+            -1.0
+        } else {
+            1.0
+        };
+        let escapement = (font.escapement as f64 / 10.0).to_radians();
+        let to_logical = Transform::rotate(escapement)
+            .then(&Transform::scale(1.0, flip))
+            .then(&Transform::translate(reference.x, reference.y));
+        let text_to_output = to_logical.then(&to_output);
+
+        let saved_clip = self.dc.clip_mask.clone();
+        if options & text_options::CLIPPED != 0
+            && let Some((l, t, r, b)) = rectangle
+        {
+            let mask = self.rasterize(
+                &Path::rect(l, t, r, b).transform(&to_output),
+                FillRule::NonZero,
+            );
+            self.combine_clip(mask, MaskOp::And);
+        }
+
+        if self.dc.background_mode == BackgroundMode::Opaque && options & text_options::OPAQUE == 0
+        {
+            let cell = Path::rect(
+                origin_x,
+                baseline + metrics.ascent * em,
+                origin_x + width,
+                baseline + metrics.descent * em,
+            );
+            self.fill_solid(
+                &cell.transform(&text_to_output),
+                FillRule::NonZero,
+                background,
+            );
+        }
+
+        let mut outlines = Path::new();
+        for (glyph, &(gx, gy)) in shaped.glyphs.iter().zip(&positions) {
+            let place =
+                Transform::scale(em, em).then(&Transform::translate(origin_x + gx, baseline + gy));
+            outlines.append(&glyph.outline.transform(&place));
+        }
+        let mut decorations = Vec::new();
+        if font.underline {
+            decorations.push(metrics.underline);
+        }
+        if font.strike_out {
+            decorations.push(metrics.strikeout);
+        }
+        for (center, thickness) in decorations {
+            outlines.append(&Path::rect(
+                origin_x,
+                baseline + (center - thickness / 2.0) * em,
+                origin_x + width,
+                baseline + (center + thickness / 2.0) * em,
+            ));
+        }
+
+        let color = parse_hex_color(&self.dc.text_color);
+        self.fill_solid(
+            &outlines.transform(&text_to_output),
+            FillRule::NonZero,
+            color,
+        );
+        self.dc.clip_mask = saved_clip;
+
+        if update_cp {
+            let advance = if align.is_right() {
+                -width
+            } else if align.is_center() {
+                0.0
+            } else {
+                width
+            };
+            let end = to_logical.apply(Point::new(advance, 0.0));
+            self.set_current(end);
+        }
+    }
+}
+
+/// Stock object for a handle with the high bit set
+fn stock_object(handle: u32) -> Option<GdiObject> {
+    let solid = |rgb: u32| GdiObject::Brush(Brush::from_emr_data(brush_style::SOLID, rgb, 0));
+    let pen = |style: u32, rgb: u32| GdiObject::Pen(Pen::from_emr_data(style, 1, rgb));
+    match handle {
+        stock_objects::WHITE_BRUSH => Some(solid(0x00FF_FFFF)),
+        stock_objects::LTGRAY_BRUSH => Some(solid(0x00C0_C0C0)),
+        stock_objects::GRAY_BRUSH => Some(solid(0x0080_8080)),
+        stock_objects::DKGRAY_BRUSH => Some(solid(0x0040_4040)),
+        stock_objects::BLACK_BRUSH => Some(solid(0)),
+        stock_objects::NULL_BRUSH => Some(GdiObject::Brush(Brush::from_emr_data(
+            brush_style::NULL,
+            0,
+            0,
+        ))),
+        stock_objects::WHITE_PEN => Some(pen(pen_style::SOLID, 0x00FF_FFFF)),
+        stock_objects::BLACK_PEN => Some(pen(pen_style::SOLID, 0)),
+        stock_objects::NULL_PEN => Some(pen(pen_style::NULL, 0)),
+        stock_objects::OEM_FIXED_FONT
+        | stock_objects::ANSI_FIXED_FONT
+        | stock_objects::SYSTEM_FIXED_FONT => Some(GdiObject::Font(Font {
+            face_name: "Courier New".to_string(),
+            ..Default::default()
+        })),
+        stock_objects::ANSI_VAR_FONT
+        | stock_objects::SYSTEM_FONT
+        | stock_objects::DEVICE_DEFAULT_FONT
+        | stock_objects::DEFAULT_GUI_FONT => Some(GdiObject::Font(Font::default())),
+        _ => None,
+    }
+}
+
+/// Dash pattern of a pen, with lengths multiplied by `unit`
+fn dash_pattern(pen: &Pen, unit: f64) -> Vec<f64> {
+    let pattern: &[f64] = if !pen.user_style.is_empty() {
+        &pen.user_style
+    } else if pen.geometric {
+        match pen.style {
+            PenStyle::Dash => &[3.0, 1.0],
+            PenStyle::Dot => &[1.0, 1.0],
+            PenStyle::DashDot => &[3.0, 1.0, 1.0, 1.0],
+            PenStyle::DashDotDot => &[3.0, 1.0, 1.0, 1.0, 1.0, 1.0],
+            _ => &[],
+        }
+    } else {
+        // Cosmetic dashes have fixed lengths in device pixels
+        match pen.style {
+            PenStyle::Dash => &[18.0, 6.0],
+            PenStyle::Dot => &[3.0, 3.0],
+            PenStyle::DashDot => &[9.0, 6.0, 3.0, 6.0],
+            PenStyle::DashDotDot => &[9.0, 3.0, 3.0, 3.0, 3.0, 3.0],
+            _ => &[],
+        }
+    };
+    pattern.iter().map(|&d| d * unit).collect()
+}
+
+fn from_world(world: &WorldTransform) -> Transform {
+    Transform {
+        a: world.m11 as f64,
+        b: world.m12 as f64,
+        c: world.m21 as f64,
+        d: world.m22 as f64,
+        e: world.dx as f64,
+        f: world.dy as f64,
+    }
+}
+
+fn to_world(t: &Transform) -> WorldTransform {
+    WorldTransform {
+        m11: t.a as f32,
+        m12: t.b as f32,
+        m21: t.c as f32,
+        m22: t.d as f32,
+        dx: t.e as f32,
+        dy: t.f as f32,
+    }
+}
+
+/// `num / denom`, or the default pixels-per-millimeter pair fallback of 1
+fn ratio(num: i32, denom: i32) -> f64 {
+    if num == 0 || denom == 0 {
+        if denom == 0 && num != 0 {
+            return 1.0;
+        }
+        // Device size unknown: assume 96 DPI
+        return 96.0 / 25.4;
+    }
+    num as f64 / denom as f64
+}
+
+fn rect(r: &RectL) -> (f64, f64, f64, f64) {
+    (r.left as f64, r.top as f64, r.right as f64, r.bottom as f64)
+}
+
+fn colorref(color: ColorRef) -> Rgba<u8> {
+    Rgba([color.r(), color.g(), color.b(), 255])
+}
+
+fn polyline_path(points: &[Point]) -> Path {
+    let mut path = Path::new();
+    for (i, &p) in points.iter().enumerate() {
+        if i == 0 {
+            path.move_to(p);
+        } else {
+            path.line_to(p);
+        }
+    }
+    path
+}
+
+/// Append Bézier segments given as groups of three points
+fn bezier_segments(path: &mut Path, points: &[Point]) {
+    for chunk in points.chunks_exact(3) {
+        path.cubic_to(chunk[0], chunk[1], chunk[2]);
+    }
+}
+
+fn append_polyline(path: &mut Path, polyline: &Polyline) {
+    path.append(&polyline_path(&polyline.points));
+    if polyline.closed {
+        path.close();
+    }
+}
+
+/// Rectangles of an RGNDATA structure as a path
+fn region_path(data: &[u8], size: u32) -> Option<Path> {
+    let data = data.get(..(size as usize).min(data.len()))?;
+    let count = u32_at(data, 8)? as usize;
+    let mut path = Path::new();
+    for i in 0..count {
+        let r = data.get(32 + i * 16..).and_then(read::<RectL>)?;
+        let (l, t, rr, b) = rect(&r);
+        path.append(&Path::rect(l, t, rr, b));
+    }
+    Some(path)
+}
+
+fn read<T: FromBytes>(bytes: &[u8]) -> Option<T> {
+    T::read_from_prefix(bytes).ok().map(|(value, _)| value)
+}
+
+fn u32_at(bytes: &[u8], offset: usize) -> Option<u32> {
+    bytes
+        .get(offset..offset + 4)
+        .map(|b| u32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+}
+
+fn i32_at(bytes: &[u8], offset: usize) -> Option<i32> {
+    u32_at(bytes, offset).map(|v| v as i32)
+}
+
+/// A checked sub-slice given a record offset and length
+fn slice(record: &[u8], offset: u32, len: u32) -> Option<&[u8]> {
+    let start = offset as usize;
+    record.get(start..start.checked_add(len as usize)?)
+}
+
+fn read_points(record: &[u8], offset: usize, count: usize, small: bool) -> Option<Vec<Point>> {
+    let size = if small { 4 } else { 8 };
+    let bytes = record.get(offset..offset.checked_add(count.checked_mul(size)?)?)?;
+    Some(
+        bytes
+            .chunks_exact(size)
+            .map(|c| {
+                if small {
+                    Point::new(
+                        i16::from_le_bytes([c[0], c[1]]) as f64,
+                        i16::from_le_bytes([c[2], c[3]]) as f64,
+                    )
+                } else {
+                    Point::new(
+                        i32::from_le_bytes([c[0], c[1], c[2], c[3]]) as f64,
+                        i32::from_le_bytes([c[4], c[5], c[6], c[7]]) as f64,
+                    )
+                }
+            })
+            .collect(),
+    )
+}
+
+/// Decode the DIB referenced by a bitmap record
+fn dib(
+    record: &[u8],
+    off_bmi: u32,
+    cb_bmi: u32,
+    off_bits: u32,
+    cb_bits: u32,
+    keep_alpha: bool,
+) -> Option<RgbaImage> {
+    decode_dib(
+        slice(record, off_bmi, cb_bmi)?,
+        slice(record, off_bits, cb_bits)?,
+        keep_alpha,
+    )
+}
+
+fn dib_is_bottom_up(record: &[u8], off_bmi: u32) -> bool {
+    let offset = off_bmi as usize;
+    match u32_at(record, offset) {
+        Some(12) => true,
+        Some(_) => i32_at(record, offset + 8).is_some_and(|h| h > 0),
+        None => true,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Build an EMF with 100x100 bounds from raw records (without EOF)
+    fn emf(records: &[Vec<u8>]) -> EmfParser {
+        let mut data = Vec::new();
+        let header = [
+            1u32,
+            88,
+            0,
+            0,
+            100,
+            100,
+            0,
+            0,
+            2646,
+            2646,
+            0x464D_4520,
+            0x0001_0000,
+            0,
+            0,
+            0,
+            0,
+            0,
+            96,
+            96,
+            25,
+            25,
+            0,
+        ];
+        for value in header {
+            data.extend_from_slice(&value.to_le_bytes());
+        }
+        for record in records {
+            data.extend_from_slice(record);
+        }
+        data.extend_from_slice(&record(EmrType::Eof, &[0, 0, 20]));
+        EmfParser::new(&data).unwrap()
+    }
+
+    fn record(kind: EmrType, values: &[i32]) -> Vec<u8> {
+        let mut data = Vec::new();
+        data.extend_from_slice(&(kind as u32).to_le_bytes());
+        data.extend_from_slice(&(8 + values.len() as u32 * 4).to_le_bytes());
+        for value in values {
+            data.extend_from_slice(&value.to_le_bytes());
+        }
+        data
+    }
+
+    fn render(records: &[Vec<u8>]) -> RgbaImage {
+        let parser = emf(records);
+        EmfRenderer::new(&parser, 100, 100).render(Rgba([255, 255, 255, 255]))
+    }
+
+    #[test]
+    fn test_filled_rectangle() {
+        let image = render(&[
+            record(EmrType::CreateBrushIndirect, &[1, 0, 0x0000_00FF, 0]),
+            record(EmrType::SelectObject, &[1]),
+            record(EmrType::SelectObject, &[stock_objects::NULL_PEN as i32]),
+            record(EmrType::Rectangle, &[10, 10, 50, 40]),
+        ]);
+        assert_eq!(image.get_pixel(30, 20), &Rgba([255, 0, 0, 255]));
+        assert_eq!(image.get_pixel(60, 20), &Rgba([255, 255, 255, 255]));
+        assert_eq!(image.get_pixel(30, 45), &Rgba([255, 255, 255, 255]));
+    }
+
+    #[test]
+    fn test_mapping_and_clipping() {
+        let image = render(&[
+            record(EmrType::SetMapMode, &[8]),
+            record(EmrType::SetWindowExtEx, &[1000, 1000]),
+            record(EmrType::SetViewportExtEx, &[100, 100]),
+            record(EmrType::IntersectClipRect, &[0, 0, 500, 1000]),
+            record(EmrType::SelectObject, &[stock_objects::BLACK_BRUSH as i32]),
+            record(EmrType::SelectObject, &[stock_objects::NULL_PEN as i32]),
+            record(EmrType::Ellipse, &[0, 0, 1000, 1000]),
+        ]);
+        // Left half of the circle is drawn, the right half is clipped
+        assert_eq!(image.get_pixel(25, 50), &Rgba([0, 0, 0, 255]));
+        assert_eq!(image.get_pixel(75, 50), &Rgba([255, 255, 255, 255]));
+        assert_eq!(image.get_pixel(2, 2), &Rgba([255, 255, 255, 255]));
+    }
+
+    #[test]
+    fn test_world_transform_and_pen() {
+        // Scale by two, then draw a 4 unit wide horizontal line
+        let scale = [2.0f32, 0.0, 0.0, 2.0, 0.0, 0.0].map(|v| v.to_bits() as i32);
+        let image = render(&[
+            record(EmrType::SetWorldTransform, &scale),
+            record(EmrType::CreatePen, &[1, 0, 4, 0, 0x00FF_0000]),
+            record(EmrType::SelectObject, &[1]),
+            record(EmrType::MoveToEx, &[5, 20]),
+            record(EmrType::LineTo, &[40, 20]),
+        ]);
+        assert_eq!(image.get_pixel(50, 40), &Rgba([0, 0, 255, 255]));
+        assert_eq!(image.get_pixel(50, 37), &Rgba([0, 0, 255, 255]));
+        assert_eq!(image.get_pixel(50, 45), &Rgba([255, 255, 255, 255]));
+    }
+
+    /// An EMR_COMMENT record carrying EMF+ records
+    fn emf_plus_comment(records: &[(u16, u16, Vec<u8>)]) -> Vec<u8> {
+        let mut payload = 0x2B46_4D45u32.to_le_bytes().to_vec();
+        for (kind, flags, data) in records {
+            payload.extend_from_slice(&kind.to_le_bytes());
+            payload.extend_from_slice(&flags.to_le_bytes());
+            payload.extend_from_slice(&(12 + data.len() as u32).to_le_bytes());
+            payload.extend_from_slice(&(data.len() as u32).to_le_bytes());
+            payload.extend_from_slice(data);
+        }
+        let mut comment = record(EmrType::Comment, &[payload.len() as i32]);
+        comment.extend_from_slice(&payload);
+        let size = comment.len() as u32;
+        comment[4..8].copy_from_slice(&size.to_le_bytes());
+        comment
+    }
+
+    #[test]
+    fn test_emf_plus_records() {
+        let header = [0xDBC0_1002u32, 1, 96, 96]
+            .iter()
+            .flat_map(|v| v.to_le_bytes())
+            .collect();
+        let mut fill = Vec::new();
+        for value in [0xFF00_FF00u32, 1] {
+            fill.extend_from_slice(&value.to_le_bytes());
+        }
+        for value in [10.0f32, 10.0, 40.0, 30.0] {
+            fill.extend_from_slice(&value.to_le_bytes());
+        }
+
+        // The GDI fallback rectangle is skipped once EMF+ records were seen
+        let image = render(&[
+            emf_plus_comment(&[(0x4001, 1, header), (0x400A, 0x8000, fill)]),
+            record(EmrType::CreateBrushIndirect, &[1, 0, 0x0000_00FF, 0]),
+            record(EmrType::SelectObject, &[1]),
+            record(EmrType::Rectangle, &[0, 0, 100, 100]),
+        ]);
+        assert_eq!(image.get_pixel(30, 20), &Rgba([0, 255, 0, 255]));
+        assert_eq!(image.get_pixel(60, 20), &Rgba([255, 255, 255, 255]));
+        assert_eq!(image.get_pixel(80, 80), &Rgba([255, 255, 255, 255]));
+    }
+
+    #[test]
+    fn test_text() {
+        let font = Font {
+            height: -40,
+            ..Default::default()
+        };
+        if font::shape(&font, TextRun::Chars(&['H'])).is_none() {
+            // No fonts installed
+            return;
+        }
+
+        let mut create_font = record(
+            EmrType::ExtCreateFontIndirectW,
+            &[1, -40, 0, 0, 0, 400, 0, 0],
+        );
+        create_font.extend_from_slice(&[0u8; 64]);
+        let size = create_font.len() as u32;
+        create_font[4..8].copy_from_slice(&size.to_le_bytes());
+
+        // EMR_EXTTEXTOUTW with a single "H" and no dx array
+        let mut text = record(
+            EmrType::ExtTextOutW,
+            &[0, 0, 100, 100, 1, 0, 0, 10, 80, 1, 76, 0, 0, 0, 0, 0, 0],
+        );
+        text.extend_from_slice(&[b'H', 0, 0, 0]);
+        let size = text.len() as u32;
+        text[4..8].copy_from_slice(&size.to_le_bytes());
+
+        let image = render(&[
+            create_font,
+            record(EmrType::SelectObject, &[1]),
+            record(EmrType::SetBkMode, &[1]),
+            record(EmrType::SetTextAlign, &[TextAlign::BASELINE as i32]),
+            text,
+        ]);
+        let dark = image.pixels().filter(|p| p[0] < 128).count();
+        assert!(dark > 50, "expected glyph pixels, found {dark}");
+        // Everything is above the baseline
+        assert!((0..100).all(|x| image.get_pixel(x, 85)[0] > 200));
+    }
+}
@@ -0,0 +1,357 @@
+// Stroker: converts polylines into fillable outlines
+//
+// Every segment, join and cap becomes its own small polygon. All pieces are
+// emitted with the same orientation so filling the result with the non-zero
+// rule yields their union without overlaps being counted twice.
+
+use super::path::{Point, Polyline};
+use crate::images::emf::gdi_objects::{LineCap, LineJoin};
+use std::f64::consts::PI;
+
+/// Geometry of a stroke
+#[derive(Debug, Clone)]
+pub struct StrokeStyle {
+    pub width: f64,
+    pub cap: LineCap,
+    pub join: LineJoin,
+    pub miter_limit: f64,
+    /// Alternating dash and gap lengths (empty for a solid line)
+    pub dashes: Vec<f64>,
+}
+
+impl Default for StrokeStyle {
+    fn default() -> Self {
+        Self {
+            width: 1.0,
+            cap: LineCap::Round,
+            join: LineJoin::Round,
+            miter_limit: 10.0,
+            dashes: Vec::new(),
+        }
+    }
+}
+
+/// Build the outline of stroked polylines, to be filled with the non-zero rule
+pub fn stroke(polylines: &[Polyline], style: &StrokeStyle) -> Vec<Polyline> {
+    let half = style.width / 2.0;
+    let mut out = Vec::new();
+    if half <= 0.0 || !half.is_finite() {
+        return out;
+    }
+
+    for polyline in polylines {
+        let points = dedup(&polyline.points);
+        if points.len() < 2 {
+            continue;
+        }
+        if style.dashes.iter().any(|&d| d > 0.0) {
+            for dash in apply_dashes(&points, polyline.closed, &style.dashes) {
+                stroke_polyline(&dedup(&dash), false, half, style, &mut out);
+            }
+        } else {
+            stroke_polyline(&points, polyline.closed, half, style, &mut out);
+        }
+    }
+    out
+}
+
+fn dedup(points: &[Point]) -> Vec<Point> {
+    let mut result: Vec<Point> = Vec::with_capacity(points.len());
+    for &p in points {
+        if result.last().is_none_or(|last| last.distance(p) > 1e-9) {
+            result.push(p);
+        }
+    }
+    result
+}
+
+fn stroke_polyline(
+    points: &[Point],
+    closed: bool,
+    half: f64,
+    style: &StrokeStyle,
+    out: &mut Vec<Polyline>,
+) {
+    let mut points = points.to_vec();
+    if closed && points.len() > 2 && points[0].distance(points[points.len() - 1]) < 1e-9 {
+        points.pop();
+    }
+    let n = points.len();
+    if n < 2 {
+        return;
+    }
+    let segment_count = if closed { n } else { n - 1 };
+
+    for i in 0..segment_count {
+        let p0 = points[i];
+        let p1 = points[(i + 1) % n];
+        let Some(normal) = unit_normal(p0, p1) else {
+            continue;
+        };
+        let nx = normal.x * half;
+        let ny = normal.y * half;
+        push_polygon(
+            out,
+            vec![
+                Point::new(p0.x + nx, p0.y + ny),
+                Point::new(p1.x + nx, p1.y + ny),
+                Point::new(p1.x - nx, p1.y - ny),
+                Point::new(p0.x - nx, p0.y - ny),
+            ],
+        );
+    }
+
+    // Joins at interior vertices (and all vertices of closed figures)
+    let join_range = if closed { 0..n } else { 1..n - 1 };
+    for i in join_range {
+        let prev = points[(i + n - 1) % n];
+        let p = points[i];
+        let next = points[(i + 1) % n];
+        add_join(prev, p, next, half, style, out);
+    }
+
+    if !closed {
+        add_cap(points[1], points[0], half, style.cap, out);
+        add_cap(points[n - 2], points[n - 1], half, style.cap, out);
+    }
+}
+
+/// Unit left-hand normal of the direction `p0 -> p1`
+fn unit_normal(p0: Point, p1: Point) -> Option<Point> {
+    let dx = p1.x - p0.x;
+    let dy = p1.y - p0.y;
+    let len = dx.hypot(dy);
+    if len < 1e-12 {
+        return None;
+    }
+    Some(Point::new(-dy / len, dx / len))
+}
+
+fn add_join(
+    prev: Point,
+    p: Point,
+    next: Point,
+    half: f64,
+    style: &StrokeStyle,
+    out: &mut Vec<Polyline>,
+) {
+    let (Some(n1), Some(n2)) = (unit_normal(prev, p), unit_normal(p, next)) else {
+        return;
+    };
+    let d1 = Point::new(p.x - prev.x, p.y - prev.y);
+    let d2 = Point::new(next.x - p.x, next.y - p.y);
+    let cross = d1.x * d2.y - d1.y * d2.x;
+    let dot = d1.x * d2.x + d1.y * d2.y;
+    if cross.abs() < 1e-12 && dot > 0.0 {
+        // Collinear, no join needed
+        return;
+    }
+
+    if style.join == LineJoin::Round {
+        push_polygon(out, circle(p, half));
+        return;
+    }
+
+    // The outer side is opposite to the turn direction
+    let sign = if cross > 0.0 { -1.0 } else { 1.0 };
+    let a = Point::new(p.x + sign * n1.x * half, p.y + sign * n1.y * half);
+    let b = Point::new(p.x + sign * n2.x * half, p.y + sign * n2.y * half);
+
+    if style.join == LineJoin::Miter {
+        let bisector = Point::new(n1.x + n2.x, n1.y + n2.y);
+        let len = bisector.x.hypot(bisector.y);
+        if len > 1e-12 {
+            let cos_half = len / 2.0;
+            if 1.0 / cos_half <= style.miter_limit.max(1.0) {
+                let scale = sign * half / cos_half / len;
+                let tip = Point::new(p.x + bisector.x * scale, p.y + bisector.y * scale);
+                push_polygon(out, vec![p, a, tip, b]);
+                return;
+            }
+        }
+    }
+
+    push_polygon(out, vec![p, a, b]);
+}
+
+/// Add a cap at `end` for a line arriving from `from`
+fn add_cap(from: Point, end: Point, half: f64, cap: LineCap, out: &mut Vec<Polyline>) {
+    match cap {
+        LineCap::Flat => {},
+        LineCap::Round => push_polygon(out, circle(end, half)),
+        LineCap::Square => {
+            let Some(normal) = unit_normal(from, end) else {
+                return;
+            };
+            // Direction of travel is the normal rotated back by 90 degrees
+            let dx = normal.y * half;
+            let dy = -normal.x * half;
+            let nx = normal.x * half;
+            let ny = normal.y * half;
+            push_polygon(
+                out,
+                vec![
+                    Point::new(end.x + nx, end.y + ny),
+                    Point::new(end.x + nx + dx, end.y + ny + dy),
+                    Point::new(end.x - nx + dx, end.y - ny + dy),
+                    Point::new(end.x - nx, end.y - ny),
+                ],
+            );
+        },
+    }
+}
+
+/// Polygon approximating a circle
+fn circle(center: Point, radius: f64) -> Vec<Point> {
+    let tolerance = 0.1f64.min(radius / 2.0);
+    let step = 2.0 * (1.0 - tolerance / radius).clamp(-1.0, 1.0).acos();
+    let count = if step > 0.0 {
+        ((2.0 * PI / step).ceil() as usize).clamp(8, 256)
+    } else {
+        8
+    };
+    (0..count)
+        .map(|i| {
+            let angle = 2.0 * PI * i as f64 / count as f64;
+            Point::new(
+                center.x + radius * angle.cos(),
+                center.y + radius * angle.sin(),
+            )
+        })
+        .collect()
+}
+
+/// Add a polygon with positive orientation
+fn push_polygon(out: &mut Vec<Polyline>, mut points: Vec<Point>) {
+    let mut area = 0.0;
+    for i in 0..points.len() {
+        let a = points[i];
+        let b = points[(i + 1) % points.len()];
+        area += a.x * b.y - b.x * a.y;
+    }
+    if area.abs() < 1e-12 {
+        return;
+    }
+    if area < 0.0 {
+        points.reverse();
+    }
+    out.push(Polyline {
+        points,
+        closed: true,
+    });
+}
+
+/// Split a polyline into dashes
+fn apply_dashes(points: &[Point], closed: bool, pattern: &[f64]) -> Vec<Vec<Point>> {
+    let mut points = points.to_vec();
+    if closed {
+        points.push(points[0]);
+    }
+
+    let mut dashes = Vec::new();
+    let mut index = 0;
+    let mut remaining = pattern[0].max(0.0);
+    let mut on = true;
+    let mut current: Vec<Point> = vec![points[0]];
+
+    for window in points.windows(2) {
+        let (mut a, b) = (window[0], window[1]);
+        let mut seg_len = a.distance(b);
+        while seg_len > 0.0 {
+            if remaining >= seg_len {
+                remaining -= seg_len;
+                if on {
+                    current.push(b);
+                }
+                break;
+            }
+            let split = a.lerp(b, remaining / seg_len);
+            if on {
+                current.push(split);
+                if current.len() > 1 {
+                    dashes.push(std::mem::take(&mut current));
+                }
+            } else {
+                current = vec![split];
+            }
+            seg_len -= remaining;
+            a = split;
+            on = !on;
+            // Skip zero-length pattern entries without looping forever
+            let mut guard = 0;
+            loop {
+                index = (index + 1) % pattern.len();
+                remaining = pattern[index].max(0.0);
+                if remaining > 0.0 || guard > pattern.len() {
+                    break;
+                }
+                on = !on;
+                guard += 1;
+            }
+            if on {
+                current = vec![a];
+            }
+        }
+    }
+    if on && current.len() > 1 {
+        dashes.push(current);
+    }
+    dashes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::rasterizer::{FillRule, rasterize};
+    use super::*;
+
+    fn line(points: &[(f64, f64)]) -> Polyline {
+        Polyline {
+            points: points.iter().map(|&(x, y)| Point::new(x, y)).collect(),
+            closed: false,
+        }
+    }
+
+    #[test]
+    fn test_stroke_horizontal_line() {
+        let style = StrokeStyle {
+            width: 4.0,
+            cap: LineCap::Flat,
+            ..Default::default()
+        };
+        let outline = stroke(&[line(&[(2.0, 10.0), (18.0, 10.0)])], &style);
+        let mask = rasterize(&outline, FillRule::NonZero, 20, 20).unwrap();
+        assert_eq!(mask.get(10, 8), 255);
+        assert_eq!(mask.get(10, 11), 255);
+        assert_eq!(mask.get(10, 12), 0);
+        assert_eq!(mask.get(1, 10), 0);
+        assert_eq!(mask.get(18, 10), 0);
+    }
+
+    #[test]
+    fn test_square_cap_and_miter_join() {
+        let style = StrokeStyle {
+            width: 2.0,
+            cap: LineCap::Square,
+            join: LineJoin::Miter,
+            ..Default::default()
+        };
+        let outline = stroke(&[line(&[(5.0, 5.0), (15.0, 5.0), (15.0, 15.0)])], &style);
+        let mask = rasterize(&outline, FillRule::NonZero, 20, 20).unwrap();
+        // Square cap extends the start by half the width
+        assert_eq!(mask.get(4, 4), 255);
+        // Miter fills the outer corner
+        assert_eq!(mask.get(15, 4), 255);
+    }
+
+    #[test]
+    fn test_dashes() {
+        let dashes = apply_dashes(
+            &[Point::new(0.0, 0.0), Point::new(10.0, 0.0)],
+            false,
+            &[3.0, 2.0],
+        );
+        assert_eq!(dashes.len(), 2);
+        assert_eq!(dashes[0], vec![Point::new(0.0, 0.0), Point::new(3.0, 0.0)]);
+        assert_eq!(dashes[1], vec![Point::new(5.0, 0.0), Point::new(8.0, 0.0)]);
+    }
+}
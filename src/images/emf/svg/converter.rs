@@ -10,10 +10,12 @@
 /// - Reuse styles via grouping
 use super::{
     buffer::ElementBuffer,
+    emfplus::SvgPlusDevice,
     path::PathBuilder,
     state::{DeviceContext, RenderState},
 };
 use crate::common::error::Result;
+use crate::images::emf::emfplus::{EmfPlusPlayer, RectF};
 use crate::images::emf::parser::EmfParser;
 use crate::images::emf::records::*;
 use crate::images::svg_utils::{write_num, write_xml_escaped};
@@ -36,8 +38,30 @@ impl<'a> EmfSvgConverter<'a> {
         let mut state = RenderState::new();
         let mut buffer = ElementBuffer::new();
 
+        let bounds = self.parser.header.bounds;
+        let mut plus = EmfPlusPlayer::new(RectF {
+            x: bounds.0 as f64,
+            y: bounds.1 as f64,
+            width: (bounds.2 - bounds.0) as f64,
+            height: (bounds.3 - bounds.1) as f64,
+        });
+        let mut plus_device = SvgPlusDevice::new();
+
         // Process all records with buffering for optimization
         for record in &self.parser.records {
+            // EMF+ records embedded in comments replace the GDI fallback
+            if record.record_type == EmrType::Comment as u32
+                && plus.play_comment(&record.data, &mut plus_device)
+            {
+                for element in plus_device.take_elements() {
+                    buffer.add_element(element, &state.dc);
+                }
+                continue;
+            }
+            if !plus.gdi_enabled() {
+                continue;
+            }
+
             if let Some(elements) = self.process_record(record, &mut state)? {
                 for element in elements {
                     buffer.add_element(element, &state.dc);
@@ -49,7 +73,7 @@ impl<'a> EmfSvgConverter<'a> {
         buffer.flush();
 
         // Build final SVG
-        self.build_svg(&buffer.elements, &state)
+        self.build_svg(&buffer.elements, plus_device.defs())
     }
 
     /// Process a single EMF record
@@ -610,7 +634,7 @@ impl<'a> EmfSvgConverter<'a> {
     }
 
     /// Build final SVG document
-    fn build_svg(&self, elements: &[String], _state: &RenderState) -> Result<String> {
+    fn build_svg(&self, elements: &[String], defs: &str) -> Result<String> {
         let header = &self.parser.header;
         let width = header.width();
         let height = header.height();
@@ -620,6 +644,12 @@ impl<'a> EmfSvgConverter<'a> {
             width, height, header.bounds.0, header.bounds.1, width, height
         );
 
+        if !defs.is_empty() {
+            svg.push_str("<defs>");
+            svg.push_str(defs);
+            svg.push_str("</defs>");
+        }
+
         for element in elements {
             svg.push_str(element);
         }
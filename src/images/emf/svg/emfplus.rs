@@ -0,0 +1,631 @@
+/// EMF+ to SVG Output
+///
+/// Collects EMF+ drawing operations as SVG elements. Geometry is written in
+/// world units with the world-to-device transform as a `transform` attribute,
+/// so gradients, patterns and strokes keep their GDI+ geometry. Clipping
+/// regions are in device units and applied through wrapping groups.
+use crate::images::emf::emfplus::objects::{CombineMode, StringAlignment, WrapMode};
+use crate::images::emf::emfplus::{
+    Brush, Color, EmfPlusDevice, Image, Pen, RectF, Region, Text, TextLayout,
+};
+use crate::images::emf::gdi_objects::{LineCap, LineJoin};
+use crate::images::emf::raster::{Path, Segment, Transform};
+use crate::images::svg_utils::{write_num, write_xml_escaped};
+use std::fmt::Write;
+
+use super::path::PathBuilder;
+
+/// Ascent of a typical sans-serif face in ems, for placing the first baseline
+const ASCENT: f64 = 0.905;
+
+/// Line spacing of a typical sans-serif face in ems
+const LINE_SPACING: f64 = 1.15;
+
+/// Size of a hatch pattern cell in device pixels
+const HATCH_SIZE: f64 = 8.0;
+
+/// SVG output device for EMF+ playback
+#[derive(Default)]
+pub struct SvgPlusDevice {
+    /// Drawn elements not yet taken by the converter
+    elements: Vec<String>,
+    /// Gradient, pattern and clip path definitions
+    defs: String,
+    next_id: usize,
+    /// ID of the current clip path
+    clip: Option<String>,
+}
+
+impl SvgPlusDevice {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Take the elements drawn since the last call
+    pub fn take_elements(&mut self) -> Vec<String> {
+        std::mem::take(&mut self.elements)
+    }
+
+    /// Definitions referenced by the elements, for the `<defs>` section
+    pub fn defs(&self) -> &str {
+        &self.defs
+    }
+
+    fn id(&mut self, prefix: &str) -> String {
+        self.next_id += 1;
+        format!("emfplus-{}{}", prefix, self.next_id)
+    }
+
+    /// Add an element, wrapped in the current clipping group
+    fn push(&mut self, element: String) {
+        match &self.clip {
+            Some(clip) => self
+                .elements
+                .push(format!("<g clip-path=\"url(#{})\">{}</g>", clip, element)),
+            None => self.elements.push(element),
+        }
+    }
+
+    /// Write `attribute="paint"` plus its opacity for a brush
+    fn write_paint(
+        &mut self,
+        out: &mut String,
+        attribute: &str,
+        brush: &Brush,
+        to_device: &Transform,
+    ) {
+        let (paint, opacity) = match brush {
+            Brush::Solid(color) => (hex(*color), color.a),
+            Brush::Hatch {
+                style,
+                foreground,
+                background,
+            } => match hatch_lines(*style) {
+                Some(lines) => (
+                    self.hatch_pattern(lines, *foreground, *background, to_device),
+                    255,
+                ),
+                None => {
+                    let color = foreground.lerp(*background, 0.5);
+                    (hex(color), color.a)
+                },
+            },
+            Brush::LinearGradient {
+                rect,
+                stops,
+                transform,
+                wrap,
+            } => (self.linear_gradient(rect, stops, transform, *wrap), 255),
+            Brush::PathGradient {
+                center,
+                boundary,
+                stops,
+                transform,
+                ..
+            } => {
+                let radius = boundary
+                    .iter()
+                    .map(|p| p.distance(*center))
+                    .fold(0.0, f64::max);
+                let id = self.id("gradient");
+                let _ = write!(
+                    self.defs,
+                    "<radialGradient id=\"{}\" gradientUnits=\"userSpaceOnUse\" cx=\"",
+                    id
+                );
+                write_num(&mut self.defs, center.x);
+                self.defs.push_str("\" cy=\"");
+                write_num(&mut self.defs, center.y);
+                self.defs.push_str("\" r=\"");
+                write_num(&mut self.defs, radius);
+                self.defs.push('"');
+                write_matrix(&mut self.defs, "gradientTransform", transform);
+                self.defs.push('>');
+                // Stops run from the boundary to the center, SVG offsets the other way
+                let reversed: Vec<_> = stops.iter().rev().map(|&(p, c)| (1.0 - p, c)).collect();
+                write_stops(&mut self.defs, &reversed);
+                self.defs.push_str("</radialGradient>");
+                (format!("url(#{})", id), 255)
+            },
+            // SVG patterns always tile, so clamped textures repeat as well
+            Brush::Texture {
+                image, transform, ..
+            } => match image_href(image, None) {
+                Some((href, width, height)) => {
+                    let id = self.id("pattern");
+                    let _ = write!(
+                        self.defs,
+                        "<pattern id=\"{}\" patternUnits=\"userSpaceOnUse\" width=\"{}\" height=\"{}\"",
+                        id, width, height
+                    );
+                    write_matrix(&mut self.defs, "patternTransform", transform);
+                    let _ = write!(
+                        self.defs,
+                        "><image width=\"{}\" height=\"{}\" href=\"{}\"/></pattern>",
+                        width, height, href
+                    );
+                    (format!("url(#{})", id), 255)
+                },
+                None => ("none".to_string(), 255),
+            },
+        };
+
+        let _ = write!(out, " {}=\"{}\"", attribute, paint);
+        write_opacity(out, &format!("{}-opacity", attribute), opacity);
+    }
+
+    fn linear_gradient(
+        &mut self,
+        rect: &RectF,
+        stops: &[(f64, Color)],
+        transform: &Transform,
+        wrap: WrapMode,
+    ) -> String {
+        let id = self.id("gradient");
+        let _ = write!(
+            self.defs,
+            "<linearGradient id=\"{}\" gradientUnits=\"userSpaceOnUse\" x1=\"",
+            id
+        );
+        write_num(&mut self.defs, rect.x);
+        self.defs.push_str("\" y1=\"");
+        write_num(&mut self.defs, rect.y);
+        self.defs.push_str("\" x2=\"");
+        write_num(&mut self.defs, rect.right());
+        self.defs.push_str("\" y2=\"");
+        write_num(&mut self.defs, rect.y);
+        self.defs.push('"');
+        let spread = match wrap {
+            WrapMode::Clamp => "pad",
+            WrapMode::Tile | WrapMode::TileFlipY => "repeat",
+            WrapMode::TileFlipX | WrapMode::TileFlipXY => "reflect",
+        };
+        let _ = write!(self.defs, " spreadMethod=\"{}\"", spread);
+        write_matrix(&mut self.defs, "gradientTransform", transform);
+        self.defs.push('>');
+        write_stops(&mut self.defs, stops);
+        self.defs.push_str("</linearGradient>");
+        format!("url(#{})", id)
+    }
+
+    fn hatch_pattern(
+        &mut self,
+        lines: &str,
+        foreground: Color,
+        background: Color,
+        to_device: &Transform,
+    ) -> String {
+        // Hatches keep their device size regardless of the world transform
+        let size = HATCH_SIZE / to_device.mean_scale().max(1e-9);
+        let id = self.id("pattern");
+        let _ = write!(
+            self.defs,
+            "<pattern id=\"{}\" patternUnits=\"userSpaceOnUse\" width=\"",
+            id
+        );
+        write_num(&mut self.defs, size);
+        self.defs.push_str("\" height=\"");
+        write_num(&mut self.defs, size);
+        self.defs.push_str("\" viewBox=\"0 0 8 8\">");
+        if background.a > 0 {
+            let _ = write!(
+                self.defs,
+                "<rect width=\"8\" height=\"8\" fill=\"{}\"",
+                hex(background)
+            );
+            write_opacity(&mut self.defs, "fill-opacity", background.a);
+            self.defs.push_str("/>");
+        }
+        let _ = write!(
+            self.defs,
+            "<path d=\"{}\" stroke=\"{}\" stroke-width=\"1\"",
+            lines,
+            hex(foreground)
+        );
+        write_opacity(&mut self.defs, "stroke-opacity", foreground.a);
+        self.defs.push_str("/></pattern>");
+        format!("url(#{})", id)
+    }
+
+    /// Define a clip path for a region, returning its ID
+    ///
+    /// Intersections nest clip paths. Exclusions and exclusive-or cannot be
+    /// expressed with SVG clip paths and keep the first operand.
+    fn define_clip(&mut self, region: &Region, outer: Option<String>) -> Option<String> {
+        if let Some(paths) = union_paths(region) {
+            let id = self.id("clip");
+            let _ = write!(self.defs, "<clipPath id=\"{}\"", id);
+            if let Some(outer) = outer {
+                let _ = write!(self.defs, " clip-path=\"url(#{})\"", outer);
+            }
+            self.defs.push('>');
+            for path in paths {
+                let _ = write!(
+                    self.defs,
+                    "<path d=\"{}\" clip-rule=\"evenodd\"/>",
+                    path_data(path)
+                );
+            }
+            self.defs.push_str("</clipPath>");
+            return Some(id);
+        }
+
+        match region {
+            Region::Empty => {
+                let id = self.id("clip");
+                let _ = write!(self.defs, "<clipPath id=\"{}\"/>", id);
+                Some(id)
+            },
+            Region::Combine(CombineMode::Intersect, left, right) => {
+                let outer = self.define_clip(left, outer);
+                self.define_clip(right, outer)
+            },
+            Region::Combine(CombineMode::Exclude | CombineMode::Xor, left, _) => {
+                self.define_clip(left, outer)
+            },
+            Region::Combine(CombineMode::Replace | CombineMode::Complement, _, right) => {
+                self.define_clip(right, outer)
+            },
+            _ => outer,
+        }
+    }
+}
+
+impl EmfPlusDevice for SvgPlusDevice {
+    fn fill_path(&mut self, path: &Path, winding: bool, brush: &Brush, to_device: &Transform) {
+        if path.is_empty() {
+            return;
+        }
+        let mut s = String::with_capacity(128);
+        s.push_str("<path");
+        write_matrix(&mut s, "transform", to_device);
+        let _ = write!(s, " d=\"{}\"", path_data(path));
+        self.write_paint(&mut s, "fill", brush, to_device);
+        if !winding {
+            s.push_str(" fill-rule=\"evenodd\"");
+        }
+        s.push_str("/>");
+        self.push(s);
+    }
+
+    fn stroke_path(&mut self, path: &Path, pen: &Pen, width: f64, to_device: &Transform) {
+        if path.is_empty() {
+            return;
+        }
+        let mut s = String::with_capacity(160);
+        s.push_str("<path");
+        write_matrix(&mut s, "transform", to_device);
+        let _ = write!(s, " d=\"{}\" fill=\"none\"", path_data(path));
+        self.write_paint(&mut s, "stroke", &pen.brush, to_device);
+        s.push_str(" stroke-width=\"");
+        write_num(&mut s, width);
+        s.push('"');
+        match pen.end_cap {
+            LineCap::Round => s.push_str(" stroke-linecap=\"round\""),
+            LineCap::Square => s.push_str(" stroke-linecap=\"square\""),
+            LineCap::Flat => {},
+        }
+        match pen.join {
+            LineJoin::Round => s.push_str(" stroke-linejoin=\"round\""),
+            LineJoin::Bevel => s.push_str(" stroke-linejoin=\"bevel\""),
+            LineJoin::Miter => {
+                s.push_str(" stroke-miterlimit=\"");
+                write_num(&mut s, pen.miter_limit.max(1.0));
+                s.push('"');
+            },
+        }
+        if !pen.dashes.is_empty() {
+            s.push_str(" stroke-dasharray=\"");
+            for (i, dash) in pen.dashes.iter().enumerate() {
+                if i > 0 {
+                    s.push(' ');
+                }
+                write_num(&mut s, dash * width);
+            }
+            s.push('"');
+        }
+        s.push_str("/>");
+        self.push(s);
+    }
+
+    fn draw_text(&mut self, text: &Text, to_device: &Transform) {
+        let em = text.em_size;
+        let mut attrs = String::with_capacity(128);
+        write_matrix(&mut attrs, "transform", to_device);
+        let _ = write!(attrs, " font-family=\"");
+        write_xml_escaped(&mut attrs, &text.font.family);
+        attrs.push_str("\" font-size=\"");
+        write_num(&mut attrs, em);
+        attrs.push('"');
+        if text.font.is_bold() {
+            attrs.push_str(" font-weight=\"bold\"");
+        }
+        if text.font.is_italic() {
+            attrs.push_str(" font-style=\"italic\"");
+        }
+        match (text.font.is_underline(), text.font.is_strikeout()) {
+            (true, true) => attrs.push_str(" text-decoration=\"underline line-through\""),
+            (true, false) => attrs.push_str(" text-decoration=\"underline\""),
+            (false, true) => attrs.push_str(" text-decoration=\"line-through\""),
+            (false, false) => {},
+        }
+        self.write_paint(&mut attrs, "fill", text.brush, to_device);
+
+        match &text.layout {
+            TextLayout::Rect {
+                text: string,
+                rect,
+                format,
+            } => {
+                let (alignment, line_alignment) = format
+                    .map_or((StringAlignment::Near, StringAlignment::Near), |format| {
+                        (format.alignment, format.line_alignment)
+                    });
+                let (x, anchor) = match alignment {
+                    StringAlignment::Near => (rect.x, None),
+                    StringAlignment::Center => (rect.x + rect.width / 2.0, Some("middle")),
+                    StringAlignment::Far => (rect.right(), Some("end")),
+                };
+                let lines: Vec<&str> = string
+                    .split('\n')
+                    .map(|l| l.trim_end_matches('\r'))
+                    .collect();
+                let block = lines.len() as f64 * LINE_SPACING * em;
+                let factor = match line_alignment {
+                    StringAlignment::Near => 0.0,
+                    StringAlignment::Center => 0.5,
+                    StringAlignment::Far => 1.0,
+                };
+                let top = rect.y + (rect.height - block) * factor;
+
+                for (i, line) in lines.iter().enumerate() {
+                    if line.is_empty() {
+                        continue;
+                    }
+                    let mut s = String::with_capacity(64 + attrs.len() + line.len());
+                    s.push_str("<text x=\"");
+                    write_num(&mut s, x);
+                    s.push_str("\" y=\"");
+                    write_num(&mut s, top + (i as f64 * LINE_SPACING + ASCENT) * em);
+                    s.push('"');
+                    if let Some(anchor) = anchor {
+                        let _ = write!(s, " text-anchor=\"{}\"", anchor);
+                    }
+                    s.push_str(&attrs);
+                    s.push('>');
+                    write_xml_escaped(&mut s, line);
+                    s.push_str("</text>");
+                    self.push(s);
+                }
+            },
+            TextLayout::Positioned {
+                codes,
+                glyph_indices,
+                positions,
+            } => {
+                // Glyph indices depend on the original font and have no SVG form
+                if *glyph_indices || positions.is_empty() {
+                    return;
+                }
+                let string: String = char::decode_utf16(codes.iter().copied())
+                    .map(|c| c.unwrap_or(char::REPLACEMENT_CHARACTER))
+                    .collect();
+                let mut s = String::with_capacity(64 + attrs.len() + string.len());
+                s.push_str("<text x=\"");
+                // One position per character, or the first one only
+                let positions = if positions.len() == string.chars().count() {
+                    &positions[..]
+                } else {
+                    &positions[..1]
+                };
+                for (i, p) in positions.iter().enumerate() {
+                    if i > 0 {
+                        s.push(' ');
+                    }
+                    write_num(&mut s, p.x);
+                }
+                s.push_str("\" y=\"");
+                for (i, p) in positions.iter().enumerate() {
+                    if i > 0 {
+                        s.push(' ');
+                    }
+                    write_num(&mut s, p.y);
+                }
+                s.push('"');
+                s.push_str(&attrs);
+                s.push('>');
+                write_xml_escaped(&mut s, &string);
+                s.push_str("</text>");
+                self.push(s);
+            },
+        }
+    }
+
+    fn draw_image(&mut self, image: &Image, src: RectF, to_device: &Transform) {
+        let Some((href, width, height)) = image_href(image, Some(&src)) else {
+            return;
+        };
+        let mut s = String::with_capacity(href.len() + 128);
+        s.push_str("<image");
+        write_matrix(&mut s, "transform", to_device);
+        s.push_str(" x=\"");
+        write_num(&mut s, src.x);
+        s.push_str("\" y=\"");
+        write_num(&mut s, src.y);
+        let _ = write!(
+            s,
+            "\" width=\"{}\" height=\"{}\" preserveAspectRatio=\"none\" href=\"{}\"/>",
+            width, height, href
+        );
+        self.push(s);
+    }
+
+    fn set_clip(&mut self, clip: Option<&Region>) {
+        self.clip = clip.and_then(|region| self.define_clip(region, None));
+    }
+
+    fn clear(&mut self, color: Color) {
+        let mut s = String::from("<rect width=\"100%\" height=\"100%\"");
+        let _ = write!(s, " fill=\"{}\"", hex(color));
+        write_opacity(&mut s, "fill-opacity", color.a);
+        s.push_str("/>");
+        self.push(s);
+    }
+}
+
+/// Path data for an SVG `d` attribute
+fn path_data(path: &Path) -> String {
+    let mut builder = PathBuilder::new();
+    for segment in path.segments() {
+        match *segment {
+            Segment::MoveTo(p) => builder.move_to(p.x, p.y),
+            Segment::LineTo(p) => builder.line_to(p.x, p.y),
+            Segment::CubicTo(c1, c2, p) => builder.cubic_to(c1.x, c1.y, c2.x, c2.y, p.x, p.y),
+            Segment::Close => builder.close(),
+        }
+    }
+    builder.build()
+}
+
+/// The paths of a region made only of paths and unions
+fn union_paths(region: &Region) -> Option<Vec<&Path>> {
+    match region {
+        Region::Path(path) => Some(vec![path]),
+        Region::Combine(CombineMode::Union, left, right) => {
+            let mut paths = union_paths(left)?;
+            paths.extend(union_paths(right)?);
+            Some(paths)
+        },
+        _ => None,
+    }
+}
+
+/// Lines of an 8x8 hatch cell for the hatch styles with a GDI equivalent
+fn hatch_lines(style: u32) -> Option<&'static str> {
+    match style {
+        0 => Some("M0 4H8"),
+        1 => Some("M4 0V8"),
+        2 => Some("M0 0L8 8M-1 7L1 9M7 -1L9 1"),
+        3 => Some("M8 0L0 8M-1 1L1 -1M7 9L9 7"),
+        4 => Some("M0 4H8M4 0V8"),
+        5 => Some("M0 0L8 8M8 0L0 8"),
+        _ => None,
+    }
+}
+
+/// PNG data URI of an image, optionally cropped, with its size in pixels
+fn image_href(image: &Image, src: Option<&RectF>) -> Option<(String, u32, u32)> {
+    use base64::Engine;
+
+    let mut pixels = image.to_rgba()?;
+    if let Some(src) = src {
+        let x = src.x.max(0.0).round() as u32;
+        let y = src.y.max(0.0).round() as u32;
+        let width = (src.width.round() as u32).min(pixels.width().saturating_sub(x));
+        let height = (src.height.round() as u32).min(pixels.height().saturating_sub(y));
+        if (x, y, width, height) != (0, 0, pixels.width(), pixels.height()) {
+            pixels = image::imageops::crop_imm(&pixels, x, y, width, height).to_image();
+        }
+    }
+    if pixels.width() == 0 || pixels.height() == 0 {
+        return None;
+    }
+
+    let mut png = Vec::new();
+    pixels
+        .write_to(&mut std::io::Cursor::new(&mut png), image::ImageFormat::Png)
+        .ok()?;
+    let encoded = base64::engine::general_purpose::STANDARD.encode(png);
+    Some((
+        format!("data:image/png;base64,{}", encoded),
+        pixels.width(),
+        pixels.height(),
+    ))
+}
+
+fn hex(color: Color) -> String {
+    format!("#{:02x}{:02x}{:02x}", color.r, color.g, color.b)
+}
+
+fn write_opacity(out: &mut String, attribute: &str, alpha: u8) {
+    if alpha < 255 {
+        let _ = write!(out, " {}=\"", attribute);
+        write_num(out, alpha as f64 / 255.0);
+        out.push('"');
+    }
+}
+
+/// Write a matrix attribute, omitted for the identity
+fn write_matrix(out: &mut String, attribute: &str, t: &Transform) {
+    if *t == Transform::IDENTITY {
+        return;
+    }
+    let _ = write!(out, " {}=\"matrix(", attribute);
+    for (i, value) in [t.a, t.b, t.c, t.d, t.e, t.f].into_iter().enumerate() {
+        if i > 0 {
+            out.push(' ');
+        }
+        // Matrix entries need more precision than coordinates
+        let _ = write!(out, "{}", (value * 1e6).round() / 1e6);
+    }
+    out.push_str(")\"");
+}
+
+fn write_stops(out: &mut String, stops: &[(f64, Color)]) {
+    for &(offset, color) in stops {
+        out.push_str("<stop offset=\"");
+        write_num(out, offset.clamp(0.0, 1.0));
+        let _ = write!(out, "\" stop-color=\"{}\"", hex(color));
+        write_opacity(out, "stop-opacity", color.a);
+        out.push_str("/>");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::images::emf::raster::Point;
+
+    #[test]
+    fn test_fill_with_gradient_and_clip() {
+        let mut device = SvgPlusDevice::new();
+        let clip = Region::Path(Path::rect(0.0, 0.0, 50.0, 50.0));
+        device.set_clip(Some(&clip));
+
+        let brush = Brush::LinearGradient {
+            rect: RectF {
+                x: 0.0,
+                y: 0.0,
+                width: 100.0,
+                height: 10.0,
+            },
+            stops: vec![
+                (0.0, Color::from_argb(0xFFFF_0000)),
+                (1.0, Color::from_argb(0x800000FF)),
+            ],
+            transform: Transform::IDENTITY,
+            wrap: WrapMode::Clamp,
+        };
+        let mut path = Path::new();
+        path.move_to(Point::new(0.0, 0.0));
+        path.line_to(Point::new(100.0, 0.0));
+        path.line_to(Point::new(100.0, 10.0));
+        path.close();
+        device.fill_path(&path, false, &brush, &Transform::scale(2.0, 2.0));
+
+        let elements = device.take_elements();
+        assert_eq!(elements.len(), 1);
+        let element = &elements[0];
+        assert!(element.starts_with("<g clip-path=\"url(#emfplus-clip1)\">"));
+        assert!(element.contains("transform=\"matrix(2 0 0 2 0 0)\""));
+        assert!(element.contains("fill=\"url(#emfplus-gradient2)\""));
+        assert!(element.contains("fill-rule=\"evenodd\""));
+
+        let defs = device.defs();
+        assert!(defs.contains("<clipPath id=\"emfplus-clip1\">"));
+        assert!(defs.contains("spreadMethod=\"pad\""));
+        assert!(defs.contains("stop-color=\"#ff0000\""));
+        assert!(defs.contains("stop-color=\"#0000ff\" stop-opacity=\"0.5\""));
+        assert!(device.take_elements().is_empty());
+    }
+}
@@ -3,6 +3,7 @@
 /// High-performance SVG generation with SVGO-like optimization
 mod buffer;
 pub mod converter;
+pub mod emfplus;
 pub mod path;
 pub mod state;
 
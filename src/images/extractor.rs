@@ -4,6 +4,7 @@
 // from Microsoft Office documents (PPT, DOC) using the Escher drawing layer.
 
 use crate::common::error::Result;
+use crate::common::{EmbeddedImage, ImageAnchor};
 use crate::images::{Blip, BlipStore, BlipStoreEntry};
 use crate::ole::ppt::escher::{EscherContainer, EscherParser, EscherRecordType};
 use std::borrow::Cow;
@@ -72,6 +73,23 @@ impl<'data> ExtractedImage<'data> {
         }
     }
 
+    /// Convert into a format-independent [`EmbeddedImage`].
+    ///
+    /// Metafiles are decompressed and WMF data gets its placeable header, so
+    /// the result is a standalone file in the BLIP's native format.
+    pub fn to_embedded_image(&self, anchor: ImageAnchor) -> Result<EmbeddedImage> {
+        let data = self.blip.get_picture_data_for_conversion()?.into_owned();
+        let stem = match &self.name {
+            Some(name) => name.split('.').next().unwrap_or(name).to_string(),
+            None => format!("image_{:03}", self.index),
+        };
+        Ok(EmbeddedImage::new(
+            format!("{}.{}", stem, self.extension()),
+            data,
+            anchor,
+        ))
+    }
+
     /// Get raw picture data
     pub fn raw_data(&self) -> &[u8] {
         self.blip.picture_data()
@@ -293,7 +311,7 @@ impl ImageExtractor {
     ///
     /// # Returns
     /// Vector of extracted images
-    fn search_blips_in_data(data: &[u8]) -> Result<Vec<ExtractedImage<'static>>> {
+    pub(crate) fn search_blips_in_data(data: &[u8]) -> Result<Vec<ExtractedImage<'static>>> {
         let mut images = Vec::new();
         let mut index = 0;
 
@@ -21,6 +21,10 @@
 // - `extractor`: High-level image extraction from Office files
 // - `svg`: SVG conversion utilities
 //
+// BLIP parsing and extraction are available with the `ole` feature; the
+// converters (`emf`, `wmf`, `pict`, `svg` and the `convert_*` functions)
+// need `imgconv`.
+//
 // # Quick Start: Extract Images from Office Files
 //
 // ```no_run
@@ -93,17 +97,23 @@
 /// - Corrupted metafile data (returns error but continues extraction)
 pub mod blip;
 pub mod bse;
+#[cfg(feature = "imgconv")]
 pub mod emf;
 pub mod extractor;
+#[cfg(feature = "imgconv")]
 pub mod pict;
+#[cfg(feature = "imgconv")]
 pub mod svg;
+#[cfg(feature = "imgconv")]
 pub mod svg_utils;
+#[cfg(feature = "imgconv")]
 pub mod wmf;
 
 use crate::common::error::Result;
 pub use blip::{BitmapBlip, Blip, BlipType, MetafileBlip, RecordHeader};
 pub use bse::{BlipStore, BlipStoreEntry};
 pub use extractor::{ExtractedImage, ImageExtractor};
+#[cfg(feature = "imgconv")]
 use image::ImageFormat;
 
 /// Convert a BLIP record to a raster image format
@@ -119,6 +129,7 @@ use image::ImageFormat;
 ///
 /// # Returns
 /// Encoded image bytes in the target format
+#[cfg(feature = "imgconv")]
 pub fn convert_blip_to_format<'data>(
     blip: &Blip<'data>,
     format: ImageFormat,
@@ -137,55 +148,71 @@ pub fn convert_blip_to_format<'data>(
                 )),
             }
         },
-        Blip::Bitmap(bitmap) => {
-            // For bitmap formats that are already in a modern format, we may just need
-            // to re-encode or pass through
-            let img = image::load_from_memory(&bitmap.picture_data[..]).map_err(|e| {
-                crate::common::error::Error::ParseError(format!("Failed to load bitmap: {}", e))
-            })?;
-
-            // Resize if requested
-            let img = match (width, height) {
-                (Some(w), Some(h)) if img.width() != w || img.height() != h => {
-                    image::DynamicImage::ImageRgba8(image::imageops::resize(
-                        &img,
-                        w,
-                        h,
-                        image::imageops::FilterType::Lanczos3,
-                    ))
-                },
-                (Some(w), None) => {
-                    let aspect = img.height() as f64 / img.width() as f64;
-                    let h = (w as f64 * aspect) as u32;
-                    image::DynamicImage::ImageRgba8(image::imageops::resize(
-                        &img,
-                        w,
-                        h,
-                        image::imageops::FilterType::Lanczos3,
-                    ))
-                },
-                (None, Some(h)) => {
-                    let aspect = img.width() as f64 / img.height() as f64;
-                    let w = (h as f64 * aspect) as u32;
-                    image::DynamicImage::ImageRgba8(image::imageops::resize(
-                        &img,
-                        w,
-                        h,
-                        image::imageops::FilterType::Lanczos3,
-                    ))
-                },
-                _ => img,
-            };
+        Blip::Bitmap(bitmap) => convert_bitmap(&bitmap.picture_data[..], format, width, height),
+    }
+}
 
-            // Encode to target format
-            let mut buffer = std::io::Cursor::new(Vec::new());
-            img.write_to(&mut buffer, format).map_err(|e| {
-                crate::common::error::Error::ParseError(format!("Failed to encode image: {}", e))
-            })?;
+/// Re-encode a bitmap (PNG, JPEG, GIF, BMP, TIFF, ...) into another raster format
+///
+/// # Arguments
+/// * `data` - Encoded bitmap bytes
+/// * `format` - Target image format
+/// * `width` - Optional output width (aspect ratio is kept when only one side is given)
+/// * `height` - Optional output height
+///
+/// # Returns
+/// Encoded image bytes in the target format
+#[cfg(feature = "imgconv")]
+pub fn convert_bitmap(
+    data: &[u8],
+    format: ImageFormat,
+    width: Option<u32>,
+    height: Option<u32>,
+) -> Result<Vec<u8>> {
+    let img = image::load_from_memory(data).map_err(|e| {
+        crate::common::error::Error::ParseError(format!("Failed to load bitmap: {}", e))
+    })?;
 
-            Ok(buffer.into_inner())
+    // Resize if requested
+    let img = match (width, height) {
+        (Some(w), Some(h)) if img.width() != w || img.height() != h => {
+            image::DynamicImage::ImageRgba8(image::imageops::resize(
+                &img,
+                w,
+                h,
+                image::imageops::FilterType::Lanczos3,
+            ))
         },
-    }
+        (Some(w), None) => {
+            let aspect = img.height() as f64 / img.width() as f64;
+            let h = (w as f64 * aspect) as u32;
+            image::DynamicImage::ImageRgba8(image::imageops::resize(
+                &img,
+                w,
+                h,
+                image::imageops::FilterType::Lanczos3,
+            ))
+        },
+        (None, Some(h)) => {
+            let aspect = img.width() as f64 / img.height() as f64;
+            let w = (h as f64 * aspect) as u32;
+            image::DynamicImage::ImageRgba8(image::imageops::resize(
+                &img,
+                w,
+                h,
+                image::imageops::FilterType::Lanczos3,
+            ))
+        },
+        _ => img,
+    };
+
+    // Encode to target format
+    let mut buffer = std::io::Cursor::new(Vec::new());
+    img.write_to(&mut buffer, format).map_err(|e| {
+        crate::common::error::Error::ParseError(format!("Failed to encode image: {}", e))
+    })?;
+
+    Ok(buffer.into_inner())
 }
 
 /// Convert a BLIP record to PNG format
@@ -207,6 +234,7 @@ pub fn convert_blip_to_format<'data>(
 /// let png = convert_blip_to_png(&blip, Some(800), None)?;
 /// # Ok::<(), litchi::common::error::Error>(())
 /// ```
+#[cfg(feature = "imgconv")]
 pub fn convert_blip_to_png<'data>(
     blip: &Blip<'data>,
     width: Option<u32>,
@@ -216,6 +244,7 @@ pub fn convert_blip_to_png<'data>(
 }
 
 /// Convert a BLIP record to JPEG format
+#[cfg(feature = "imgconv")]
 pub fn convert_blip_to_jpeg<'data>(
     blip: &Blip<'data>,
     width: Option<u32>,
@@ -225,6 +254,7 @@ pub fn convert_blip_to_jpeg<'data>(
 }
 
 /// Convert a BLIP record to WebP format
+#[cfg(feature = "imgconv")]
 pub fn convert_blip_to_webp<'data>(
     blip: &Blip<'data>,
     width: Option<u32>,
@@ -15,6 +15,7 @@ use plist::Value;
 use zip::ZipArchive;
 
 use crate::iwa::archive::{Archive, ArchiveObject};
+use crate::iwa::media::MediaManager;
 use crate::iwa::zip_utils::parse_iwa_files_from_zip;
 use crate::iwa::{Error, Result};
 
@@ -27,6 +28,8 @@ pub struct Bundle {
     archives: HashMap<String, Archive>,
     /// Metadata from Metadata/ directory
     metadata: BundleMetadata,
+    /// Media assets from the Data/ directory
+    media: MediaManager,
 }
 
 impl Bundle {
@@ -71,7 +74,7 @@ impl Bundle {
     /// ```
     pub fn from_bytes(bytes: &[u8]) -> Result<Self> {
         // Parse the ZIP archive directly from bytes
-        let archives = Self::parse_zip_bytes(bytes)?;
+        let (archives, media) = Self::parse_zip_bytes(bytes)?;
 
         // For single-file bundles, metadata is typically embedded
         let metadata = BundleMetadata {
@@ -88,6 +91,7 @@ impl Bundle {
             bundle_path: std::path::PathBuf::from("<bytes>"), // Placeholder path
             archives,
             metadata,
+            media,
         })
     }
 
@@ -98,6 +102,7 @@ impl Bundle {
     pub fn from_zip_archive(mut zip_archive: ZipArchive<std::io::Cursor<Vec<u8>>>) -> Result<Self> {
         // Parse IWA files from the ZIP archive
         let archives = parse_iwa_files_from_zip(&mut zip_archive)?;
+        let media = MediaManager::from_zip_archive(&mut zip_archive)?;
 
         // For single-file bundles, metadata is typically embedded
         let metadata = BundleMetadata {
@@ -114,6 +119,7 @@ impl Bundle {
             bundle_path: std::path::PathBuf::from("<zip_archive>"), // Placeholder path
             archives,
             metadata,
+            media,
         })
     }
 
@@ -128,17 +134,21 @@ impl Bundle {
         // Parse metadata
         let metadata = Self::parse_metadata(bundle_path)?;
 
+        // Index media assets in Data/
+        let media = MediaManager::new(bundle_path)?;
+
         Ok(Bundle {
             bundle_path: bundle_path.to_path_buf(),
             archives,
             metadata,
+            media,
         })
     }
 
     /// Open a single-file bundle (zip archive)
     fn open_file_bundle(bundle_path: &Path) -> Result<Self> {
         // Parse the zip file directly
-        let archives = Self::parse_zip_bundle(bundle_path)?;
+        let (archives, media) = Self::parse_zip_bundle(bundle_path)?;
 
         // For single-file bundles, metadata is typically embedded
         let metadata = BundleMetadata {
@@ -155,6 +165,7 @@ impl Bundle {
             bundle_path: bundle_path.to_path_buf(),
             archives,
             metadata,
+            media,
         })
     }
 
@@ -186,23 +197,27 @@ impl Bundle {
         parse_iwa_files_from_zip(&mut zip_archive)
     }
 
-    /// Parse a single-file bundle (zip archive) and extract all IWA files
-    fn parse_zip_bundle(bundle_path: &Path) -> Result<HashMap<String, Archive>> {
+    /// Parse a single-file bundle (zip archive) and extract all IWA files and media
+    fn parse_zip_bundle(bundle_path: &Path) -> Result<(HashMap<String, Archive>, MediaManager)> {
         let file = fs::File::open(bundle_path).map_err(Error::Io)?;
 
         let mut zip_archive = ZipArchive::new(file)
             .map_err(|e| Error::Bundle(format!("Failed to open bundle file: {}", e)))?;
 
-        parse_iwa_files_from_zip(&mut zip_archive)
+        let archives = parse_iwa_files_from_zip(&mut zip_archive)?;
+        let media = MediaManager::from_zip_archive(&mut zip_archive)?;
+        Ok((archives, media))
     }
 
-    /// Parse a ZIP archive from raw bytes and extract all IWA files
-    fn parse_zip_bytes(bytes: &[u8]) -> Result<HashMap<String, Archive>> {
+    /// Parse a ZIP archive from raw bytes and extract all IWA files and media
+    fn parse_zip_bytes(bytes: &[u8]) -> Result<(HashMap<String, Archive>, MediaManager)> {
         let cursor = Cursor::new(bytes);
         let mut zip_archive = ZipArchive::new(cursor)
             .map_err(|e| Error::Bundle(format!("Failed to open ZIP archive from bytes: {}", e)))?;
 
-        parse_iwa_files_from_zip(&mut zip_archive)
+        let archives = parse_iwa_files_from_zip(&mut zip_archive)?;
+        let media = MediaManager::from_zip_archive(&mut zip_archive)?;
+        Ok((archives, media))
     }
 
     /// Parse metadata from Metadata/ directory
@@ -329,6 +344,11 @@ impl Bundle {
         &self.bundle_path
     }
 
+    /// Get the media assets stored in the bundle's Data/ directory
+    pub fn media(&self) -> &MediaManager {
+        &self.media
+    }
+
     /// Validate the bundle structure and integrity
     ///
     /// Performs comprehensive validation including:
@@ -6,9 +6,10 @@
 
 use std::collections::HashMap;
 use std::fs;
-use std::io::Read;
+use std::io::{Read, Seek};
 use std::path::{Path, PathBuf};
 
+use crate::common::{EmbeddedImage, ImageAnchor};
 use crate::iwa::{Error, Result};
 
 /// Types of media assets that can be found in iWork documents
@@ -111,6 +112,8 @@ pub struct MediaManager {
     bundle_path: PathBuf,
     /// Map of media assets by filename
     assets: HashMap<String, MediaAsset>,
+    /// Asset contents read from single-file (zip) bundles, keyed by filename
+    embedded: HashMap<String, Vec<u8>>,
 }
 
 impl MediaManager {
@@ -127,6 +130,37 @@ impl MediaManager {
         Ok(Self {
             bundle_path,
             assets,
+            embedded: HashMap::new(),
+        })
+    }
+
+    /// Create a media manager from the `Data/` entries of a single-file bundle
+    ///
+    /// The asset contents are read into memory since the archive is not kept.
+    pub fn from_zip_archive<R: Read + Seek>(archive: &mut zip::ZipArchive<R>) -> Result<Self> {
+        let mut assets = HashMap::new();
+        let mut embedded = HashMap::new();
+
+        for i in 0..archive.len() {
+            let mut file = archive
+                .by_index(i)
+                .map_err(|e| Error::Bundle(format!("Failed to read zip entry: {}", e)))?;
+            if file.is_dir() || !file.name().starts_with("Data/") {
+                continue;
+            }
+
+            let asset = MediaAsset::new(PathBuf::from(file.name()), file.size());
+            let mut data = Vec::with_capacity(file.size() as usize);
+            file.read_to_end(&mut data).map_err(Error::Io)?;
+
+            embedded.insert(asset.filename.clone(), data);
+            assets.insert(asset.filename.clone(), asset);
+        }
+
+        Ok(Self {
+            bundle_path: PathBuf::new(),
+            assets,
+            embedded,
         })
     }
 
@@ -214,6 +248,10 @@ impl MediaManager {
             .get(filename)
             .ok_or_else(|| Error::Bundle(format!("Media asset not found: {}", filename)))?;
 
+        if let Some(data) = self.embedded.get(filename) {
+            return Ok(data.clone());
+        }
+
         let full_path = self.bundle_path.join(&asset.path);
         let mut file = fs::File::open(&full_path).map_err(Error::Io)?;
         let mut data = Vec::new();
@@ -221,6 +259,27 @@ impl MediaManager {
         Ok(data)
     }
 
+    /// Read all image assets as [`EmbeddedImage`]s, sorted by filename
+    ///
+    /// Placement is not resolved from the IWA object graph, so the images
+    /// are unanchored.
+    pub fn embedded_images(&self) -> Result<Vec<EmbeddedImage>> {
+        let mut assets = self.images();
+        assets.sort_by(|a, b| a.filename.cmp(&b.filename));
+
+        assets
+            .into_iter()
+            .map(|asset| {
+                let data = self.extract(&asset.filename)?;
+                Ok(EmbeddedImage::new(
+                    asset.filename.clone(),
+                    data,
+                    ImageAnchor::Unanchored,
+                ))
+            })
+            .collect()
+    }
+
     /// Extract a media asset to a file
     pub fn extract_to_file(&self, filename: &str, output_path: &Path) -> Result<()> {
         let data = self.extract(filename)?;
@@ -163,7 +163,9 @@ pub use media::{MediaAsset, MediaManager, MediaStats, MediaType};
 pub use ref_graph::ReferenceGraph;
 pub use snappy::SnappyStream;
 pub use structured::{CellValue, Section, Slide, StructuredData, Table};
-pub use text::{ParagraphStyle, TextExtractor, TextFragment, TextStorage, TextStyle};
+pub use text::{
+    ParagraphStyle, TextExtractor, TextFragment, TextParagraph, TextSpan, TextStorage, TextStyle,
+};
 pub use zip_utils::{
     FileStructureInfo, analyze_file_structure, extract_message_types_from_zip,
     parse_iwa_files_from_zip,
@@ -4,12 +4,16 @@
 
 use std::path::Path;
 
+use prost::Message;
+
 use super::section::{PagesSection, PagesSectionType};
 use crate::iwa::Result;
 use crate::iwa::bundle::Bundle;
 use crate::iwa::object_index::ObjectIndex;
+use crate::iwa::protobuf::tswp;
 use crate::iwa::registry::Application;
-use crate::iwa::text::TextExtractor;
+use crate::iwa::text::paragraph::STORAGE_ARCHIVE_TYPE;
+use crate::iwa::text::{StorageParagraphs, TextExtractor, TextParagraph};
 
 /// High-level interface for Pages documents
 pub struct PagesDocument {
@@ -186,6 +190,71 @@ impl PagesDocument {
         Ok(sections)
     }
 
+    /// Extract the paragraphs of the document's text storages
+    ///
+    /// Each paragraph carries its paragraph style name, the heading level
+    /// and alignment of that style, its list level and format, and the
+    /// targets of hyperlink fields. The body storage comes first, followed
+    /// by headers, footnotes and text boxes. Documents whose storages cannot
+    /// be decoded fall back to the unstyled text of [`sections`](Self::sections).
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use litchi::iwa::pages::PagesDocument;
+    ///
+    /// let doc = PagesDocument::open("document.pages")?;
+    /// for para in doc.paragraphs()? {
+    ///     if let Some(level) = para.heading_level {
+    ///         println!("{} {}", "#".repeat(level as usize), para.text());
+    ///     }
+    /// }
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn paragraphs(&self) -> Result<Vec<TextParagraph>> {
+        let mut storages = Vec::new();
+        for (_archive_name, object) in self.bundle.find_objects_by_type(STORAGE_ARCHIVE_TYPE) {
+            let messages = object
+                .messages
+                .iter()
+                .filter(|message| message.type_ == STORAGE_ARCHIVE_TYPE);
+            for message in messages {
+                if let Ok(storage) = tswp::StorageArchive::decode(&*message.data) {
+                    // Kind defaults to TEXTBOX (3)
+                    let kind = storage.kind.unwrap_or(3);
+                    let id = object.archive_info.identifier.unwrap_or_default();
+                    storages.push((kind, id, storage));
+                }
+            }
+        }
+        // Archives are not ordered; put the body first and keep the rest stable
+        storages.sort_by_key(|(kind, id, _)| (*kind, *id));
+
+        let lookup = |id: u64| {
+            self.object_index
+                .resolve_object(&self.bundle, id)
+                .ok()
+                .flatten()
+                .and_then(|object| object.messages.into_iter().next())
+        };
+        let mut resolver = StorageParagraphs::new(&lookup);
+        let paragraphs: Vec<TextParagraph> = storages
+            .iter()
+            .flat_map(|(_, _, storage)| resolver.paragraphs(storage))
+            .collect();
+        if !paragraphs.is_empty() {
+            return Ok(paragraphs);
+        }
+
+        let paragraphs = self
+            .sections()?
+            .into_iter()
+            .flat_map(|section| section.paragraphs)
+            .map(TextParagraph::from_text)
+            .collect();
+        Ok(paragraphs)
+    }
+
     /// Parse a single section from an object
     fn parse_section(
         &self,
@@ -4,9 +4,11 @@
 //! Pages, Numbers, and Keynote documents.
 
 pub mod extractor;
+pub mod paragraph;
 pub mod storage;
 pub mod style;
 
 pub use extractor::TextExtractor;
+pub use paragraph::{StorageParagraphs, TextParagraph, TextSpan};
 pub use storage::{TextFragment, TextRun, TextStorage};
 pub use style::{ParagraphStyle, TextStyle};
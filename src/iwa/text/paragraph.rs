@@ -0,0 +1,659 @@
+//! Paragraph Structure of Text Storages
+//!
+//! A TSWP.StorageArchive holds its text as one string and describes its
+//! structure in attribute tables keyed by character index: paragraph styles
+//! (`table_para_style`), character styles (`table_char_style`), list styles
+//! (`table_list_style`) with the list level of each paragraph
+//! (`table_para_data`), and smart fields such as hyperlinks
+//! (`table_smartfield`). This module splits the text into paragraphs and
+//! resolves those tables against the style and field objects they reference.
+
+use std::collections::HashMap;
+
+use prost::Message;
+
+use super::style::TextStyle;
+use crate::common::{ListFormat, ListInfo, TextAlignment, VerticalPosition};
+use crate::iwa::archive::RawMessage;
+use crate::iwa::protobuf::{tsp, tswp};
+
+/// Message type of TSWP.StorageArchive
+pub const STORAGE_ARCHIVE_TYPE: u32 = 2001;
+/// Message type of TSWP.CharacterStyleArchive
+const CHARACTER_STYLE_TYPE: u32 = 2021;
+/// Message type of TSWP.ParagraphStyleArchive
+const PARAGRAPH_STYLE_TYPE: u32 = 2022;
+/// Message type of TSWP.ListStyleArchive
+const LIST_STYLE_TYPE: u32 = 2023;
+/// Message type of TSWP.HyperlinkFieldArchive
+const HYPERLINK_FIELD_TYPE: u32 = 2032;
+/// Deepest style parent chain followed
+const MAX_STYLE_DEPTH: usize = 16;
+
+/// A paragraph of a text storage with its structure resolved.
+#[derive(Debug, Clone, Default)]
+pub struct TextParagraph {
+    /// Spans of text, split where the formatting or hyperlink target changes
+    pub spans: Vec<TextSpan>,
+    /// Name of the paragraph style, such as "Heading 2"
+    pub style_name: Option<String>,
+    /// Heading level (1-9) given by the paragraph style
+    pub heading_level: Option<u8>,
+    /// Alignment set by the paragraph style; `None` for natural alignment
+    pub alignment: Option<TextAlignment>,
+    /// List membership from the list style and list level
+    pub list: Option<ListInfo>,
+}
+
+impl TextParagraph {
+    /// Create an unstyled paragraph from plain text
+    pub fn from_text(text: String) -> Self {
+        Self {
+            spans: vec![TextSpan {
+                text,
+                ..TextSpan::default()
+            }],
+            ..Self::default()
+        }
+    }
+
+    /// Get the text of the paragraph
+    pub fn text(&self) -> String {
+        self.spans.iter().map(|span| span.text.as_str()).collect()
+    }
+}
+
+/// A span of text within a paragraph
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct TextSpan {
+    /// The text content
+    pub text: String,
+    /// Character formatting from the character and paragraph styles
+    pub style: TextStyle,
+    /// Target URL of the hyperlink field the span is in
+    pub hyperlink: Option<String>,
+}
+
+/// Paragraph style properties, with the parent styles applied
+#[derive(Debug, Clone, Default)]
+struct ResolvedParagraphStyle {
+    name: Option<String>,
+    alignment: Option<TextAlignment>,
+    list_style: Option<u64>,
+    /// Character properties of the style and its parents, nearest first
+    char_properties: Vec<tswp::CharacterStylePropertiesArchive>,
+}
+
+/// Splits text storages into paragraphs, resolving the objects their
+/// attribute tables refer to.
+///
+/// Objects are looked up by identifier through `lookup`, which returns the
+/// object's primary message. Resolved styles and fields are cached.
+pub struct StorageParagraphs<'a> {
+    lookup: &'a dyn Fn(u64) -> Option<RawMessage>,
+    paragraph_styles: HashMap<u64, ResolvedParagraphStyle>,
+    /// Formatting by paragraph style and character style
+    text_styles: HashMap<(Option<u64>, Option<u64>), TextStyle>,
+    hyperlinks: HashMap<u64, Option<String>>,
+}
+
+impl<'a> StorageParagraphs<'a> {
+    /// Create a resolver looking objects up with `lookup`
+    pub fn new(lookup: &'a dyn Fn(u64) -> Option<RawMessage>) -> Self {
+        Self {
+            lookup,
+            paragraph_styles: HashMap::new(),
+            text_styles: HashMap::new(),
+            hyperlinks: HashMap::new(),
+        }
+    }
+
+    /// Split a storage into paragraphs.
+    ///
+    /// Paragraphs end at line feeds and paragraph separators; attachment
+    /// placeholders (U+FFFC) are dropped.
+    pub fn paragraphs(&mut self, storage: &tswp::StorageArchive) -> Vec<TextParagraph> {
+        let text = storage.text.concat();
+        let para_styles = object_entries(storage.table_para_style.as_ref());
+        let char_styles = object_entries(storage.table_char_style.as_ref());
+        let list_styles = object_entries(storage.table_list_style.as_ref());
+        let fields = object_entries(storage.table_smartfield.as_ref());
+        let list_levels: Vec<(u32, u32)> = storage
+            .table_para_data
+            .iter()
+            .flat_map(|table| &table.entries)
+            .map(|entry| (entry.character_index, entry.first))
+            .collect();
+
+        let mut paragraphs = Vec::new();
+        let mut spans: Vec<TextSpan> = Vec::new();
+        // Character indices count UTF-16 code units
+        let mut index = 0u32;
+        let mut paragraph_start = 0u32;
+
+        for ch in text.chars() {
+            match ch {
+                '\n' | '\u{2029}' => {
+                    let spans = std::mem::take(&mut spans);
+                    paragraphs.push(self.paragraph(
+                        spans,
+                        entry_at(&para_styles, paragraph_start).flatten(),
+                        entry_at(&list_styles, paragraph_start).flatten(),
+                        entry_at(&list_levels, paragraph_start).unwrap_or(0),
+                    ));
+                    paragraph_start = index + 1;
+                },
+                '\u{FFFC}' => {},
+                _ => {
+                    let style = self.text_style(
+                        entry_at(&para_styles, paragraph_start).flatten(),
+                        entry_at(&char_styles, index).flatten(),
+                    );
+                    let hyperlink = entry_at(&fields, index)
+                        .flatten()
+                        .and_then(|field| self.hyperlink(field));
+                    match spans.last_mut() {
+                        Some(span) if span.style == style && span.hyperlink == hyperlink => {
+                            span.text.push(ch)
+                        },
+                        _ => spans.push(TextSpan {
+                            text: ch.to_string(),
+                            style,
+                            hyperlink,
+                        }),
+                    }
+                },
+            }
+            index += ch.len_utf16() as u32;
+        }
+
+        if !spans.is_empty() {
+            paragraphs.push(self.paragraph(
+                spans,
+                entry_at(&para_styles, paragraph_start).flatten(),
+                entry_at(&list_styles, paragraph_start).flatten(),
+                entry_at(&list_levels, paragraph_start).unwrap_or(0),
+            ));
+        }
+        paragraphs
+    }
+
+    /// Build a paragraph from its spans and the table entries at its start.
+    fn paragraph(
+        &mut self,
+        spans: Vec<TextSpan>,
+        style: Option<u64>,
+        list_style: Option<u64>,
+        list_level: u32,
+    ) -> TextParagraph {
+        let style = style
+            .map(|style| self.paragraph_style(style))
+            .unwrap_or_default();
+        let level = list_level.min(8) as u8;
+        let list = list_style
+            .or(style.list_style)
+            .and_then(|list_style| self.list_format(list_style, level))
+            .map(|format| ListInfo::new(level, format));
+
+        TextParagraph {
+            spans,
+            heading_level: style.name.as_deref().and_then(heading_level_from_name),
+            style_name: style.name,
+            alignment: style.alignment,
+            list,
+        }
+    }
+
+    /// Resolve a paragraph style, taking unset properties from its parents.
+    fn paragraph_style(&mut self, id: u64) -> ResolvedParagraphStyle {
+        if let Some(style) = self.paragraph_styles.get(&id) {
+            return style.clone();
+        }
+
+        let mut resolved = ResolvedParagraphStyle::default();
+        let mut list_style_set = false;
+        let mut current = Some(id);
+        for _ in 0..MAX_STYLE_DEPTH {
+            let Some(style) = current.and_then(|id| {
+                self.decode::<tswp::ParagraphStyleArchive>(id, PARAGRAPH_STYLE_TYPE)
+            }) else {
+                break;
+            };
+            if resolved.name.is_none() {
+                resolved.name = style.super_.name.clone().filter(|name| !name.is_empty());
+            }
+            resolved
+                .char_properties
+                .extend(style.char_properties.clone());
+            if let Some(properties) = &style.para_properties {
+                if resolved.alignment.is_none() {
+                    resolved.alignment = properties.alignment.and_then(alignment_from_iwork);
+                }
+                if !list_style_set && properties.list_style_null == Some(true) {
+                    list_style_set = true;
+                } else if !list_style_set && let Some(list_style) = &properties.list_style {
+                    resolved.list_style = Some(list_style.identifier);
+                    list_style_set = true;
+                }
+            }
+            current = style.super_.parent.as_ref().map(|parent| parent.identifier);
+        }
+
+        self.paragraph_styles.insert(id, resolved.clone());
+        resolved
+    }
+
+    /// Resolve the formatting of text with a character style in a paragraph
+    /// with a paragraph style; character style properties take precedence.
+    fn text_style(&mut self, paragraph_style: Option<u64>, char_style: Option<u64>) -> TextStyle {
+        let key = (paragraph_style, char_style);
+        if let Some(style) = self.text_styles.get(&key) {
+            return style.clone();
+        }
+
+        let mut chain = Vec::new();
+        let mut current = char_style;
+        for _ in 0..MAX_STYLE_DEPTH {
+            let Some(style) = current.and_then(|id| {
+                self.decode::<tswp::CharacterStyleArchive>(id, CHARACTER_STYLE_TYPE)
+            }) else {
+                break;
+            };
+            chain.extend(style.char_properties);
+            current = style.super_.parent.as_ref().map(|parent| parent.identifier);
+        }
+        if let Some(id) = paragraph_style {
+            chain.extend(self.paragraph_style(id).char_properties);
+        }
+
+        let style = text_style(&chain);
+        self.text_styles.insert(key, style.clone());
+        style
+    }
+
+    /// Get the format of a level of a list style, or `None` if the level has
+    /// no label, which is how paragraphs outside a list are styled.
+    fn list_format(&self, id: u64, level: u8) -> Option<ListFormat> {
+        let mut current = Some(id);
+        for _ in 0..MAX_STYLE_DEPTH {
+            let style = self.decode::<tswp::ListStyleArchive>(current?, LIST_STYLE_TYPE)?;
+            if let Some(label_type) = style.label_types.get(level as usize) {
+                return match label_type {
+                    // kNone
+                    0 => None,
+                    // kNumber
+                    3 => Some(number_format(
+                        style.number_types.get(level as usize).copied().unwrap_or(0),
+                    )),
+                    // kImage and kString
+                    _ => Some(ListFormat::Bullet),
+                };
+            }
+            current = style.super_.parent.as_ref().map(|parent| parent.identifier);
+        }
+        None
+    }
+
+    /// Get the URL of a hyperlink field; other smart fields have none.
+    fn hyperlink(&mut self, id: u64) -> Option<String> {
+        if let Some(url) = self.hyperlinks.get(&id) {
+            return url.clone();
+        }
+        let url = self
+            .decode::<tswp::HyperlinkFieldArchive>(id, HYPERLINK_FIELD_TYPE)
+            .and_then(|field| field.url_ref)
+            .filter(|url| !url.is_empty());
+        self.hyperlinks.insert(id, url.clone());
+        url
+    }
+
+    /// Decode an object's primary message if it has the expected type.
+    fn decode<M: Message + Default>(&self, id: u64, message_type: u32) -> Option<M> {
+        let message = (self.lookup)(id)?;
+        if message.type_ != message_type {
+            return None;
+        }
+        M::decode(&*message.data).ok()
+    }
+}
+
+/// Merge character properties, nearest first, into a text style.
+fn text_style(chain: &[tswp::CharacterStylePropertiesArchive]) -> TextStyle {
+    fn first<T>(
+        chain: &[tswp::CharacterStylePropertiesArchive],
+        property: impl Fn(&tswp::CharacterStylePropertiesArchive) -> Option<T>,
+    ) -> Option<T> {
+        chain.iter().find_map(property)
+    }
+
+    TextStyle {
+        // A "null" flag removes an inherited font or color
+        font_family: first(chain, |p| match p.font_name_null {
+            Some(true) => Some(None),
+            _ => p.font_name.clone().map(Some),
+        })
+        .flatten(),
+        font_size: first(chain, |p| p.font_size),
+        bold: first(chain, |p| p.bold).unwrap_or(false),
+        italic: first(chain, |p| p.italic).unwrap_or(false),
+        underline: first(chain, |p| p.underline).is_some_and(|underline| underline != 0),
+        strikethrough: first(chain, |p| p.strikethru).is_some_and(|strikethru| strikethru != 0),
+        color: first(chain, |p| match p.font_color_null {
+            Some(true) => Some(None),
+            _ => p.font_color.as_ref().map(|color| Some(rgb(color))),
+        })
+        .flatten(),
+        vertical_position: match first(chain, |p| p.superscript) {
+            Some(1) => VerticalPosition::Superscript,
+            Some(2) => VerticalPosition::Subscript,
+            _ => VerticalPosition::Normal,
+        },
+    }
+}
+
+/// Convert a TSP color to 8-bit RGB.
+fn rgb(color: &tsp::Color) -> (u8, u8, u8) {
+    let channel = |value: f32| (value.clamp(0.0, 1.0) * 255.0).round() as u8;
+    match color.model {
+        // cmyk
+        2 => {
+            let black = 1.0 - color.k.unwrap_or(0.0);
+            let ink = |value: Option<f32>| channel((1.0 - value.unwrap_or(0.0)) * black);
+            (ink(color.c), ink(color.m), ink(color.y))
+        },
+        // white
+        3 => {
+            let gray = channel(color.w.unwrap_or(0.0));
+            (gray, gray, gray)
+        },
+        _ => (
+            channel(color.r.unwrap_or(0.0)),
+            channel(color.g.unwrap_or(0.0)),
+            channel(color.b.unwrap_or(0.0)),
+        ),
+    }
+}
+
+/// Get the `(character index, object)` entries of an attribute table.
+fn object_entries(table: Option<&tswp::ObjectAttributeTable>) -> Vec<(u32, Option<u64>)> {
+    table
+        .map(|table| {
+            table
+                .entries
+                .iter()
+                .map(|entry| {
+                    (
+                        entry.character_index,
+                        entry.object.as_ref().map(|object| object.identifier),
+                    )
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Get the value of the last entry starting at or before `index`.
+fn entry_at<T: Copy>(entries: &[(u32, T)], index: u32) -> Option<T> {
+    let position = entries.partition_point(|(start, _)| *start <= index);
+    position.checked_sub(1).map(|position| entries[position].1)
+}
+
+/// Map a TSWP alignment, which follows NSTextAlignment; natural alignment
+/// depends on the writing direction and is left unset.
+fn alignment_from_iwork(value: i32) -> Option<TextAlignment> {
+    match value {
+        0 => Some(TextAlignment::Left),
+        1 => Some(TextAlignment::Right),
+        2 => Some(TextAlignment::Center),
+        3 => Some(TextAlignment::Justify),
+        _ => None,
+    }
+}
+
+/// Map a ListStyleArchive number type. Each format comes with three label
+/// punctuations, which do not change the format.
+fn number_format(number_type: i32) -> ListFormat {
+    match number_type {
+        3..=5 => ListFormat::UpperRoman,
+        6..=8 => ListFormat::LowerRoman,
+        9..=11 => ListFormat::UpperLetter,
+        12..=14 => ListFormat::LowerLetter,
+        // Decimal and the East Asian, Arabic and Hebrew numberings
+        _ => ListFormat::Decimal,
+    }
+}
+
+/// Heading level of the built-in "Heading" and "Heading 2" to "Heading 9"
+/// paragraph styles.
+fn heading_level_from_name(name: &str) -> Option<u8> {
+    let rest = name.trim().to_ascii_lowercase();
+    let level = rest.strip_prefix("heading")?.trim();
+    if level.is_empty() {
+        return Some(1);
+    }
+    let level = level.parse().ok()?;
+    (1..=9).contains(&level).then_some(level)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::iwa::protobuf::tss;
+
+    fn reference(identifier: u64) -> Option<tsp::Reference> {
+        Some(tsp::Reference {
+            identifier,
+            ..Default::default()
+        })
+    }
+
+    fn table(entries: &[(u32, u64)]) -> Option<tswp::ObjectAttributeTable> {
+        Some(tswp::ObjectAttributeTable {
+            entries: entries
+                .iter()
+                .map(
+                    |&(character_index, object)| tswp::object_attribute_table::ObjectAttribute {
+                        character_index,
+                        object: (object != 0).then(|| reference(object)).flatten(),
+                    },
+                )
+                .collect(),
+        })
+    }
+
+    fn objects() -> HashMap<u64, RawMessage> {
+        let mut objects = HashMap::new();
+        let mut add = |id: u64, type_: u32, data: Vec<u8>| {
+            objects.insert(id, RawMessage { type_, data });
+        };
+
+        let body = tswp::ParagraphStyleArchive {
+            super_: tss::StyleArchive {
+                name: Some("Body".to_string()),
+                ..Default::default()
+            },
+            char_properties: Some(tswp::CharacterStylePropertiesArchive {
+                font_size: Some(11.0),
+                ..Default::default()
+            }),
+            para_properties: Some(tswp::ParagraphStylePropertiesArchive {
+                alignment: Some(4),
+                list_style: reference(20),
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+        add(10, PARAGRAPH_STYLE_TYPE, body.encode_to_vec());
+
+        // A centered variation of "Heading 2"
+        let heading = tswp::ParagraphStyleArchive {
+            super_: tss::StyleArchive {
+                name: Some("Heading 2".to_string()),
+                parent: reference(10),
+                ..Default::default()
+            },
+            char_properties: Some(tswp::CharacterStylePropertiesArchive {
+                bold: Some(true),
+                font_size: Some(16.0),
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+        add(11, PARAGRAPH_STYLE_TYPE, heading.encode_to_vec());
+        let centered = tswp::ParagraphStyleArchive {
+            super_: tss::StyleArchive {
+                parent: reference(11),
+                is_variation: Some(true),
+                ..Default::default()
+            },
+            para_properties: Some(tswp::ParagraphStylePropertiesArchive {
+                alignment: Some(2),
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+        add(12, PARAGRAPH_STYLE_TYPE, centered.encode_to_vec());
+
+        let none = tswp::ListStyleArchive {
+            label_types: vec![0; 9],
+            ..Default::default()
+        };
+        add(20, LIST_STYLE_TYPE, none.encode_to_vec());
+        let numbered = tswp::ListStyleArchive {
+            label_types: vec![3, 2],
+            number_types: vec![0, 0],
+            ..Default::default()
+        };
+        add(21, LIST_STYLE_TYPE, numbered.encode_to_vec());
+        let lettered = tswp::ListStyleArchive {
+            super_: tss::StyleArchive {
+                parent: reference(21),
+                ..Default::default()
+            },
+            label_types: vec![3],
+            number_types: vec![12],
+            ..Default::default()
+        };
+        add(22, LIST_STYLE_TYPE, lettered.encode_to_vec());
+
+        let emphasis = tswp::CharacterStyleArchive {
+            char_properties: Some(tswp::CharacterStylePropertiesArchive {
+                italic: Some(true),
+                superscript: Some(1),
+                font_color: Some(tsp::Color {
+                    model: 1,
+                    r: Some(1.0),
+                    g: Some(0.0),
+                    b: Some(0.0),
+                    ..Default::default()
+                }),
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+        add(40, CHARACTER_STYLE_TYPE, emphasis.encode_to_vec());
+
+        let link = tswp::HyperlinkFieldArchive {
+            url_ref: Some("https://example.com".to_string()),
+            ..Default::default()
+        };
+        add(30, HYPERLINK_FIELD_TYPE, link.encode_to_vec());
+        objects
+    }
+
+    #[test]
+    fn test_storage_paragraphs() {
+        let objects = objects();
+        let lookup = |id: u64| objects.get(&id).cloned();
+        let mut resolver = StorageParagraphs::new(&lookup);
+
+        // Character indices:   0      6          17    23     29
+        let text = "Title\nSee site\u{FFFC}.\nFirst\nInner\nSecond\n";
+        let storage = tswp::StorageArchive {
+            text: vec![text.to_string()],
+            table_para_style: table(&[(0, 12), (6, 10)]),
+            table_list_style: table(&[(17, 21), (23, 22), (29, 21)]),
+            table_para_data: Some(tswp::ParaDataAttributeTable {
+                entries: [(0, 0), (23, 1), (29, 0)]
+                    .into_iter()
+                    .map(|(character_index, first)| {
+                        tswp::para_data_attribute_table::ParaDataAttribute {
+                            character_index,
+                            first,
+                            second: 0,
+                        }
+                    })
+                    .collect(),
+            }),
+            table_char_style: table(&[(0, 0), (17, 40), (20, 0)]),
+            table_smartfield: table(&[(10, 30), (14, 0)]),
+            ..Default::default()
+        };
+
+        let paragraphs = resolver.paragraphs(&storage);
+        let texts: Vec<_> = paragraphs.iter().map(TextParagraph::text).collect();
+        assert_eq!(texts, ["Title", "See site.", "First", "Inner", "Second"]);
+
+        assert_eq!(paragraphs[0].style_name.as_deref(), Some("Heading 2"));
+        assert_eq!(paragraphs[0].heading_level, Some(2));
+        assert_eq!(paragraphs[0].alignment, Some(TextAlignment::Center));
+        assert_eq!(paragraphs[0].list, None);
+        let title = &paragraphs[0].spans[0].style;
+        assert!(title.bold);
+        assert_eq!(title.font_size, Some(16.0));
+
+        assert_eq!(paragraphs[1].heading_level, None);
+        assert_eq!(paragraphs[1].alignment, None);
+        assert_eq!(paragraphs[1].list, None);
+        let spans: Vec<_> = paragraphs[1]
+            .spans
+            .iter()
+            .map(|span| (span.text.as_str(), span.hyperlink.as_deref()))
+            .collect();
+        assert_eq!(
+            spans,
+            [
+                ("See ", None),
+                ("site", Some("https://example.com")),
+                (".", None)
+            ]
+        );
+        assert!(!paragraphs[1].spans[0].style.bold);
+        assert_eq!(paragraphs[1].spans[0].style.font_size, Some(11.0));
+
+        let spans: Vec<_> = paragraphs[2]
+            .spans
+            .iter()
+            .map(|span| (span.text.as_str(), span.style.italic, span.style.color))
+            .collect();
+        assert_eq!(
+            spans,
+            [("Fir", true, Some((255, 0, 0))), ("st", false, None)]
+        );
+        assert_eq!(
+            paragraphs[2].spans[0].style.vertical_position,
+            VerticalPosition::Superscript
+        );
+
+        assert_eq!(
+            paragraphs[2].list,
+            Some(ListInfo::new(0, ListFormat::Decimal))
+        );
+        assert_eq!(
+            paragraphs[3].list,
+            Some(ListInfo::new(1, ListFormat::Bullet))
+        );
+        assert_eq!(
+            paragraphs[4].list,
+            Some(ListInfo::new(0, ListFormat::Decimal))
+        );
+    }
+
+    #[test]
+    fn test_heading_names() {
+        assert_eq!(heading_level_from_name("Heading"), Some(1));
+        assert_eq!(heading_level_from_name("Heading 3"), Some(3));
+        assert_eq!(heading_level_from_name("Heading 12"), None);
+        assert_eq!(heading_level_from_name("Body"), None);
+    }
+}
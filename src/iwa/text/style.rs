@@ -2,8 +2,10 @@
 //!
 //! iWork documents support rich text with character-level and paragraph-level styling.
 
+use crate::common::VerticalPosition;
+
 /// Text style properties (character-level)
-#[derive(Debug, Clone, Default)]
+#[derive(Debug, Clone, Default, PartialEq)]
 pub struct TextStyle {
     /// Font family name
     pub font_family: Option<String>,
@@ -19,6 +21,8 @@ pub struct TextStyle {
     pub strikethrough: bool,
     /// Text color (RGB)
     pub color: Option<(u8, u8, u8)>,
+    /// Superscript or subscript
+    pub vertical_position: VerticalPosition,
 }
 
 impl TextStyle {
@@ -36,6 +40,7 @@ impl TextStyle {
             || self.font_family.is_some()
             || self.font_size.is_some()
             || self.color.is_some()
+            || self.vertical_position != VerticalPosition::Normal
     }
 }
 
@@ -179,8 +179,9 @@ pub mod document;
 /// Provides functionality to parse and convert Office Drawing formats
 /// (EMF, WMF, PICT) to modern image standards (PNG, JPEG, WebP).
 ///
-/// **Note**: This requires the `imgconv` feature to be enabled.
-#[cfg(feature = "imgconv")]
+/// **Note**: Extracting pictures from OLE files requires the `ole` feature;
+/// converting them requires the `imgconv` feature.
+#[cfg(any(feature = "ole", feature = "imgconv"))]
 pub mod images;
 
 /// Unified PowerPoint presentation API
@@ -208,6 +209,13 @@ pub mod sheet;
 /// Use the [`markdown::ToMarkdown`] trait on Document or Presentation types.
 pub mod markdown;
 
+/// HTML conversion module
+///
+/// Provides functionality to convert Office documents, presentations and
+/// workbooks to HTML5. Use the [`html::ToHtml`] trait on Document,
+/// Presentation or Workbook types.
+pub mod html;
+
 // Low-level format-specific modules (advanced use)
 /// OLE2 format parser (legacy .doc, .ppt files)
 ///
@@ -261,7 +269,8 @@ pub mod odf;
 ///
 /// Provides high-performance parsing of RTF documents with support for RTF 1.9.1.
 /// RTF documents are automatically integrated with the unified Document API.
-/// Use [`Document::open()`] to parse RTF files.
+/// Use [`Document::open()`] to parse RTF files, and [`rtf::MutableDocument`]
+/// or [`rtf::ToRtf`] to write them.
 ///
 /// **Note**: This requires the `rtf` feature to be enabled.
 #[cfg(feature = "rtf")]
@@ -278,6 +287,6 @@ pub use presentation::Presentation;
 
 // Re-export commonly used types
 pub use common::{
-    FileFormat, Length, PlaceholderType, RGBColor, ShapeType, detect_file_format,
-    detect_file_format_from_bytes,
+    EmbeddedImage, FileFormat, ImageAnchor, Length, PlaceholderType, RGBColor, ShapeType,
+    detect_file_format, detect_file_format_from_bytes,
 };
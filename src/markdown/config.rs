@@ -2,6 +2,8 @@
 ///
 /// This module defines the configuration options and enums used to customize
 /// the Markdown conversion process.
+use super::image::ImageSink;
+
 /// Configuration options for Markdown conversion.
 ///
 /// This struct controls various aspects of the Markdown output, including
@@ -23,9 +25,9 @@
 /// ```
 ///
 /// # Note
-/// This struct is `Copy` since it only contains simple values,
-/// making it cheap to pass around without cloning overhead.
-#[derive(Debug, Copy, Clone)]
+/// This struct is cheap to clone: the only non-trivial field is the
+/// [`ImageSink`], which shares its callback through an `Arc`.
+#[derive(Debug, Clone)]
 pub struct MarkdownOptions {
     /// Whether to include text styles (bold, italic, underline, etc.)
     pub include_styles: bool,
@@ -49,6 +51,10 @@ pub struct MarkdownOptions {
     /// with 50+ paragraphs. Automatically uses sequential processing for smaller
     /// documents to avoid parallelization overhead.
     pub use_parallel: bool,
+    /// Where to put images; `None` (the default) leaves images out
+    pub image_sink: Option<ImageSink>,
+    /// How to convert EMF/WMF/PICT images before they reach the sink
+    pub metafile_style: MetafileStyle,
 }
 
 impl Default for MarkdownOptions {
@@ -63,6 +69,8 @@ impl Default for MarkdownOptions {
             script_style: ScriptStyle::Html,
             strikethrough_style: StrikethroughStyle::Markdown,
             use_parallel: true, // Enable parallel processing by default
+            image_sink: None,
+            metafile_style: MetafileStyle::Png,
         }
     }
 }
@@ -240,6 +248,49 @@ impl MarkdownOptions {
         self.use_parallel = use_parallel;
         self
     }
+
+    /// Set where images are stored and enable `![alt](target)` output.
+    ///
+    /// Each image is written after the paragraph (or slide) holding it;
+    /// images the format does not anchor are written at the end.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use litchi::markdown::{ImageSink, MarkdownOptions};
+    ///
+    /// // Write images next to the Markdown file
+    /// let options = MarkdownOptions::new()
+    ///     .with_image_sink(ImageSink::directory("images"));
+    ///
+    /// // Or embed them in the output
+    /// let options = MarkdownOptions::new()
+    ///     .with_image_sink(ImageSink::DataUri);
+    /// ```
+    #[inline]
+    pub fn with_image_sink(mut self, sink: ImageSink) -> Self {
+        self.image_sink = Some(sink);
+        self
+    }
+
+    /// Set how metafile images (EMF, WMF, PICT) are converted.
+    ///
+    /// Conversion requires the `imgconv` feature; without it, or when a
+    /// metafile cannot be converted, the original data is kept.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use litchi::markdown::{MarkdownOptions, MetafileStyle};
+    ///
+    /// let options = MarkdownOptions::new()
+    ///     .with_metafile_style(MetafileStyle::Svg);
+    /// ```
+    #[inline]
+    pub fn with_metafile_style(mut self, style: MetafileStyle) -> Self {
+        self.metafile_style = style;
+        self
+    }
 }
 
 /// Table rendering styles for Markdown conversion.
@@ -346,6 +397,22 @@ pub enum StrikethroughStyle {
     Html,
 }
 
+/// Metafile conversion for images.
+///
+/// Browsers and most Markdown renderers cannot display EMF, WMF or PICT, so
+/// these are converted before being handed to the [`ImageSink`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MetafileStyle {
+    /// Render metafiles to PNG.
+    Png,
+
+    /// Convert EMF and WMF to SVG; PICT is rendered to PNG.
+    Svg,
+
+    /// Keep the original metafile data.
+    Keep,
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -383,5 +450,7 @@ mod tests {
         assert_eq!(options.list_indent, 2);
         assert_eq!(options.script_style, ScriptStyle::Html);
         assert_eq!(options.strikethrough_style, StrikethroughStyle::Markdown);
+        assert!(options.image_sink.is_none());
+        assert_eq!(options.metafile_style, MetafileStyle::Png);
     }
 }
@@ -1,6 +1,7 @@
 use super::config::MarkdownOptions;
+use super::image::PlacedImages;
 use super::traits::ToMarkdown;
-use super::writer::MarkdownWriter;
+use super::writer::{ListNumbering, MarkdownWriter};
 /// ToMarkdown implementations for Document types.
 ///
 /// This module implements the `ToMarkdown` trait for Word document types,
@@ -8,19 +9,18 @@ use super::writer::MarkdownWriter;
 ///
 /// **Note**: This module is only available when the `ole` or `ooxml` feature is enabled.
 use crate::common::Result;
-use crate::document::{Document, Paragraph, Run, Table};
+use crate::document::{Document, DocumentElement, Paragraph, Run, Table};
 use rayon::prelude::*;
+use std::ops::Range;
 
 /// Minimum number of elements to justify parallel processing overhead.
 const PARALLEL_THRESHOLD: usize = 50;
 
 impl ToMarkdown for Document {
     fn to_markdown_with_options(&self, options: &MarkdownOptions) -> Result<String> {
-        use crate::document::DocumentElement;
-
         // Write metadata first (must be sequential)
         let metadata_md = if options.include_metadata {
-            let mut metadata_writer = MarkdownWriter::new(*options);
+            let mut metadata_writer = MarkdownWriter::new(options.clone());
             let metadata = self.metadata()?;
             metadata_writer.write_metadata(&metadata)?;
             metadata_writer.finish()
@@ -28,17 +28,29 @@ impl ToMarkdown for Document {
             String::new()
         };
 
+        // Store images up front (sequentially) so their link targets are known
+        let images = if options.image_sink.is_some() {
+            PlacedImages::store(self.images()?, options)?
+        } else {
+            PlacedImages::default()
+        };
+
         // Extract all document elements (paragraphs and tables) in document order
         let elements = self.elements()?;
+        let paragraphs = paragraph_ranges(&elements, &images)?;
 
         // Decide whether to use parallel or sequential processing
         let content_md = if options.use_parallel && elements.len() >= PARALLEL_THRESHOLD {
             // PARALLEL PATH: Process elements in parallel for large documents
             // With Arc-based Send + Sync types, we can now safely parallelize
+            let numbering = list_numbering(&elements)?;
             let element_strings: Vec<String> = elements
                 .par_iter()
-                .map(|element| {
-                    let mut writer = MarkdownWriter::new(*options);
+                .zip(paragraphs.par_iter())
+                .zip(numbering)
+                .map(|((element, paragraphs), numbering)| {
+                    let mut writer = MarkdownWriter::new(options.clone());
+                    writer.set_list_numbering(numbering);
                     match element {
                         DocumentElement::Paragraph(para) => {
                             let _ = writer.write_paragraph(para);
@@ -47,6 +59,7 @@ impl ToMarkdown for Document {
                             let _ = writer.write_table(table);
                         },
                     }
+                    writer.write_images(&images.within(paragraphs.clone()));
                     writer.finish()
                 })
                 .collect();
@@ -60,16 +73,20 @@ impl ToMarkdown for Document {
                 result.push_str(s);
             }
 
+            let mut writer = MarkdownWriter::new(options.clone());
+            writer.write_images(images.trailing());
+            result.push_str(&writer.finish());
+
             result
         } else {
             // SEQUENTIAL PATH: Process elements sequentially for small documents
             // This avoids the parallelization overhead when it's not beneficial
-            let mut writer = MarkdownWriter::new(*options);
+            let mut writer = MarkdownWriter::new(options.clone());
             // Estimate: 100 bytes per paragraph, 500 bytes per table
             let estimated_size = elements.len() * 150; // Rough average
             writer.reserve(estimated_size);
 
-            for element in elements {
+            for (element, paragraphs) in elements.into_iter().zip(paragraphs) {
                 match element {
                     DocumentElement::Paragraph(para) => {
                         writer.write_paragraph(&para)?;
@@ -78,7 +95,9 @@ impl ToMarkdown for Document {
                         writer.write_table(&table)?;
                     },
                 }
+                writer.write_images(&images.within(paragraphs));
             }
+            writer.write_images(images.trailing());
 
             writer.finish()
         };
@@ -88,9 +107,54 @@ impl ToMarkdown for Document {
     }
 }
 
+/// Get the paragraph indices covered by each element, counted like
+/// `Document::paragraphs()`, which image anchors refer to.
+///
+/// Table paragraphs are only counted when some image is anchored, since
+/// that means parsing every cell.
+pub(crate) fn paragraph_ranges(
+    elements: &[DocumentElement],
+    images: &PlacedImages,
+) -> Result<Vec<Range<usize>>> {
+    if !images.has_anchored() {
+        return Ok(vec![0..0; elements.len()]);
+    }
+
+    let mut ranges = Vec::with_capacity(elements.len());
+    let mut next = 0;
+    for element in elements {
+        let count = match element {
+            DocumentElement::Paragraph(_) => 1,
+            DocumentElement::Table(table) => table.paragraph_count()?,
+        };
+        ranges.push(next..next + count);
+        next += count;
+    }
+    Ok(ranges)
+}
+
+/// Get the list numbering in effect before each element, so elements
+/// written separately continue the numbering of the lists before them.
+fn list_numbering(elements: &[DocumentElement]) -> Result<Vec<ListNumbering>> {
+    let mut states = Vec::with_capacity(elements.len());
+    let mut numbering = ListNumbering::default();
+    for element in elements {
+        states.push(numbering.clone());
+        if let DocumentElement::Paragraph(para) = element {
+            let heading_level = para.heading_level()?;
+            let list_info = match heading_level {
+                Some(_) => None,
+                None => para.list_info()?,
+            };
+            numbering.next(heading_level, list_info.as_ref());
+        }
+    }
+    Ok(states)
+}
+
 impl ToMarkdown for Paragraph {
     fn to_markdown_with_options(&self, options: &MarkdownOptions) -> Result<String> {
-        let mut writer = MarkdownWriter::new(*options);
+        let mut writer = MarkdownWriter::new(options.clone());
         writer.write_paragraph(self)?;
         Ok(writer.finish().trim_end().to_string())
     }
@@ -98,7 +162,7 @@ impl ToMarkdown for Paragraph {
 
 impl ToMarkdown for Run {
     fn to_markdown_with_options(&self, options: &MarkdownOptions) -> Result<String> {
-        let mut writer = MarkdownWriter::new(*options);
+        let mut writer = MarkdownWriter::new(options.clone());
         writer.write_run(self)?;
         Ok(writer.finish())
     }
@@ -106,7 +170,7 @@ impl ToMarkdown for Run {
 
 impl ToMarkdown for Table {
     fn to_markdown_with_options(&self, options: &MarkdownOptions) -> Result<String> {
-        let mut writer = MarkdownWriter::new(*options);
+        let mut writer = MarkdownWriter::new(options.clone());
         writer.write_table(self)?;
         Ok(writer.finish().trim_end().to_string())
     }
@@ -0,0 +1,266 @@
+/// Image output for Markdown conversion.
+///
+/// Images are taken from the `images()` methods of documents and
+/// presentations, so every source is handled alike: DOCX drawings, DOC and
+/// PPT BLIPs, PPTX pictures, ODF frames, RTF pictures and iWork media. Each
+/// image is converted according to [`MetafileStyle`], handed to the
+/// [`ImageSink`], and written as `![alt](target)` after the paragraph or
+/// slide it is anchored to.
+use super::config::{MarkdownOptions, MetafileStyle};
+use crate::common::{EmbeddedImage, ImageAnchor, Result};
+use base64::Engine;
+use std::collections::HashMap;
+use std::fmt;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+/// Callback that stores an image and returns the link target to write.
+pub type ImageCallback = dyn Fn(&EmbeddedImage) -> Result<String> + Send + Sync;
+
+/// Where images go during Markdown conversion.
+///
+/// # Examples
+///
+/// ```rust
+/// use litchi::markdown::{ImageSink, MarkdownOptions};
+///
+/// // Upload elsewhere and link to the result
+/// let sink = ImageSink::callback(|image| {
+///     Ok(format!("https://cdn.example.com/{}", image.name))
+/// });
+/// let options = MarkdownOptions::new().with_image_sink(sink);
+/// ```
+#[derive(Clone)]
+pub enum ImageSink {
+    /// Write each image to `imageN.ext` in this directory, creating it if
+    /// needed, and link to the written file
+    Directory(PathBuf),
+    /// Embed each image in the output as a base64 `data:` URI
+    DataUri,
+    /// Pass each image to a callback, which returns the link target
+    Callback(Arc<ImageCallback>),
+}
+
+impl ImageSink {
+    /// Write images to files in a directory.
+    pub fn directory(path: impl Into<PathBuf>) -> Self {
+        Self::Directory(path.into())
+    }
+
+    /// Hand images to a callback.
+    pub fn callback(
+        callback: impl Fn(&EmbeddedImage) -> Result<String> + Send + Sync + 'static,
+    ) -> Self {
+        Self::Callback(Arc::new(callback))
+    }
+
+    /// Store an image and return its link target.
+    ///
+    /// `number` is the 1-based position of the image in the document.
+    fn store(&self, image: &EmbeddedImage, number: usize) -> Result<String> {
+        match self {
+            Self::Directory(dir) => {
+                std::fs::create_dir_all(dir)?;
+                let path = dir.join(format!("image{}.{}", number, image.extension()));
+                std::fs::write(&path, &image.data)?;
+                // Markdown links use forward slashes on every platform
+                Ok(path.to_string_lossy().replace('\\', "/"))
+            },
+            Self::DataUri => Ok(format!(
+                "data:{};base64,{}",
+                image.mime_type,
+                base64::engine::general_purpose::STANDARD.encode(&image.data)
+            )),
+            Self::Callback(callback) => callback(image),
+        }
+    }
+}
+
+impl fmt::Debug for ImageSink {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Directory(dir) => f.debug_tuple("Directory").field(dir).finish(),
+            Self::DataUri => f.write_str("DataUri"),
+            Self::Callback(_) => f.write_str("Callback(..)"),
+        }
+    }
+}
+
+/// An image that has been stored, ready to be written.
+#[derive(Debug, Clone)]
+pub(crate) struct StoredImage {
+    pub alt_text: String,
+    pub target: String,
+}
+
+/// Stored images, grouped by where they are written.
+#[derive(Debug, Default)]
+pub(crate) struct PlacedImages {
+    /// Images by 0-based paragraph (documents) or slide (presentations) index
+    anchored: HashMap<usize, Vec<StoredImage>>,
+    /// Images without a usable anchor, written at the end
+    trailing: Vec<StoredImage>,
+}
+
+impl PlacedImages {
+    /// Convert and store the images of a document or presentation.
+    ///
+    /// Nothing is stored unless `options` has an image sink.
+    pub(crate) fn store(images: Vec<EmbeddedImage>, options: &MarkdownOptions) -> Result<Self> {
+        Self::store_in(images, options.image_sink.as_ref(), options.metafile_style)
+    }
+
+    /// Convert and store images in a sink; nothing is stored without one.
+    pub(crate) fn store_in(
+        images: Vec<EmbeddedImage>,
+        sink: Option<&ImageSink>,
+        metafile_style: MetafileStyle,
+    ) -> Result<Self> {
+        let mut placed = Self::default();
+        let Some(sink) = sink else {
+            return Ok(placed);
+        };
+
+        for (index, image) in images.into_iter().enumerate() {
+            let image = convert_metafile(image, metafile_style);
+            let stored = StoredImage {
+                target: sink.store(&image, index + 1)?,
+                alt_text: image.alt_text.unwrap_or_default(),
+            };
+            match image.anchor {
+                ImageAnchor::Paragraph(index) | ImageAnchor::Slide(index) => {
+                    placed.anchored.entry(index).or_default().push(stored)
+                },
+                ImageAnchor::Cell { .. } | ImageAnchor::Unanchored => placed.trailing.push(stored),
+            }
+        }
+
+        Ok(placed)
+    }
+
+    /// Whether any image is anchored to a paragraph or slide.
+    pub(crate) fn has_anchored(&self) -> bool {
+        !self.anchored.is_empty()
+    }
+
+    /// Images anchored to a paragraph or slide.
+    pub(crate) fn at(&self, index: usize) -> &[StoredImage] {
+        self.anchored.get(&index).map_or(&[], Vec::as_slice)
+    }
+
+    /// Images anchored to any of a range of paragraphs, in order.
+    pub(crate) fn within(&self, range: std::ops::Range<usize>) -> Vec<StoredImage> {
+        if !self.has_anchored() {
+            return Vec::new();
+        }
+        range
+            .flat_map(|index| self.at(index).iter().cloned())
+            .collect()
+    }
+
+    /// Images written after all content.
+    pub(crate) fn trailing(&self) -> &[StoredImage] {
+        &self.trailing
+    }
+}
+
+/// Convert a metafile image for display; other images are returned as is.
+///
+/// Conversion failures keep the original data rather than losing the image.
+#[cfg(feature = "imgconv")]
+fn convert_metafile(image: EmbeddedImage, style: MetafileStyle) -> EmbeddedImage {
+    use crate::images::{emf, wmf};
+
+    if !image.is_metafile() || style == MetafileStyle::Keep {
+        return image;
+    }
+
+    let converted = match (style, image.mime_type) {
+        (MetafileStyle::Svg, "image/x-emf") => {
+            emf::convert_emf_to_svg_bytes(&image.data).map(|data| (data, "svg"))
+        },
+        (MetafileStyle::Svg, "image/x-wmf") => {
+            wmf::convert_wmf_to_svg_bytes(&image.data).map(|data| (data, "svg"))
+        },
+        _ => image.to_png(None, None).map(|data| (data, "png")),
+    };
+
+    match converted {
+        Ok((data, extension)) => {
+            let stem = image
+                .name
+                .rsplit_once('.')
+                .map_or(image.name.as_str(), |(stem, _)| stem);
+            EmbeddedImage::new(
+                format!("{}.{}", stem, extension),
+                data,
+                image.anchor.clone(),
+            )
+            .with_alt_text(image.alt_text.clone())
+        },
+        Err(_) => image,
+    }
+}
+
+/// Metafile conversion needs the `imgconv` feature; keep the original.
+#[cfg(not(feature = "imgconv"))]
+fn convert_metafile(image: EmbeddedImage, _style: MetafileStyle) -> EmbeddedImage {
+    image
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn png() -> Vec<u8> {
+        let mut png = b"\x89PNG\r\n\x1a\n\0\0\0\x0dIHDR".to_vec();
+        png.extend_from_slice(&1u32.to_be_bytes());
+        png.extend_from_slice(&1u32.to_be_bytes());
+        png
+    }
+
+    #[test]
+    fn test_data_uri_and_placement() {
+        let images = vec![
+            EmbeddedImage::new("a.png", png(), ImageAnchor::Paragraph(2))
+                .with_alt_text(Some("Logo".to_string())),
+            EmbeddedImage::new("b.png", png(), ImageAnchor::Unanchored),
+        ];
+        let options = MarkdownOptions::new().with_image_sink(ImageSink::DataUri);
+        let placed = PlacedImages::store(images, &options).unwrap();
+
+        assert!(placed.at(0).is_empty());
+        assert_eq!(placed.at(2).len(), 1);
+        assert_eq!(placed.at(2)[0].alt_text, "Logo");
+        assert!(
+            placed.at(2)[0]
+                .target
+                .starts_with("data:image/png;base64,iVBORw0KGgo")
+        );
+        assert_eq!(placed.within(0..3).len(), 1);
+        assert_eq!(placed.trailing().len(), 1);
+        assert_eq!(placed.trailing()[0].alt_text, "");
+    }
+
+    #[test]
+    fn test_directory_and_callback() {
+        let dir = std::env::temp_dir().join(format!("litchi-md-images-{}", std::process::id()));
+        let options = MarkdownOptions::new().with_image_sink(ImageSink::directory(&dir));
+        let images = vec![EmbeddedImage::new("x.png", png(), ImageAnchor::Slide(0))];
+        let placed = PlacedImages::store(images.clone(), &options).unwrap();
+
+        let target = &placed.at(0)[0].target;
+        assert!(target.ends_with("/image1.png"));
+        assert_eq!(std::fs::read(dir.join("image1.png")).unwrap(), png());
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        let options = MarkdownOptions::new().with_image_sink(ImageSink::callback(|image| {
+            Ok(format!("media/{}", image.name))
+        }));
+        let placed = PlacedImages::store(images, &options).unwrap();
+        assert_eq!(placed.at(0)[0].target, "media/x.png");
+
+        let none = PlacedImages::store(Vec::new(), &MarkdownOptions::new()).unwrap();
+        assert!(none.trailing().is_empty());
+    }
+}
@@ -0,0 +1,286 @@
+/// Markdown import into Word documents.
+///
+/// Blocks map onto the writer API: headings through `add_heading`, lists as
+/// list paragraphs, code blocks in the `MacroText` style, block quotes in
+/// the `Quote` style, GFM tables as bordered tables, and math as OMML.
+/// Thematic breaks have no counterpart in a flowing document and are
+/// dropped.
+use super::math::latex_to_omml;
+use super::parser::{Block, Inline, Style, parse};
+use super::{FromMarkdown, ImportOptions, fit_image};
+use crate::common::{Result, TextAlignment};
+use crate::ooxml::docx::{
+    ImageFormat, ListType, MutableDocument, MutableParagraph, Package, ParagraphAlignment,
+    TableBorder,
+};
+
+/// Largest picture size: the text width and height of a Letter page
+/// with 1-inch margins.
+const MAX_IMAGE_WIDTH: i64 = 6 * 914400;
+const MAX_IMAGE_HEIGHT: i64 = 9 * 914400;
+
+/// Indentation per list level, in inches.
+const LIST_INDENT: f64 = 0.5;
+
+/// Font for code spans and code blocks.
+const CODE_FONT: &str = "Courier New";
+
+impl FromMarkdown for Package {
+    fn from_markdown_with_options(markdown: &str, options: &ImportOptions) -> Result<Self> {
+        let mut pkg = Package::new()?;
+        let mut writer = DocxWriter {
+            doc: pkg.document_mut()?,
+            options,
+        };
+        for block in parse(markdown) {
+            writer.write_block(&block, Context::default())?;
+        }
+        Ok(pkg)
+    }
+}
+
+/// Where a block is being written.
+#[derive(Debug, Clone, Copy, Default)]
+struct Context {
+    /// Level of the enclosing list, if any
+    list_level: Option<u32>,
+    /// Whether the block is inside a block quote
+    quote: bool,
+}
+
+struct DocxWriter<'a> {
+    doc: &'a mut MutableDocument,
+    options: &'a ImportOptions,
+}
+
+impl DocxWriter<'_> {
+    /// Add a body paragraph styled for its context.
+    fn paragraph(&mut self, context: Context) -> &mut MutableParagraph {
+        let para = self.doc.add_paragraph();
+        if context.quote {
+            para.set_style("Quote");
+        }
+        if let Some(level) = context.list_level {
+            para.set_indent_left(LIST_INDENT * (level + 1) as f64);
+        }
+        para
+    }
+
+    fn write_block(&mut self, block: &Block, context: Context) -> Result<()> {
+        match block {
+            Block::Heading { level, content } => {
+                let para = self.doc.add_heading("", *level)?;
+                para.clear();
+                write_inlines(para, content, self.options, false)?;
+            },
+            Block::Paragraph(content) => {
+                let options = self.options;
+                write_inlines(self.paragraph(context), content, options, false)?;
+            },
+            Block::List { ordered, items } => {
+                let level = context.list_level.map_or(0, |level| level + 1);
+                let list_type = if *ordered {
+                    ListType::Decimal
+                } else {
+                    ListType::Bullet
+                };
+                let inner = Context {
+                    list_level: Some(level),
+                    ..context
+                };
+                for item in items {
+                    // The item's first paragraph carries the marker
+                    let para = self.doc.add_paragraph();
+                    para.set_list(list_type, level);
+                    if context.quote {
+                        para.set_style("Quote");
+                    }
+                    let rest = match item.split_first() {
+                        Some((Block::Paragraph(content), rest)) => {
+                            write_inlines(para, content, self.options, false)?;
+                            rest
+                        },
+                        _ => item.as_slice(),
+                    };
+                    for block in rest {
+                        self.write_block(block, inner)?;
+                    }
+                }
+            },
+            Block::Code(code) => {
+                let para = self.paragraph(context);
+                para.set_style("MacroText");
+                let mut lines = code.lines().peekable();
+                while let Some(line) = lines.next() {
+                    let run = para.add_run_with_text(line);
+                    run.font_name(CODE_FONT);
+                    if lines.peek().is_some() {
+                        run.add_break();
+                    }
+                }
+            },
+            Block::Quote(blocks) => {
+                let inner = Context {
+                    quote: true,
+                    ..context
+                };
+                for block in blocks {
+                    self.write_block(block, inner)?;
+                }
+            },
+            Block::Table {
+                alignments,
+                header,
+                rows,
+            } => {
+                let table = self.doc.add_table(rows.len() + 1, header.len());
+                table.set_borders(TableBorder::default());
+                let all_rows = std::iter::once(header).chain(rows);
+                for (row_index, row) in all_rows.enumerate() {
+                    for (col, content) in row.iter().enumerate() {
+                        let Some(para) = table
+                            .cell(row_index, col)
+                            .and_then(|cell| cell.paragraph(0))
+                        else {
+                            continue;
+                        };
+                        if let Some(Some(alignment)) = alignments.get(col) {
+                            para.set_alignment(paragraph_alignment(*alignment));
+                        }
+                        if row_index == 0 {
+                            write_inlines(para, &embolden(content), self.options, true)?;
+                        } else {
+                            write_inlines(para, content, self.options, true)?;
+                        }
+                    }
+                }
+            },
+            Block::Math(latex) => {
+                self.paragraph(context)
+                    .add_math(&latex_to_omml(latex, true));
+            },
+            Block::Rule => {},
+        }
+        Ok(())
+    }
+}
+
+/// Make text bold, for table header cells.
+fn embolden(inlines: &[Inline]) -> Vec<Inline> {
+    inlines
+        .iter()
+        .map(|inline| match inline {
+            Inline::Text(text, style) => Inline::Text(
+                text.clone(),
+                Style {
+                    bold: true,
+                    ..*style
+                },
+            ),
+            other => other.clone(),
+        })
+        .collect()
+}
+
+fn paragraph_alignment(alignment: TextAlignment) -> ParagraphAlignment {
+    match alignment {
+        TextAlignment::Left => ParagraphAlignment::Left,
+        TextAlignment::Center => ParagraphAlignment::Center,
+        TextAlignment::Right => ParagraphAlignment::Right,
+        TextAlignment::Justify => ParagraphAlignment::Justify,
+    }
+}
+
+/// Write inline content into a paragraph.
+///
+/// Table cells are serialized without relationships, so in cells links
+/// become underlined text and images their alt text.
+fn write_inlines(
+    para: &mut MutableParagraph,
+    inlines: &[Inline],
+    options: &ImportOptions,
+    in_table: bool,
+) -> Result<()> {
+    for inline in inlines {
+        match inline {
+            Inline::Text(text, style) => write_text(para, text, *style),
+            Inline::Link { text, url } => {
+                if in_table {
+                    write_link_text(para, text);
+                } else {
+                    para.add_hyperlink(text, url);
+                }
+            },
+            Inline::Image { alt, url } => {
+                if in_table {
+                    write_text(para, alt, Style::default());
+                    continue;
+                }
+                match options.load_image(url)? {
+                    Some(data) if ImageFormat::detect_from_bytes(&data).is_some() => {
+                        let (width, height) = fit_image(&data, MAX_IMAGE_WIDTH, MAX_IMAGE_HEIGHT);
+                        para.add_picture_from_bytes(data, Some(width), Some(height))?
+                            .set_description(alt.as_str());
+                    },
+                    // Remote, missing or unsupported images are linked instead
+                    _ => {
+                        let text = if alt.is_empty() { url } else { alt };
+                        para.add_hyperlink(text, url);
+                    },
+                }
+            },
+            Inline::Math(latex) => para.add_math(&latex_to_omml(latex, false)),
+            Inline::Break => {
+                para.add_run().add_break();
+            },
+        }
+    }
+    Ok(())
+}
+
+fn write_text(para: &mut MutableParagraph, text: &str, style: Style) {
+    let run = para.add_run_with_text(text);
+    if style.bold {
+        run.bold(true);
+    }
+    if style.italic {
+        run.italic(true);
+    }
+    if style.strike {
+        run.strikethrough(true);
+    }
+    if style.code {
+        run.font_name(CODE_FONT);
+    }
+}
+
+/// Write link text styled like a hyperlink, for places links can't go.
+fn write_link_text(para: &mut MutableParagraph, text: &str) {
+    para.add_run_with_text(text)
+        .underline(crate::ooxml::docx::UnderlineStyle::Single)
+        .color("0563C1");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_markdown_to_docx() {
+        let markdown = "# Title\n\nSome **bold** and [a link](https://example.com).\n\n\
+                        - one\n  - nested\n\n| A | B |\n|---|:-:|\n| `x` | $y^2$ |\n\n$$\\frac{a}{b}$$\n";
+        let mut pkg = Package::from_markdown(markdown).unwrap();
+        let doc = pkg.document_mut().unwrap();
+        assert_eq!(doc.paragraph_count(), 5);
+        assert_eq!(doc.table_count(), 1);
+
+        let xml = doc.to_xml().unwrap();
+        assert!(xml.contains("<w:pStyle w:val=\"Heading 1\"/>"));
+        assert!(xml.contains("<w:b/></w:rPr><w:t xml:space=\"preserve\">bold</w:t>"));
+        assert!(xml.contains("<w:hyperlink"));
+        assert!(xml.contains("<w:ilvl w:val=\"1\"/><w:numId w:val=\"1\"/>"));
+        assert!(xml.contains("<w:jc w:val=\"center\"/>"));
+        assert!(xml.contains("<m:oMath><m:sSup>"));
+        assert!(xml.contains("<m:oMathPara><m:oMath><m:f>"));
+    }
+}
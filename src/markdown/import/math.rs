@@ -0,0 +1,648 @@
+/// LaTeX to OMML conversion for Markdown math.
+///
+/// Covers the LaTeX people write in Markdown: scripts, fractions, roots,
+/// large operators, `\left`/`\right` delimiters, `\text`, function names
+/// and the common symbol commands. Anything else is kept as literal text
+/// so no input is lost.
+use std::fmt::Write as FmtWrite;
+
+/// A parsed piece of a formula.
+#[derive(Debug, Clone)]
+enum Atom {
+    /// A symbol or run of text; `upright` for function names and `\text`
+    Text {
+        text: String,
+        upright: bool,
+    },
+    /// A `{...}` group
+    Group(Vec<Atom>),
+    Fraction(Vec<Atom>, Vec<Atom>),
+    Root {
+        degree: Option<Vec<Atom>>,
+        body: Vec<Atom>,
+    },
+    Scripts {
+        base: Box<Atom>,
+        sub: Option<Vec<Atom>>,
+        sup: Option<Vec<Atom>>,
+    },
+    /// A large operator applied to the rest of the row
+    Nary {
+        symbol: char,
+        sub: Option<Vec<Atom>>,
+        sup: Option<Vec<Atom>>,
+        body: Vec<Atom>,
+    },
+    Delimited {
+        open: String,
+        close: String,
+        body: Vec<Atom>,
+    },
+}
+
+/// Convert LaTeX to an OMML `m:oMath` element, wrapped in `m:oMathPara`
+/// for display math.
+pub(crate) fn latex_to_omml(latex: &str, display: bool) -> String {
+    let mut parser = Parser {
+        chars: latex.chars().collect(),
+        pos: 0,
+    };
+    let atoms = parser.parse_row(None);
+
+    let mut xml = String::with_capacity(latex.len() * 8);
+    if display {
+        xml.push_str("<m:oMathPara>");
+    }
+    xml.push_str("<m:oMath>");
+    write_atoms(&mut xml, &atoms);
+    xml.push_str("</m:oMath>");
+    if display {
+        xml.push_str("</m:oMathPara>");
+    }
+    xml
+}
+
+struct Parser {
+    chars: Vec<char>,
+    pos: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<char> {
+        self.chars.get(self.pos).copied()
+    }
+
+    fn skip_whitespace(&mut self) {
+        while self.peek().is_some_and(char::is_whitespace) {
+            self.pos += 1;
+        }
+    }
+
+    /// Parse atoms until the end, a closing brace, or `\right`.
+    fn parse_row(&mut self, until: Option<char>) -> Vec<Atom> {
+        let mut atoms: Vec<Atom> = Vec::new();
+        loop {
+            self.skip_whitespace();
+            let Some(ch) = self.peek() else {
+                break;
+            };
+            if Some(ch) == until {
+                self.pos += 1;
+                break;
+            }
+            if ch == '}' && until.is_none() {
+                // Stray brace; keep it
+                self.pos += 1;
+                atoms.push(text(ch.to_string()));
+                continue;
+            }
+            if ch == '\\' && self.at_command("right") {
+                break;
+            }
+
+            match ch {
+                '^' | '_' => {
+                    self.pos += 1;
+                    let script = self.parse_argument();
+                    let base = atoms.pop().unwrap_or(Atom::Group(Vec::new()));
+                    atoms.push(attach_script(base, ch, script));
+                },
+                _ => {
+                    let atom = self.parse_atom();
+                    if let Atom::Nary { symbol, .. } = atom {
+                        // Scripts on the operator, then the rest of the row as its operand
+                        let (sub, sup) = self.parse_limits();
+                        let body = self.parse_row(until);
+                        atoms.push(Atom::Nary {
+                            symbol,
+                            sub,
+                            sup,
+                            body,
+                        });
+                        if until.is_some() {
+                            // The nested row consumed the closing brace
+                            break;
+                        }
+                    } else {
+                        atoms.push(atom);
+                    }
+                },
+            }
+        }
+        atoms
+    }
+
+    /// Parse `_` and `^` limits directly after a large operator.
+    fn parse_limits(&mut self) -> (Option<Vec<Atom>>, Option<Vec<Atom>>) {
+        let (mut sub, mut sup) = (None, None);
+        loop {
+            self.skip_whitespace();
+            if self.at_command("limits") || self.at_command("nolimits") {
+                self.parse_command_name();
+                continue;
+            }
+            match self.peek() {
+                Some('_') if sub.is_none() => {
+                    self.pos += 1;
+                    sub = Some(self.parse_argument());
+                },
+                Some('^') if sup.is_none() => {
+                    self.pos += 1;
+                    sup = Some(self.parse_argument());
+                },
+                _ => return (sub, sup),
+            }
+        }
+    }
+
+    /// Parse a command or script argument: a group or a single atom.
+    fn parse_argument(&mut self) -> Vec<Atom> {
+        self.skip_whitespace();
+        match self.peek() {
+            Some('{') => {
+                self.pos += 1;
+                self.parse_row(Some('}'))
+            },
+            Some(_) => vec![self.parse_atom()],
+            None => Vec::new(),
+        }
+    }
+
+    /// Parse a `{...}` argument as raw text.
+    fn parse_text_argument(&mut self) -> String {
+        self.skip_whitespace();
+        if self.peek() != Some('{') {
+            return self
+                .peek()
+                .map(|ch| {
+                    self.pos += 1;
+                    ch.to_string()
+                })
+                .unwrap_or_default();
+        }
+        self.pos += 1;
+        let mut depth = 1;
+        let mut out = String::new();
+        while let Some(ch) = self.peek() {
+            self.pos += 1;
+            match ch {
+                '{' => depth += 1,
+                '}' => {
+                    depth -= 1;
+                    if depth == 0 {
+                        break;
+                    }
+                },
+                _ => {},
+            }
+            out.push(ch);
+        }
+        out
+    }
+
+    fn at_command(&self, name: &str) -> bool {
+        let end = self.pos + 1 + name.len();
+        self.chars.get(self.pos) == Some(&'\\')
+            && self
+                .chars
+                .get(self.pos + 1..end)
+                .is_some_and(|chars| chars.iter().copied().eq(name.chars()))
+            && !self.chars.get(end).is_some_and(char::is_ascii_alphabetic)
+    }
+
+    /// Parse the name after a backslash (which must be at `pos`).
+    fn parse_command_name(&mut self) -> String {
+        self.pos += 1;
+        let start = self.pos;
+        while self.peek().is_some_and(|ch| ch.is_ascii_alphabetic()) {
+            self.pos += 1;
+        }
+        if self.pos == start && self.peek().is_some() {
+            // Control symbol such as \{ or \,
+            self.pos += 1;
+        }
+        self.chars[start..self.pos].iter().collect()
+    }
+
+    /// Parse a delimiter after `\left` or `\right`.
+    fn parse_delimiter(&mut self) -> String {
+        self.skip_whitespace();
+        match self.peek() {
+            Some('\\') => {
+                let name = self.parse_command_name();
+                symbol(&name).map(String::from).unwrap_or(name)
+            },
+            Some('.') => {
+                self.pos += 1;
+                String::new()
+            },
+            Some(ch) => {
+                self.pos += 1;
+                ch.to_string()
+            },
+            None => String::new(),
+        }
+    }
+
+    fn parse_atom(&mut self) -> Atom {
+        let Some(ch) = self.peek() else {
+            return Atom::Group(Vec::new());
+        };
+        match ch {
+            '{' => {
+                self.pos += 1;
+                Atom::Group(self.parse_row(Some('}')))
+            },
+            '\\' => self.parse_command(),
+            _ => {
+                self.pos += 1;
+                text(ch.to_string())
+            },
+        }
+    }
+
+    fn parse_command(&mut self) -> Atom {
+        let name = self.parse_command_name();
+        match name.as_str() {
+            "frac" | "dfrac" | "tfrac" => {
+                let num = self.parse_argument();
+                let den = self.parse_argument();
+                Atom::Fraction(num, den)
+            },
+            "sqrt" => {
+                self.skip_whitespace();
+                let degree = (self.peek() == Some('[')).then(|| {
+                    self.pos += 1;
+                    self.parse_row(Some(']'))
+                });
+                Atom::Root {
+                    degree,
+                    body: self.parse_argument(),
+                }
+            },
+            "left" => {
+                let open = self.parse_delimiter();
+                let body = self.parse_row(None);
+                let close = if self.at_command("right") {
+                    self.parse_command_name();
+                    self.parse_delimiter()
+                } else {
+                    String::new()
+                };
+                Atom::Delimited { open, close, body }
+            },
+            "text" | "textrm" | "mathrm" | "operatorname" | "mbox" => Atom::Text {
+                text: self.parse_text_argument(),
+                upright: true,
+            },
+            "mathbf" | "mathit" | "mathbb" | "mathcal" | "boldsymbol" => {
+                Atom::Group(self.parse_argument())
+            },
+            _ => {
+                if let Some(symbol) = nary_symbol(&name) {
+                    return Atom::Nary {
+                        symbol,
+                        sub: None,
+                        sup: None,
+                        body: Vec::new(),
+                    };
+                }
+                if FUNCTIONS.contains(&name.as_str()) {
+                    return Atom::Text {
+                        text: name,
+                        upright: true,
+                    };
+                }
+                match symbol(&name) {
+                    Some(symbol) => text(symbol.to_string()),
+                    // Unknown commands stay as written
+                    None => text(format!("\\{}", name)),
+                }
+            },
+        }
+    }
+}
+
+fn text(text: String) -> Atom {
+    Atom::Text {
+        text,
+        upright: false,
+    }
+}
+
+/// Attach a `_` or `^` script to a base, merging with existing scripts.
+fn attach_script(base: Atom, kind: char, script: Vec<Atom>) -> Atom {
+    match base {
+        Atom::Scripts { base, sub, sup } => {
+            if kind == '_' {
+                Atom::Scripts {
+                    base,
+                    sub: Some(script),
+                    sup,
+                }
+            } else {
+                Atom::Scripts {
+                    base,
+                    sub,
+                    sup: Some(script),
+                }
+            }
+        },
+        base => {
+            let (sub, sup) = if kind == '_' {
+                (Some(script), None)
+            } else {
+                (None, Some(script))
+            };
+            Atom::Scripts {
+                base: Box::new(base),
+                sub,
+                sup,
+            }
+        },
+    }
+}
+
+fn write_atoms(xml: &mut String, atoms: &[Atom]) {
+    let mut index = 0;
+    while index < atoms.len() {
+        // Merge runs of italic symbols into one run
+        if let Atom::Text { upright: false, .. } = &atoms[index] {
+            let mut run = String::new();
+            while let Some(Atom::Text {
+                text,
+                upright: false,
+            }) = atoms.get(index)
+            {
+                run.push_str(text);
+                index += 1;
+            }
+            write_run(xml, &run, false);
+            continue;
+        }
+        write_atom(xml, &atoms[index]);
+        index += 1;
+    }
+}
+
+fn write_run(xml: &mut String, text: &str, upright: bool) {
+    xml.push_str("<m:r>");
+    if upright {
+        xml.push_str("<m:rPr><m:sty m:val=\"p\"/></m:rPr>");
+    }
+    xml.push_str("<m:t xml:space=\"preserve\">");
+    escape_into(xml, text);
+    xml.push_str("</m:t></m:r>");
+}
+
+fn write_element(xml: &mut String, tag: &str, atoms: &[Atom]) {
+    let _ = write!(xml, "<m:{}>", tag);
+    write_atoms(xml, atoms);
+    let _ = write!(xml, "</m:{}>", tag);
+}
+
+fn write_atom(xml: &mut String, atom: &Atom) {
+    match atom {
+        Atom::Text { text, upright } => write_run(xml, text, *upright),
+        Atom::Group(atoms) => write_atoms(xml, atoms),
+        Atom::Fraction(num, den) => {
+            xml.push_str("<m:f>");
+            write_element(xml, "num", num);
+            write_element(xml, "den", den);
+            xml.push_str("</m:f>");
+        },
+        Atom::Root { degree, body } => {
+            xml.push_str("<m:rad>");
+            match degree {
+                Some(degree) => write_element(xml, "deg", degree),
+                None => xml.push_str("<m:radPr><m:degHide m:val=\"1\"/></m:radPr><m:deg/>"),
+            }
+            write_element(xml, "e", body);
+            xml.push_str("</m:rad>");
+        },
+        Atom::Scripts { base, sub, sup } => {
+            let tag = match (sub, sup) {
+                (Some(_), Some(_)) => "sSubSup",
+                (Some(_), None) => "sSub",
+                _ => "sSup",
+            };
+            let _ = write!(xml, "<m:{}><m:e>", tag);
+            write_atom(xml, base);
+            xml.push_str("</m:e>");
+            if let Some(sub) = sub {
+                write_element(xml, "sub", sub);
+            }
+            if let Some(sup) = sup {
+                write_element(xml, "sup", sup);
+            }
+            let _ = write!(xml, "</m:{}>", tag);
+        },
+        Atom::Nary {
+            symbol,
+            sub,
+            sup,
+            body,
+        } => {
+            xml.push_str("<m:nary><m:naryPr>");
+            let _ = write!(xml, "<m:chr m:val=\"{}\"/>", symbol);
+            if sub.is_none() {
+                xml.push_str("<m:subHide m:val=\"1\"/>");
+            }
+            if sup.is_none() {
+                xml.push_str("<m:supHide m:val=\"1\"/>");
+            }
+            xml.push_str("</m:naryPr>");
+            write_element(xml, "sub", sub.as_deref().unwrap_or_default());
+            write_element(xml, "sup", sup.as_deref().unwrap_or_default());
+            write_element(xml, "e", body);
+            xml.push_str("</m:nary>");
+        },
+        Atom::Delimited { open, close, body } => {
+            xml.push_str("<m:d><m:dPr><m:begChr m:val=\"");
+            escape_into(xml, open);
+            xml.push_str("\"/><m:endChr m:val=\"");
+            escape_into(xml, close);
+            xml.push_str("\"/></m:dPr>");
+            write_element(xml, "e", body);
+            xml.push_str("</m:d>");
+        },
+    }
+}
+
+fn escape_into(xml: &mut String, text: &str) {
+    for ch in text.chars() {
+        match ch {
+            '&' => xml.push_str("&amp;"),
+            '<' => xml.push_str("&lt;"),
+            '>' => xml.push_str("&gt;"),
+            '"' => xml.push_str("&quot;"),
+            _ => xml.push(ch),
+        }
+    }
+}
+
+/// Function names set upright.
+const FUNCTIONS: &[&str] = &[
+    "sin", "cos", "tan", "cot", "sec", "csc", "arcsin", "arccos", "arctan", "sinh", "cosh", "tanh",
+    "log", "ln", "lg", "exp", "lim", "max", "min", "sup", "inf", "det", "gcd", "deg", "arg", "dim",
+    "ker", "Pr",
+];
+
+/// The operator character of a large operator command.
+fn nary_symbol(name: &str) -> Option<char> {
+    Some(match name {
+        "sum" => '∑',
+        "prod" => '∏',
+        "coprod" => '∐',
+        "int" => '∫',
+        "iint" => '∬',
+        "iiint" => '∭',
+        "oint" => '∮',
+        "bigcup" => '⋃',
+        "bigcap" => '⋂',
+        _ => return None,
+    })
+}
+
+/// The character of a symbol command.
+fn symbol(name: &str) -> Option<&'static str> {
+    Some(match name {
+        // Greek letters
+        "alpha" => "α",
+        "beta" => "β",
+        "gamma" => "γ",
+        "delta" => "δ",
+        "epsilon" => "ϵ",
+        "varepsilon" => "ε",
+        "zeta" => "ζ",
+        "eta" => "η",
+        "theta" => "θ",
+        "vartheta" => "ϑ",
+        "iota" => "ι",
+        "kappa" => "κ",
+        "lambda" => "λ",
+        "mu" => "μ",
+        "nu" => "ν",
+        "xi" => "ξ",
+        "pi" => "π",
+        "rho" => "ρ",
+        "sigma" => "σ",
+        "tau" => "τ",
+        "upsilon" => "υ",
+        "phi" => "ϕ",
+        "varphi" => "φ",
+        "chi" => "χ",
+        "psi" => "ψ",
+        "omega" => "ω",
+        "Gamma" => "Γ",
+        "Delta" => "Δ",
+        "Theta" => "Θ",
+        "Lambda" => "Λ",
+        "Xi" => "Ξ",
+        "Pi" => "Π",
+        "Sigma" => "Σ",
+        "Phi" => "Φ",
+        "Psi" => "Ψ",
+        "Omega" => "Ω",
+        // Operators and relations
+        "times" => "×",
+        "cdot" => "⋅",
+        "div" => "÷",
+        "pm" => "±",
+        "mp" => "∓",
+        "leq" | "le" => "≤",
+        "geq" | "ge" => "≥",
+        "neq" | "ne" => "≠",
+        "approx" => "≈",
+        "equiv" => "≡",
+        "sim" => "∼",
+        "propto" => "∝",
+        "ll" => "≪",
+        "gg" => "≫",
+        "in" => "∈",
+        "notin" => "∉",
+        "subset" => "⊂",
+        "subseteq" => "⊆",
+        "supset" => "⊃",
+        "supseteq" => "⊇",
+        "cup" => "∪",
+        "cap" => "∩",
+        "setminus" => "∖",
+        "wedge" | "land" => "∧",
+        "vee" | "lor" => "∨",
+        "neg" | "lnot" => "¬",
+        "forall" => "∀",
+        "exists" => "∃",
+        "to" | "rightarrow" => "→",
+        "leftarrow" | "gets" => "←",
+        "leftrightarrow" => "↔",
+        "Rightarrow" | "implies" => "⇒",
+        "Leftarrow" => "⇐",
+        "Leftrightarrow" | "iff" => "⇔",
+        "mapsto" => "↦",
+        // Miscellaneous
+        "infty" => "∞",
+        "partial" => "∂",
+        "nabla" => "∇",
+        "emptyset" | "varnothing" => "∅",
+        "degree" => "°",
+        "prime" => "′",
+        "ldots" | "dots" => "…",
+        "cdots" => "⋯",
+        "vdots" => "⋮",
+        "ddots" => "⋱",
+        "langle" => "⟨",
+        "rangle" => "⟩",
+        "lfloor" => "⌊",
+        "rfloor" => "⌋",
+        "lceil" => "⌈",
+        "rceil" => "⌉",
+        "vert" | "|" => "|",
+        "Vert" => "‖",
+        "{" | "lbrace" => "{",
+        "}" | "rbrace" => "}",
+        "%" => "%",
+        "$" => "$",
+        "#" => "#",
+        "&" => "&",
+        "_" => "_",
+        // Spacing
+        "," => "\u{2009}",
+        ":" | ">" => "\u{205F}",
+        ";" => "\u{2004}",
+        "quad" => "\u{2003}",
+        "qquad" => "\u{2003}\u{2003}",
+        "!" => "",
+        " " => " ",
+        _ => return None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_latex_to_omml() {
+        assert_eq!(
+            latex_to_omml("x^2 + \\alpha", false),
+            "<m:oMath><m:sSup><m:e><m:r><m:t xml:space=\"preserve\">x</m:t></m:r></m:e>\
+             <m:sup><m:r><m:t xml:space=\"preserve\">2</m:t></m:r></m:sup></m:sSup>\
+             <m:r><m:t xml:space=\"preserve\">+α</m:t></m:r></m:oMath>"
+        );
+
+        let omml = latex_to_omml("\\sum_{i=1}^n \\frac{1}{\\sqrt{i}} \\foo", true);
+        assert!(omml.starts_with(
+            "<m:oMathPara><m:oMath><m:nary><m:naryPr><m:chr m:val=\"∑\"/></m:naryPr>"
+        ));
+        assert!(
+            omml.contains("<m:f><m:num><m:r><m:t xml:space=\"preserve\">1</m:t></m:r></m:num>")
+        );
+        assert!(omml.contains("<m:rad><m:radPr><m:degHide m:val=\"1\"/></m:radPr><m:deg/>"));
+        assert!(omml.contains("\\foo"));
+
+        let omml = latex_to_omml("\\left( a < b \\right]", false);
+        assert!(omml.contains("<m:begChr m:val=\"(\"/><m:endChr m:val=\"]\"/>"));
+        assert!(omml.contains("a&lt;b"));
+    }
+}
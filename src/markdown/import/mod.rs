@@ -0,0 +1,202 @@
+/// Markdown import into Office documents.
+///
+/// Builds Word documents and PowerPoint presentations from Markdown through
+/// the writer APIs ([`MutableDocument`](crate::ooxml::docx::MutableDocument)
+/// and [`MutablePresentation`](crate::ooxml::pptx::MutablePresentation)).
+///
+/// Supported Markdown is CommonMark plus GFM: headings, emphasis and
+/// strikethrough, nested lists and task lists, block quotes, fenced and
+/// indented code, tables, links, autolinks and images, with `$...$` and
+/// `$$...$$` LaTeX math.
+///
+/// **Note**: This module is only available when the `ooxml` feature is enabled.
+mod docx;
+mod math;
+mod parser;
+mod pptx;
+
+use crate::common::{Error, Result};
+use base64::Engine;
+use std::path::{Path, PathBuf};
+
+/// Options for importing Markdown.
+///
+/// # Examples
+///
+/// ```rust
+/// use litchi::markdown::ImportOptions;
+///
+/// // Resolve relative image paths against the Markdown file's directory
+/// let options = ImportOptions::new().with_base_dir("docs");
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct ImportOptions {
+    /// Directory that relative image paths are resolved against
+    ///
+    /// Defaults to the current directory. Local images are only read from
+    /// inside this directory; others are written as links.
+    pub base_dir: Option<PathBuf>,
+}
+
+impl ImportOptions {
+    /// Create a new `ImportOptions` with default values.
+    #[inline]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the directory that relative image paths are resolved against
+    /// and local images are read from.
+    #[inline]
+    pub fn with_base_dir(mut self, dir: impl Into<PathBuf>) -> Self {
+        self.base_dir = Some(dir.into());
+        self
+    }
+
+    /// Load an image referenced from Markdown.
+    ///
+    /// Local paths and `data:` URIs are loaded; remote images, missing
+    /// files and files outside the base directory return `None` and are
+    /// written as links instead.
+    fn load_image(&self, url: &str) -> Result<Option<Vec<u8>>> {
+        if let Some(data) = url.strip_prefix("data:") {
+            let (header, payload) = data
+                .split_once(',')
+                .ok_or_else(|| Error::ParseError(format!("Invalid data URI: {}", url)))?;
+            if !header.ends_with(";base64") {
+                return Ok(Some(payload.as_bytes().to_vec()));
+            }
+            return base64::engine::general_purpose::STANDARD
+                .decode(payload)
+                .map(Some)
+                .map_err(|e| Error::ParseError(format!("Invalid base64 image data: {}", e)));
+        }
+
+        let path = match url.strip_prefix("file://") {
+            Some(path) => Path::new(path),
+            None if url.contains("://") => return Ok(None),
+            None => Path::new(url),
+        };
+        let base = match &self.base_dir {
+            Some(base) => base.clone(),
+            None => match std::env::current_dir() {
+                Ok(dir) => dir,
+                Err(_) => return Ok(None),
+            },
+        };
+        let path = base.join(path);
+
+        // Resolve `..` and symlinks before checking the file is inside the base
+        let (Ok(base), Ok(path)) = (base.canonicalize(), path.canonicalize()) else {
+            return Ok(None);
+        };
+        if !path.starts_with(&base) {
+            return Ok(None);
+        }
+        Ok(std::fs::read(path).ok())
+    }
+}
+
+/// Trait for types that can be built from Markdown.
+///
+/// This is the counterpart of [`ToMarkdown`](super::ToMarkdown), implemented
+/// for the OOXML packages:
+///
+/// - [`docx::Package`](crate::ooxml::docx::Package): headings, paragraphs,
+///   lists, tables, hyperlinks, pictures and equations
+/// - [`pptx::Package`](crate::ooxml::pptx::Package): one slide per `---`
+///   separated section, titled by its first heading
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// use litchi::markdown::FromMarkdown;
+/// use litchi::ooxml::docx::Package;
+///
+/// # fn main() -> Result<(), litchi::Error> {
+/// let mut pkg = Package::from_markdown("# Report\n\nSome **bold** text and $e^{i\\pi} = -1$.")?;
+/// pkg.save("report.docx")?;
+/// # Ok(())
+/// # }
+/// ```
+pub trait FromMarkdown: Sized {
+    /// Build from Markdown with default options.
+    fn from_markdown(markdown: &str) -> Result<Self> {
+        Self::from_markdown_with_options(markdown, &ImportOptions::default())
+    }
+
+    /// Build from Markdown with custom options.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use litchi::markdown::{FromMarkdown, ImportOptions};
+    /// use litchi::ooxml::pptx::Package;
+    ///
+    /// # fn main() -> Result<(), litchi::Error> {
+    /// let markdown = std::fs::read_to_string("talk/slides.md")?;
+    /// let options = ImportOptions::new().with_base_dir("talk");
+    /// let mut pkg = Package::from_markdown_with_options(&markdown, &options)?;
+    /// pkg.save("talk.pptx")?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    fn from_markdown_with_options(markdown: &str, options: &ImportOptions) -> Result<Self>;
+}
+
+/// Scale an image's natural size (at 96 DPI) to fit within a box, in EMUs.
+///
+/// Images of unknown size get a 1-inch square.
+fn fit_image(data: &[u8], max_width: i64, max_height: i64) -> (i64, i64) {
+    const EMU_PER_PIXEL: i64 = 9525;
+    let Some((width, height)) = crate::common::image::sniff_dimensions(data)
+        .filter(|(width, height)| *width > 0 && *height > 0)
+    else {
+        let side = 914400.min(max_width).min(max_height);
+        return (side, side);
+    };
+
+    let width = width as i64 * EMU_PER_PIXEL;
+    let height = height as i64 * EMU_PER_PIXEL;
+    let scale = (max_width as f64 / width as f64)
+        .min(max_height as f64 / height as f64)
+        .min(1.0);
+    (
+        (width as f64 * scale) as i64,
+        (height as f64 * scale) as i64,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_load_image() {
+        let dir = std::env::temp_dir().join(format!("litchi-md-import-{}", std::process::id()));
+        let base = dir.join("docs");
+        std::fs::create_dir_all(&base).unwrap();
+        std::fs::write(base.join("inside.png"), b"inside").unwrap();
+        std::fs::write(dir.join("outside.png"), b"outside").unwrap();
+
+        let options = ImportOptions::new().with_base_dir(&base);
+        assert_eq!(
+            options.load_image("inside.png").unwrap().as_deref(),
+            Some(&b"inside"[..])
+        );
+        assert_eq!(options.load_image("missing.png").unwrap(), None);
+        assert_eq!(options.load_image("../outside.png").unwrap(), None);
+        let outside = dir.join("outside.png");
+        assert_eq!(options.load_image(outside.to_str().unwrap()).unwrap(), None);
+        assert_eq!(
+            options.load_image("https://example.com/a.png").unwrap(),
+            None
+        );
+        assert_eq!(
+            options.load_image("data:image/png;base64,aGk=").unwrap(),
+            Some(b"hi".to_vec())
+        );
+
+        std::fs::remove_dir_all(&dir).ok();
+    }
+}
@@ -0,0 +1,1074 @@
+/// Markdown parser for the importer.
+///
+/// A line-oriented parser for the CommonMark blocks and inlines the importer
+/// supports, plus the GFM extensions (tables, strikethrough, task lists,
+/// autolinks) and `$`/`$$` math. Inline content comes out flattened into
+/// styled spans, which is as much structure as the Office writers can hold.
+use crate::common::TextAlignment;
+
+/// A block of Markdown content.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum Block {
+    /// ATX or setext heading, level 1-6
+    Heading { level: u8, content: Vec<Inline> },
+    /// Paragraph of inline content
+    Paragraph(Vec<Inline>),
+    /// Bullet or ordered list; each item is a list of blocks
+    List {
+        ordered: bool,
+        items: Vec<Vec<Block>>,
+    },
+    /// Fenced or indented code block
+    Code(String),
+    /// Block quote
+    Quote(Vec<Block>),
+    /// GFM table; `None` alignment means the column has no alignment marker
+    Table {
+        alignments: Vec<Option<TextAlignment>>,
+        header: Vec<Vec<Inline>>,
+        rows: Vec<Vec<Vec<Inline>>>,
+    },
+    /// Display math (`$$...$$` or a `math` code fence), as LaTeX
+    Math(String),
+    /// Thematic break (`---`, `***`, `___`)
+    Rule,
+}
+
+/// Character formatting of a text span.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub(crate) struct Style {
+    pub bold: bool,
+    pub italic: bool,
+    pub strike: bool,
+    pub code: bool,
+}
+
+/// A span of inline content.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum Inline {
+    /// Styled text
+    Text(String, Style),
+    /// Hyperlink; the label is flattened to plain text
+    Link { text: String, url: String },
+    /// Image reference
+    Image { alt: String, url: String },
+    /// Inline math (`$...$`), as LaTeX
+    Math(String),
+    /// Hard line break
+    Break,
+}
+
+/// Get the plain text of inline content.
+pub(crate) fn plain_text(inlines: &[Inline]) -> String {
+    let mut text = String::new();
+    for inline in inlines {
+        match inline {
+            Inline::Text(t, _) | Inline::Link { text: t, .. } | Inline::Math(t) => text.push_str(t),
+            Inline::Image { alt, .. } => text.push_str(alt),
+            Inline::Break => text.push('\n'),
+        }
+    }
+    text
+}
+
+/// Parse a Markdown document into blocks.
+pub(crate) fn parse(markdown: &str) -> Vec<Block> {
+    let lines: Vec<String> = markdown.lines().map(expand_tabs).collect();
+    parse_blocks(&lines)
+}
+
+/// Expand tabs in the leading whitespace of a line to 4-column stops.
+fn expand_tabs(line: &str) -> String {
+    if !line.starts_with([' ', '\t']) || !line.contains('\t') {
+        return line.to_string();
+    }
+    let mut out = String::with_capacity(line.len() + 8);
+    let mut chars = line.char_indices();
+    for (index, ch) in chars.by_ref() {
+        match ch {
+            '\t' => {
+                let width = 4 - out.len() % 4;
+                out.extend(std::iter::repeat_n(' ', width));
+            },
+            ' ' => out.push(' '),
+            _ => {
+                out.push_str(&line[index..]);
+                break;
+            },
+        }
+    }
+    out
+}
+
+/// Number of leading spaces.
+fn indent_of(line: &str) -> usize {
+    line.len() - line.trim_start_matches(' ').len()
+}
+
+fn is_blank(line: &str) -> bool {
+    line.trim().is_empty()
+}
+
+/// Strip up to `n` leading spaces.
+fn dedent(line: &str, n: usize) -> &str {
+    &line[indent_of(line).min(n)..]
+}
+
+fn parse_blocks(lines: &[String]) -> Vec<Block> {
+    let mut blocks = Vec::new();
+    let mut i = 0;
+
+    while i < lines.len() {
+        let line = lines[i].as_str();
+        if is_blank(line) {
+            i += 1;
+            continue;
+        }
+
+        let indent = indent_of(line);
+        let trimmed = &line[indent..];
+
+        // Indented code block
+        if indent >= 4 {
+            let mut code = Vec::new();
+            while i < lines.len() && (is_blank(&lines[i]) || indent_of(&lines[i]) >= 4) {
+                code.push(dedent(&lines[i], 4));
+                i += 1;
+            }
+            while code.last().is_some_and(|line| is_blank(line)) {
+                code.pop();
+            }
+            blocks.push(Block::Code(code.join("\n")));
+            continue;
+        }
+
+        // Fenced code block
+        if let Some((fence_char, fence_len, info)) = fence_open(trimmed) {
+            let mut code = Vec::new();
+            i += 1;
+            while i < lines.len() && !is_fence_close(&lines[i], fence_char, fence_len) {
+                code.push(dedent(&lines[i], indent));
+                i += 1;
+            }
+            i += 1;
+            let code = code.join("\n");
+            if info.split_whitespace().next() == Some("math") {
+                blocks.push(Block::Math(code.trim().to_string()));
+            } else {
+                blocks.push(Block::Code(code));
+            }
+            continue;
+        }
+
+        if let Some((level, text)) = atx_heading(trimmed) {
+            blocks.push(Block::Heading {
+                level,
+                content: parse_inlines(text),
+            });
+            i += 1;
+            continue;
+        }
+
+        if is_rule(trimmed) {
+            blocks.push(Block::Rule);
+            i += 1;
+            continue;
+        }
+
+        // Display math
+        if let Some(rest) = trimmed.strip_prefix("$$") {
+            let rest = rest.trim_end();
+            if let Some(math) = rest.strip_suffix("$$") {
+                blocks.push(Block::Math(math.trim().to_string()));
+                i += 1;
+                continue;
+            }
+            let mut math = vec![rest];
+            i += 1;
+            while i < lines.len() {
+                let line = lines[i].trim_end();
+                i += 1;
+                if let Some(last) = line.strip_suffix("$$") {
+                    math.push(last);
+                    break;
+                }
+                math.push(line);
+            }
+            blocks.push(Block::Math(math.join("\n").trim().to_string()));
+            continue;
+        }
+
+        // Block quote
+        if trimmed.starts_with('>') {
+            let mut quoted = Vec::new();
+            while i < lines.len() {
+                let line = lines[i].as_str();
+                let trimmed = line.trim_start();
+                if let Some(rest) = trimmed.strip_prefix('>') {
+                    quoted.push(rest.strip_prefix(' ').unwrap_or(rest).to_string());
+                } else if !is_blank(line)
+                    && quoted.last().is_some_and(|last: &String| !is_blank(last))
+                    && !interrupts_paragraph(trimmed)
+                {
+                    // Lazy continuation of a quoted paragraph
+                    quoted.push(trimmed.to_string());
+                } else {
+                    break;
+                }
+                i += 1;
+            }
+            blocks.push(Block::Quote(parse_blocks(&quoted)));
+            continue;
+        }
+
+        if let Some(marker) = list_marker(line) {
+            let (list, next) = parse_list(lines, i, marker);
+            blocks.push(list);
+            i = next;
+            continue;
+        }
+
+        // GFM table
+        if trimmed.contains('|')
+            && let Some(alignments) = lines.get(i + 1).and_then(|line| delimiter_row(line))
+        {
+            let header = split_row(trimmed);
+            if header.len() == alignments.len() {
+                let columns = header.len();
+                let header = header.iter().map(|cell| parse_inlines(cell)).collect();
+                let mut rows = Vec::new();
+                i += 2;
+                while i < lines.len() {
+                    let line = lines[i].trim_start();
+                    if is_blank(line) || !line.contains('|') || interrupts_paragraph(line) {
+                        break;
+                    }
+                    let mut cells: Vec<Vec<Inline>> = split_row(line)
+                        .iter()
+                        .map(|cell| parse_inlines(cell))
+                        .collect();
+                    cells.resize(columns, Vec::new());
+                    rows.push(cells);
+                    i += 1;
+                }
+                blocks.push(Block::Table {
+                    alignments,
+                    header,
+                    rows,
+                });
+                continue;
+            }
+        }
+
+        // Paragraph, possibly turned into a setext heading by its underline
+        let mut text = vec![trimmed];
+        let mut setext = None;
+        i += 1;
+        while i < lines.len() {
+            let line = lines[i].as_str();
+            if is_blank(line) {
+                break;
+            }
+            let trimmed = line.trim();
+            if indent_of(line) < 4 {
+                if let Some(level) = setext_underline(trimmed) {
+                    setext = Some(level);
+                    i += 1;
+                    break;
+                }
+                if interrupts_paragraph(trimmed) || list_marker(line).is_some() {
+                    break;
+                }
+            }
+            text.push(line.trim_start());
+            i += 1;
+        }
+
+        let content = parse_inlines(text.join("\n").trim_end());
+        blocks.push(match setext {
+            Some(level) => Block::Heading { level, content },
+            None => Block::Paragraph(content),
+        });
+    }
+
+    blocks
+}
+
+/// Whether a line starts a block that can interrupt a paragraph.
+fn interrupts_paragraph(trimmed: &str) -> bool {
+    atx_heading(trimmed).is_some()
+        || fence_open(trimmed).is_some()
+        || is_rule(trimmed)
+        || trimmed.starts_with("$$")
+        || trimmed.starts_with('>')
+}
+
+/// Parse an opening code fence, returning its character, length and info string.
+fn fence_open(trimmed: &str) -> Option<(char, usize, &str)> {
+    let fence_char = trimmed.chars().next().filter(|c| *c == '`' || *c == '~')?;
+    let len = trimmed.len() - trimmed.trim_start_matches(fence_char).len();
+    if len < 3 {
+        return None;
+    }
+    let info = trimmed[len..].trim();
+    if fence_char == '`' && info.contains('`') {
+        return None;
+    }
+    Some((fence_char, len, info))
+}
+
+fn is_fence_close(line: &str, fence_char: char, fence_len: usize) -> bool {
+    let trimmed = line.trim();
+    indent_of(line) < 4 && trimmed.len() >= fence_len && trimmed.chars().all(|c| c == fence_char)
+}
+
+/// Parse an ATX heading, returning its level and text.
+fn atx_heading(trimmed: &str) -> Option<(u8, &str)> {
+    let level = trimmed.len() - trimmed.trim_start_matches('#').len();
+    if !(1..=6).contains(&level) {
+        return None;
+    }
+    let rest = &trimmed[level..];
+    if !rest.is_empty() && !rest.starts_with([' ', '\t']) {
+        return None;
+    }
+    // Drop an optional closing sequence of #s
+    let mut text = rest.trim();
+    let closed = text.trim_end_matches('#');
+    if closed.is_empty() || closed.ends_with(' ') {
+        text = closed.trim_end();
+    }
+    Some((level as u8, text))
+}
+
+/// Whether a line is a thematic break.
+fn is_rule(trimmed: &str) -> bool {
+    let Some(rule_char) = trimmed
+        .chars()
+        .next()
+        .filter(|c| matches!(c, '-' | '*' | '_'))
+    else {
+        return false;
+    };
+    let mut count = 0;
+    for ch in trimmed.chars() {
+        match ch {
+            c if c == rule_char => count += 1,
+            ' ' | '\t' => {},
+            _ => return false,
+        }
+    }
+    count >= 3
+}
+
+/// The heading level of a setext underline.
+fn setext_underline(trimmed: &str) -> Option<u8> {
+    if !trimmed.is_empty() && trimmed.chars().all(|c| c == '=') {
+        Some(1)
+    } else if !trimmed.is_empty() && trimmed.chars().all(|c| c == '-') {
+        Some(2)
+    } else {
+        None
+    }
+}
+
+/// A list item marker.
+#[derive(Debug, Clone, Copy)]
+struct ListMarker {
+    /// Whether the list is ordered
+    ordered: bool,
+    /// The bullet character, or the delimiter after the number
+    delimiter: char,
+    /// Column where the item content starts
+    content: usize,
+}
+
+impl ListMarker {
+    fn continues(&self, other: &ListMarker) -> bool {
+        self.ordered == other.ordered && self.delimiter == other.delimiter
+    }
+}
+
+/// Parse a list item marker at the start of a line.
+fn list_marker(line: &str) -> Option<ListMarker> {
+    let indent = indent_of(line);
+    if indent >= 4 {
+        return None;
+    }
+    let rest = &line[indent..];
+    let (ordered, delimiter, marker_len) = match rest.as_bytes().first()? {
+        b'-' | b'*' | b'+' => (false, rest.as_bytes()[0] as char, 1),
+        b'0'..=b'9' => {
+            let digits = rest.len() - rest.trim_start_matches(|c: char| c.is_ascii_digit()).len();
+            let delimiter = rest[digits..].chars().next()?;
+            if digits > 9 || !matches!(delimiter, '.' | ')') {
+                return None;
+            }
+            (true, delimiter, digits + 1)
+        },
+        _ => return None,
+    };
+
+    let after = &rest[marker_len..];
+    if after.is_empty() {
+        return Some(ListMarker {
+            ordered,
+            delimiter,
+            content: indent + marker_len + 1,
+        });
+    }
+    let spaces = after.len() - after.trim_start_matches(' ').len();
+    if spaces == 0 {
+        return None;
+    }
+    // A marker followed by more than four spaces starts indented code
+    let spaces = if spaces > 4 { 1 } else { spaces };
+    Some(ListMarker {
+        ordered,
+        delimiter,
+        content: indent + marker_len + spaces,
+    })
+}
+
+/// Parse a list starting at line `start`, returning it and the next line index.
+fn parse_list(lines: &[String], start: usize, first: ListMarker) -> (Block, usize) {
+    let mut items = Vec::new();
+    let mut i = start;
+
+    while i < lines.len() {
+        let Some(marker) = list_marker(&lines[i]).filter(|m| m.continues(&first)) else {
+            break;
+        };
+        let first_line = lines[i].get(marker.content..).unwrap_or("");
+        let mut item = vec![task_marker(first_line)];
+        i += 1;
+
+        while i < lines.len() {
+            let line = lines[i].as_str();
+            if is_blank(line) {
+                // Blank lines belong to the item only if indented content follows
+                let next = lines[i..].iter().position(|line| !is_blank(line));
+                match next {
+                    Some(offset) if indent_of(&lines[i + offset]) >= marker.content => {
+                        item.push(String::new());
+                        i += 1;
+                    },
+                    _ => break,
+                }
+            } else if indent_of(line) >= marker.content {
+                item.push(line[marker.content..].to_string());
+                i += 1;
+            } else if list_marker(line).is_none()
+                && !interrupts_paragraph(line.trim_start())
+                && item.last().is_some_and(|last| !is_blank(last))
+            {
+                // Lazy continuation of the item's paragraph
+                item.push(line.trim_start().to_string());
+                i += 1;
+            } else {
+                break;
+            }
+        }
+        items.push(parse_blocks(&item));
+
+        // Items may be separated by blank lines
+        let next = lines[i..]
+            .iter()
+            .position(|line| !is_blank(line))
+            .map(|offset| i + offset);
+        match next {
+            Some(next) if list_marker(&lines[next]).is_some_and(|m| m.continues(&first)) => {
+                i = next
+            },
+            _ => break,
+        }
+    }
+
+    let list = Block::List {
+        ordered: first.ordered,
+        items,
+    };
+    (list, i)
+}
+
+/// Replace a GFM task list marker with a ballot box.
+fn task_marker(text: &str) -> String {
+    if let Some(rest) = text.strip_prefix("[ ] ") {
+        format!("\u{2610} {}", rest)
+    } else if let Some(rest) = text
+        .strip_prefix("[x] ")
+        .or_else(|| text.strip_prefix("[X] "))
+    {
+        format!("\u{2612} {}", rest)
+    } else {
+        text.to_string()
+    }
+}
+
+/// Parse a GFM table delimiter row into column alignments.
+fn delimiter_row(line: &str) -> Option<Vec<Option<TextAlignment>>> {
+    let trimmed = line.trim();
+    if !trimmed.contains('-') || indent_of(line) >= 4 {
+        return None;
+    }
+    split_row(trimmed)
+        .iter()
+        .map(|cell| {
+            let left = cell.starts_with(':');
+            let right = cell.ends_with(':');
+            let dashes = cell.trim_matches(':');
+            if dashes.is_empty() || !dashes.chars().all(|c| c == '-') {
+                return None;
+            }
+            Some(match (left, right) {
+                (true, true) => Some(TextAlignment::Center),
+                (false, true) => Some(TextAlignment::Right),
+                (true, false) => Some(TextAlignment::Left),
+                (false, false) => None,
+            })
+        })
+        .collect()
+}
+
+/// Split a table row into trimmed cells, honoring `\|` escapes.
+fn split_row(line: &str) -> Vec<String> {
+    let line = line.trim();
+    let line = line.strip_prefix('|').unwrap_or(line);
+    let line = match line.strip_suffix('|') {
+        Some(stripped) if !stripped.ends_with('\\') => stripped,
+        _ => line,
+    };
+
+    let mut cells = Vec::new();
+    let mut cell = String::new();
+    let mut chars = line.chars().peekable();
+    while let Some(ch) = chars.next() {
+        match ch {
+            '\\' if chars.peek() == Some(&'|') => {
+                cell.push('|');
+                chars.next();
+            },
+            '|' => cells.push(std::mem::take(&mut cell).trim().to_string()),
+            _ => cell.push(ch),
+        }
+    }
+    cells.push(cell.trim().to_string());
+    cells
+}
+
+/// Parse inline content into flattened, styled spans.
+pub(crate) fn parse_inlines(text: &str) -> Vec<Inline> {
+    let mut inlines = Vec::new();
+    parse_inlines_into(text, Style::default(), &mut inlines);
+
+    // Merge adjacent text spans that share a style
+    let mut merged: Vec<Inline> = Vec::with_capacity(inlines.len());
+    for inline in inlines {
+        if let Inline::Text(text, style) = &inline
+            && let Some(Inline::Text(last, last_style)) = merged.last_mut()
+            && last_style == style
+        {
+            last.push_str(text);
+            continue;
+        }
+        merged.push(inline);
+    }
+    merged
+}
+
+fn parse_inlines_into(text: &str, style: Style, out: &mut Vec<Inline>) {
+    let bytes = text.as_bytes();
+    let mut buffer = String::new();
+    let mut i = 0;
+
+    let flush = |buffer: &mut String, out: &mut Vec<Inline>| {
+        if !buffer.is_empty() {
+            out.push(Inline::Text(std::mem::take(buffer), style));
+        }
+    };
+
+    while i < bytes.len() {
+        match bytes[i] {
+            b'\\' => match bytes.get(i + 1) {
+                Some(b'\n') => {
+                    flush(&mut buffer, out);
+                    out.push(Inline::Break);
+                    i += 2;
+                },
+                Some(next) if next.is_ascii_punctuation() => {
+                    buffer.push(*next as char);
+                    i += 2;
+                },
+                _ => {
+                    buffer.push('\\');
+                    i += 1;
+                },
+            },
+            b'`' => {
+                let run = run_length(bytes, i, b'`');
+                match find_code_close(bytes, i + run, run) {
+                    Some(close) => {
+                        flush(&mut buffer, out);
+                        out.push(Inline::Text(
+                            code_span_text(&text[i + run..close]),
+                            Style {
+                                code: true,
+                                ..style
+                            },
+                        ));
+                        i = close + run;
+                    },
+                    None => {
+                        buffer.push_str(&text[i..i + run]);
+                        i += run;
+                    },
+                }
+            },
+            b'$' => match inline_math(text, i) {
+                Some((math, end)) => {
+                    flush(&mut buffer, out);
+                    out.push(Inline::Math(math.to_string()));
+                    i = end;
+                },
+                None => {
+                    buffer.push('$');
+                    i += 1;
+                },
+            },
+            b'!' if bytes.get(i + 1) == Some(&b'[') => match link(text, i + 1) {
+                Some((label, url, end)) => {
+                    flush(&mut buffer, out);
+                    out.push(Inline::Image {
+                        alt: plain_text(&parse_inlines(label)),
+                        url,
+                    });
+                    i = end;
+                },
+                None => {
+                    buffer.push('!');
+                    i += 1;
+                },
+            },
+            b'[' => match link(text, i) {
+                Some((label, url, end)) => {
+                    flush(&mut buffer, out);
+                    let label = parse_inlines(label);
+                    if label
+                        .iter()
+                        .any(|inline| matches!(inline, Inline::Image { .. }))
+                    {
+                        // A linked image keeps the image and drops the link
+                        out.extend(
+                            label
+                                .into_iter()
+                                .filter(|inline| matches!(inline, Inline::Image { .. })),
+                        );
+                    } else {
+                        out.push(Inline::Link {
+                            text: plain_text(&label),
+                            url,
+                        });
+                    }
+                    i = end;
+                },
+                None => {
+                    buffer.push('[');
+                    i += 1;
+                },
+            },
+            b'<' => match autolink(text, i) {
+                Some((label, url, end)) => {
+                    flush(&mut buffer, out);
+                    out.push(Inline::Link {
+                        text: label.to_string(),
+                        url,
+                    });
+                    i = end;
+                },
+                None => {
+                    buffer.push('<');
+                    i += 1;
+                },
+            },
+            delimiter @ (b'*' | b'_' | b'~') => {
+                let run = run_length(bytes, i, delimiter);
+                match emphasis(text, i, delimiter, run) {
+                    Some(close) => {
+                        flush(&mut buffer, out);
+                        let inner = match (delimiter, run) {
+                            (b'~', _) => Style {
+                                strike: true,
+                                ..style
+                            },
+                            (_, 1) => Style {
+                                italic: true,
+                                ..style
+                            },
+                            (_, 2) => Style {
+                                bold: true,
+                                ..style
+                            },
+                            _ => Style {
+                                bold: true,
+                                italic: true,
+                                ..style
+                            },
+                        };
+                        parse_inlines_into(&text[i + run..close], inner, out);
+                        i = close + run;
+                    },
+                    None => {
+                        buffer.push_str(&text[i..i + run]);
+                        i += run;
+                    },
+                }
+            },
+            b'\n' => {
+                // Two trailing spaces make a hard break; otherwise a soft break
+                let hard = buffer.ends_with("  ");
+                buffer.truncate(buffer.trim_end_matches(' ').len());
+                if hard {
+                    flush(&mut buffer, out);
+                    out.push(Inline::Break);
+                } else {
+                    buffer.push(' ');
+                }
+                i += 1;
+                while bytes.get(i) == Some(&b' ') {
+                    i += 1;
+                }
+            },
+            _ => {
+                let ch = text[i..].chars().next().unwrap_or_default();
+                buffer.push(ch);
+                i += ch.len_utf8().max(1);
+            },
+        }
+    }
+
+    flush(&mut buffer, out);
+}
+
+/// Length of the run of `byte` starting at `start`.
+fn run_length(bytes: &[u8], start: usize, byte: u8) -> usize {
+    bytes[start..].iter().take_while(|b| **b == byte).count()
+}
+
+/// Find the backtick run of exactly `len` that closes a code span.
+fn find_code_close(bytes: &[u8], mut i: usize, len: usize) -> Option<usize> {
+    while i < bytes.len() {
+        if bytes[i] == b'`' {
+            let run = run_length(bytes, i, b'`');
+            if run == len {
+                return Some(i);
+            }
+            i += run;
+        } else {
+            i += 1;
+        }
+    }
+    None
+}
+
+/// Normalize code span content: line endings become spaces, and one
+/// space is stripped from each side when both are present.
+fn code_span_text(code: &str) -> String {
+    let code = code.replace('\n', " ");
+    if code.len() >= 2 && code.starts_with(' ') && code.ends_with(' ') && !code.trim().is_empty() {
+        code[1..code.len() - 1].to_string()
+    } else {
+        code
+    }
+}
+
+/// Parse inline math at `start`, returning its LaTeX and the end index.
+///
+/// Follows Pandoc: the opening `$` must be followed by a non-space, the
+/// closing `$` preceded by a non-space and not followed by a digit, so
+/// prices like "$5 and $10" stay text.
+fn inline_math(text: &str, start: usize) -> Option<(&str, usize)> {
+    let bytes = text.as_bytes();
+    let first = *bytes.get(start + 1)?;
+    if first == b'$' || first.is_ascii_whitespace() {
+        return None;
+    }
+    let mut i = start + 1;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'\\' => i += 2,
+            b'$' => {
+                if bytes[i - 1].is_ascii_whitespace()
+                    || bytes.get(i + 1).is_some_and(u8::is_ascii_digit)
+                {
+                    return None;
+                }
+                return Some((&text[start + 1..i], i + 1));
+            },
+            _ => i += 1,
+        }
+    }
+    None
+}
+
+/// Parse a link at the `[` at `start`, returning its label, URL and end index.
+fn link(text: &str, start: usize) -> Option<(&str, String, usize)> {
+    let bytes = text.as_bytes();
+    let mut depth = 0;
+    let mut i = start;
+    let label_end = loop {
+        match bytes.get(i)? {
+            b'\\' => i += 1,
+            b'[' => depth += 1,
+            b']' => {
+                depth -= 1;
+                if depth == 0 {
+                    break i;
+                }
+            },
+            _ => {},
+        }
+        i += 1;
+    };
+    if bytes.get(label_end + 1) != Some(&b'(') {
+        return None;
+    }
+
+    // Destination, either <bracketed> or up to whitespace with balanced parentheses
+    let mut i = label_end + 2;
+    while bytes.get(i).is_some_and(u8::is_ascii_whitespace) {
+        i += 1;
+    }
+    let url = if bytes.get(i) == Some(&b'<') {
+        let close = text[i..].find('>')? + i;
+        let url = &text[i + 1..close];
+        i = close + 1;
+        url
+    } else {
+        let url_start = i;
+        let mut parens = 0;
+        while let Some(&b) = bytes.get(i) {
+            match b {
+                b'(' => parens += 1,
+                b')' if parens == 0 => break,
+                b')' => parens -= 1,
+                b if b.is_ascii_whitespace() => break,
+                _ => {},
+            }
+            i += 1;
+        }
+        &text[url_start..i]
+    };
+
+    // Optional title, which is dropped
+    while bytes.get(i).is_some_and(u8::is_ascii_whitespace) {
+        i += 1;
+    }
+    if let Some(&quote @ (b'"' | b'\'' | b'(')) = bytes.get(i) {
+        let close = if quote == b'(' { b')' } else { quote };
+        i += 1;
+        while *bytes.get(i)? != close {
+            i += 1;
+        }
+        i += 1;
+        while bytes.get(i).is_some_and(u8::is_ascii_whitespace) {
+            i += 1;
+        }
+    }
+    if bytes.get(i) != Some(&b')') {
+        return None;
+    }
+
+    Some((&text[start + 1..label_end], url.to_string(), i + 1))
+}
+
+/// Parse an autolink at the `<` at `start`, returning its text, URL and end index.
+fn autolink(text: &str, start: usize) -> Option<(&str, String, usize)> {
+    let close = text[start..].find('>')? + start;
+    let inner = &text[start + 1..close];
+    if inner.is_empty() || inner.contains(char::is_whitespace) {
+        return None;
+    }
+    let url = if inner.contains("://") || inner.starts_with("mailto:") {
+        inner.to_string()
+    } else if inner.contains('@') && !inner.contains(':') {
+        format!("mailto:{}", inner)
+    } else {
+        return None;
+    };
+    Some((inner, url, close + 1))
+}
+
+/// Find the delimiter run that closes emphasis opened at `start`.
+///
+/// A simplification of the CommonMark rules: a run can close when it does
+/// not follow whitespace and open when it does not precede whitespace (`_`
+/// additionally may not sit inside a word). A closer at least as long as
+/// the opener closes it with its leading delimiters, and nested openers are
+/// matched first so their closers are skipped. Code spans are skipped too.
+fn emphasis(text: &str, start: usize, delimiter: u8, run: usize) -> Option<usize> {
+    let bytes = text.as_bytes();
+    if (delimiter == b'~' && run > 2) || run > 3 {
+        return None;
+    }
+    let alphanumeric = |b: Option<&u8>| b.is_some_and(u8::is_ascii_alphanumeric);
+    let can_open = |i: usize, len: usize| {
+        bytes.get(i + len).is_some_and(|b| !b.is_ascii_whitespace())
+            && (delimiter != b'_' || i == 0 || !alphanumeric(bytes.get(i - 1)))
+    };
+    let can_close = |i: usize, len: usize| {
+        i > 0
+            && !bytes[i - 1].is_ascii_whitespace()
+            && (delimiter != b'_' || !alphanumeric(bytes.get(i + len)))
+    };
+    if !can_open(start, run) {
+        return None;
+    }
+
+    let mut i = start + run;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'\\' => i += 2,
+            b'`' => {
+                let len = run_length(bytes, i, b'`');
+                i = find_code_close(bytes, i + len, len).map_or(i + len, |close| close + len);
+            },
+            b if b == delimiter => {
+                let len = run_length(bytes, i, delimiter);
+                if i > start + run && len >= run && can_close(i, len) {
+                    return Some(i);
+                }
+                i = match can_open(i, len).then(|| emphasis(text, i, delimiter, len)) {
+                    Some(Some(close)) => close + len,
+                    _ => i + len,
+                };
+            },
+            _ => i += 1,
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn text(s: &str) -> Inline {
+        Inline::Text(s.to_string(), Style::default())
+    }
+
+    #[test]
+    fn test_parse_inlines() {
+        let bold = Style {
+            bold: true,
+            ..Default::default()
+        };
+        let italic = Style {
+            italic: true,
+            ..Default::default()
+        };
+        assert_eq!(
+            parse_inlines("a **b *c*** `d` [e](http://x \"t\") $x^2$ costs $5"),
+            vec![
+                text("a "),
+                Inline::Text("b ".to_string(), bold),
+                Inline::Text(
+                    "c".to_string(),
+                    Style {
+                        bold: true,
+                        italic: true,
+                        ..Default::default()
+                    }
+                ),
+                text(" "),
+                Inline::Text(
+                    "d".to_string(),
+                    Style {
+                        code: true,
+                        ..Default::default()
+                    }
+                ),
+                text(" "),
+                Inline::Link {
+                    text: "e".to_string(),
+                    url: "http://x".to_string()
+                },
+                text(" "),
+                Inline::Math("x^2".to_string()),
+                text(" costs $5"),
+            ]
+        );
+        assert_eq!(
+            parse_inlines("snake_case_name and _it_\\*"),
+            vec![
+                text("snake_case_name and "),
+                Inline::Text("it".to_string(), italic),
+                text("*"),
+            ]
+        );
+        assert_eq!(
+            parse_inlines("line  \nnext ![alt](a.png)"),
+            vec![
+                text("line"),
+                Inline::Break,
+                text("next "),
+                Inline::Image {
+                    alt: "alt".to_string(),
+                    url: "a.png".to_string()
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_blocks() {
+        let markdown = "# Title\n\nSome *text*\nwrapped.\n\n- one\n- two\n  1. nested\n- [x] done\n\n\
+                        > quote\n\n```rust\nfn main() {}\n```\n\n| A | B |\n|:-|-:|\n| 1 | 2 |\n\n$$\nx^2\n$$\n\n---\n\nSub\n---\n";
+        let blocks = parse(markdown);
+        assert_eq!(blocks.len(), 9);
+        assert_eq!(
+            blocks[0],
+            Block::Heading {
+                level: 1,
+                content: vec![text("Title")]
+            }
+        );
+        assert!(
+            matches!(&blocks[1], Block::Paragraph(content) if plain_text(content) == "Some text wrapped.")
+        );
+        let Block::List { ordered, items } = &blocks[2] else {
+            panic!("expected a list");
+        };
+        assert!(!ordered);
+        assert_eq!(items.len(), 3);
+        assert!(matches!(&items[1][1], Block::List { ordered: true, items } if items.len() == 1));
+        assert_eq!(
+            items[2],
+            vec![Block::Paragraph(vec![text("\u{2612} done")])]
+        );
+        assert_eq!(
+            blocks[3],
+            Block::Quote(vec![Block::Paragraph(vec![text("quote")])])
+        );
+        assert_eq!(blocks[4], Block::Code("fn main() {}".to_string()));
+        let Block::Table {
+            alignments, rows, ..
+        } = &blocks[5]
+        else {
+            panic!("expected a table");
+        };
+        assert_eq!(
+            alignments,
+            &vec![Some(TextAlignment::Left), Some(TextAlignment::Right)]
+        );
+        assert_eq!(rows.len(), 1);
+        assert_eq!(blocks[6], Block::Math("x^2".to_string()));
+        assert_eq!(blocks[7], Block::Rule);
+        assert_eq!(
+            blocks[8],
+            Block::Heading {
+                level: 2,
+                content: vec![text("Sub")]
+            }
+        );
+    }
+}
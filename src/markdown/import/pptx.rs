@@ -0,0 +1,274 @@
+/// Markdown import into PowerPoint presentations.
+///
+/// Sections separated by thematic breaks (`---`) become slides. A heading
+/// at the start of a section becomes the slide title; the remaining blocks
+/// are stacked top to bottom as one text box per line, with pictures for
+/// images that stand alone in a paragraph. Content that doesn't fit on a
+/// slide runs past its bottom edge.
+use super::parser::{Block, Inline, parse, plain_text};
+use super::{FromMarkdown, ImportOptions, fit_image};
+use crate::common::Result;
+use crate::ooxml::pptx::{ImageFormat, MutableShape, MutableSlide, Package};
+
+/// Space around the slide content (0.5 inch).
+const MARGIN: i64 = 457200;
+
+/// Top of the content on slides with a title (1.5 inches).
+const TITLE_HEIGHT: i64 = 1371600;
+
+/// Height of one line of text (0.4 inch).
+const LINE_HEIGHT: i64 = 365760;
+
+/// Indentation per list or quote level (0.5 inch).
+const INDENT: i64 = 457200;
+
+/// Font for code spans and code blocks.
+const CODE_FONT: &str = "Courier New";
+
+/// Font for equations, which keep their LaTeX source.
+const MATH_FONT: &str = "Cambria Math";
+
+impl FromMarkdown for Package {
+    fn from_markdown_with_options(markdown: &str, options: &ImportOptions) -> Result<Self> {
+        let mut pkg = Package::new()?;
+        let pres = pkg.presentation_mut()?;
+        let (width, height) = (pres.slide_width(), pres.slide_height());
+
+        let blocks = parse(markdown);
+        for section in blocks.split(|block| matches!(block, Block::Rule)) {
+            if section.is_empty() {
+                continue;
+            }
+
+            let slide = pres.add_slide()?;
+            let (body, top) = match section.split_first() {
+                Some((Block::Heading { content, .. }, body)) => {
+                    slide.set_title(&plain_text(content));
+                    (body, TITLE_HEIGHT)
+                },
+                _ => (section, MARGIN),
+            };
+
+            let mut writer = SlideWriter {
+                slide,
+                options,
+                y: top,
+                width,
+                height,
+            };
+            for block in body {
+                writer.write_block(block, Context::default())?;
+            }
+        }
+        Ok(pkg)
+    }
+}
+
+/// Where a block is being written.
+#[derive(Debug, Clone, Copy, Default)]
+struct Context {
+    /// Indentation level from enclosing lists and quotes
+    indent: u32,
+    /// Whether the block is inside a block quote
+    quote: bool,
+}
+
+impl Context {
+    fn nested(self) -> Self {
+        Self {
+            indent: self.indent + 1,
+            ..self
+        }
+    }
+}
+
+struct SlideWriter<'a> {
+    slide: &'a mut MutableSlide,
+    options: &'a ImportOptions,
+    /// Top of the next shape
+    y: i64,
+    width: i64,
+    height: i64,
+}
+
+impl SlideWriter<'_> {
+    fn left(&self, context: Context) -> i64 {
+        MARGIN + context.indent as i64 * INDENT
+    }
+
+    fn content_width(&self, context: Context) -> i64 {
+        (self.width - MARGIN - self.left(context)).max(INDENT)
+    }
+
+    /// Add a line of text below the previous shape.
+    fn add_line(&mut self, text: &str, context: Context) -> &mut MutableShape {
+        let (x, width) = (self.left(context), self.content_width(context));
+        self.slide.add_text_box(text, x, self.y, width, LINE_HEIGHT);
+        self.y += LINE_HEIGHT;
+
+        let index = self.slide.shape_count() - 1;
+        let shape = self.slide.shape_mut(index).unwrap();
+        if context.quote {
+            shape.italic(true);
+        }
+        shape
+    }
+
+    /// Add each line of `text`, the first one prefixed with a list marker.
+    ///
+    /// Lines after a marked first line are indented to the item's text.
+    fn add_lines(&mut self, text: &str, marker: &str, context: Context) {
+        let continued = if marker.is_empty() {
+            context
+        } else {
+            context.nested()
+        };
+        for (index, line) in text.lines().enumerate() {
+            if index == 0 {
+                self.add_line(&format!("{}{}", marker, line), context);
+            } else {
+                self.add_line(line, continued);
+            }
+        }
+    }
+
+    fn write_block(&mut self, block: &Block, context: Context) -> Result<()> {
+        match block {
+            Block::Heading { content, .. } => {
+                for line in plain_text(content).lines() {
+                    self.add_line(line, context).bold(true);
+                }
+            },
+            Block::Paragraph(content) => self.write_paragraph(content, context)?,
+            Block::List { ordered, items } => {
+                for (index, item) in items.iter().enumerate() {
+                    let marker = if *ordered {
+                        format!("{}. ", index + 1)
+                    } else {
+                        "• ".to_string()
+                    };
+                    let rest = match item.split_first() {
+                        Some((Block::Paragraph(content), rest)) => {
+                            self.add_lines(&plain_text(content), &marker, context);
+                            rest
+                        },
+                        _ => {
+                            self.add_line(marker.trim_end(), context);
+                            item.as_slice()
+                        },
+                    };
+                    for block in rest {
+                        self.write_block(block, context.nested())?;
+                    }
+                }
+            },
+            Block::Code(code) => {
+                for line in code.lines() {
+                    self.add_line(line, context).font(CODE_FONT);
+                }
+            },
+            Block::Quote(blocks) => {
+                let inner = Context {
+                    quote: true,
+                    ..context.nested()
+                };
+                for block in blocks {
+                    self.write_block(block, inner)?;
+                }
+            },
+            Block::Table { header, rows, .. } => {
+                let row_text = |row: &[Vec<Inline>]| {
+                    row.iter()
+                        .map(|cell| plain_text(cell))
+                        .collect::<Vec<_>>()
+                        .join(" | ")
+                };
+                self.add_line(&row_text(header), context).bold(true);
+                for row in rows {
+                    self.add_line(&row_text(row), context);
+                }
+            },
+            Block::Math(latex) => {
+                for line in latex.lines() {
+                    self.add_line(line, context).font(MATH_FONT);
+                }
+            },
+            Block::Rule => {},
+        }
+        Ok(())
+    }
+
+    /// Write a paragraph as text, or as pictures if it only holds images.
+    fn write_paragraph(&mut self, content: &[Inline], context: Context) -> Result<()> {
+        let images_only = content.iter().all(|inline| match inline {
+            Inline::Image { .. } | Inline::Break => true,
+            Inline::Text(text, _) => text.trim().is_empty(),
+            _ => false,
+        });
+        if !images_only {
+            self.add_lines(&plain_text(content), "", context);
+            return Ok(());
+        }
+
+        for inline in content {
+            if let Inline::Image { alt, url } = inline {
+                self.write_image(alt, url, context)?;
+            }
+        }
+        Ok(())
+    }
+
+    fn write_image(&mut self, alt: &str, url: &str, context: Context) -> Result<()> {
+        match self.options.load_image(url)? {
+            Some(data) if ImageFormat::detect_from_bytes(&data).is_some() => {
+                let max_height = (self.height - MARGIN - self.y).max(LINE_HEIGHT);
+                let (width, height) = fit_image(&data, self.content_width(context), max_height);
+                self.slide.add_picture_from_bytes(
+                    data,
+                    self.left(context),
+                    self.y,
+                    width,
+                    height,
+                    Some(alt.to_string()),
+                )?;
+                self.y += height;
+            },
+            // Remote, missing or unsupported images keep their alt text and address
+            _ => {
+                let text = if alt.is_empty() {
+                    url.to_string()
+                } else {
+                    format!("{} ({})", alt, url)
+                };
+                self.add_line(&text, context);
+            },
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_markdown_to_pptx() {
+        let markdown = "# Intro\n\n- one\n- two\n\n---\n\n## Details\n\n```\nfn main() {}\n```\n\n\
+                        ---\n\nNo title here.\n";
+        let mut pkg = Package::from_markdown(markdown).unwrap();
+        let pres = pkg.presentation_mut().unwrap();
+        assert_eq!(pres.slide_count(), 3);
+
+        let titles: Vec<_> = (0..3)
+            .map(|index| pres.slide_mut(index).unwrap().title().map(str::to_string))
+            .collect();
+        assert_eq!(
+            titles,
+            [Some("Intro".to_string()), Some("Details".to_string()), None]
+        );
+
+        let slide = pres.slide_mut(0).unwrap();
+        assert_eq!(slide.shape_count(), 2);
+        assert_eq!(slide.shape(0).unwrap().text(), Some("• one"));
+    }
+}
@@ -40,9 +40,12 @@
 /// - [`ToMarkdown`] trait: Core trait for types that can be converted to Markdown
 /// - [`MarkdownOptions`]: Configuration for conversion behavior
 /// - [`config`]: Configuration types and enums
+/// - [`ImageSink`]: Destination for images (files, data URIs or a callback)
 /// - [`writer`]: Low-level writer for efficient output generation
 /// - [`document`]: Document-specific implementations
 /// - [`presentation`]: Presentation-specific implementations
+/// - [`FromMarkdown`] trait: Builds Word documents and presentations from
+///   Markdown (requires the `ooxml` feature)
 ///
 /// # Performance Considerations
 ///
@@ -100,6 +103,7 @@
 /// ```
 // Module declarations
 mod config;
+mod image;
 mod traits;
 pub mod unicode;
 mod writer;
@@ -117,6 +121,35 @@ mod document;
 #[cfg(any(feature = "ole", feature = "ooxml", feature = "odf", feature = "iwa"))]
 mod presentation;
 
+// Markdown import builds OOXML packages
+#[cfg(feature = "ooxml")]
+mod import;
+
 // Re-export public API
-pub use config::{FormulaStyle, MarkdownOptions, ScriptStyle, StrikethroughStyle, TableStyle};
+pub use config::{
+    FormulaStyle, MarkdownOptions, MetafileStyle, ScriptStyle, StrikethroughStyle, TableStyle,
+};
+pub use image::{ImageCallback, ImageSink};
 pub use traits::ToMarkdown;
+
+#[cfg(feature = "ooxml")]
+pub use import::{FromMarkdown, ImportOptions};
+
+// Shared with the HTML writer
+#[cfg(any(
+    feature = "ole",
+    feature = "ooxml",
+    feature = "odf",
+    feature = "rtf",
+    feature = "iwa"
+))]
+pub(crate) use document::paragraph_ranges;
+pub(crate) use image::{PlacedImages, StoredImage};
+#[cfg(any(
+    feature = "ole",
+    feature = "ooxml",
+    feature = "odf",
+    feature = "rtf",
+    feature = "iwa"
+))]
+pub(crate) use writer::{CellSpan, analyze_table_spans};
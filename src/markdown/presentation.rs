@@ -1,4 +1,5 @@
 use super::config::MarkdownOptions;
+use super::image::PlacedImages;
 use super::traits::ToMarkdown;
 use super::writer::MarkdownWriter;
 /// ToMarkdown implementations for Presentation types.
@@ -20,13 +21,20 @@ impl ToMarkdown for Presentation {
         let metadata_md = if options.include_metadata
             && let Some(metadata) = self.metadata()?
         {
-            let mut metadata_writer = MarkdownWriter::new(*options);
+            let mut metadata_writer = MarkdownWriter::new(options.clone());
             metadata_writer.write_metadata(&metadata)?;
             metadata_writer.finish()
         } else {
             String::new()
         };
 
+        // Store images up front (sequentially) so their link targets are known
+        let images = if options.image_sink.is_some() {
+            PlacedImages::store(self.images()?, options)?
+        } else {
+            PlacedImages::default()
+        };
+
         // Use optimized fast path that extracts text without shape parsing
         // This is significantly faster for PPT files (3-10x speedup)
         let slide_texts = self.extract_text_for_markdown()?;
@@ -38,7 +46,7 @@ impl ToMarkdown for Presentation {
             let slide_strings: Vec<String> = slide_texts
                 .into_par_iter()
                 .map(|(slide_num, text)| {
-                    let mut writer = MarkdownWriter::new(*options);
+                    let mut writer = MarkdownWriter::new(options.clone());
 
                     // Format slide header with first line as title
                     let first_line = text.lines().next().unwrap_or("");
@@ -56,6 +64,7 @@ impl ToMarkdown for Presentation {
                         writer.push_str(&text);
                         writer.push_str("\n\n");
                     }
+                    writer.write_images(images.at(slide_num.saturating_sub(1)));
 
                     writer.finish()
                 })
@@ -74,10 +83,14 @@ impl ToMarkdown for Presentation {
                 result.push_str(slide_md);
             }
 
+            let mut writer = MarkdownWriter::new(options.clone());
+            writer.write_images(images.trailing());
+            result.push_str(&writer.finish());
+
             result
         } else {
             // SEQUENTIAL PATH: Process slides sequentially for small presentations
-            let mut writer = MarkdownWriter::new(*options);
+            let mut writer = MarkdownWriter::new(options.clone());
 
             for (i, (slide_num, text)) in slide_texts.iter().enumerate() {
                 if i > 0 {
@@ -100,7 +113,9 @@ impl ToMarkdown for Presentation {
                     writer.push_str(text);
                     writer.push_str("\n\n");
                 }
+                writer.write_images(images.at(slide_num.saturating_sub(1)));
             }
+            writer.write_images(images.trailing());
 
             writer.finish()
         };
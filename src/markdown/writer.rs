@@ -1,10 +1,19 @@
 use super::config::{MarkdownOptions, TableStyle};
+use super::image::StoredImage;
 /// Low-level writer for Markdown generation.
 ///
 /// This module provides the `MarkdownWriter` struct which handles the actual
 /// conversion of document elements to Markdown format.
 ///
 /// **Note**: Some functionality requires the `ole` or `ooxml` feature to be enabled.
+#[cfg(any(
+    feature = "ole",
+    feature = "ooxml",
+    feature = "odf",
+    feature = "rtf",
+    feature = "iwa"
+))]
+use crate::common::ListInfo;
 use crate::common::{Error, Metadata, Result};
 #[cfg(any(feature = "ole", feature = "ooxml"))]
 use crate::document::{Cell, Paragraph, Run, Table};
@@ -43,20 +52,71 @@ enum ListType {
     Unordered,
 }
 
+/// Numbers of the ordered list items written so far, by list level.
+///
+/// A list item continues the numbering of the previous item at its level and
+/// restarts the levels below it. Other paragraphs leave the numbering alone,
+/// as a list continues after them; headings restart it.
+#[cfg(any(
+    feature = "ole",
+    feature = "ooxml",
+    feature = "odf",
+    feature = "rtf",
+    feature = "iwa"
+))]
+#[derive(Debug, Clone, Default)]
+pub(crate) struct ListNumbering {
+    /// Number of the last item at each level, `None` after a bulleted item
+    levels: Vec<Option<u32>>,
+}
+
+#[cfg(any(
+    feature = "ole",
+    feature = "ooxml",
+    feature = "odf",
+    feature = "rtf",
+    feature = "iwa"
+))]
+impl ListNumbering {
+    /// Advance past a paragraph, returning its number if it is an item of
+    /// an ordered list.
+    pub(crate) fn next(
+        &mut self,
+        heading_level: Option<u8>,
+        list_info: Option<&ListInfo>,
+    ) -> Option<u32> {
+        let Some(info) = list_info else {
+            if heading_level.is_some() {
+                self.levels.clear();
+            }
+            return None;
+        };
+
+        let level = info.level as usize;
+        self.levels.resize(level + 1, None);
+        let number = info.is_ordered().then(|| match self.levels[level] {
+            Some(previous) => previous.saturating_add(1),
+            None => info.start,
+        });
+        self.levels[level] = number;
+        number
+    }
+}
+
 /// Information about cell span (colspan and rowspan) for HTML rendering.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
-struct CellSpan {
+pub(crate) struct CellSpan {
     /// Number of columns this cell spans (horizontal merge)
-    colspan: usize,
+    pub colspan: usize,
     /// Number of rows this cell spans (vertical merge)
-    rowspan: usize,
+    pub rowspan: usize,
     /// Whether this cell should be skipped in rendering (it's covered by a merge)
-    skip: bool,
+    pub skip: bool,
 }
 
 impl CellSpan {
     /// Create a new cell span with default values (no merge).
-    fn new() -> Self {
+    pub(crate) fn new() -> Self {
         Self {
             colspan: 1,
             rowspan: 1,
@@ -87,6 +147,15 @@ pub(crate) struct MarkdownWriter {
     current_bold: bool,
     current_italic: bool,
     current_strikethrough: bool,
+    /// Numbering of the ordered lists written so far
+    #[cfg(any(
+        feature = "ole",
+        feature = "ooxml",
+        feature = "odf",
+        feature = "rtf",
+        feature = "iwa"
+    ))]
+    numbering: ListNumbering,
 }
 
 /// Pre-extracted cell information for efficient table processing.
@@ -120,7 +189,7 @@ struct CellData {
     feature = "rtf",
     feature = "iwa"
 ))]
-fn analyze_table_spans(table: &Table, use_parallel: bool) -> Result<Vec<Vec<CellSpan>>> {
+pub(crate) fn analyze_table_spans(table: &Table, use_parallel: bool) -> Result<Vec<Vec<CellSpan>>> {
     let rows = table.rows()?;
     if rows.is_empty() {
         return Ok(Vec::new());
@@ -303,9 +372,30 @@ impl MarkdownWriter {
             current_bold: false,
             current_italic: false,
             current_strikethrough: false,
+            #[cfg(any(
+                feature = "ole",
+                feature = "ooxml",
+                feature = "odf",
+                feature = "rtf",
+                feature = "iwa"
+            ))]
+            numbering: ListNumbering::default(),
         }
     }
 
+    /// Continue the list numbering of the paragraphs before the next one,
+    /// for a writer that does not see them.
+    #[cfg(any(
+        feature = "ole",
+        feature = "ooxml",
+        feature = "odf",
+        feature = "rtf",
+        feature = "iwa"
+    ))]
+    pub(crate) fn set_list_numbering(&mut self, numbering: ListNumbering) {
+        self.numbering = numbering;
+    }
+
     /// Write a paragraph to the buffer.
     ///
     /// **Note**: This method requires the `ole` or `ooxml` feature to be enabled.
@@ -320,6 +410,14 @@ impl MarkdownWriter {
         feature = "iwa"
     ))]
     pub fn write_paragraph(&mut self, para: &Paragraph) -> Result<()> {
+        // Headings and list items known from the document structure
+        let heading_level = para.heading_level()?;
+        let list_info = match heading_level {
+            Some(_) => None,
+            None => para.list_info()?,
+        };
+        let number = self.numbering.next(heading_level, list_info.as_ref());
+
         // First check for paragraph-level formulas (display math)
         #[cfg(feature = "ooxml")]
         {
@@ -336,6 +434,14 @@ impl MarkdownWriter {
             }
         }
 
+        if (heading_level.is_some() || list_info.is_some())
+            && self.write_structured_paragraph(para, heading_level, list_info, number)?
+        {
+            return Ok(());
+        }
+
+        // Otherwise lists are detected from the paragraph text.
+        //
         // PERFORMANCE OPTIMIZATION:
         // For styled output (which needs runs anyway), get runs first and derive text from them.
         // This avoids parsing the paragraph XML twice (once for text(), once for runs()).
@@ -393,9 +499,7 @@ impl MarkdownWriter {
                     self.write_list_item_from_runs(&runs, &list_info)?;
                 } else {
                     // Write runs with style information
-                    for run in runs {
-                        self.write_run(&run)?;
-                    }
+                    self.write_runs(&runs)?;
                 }
             }
         } else {
@@ -438,6 +542,132 @@ impl MarkdownWriter {
         Ok(())
     }
 
+    /// Write a heading or list item paragraph as `#` heading or list item.
+    ///
+    /// Returns `false` without writing anything if the paragraph is empty.
+    #[cfg(any(
+        feature = "ole",
+        feature = "ooxml",
+        feature = "odf",
+        feature = "rtf",
+        feature = "iwa"
+    ))]
+    fn write_structured_paragraph(
+        &mut self,
+        para: &Paragraph,
+        heading_level: Option<u8>,
+        list_info: Option<ListInfo>,
+        number: Option<u32>,
+    ) -> Result<bool> {
+        let runs = if self.options.include_styles {
+            para.runs()?
+        } else {
+            Vec::new()
+        };
+        let text = if runs.is_empty() {
+            para.text()?
+        } else {
+            self.extract_text_from_runs(&runs)?
+        };
+        if text.trim().is_empty() {
+            return Ok(false);
+        }
+
+        if let Some(level) = heading_level {
+            // Markdown has six heading levels
+            for _ in 0..level.clamp(1, 6) {
+                self.buffer.push('#');
+            }
+            self.buffer.push(' ');
+        } else if let Some(info) = list_info {
+            let indent = info.level as usize * self.options.list_indent;
+            let marker = match number.and_then(|number| info.format.label(number)) {
+                Some(label) => label + ".",
+                None => "-".to_string(),
+            };
+            write!(self.buffer, "{:indent$}{} ", "", marker, indent = indent)
+                .map_err(|e| Error::Other(e.to_string()))?;
+        }
+
+        if runs.is_empty() {
+            self.buffer.push_str(text.trim());
+        } else {
+            self.write_runs(&runs)?;
+        }
+
+        self.close_formatting();
+        self.buffer.push_str("\n\n");
+        Ok(true)
+    }
+
+    /// Write runs with formatting, grouping consecutive runs with the same
+    /// hyperlink target into a `[text](url)` link.
+    #[cfg(any(
+        feature = "ole",
+        feature = "ooxml",
+        feature = "odf",
+        feature = "rtf",
+        feature = "iwa"
+    ))]
+    fn write_runs(&mut self, runs: &[Run]) -> Result<()> {
+        let mut open_link: Option<String> = None;
+
+        for run in runs {
+            let link = run.hyperlink()?;
+            if link != open_link {
+                // Formatting markers must not straddle the link brackets
+                self.close_formatting();
+                if let Some(url) = open_link.take() {
+                    self.write_link_target(&url);
+                }
+                if link.is_some() {
+                    self.buffer.push('[');
+                }
+                open_link = link;
+            }
+            self.write_run(run)?;
+        }
+
+        if let Some(url) = open_link {
+            self.close_formatting();
+            self.write_link_target(&url);
+        }
+        Ok(())
+    }
+
+    /// Close a link opened with `[`, writing its target.
+    fn write_link_target(&mut self, url: &str) {
+        self.buffer.push_str("](");
+        for ch in url.chars() {
+            match ch {
+                ' ' => self.buffer.push_str("%20"),
+                '(' => self.buffer.push_str("%28"),
+                ')' => self.buffer.push_str("%29"),
+                _ => self.buffer.push(ch),
+            }
+        }
+        self.buffer.push(')');
+    }
+
+    /// Write images as `![alt](target)` blocks.
+    pub fn write_images(&mut self, images: &[StoredImage]) {
+        for image in images {
+            self.buffer.push_str("![");
+            for ch in image.alt_text.chars() {
+                match ch {
+                    '\\' | '[' | ']' => {
+                        self.buffer.push('\\');
+                        self.buffer.push(ch);
+                    },
+                    '\n' | '\r' => self.buffer.push(' '),
+                    _ => self.buffer.push(ch),
+                }
+            }
+            self.write_link_target(&image.target);
+            self.buffer.push_str("\n\n");
+        }
+    }
+
     /// Write a paragraph that contains display-level formulas.
     ///
     /// This handles paragraphs where formulas are direct children of the paragraph (not within runs).
@@ -1681,3 +1911,148 @@ impl MarkdownWriter {
         Ok(())
     }
 }
+
+#[cfg(all(test, feature = "rtf"))]
+mod tests {
+    use super::*;
+    use crate::common::ListFormat;
+    use crate::rtf::{Formatting, ParagraphContent, Run as RtfRun};
+    use std::borrow::Cow;
+
+    fn paragraph(
+        properties: crate::rtf::Paragraph,
+        runs: Vec<(&'static str, Option<&'static str>, bool)>,
+    ) -> Paragraph {
+        let runs = runs
+            .into_iter()
+            .map(|(text, link, bold)| {
+                let formatting = Formatting {
+                    bold,
+                    ..Formatting::default()
+                };
+                RtfRun::new(Cow::Borrowed(text), formatting).with_hyperlink(link.map(Cow::Borrowed))
+            })
+            .collect();
+        Paragraph::Rtf(ParagraphContent::new(properties, runs))
+    }
+
+    fn render(paragraphs: &[Paragraph]) -> String {
+        let mut writer = MarkdownWriter::new(MarkdownOptions::default());
+        for para in paragraphs {
+            writer.write_paragraph(para).unwrap();
+        }
+        writer.finish()
+    }
+
+    #[test]
+    fn test_headings_and_lists() {
+        let heading = crate::rtf::Paragraph {
+            outline_level: Some(1),
+            ..Default::default()
+        };
+        let item = |level, format| crate::rtf::Paragraph {
+            list_override: Some(1),
+            list_level: level,
+            list_format: Some(format),
+            ..Default::default()
+        };
+
+        let markdown = render(&[
+            paragraph(heading, vec![("Overview", None, false)]),
+            paragraph(item(0, ListFormat::Decimal), vec![("First", None, false)]),
+            paragraph(item(1, ListFormat::Bullet), vec![("Nested", None, false)]),
+            paragraph(
+                item(0, ListFormat::LowerRoman),
+                vec![("Second", None, false)],
+            ),
+            paragraph(heading, vec![]),
+            paragraph(Default::default(), vec![("- not a list", None, false)]),
+        ]);
+        assert_eq!(
+            markdown,
+            "## Overview\n\n1. First\n\n  - Nested\n\nii. Second\n\n\n\n- not a list\n\n"
+        );
+    }
+
+    #[test]
+    fn test_list_numbering() {
+        let item = |level, format, start| crate::rtf::Paragraph {
+            list_override: Some(1),
+            list_level: level,
+            list_format: Some(format),
+            list_start: Some(start),
+            ..Default::default()
+        };
+        let heading = crate::rtf::Paragraph {
+            outline_level: Some(0),
+            ..Default::default()
+        };
+
+        let markdown = render(&[
+            paragraph(
+                item(0, ListFormat::Decimal, 3),
+                vec![("Three", None, false)],
+            ),
+            paragraph(
+                item(1, ListFormat::UpperLetter, 1),
+                vec![("A", None, false)],
+            ),
+            paragraph(
+                item(1, ListFormat::UpperLetter, 1),
+                vec![("B", None, false)],
+            ),
+            paragraph(Default::default(), vec![("Note", None, false)]),
+            paragraph(item(0, ListFormat::Decimal, 3), vec![("Four", None, false)]),
+            paragraph(
+                item(1, ListFormat::UpperLetter, 1),
+                vec![("A", None, false)],
+            ),
+            paragraph(heading, vec![("Next", None, false)]),
+            paragraph(
+                item(0, ListFormat::Decimal, 3),
+                vec![("Three", None, false)],
+            ),
+        ]);
+        assert_eq!(
+            markdown,
+            "3. Three\n\n  A. A\n\n  B. B\n\nNote\n\n4. Four\n\n  A. A\n\n# Next\n\n3. Three\n\n"
+        );
+    }
+
+    #[test]
+    fn test_links() {
+        let markdown = render(&[paragraph(
+            Default::default(),
+            vec![
+                ("See ", None, false),
+                ("the ", Some("https://example.com/a b"), false),
+                ("docs", Some("https://example.com/a b"), true),
+                (" or ", None, false),
+                ("intro", Some("#intro"), false),
+            ],
+        )]);
+        assert_eq!(
+            markdown,
+            "See [the **docs**](https://example.com/a%20b) or [intro](#intro)\n\n"
+        );
+    }
+
+    #[test]
+    fn test_images() {
+        let mut writer = MarkdownWriter::new(MarkdownOptions::default());
+        writer.write_images(&[
+            StoredImage {
+                alt_text: "Chart [Q1]\nsales".to_string(),
+                target: "images/image 1.png".to_string(),
+            },
+            StoredImage {
+                alt_text: String::new(),
+                target: "data:image/png;base64,AAAA".to_string(),
+            },
+        ]);
+        assert_eq!(
+            writer.finish(),
+            "![Chart \\[Q1\\] sales](images/image%201.png)\n\n![](data:image/png;base64,AAAA)\n\n"
+        );
+    }
+}
@@ -9,6 +9,8 @@ mod manifest;
 mod metadata;
 /// ODF package handling
 mod package;
+/// Framed picture references
+pub(crate) mod pictures;
 /// ODF XML utilities
 mod xml;
 
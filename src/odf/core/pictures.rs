@@ -0,0 +1,286 @@
+//! Picture references in ODF content.
+//!
+//! Pictures are `draw:image` elements inside `draw:frame`s whose
+//! `xlink:href` points into the package (usually `Pictures/`). The frame's
+//! `svg:title` and `svg:desc` children hold the alternative text.
+
+use super::Package;
+use crate::common::{EmbeddedImage, Error, ImageAnchor, Result};
+use quick_xml::Reader;
+use quick_xml::events::{BytesStart, Event};
+use std::io::{Read, Seek};
+
+/// A picture reference found in `content.xml`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PictureRef {
+    /// Path of the image inside the package
+    pub href: String,
+    /// Description (or title) of the enclosing frame
+    pub alt_text: Option<String>,
+    /// 0-based index of the enclosing `text:p`/`text:h`, counted like `Document::paragraphs()`
+    pub paragraph: Option<usize>,
+    /// 0-based index of the enclosing `draw:page`
+    pub page: Option<usize>,
+    /// Sheet name with 0-based row and column of the enclosing table cell
+    pub cell: Option<(String, u32, u32)>,
+}
+
+/// A `draw:frame` being read.
+#[derive(Default)]
+struct Frame {
+    href: Option<String>,
+    title: Option<String>,
+    desc: Option<String>,
+    has_object: bool,
+    paragraph: Option<usize>,
+    page: Option<usize>,
+    cell: Option<(String, u32, u32)>,
+}
+
+/// Which frame child text is being collected.
+#[derive(Clone, Copy, PartialEq)]
+enum Capture {
+    None,
+    Title,
+    Desc,
+}
+
+/// Position within `table:table` elements.
+#[derive(Default)]
+struct TableCursor {
+    sheet: Option<String>,
+    next_row: u32,
+    row: u32,
+    row_repeat: u32,
+    next_column: u32,
+    column: u32,
+    column_repeat: u32,
+    in_cell: bool,
+}
+
+impl TableCursor {
+    fn cell(&self) -> Option<(String, u32, u32)> {
+        let sheet = self.sheet.clone()?;
+        if self.in_cell {
+            Some((sheet, self.row, self.column))
+        } else {
+            // Shapes in `table:shapes` float over the sheet
+            Some((sheet, 0, 0))
+        }
+    }
+}
+
+/// Scan ODF content for framed pictures in document order.
+///
+/// Frames holding embedded objects (`draw:object`) are skipped: their
+/// `draw:image` is only a replacement preview.
+pub fn scan_picture_refs(xml: &str) -> Result<Vec<PictureRef>> {
+    let mut reader = Reader::from_str(xml);
+    let mut buf = Vec::new();
+    let mut refs = Vec::new();
+
+    let mut paragraph_count = 0usize;
+    let mut paragraph_depth = 0usize;
+    let mut page_count = 0usize;
+    let mut in_page = false;
+    let mut table = TableCursor::default();
+    let mut frames: Vec<Frame> = Vec::new();
+    let mut capture = Capture::None;
+
+    loop {
+        match reader.read_event_into(&mut buf) {
+            Ok(Event::Start(e)) => match e.name().as_ref() {
+                b"text:p" | b"text:h" => {
+                    paragraph_count += 1;
+                    paragraph_depth += 1;
+                },
+                b"draw:page" => {
+                    page_count += 1;
+                    in_page = true;
+                },
+                b"table:table" => {
+                    table = TableCursor {
+                        sheet: attribute(&e, b"table:name"),
+                        ..TableCursor::default()
+                    };
+                },
+                b"table:table-row" => {
+                    table.row = table.next_row;
+                    table.row_repeat = repeat(&e, b"table:number-rows-repeated");
+                    table.next_column = 0;
+                },
+                b"table:table-cell" | b"table:covered-table-cell" => {
+                    table.column = table.next_column;
+                    table.column_repeat = repeat(&e, b"table:number-columns-repeated");
+                    table.in_cell = true;
+                },
+                b"draw:frame" => frames.push(Frame {
+                    paragraph: (paragraph_depth > 0).then(|| paragraph_count - 1),
+                    page: in_page.then(|| page_count - 1),
+                    cell: table.cell(),
+                    ..Frame::default()
+                }),
+                b"draw:image" => visit_image(&e, &mut frames),
+                b"draw:object" | b"draw:object-ole" => {
+                    if let Some(frame) = frames.last_mut() {
+                        frame.has_object = true;
+                    }
+                },
+                b"svg:title" if !frames.is_empty() => capture = Capture::Title,
+                b"svg:desc" if !frames.is_empty() => capture = Capture::Desc,
+                _ => {},
+            },
+            Ok(Event::Empty(e)) => match e.name().as_ref() {
+                b"draw:page" => page_count += 1,
+                b"table:table-row" => table.next_row += repeat(&e, b"table:number-rows-repeated"),
+                b"table:table-cell" | b"table:covered-table-cell" => {
+                    table.next_column += repeat(&e, b"table:number-columns-repeated")
+                },
+                b"draw:image" => visit_image(&e, &mut frames),
+                b"draw:object" | b"draw:object-ole" => {
+                    if let Some(frame) = frames.last_mut() {
+                        frame.has_object = true;
+                    }
+                },
+                _ => {},
+            },
+            Ok(Event::Text(t)) if capture != Capture::None => {
+                let text = t.decode().map_err(|e| Error::XmlError(e.to_string()))?;
+                if let Some(frame) = frames.last_mut() {
+                    let target = match capture {
+                        Capture::Title => &mut frame.title,
+                        _ => &mut frame.desc,
+                    };
+                    target.get_or_insert_with(String::new).push_str(&text);
+                }
+            },
+            Ok(Event::End(e)) => match e.name().as_ref() {
+                b"text:p" | b"text:h" => paragraph_depth = paragraph_depth.saturating_sub(1),
+                b"draw:page" => in_page = false,
+                b"table:table" => table = TableCursor::default(),
+                // Repeat counts come from the start tag and apply once the element closes
+                b"table:table-row" => table.next_row = table.row + table.row_repeat,
+                b"table:table-cell" | b"table:covered-table-cell" => {
+                    table.next_column = table.column + table.column_repeat;
+                    table.in_cell = false;
+                },
+                b"svg:title" | b"svg:desc" => capture = Capture::None,
+                b"draw:frame" => {
+                    if let Some(frame) = frames.pop()
+                        && let Some(href) = frame.href
+                        && !frame.has_object
+                    {
+                        refs.push(PictureRef {
+                            href,
+                            alt_text: frame.desc.or(frame.title),
+                            paragraph: frame.paragraph,
+                            page: frame.page,
+                            cell: frame.cell,
+                        });
+                    }
+                },
+                _ => {},
+            },
+            Ok(Event::Eof) => break,
+            Err(e) => return Err(Error::XmlError(e.to_string())),
+            _ => {},
+        }
+        buf.clear();
+    }
+
+    Ok(refs)
+}
+
+/// Record the first `draw:image` of the innermost frame.
+fn visit_image(e: &BytesStart, frames: &mut [Frame]) {
+    if let Some(frame) = frames.last_mut()
+        && frame.href.is_none()
+        && let Some(href) = attribute(e, b"xlink:href")
+    {
+        frame.href = Some(href);
+    }
+}
+
+fn attribute(e: &BytesStart, name: &[u8]) -> Option<String> {
+    e.attributes()
+        .flatten()
+        .find(|attr| attr.key.as_ref() == name)
+        .and_then(|attr| attr.unescape_value().ok().map(|v| v.into_owned()))
+}
+
+fn repeat(e: &BytesStart, name: &[u8]) -> u32 {
+    attribute(e, name)
+        .and_then(|value| value.parse().ok())
+        .unwrap_or(1)
+}
+
+/// Resolve the pictures referenced by `content_xml` to their image data.
+///
+/// Links to files outside the package are skipped. `anchor` maps each
+/// reference to its position in the document.
+pub fn collect_pictures<R: Read + Seek>(
+    package: &Package<R>,
+    content_xml: &str,
+    anchor: impl Fn(&PictureRef) -> ImageAnchor,
+) -> Result<Vec<EmbeddedImage>> {
+    let mut images = Vec::new();
+
+    for picture in scan_picture_refs(content_xml)? {
+        let path = picture.href.trim_start_matches("./");
+        if path.contains("://") || path.starts_with('/') {
+            continue;
+        }
+        let Ok(data) = package.get_file(path) else {
+            continue;
+        };
+        let name = path.rsplit('/').next().unwrap_or(path);
+
+        images.push(
+            EmbeddedImage::new(name, data, anchor(&picture))
+                .with_alt_text(picture.alt_text.clone()),
+        );
+    }
+
+    Ok(images)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_scan_text_frame() {
+        let xml = r#"<office:text>
+            <text:p>Intro</text:p>
+            <text:p><draw:frame draw:name="Image1">
+                <draw:image xlink:href="Pictures/logo.png"/>
+                <svg:title>Logo</svg:title>
+                <svg:desc>Company logo</svg:desc>
+            </draw:frame></text:p>
+            <text:p><draw:frame><draw:object xlink:href="./Object 1"/>
+                <draw:image xlink:href="./ObjectReplacements/Object 1"/></draw:frame></text:p>
+        </office:text>"#;
+
+        let refs = scan_picture_refs(xml).unwrap();
+        assert_eq!(refs.len(), 1);
+        assert_eq!(refs[0].href, "Pictures/logo.png");
+        assert_eq!(refs[0].alt_text.as_deref(), Some("Company logo"));
+        assert_eq!(refs[0].paragraph, Some(1));
+        assert_eq!(refs[0].page, None);
+    }
+
+    #[test]
+    fn test_scan_spreadsheet_cell() {
+        let xml = r#"<office:spreadsheet><table:table table:name="Data">
+            <table:table-row table:number-rows-repeated="2"><table:table-cell/></table:table-row>
+            <table:table-row>
+                <table:table-cell table:number-columns-repeated="3"/>
+                <table:table-cell><draw:frame><draw:image xlink:href="Pictures/a.jpg"/></draw:frame></table:table-cell>
+            </table:table-row>
+        </table:table></office:spreadsheet>"#;
+
+        let refs = scan_picture_refs(xml).unwrap();
+        assert_eq!(refs.len(), 1);
+        assert_eq!(refs[0].cell, Some(("Data".to_string(), 2, 3)));
+    }
+}
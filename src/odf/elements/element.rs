@@ -238,6 +238,14 @@ impl Element {
         None
     }
 
+    /// Append a child element.
+    ///
+    /// Prefer this over [`ElementBase::add_child`], which stores trait objects
+    /// and cannot be used with the concrete children of an `Element`.
+    pub fn append_child(&mut self, child: Element) {
+        self.children.push(child);
+    }
+
     /// Create element from XML bytes
     pub fn from_bytes(bytes: &[u8]) -> Result<Self> {
         let mut reader = quick_xml::Reader::from_reader(bytes);
@@ -425,6 +433,15 @@ impl ElementBase for Element {
     fn children_mut(&mut self) -> &mut Vec<Box<dyn ElementBase>> {
         unsafe { std::mem::transmute(&mut self.children) }
     }
+
+    fn get_text_recursive(&self) -> String {
+        // Walk the concrete children; `children()` does not hold boxed elements
+        let mut text = self.text_content.clone();
+        for child in &self.children {
+            text.push_str(&child.get_text_recursive());
+        }
+        text
+    }
 }
 
 /// Helper for creating elements with specific tag names
@@ -142,12 +142,29 @@ impl DocumentParser {
 
                             element_stack.push((tag_name, element));
                         },
+                        "text:list" if table_depth == 0 => {
+                            // Nested list, kept inside its list item
+                            list_depth += 1;
+                            let mut element = super::element::Element::new(&tag_name);
+
+                            // Parse attributes
+                            for attr in e.attributes().flatten() {
+                                let key = String::from_utf8_lossy(attr.key.as_ref());
+                                let value = String::from_utf8_lossy(&attr.value);
+                                element.set_attribute(&key, &value);
+                            }
+
+                            element_stack.push((tag_name, element));
+                        },
                         "text:list" => {
-                            // Nested list
+                            // List inside a table
                             list_depth += 1;
                         },
                         // Handle nested elements within tracked elements
-                        _ if !element_stack.is_empty() && table_depth <= 1 && list_depth <= 1 => {
+                        _ if !element_stack.is_empty()
+                            && table_depth <= 1
+                            && (list_depth <= 1 || table_depth == 0) =>
+                        {
                             let mut element = super::element::Element::new(&tag_name);
 
                             // Parse attributes
@@ -164,10 +181,19 @@ impl DocumentParser {
                 },
                 Ok(Event::Text(ref t)) => {
                     // Add text content to the current element
-                    if let Some((_, element)) = element_stack.last_mut() {
+                    if let Some((tag, element)) = element_stack.last_mut() {
                         let text = String::from_utf8_lossy(t).to_string();
-                        let current_text = element.text().to_string();
-                        element.set_text(&format!("{}{}", current_text, text));
+                        if !element.children.is_empty()
+                            && matches!(tag.as_str(), "text:p" | "text:h" | "text:span" | "text:a")
+                        {
+                            // Text after a child keeps its position as a span
+                            let mut span = super::element::Element::new("text:span");
+                            span.set_text(&text);
+                            element.append_child(span);
+                        } else {
+                            let current_text = element.text().to_string();
+                            element.set_text(&format!("{}{}", current_text, text));
+                        }
                     }
                 },
                 Ok(Event::End(ref e)) => {
@@ -215,6 +241,16 @@ impl DocumentParser {
                                 elements.push(DocumentOrderElement::List(list));
                             }
                         },
+                        "text:list" if table_depth == 0 => {
+                            // Complete a nested list and add it to its list item
+                            list_depth -= 1;
+                            if element_stack.len() > 1 {
+                                let (_, child_element) = element_stack.pop().unwrap();
+                                if let Some((_, parent_element)) = element_stack.last_mut() {
+                                    parent_element.append_child(child_element);
+                                }
+                            }
+                        },
                         "text:list" => {
                             list_depth -= 1;
                         },
@@ -223,7 +259,7 @@ impl DocumentParser {
                             if element_stack.len() > 1 {
                                 let (_, child_element) = element_stack.pop().unwrap();
                                 if let Some((_, parent_element)) = element_stack.last_mut() {
-                                    parent_element.add_child(Box::new(child_element));
+                                    parent_element.append_child(child_element);
                                 }
                             } else {
                                 // Single element on stack, check if it should be completed
@@ -295,3 +331,53 @@ impl DocumentParser {
         Ok(tables)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::{ListFormat, ListInfo};
+
+    #[test]
+    fn test_nested_lists_and_links() {
+        let xml = r#"<office:text>
+            <text:h text:outline-level="2">Title</text:h>
+            <text:p>See <text:a xlink:href="https://example.com">the site</text:a> now</text:p>
+            <text:list text:style-name="L1">
+                <text:list-item><text:p>One</text:p>
+                    <text:list><text:list-item><text:p>Inner</text:p></text:list-item></text:list>
+                </text:list-item>
+                <text:list-item><text:p>Two</text:p></text:list-item>
+            </text:list>
+        </office:text>"#;
+        let elements = DocumentParser::parse_elements_in_order(xml).unwrap();
+        assert_eq!(elements.len(), 3);
+
+        let DocumentOrderElement::Paragraph(para) = &elements[1] else {
+            panic!("expected a paragraph");
+        };
+        assert_eq!(para.text().unwrap(), "See the site now");
+        let runs = para.runs().unwrap();
+        let links: Vec<_> = runs.iter().map(|run| run.hyperlink()).collect();
+        assert_eq!(links, [None, Some("https://example.com"), None]);
+
+        let DocumentOrderElement::List(list) = &elements[2] else {
+            panic!("expected a list");
+        };
+        let paragraphs = list.to_paragraphs(|style, level| match (style, level) {
+            (Some("L1"), 0) => ListInfo::new(level, ListFormat::Decimal),
+            _ => ListInfo::new(level, ListFormat::Bullet),
+        });
+        let items: Vec<_> = paragraphs
+            .iter()
+            .map(|para| (para.text().unwrap(), para.list_info().unwrap()))
+            .collect();
+        assert_eq!(
+            items,
+            [
+                ("One".to_string(), ListInfo::new(0, ListFormat::Decimal)),
+                ("Inner".to_string(), ListInfo::new(1, ListFormat::Bullet)),
+                ("Two".to_string(), ListInfo::new(0, ListFormat::Decimal)),
+            ]
+        );
+    }
+}
@@ -4,7 +4,7 @@
 //! including parsing, inheritance, and property resolution.
 
 use super::element::{Element, ElementBase};
-use crate::common::Result;
+use crate::common::{ListFormat, ListInfo, Result};
 use std::borrow::Cow;
 use std::collections::HashMap;
 
@@ -320,6 +320,9 @@ impl From<Style> for Element {
 #[derive(Debug, Clone, Default)]
 pub struct StyleRegistry {
     pub styles: HashMap<String, Style>,
+    /// Level format and start value of each list style (`text:list-style`),
+    /// by 0-based level
+    pub list_styles: HashMap<String, HashMap<u8, ListInfo>>,
 }
 
 impl StyleRegistry {
@@ -335,6 +338,17 @@ impl StyleRegistry {
         self.styles.get(name)
     }
 
+    /// Get the format of a level (0-based) of a list style
+    pub fn list_level_format(&self, list_style: &str, level: u8) -> Option<ListFormat> {
+        self.list_level_info(list_style, level)
+            .map(|info| info.format)
+    }
+
+    /// Get the format and start value of a level (0-based) of a list style
+    pub fn list_level_info(&self, list_style: &str, level: u8) -> Option<ListInfo> {
+        self.list_styles.get(list_style)?.get(&level).copied()
+    }
+
     /// Get resolved properties for a style (with inheritance)
     pub fn get_resolved_properties(&self, style_name: &str) -> StyleProperties<'static> {
         let mut resolved = StyleProperties::default();
@@ -418,9 +432,56 @@ impl StyleRegistry {
         // Full property parsing can be added later
         let mut reader = quick_xml::Reader::from_str(xml_content);
         let mut buf = Vec::new();
+        // The text:list-style being read
+        let mut list_style: Option<(String, HashMap<u8, ListInfo>)> = None;
 
         loop {
             match reader.read_event_into(&mut buf) {
+                Ok(
+                    quick_xml::events::Event::Start(ref e) | quick_xml::events::Event::Empty(ref e),
+                ) if e.name().as_ref().starts_with(b"text:list") => {
+                    let attribute = |name: &[u8]| {
+                        e.attributes()
+                            .flatten()
+                            .find(|attr| attr.key.as_ref() == name)
+                            .map(|attr| String::from_utf8_lossy(&attr.value).into_owned())
+                    };
+                    let level = attribute(b"text:level")
+                        .and_then(|level| level.parse::<u8>().ok())
+                        .map(|level| level.saturating_sub(1));
+                    match e.name().as_ref() {
+                        b"text:list-style" => {
+                            list_style =
+                                attribute(b"style:name").map(|name| (name, HashMap::new()));
+                        },
+                        b"text:list-level-style-number" => {
+                            if let (Some((_, levels)), Some(level)) = (list_style.as_mut(), level) {
+                                let format = attribute(b"style:num-format").unwrap_or_default();
+                                let start = attribute(b"text:start-value")
+                                    .and_then(|start| start.parse().ok())
+                                    .unwrap_or(1);
+                                levels.insert(
+                                    level,
+                                    ListInfo::new(level, ListFormat::from_odf(&format))
+                                        .with_start(start),
+                                );
+                            }
+                        },
+                        b"text:list-level-style-bullet" | b"text:list-level-style-image" => {
+                            if let (Some((_, levels)), Some(level)) = (list_style.as_mut(), level) {
+                                levels.insert(level, ListInfo::new(level, ListFormat::Bullet));
+                            }
+                        },
+                        _ => {},
+                    }
+                },
+                Ok(quick_xml::events::Event::End(ref e))
+                    if e.name().as_ref() == b"text:list-style" =>
+                {
+                    if let Some((name, levels)) = list_style.take() {
+                        registry.list_styles.insert(name, levels);
+                    }
+                },
                 Ok(quick_xml::events::Event::Start(ref e)) => {
                     let tag_name =
                         String::from_utf8(e.name().as_ref().to_vec()).unwrap_or_default();
@@ -86,7 +86,7 @@ impl Table {
 
     /// Add a row to the table
     pub fn add_row(&mut self, row: TableRow) {
-        self.element.add_child(Box::new(row.element));
+        self.element.append_child(row.element);
     }
 
     /// Get the number of columns (based on the widest row)
@@ -99,6 +99,21 @@ impl Table {
             .unwrap_or(0);
         Ok(max_cols)
     }
+
+    /// Get the number of paragraphs and headings in the table's cells
+    pub fn paragraph_count(&self) -> usize {
+        fn count(element: &Element) -> usize {
+            element
+                .children
+                .iter()
+                .map(|child| match child.tag_name() {
+                    "text:p" | "text:h" => 1 + count(child),
+                    _ => count(child),
+                })
+                .sum()
+        }
+        count(&self.element)
+    }
 }
 
 impl From<Table> for Element {
@@ -168,7 +183,7 @@ impl TableRow {
 
     /// Add a cell to the row
     pub fn add_cell(&mut self, cell: TableCell) {
-        self.element.add_child(Box::new(cell.element));
+        self.element.append_child(cell.element);
     }
 
     /// Get the style name
@@ -574,7 +589,7 @@ impl TableElements {
                     } else if !stack.is_empty() {
                         let element = stack.pop().unwrap();
                         if let Some(parent) = stack.last_mut() {
-                            parent.add_child(Box::new(element));
+                            parent.append_child(element);
                         }
                     }
                 },
@@ -4,12 +4,14 @@
 //! headings, and other text content elements.
 
 use super::element::{Element, ElementBase};
-use crate::common::{Error, Result};
+use crate::common::{Error, ListInfo, Result};
 
 /// A text paragraph element
 #[derive(Debug, Clone)]
 pub struct Paragraph {
     element: Element,
+    /// List membership, for paragraphs taken from list items
+    list: Option<ListInfo>,
 }
 
 impl Default for Paragraph {
@@ -23,6 +25,7 @@ impl Paragraph {
     pub fn new() -> Self {
         Self {
             element: Element::new("text:p"),
+            list: None,
         }
     }
 
@@ -33,7 +36,24 @@ impl Paragraph {
                 "Element is not a paragraph".to_string(),
             ));
         }
-        Ok(Self { element })
+        Ok(Self {
+            element,
+            list: None,
+        })
+    }
+
+    /// Create a paragraph from a heading, keeping its content and level
+    pub fn from_heading(heading: Heading) -> Self {
+        let mut element = Element::new("text:p");
+        for (name, value) in heading.element.attributes() {
+            element.set_attribute(name, value);
+        }
+        element.set_text(heading.element.text());
+        element.children = heading.element.children;
+        Self {
+            element,
+            list: None,
+        }
     }
 
     /// Get the text content of the paragraph
@@ -59,16 +79,19 @@ impl Paragraph {
         Ok(spans)
     }
 
-    /// Get all runs (text spans) within this paragraph.
+    /// Get all runs within this paragraph, in document order.
     ///
-    /// This is an alias for `spans()` to match the unified document API.
+    /// Unlike `spans()`, this includes the paragraph's own text and the
+    /// content of hyperlinks (`text:a`), whose runs carry the link target.
     pub fn runs(&self) -> Result<Vec<Span>> {
-        self.spans()
+        let mut runs = Vec::new();
+        collect_runs(&self.element, None, &mut runs);
+        Ok(runs)
     }
 
     /// Add a text span to this paragraph
     pub fn add_span(&mut self, span: Span) {
-        self.element.add_child(Box::new(span.element));
+        self.element.append_child(span.element);
     }
 
     /// Check if this paragraph is a heading
@@ -85,6 +108,55 @@ impl Paragraph {
     pub fn set_style_name(&mut self, name: &str) {
         self.element.set_attribute("text:style-name", name);
     }
+
+    /// Get the heading level (1-10) of a paragraph made from a heading
+    pub fn heading_level(&self) -> Option<u8> {
+        self.element
+            .get_attribute("text:outline-level")
+            .and_then(|level| level.parse().ok())
+            .filter(|level| *level > 0)
+    }
+
+    /// Get the list membership of a paragraph taken from a list item
+    pub fn list_info(&self) -> Option<ListInfo> {
+        self.list
+    }
+
+    /// Set the list membership of this paragraph
+    pub fn set_list_info(&mut self, list: Option<ListInfo>) {
+        self.list = list;
+    }
+}
+
+/// Collect the runs of a paragraph or hyperlink element.
+fn collect_runs(element: &Element, hyperlink: Option<&str>, runs: &mut Vec<Span>) {
+    // Text before the first child; later text is wrapped in spans by the parser
+    if !element.text().is_empty() {
+        let mut span = Span::new();
+        span.set_text(element.text());
+        if let Some(url) = hyperlink {
+            span.set_hyperlink(url);
+        }
+        runs.push(span);
+    }
+
+    for child in &element.children {
+        match child.tag_name() {
+            "text:span" => {
+                if let Ok(mut span) = Span::from_element(child.clone()) {
+                    if let Some(url) = hyperlink {
+                        span.set_hyperlink(url);
+                    }
+                    runs.push(span);
+                }
+            },
+            "text:a" => {
+                let url = child.get_attribute("xlink:href").or(hyperlink);
+                collect_runs(child, url, runs);
+            },
+            _ => {},
+        }
+    }
 }
 
 impl From<Paragraph> for Element {
@@ -141,6 +213,16 @@ impl Span {
         self.element.set_attribute("text:style-name", name);
     }
 
+    /// Get the hyperlink target of this span
+    pub fn hyperlink(&self) -> Option<&str> {
+        self.element.get_attribute("xlink:href")
+    }
+
+    /// Set the hyperlink target of this span
+    pub fn set_hyperlink(&mut self, url: &str) {
+        self.element.set_attribute("xlink:href", url);
+    }
+
     /// Check if the text is bold.
     ///
     /// Returns `None` if the style doesn't specify bold formatting.
@@ -291,7 +373,7 @@ impl List {
 
     /// Add a list item
     pub fn add_item(&mut self, item: ListItem) {
-        self.element.add_child(Box::new(item.element));
+        self.element.append_child(item.element);
     }
 
     /// Get the style name
@@ -303,6 +385,63 @@ impl List {
     pub fn set_style_name(&mut self, name: &str) {
         self.element.set_attribute("text:style-name", name);
     }
+
+    /// Expand this list, including nested lists, into paragraphs in
+    /// document order, each carrying its list level and format.
+    ///
+    /// `level_info` gives the format and start value of a level (0-based)
+    /// of a list style; nested lists without a style of their own use their
+    /// parent's.
+    pub fn to_paragraphs(
+        &self,
+        level_info: impl Fn(Option<&str>, u8) -> ListInfo,
+    ) -> Vec<Paragraph> {
+        let mut paragraphs = Vec::new();
+        expand_list(&self.element, None, 0, &level_info, &mut paragraphs);
+        paragraphs
+    }
+}
+
+fn expand_list(
+    list: &Element,
+    style: Option<&str>,
+    level: u8,
+    level_info: &dyn Fn(Option<&str>, u8) -> ListInfo,
+    paragraphs: &mut Vec<Paragraph>,
+) {
+    let style = list.get_attribute("text:style-name").or(style);
+    let info = level_info(style, level);
+
+    let items = list
+        .children
+        .iter()
+        .filter(|child| matches!(child.tag_name(), "text:list-item" | "text:list-header"));
+    for item in items {
+        for child in &item.children {
+            match child.tag_name() {
+                "text:p" | "text:h" => {
+                    let mut paragraph = Paragraph::new();
+                    for (name, value) in child.attributes() {
+                        paragraph.element.set_attribute(name, value);
+                    }
+                    // A heading inside a list is numbered as a list item
+                    paragraph.element.remove_attribute("text:outline-level");
+                    paragraph.element.set_text(child.text());
+                    paragraph.element.children = child.children.clone();
+                    paragraph.list = Some(info);
+                    paragraphs.push(paragraph);
+                },
+                "text:list" => expand_list(
+                    child,
+                    style,
+                    level.saturating_add(1),
+                    level_info,
+                    paragraphs,
+                ),
+                _ => {},
+            }
+        }
+    }
 }
 
 impl From<List> for Element {
@@ -366,7 +505,7 @@ impl ListItem {
 
     /// Add a paragraph to this list item
     pub fn add_paragraph(&mut self, paragraph: Paragraph) {
-        self.element.add_child(Box::new(paragraph.element));
+        self.element.append_child(paragraph.element);
     }
 }
 
@@ -9,6 +9,7 @@
 //! - Extract text, structured content, and metadata
 //! - Support for styles and formatting
 //! - Export capabilities (e.g., CSV for spreadsheets)
+//! - Create new documents, spreadsheets and presentations (see [`writer`])
 //!
 //! # Examples
 //!
@@ -42,12 +43,20 @@ mod odp;
 mod ods;
 /// ODF text document (.odt) support
 mod odt;
+/// ODF package writers (.odt, .ods, .odp)
+pub mod writer;
 
 // Re-export main types for convenience
 pub use odp::Presentation;
 pub use ods::{Cell as SCell, CellValue, Row as SRow, Sheet, Spreadsheet};
 pub use odt::Document;
 
+// Re-export writer types for creating documents
+pub use writer::{
+    MutableDocument, MutablePresentation, MutableSheet, MutableSlide, MutableSpreadsheet,
+    PackageWriter, ParagraphAlignment,
+};
+
 // Re-export shapes for presentations
 pub use odp::{Shape, Slide};
 
@@ -1,7 +1,8 @@
 //! Main Presentation structure and implementation.
 
 use super::Slide;
-use crate::common::{Error, Metadata, Result};
+use crate::common::{EmbeddedImage, Error, ImageAnchor, Metadata, Result};
+use crate::odf::core::pictures::collect_pictures;
 use crate::odf::core::{Content, Meta, Package, Styles};
 use std::io::Cursor;
 use std::path::Path;
@@ -225,4 +226,14 @@ impl Presentation {
             Ok(Metadata::default())
         }
     }
+
+    /// Get all framed pictures, anchored to the 0-based index of their slide.
+    pub fn images(&self) -> Result<Vec<EmbeddedImage>> {
+        collect_pictures(&self.package, self.content.xml_content(), |picture| {
+            picture
+                .page
+                .map(ImageAnchor::Slide)
+                .unwrap_or(ImageAnchor::Unanchored)
+        })
+    }
 }
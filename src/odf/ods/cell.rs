@@ -26,6 +26,42 @@ pub enum CellValue {
     Time(String),
 }
 
+impl From<&str> for CellValue {
+    fn from(s: &str) -> Self {
+        CellValue::Text(s.to_string())
+    }
+}
+
+impl From<String> for CellValue {
+    fn from(s: String) -> Self {
+        CellValue::Text(s)
+    }
+}
+
+impl From<f64> for CellValue {
+    fn from(n: f64) -> Self {
+        CellValue::Number(n)
+    }
+}
+
+impl From<i64> for CellValue {
+    fn from(n: i64) -> Self {
+        CellValue::Number(n as f64)
+    }
+}
+
+impl From<i32> for CellValue {
+    fn from(n: i32) -> Self {
+        CellValue::Number(n as f64)
+    }
+}
+
+impl From<bool> for CellValue {
+    fn from(b: bool) -> Self {
+        CellValue::Boolean(b)
+    }
+}
+
 /// A cell in an ODS spreadsheet.
 ///
 /// Cells contain typed values, optional formulas, and positioning information.
@@ -143,7 +143,11 @@ impl OdsParser {
                             .map_err(|_| Error::InvalidFormat("Invalid UTF-8".to_string()))?,
                     );
                 },
-                b"office:value" => {
+                // Booleans, dates and times carry their value in dedicated attributes
+                b"office:value"
+                | b"office:boolean-value"
+                | b"office:date-value"
+                | b"office:time-value" => {
                     value_str = Some(
                         String::from_utf8(attr.value.to_vec())
                             .map_err(|_| Error::InvalidFormat("Invalid UTF-8".to_string()))?,
@@ -1,7 +1,8 @@
 //! Main Spreadsheet structure and implementation.
 
 use super::Sheet;
-use crate::common::{Error, Metadata, Result};
+use crate::common::{EmbeddedImage, Error, ImageAnchor, Metadata, Result};
+use crate::odf::core::pictures::collect_pictures;
 use crate::odf::core::{Content, Meta, Package, Styles};
 use std::io::Cursor;
 use std::path::Path;
@@ -300,4 +301,23 @@ impl Spreadsheet {
             Ok(Metadata::default())
         }
     }
+
+    /// Get all framed pictures, anchored to the cell that contains them.
+    ///
+    /// Shapes floating over a sheet (in `table:shapes`) are reported at
+    /// row 0, column 0 of that sheet.
+    pub fn images(&self) -> Result<Vec<EmbeddedImage>> {
+        collect_pictures(
+            &self.package,
+            self.content.xml_content(),
+            |picture| match &picture.cell {
+                Some((sheet, row, column)) => ImageAnchor::Cell {
+                    sheet: sheet.clone(),
+                    row: *row,
+                    column: *column,
+                },
+                None => ImageAnchor::Unanchored,
+            },
+        )
+    }
 }
@@ -1,6 +1,7 @@
 //! OpenDocument Text document structure and API.
 
-use crate::common::{Error, Metadata, Result};
+use crate::common::{EmbeddedImage, Error, ImageAnchor, ListFormat, ListInfo, Metadata, Result};
+use crate::odf::core::pictures::collect_pictures;
 use crate::odf::core::{Content, Meta, Package, Styles};
 use crate::odf::elements::style::{StyleElements, StyleRegistry};
 use crate::odf::elements::table::Table as ElementTable;
@@ -159,6 +160,9 @@ impl Document {
             for (_name, style) in content_registry.styles {
                 style_registry.add_style(style);
             }
+            style_registry
+                .list_styles
+                .extend(content_registry.list_styles);
         }
 
         Ok(Self {
@@ -222,6 +226,9 @@ impl Document {
             for (_name, style) in content_registry.styles {
                 style_registry.add_style(style);
             }
+            style_registry
+                .list_styles
+                .extend(content_registry.list_styles);
         }
 
         Ok(Self {
@@ -356,27 +363,28 @@ impl Document {
                     ));
                 },
                 DocumentOrderElement::Heading(heading) => {
-                    // Convert heading to paragraph for unified API
-                    if let Ok(text) = heading.text() {
-                        let mut para = ElementParagraph::new();
-                        para.set_text(&text);
-                        if let Some(style) = heading.style_name() {
-                            para.set_style_name(style);
-                        }
-                        elements.push(crate::document::DocumentElement::Paragraph(
-                            crate::document::Paragraph::Odt(para),
-                        ));
-                    }
+                    // Convert heading to paragraph for unified API, keeping its level
+                    elements.push(crate::document::DocumentElement::Paragraph(
+                        crate::document::Paragraph::Odt(ElementParagraph::from_heading(heading)),
+                    ));
                 },
                 DocumentOrderElement::Table(table) => {
                     elements.push(crate::document::DocumentElement::Table(
                         crate::document::Table::Odt(table),
                     ));
                 },
-                DocumentOrderElement::List(_list) => {
-                    // Lists could be converted to paragraphs or handled separately
-                    // For now, skip lists in the unified document element API
-                    // as they are typically expanded to paragraphs by text extraction
+                DocumentOrderElement::List(list) => {
+                    // Expand lists into paragraphs carrying their level, format and start
+                    let paragraphs = list.to_paragraphs(|style, level| {
+                        style
+                            .and_then(|style| self.style_registry.list_level_info(style, level))
+                            .unwrap_or_else(|| ListInfo::new(level, ListFormat::default()))
+                    });
+                    elements.extend(paragraphs.into_iter().map(|para| {
+                        crate::document::DocumentElement::Paragraph(
+                            crate::document::Paragraph::Odt(para),
+                        )
+                    }));
                 },
             }
         }
@@ -423,6 +431,34 @@ impl Document {
         }
     }
 
+    /// Get all framed pictures in the document.
+    ///
+    /// Pictures inside a paragraph are anchored to its index in
+    /// [`paragraphs()`](Self::paragraphs); page-anchored frames are
+    /// unanchored. Alt text comes from the frame's `svg:desc` or `svg:title`.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use litchi::odf::Document;
+    ///
+    /// # fn main() -> litchi::Result<()> {
+    /// let doc = Document::open("document.odt")?;
+    /// for image in doc.images()? {
+    ///     println!("{}: {:?}", image.name, image.alt_text);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn images(&self) -> Result<Vec<EmbeddedImage>> {
+        collect_pictures(&self.package, self.content.xml_content(), |picture| {
+            picture
+                .paragraph
+                .map(ImageAnchor::Paragraph)
+                .unwrap_or(ImageAnchor::Unanchored)
+        })
+    }
+
     /// Get the style registry for this document.
     ///
     /// The style registry contains all styles defined in the document,
@@ -0,0 +1,80 @@
+//! Image format types for ODF writers.
+
+/// Image format types supported in the `Pictures/` folder of ODF packages.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ImageFormat {
+    Png,
+    Jpeg,
+    Gif,
+    Bmp,
+    Tiff,
+    Svg,
+}
+
+impl ImageFormat {
+    /// Get the MIME type for this image format.
+    pub fn mime_type(&self) -> &'static str {
+        match self {
+            Self::Png => "image/png",
+            Self::Jpeg => "image/jpeg",
+            Self::Gif => "image/gif",
+            Self::Bmp => "image/bmp",
+            Self::Tiff => "image/tiff",
+            Self::Svg => "image/svg+xml",
+        }
+    }
+
+    /// Get the file extension for this image format.
+    pub fn extension(&self) -> &'static str {
+        match self {
+            Self::Png => "png",
+            Self::Jpeg => "jpg",
+            Self::Gif => "gif",
+            Self::Bmp => "bmp",
+            Self::Tiff => "tiff",
+            Self::Svg => "svg",
+        }
+    }
+
+    /// Detect image format from bytes (magic number detection).
+    pub fn detect_from_bytes(bytes: &[u8]) -> Option<Self> {
+        if bytes.len() < 4 {
+            return None;
+        }
+
+        // PNG: 89 50 4E 47
+        if bytes.starts_with(&[0x89, 0x50, 0x4E, 0x47]) {
+            return Some(Self::Png);
+        }
+
+        // JPEG: FF D8 FF
+        if bytes.starts_with(&[0xFF, 0xD8, 0xFF]) {
+            return Some(Self::Jpeg);
+        }
+
+        // GIF: 47 49 46 38 (GIF8)
+        if bytes.starts_with(&[0x47, 0x49, 0x46, 0x38]) {
+            return Some(Self::Gif);
+        }
+
+        // BMP: 42 4D (BM)
+        if bytes.starts_with(&[0x42, 0x4D]) {
+            return Some(Self::Bmp);
+        }
+
+        // TIFF: 49 49 2A 00 (little-endian) or 4D 4D 00 2A (big-endian)
+        if bytes.starts_with(&[0x49, 0x49, 0x2A, 0x00])
+            || bytes.starts_with(&[0x4D, 0x4D, 0x00, 0x2A])
+        {
+            return Some(Self::Tiff);
+        }
+
+        // SVG: XML text containing an <svg root element near the start
+        let head = &bytes[..bytes.len().min(512)];
+        if memchr::memmem::find(head, b"<svg").is_some() {
+            return Some(Self::Svg);
+        }
+
+        None
+    }
+}
@@ -0,0 +1,171 @@
+//! Writer components for creating OpenDocument packages.
+//!
+//! This module provides builders for text documents (.odt), spreadsheets (.ods)
+//! and presentations (.odp). The API mirrors the OOXML writers: content is built
+//! up through mutable document types and then serialized into a ZIP package
+//! containing `mimetype`, `META-INF/manifest.xml`, `content.xml`, `styles.xml`
+//! and `meta.xml`.
+//!
+//! # Examples
+//!
+//! ```no_run
+//! use litchi::odf::{MutableDocument, MutableSpreadsheet, CellValue};
+//!
+//! # fn main() -> litchi::Result<()> {
+//! let mut doc = MutableDocument::new();
+//! doc.add_heading("Report", 1);
+//! doc.add_paragraph_with_text("Hello, world!");
+//! doc.save("report.odt")?;
+//!
+//! let mut book = MutableSpreadsheet::new();
+//! let sheet = book.add_sheet("Data");
+//! sheet.set_cell_value(0, 0, CellValue::Number(42.0));
+//! sheet.set_cell_formula(0, 1, "=A1*2");
+//! book.save("data.ods")?;
+//! # Ok(())
+//! # }
+//! ```
+
+pub mod image;
+pub mod package;
+pub mod presentation;
+pub mod spreadsheet;
+pub(crate) mod styles;
+pub mod text;
+
+// Re-export main types
+pub use image::ImageFormat;
+pub use package::PackageWriter;
+pub use presentation::{MutablePresentation, MutableSlide, TextFormat};
+pub use spreadsheet::{MutableSheet, MutableSpreadsheet};
+pub use text::{
+    MutableCell, MutableDocument, MutableParagraph, MutableRow, MutableRun, MutableTable,
+    ParagraphAlignment,
+};
+
+/// Namespace declarations shared by the root elements of every generated part.
+pub(crate) const NAMESPACES: &str = concat!(
+    r#"xmlns:office="urn:oasis:names:tc:opendocument:xmlns:office:1.0" "#,
+    r#"xmlns:style="urn:oasis:names:tc:opendocument:xmlns:style:1.0" "#,
+    r#"xmlns:text="urn:oasis:names:tc:opendocument:xmlns:text:1.0" "#,
+    r#"xmlns:table="urn:oasis:names:tc:opendocument:xmlns:table:1.0" "#,
+    r#"xmlns:draw="urn:oasis:names:tc:opendocument:xmlns:drawing:1.0" "#,
+    r#"xmlns:fo="urn:oasis:names:tc:opendocument:xmlns:xsl-fo-compatible:1.0" "#,
+    r#"xmlns:xlink="http://www.w3.org/1999/xlink" "#,
+    r#"xmlns:dc="http://purl.org/dc/elements/1.1/" "#,
+    r#"xmlns:meta="urn:oasis:names:tc:opendocument:xmlns:meta:1.0" "#,
+    r#"xmlns:number="urn:oasis:names:tc:opendocument:xmlns:datastyle:1.0" "#,
+    r#"xmlns:presentation="urn:oasis:names:tc:opendocument:xmlns:presentation:1.0" "#,
+    r#"xmlns:svg="urn:oasis:names:tc:opendocument:xmlns:svg-compatible:1.0" "#,
+    r#"xmlns:of="urn:oasis:names:tc:opendocument:xmlns:of:1.2""#,
+);
+
+/// Escape XML special characters.
+pub(crate) fn escape_xml(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&apos;")
+}
+
+/// Write text as ODF paragraph content.
+///
+/// ODF collapses runs of whitespace, so tabs, line breaks and repeated spaces
+/// are written as `text:tab`, `text:line-break` and `text:s` elements.
+pub(crate) fn write_text_content(xml: &mut String, text: &str) {
+    let mut pending_spaces = 0usize;
+    let mut at_start = true;
+
+    let flush_spaces = |xml: &mut String, count: &mut usize, at_start: bool| {
+        if *count == 0 {
+            return;
+        }
+        // A single space between words is preserved as-is; leading spaces and
+        // additional spaces must be spelled out.
+        let (literal, extra) = if at_start {
+            (0, *count)
+        } else {
+            (1, *count - 1)
+        };
+        if literal == 1 {
+            xml.push(' ');
+        }
+        if extra == 1 {
+            xml.push_str("<text:s/>");
+        } else if extra > 1 {
+            xml.push_str(&format!("<text:s text:c=\"{}\"/>", extra));
+        }
+        *count = 0;
+    };
+
+    for ch in text.chars() {
+        match ch {
+            ' ' => pending_spaces += 1,
+            '\t' => {
+                flush_spaces(xml, &mut pending_spaces, at_start);
+                xml.push_str("<text:tab/>");
+                at_start = true;
+            },
+            '\n' => {
+                flush_spaces(xml, &mut pending_spaces, at_start);
+                xml.push_str("<text:line-break/>");
+                at_start = true;
+            },
+            '\r' => {},
+            _ => {
+                flush_spaces(xml, &mut pending_spaces, at_start);
+                at_start = false;
+                match ch {
+                    '&' => xml.push_str("&amp;"),
+                    '<' => xml.push_str("&lt;"),
+                    '>' => xml.push_str("&gt;"),
+                    _ => xml.push(ch),
+                }
+            },
+        }
+    }
+
+    // Trailing spaces would be collapsed away, so spell them out explicitly
+    if pending_spaces > 0 {
+        if pending_spaces == 1 {
+            xml.push_str("<text:s/>");
+        } else {
+            xml.push_str(&format!("<text:s text:c=\"{}\"/>", pending_spaces));
+        }
+    }
+}
+
+/// Convert a length in EMUs (English Metric Units) to an ODF length in centimeters.
+///
+/// EMUs are used by the presentation writer for parity with the PPTX writer API.
+pub(crate) fn emu_to_cm(emu: i64) -> String {
+    format!("{:.3}cm", emu as f64 / 360_000.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn text_xml(text: &str) -> String {
+        let mut xml = String::new();
+        write_text_content(&mut xml, text);
+        xml
+    }
+
+    #[test]
+    fn test_write_text_content_whitespace() {
+        assert_eq!(text_xml("a b"), "a b");
+        assert_eq!(text_xml("a   b"), "a <text:s text:c=\"2\"/>b");
+        assert_eq!(text_xml("  a"), "<text:s text:c=\"2\"/>a");
+        assert_eq!(text_xml("a\tb\nc"), "a<text:tab/>b<text:line-break/>c");
+        assert_eq!(text_xml("a "), "a<text:s/>");
+        assert_eq!(text_xml("<&>"), "&lt;&amp;&gt;");
+    }
+
+    #[test]
+    fn test_emu_to_cm() {
+        assert_eq!(emu_to_cm(360_000), "1.000cm");
+        assert_eq!(emu_to_cm(914_400), "2.540cm");
+    }
+}
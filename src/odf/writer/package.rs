@@ -0,0 +1,274 @@
+//! ODF package (ZIP archive) writing functionality.
+//!
+//! An ODF package must start with an uncompressed `mimetype` entry so that
+//! consumers can identify the document type from a fixed offset, followed by
+//! the XML parts and a `META-INF/manifest.xml` listing every entry.
+
+use super::escape_xml;
+use crate::common::{Metadata, Result};
+use std::fmt::Write as FmtWrite;
+use std::io::{Cursor, Seek, Write};
+use std::path::Path;
+
+/// Generator string written into `meta.xml`.
+const GENERATOR: &str = concat!("Litchi/", env!("CARGO_PKG_VERSION"));
+
+/// A file queued for inclusion in an ODF package.
+#[derive(Debug, Clone)]
+struct PackageEntry {
+    /// Path inside the archive (e.g., "content.xml", "Pictures/image1.png")
+    path: String,
+    /// Media type recorded in the manifest
+    media_type: String,
+    /// File contents
+    data: Vec<u8>,
+}
+
+/// Low-level writer assembling an ODF ZIP package.
+///
+/// The document writers use this to emit their parts, but it can also be used
+/// directly to build packages from hand-written XML.
+///
+/// # Examples
+///
+/// ```no_run
+/// use litchi::odf::PackageWriter;
+///
+/// # fn main() -> litchi::Result<()> {
+/// let mut package = PackageWriter::new("application/vnd.oasis.opendocument.text");
+/// package.add_file("content.xml", "text/xml", b"<office:document-content/>".to_vec());
+/// package.save("minimal.odt")?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct PackageWriter {
+    /// Package MIME type (written to the `mimetype` entry)
+    mimetype: String,
+    /// Files in insertion order
+    entries: Vec<PackageEntry>,
+}
+
+impl PackageWriter {
+    /// Create a new package writer for the given ODF MIME type.
+    pub fn new(mimetype: &str) -> Self {
+        Self {
+            mimetype: mimetype.to_string(),
+            entries: Vec::new(),
+        }
+    }
+
+    /// Get the package MIME type.
+    pub fn mimetype(&self) -> &str {
+        &self.mimetype
+    }
+
+    /// Add a file to the package, replacing any existing file at the same path.
+    pub fn add_file(&mut self, path: &str, media_type: &str, data: Vec<u8>) {
+        let entry = PackageEntry {
+            path: path.to_string(),
+            media_type: media_type.to_string(),
+            data,
+        };
+        if let Some(existing) = self.entries.iter_mut().find(|e| e.path == path) {
+            *existing = entry;
+        } else {
+            self.entries.push(entry);
+        }
+    }
+
+    /// Check if a file exists in the package.
+    pub fn has_file(&self, path: &str) -> bool {
+        self.entries.iter().any(|e| e.path == path)
+    }
+
+    /// Generate the `META-INF/manifest.xml` content.
+    pub fn manifest_xml(&self) -> String {
+        let mut xml = String::with_capacity(512 + self.entries.len() * 128);
+        xml.push_str(r#"<?xml version="1.0" encoding="UTF-8"?>"#);
+        xml.push('\n');
+        xml.push_str(
+            r#"<manifest:manifest xmlns:manifest="urn:oasis:names:tc:opendocument:xmlns:manifest:1.0" manifest:version="1.3">"#,
+        );
+        write!(
+            xml,
+            r#"<manifest:file-entry manifest:full-path="/" manifest:version="1.3" manifest:media-type="{}"/>"#,
+            escape_xml(&self.mimetype)
+        )
+        .unwrap();
+        for entry in &self.entries {
+            write!(
+                xml,
+                r#"<manifest:file-entry manifest:full-path="{}" manifest:media-type="{}"/>"#,
+                escape_xml(&entry.path),
+                escape_xml(&entry.media_type)
+            )
+            .unwrap();
+        }
+        xml.push_str("</manifest:manifest>");
+        xml
+    }
+
+    /// Write the package to any seekable writer.
+    pub fn write_to<W: Write + Seek>(&self, writer: W) -> Result<()> {
+        use zip::write::SimpleFileOptions;
+
+        let mut archive = zip::ZipWriter::new(writer);
+
+        // The mimetype entry must come first and must not be compressed
+        let stored =
+            SimpleFileOptions::default().compression_method(zip::CompressionMethod::Stored);
+        archive.start_file("mimetype", stored)?;
+        archive.write_all(self.mimetype.as_bytes())?;
+
+        let deflated = SimpleFileOptions::default()
+            .compression_method(zip::CompressionMethod::Deflated)
+            .compression_level(Some(6));
+
+        for entry in &self.entries {
+            // Images are already compressed; storing them avoids wasted work
+            let options =
+                if entry.media_type.starts_with("image/") && entry.media_type != "image/svg+xml" {
+                    stored
+                } else {
+                    deflated
+                };
+            archive.start_file(entry.path.as_str(), options)?;
+            archive.write_all(&entry.data)?;
+        }
+
+        archive.start_file("META-INF/manifest.xml", deflated)?;
+        archive.write_all(self.manifest_xml().as_bytes())?;
+
+        archive.finish()?;
+        Ok(())
+    }
+
+    /// Serialize the package to an in-memory byte vector.
+    pub fn to_bytes(&self) -> Result<Vec<u8>> {
+        let mut cursor = Cursor::new(Vec::new());
+        self.write_to(&mut cursor)?;
+        Ok(cursor.into_inner())
+    }
+
+    /// Save the package to a file.
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<()> {
+        let file = std::fs::File::create(path)?;
+        self.write_to(std::io::BufWriter::new(file))
+    }
+}
+
+/// Generate `meta.xml` content from document metadata.
+pub(crate) fn generate_meta_xml(metadata: &Metadata) -> String {
+    let mut xml = String::with_capacity(1024);
+    xml.push_str(r#"<?xml version="1.0" encoding="UTF-8"?>"#);
+    xml.push('\n');
+    write!(
+        xml,
+        r#"<office:document-meta {} office:version="1.3"><office:meta>"#,
+        super::NAMESPACES
+    )
+    .unwrap();
+
+    write!(xml, "<meta:generator>{}</meta:generator>", GENERATOR).unwrap();
+
+    let simple = [
+        ("dc:title", &metadata.title),
+        ("dc:subject", &metadata.subject),
+        ("dc:description", &metadata.description),
+        ("meta:initial-creator", &metadata.author),
+        ("dc:creator", &metadata.last_modified_by),
+    ];
+    for (tag, value) in simple {
+        if let Some(value) = value {
+            write!(xml, "<{tag}>{}</{tag}>", escape_xml(value)).unwrap();
+        }
+    }
+
+    if let Some(keywords) = &metadata.keywords {
+        for keyword in keywords.split([',', ';']).map(str::trim) {
+            if !keyword.is_empty() {
+                write!(xml, "<meta:keyword>{}</meta:keyword>", escape_xml(keyword)).unwrap();
+            }
+        }
+    }
+
+    let created = metadata.created.unwrap_or_else(chrono::Utc::now);
+    let modified = metadata.modified.unwrap_or(created);
+    write!(
+        xml,
+        "<meta:creation-date>{}</meta:creation-date><dc:date>{}</dc:date>",
+        created.format("%Y-%m-%dT%H:%M:%SZ"),
+        modified.format("%Y-%m-%dT%H:%M:%SZ")
+    )
+    .unwrap();
+
+    if let Some(revision) = &metadata.revision {
+        write!(
+            xml,
+            "<meta:editing-cycles>{}</meta:editing-cycles>",
+            escape_xml(revision)
+        )
+        .unwrap();
+    }
+
+    xml.push_str("</office:meta></office:document-meta>");
+    xml
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Read;
+
+    #[test]
+    fn test_mimetype_is_first_and_stored() {
+        let mut package = PackageWriter::new("application/vnd.oasis.opendocument.text");
+        package.add_file("content.xml", "text/xml", b"<a/>".to_vec());
+        let bytes = package.to_bytes().unwrap();
+
+        // The mimetype must be readable at a fixed offset for magic detection
+        assert_eq!(&bytes[30..38], b"mimetype");
+        assert_eq!(
+            &bytes[38..38 + package.mimetype().len()],
+            package.mimetype().as_bytes()
+        );
+
+        let mut archive = zip::ZipArchive::new(Cursor::new(bytes)).unwrap();
+        let first = archive.by_index(0).unwrap();
+        assert_eq!(first.name(), "mimetype");
+        assert_eq!(first.compression(), zip::CompressionMethod::Stored);
+        drop(first);
+
+        let mut manifest = String::new();
+        archive
+            .by_name("META-INF/manifest.xml")
+            .unwrap()
+            .read_to_string(&mut manifest)
+            .unwrap();
+        assert!(manifest.contains(r#"manifest:full-path="content.xml""#));
+    }
+
+    #[test]
+    fn test_add_file_replaces_existing() {
+        let mut package = PackageWriter::new("application/vnd.oasis.opendocument.text");
+        package.add_file("content.xml", "text/xml", b"old".to_vec());
+        package.add_file("content.xml", "text/xml", b"new".to_vec());
+        assert_eq!(package.entries.len(), 1);
+        assert_eq!(package.entries[0].data, b"new");
+    }
+
+    #[test]
+    fn test_generate_meta_xml() {
+        let metadata = Metadata {
+            title: Some("Q3 <Report>".to_string()),
+            author: Some("Finance".to_string()),
+            keywords: Some("sales, q3".to_string()),
+            ..Default::default()
+        };
+        let xml = generate_meta_xml(&metadata);
+        assert!(xml.contains("<dc:title>Q3 &lt;Report&gt;</dc:title>"));
+        assert!(xml.contains("<meta:initial-creator>Finance</meta:initial-creator>"));
+        assert!(xml.contains("<meta:keyword>q3</meta:keyword>"));
+    }
+}
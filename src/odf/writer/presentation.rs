@@ -0,0 +1,587 @@
+//! Presentation (.odp) writer.
+use super::image::ImageFormat;
+use super::package::{PackageWriter, generate_meta_xml};
+use super::styles::{AutomaticStyles, TextProperties, normalize_color};
+use super::text::ImageCollector;
+use super::{NAMESPACES, emu_to_cm, escape_xml, write_text_content};
+use crate::common::{Error, Metadata, Result};
+use std::fmt::Write as FmtWrite;
+use std::path::Path;
+
+/// MIME type of OpenDocument presentations.
+const MIMETYPE: &str = "application/vnd.oasis.opendocument.presentation";
+
+/// Default slide width (10 inches, matching the PPTX writer) in EMUs.
+const DEFAULT_SLIDE_WIDTH: i64 = 9_144_000;
+/// Default slide height (7.5 inches, matching the PPTX writer) in EMUs.
+const DEFAULT_SLIDE_HEIGHT: i64 = 6_858_000;
+
+/// Kind of shape placed on a slide.
+#[derive(Debug, Clone)]
+enum ShapeKind {
+    /// Text box with character formatting applied to all of its text
+    TextBox { text: String, format: TextFormat },
+    /// Filled rectangle
+    Rectangle { fill_color: Option<String> },
+    /// Filled ellipse
+    Ellipse { fill_color: Option<String> },
+    /// Embedded picture
+    Picture {
+        data: Vec<u8>,
+        format: ImageFormat,
+        description: String,
+    },
+}
+
+/// Character formatting applied to all text in a text box.
+#[derive(Debug, Clone, Default)]
+pub struct TextFormat {
+    properties: TextProperties,
+}
+
+impl TextFormat {
+    /// Set bold formatting.
+    pub fn bold(&mut self, bold: bool) -> &mut Self {
+        self.properties.bold = bold;
+        self
+    }
+
+    /// Set italic formatting.
+    pub fn italic(&mut self, italic: bool) -> &mut Self {
+        self.properties.italic = italic;
+        self
+    }
+
+    /// Set single underline.
+    pub fn underline(&mut self, underline: bool) -> &mut Self {
+        self.properties.underline = underline;
+        self
+    }
+
+    /// Set font size in points.
+    pub fn font_size(&mut self, size: f64) -> &mut Self {
+        self.properties.font_size = Some(size);
+        self
+    }
+
+    /// Set font family name.
+    pub fn font(&mut self, font: &str) -> &mut Self {
+        self.properties.font_name = Some(font.to_string());
+        self
+    }
+
+    /// Set text color (hex RGB, e.g., "FF0000").
+    pub fn color(&mut self, color: &str) -> &mut Self {
+        self.properties.color = Some(color.to_string());
+        self
+    }
+}
+
+/// A shape positioned on a slide (coordinates in EMUs, as in the PPTX writer).
+#[derive(Debug, Clone)]
+struct SlideShape {
+    kind: ShapeKind,
+    x: i64,
+    y: i64,
+    width: i64,
+    height: i64,
+}
+
+/// A mutable slide in a presentation.
+#[derive(Debug, Clone)]
+pub struct MutableSlide {
+    /// Slide title (stored in a title frame and used as the page name)
+    title: Option<String>,
+    /// Shapes on the slide
+    shapes: Vec<SlideShape>,
+    /// Speaker notes for the slide
+    notes: Option<String>,
+}
+
+impl MutableSlide {
+    fn new() -> Self {
+        Self {
+            title: None,
+            shapes: Vec::new(),
+            notes: None,
+        }
+    }
+
+    /// Set the slide title.
+    pub fn set_title(&mut self, title: &str) {
+        self.title = Some(title.to_string());
+    }
+
+    /// Get the slide title.
+    pub fn title(&self) -> Option<&str> {
+        self.title.as_deref()
+    }
+
+    /// Set speaker notes for the slide.
+    pub fn set_notes(&mut self, notes: &str) {
+        self.notes = Some(notes.to_string());
+    }
+
+    /// Get the speaker notes for the slide.
+    pub fn notes(&self) -> Option<&str> {
+        self.notes.as_deref()
+    }
+
+    /// Add a text box to the slide and return its text format for styling.
+    pub fn add_text_box(
+        &mut self,
+        text: &str,
+        x: i64,
+        y: i64,
+        width: i64,
+        height: i64,
+    ) -> &mut TextFormat {
+        self.shapes.push(SlideShape {
+            kind: ShapeKind::TextBox {
+                text: text.to_string(),
+                format: TextFormat::default(),
+            },
+            x,
+            y,
+            width,
+            height,
+        });
+        match &mut self.shapes.last_mut().unwrap().kind {
+            ShapeKind::TextBox { format, .. } => format,
+            _ => unreachable!(),
+        }
+    }
+
+    /// Add a rectangle to the slide.
+    pub fn add_rectangle(
+        &mut self,
+        x: i64,
+        y: i64,
+        width: i64,
+        height: i64,
+        fill_color: Option<String>,
+    ) {
+        self.shapes.push(SlideShape {
+            kind: ShapeKind::Rectangle { fill_color },
+            x,
+            y,
+            width,
+            height,
+        });
+    }
+
+    /// Add an ellipse (circle/oval) to the slide.
+    pub fn add_ellipse(
+        &mut self,
+        x: i64,
+        y: i64,
+        width: i64,
+        height: i64,
+        fill_color: Option<String>,
+    ) {
+        self.shapes.push(SlideShape {
+            kind: ShapeKind::Ellipse { fill_color },
+            x,
+            y,
+            width,
+            height,
+        });
+    }
+
+    /// Add a picture to the slide from a file.
+    pub fn add_picture(
+        &mut self,
+        image_path: &str,
+        x: i64,
+        y: i64,
+        width: i64,
+        height: i64,
+    ) -> Result<()> {
+        let data = std::fs::read(image_path)?;
+        self.add_picture_from_bytes(
+            data,
+            x,
+            y,
+            width,
+            height,
+            Some(format!("Picture from {}", image_path)),
+        )
+    }
+
+    /// Add a picture to the slide from bytes.
+    pub fn add_picture_from_bytes(
+        &mut self,
+        data: Vec<u8>,
+        x: i64,
+        y: i64,
+        width: i64,
+        height: i64,
+        description: Option<String>,
+    ) -> Result<()> {
+        let format = ImageFormat::detect_from_bytes(&data)
+            .ok_or_else(|| Error::InvalidFormat("Unknown image format".to_string()))?;
+        self.shapes.push(SlideShape {
+            kind: ShapeKind::Picture {
+                data,
+                format,
+                description: description.unwrap_or_default(),
+            },
+            x,
+            y,
+            width,
+            height,
+        });
+        Ok(())
+    }
+
+    /// Get the number of shapes on the slide (excluding the title).
+    pub fn shape_count(&self) -> usize {
+        self.shapes.len()
+    }
+
+    /// Serialize the slide as `draw:page`.
+    fn to_xml(
+        &self,
+        xml: &mut String,
+        index: usize,
+        slide_width: i64,
+        styles: &mut AutomaticStyles,
+        images: &mut ImageCollector,
+    ) {
+        let page_name = self
+            .title
+            .clone()
+            .unwrap_or_else(|| format!("page{}", index + 1));
+        write!(
+            xml,
+            r#"<draw:page draw:name="{}" draw:master-page-name="Default">"#,
+            escape_xml(&page_name)
+        )
+        .unwrap();
+
+        if let Some(title) = &self.title {
+            let margin = slide_width / 20;
+            write!(
+                xml,
+                r#"<draw:frame presentation:class="title" svg:x="{}" svg:y="{}" svg:width="{}" svg:height="{}"><draw:text-box><text:p>"#,
+                emu_to_cm(margin),
+                emu_to_cm(margin / 2),
+                emu_to_cm(slide_width - 2 * margin),
+                emu_to_cm(1_143_000)
+            )
+            .unwrap();
+            write_text_content(xml, title);
+            xml.push_str("</text:p></draw:text-box></draw:frame>");
+        }
+
+        for (i, shape) in self.shapes.iter().enumerate() {
+            let geometry = format!(
+                r#"svg:x="{}" svg:y="{}" svg:width="{}" svg:height="{}""#,
+                emu_to_cm(shape.x),
+                emu_to_cm(shape.y),
+                emu_to_cm(shape.width),
+                emu_to_cm(shape.height)
+            );
+            let name = format!("Shape {}", i + 1);
+            match &shape.kind {
+                ShapeKind::TextBox { text, format } => {
+                    write!(
+                        xml,
+                        r#"<draw:frame draw:name="{}" {}><draw:text-box>"#,
+                        name, geometry
+                    )
+                    .unwrap();
+                    let props = &format.properties;
+                    let span_style = (!props.is_empty())
+                        .then(|| styles.get_or_add("text", "T", None, props.to_xml()));
+                    for line in text.split('\n') {
+                        xml.push_str("<text:p>");
+                        if let Some(style) = &span_style {
+                            write!(xml, r#"<text:span text:style-name="{}">"#, style).unwrap();
+                            write_text_content(xml, line);
+                            xml.push_str("</text:span>");
+                        } else {
+                            write_text_content(xml, line);
+                        }
+                        xml.push_str("</text:p>");
+                    }
+                    xml.push_str("</draw:text-box></draw:frame>");
+                },
+                ShapeKind::Rectangle { fill_color } | ShapeKind::Ellipse { fill_color } => {
+                    let tag = if matches!(shape.kind, ShapeKind::Rectangle { .. }) {
+                        "draw:rect"
+                    } else {
+                        "draw:ellipse"
+                    };
+                    let props = match fill_color {
+                        Some(color) => format!(
+                            r##"<style:graphic-properties draw:fill="solid" draw:fill-color="#{}"/>"##,
+                            normalize_color(color)
+                        ),
+                        None => r#"<style:graphic-properties draw:fill="none"/>"#.to_string(),
+                    };
+                    let style = styles.get_or_add("graphic", "gr", None, props);
+                    write!(
+                        xml,
+                        r#"<{} draw:name="{}" draw:style-name="{}" {}></{}>"#,
+                        tag, name, style, geometry, tag
+                    )
+                    .unwrap();
+                },
+                ShapeKind::Picture {
+                    data,
+                    format,
+                    description,
+                } => {
+                    let (_, href) = images.add(data.clone(), *format);
+                    write!(
+                        xml,
+                        r#"<draw:frame draw:name="{}" {}><draw:image xlink:href="{}" xlink:type="simple" xlink:show="embed" xlink:actuate="onLoad"/>"#,
+                        name, geometry, href
+                    )
+                    .unwrap();
+                    if !description.is_empty() {
+                        write!(xml, "<svg:desc>{}</svg:desc>", escape_xml(description)).unwrap();
+                    }
+                    xml.push_str("</draw:frame>");
+                },
+            }
+        }
+
+        if let Some(notes) = &self.notes {
+            xml.push_str(
+                r#"<presentation:notes><draw:frame presentation:class="notes"><draw:text-box>"#,
+            );
+            for line in notes.split('\n') {
+                xml.push_str("<text:p>");
+                write_text_content(xml, line);
+                xml.push_str("</text:p>");
+            }
+            xml.push_str("</draw:text-box></draw:frame></presentation:notes>");
+        }
+
+        xml.push_str("</draw:page>");
+    }
+}
+
+/// A mutable OpenDocument presentation (.odp) for writing.
+///
+/// Positions and sizes are given in EMUs (914400 per inch) for parity with
+/// the PPTX writer.
+///
+/// # Examples
+///
+/// ```no_run
+/// use litchi::odf::MutablePresentation;
+///
+/// # fn main() -> litchi::Result<()> {
+/// let mut pres = MutablePresentation::new();
+/// let slide = pres.add_slide();
+/// slide.set_title("Welcome");
+/// slide.add_text_box("Agenda", 914400, 1828800, 7315200, 914400);
+/// pres.save("welcome.odp")?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct MutablePresentation {
+    /// Slides in order
+    slides: Vec<MutableSlide>,
+    /// Slide width in EMUs
+    slide_width: i64,
+    /// Slide height in EMUs
+    slide_height: i64,
+    /// Document metadata written to meta.xml
+    metadata: Metadata,
+}
+
+impl Default for MutablePresentation {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl MutablePresentation {
+    /// Create a new presentation without slides.
+    pub fn new() -> Self {
+        Self {
+            slides: Vec::new(),
+            slide_width: DEFAULT_SLIDE_WIDTH,
+            slide_height: DEFAULT_SLIDE_HEIGHT,
+            metadata: Metadata::default(),
+        }
+    }
+
+    /// Add a new slide.
+    pub fn add_slide(&mut self) -> &mut MutableSlide {
+        self.slides.push(MutableSlide::new());
+        self.slides.last_mut().unwrap()
+    }
+
+    /// Get the number of slides.
+    pub fn slide_count(&self) -> usize {
+        self.slides.len()
+    }
+
+    /// Get a mutable reference to a slide by index.
+    pub fn slide_mut(&mut self, index: usize) -> Option<&mut MutableSlide> {
+        self.slides.get_mut(index)
+    }
+
+    /// Get the slide width in EMUs.
+    pub fn slide_width(&self) -> i64 {
+        self.slide_width
+    }
+
+    /// Set the slide width in EMUs.
+    pub fn set_slide_width(&mut self, width: i64) {
+        self.slide_width = width;
+    }
+
+    /// Get the slide height in EMUs.
+    pub fn slide_height(&self) -> i64 {
+        self.slide_height
+    }
+
+    /// Set the slide height in EMUs.
+    pub fn set_slide_height(&mut self, height: i64) {
+        self.slide_height = height;
+    }
+
+    /// Get a reference to the document metadata.
+    pub fn metadata(&self) -> &Metadata {
+        &self.metadata
+    }
+
+    /// Get a mutable reference to the document metadata.
+    pub fn metadata_mut(&mut self) -> &mut Metadata {
+        &mut self.metadata
+    }
+
+    /// Generate `content.xml`, collecting referenced images.
+    fn generate_content_xml(&self, images: &mut ImageCollector) -> String {
+        let mut styles = AutomaticStyles::new();
+        let mut body = String::with_capacity(4096);
+        for (i, slide) in self.slides.iter().enumerate() {
+            slide.to_xml(&mut body, i, self.slide_width, &mut styles, images);
+        }
+
+        let mut xml = String::with_capacity(body.len() + 2048);
+        xml.push_str(r#"<?xml version="1.0" encoding="UTF-8"?>"#);
+        xml.push('\n');
+        write!(
+            xml,
+            r#"<office:document-content {} office:version="1.3">"#,
+            NAMESPACES
+        )
+        .unwrap();
+        styles.to_xml(&mut xml);
+        xml.push_str("<office:body><office:presentation>");
+        xml.push_str(&body);
+        xml.push_str("</office:presentation></office:body></office:document-content>");
+        xml
+    }
+
+    /// Generate `styles.xml` with the page layout and default master page.
+    fn generate_styles_xml(&self) -> String {
+        let mut xml = String::with_capacity(1024);
+        xml.push_str(r#"<?xml version="1.0" encoding="UTF-8"?>"#);
+        xml.push('\n');
+        write!(
+            xml,
+            r#"<office:document-styles {} office:version="1.3"><office:styles/>"#,
+            NAMESPACES
+        )
+        .unwrap();
+        let orientation = if self.slide_width >= self.slide_height {
+            "landscape"
+        } else {
+            "portrait"
+        };
+        write!(
+            xml,
+            r#"<office:automatic-styles><style:page-layout style:name="PM1"><style:page-layout-properties fo:margin-top="0cm" fo:margin-bottom="0cm" fo:margin-left="0cm" fo:margin-right="0cm" fo:page-width="{}" fo:page-height="{}" style:print-orientation="{}"/></style:page-layout></office:automatic-styles>"#,
+            emu_to_cm(self.slide_width),
+            emu_to_cm(self.slide_height),
+            orientation
+        )
+        .unwrap();
+        xml.push_str(
+            r#"<office:master-styles><style:master-page style:name="Default" style:page-layout-name="PM1"/></office:master-styles>"#,
+        );
+        xml.push_str("</office:document-styles>");
+        xml
+    }
+
+    /// Build the ODF package for this presentation.
+    pub fn to_package(&self) -> PackageWriter {
+        let mut images = ImageCollector::default();
+        let content = self.generate_content_xml(&mut images);
+
+        let mut package = PackageWriter::new(MIMETYPE);
+        package.add_file("content.xml", "text/xml", content.into_bytes());
+        package.add_file(
+            "styles.xml",
+            "text/xml",
+            self.generate_styles_xml().into_bytes(),
+        );
+        package.add_file(
+            "meta.xml",
+            "text/xml",
+            generate_meta_xml(&self.metadata).into_bytes(),
+        );
+        images.write_to(&mut package);
+        package
+    }
+
+    /// Serialize the presentation to an in-memory .odp file.
+    pub fn to_bytes(&self) -> Result<Vec<u8>> {
+        self.to_package().to_bytes()
+    }
+
+    /// Save the presentation to a .odp file.
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<()> {
+        self.to_package().save(path)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::odf::Presentation;
+
+    #[test]
+    fn test_round_trip_slides() {
+        let mut pres = MutablePresentation::new();
+        let slide = pres.add_slide();
+        slide.set_title("Overview");
+        slide.add_text_box("First point", 914400, 1828800, 7315200, 914400);
+        slide.add_rectangle(0, 0, 914400, 914400, Some("#FF0000".to_string()));
+        pres.add_slide()
+            .add_text_box("Big", 0, 0, 914400, 914400)
+            .bold(true)
+            .font_size(40.0);
+
+        let reopened = Presentation::from_bytes(pres.to_bytes().unwrap()).unwrap();
+        assert_eq!(reopened.slide_count().unwrap(), 2);
+
+        let slides = reopened.slides().unwrap();
+        assert_eq!(slides[0].title.as_deref(), Some("Overview"));
+        assert!(slides[0].text.contains("First point"));
+        assert_eq!(slides[0].shapes.len(), 3);
+        assert!(slides[1].text.contains("Big"));
+    }
+
+    #[test]
+    fn test_graphic_styles_are_shared() {
+        let mut pres = MutablePresentation::new();
+        let slide = pres.add_slide();
+        slide.add_rectangle(0, 0, 10, 10, Some("00FF00".to_string()));
+        slide.add_ellipse(0, 0, 10, 10, Some("#00ff00".to_string()));
+
+        let mut images = ImageCollector::default();
+        let xml = pres.generate_content_xml(&mut images);
+        assert!(xml.contains(r##"draw:fill-color="#00FF00""##));
+        assert!(!xml.contains(r#"style:name="gr2""#));
+    }
+}
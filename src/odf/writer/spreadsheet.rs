@@ -0,0 +1,752 @@
+//! Spreadsheet (.ods) writer.
+use super::package::{PackageWriter, generate_meta_xml};
+use super::styles::AutomaticStyles;
+use super::{NAMESPACES, escape_xml, write_text_content};
+use crate::common::{Metadata, Result};
+use crate::odf::ods::CellValue;
+use std::collections::{BTreeMap, HashMap};
+use std::fmt::Write as FmtWrite;
+use std::path::Path;
+
+/// MIME type of OpenDocument spreadsheets.
+const MIMETYPE: &str = "application/vnd.oasis.opendocument.spreadsheet";
+
+/// Cell content stored in a mutable sheet.
+#[derive(Debug, Clone)]
+struct SheetCell {
+    /// Cell value (the cached result for formula cells)
+    value: CellValue,
+    /// Formula in OpenFormula syntax (e.g., "of:=SUM([.A1:.A3])")
+    formula: Option<String>,
+}
+
+/// A mutable worksheet in an ODS spreadsheet.
+///
+/// Rows and columns are 0-based, matching the XLSX writer.
+#[derive(Debug, Clone)]
+pub struct MutableSheet {
+    /// Sheet name
+    name: String,
+    /// Cell data (row, col) -> cell, ordered for serialization
+    cells: BTreeMap<(u32, u32), SheetCell>,
+    /// Merged cell ranges (start_row, start_col, end_row, end_col)
+    merged_cells: Vec<(u32, u32, u32, u32)>,
+    /// Column widths (col -> width in characters)
+    column_widths: HashMap<u32, f64>,
+    /// Row heights (row -> height in points)
+    row_heights: HashMap<u32, f64>,
+}
+
+impl MutableSheet {
+    /// Create a new empty sheet.
+    pub fn new(name: String) -> Self {
+        Self {
+            name,
+            cells: BTreeMap::new(),
+            merged_cells: Vec::new(),
+            column_widths: HashMap::new(),
+            row_heights: HashMap::new(),
+        }
+    }
+
+    /// Get the sheet name.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Set the sheet name.
+    pub fn set_name(&mut self, name: String) {
+        self.name = name;
+    }
+
+    /// Set a typed cell value.
+    pub fn set_cell_value<V: Into<CellValue>>(&mut self, row: u32, col: u32, value: V) {
+        self.cells.insert(
+            (row, col),
+            SheetCell {
+                value: value.into(),
+                formula: None,
+            },
+        );
+    }
+
+    /// Set a cell formula.
+    ///
+    /// Accepts OpenFormula (`of:=SUM([.A1:.A3])`) or Excel-style formulas
+    /// (`=SUM(A1:A3)`), which are converted to OpenFormula syntax.
+    pub fn set_cell_formula(&mut self, row: u32, col: u32, formula: &str) {
+        self.set_cell_formula_with_cache(row, col, formula, CellValue::Empty);
+    }
+
+    /// Set a cell formula together with its cached result.
+    pub fn set_cell_formula_with_cache<V: Into<CellValue>>(
+        &mut self,
+        row: u32,
+        col: u32,
+        formula: &str,
+        cached_value: V,
+    ) {
+        self.cells.insert(
+            (row, col),
+            SheetCell {
+                value: cached_value.into(),
+                formula: Some(to_open_formula(formula)),
+            },
+        );
+    }
+
+    /// Get a cell value.
+    pub fn cell_value(&self, row: u32, col: u32) -> Option<&CellValue> {
+        self.cells.get(&(row, col)).map(|c| &c.value)
+    }
+
+    /// Get a cell formula in OpenFormula syntax.
+    pub fn cell_formula(&self, row: u32, col: u32) -> Option<&str> {
+        self.cells
+            .get(&(row, col))
+            .and_then(|c| c.formula.as_deref())
+    }
+
+    /// Clear a cell.
+    pub fn clear_cell(&mut self, row: u32, col: u32) {
+        self.cells.remove(&(row, col));
+    }
+
+    /// Get the number of non-empty cells.
+    pub fn cell_count(&self) -> usize {
+        self.cells.len()
+    }
+
+    /// Merge a range of cells.
+    pub fn merge_cells(&mut self, start_row: u32, start_col: u32, end_row: u32, end_col: u32) {
+        self.merged_cells
+            .push((start_row, start_col, end_row, end_col));
+    }
+
+    /// Set a column width in characters (converted assuming a 7 pixel digit width).
+    pub fn set_column_width(&mut self, col: u32, width: f64) {
+        self.column_widths.insert(col, width);
+    }
+
+    /// Set a row height in points.
+    pub fn set_row_height(&mut self, row: u32, height: f64) {
+        self.row_heights.insert(row, height);
+    }
+
+    /// Get the used range as (min_row, min_col, max_row, max_col).
+    pub fn used_range(&self) -> Option<(u32, u32, u32, u32)> {
+        let mut keys = self.cells.keys();
+        let &(first_row, first_col) = keys.next()?;
+        let mut range = (first_row, first_col, first_row, first_col);
+        for &(row, col) in self.cells.keys() {
+            range.0 = range.0.min(row);
+            range.1 = range.1.min(col);
+            range.2 = range.2.max(row);
+            range.3 = range.3.max(col);
+        }
+        for &(_, _, end_row, end_col) in &self.merged_cells {
+            range.2 = range.2.max(end_row);
+            range.3 = range.3.max(end_col);
+        }
+        Some(range)
+    }
+
+    /// Find the merge anchored at a cell, or whether the cell is covered by a merge.
+    fn merge_state(&self, row: u32, col: u32) -> MergeState {
+        for &(start_row, start_col, end_row, end_col) in &self.merged_cells {
+            if row == start_row && col == start_col {
+                return MergeState::Anchor {
+                    rows: end_row - start_row + 1,
+                    cols: end_col - start_col + 1,
+                };
+            }
+            if (start_row..=end_row).contains(&row) && (start_col..=end_col).contains(&col) {
+                return MergeState::Covered;
+            }
+        }
+        MergeState::None
+    }
+
+    /// Serialize the sheet as `table:table`.
+    fn to_xml(&self, xml: &mut String, styles: &mut AutomaticStyles) {
+        write!(
+            xml,
+            r#"<table:table table:name="{}">"#,
+            escape_xml(&self.name)
+        )
+        .unwrap();
+
+        let (max_row, max_col) = self
+            .used_range()
+            .map(|(_, _, r, c)| (r, c))
+            .unwrap_or((0, 0));
+
+        // Column definitions, compressing runs of identically styled columns
+        let mut col = 0;
+        while col <= max_col {
+            let style = self.column_widths.get(&col).map(|w| {
+                let cm = (w * 7.0 + 5.0) / 96.0 * 2.54;
+                styles.get_or_add(
+                    "table-column",
+                    "co",
+                    None,
+                    format!(r#"<style:table-column-properties style:column-width="{cm:.3}cm"/>"#),
+                )
+            });
+            let mut repeat = 1;
+            while col + repeat <= max_col
+                && self.column_widths.get(&(col + repeat)) == self.column_widths.get(&col)
+            {
+                repeat += 1;
+            }
+            xml.push_str("<table:table-column");
+            if let Some(style) = style {
+                write!(xml, r#" table:style-name="{}""#, style).unwrap();
+            }
+            if repeat > 1 {
+                write!(xml, r#" table:number-columns-repeated="{}""#, repeat).unwrap();
+            }
+            xml.push_str("/>");
+            col += repeat;
+        }
+
+        for row in 0..=max_row {
+            xml.push_str("<table:table-row");
+            if let Some(height) = self.row_heights.get(&row) {
+                let style = styles.get_or_add(
+                    "table-row",
+                    "ro",
+                    None,
+                    format!(
+                        r#"<style:table-row-properties style:row-height="{height}pt" style:use-optimal-row-height="false"/>"#
+                    ),
+                );
+                write!(xml, r#" table:style-name="{}""#, style).unwrap();
+            }
+            xml.push('>');
+
+            let mut pending_empty = 0u32;
+            for col in 0..=max_col {
+                let state = self.merge_state(row, col);
+                let cell = self.cells.get(&(row, col));
+                if cell.is_none() && matches!(state, MergeState::None) {
+                    pending_empty += 1;
+                    continue;
+                }
+                write_empty_cells(xml, pending_empty);
+                pending_empty = 0;
+
+                match state {
+                    MergeState::Covered => xml.push_str("<table:covered-table-cell/>"),
+                    MergeState::Anchor { rows, cols } => {
+                        write_cell(xml, cell, Some((rows, cols)));
+                    },
+                    MergeState::None => write_cell(xml, cell, None),
+                }
+            }
+            write_empty_cells(xml, pending_empty);
+
+            xml.push_str("</table:table-row>");
+        }
+
+        xml.push_str("</table:table>");
+    }
+}
+
+/// Merge relationship of a cell.
+enum MergeState {
+    None,
+    Anchor { rows: u32, cols: u32 },
+    Covered,
+}
+
+/// Write a run of empty cells.
+///
+/// An explicit end tag is used rather than a self-closing element so that
+/// the repeat count is honored by streaming readers that only track start tags.
+fn write_empty_cells(xml: &mut String, count: u32) {
+    match count {
+        0 => {},
+        1 => xml.push_str("<table:table-cell></table:table-cell>"),
+        n => {
+            write!(
+                xml,
+                r#"<table:table-cell table:number-columns-repeated="{}"></table:table-cell>"#,
+                n
+            )
+            .unwrap();
+        },
+    }
+}
+
+/// Write a single cell with its typed value, formula and optional span.
+fn write_cell(xml: &mut String, cell: Option<&SheetCell>, span: Option<(u32, u32)>) {
+    xml.push_str("<table:table-cell");
+    if let Some((rows, cols)) = span {
+        write!(
+            xml,
+            r#" table:number-rows-spanned="{}" table:number-columns-spanned="{}""#,
+            rows, cols
+        )
+        .unwrap();
+    }
+
+    let Some(cell) = cell else {
+        xml.push_str("></table:table-cell>");
+        return;
+    };
+
+    if let Some(formula) = &cell.formula {
+        write!(xml, r#" table:formula="{}""#, escape_xml(formula)).unwrap();
+    }
+
+    let display = match &cell.value {
+        CellValue::Empty => {
+            if cell.formula.is_some() {
+                // Let consumers recalculate; declare a numeric result type
+                xml.push_str(r#" office:value-type="float" office:value="0""#);
+            }
+            None
+        },
+        CellValue::Text(s) => {
+            xml.push_str(r#" office:value-type="string""#);
+            Some(s.clone())
+        },
+        CellValue::Number(n) => {
+            write!(xml, r#" office:value-type="float" office:value="{}""#, n).unwrap();
+            Some(n.to_string())
+        },
+        CellValue::Boolean(b) => {
+            write!(
+                xml,
+                r#" office:value-type="boolean" office:boolean-value="{}""#,
+                b
+            )
+            .unwrap();
+            Some(if *b { "TRUE" } else { "FALSE" }.to_string())
+        },
+        CellValue::Date(d) => {
+            write!(
+                xml,
+                r#" office:value-type="date" office:date-value="{}""#,
+                escape_xml(d)
+            )
+            .unwrap();
+            Some(d.clone())
+        },
+        CellValue::Time(t) => {
+            write!(
+                xml,
+                r#" office:value-type="time" office:time-value="{}""#,
+                escape_xml(t)
+            )
+            .unwrap();
+            Some(t.clone())
+        },
+        CellValue::Currency(amount, code) => {
+            write!(
+                xml,
+                r#" office:value-type="currency" office:currency="{}" office:value="{}""#,
+                escape_xml(code),
+                amount
+            )
+            .unwrap();
+            Some(format!("{} {:.2}", code, amount))
+        },
+        CellValue::Percentage(p) => {
+            write!(
+                xml,
+                r#" office:value-type="percentage" office:value="{}""#,
+                p
+            )
+            .unwrap();
+            Some(format!("{}%", p * 100.0))
+        },
+    };
+
+    xml.push('>');
+    if let Some(text) = display {
+        xml.push_str("<text:p>");
+        write_text_content(xml, &text);
+        xml.push_str("</text:p>");
+    }
+    xml.push_str("</table:table-cell>");
+}
+
+/// Convert an Excel-style formula to OpenFormula syntax.
+///
+/// Cell references become bracketed (`A1` -> `[.A1]`, `A1:B2` -> `[.A1:.B2]`,
+/// `Sheet2!A1` -> `[$Sheet2.A1]`) and argument separators become semicolons.
+/// Formulas already carrying a namespace prefix (e.g., `of:`) are kept as-is.
+pub(crate) fn to_open_formula(formula: &str) -> String {
+    let trimmed = formula.trim();
+    if let Some((prefix, _)) = trimmed.split_once(":=")
+        && !prefix.is_empty()
+        && prefix.chars().all(|c| c.is_ascii_alphanumeric())
+    {
+        return trimmed.to_string();
+    }
+
+    let body = trimmed.strip_prefix('=').unwrap_or(trimmed);
+    let chars: Vec<char> = body.chars().collect();
+    let mut out = String::with_capacity(body.len() + 16);
+    out.push_str("of:=");
+
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        if c == '"' {
+            // Copy string literals verbatim (doubled quotes escape a quote)
+            out.push(c);
+            i += 1;
+            while i < chars.len() {
+                out.push(chars[i]);
+                if chars[i] == '"' {
+                    if chars.get(i + 1) == Some(&'"') {
+                        out.push('"');
+                        i += 2;
+                        continue;
+                    }
+                    i += 1;
+                    break;
+                }
+                i += 1;
+            }
+            continue;
+        }
+        if c == ',' {
+            out.push(';');
+            i += 1;
+            continue;
+        }
+        if c.is_ascii_alphabetic() || c == '$' || c == '\'' {
+            let prev_is_word =
+                i > 0 && (chars[i - 1].is_ascii_alphanumeric() || chars[i - 1] == '_');
+            if !prev_is_word && let Some((reference, len)) = parse_reference(&chars[i..]) {
+                out.push_str(&reference);
+                i += len;
+                continue;
+            }
+            // Copy the whole identifier so its tail is not mistaken for a reference
+            while i < chars.len()
+                && (chars[i].is_ascii_alphanumeric() || matches!(chars[i], '_' | '.' | '$'))
+            {
+                out.push(chars[i]);
+                i += 1;
+            }
+            continue;
+        }
+        out.push(c);
+        i += 1;
+    }
+    out
+}
+
+/// Parse an optional sheet prefix and a cell reference or range at the start of `chars`.
+///
+/// Returns the OpenFormula reference and the number of characters consumed.
+fn parse_reference(chars: &[char]) -> Option<(String, usize)> {
+    let mut pos = 0;
+    let mut sheet = None;
+
+    // Sheet prefix: 'Quoted Name'! or Name!
+    if chars.first() == Some(&'\'') {
+        let end = chars[1..].iter().position(|&c| c == '\'')? + 1;
+        if chars.get(end + 1) != Some(&'!') {
+            return None;
+        }
+        sheet = Some(chars[1..end].iter().collect::<String>());
+        pos = end + 2;
+    } else {
+        let name_len = chars
+            .iter()
+            .take_while(|c| c.is_ascii_alphanumeric() || **c == '_')
+            .count();
+        if name_len > 0 && chars.get(name_len) == Some(&'!') {
+            sheet = Some(chars[..name_len].iter().collect::<String>());
+            pos = name_len + 1;
+        }
+    }
+
+    let (start, len) = parse_cell(&chars[pos..])?;
+    pos += len;
+
+    let mut end = None;
+    if chars.get(pos) == Some(&':')
+        && let Some((cell, len)) = parse_cell(&chars[pos + 1..])
+    {
+        end = Some(cell);
+        pos += 1 + len;
+    }
+
+    // A following '(' means this was a function name such as LOG10(
+    if chars.get(pos) == Some(&'(') {
+        return None;
+    }
+
+    let sheet_prefix = match &sheet {
+        Some(name) if name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') => {
+            format!("${}", name)
+        },
+        Some(name) => format!("$'{}'", name.replace('\'', "''")),
+        None => String::new(),
+    };
+    let reference = match end {
+        Some(end) => format!("[{}.{}:.{}]", sheet_prefix, start, end),
+        None => format!("[{}.{}]", sheet_prefix, start),
+    };
+    Some((reference, pos))
+}
+
+/// Parse an A1-style cell reference (with optional `$` anchors).
+fn parse_cell(chars: &[char]) -> Option<(String, usize)> {
+    let mut pos = 0;
+    if chars.get(pos) == Some(&'$') {
+        pos += 1;
+    }
+    let letters = chars[pos..]
+        .iter()
+        .take_while(|c| c.is_ascii_alphabetic())
+        .count();
+    if letters == 0 || letters > 3 {
+        return None;
+    }
+    pos += letters;
+    if chars.get(pos) == Some(&'$') {
+        pos += 1;
+    }
+    let digits = chars[pos..]
+        .iter()
+        .take_while(|c| c.is_ascii_digit())
+        .count();
+    if digits == 0 {
+        return None;
+    }
+    pos += digits;
+    if chars
+        .get(pos)
+        .is_some_and(|c| c.is_ascii_alphanumeric() || *c == '_')
+    {
+        return None;
+    }
+    Some((
+        chars[..pos].iter().collect::<String>().to_ascii_uppercase(),
+        pos,
+    ))
+}
+
+/// A mutable OpenDocument spreadsheet (.ods) for writing.
+///
+/// # Examples
+///
+/// ```no_run
+/// use litchi::odf::{CellValue, MutableSpreadsheet};
+///
+/// # fn main() -> litchi::Result<()> {
+/// let mut book = MutableSpreadsheet::new();
+/// let sheet = book.add_sheet("Sales");
+/// sheet.set_cell_value(0, 0, "Region");
+/// sheet.set_cell_value(0, 1, "Total");
+/// sheet.set_cell_value(1, 0, "North");
+/// sheet.set_cell_value(1, 1, 1250.0);
+/// sheet.set_cell_value(2, 1, CellValue::Currency(99.5, "EUR".to_string()));
+/// sheet.set_cell_formula(3, 1, "=SUM(B2:B3)");
+/// book.save("sales.ods")?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct MutableSpreadsheet {
+    /// Sheets in order
+    sheets: Vec<MutableSheet>,
+    /// Document metadata written to meta.xml
+    metadata: Metadata,
+}
+
+impl MutableSpreadsheet {
+    /// Create a new spreadsheet without sheets.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a new sheet.
+    pub fn add_sheet(&mut self, name: &str) -> &mut MutableSheet {
+        self.sheets.push(MutableSheet::new(name.to_string()));
+        self.sheets.last_mut().unwrap()
+    }
+
+    /// Get the number of sheets.
+    pub fn sheet_count(&self) -> usize {
+        self.sheets.len()
+    }
+
+    /// Get a mutable sheet by index.
+    pub fn sheet_mut(&mut self, index: usize) -> Option<&mut MutableSheet> {
+        self.sheets.get_mut(index)
+    }
+
+    /// Get a mutable sheet by name.
+    pub fn sheet_by_name_mut(&mut self, name: &str) -> Option<&mut MutableSheet> {
+        self.sheets.iter_mut().find(|s| s.name == name)
+    }
+
+    /// Get a reference to the document metadata.
+    pub fn metadata(&self) -> &Metadata {
+        &self.metadata
+    }
+
+    /// Get a mutable reference to the document metadata.
+    pub fn metadata_mut(&mut self) -> &mut Metadata {
+        &mut self.metadata
+    }
+
+    /// Generate `content.xml`.
+    pub(crate) fn generate_content_xml(&self) -> String {
+        let mut styles = AutomaticStyles::new();
+        let mut body = String::with_capacity(4096);
+        if self.sheets.is_empty() {
+            // A spreadsheet must contain at least one table
+            MutableSheet::new("Sheet1".to_string()).to_xml(&mut body, &mut styles);
+        }
+        for sheet in &self.sheets {
+            sheet.to_xml(&mut body, &mut styles);
+        }
+
+        let mut xml = String::with_capacity(body.len() + 2048);
+        xml.push_str(r#"<?xml version="1.0" encoding="UTF-8"?>"#);
+        xml.push('\n');
+        write!(
+            xml,
+            r#"<office:document-content {} office:version="1.3">"#,
+            NAMESPACES
+        )
+        .unwrap();
+        styles.to_xml(&mut xml);
+        xml.push_str("<office:body><office:spreadsheet>");
+        xml.push_str(&body);
+        xml.push_str("</office:spreadsheet></office:body></office:document-content>");
+        xml
+    }
+
+    /// Generate `styles.xml`.
+    pub(crate) fn generate_styles_xml(&self) -> String {
+        let mut xml = String::with_capacity(1024);
+        xml.push_str(r#"<?xml version="1.0" encoding="UTF-8"?>"#);
+        xml.push('\n');
+        write!(
+            xml,
+            r#"<office:document-styles {} office:version="1.3">"#,
+            NAMESPACES
+        )
+        .unwrap();
+        xml.push_str(
+            r#"<office:styles><style:style style:name="Default" style:family="table-cell"/></office:styles>"#,
+        );
+        xml.push_str(
+            r#"<office:automatic-styles><style:page-layout style:name="pm1"/></office:automatic-styles>"#,
+        );
+        xml.push_str(
+            r#"<office:master-styles><style:master-page style:name="Default" style:page-layout-name="pm1"/></office:master-styles>"#,
+        );
+        xml.push_str("</office:document-styles>");
+        xml
+    }
+
+    /// Build the ODF package for this spreadsheet.
+    pub fn to_package(&self) -> PackageWriter {
+        let mut package = PackageWriter::new(MIMETYPE);
+        package.add_file(
+            "content.xml",
+            "text/xml",
+            self.generate_content_xml().into_bytes(),
+        );
+        package.add_file(
+            "styles.xml",
+            "text/xml",
+            self.generate_styles_xml().into_bytes(),
+        );
+        package.add_file(
+            "meta.xml",
+            "text/xml",
+            generate_meta_xml(&self.metadata).into_bytes(),
+        );
+        package
+    }
+
+    /// Serialize the spreadsheet to an in-memory .ods file.
+    pub fn to_bytes(&self) -> Result<Vec<u8>> {
+        self.to_package().to_bytes()
+    }
+
+    /// Save the spreadsheet to a .ods file.
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<()> {
+        self.to_package().save(path)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::odf::Spreadsheet;
+
+    #[test]
+    fn test_to_open_formula() {
+        assert_eq!(to_open_formula("=SUM(A1:A3)"), "of:=SUM([.A1:.A3])");
+        assert_eq!(to_open_formula("=$B$2*2"), "of:=[.$B$2]*2");
+        assert_eq!(
+            to_open_formula("=IF(A1>0,\"A1\",B1)"),
+            "of:=IF([.A1]>0;\"A1\";[.B1])"
+        );
+        assert_eq!(
+            to_open_formula("=Data!C4+'My Sheet'!D5"),
+            "of:=[$Data.C4]+[$'My Sheet'.D5]"
+        );
+        assert_eq!(to_open_formula("=LOG10(A2)"), "of:=LOG10([.A2])");
+        assert_eq!(to_open_formula("of:=[.A1]+1"), "of:=[.A1]+1");
+    }
+
+    #[test]
+    fn test_round_trip_typed_cells() {
+        let mut book = MutableSpreadsheet::new();
+        let sheet = book.add_sheet("Data");
+        sheet.set_cell_value(0, 0, "Name");
+        sheet.set_cell_value(0, 2, 3.5);
+        sheet.set_cell_value(1, 0, CellValue::Currency(12.5, "EUR".to_string()));
+        sheet.set_cell_value(1, 1, CellValue::Percentage(0.25));
+        sheet.set_cell_formula_with_cache(2, 0, "=C1*2", 7.0);
+        book.add_sheet("Empty");
+
+        let mut reopened = Spreadsheet::from_bytes(book.to_bytes().unwrap()).unwrap();
+        assert_eq!(reopened.sheet_count().unwrap(), 2);
+
+        let sheet = reopened.sheet_by_name("Data").unwrap().unwrap();
+        let rows = &sheet.rows;
+        assert_eq!(
+            rows[0].cells[0].value().unwrap(),
+            &CellValue::Text("Name".to_string())
+        );
+        assert_eq!(rows[0].cells[1].value().unwrap(), &CellValue::Empty);
+        assert_eq!(rows[0].cells[2].value().unwrap(), &CellValue::Number(3.5));
+        assert_eq!(
+            rows[1].cells[0].value().unwrap(),
+            &CellValue::Currency(12.5, "EUR".to_string())
+        );
+        assert_eq!(
+            rows[1].cells[1].value().unwrap(),
+            &CellValue::Percentage(0.25)
+        );
+        assert_eq!(rows[2].cells[0].formula().unwrap(), Some("of:=[.C1]*2"));
+        assert_eq!(rows[2].cells[0].value().unwrap(), &CellValue::Number(7.0));
+    }
+
+    #[test]
+    fn test_merged_cells_are_covered() {
+        let mut book = MutableSpreadsheet::new();
+        let sheet = book.add_sheet("Merged");
+        sheet.set_cell_value(0, 0, "Header");
+        sheet.merge_cells(0, 0, 1, 1);
+        let xml = book.generate_content_xml();
+        assert!(xml.contains(r#"table:number-rows-spanned="2" table:number-columns-spanned="2""#));
+        assert_eq!(xml.matches("<table:covered-table-cell/>").count(), 3);
+    }
+}
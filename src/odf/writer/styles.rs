@@ -0,0 +1,163 @@
+//! Automatic style collection for ODF writers.
+//!
+//! ODF stores direct formatting as named "automatic" styles in `content.xml`.
+//! Writers register the formatting they need while serializing content and get
+//! back a style name; identical formatting shares a single style.
+
+use super::escape_xml;
+use std::collections::HashMap;
+use std::fmt::Write as FmtWrite;
+
+/// A single automatic style definition.
+#[derive(Debug, Clone)]
+struct AutomaticStyle {
+    name: String,
+    family: &'static str,
+    parent: Option<String>,
+    properties: String,
+}
+
+/// Collector for `office:automatic-styles`.
+#[derive(Debug, Default)]
+pub(crate) struct AutomaticStyles {
+    styles: Vec<AutomaticStyle>,
+    /// (family, parent, properties) -> style name
+    index: HashMap<(&'static str, Option<String>, String), String>,
+    /// Per-prefix counters used to generate style names
+    counters: HashMap<&'static str, usize>,
+}
+
+impl AutomaticStyles {
+    pub(crate) fn new() -> Self {
+        Self::default()
+    }
+
+    /// Register a style and return its name, reusing an identical existing style.
+    ///
+    /// # Arguments
+    /// * `family` - Style family (e.g., "text", "paragraph", "table-cell")
+    /// * `prefix` - Name prefix for generated style names (e.g., "T", "P")
+    /// * `parent` - Optional parent style name
+    /// * `properties` - Serialized property elements (e.g., `<style:text-properties .../>`)
+    pub(crate) fn get_or_add(
+        &mut self,
+        family: &'static str,
+        prefix: &'static str,
+        parent: Option<&str>,
+        properties: String,
+    ) -> String {
+        let key = (family, parent.map(str::to_string), properties);
+        if let Some(name) = self.index.get(&key) {
+            return name.clone();
+        }
+
+        let counter = self.counters.entry(prefix).or_insert(0);
+        *counter += 1;
+        let name = format!("{}{}", prefix, counter);
+
+        self.styles.push(AutomaticStyle {
+            name: name.clone(),
+            family,
+            parent: key.1.clone(),
+            properties: key.2.clone(),
+        });
+        self.index.insert(key, name.clone());
+        name
+    }
+
+    /// Serialize as an `office:automatic-styles` element.
+    pub(crate) fn to_xml(&self, xml: &mut String) {
+        if self.styles.is_empty() {
+            xml.push_str("<office:automatic-styles/>");
+            return;
+        }
+
+        xml.push_str("<office:automatic-styles>");
+        for style in &self.styles {
+            write!(
+                xml,
+                r#"<style:style style:name="{}" style:family="{}""#,
+                style.name, style.family
+            )
+            .unwrap();
+            if let Some(parent) = &style.parent {
+                write!(xml, r#" style:parent-style-name="{}""#, escape_xml(parent)).unwrap();
+            }
+            xml.push('>');
+            xml.push_str(&style.properties);
+            xml.push_str("</style:style>");
+        }
+        xml.push_str("</office:automatic-styles>");
+    }
+}
+
+/// Character formatting shared by text runs in all ODF writers.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub(crate) struct TextProperties {
+    pub bold: bool,
+    pub italic: bool,
+    pub underline: bool,
+    pub strikethrough: bool,
+    pub font_size: Option<f64>,
+    pub font_name: Option<String>,
+    pub color: Option<String>,
+    pub highlight: Option<String>,
+}
+
+impl TextProperties {
+    /// Check whether any formatting is set.
+    pub(crate) fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// Serialize as a `style:text-properties` element.
+    pub(crate) fn to_xml(&self) -> String {
+        let mut xml = String::from("<style:text-properties");
+        if self.bold {
+            xml.push_str(
+                r#" fo:font-weight="bold" style:font-weight-asian="bold" style:font-weight-complex="bold""#,
+            );
+        }
+        if self.italic {
+            xml.push_str(
+                r#" fo:font-style="italic" style:font-style-asian="italic" style:font-style-complex="italic""#,
+            );
+        }
+        if self.underline {
+            xml.push_str(
+                r#" style:text-underline-style="solid" style:text-underline-width="auto" style:text-underline-color="font-color""#,
+            );
+        }
+        if self.strikethrough {
+            xml.push_str(r#" style:text-line-through-style="solid""#);
+        }
+        if let Some(size) = self.font_size {
+            write!(
+                xml,
+                r#" fo:font-size="{size}pt" style:font-size-asian="{size}pt" style:font-size-complex="{size}pt""#
+            )
+            .unwrap();
+        }
+        if let Some(name) = &self.font_name {
+            write!(xml, r#" fo:font-family="{}""#, escape_xml(name)).unwrap();
+        }
+        if let Some(color) = &self.color {
+            write!(xml, r##" fo:color="#{}""##, normalize_color(color)).unwrap();
+        }
+        if let Some(color) = &self.highlight {
+            write!(
+                xml,
+                r##" fo:background-color="#{}""##,
+                normalize_color(color)
+            )
+            .unwrap();
+        }
+        xml.push_str("/>");
+        xml
+    }
+}
+
+/// Normalize a hex color ("#FF0000" or "ff0000") to uppercase hex without `#`.
+pub(crate) fn normalize_color(color: &str) -> String {
+    color.trim_start_matches('#').to_ascii_uppercase()
+}
@@ -0,0 +1,896 @@
+//! Text document (.odt) writer.
+use super::image::ImageFormat;
+use super::package::{PackageWriter, generate_meta_xml};
+use super::styles::{AutomaticStyles, TextProperties, normalize_color};
+use super::{NAMESPACES, emu_to_cm, escape_xml, write_text_content};
+use crate::common::{Error, Metadata, Result};
+use std::fmt::Write as FmtWrite;
+use std::path::Path;
+
+/// MIME type of OpenDocument text documents.
+const MIMETYPE: &str = "application/vnd.oasis.opendocument.text";
+
+/// Paragraph alignment.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ParagraphAlignment {
+    Left,
+    Center,
+    Right,
+    Justify,
+}
+
+impl ParagraphAlignment {
+    /// Get the `fo:text-align` attribute value.
+    fn as_odf(&self) -> &'static str {
+        match self {
+            Self::Left => "start",
+            Self::Center => "center",
+            Self::Right => "end",
+            Self::Justify => "justify",
+        }
+    }
+}
+
+/// A mutable run of text with character formatting.
+#[derive(Debug, Clone, Default)]
+pub struct MutableRun {
+    /// Run text
+    pub(crate) text: String,
+    /// Character formatting
+    pub(crate) properties: TextProperties,
+}
+
+impl MutableRun {
+    pub(crate) fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the text content.
+    pub fn set_text(&mut self, text: &str) {
+        self.text = text.to_string();
+    }
+
+    /// Get the text content.
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    /// Set bold formatting.
+    pub fn bold(&mut self, bold: bool) -> &mut Self {
+        self.properties.bold = bold;
+        self
+    }
+
+    /// Set italic formatting.
+    pub fn italic(&mut self, italic: bool) -> &mut Self {
+        self.properties.italic = italic;
+        self
+    }
+
+    /// Set single underline.
+    pub fn underline(&mut self, underline: bool) -> &mut Self {
+        self.properties.underline = underline;
+        self
+    }
+
+    /// Set strikethrough.
+    pub fn strikethrough(&mut self, strikethrough: bool) -> &mut Self {
+        self.properties.strikethrough = strikethrough;
+        self
+    }
+
+    /// Set font size in points.
+    pub fn font_size(&mut self, size: f64) -> &mut Self {
+        self.properties.font_size = Some(size);
+        self
+    }
+
+    /// Set font family name.
+    pub fn font_name(&mut self, name: &str) -> &mut Self {
+        self.properties.font_name = Some(name.to_string());
+        self
+    }
+
+    /// Set text color (hex RGB, e.g., "FF0000").
+    pub fn color(&mut self, color: &str) -> &mut Self {
+        self.properties.color = Some(color.to_string());
+        self
+    }
+
+    /// Set highlight (background) color (hex RGB, e.g., "FFFF00").
+    pub fn highlight(&mut self, color: &str) -> &mut Self {
+        self.properties.highlight = Some(color.to_string());
+        self
+    }
+
+    /// Serialize the run as a `text:span` (or bare text if unformatted).
+    fn to_xml(&self, xml: &mut String, styles: &mut AutomaticStyles) {
+        if self.properties.is_empty() {
+            write_text_content(xml, &self.text);
+        } else {
+            let name = styles.get_or_add("text", "T", None, self.properties.to_xml());
+            write!(xml, r#"<text:span text:style-name="{}">"#, name).unwrap();
+            write_text_content(xml, &self.text);
+            xml.push_str("</text:span>");
+        }
+    }
+}
+
+/// An image embedded in a paragraph.
+#[derive(Debug, Clone)]
+pub(crate) struct InlineImage {
+    pub(crate) data: Vec<u8>,
+    pub(crate) format: ImageFormat,
+    /// Width in EMUs
+    pub(crate) width_emu: i64,
+    /// Height in EMUs
+    pub(crate) height_emu: i64,
+    pub(crate) description: String,
+}
+
+/// Elements that can appear in a paragraph.
+#[derive(Debug, Clone)]
+pub(crate) enum ParagraphElement {
+    Run(MutableRun),
+    Hyperlink { url: String, run: MutableRun },
+    Image(InlineImage),
+}
+
+/// A mutable paragraph or heading.
+#[derive(Debug, Clone, Default)]
+pub struct MutableParagraph {
+    /// Elements (runs, hyperlinks, images) in this paragraph
+    pub(crate) elements: Vec<ParagraphElement>,
+    /// Named paragraph style (from styles.xml)
+    pub(crate) style: Option<String>,
+    /// Outline level for headings (1-10)
+    pub(crate) heading_level: Option<u8>,
+    /// Paragraph alignment
+    pub(crate) alignment: Option<ParagraphAlignment>,
+    /// Whether a page break precedes this paragraph
+    pub(crate) page_break_before: bool,
+}
+
+impl MutableParagraph {
+    pub(crate) fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a new run to the paragraph.
+    pub fn add_run(&mut self) -> &mut MutableRun {
+        self.elements.push(ParagraphElement::Run(MutableRun::new()));
+        match self.elements.last_mut().unwrap() {
+            ParagraphElement::Run(r) => r,
+            _ => unreachable!(),
+        }
+    }
+
+    /// Add a run with text.
+    pub fn add_run_with_text(&mut self, text: &str) -> &mut MutableRun {
+        let run = self.add_run();
+        run.set_text(text);
+        run
+    }
+
+    /// Add a hyperlink and return its run for formatting.
+    pub fn add_hyperlink(&mut self, text: &str, url: &str) -> &mut MutableRun {
+        let mut run = MutableRun::new();
+        run.set_text(text);
+        self.elements.push(ParagraphElement::Hyperlink {
+            url: url.to_string(),
+            run,
+        });
+        match self.elements.last_mut().unwrap() {
+            ParagraphElement::Hyperlink { run, .. } => run,
+            _ => unreachable!(),
+        }
+    }
+
+    /// Add an inline image from a file.
+    pub fn add_picture(
+        &mut self,
+        image_path: &str,
+        width_emu: Option<i64>,
+        height_emu: Option<i64>,
+    ) -> Result<()> {
+        let data = std::fs::read(image_path)?;
+        self.add_picture_from_bytes(data, width_emu, height_emu, None)
+    }
+
+    /// Add an inline image from bytes.
+    ///
+    /// Dimensions are in EMUs and default to one inch when not given.
+    pub fn add_picture_from_bytes(
+        &mut self,
+        data: Vec<u8>,
+        width_emu: Option<i64>,
+        height_emu: Option<i64>,
+        description: Option<String>,
+    ) -> Result<()> {
+        let format = ImageFormat::detect_from_bytes(&data)
+            .ok_or_else(|| Error::InvalidFormat("Unknown image format".to_string()))?;
+        self.elements.push(ParagraphElement::Image(InlineImage {
+            data,
+            format,
+            width_emu: width_emu.unwrap_or(914400),
+            height_emu: height_emu.unwrap_or(914400),
+            description: description.unwrap_or_default(),
+        }));
+        Ok(())
+    }
+
+    /// Set the named paragraph style (e.g., "Text_20_body").
+    pub fn set_style(&mut self, style_name: &str) {
+        self.style = Some(style_name.to_string());
+    }
+
+    /// Set paragraph alignment.
+    pub fn set_alignment(&mut self, alignment: ParagraphAlignment) {
+        self.alignment = Some(alignment);
+    }
+
+    /// Insert a page break before this paragraph.
+    pub fn set_page_break_before(&mut self, page_break: bool) {
+        self.page_break_before = page_break;
+    }
+
+    /// Get the heading level, if this paragraph is a heading.
+    pub fn heading_level(&self) -> Option<u8> {
+        self.heading_level
+    }
+
+    /// Get the number of elements in this paragraph.
+    pub fn element_count(&self) -> usize {
+        self.elements.len()
+    }
+
+    /// Get the plain text of the paragraph.
+    pub fn text(&self) -> String {
+        let mut text = String::new();
+        for element in &self.elements {
+            match element {
+                ParagraphElement::Run(run) | ParagraphElement::Hyperlink { run, .. } => {
+                    text.push_str(&run.text)
+                },
+                ParagraphElement::Image(_) => {},
+            }
+        }
+        text
+    }
+
+    /// Serialize the paragraph as `text:p` or `text:h`.
+    pub(crate) fn to_xml(
+        &self,
+        xml: &mut String,
+        styles: &mut AutomaticStyles,
+        images: &mut ImageCollector,
+    ) {
+        let base_style = match (&self.style, self.heading_level) {
+            (Some(style), _) => Some(style.clone()),
+            (None, Some(level)) => Some(format!("Heading_20_{}", level)),
+            (None, None) => None,
+        };
+
+        // Direct paragraph formatting becomes an automatic style derived from the base style
+        let style_name = if self.alignment.is_some() || self.page_break_before {
+            let mut props = String::from("<style:paragraph-properties");
+            if let Some(alignment) = self.alignment {
+                write!(props, r#" fo:text-align="{}""#, alignment.as_odf()).unwrap();
+            }
+            if self.page_break_before {
+                props.push_str(r#" fo:break-before="page""#);
+            }
+            props.push_str("/>");
+            let parent = base_style.as_deref().unwrap_or("Standard");
+            Some(styles.get_or_add("paragraph", "P", Some(parent), props))
+        } else {
+            base_style
+        };
+
+        let tag = if self.heading_level.is_some() {
+            "text:h"
+        } else {
+            "text:p"
+        };
+        xml.push('<');
+        xml.push_str(tag);
+        if let Some(name) = &style_name {
+            write!(xml, r#" text:style-name="{}""#, escape_xml(name)).unwrap();
+        }
+        if let Some(level) = self.heading_level {
+            write!(xml, r#" text:outline-level="{}""#, level).unwrap();
+        }
+        xml.push('>');
+
+        for element in &self.elements {
+            match element {
+                ParagraphElement::Run(run) => run.to_xml(xml, styles),
+                ParagraphElement::Hyperlink { url, run } => {
+                    write!(
+                        xml,
+                        r#"<text:a xlink:type="simple" xlink:href="{}">"#,
+                        escape_xml(url)
+                    )
+                    .unwrap();
+                    run.to_xml(xml, styles);
+                    xml.push_str("</text:a>");
+                },
+                ParagraphElement::Image(image) => {
+                    let (index, href) = images.add(image.data.clone(), image.format);
+                    write!(
+                        xml,
+                        r#"<draw:frame draw:name="Image{}" text:anchor-type="as-char" svg:width="{}" svg:height="{}" draw:z-index="0"><draw:image xlink:href="{}" xlink:type="simple" xlink:show="embed" xlink:actuate="onLoad"/>"#,
+                        index,
+                        emu_to_cm(image.width_emu),
+                        emu_to_cm(image.height_emu),
+                        href
+                    )
+                    .unwrap();
+                    if !image.description.is_empty() {
+                        write!(
+                            xml,
+                            "<svg:desc>{}</svg:desc>",
+                            escape_xml(&image.description)
+                        )
+                        .unwrap();
+                    }
+                    xml.push_str("</draw:frame>");
+                },
+            }
+        }
+
+        write!(xml, "</{}>", tag).unwrap();
+    }
+}
+
+/// Collects images referenced from content so they can be written to `Pictures/`.
+#[derive(Debug, Default)]
+pub(crate) struct ImageCollector {
+    pub(crate) images: Vec<(String, ImageFormat, Vec<u8>)>,
+}
+
+impl ImageCollector {
+    /// Register an image and return its 1-based index and package path.
+    pub(crate) fn add(&mut self, data: Vec<u8>, format: ImageFormat) -> (usize, String) {
+        let index = self.images.len() + 1;
+        let path = format!("Pictures/image{}.{}", index, format.extension());
+        self.images.push((path.clone(), format, data));
+        (index, path)
+    }
+
+    /// Add all collected images to a package.
+    pub(crate) fn write_to(self, package: &mut PackageWriter) {
+        for (path, format, data) in self.images {
+            package.add_file(&path, format.mime_type(), data);
+        }
+    }
+}
+
+/// A mutable table cell.
+#[derive(Debug, Clone, Default)]
+pub struct MutableCell {
+    /// Paragraphs in this cell
+    pub(crate) paragraphs: Vec<MutableParagraph>,
+    /// Background color (hex RGB)
+    pub(crate) background_color: Option<String>,
+    /// Number of columns this cell spans
+    pub(crate) column_span: u32,
+}
+
+impl MutableCell {
+    pub(crate) fn new() -> Self {
+        Self {
+            column_span: 1,
+            ..Default::default()
+        }
+    }
+
+    /// Add a paragraph to the cell.
+    pub fn add_paragraph(&mut self) -> &mut MutableParagraph {
+        self.paragraphs.push(MutableParagraph::new());
+        self.paragraphs.last_mut().unwrap()
+    }
+
+    /// Get the number of paragraphs in the cell.
+    pub fn paragraph_count(&self) -> usize {
+        self.paragraphs.len()
+    }
+
+    /// Get a paragraph by index.
+    pub fn paragraph(&mut self, index: usize) -> Option<&mut MutableParagraph> {
+        self.paragraphs.get_mut(index)
+    }
+
+    /// Replace the cell content with a single paragraph of text.
+    pub fn set_text(&mut self, text: &str) {
+        self.paragraphs.clear();
+        self.add_paragraph().add_run_with_text(text);
+    }
+
+    /// Set the cell background color (hex RGB).
+    pub fn set_background_color(&mut self, color: &str) {
+        self.background_color = Some(color.to_string());
+    }
+
+    /// Make this cell span multiple columns.
+    ///
+    /// The cells it covers in the same row are written as covered cells.
+    pub fn set_column_span(&mut self, span: u32) {
+        self.column_span = span.max(1);
+    }
+}
+
+/// A mutable table row.
+#[derive(Debug, Clone, Default)]
+pub struct MutableRow {
+    pub(crate) cells: Vec<MutableCell>,
+    /// Whether this row repeats as a header on each page
+    pub(crate) is_header: bool,
+}
+
+impl MutableRow {
+    pub(crate) fn new(cols: usize) -> Self {
+        Self {
+            cells: (0..cols).map(|_| MutableCell::new()).collect(),
+            is_header: false,
+        }
+    }
+
+    /// Get a cell by index.
+    pub fn cell(&mut self, index: usize) -> Option<&mut MutableCell> {
+        self.cells.get_mut(index)
+    }
+
+    /// Add a cell to the row.
+    pub fn add_cell(&mut self) -> &mut MutableCell {
+        self.cells.push(MutableCell::new());
+        self.cells.last_mut().unwrap()
+    }
+
+    /// Get the number of cells in the row.
+    pub fn cell_count(&self) -> usize {
+        self.cells.len()
+    }
+
+    /// Mark this row as a repeating header row.
+    pub fn set_header(&mut self, is_header: bool) {
+        self.is_header = is_header;
+    }
+}
+
+/// A mutable table.
+#[derive(Debug, Clone)]
+pub struct MutableTable {
+    pub(crate) name: String,
+    pub(crate) rows: Vec<MutableRow>,
+}
+
+impl MutableTable {
+    pub(crate) fn new(name: String, rows: usize, cols: usize) -> Self {
+        Self {
+            name,
+            rows: (0..rows).map(|_| MutableRow::new(cols)).collect(),
+        }
+    }
+
+    /// Get the table name.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Set the table name.
+    pub fn set_name(&mut self, name: &str) {
+        self.name = name.to_string();
+    }
+
+    /// Add a row with the given number of cells.
+    pub fn add_row(&mut self, cols: usize) -> &mut MutableRow {
+        self.rows.push(MutableRow::new(cols));
+        self.rows.last_mut().unwrap()
+    }
+
+    /// Get a cell by row and column index.
+    pub fn cell(&mut self, row: usize, col: usize) -> Option<&mut MutableCell> {
+        self.rows.get_mut(row).and_then(|r| r.cell(col))
+    }
+
+    /// Get the number of rows.
+    pub fn row_count(&self) -> usize {
+        self.rows.len()
+    }
+
+    /// Get a row by index.
+    pub fn row(&mut self, index: usize) -> Option<&mut MutableRow> {
+        self.rows.get_mut(index)
+    }
+
+    /// Get the number of columns (widest row, including spans).
+    pub fn column_count(&self) -> usize {
+        self.rows
+            .iter()
+            .map(|r| {
+                r.cells
+                    .iter()
+                    .map(|c| c.column_span as usize)
+                    .sum::<usize>()
+            })
+            .max()
+            .unwrap_or(0)
+    }
+
+    /// Serialize the table as `table:table`.
+    pub(crate) fn to_xml(
+        &self,
+        xml: &mut String,
+        styles: &mut AutomaticStyles,
+        images: &mut ImageCollector,
+    ) {
+        write!(
+            xml,
+            r#"<table:table table:name="{}">"#,
+            escape_xml(&self.name)
+        )
+        .unwrap();
+
+        let cols = self.column_count().max(1);
+        write!(
+            xml,
+            r#"<table:table-column table:number-columns-repeated="{}"/>"#,
+            cols
+        )
+        .unwrap();
+
+        let header_rows: Vec<&MutableRow> = self.rows.iter().take_while(|r| r.is_header).collect();
+        if !header_rows.is_empty() {
+            xml.push_str("<table:table-header-rows>");
+            for row in &header_rows {
+                Self::row_to_xml(row, xml, styles, images);
+            }
+            xml.push_str("</table:table-header-rows>");
+        }
+        for row in self.rows.iter().skip(header_rows.len()) {
+            Self::row_to_xml(row, xml, styles, images);
+        }
+
+        xml.push_str("</table:table>");
+    }
+
+    fn row_to_xml(
+        row: &MutableRow,
+        xml: &mut String,
+        styles: &mut AutomaticStyles,
+        images: &mut ImageCollector,
+    ) {
+        xml.push_str("<table:table-row>");
+        for cell in &row.cells {
+            xml.push_str("<table:table-cell");
+            if let Some(color) = &cell.background_color {
+                let props = format!(
+                    r##"<style:table-cell-properties fo:background-color="#{}"/>"##,
+                    normalize_color(color)
+                );
+                let name = styles.get_or_add("table-cell", "Cell", None, props);
+                write!(xml, r#" table:style-name="{}""#, name).unwrap();
+            }
+            if cell.column_span > 1 {
+                write!(
+                    xml,
+                    r#" table:number-columns-spanned="{}""#,
+                    cell.column_span
+                )
+                .unwrap();
+            }
+            xml.push_str(r#" office:value-type="string">"#);
+            if cell.paragraphs.is_empty() {
+                xml.push_str("<text:p/>");
+            }
+            for para in &cell.paragraphs {
+                para.to_xml(xml, styles, images);
+            }
+            xml.push_str("</table:table-cell>");
+            for _ in 1..cell.column_span {
+                xml.push_str("<table:covered-table-cell/>");
+            }
+        }
+        xml.push_str("</table:table-row>");
+    }
+}
+
+/// Top-level elements in the document body.
+#[derive(Debug, Clone)]
+pub(crate) enum BodyElement {
+    Paragraph(MutableParagraph),
+    Table(MutableTable),
+}
+
+/// A mutable OpenDocument text document (.odt) for writing.
+///
+/// # Examples
+///
+/// ```no_run
+/// use litchi::odf::{MutableDocument, ParagraphAlignment};
+///
+/// # fn main() -> litchi::Result<()> {
+/// let mut doc = MutableDocument::new();
+/// doc.add_heading("Quarterly Report", 1);
+///
+/// let para = doc.add_paragraph();
+/// para.set_alignment(ParagraphAlignment::Justify);
+/// para.add_run_with_text("Revenue grew ");
+/// para.add_run_with_text("12%").bold(true);
+///
+/// let table = doc.add_table(2, 2);
+/// table.cell(0, 0).unwrap().set_text("Region");
+/// table.cell(0, 1).unwrap().set_text("Sales");
+///
+/// doc.save("report.odt")?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct MutableDocument {
+    /// Body content in document order
+    pub(crate) body: Vec<BodyElement>,
+    /// Document metadata written to meta.xml
+    pub(crate) metadata: Metadata,
+}
+
+impl MutableDocument {
+    /// Create a new empty document.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a new paragraph to the end of the document.
+    pub fn add_paragraph(&mut self) -> &mut MutableParagraph {
+        self.body
+            .push(BodyElement::Paragraph(MutableParagraph::new()));
+        match self.body.last_mut().unwrap() {
+            BodyElement::Paragraph(p) => p,
+            _ => unreachable!(),
+        }
+    }
+
+    /// Add a paragraph with text.
+    pub fn add_paragraph_with_text(&mut self, text: &str) -> &mut MutableParagraph {
+        let para = self.add_paragraph();
+        para.add_run_with_text(text);
+        para
+    }
+
+    /// Add a heading paragraph.
+    ///
+    /// Level 0 produces a title; levels 1-10 produce outline headings.
+    pub fn add_heading(&mut self, text: &str, level: u8) -> &mut MutableParagraph {
+        let para = self.add_paragraph();
+        if level == 0 {
+            para.set_style("Title");
+        } else {
+            para.heading_level = Some(level.min(10));
+        }
+        para.add_run_with_text(text);
+        para
+    }
+
+    /// Add a table with the specified rows and columns.
+    pub fn add_table(&mut self, rows: usize, cols: usize) -> &mut MutableTable {
+        let name = format!("Table{}", self.table_count() + 1);
+        self.body
+            .push(BodyElement::Table(MutableTable::new(name, rows, cols)));
+        match self.body.last_mut().unwrap() {
+            BodyElement::Table(t) => t,
+            _ => unreachable!(),
+        }
+    }
+
+    /// Add an empty paragraph that starts on a new page.
+    pub fn add_page_break(&mut self) -> &mut MutableParagraph {
+        let para = self.add_paragraph();
+        para.set_page_break_before(true);
+        para
+    }
+
+    /// Get the number of paragraphs (including headings) in the document body.
+    pub fn paragraph_count(&self) -> usize {
+        self.body
+            .iter()
+            .filter(|e| matches!(e, BodyElement::Paragraph(_)))
+            .count()
+    }
+
+    /// Get the number of tables in the document body.
+    pub fn table_count(&self) -> usize {
+        self.body
+            .iter()
+            .filter(|e| matches!(e, BodyElement::Table(_)))
+            .count()
+    }
+
+    /// Get a reference to the document metadata.
+    pub fn metadata(&self) -> &Metadata {
+        &self.metadata
+    }
+
+    /// Get a mutable reference to the document metadata.
+    pub fn metadata_mut(&mut self) -> &mut Metadata {
+        &mut self.metadata
+    }
+
+    /// Generate `content.xml`, collecting referenced images.
+    pub(crate) fn generate_content_xml(&self, images: &mut ImageCollector) -> String {
+        let mut styles = AutomaticStyles::new();
+        let mut body = String::with_capacity(4096);
+        for element in &self.body {
+            match element {
+                BodyElement::Paragraph(p) => p.to_xml(&mut body, &mut styles, images),
+                BodyElement::Table(t) => t.to_xml(&mut body, &mut styles, images),
+            }
+        }
+
+        let mut xml = String::with_capacity(body.len() + 2048);
+        xml.push_str(r#"<?xml version="1.0" encoding="UTF-8"?>"#);
+        xml.push('\n');
+        write!(
+            xml,
+            r#"<office:document-content {} office:version="1.3">"#,
+            NAMESPACES
+        )
+        .unwrap();
+        styles.to_xml(&mut xml);
+        xml.push_str("<office:body><office:text>");
+        xml.push_str(&body);
+        xml.push_str("</office:text></office:body></office:document-content>");
+        xml
+    }
+
+    /// Generate `styles.xml` with the default paragraph and heading styles.
+    pub(crate) fn generate_styles_xml(&self) -> String {
+        let mut xml = String::with_capacity(4096);
+        xml.push_str(r#"<?xml version="1.0" encoding="UTF-8"?>"#);
+        xml.push('\n');
+        write!(
+            xml,
+            r#"<office:document-styles {} office:version="1.3"><office:styles>"#,
+            NAMESPACES
+        )
+        .unwrap();
+        xml.push_str(
+            r#"<style:default-style style:family="paragraph"><style:paragraph-properties fo:margin-top="0cm" fo:margin-bottom="0.212cm"/><style:text-properties fo:font-size="12pt"/></style:default-style>"#,
+        );
+        xml.push_str(
+            r#"<style:style style:name="Standard" style:family="paragraph" style:class="text"/>"#,
+        );
+        xml.push_str(
+            r#"<style:style style:name="Text_20_body" style:display-name="Text body" style:family="paragraph" style:parent-style-name="Standard" style:class="text"/>"#,
+        );
+        xml.push_str(
+            r#"<style:style style:name="Heading" style:family="paragraph" style:parent-style-name="Standard" style:next-style-name="Text_20_body" style:class="text"><style:paragraph-properties fo:margin-top="0.423cm" fo:margin-bottom="0.212cm" fo:keep-with-next="always"/><style:text-properties fo:font-weight="bold"/></style:style>"#,
+        );
+        xml.push_str(
+            r#"<style:style style:name="Title" style:family="paragraph" style:parent-style-name="Heading" style:class="chapter"><style:paragraph-properties fo:text-align="center"/><style:text-properties fo:font-size="28pt"/></style:style>"#,
+        );
+        const HEADING_SIZES: [&str; 10] = [
+            "130%", "115%", "101%", "95%", "85%", "85%", "80%", "80%", "75%", "75%",
+        ];
+        for (i, size) in HEADING_SIZES.iter().enumerate() {
+            let level = i + 1;
+            write!(
+                xml,
+                r#"<style:style style:name="Heading_20_{level}" style:display-name="Heading {level}" style:family="paragraph" style:parent-style-name="Heading" style:next-style-name="Text_20_body" style:default-outline-level="{level}" style:class="text"><style:text-properties fo:font-size="{size}"/></style:style>"#
+            )
+            .unwrap();
+        }
+        xml.push_str(
+            r#"<style:style style:name="Table_20_Contents" style:display-name="Table Contents" style:family="paragraph" style:parent-style-name="Standard" style:class="extra"/>"#,
+        );
+        xml.push_str("</office:styles>");
+        xml.push_str(
+            r#"<office:automatic-styles><style:page-layout style:name="pm1"><style:page-layout-properties fo:page-width="21.001cm" fo:page-height="29.7cm" style:print-orientation="portrait" fo:margin-top="2cm" fo:margin-bottom="2cm" fo:margin-left="2cm" fo:margin-right="2cm"/></style:page-layout></office:automatic-styles>"#,
+        );
+        xml.push_str(
+            r#"<office:master-styles><style:master-page style:name="Standard" style:page-layout-name="pm1"/></office:master-styles>"#,
+        );
+        xml.push_str("</office:document-styles>");
+        xml
+    }
+
+    /// Build the ODF package for this document.
+    pub fn to_package(&self) -> PackageWriter {
+        let mut images = ImageCollector::default();
+        let content = self.generate_content_xml(&mut images);
+
+        let mut package = PackageWriter::new(MIMETYPE);
+        package.add_file("content.xml", "text/xml", content.into_bytes());
+        package.add_file(
+            "styles.xml",
+            "text/xml",
+            self.generate_styles_xml().into_bytes(),
+        );
+        package.add_file(
+            "meta.xml",
+            "text/xml",
+            generate_meta_xml(&self.metadata).into_bytes(),
+        );
+        images.write_to(&mut package);
+        package
+    }
+
+    /// Serialize the document to an in-memory .odt file.
+    pub fn to_bytes(&self) -> Result<Vec<u8>> {
+        self.to_package().to_bytes()
+    }
+
+    /// Save the document to a .odt file.
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<()> {
+        self.to_package().save(path)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::odf::Document;
+
+    /// Smallest valid PNG (1x1 transparent pixel)
+    const PNG_1X1: &[u8] = &[
+        0x89, 0x50, 0x4E, 0x47, 0x0D, 0x0A, 0x1A, 0x0A, 0x00, 0x00, 0x00, 0x0D, 0x49, 0x48, 0x44,
+        0x52, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x00, 0x01, 0x08, 0x06, 0x00, 0x00, 0x00, 0x1F,
+        0x15, 0xC4, 0x89, 0x00, 0x00, 0x00, 0x0A, 0x49, 0x44, 0x41, 0x54, 0x78, 0x9C, 0x63, 0x00,
+        0x01, 0x00, 0x00, 0x05, 0x00, 0x01, 0x0D, 0x0A, 0x2D, 0xB4, 0x00, 0x00, 0x00, 0x00, 0x49,
+        0x45, 0x4E, 0x44, 0xAE, 0x42, 0x60, 0x82,
+    ];
+
+    #[test]
+    fn test_run_styles_are_deduplicated() {
+        let mut doc = MutableDocument::new();
+        let para = doc.add_paragraph();
+        para.add_run_with_text("a").bold(true);
+        para.add_run_with_text("b").bold(true);
+        para.add_run_with_text("c").italic(true);
+
+        let mut images = ImageCollector::default();
+        let xml = doc.generate_content_xml(&mut images);
+        assert_eq!(xml.matches(r#"style:name="T1""#).count(), 1);
+        assert!(xml.contains(r#"style:name="T2""#));
+        assert!(!xml.contains(r#"style:name="T3""#));
+    }
+
+    #[test]
+    fn test_round_trip_through_reader() {
+        let mut doc = MutableDocument::new();
+        doc.metadata_mut().title = Some("Round Trip".to_string());
+        doc.add_heading("Introduction", 1);
+        let para = doc.add_paragraph_with_text("See ");
+        para.add_hyperlink("example", "https://example.com");
+        para.add_picture_from_bytes(PNG_1X1.to_vec(), None, None, Some("dot".to_string()))
+            .unwrap();
+
+        let table = doc.add_table(2, 2);
+        table.cell(0, 0).unwrap().set_text("A1");
+        table.cell(1, 1).unwrap().set_text("B2");
+
+        let bytes = doc.to_bytes().unwrap();
+        let reopened = Document::from_bytes(bytes).unwrap();
+
+        let text = reopened.text().unwrap();
+        assert!(text.contains("Introduction"));
+        assert!(text.contains("See example"));
+
+        let tables = reopened.tables().unwrap();
+        assert_eq!(tables.len(), 1);
+
+        let metadata = reopened.metadata().unwrap();
+        assert_eq!(metadata.title.as_deref(), Some("Round Trip"));
+    }
+
+    #[test]
+    fn test_image_is_packaged() {
+        let mut doc = MutableDocument::new();
+        doc.add_paragraph()
+            .add_picture_from_bytes(PNG_1X1.to_vec(), Some(360_000), Some(360_000), None)
+            .unwrap();
+        let package = doc.to_package();
+        assert!(package.has_file("Pictures/image1.png"));
+        assert!(package.manifest_xml().contains("Pictures/image1.png"));
+    }
+}
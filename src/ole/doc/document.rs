@@ -1,10 +1,11 @@
 use super::super::OleFile;
 /// Document - the main API for working with Word document content.
 use super::package::{DocError, Result};
-use super::paragraph::{Paragraph, Run};
+use super::paragraph::{Paragraph, Run, resolve_hyperlink_fields};
 use super::parts::chp_bin_table::ChpBinTable;
 use super::parts::fib::FileInformationBlock;
 use super::parts::fields::FieldsTable;
+use super::parts::lists::ListTables;
 use super::parts::paragraph_extractor::{ExtractedParagraph, ParagraphExtractor};
 use super::parts::text::TextExtractor;
 use super::table::Table;
@@ -60,6 +61,8 @@ pub struct Document {
     /// Fields table - contains field information (embedded equations, hyperlinks, etc.)
     #[allow(dead_code)] // Stored for future field extraction features
     fields_table: Option<FieldsTable>,
+    /// List definitions - give list paragraphs their number formats
+    lists: ListTables,
     /// Extracted MTEF data from OLE streams (stream_name -> mtef_data)
     #[allow(dead_code)] // Stored for debugging and raw access
     mtef_data: std::collections::HashMap<String, Vec<u8>>,
@@ -80,6 +83,8 @@ pub struct Document {
     /// Parsed MTEF formulas placeholder (when formula feature is disabled)
     #[cfg(not(feature = "formula"))]
     parsed_mtef: std::collections::HashMap<String, Arc<Vec<()>>>,
+    /// The Data stream - holds the picture BLIPs referenced from the text
+    data_stream: Option<Vec<u8>>,
 }
 
 impl Document {
@@ -113,6 +118,9 @@ impl Document {
         // Parse fields table to identify embedded equations
         let fields_table = FieldsTable::parse(&fib, &table_stream).ok();
 
+        // Parse list tables to resolve list paragraph formats
+        let lists = ListTables::parse(&fib, &table_stream);
+
         // Extract MTEF data from OLE streams
         let mtef_data = Self::extract_mtef_data(ole)?;
 
@@ -126,6 +134,9 @@ impl Document {
         // This is a major performance optimization since ChpBinTable::parse is expensive
         let chp_bin_table = Self::parse_chp_bin_table(&fib, &table_stream, &word_document)?;
 
+        // Pictures live in the optional Data stream
+        let data_stream = ole.open_stream(&["Data"]).ok();
+
         Ok(Self {
             fib,
             word_document,
@@ -133,12 +144,14 @@ impl Document {
             text_extractor,
             chp_bin_table,
             fields_table,
+            lists,
             mtef_data,
             #[cfg(feature = "formula")]
             formula_arenas,
             #[cfg(feature = "formula")]
             data_buffers,
             parsed_mtef,
+            data_stream,
         })
     }
 
@@ -464,6 +477,18 @@ impl Document {
         &self.fib
     }
 
+    /// Extract all pictures stored in the Data stream.
+    ///
+    /// Returns the BLIP records in stream order; documents without a Data
+    /// stream have no pictures.
+    pub fn extract_all_images(&self) -> Result<Vec<crate::images::ExtractedImage<'static>>> {
+        match &self.data_stream {
+            Some(data) => crate::images::ImageExtractor::search_blips_in_data(data)
+                .map_err(|e| DocError::Corrupted(format!("Failed to extract images: {}", e))),
+            None => Ok(Vec::new()),
+        }
+    }
+
     /// Get all paragraphs in the document.
     ///
     /// Returns a vector of `Paragraph` objects representing paragraphs
@@ -578,9 +603,15 @@ impl Document {
             // Create paragraph with runs and properties
             // Following Apache POI's design: text is stored in runs, not duplicated in paragraph
             // Pass empty string since runs contain all the text
+            let list = para_props.list_format_override.and_then(|ilfo| {
+                self.lists
+                    .level_info(ilfo, para_props.list_level.unwrap_or(0))
+            });
+
             let mut para = Paragraph::new(String::new());
-            para.set_runs(run_objects);
+            para.set_runs(resolve_hyperlink_fields(run_objects));
             para.set_properties(para_props);
+            para.set_list_info(list);
             output.push(para);
         }
     }
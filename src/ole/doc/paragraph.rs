@@ -1,6 +1,8 @@
 /// Paragraph and Run structures for legacy Word documents.
 use super::package::Result;
 use super::parts::chp::{CharacterProperties, UnderlineStyle, VerticalPosition};
+use crate::common::ListInfo;
+use crate::common::field::hyperlink_target;
 use std::sync::Arc;
 
 /// A paragraph in a Word document.
@@ -22,6 +24,8 @@ pub struct Paragraph {
     runs: Vec<Run>,
     /// Paragraph formatting properties (PAP)
     properties: super::parts::pap::ParagraphProperties,
+    /// List membership, resolved from the list tables
+    list: Option<ListInfo>,
 }
 
 impl Paragraph {
@@ -47,6 +51,7 @@ impl Paragraph {
             text,
             runs,
             properties: super::parts::pap::ParagraphProperties::default(),
+            list: None,
         }
     }
 
@@ -62,6 +67,7 @@ impl Paragraph {
             text,
             runs,
             properties: super::parts::pap::ParagraphProperties::default(),
+            list: None,
         }
     }
 
@@ -75,6 +81,7 @@ impl Paragraph {
             text,
             runs: Vec::new(),
             properties,
+            list: None,
         }
     }
 
@@ -112,6 +119,33 @@ impl Paragraph {
         &self.properties
     }
 
+    /// Set the list membership of the paragraph (internal use).
+    pub(crate) fn set_list_info(&mut self, list: Option<ListInfo>) {
+        self.list = list;
+    }
+
+    /// Get the heading level (1-9) of this paragraph.
+    ///
+    /// Uses the outline level when set, otherwise the built-in "heading 1"
+    /// to "heading 9" styles (istd 1-9). Returns `None` for body text.
+    pub fn heading_level(&self) -> Option<u8> {
+        match self.properties.outline_level {
+            Some(level) => (level < 9).then_some(level + 1),
+            None => self
+                .properties
+                .style_index
+                .filter(|istd| (1..=9).contains(istd))
+                .map(|istd| istd as u8),
+        }
+    }
+
+    /// Get the list membership of this paragraph.
+    ///
+    /// Returns `None` if the paragraph is not in a list.
+    pub fn list_info(&self) -> Option<ListInfo> {
+        self.list
+    }
+
     /// Extract all MTEF formulas from this paragraph as LaTeX.
     ///
     /// Returns a vector of LaTeX formula strings found in any run within this paragraph.
@@ -173,6 +207,8 @@ pub struct Run {
     /// Parsed MTEF formula AST placeholder (when formula feature is disabled)
     #[cfg(not(feature = "formula"))]
     mtef_formula_ast: Option<Arc<Vec<()>>>,
+    /// Target of the HYPERLINK field this run is the result of
+    hyperlink: Option<String>,
 }
 
 impl Run {
@@ -182,6 +218,7 @@ impl Run {
             text,
             properties,
             mtef_formula_ast: None,
+            hyperlink: None,
         }
     }
 
@@ -196,6 +233,7 @@ impl Run {
             text,
             properties,
             mtef_formula_ast: Some(mtef_ast),
+            hyperlink: None,
         }
     }
 
@@ -210,6 +248,7 @@ impl Run {
             text,
             properties,
             mtef_formula_ast: None,
+            hyperlink: None,
         }
     }
 
@@ -382,11 +421,93 @@ impl Run {
     pub fn is_ole_object(&self) -> bool {
         self.properties.is_ole2
     }
+
+    /// Get the hyperlink target of this run.
+    ///
+    /// Returns the URL (or `#bookmark`) of the HYPERLINK field whose result
+    /// contains this run.
+    pub fn hyperlink(&self) -> Option<&str> {
+        self.hyperlink.as_deref()
+    }
+}
+
+/// Resolve the HYPERLINK fields of a paragraph's runs.
+///
+/// Field codes are stored inline as `0x13 instruction 0x14 result 0x15`.
+/// For HYPERLINK fields the instruction and markers are removed and the
+/// result runs carry the link target; other fields are left untouched.
+pub(crate) fn resolve_hyperlink_fields(runs: Vec<Run>) -> Vec<Run> {
+    if !runs.iter().any(|run| run.text.contains('\u{13}')) {
+        return runs;
+    }
+
+    let chars: Vec<(char, usize)> = runs
+        .iter()
+        .enumerate()
+        .flat_map(|(index, run)| run.text.chars().map(move |c| (c, index)))
+        .collect();
+    let mut dropped = vec![false; chars.len()];
+    let mut links: Vec<Option<usize>> = vec![None; chars.len()];
+    let mut urls = Vec::new();
+    // Open fields, as (begin, separator) positions
+    let mut stack: Vec<(usize, Option<usize>)> = Vec::new();
+
+    for (position, (c, _)) in chars.iter().enumerate() {
+        match c {
+            '\u{13}' => stack.push((position, None)),
+            '\u{14}' => {
+                if let Some((_, separator)) = stack.last_mut()
+                    && separator.is_none()
+                {
+                    *separator = Some(position);
+                }
+            },
+            '\u{15}' => {
+                let Some((begin, separator)) = stack.pop() else {
+                    continue;
+                };
+                let separator = separator.unwrap_or(position);
+                let instruction: String =
+                    chars[begin + 1..separator].iter().map(|(c, _)| c).collect();
+                if let Some(url) = hyperlink_target(&instruction) {
+                    dropped[begin..=separator].fill(true);
+                    dropped[position] = true;
+                    // Inner links were resolved first and take precedence
+                    for link in &mut links[separator + 1..position] {
+                        link.get_or_insert(urls.len());
+                    }
+                    urls.push(url);
+                }
+            },
+            _ => {},
+        }
+    }
+
+    let mut resolved: Vec<Run> = Vec::with_capacity(runs.len());
+    let mut current: Option<(usize, Option<usize>)> = None;
+    for (position, (c, index)) in chars.into_iter().enumerate() {
+        if dropped[position] {
+            continue;
+        }
+        let key = (index, links[position]);
+        match resolved.last_mut() {
+            Some(run) if current == Some(key) => run.text.push(c),
+            _ => {
+                let mut run = runs[index].clone();
+                run.text = c.to_string();
+                run.hyperlink = key.1.map(|link| urls[link].clone());
+                resolved.push(run);
+                current = Some(key);
+            },
+        }
+    }
+    resolved
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::common::ListFormat;
 
     #[test]
     fn test_paragraph_text() {
@@ -415,4 +536,47 @@ mod tests {
         assert!(run.italic().unwrap_or(false));
         assert_eq!(run.font_size(), Some(24));
     }
+
+    #[test]
+    fn test_resolve_hyperlink_fields() {
+        let props = CharacterProperties::default();
+        let runs = vec![
+            Run::new(
+                "See \u{13} HYPERLINK \"https://example.com\" ".to_string(),
+                props.clone(),
+            ),
+            Run::new("\u{14}the site\u{15} or ".to_string(), props.clone()),
+            Run::new("\u{13}PAGE\u{14}3\u{15}".to_string(), props),
+        ];
+        let runs = resolve_hyperlink_fields(runs);
+        let texts: Vec<&str> = runs.iter().map(|run| run.text().unwrap()).collect();
+        assert_eq!(
+            texts,
+            ["See ", "the site", " or ", "\u{13}PAGE\u{14}3\u{15}"]
+        );
+        assert_eq!(runs[0].hyperlink(), None);
+        assert_eq!(runs[1].hyperlink(), Some("https://example.com"));
+        assert_eq!(runs[2].hyperlink(), None);
+    }
+
+    #[test]
+    #[allow(clippy::field_reassign_with_default)]
+    fn test_heading_and_list() {
+        let mut props = super::super::parts::pap::ParagraphProperties::default();
+        props.style_index = Some(2);
+        let mut para = Paragraph::with_properties(String::new(), props);
+        assert_eq!(para.heading_level(), Some(2));
+        assert_eq!(para.list_info(), None);
+
+        let mut props = super::super::parts::pap::ParagraphProperties::default();
+        props.outline_level = Some(9);
+        props.list_level = Some(1);
+        para.set_properties(props);
+        para.set_list_info(Some(ListInfo::new(1, ListFormat::Decimal).with_start(3)));
+        assert_eq!(para.heading_level(), None);
+        assert_eq!(
+            para.list_info(),
+            Some(ListInfo::new(1, ListFormat::Decimal).with_start(3))
+        );
+    }
 }
@@ -0,0 +1,206 @@
+/// List tables parser for Word binary format.
+///
+/// Based on Apache POI's ListTables. A paragraph joins a list through its
+/// `ilfo` (sprmPIlfo), a 1-based index into the PlfLfo. Each LFO names a
+/// list definition (LSTF) in the PlfLst by its `lsid`, and the LVLs that
+/// follow the PlfLst give each level's start value (`iStartAt`) and number
+/// format (`nfc`).
+use super::fib::FileInformationBlock;
+use crate::common::binary::{read_i32_le, read_u16_le, read_u32_le};
+use crate::common::{ListFormat, ListInfo};
+use std::collections::HashMap;
+
+/// FibRgFcLcb97 index of fcPlfLst/lcbPlfLst
+const PLF_LST_INDEX: usize = 73;
+/// FibRgFcLcb97 index of fcPlfLfo/lcbPlfLfo
+const PLF_LFO_INDEX: usize = 74;
+/// Size of an LSTF structure
+const LSTF_SIZE: usize = 28;
+/// Size of the fixed LVLF part of an LVL structure
+const LVLF_SIZE: usize = 28;
+/// Size of an LFO structure
+const LFO_SIZE: usize = 16;
+/// Levels of a list that is not simple
+const LEVEL_COUNT: usize = 9;
+
+/// List definitions and list format overrides of a document.
+#[derive(Debug, Clone, Default)]
+pub struct ListTables {
+    /// Format and start value of each level of each list, by `lsid`
+    lists: HashMap<i32, Vec<(ListFormat, u32)>>,
+    /// `lsid` of each LFO, in PlfLfo order
+    overrides: Vec<i32>,
+}
+
+impl ListTables {
+    /// Parse the PlfLst and PlfLfo from the table stream.
+    ///
+    /// Missing or truncated tables yield no lists.
+    pub fn parse(fib: &FileInformationBlock, table_stream: &[u8]) -> Self {
+        let mut tables = Self::default();
+
+        if let Some((offset, length)) = fib.get_table_pointer(PLF_LST_INDEX)
+            && length >= 2
+        {
+            tables.parse_plf_lst(table_stream, offset as usize);
+        }
+
+        if let Some((offset, length)) = fib.get_table_pointer(PLF_LFO_INDEX)
+            && length >= 4
+        {
+            tables.parse_plf_lfo(table_stream, offset as usize);
+        }
+
+        tables
+    }
+
+    /// Parse the LSTFs and the LVLs that follow them.
+    fn parse_plf_lst(&mut self, data: &[u8], offset: usize) {
+        let Ok(count) = read_u16_le(data, offset) else {
+            return;
+        };
+
+        let mut definitions = Vec::with_capacity(count as usize);
+        for i in 0..count as usize {
+            let lstf = offset + 2 + i * LSTF_SIZE;
+            let (Ok(lsid), Some(flags)) = (read_i32_le(data, lstf), data.get(lstf + 26)) else {
+                return;
+            };
+            let levels = if flags & 0x01 != 0 { 1 } else { LEVEL_COUNT };
+            definitions.push((lsid, levels));
+        }
+
+        // The LVLs of every list follow the PlfLst, in list order
+        let mut position = offset + 2 + count as usize * LSTF_SIZE;
+        for (lsid, levels) in definitions {
+            let mut formats = Vec::with_capacity(levels);
+            for _ in 0..levels {
+                match Self::parse_lvl(data, position) {
+                    Some((level, size)) => {
+                        formats.push(level);
+                        position += size;
+                    },
+                    None => {
+                        self.lists.insert(lsid, formats);
+                        return;
+                    },
+                }
+            }
+            self.lists.insert(lsid, formats);
+        }
+    }
+
+    /// Parse one LVL, returning its format, start value and total size.
+    fn parse_lvl(data: &[u8], offset: usize) -> Option<((ListFormat, u32), usize)> {
+        let lvlf = data.get(offset..offset + LVLF_SIZE)?;
+        let start = read_i32_le(lvlf, 0).ok()?.max(0) as u32;
+        let nfc = lvlf[4];
+        let chpx_size = lvlf[24] as usize;
+        let papx_size = lvlf[25] as usize;
+
+        // grpprlPapx and grpprlChpx, then the number text as an Xst
+        let xst = offset + LVLF_SIZE + papx_size + chpx_size;
+        let cch = read_u16_le(data, xst).ok()? as usize;
+        Some((
+            (ListFormat::from_nfc(nfc), start),
+            LVLF_SIZE + papx_size + chpx_size + 2 + cch * 2,
+        ))
+    }
+
+    /// Parse the LFOs; their LFOData overrides are not read.
+    fn parse_plf_lfo(&mut self, data: &[u8], offset: usize) {
+        let Ok(count) = read_u32_le(data, offset) else {
+            return;
+        };
+
+        for i in 0..count as usize {
+            match read_i32_le(data, offset + 4 + i * LFO_SIZE) {
+                Ok(lsid) => self.overrides.push(lsid),
+                Err(_) => return,
+            }
+        }
+    }
+
+    /// Get the format of a list level, from a paragraph's `ilfo` and `ilvl`.
+    ///
+    /// Returns `None` if `ilfo` does not put the paragraph in a list.
+    pub fn level_format(&self, ilfo: i16, ilvl: u8) -> Option<ListFormat> {
+        self.level_info(ilfo, ilvl).map(|info| info.format)
+    }
+
+    /// Get the format and start value of a list level, from a paragraph's
+    /// `ilfo` and `ilvl`.
+    ///
+    /// Returns `None` if `ilfo` does not put the paragraph in a list.
+    pub fn level_info(&self, ilfo: i16, ilvl: u8) -> Option<ListInfo> {
+        // 0 is no list, negative values and 0x7FF are reserved
+        if ilfo <= 0 || ilfo >= 0x7FF {
+            return None;
+        }
+        let lsid = self.overrides.get(ilfo as usize - 1)?;
+        let levels = self.lists.get(lsid)?;
+        let (format, start) = levels
+            .get(ilvl as usize)
+            .or_else(|| levels.first())
+            .copied()?;
+        Some(ListInfo::new(ilvl, format).with_start(start))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lvl(nfc: u8) -> Vec<u8> {
+        let mut lvl = vec![0u8; LVLF_SIZE];
+        lvl[..4].copy_from_slice(&1i32.to_le_bytes());
+        lvl[4] = nfc;
+        lvl[25] = 2; // grpprlPapx
+        lvl.extend_from_slice(&[0, 0]);
+        lvl.extend_from_slice(&1u16.to_le_bytes());
+        lvl.extend_from_slice(&[0, 0]);
+        lvl
+    }
+
+    #[test]
+    fn test_level_formats() {
+        let mut data = 2u16.to_le_bytes().to_vec();
+        // A simple bullet list, then a nine-level numbered list
+        for (lsid, simple) in [(7i32, true), (9, false)] {
+            let mut lstf = vec![0u8; LSTF_SIZE];
+            lstf[..4].copy_from_slice(&lsid.to_le_bytes());
+            lstf[26] = simple as u8;
+            data.extend(lstf);
+        }
+        data.extend(lvl(23));
+        let mut first = lvl(0);
+        first[..4].copy_from_slice(&5i32.to_le_bytes());
+        data.extend(first);
+        data.extend(lvl(4));
+        for _ in 2..LEVEL_COUNT {
+            data.extend(lvl(2));
+        }
+
+        let lfo_offset = data.len();
+        data.extend_from_slice(&2u32.to_le_bytes());
+        for lsid in [9i32, 7] {
+            let mut lfo = vec![0u8; LFO_SIZE];
+            lfo[..4].copy_from_slice(&lsid.to_le_bytes());
+            data.extend(lfo);
+        }
+
+        let mut tables = ListTables::default();
+        tables.parse_plf_lst(&data, 0);
+        tables.parse_plf_lfo(&data, lfo_offset);
+
+        assert_eq!(tables.level_format(1, 0), Some(ListFormat::Decimal));
+        assert_eq!(tables.level_format(1, 1), Some(ListFormat::LowerLetter));
+        assert_eq!(tables.level_format(1, 8), Some(ListFormat::LowerRoman));
+        assert_eq!(tables.level_format(2, 0), Some(ListFormat::Bullet));
+        assert_eq!(tables.level_format(2, 3), Some(ListFormat::Bullet));
+        assert_eq!(tables.level_format(0, 0), None);
+        assert_eq!(tables.level_format(3, 0), None);
+        assert_eq!(tables.level_info(1, 0).unwrap().start, 5);
+        assert_eq!(tables.level_info(1, 1).unwrap().start, 1);
+    }
+}
@@ -12,6 +12,7 @@ pub mod chp_bin_table;
 pub mod fib;
 pub mod fields;
 pub mod fkp;
+pub mod lists;
 pub mod pap;
 pub mod paragraph_extractor;
 pub mod piece_table;
@@ -4,7 +4,6 @@ use super::package::{PptError, Result};
 use super::parsers::PptRecordParser;
 use super::persist::PersistMapping;
 use super::slide::{Slide, SlideFactory};
-#[cfg(feature = "imgconv")]
 use crate::images::{BlipStore, ExtractedImage, ImageExtractor};
 use std::io::{Read, Seek};
 
@@ -40,10 +39,8 @@ pub struct Presentation {
     /// Persist ID to offset mapping
     pub(crate) persist_mapping: PersistMapping,
     /// Pictures stream data (for image extraction)
-    #[cfg(feature = "imgconv")]
     pictures_data: Option<Vec<u8>>,
     /// BLIP store (image metadata index)
-    #[cfg(feature = "imgconv")]
     blip_store: Option<BlipStore<'static>>,
 }
 
@@ -63,7 +60,6 @@ impl Presentation {
         let persist_mapping = PersistMapping::build_from_records_ref(&all_records_ref);
 
         // Try to read Pictures stream for image extraction
-        #[cfg(feature = "imgconv")]
         let (pictures_data, blip_store) = if let Ok(pictures) = ole.open_stream(&["Pictures"]) {
             // Extract BLIP store from pictures data
             let store = ImageExtractor::extract_blip_store(&pictures)
@@ -78,9 +74,7 @@ impl Presentation {
             powerpoint_document,
             parser,
             persist_mapping,
-            #[cfg(feature = "imgconv")]
             pictures_data,
-            #[cfg(feature = "imgconv")]
             blip_store,
         })
     }
@@ -225,7 +219,6 @@ impl Presentation {
     /// }
     /// # Ok::<(), Box<dyn std::error::Error>>(())
     /// ```
-    #[cfg(feature = "imgconv")]
     pub fn extract_all_images(&self) -> Result<Vec<ExtractedImage<'static>>> {
         if let Some(ref pictures_data) = self.pictures_data {
             ImageExtractor::extract_from_pictures_stream(pictures_data)
@@ -245,7 +238,6 @@ impl Presentation {
     ///
     /// # Returns
     /// The extracted image, or None if not found
-    #[cfg(feature = "imgconv")]
     pub(crate) fn extract_image_by_blip_id(
         &self,
         blip_id: u32,
@@ -262,14 +254,46 @@ impl Presentation {
     /// Get the BLIP store (image metadata index)
     ///
     /// This provides access to image metadata without extracting the full image data.
-    #[cfg(feature = "imgconv")]
     pub fn blip_store(&self) -> Option<&BlipStore<'static>> {
         self.blip_store.as_ref()
     }
 
     /// Check if the presentation has a Pictures stream
-    #[cfg(feature = "imgconv")]
     pub fn has_pictures(&self) -> bool {
         self.pictures_data.is_some()
     }
+
+    /// Extract all pictures as format-independent images.
+    ///
+    /// Each picture is anchored to the first slide (0-based) whose picture
+    /// shape references its BLIP; pictures not placed on any slide (e.g.
+    /// used only by masters) are unanchored.
+    pub fn images(&self) -> Result<Vec<crate::common::EmbeddedImage>> {
+        use crate::common::ImageAnchor;
+        use std::collections::HashMap;
+
+        let mut placements: HashMap<u32, usize> = HashMap::new();
+        for (index, slide) in self.slides()?.iter().enumerate() {
+            for shape in slide.shapes()? {
+                if let Some(blip_id) = shape.as_picture().and_then(|picture| picture.blip_id()) {
+                    placements.entry(blip_id).or_insert(index);
+                }
+            }
+        }
+
+        self.extract_all_images()?
+            .iter()
+            .enumerate()
+            .map(|(index, image)| {
+                // BLIP IDs are 1-based indices into the Pictures stream
+                let anchor = placements
+                    .get(&(index as u32 + 1))
+                    .map(|&slide| ImageAnchor::Slide(slide))
+                    .unwrap_or(ImageAnchor::Unanchored);
+                image
+                    .to_embedded_image(anchor)
+                    .map_err(|e| PptError::Corrupted(format!("Failed to extract image: {}", e)))
+            })
+            .collect()
+    }
 }
@@ -59,7 +59,6 @@ pub use shape_enum::ShapeEnum;
 // Re-export concrete shape types
 pub use autoshape::AutoShape;
 pub use picture::PictureShape;
-#[cfg(feature = "imgconv")]
 pub use picture::extract_blip_id_from_escher;
 pub use placeholder::{Placeholder, PlaceholderSize, PlaceholderType};
 pub use textbox::TextBox;
@@ -4,11 +4,8 @@
 // embedded images directly from shapes, similar to python-pptx.
 
 use super::shape::{Shape, ShapeProperties, ShapeType};
-#[cfg(feature = "imgconv")]
 use crate::common::error::Result;
-#[cfg(feature = "imgconv")]
 use crate::images::{Blip, ExtractedImage};
-#[cfg(feature = "imgconv")]
 use crate::ole::ppt::escher::EscherContainer;
 use crate::ole::ppt::package::PptError;
 
@@ -46,7 +43,6 @@ pub struct PictureShape {
     /// Picture name/filename
     pub name: Option<String>,
     /// Escher container data (for extracting BLIP)
-    #[cfg(feature = "imgconv")]
     escher_data: Option<Vec<u8>>,
 }
 
@@ -63,7 +59,6 @@ impl PictureShape {
             properties,
             blip_id: None,
             name: None,
-            #[cfg(feature = "imgconv")]
             escher_data: None,
         }
     }
@@ -74,7 +69,6 @@ impl PictureShape {
             properties,
             blip_id: None,
             name: None,
-            #[cfg(feature = "imgconv")]
             escher_data: None,
         }
     }
@@ -98,7 +92,6 @@ impl PictureShape {
     }
 
     /// Set Escher container data for BLIP extraction
-    #[cfg(feature = "imgconv")]
     pub fn set_escher_data(&mut self, data: Vec<u8>) {
         self.escher_data = Some(data);
     }
@@ -124,7 +117,6 @@ impl PictureShape {
     ///
     /// # Returns
     /// The extracted image, or None if no image data is found
-    #[cfg(feature = "imgconv")]
     pub fn extract_image(
         &self,
         presentation: &crate::ole::ppt::Presentation,
@@ -154,7 +146,6 @@ impl PictureShape {
     ///
     /// This is a lower-level method that extracts the BLIP without
     /// requiring access to the full presentation.
-    #[cfg(feature = "imgconv")]
     pub fn extract_blip_from_escher(&self) -> Result<Option<Blip<'static>>> {
         if let Some(ref escher_data) = self.escher_data {
             let images = crate::images::ImageExtractor::extract_blips(escher_data)?;
@@ -211,7 +202,6 @@ impl Shape for PictureShape {
 /// the reference to the BLIP in the BStoreContainer.
 ///
 /// Uses zero-copy parsing with `Cow` to avoid unnecessary allocations.
-#[cfg(feature = "imgconv")]
 pub fn extract_blip_id_from_escher(container: &EscherContainer) -> Option<u32> {
     use crate::ole::ppt::escher::EscherRecordType;
 
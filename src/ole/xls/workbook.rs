@@ -1,5 +1,7 @@
 //! Workbook implementation for XLS files
 
+use crate::common::{EmbeddedImage, ImageAnchor};
+use crate::images::ImageExtractor;
 use crate::ole::file::OleFile;
 use crate::ole::xls::cell::XlsCell;
 use crate::ole::xls::error::{XlsError, XlsResult};
@@ -22,6 +24,9 @@ pub struct XlsWorkbook<R: Read + Seek> {
     shared_strings: Option<Arc<Vec<String>>>,
     biff_version: BiffVersion,
     is_1904_date_system: bool,
+    /// Escher drawing group from the MSODRAWINGGROUP records, which holds
+    /// the pictures of all sheets
+    drawing_group: Vec<u8>,
 }
 
 impl<R: Read + Seek> XlsWorkbook<R> {
@@ -36,6 +41,7 @@ impl<R: Read + Seek> XlsWorkbook<R> {
             shared_strings: None,
             biff_version: BiffVersion::Biff8,
             is_1904_date_system: false,
+            drawing_group: Vec::new(),
         };
 
         workbook.parse_workbook()?;
@@ -58,12 +64,28 @@ impl<R: Read + Seek> XlsWorkbook<R> {
             shared_strings: None,
             biff_version: BiffVersion::Biff8,
             is_1904_date_system: false,
+            drawing_group: Vec::new(),
         };
 
         workbook.parse_workbook()?;
         Ok(workbook)
     }
 
+    /// Get the pictures stored in the workbook's drawing group.
+    ///
+    /// Pictures are returned in BLIP store order and are not anchored to
+    /// their sheets.
+    pub fn images(&self) -> XlsResult<Vec<EmbeddedImage>> {
+        ImageExtractor::search_blips_in_data(&self.drawing_group)
+            .and_then(|images| {
+                images
+                    .iter()
+                    .map(|image| image.to_embedded_image(ImageAnchor::Unanchored))
+                    .collect()
+            })
+            .map_err(|e| XlsError::InvalidData(format!("Failed to extract images: {}", e)))
+    }
+
     /// Parse the workbook stream
     fn parse_workbook(&mut self) -> XlsResult<()> {
         // Find and read the Workbook stream
@@ -166,6 +188,15 @@ impl<R: Read + Seek> XlsWorkbook<R> {
                     // Skip the CONTINUE records we consumed
                     i = sst_idx - 1;
                 },
+                0x00EB => {
+                    // MSODRAWINGGROUP, continued by CONTINUE records or
+                    // further MSODRAWINGGROUP records
+                    self.drawing_group.extend_from_slice(&record.data);
+                    while i + 1 < records.len() && records[i + 1].header.record_type == 0x003C {
+                        i += 1;
+                        self.drawing_group.extend_from_slice(&records[i].data);
+                    }
+                },
                 0x000A => {
                     // EOF - End of workbook globals
                     break;
@@ -0,0 +1,522 @@
+//! DrawingML charts (`c:chartSpace` parts).
+//!
+//! Worksheets, slides and documents embed charts the same way: a graphic
+//! frame holding a `c:chart r:id` reference to a chart part. This module
+//! reads those parts into [`ChartData`], keeping each series' name, its
+//! category and value references and the cached values Office stores next
+//! to them (`c:strCache` / `c:numCache`), so a chart can be rebuilt without
+//! the cells it was drawn from.
+
+use crate::common::ImageAnchor;
+use crate::ooxml::common::xml::{attribute, push_text};
+use crate::ooxml::error::{OoxmlError, Result};
+use crate::ooxml::opc::constants::relationship_type;
+use crate::ooxml::opc::{OpcPackage, Part};
+use quick_xml::Reader;
+use quick_xml::events::{BytesStart, Event};
+
+use super::pictures::{PictureRef, scan_chart_refs};
+
+/// Kind of plot a series is drawn with.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ChartKind {
+    /// Horizontal bars (`barChart` with `barDir="bar"`)
+    Bar,
+    /// Vertical bars (`barChart` with `barDir="col"`)
+    Column,
+    Line,
+    Pie,
+    Doughnut,
+    Area,
+    Scatter,
+    Radar,
+    Bubble,
+    Stock,
+    Surface,
+    /// Any other plot element, by local name
+    Other(String),
+}
+
+impl ChartKind {
+    /// Map a plot element's local name to a chart kind.
+    fn from_plot_element(name: &str) -> Option<Self> {
+        Some(match name {
+            "barChart" | "bar3DChart" => ChartKind::Column,
+            "lineChart" | "line3DChart" => ChartKind::Line,
+            "pieChart" | "pie3DChart" | "ofPieChart" => ChartKind::Pie,
+            "doughnutChart" => ChartKind::Doughnut,
+            "areaChart" | "area3DChart" => ChartKind::Area,
+            "scatterChart" => ChartKind::Scatter,
+            "radarChart" => ChartKind::Radar,
+            "bubbleChart" => ChartKind::Bubble,
+            "stockChart" => ChartKind::Stock,
+            "surfaceChart" | "surface3DChart" => ChartKind::Surface,
+            other if other.ends_with("Chart") => ChartKind::Other(other.to_string()),
+            _ => return None,
+        })
+    }
+}
+
+/// Values cached in a chart part for one reference.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub enum ChartCache {
+    /// No cache or literal values were stored
+    #[default]
+    Empty,
+    /// Numbers by point index (`c:numCache` / `c:numLit`)
+    Numbers(Vec<Option<f64>>),
+    /// Text by point index (`c:strCache` / `c:strLit`, first level of `c:multiLvlStrCache`)
+    Strings(Vec<Option<String>>),
+}
+
+/// Categories or values of a series.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct ChartValues {
+    /// Cell reference formula (e.g. `Sheet1!$B$2:$B$5`), absent for literals
+    pub reference: Option<String>,
+    /// Number format of cached numbers
+    pub format_code: Option<String>,
+    /// Cached values
+    pub cache: ChartCache,
+}
+
+impl ChartValues {
+    /// Cached values as numbers; text that does not parse is `None`.
+    pub fn numbers(&self) -> Vec<Option<f64>> {
+        match &self.cache {
+            ChartCache::Empty => Vec::new(),
+            ChartCache::Numbers(numbers) => numbers.clone(),
+            ChartCache::Strings(strings) => strings
+                .iter()
+                .map(|s| s.as_deref().and_then(|s| s.trim().parse().ok()))
+                .collect(),
+        }
+    }
+
+    /// Cached values as text.
+    pub fn strings(&self) -> Vec<Option<String>> {
+        match &self.cache {
+            ChartCache::Empty => Vec::new(),
+            ChartCache::Numbers(numbers) => {
+                numbers.iter().map(|n| n.map(|n| n.to_string())).collect()
+            },
+            ChartCache::Strings(strings) => strings.clone(),
+        }
+    }
+}
+
+/// One data series of a chart.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ChartSeries {
+    /// Plot the series belongs to
+    pub kind: ChartKind,
+    /// Series name, from the name cache or a literal
+    pub name: Option<String>,
+    /// Cell reference of the series name
+    pub name_reference: Option<String>,
+    /// Categories (`c:cat`), or X values for scatter and bubble charts (`c:xVal`)
+    pub categories: Option<ChartValues>,
+    /// Values (`c:val`), or Y values for scatter and bubble charts (`c:yVal`)
+    pub values: Option<ChartValues>,
+}
+
+/// A chart read from a `c:chartSpace` part.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ChartData {
+    /// Partname of the chart part (e.g. `/xl/charts/chart1.xml`)
+    pub partname: String,
+    /// Chart title, paragraphs separated by newlines
+    pub title: Option<String>,
+    /// Kind of the first plot; combination charts mix kinds across series
+    pub kind: ChartKind,
+    /// Series in plot order
+    pub series: Vec<ChartSeries>,
+    /// Where the chart frame is placed
+    pub anchor: ImageAnchor,
+}
+
+impl ChartData {
+    /// Series names, skipping unnamed series.
+    pub fn series_names(&self) -> Vec<String> {
+        self.series.iter().filter_map(|s| s.name.clone()).collect()
+    }
+
+    /// Category labels of the first series that has any.
+    pub fn category_names(&self) -> Vec<String> {
+        self.series
+            .iter()
+            .find_map(|s| s.categories.as_ref())
+            .map(|c| c.strings().into_iter().flatten().collect())
+            .unwrap_or_default()
+    }
+
+    /// Get all text content from the chart.
+    pub fn all_text(&self) -> Vec<String> {
+        let mut text = Vec::new();
+        text.extend(self.title.clone());
+        text.extend(self.series_names());
+        text.extend(self.category_names());
+        text
+    }
+
+    /// Check if the chart has any meaningful content.
+    pub fn has_content(&self) -> bool {
+        self.title.is_some() || !self.series.is_empty()
+    }
+}
+
+/// Which part of the chart text and cache events belong to.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Target {
+    None,
+    Title,
+    Name,
+    Categories,
+    Values,
+}
+
+/// Parse a `c:chartSpace` part.
+///
+/// `partname` is recorded on the result; the anchor is
+/// [`ImageAnchor::Unanchored`].
+pub fn parse_chart(xml: &[u8], partname: &str) -> Result<ChartData> {
+    let mut reader = Reader::from_reader(xml);
+    let mut buf = Vec::with_capacity(512);
+    let mut path: Vec<String> = Vec::new();
+
+    let mut title: Option<String> = None;
+    let mut kinds: Vec<ChartKind> = Vec::new();
+    let mut series: Vec<ChartSeries> = Vec::new();
+    let mut current: Option<ChartSeries> = None;
+    let mut target = Target::None;
+    let mut values = ChartValues::default();
+    let mut point: Option<usize> = None;
+    let mut level = 0usize;
+    let mut text = String::new();
+
+    loop {
+        let event = reader
+            .read_event_into(&mut buf)
+            .map_err(|e| OoxmlError::Xml(e.to_string()))?;
+        match &event {
+            Event::Start(e) | Event::Empty(e) => {
+                let name = local_name(e);
+                let parent = path.last().map(String::as_str);
+
+                match name.as_str() {
+                    "title" if parent == Some("chart") => {
+                        target = Target::Title;
+                        title.get_or_insert_with(String::new);
+                    },
+                    "p" if target == Target::Title => {
+                        if let Some(title) = title.as_mut().filter(|t| !t.is_empty()) {
+                            title.push('\n');
+                        }
+                    },
+                    name if parent == Some("plotArea") => {
+                        if let Some(kind) = ChartKind::from_plot_element(name) {
+                            kinds.push(kind);
+                        }
+                    },
+                    "barDir" => {
+                        if attribute(e, b"val").as_deref() == Some("bar")
+                            && let Some(kind) = kinds.last_mut()
+                        {
+                            *kind = ChartKind::Bar;
+                        }
+                    },
+                    "ser" if !kinds.is_empty() => {
+                        current = Some(ChartSeries {
+                            kind: kinds.last().cloned().unwrap_or(ChartKind::Column),
+                            name: None,
+                            name_reference: None,
+                            categories: None,
+                            values: None,
+                        });
+                    },
+                    "tx" if parent == Some("ser") => target = Target::Name,
+                    "cat" | "xVal" if parent == Some("ser") => target = Target::Categories,
+                    "val" | "yVal" if parent == Some("ser") => target = Target::Values,
+                    "numRef" | "numLit" if target != Target::None => {
+                        values.cache = ChartCache::Numbers(Vec::new());
+                        level = 0;
+                    },
+                    "strRef" | "strLit" | "multiLvlStrRef" if target != Target::None => {
+                        values.cache = ChartCache::Strings(Vec::new());
+                        level = 0;
+                    },
+                    "lvl" => level += 1,
+                    "ptCount" if level <= 1 => {
+                        let count = attribute(e, b"val")
+                            .and_then(|v| v.parse::<usize>().ok())
+                            .unwrap_or(0);
+                        match &mut values.cache {
+                            ChartCache::Numbers(points) => points.resize(count, None),
+                            ChartCache::Strings(points) => points.resize(count, None),
+                            ChartCache::Empty => {},
+                        }
+                    },
+                    "pt" if level <= 1 => {
+                        point = attribute(e, b"idx").and_then(|v| v.parse::<usize>().ok());
+                    },
+                    _ => {},
+                }
+
+                if matches!(event, Event::Start(_)) {
+                    path.push(name);
+                    text.clear();
+                }
+            },
+            Event::Text(_) | Event::CData(_) | Event::GeneralRef(_) => {
+                push_text(&event, &mut text)?;
+            },
+            Event::End(_) => {
+                let name = path.pop().unwrap_or_default();
+                let parent = path.last().map(String::as_str);
+
+                match name.as_str() {
+                    "t" if target == Target::Title => {
+                        if let Some(title) = title.as_mut() {
+                            title.push_str(&text);
+                        }
+                    },
+                    "v" if target == Target::Title && parent == Some("pt") => {
+                        if let Some(title) = title.as_mut() {
+                            title.push_str(&text);
+                        }
+                    },
+                    "title" if target == Target::Title => {
+                        values = ChartValues::default();
+                        target = Target::None;
+                    },
+                    "f" if target != Target::None && target != Target::Title => {
+                        values.reference = Some(text.trim().to_string());
+                    },
+                    "formatCode" if target != Target::None => {
+                        values.format_code = Some(text.clone());
+                    },
+                    "v" if parent == Some("pt") && level <= 1 => {
+                        if let Some(index) = point {
+                            store_point(&mut values.cache, index, &text);
+                        }
+                    },
+                    // A literal series name
+                    "v" if parent == Some("tx") && target == Target::Name => {
+                        if let Some(series) = current.as_mut() {
+                            series.name = Some(text.clone());
+                        }
+                    },
+                    "pt" => point = None,
+                    "tx" | "cat" | "xVal" | "val" | "yVal" if parent == Some("ser") => {
+                        let finished = std::mem::take(&mut values);
+                        if let Some(series) = current.as_mut() {
+                            match target {
+                                Target::Name => {
+                                    if let ChartCache::Strings(names) = &finished.cache {
+                                        series.name = names.iter().flatten().next().cloned();
+                                    }
+                                    if finished.reference.is_some() {
+                                        series.name_reference = finished.reference;
+                                    }
+                                },
+                                Target::Categories => series.categories = Some(finished),
+                                Target::Values => series.values = Some(finished),
+                                Target::None | Target::Title => {},
+                            }
+                        }
+                        target = Target::None;
+                    },
+                    "ser" => series.extend(current.take()),
+                    _ => {},
+                }
+                text.clear();
+            },
+            Event::Eof => break,
+            _ => {},
+        }
+        buf.clear();
+    }
+
+    Ok(ChartData {
+        partname: partname.to_string(),
+        title: title.filter(|t| !t.trim().is_empty()),
+        kind: kinds
+            .into_iter()
+            .next()
+            .unwrap_or_else(|| ChartKind::Other(String::new())),
+        series,
+        anchor: ImageAnchor::Unanchored,
+    })
+}
+
+/// Record a cached point, growing the cache if `ptCount` was too small.
+fn store_point(cache: &mut ChartCache, index: usize, text: &str) {
+    match cache {
+        ChartCache::Numbers(points) => {
+            if points.len() <= index {
+                points.resize(index + 1, None);
+            }
+            points[index] = text.trim().parse().ok();
+        },
+        ChartCache::Strings(points) => {
+            if points.len() <= index {
+                points.resize(index + 1, None);
+            }
+            points[index] = Some(text.to_string());
+        },
+        ChartCache::Empty => {},
+    }
+}
+
+/// Get the local name of an element.
+fn local_name(e: &BytesStart) -> String {
+    String::from_utf8_lossy(e.local_name().as_ref()).into_owned()
+}
+
+/// Read the charts whose frames appear in `part`, in document order.
+///
+/// References to external targets, missing parts or parts of another type
+/// are skipped. `anchor` maps each frame to its position in the document.
+pub fn collect_charts(
+    part: &dyn Part,
+    package: &OpcPackage,
+    anchor: impl Fn(&PictureRef) -> ImageAnchor,
+) -> Result<Vec<ChartData>> {
+    let refs = scan_chart_refs(part.blob())?;
+    let mut charts = Vec::with_capacity(refs.len());
+
+    for frame in &refs {
+        let Some(rel) = part.rels().get(&frame.r_id) else {
+            continue;
+        };
+        if rel.is_external() || rel.reltype() != relationship_type::CHART {
+            continue;
+        }
+        let Ok(partname) = rel.target_partname() else {
+            continue;
+        };
+        let Ok(chart_part) = package.get_part(&partname) else {
+            continue;
+        };
+
+        let mut chart = parse_chart(chart_part.blob(), partname.as_str())?;
+        chart.anchor = anchor(frame);
+        charts.push(chart);
+    }
+
+    Ok(charts)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const COMBO_CHART: &str = r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<c:chartSpace xmlns:c="http://schemas.openxmlformats.org/drawingml/2006/chart" xmlns:a="http://schemas.openxmlformats.org/drawingml/2006/main">
+  <c:chart>
+    <c:title><c:tx><c:rich><a:bodyPr/><a:p><a:r><a:t>Sales &amp; </a:t></a:r><a:r><a:t>Margin</a:t></a:r></a:p><a:p><a:r><a:t>2024</a:t></a:r></a:p></c:rich></c:tx></c:title>
+    <c:plotArea>
+      <c:layout/>
+      <c:barChart>
+        <c:barDir val="bar"/>
+        <c:ser>
+          <c:idx val="0"/><c:order val="0"/>
+          <c:tx><c:strRef><c:f>Sheet1!$B$1</c:f><c:strCache><c:ptCount val="1"/><c:pt idx="0"><c:v>Revenue</c:v></c:pt></c:strCache></c:strRef></c:tx>
+          <c:cat><c:strRef><c:f>Sheet1!$A$2:$A$4</c:f><c:strCache><c:ptCount val="3"/><c:pt idx="0"><c:v>Jan</c:v></c:pt><c:pt idx="2"><c:v>Mar</c:v></c:pt></c:strCache></c:strRef></c:cat>
+          <c:val><c:numRef><c:f>Sheet1!$B$2:$B$4</c:f><c:numCache><c:formatCode>General</c:formatCode><c:ptCount val="3"/><c:pt idx="0"><c:v>10</c:v></c:pt><c:pt idx="1"><c:v>12.5</c:v></c:pt><c:pt idx="2"><c:v>15</c:v></c:pt></c:numCache></c:numRef></c:val>
+        </c:ser>
+        <c:axId val="1"/><c:axId val="2"/>
+      </c:barChart>
+      <c:lineChart>
+        <c:ser>
+          <c:idx val="1"/><c:order val="1"/>
+          <c:tx><c:v>Margin</c:v></c:tx>
+          <c:val><c:numLit><c:ptCount val="2"/><c:pt idx="0"><c:v>0.1</c:v></c:pt><c:pt idx="1"><c:v>0.2</c:v></c:pt></c:numLit></c:val>
+        </c:ser>
+      </c:lineChart>
+      <c:catAx><c:axId val="1"/><c:title><c:tx><c:rich><a:p><a:r><a:t>Month</a:t></a:r></a:p></c:rich></c:tx></c:title></c:catAx>
+    </c:plotArea>
+  </c:chart>
+</c:chartSpace>"#;
+
+    #[test]
+    fn test_parse_combo_chart() {
+        let chart = parse_chart(COMBO_CHART.as_bytes(), "/xl/charts/chart1.xml").unwrap();
+
+        assert_eq!(chart.partname, "/xl/charts/chart1.xml");
+        assert_eq!(chart.title.as_deref(), Some("Sales & Margin\n2024"));
+        assert_eq!(chart.kind, ChartKind::Bar);
+        assert_eq!(chart.series.len(), 2);
+
+        let revenue = &chart.series[0];
+        assert_eq!(revenue.name.as_deref(), Some("Revenue"));
+        assert_eq!(revenue.name_reference.as_deref(), Some("Sheet1!$B$1"));
+        let categories = revenue.categories.as_ref().unwrap();
+        assert_eq!(categories.reference.as_deref(), Some("Sheet1!$A$2:$A$4"));
+        assert_eq!(
+            categories.cache,
+            ChartCache::Strings(vec![Some("Jan".into()), None, Some("Mar".into())])
+        );
+        let values = revenue.values.as_ref().unwrap();
+        assert_eq!(values.format_code.as_deref(), Some("General"));
+        assert_eq!(
+            values.cache,
+            ChartCache::Numbers(vec![Some(10.0), Some(12.5), Some(15.0)])
+        );
+
+        let margin = &chart.series[1];
+        assert_eq!(margin.kind, ChartKind::Line);
+        assert_eq!(margin.name.as_deref(), Some("Margin"));
+        assert_eq!(margin.categories, None);
+        assert_eq!(margin.values.as_ref().unwrap().reference, None);
+        assert_eq!(
+            margin.values.as_ref().unwrap().numbers(),
+            vec![Some(0.1), Some(0.2)]
+        );
+
+        assert_eq!(chart.series_names(), vec!["Revenue", "Margin"]);
+        assert_eq!(chart.category_names(), vec!["Jan", "Mar"]);
+    }
+
+    #[test]
+    fn test_parse_scatter_and_multi_level_categories() {
+        let xml = r#"<c:chartSpace xmlns:c="c"><c:chart><c:plotArea>
+            <c:scatterChart><c:ser>
+                <c:xVal><c:multiLvlStrRef><c:f>Data!$A$2:$B$3</c:f><c:multiLvlStrCache><c:ptCount val="2"/>
+                    <c:lvl><c:pt idx="0"><c:v>North</c:v></c:pt><c:pt idx="1"><c:v>South</c:v></c:pt></c:lvl>
+                    <c:lvl><c:pt idx="0"><c:v>2023</c:v></c:pt></c:lvl>
+                </c:multiLvlStrCache></c:multiLvlStrRef></c:xVal>
+                <c:yVal><c:numRef><c:f>Data!$C$2:$C$3</c:f></c:numRef></c:yVal>
+            </c:ser></c:scatterChart>
+        </c:plotArea></c:chart></c:chartSpace>"#;
+
+        let chart = parse_chart(xml.as_bytes(), "/word/charts/chart1.xml").unwrap();
+        assert_eq!(chart.kind, ChartKind::Scatter);
+        assert_eq!(chart.title, None);
+        let series = &chart.series[0];
+        assert_eq!(
+            series.categories.as_ref().unwrap().strings(),
+            vec![Some("North".to_string()), Some("South".to_string())]
+        );
+        let values = series.values.as_ref().unwrap();
+        assert_eq!(values.reference.as_deref(), Some("Data!$C$2:$C$3"));
+        assert_eq!(values.cache, ChartCache::Numbers(Vec::new()));
+    }
+
+    #[test]
+    fn test_scan_chart_refs() {
+        let xml = br#"<w:document xmlns:w="w" xmlns:wp="wp" xmlns:a="a" xmlns:c="c" xmlns:r="r"><w:body>
+            <w:p><w:r><w:t>Intro</w:t></w:r></w:p>
+            <w:p><w:r><w:drawing><wp:inline><wp:docPr id="1" name="Chart 1" descr="Revenue"/>
+                <a:graphic><a:graphicData><c:chart r:id="rId7"/></a:graphicData></a:graphic>
+            </wp:inline></w:drawing></w:r></w:p>
+            <w:p><w:r><w:drawing><wp:inline><a:graphic><a:graphicData><pic:pic xmlns:pic="pic"><pic:blipFill><a:blip r:embed="rId8"/></pic:blipFill></pic:pic></a:graphicData></a:graphic></wp:inline></w:drawing></w:r></w:p>
+        </w:body></w:document>"#;
+
+        let refs = scan_chart_refs(xml).unwrap();
+        assert_eq!(refs.len(), 1);
+        assert_eq!(refs[0].r_id, "rId7");
+        assert_eq!(refs[0].paragraph, Some(1));
+        assert_eq!(refs[0].alt_text.as_deref(), Some("Revenue"));
+    }
+}
@@ -0,0 +1,38 @@
+//! Deterministic GUIDs for parts and elements that need unique IDs.
+
+use sha2::{Digest, Sha256};
+
+/// A GUID in registry format derived from `seed`, so saving the same
+/// content twice gives the same IDs.
+pub(crate) fn guid(seed: &str) -> String {
+    let hash = Sha256::digest(seed.as_bytes());
+    let mut bytes = [0u8; 16];
+    bytes.copy_from_slice(&hash[..16]);
+    // Version 4 and RFC 4122 variant bits
+    bytes[6] = (bytes[6] & 0x0F) | 0x40;
+    bytes[8] = (bytes[8] & 0x3F) | 0x80;
+    let hex: String = bytes.iter().map(|b| format!("{:02X}", b)).collect();
+    format!(
+        "{{{}-{}-{}-{}-{}}}",
+        &hex[..8],
+        &hex[8..12],
+        &hex[12..16],
+        &hex[16..20],
+        &hex[20..]
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_guid() {
+        let id = guid("seed");
+        assert_eq!(id, guid("seed"));
+        assert_ne!(id, guid("other"));
+        assert_eq!(id.len(), 38);
+        assert!(id.starts_with('{') && id.ends_with('}'));
+        assert_eq!(&id[15..16], "4");
+    }
+}
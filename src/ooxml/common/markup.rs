@@ -0,0 +1,219 @@
+//! A light XML tree that keeps the original markup.
+//!
+//! Template filling, revisions and content controls rewrite a few elements
+//! of a part and must leave everything else as written. The tree stores
+//! each element's start and end tags verbatim and everything that isn't an
+//! element (text, comments, processing instructions) as raw markup, so
+//! writing it back gives the input unchanged.
+use crate::ooxml::error::{OoxmlError, Result};
+use quick_xml::Reader;
+use quick_xml::escape::{escape, unescape};
+use quick_xml::events::Event;
+
+/// A node of the markup tree.
+#[derive(Debug, Clone)]
+pub(crate) enum Node {
+    Element(Element),
+    /// Text, comments and other markup, kept verbatim
+    Raw(String),
+}
+
+/// An element with its original markup.
+#[derive(Debug, Clone)]
+pub(crate) struct Element {
+    /// Qualified name, e.g. `w:t`
+    pub(crate) name: String,
+    /// Start tag, or the whole tag of an empty element
+    pub(crate) start: String,
+    /// End tag; empty for empty elements
+    pub(crate) end: String,
+    pub(crate) children: Vec<Node>,
+}
+
+impl Element {
+    pub(crate) fn local_name(&self) -> &str {
+        self.name.rsplit(':').next().unwrap_or(&self.name)
+    }
+
+    /// Check if the element was written as an empty tag.
+    pub(crate) fn is_empty(&self) -> bool {
+        self.end.is_empty()
+    }
+
+    pub(crate) fn elements(&self) -> impl Iterator<Item = &Element> {
+        self.children.iter().filter_map(|node| match node {
+            Node::Element(element) => Some(element),
+            Node::Raw(_) => None,
+        })
+    }
+
+    /// Get the first child element with a local name.
+    pub(crate) fn child(&self, local_name: &str) -> Option<&Element> {
+        self.elements()
+            .find(|element| element.local_name() == local_name)
+    }
+
+    /// Get the first child element with a local name, mutably.
+    pub(crate) fn child_mut(&mut self, local_name: &str) -> Option<&mut Element> {
+        self.children.iter_mut().find_map(|node| match node {
+            Node::Element(child) if child.local_name() == local_name => Some(child),
+            _ => None,
+        })
+    }
+
+    /// Check if the element or any descendant has one of the local names.
+    pub(crate) fn contains_any(&self, local_names: &[&str]) -> bool {
+        self.elements().any(|child| {
+            local_names.contains(&child.local_name()) || child.contains_any(local_names)
+        })
+    }
+
+    /// Get an attribute of the start tag by local name.
+    pub(crate) fn attribute(&self, local_name: &str) -> Option<String> {
+        let mut reader = Reader::from_str(&self.start);
+        match reader.read_event() {
+            Ok(Event::Start(e)) | Ok(Event::Empty(e)) => e
+                .attributes()
+                .flatten()
+                .find(|attr| attr.key.local_name().as_ref() == local_name.as_bytes())
+                .and_then(|attr| attr.unescape_value().ok())
+                .map(|value| value.into_owned()),
+            _ => None,
+        }
+    }
+
+    /// Rename the element, keeping its attributes.
+    pub(crate) fn rename(&mut self, from: &str, to: &str) {
+        self.name = self.name.replacen(from, to, 1);
+        self.start = self.start.replacen(from, to, 1);
+        self.end = self.end.replacen(from, to, 1);
+    }
+
+    /// Get the content of a text element.
+    pub(crate) fn text(&self) -> String {
+        let mut text = String::new();
+        for node in &self.children {
+            if let Node::Raw(raw) = node {
+                match unescape(raw) {
+                    Ok(unescaped) => text.push_str(&unescaped),
+                    Err(_) => text.push_str(raw),
+                }
+            }
+        }
+        text
+    }
+
+    /// Replace the content of a text element, turning newlines into
+    /// `line_break` markup when given.
+    pub(crate) fn set_text(&mut self, text: &str, preserve_space: bool, line_break: Option<&str>) {
+        if self.end.is_empty() {
+            self.start = format!("<{}>", self.name);
+            self.end = format!("</{}>", self.name);
+        }
+        if preserve_space && !self.start.contains("xml:space") {
+            let close = self.start.len() - 1;
+            self.start.insert_str(close, " xml:space=\"preserve\"");
+        }
+
+        let text = text.replace("\r\n", "\n");
+        let content = match line_break {
+            // Close the text element around each break
+            Some(line_break) => text
+                .split('\n')
+                .map(|line| escape(line).into_owned())
+                .collect::<Vec<_>>()
+                .join(&format!("{}{}{}", self.end, line_break, self.start)),
+            None => escape(&text).into_owned(),
+        };
+        self.children = vec![Node::Raw(content)];
+    }
+
+    pub(crate) fn write(&self, out: &mut String) {
+        out.push_str(&self.start);
+        write_nodes(&self.children, out);
+        out.push_str(&self.end);
+    }
+}
+
+pub(crate) fn write_nodes(nodes: &[Node], out: &mut String) {
+    for node in nodes {
+        match node {
+            Node::Element(element) => element.write(out),
+            Node::Raw(raw) => out.push_str(raw),
+        }
+    }
+}
+
+/// Parse XML into a tree keeping the original markup.
+pub(crate) fn parse(xml: &str) -> Result<Vec<Node>> {
+    let mut reader = Reader::from_str(xml);
+    let mut stack: Vec<Element> = Vec::new();
+    let mut top = Vec::new();
+
+    loop {
+        let start = reader.buffer_position() as usize;
+        let event = reader
+            .read_event()
+            .map_err(|e| OoxmlError::Xml(e.to_string()))?;
+        let raw = &xml[start..reader.buffer_position() as usize];
+        let node = match event {
+            Event::Start(e) => {
+                stack.push(Element {
+                    name: String::from_utf8_lossy(e.name().as_ref()).into_owned(),
+                    start: raw.to_string(),
+                    end: String::new(),
+                    children: Vec::new(),
+                });
+                continue;
+            },
+            Event::Empty(e) => Node::Element(Element {
+                name: String::from_utf8_lossy(e.name().as_ref()).into_owned(),
+                start: raw.to_string(),
+                end: String::new(),
+                children: Vec::new(),
+            }),
+            Event::End(_) => {
+                let Some(mut element) = stack.pop() else {
+                    return Err(OoxmlError::Xml("Unexpected end tag".to_string()));
+                };
+                element.end = raw.to_string();
+                Node::Element(element)
+            },
+            Event::Eof => break,
+            _ => Node::Raw(raw.to_string()),
+        };
+        match stack.last_mut() {
+            Some(parent) => parent.children.push(node),
+            None => top.push(node),
+        }
+    }
+
+    if !stack.is_empty() {
+        return Err(OoxmlError::Xml("Unclosed element".to_string()));
+    }
+    Ok(top)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_round_trip() {
+        let xml = "<?xml version=\"1.0\"?><w:p a=\"1\"><!-- note --><w:r><w:t>A &amp; B</w:t>\
+                   <w:br/></w:r></w:p>";
+        let nodes = parse(xml).unwrap();
+        let mut out = String::new();
+        write_nodes(&nodes, &mut out);
+        assert_eq!(out, xml);
+
+        let Some(Node::Element(p)) = nodes.get(1) else {
+            panic!("expected the paragraph");
+        };
+        assert_eq!(p.attribute("a").as_deref(), Some("1"));
+        let run = p.child("r").unwrap();
+        assert_eq!(run.child("t").unwrap().text(), "A & B");
+        assert!(run.child("br").unwrap().is_empty());
+        assert!(p.contains_any(&["br"]));
+    }
+}
@@ -0,0 +1,933 @@
+//! Template filling (mail merge) for OOXML documents.
+//!
+//! Templates hold placeholders in their text:
+//!
+//! - `{{name}}` is replaced by a value; `{{customer.name}}` looks into
+//!   nested data and `{{.}}` is the current list item
+//! - `{{#name}}...{{/name}}` repeats its content for each item of a list,
+//!   or keeps it once when the value is set (non-empty text, `true` or
+//!   nested data) and removes it otherwise
+//! - `{{^name}}...{{/name}}` keeps its content only when the value is
+//!   missing, empty or `false`
+//!
+//! Word and PowerPoint often split a placeholder over several runs, so the
+//! text of each paragraph is normalized first: a placeholder is moved whole
+//! into the run where it starts and takes that run's formatting.
+//!
+//! A section whose tags are alone in their paragraphs repeats the
+//! paragraphs (and tables) between them; one whose tags are in table rows
+//! repeats the rows from the opening to the closing tag; otherwise the
+//! runs between the tags repeat within the paragraph.
+//!
+//! The markup is kept as written except for the elements that change.
+use super::markup::{self, Element, Node};
+use crate::ooxml::error::Result;
+use std::collections::HashMap;
+
+/// A value filled into a template.
+#[derive(Debug, Clone, PartialEq)]
+pub enum TemplateValue {
+    /// Text, for `{{name}}`
+    Text(String),
+    /// A flag, for `{{#name}}` and `{{^name}}` sections
+    Bool(bool),
+    /// Items a `{{#name}}` section repeats over
+    List(Vec<TemplateValue>),
+    /// Nested values, for `{{name.field}}` and sections over a record
+    Data(TemplateData),
+}
+
+impl TemplateValue {
+    /// Whether a `{{#name}}` section over the value is kept.
+    pub fn is_truthy(&self) -> bool {
+        match self {
+            Self::Text(text) => !text.is_empty(),
+            Self::Bool(value) => *value,
+            Self::List(items) => !items.is_empty(),
+            Self::Data(_) => true,
+        }
+    }
+
+    /// Text shown for a `{{name}}` placeholder.
+    fn to_text(&self) -> String {
+        match self {
+            Self::Text(text) => text.clone(),
+            Self::Bool(value) => value.to_string(),
+            Self::List(items) => items
+                .iter()
+                .map(Self::to_text)
+                .collect::<Vec<_>>()
+                .join(", "),
+            Self::Data(_) => String::new(),
+        }
+    }
+}
+
+impl From<&str> for TemplateValue {
+    fn from(value: &str) -> Self {
+        Self::Text(value.to_string())
+    }
+}
+
+impl From<String> for TemplateValue {
+    fn from(value: String) -> Self {
+        Self::Text(value)
+    }
+}
+
+impl From<bool> for TemplateValue {
+    fn from(value: bool) -> Self {
+        Self::Bool(value)
+    }
+}
+
+impl From<i64> for TemplateValue {
+    fn from(value: i64) -> Self {
+        Self::Text(value.to_string())
+    }
+}
+
+impl From<f64> for TemplateValue {
+    fn from(value: f64) -> Self {
+        Self::Text(value.to_string())
+    }
+}
+
+impl From<TemplateData> for TemplateValue {
+    fn from(value: TemplateData) -> Self {
+        Self::Data(value)
+    }
+}
+
+impl<T: Into<TemplateValue>> From<Vec<T>> for TemplateValue {
+    fn from(items: Vec<T>) -> Self {
+        Self::List(items.into_iter().map(Into::into).collect())
+    }
+}
+
+/// Values to fill a template with, by placeholder name.
+///
+/// # Examples
+///
+/// ```rust
+/// use litchi::ooxml::common::TemplateData;
+///
+/// let mut data = TemplateData::new();
+/// data.set("customer", "ACME Corp.").set("vip", true).set(
+///     "items",
+///     vec![
+///         TemplateData::new().with("name", "Widget").with("price", "9.99"),
+///         TemplateData::new().with("name", "Gadget").with("price", "19.99"),
+///     ],
+/// );
+/// assert_eq!(data.len(), 3);
+/// ```
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct TemplateData {
+    values: HashMap<String, TemplateValue>,
+}
+
+impl TemplateData {
+    /// Create empty template data.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set a value.
+    pub fn set(&mut self, name: &str, value: impl Into<TemplateValue>) -> &mut Self {
+        self.values.insert(name.to_string(), value.into());
+        self
+    }
+
+    /// Set a value, for building nested data in place.
+    pub fn with(mut self, name: &str, value: impl Into<TemplateValue>) -> Self {
+        self.set(name, value);
+        self
+    }
+
+    /// Get a value.
+    pub fn get(&self, name: &str) -> Option<&TemplateValue> {
+        self.values.get(name)
+    }
+
+    /// Get the number of values.
+    pub fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    /// Check if no values are set.
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+}
+
+/// Element names of a markup language, for filling its templates.
+#[derive(Debug)]
+pub(crate) struct Dialect {
+    /// Paragraph elements
+    pub(crate) paragraphs: &'static [&'static str],
+    /// Run element
+    pub(crate) run: &'static str,
+    /// Text element
+    pub(crate) text: &'static str,
+    /// Table row, repeated by sections whose tags are in it
+    pub(crate) row: Option<&'static str>,
+    /// Table, removed when none of its rows are left
+    pub(crate) table: Option<&'static str>,
+    /// Element that needs a paragraph, and the empty paragraph to give it
+    pub(crate) paragraph_container: Option<(&'static str, &'static str)>,
+    /// Markup between two text elements for a line break
+    pub(crate) line_break: Option<&'static str>,
+    /// Whether text elements need `xml:space="preserve"`
+    pub(crate) preserve_space: bool,
+    /// Whether sections may span several paragraphs
+    pub(crate) paragraph_blocks: bool,
+}
+
+impl Dialect {
+    fn is_paragraph(&self, local_name: &str) -> bool {
+        self.paragraphs.contains(&local_name)
+    }
+}
+
+/// A `{{...}}` placeholder.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Tag {
+    /// `{{name}}`
+    Value(String),
+    /// `{{#name}}`
+    Open(String),
+    /// `{{^name}}`
+    Inverted(String),
+    /// `{{/name}}`
+    Close(String),
+}
+
+impl Tag {
+    fn parse(token: &str) -> Self {
+        let inner = token[2..token.len() - 2].trim();
+        let name = |prefix: char| inner.trim_start_matches(prefix).trim().to_string();
+        match inner.chars().next() {
+            Some('#') => Self::Open(name('#')),
+            Some('^') => Self::Inverted(name('^')),
+            Some('/') => Self::Close(name('/')),
+            _ => Self::Value(inner.to_string()),
+        }
+    }
+
+    /// Name of the section the tag opens.
+    fn opens(&self) -> Option<&str> {
+        match self {
+            Self::Open(name) | Self::Inverted(name) => Some(name),
+            _ => None,
+        }
+    }
+
+    fn is_section(&self) -> bool {
+        !matches!(self, Self::Value(_))
+    }
+}
+
+/// Byte ranges of the `{{...}}` placeholders in a text.
+fn token_spans(text: &str) -> Vec<(usize, usize)> {
+    let mut spans = Vec::new();
+    let mut pos = 0;
+    while let Some(open) = text[pos..].find("{{") {
+        let start = pos + open;
+        let Some(close) = text[start + 2..].find("}}") else {
+            break;
+        };
+        let end = start + 2 + close + 2;
+        spans.push((start, end));
+        pos = end;
+    }
+    spans
+}
+
+/// Collect the text elements of a paragraph, leaving out nested paragraphs.
+fn text_elements<'e>(element: &'e mut Element, dialect: &Dialect, out: &mut Vec<&'e mut Element>) {
+    for child in element.children.iter_mut() {
+        if let Node::Element(child) = child {
+            if child.local_name() == dialect.text {
+                out.push(child);
+            } else if !dialect.is_paragraph(child.local_name()) {
+                text_elements(child, dialect, out);
+            }
+        }
+    }
+}
+
+/// Move each placeholder split over several text elements of a paragraph
+/// into the element where it starts.
+fn join_split_tokens(paragraph: &mut Element, dialect: &Dialect) {
+    let mut slots = Vec::new();
+    text_elements(paragraph, dialect, &mut slots);
+    if slots.len() < 2 {
+        return;
+    }
+    let texts: Vec<String> = slots.iter().map(|slot| slot.text()).collect();
+    let joined = texts.concat();
+    if !joined.contains("{{") {
+        return;
+    }
+
+    // Text element each byte belongs to; never decreasing, so each element
+    // keeps one contiguous range
+    let mut owner: Vec<usize> = texts
+        .iter()
+        .enumerate()
+        .flat_map(|(i, text)| std::iter::repeat_n(i, text.len()))
+        .collect();
+    for (start, end) in token_spans(&joined) {
+        let first = owner[start];
+        owner[start..end].fill(first);
+    }
+
+    let mut pos = 0;
+    for (i, slot) in slots.into_iter().enumerate() {
+        let len = owner[pos..].iter().take_while(|&&o| o == i).count();
+        let text = &joined[pos..pos + len];
+        if text != texts[i] {
+            slot.set_text(text, dialect.preserve_space, None);
+        }
+        pos += len;
+    }
+}
+
+/// Split a run so each section tag is in a run of its own.
+fn split_run(run: Element, dialect: &Dialect) -> Vec<Node> {
+    let mut properties = Vec::new();
+    let mut pieces: Vec<Vec<Node>> = Vec::new();
+    let mut current = Vec::new();
+    let text_node = |element: &Element, text: &str| {
+        let mut element = element.clone();
+        element.set_text(text, dialect.preserve_space, None);
+        Node::Element(element)
+    };
+
+    for child in &run.children {
+        match child {
+            Node::Element(element) if element.local_name() == "rPr" => {
+                properties.push(child.clone());
+            },
+            Node::Element(element) if element.local_name() == dialect.text => {
+                let text = element.text();
+                let mut last = 0;
+                for (start, end) in token_spans(&text) {
+                    if !Tag::parse(&text[start..end]).is_section() {
+                        continue;
+                    }
+                    if start > last {
+                        current.push(text_node(element, &text[last..start]));
+                    }
+                    if !current.is_empty() {
+                        pieces.push(std::mem::take(&mut current));
+                    }
+                    pieces.push(vec![text_node(element, &text[start..end])]);
+                    last = end;
+                }
+                if last == 0 {
+                    current.push(child.clone());
+                } else if last < text.len() {
+                    current.push(text_node(element, &text[last..]));
+                }
+            },
+            _ => current.push(child.clone()),
+        }
+    }
+    if !current.is_empty() {
+        pieces.push(current);
+    }
+
+    if pieces.len() < 2 {
+        return vec![Node::Element(run)];
+    }
+    pieces
+        .into_iter()
+        .map(|children| {
+            let mut piece = Element {
+                name: run.name.clone(),
+                start: run.start.clone(),
+                end: run.end.clone(),
+                children: properties.clone(),
+            };
+            piece.children.extend(children);
+            Node::Element(piece)
+        })
+        .collect()
+}
+
+/// Join split placeholders and give section tags runs of their own.
+fn normalize(nodes: &mut Vec<Node>, dialect: &Dialect) {
+    let mut split = false;
+    for node in nodes.iter_mut() {
+        if let Node::Element(element) = node {
+            if dialect.is_paragraph(element.local_name()) {
+                join_split_tokens(element, dialect);
+            }
+            normalize(&mut element.children, dialect);
+            split |= element.local_name() == dialect.run && has_section_tag(element, dialect);
+        }
+    }
+
+    if split {
+        *nodes = std::mem::take(nodes)
+            .into_iter()
+            .flat_map(|node| match node {
+                Node::Element(element) if element.local_name() == dialect.run => {
+                    split_run(element, dialect)
+                },
+                node => vec![node],
+            })
+            .collect();
+    }
+}
+
+fn has_section_tag(element: &Element, dialect: &Dialect) -> bool {
+    element.elements().any(|child| {
+        if child.local_name() != dialect.text {
+            return false;
+        }
+        let text = child.text();
+        token_spans(&text)
+            .into_iter()
+            .any(|(start, end)| Tag::parse(&text[start..end]).is_section())
+    })
+}
+
+/// Collect the section tags in an element's text, with the index of the
+/// paragraph holding each.
+fn section_tags(element: &Element, dialect: &Dialect) -> Vec<(Tag, usize)> {
+    fn collect(
+        element: &Element,
+        dialect: &Dialect,
+        para: &mut usize,
+        out: &mut Vec<(Tag, usize)>,
+    ) {
+        for child in element.elements() {
+            if child.local_name() == dialect.text {
+                let text = child.text();
+                for (start, end) in token_spans(&text) {
+                    let tag = Tag::parse(&text[start..end]);
+                    if tag.is_section() {
+                        out.push((tag, *para));
+                    }
+                }
+            } else {
+                if dialect.is_paragraph(child.local_name()) {
+                    *para += 1;
+                }
+                collect(child, dialect, para, out);
+            }
+        }
+    }
+
+    let mut tags = Vec::new();
+    collect(element, dialect, &mut 0, &mut tags);
+    tags
+}
+
+/// Full text of an element.
+fn full_text(element: &Element, dialect: &Dialect) -> String {
+    let mut text = String::new();
+    for child in element.elements() {
+        if child.local_name() == dialect.text {
+            text.push_str(&child.text());
+        } else {
+            text.push_str(&full_text(child, dialect));
+        }
+    }
+    text
+}
+
+/// Remove the `nth` occurrence of a section tag from an element's text.
+fn strip_tag(element: &mut Element, tag: &Tag, nth: &mut usize, dialect: &Dialect) -> bool {
+    for child in element.children.iter_mut() {
+        let Node::Element(child) = child else {
+            continue;
+        };
+        if child.local_name() == dialect.text {
+            let text = child.text();
+            for (start, end) in token_spans(&text) {
+                if Tag::parse(&text[start..end]) != *tag {
+                    continue;
+                }
+                if *nth == 0 {
+                    let stripped = format!("{}{}", &text[..start], &text[end..]);
+                    child.set_text(&stripped, dialect.preserve_space, None);
+                    return true;
+                }
+                *nth -= 1;
+            }
+        } else if strip_tag(child, tag, nth, dialect) {
+            return true;
+        }
+    }
+    false
+}
+
+/// The opening tag of a section starting in a node.
+struct BlockStart {
+    tag: Tag,
+    /// Occurrence of the tag within the node
+    nth: usize,
+    /// Occurrence of the closing tag, when the section ends in the same
+    /// table row
+    row_end: Option<usize>,
+}
+
+/// Values in scope while filling a template.
+struct Scope<'a> {
+    root: &'a TemplateData,
+    /// Current section values, innermost last
+    stack: Vec<&'a TemplateValue>,
+}
+
+impl<'a> Scope<'a> {
+    fn lookup(&self, path: &str) -> Option<&'a TemplateValue> {
+        if path == "." {
+            return self.stack.last().copied();
+        }
+        let mut parts = path.split('.').map(str::trim);
+        let first = parts.next()?;
+        let mut value = self
+            .stack
+            .iter()
+            .rev()
+            .find_map(|value| match value {
+                TemplateValue::Data(data) => data.get(first),
+                _ => None,
+            })
+            .or_else(|| self.root.get(first))?;
+        for part in parts {
+            value = match value {
+                TemplateValue::Data(data) => data.get(part)?,
+                _ => return None,
+            };
+        }
+        Some(value)
+    }
+}
+
+/// Fills a parsed template.
+struct Renderer<'d> {
+    dialect: &'d Dialect,
+}
+
+impl Renderer<'_> {
+    /// Kind of block a node can start: a paragraph or run whose tags open
+    /// a section ending in a later sibling, or a table row.
+    fn block_start(&self, node: &Node) -> Option<BlockStart> {
+        let Node::Element(element) = node else {
+            return None;
+        };
+        let local_name = element.local_name();
+        let is_row = Some(local_name) == self.dialect.row;
+        let is_unit = local_name == self.dialect.run
+            || is_row
+            || (self.dialect.paragraph_blocks && self.dialect.is_paragraph(local_name));
+        if !is_unit {
+            return None;
+        }
+
+        let tags = section_tags(element, self.dialect);
+        let nth_of = |index: usize| {
+            tags[..index]
+                .iter()
+                .filter(|(t, _)| *t == tags[index].0)
+                .count()
+        };
+
+        // A row whose first tag opens a section closed in another cell
+        // paragraph of the row repeats by itself
+        if is_row
+            && let Some((first, para)) = tags.first()
+            && let Some(name) = first.opens()
+        {
+            let mut depth = 0;
+            for (index, (tag, close_para)) in tags.iter().enumerate().skip(1) {
+                if tag.opens() == Some(name) {
+                    depth += 1;
+                } else if *tag == Tag::Close(name.to_string()) {
+                    if depth > 0 {
+                        depth -= 1;
+                    } else if close_para != para {
+                        return Some(BlockStart {
+                            tag: first.clone(),
+                            nth: 0,
+                            row_end: Some(nth_of(index)),
+                        });
+                    } else {
+                        break;
+                    }
+                }
+            }
+        }
+
+        // The first opening tag left unclosed within the node
+        let mut open: Vec<usize> = Vec::new();
+        for (index, (tag, _)) in tags.iter().enumerate() {
+            match tag {
+                Tag::Open(_) | Tag::Inverted(_) => open.push(index),
+                Tag::Close(name) => {
+                    if open
+                        .last()
+                        .is_some_and(|&last| tags[last].0.opens() == Some(name))
+                    {
+                        open.pop();
+                    }
+                },
+                Tag::Value(_) => {},
+            }
+        }
+        let index = *open.first()?;
+        Some(BlockStart {
+            tag: tags[index].0.clone(),
+            nth: nth_of(index),
+            row_end: None,
+        })
+    }
+
+    /// Find the sibling closing a section, and the occurrence of the
+    /// closing tag within it.
+    fn block_end(&self, nodes: &[Node], from: usize, name: &str) -> Option<(usize, usize)> {
+        let close = Tag::Close(name.to_string());
+        let mut depth = 0;
+        for (index, node) in nodes.iter().enumerate().skip(from + 1) {
+            let Node::Element(element) = node else {
+                continue;
+            };
+            let mut nth = 0;
+            for (tag, _) in section_tags(element, self.dialect) {
+                if tag.opens() == Some(name) {
+                    depth += 1;
+                } else if tag == close {
+                    if depth == 0 {
+                        return Some((index, nth));
+                    }
+                    depth -= 1;
+                    nth += 1;
+                }
+            }
+        }
+        None
+    }
+
+    /// Remove a section tag from a node, dropping a paragraph or run left
+    /// with nothing but the tag.
+    fn strip(&self, node: Node, tag: &Tag, nth: usize) -> Option<Node> {
+        let Node::Element(mut element) = node else {
+            return Some(node);
+        };
+        let droppable = Some(element.local_name()) != self.dialect.row
+            && full_text(&element, self.dialect).trim().len()
+                == tag_len(&element, tag, self.dialect)
+            && !element.contains_any(&["sectPr", "drawing", "pict", "object", "graphicFrame"]);
+        strip_tag(&mut element, tag, &mut { nth }, self.dialect);
+        if droppable && full_text(&element, self.dialect).trim().is_empty() {
+            None
+        } else {
+            Some(Node::Element(element))
+        }
+    }
+
+    fn render_nodes<'a>(&self, mut nodes: Vec<Node>, scope: &mut Scope<'a>) -> Vec<Node> {
+        let mut out = Vec::with_capacity(nodes.len());
+        let mut i = 0;
+        while i < nodes.len() {
+            if let Some(start) = self.block_start(&nodes[i])
+                && let Some(name) = start.tag.opens().map(str::to_string)
+            {
+                let end = match start.row_end {
+                    Some(nth) => Some((i, nth)),
+                    None => self.block_end(&nodes, i, &name),
+                };
+                if let Some((end, close_nth)) = end {
+                    let close = Tag::Close(name.clone());
+                    let mut block: Vec<Node> = nodes[i..=end]
+                        .iter_mut()
+                        .map(|node| std::mem::replace(node, Node::Raw(String::new())))
+                        .collect();
+                    let last = block.len() - 1;
+                    let mut stripped = Vec::with_capacity(block.len());
+                    for (index, node) in block.drain(..).enumerate() {
+                        let mut node = Some(node);
+                        // Strip the closing tag first, in case both are in
+                        // the same node
+                        if index == last {
+                            node = node.and_then(|n| self.strip(n, &close, close_nth));
+                        }
+                        if index == 0 {
+                            node = node.and_then(|n| self.strip(n, &start.tag, start.nth));
+                        }
+                        stripped.extend(node);
+                    }
+                    out.extend(self.expand(&start.tag, stripped, scope));
+                    i = end + 1;
+                    continue;
+                }
+            }
+
+            let node = std::mem::replace(&mut nodes[i], Node::Raw(String::new()));
+            out.extend(self.render_node(node, scope));
+            i += 1;
+        }
+        out
+    }
+
+    /// Fill a section's content according to its value.
+    fn expand<'a>(&self, tag: &Tag, block: Vec<Node>, scope: &mut Scope<'a>) -> Vec<Node> {
+        let mut out = Vec::new();
+        match tag {
+            Tag::Open(name) => match scope.lookup(name) {
+                Some(TemplateValue::List(items)) => {
+                    for item in items {
+                        scope.stack.push(item);
+                        out.extend(self.render_nodes(block.clone(), scope));
+                        scope.stack.pop();
+                    }
+                },
+                Some(value @ TemplateValue::Data(_)) => {
+                    scope.stack.push(value);
+                    out.extend(self.render_nodes(block, scope));
+                    scope.stack.pop();
+                },
+                Some(value) if value.is_truthy() => out.extend(self.render_nodes(block, scope)),
+                _ => {},
+            },
+            Tag::Inverted(name) if !scope.lookup(name).is_some_and(TemplateValue::is_truthy) => {
+                out.extend(self.render_nodes(block, scope));
+            },
+            _ => {},
+        }
+        out
+    }
+
+    fn render_node<'a>(&self, node: Node, scope: &mut Scope<'a>) -> Option<Node> {
+        let Node::Element(mut element) = node else {
+            return Some(node);
+        };
+
+        if element.local_name() == self.dialect.text {
+            self.fill_text(&mut element, scope);
+            return Some(Node::Element(element));
+        }
+
+        element.children = self.render_nodes(std::mem::take(&mut element.children), scope);
+
+        if let Some((container, empty_paragraph)) = self.dialect.paragraph_container
+            && element.local_name() == container
+            && !element
+                .elements()
+                .any(|child| self.dialect.is_paragraph(child.local_name()))
+        {
+            if element.end.is_empty() {
+                element.end = format!("</{}>", element.name);
+                element.start = format!("{}>", element.start.trim_end_matches("/>"));
+            }
+            element
+                .children
+                .push(Node::Raw(empty_paragraph.to_string()));
+        }
+        if Some(element.local_name()) == self.dialect.table
+            && !element
+                .elements()
+                .any(|child| Some(child.local_name()) == self.dialect.row)
+        {
+            return None;
+        }
+        Some(Node::Element(element))
+    }
+
+    /// Replace the value placeholders in a text element.
+    fn fill_text(&self, element: &mut Element, scope: &Scope<'_>) {
+        let text = element.text();
+        let mut filled = String::with_capacity(text.len());
+        let mut last = 0;
+        for (start, end) in token_spans(&text) {
+            if let Tag::Value(path) = Tag::parse(&text[start..end]) {
+                filled.push_str(&text[last..start]);
+                if let Some(value) = scope.lookup(&path) {
+                    filled.push_str(&value.to_text());
+                }
+                last = end;
+            }
+        }
+        if last > 0 {
+            filled.push_str(&text[last..]);
+            element.set_text(
+                &filled,
+                self.dialect.preserve_space,
+                self.dialect.line_break,
+            );
+        }
+    }
+}
+
+/// Length of the first occurrence of a tag as written in an element.
+fn tag_len(element: &Element, tag: &Tag, dialect: &Dialect) -> usize {
+    let text = full_text(element, dialect);
+    token_spans(&text)
+        .into_iter()
+        .find(|&(start, end)| Tag::parse(&text[start..end]) == *tag)
+        .map_or(usize::MAX, |(start, end)| end - start)
+}
+
+/// Fill the placeholders of an XML part.
+///
+/// `prepare` can rewrite the parsed tree before filling, e.g. to turn
+/// format-specific merge fields into placeholders. Returns `None` when
+/// the part has no placeholders.
+pub(crate) fn render(
+    xml: &str,
+    data: &TemplateData,
+    dialect: &Dialect,
+    prepare: impl FnOnce(&mut Vec<Node>),
+) -> Result<Option<String>> {
+    let mut nodes = markup::parse(xml)?;
+    prepare(&mut nodes);
+    normalize(&mut nodes, dialect);
+
+    let mut scope = Scope {
+        root: data,
+        stack: Vec::new(),
+    };
+    let nodes = Renderer { dialect }.render_nodes(nodes, &mut scope);
+
+    let mut out = String::with_capacity(xml.len());
+    markup::write_nodes(&nodes, &mut out);
+    Ok((out != xml).then_some(out))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const WORD: Dialect = Dialect {
+        paragraphs: &["p"],
+        run: "r",
+        text: "t",
+        row: Some("tr"),
+        table: Some("tbl"),
+        paragraph_container: Some(("tc", "<w:p/>")),
+        line_break: Some("<w:br/>"),
+        preserve_space: true,
+        paragraph_blocks: true,
+    };
+
+    fn fill(xml: &str, data: &TemplateData) -> String {
+        render(xml, data, &WORD, |_| {})
+            .unwrap()
+            .unwrap_or_else(|| xml.to_string())
+    }
+
+    fn texts(xml: &str) -> Vec<String> {
+        let nodes = markup::parse(xml).unwrap();
+        let mut texts = Vec::new();
+        fn walk(nodes: &[Node], texts: &mut Vec<String>) {
+            for node in nodes {
+                if let Node::Element(element) = node {
+                    if element.local_name() == "p" {
+                        texts.push(full_text(element, &WORD));
+                    } else {
+                        walk(&element.children, texts);
+                    }
+                }
+            }
+        }
+        walk(&nodes, &mut texts);
+        texts
+    }
+
+    #[test]
+    fn test_split_placeholder() {
+        let xml = "<w:body><w:p><w:r><w:rPr><w:b/></w:rPr><w:t>Dear {</w:t></w:r><w:r><w:t>{na</w:t></w:r><w:r><w:t>me}}, hi &amp; bye</w:t></w:r></w:p></w:body>";
+        let mut data = TemplateData::new();
+        data.set("name", "Ann & Bob");
+        let out = fill(xml, &data);
+        assert_eq!(texts(&out), vec!["Dear Ann & Bob, hi & bye"]);
+        // The placeholder takes the formatting of the run it starts in
+        assert!(out.contains(
+            "<w:r><w:rPr><w:b/></w:rPr><w:t xml:space=\"preserve\">Dear Ann &amp; Bob</w:t></w:r>"
+        ));
+    }
+
+    #[test]
+    fn test_paragraph_and_inline_sections() {
+        let xml = "<w:body>\
+            <w:p><w:r><w:t>{{#items}}</w:t></w:r></w:p>\
+            <w:p><w:r><w:t>- {{name}}{{#sale}} (sale){{/sale}}</w:t></w:r></w:p>\
+            <w:p><w:r><w:t>{{/items}}</w:t></w:r></w:p>\
+            <w:p><w:r><w:t>{{^items}}None{{/items}}{{^missing}}!{{/missing}}</w:t></w:r></w:p>\
+            <w:p><w:r><w:t>Line {{note}}</w:t></w:r></w:p>\
+            </w:body>";
+        let mut data = TemplateData::new();
+        data.set(
+            "items",
+            vec![
+                TemplateData::new()
+                    .with("name", "Widget")
+                    .with("sale", true),
+                TemplateData::new().with("name", "Gadget"),
+            ],
+        )
+        .set("note", "one\ntwo");
+        let out = fill(xml, &data);
+        assert_eq!(
+            texts(&out),
+            vec!["- Widget (sale)", "- Gadget", "!", "Line onetwo"]
+        );
+        assert!(out.contains("Line one</w:t><w:br/><w:t xml:space=\"preserve\">two</w:t>"));
+    }
+
+    #[test]
+    fn test_table_rows() {
+        let row = |a: &str, b: &str| {
+            format!(
+                "<w:tr><w:tc><w:p><w:r><w:t>{}</w:t></w:r></w:p></w:tc><w:tc><w:p><w:r><w:t>{}</w:t></w:r></w:p></w:tc></w:tr>",
+                a, b
+            )
+        };
+        let xml = format!(
+            "<w:body><w:tbl>{}{}</w:tbl><w:tbl>{}{}{}</w:tbl></w:body>",
+            row("Name", "Price"),
+            row("{{#items}}{{name}}", "{{price}}{{/items}}"),
+            row("{{#none}}", ""),
+            row("{{.}}", ""),
+            row("", "{{/none}}"),
+        );
+        let mut data = TemplateData::new();
+        data.set(
+            "items",
+            vec![
+                TemplateData::new()
+                    .with("name", "Widget")
+                    .with("price", "9.99"),
+                TemplateData::new()
+                    .with("name", "Gadget")
+                    .with("price", 19.5),
+            ],
+        )
+        .set("none", Vec::<TemplateValue>::new());
+        let out = fill(&xml, &data);
+        assert_eq!(
+            texts(&out),
+            vec!["Name", "Price", "Widget", "9.99", "Gadget", "19.5"]
+        );
+        // A table left without rows is removed
+        assert_eq!(out.matches("<w:tbl>").count(), 1);
+    }
+
+    #[test]
+    fn test_unmatched_and_missing() {
+        let xml = "<w:p><w:r><w:t>{{#open}} {{unknown}}</w:t></w:r></w:p>";
+        let out = fill(xml, &TemplateData::new());
+        assert_eq!(texts(&out), vec!["{{#open}} "]);
+        let plain = "<w:p><w:r><w:t>No placeholders</w:t></w:r></w:p>";
+        assert!(
+            render(plain, &TemplateData::new(), &WORD, |_| {})
+                .unwrap()
+                .is_none()
+        );
+    }
+}
@@ -1,5 +1,17 @@
 //! Common OOXML functionality shared across formats.
 
+pub mod charts;
+mod guid;
+pub(crate) mod markup;
+pub mod merge;
+pub mod pictures;
 pub mod properties;
+pub(crate) mod xml;
 
+pub use charts::{
+    ChartCache, ChartData, ChartKind, ChartSeries, ChartValues, collect_charts, parse_chart,
+};
+pub(crate) use guid::guid;
+pub use merge::{TemplateData, TemplateValue};
+pub use pictures::{PictureRef, collect_pictures, scan_chart_refs, scan_picture_refs};
 pub use properties::DocumentProperties;
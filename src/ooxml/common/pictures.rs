@@ -0,0 +1,269 @@
+//! Picture references in DrawingML and VML markup.
+//!
+//! WordprocessingML, PresentationML and SpreadsheetML drawings all point at
+//! their image parts the same way: an `a:blip r:embed` (or VML
+//! `v:imagedata r:id`) relationship ID, preceded by a `wp:docPr` /
+//! `p:cNvPr` / `xdr:cNvPr` element carrying the alternative text. This module
+//! scans a part for those references and resolves them to
+//! [`EmbeddedImage`]s. Chart frames (`c:chart r:id`) are found the same way.
+
+use super::xml;
+use crate::common::{EmbeddedImage, ImageAnchor};
+use crate::ooxml::error::{OoxmlError, Result};
+use crate::ooxml::opc::{OpcPackage, Part};
+use quick_xml::Reader;
+use quick_xml::events::{BytesStart, Event};
+
+/// A picture (or chart) reference found in a part's XML.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PictureRef {
+    /// Relationship ID of the image (or chart) part
+    pub r_id: String,
+    /// Description (or title) of the enclosing drawing
+    pub alt_text: Option<String>,
+    /// 0-based index of the enclosing `w:p`, counted like `Document::paragraphs()`
+    pub paragraph: Option<usize>,
+    /// 0-based `(row, column)` of the enclosing `xdr:from` anchor
+    pub cell: Option<(u32, u32)>,
+}
+
+/// Which coordinate of an `xdr:from` marker is being read.
+#[derive(Clone, Copy)]
+enum Marker {
+    None,
+    Row,
+    Column,
+}
+
+/// Scan DrawingML/VML markup for picture references in document order.
+pub fn scan_picture_refs(xml: &[u8]) -> Result<Vec<PictureRef>> {
+    scan_refs(xml, false)
+}
+
+/// Scan DrawingML markup for chart frame references in document order.
+pub fn scan_chart_refs(xml: &[u8]) -> Result<Vec<PictureRef>> {
+    scan_refs(xml, true)
+}
+
+/// Scan for picture references, or chart references when `charts` is set.
+fn scan_refs(xml: &[u8], charts: bool) -> Result<Vec<PictureRef>> {
+    let mut reader = Reader::from_reader(xml);
+    let mut buf = Vec::with_capacity(512);
+    let mut refs = Vec::new();
+
+    let mut paragraph_count = 0usize;
+    let mut paragraph_depth = 0usize;
+    let mut alt_text: Option<String> = None;
+    let mut in_from = false;
+    let mut marker = Marker::None;
+    let mut from = (0u32, 0u32);
+    let mut cell = None;
+
+    loop {
+        match reader.read_event_into(&mut buf) {
+            Ok(Event::Start(e)) => {
+                match e.name().as_ref() {
+                    b"w:p" => {
+                        if paragraph_depth == 0 {
+                            paragraph_count += 1;
+                        }
+                        paragraph_depth += 1;
+                    },
+                    _ => match e.local_name().as_ref() {
+                        b"from" => {
+                            in_from = true;
+                            from = (0, 0);
+                        },
+                        b"row" if in_from => marker = Marker::Row,
+                        b"col" if in_from => marker = Marker::Column,
+                        _ => {},
+                    },
+                }
+                visit_element(
+                    &e,
+                    charts,
+                    &mut alt_text,
+                    &mut refs,
+                    paragraph_count,
+                    paragraph_depth,
+                    cell,
+                );
+            },
+            Ok(Event::Empty(e)) => {
+                visit_element(
+                    &e,
+                    charts,
+                    &mut alt_text,
+                    &mut refs,
+                    paragraph_count,
+                    paragraph_depth,
+                    cell,
+                );
+            },
+            Ok(Event::Text(t)) if in_from => {
+                let value = t.decode().ok().and_then(|s| s.trim().parse::<u32>().ok());
+                match (marker, value) {
+                    (Marker::Row, Some(row)) => from.0 = row,
+                    (Marker::Column, Some(col)) => from.1 = col,
+                    _ => {},
+                }
+            },
+            Ok(Event::End(e)) => match e.name().as_ref() {
+                b"w:p" => paragraph_depth = paragraph_depth.saturating_sub(1),
+                _ => match e.local_name().as_ref() {
+                    b"from" => {
+                        in_from = false;
+                        cell = Some(from);
+                    },
+                    b"row" | b"col" => marker = Marker::None,
+                    _ => {},
+                },
+            },
+            Ok(Event::Eof) => break,
+            Err(e) => return Err(OoxmlError::Xml(e.to_string())),
+            _ => {},
+        }
+        buf.clear();
+    }
+
+    Ok(refs)
+}
+
+/// Track alternative text and record picture references for one element.
+fn visit_element(
+    e: &BytesStart,
+    charts: bool,
+    alt_text: &mut Option<String>,
+    refs: &mut Vec<PictureRef>,
+    paragraph_count: usize,
+    paragraph_depth: usize,
+    cell: Option<(u32, u32)>,
+) {
+    let paragraph = (paragraph_depth > 0).then(|| paragraph_count - 1);
+
+    match e.local_name().as_ref() {
+        b"docPr" | b"cNvPr" => {
+            *alt_text = attribute(e, b"descr").or_else(|| attribute(e, b"title"));
+        },
+        b"chart" if charts => {
+            if let Some(r_id) = attribute(e, b"id") {
+                refs.push(PictureRef {
+                    r_id,
+                    alt_text: alt_text.clone(),
+                    paragraph,
+                    cell,
+                });
+            }
+        },
+        b"blip" if !charts => {
+            if let Some(r_id) = attribute(e, b"embed") {
+                refs.push(PictureRef {
+                    r_id,
+                    alt_text: alt_text.clone(),
+                    paragraph,
+                    cell,
+                });
+            }
+        },
+        b"imagedata" if !charts => {
+            if let Some(r_id) = attribute(e, b"id") {
+                refs.push(PictureRef {
+                    r_id,
+                    alt_text: attribute(e, b"title").or_else(|| alt_text.clone()),
+                    paragraph,
+                    cell,
+                });
+            }
+        },
+        _ => {},
+    }
+}
+
+/// Get a non-empty attribute value by local name.
+fn attribute(e: &BytesStart, local_name: &[u8]) -> Option<String> {
+    xml::attribute(e, local_name).filter(|value| !value.is_empty())
+}
+
+/// Resolve the pictures referenced by `part` to their image data.
+///
+/// References to external targets or missing parts are skipped. `anchor`
+/// maps each reference to its position in the document.
+pub fn collect_pictures(
+    part: &dyn Part,
+    package: &OpcPackage,
+    anchor: impl Fn(&PictureRef) -> ImageAnchor,
+) -> Result<Vec<EmbeddedImage>> {
+    let refs = scan_picture_refs(part.blob())?;
+    let mut images = Vec::with_capacity(refs.len());
+
+    for picture in &refs {
+        let Some(rel) = part.rels().get(&picture.r_id) else {
+            continue;
+        };
+        if rel.is_external() {
+            continue;
+        }
+        let Ok(partname) = rel.target_partname() else {
+            continue;
+        };
+        let Ok(image_part) = package.get_part(&partname) else {
+            continue;
+        };
+
+        images.push(
+            EmbeddedImage::new(
+                partname.filename(),
+                image_part.blob().to_vec(),
+                anchor(picture),
+            )
+            .with_alt_text(picture.alt_text.clone()),
+        );
+    }
+
+    Ok(images)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_scan_docx_inline_picture() {
+        let xml = br#"<w:document xmlns:w="w" xmlns:wp="wp" xmlns:a="a" xmlns:r="r"><w:body>
+            <w:p><w:r><w:t>Intro</w:t></w:r></w:p>
+            <w:p><w:r><w:drawing><wp:inline>
+                <wp:docPr id="1" name="Picture 1" descr="A red square"/>
+                <a:graphic><a:graphicData><pic:pic xmlns:pic="pic"><pic:blipFill>
+                    <a:blip r:embed="rId5"/>
+                </pic:blipFill></pic:pic></a:graphicData></a:graphic>
+            </wp:inline></w:drawing></w:r></w:p>
+        </w:body></w:document>"#;
+
+        let refs = scan_picture_refs(xml).unwrap();
+        assert_eq!(refs.len(), 1);
+        assert_eq!(refs[0].r_id, "rId5");
+        assert_eq!(refs[0].alt_text.as_deref(), Some("A red square"));
+        assert_eq!(refs[0].paragraph, Some(1));
+        assert_eq!(refs[0].cell, None);
+    }
+
+    #[test]
+    fn test_scan_spreadsheet_drawing_anchor() {
+        let xml = br#"<xdr:wsDr xmlns:xdr="xdr" xmlns:a="a" xmlns:r="r">
+            <xdr:twoCellAnchor>
+                <xdr:from><xdr:col>3</xdr:col><xdr:colOff>0</xdr:colOff><xdr:row>7</xdr:row><xdr:rowOff>0</xdr:rowOff></xdr:from>
+                <xdr:to><xdr:col>5</xdr:col><xdr:row>9</xdr:row></xdr:to>
+                <xdr:pic>
+                    <xdr:nvPicPr><xdr:cNvPr id="2" name="Logo" title="Company logo"/></xdr:nvPicPr>
+                    <xdr:blipFill><a:blip r:embed="rId1"/></xdr:blipFill>
+                </xdr:pic>
+            </xdr:twoCellAnchor>
+        </xdr:wsDr>"#;
+
+        let refs = scan_picture_refs(xml).unwrap();
+        assert_eq!(refs.len(), 1);
+        assert_eq!(refs[0].cell, Some((7, 3)));
+        assert_eq!(refs[0].alt_text.as_deref(), Some("Company logo"));
+        assert_eq!(refs[0].paragraph, None);
+    }
+}
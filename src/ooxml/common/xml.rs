@@ -0,0 +1,95 @@
+//! Helpers for reading OOXML parts with the quick-xml event reader.
+use crate::ooxml::error::{OoxmlError, Result};
+use quick_xml::Reader;
+use quick_xml::escape::resolve_predefined_entity;
+use quick_xml::events::{BytesStart, Event};
+use std::ops::Range;
+
+/// Get an attribute value by local name.
+pub(crate) fn attribute(e: &BytesStart, local_name: &[u8]) -> Option<String> {
+    e.attributes()
+        .flatten()
+        .find(|attr| attr.key.local_name().as_ref() == local_name)
+        .and_then(|attr| attr.unescape_value().ok().map(|v| v.into_owned()))
+}
+
+/// Append character data from a text, entity or CDATA event.
+pub(crate) fn push_text(event: &Event, out: &mut String) -> Result<()> {
+    let xml_error = |e: quick_xml::encoding::EncodingError| OoxmlError::Xml(e.to_string());
+    match event {
+        Event::Text(t) => out.push_str(&t.decode().map_err(xml_error)?),
+        Event::CData(c) => out.push_str(&c.decode().map_err(xml_error)?),
+        Event::GeneralRef(r) => {
+            if let Some(ch) = r
+                .resolve_char_ref()
+                .map_err(|e| OoxmlError::Xml(e.to_string()))?
+            {
+                out.push(ch);
+            } else {
+                let name = r.decode().map_err(xml_error)?;
+                if let Some(value) = resolve_predefined_entity(&name) {
+                    out.push_str(value);
+                }
+            }
+        },
+        _ => {},
+    }
+    Ok(())
+}
+
+/// Get the raw attribute markup of a start tag.
+pub(crate) fn raw_attributes(e: &BytesStart) -> String {
+    String::from_utf8_lossy(e.attributes_raw()).into_owned()
+}
+
+/// Get the local name of an element.
+pub(crate) fn local_name(e: &BytesStart) -> String {
+    String::from_utf8_lossy(e.local_name().as_ref()).into_owned()
+}
+
+/// A direct child element of a split element.
+pub(crate) struct Child {
+    /// Local name of the element
+    pub(crate) name: String,
+    /// Byte range of the element's markup, children included
+    pub(crate) range: Range<usize>,
+}
+
+/// Split an element into its raw attributes and direct children.
+///
+/// Ranges index into `xml`, which may start with an XML declaration.
+pub(crate) fn split_children(xml: &str) -> Result<(String, Vec<Child>)> {
+    let mut reader = Reader::from_str(xml);
+    let mut root = None;
+    let mut children = Vec::new();
+
+    loop {
+        let start = reader.buffer_position() as usize;
+        match reader.read_event() {
+            Ok(Event::Start(e)) if root.is_none() => root = Some(raw_attributes(&e)),
+            Ok(Event::Empty(e)) if root.is_none() => {
+                root = Some(raw_attributes(&e));
+                break;
+            },
+            Ok(Event::Start(e)) => {
+                let name = local_name(&e);
+                reader
+                    .read_to_end(e.name())
+                    .map_err(|e| OoxmlError::Xml(e.to_string()))?;
+                children.push(Child {
+                    name,
+                    range: start..reader.buffer_position() as usize,
+                });
+            },
+            Ok(Event::Empty(e)) => children.push(Child {
+                name: local_name(&e),
+                range: start..reader.buffer_position() as usize,
+            }),
+            Ok(Event::End(_)) | Ok(Event::Eof) => break,
+            Err(e) => return Err(OoxmlError::Xml(e.to_string())),
+            _ => {},
+        }
+    }
+
+    Ok((root.unwrap_or_default(), children))
+}
@@ -0,0 +1,380 @@
+/// Comments in Word documents.
+///
+/// Comments are stored in `word/comments.xml`, each `<w:comment>` holding
+/// its author, initials, date and paragraphs. The commented text is
+/// delimited in the document by `<w:commentRangeStart>` and
+/// `<w:commentRangeEnd>` sharing the comment's `w:id`, followed by a run
+/// with a `<w:commentReference>`.
+///
+/// Word 2013 and later add `word/commentsExtended.xml`, which links replies
+/// to the comment they answer and records resolved comments. Entries are
+/// keyed by the `w14:paraId` of each comment's last paragraph, as are the
+/// durable IDs in `word/commentsIds.xml`.
+use crate::ooxml::common::xml::{attribute, push_text as push_event_text};
+use crate::ooxml::docx::field::{Bookmark, TextPosition};
+use crate::ooxml::error::{OoxmlError, Result};
+use quick_xml::Reader;
+use quick_xml::events::Event;
+use std::collections::HashMap;
+
+/// A comment on a range of document text.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Comment {
+    /// Comment ID (`w:id`), shared with the range markers in the document
+    pub id: String,
+    /// Author of the comment
+    pub author: Option<String>,
+    /// Author's initials
+    pub initials: Option<String>,
+    /// Date and time of the comment, as written (ISO 8601)
+    pub date: Option<String>,
+    /// Comment text, with paragraphs separated by newlines
+    pub text: String,
+    /// Where the commented text starts, if the comment has a range
+    pub start: Option<TextPosition>,
+    /// Where the commented text ends, if the comment has a range
+    pub end: Option<TextPosition>,
+    /// Commented text, with paragraphs separated by newlines
+    pub anchor_text: String,
+    /// ID of the comment this one replies to
+    pub parent_id: Option<String>,
+    /// Whether the comment was marked as resolved
+    pub resolved: bool,
+    /// Durable ID from `commentsIds.xml`, which stays the same across edits
+    pub durable_id: Option<String>,
+}
+
+impl Comment {
+    /// Whether this comment is a reply to another one.
+    pub fn is_reply(&self) -> bool {
+        self.parent_id.is_some()
+    }
+}
+
+/// Thread information from `commentsExtended.xml`.
+struct CommentEx {
+    parent_para_id: Option<String>,
+    done: bool,
+}
+
+/// Read the comments of a document.
+///
+/// `extended` and `ids` are the optional `commentsExtended.xml` and
+/// `commentsIds.xml` parts; `ranges` are the commented ranges found in the
+/// document body.
+pub(crate) fn read_comments(
+    comments: &[u8],
+    extended: Option<&[u8]>,
+    ids: Option<&[u8]>,
+    ranges: &[Bookmark],
+) -> Result<Vec<Comment>> {
+    let parsed = parse_comments(comments)?;
+    let extended = match extended {
+        Some(xml) => parse_extended(xml)?,
+        None => HashMap::new(),
+    };
+    let durable_ids = match ids {
+        Some(xml) => parse_ids(xml)?,
+        None => HashMap::new(),
+    };
+
+    // Replies name their parent by paragraph ID
+    let ids_by_para: HashMap<&str, &str> = parsed
+        .iter()
+        .filter_map(|(comment, para_id)| Some((para_id.as_deref()?, comment.id.as_str())))
+        .collect();
+
+    let mut result = Vec::with_capacity(parsed.len());
+    for (mut comment, para_id) in parsed.iter().cloned() {
+        if let Some(para_id) = para_id.as_deref() {
+            if let Some(ex) = extended.get(para_id) {
+                comment.resolved = ex.done;
+                comment.parent_id = ex
+                    .parent_para_id
+                    .as_deref()
+                    .and_then(|parent| ids_by_para.get(parent))
+                    .map(|id| id.to_string());
+            }
+            comment.durable_id = durable_ids.get(para_id).cloned();
+        }
+        if let Some(range) = ranges.iter().find(|range| range.id == comment.id) {
+            comment.start = Some(range.start);
+            comment.end = Some(range.end);
+            comment.anchor_text = range.text.clone();
+        }
+        result.push(comment);
+    }
+    Ok(result)
+}
+
+/// Parse `comments.xml` into comments and the paragraph ID of each
+/// comment's last paragraph.
+fn parse_comments(xml: &[u8]) -> Result<Vec<(Comment, Option<String>)>> {
+    let mut reader = Reader::from_reader(xml);
+    let mut comments = Vec::new();
+    let mut current: Option<(Comment, Option<String>)> = None;
+    let mut paragraphs = 0usize;
+    let mut in_text = false;
+    let mut buf = Vec::with_capacity(1024);
+
+    loop {
+        let event = reader
+            .read_event_into(&mut buf)
+            .map_err(|e| OoxmlError::Xml(e.to_string()))?;
+        match event {
+            Event::Start(ref e) | Event::Empty(ref e) => {
+                let empty = matches!(event, Event::Empty(_));
+                match e.local_name().as_ref() {
+                    b"comment" => {
+                        let comment = Comment {
+                            id: attribute(e, b"id").unwrap_or_default(),
+                            author: attribute(e, b"author"),
+                            initials: attribute(e, b"initials"),
+                            date: attribute(e, b"date"),
+                            text: String::new(),
+                            start: None,
+                            end: None,
+                            anchor_text: String::new(),
+                            parent_id: None,
+                            resolved: false,
+                            durable_id: None,
+                        };
+                        paragraphs = 0;
+                        if empty {
+                            comments.push((comment, None));
+                        } else {
+                            current = Some((comment, None));
+                        }
+                    },
+                    b"p" => {
+                        if let Some((comment, para_id)) = current.as_mut() {
+                            if paragraphs > 0 {
+                                comment.text.push('\n');
+                            }
+                            paragraphs += 1;
+                            *para_id = attribute(e, b"paraId");
+                        }
+                    },
+                    b"t" if !empty => in_text = true,
+                    b"tab" => {
+                        if let Some((comment, _)) = current.as_mut() {
+                            comment.text.push('\t');
+                        }
+                    },
+                    b"br" => {
+                        if let Some((comment, _)) = current.as_mut() {
+                            comment.text.push('\n');
+                        }
+                    },
+                    _ => {},
+                }
+            },
+            Event::End(ref e) => match e.local_name().as_ref() {
+                b"t" => in_text = false,
+                b"comment" => comments.extend(current.take()),
+                _ => {},
+            },
+            Event::Text(_) | Event::GeneralRef(_) if in_text => {
+                if let Some((comment, _)) = current.as_mut() {
+                    push_event_text(&event, &mut comment.text)?;
+                }
+            },
+            Event::Eof => break,
+            _ => {},
+        }
+        buf.clear();
+    }
+    Ok(comments)
+}
+
+/// Parse `commentsExtended.xml`, keyed by paragraph ID.
+fn parse_extended(xml: &[u8]) -> Result<HashMap<String, CommentEx>> {
+    let mut reader = Reader::from_reader(xml);
+    let mut extended = HashMap::new();
+    let mut buf = Vec::new();
+
+    loop {
+        match reader.read_event_into(&mut buf) {
+            Ok(Event::Start(ref e) | Event::Empty(ref e))
+                if e.local_name().as_ref() == b"commentEx" =>
+            {
+                if let Some(para_id) = attribute(e, b"paraId") {
+                    let done = attribute(e, b"done");
+                    extended.insert(
+                        para_id,
+                        CommentEx {
+                            parent_para_id: attribute(e, b"paraIdParent"),
+                            done: matches!(done.as_deref(), Some("1" | "true")),
+                        },
+                    );
+                }
+            },
+            Ok(Event::Eof) => break,
+            Err(e) => return Err(OoxmlError::Xml(e.to_string())),
+            _ => {},
+        }
+        buf.clear();
+    }
+    Ok(extended)
+}
+
+/// Parse `commentsIds.xml` into durable IDs keyed by paragraph ID.
+fn parse_ids(xml: &[u8]) -> Result<HashMap<String, String>> {
+    let mut reader = Reader::from_reader(xml);
+    let mut ids = HashMap::new();
+    let mut buf = Vec::new();
+
+    loop {
+        match reader.read_event_into(&mut buf) {
+            Ok(Event::Start(ref e) | Event::Empty(ref e))
+                if e.local_name().as_ref() == b"commentId" =>
+            {
+                if let (Some(para_id), Some(durable_id)) =
+                    (attribute(e, b"paraId"), attribute(e, b"durableId"))
+                {
+                    ids.insert(para_id, durable_id);
+                }
+            },
+            Ok(Event::Eof) => break,
+            Err(e) => return Err(OoxmlError::Xml(e.to_string())),
+            _ => {},
+        }
+        buf.clear();
+    }
+    Ok(ids)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ooxml::docx::field::scan;
+    use std::io::Cursor;
+
+    #[test]
+    fn test_read_comments() {
+        let comments = br#"<w:comments xmlns:w="w" xmlns:w14="w14">
+<w:comment w:id="0" w:author="Ann Lee" w:initials="AL" w:date="2024-05-01T10:00:00Z">
+<w:p w14:paraId="1A"><w:r><w:annotationRef/></w:r><w:r><w:t>Check this</w:t></w:r></w:p>
+<w:p w14:paraId="1B"><w:r><w:t xml:space="preserve">and &amp; that</w:t></w:r></w:p>
+</w:comment>
+<w:comment w:id="1" w:author="Bob"><w:p w14:paraId="2A"><w:r><w:t>Done</w:t></w:r></w:p></w:comment>
+</w:comments>"#;
+        let extended = br#"<w15:commentsEx xmlns:w15="w15">
+<w15:commentEx w15:paraId="1B" w15:done="1"/>
+<w15:commentEx w15:paraId="2A" w15:paraIdParent="1B" w15:done="0"/>
+</w15:commentsEx>"#;
+        let ids = br#"<w16cid:commentsIds xmlns:w16cid="w16cid">
+<w16cid:commentId w16cid:paraId="1B" w16cid:durableId="3F2A"/>
+</w16cid:commentsIds>"#;
+        let body = br#"<w:body><w:p><w:r><w:t xml:space="preserve">Some </w:t></w:r><w:commentRangeStart w:id="0"/><w:commentRangeStart w:id="1"/><w:r><w:t>text</w:t></w:r><w:commentRangeEnd w:id="0"/><w:commentRangeEnd w:id="1"/><w:r><w:commentReference w:id="0"/></w:r></w:p></w:body>"#;
+        let ranges = scan(body, None).unwrap().comment_ranges;
+
+        let comments = read_comments(comments, Some(extended), Some(ids), &ranges).unwrap();
+        assert_eq!(comments.len(), 2);
+
+        let first = &comments[0];
+        assert_eq!(first.author.as_deref(), Some("Ann Lee"));
+        assert_eq!(first.initials.as_deref(), Some("AL"));
+        assert_eq!(first.date.as_deref(), Some("2024-05-01T10:00:00Z"));
+        assert_eq!(first.text, "Check this\nand & that");
+        assert_eq!(first.anchor_text, "text");
+        assert_eq!(
+            first.start,
+            Some(TextPosition {
+                paragraph: 0,
+                offset: 5
+            })
+        );
+        assert_eq!(
+            first.end,
+            Some(TextPosition {
+                paragraph: 0,
+                offset: 9
+            })
+        );
+        assert!(first.resolved);
+        assert!(!first.is_reply());
+        assert_eq!(first.durable_id.as_deref(), Some("3F2A"));
+
+        let reply = &comments[1];
+        assert_eq!(reply.parent_id.as_deref(), Some("0"));
+        assert!(!reply.resolved);
+        assert_eq!(reply.anchor_text, "text");
+    }
+
+    #[test]
+    fn test_comment_round_trip() {
+        use crate::ooxml::docx::Package;
+
+        let mut pkg = Package::new().unwrap();
+        {
+            let doc = pkg.document_mut().unwrap();
+            let (id, comment) = doc.add_comment("Ann");
+            comment.add_paragraph_with_text("Why?");
+            let para = doc.add_paragraph();
+            para.add_run_with_text("Plain ");
+            para.add_commented_text("flagged", id);
+            let (_, reply) = doc.add_reply(id, "Bob").unwrap();
+            reply
+                .add_paragraph_with_text("Because.")
+                .add_run_with_text(" Really.");
+            reply.set_resolved(true);
+        }
+        let mut buffer = Cursor::new(Vec::new());
+        pkg.save_to_writer(&mut buffer).unwrap();
+
+        let pkg = Package::from_reader(buffer).unwrap();
+        let comments = pkg.document().unwrap().comments().unwrap();
+
+        assert_eq!(comments.len(), 2);
+        assert_eq!(comments[0].author.as_deref(), Some("Ann"));
+        assert_eq!(comments[0].text, "Why?");
+        assert_eq!(comments[0].anchor_text, "flagged");
+        assert_eq!(comments[1].text, "Because. Really.");
+        assert_eq!(comments[1].parent_id.as_deref(), Some("0"));
+        assert_eq!(comments[1].anchor_text, "flagged");
+        assert!(comments[1].resolved);
+    }
+
+    #[test]
+    fn test_comment_added_to_opened_document() {
+        use crate::ooxml::docx::Package;
+
+        let mut pkg = Package::new().unwrap();
+        {
+            let doc = pkg.document_mut().unwrap();
+            let (id, comment) = doc.add_comment("Ann");
+            comment.add_paragraph_with_text("Why?");
+            doc.add_paragraph().add_commented_text("flagged", id);
+        }
+        let mut buffer = Cursor::new(Vec::new());
+        pkg.save_to_writer(&mut buffer).unwrap();
+
+        let mut pkg = Package::from_reader(buffer).unwrap();
+        {
+            let doc = pkg.document_mut().unwrap();
+            let (id, comment) = doc.add_comment("Bob");
+            assert_eq!(id, 1);
+            comment.add_paragraph_with_text("Checked.");
+            doc.add_paragraph().add_commented_text("noted", id);
+            let (reply_id, reply) = doc.add_reply(0, "Cy").unwrap();
+            assert_eq!(reply_id, 2);
+            reply.add_paragraph_with_text("Agreed.");
+        }
+        let mut buffer = Cursor::new(Vec::new());
+        pkg.save_to_writer(&mut buffer).unwrap();
+
+        let pkg = Package::from_reader(buffer).unwrap();
+        let comments = pkg.document().unwrap().comments().unwrap();
+
+        assert_eq!(comments.len(), 3);
+        assert_eq!(comments[0].id, "0");
+        assert_eq!(comments[0].text, "Why?");
+        assert_eq!(comments[1].id, "1");
+        assert_eq!(comments[1].author.as_deref(), Some("Bob"));
+        assert_eq!(comments[1].anchor_text, "noted");
+        assert_eq!(comments[2].parent_id.as_deref(), Some("0"));
+        assert!(comments.iter().all(|comment| comment.durable_id.is_some()));
+        assert_ne!(comments[0].durable_id, comments[1].durable_id);
+    }
+}
@@ -0,0 +1,947 @@
+/// Content controls (structured document tags) in Word documents.
+///
+/// A content control is a `<w:sdt>` element: `<w:sdtPr>` holds its ID,
+/// tag, alias, type, list items and data binding, and `<w:sdtContent>`
+/// the content shown. Depending on where it is placed, the content is
+/// paragraphs and tables, runs within a paragraph, table rows or a cell.
+///
+/// The control type is given by an element in `w:sdtPr`: `w:text`,
+/// `w:comboBox`, `w:dropDownList`, `w:date`, `w:picture`, `w14:checkbox`,
+/// `w15:repeatingSection` and so on; a control without one holds rich
+/// text. While `w:showingPlcHdr` is set, the content is the placeholder
+/// text rather than a value.
+///
+/// A `w:dataBinding` ties the control to a node of a custom XML part
+/// (see [`custom_xml`](crate::ooxml::docx::custom_xml)), which Word keeps
+/// in sync with the content.
+use crate::ooxml::common::markup::{self, Element, Node};
+use crate::ooxml::docx::custom_xml::set_attribute;
+use crate::ooxml::error::{OoxmlError, Result};
+use quick_xml::escape::escape;
+
+/// Type of a content control.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ContentControlType {
+    /// Formatted text, paragraphs or tables
+    RichText,
+    /// Text with a single format
+    PlainText,
+    /// List of choices that also accepts other text
+    ComboBox,
+    /// List of choices
+    DropDownList,
+    /// Date picker
+    Date,
+    /// Checkbox (Word 2010 and later)
+    Checkbox,
+    /// Picture
+    Picture,
+    /// Section whose items can be repeated (Word 2013 and later)
+    RepeatingSection,
+    /// One item of a repeating section
+    RepeatingSectionItem,
+    /// Group of content that can only be edited in its nested controls
+    Group,
+    /// Building block gallery, such as a table of contents or cover page
+    BuildingBlock,
+    /// Citation
+    Citation,
+    /// Bibliography
+    Bibliography,
+    /// Equation
+    Equation,
+}
+
+/// Where a content control is placed, which decides what its content is.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ContentControlLevel {
+    /// Around paragraphs and tables
+    Block,
+    /// Around runs within a paragraph
+    Inline,
+    /// Around table rows
+    Row,
+    /// Around a table cell
+    Cell,
+}
+
+/// A choice of a combo box or drop-down list.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ListItem {
+    /// Text shown for the choice
+    pub display_text: String,
+    /// Value stored for the choice
+    pub value: String,
+}
+
+/// The custom XML node a content control is bound to.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DataBinding {
+    /// XPath of the node, e.g. `/ns0:invoice[1]/ns0:customer[1]`
+    pub xpath: String,
+    /// Namespace prefixes used in the path, as `xmlns:ns0='...'`
+    /// declarations
+    pub prefix_mappings: String,
+    /// Item ID of the custom XML part, e.g. `{4F2A...}`
+    pub store_item_id: Option<String>,
+}
+
+/// A content control in a document.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ContentControl {
+    /// Control ID (`w:id`)
+    pub id: Option<String>,
+    /// Tag identifying the control to programs
+    pub tag: Option<String>,
+    /// Title shown to users
+    pub alias: Option<String>,
+    pub control_type: ContentControlType,
+    pub level: ContentControlLevel,
+    /// Text of the content, with paragraphs separated by newlines
+    pub text: String,
+    /// Whether the content is the placeholder text
+    pub showing_placeholder: bool,
+    /// Editing restriction (`w:lock`), e.g. `sdtContentLocked`
+    pub lock: Option<String>,
+    /// Choices of a combo box or drop-down list
+    pub list_items: Vec<ListItem>,
+    /// Whether a checkbox is checked
+    pub checked: Option<bool>,
+    /// Date of a date picker, as written (ISO 8601)
+    pub date: Option<String>,
+    /// Display format of a date picker, e.g. `M/d/yyyy`
+    pub date_format: Option<String>,
+    pub data_binding: Option<DataBinding>,
+    /// Index of the control this one is nested in
+    pub parent: Option<usize>,
+}
+
+impl ContentControl {
+    /// Get the value of the control: the checked state of a checkbox, the
+    /// stored value of the selected list item, the date of a date picker,
+    /// or the text. `None` while the placeholder is shown.
+    pub fn value(&self) -> Option<String> {
+        if self.showing_placeholder {
+            return None;
+        }
+        match self.control_type {
+            ContentControlType::Checkbox => self.checked.map(|checked| checked.to_string()),
+            ContentControlType::ComboBox | ContentControlType::DropDownList => Some(
+                self.list_item(&self.text)
+                    .map_or_else(|| self.text.clone(), |item| item.value.clone()),
+            ),
+            ContentControlType::Date => self.date.clone().or_else(|| Some(self.text.clone())),
+            _ => Some(self.text.clone()),
+        }
+    }
+
+    /// Find a list item by its value or display text.
+    pub fn list_item(&self, value: &str) -> Option<&ListItem> {
+        self.list_items
+            .iter()
+            .find(|item| item.value == value)
+            .or_else(|| {
+                self.list_items
+                    .iter()
+                    .find(|item| item.display_text == value)
+            })
+    }
+}
+
+/// Whether an on/off property is on.
+fn is_on(element: &Element) -> bool {
+    !matches!(
+        element.attribute("val").as_deref(),
+        Some("0" | "false" | "off")
+    )
+}
+
+/// Find the first descendant with a local name.
+fn descendant<'e>(element: &'e Element, local_name: &str) -> Option<&'e Element> {
+    element.elements().find_map(|child| {
+        if child.local_name() == local_name {
+            Some(child)
+        } else {
+            descendant(child, local_name)
+        }
+    })
+}
+
+/// Read the properties and text of an `sdt` element.
+fn read_control(
+    sdt: &Element,
+    level: ContentControlLevel,
+    parent: Option<usize>,
+) -> ContentControl {
+    let mut control = ContentControl {
+        id: None,
+        tag: None,
+        alias: None,
+        control_type: ContentControlType::RichText,
+        level,
+        text: String::new(),
+        showing_placeholder: false,
+        lock: None,
+        list_items: Vec::new(),
+        checked: None,
+        date: None,
+        date_format: None,
+        data_binding: None,
+        parent,
+    };
+
+    if let Some(properties) = sdt.child("sdtPr") {
+        for property in properties.elements() {
+            match property.local_name() {
+                "id" => control.id = property.attribute("val"),
+                "tag" => control.tag = property.attribute("val"),
+                "alias" => control.alias = property.attribute("val"),
+                "lock" => control.lock = property.attribute("val"),
+                "showingPlcHdr" => control.showing_placeholder = is_on(property),
+                "dataBinding" if control.data_binding.is_none() => {
+                    control.data_binding = Some(DataBinding {
+                        xpath: property.attribute("xpath").unwrap_or_default(),
+                        prefix_mappings: property.attribute("prefixMappings").unwrap_or_default(),
+                        store_item_id: property.attribute("storeItemID"),
+                    });
+                },
+                "text" => control.control_type = ContentControlType::PlainText,
+                "comboBox" | "dropDownList" => {
+                    control.control_type = if property.local_name() == "comboBox" {
+                        ContentControlType::ComboBox
+                    } else {
+                        ContentControlType::DropDownList
+                    };
+                    control.list_items = property
+                        .elements()
+                        .filter(|item| item.local_name() == "listItem")
+                        .map(|item| {
+                            let value = item.attribute("value").unwrap_or_default();
+                            ListItem {
+                                display_text: item
+                                    .attribute("displayText")
+                                    .unwrap_or(value.clone()),
+                                value,
+                            }
+                        })
+                        .collect();
+                },
+                "date" => {
+                    control.control_type = ContentControlType::Date;
+                    control.date = property.attribute("fullDate");
+                    control.date_format = property
+                        .child("dateFormat")
+                        .and_then(|format| format.attribute("val"));
+                },
+                "checkbox" => {
+                    control.control_type = ContentControlType::Checkbox;
+                    control.checked = Some(property.child("checked").is_some_and(is_on));
+                },
+                "picture" => control.control_type = ContentControlType::Picture,
+                "repeatingSection" => control.control_type = ContentControlType::RepeatingSection,
+                "repeatingSectionItem" => {
+                    control.control_type = ContentControlType::RepeatingSectionItem
+                },
+                "group" => control.control_type = ContentControlType::Group,
+                "docPartObj" | "docPartList" => {
+                    control.control_type = ContentControlType::BuildingBlock
+                },
+                "citation" => control.control_type = ContentControlType::Citation,
+                "bibliography" => control.control_type = ContentControlType::Bibliography,
+                "equation" => control.control_type = ContentControlType::Equation,
+                _ => {},
+            }
+        }
+    }
+
+    if let Some(content) = sdt.child("sdtContent") {
+        let mut paragraphs = 0;
+        push_text(content, &mut control.text, &mut paragraphs);
+    }
+    control
+}
+
+/// Append the text of an element, with paragraphs separated by newlines.
+fn push_text(element: &Element, out: &mut String, paragraphs: &mut usize) {
+    for child in element.elements() {
+        match child.local_name() {
+            "t" => out.push_str(&child.text()),
+            "tab" => out.push('\t'),
+            "br" | "cr" => out.push('\n'),
+            "p" => {
+                if *paragraphs > 0 {
+                    out.push('\n');
+                }
+                *paragraphs += 1;
+                push_text(child, out, paragraphs);
+            },
+            "sdtPr" | "pPr" | "rPr" | "tblPr" | "trPr" | "tcPr" => {},
+            _ => push_text(child, out, paragraphs),
+        }
+    }
+}
+
+fn level_in(container: &str, in_paragraph: bool) -> ContentControlLevel {
+    match container {
+        _ if in_paragraph => ContentControlLevel::Inline,
+        "tbl" => ContentControlLevel::Row,
+        "tr" => ContentControlLevel::Cell,
+        _ => ContentControlLevel::Block,
+    }
+}
+
+/// Read the content controls of a piece of document XML, parents before
+/// the controls nested in them.
+pub(crate) fn read(xml: &str) -> Result<Vec<ContentControl>> {
+    fn collect(
+        nodes: &[Node],
+        container: &str,
+        in_paragraph: bool,
+        parent: Option<usize>,
+        out: &mut Vec<ContentControl>,
+    ) {
+        for node in nodes {
+            let Node::Element(element) = node else {
+                continue;
+            };
+            let local_name = element.local_name();
+            if local_name == "sdt" {
+                let index = out.len();
+                out.push(read_control(
+                    element,
+                    level_in(container, in_paragraph),
+                    parent,
+                ));
+                if let Some(content) = element.child("sdtContent") {
+                    collect(&content.children, container, in_paragraph, Some(index), out);
+                }
+            } else {
+                let in_paragraph = in_paragraph || local_name == "p";
+                collect(&element.children, local_name, in_paragraph, parent, out);
+            }
+        }
+    }
+
+    let nodes = markup::parse(xml)?;
+    let mut controls = Vec::new();
+    collect(&nodes, "", false, None, &mut controls);
+    Ok(controls)
+}
+
+/// A new value for a content control.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum Value {
+    /// Text, a list item's value or display text, or an ISO 8601 date
+    Text(String),
+    /// Checked state of a checkbox
+    Checked(bool),
+}
+
+/// The result of setting content control values in a part.
+#[derive(Debug)]
+pub(crate) struct Update {
+    pub xml: String,
+    /// Number of controls set
+    pub count: usize,
+    /// Values to store in custom XML for the bound controls that were set
+    pub bound: Vec<(DataBinding, String)>,
+}
+
+/// Set the value of the content controls `value_for` returns one for.
+///
+/// Returns `None` when no control was set.
+pub(crate) fn update(
+    xml: &str,
+    mut value_for: impl FnMut(&ContentControl) -> Option<Value>,
+) -> Result<Option<Update>> {
+    fn visit(
+        nodes: &mut [Node],
+        container: &str,
+        in_paragraph: bool,
+        value_for: &mut dyn FnMut(&ContentControl) -> Option<Value>,
+        update: &mut Update,
+    ) -> Result<()> {
+        for node in nodes {
+            let Node::Element(element) = node else {
+                continue;
+            };
+            let local_name = element.local_name().to_string();
+            if local_name == "sdt" {
+                let control = read_control(element, level_in(container, in_paragraph), None);
+                if let Some(value) = value_for(&control) {
+                    let stored = set_value(element, &control, value)?;
+                    update.count += 1;
+                    if let Some(binding) = control.data_binding {
+                        update.bound.push((binding, stored));
+                    }
+                }
+                if let Some(content) = element.child_mut("sdtContent") {
+                    visit(
+                        &mut content.children,
+                        container,
+                        in_paragraph,
+                        value_for,
+                        update,
+                    )?;
+                }
+            } else {
+                let in_paragraph = in_paragraph || local_name == "p";
+                visit(
+                    &mut element.children,
+                    &local_name,
+                    in_paragraph,
+                    value_for,
+                    update,
+                )?;
+            }
+        }
+        Ok(())
+    }
+
+    if !xml.contains("sdt") {
+        return Ok(None);
+    }
+    let mut nodes = markup::parse(xml)?;
+    let mut update = Update {
+        xml: String::new(),
+        count: 0,
+        bound: Vec::new(),
+    };
+    visit(&mut nodes, "", false, &mut value_for, &mut update)?;
+    if update.count == 0 {
+        return Ok(None);
+    }
+    markup::write_nodes(&nodes, &mut update.xml);
+    Ok(Some(update))
+}
+
+/// Set the value of a content control, returning the value to store in
+/// custom XML.
+fn set_value(sdt: &mut Element, control: &ContentControl, value: Value) -> Result<String> {
+    let describe = || {
+        control
+            .tag
+            .as_deref()
+            .or(control.alias.as_deref())
+            .unwrap_or_default()
+            .to_string()
+    };
+    if control.level == ContentControlLevel::Row {
+        return Err(OoxmlError::InvalidFormat(format!(
+            "Cannot set the text of row content control {:?}",
+            describe()
+        )));
+    }
+    let (display, stored) = match (control.control_type, value) {
+        (ContentControlType::Checkbox, Value::Checked(checked)) => {
+            let symbol = set_checked(sdt, checked);
+            (symbol, checked.to_string())
+        },
+        (ContentControlType::ComboBox | ContentControlType::DropDownList, Value::Text(text)) => {
+            let (display, stored) = match control.list_item(&text) {
+                Some(item) => (item.display_text.clone(), item.value.clone()),
+                None if control.control_type == ContentControlType::ComboBox => {
+                    (text.clone(), text)
+                },
+                None => {
+                    return Err(OoxmlError::InvalidFormat(format!(
+                        "{:?} is not a choice of content control {:?}",
+                        text,
+                        describe()
+                    )));
+                },
+            };
+            let list_name = if control.control_type == ContentControlType::ComboBox {
+                "comboBox"
+            } else {
+                "dropDownList"
+            };
+            if let Some(list) =
+                properties_mut(sdt).and_then(|properties| properties.child_mut(list_name))
+            {
+                let name = format!("{}lastValue", prefix(&list.name));
+                set_attribute(list, &name, &stored);
+            }
+            (display, stored)
+        },
+        (ContentControlType::Date, Value::Text(text)) => {
+            let Some(date) = Date::parse(&text) else {
+                return Err(OoxmlError::InvalidFormat(format!(
+                    "{:?} is not an ISO 8601 date for content control {:?}",
+                    text,
+                    describe()
+                )));
+            };
+            let full_date = if text.contains('T') {
+                text.clone()
+            } else {
+                format!("{}T00:00:00Z", text)
+            };
+            if let Some(date) =
+                properties_mut(sdt).and_then(|properties| properties.child_mut("date"))
+            {
+                let name = format!("{}fullDate", prefix(&date.name));
+                set_attribute(date, &name, &full_date);
+            }
+            let display = match control.date_format.as_deref() {
+                Some(format) => date.format(format),
+                None => text.clone(),
+            };
+            (display, text)
+        },
+        (ContentControlType::RichText | ContentControlType::PlainText, Value::Text(text)) => {
+            (text.clone(), text)
+        },
+        (control_type, _) => {
+            return Err(OoxmlError::InvalidFormat(format!(
+                "Cannot set content control {:?} of type {:?} to this value",
+                describe(),
+                control_type
+            )));
+        },
+    };
+
+    if let Some(properties) = properties_mut(sdt) {
+        properties.children.retain(
+            |node| !matches!(node, Node::Element(child) if child.local_name() == "showingPlcHdr"),
+        );
+    }
+    set_content(
+        sdt,
+        &display,
+        control.level,
+        control.control_type != ContentControlType::RichText,
+    )?;
+    Ok(stored)
+}
+
+fn properties_mut(sdt: &mut Element) -> Option<&mut Element> {
+    sdt.child_mut("sdtPr")
+}
+
+/// Namespace prefix of a qualified name, with its colon.
+fn prefix(name: &str) -> &str {
+    name.rfind(':').map_or("", |colon| &name[..=colon])
+}
+
+/// Set the checked state of a checkbox, returning the symbol to show.
+fn set_checked(sdt: &mut Element, checked: bool) -> String {
+    let Some(checkbox) =
+        properties_mut(sdt).and_then(|properties| properties.child_mut("checkbox"))
+    else {
+        return String::new();
+    };
+    let ns = prefix(&checkbox.name).to_string();
+    if checkbox.child("checked").is_none() {
+        checkbox
+            .children
+            .insert(0, Node::Element(empty_element(&format!("{}checked", ns))));
+    }
+    if let Some(state) = checkbox.child_mut("checked") {
+        set_attribute(
+            state,
+            &format!("{}val", ns),
+            if checked { "1" } else { "0" },
+        );
+    }
+
+    // Word's defaults are a ballot box with and without an X
+    let state = checkbox.child(if checked {
+        "checkedState"
+    } else {
+        "uncheckedState"
+    });
+    let code = state
+        .and_then(|state| state.attribute("val"))
+        .and_then(|val| u32::from_str_radix(&val, 16).ok())
+        .unwrap_or(if checked { 0x2612 } else { 0x2610 });
+    char::from_u32(code).map(String::from).unwrap_or_default()
+}
+
+fn empty_element(name: &str) -> Element {
+    Element {
+        name: name.to_string(),
+        start: format!("<{}/>", name),
+        end: String::new(),
+        children: Vec::new(),
+    }
+}
+
+/// Replace the content of a control with text, keeping the formatting of
+/// its first paragraph and run.
+fn set_content(
+    sdt: &mut Element,
+    text: &str,
+    level: ContentControlLevel,
+    single_paragraph: bool,
+) -> Result<()> {
+    let ns = prefix(&sdt.name).to_string();
+    if sdt.child("sdtContent").is_none() {
+        let name = format!("{}sdtContent", ns);
+        sdt.children.push(Node::Element(Element {
+            start: format!("<{}>", name),
+            end: format!("</{}>", name),
+            name,
+            children: Vec::new(),
+        }));
+    }
+    let Some(content) = sdt.child_mut("sdtContent") else {
+        return Ok(());
+    };
+
+    let markup = match level {
+        ContentControlLevel::Inline => run_markup(&ns, descendant(content, "r"), text),
+        ContentControlLevel::Block => paragraphs_markup(&ns, content, text, single_paragraph),
+        ContentControlLevel::Cell => {
+            if let Some(cell) = content.child_mut("tc") {
+                let markup = paragraphs_markup(&ns, cell, text, single_paragraph);
+                // Keep the cell properties
+                cell.children.retain(
+                    |node| matches!(node, Node::Element(child) if child.local_name() == "tcPr"),
+                );
+                cell.children.extend(markup::parse(&markup)?);
+            }
+            return Ok(());
+        },
+        ContentControlLevel::Row => return Ok(()),
+    };
+    content.children = markup::parse(&markup)?;
+    Ok(())
+}
+
+/// Markup for a run with text, formatted like `template`.
+fn run_markup(ns: &str, template: Option<&Element>, text: &str) -> String {
+    let mut markup = format!("<{}r>", ns);
+    if let Some(properties) = template.and_then(|run| run.child("rPr")) {
+        let mut properties = properties.clone();
+        // The placeholder style doesn't apply to a value
+        properties.children.retain(|node| {
+            !matches!(node, Node::Element(child) if child.local_name() == "rStyle"
+                && child.attribute("val").as_deref() == Some("PlaceholderText"))
+        });
+        properties.write(&mut markup);
+    }
+    let lines: Vec<String> = text
+        .replace("\r\n", "\n")
+        .split('\n')
+        .map(|line| format!("<{ns}t xml:space=\"preserve\">{}</{ns}t>", escape(line)))
+        .collect();
+    markup.push_str(&lines.join(&format!("<{}br/>", ns)));
+    markup.push_str(&format!("</{}r>", ns));
+    markup
+}
+
+/// Markup for paragraphs with text, formatted like the first paragraph
+/// of `container`.
+fn paragraphs_markup(ns: &str, container: &Element, text: &str, single_paragraph: bool) -> String {
+    let template = descendant(container, "p");
+    let mut paragraph_start = format!("<{}p>", ns);
+    if let Some(properties) = template.and_then(|paragraph| paragraph.child("pPr")) {
+        properties.write(&mut paragraph_start);
+    }
+    let run = template.and_then(|paragraph| descendant(paragraph, "r"));
+
+    let text = text.replace("\r\n", "\n");
+    let lines: Vec<&str> = if single_paragraph {
+        vec![&text]
+    } else {
+        text.split('\n').collect()
+    };
+    lines
+        .into_iter()
+        .map(|line| format!("{}{}</{}p>", paragraph_start, run_markup(ns, run, line), ns))
+        .collect()
+}
+
+/// A calendar date, from an ISO 8601 date or date-time.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Date {
+    year: u32,
+    month: u32,
+    day: u32,
+}
+
+impl Date {
+    /// Parse `yyyy-MM-dd`, optionally followed by `Thh:mm:ss`, fractional
+    /// seconds and a `Z` or `+hh:mm` time zone.
+    fn parse(value: &str) -> Option<Self> {
+        fn number(digits: &str, len: usize) -> Option<u32> {
+            if digits.len() != len || !digits.bytes().all(|b| b.is_ascii_digit()) {
+                return None;
+            }
+            digits.parse().ok()
+        }
+
+        let (date, time) = match value.split_once('T') {
+            Some((date, time)) => (date, Some(time)),
+            None => (value, None),
+        };
+        let mut parts = date.split('-');
+        let year = number(parts.next()?, 4)?;
+        let month = number(parts.next()?, 2)?;
+        let day = number(parts.next()?, 2)?;
+        if parts.next().is_some() || year == 0 || !(1..=12).contains(&month) {
+            return None;
+        }
+        let leap = year % 4 == 0 && (year % 100 != 0 || year % 400 == 0);
+        let days_in_month = match month {
+            2 if leap => 29,
+            2 => 28,
+            4 | 6 | 9 | 11 => 30,
+            _ => 31,
+        };
+        if !(1..=days_in_month).contains(&day) {
+            return None;
+        }
+
+        if let Some(time) = time {
+            let (time, zone) = match time.find(['Z', '+', '-']) {
+                Some(index) => time.split_at(index),
+                None => (time, ""),
+            };
+            let (time, fraction) = match time.split_once('.') {
+                Some((time, fraction)) => (time, Some(fraction)),
+                None => (time, None),
+            };
+            let mut fields = time.split(':');
+            let hour = number(fields.next()?, 2)?;
+            let minute = number(fields.next()?, 2)?;
+            let second = number(fields.next()?, 2)?;
+            if fields.next().is_some() || hour > 23 || minute > 59 || second > 59 {
+                return None;
+            }
+            if fraction.is_some_and(|f| f.is_empty() || !f.bytes().all(|b| b.is_ascii_digit())) {
+                return None;
+            }
+            if let Some(offset) = zone.strip_prefix(['+', '-']) {
+                let (hours, minutes) = offset.split_once(':')?;
+                if number(hours, 2)? > 14 || number(minutes, 2)? > 59 {
+                    return None;
+                }
+            } else if !zone.is_empty() && zone != "Z" {
+                return None;
+            }
+        }
+        Some(Date { year, month, day })
+    }
+
+    /// Day of the week, 0 for Sunday, by Sakamoto's method.
+    fn weekday(&self) -> usize {
+        const OFFSETS: [i64; 12] = [0, 3, 2, 5, 0, 3, 5, 1, 4, 6, 2, 4];
+        let (month, day) = (self.month as i64, self.day as i64);
+        let year = self.year as i64 - i64::from(month < 3);
+        let days = year + year / 4 - year / 100 + year / 400 + OFFSETS[month as usize - 1] + day;
+        days.rem_euclid(7) as usize
+    }
+
+    /// Format the date with a Word date picker format such as `M/d/yyyy`
+    /// or `dddd, MMMM d, yyyy`.
+    fn format(&self, format: &str) -> String {
+        const MONTHS: [&str; 12] = [
+            "January",
+            "February",
+            "March",
+            "April",
+            "May",
+            "June",
+            "July",
+            "August",
+            "September",
+            "October",
+            "November",
+            "December",
+        ];
+        const DAYS: [&str; 7] = [
+            "Sunday",
+            "Monday",
+            "Tuesday",
+            "Wednesday",
+            "Thursday",
+            "Friday",
+            "Saturday",
+        ];
+
+        let Date { year, month, day } = *self;
+        let month_name = MONTHS[month as usize - 1];
+        let day_name = DAYS[self.weekday()];
+
+        let mut out = String::new();
+        let chars: Vec<char> = format.chars().collect();
+        let mut i = 0;
+        while i < chars.len() {
+            let c = chars[i];
+            if c == '\'' {
+                // Quoted literal text
+                i += 1;
+                while i < chars.len() && chars[i] != '\'' {
+                    out.push(chars[i]);
+                    i += 1;
+                }
+                i += 1;
+                continue;
+            }
+            let mut count = 1;
+            while i + count < chars.len() && chars[i + count] == c {
+                count += 1;
+            }
+            match (c, count) {
+                ('y', 1 | 2) => out.push_str(&format!("{:02}", year % 100)),
+                ('y', _) => out.push_str(&year.to_string()),
+                ('M', 1) => out.push_str(&month.to_string()),
+                ('M', 2) => out.push_str(&format!("{:02}", month)),
+                ('M', 3) => out.push_str(&month_name[..3]),
+                ('M', _) => out.push_str(month_name),
+                ('d', 1) => out.push_str(&day.to_string()),
+                ('d', 2) => out.push_str(&format!("{:02}", day)),
+                ('d', 3) => out.push_str(&day_name[..3]),
+                ('d', _) => out.push_str(day_name),
+                _ => out.extend(std::iter::repeat_n(c, count)),
+            }
+            i += count;
+        }
+        out
+    }
+}
+
+/// Check if a value is an ISO 8601 date or date-time a date picker takes.
+pub(crate) fn is_date(value: &str) -> bool {
+    Date::parse(value).is_some()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BODY: &str = "<w:body>\
+        <w:sdt><w:sdtPr><w:alias w:val=\"Customer\"/><w:tag w:val=\"customer\"/><w:id w:val=\"1\"/>\
+        <w:showingPlcHdr/><w:dataBinding w:prefixMappings=\"xmlns:ns0='urn:invoice'\" \
+        w:xpath=\"/ns0:invoice[1]/ns0:customer[1]\" w:storeItemID=\"{1234}\"/><w:text/></w:sdtPr>\
+        <w:sdtContent><w:p><w:pPr><w:jc w:val=\"center\"/></w:pPr><w:r><w:rPr>\
+        <w:rStyle w:val=\"PlaceholderText\"/><w:b/></w:rPr><w:t>Click here</w:t></w:r></w:p></w:sdtContent></w:sdt>\
+        <w:p><w:r><w:t>Status: </w:t></w:r><w:sdt><w:sdtPr><w:tag w:val=\"status\"/>\
+        <w:dropDownList w:lastValue=\"o\"><w:listItem w:displayText=\"Open\" w:value=\"o\"/>\
+        <w:listItem w:displayText=\"Closed\" w:value=\"c\"/></w:dropDownList></w:sdtPr>\
+        <w:sdtContent><w:r><w:t>Open</w:t></w:r></w:sdtContent></w:sdt>\
+        <w:sdt><w:sdtPr><w:tag w:val=\"paid\"/><w14:checkbox><w14:checked w14:val=\"0\"/>\
+        <w14:checkedState w14:val=\"2612\" w14:font=\"MS Gothic\"/>\
+        <w14:uncheckedState w14:val=\"2610\" w14:font=\"MS Gothic\"/></w14:checkbox></w:sdtPr>\
+        <w:sdtContent><w:r><w:rPr><w:rFonts w:ascii=\"MS Gothic\"/></w:rPr><w:t>\u{2610}</w:t></w:r>\
+        </w:sdtContent></w:sdt>\
+        <w:sdt><w:sdtPr><w:tag w:val=\"due\"/><w:date w:fullDate=\"2024-03-01T00:00:00Z\">\
+        <w:dateFormat w:val=\"dddd, MMMM d, yyyy\"/></w:date></w:sdtPr>\
+        <w:sdtContent><w:r><w:t>Friday, March 1, 2024</w:t></w:r></w:sdtContent></w:sdt></w:p>\
+        <w:tbl><w:sdt><w:sdtPr><w:tag w:val=\"lines\"/><w15:repeatingSection/></w:sdtPr><w:sdtContent>\
+        <w:sdt><w:sdtPr><w15:repeatingSectionItem/></w:sdtPr><w:sdtContent><w:tr><w:tc><w:p><w:r>\
+        <w:t>Widget</w:t></w:r></w:p></w:tc></w:tr></w:sdtContent></w:sdt></w:sdtContent></w:sdt></w:tbl>\
+        </w:body>";
+
+    #[test]
+    fn test_read() {
+        let controls = read(BODY).unwrap();
+        assert_eq!(controls.len(), 6);
+
+        let customer = &controls[0];
+        assert_eq!(customer.tag.as_deref(), Some("customer"));
+        assert_eq!(customer.alias.as_deref(), Some("Customer"));
+        assert_eq!(customer.control_type, ContentControlType::PlainText);
+        assert_eq!(customer.level, ContentControlLevel::Block);
+        assert!(customer.showing_placeholder);
+        assert_eq!(customer.value(), None);
+        let binding = customer.data_binding.as_ref().unwrap();
+        assert_eq!(binding.xpath, "/ns0:invoice[1]/ns0:customer[1]");
+        assert_eq!(binding.store_item_id.as_deref(), Some("{1234}"));
+
+        let status = &controls[1];
+        assert_eq!(status.control_type, ContentControlType::DropDownList);
+        assert_eq!(status.level, ContentControlLevel::Inline);
+        assert_eq!(status.list_items.len(), 2);
+        assert_eq!(status.value().as_deref(), Some("o"));
+
+        assert_eq!(controls[2].checked, Some(false));
+        assert_eq!(controls[3].date.as_deref(), Some("2024-03-01T00:00:00Z"));
+
+        assert_eq!(
+            controls[4].control_type,
+            ContentControlType::RepeatingSection
+        );
+        assert_eq!(controls[4].level, ContentControlLevel::Row);
+        assert_eq!(
+            controls[5].control_type,
+            ContentControlType::RepeatingSectionItem
+        );
+        assert_eq!(controls[5].parent, Some(4));
+        assert_eq!(controls[5].text, "Widget");
+    }
+
+    #[test]
+    fn test_update() {
+        let result = update(BODY, |control| match control.tag.as_deref()? {
+            "customer" => Some(Value::Text("ACME & Co.".to_string())),
+            "status" => Some(Value::Text("Closed".to_string())),
+            "paid" => Some(Value::Checked(true)),
+            "due" => Some(Value::Text("2024-12-25".to_string())),
+            _ => None,
+        })
+        .unwrap()
+        .unwrap();
+        assert_eq!(result.count, 4);
+        assert_eq!(result.bound.len(), 1);
+        assert_eq!(result.bound[0].1, "ACME & Co.");
+
+        let controls = read(&result.xml).unwrap();
+        assert!(!controls[0].showing_placeholder);
+        assert_eq!(controls[0].text, "ACME & Co.");
+        assert!(result.xml.contains(
+            "<w:sdtContent><w:p><w:pPr><w:jc w:val=\"center\"/></w:pPr><w:r><w:rPr><w:b/></w:rPr>"
+        ));
+        assert_eq!(controls[1].text, "Closed");
+        assert_eq!(controls[1].value().as_deref(), Some("c"));
+        assert_eq!(controls[2].checked, Some(true));
+        assert_eq!(controls[2].text, "\u{2612}");
+        assert_eq!(controls[3].text, "Wednesday, December 25, 2024");
+        assert_eq!(controls[3].date.as_deref(), Some("2024-12-25T00:00:00Z"));
+
+        let invalid = update(BODY, |control| {
+            (control.tag.as_deref() == Some("status")).then(|| Value::Text("Pending".to_string()))
+        });
+        assert!(invalid.is_err());
+    }
+
+    #[test]
+    fn test_dates() {
+        for value in [
+            "not a date",
+            "0000-01-05",
+            "2023-02-29",
+            "2024-13-01",
+            "2024-1-05",
+            "2024-01-05T25:00:00Z",
+            "2024-01-05T10:00:00Q",
+        ] {
+            assert!(Date::parse(value).is_none(), "{}", value);
+            let result = update(BODY, |control| {
+                (control.tag.as_deref() == Some("due")).then(|| Value::Text(value.to_string()))
+            });
+            assert!(result.is_err(), "{}", value);
+        }
+        for value in [
+            "2024-02-29",
+            "2024-02-29T10:30:00Z",
+            "2024-02-29T10:30:00.250+01:00",
+        ] {
+            assert!(Date::parse(value).is_some(), "{}", value);
+        }
+
+        let first = Date::parse("0001-01-01").unwrap();
+        assert_eq!(first.format("dddd d MMM yy"), "Monday 1 Jan 01");
+        let result = update(BODY, |control| {
+            (control.tag.as_deref() == Some("due"))
+                .then(|| Value::Text("2025-06-30T08:00:00Z".to_string()))
+        })
+        .unwrap()
+        .unwrap();
+        let controls = read(&result.xml).unwrap();
+        assert_eq!(controls[3].date.as_deref(), Some("2025-06-30T08:00:00Z"));
+        assert_eq!(controls[3].text, "Monday, June 30, 2025");
+    }
+}
@@ -0,0 +1,422 @@
+/// Custom XML data parts in Word documents.
+///
+/// Custom XML parts are stored as `/customXml/itemN.xml`, related to the
+/// main document part. Each has a `/customXml/itemPropsN.xml` part holding
+/// a `<ds:datastoreItem ds:itemID="{...}">` with the GUID that content
+/// control data bindings use as their `w:storeItemID`, and the schemas the
+/// data follows.
+///
+/// Data bindings locate their node with a simple XPath such as
+/// `/ns0:invoice[1]/ns0:customer[1]/@id`, where prefixes are declared in
+/// the binding's `w:prefixMappings`. Only such paths of child steps with
+/// optional positions are supported.
+use crate::ooxml::common::markup::{self, Element, Node};
+use crate::ooxml::common::xml::attribute;
+use crate::ooxml::docx::content_control::DataBinding;
+use crate::ooxml::error::{OoxmlError, Result};
+use quick_xml::Reader;
+use quick_xml::escape::escape;
+use quick_xml::events::{BytesStart, Event};
+use std::collections::HashMap;
+
+/// Namespace of `itemProps` parts.
+const DATASTORE_NS: &str = "http://schemas.openxmlformats.org/officeDocument/2006/customXml";
+
+/// A custom XML data part.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CustomXmlPart {
+    /// Part name, e.g. `/customXml/item1.xml`
+    pub partname: String,
+    /// Data store item ID (`ds:itemID`), matched by the `storeItemID` of
+    /// data bindings
+    pub item_id: Option<String>,
+    /// Namespaces of the schemas the data follows
+    pub schemas: Vec<String>,
+    /// The XML data
+    pub xml: String,
+}
+
+impl CustomXmlPart {
+    /// Check if a data binding refers to this part.
+    pub fn is_bound_by(&self, binding: &DataBinding) -> bool {
+        match (&self.item_id, &binding.store_item_id) {
+            (Some(item_id), Some(store_item_id)) => item_id.eq_ignore_ascii_case(store_item_id),
+            _ => false,
+        }
+    }
+
+    /// Get the text of the node a data binding points to.
+    ///
+    /// Returns `None` when the path matches nothing or isn't supported.
+    pub fn value(&self, binding: &DataBinding) -> Option<String> {
+        select(&self.xml, binding)
+    }
+}
+
+/// Read the item ID and schema namespaces from an `itemProps` part.
+pub(crate) fn parse_properties(xml: &[u8]) -> Result<(Option<String>, Vec<String>)> {
+    let mut reader = Reader::from_reader(xml);
+    let mut item_id = None;
+    let mut schemas = Vec::new();
+    let mut buf = Vec::new();
+
+    loop {
+        match reader
+            .read_event_into(&mut buf)
+            .map_err(|e| OoxmlError::Xml(e.to_string()))?
+        {
+            Event::Start(ref e) | Event::Empty(ref e) => match e.local_name().as_ref() {
+                b"datastoreItem" => item_id = attribute(e, b"itemID"),
+                b"schemaRef" => schemas.extend(attribute(e, b"uri")),
+                _ => {},
+            },
+            Event::Eof => break,
+            _ => {},
+        }
+        buf.clear();
+    }
+    Ok((item_id, schemas))
+}
+
+/// Generate an `itemProps` part.
+pub(crate) fn properties_xml(item_id: &str, schemas: &[&str]) -> String {
+    let schema_refs: String = schemas
+        .iter()
+        .map(|uri| format!("<ds:schemaRef ds:uri=\"{}\"/>", escape(*uri)))
+        .collect();
+    format!(
+        "<?xml version=\"1.0\" encoding=\"UTF-8\" standalone=\"no\"?>\n\
+         <ds:datastoreItem ds:itemID=\"{}\" xmlns:ds=\"{}\">\
+         <ds:schemaRefs>{}</ds:schemaRefs></ds:datastoreItem>",
+        escape(item_id),
+        DATASTORE_NS,
+        schema_refs
+    )
+}
+
+/// One step of a binding path.
+struct Step {
+    prefix: Option<String>,
+    local_name: String,
+    /// 1-based position among matching siblings
+    position: usize,
+    attribute: bool,
+}
+
+fn parse_path(xpath: &str) -> Option<Vec<Step>> {
+    let steps: Vec<Step> = xpath
+        .trim()
+        .strip_prefix('/')?
+        .split('/')
+        .map(|step| {
+            let (step, attribute) = match step.strip_prefix('@') {
+                Some(name) => (name, true),
+                None => (step, false),
+            };
+            let (name, position) = match step.split_once('[') {
+                Some((name, predicate)) => {
+                    (name, predicate.strip_suffix(']')?.trim().parse().ok()?)
+                },
+                None => (step, 1),
+            };
+            let (prefix, local_name) = match name.split_once(':') {
+                Some((prefix, local_name)) => (Some(prefix.to_string()), local_name),
+                None => (None, name),
+            };
+            if local_name.is_empty() || position == 0 {
+                return None;
+            }
+            Some(Step {
+                prefix,
+                local_name: local_name.to_string(),
+                position,
+                attribute,
+            })
+        })
+        .collect::<Option<_>>()?;
+    // Only the last step may name an attribute
+    let last = steps.len().checked_sub(1)?;
+    if steps[..last].iter().any(|step| step.attribute) {
+        return None;
+    }
+    Some(steps)
+}
+
+/// Parse `xmlns:ns0='...' xmlns:ns1='...'` prefix mappings.
+fn parse_prefix_mappings(mappings: &str) -> HashMap<String, String> {
+    let mut result = HashMap::new();
+    let mut rest = mappings;
+    while let Some(start) = rest.find("xmlns:") {
+        rest = &rest[start + "xmlns:".len()..];
+        let Some((prefix, value)) = rest.split_once('=') else {
+            break;
+        };
+        let value = value.trim_start();
+        let Some(quote) = value.chars().next().filter(|c| *c == '\'' || *c == '"') else {
+            break;
+        };
+        let Some(end) = value[1..].find(quote) else {
+            break;
+        };
+        result.insert(prefix.trim().to_string(), value[1..=end].to_string());
+        rest = &value[end + 2..];
+    }
+    result
+}
+
+/// Namespace declarations in scope while walking the tree.
+#[derive(Clone, Default)]
+struct Namespaces {
+    default: Option<String>,
+    prefixed: HashMap<String, String>,
+}
+
+impl Namespaces {
+    /// Add the declarations of an element's start tag.
+    fn declare(&mut self, element: &Element) {
+        let mut reader = Reader::from_str(&element.start);
+        let (Ok(Event::Start(start)) | Ok(Event::Empty(start))) = reader.read_event() else {
+            return;
+        };
+        for attr in start.attributes().flatten() {
+            let Ok(value) = attr.unescape_value() else {
+                continue;
+            };
+            let key = String::from_utf8_lossy(attr.key.as_ref());
+            if key == "xmlns" {
+                self.default = Some(value.into_owned());
+            } else if let Some(prefix) = key.strip_prefix("xmlns:") {
+                self.prefixed.insert(prefix.to_string(), value.into_owned());
+            }
+        }
+    }
+
+    fn resolve(&self, qualified_name: &str) -> Option<&str> {
+        match qualified_name.split_once(':') {
+            Some((prefix, _)) => self.prefixed.get(prefix).map(String::as_str),
+            None => self.default.as_deref(),
+        }
+    }
+}
+
+/// A path resolved against a tree, as child indices from the top.
+struct Target {
+    path: Vec<usize>,
+    attribute: Option<String>,
+}
+
+fn resolve(nodes: &[Node], binding: &DataBinding) -> Option<Target> {
+    let steps = parse_path(&binding.xpath)?;
+    let mappings = parse_prefix_mappings(&binding.prefix_mappings);
+    let matches = |step: &Step, name: &str, namespace: Option<&str>| {
+        let local_name = name.rsplit(':').next().unwrap_or(name);
+        local_name == step.local_name
+            && match step.prefix.as_ref().and_then(|prefix| mappings.get(prefix)) {
+                Some(uri) => namespace == Some(uri.as_str()),
+                None => true,
+            }
+    };
+
+    let mut namespaces = Namespaces::default();
+    let mut children = nodes;
+    let mut path = Vec::new();
+    let mut current: Option<&Element> = None;
+    for step in &steps {
+        if step.attribute {
+            let element = current?;
+            let mut reader = Reader::from_str(&element.start);
+            let (Ok(Event::Start(start)) | Ok(Event::Empty(start))) = reader.read_event() else {
+                return None;
+            };
+            let name = start
+                .attributes()
+                .flatten()
+                .map(|attr| String::from_utf8_lossy(attr.key.as_ref()).into_owned())
+                .filter(|name| !name.starts_with("xmlns"))
+                .find(|name| {
+                    // Unprefixed attributes have no namespace
+                    let namespace = name
+                        .contains(':')
+                        .then(|| namespaces.resolve(name))
+                        .flatten();
+                    matches(step, name, namespace)
+                })?;
+            return Some(Target {
+                path,
+                attribute: Some(name),
+            });
+        }
+
+        let mut seen = 0;
+        let (index, element, scope) = children.iter().enumerate().find_map(|(index, node)| {
+            let Node::Element(element) = node else {
+                return None;
+            };
+            let mut scope = namespaces.clone();
+            scope.declare(element);
+            if !matches(step, &element.name, scope.resolve(&element.name)) {
+                return None;
+            }
+            seen += 1;
+            (seen == step.position).then_some((index, element, scope))
+        })?;
+        path.push(index);
+        namespaces = scope;
+        children = &element.children;
+        current = Some(element);
+    }
+    Some(Target {
+        path,
+        attribute: None,
+    })
+}
+
+fn element_at<'a>(nodes: &'a mut [Node], path: &[usize]) -> Option<&'a mut Element> {
+    let (first, rest) = path.split_first()?;
+    let Node::Element(element) = nodes.get_mut(*first)? else {
+        return None;
+    };
+    if rest.is_empty() {
+        Some(element)
+    } else {
+        element_at(&mut element.children, rest)
+    }
+}
+
+/// Get the text of an element and its descendants.
+fn inner_text(element: &Element) -> String {
+    let mut text = element.text();
+    for child in element.elements() {
+        text.push_str(&inner_text(child));
+    }
+    text
+}
+
+/// Get the text of the node a data binding points to.
+pub(crate) fn select(xml: &str, binding: &DataBinding) -> Option<String> {
+    let mut nodes = markup::parse(xml).ok()?;
+    let target = resolve(&nodes, binding)?;
+    let element = element_at(&mut nodes, &target.path)?;
+    match target.attribute {
+        Some(name) => attribute_value(element, &name),
+        None => Some(inner_text(element)),
+    }
+}
+
+fn attribute_value(element: &Element, qualified_name: &str) -> Option<String> {
+    let mut reader = Reader::from_str(&element.start);
+    let (Ok(Event::Start(start)) | Ok(Event::Empty(start))) = reader.read_event() else {
+        return None;
+    };
+    start
+        .attributes()
+        .flatten()
+        .find(|attr| attr.key.as_ref() == qualified_name.as_bytes())
+        .and_then(|attr| attr.unescape_value().ok())
+        .map(|value| value.into_owned())
+}
+
+/// Set the text of the node a data binding points to.
+///
+/// Returns `None` when the path matches nothing.
+pub(crate) fn set_value(xml: &str, binding: &DataBinding, value: &str) -> Result<Option<String>> {
+    let mut nodes = markup::parse(xml)?;
+    let Some(target) = resolve(&nodes, binding) else {
+        return Ok(None);
+    };
+    let Some(element) = element_at(&mut nodes, &target.path) else {
+        return Ok(None);
+    };
+    match target.attribute {
+        Some(name) => set_attribute(element, &name, value),
+        None => {
+            // Open an empty element here so its attributes are kept
+            if element.end.is_empty() {
+                element.start = format!("{}>", element.start.trim_end_matches("/>").trim_end());
+                element.end = format!("</{}>", element.name);
+            }
+            element.set_text(value, false, None);
+        },
+    }
+
+    let mut out = String::with_capacity(xml.len());
+    markup::write_nodes(&nodes, &mut out);
+    Ok(Some(out))
+}
+
+/// Set an attribute of an element's start tag by qualified name, keeping
+/// the other attributes.
+pub(crate) fn set_attribute(element: &mut Element, qualified_name: &str, value: &str) {
+    let mut reader = Reader::from_str(&element.start);
+    let (Ok(Event::Start(start)) | Ok(Event::Empty(start))) = reader.read_event() else {
+        return;
+    };
+    let mut updated = BytesStart::new(element.name.as_str());
+    for attr in start.attributes().flatten() {
+        if attr.key.as_ref() != qualified_name.as_bytes() {
+            updated.push_attribute(attr);
+        }
+    }
+    updated.push_attribute((qualified_name, value));
+
+    let markup = String::from_utf8_lossy(&updated).into_owned();
+    element.start = if element.end.is_empty() {
+        format!("<{}/>", markup)
+    } else {
+        format!("<{}>", markup)
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn binding(xpath: &str) -> DataBinding {
+        DataBinding {
+            xpath: xpath.to_string(),
+            prefix_mappings: "xmlns:ns0='urn:invoice'".to_string(),
+            store_item_id: Some("{1234}".to_string()),
+        }
+    }
+
+    #[test]
+    fn test_select_and_set() {
+        let xml = "<?xml version=\"1.0\"?><invoice xmlns=\"urn:invoice\" id=\"7\">\
+            <customer>ACME</customer><line>a</line><line>b</line><note/></invoice>";
+
+        assert_eq!(
+            select(xml, &binding("/ns0:invoice[1]/ns0:customer[1]")).as_deref(),
+            Some("ACME")
+        );
+        assert_eq!(
+            select(xml, &binding("/ns0:invoice[1]/ns0:line[2]")).as_deref(),
+            Some("b")
+        );
+        assert_eq!(
+            select(xml, &binding("/ns0:invoice[1]/@id")).as_deref(),
+            Some("7")
+        );
+        assert_eq!(select(xml, &binding("/ns0:invoice[1]/ns0:line[3]")), None);
+
+        let updated = set_value(xml, &binding("/ns0:invoice/ns0:note"), "R&D")
+            .unwrap()
+            .unwrap();
+        assert!(updated.contains("<note>R&amp;D</note>"));
+        let updated = set_value(&updated, &binding("/ns0:invoice/@id"), "8")
+            .unwrap()
+            .unwrap();
+        assert!(updated.contains("<invoice xmlns=\"urn:invoice\" id=\"8\">"));
+        assert_eq!(
+            set_value(xml, &binding("/ns0:invoice/ns0:missing"), "x").unwrap(),
+            None
+        );
+    }
+
+    #[test]
+    fn test_properties() {
+        let xml = properties_xml("{ABCD}", &["urn:invoice"]);
+        let (item_id, schemas) = parse_properties(xml.as_bytes()).unwrap();
+        assert_eq!(item_id.as_deref(), Some("{ABCD}"));
+        assert_eq!(schemas, vec!["urn:invoice"]);
+    }
+}
@@ -1,6 +1,14 @@
 /// Document - the main API for working with Word document content.
+use crate::common::{EmbeddedImage, ImageAnchor};
+use crate::ooxml::common::{ChartData, collect_charts, collect_pictures};
+use crate::ooxml::docx::comment::{self, Comment};
+use crate::ooxml::docx::content_control::{self, ContentControl};
+use crate::ooxml::docx::custom_xml::{self, CustomXmlPart};
+use crate::ooxml::docx::field::{self, Bookmark, Field, Hyperlink};
+use crate::ooxml::docx::outline::OutlineContext;
 use crate::ooxml::docx::paragraph::Paragraph;
 use crate::ooxml::docx::parts::DocumentPart;
+use crate::ooxml::docx::revision::{self, Revision, RevisionView};
 use crate::ooxml::docx::section::{Section, Sections};
 use crate::ooxml::docx::styles::Styles;
 use crate::ooxml::docx::table::Table;
@@ -9,6 +17,7 @@ use crate::ooxml::opc::OpcPackage;
 use crate::ooxml::opc::constants::relationship_type;
 use quick_xml::Reader;
 use quick_xml::events::Event;
+use std::sync::{Arc, OnceLock};
 
 /// A Word document.
 ///
@@ -38,6 +47,8 @@ pub struct Document<'a> {
     part: DocumentPart<'a>,
     /// Reference to the OPC package (needed for accessing related parts like styles)
     opc: &'a OpcPackage,
+    /// Styles, numbering and hyperlinks shared by the paragraphs, loaded on first use
+    outline: OnceLock<Arc<OutlineContext>>,
 }
 
 impl<'a> Document<'a> {
@@ -46,7 +57,19 @@ impl<'a> Document<'a> {
     /// This is typically called internally by `Package::document()`.
     #[inline]
     pub(crate) fn new(part: DocumentPart<'a>, opc: &'a OpcPackage) -> Self {
-        Self { part, opc }
+        Self {
+            part,
+            opc,
+            outline: OnceLock::new(),
+        }
+    }
+
+    /// Get the context paragraphs use to resolve their heading level, list
+    /// format and hyperlink targets.
+    fn outline(&self) -> Arc<OutlineContext> {
+        self.outline
+            .get_or_init(|| Arc::new(OutlineContext::load(self.opc).unwrap_or_default()))
+            .clone()
     }
 
     /// Get all text content from the document.
@@ -135,7 +158,13 @@ impl<'a> Document<'a> {
     /// ```
     pub fn paragraphs(&self) -> Result<Vec<Paragraph>> {
         // Convert SmallVec to Vec for API compatibility
-        Ok(self.part.paragraphs()?.into_iter().collect())
+        let outline = self.outline();
+        Ok(self
+            .part
+            .paragraphs()?
+            .into_iter()
+            .map(|para| para.with_outline(outline.clone()))
+            .collect())
     }
 
     /// Get all tables in the document.
@@ -201,7 +230,22 @@ impl<'a> Document<'a> {
     /// Uses a single-pass XML parser that is significantly faster than
     /// calling `paragraphs()` and `tables()` separately.
     pub fn elements(&self) -> Result<Vec<crate::document::DocumentElement>> {
-        self.part.elements()
+        use crate::document::{DocumentElement, Paragraph as UnifiedParagraph};
+
+        let outline = self.outline();
+        Ok(self
+            .part
+            .elements()?
+            .into_iter()
+            .map(|element| match element {
+                DocumentElement::Paragraph(UnifiedParagraph::Docx(para)) => {
+                    DocumentElement::Paragraph(UnifiedParagraph::Docx(
+                        para.with_outline(outline.clone()),
+                    ))
+                },
+                other => other,
+            })
+            .collect())
     }
 
     /// Get all sections in the document.
@@ -471,6 +515,283 @@ impl<'a> Document<'a> {
         Ok(self.table_count()? > 0)
     }
 
+    /// Get all fields in the document body, in the order they start.
+    ///
+    /// Covers `w:fldSimple` elements and complex fields (`w:fldChar` and
+    /// `w:instrText`), including nested fields and fields spanning
+    /// paragraphs, with their parsed instruction and cached result.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use litchi::ooxml::docx::{FieldType, Package};
+    ///
+    /// let pkg = Package::open("document.docx")?;
+    /// let doc = pkg.document()?;
+    ///
+    /// for field in doc.fields()? {
+    ///     if field.field_type == FieldType::Reference {
+    ///         println!("{:?} -> {}", field.bookmark(), field.display_text());
+    ///     }
+    /// }
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn fields(&self) -> Result<Vec<Field>> {
+        Ok(self.scan_markup()?.fields)
+    }
+
+    /// Get all hyperlinks in the document body.
+    ///
+    /// Covers `w:hyperlink` elements, with their relationship targets
+    /// resolved, and `HYPERLINK` fields.
+    pub fn hyperlinks(&self) -> Result<Vec<Hyperlink>> {
+        Ok(self.scan_markup()?.hyperlinks)
+    }
+
+    /// Get all bookmarks in the document body.
+    ///
+    /// Bookmark positions use the same paragraph indices as
+    /// [`paragraphs()`](Self::paragraphs), with character offsets into
+    /// each paragraph's text.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use litchi::ooxml::docx::Package;
+    ///
+    /// let pkg = Package::open("document.docx")?;
+    /// let doc = pkg.document()?;
+    ///
+    /// for bookmark in doc.bookmarks()? {
+    ///     println!("{} at paragraph {}: {}", bookmark.name, bookmark.start.paragraph, bookmark.text);
+    /// }
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn bookmarks(&self) -> Result<Vec<Bookmark>> {
+        Ok(self.scan_markup()?.bookmarks)
+    }
+
+    /// Get all comments on the document, in the order they are stored.
+    ///
+    /// Replies name the comment they answer in
+    /// [`parent_id`](Comment::parent_id), and resolved comments are
+    /// flagged, when the document has Word 2013 comment threads. Commented
+    /// ranges use the same paragraph indices as
+    /// [`paragraphs()`](Self::paragraphs).
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use litchi::ooxml::docx::Package;
+    ///
+    /// let pkg = Package::open("reviewed.docx")?;
+    /// let doc = pkg.document()?;
+    ///
+    /// for comment in doc.comments()? {
+    ///     let indent = if comment.is_reply() { "  " } else { "" };
+    ///     println!("{}{:?} on {:?}: {}", indent, comment.author, comment.anchor_text, comment.text);
+    /// }
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn comments(&self) -> Result<Vec<Comment>> {
+        let main_part = self.opc.main_document_part()?;
+        let related = |reltype: &str| {
+            let rel = main_part.rels().part_with_reltype(reltype).ok()?;
+            let target = rel.target_partname().ok()?;
+            self.opc.get_part(&target).ok().map(|part| part.blob())
+        };
+
+        let Some(comments) = related(relationship_type::COMMENTS) else {
+            return Ok(Vec::new());
+        };
+        comment::read_comments(
+            comments,
+            related(relationship_type::COMMENTS_EXTENDED),
+            related(relationship_type::COMMENTS_IDS),
+            &self.scan_markup()?.comment_ranges,
+        )
+    }
+
+    /// Get all content controls in the document body, in document order.
+    ///
+    /// Controls nested in another one, such as the items of a repeating
+    /// section, follow it and name it by index in
+    /// [`parent`](ContentControl::parent).
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use litchi::ooxml::docx::Package;
+    ///
+    /// let pkg = Package::open("form.docx")?;
+    /// let doc = pkg.document()?;
+    ///
+    /// for control in doc.content_controls()? {
+    ///     println!("{:?} ({:?}): {:?}", control.tag, control.control_type, control.value());
+    /// }
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn content_controls(&self) -> Result<Vec<ContentControl>> {
+        let xml = std::str::from_utf8(self.part.xml_bytes())
+            .map_err(|e| OoxmlError::Xml(format!("Invalid UTF-8 in document: {}", e)))?;
+        content_control::read(xml)
+    }
+
+    /// Get the custom XML data parts of the document, which content
+    /// controls can be bound to.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use litchi::ooxml::docx::Package;
+    ///
+    /// let pkg = Package::open("form.docx")?;
+    /// let doc = pkg.document()?;
+    /// let parts = doc.custom_xml_parts()?;
+    ///
+    /// for control in doc.content_controls()? {
+    ///     let Some(binding) = &control.data_binding else { continue };
+    ///     if let Some(part) = parts.iter().find(|part| part.is_bound_by(binding)) {
+    ///         println!("{:?} = {:?}", control.tag, part.value(binding));
+    ///     }
+    /// }
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn custom_xml_parts(&self) -> Result<Vec<CustomXmlPart>> {
+        let main_part = self.opc.main_document_part()?;
+        let mut parts = Vec::new();
+        for rel in main_part.rels().iter() {
+            if rel.is_external() || rel.reltype() != relationship_type::CUSTOM_XML {
+                continue;
+            }
+            let partname = rel.target_partname()?;
+            let Ok(part) = self.opc.get_part(&partname) else {
+                continue;
+            };
+
+            let properties = part
+                .rels()
+                .part_with_reltype(relationship_type::CUSTOM_XML_PROPS)
+                .ok()
+                .and_then(|rel| rel.target_partname().ok())
+                .and_then(|partname| self.opc.get_part(&partname).ok());
+            let (item_id, schemas) = match properties {
+                Some(properties) => custom_xml::parse_properties(properties.blob())?,
+                None => (None, Vec::new()),
+            };
+
+            let xml = String::from_utf8_lossy(part.blob());
+            parts.push(CustomXmlPart {
+                partname: partname.to_string(),
+                item_id,
+                schemas,
+                xml: xml.trim_start_matches('\u{feff}').to_string(),
+            });
+        }
+        parts.sort_by(|a, b| a.partname.cmp(&b.partname));
+        Ok(parts)
+    }
+
+    fn scan_markup(&self) -> Result<field::Markup> {
+        field::scan(self.part.xml_bytes(), Some(&self.outline()))
+    }
+
+    /// Get all tracked changes in the document body.
+    ///
+    /// Covers inserted, deleted and moved content, inserted and deleted
+    /// paragraph marks and table rows, and formatting changes. Each
+    /// revision records the paragraph it is in, using the same indices as
+    /// [`paragraphs()`](Self::paragraphs).
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use litchi::ooxml::docx::{Package, RevisionType};
+    ///
+    /// let pkg = Package::open("redline.docx")?;
+    /// let doc = pkg.document()?;
+    ///
+    /// for revision in doc.revisions()? {
+    ///     if revision.revision_type == RevisionType::Deletion {
+    ///         println!("{:?} deleted {:?}", revision.author, revision.text);
+    ///     }
+    /// }
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn revisions(&self) -> Result<Vec<Revision>> {
+        revision::revisions(self.part.xml_bytes())
+    }
+
+    /// Get the document text with tracked changes applied or undone.
+    ///
+    /// [`RevisionView::Final`] shows the text as if all changes were
+    /// accepted, [`RevisionView::Original`] as if all were rejected.
+    /// Paragraphs are separated by newlines, and a deleted paragraph mark
+    /// joins its paragraph to the next one.
+    pub fn text_with_view(&self, view: RevisionView) -> Result<String> {
+        revision::view_text(self.part.xml_bytes(), view)
+    }
+
+    /// Get all pictures placed in the document body.
+    ///
+    /// Each image is anchored to the paragraph containing its `w:drawing`
+    /// (or VML `v:imagedata`), using the same indices as
+    /// [`paragraphs()`](Self::paragraphs).
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use litchi::ooxml::docx::Package;
+    ///
+    /// let pkg = Package::open("document.docx")?;
+    /// let doc = pkg.document()?;
+    ///
+    /// for image in doc.images()? {
+    ///     println!("{} ({}) in {:?}", image.name, image.mime_type, image.anchor);
+    /// }
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn images(&self) -> Result<Vec<EmbeddedImage>> {
+        let part = self.opc.main_document_part()?;
+        collect_pictures(part, self.opc, |picture| {
+            picture
+                .paragraph
+                .map(ImageAnchor::Paragraph)
+                .unwrap_or(ImageAnchor::Unanchored)
+        })
+    }
+
+    /// Get all charts embedded in the main document.
+    ///
+    /// Charts are returned in document order. Each one is anchored to the
+    /// index of the paragraph holding its drawing, counted like
+    /// [`paragraphs()`](Self::paragraphs).
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use litchi::ooxml::docx::Package;
+    ///
+    /// let pkg = Package::open("report.docx")?;
+    /// let doc = pkg.document()?;
+    ///
+    /// for chart in doc.charts()? {
+    ///     for series in &chart.series {
+    ///         println!("{:?}: {:?}", series.name, series.values);
+    ///     }
+    /// }
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn charts(&self) -> Result<Vec<ChartData>> {
+        let part = self.opc.main_document_part()?;
+        collect_charts(part, self.opc, |frame| {
+            frame
+                .paragraph
+                .map(ImageAnchor::Paragraph)
+                .unwrap_or(ImageAnchor::Unanchored)
+        })
+    }
+
     /// Get the underlying OPC package reference.
     ///
     /// This provides access to low-level package operations.
@@ -554,7 +875,6 @@ impl<'a> Document<'a> {
     // TODO: Apache POI features not yet implemented:
     // - Headers and footers: add_header(), add_footer(), get_header(), get_footer()
     // - Bookmarks: add_bookmark(), get_bookmarks(), goto_bookmark()
-    // - Comments: add_comment(), get_comments(), reply_to_comment()
     // - Track changes: enable_track_changes(), get_revisions(), accept_revision(), reject_revision()
     // - Fields: insert_field(), update_fields(), get_fields()
     // - Hyperlinks (reading): get_hyperlinks(), follow_hyperlink()
@@ -0,0 +1,618 @@
+use crate::common::field::{arguments, hyperlink_target};
+/// Fields, hyperlinks and bookmarks in Word documents.
+///
+/// Fields are stored either as `<w:fldSimple w:instr="...">` around their
+/// result runs, or as complex fields: runs holding `<w:fldChar
+/// w:fldCharType="begin"/>`, the instruction in `<w:instrText>`, an
+/// optional `separate` character followed by the cached result, and an
+/// `end` character. Complex fields may nest and span paragraphs.
+///
+/// Bookmarks are delimited by `<w:bookmarkStart>` and `<w:bookmarkEnd>`
+/// elements sharing a `w:id`, which may also be in different paragraphs.
+/// Commented text is marked the same way, with `<w:commentRangeStart>` and
+/// `<w:commentRangeEnd>`.
+use crate::ooxml::common::xml::{attribute, push_text as push_event_text};
+use crate::ooxml::docx::outline::OutlineContext;
+use crate::ooxml::error::{OoxmlError, Result};
+use quick_xml::Reader;
+use quick_xml::events::{BytesStart, Event};
+
+/// Field type, from the first word of the instruction.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FieldType {
+    /// `HYPERLINK`
+    Hyperlink,
+    /// `REF` or a bare bookmark name
+    Reference,
+    /// `PAGEREF`
+    PageRef,
+    /// `PAGE`
+    Page,
+    /// `NUMPAGES` or `SECTIONPAGES`
+    NumPages,
+    /// `DATE`, `TIME`, `CREATEDATE`, `SAVEDATE` or `PRINTDATE`
+    Date,
+    /// `TOC`
+    Toc,
+    /// `SEQ`
+    Sequence,
+    /// `MERGEFIELD`
+    MergeField,
+    /// `EQ`
+    Equation,
+    /// `INDEX` or `XE`
+    Index,
+    /// `EMBED` (OLE object)
+    Embed,
+    /// Any other field
+    Other,
+}
+
+impl FieldType {
+    /// Get the field type for a field name (case-insensitive).
+    pub fn from_name(name: &str) -> Self {
+        match name.to_ascii_uppercase().as_str() {
+            "HYPERLINK" => FieldType::Hyperlink,
+            "REF" => FieldType::Reference,
+            "PAGEREF" => FieldType::PageRef,
+            "PAGE" => FieldType::Page,
+            "NUMPAGES" | "SECTIONPAGES" => FieldType::NumPages,
+            "DATE" | "TIME" | "CREATEDATE" | "SAVEDATE" | "PRINTDATE" => FieldType::Date,
+            "TOC" => FieldType::Toc,
+            "SEQ" => FieldType::Sequence,
+            "MERGEFIELD" => FieldType::MergeField,
+            "EQ" => FieldType::Equation,
+            "INDEX" | "XE" => FieldType::Index,
+            "EMBED" => FieldType::Embed,
+            _ => FieldType::Other,
+        }
+    }
+}
+
+/// A switch in a field instruction, such as `\h` or `\* MERGEFORMAT`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FieldSwitch {
+    /// Switch name without the backslash, e.g. `h`, `*` or `@`
+    pub name: String,
+    /// Argument following the switch, if any
+    pub argument: Option<String>,
+}
+
+/// A field parsed from a `w:fldSimple` element or a complex field.
+///
+/// # Examples
+///
+/// ```rust
+/// use litchi::ooxml::docx::{Field, FieldType};
+///
+/// let field = Field::parse(r#" DATE \@ "MMMM d, yyyy" \* MERGEFORMAT "#);
+/// assert_eq!(field.field_type, FieldType::Date);
+/// assert_eq!(field.switch("@").and_then(|s| s.argument.as_deref()), Some("MMMM d, yyyy"));
+/// assert!(field.has_switch("*"));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Field {
+    /// Field type
+    pub field_type: FieldType,
+    /// Field name as written, e.g. `HYPERLINK`
+    pub name: String,
+    /// Full field instruction
+    pub instruction: String,
+    /// Arguments between the field name and the first switch
+    pub arguments: Vec<String>,
+    /// Switches in instruction order
+    pub switches: Vec<FieldSwitch>,
+    /// Result Word last displayed for the field, if the field has one
+    pub result: Option<String>,
+}
+
+impl Field {
+    /// Parse a field instruction.
+    ///
+    /// A switch takes the following word or quoted string as its argument
+    /// unless that is another switch. The result is left empty.
+    pub fn parse(instruction: &str) -> Self {
+        let mut words = arguments(instruction).into_iter().peekable();
+        let name = words.next().unwrap_or_default();
+
+        let mut positional = Vec::new();
+        let mut switches: Vec<FieldSwitch> = Vec::new();
+        while let Some(word) = words.next() {
+            match word.strip_prefix('\\') {
+                Some(switch) => {
+                    let argument = words.next_if(|next| !next.starts_with('\\'));
+                    switches.push(FieldSwitch {
+                        name: switch.to_string(),
+                        argument,
+                    });
+                },
+                None if switches.is_empty() => positional.push(word),
+                None => {},
+            }
+        }
+
+        Self {
+            field_type: FieldType::from_name(&name),
+            name,
+            instruction: instruction.trim().to_string(),
+            arguments: positional,
+            switches,
+            result: None,
+        }
+    }
+
+    /// Get the first switch with the given name (without the backslash).
+    pub fn switch(&self, name: &str) -> Option<&FieldSwitch> {
+        self.switches.iter().find(|switch| switch.name == name)
+    }
+
+    /// Check whether the instruction has the given switch.
+    pub fn has_switch(&self, name: &str) -> bool {
+        self.switch(name).is_some()
+    }
+
+    /// Get the target of a `HYPERLINK` field: the URL, `#bookmark` for a
+    /// link to a bookmark, or both.
+    pub fn hyperlink_target(&self) -> Option<String> {
+        hyperlink_target(&self.instruction)
+    }
+
+    /// Get the bookmark a `REF` or `PAGEREF` field refers to.
+    pub fn bookmark(&self) -> Option<&str> {
+        match self.field_type {
+            FieldType::Reference | FieldType::PageRef => self.arguments.first().map(String::as_str),
+            _ => None,
+        }
+    }
+
+    /// Get the display text: the cached result, or the instruction if the
+    /// field has no result.
+    pub fn display_text(&self) -> &str {
+        self.result.as_deref().unwrap_or(&self.instruction)
+    }
+}
+
+/// A hyperlink from a `w:hyperlink` element or a `HYPERLINK` field.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Hyperlink {
+    /// External target, resolved from the relationship ID
+    ///
+    /// Only available for paragraphs that come from a
+    /// [`Document`](super::Document).
+    pub url: Option<String>,
+    /// Bookmark the link points to
+    pub anchor: Option<String>,
+    /// Tooltip shown on hover
+    pub tooltip: Option<String>,
+    /// Displayed text
+    pub text: String,
+}
+
+impl Hyperlink {
+    /// Get the link target: the URL, `#anchor`, or both (`url#anchor`).
+    pub fn target(&self) -> Option<String> {
+        match (&self.url, &self.anchor) {
+            (Some(url), Some(anchor)) => Some(format!("{}#{}", url, anchor)),
+            (Some(url), None) => Some(url.clone()),
+            (None, Some(anchor)) => Some(format!("#{}", anchor)),
+            (None, None) => None,
+        }
+    }
+}
+
+/// A position in the text of a document or paragraph.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord)]
+pub struct TextPosition {
+    /// Index of the paragraph, as returned by `paragraphs()`
+    pub paragraph: usize,
+    /// Offset in characters into the paragraph's text, which includes
+    /// the text of every `w:t` element
+    pub offset: usize,
+}
+
+/// A bookmark with the range of text it covers.
+///
+/// Names starting with an underscore (`_Toc...`, `_GoBack`) are hidden
+/// bookmarks Word creates for its own use.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Bookmark {
+    /// Bookmark ID shared by the start and end markers
+    pub id: String,
+    /// Bookmark name
+    pub name: String,
+    /// Where the bookmark starts
+    pub start: TextPosition,
+    /// Where the bookmark ends; the end of the text for a bookmark without
+    /// an end marker
+    pub end: TextPosition,
+    /// Text covered by the bookmark, with paragraphs separated by newlines
+    pub text: String,
+}
+
+/// Fields, hyperlinks and bookmarks found in a piece of document XML.
+#[derive(Debug, Default)]
+pub(crate) struct Markup {
+    pub fields: Vec<Field>,
+    pub hyperlinks: Vec<Hyperlink>,
+    pub bookmarks: Vec<Bookmark>,
+    /// Commented ranges, as bookmarks with the comment ID and no name
+    pub comment_ranges: Vec<Bookmark>,
+}
+
+/// Where a range that hasn't ended yet goes in the output.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum RangeSlot {
+    Bookmark(usize),
+    Comment(usize),
+}
+
+/// A field whose end hasn't been reached yet.
+struct OpenField {
+    /// Index of the field's slot in the output, so fields keep their start order
+    slot: usize,
+    instruction: String,
+    result: Option<String>,
+    /// Whether the field is a `w:fldSimple` element rather than fldChars
+    simple: bool,
+}
+
+/// Scan paragraphs for fields, hyperlinks and bookmarks.
+///
+/// `outline` resolves hyperlink relationship IDs. Fields and bookmarks
+/// left open at the end of `xml` are reported as they stand.
+pub(crate) fn scan(xml: &[u8], outline: Option<&OutlineContext>) -> Result<Markup> {
+    // Spaces in instructions and text runs are significant
+    let mut reader = Reader::from_reader(xml);
+
+    let mut markup = Markup::default();
+    let mut fields: Vec<Option<Field>> = Vec::new();
+    let mut open_fields: Vec<OpenField> = Vec::new();
+    let mut link: Option<Hyperlink> = None;
+    let mut bookmarks: Vec<Option<Bookmark>> = Vec::new();
+    let mut comment_ranges: Vec<Option<Bookmark>> = Vec::new();
+    let mut open_ranges: Vec<(Bookmark, RangeSlot)> = Vec::new();
+
+    // Paragraph tracking, matching how paragraphs are split out of the body
+    let mut paragraph: Option<usize> = None;
+    let mut next_paragraph = 0;
+    let mut depth = 0usize;
+    let mut offset = 0usize;
+    // End of the last paragraph, where unterminated bookmarks end
+    let mut text_end = TextPosition::default();
+
+    let mut in_text = false;
+    let mut in_instr_text = false;
+    let mut text = String::new();
+    let mut buf = Vec::with_capacity(1024);
+
+    loop {
+        let event = reader
+            .read_event_into(&mut buf)
+            .map_err(|e| OoxmlError::Xml(e.to_string()))?;
+        match event {
+            Event::Start(ref e) => {
+                if paragraph.is_some() {
+                    depth += 1;
+                } else if e.local_name().as_ref() == b"p" {
+                    if next_paragraph > 0 {
+                        // Paragraph break in text spanning paragraphs
+                        push_text("\n", &mut open_fields, &mut open_ranges, None);
+                    }
+                    paragraph = Some(next_paragraph);
+                    next_paragraph += 1;
+                    depth = 1;
+                    offset = 0;
+                    buf.clear();
+                    continue;
+                }
+                match e.local_name().as_ref() {
+                    b"t" => in_text = true,
+                    b"instrText" => in_instr_text = true,
+                    b"hyperlink" => link = Some(hyperlink(e, outline)),
+                    b"fldSimple" => {
+                        open_fields.push(OpenField {
+                            slot: fields.len(),
+                            instruction: attribute(e, b"instr").unwrap_or_default(),
+                            result: Some(String::new()),
+                            simple: true,
+                        });
+                        fields.push(None);
+                    },
+                    _ => {},
+                }
+            },
+            Event::Empty(ref e) => {
+                // Markers between paragraphs belong to the start of the next one
+                let position = match paragraph {
+                    Some(paragraph) => TextPosition { paragraph, offset },
+                    None => TextPosition {
+                        paragraph: next_paragraph,
+                        offset: 0,
+                    },
+                };
+                match e.local_name().as_ref() {
+                    b"fldChar" => match attribute(e, b"fldCharType").as_deref() {
+                        Some("begin") => {
+                            open_fields.push(OpenField {
+                                slot: fields.len(),
+                                instruction: String::new(),
+                                result: None,
+                                simple: false,
+                            });
+                            fields.push(None);
+                        },
+                        Some("separate") => {
+                            if let Some(field) =
+                                open_fields.iter_mut().rev().find(|field| !field.simple)
+                            {
+                                field.result = Some(String::new());
+                            }
+                        },
+                        Some("end") => {
+                            if let Some(index) = open_fields.iter().rposition(|field| !field.simple)
+                            {
+                                let field = open_fields.remove(index);
+                                close_field(field, &mut fields, &mut markup.hyperlinks);
+                            }
+                        },
+                        _ => {},
+                    },
+                    b"fldSimple" => {
+                        let mut field = Field::parse(&attribute(e, b"instr").unwrap_or_default());
+                        field.result = Some(String::new());
+                        fields.push(Some(field));
+                    },
+                    name @ (b"bookmarkStart" | b"commentRangeStart") => {
+                        let bookmark = Bookmark {
+                            id: attribute(e, b"id").unwrap_or_default(),
+                            name: attribute(e, b"name").unwrap_or_default(),
+                            start: position,
+                            end: position,
+                            text: String::new(),
+                        };
+                        let slot = if name == b"bookmarkStart" {
+                            bookmarks.push(None);
+                            RangeSlot::Bookmark(bookmarks.len() - 1)
+                        } else {
+                            comment_ranges.push(None);
+                            RangeSlot::Comment(comment_ranges.len() - 1)
+                        };
+                        open_ranges.push((bookmark, slot));
+                    },
+                    name @ (b"bookmarkEnd" | b"commentRangeEnd") => {
+                        let id = attribute(e, b"id").unwrap_or_default();
+                        let comment = name == b"commentRangeEnd";
+                        if let Some(index) = open_ranges.iter().position(|(bookmark, slot)| {
+                            bookmark.id == id && matches!(slot, RangeSlot::Comment(_)) == comment
+                        }) {
+                            let (mut bookmark, slot) = open_ranges.remove(index);
+                            bookmark.end = position;
+                            close_range(bookmark, slot, &mut bookmarks, &mut comment_ranges);
+                        }
+                    },
+                    _ => {},
+                }
+            },
+            Event::End(ref e) => {
+                if paragraph.is_some() {
+                    depth -= 1;
+                    if depth == 0 {
+                        text_end = TextPosition {
+                            paragraph: paragraph.unwrap_or_default(),
+                            offset,
+                        };
+                        paragraph = None;
+                        buf.clear();
+                        continue;
+                    }
+                }
+                match e.local_name().as_ref() {
+                    b"t" => in_text = false,
+                    b"instrText" => in_instr_text = false,
+                    b"hyperlink" => markup.hyperlinks.extend(link.take()),
+                    b"fldSimple" => {
+                        if let Some(index) = open_fields.iter().rposition(|field| field.simple) {
+                            let field = open_fields.remove(index);
+                            close_field(field, &mut fields, &mut markup.hyperlinks);
+                        }
+                    },
+                    _ => {},
+                }
+            },
+            Event::Text(_) | Event::GeneralRef(_) if in_text || in_instr_text => {
+                text.clear();
+                push_event_text(&event, &mut text)?;
+                if in_instr_text {
+                    if let Some(field) = open_fields.last_mut()
+                        && field.result.is_none()
+                    {
+                        field.instruction.push_str(&text);
+                    }
+                } else {
+                    offset += text.chars().count();
+                    push_text(&text, &mut open_fields, &mut open_ranges, link.as_mut());
+                }
+            },
+            Event::Eof => break,
+            _ => {},
+        }
+        buf.clear();
+    }
+
+    // Report unterminated fields and bookmarks as they stand
+    while let Some(field) = open_fields.pop() {
+        close_field(field, &mut fields, &mut markup.hyperlinks);
+    }
+    for (mut bookmark, slot) in open_ranges {
+        bookmark.end = bookmark.end.max(text_end);
+        close_range(bookmark, slot, &mut bookmarks, &mut comment_ranges);
+    }
+    markup.fields = fields.into_iter().flatten().collect();
+    markup.bookmarks = bookmarks.into_iter().flatten().collect();
+    markup.comment_ranges = comment_ranges.into_iter().flatten().collect();
+    Ok(markup)
+}
+
+/// Put a finished bookmark or comment range into its slot.
+fn close_range(
+    range: Bookmark,
+    slot: RangeSlot,
+    bookmarks: &mut [Option<Bookmark>],
+    comment_ranges: &mut [Option<Bookmark>],
+) {
+    match slot {
+        RangeSlot::Bookmark(index) => bookmarks[index] = Some(range),
+        RangeSlot::Comment(index) => comment_ranges[index] = Some(range),
+    }
+}
+
+/// Append displayed text to the open fields, ranges and hyperlink.
+///
+/// Text goes to the innermost field; a field's result also shows through
+/// in the field containing it, as part of its result or its instruction.
+fn push_text(
+    text: &str,
+    open_fields: &mut [OpenField],
+    open_ranges: &mut [(Bookmark, RangeSlot)],
+    link: Option<&mut Hyperlink>,
+) {
+    for field in open_fields.iter_mut().rev() {
+        match &mut field.result {
+            Some(result) => result.push_str(text),
+            None => {
+                field.instruction.push_str(text);
+                break;
+            },
+        }
+    }
+    for (bookmark, _) in open_ranges.iter_mut() {
+        bookmark.text.push_str(text);
+    }
+    if let Some(link) = link
+        && text != "\n"
+    {
+        link.text.push_str(text);
+    }
+}
+
+/// Finish a field, recording a hyperlink for `HYPERLINK` fields.
+fn close_field(open: OpenField, fields: &mut [Option<Field>], hyperlinks: &mut Vec<Hyperlink>) {
+    let mut field = Field::parse(&open.instruction);
+    field.result = open.result;
+
+    if field.field_type == FieldType::Hyperlink {
+        let argument = |name: &str| field.switch(name).and_then(|s| s.argument.clone());
+        hyperlinks.push(Hyperlink {
+            url: field.arguments.first().cloned(),
+            anchor: argument("l"),
+            tooltip: argument("o"),
+            text: field.result.clone().unwrap_or_default(),
+        });
+    }
+    fields[open.slot] = Some(field);
+}
+
+/// Read a `w:hyperlink` element's target and tooltip.
+fn hyperlink(e: &BytesStart, outline: Option<&OutlineContext>) -> Hyperlink {
+    Hyperlink {
+        url: attribute(e, b"id")
+            .and_then(|id| outline.and_then(|outline| outline.hyperlink(&id)))
+            .map(str::to_string),
+        anchor: attribute(e, b"anchor"),
+        tooltip: attribute(e, b"tooltip"),
+        text: String::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_instruction() {
+        let field = Field::parse(r#" HYPERLINK "https://example.com" \l "top" \o "Go" \h "#);
+        assert_eq!(field.field_type, FieldType::Hyperlink);
+        assert_eq!(field.arguments, ["https://example.com"]);
+        assert_eq!(
+            field.switches,
+            [
+                FieldSwitch {
+                    name: "l".to_string(),
+                    argument: Some("top".to_string()),
+                },
+                FieldSwitch {
+                    name: "o".to_string(),
+                    argument: Some("Go".to_string()),
+                },
+                FieldSwitch {
+                    name: "h".to_string(),
+                    argument: None,
+                },
+            ]
+        );
+        assert_eq!(
+            field.hyperlink_target().as_deref(),
+            Some("https://example.com#top")
+        );
+
+        let field = Field::parse(r"PAGEREF _Toc42 \h");
+        assert_eq!(field.bookmark(), Some("_Toc42"));
+        assert_eq!(field.display_text(), r"PAGEREF _Toc42 \h");
+    }
+
+    #[test]
+    fn test_scan() {
+        let xml = br#"<w:body>
+<w:p><w:bookmarkStart w:id="0" w:name="intro"/><w:r><w:t>See </w:t></w:r>
+<w:r><w:fldChar w:fldCharType="begin"/></w:r><w:r><w:instrText> REF </w:instrText></w:r>
+<w:r><w:fldChar w:fldCharType="begin"/></w:r><w:r><w:instrText>QUOTE target</w:instrText></w:r>
+<w:r><w:fldChar w:fldCharType="separate"/></w:r><w:r><w:t>target</w:t></w:r>
+<w:r><w:fldChar w:fldCharType="end"/></w:r><w:r><w:instrText> \h</w:instrText></w:r>
+<w:r><w:fldChar w:fldCharType="separate"/></w:r><w:r><w:t>page 2</w:t></w:r>
+<w:r><w:fldChar w:fldCharType="end"/></w:r></w:p>
+<w:p><w:fldSimple w:instr=" PAGE "><w:r><w:t>3</w:t></w:r></w:fldSimple>
+<w:hyperlink w:anchor="intro" w:tooltip="Back"><w:r><w:t>up &amp; away</w:t></w:r></w:hyperlink>
+<w:bookmarkEnd w:id="0"/></w:p>
+</w:body>"#;
+        let markup = scan(xml, None).unwrap();
+
+        let fields: Vec<_> = markup
+            .fields
+            .iter()
+            .map(|field| (field.instruction.as_str(), field.result.as_deref()))
+            .collect();
+        assert_eq!(
+            fields,
+            [
+                (r"REF target \h", Some("page 2")),
+                ("QUOTE target", Some("target")),
+                ("PAGE", Some("3")),
+            ]
+        );
+        assert_eq!(markup.fields[0].bookmark(), Some("target"));
+
+        assert_eq!(
+            markup.hyperlinks,
+            [Hyperlink {
+                url: None,
+                anchor: Some("intro".to_string()),
+                tooltip: Some("Back".to_string()),
+                text: "up & away".to_string(),
+            }]
+        );
+
+        assert_eq!(
+            markup.bookmarks,
+            [Bookmark {
+                id: "0".to_string(),
+                name: "intro".to_string(),
+                start: TextPosition::default(),
+                end: TextPosition {
+                    paragraph: 1,
+                    offset: 10,
+                },
+                text: "See targetpage 2\n3up & away".to_string(),
+            }]
+        );
+    }
+}
@@ -10,9 +10,14 @@
 /// - `Document`: The main document content and API
 /// - `Paragraph`: A paragraph with runs
 /// - `Run`: A text run with formatting
+/// - `Field`, `Hyperlink`, `Bookmark`: Fields, links and bookmarks in the text
+/// - `Revision`: Tracked changes, with final and original text views
+/// - `Comment`: Comments with their commented text and reply threads
+/// - `ContentControl`: Content controls, with their data bindings to `CustomXmlPart`s
 /// - `Table`: A table with rows and cells
 /// - `Section`: A document section with page properties
 /// - `Styles`: Collection of document styles
+/// - `MutableStyles`, `MutableNumbering`: Style and list definitions for new documents
 /// - `DocumentPart`: The core document.xml part
 ///
 /// # Example
@@ -48,22 +53,37 @@
 /// }
 /// # Ok::<(), Box<dyn std::error::Error>>(())
 /// ```
+pub mod comment;
+pub mod content_control;
+pub mod custom_xml;
 pub mod document;
 pub mod enums;
+pub mod field;
 pub mod format;
+pub mod numbering;
+pub(crate) mod outline;
 pub mod package;
 pub mod paragraph;
 pub mod parts;
+pub mod revision;
 pub mod section;
 pub mod styles;
 pub mod table;
 pub mod template;
 pub mod writer;
 
+pub use comment::Comment;
+pub use content_control::{
+    ContentControl, ContentControlLevel, ContentControlType, DataBinding, ListItem,
+};
+pub use custom_xml::CustomXmlPart;
 pub use document::Document;
 pub use enums::{WdHeaderFooter, WdOrientation, WdSectionStart, WdStyleType};
+pub use field::{Bookmark, Field, FieldSwitch, FieldType, Hyperlink, TextPosition};
+pub use numbering::Numbering;
 pub use package::Package;
 pub use paragraph::{Paragraph, Run, RunProperties};
+pub use revision::{Revision, RevisionType, RevisionView};
 pub use section::{Emu, Margins, PageSize, Section, Sections};
 pub use styles::{Style, Styles};
 pub use table::{Cell, Row, Table, VMergeState};
@@ -71,7 +91,9 @@ pub use table::{Cell, Row, Table, VMergeState};
 pub use format::{ImageFormat, LineSpacing, ParagraphAlignment, TableBorderStyle, UnderlineStyle};
 // Re-export writer types
 pub use writer::{
-    CellProperties, ListType, MutableDocument, MutableHyperlink, MutableInlineImage,
-    MutableParagraph, MutableRun, MutableTable, Note, PageNumberFormat, PageOrientation,
-    RunContent, SectionProperties, TableBorder, TableBorders,
+    CellProperties, ListType, MutableComment, MutableDocument, MutableHyperlink,
+    MutableInlineImage, MutableNumbering, MutableParagraph, MutableRun, MutableStyle,
+    MutableStyles, MutableTable, Note, NumberingDefinition, NumberingLevel, PageNumberFormat,
+    PageOrientation, ParagraphFormat, RunContent, RunFormat, SectionProperties, TableBorder,
+    TableBorders, TableFormat, TableStyleCondition, TableStyleRegion,
 };
@@ -0,0 +1,203 @@
+/// Numbering - list definitions from the numbering part.
+///
+/// A paragraph joins a list through `w:numPr`, which names a numbering
+/// instance (`w:num`) and a level. Each instance points at an abstract
+/// definition (`w:abstractNum`) holding the format and start value of its
+/// nine levels, and may override individual levels.
+use crate::common::{ListFormat, ListInfo};
+use crate::ooxml::common::xml::attribute;
+use crate::ooxml::error::{OoxmlError, Result};
+use quick_xml::Reader;
+use quick_xml::events::{BytesStart, Event};
+use std::collections::HashMap;
+
+/// List definitions of a Word document (`word/numbering.xml`).
+#[derive(Debug, Clone, Default)]
+pub struct Numbering {
+    /// Levels of each abstract definition, by `w:abstractNumId`
+    abstract_nums: HashMap<u32, HashMap<u8, Level>>,
+    /// Numbering instances, by `w:numId`
+    nums: HashMap<u32, NumberingInstance>,
+}
+
+/// A `w:num` element.
+#[derive(Debug, Clone, Default)]
+struct NumberingInstance {
+    abstract_num_id: u32,
+    /// Levels overridden by `w:lvlOverride`
+    overrides: HashMap<u8, Level>,
+}
+
+/// The properties of a list level that are read; unset ones are inherited
+/// from the abstract definition.
+#[derive(Debug, Clone, Copy, Default)]
+struct Level {
+    format: Option<ListFormat>,
+    start: Option<u32>,
+}
+
+impl Numbering {
+    /// Parse the numbering part.
+    pub fn parse(xml: &[u8]) -> Result<Self> {
+        let mut reader = Reader::from_reader(xml);
+        reader.config_mut().trim_text(true);
+
+        let mut numbering = Self::default();
+        let mut buf = Vec::with_capacity(512);
+        // The abstractNum or num being read
+        let mut abstract_num: Option<(u32, HashMap<u8, Level>)> = None;
+        let mut num: Option<(u32, NumberingInstance)> = None;
+        // Level being read within either
+        let mut level: Option<u8> = None;
+
+        loop {
+            match reader.read_event_into(&mut buf) {
+                Ok(Event::Start(e)) => match e.local_name().as_ref() {
+                    b"abstractNum" => {
+                        abstract_num =
+                            attr_u32(&e, b"abstractNumId").map(|id| (id, HashMap::new()));
+                    },
+                    b"num" => {
+                        num = attr_u32(&e, b"numId").map(|id| (id, NumberingInstance::default()));
+                    },
+                    b"lvl" | b"lvlOverride" => {
+                        if let Some(ilvl) = attr_u32(&e, b"ilvl") {
+                            level = Some(ilvl as u8);
+                        }
+                    },
+                    _ => {},
+                },
+                Ok(Event::Empty(e)) => match e.local_name().as_ref() {
+                    b"numFmt" => {
+                        if let Some(ilvl) = level
+                            && let Some(value) = attribute(&e, b"val")
+                            && let Some(definition) = level_mut(&mut abstract_num, &mut num, ilvl)
+                        {
+                            definition.format = Some(ListFormat::from_ooxml(&value));
+                        }
+                    },
+                    // `w:start` within a level, `w:startOverride` within an override
+                    b"start" | b"startOverride" => {
+                        if let Some(ilvl) = level
+                            && let Some(start) = attr_u32(&e, b"val")
+                            && let Some(definition) = level_mut(&mut abstract_num, &mut num, ilvl)
+                        {
+                            definition.start = Some(start);
+                        }
+                    },
+                    b"abstractNumId" => {
+                        if let Some((_, instance)) = num.as_mut()
+                            && let Some(id) = attr_u32(&e, b"val")
+                        {
+                            instance.abstract_num_id = id;
+                        }
+                    },
+                    _ => {},
+                },
+                Ok(Event::End(e)) => match e.local_name().as_ref() {
+                    b"abstractNum" => {
+                        if let Some((id, levels)) = abstract_num.take() {
+                            numbering.abstract_nums.insert(id, levels);
+                        }
+                    },
+                    b"num" => {
+                        if let Some((id, instance)) = num.take() {
+                            numbering.nums.insert(id, instance);
+                        }
+                    },
+                    b"lvl" | b"lvlOverride" => level = None,
+                    _ => {},
+                },
+                Ok(Event::Eof) => break,
+                Err(e) => return Err(OoxmlError::Xml(e.to_string())),
+                _ => {},
+            }
+            buf.clear();
+        }
+
+        Ok(numbering)
+    }
+
+    /// Get the format of a level of a numbering instance.
+    ///
+    /// Returns `None` if the instance or level is not defined.
+    pub fn level_format(&self, num_id: u32, ilvl: u8) -> Option<ListFormat> {
+        self.level_info(num_id, ilvl).map(|info| info.format)
+    }
+
+    /// Get the format and start value of a level of a numbering instance.
+    ///
+    /// Returns `None` if the instance or level format is not defined.
+    pub fn level_info(&self, num_id: u32, ilvl: u8) -> Option<ListInfo> {
+        let instance = self.nums.get(&num_id)?;
+        let overridden = instance.overrides.get(&ilvl).copied().unwrap_or_default();
+        let defined = self
+            .abstract_nums
+            .get(&instance.abstract_num_id)
+            .and_then(|levels| levels.get(&ilvl))
+            .copied()
+            .unwrap_or_default();
+
+        let format = overridden.format.or(defined.format)?;
+        let start = overridden.start.or(defined.start).unwrap_or(1);
+        Some(ListInfo::new(ilvl, format).with_start(start))
+    }
+}
+
+/// Get the level being read in the current `w:abstractNum` or `w:num`.
+fn level_mut<'a>(
+    abstract_num: &'a mut Option<(u32, HashMap<u8, Level>)>,
+    num: &'a mut Option<(u32, NumberingInstance)>,
+    ilvl: u8,
+) -> Option<&'a mut Level> {
+    if let Some((_, levels)) = abstract_num.as_mut() {
+        Some(levels.entry(ilvl).or_default())
+    } else {
+        num.as_mut()
+            .map(|(_, instance)| instance.overrides.entry(ilvl).or_default())
+    }
+}
+
+fn attr_u32(e: &BytesStart, name: &[u8]) -> Option<u32> {
+    attribute(e, name)?.parse().ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_level_formats() {
+        let xml = br#"<w:numbering xmlns:w="http://schemas.openxmlformats.org/wordprocessingml/2006/main">
+            <w:abstractNum w:abstractNumId="0">
+                <w:lvl w:ilvl="0"><w:start w:val="1"/><w:numFmt w:val="decimal"/></w:lvl>
+                <w:lvl w:ilvl="1"><w:numFmt w:val="lowerLetter"/></w:lvl>
+            </w:abstractNum>
+            <w:abstractNum w:abstractNumId="1">
+                <w:lvl w:ilvl="0"><w:numFmt w:val="bullet"/><w:lvlText w:val="o"/></w:lvl>
+            </w:abstractNum>
+            <w:num w:numId="1"><w:abstractNumId w:val="0"/></w:num>
+            <w:num w:numId="2"><w:abstractNumId w:val="1"/></w:num>
+            <w:num w:numId="3">
+                <w:abstractNumId w:val="0"/>
+                <w:lvlOverride w:ilvl="0"><w:lvl w:ilvl="0"><w:numFmt w:val="upperRoman"/></w:lvl></w:lvlOverride>
+            </w:num>
+            <w:num w:numId="5">
+                <w:abstractNumId w:val="0"/>
+                <w:lvlOverride w:ilvl="0"><w:startOverride w:val="4"/></w:lvlOverride>
+            </w:num>
+        </w:numbering>"#;
+        let numbering = Numbering::parse(xml).unwrap();
+        assert_eq!(numbering.level_format(1, 0), Some(ListFormat::Decimal));
+        assert_eq!(numbering.level_format(1, 1), Some(ListFormat::LowerLetter));
+        assert_eq!(numbering.level_format(2, 0), Some(ListFormat::Bullet));
+        assert_eq!(numbering.level_format(3, 0), Some(ListFormat::UpperRoman));
+        assert_eq!(numbering.level_format(3, 1), Some(ListFormat::LowerLetter));
+        assert_eq!(numbering.level_format(4, 0), None);
+        assert_eq!(numbering.level_format(5, 0), Some(ListFormat::Decimal));
+
+        assert_eq!(numbering.level_info(1, 0).unwrap().start, 1);
+        assert_eq!(numbering.level_info(5, 0).unwrap().start, 4);
+        assert_eq!(numbering.level_info(5, 1).unwrap().start, 1);
+    }
+}
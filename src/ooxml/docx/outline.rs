@@ -0,0 +1,198 @@
+/// Outline - resolution of paragraph structure against document-level parts.
+///
+/// A paragraph's heading level, list format and hyperlink targets are not
+/// in its own XML: they come from the styles part (`w:pStyle` →
+/// `w:outlineLvl`, style names such as "heading 1", style `w:numPr`), the
+/// numbering part (`w:numId` → `w:numFmt`, `w:start`) and the document part's
+/// relationships (`r:id` → URL). This context is loaded once per document
+/// and shared by its paragraphs.
+use crate::common::ListInfo;
+use crate::ooxml::docx::numbering::Numbering;
+use crate::ooxml::docx::styles::{Style, Styles};
+use crate::ooxml::error::Result;
+use crate::ooxml::opc::OpcPackage;
+use crate::ooxml::opc::constants::relationship_type;
+use std::collections::HashMap;
+
+/// Deepest `w:basedOn` chain followed when resolving styles.
+const MAX_STYLE_DEPTH: usize = 16;
+
+/// Document-level data needed to resolve paragraph structure.
+#[derive(Debug, Default)]
+pub(crate) struct OutlineContext {
+    /// Paragraph styles, by style ID
+    styles: HashMap<String, StyleOutline>,
+    numbering: Numbering,
+    /// External hyperlink targets, by relationship ID
+    hyperlinks: HashMap<String, String>,
+}
+
+/// Structure-related properties of a style, with `w:basedOn` applied.
+#[derive(Debug, Default)]
+struct StyleOutline {
+    name: Option<String>,
+    heading_level: Option<u8>,
+    numbering: Option<(u32, u8)>,
+}
+
+impl OutlineContext {
+    /// Load the styles, numbering and hyperlink relationships of the main
+    /// document part. Missing parts are treated as empty.
+    pub(crate) fn load(opc: &OpcPackage) -> Result<Self> {
+        let main_part = opc.main_document_part()?;
+        let rels = main_part.rels();
+
+        let mut styles = Vec::new();
+        if let Ok(rel) = rels.part_with_reltype(relationship_type::STYLES) {
+            let part = opc.get_part(&rel.target_partname()?)?;
+            styles.extend(Styles::from_part(part).iter()?.cloned());
+        }
+
+        let numbering = match rels.part_with_reltype(relationship_type::NUMBERING) {
+            Ok(rel) => Numbering::parse(opc.get_part(&rel.target_partname()?)?.blob())?,
+            Err(_) => Numbering::default(),
+        };
+
+        let hyperlinks = rels
+            .iter()
+            .filter(|rel| rel.is_external() && rel.reltype() == relationship_type::HYPERLINK)
+            .map(|rel| (rel.r_id().to_string(), rel.target_ref().to_string()))
+            .collect();
+
+        Ok(Self::new(&styles, numbering, hyperlinks))
+    }
+
+    /// Build a context from already-parsed parts.
+    pub(crate) fn new(
+        styles: &[Style],
+        numbering: Numbering,
+        hyperlinks: HashMap<String, String>,
+    ) -> Self {
+        let by_id: HashMap<&str, &Style> = styles
+            .iter()
+            .map(|style| (style.style_id(), style))
+            .collect();
+
+        let styles = styles
+            .iter()
+            .map(|style| {
+                let outline = StyleOutline {
+                    name: style.name().map(str::to_string),
+                    heading_level: inherited(&by_id, style, |style| {
+                        style
+                            .name()
+                            .and_then(heading_level_from_name)
+                            .or_else(|| style.outline_level().and_then(heading_level_from_outline))
+                    }),
+                    numbering: inherited(&by_id, style, Style::numbering),
+                };
+                (style.style_id().to_string(), outline)
+            })
+            .collect();
+
+        Self {
+            styles,
+            numbering,
+            hyperlinks,
+        }
+    }
+
+    /// Get the display name of a paragraph style.
+    pub(crate) fn style_name(&self, style_id: &str) -> Option<&str> {
+        self.styles.get(style_id)?.name.as_deref()
+    }
+
+    /// Get the heading level (1-9) a paragraph style gives its paragraphs.
+    pub(crate) fn style_heading_level(&self, style_id: &str) -> Option<u8> {
+        match self.styles.get(style_id) {
+            Some(style) => style.heading_level,
+            // Without a styles part, rely on the built-in IDs
+            None if self.styles.is_empty() => style_id
+                .strip_prefix("Heading")
+                .and_then(|level| level.parse().ok())
+                .filter(|level| (1..=9).contains(level)),
+            None => None,
+        }
+    }
+
+    /// Get the numbering a paragraph style applies, as `(numId, ilvl)`.
+    pub(crate) fn style_numbering(&self, style_id: &str) -> Option<(u32, u8)> {
+        self.styles.get(style_id)?.numbering
+    }
+
+    /// Get the format and start value of a numbering level.
+    pub(crate) fn list_info(&self, num_id: u32, ilvl: u8) -> Option<ListInfo> {
+        self.numbering.level_info(num_id, ilvl)
+    }
+
+    /// Get the URL of an external hyperlink relationship.
+    pub(crate) fn hyperlink(&self, r_id: &str) -> Option<&str> {
+        self.hyperlinks.get(r_id).map(String::as_str)
+    }
+}
+
+/// Take a property from a style or the nearest style it is based on.
+fn inherited<T>(
+    by_id: &HashMap<&str, &Style>,
+    style: &Style,
+    property: impl Fn(&Style) -> Option<T>,
+) -> Option<T> {
+    let mut current = style;
+    for _ in 0..MAX_STYLE_DEPTH {
+        if let Some(value) = property(current) {
+            return Some(value);
+        }
+        current = by_id.get(current.based_on()?)?;
+    }
+    None
+}
+
+/// Heading level of the built-in "heading 1" to "heading 9" styles.
+fn heading_level_from_name(name: &str) -> Option<u8> {
+    let level = name
+        .to_ascii_lowercase()
+        .strip_prefix("heading ")?
+        .parse()
+        .ok()?;
+    (1..=9).contains(&level).then_some(level)
+}
+
+/// Heading level of a 0-based outline level; level 9 is body text.
+pub(crate) fn heading_level_from_outline(outline_level: u8) -> Option<u8> {
+    (outline_level < 9).then_some(outline_level + 1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ooxml::opc::packuri::PackURI;
+    use crate::ooxml::opc::part::XmlPart;
+
+    #[test]
+    fn test_style_resolution() {
+        let xml = br#"<w:styles xmlns:w="http://schemas.openxmlformats.org/wordprocessingml/2006/main">
+            <w:style w:type="paragraph" w:styleId="Heading2"><w:name w:val="heading 2"/></w:style>
+            <w:style w:type="paragraph" w:styleId="Chapter"><w:name w:val="Chapter"/><w:pPr><w:outlineLvl w:val="0"/></w:pPr></w:style>
+            <w:style w:type="paragraph" w:styleId="Appendix"><w:name w:val="Appendix"/><w:basedOn w:val="Chapter"/></w:style>
+            <w:style w:type="paragraph" w:styleId="ListNumber"><w:name w:val="List Number"/><w:pPr><w:numPr><w:numId w:val="4"/></w:numPr></w:pPr></w:style>
+        </w:styles>"#;
+        let part = XmlPart::new(
+            PackURI::new("/word/styles.xml").unwrap(),
+            String::new(),
+            xml.to_vec(),
+        );
+        let styles: Vec<Style> = Styles::from_part(&part).iter().unwrap().cloned().collect();
+        let context = OutlineContext::new(&styles, Numbering::default(), HashMap::new());
+
+        assert_eq!(context.style_heading_level("Heading2"), Some(2));
+        assert_eq!(context.style_heading_level("Chapter"), Some(1));
+        assert_eq!(context.style_heading_level("Appendix"), Some(1));
+        assert_eq!(context.style_heading_level("ListNumber"), None);
+        assert_eq!(context.style_heading_level("Heading3"), None);
+        assert_eq!(context.style_numbering("ListNumber"), Some((4, 0)));
+        assert_eq!(context.style_name("Appendix"), Some("Appendix"));
+
+        let empty = OutlineContext::default();
+        assert_eq!(empty.style_heading_level("Heading3"), Some(3));
+    }
+}
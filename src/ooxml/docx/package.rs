@@ -1,13 +1,16 @@
-use crate::ooxml::common::DocumentProperties;
+use crate::ooxml::common::{DocumentProperties, TemplateData};
+use crate::ooxml::docx::content_control::{self, ContentControl, ContentControlType, Value};
+use crate::ooxml::docx::custom_xml::{self, CustomXmlPart};
 use crate::ooxml::docx::document::Document;
 use crate::ooxml::docx::parts::DocumentPart;
 use crate::ooxml::docx::writer::MutableDocument;
+use crate::ooxml::docx::{revision, template};
 /// Package implementation for Word documents.
 use crate::ooxml::error::{OoxmlError, Result};
 use crate::ooxml::opc::OpcPackage;
 use crate::ooxml::opc::constants::content_type as ct;
 use crate::ooxml::opc::packuri::PackURI;
-use std::io::{Read, Seek};
+use std::io::{Read, Seek, Write};
 use std::path::Path;
 
 /// A Word (.docx) package.
@@ -398,7 +401,14 @@ impl Package {
             if let Ok(part) = self.opc.get_part(&doc_uri) {
                 let xml = std::str::from_utf8(part.blob())
                     .map_err(|e| OoxmlError::InvalidFormat(format!("Invalid UTF-8: {}", e)))?;
-                self.mutable_doc = Some(MutableDocument::from_xml(xml)?);
+                let mut mutable_doc = MutableDocument::from_xml(xml)?;
+                if let Some(numbering_xml) = self.definitions_part_xml("/word/numbering.xml")? {
+                    mutable_doc.number_lists_after(&numbering_xml)?;
+                }
+                if let Some(comments_xml) = self.definitions_part_xml("/word/comments.xml")? {
+                    mutable_doc.number_comments_after(&comments_xml)?;
+                }
+                self.mutable_doc = Some(mutable_doc);
             } else {
                 // Create a new empty document
                 self.mutable_doc = Some(MutableDocument::new());
@@ -427,121 +437,248 @@ impl Package {
     /// # Ok::<(), Box<dyn std::error::Error>>(())
     /// ```
     pub fn save<P: AsRef<Path>>(&mut self, path: P) -> Result<()> {
-        use crate::ooxml::docx::writer::relmap::RelationshipMapper;
-        use crate::ooxml::opc::constants::relationship_type as rt;
+        self.write_pending_changes()?;
+        self.opc.save(path).map_err(|e| {
+            OoxmlError::IoError(std::io::Error::other(format!(
+                "Failed to save package: {}",
+                e
+            )))
+        })
+    }
 
+    /// Save the package to a writer.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use litchi::ooxml::docx::Package;
+    /// use std::io::Cursor;
+    ///
+    /// let mut pkg = Package::new()?;
+    /// let mut buffer = Cursor::new(Vec::new());
+    /// pkg.save_to_writer(&mut buffer)?;
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn save_to_writer<W: Write + Seek>(&mut self, writer: W) -> Result<()> {
+        self.write_pending_changes()?;
+        self.opc.save_to_writer(writer).map_err(|e| {
+            OoxmlError::IoError(std::io::Error::other(format!(
+                "Failed to save package: {}",
+                e
+            )))
+        })
+    }
+
+    /// Write the mutable document and core properties into the parts.
+    fn write_pending_changes(&mut self) -> Result<()> {
         // If we have a mutable document, update the document.xml part
         if let Some(mutable_doc) = self.mutable_doc.take() {
-            if mutable_doc.is_modified() {
-                // Step 1: Collect all content that needs relationships
-                let hyperlink_urls = mutable_doc.collect_hyperlink_urls();
-                let images = mutable_doc.collect_images();
-                let has_header = mutable_doc.has_header();
-                let has_footer = mutable_doc.has_footer();
-
-                // Step 2: Create a relationship mapper and add relationships
-                let mut rel_mapper = RelationshipMapper::new();
-
-                // Create the document part first (we'll update it later)
-                let doc_uri = PackURI::new("/word/document.xml")
-                    .map_err(|e| OoxmlError::InvalidUri(format!("document URI: {}", e)))?;
-
-                // Get or create the document part to add relationships to
-                let content_type = self
-                    .opc
-                    .get_part(&doc_uri)
-                    .map(|p| p.content_type().to_string())
-                    .unwrap_or_else(|_| ct::WML_DOCUMENT_MAIN.to_string());
-
-                // Create new temporary part for relationships
-                use crate::ooxml::opc::part::{BlobPart, Part};
-                let mut temp_part =
-                    BlobPart::new(doc_uri.clone(), content_type.clone(), Vec::new());
-
-                // Add hyperlink relationships (external)
-                for (i, url) in hyperlink_urls.iter().enumerate() {
-                    let rid = temp_part.relate_to_ext(url, rt::HYPERLINK);
-                    rel_mapper.add_hyperlink(i, rid);
-                }
+            let result = if mutable_doc.is_modified() {
+                self.write_mutable_document(&mutable_doc)
+            } else {
+                Ok(())
+            };
+            // Put the document back
+            self.mutable_doc = Some(mutable_doc);
+            result?;
+        }
 
-                // Add image parts and relationships
-                for (i, (image_data, image_format)) in images.iter().enumerate() {
-                    let image_num = i + 1;
-                    let ext = image_format.extension();
-                    let image_partname = format!("/word/media/image{}.{}", image_num, ext);
-                    let image_uri = PackURI::new(&image_partname)
-                        .map_err(|e| OoxmlError::InvalidUri(format!("image URI: {}", e)))?;
-
-                    // Create and add image part
-                    let image_part = BlobPart::new(
-                        image_uri,
-                        image_format.mime_type().to_string(),
-                        image_data.to_vec(),
-                    );
-                    self.opc.add_part(Box::new(image_part));
-
-                    // Create relationship from document to image
-                    let rid = temp_part.relate_to(&image_partname, rt::IMAGE);
-                    rel_mapper.add_image(i, rid);
-                }
+        // Update core properties
+        self.update_core_properties()
+    }
 
-                // Add header/footer parts and relationships
-                if has_header && let Some(header_xml) = mutable_doc.generate_header_xml()? {
-                    let header_uri = PackURI::new("/word/header1.xml")
-                        .map_err(|e| OoxmlError::InvalidUri(format!("header URI: {}", e)))?;
-                    let header_part = BlobPart::new(
-                        header_uri,
-                        ct::WML_HEADER.to_string(),
-                        header_xml.into_bytes(),
-                    );
-                    self.opc.add_part(Box::new(header_part));
-                    let rid = temp_part.relate_to("/word/header1.xml", rt::HEADER);
-                    rel_mapper.set_header_id(rid);
-                }
+    /// Write the document built through [`document_mut`](Self::document_mut)
+    /// into the package parts.
+    fn write_mutable_document(&mut self, mutable_doc: &MutableDocument) -> Result<()> {
+        use crate::ooxml::docx::writer::relmap::RelationshipMapper;
+        use crate::ooxml::opc::constants::relationship_type as rt;
 
-                if has_footer && let Some(footer_xml) = mutable_doc.generate_footer_xml()? {
-                    let footer_uri = PackURI::new("/word/footer1.xml")
-                        .map_err(|e| OoxmlError::InvalidUri(format!("footer URI: {}", e)))?;
-                    let footer_part = BlobPart::new(
-                        footer_uri,
-                        ct::WML_FOOTER.to_string(),
-                        footer_xml.into_bytes(),
-                    );
-                    self.opc.add_part(Box::new(footer_part));
-                    let rid = temp_part.relate_to("/word/footer1.xml", rt::FOOTER);
-                    rel_mapper.set_footer_id(rid);
-                }
+        // Step 1: Collect all content that needs relationships
+        let hyperlink_urls = mutable_doc.collect_hyperlink_urls();
+        let images = mutable_doc.collect_images();
+        let has_header = mutable_doc.has_header();
+        let has_footer = mutable_doc.has_footer();
 
-                // Step 3: Generate XML with actual relationship IDs
-                let xml = mutable_doc.to_xml_with_rels(&rel_mapper)?;
+        // Step 2: Create a relationship mapper and add relationships
+        let mut rel_mapper = RelationshipMapper::new();
 
-                // Step 4: Update the document part with final XML and relationships
-                temp_part.set_blob(xml.into_bytes());
-                self.opc.add_part(Box::new(temp_part));
+        // Create the document part first (we'll update it later)
+        let doc_uri = PackURI::new("/word/document.xml")
+            .map_err(|e| OoxmlError::InvalidUri(format!("document URI: {}", e)))?;
 
-                // Update footnotes if present
-                if let Some(footnotes_xml) = mutable_doc.generate_footnotes_xml()? {
-                    self.update_footnotes_part(footnotes_xml)?;
+        // Get or create the document part to add relationships to
+        let content_type = self
+            .opc
+            .get_part(&doc_uri)
+            .map(|p| p.content_type().to_string())
+            .unwrap_or_else(|_| ct::WML_DOCUMENT_MAIN.to_string());
+
+        // Create new temporary part for relationships
+        use crate::ooxml::opc::part::{BlobPart, Part};
+        let mut temp_part = BlobPart::new(doc_uri.clone(), content_type.clone(), Vec::new());
+
+        // Keep the relationships to parts the body does not refer to
+        if let Ok(doc_part) = self.opc.get_part(&doc_uri) {
+            for rel in doc_part.rels().iter() {
+                if !rel.is_external()
+                    && [
+                        rt::STYLES,
+                        rt::NUMBERING,
+                        rt::SETTINGS,
+                        rt::WEB_SETTINGS,
+                        rt::FONT_TABLE,
+                        rt::THEME,
+                        rt::CUSTOM_XML,
+                        rt::COMMENTS,
+                        rt::COMMENTS_EXTENDED,
+                        rt::COMMENTS_IDS,
+                    ]
+                    .contains(&rel.reltype())
+                {
+                    temp_part.relate_to(rel.target_ref(), rel.reltype());
                 }
+            }
+        }
 
-                // Update endnotes if present
-                if let Some(endnotes_xml) = mutable_doc.generate_endnotes_xml()? {
-                    self.update_endnotes_part(endnotes_xml)?;
-                }
+        // Add hyperlink relationships (external)
+        for (i, url) in hyperlink_urls.iter().enumerate() {
+            let rid = temp_part.relate_to_ext(url, rt::HYPERLINK);
+            rel_mapper.add_hyperlink(i, rid);
+        }
+
+        // Add image parts and relationships
+        for (i, (image_data, image_format)) in images.iter().enumerate() {
+            let image_num = i + 1;
+            let ext = image_format.extension();
+            let image_partname = format!("/word/media/image{}.{}", image_num, ext);
+            let image_uri = PackURI::new(&image_partname)
+                .map_err(|e| OoxmlError::InvalidUri(format!("image URI: {}", e)))?;
+
+            // Create and add image part
+            let image_part = BlobPart::new(
+                image_uri,
+                image_format.mime_type().to_string(),
+                image_data.to_vec(),
+            );
+            self.opc.add_part(Box::new(image_part));
+
+            // Create relationship from document to image
+            let rid = temp_part.relate_to(&image_partname, rt::IMAGE);
+            rel_mapper.add_image(i, rid);
+        }
+
+        // Add header/footer parts and relationships
+        if has_header && let Some(header_xml) = mutable_doc.generate_header_xml()? {
+            let header_uri = PackURI::new("/word/header1.xml")
+                .map_err(|e| OoxmlError::InvalidUri(format!("header URI: {}", e)))?;
+            let header_part = BlobPart::new(
+                header_uri,
+                ct::WML_HEADER.to_string(),
+                header_xml.into_bytes(),
+            );
+            self.opc.add_part(Box::new(header_part));
+            let rid = temp_part.relate_to("/word/header1.xml", rt::HEADER);
+            rel_mapper.set_header_id(rid);
+        }
+
+        if has_footer && let Some(footer_xml) = mutable_doc.generate_footer_xml()? {
+            let footer_uri = PackURI::new("/word/footer1.xml")
+                .map_err(|e| OoxmlError::InvalidUri(format!("footer URI: {}", e)))?;
+            let footer_part = BlobPart::new(
+                footer_uri,
+                ct::WML_FOOTER.to_string(),
+                footer_xml.into_bytes(),
+            );
+            self.opc.add_part(Box::new(footer_part));
+            let rid = temp_part.relate_to("/word/footer1.xml", rt::FOOTER);
+            rel_mapper.set_footer_id(rid);
+        }
+
+        // Step 3: Generate XML with actual relationship IDs
+        let xml = mutable_doc.to_xml_with_rels(&rel_mapper)?;
+
+        // Step 4: Update the document part with final XML and relationships
+        temp_part.set_blob(xml.into_bytes());
+        self.opc.add_part(Box::new(temp_part));
+
+        // Update footnotes if present
+        if let Some(footnotes_xml) = mutable_doc.generate_footnotes_xml()? {
+            self.update_footnotes_part(footnotes_xml)?;
+        }
+
+        // Update endnotes if present
+        if let Some(endnotes_xml) = mutable_doc.generate_endnotes_xml()? {
+            self.update_endnotes_part(endnotes_xml)?;
+        }
+
+        // Add comments to the existing comment parts, if any
+        let existing_comments = self.definitions_part_xml("/word/comments.xml")?;
+        let existing_extended = self.definitions_part_xml("/word/commentsExtended.xml")?;
+        let existing_ids = self.definitions_part_xml("/word/commentsIds.xml")?;
+        if let (Some(comments_xml), Some(extended_xml), Some(ids_xml)) = (
+            mutable_doc.generate_comments_xml(existing_comments.as_deref())?,
+            mutable_doc.generate_comments_extended_xml(existing_extended.as_deref())?,
+            mutable_doc.generate_comments_ids_xml(existing_ids.as_deref())?,
+        ) {
+            for (partname, content_type, reltype, xml) in [
+                (
+                    "/word/comments.xml",
+                    ct::WML_COMMENTS,
+                    rt::COMMENTS,
+                    comments_xml,
+                ),
+                (
+                    "/word/commentsExtended.xml",
+                    ct::WML_COMMENTS_EXTENDED,
+                    rt::COMMENTS_EXTENDED,
+                    extended_xml,
+                ),
+                (
+                    "/word/commentsIds.xml",
+                    ct::WML_COMMENTS_IDS,
+                    rt::COMMENTS_IDS,
+                    ids_xml,
+                ),
+            ] {
+                self.update_definitions_part(partname, content_type, reltype, xml)?;
             }
-            // Put the document back
-            self.mutable_doc = Some(mutable_doc);
         }
 
-        // Update core properties
-        self.update_core_properties()?;
+        // Update styles and numbering if defined
+        let existing_styles = self.definitions_part_xml("/word/styles.xml")?;
+        if let Some(styles_xml) = mutable_doc.generate_styles_xml(existing_styles.as_deref())? {
+            self.update_definitions_part(
+                "/word/styles.xml",
+                ct::WML_STYLES,
+                rt::STYLES,
+                styles_xml,
+            )?;
+        }
+        let existing_numbering = self.definitions_part_xml("/word/numbering.xml")?;
+        if let Some(numbering_xml) =
+            mutable_doc.generate_numbering_xml(existing_numbering.as_deref())?
+        {
+            self.update_definitions_part(
+                "/word/numbering.xml",
+                ct::WML_NUMBERING,
+                rt::NUMBERING,
+                numbering_xml,
+            )?;
+        }
 
-        self.opc.save(path).map_err(|e| {
-            OoxmlError::IoError(std::io::Error::other(format!(
-                "Failed to save package: {}",
-                e
-            )))
-        })
+        Ok(())
+    }
+
+    /// Write pending edits made through [`document_mut`](Self::document_mut)
+    /// into the stored parts and drop the mutable document, so that changes
+    /// made directly to the stored parts are not overwritten on save.
+    fn sync_mutable_document(&mut self) -> Result<()> {
+        if let Some(mutable_doc) = self.mutable_doc.take()
+            && mutable_doc.is_modified()
+        {
+            self.write_mutable_document(&mutable_doc)?;
+        }
+        Ok(())
     }
 
     /// Get a reference to the document properties.
@@ -576,6 +713,336 @@ impl Package {
         &mut self.properties
     }
 
+    /// Accept all tracked changes.
+    ///
+    /// Insertions become regular content, deletions are removed and
+    /// formatting changes are kept. Applies to the main document, headers,
+    /// footers, footnotes and endnotes. Returns the number of revisions
+    /// accepted.
+    ///
+    /// Revisions are resolved in the stored parts. Pending edits made
+    /// through [`document_mut`](Self::document_mut) are written to the
+    /// parts first, and the mutable document starts over afterwards.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use litchi::ooxml::docx::Package;
+    ///
+    /// let mut pkg = Package::open("redline.docx")?;
+    /// let accepted = pkg.accept_all_revisions()?;
+    /// println!("Accepted {} changes", accepted);
+    /// pkg.save("clean.docx")?;
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn accept_all_revisions(&mut self) -> Result<usize> {
+        self.resolve_revisions(None, true)
+    }
+
+    /// Reject all tracked changes.
+    ///
+    /// Insertions are removed, deleted text is restored and formatting
+    /// changes revert to the recorded earlier properties. Returns the
+    /// number of revisions rejected.
+    pub fn reject_all_revisions(&mut self) -> Result<usize> {
+        self.resolve_revisions(None, false)
+    }
+
+    /// Accept the tracked changes made by one author.
+    ///
+    /// The author is matched exactly against `w:author`. Returns the number
+    /// of revisions accepted.
+    pub fn accept_revisions_by(&mut self, author: &str) -> Result<usize> {
+        self.resolve_revisions(Some(author), true)
+    }
+
+    /// Reject the tracked changes made by one author.
+    ///
+    /// The author is matched exactly against `w:author`. Returns the number
+    /// of revisions rejected.
+    pub fn reject_revisions_by(&mut self, author: &str) -> Result<usize> {
+        self.resolve_revisions(Some(author), false)
+    }
+
+    /// Fill placeholders in the document with data.
+    ///
+    /// `{{name}}` placeholders and `MERGEFIELD` fields are replaced with
+    /// values, `{{#name}}...{{/name}}` sections repeat paragraphs, table
+    /// rows or runs over lists and keep or remove content by flags, and
+    /// `{{^name}}...{{/name}}` sections are kept when a value is missing.
+    /// Placeholders that Word split over several runs are joined first and
+    /// keep the formatting of their first character. See
+    /// [`merge`](crate::ooxml::common::merge) for the details.
+    ///
+    /// Applies to the main document, headers, footers, footnotes and
+    /// endnotes. Like [`accept_all_revisions`](Self::accept_all_revisions),
+    /// this works on the stored parts and writes pending edits made through
+    /// [`document_mut`](Self::document_mut) first.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use litchi::ooxml::common::TemplateData;
+    /// use litchi::ooxml::docx::Package;
+    ///
+    /// let mut pkg = Package::open("contract-template.docx")?;
+    /// let mut data = TemplateData::new();
+    /// data.set("customer", "ACME Corp.").set(
+    ///     "items",
+    ///     vec![
+    ///         TemplateData::new().with("name", "Widget").with("price", "9.99"),
+    ///         TemplateData::new().with("name", "Gadget").with("price", "19.99"),
+    ///     ],
+    /// );
+    /// pkg.render(&data)?;
+    /// pkg.save("contract-acme.docx")?;
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn render(&mut self, data: &TemplateData) -> Result<()> {
+        self.sync_mutable_document()?;
+        for uri in self.content_part_uris()? {
+            let part = self.opc.get_part_mut(&uri)?;
+            let xml = std::str::from_utf8(part.blob())
+                .map_err(|e| OoxmlError::InvalidFormat(format!("Invalid UTF-8: {}", e)))?;
+            if let Some(xml) = template::render_part(xml, data)? {
+                part.set_blob(xml.into_bytes());
+            }
+        }
+        Ok(())
+    }
+
+    /// Set the value of the content controls with a tag.
+    ///
+    /// Text controls take the text, with newlines starting new paragraphs
+    /// in rich text and line breaks in plain text. Combo boxes and
+    /// drop-down lists take the value or display text of a list item, and
+    /// date pickers an ISO 8601 date, shown in the control's date format.
+    /// The placeholder is cleared and the formatting of the first run is
+    /// kept. Controls bound to custom XML also get the value stored in
+    /// their custom XML part. Returns the number of controls set.
+    ///
+    /// Applies to the main document, headers, footers, footnotes and
+    /// endnotes. This works on the stored parts and writes pending edits
+    /// made through [`document_mut`](Self::document_mut) first.
+    ///
+    /// Fails for a drop-down list value that isn't one of its items, a date
+    /// that isn't a valid ISO 8601 date or date-time, and for controls
+    /// that don't hold text, such as pictures, checkboxes
+    /// (see [`set_content_control_checked`](Self::set_content_control_checked))
+    /// and repeating sections.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use litchi::ooxml::docx::Package;
+    ///
+    /// let mut pkg = Package::open("form.docx")?;
+    /// pkg.set_content_control("customer", "ACME Corp.")?;
+    /// pkg.set_content_control("status", "Approved")?;
+    /// pkg.set_content_control("due", "2025-01-31")?;
+    /// pkg.set_content_control_checked("paid", true)?;
+    /// pkg.save("form-filled.docx")?;
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn set_content_control(&mut self, tag: &str, value: &str) -> Result<usize> {
+        self.update_content_controls(true, |control| {
+            (control.tag.as_deref() == Some(tag)).then(|| Value::Text(value.to_string()))
+        })
+    }
+
+    /// Check or uncheck the checkbox content controls with a tag.
+    ///
+    /// Returns the number of controls set.
+    pub fn set_content_control_checked(&mut self, tag: &str, checked: bool) -> Result<usize> {
+        self.update_content_controls(true, |control| {
+            (control.tag.as_deref() == Some(tag)).then_some(Value::Checked(checked))
+        })
+    }
+
+    /// Replace the data of a custom XML part.
+    ///
+    /// `item_id` is the part's [`item_id`](CustomXmlPart::item_id), as
+    /// used by the `storeItemID` of data bindings. The content controls
+    /// bound to the part are updated to show the new data. Returns the
+    /// number of controls updated.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use litchi::ooxml::docx::Package;
+    ///
+    /// let mut pkg = Package::open("form.docx")?;
+    /// let parts = pkg.document()?.custom_xml_parts()?;
+    /// if let Some(item_id) = parts.first().and_then(|part| part.item_id.clone()) {
+    ///     let xml = std::fs::read_to_string("invoice.xml")?;
+    ///     pkg.set_custom_xml_part(&item_id, &xml)?;
+    /// }
+    /// pkg.save("form-filled.docx")?;
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn set_custom_xml_part(&mut self, item_id: &str, xml: &str) -> Result<usize> {
+        let part = self
+            .document()?
+            .custom_xml_parts()?
+            .into_iter()
+            .find(|part| {
+                part.item_id
+                    .as_deref()
+                    .is_some_and(|id| id.eq_ignore_ascii_case(item_id))
+            })
+            .ok_or_else(|| OoxmlError::PartNotFound(format!("custom XML part {}", item_id)))?;
+        let uri = PackURI::new(&part.partname)
+            .map_err(|e| OoxmlError::InvalidUri(format!("custom XML URI: {}", e)))?;
+        self.opc
+            .get_part_mut(&uri)?
+            .set_blob(xml.as_bytes().to_vec());
+
+        let part = CustomXmlPart {
+            xml: xml.to_string(),
+            ..part
+        };
+        self.update_content_controls(false, |control| {
+            let binding = control.data_binding.as_ref()?;
+            if !part.is_bound_by(binding) {
+                return None;
+            }
+            let value = part.value(binding)?;
+            match control.control_type {
+                ContentControlType::Checkbox => {
+                    Some(Value::Checked(matches!(value.trim(), "true" | "1")))
+                },
+                ContentControlType::DropDownList => {
+                    control.list_item(&value).map(|_| Value::Text(value))
+                },
+                ContentControlType::Date => {
+                    content_control::is_date(&value).then_some(Value::Text(value))
+                },
+                ContentControlType::PlainText
+                | ContentControlType::RichText
+                | ContentControlType::ComboBox => Some(Value::Text(value)),
+                _ => None,
+            }
+        })
+    }
+
+    /// Add a custom XML data part to the document.
+    ///
+    /// `schemas` lists the namespaces of the schemas the data follows.
+    /// Returns the item ID of the new part, for the `storeItemID` of data
+    /// bindings.
+    pub fn add_custom_xml_part(&mut self, xml: &str, schemas: &[&str]) -> Result<String> {
+        use crate::ooxml::common::guid;
+        use crate::ooxml::opc::constants::relationship_type as rt;
+        use crate::ooxml::opc::part::{BlobPart, Part};
+
+        let mut index = 1;
+        let (item_uri, props_uri) = loop {
+            let item_uri = PackURI::new(format!("/customXml/item{}.xml", index))
+                .map_err(|e| OoxmlError::InvalidUri(format!("custom XML URI: {}", e)))?;
+            let props_uri = PackURI::new(format!("/customXml/itemProps{}.xml", index))
+                .map_err(|e| OoxmlError::InvalidUri(format!("custom XML URI: {}", e)))?;
+            if self.opc.get_part(&item_uri).is_err() && self.opc.get_part(&props_uri).is_err() {
+                break (item_uri, props_uri);
+            }
+            index += 1;
+        };
+
+        let item_id = guid(&format!("{}{}", item_uri, xml));
+        let props = BlobPart::new(
+            props_uri,
+            ct::OFC_CUSTOM_XML_PROPERTIES.to_string(),
+            custom_xml::properties_xml(&item_id, schemas).into_bytes(),
+        );
+        self.opc.add_part(Box::new(props));
+
+        let mut item = BlobPart::new(item_uri, ct::XML.to_string(), xml.as_bytes().to_vec());
+        item.relate_to(&format!("itemProps{}.xml", index), rt::CUSTOM_XML_PROPS);
+        self.opc.add_part(Box::new(item));
+
+        let main_uri = self.opc.main_document_part()?.partname().clone();
+        self.opc
+            .get_part_mut(&main_uri)?
+            .relate_to(&format!("../customXml/item{}.xml", index), rt::CUSTOM_XML);
+        Ok(item_id)
+    }
+
+    /// Set content control values in every part that holds document
+    /// content, storing the values of bound controls in their custom XML
+    /// parts when `store` is set.
+    fn update_content_controls(
+        &mut self,
+        store: bool,
+        mut value_for: impl FnMut(&ContentControl) -> Option<Value>,
+    ) -> Result<usize> {
+        self.sync_mutable_document()?;
+        let mut count = 0;
+        let mut bound = Vec::new();
+        for uri in self.content_part_uris()? {
+            let part = self.opc.get_part_mut(&uri)?;
+            let xml = std::str::from_utf8(part.blob())
+                .map_err(|e| OoxmlError::InvalidFormat(format!("Invalid UTF-8: {}", e)))?;
+            if let Some(update) = content_control::update(xml, &mut value_for)? {
+                part.set_blob(update.xml.into_bytes());
+                count += update.count;
+                bound.extend(update.bound);
+            }
+        }
+
+        if store && !bound.is_empty() {
+            for part in self.document()?.custom_xml_parts()? {
+                let mut xml = part.xml.clone();
+                for (binding, value) in bound
+                    .iter()
+                    .filter(|(binding, _)| part.is_bound_by(binding))
+                {
+                    if let Some(updated) = custom_xml::set_value(&xml, binding, value)? {
+                        xml = updated;
+                    }
+                }
+                if xml != part.xml {
+                    let uri = PackURI::new(&part.partname)
+                        .map_err(|e| OoxmlError::InvalidUri(format!("custom XML URI: {}", e)))?;
+                    self.opc.get_part_mut(&uri)?.set_blob(xml.into_bytes());
+                }
+            }
+        }
+        Ok(count)
+    }
+
+    /// Get the parts that hold document content: the main document,
+    /// headers, footers, footnotes and endnotes.
+    fn content_part_uris(&self) -> Result<Vec<PackURI>> {
+        let main_uri = self.opc.main_document_part()?.partname().clone();
+        Ok(self
+            .opc
+            .iter_parts()
+            .filter(|part| {
+                *part.partname() == main_uri
+                    || matches!(
+                        part.content_type(),
+                        ct::WML_HEADER | ct::WML_FOOTER | ct::WML_FOOTNOTES | ct::WML_ENDNOTES
+                    )
+            })
+            .map(|part| part.partname().clone())
+            .collect())
+    }
+
+    /// Accept or reject revisions in every part that holds document content.
+    fn resolve_revisions(&mut self, author: Option<&str>, accept: bool) -> Result<usize> {
+        self.sync_mutable_document()?;
+        let mut count = 0;
+        for uri in self.content_part_uris()? {
+            let part = self.opc.get_part_mut(&uri)?;
+            let xml = std::str::from_utf8(part.blob())
+                .map_err(|e| OoxmlError::InvalidFormat(format!("Invalid UTF-8: {}", e)))?;
+            if let Some((xml, resolved)) = revision::resolve(xml, author, accept)? {
+                part.set_blob(xml.into_bytes());
+                count += resolved;
+            }
+        }
+        Ok(count)
+    }
+
     /// Update the core.xml properties part.
     fn update_core_properties(&mut self) -> Result<()> {
         use crate::ooxml::opc::part::BlobPart;
@@ -624,6 +1091,46 @@ impl Package {
         Ok(())
     }
 
+    /// Get the XML of a definitions part such as styles.xml or
+    /// comments.xml, if the package has one.
+    fn definitions_part_xml(&self, partname: &str) -> Result<Option<String>> {
+        let uri = PackURI::new(partname)
+            .map_err(|e| OoxmlError::InvalidUri(format!("{} URI: {}", partname, e)))?;
+        match self.opc.get_part(&uri) {
+            Ok(part) => String::from_utf8(part.blob().to_vec())
+                .map(Some)
+                .map_err(|e| OoxmlError::InvalidFormat(format!("Invalid UTF-8: {}", e))),
+            Err(_) => Ok(None),
+        }
+    }
+
+    /// Replace a definitions part such as styles.xml or comments.xml,
+    /// relating it to the document unless it already is.
+    fn update_definitions_part(
+        &mut self,
+        partname: &str,
+        content_type: &str,
+        reltype: &str,
+        xml: String,
+    ) -> Result<()> {
+        use crate::ooxml::opc::part::BlobPart;
+
+        let uri = PackURI::new(partname)
+            .map_err(|e| OoxmlError::InvalidUri(format!("{} URI: {}", partname, e)))?;
+        let part = BlobPart::new(uri, content_type.to_string(), xml.into_bytes());
+        self.opc.add_part(Box::new(part));
+
+        let doc_uri = PackURI::new("/word/document.xml")
+            .map_err(|e| OoxmlError::InvalidUri(format!("document URI: {}", e)))?;
+        if let Ok(doc_part) = self.opc.get_part_mut(&doc_uri)
+            && doc_part.rels().part_with_reltype(reltype).is_err()
+        {
+            doc_part.relate_to(partname.trim_start_matches("/word/"), reltype);
+        }
+
+        Ok(())
+    }
+
     /// Update the endnotes.xml part with new content.
     fn update_endnotes_part(&mut self, xml: String) -> Result<()> {
         use crate::ooxml::opc::constants::content_type as ct;
@@ -654,6 +1161,7 @@ impl Package {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use std::io::Cursor;
 
     #[test]
     #[ignore] // Requires test file
@@ -661,4 +1169,112 @@ mod tests {
         let result = Package::open("test.docx");
         assert!(result.is_ok());
     }
+
+    #[test]
+    fn test_revisions_keep_pending_edits() {
+        let mut pkg = Package::new().unwrap();
+        pkg.document_mut()
+            .unwrap()
+            .add_paragraph_with_text("Pending edit");
+        assert_eq!(pkg.accept_all_revisions().unwrap(), 0);
+        assert!(pkg.mutable_doc.is_none());
+
+        let text = pkg.document().unwrap().text().unwrap();
+        assert!(text.contains("Pending edit"));
+    }
+
+    #[test]
+    fn test_render_keeps_pending_edits() {
+        let mut pkg = Package::new().unwrap();
+        pkg.document_mut()
+            .unwrap()
+            .add_paragraph_with_text("Hi {{name}}");
+        pkg.render(&TemplateData::new().with("name", "Ann"))
+            .unwrap();
+        assert!(pkg.mutable_doc.is_none());
+
+        let text = pkg.document().unwrap().text().unwrap();
+        assert!(text.contains("Hi Ann"));
+    }
+
+    #[test]
+    fn test_content_controls_keep_pending_edits() {
+        let mut pkg = Package::new().unwrap();
+        pkg.document_mut()
+            .unwrap()
+            .add_paragraph_with_text("Pending edit");
+        assert_eq!(pkg.set_content_control("customer", "ACME").unwrap(), 0);
+        assert!(pkg.mutable_doc.is_none());
+
+        let text = pkg.document().unwrap().text().unwrap();
+        assert!(text.contains("Pending edit"));
+    }
+
+    #[test]
+    fn test_content_controls_with_custom_xml() {
+        let mut pkg = Package::new().unwrap();
+        let item_id = pkg
+            .add_custom_xml_part(
+                "<invoice xmlns=\"urn:invoice\"><customer/><paid>false</paid></invoice>",
+                &["urn:invoice"],
+            )
+            .unwrap();
+        let binding = |xpath: &str| {
+            format!(
+                "<w:dataBinding w:prefixMappings=\"xmlns:ns0='urn:invoice'\" \
+                 w:xpath=\"{}\" w:storeItemID=\"{}\"/>",
+                xpath, item_id
+            )
+        };
+        let body = format!(
+            "<w:document xmlns:w=\"http://schemas.openxmlformats.org/wordprocessingml/2006/main\" \
+             xmlns:w14=\"http://schemas.microsoft.com/office/word/2010/wordml\"><w:body>\
+             <w:sdt><w:sdtPr><w:tag w:val=\"customer\"/>{}<w:text/></w:sdtPr>\
+             <w:sdtContent><w:p><w:r><w:t>Customer</w:t></w:r></w:p></w:sdtContent></w:sdt>\
+             <w:p><w:sdt><w:sdtPr><w:tag w:val=\"paid\"/>{}<w14:checkbox>\
+             <w14:checked w14:val=\"0\"/></w14:checkbox></w:sdtPr><w:sdtContent><w:r>\
+             <w:t>&#x2610;</w:t></w:r></w:sdtContent></w:sdt></w:p></w:body></w:document>",
+            binding("/ns0:invoice[1]/ns0:customer[1]"),
+            binding("/ns0:invoice[1]/ns0:paid[1]")
+        );
+        let uri = pkg.opc.main_document_part().unwrap().partname().clone();
+        pkg.opc
+            .get_part_mut(&uri)
+            .unwrap()
+            .set_blob(body.into_bytes());
+
+        assert_eq!(pkg.set_content_control("customer", "ACME").unwrap(), 1);
+        assert_eq!(pkg.set_content_control("missing", "x").unwrap(), 0);
+        assert!(pkg.set_content_control("paid", "yes").is_err());
+        let mut buffer = Cursor::new(Vec::new());
+        pkg.save_to_writer(&mut buffer).unwrap();
+
+        let mut pkg = Package::from_reader(buffer).unwrap();
+        {
+            let doc = pkg.document().unwrap();
+            let controls = doc.content_controls().unwrap();
+            assert_eq!(controls[0].text, "ACME");
+            let parts = doc.custom_xml_parts().unwrap();
+            assert_eq!(parts.len(), 1);
+            assert_eq!(parts[0].item_id.as_deref(), Some(item_id.as_str()));
+            assert_eq!(parts[0].schemas, vec!["urn:invoice"]);
+            assert_eq!(
+                parts[0]
+                    .value(controls[0].data_binding.as_ref().unwrap())
+                    .as_deref(),
+                Some("ACME")
+            );
+        }
+
+        let updated = pkg
+            .set_custom_xml_part(
+                &item_id,
+                "<invoice xmlns=\"urn:invoice\"><customer>Initech</customer><paid>true</paid></invoice>",
+            )
+            .unwrap();
+        assert_eq!(updated, 2);
+        let controls = pkg.document().unwrap().content_controls().unwrap();
+        assert_eq!(controls[0].text, "Initech");
+        assert_eq!(controls[1].checked, Some(true));
+    }
 }
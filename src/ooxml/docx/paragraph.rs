@@ -1,10 +1,15 @@
 /// Paragraph and Run structures for Word documents.
-use crate::common::VerticalPosition;
+use crate::common::field::hyperlink_target as hyperlink_field_target;
+use crate::common::{ListFormat, ListInfo, RGBColor, TextAlignment, VerticalPosition};
+use crate::ooxml::docx::field::{self, Bookmark, Field, Hyperlink};
+use crate::ooxml::docx::outline::{OutlineContext, heading_level_from_outline};
+use crate::ooxml::docx::revision::{self, Revision, RevisionType, RevisionView};
 use crate::ooxml::error::{OoxmlError, Result};
 use quick_xml::Reader;
-use quick_xml::events::Event;
+use quick_xml::events::{BytesStart, Event};
 use smallvec::SmallVec;
 use std::borrow::Cow;
+use std::sync::Arc;
 
 /// A paragraph in a Word document.
 ///
@@ -25,6 +30,18 @@ use std::borrow::Cow;
 pub struct Paragraph {
     /// The raw XML bytes for this paragraph
     xml_bytes: Vec<u8>,
+    /// Styles, numbering and relationships of the containing document
+    outline: Option<Arc<OutlineContext>>,
+}
+
+/// Structure-related paragraph properties read from `<w:pPr>`.
+#[derive(Debug, Default)]
+struct ParagraphOutline {
+    style_id: Option<String>,
+    outline_level: Option<u8>,
+    num_id: Option<u32>,
+    ilvl: Option<u8>,
+    alignment: Option<TextAlignment>,
 }
 
 impl Paragraph {
@@ -34,7 +51,183 @@ impl Paragraph {
     ///
     /// * `xml_bytes` - The XML content of the `<w:p>` element
     pub fn new(xml_bytes: Vec<u8>) -> Self {
-        Self { xml_bytes }
+        Self {
+            xml_bytes,
+            outline: None,
+        }
+    }
+
+    /// Attach the document context used to resolve styles, numbering and
+    /// hyperlink targets.
+    pub(crate) fn with_outline(mut self, outline: Arc<OutlineContext>) -> Self {
+        self.outline = Some(outline);
+        self
+    }
+
+    /// Get the paragraph style ID (`w:pStyle`).
+    pub fn style_id(&self) -> Result<Option<String>> {
+        Ok(self.outline_properties()?.style_id)
+    }
+
+    /// Get the paragraph style's display name, e.g. "heading 1".
+    ///
+    /// Falls back to the style ID when the styles part is unavailable.
+    pub fn style_name(&self) -> Result<Option<String>> {
+        let style_id = self.outline_properties()?.style_id;
+        Ok(style_id.map(|id| {
+            self.outline
+                .as_deref()
+                .and_then(|outline| outline.style_name(&id))
+                .map(str::to_string)
+                .unwrap_or(id)
+        }))
+    }
+
+    /// Get the outline level (0-based) set directly on the paragraph with
+    /// `w:outlineLvl`; level 9 means body text.
+    pub fn outline_level(&self) -> Result<Option<u8>> {
+        Ok(self.outline_properties()?.outline_level)
+    }
+
+    /// Get the numbering set directly on the paragraph with `w:numPr`, as
+    /// `(numId, ilvl)`.
+    pub fn numbering(&self) -> Result<Option<(u32, u8)>> {
+        let props = self.outline_properties()?;
+        Ok(props.num_id.map(|num_id| (num_id, props.ilvl.unwrap_or(0))))
+    }
+
+    /// Get the heading level (1-9) of this paragraph.
+    ///
+    /// A direct `w:outlineLvl` takes precedence over the paragraph style,
+    /// whose level comes from its own outline level, its name ("heading 1"
+    /// to "heading 9") or the style it is based on.
+    pub fn heading_level(&self) -> Result<Option<u8>> {
+        let props = self.outline_properties()?;
+        if let Some(level) = props.outline_level {
+            return Ok(heading_level_from_outline(level));
+        }
+        let Some(style_id) = props.style_id else {
+            return Ok(None);
+        };
+        Ok(match self.outline.as_deref() {
+            Some(outline) => outline.style_heading_level(&style_id),
+            None => OutlineContext::default().style_heading_level(&style_id),
+        })
+    }
+
+    /// Get the alignment set directly on the paragraph with `w:jc`.
+    ///
+    /// Returns `None` if the alignment is inherited from the style.
+    pub fn alignment(&self) -> Result<Option<TextAlignment>> {
+        Ok(self.outline_properties()?.alignment)
+    }
+
+    /// Get this paragraph's list membership.
+    ///
+    /// Numbering comes from `w:numPr` on the paragraph or its style; a
+    /// `w:numId` of 0 removes the paragraph from a list. The format and start
+    /// value are read from the numbering part, defaulting to bullets when it
+    /// is unavailable.
+    pub fn list_info(&self) -> Result<Option<ListInfo>> {
+        let props = self.outline_properties()?;
+        let outline = self.outline.as_deref();
+        let style_numbering = props
+            .style_id
+            .as_deref()
+            .zip(outline)
+            .and_then(|(style_id, outline)| outline.style_numbering(style_id));
+
+        let (num_id, ilvl) = match (props.num_id, style_numbering) {
+            (Some(num_id), _) => (num_id, props.ilvl.unwrap_or(0)),
+            (None, Some((num_id, style_ilvl))) => (num_id, props.ilvl.unwrap_or(style_ilvl)),
+            (None, None) => return Ok(None),
+        };
+        if num_id == 0 {
+            return Ok(None);
+        }
+
+        let info = outline
+            .and_then(|outline| outline.list_info(num_id, ilvl))
+            .unwrap_or_else(|| ListInfo::new(ilvl, ListFormat::Bullet));
+        Ok(Some(info))
+    }
+
+    /// Read the structure-related properties from `<w:pPr>`.
+    fn outline_properties(&self) -> Result<ParagraphOutline> {
+        let mut reader = Reader::from_reader(&self.xml_bytes[..]);
+        reader.config_mut().trim_text(true);
+
+        let mut props = ParagraphOutline::default();
+        let mut in_ppr = false;
+        let mut buf = Vec::with_capacity(256);
+
+        loop {
+            match reader.read_event_into(&mut buf) {
+                Ok(Event::Start(e)) if e.local_name().as_ref() == b"pPr" => in_ppr = true,
+                Ok(Event::Start(e)) if !in_ppr && e.local_name().as_ref() != b"p" => break,
+                Ok(Event::Empty(e)) if in_ppr => {
+                    let value = || {
+                        e.attributes()
+                            .flatten()
+                            .find(|attr| attr.key.local_name().as_ref() == b"val")
+                            .and_then(|attr| attr.unescape_value().ok())
+                            .map(|value| value.into_owned())
+                    };
+                    match e.local_name().as_ref() {
+                        b"pStyle" => props.style_id = value(),
+                        b"outlineLvl" => props.outline_level = value().and_then(|v| v.parse().ok()),
+                        b"numId" => props.num_id = value().and_then(|v| v.parse().ok()),
+                        b"ilvl" => props.ilvl = value().and_then(|v| v.parse().ok()),
+                        b"jc" => {
+                            props.alignment = match value().as_deref() {
+                                Some("left" | "start") => Some(TextAlignment::Left),
+                                Some("center") => Some(TextAlignment::Center),
+                                Some("right" | "end") => Some(TextAlignment::Right),
+                                Some("both" | "distribute") => Some(TextAlignment::Justify),
+                                _ => None,
+                            }
+                        },
+                        _ => {},
+                    }
+                },
+                Ok(Event::End(e)) if e.local_name().as_ref() == b"pPr" => break,
+                Ok(Event::Eof) => break,
+                Err(e) => return Err(OoxmlError::Xml(e.to_string())),
+                _ => {},
+            }
+            buf.clear();
+        }
+
+        Ok(props)
+    }
+
+    /// Resolve the target of a `<w:hyperlink>`: its relationship's URL, its
+    /// bookmark anchor (as `#name`), or both.
+    fn hyperlink_target(&self, e: &BytesStart) -> Option<String> {
+        let mut url = None;
+        let mut anchor = None;
+        for attr in e.attributes().flatten() {
+            let Ok(value) = attr.unescape_value() else {
+                continue;
+            };
+            match attr.key.local_name().as_ref() {
+                b"id" => {
+                    url = self
+                        .outline
+                        .as_deref()
+                        .and_then(|outline| outline.hyperlink(&value))
+                        .map(str::to_string)
+                },
+                b"anchor" => anchor = Some(value.into_owned()),
+                _ => {},
+            }
+        }
+        match (url, anchor) {
+            (Some(url), Some(anchor)) => Some(format!("{}#{}", url, anchor)),
+            (Some(url), None) => Some(url),
+            (None, Some(anchor)) => Some(format!("#{}", anchor)),
+            (None, None) => None,
+        }
     }
 
     /// Get the text content of this paragraph.
@@ -96,10 +289,52 @@ impl Paragraph {
         let mut in_run = false;
         let mut depth = 0;
         let mut buf = Vec::with_capacity(1024); // Reusable buffer (increased from 512)
+        // Target of the enclosing <w:hyperlink>
+        let mut link: Option<String> = None;
+        // Target of the enclosing HYPERLINK <w:fldSimple>
+        let mut simple_link: Option<String> = None;
+        // Complex fields (w:fldChar): nesting depth, instruction of the
+        // outermost field, and its target once the instruction is complete
+        let mut field_depth = 0usize;
+        let mut field_instruction = String::new();
+        let mut field_link: Option<String> = None;
+        let mut in_instr_text = false;
+        // Enclosing tracked-change containers (w:ins, w:del, ...)
+        let mut revisions: Vec<RevisionType> = Vec::new();
 
         loop {
             match reader.read_event_into(&mut buf) {
+                Ok(Event::Start(ref e))
+                    if !in_run && let Some(revision) = run_revision(e.local_name().as_ref()) =>
+                {
+                    revisions.push(revision);
+                },
+                Ok(Event::End(ref e))
+                    if !in_run && run_revision(e.local_name().as_ref()).is_some() =>
+                {
+                    revisions.pop();
+                },
+                Ok(Event::Start(ref e)) if !in_run && e.local_name().as_ref() == b"hyperlink" => {
+                    link = self.hyperlink_target(e);
+                },
+                Ok(Event::End(ref e)) if !in_run && e.local_name().as_ref() == b"hyperlink" => {
+                    link = None;
+                },
+                Ok(Event::Start(ref e)) if !in_run && e.local_name().as_ref() == b"fldSimple" => {
+                    simple_link = e
+                        .attributes()
+                        .flatten()
+                        .find(|attr| attr.key.local_name().as_ref() == b"instr")
+                        .and_then(|attr| attr.unescape_value().ok())
+                        .and_then(|instr| hyperlink_field_target(&instr));
+                },
+                Ok(Event::End(ref e)) if !in_run && e.local_name().as_ref() == b"fldSimple" => {
+                    simple_link = None;
+                },
                 Ok(Event::Start(ref e)) => {
+                    if in_run && e.local_name().as_ref() == b"instrText" {
+                        in_instr_text = true;
+                    }
                     // Check for w:r (word run) specifically using the full name
                     // This avoids confusion with m:r (math run) which appears in OMML formulas
                     let is_word_run = e.local_name().as_ref() == b"r"
@@ -138,6 +373,9 @@ impl Paragraph {
                     }
                 },
                 Ok(Event::End(ref e)) => {
+                    if e.local_name().as_ref() == b"instrText" {
+                        in_instr_text = false;
+                    }
                     if in_run {
                         current_run_xml.extend_from_slice(b"</");
                         current_run_xml.extend_from_slice(e.name().as_ref());
@@ -150,15 +388,49 @@ impl Paragraph {
 
                         depth -= 1;
                         if is_word_run_end && depth == 0 {
-                            runs.push(Run::new(current_run_xml.clone()));
+                            let mut run = Run::new(current_run_xml.clone());
+                            run.hyperlink = link
+                                .clone()
+                                .or_else(|| field_link.clone())
+                                .or_else(|| simple_link.clone());
+                            run.revision = revisions.last().copied();
+                            runs.push(run);
                             in_run = false;
                         }
                     }
                 },
                 Ok(Event::Text(e)) if in_run => {
+                    if in_instr_text && field_depth == 1 {
+                        field_instruction.push_str(&String::from_utf8_lossy(e.as_ref()));
+                    }
                     current_run_xml.extend_from_slice(e.as_ref());
                 },
                 Ok(Event::Empty(e)) if in_run => {
+                    if e.local_name().as_ref() == b"fldChar" {
+                        let char_type = e
+                            .attributes()
+                            .flatten()
+                            .find(|attr| attr.key.local_name().as_ref() == b"fldCharType")
+                            .map(|attr| attr.value.into_owned());
+                        match char_type.as_deref() {
+                            Some(b"begin") => {
+                                field_depth += 1;
+                                if field_depth == 1 {
+                                    field_instruction.clear();
+                                }
+                            },
+                            Some(b"separate") if field_depth == 1 => {
+                                field_link = hyperlink_field_target(&field_instruction);
+                            },
+                            Some(b"end") => {
+                                field_depth = field_depth.saturating_sub(1);
+                                if field_depth == 0 {
+                                    field_link = None;
+                                }
+                            },
+                            _ => {},
+                        }
+                    }
                     current_run_xml.push(b'<');
                     current_run_xml.extend_from_slice(e.name().as_ref());
                     for attr in e.attributes().flatten() {
@@ -180,6 +452,57 @@ impl Paragraph {
         Ok(runs)
     }
 
+    /// Get the fields in this paragraph, in the order they start.
+    ///
+    /// Covers `w:fldSimple` elements and complex fields, including fields
+    /// nested in other fields. A complex field continuing into the next
+    /// paragraph is returned with the part of its result in this one; use
+    /// [`Document::fields`](super::Document::fields) for whole fields.
+    pub fn fields(&self) -> Result<Vec<Field>> {
+        Ok(self.scan()?.fields)
+    }
+
+    /// Get the hyperlinks in this paragraph.
+    ///
+    /// Covers `w:hyperlink` elements and `HYPERLINK` fields. External
+    /// targets need the paragraph to come from a
+    /// [`Document`](super::Document).
+    pub fn hyperlinks(&self) -> Result<Vec<Hyperlink>> {
+        Ok(self.scan()?.hyperlinks)
+    }
+
+    /// Get the bookmarks that start in this paragraph.
+    ///
+    /// Positions are relative to this paragraph (index 0); a bookmark
+    /// continuing into later paragraphs ends at the end of this one. Use
+    /// [`Document::bookmarks`](super::Document::bookmarks) for bookmarks
+    /// spanning paragraphs.
+    pub fn bookmarks(&self) -> Result<Vec<Bookmark>> {
+        Ok(self.scan()?.bookmarks)
+    }
+
+    fn scan(&self) -> Result<field::Markup> {
+        field::scan(&self.xml_bytes, self.outline.as_deref())
+    }
+
+    /// Get the tracked changes in this paragraph.
+    ///
+    /// Revision paragraph indices are 0; see
+    /// [`Document::revisions`](super::Document::revisions) for the whole
+    /// body.
+    pub fn revisions(&self) -> Result<Vec<Revision>> {
+        revision::revisions(&self.xml_bytes)
+    }
+
+    /// Get the paragraph text with tracked changes applied or undone.
+    ///
+    /// [`RevisionView::Final`] shows insertions and hides deletions;
+    /// [`RevisionView::Original`] shows the text before the changes.
+    /// Unlike [`text()`](Self::text), whitespace is kept as written.
+    pub fn text_with_view(&self, view: RevisionView) -> Result<String> {
+        revision::view_text(&self.xml_bytes, view)
+    }
+
     /// Extract all OMML formulas from this paragraph.
     ///
     /// Returns a vector of OMML formula strings found in any run within this paragraph.
@@ -384,6 +707,17 @@ impl Paragraph {
     }
 }
 
+/// Get the revision type of a tracked-change container around runs.
+fn run_revision(local_name: &[u8]) -> Option<RevisionType> {
+    match local_name {
+        b"ins" => Some(RevisionType::Insertion),
+        b"del" => Some(RevisionType::Deletion),
+        b"moveFrom" => Some(RevisionType::MoveFrom),
+        b"moveTo" => Some(RevisionType::MoveTo),
+        _ => None,
+    }
+}
+
 /// A run within a paragraph.
 ///
 /// Represents a `<w:r>` element. A run is a region of text with a single
@@ -422,12 +756,39 @@ pub struct RunProperties {
 pub struct Run {
     /// The raw XML bytes for this run
     xml_bytes: Vec<u8>,
+    /// Target of the hyperlink containing this run
+    hyperlink: Option<String>,
+    /// Tracked change containing this run
+    revision: Option<RevisionType>,
 }
 
 impl Run {
     /// Create a new Run from XML bytes.
     pub fn new(xml_bytes: Vec<u8>) -> Self {
-        Self { xml_bytes }
+        Self {
+            xml_bytes,
+            hyperlink: None,
+            revision: None,
+        }
+    }
+
+    /// Get the target of the hyperlink this run belongs to.
+    ///
+    /// Set for runs inside `<w:hyperlink>` or the result of a `HYPERLINK`
+    /// field. External targets are URLs, which need the paragraph to come
+    /// from a [`Document`](super::Document); links to bookmarks are
+    /// returned as `#name`.
+    pub fn hyperlink(&self) -> Option<&str> {
+        self.hyperlink.as_deref()
+    }
+
+    /// Get the type of tracked change this run belongs to.
+    ///
+    /// Set for runs inside `<w:ins>`, `<w:del>`, `<w:moveFrom>` or
+    /// `<w:moveTo>`. Deleted runs keep their text in `w:delText`, which
+    /// [`text()`](Self::text) doesn't return.
+    pub fn revision(&self) -> Option<RevisionType> {
+        self.revision
     }
 
     /// Get the text content of this run.
@@ -925,6 +1286,46 @@ impl Run {
         Ok(None)
     }
 
+    /// Get the text color for this run.
+    ///
+    /// Returns the `w:color` value if specified, None if inherited or `auto`.
+    pub fn color(&self) -> Result<Option<RGBColor>> {
+        let mut reader = Reader::from_reader(&self.xml_bytes[..]);
+        reader.config_mut().trim_text(true);
+
+        let mut in_r_pr = false;
+        let mut buf = Vec::new();
+
+        loop {
+            match reader.read_event_into(&mut buf) {
+                Ok(Event::Start(e)) | Ok(Event::Empty(e)) => {
+                    let name = e.local_name();
+                    if name.as_ref() == b"rPr" {
+                        in_r_pr = true;
+                    } else if in_r_pr && name.as_ref() == b"color" {
+                        for attr in e.attributes().flatten() {
+                            if attr.key.local_name().as_ref() == b"val" {
+                                let value = attr.unescape_value().unwrap_or(Cow::Borrowed(""));
+                                return Ok(RGBColor::from_hex(&value));
+                            }
+                        }
+                    }
+                },
+                Ok(Event::End(e)) => {
+                    if e.local_name().as_ref() == b"rPr" {
+                        break;
+                    }
+                },
+                Ok(Event::Eof) => break,
+                Err(e) => return Err(OoxmlError::Xml(e.to_string())),
+                _ => {},
+            }
+            buf.clear();
+        }
+
+        Ok(None)
+    }
+
     /// Check if this run contains an OMML formula.
     ///
     /// Returns the OMML XML content if this run contains a mathematical formula,
@@ -1214,4 +1615,37 @@ mod tests {
         let run = Run::new(xml.to_vec());
         assert!(run.italic().unwrap().unwrap_or(false));
     }
+
+    #[test]
+    fn test_alignment_and_color() {
+        let xml = br#"<w:p xmlns:w="http://schemas.openxmlformats.org/wordprocessingml/2006/main">
+            <w:pPr><w:jc w:val="center"/></w:pPr>
+            <w:r><w:rPr><w:color w:val="FF0000"/></w:rPr><w:t>Red</w:t></w:r>
+            <w:r><w:rPr><w:color w:val="auto"/></w:rPr><w:t>Auto</w:t></w:r>
+        </w:p>"#;
+
+        let para = Paragraph::new(xml.to_vec());
+        assert_eq!(para.alignment().unwrap(), Some(TextAlignment::Center));
+        let runs = para.runs().unwrap();
+        assert_eq!(runs[0].color().unwrap(), Some(RGBColor::new(255, 0, 0)));
+        assert_eq!(runs[1].color().unwrap(), None);
+    }
+
+    #[test]
+    fn test_simple_field_hyperlink() {
+        let xml = br#"<w:p xmlns:w="http://schemas.openxmlformats.org/wordprocessingml/2006/main">
+            <w:fldSimple w:instr=" HYPERLINK &quot;https://example.com&quot; "><w:r><w:t>Site</w:t></w:r></w:fldSimple>
+            <w:r><w:t> and more</w:t></w:r>
+        </w:p>"#;
+
+        let para = Paragraph::new(xml.to_vec());
+        let runs = para.runs().unwrap();
+        assert_eq!(runs[0].hyperlink(), Some("https://example.com"));
+        assert_eq!(runs[1].hyperlink(), None);
+
+        let links = para.hyperlinks().unwrap();
+        assert_eq!(links.len(), 1);
+        assert_eq!(links[0].target().as_deref(), Some("https://example.com"));
+        assert_eq!(links[0].text, "Site");
+    }
 }
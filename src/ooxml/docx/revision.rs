@@ -0,0 +1,573 @@
+/// Tracked changes (revisions) in Word documents.
+///
+/// Word records revisions in the markup itself:
+///
+/// - `w:ins` and `w:del` wrap inserted and deleted runs; deleted text is
+///   stored in `w:delText` (and `w:delInstrText` for field instructions)
+/// - `w:moveFrom` and `w:moveTo` wrap the source and destination of moved
+///   text, delimited by `w:moveFromRangeStart/End` and
+///   `w:moveToRangeStart/End`
+/// - empty `w:ins`/`w:del` markers in a paragraph's `w:pPr/w:rPr` record an
+///   inserted or deleted paragraph mark, and in `w:trPr` an inserted or
+///   deleted table row
+/// - `w:rPrChange`, `w:pPrChange`, `w:tblPrChange`, `w:trPrChange`,
+///   `w:tcPrChange`, `w:sectPrChange`, `w:tblPrExChange` and
+///   `w:tblGridChange` hold the properties from before a formatting change
+///
+/// Accepting and rejecting works on a light tree that keeps the original
+/// markup of everything it doesn't change.
+use crate::ooxml::common::markup::{self, Element, Node};
+use crate::ooxml::error::{OoxmlError, Result};
+use std::collections::HashSet;
+
+/// Type of a tracked change.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RevisionType {
+    /// Inserted content (`w:ins`), paragraph mark or table row
+    Insertion,
+    /// Deleted content (`w:del`), paragraph mark or table row
+    Deletion,
+    /// Source of moved content (`w:moveFrom`)
+    MoveFrom,
+    /// Destination of moved content (`w:moveTo`)
+    MoveTo,
+    /// Run formatting change (`w:rPrChange`)
+    RunFormat,
+    /// Paragraph formatting change (`w:pPrChange`)
+    ParagraphFormat,
+    /// Table, row or cell formatting change (`w:tblPrChange`,
+    /// `w:trPrChange`, `w:tcPrChange`, `w:tblPrExChange`, `w:tblGridChange`)
+    TableFormat,
+    /// Section formatting change (`w:sectPrChange`)
+    SectionFormat,
+}
+
+/// A tracked change.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Revision {
+    /// Revision ID (`w:id`)
+    pub id: String,
+    /// Type of change
+    pub revision_type: RevisionType,
+    /// Author who made the change
+    pub author: Option<String>,
+    /// Date and time of the change, as written (ISO 8601)
+    pub date: Option<String>,
+    /// Text affected by the change
+    ///
+    /// Inserted, deleted or moved text; the text of the run, paragraph,
+    /// row or table whose formatting changed; `"\n"` for an inserted or
+    /// deleted paragraph mark.
+    pub text: String,
+    /// Index of the paragraph where the change is, counted like
+    /// `paragraphs()`
+    pub paragraph: usize,
+}
+
+/// Which side of the tracked changes text extraction shows.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum RevisionView {
+    /// Text with all changes accepted: insertions shown, deletions hidden
+    #[default]
+    Final,
+    /// Text before the changes: deletions shown, insertions hidden
+    Original,
+}
+
+/// Get the revision type of a change-tracking element.
+fn revision_type(local_name: &str) -> Option<RevisionType> {
+    Some(match local_name {
+        "ins" | "cellIns" => RevisionType::Insertion,
+        "del" | "cellDel" => RevisionType::Deletion,
+        "moveFrom" => RevisionType::MoveFrom,
+        "moveTo" => RevisionType::MoveTo,
+        "rPrChange" => RevisionType::RunFormat,
+        "pPrChange" => RevisionType::ParagraphFormat,
+        "tblPrChange" | "trPrChange" | "tcPrChange" | "tblPrExChange" | "tblGridChange" => {
+            RevisionType::TableFormat
+        },
+        "sectPrChange" => RevisionType::SectionFormat,
+        _ => return None,
+    })
+}
+
+/// Whether an element holds properties that may carry revision markers.
+fn is_properties(local_name: &str) -> bool {
+    matches!(
+        local_name,
+        "rPr" | "pPr" | "tblPr" | "trPr" | "tcPr" | "sectPr" | "tblPrEx" | "tblGrid"
+    )
+}
+
+/// Whether the element's content is removed in the given view.
+fn is_hidden(element: &Element, view: RevisionView) -> bool {
+    !element.is_empty()
+        && match view {
+            RevisionView::Final => matches!(element.local_name(), "del" | "moveFrom"),
+            RevisionView::Original => matches!(element.local_name(), "ins" | "moveTo"),
+        }
+}
+
+/// Find the revision marker on a paragraph mark (`w:pPr/w:rPr`) or a
+/// table row (`w:trPr`).
+fn marker(element: &Element) -> Option<&Element> {
+    let properties = match element.local_name() {
+        "p" => element.child("pPr")?.child("rPr")?,
+        "tr" => element.child("trPr")?,
+        _ => return None,
+    };
+    properties
+        .elements()
+        .find(|child| matches!(child.local_name(), "ins" | "del" | "moveFrom" | "moveTo"))
+}
+
+/// Whether a paragraph mark or table row is removed in the given view.
+fn is_marked_hidden(element: &Element, view: RevisionView) -> bool {
+    marker(element).is_some_and(|marker| {
+        matches!(
+            (view, marker.local_name()),
+            (RevisionView::Final, "del" | "moveFrom") | (RevisionView::Original, "ins" | "moveTo")
+        )
+    })
+}
+
+/// Wrap top-level nodes in a nameless container.
+fn root(children: Vec<Node>) -> Element {
+    Element {
+        name: String::new(),
+        start: String::new(),
+        end: String::new(),
+        children,
+    }
+}
+
+/// Collect an element's text, with paragraphs separated by newlines.
+fn collect_text(element: &Element, out: &mut String) {
+    for child in element.elements() {
+        match child.local_name() {
+            "t" | "delText" => out.push_str(&child.text()),
+            "p" => {
+                if !out.is_empty() && !out.ends_with('\n') {
+                    out.push('\n');
+                }
+                collect_text(child, out);
+            },
+            _ => collect_text(child, out),
+        }
+    }
+}
+
+fn text_of(element: &Element) -> String {
+    let mut text = String::new();
+    collect_text(element, &mut text);
+    text
+}
+
+/// Walks a tree collecting revisions.
+#[derive(Default)]
+struct RevisionCollector {
+    revisions: Vec<Revision>,
+    paragraph: Option<usize>,
+    next_paragraph: usize,
+}
+
+impl RevisionCollector {
+    fn push(&mut self, change: &Element, revision_type: RevisionType, text: String) {
+        self.revisions.push(Revision {
+            id: change.attribute("id").unwrap_or_default(),
+            revision_type,
+            author: change.attribute("author"),
+            date: change.attribute("date"),
+            text,
+            paragraph: self.paragraph.unwrap_or(self.next_paragraph),
+        });
+    }
+
+    fn visit(&mut self, element: &Element) {
+        let top_paragraph = element.local_name() == "p" && self.paragraph.is_none();
+        if top_paragraph {
+            self.paragraph = Some(self.next_paragraph);
+            self.next_paragraph += 1;
+        }
+
+        for child in element.elements() {
+            let name = child.local_name();
+            if is_properties(name) {
+                for change in child.elements() {
+                    let Some(revision_type) = revision_type(change.local_name()) else {
+                        continue;
+                    };
+                    // Markers on a paragraph mark stand for the mark itself
+                    let text = if element.local_name() == "pPr" && change.is_empty() {
+                        "\n".to_string()
+                    } else {
+                        text_of(element)
+                    };
+                    self.push(change, revision_type, text);
+                }
+            } else if !child.is_empty()
+                && matches!(name, "ins" | "del" | "moveFrom" | "moveTo")
+                && let Some(revision_type) = revision_type(name)
+            {
+                self.push(child, revision_type, text_of(child));
+            }
+            self.visit(child);
+        }
+
+        if top_paragraph {
+            self.paragraph = None;
+        }
+    }
+}
+
+/// List the revisions in document or paragraph XML.
+pub(crate) fn revisions(xml: &[u8]) -> Result<Vec<Revision>> {
+    let xml = std::str::from_utf8(xml)
+        .map_err(|e| OoxmlError::InvalidFormat(format!("Invalid UTF-8: {}", e)))?;
+    let mut collector = RevisionCollector::default();
+    collector.visit(&root(markup::parse(xml)?));
+    Ok(collector.revisions)
+}
+
+/// Collect the text of paragraphs as seen in the given view.
+fn collect_view_text(element: &Element, view: RevisionView, out: &mut String, in_paragraph: bool) {
+    for child in element.elements() {
+        if is_hidden(child, view) {
+            continue;
+        }
+        match child.local_name() {
+            "t" => out.push_str(&child.text()),
+            "delText" if view == RevisionView::Original => out.push_str(&child.text()),
+            "tr" if is_marked_hidden(child, view) => {},
+            "p" if !in_paragraph => {
+                collect_view_text(child, view, out, true);
+                if !is_marked_hidden(child, view) {
+                    out.push('\n');
+                }
+            },
+            _ => collect_view_text(child, view, out, in_paragraph),
+        }
+    }
+}
+
+/// Get the text of document or paragraph XML in the given view, with
+/// paragraphs separated by newlines.
+pub(crate) fn view_text(xml: &[u8], view: RevisionView) -> Result<String> {
+    let xml = std::str::from_utf8(xml)
+        .map_err(|e| OoxmlError::InvalidFormat(format!("Invalid UTF-8: {}", e)))?;
+    let mut text = String::new();
+    collect_view_text(&root(markup::parse(xml)?), view, &mut text, false);
+    if text.ends_with('\n') {
+        text.pop();
+    }
+    Ok(text)
+}
+
+/// Which children of a properties element survive restoring the
+/// properties from before a change, and whether they come first.
+fn kept_properties(local_name: &str) -> (&'static [&'static str], bool) {
+    match local_name {
+        "rPr" => (&["ins", "del", "moveFrom", "moveTo"], true),
+        "sectPr" => (&["headerReference", "footerReference"], true),
+        "pPr" => (&["rPr", "sectPr"], false),
+        "trPr" => (&["ins", "del"], false),
+        "tcPr" => (&["cellIns", "cellDel", "cellMerge"], false),
+        _ => (&[], false),
+    }
+}
+
+/// Remove the revision marker from a paragraph mark or table row.
+fn remove_marker(element: &mut Element) {
+    let properties = match element.local_name() {
+        "p" => element
+            .child_mut("pPr")
+            .and_then(|ppr| ppr.child_mut("rPr")),
+        "tr" => element.child_mut("trPr"),
+        _ => None,
+    };
+    if let Some(properties) = properties {
+        properties.children.retain(|node| {
+            !matches!(node, Node::Element(child)
+                if matches!(child.local_name(), "ins" | "del" | "moveFrom" | "moveTo"))
+        });
+    }
+}
+
+/// Turn deleted text back into regular text.
+fn restore_deleted_text(nodes: &mut [Node]) {
+    for node in nodes {
+        if let Node::Element(element) = node {
+            match element.local_name() {
+                "delText" => element.rename("delText", "t"),
+                "delInstrText" => element.rename("delInstrText", "instrText"),
+                _ => restore_deleted_text(&mut element.children),
+            }
+        }
+    }
+}
+
+/// Accepts or rejects revisions, optionally only those of one author.
+struct Resolver<'a> {
+    author: Option<&'a str>,
+    accept: bool,
+    /// Number of revisions resolved
+    count: usize,
+    /// IDs of move ranges whose start marker was removed
+    removed_ranges: HashSet<String>,
+}
+
+impl Resolver<'_> {
+    fn selected(&self, change: &Element) -> bool {
+        self.author
+            .is_none_or(|author| change.attribute("author").as_deref() == Some(author))
+    }
+
+    fn resolve_nodes(&mut self, nodes: Vec<Node>) -> Vec<Node> {
+        let mut out = Vec::with_capacity(nodes.len());
+        // Paragraph in `out` whose mark was removed, to merge with the next one
+        let mut merge_into: Option<usize> = None;
+
+        for node in nodes {
+            let Node::Element(mut element) = node else {
+                out.push(node);
+                continue;
+            };
+            let name = element.local_name().to_string();
+
+            match name.as_str() {
+                "ins" | "del" | "moveFrom" | "moveTo"
+                    if !element.is_empty() && self.selected(&element) =>
+                {
+                    self.count += 1;
+                    let inserted = matches!(name.as_str(), "ins" | "moveTo");
+                    if self.accept == inserted {
+                        let mut children = self.resolve_nodes(element.children);
+                        if !inserted {
+                            restore_deleted_text(&mut children);
+                        }
+                        out.extend(children);
+                    }
+                    continue;
+                },
+                "cellIns" | "cellDel" if self.selected(&element) => {
+                    self.count += 1;
+                    continue;
+                },
+                "moveFromRangeStart" | "moveToRangeStart" if self.selected(&element) => {
+                    self.removed_ranges.extend(element.attribute("id"));
+                    continue;
+                },
+                "moveFromRangeEnd" | "moveToRangeEnd"
+                    if element
+                        .attribute("id")
+                        .is_some_and(|id| self.removed_ranges.contains(&id)) =>
+                {
+                    continue;
+                },
+                _ => {},
+            }
+
+            // Inserted or deleted paragraph marks and table rows
+            let mut hidden = false;
+            if let Some(marker) = marker(&element)
+                && self.selected(marker)
+            {
+                self.count += 1;
+                hidden = self.accept != matches!(marker.local_name(), "ins" | "moveTo");
+                remove_marker(&mut element);
+            }
+            if hidden && name == "tr" {
+                continue;
+            }
+
+            element.children = self.resolve_nodes(std::mem::take(&mut element.children));
+            if is_properties(&name) {
+                self.resolve_properties(&mut element);
+            }
+
+            if name == "p" {
+                if let Some(index) = merge_into.take()
+                    && let Some(Node::Element(target)) = out.get_mut(index)
+                {
+                    merge_paragraphs(target, element);
+                    if hidden {
+                        merge_into = Some(index);
+                    }
+                    continue;
+                }
+                if hidden {
+                    merge_into = Some(out.len());
+                }
+            } else {
+                merge_into = None;
+            }
+            out.push(Node::Element(element));
+        }
+        out
+    }
+
+    /// Accept or reject a formatting change recorded in a properties element.
+    fn resolve_properties(&mut self, element: &mut Element) {
+        let change_name = format!("{}Change", element.local_name());
+        let Some(index) = element.children.iter().position(|node| {
+            matches!(node, Node::Element(child)
+                if child.local_name() == change_name && self.selected(child))
+        }) else {
+            return;
+        };
+        self.count += 1;
+        let Node::Element(change) = element.children.remove(index) else {
+            return;
+        };
+        if self.accept {
+            return;
+        }
+
+        // Restore the earlier properties, keeping markers and nested properties
+        let old = change
+            .children
+            .into_iter()
+            .find_map(|node| match node {
+                Node::Element(old) => Some(old.children),
+                Node::Raw(_) => None,
+            })
+            .unwrap_or_default();
+        let (kept_names, kept_first) = kept_properties(element.local_name());
+        let kept: Vec<Node> = std::mem::take(&mut element.children)
+            .into_iter()
+            .filter(|node| {
+                matches!(node, Node::Element(child) if kept_names.contains(&child.local_name()))
+            })
+            .collect();
+        element.children = if kept_first {
+            kept.into_iter().chain(old).collect()
+        } else {
+            old.into_iter().chain(kept).collect()
+        };
+    }
+}
+
+/// Merge a paragraph into the one before it whose mark was removed.
+///
+/// The merged paragraph ends with the second paragraph's mark, so it takes
+/// that paragraph's properties.
+fn merge_paragraphs(target: &mut Element, next: Element) {
+    if target.is_empty() {
+        target.start = format!("{}>", target.start.trim_end_matches("/>").trim_end());
+        target.end = format!("</{}>", target.name);
+    }
+    target
+        .children
+        .retain(|node| !matches!(node, Node::Element(child) if child.local_name() == "pPr"));
+    let (properties, content): (Vec<Node>, Vec<Node>) = next
+        .children
+        .into_iter()
+        .partition(|node| matches!(node, Node::Element(child) if child.local_name() == "pPr"));
+    target.children.splice(0..0, properties);
+    target.children.extend(content);
+}
+
+/// Accept or reject revisions in document XML.
+///
+/// With `author`, only that author's revisions are resolved. Returns the
+/// rewritten XML and the number of revisions resolved, or `None` if there
+/// was nothing to resolve.
+pub(crate) fn resolve(
+    xml: &str,
+    author: Option<&str>,
+    accept: bool,
+) -> Result<Option<(String, usize)>> {
+    let mut resolver = Resolver {
+        author,
+        accept,
+        count: 0,
+        removed_ranges: HashSet::new(),
+    };
+    let nodes = resolver.resolve_nodes(markup::parse(xml)?);
+    if resolver.count == 0 {
+        return Ok(None);
+    }
+
+    let mut out = String::with_capacity(xml.len());
+    markup::write_nodes(&nodes, &mut out);
+    Ok(Some((out, resolver.count)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BODY: &str = r#"<w:body><w:p><w:r><w:t xml:space="preserve">The </w:t></w:r><w:del w:id="1" w:author="Ann" w:date="2024-01-02T03:04:05Z"><w:r><w:delText>old</w:delText></w:r></w:del><w:ins w:id="2" w:author="Bob"><w:r><w:t>new</w:t></w:r></w:ins><w:r><w:rPr><w:b/><w:rPrChange w:id="3" w:author="Ann"><w:rPr><w:i/></w:rPr></w:rPrChange></w:rPr><w:t xml:space="preserve"> text</w:t></w:r></w:p><w:p><w:pPr><w:rPr><w:del w:id="4" w:author="Ann"/></w:rPr></w:pPr><w:r><w:t>Joined</w:t></w:r></w:p><w:p><w:pPr><w:jc w:val="center"/></w:pPr><w:r><w:t xml:space="preserve"> on &amp; on</w:t></w:r></w:p></w:body>"#;
+
+    #[test]
+    fn test_revisions() {
+        let revisions = revisions(BODY.as_bytes()).unwrap();
+        let summary: Vec<_> = revisions
+            .iter()
+            .map(|r| {
+                (
+                    r.revision_type,
+                    r.author.as_deref(),
+                    r.text.as_str(),
+                    r.paragraph,
+                )
+            })
+            .collect();
+        assert_eq!(
+            summary,
+            [
+                (RevisionType::Deletion, Some("Ann"), "old", 0),
+                (RevisionType::Insertion, Some("Bob"), "new", 0),
+                (RevisionType::RunFormat, Some("Ann"), " text", 0),
+                (RevisionType::Deletion, Some("Ann"), "\n", 1),
+            ]
+        );
+        assert_eq!(revisions[0].id, "1");
+        assert_eq!(revisions[0].date.as_deref(), Some("2024-01-02T03:04:05Z"));
+    }
+
+    #[test]
+    fn test_view_text() {
+        assert_eq!(
+            view_text(BODY.as_bytes(), RevisionView::Final).unwrap(),
+            "The new text\nJoined on & on"
+        );
+        assert_eq!(
+            view_text(BODY.as_bytes(), RevisionView::Original).unwrap(),
+            "The old text\nJoined\n on & on"
+        );
+    }
+
+    #[test]
+    fn test_accept_all() {
+        let (xml, count) = resolve(BODY, None, true).unwrap().unwrap();
+        assert_eq!(count, 4);
+        assert_eq!(
+            view_text(xml.as_bytes(), RevisionView::Original).unwrap(),
+            "The new text\nJoined on & on"
+        );
+        assert!(revisions(xml.as_bytes()).unwrap().is_empty());
+        // The joined paragraph keeps the properties of the surviving mark
+        assert!(xml.contains(
+            r#"<w:p><w:pPr><w:jc w:val="center"/></w:pPr><w:r><w:t>Joined</w:t></w:r><w:r><w:t xml:space="preserve"> on &amp; on</w:t></w:r></w:p>"#
+        ));
+        assert!(xml.contains("<w:rPr><w:b/></w:rPr>"));
+    }
+
+    #[test]
+    fn test_reject_by_author() {
+        let (xml, count) = resolve(BODY, Some("Ann"), false).unwrap().unwrap();
+        assert_eq!(count, 3);
+        let remaining = revisions(xml.as_bytes()).unwrap();
+        assert_eq!(remaining.len(), 1);
+        assert_eq!(remaining[0].author.as_deref(), Some("Bob"));
+        assert!(xml.contains("<w:t>old</w:t>"));
+        assert!(xml.contains("<w:rPr><w:i/></w:rPr>"));
+        assert_eq!(
+            view_text(xml.as_bytes(), RevisionView::Final).unwrap(),
+            "The oldnew text\nJoined\n on & on"
+        );
+
+        assert!(resolve(BODY, Some("Eve"), true).unwrap().is_none());
+    }
+}
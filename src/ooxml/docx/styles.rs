@@ -209,6 +209,21 @@ impl<'a> Styles<'a> {
                         b"locked" => {
                             builder.is_locked = true;
                         },
+                        b"outlineLvl" | b"numId" | b"ilvl" => {
+                            let value = e
+                                .attributes()
+                                .flatten()
+                                .find(|attr| attr.key.local_name().as_ref() == b"val")
+                                .and_then(|attr| attr.unescape_value().ok())
+                                .and_then(|value| value.parse::<u32>().ok());
+                            match e.local_name().as_ref() {
+                                b"outlineLvl" => {
+                                    builder.outline_level = value.map(|level| level as u8)
+                                },
+                                b"numId" => builder.num_id = value,
+                                _ => builder.num_level = value.map(|level| level as u8),
+                            }
+                        },
                         _ => {},
                     }
                 },
@@ -228,6 +243,9 @@ impl<'a> Styles<'a> {
                             is_quick_style: builder.is_quick_style,
                             is_hidden: builder.is_hidden,
                             is_locked: builder.is_locked,
+                            outline_level: builder.outline_level,
+                            num_id: builder.num_id,
+                            num_level: builder.num_level,
                         });
                     }
                 },
@@ -256,6 +274,9 @@ struct StyleBuilder {
     is_quick_style: bool,
     is_hidden: bool,
     is_locked: bool,
+    outline_level: Option<u8>,
+    num_id: Option<u32>,
+    num_level: Option<u8>,
 }
 
 /// A single style definition in a Word document.
@@ -284,6 +305,12 @@ pub struct Style {
     is_hidden: bool,
     /// Whether locked (formatting protection)
     is_locked: bool,
+    /// Outline level (`w:outlineLvl`, 0-based)
+    outline_level: Option<u8>,
+    /// Numbering instance (`w:numId`)
+    num_id: Option<u32>,
+    /// Numbering level (`w:ilvl`)
+    num_level: Option<u8>,
 }
 
 impl Style {
@@ -360,6 +387,21 @@ impl Style {
     pub fn is_locked(&self) -> bool {
         self.is_locked
     }
+
+    /// Get the outline level (0-based) set by this style's paragraph
+    /// properties; level 9 means body text.
+    #[inline]
+    pub fn outline_level(&self) -> Option<u8> {
+        self.outline_level
+    }
+
+    /// Get the numbering instance and level applied by this style, as
+    /// `(numId, ilvl)`.
+    #[inline]
+    pub fn numbering(&self) -> Option<(u32, u8)> {
+        self.num_id
+            .map(|num_id| (num_id, self.num_level.unwrap_or(0)))
+    }
 }
 
 #[cfg(test)]
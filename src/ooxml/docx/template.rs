@@ -3,9 +3,32 @@
 //! Provides minimal valid templates for creating new Word documents.
 //! These templates contain the bare minimum structure required for a valid .docx file.
 //! Generate a minimal valid document.xml content.
+//!
+//! Also fills template documents with data: `MERGEFIELD` fields become
+//! `{{name}}` placeholders (`TableStart:name` and `TableEnd:name` become
+//! `{{#name}}` and `{{/name}}`), which are then filled as described in
+//! [`merge`](crate::ooxml::common::merge).
 
+use crate::ooxml::common::TemplateData;
+use crate::ooxml::common::markup::{Element, Node};
+use crate::ooxml::common::merge::{self, Dialect};
+use crate::ooxml::error::Result;
+use quick_xml::escape::escape;
 use xml_minifier::minified_xml;
 
+/// WordprocessingML element names for filling templates.
+const DIALECT: Dialect = Dialect {
+    paragraphs: &["p"],
+    run: "r",
+    text: "t",
+    row: Some("tr"),
+    table: Some("tbl"),
+    paragraph_container: Some(("tc", "<w:p/>")),
+    line_break: Some("<w:br/>"),
+    preserve_space: true,
+    paragraph_blocks: true,
+};
+
 /// Creates an empty document with a single section definition.
 pub fn default_document_xml() -> &'static str {
     minified_xml!("resources/document.xml")
@@ -18,6 +41,11 @@ pub fn default_styles_xml() -> &'static str {
     minified_xml!("resources/styles.xml")
 }
 
+/// Generate default numbering.xml content
+pub fn default_numbering_xml() -> &'static str {
+    minified_xml!("resources/numbering.xml")
+}
+
 /// Generate default settings.xml content
 pub fn default_settings_xml() -> &'static str {
     minified_xml!("resources/settings.xml")
@@ -49,3 +77,200 @@ pub fn default_app_props_xml() -> &'static str {
 pub fn default_theme_xml() -> &'static str {
     minified_xml!("resources/theme/theme1.xml")
 }
+
+/// Fill the placeholders and merge fields of a document, header, footer
+/// or notes part. Returns `None` when the part has nothing to fill.
+pub(crate) fn render_part(xml: &str, data: &TemplateData) -> Result<Option<String>> {
+    if !xml.contains('{') && !xml.contains("MERGEFIELD") {
+        return Ok(None);
+    }
+    merge::render(xml, data, &DIALECT, convert_merge_fields)
+}
+
+/// Get the placeholder a `MERGEFIELD` instruction stands for.
+fn merge_field_placeholder(instr: &str) -> Option<String> {
+    let instr = instr.trim_start();
+    let keyword = instr.get(..10)?;
+    if !keyword.eq_ignore_ascii_case("MERGEFIELD") {
+        return None;
+    }
+    let rest = instr[10..].trim_start();
+    let name = match rest.strip_prefix('"') {
+        Some(quoted) => quoted.split('"').next()?,
+        None => rest.split_whitespace().next()?,
+    };
+    if name.is_empty() {
+        return None;
+    }
+
+    Some(match name.split_once(':') {
+        Some((kind, region))
+            if kind.eq_ignore_ascii_case("TableStart")
+                || kind.eq_ignore_ascii_case("BeginGroup") =>
+        {
+            format!("{{{{#{}}}}}", region)
+        },
+        Some((kind, region))
+            if kind.eq_ignore_ascii_case("TableEnd") || kind.eq_ignore_ascii_case("EndGroup") =>
+        {
+            format!("{{{{/{}}}}}", region)
+        },
+        _ => format!("{{{{{}}}}}", name),
+    })
+}
+
+/// Make a run showing a placeholder with the properties of `template`.
+fn placeholder_run(template: Option<&Element>, placeholder: &str) -> Node {
+    let mut run = Element {
+        name: "w:r".to_string(),
+        start: "<w:r>".to_string(),
+        end: "</w:r>".to_string(),
+        children: Vec::new(),
+    };
+    if let Some(template) = template {
+        run.name = template.name.clone();
+        run.children.extend(
+            template
+                .children
+                .iter()
+                .filter(|node| matches!(node, Node::Element(e) if e.local_name() == "rPr"))
+                .cloned(),
+        );
+    }
+    run.children.push(Node::Element(Element {
+        name: "w:t".to_string(),
+        start: "<w:t xml:space=\"preserve\">".to_string(),
+        end: "</w:t>".to_string(),
+        children: vec![Node::Raw(escape(placeholder).into_owned())],
+    }));
+    Node::Element(run)
+}
+
+fn as_run(node: &Node) -> Option<&Element> {
+    match node {
+        Node::Element(element) if element.local_name() == "r" => Some(element),
+        _ => None,
+    }
+}
+
+/// Get the `w:fldCharType` of a run holding a field character.
+fn field_char(run: &Element) -> Option<String> {
+    run.elements()
+        .find(|child| child.local_name() == "fldChar")
+        .and_then(|fld_char| fld_char.attribute("fldCharType"))
+}
+
+/// Replace `MERGEFIELD` fields with placeholders, keeping the formatting
+/// of the field result.
+fn convert_merge_fields(nodes: &mut Vec<Node>) {
+    for node in nodes.iter_mut() {
+        if let Node::Element(element) = node {
+            convert_merge_fields(&mut element.children);
+        }
+    }
+
+    // Simple fields
+    for node in nodes.iter_mut() {
+        if let Node::Element(element) = node
+            && element.local_name() == "fldSimple"
+            && let Some(placeholder) = element
+                .attribute("instr")
+                .and_then(|instr| merge_field_placeholder(&instr))
+        {
+            let run = placeholder_run(element.children.iter().find_map(as_run), &placeholder);
+            *node = run;
+        }
+    }
+
+    // Complex fields, from the begin to the end field character
+    let mut i = 0;
+    while i < nodes.len() {
+        if as_run(&nodes[i]).and_then(field_char).as_deref() != Some("begin") {
+            i += 1;
+            continue;
+        }
+
+        let mut depth = 0;
+        let mut instr = String::new();
+        let mut separate = None;
+        let mut end = None;
+        for (index, node) in nodes.iter().enumerate().skip(i) {
+            let Some(run) = as_run(node) else {
+                continue;
+            };
+            match field_char(run).as_deref() {
+                Some("begin") => depth += 1,
+                Some("separate") if depth == 1 => separate = Some(index),
+                Some("end") => {
+                    depth -= 1;
+                    if depth == 0 {
+                        end = Some(index);
+                        break;
+                    }
+                },
+                _ => {},
+            }
+            if depth == 1 && separate.is_none() {
+                for child in run.elements() {
+                    if child.local_name() == "instrText" {
+                        instr.push_str(&child.text());
+                    }
+                }
+            }
+        }
+
+        if let Some(end) = end
+            && let Some(placeholder) = merge_field_placeholder(&instr)
+        {
+            // Take the formatting of the field result, or of the instruction
+            let template = separate
+                .and_then(|separate| nodes[separate + 1..end].iter().find_map(as_run))
+                .or_else(|| nodes[i + 1..end].iter().find_map(as_run))
+                .cloned();
+            let run = placeholder_run(template.as_ref(), &placeholder);
+            nodes.splice(i..=end, [run]);
+        }
+        i += 1;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_merge_fields() {
+        let xml = concat!(
+            r#"<w:body><w:p><w:fldSimple w:instr=" MERGEFIELD  First_Name \* MERGEFORMAT "><w:r><w:rPr><w:i/></w:rPr><w:t>«First_Name»</w:t></w:r></w:fldSimple>"#,
+            r#"<w:r><w:t xml:space="preserve"> </w:t></w:r>"#,
+            r#"<w:r><w:fldChar w:fldCharType="begin"/></w:r><w:r><w:instrText xml:space="preserve"> MERGEFIELD "Last Name" </w:instrText></w:r>"#,
+            r#"<w:r><w:fldChar w:fldCharType="separate"/></w:r><w:r><w:rPr><w:b/></w:rPr><w:t>«Last Name»</w:t></w:r><w:r><w:fldChar w:fldCharType="end"/></w:r>"#,
+            r#"<w:r><w:t>, {{title}}</w:t></w:r></w:p></w:body>"#,
+        );
+        let mut data = TemplateData::new();
+        data.set("First_Name", "Ada")
+            .set("Last Name", "Lovelace")
+            .set("title", "Countess");
+
+        let out = render_part(xml, &data).unwrap().unwrap();
+        assert!(
+            out.contains(r#"<w:r><w:rPr><w:i/></w:rPr><w:t xml:space="preserve">Ada</w:t></w:r>"#)
+        );
+        assert!(out.contains(
+            r#"<w:r><w:rPr><w:b/></w:rPr><w:t xml:space="preserve">Lovelace</w:t></w:r>"#
+        ));
+        assert!(out.contains("Countess"));
+        assert!(!out.contains("fldChar"));
+
+        assert_eq!(
+            merge_field_placeholder(" MERGEFIELD TableStart:Items "),
+            Some("{{#Items}}".to_string())
+        );
+        assert_eq!(merge_field_placeholder(" PAGE "), None);
+        assert!(
+            render_part("<w:p><w:r><w:t>Plain</w:t></w:r></w:p>", &data)
+                .unwrap()
+                .is_none()
+        );
+    }
+}
@@ -0,0 +1,148 @@
+use super::paragraph::MutableParagraph;
+use crate::ooxml::common::guid;
+use crate::ooxml::common::xml::attribute;
+use crate::ooxml::error::{OoxmlError, Result};
+use quick_xml::Reader;
+use quick_xml::events::Event;
+use std::collections::HashMap;
+
+/// A comment being written.
+///
+/// Comments are created with
+/// [`MutableDocument::add_comment`](super::MutableDocument::add_comment)
+/// and placed on text with
+/// [`MutableParagraph::add_commented_text`] or
+/// [`start_comment`](MutableParagraph::start_comment) and
+/// [`end_comment`](MutableParagraph::end_comment).
+#[derive(Debug)]
+pub struct MutableComment {
+    /// Comment ID (starting from 0)
+    pub(crate) id: u32,
+    /// Author name
+    pub(crate) author: String,
+    /// Author initials
+    pub(crate) initials: Option<String>,
+    /// Date and time (ISO 8601)
+    pub(crate) date: Option<String>,
+    /// ID of the comment this one replies to
+    pub(crate) parent: Option<u32>,
+    /// Whether the comment is marked as resolved
+    pub(crate) resolved: bool,
+    /// Comment content (paragraphs)
+    pub(crate) paragraphs: Vec<MutableParagraph>,
+}
+
+impl MutableComment {
+    pub(crate) fn new(id: u32, author: &str, parent: Option<u32>) -> Self {
+        Self {
+            id,
+            author: author.to_string(),
+            initials: None,
+            date: None,
+            parent,
+            resolved: false,
+            paragraphs: Vec::new(),
+        }
+    }
+
+    /// Get the comment ID.
+    pub fn id(&self) -> u32 {
+        self.id
+    }
+
+    /// Set the author's initials.
+    pub fn set_initials(&mut self, initials: &str) -> &mut Self {
+        self.initials = Some(initials.to_string());
+        self
+    }
+
+    /// Set the date and time, as an ISO 8601 string such as
+    /// `2024-05-01T10:00:00Z`.
+    pub fn set_date(&mut self, date: &str) -> &mut Self {
+        self.date = Some(date.to_string());
+        self
+    }
+
+    /// Mark the comment as resolved.
+    pub fn set_resolved(&mut self, resolved: bool) -> &mut Self {
+        self.resolved = resolved;
+        self
+    }
+
+    /// Add a paragraph to this comment.
+    pub fn add_paragraph(&mut self) -> &mut MutableParagraph {
+        let para = MutableParagraph::new();
+        self.paragraphs.push(para);
+        self.paragraphs.last_mut().unwrap()
+    }
+
+    /// Add a paragraph with text to this comment.
+    pub fn add_paragraph_with_text(&mut self, text: &str) -> &mut MutableParagraph {
+        let para = self.add_paragraph();
+        para.add_run_with_text(text);
+        para
+    }
+
+    /// Paragraph ID linking the comment to its thread information, set on
+    /// the comment's last paragraph.
+    pub(crate) fn para_id(&self) -> String {
+        // Paragraph IDs must be below 0x80000000
+        format!("{:08X}", self.id + 1)
+    }
+
+    /// Durable ID identifying the comment in `commentsIds.xml`.
+    pub(crate) fn durable_id(&self) -> String {
+        let seed = guid(&format!("comment {}", self.para_id()));
+        let value = u32::from_str_radix(&seed[1..9], 16).unwrap_or(self.id + 1);
+        format!("{:08X}", value & 0x7FFF_FFFF)
+    }
+}
+
+/// Comments already in a document's `comments.xml`, which new comments are
+/// numbered after and may reply to.
+#[derive(Debug, Default)]
+pub(crate) struct ExistingComments {
+    /// ID of the first comment added
+    pub(crate) next_id: u32,
+    /// Paragraph ID of each existing comment's last paragraph, by comment ID
+    pub(crate) para_ids: HashMap<u32, String>,
+}
+
+impl ExistingComments {
+    /// Collect the comment IDs and paragraph IDs of `comments.xml`.
+    pub(crate) fn parse(xml: &str) -> Result<Self> {
+        let mut existing = Self::default();
+        let mut reader = Reader::from_str(xml);
+        let mut current = None;
+        loop {
+            match reader
+                .read_event()
+                .map_err(|e| OoxmlError::Xml(e.to_string()))?
+            {
+                Event::Start(ref e) | Event::Empty(ref e) => match e.local_name().as_ref() {
+                    b"comment" => {
+                        current = attribute(e, b"id").and_then(|id| id.parse::<u32>().ok());
+                        if let Some(id) = current {
+                            existing.next_id = existing.next_id.max(id + 1);
+                        }
+                    },
+                    b"p" => {
+                        if let (Some(id), Some(para_id)) = (current, attribute(e, b"paraId")) {
+                            existing.para_ids.insert(id, para_id);
+                        }
+                    },
+                    _ => {},
+                },
+                Event::End(ref e) if e.local_name().as_ref() == b"comment" => current = None,
+                Event::Eof => break,
+                _ => {},
+            }
+        }
+        Ok(existing)
+    }
+
+    /// Paragraph ID of an existing comment, which replies refer to.
+    pub(crate) fn para_id(&self, id: u32) -> Option<&str> {
+        self.para_ids.get(&id).map(String::as_str)
+    }
+}
@@ -5,11 +5,57 @@ use std::fmt::Write as FmtWrite;
 // Import shared format types
 pub use super::super::format::ImageFormat;
 // Import from other writer modules
+use super::comment::{ExistingComments, MutableComment};
 use super::note::Note;
+use super::numbering::MutableNumbering;
 use super::paragraph::{MutableParagraph, ParagraphElement};
 use super::section::SectionProperties;
+use super::style::MutableStyles;
 use super::table::MutableTable;
 
+/// Escape XML special characters.
+fn escape_xml(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&apos;")
+}
+
+/// Insert `entries` at the end of a part's root element, or wrap them in a
+/// new part opened by `root_start` when there is no existing part.
+fn append_to_part(
+    existing: Option<&str>,
+    root_start: &str,
+    root_end: &str,
+    entries: &str,
+) -> Result<String> {
+    match existing {
+        Some(base) => {
+            let end = base
+                .rfind(root_end)
+                .ok_or_else(|| OoxmlError::Xml(format!("Part has no {}", root_end)))?;
+            Ok(format!("{}{}{}", &base[..end], entries, &base[end..]))
+        },
+        None => Ok(format!(
+            r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>{}{}{}"#,
+            root_start, entries, root_end
+        )),
+    }
+}
+
+/// Declare a namespace prefix on a part's root element unless it already is.
+fn declare_namespace(xml: &str, root: &str, prefix: &str, uri: &str) -> String {
+    let declaration = format!(r#" xmlns:{}="{}""#, prefix, uri);
+    match xml.find(root) {
+        Some(start) if !xml.contains(&format!("xmlns:{}=", prefix)) => {
+            let at = start + root.len();
+            format!("{}{}{}", &xml[..at], declaration, &xml[at..])
+        },
+        _ => xml.to_string(),
+    }
+}
+
 /// A mutable Word document for writing and modification.
 ///
 /// Provides methods to add and modify document content including paragraphs,
@@ -25,6 +71,14 @@ pub struct MutableDocument {
     footnotes: Vec<Note>,
     /// Endnotes (ID -> Note)
     endnotes: Vec<Note>,
+    /// Comments added, indexed by ID from `existing_comments.next_id`
+    comments: Vec<MutableComment>,
+    /// Comments already in the document
+    existing_comments: ExistingComments,
+    /// Style definitions
+    styles: MutableStyles,
+    /// List definitions
+    numbering: MutableNumbering,
     /// Section properties (page setup, margins, orientation)
     section: SectionProperties,
     /// Whether the document has been modified
@@ -40,6 +94,10 @@ impl MutableDocument {
             footer: None,
             footnotes: Vec::new(),
             endnotes: Vec::new(),
+            comments: Vec::new(),
+            existing_comments: ExistingComments::default(),
+            styles: MutableStyles::new(),
+            numbering: MutableNumbering::new(),
             section: SectionProperties::default(),
             modified: false,
         }
@@ -54,6 +112,10 @@ impl MutableDocument {
             footer: None,
             footnotes: Vec::new(),
             endnotes: Vec::new(),
+            comments: Vec::new(),
+            existing_comments: ExistingComments::default(),
+            styles: MutableStyles::new(),
+            numbering: MutableNumbering::new(),
             section: SectionProperties::default(),
             modified: false,
         })
@@ -197,6 +259,79 @@ impl MutableDocument {
         (id, self.endnotes.last_mut().unwrap())
     }
 
+    /// Add a comment and return its ID and mutable reference.
+    ///
+    /// Place the comment on text with the returned ID, using
+    /// [`MutableParagraph::add_commented_text`] or
+    /// [`start_comment`](MutableParagraph::start_comment) and
+    /// [`end_comment`](MutableParagraph::end_comment).
+    pub fn add_comment(&mut self, author: &str) -> (u32, &mut MutableComment) {
+        self.push_comment(author, None)
+    }
+
+    /// Add a reply to a comment and return its ID and mutable reference.
+    ///
+    /// The reply is placed on the same text as the comment it answers, so
+    /// the comment should be placed before replying to it.
+    pub fn add_reply(&mut self, parent: u32, author: &str) -> Result<(u32, &mut MutableComment)> {
+        if self.comment(parent).is_none() && self.existing_comments.para_id(parent).is_none() {
+            return Err(OoxmlError::InvalidFormat(format!(
+                "No comment with ID {}",
+                parent
+            )));
+        }
+
+        let id = self.next_comment_id();
+        for element in &mut self.body.elements {
+            match element {
+                BodyElement::Paragraph(para) => para.anchor_reply(parent, id),
+                BodyElement::Table(table) => {
+                    for cell in table.rows.iter_mut().flat_map(|row| row.cells.iter_mut()) {
+                        for para in &mut cell.paragraphs {
+                            para.anchor_reply(parent, id);
+                        }
+                    }
+                },
+            }
+        }
+        Ok(self.push_comment(author, Some(parent)))
+    }
+
+    fn next_comment_id(&self) -> u32 {
+        self.existing_comments.next_id + self.comments.len() as u32
+    }
+
+    /// Get a comment added to the document by ID.
+    fn comment(&self, id: u32) -> Option<&MutableComment> {
+        id.checked_sub(self.existing_comments.next_id)
+            .and_then(|index| self.comments.get(index as usize))
+    }
+
+    fn push_comment(&mut self, author: &str, parent: Option<u32>) -> (u32, &mut MutableComment) {
+        let id = self.next_comment_id();
+        self.comments.push(MutableComment::new(id, author, parent));
+        self.modified = true;
+        (id, self.comments.last_mut().unwrap())
+    }
+
+    /// Get the style definitions, to add styles that paragraphs, runs and
+    /// tables can refer to.
+    pub fn styles_mut(&mut self) -> &mut MutableStyles {
+        self.modified = true;
+        &mut self.styles
+    }
+
+    /// Get the list definitions, to add lists that paragraphs can join.
+    pub fn numbering_mut(&mut self) -> &mut MutableNumbering {
+        self.modified = true;
+        &mut self.numbering
+    }
+
+    /// Check if the document has comments.
+    pub fn has_comments(&self) -> bool {
+        !self.comments.is_empty()
+    }
+
     /// Check if the document has footnotes.
     pub fn has_footnotes(&self) -> bool {
         !self.footnotes.is_empty()
@@ -359,6 +494,169 @@ impl MutableDocument {
         Ok(Some(xml))
     }
 
+    /// Generate comments XML content, appended to the document's current
+    /// `comments.xml` if it has one.
+    ///
+    /// Each comment's last paragraph carries the paragraph ID that
+    /// `commentsExtended.xml` refers to.
+    pub(crate) fn generate_comments_xml(&self, existing: Option<&str>) -> Result<Option<String>> {
+        if self.comments.is_empty() {
+            return Ok(None);
+        }
+
+        let mut xml = String::with_capacity(2048);
+        for comment in &self.comments {
+            write!(
+                xml,
+                r#"<w:comment w:id="{}" w:author="{}""#,
+                comment.id,
+                escape_xml(&comment.author)
+            )
+            .map_err(|e| OoxmlError::Xml(e.to_string()))?;
+            if let Some(ref initials) = comment.initials {
+                write!(xml, r#" w:initials="{}""#, escape_xml(initials))
+                    .map_err(|e| OoxmlError::Xml(e.to_string()))?;
+            }
+            if let Some(ref date) = comment.date {
+                write!(xml, r#" w:date="{}""#, escape_xml(date))
+                    .map_err(|e| OoxmlError::Xml(e.to_string()))?;
+            }
+            xml.push('>');
+
+            let last_start = format!(r#"<w:p w14:paraId="{}">"#, comment.para_id());
+            match comment.paragraphs.split_last() {
+                Some((last, rest)) => {
+                    for para in rest {
+                        para.to_xml(&mut xml)?;
+                    }
+                    let mut para_xml = String::new();
+                    last.to_xml(&mut para_xml)?;
+                    xml.push_str(&last_start);
+                    xml.push_str(para_xml.strip_prefix("<w:p>").unwrap_or(&para_xml));
+                },
+                None => {
+                    xml.push_str(&last_start);
+                    xml.push_str("</w:p>");
+                },
+            }
+
+            xml.push_str("</w:comment>");
+        }
+
+        let existing = existing.map(|base| {
+            declare_namespace(
+                base,
+                "<w:comments",
+                "w14",
+                "http://schemas.microsoft.com/office/word/2010/wordml",
+            )
+        });
+        append_to_part(
+            existing.as_deref(),
+            r#"<w:comments xmlns:w="http://schemas.openxmlformats.org/wordprocessingml/2006/main" xmlns:w14="http://schemas.microsoft.com/office/word/2010/wordml" xmlns:mc="http://schemas.openxmlformats.org/markup-compatibility/2006" mc:Ignorable="w14">"#,
+            "</w:comments>",
+            &xml,
+        )
+        .map(Some)
+    }
+
+    /// Generate commentsExtended XML content, with reply threads and
+    /// resolved state, appended to the document's current
+    /// `commentsExtended.xml` if it has one.
+    pub(crate) fn generate_comments_extended_xml(
+        &self,
+        existing: Option<&str>,
+    ) -> Result<Option<String>> {
+        if self.comments.is_empty() {
+            return Ok(None);
+        }
+
+        let mut xml = String::with_capacity(1024);
+        for comment in &self.comments {
+            write!(xml, r#"<w15:commentEx w15:paraId="{}""#, comment.para_id())
+                .map_err(|e| OoxmlError::Xml(e.to_string()))?;
+            let parent_para_id = comment.parent.and_then(|id| match self.comment(id) {
+                Some(parent) => Some(parent.para_id()),
+                None => self.existing_comments.para_id(id).map(str::to_string),
+            });
+            if let Some(parent_para_id) = parent_para_id {
+                write!(xml, r#" w15:paraIdParent="{}""#, parent_para_id)
+                    .map_err(|e| OoxmlError::Xml(e.to_string()))?;
+            }
+            write!(xml, r#" w15:done="{}"/>"#, u8::from(comment.resolved))
+                .map_err(|e| OoxmlError::Xml(e.to_string()))?;
+        }
+
+        append_to_part(
+            existing,
+            r#"<w15:commentsEx xmlns:w15="http://schemas.microsoft.com/office/word/2012/wordml">"#,
+            "</w15:commentsEx>",
+            &xml,
+        )
+        .map(Some)
+    }
+
+    /// Generate commentsIds XML content, giving each comment a durable ID,
+    /// appended to the document's current `commentsIds.xml` if it has one.
+    pub(crate) fn generate_comments_ids_xml(
+        &self,
+        existing: Option<&str>,
+    ) -> Result<Option<String>> {
+        if self.comments.is_empty() {
+            return Ok(None);
+        }
+
+        let mut xml = String::with_capacity(512);
+        for comment in &self.comments {
+            write!(
+                xml,
+                r#"<w16cid:commentId w16cid:paraId="{}" w16cid:durableId="{}"/>"#,
+                comment.para_id(),
+                comment.durable_id()
+            )
+            .map_err(|e| OoxmlError::Xml(e.to_string()))?;
+        }
+
+        append_to_part(
+            existing,
+            r#"<w16cid:commentsIds xmlns:w16cid="http://schemas.microsoft.com/office/word/2016/wordml/cid">"#,
+            "</w16cid:commentsIds>",
+            &xml,
+        )
+        .map(Some)
+    }
+
+    /// Generate styles XML content, if styles or defaults were defined,
+    /// merged into the document's current `styles.xml` if it has one.
+    pub(crate) fn generate_styles_xml(&self, existing: Option<&str>) -> Result<Option<String>> {
+        if !self.styles.has_definitions() {
+            return Ok(None);
+        }
+        self.styles.to_xml(existing).map(Some)
+    }
+
+    /// Generate numbering XML content, if lists were defined, merged into
+    /// the document's current `numbering.xml` if it has one.
+    pub(crate) fn generate_numbering_xml(&self, existing: Option<&str>) -> Result<Option<String>> {
+        if self.numbering.is_empty() {
+            return Ok(None);
+        }
+        self.numbering.to_xml(existing).map(Some)
+    }
+
+    /// Number new lists after the ones in the document's current
+    /// `numbering.xml`.
+    pub(crate) fn number_lists_after(&mut self, numbering_xml: &str) -> Result<()> {
+        self.numbering = MutableNumbering::following(numbering_xml)?;
+        Ok(())
+    }
+
+    /// Number new comments after those in the document's `comments.xml`.
+    pub(crate) fn number_comments_after(&mut self, comments_xml: &str) -> Result<()> {
+        self.existing_comments = ExistingComments::parse(comments_xml)?;
+        Ok(())
+    }
+
     /// Get a reference to a paragraph by index.
     pub fn paragraph(&mut self, index: usize) -> Option<&mut MutableParagraph> {
         self.body.paragraph(index)
@@ -373,7 +671,7 @@ impl MutableDocument {
     pub fn to_xml(&self) -> Result<String> {
         let mut xml = String::with_capacity(4096);
         xml.push_str(r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>"#);
-        xml.push_str(r#"<w:document xmlns:w="http://schemas.openxmlformats.org/wordprocessingml/2006/main" xmlns:r="http://schemas.openxmlformats.org/officeDocument/2006/relationships" xmlns:wp="http://schemas.openxmlformats.org/drawingml/2006/wordprocessingDrawing" xmlns:a="http://schemas.openxmlformats.org/drawingml/2006/main">"#);
+        xml.push_str(r#"<w:document xmlns:w="http://schemas.openxmlformats.org/wordprocessingml/2006/main" xmlns:r="http://schemas.openxmlformats.org/officeDocument/2006/relationships" xmlns:wp="http://schemas.openxmlformats.org/drawingml/2006/wordprocessingDrawing" xmlns:a="http://schemas.openxmlformats.org/drawingml/2006/main" xmlns:m="http://schemas.openxmlformats.org/officeDocument/2006/math">"#);
         self.body.to_xml(&mut xml)?;
         xml.push_str("</w:document>");
         Ok(xml)
@@ -389,7 +687,7 @@ impl MutableDocument {
     ) -> Result<String> {
         let mut xml = String::with_capacity(4096);
         xml.push_str(r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>"#);
-        xml.push_str(r#"<w:document xmlns:w="http://schemas.openxmlformats.org/wordprocessingml/2006/main" xmlns:r="http://schemas.openxmlformats.org/officeDocument/2006/relationships" xmlns:wp="http://schemas.openxmlformats.org/drawingml/2006/wordprocessingDrawing" xmlns:a="http://schemas.openxmlformats.org/drawingml/2006/main">"#);
+        xml.push_str(r#"<w:document xmlns:w="http://schemas.openxmlformats.org/wordprocessingml/2006/main" xmlns:r="http://schemas.openxmlformats.org/officeDocument/2006/relationships" xmlns:wp="http://schemas.openxmlformats.org/drawingml/2006/wordprocessingDrawing" xmlns:a="http://schemas.openxmlformats.org/drawingml/2006/main" xmlns:m="http://schemas.openxmlformats.org/officeDocument/2006/math">"#);
 
         // Generate body with relationship IDs
         self.body.to_xml_with_rels(&mut xml, rel_mapper)?;
@@ -640,6 +938,47 @@ mod tests {
         assert!(xml.contains("Test paragraph"));
     }
 
+    #[test]
+    fn test_comments() {
+        let mut doc = MutableDocument::new();
+        let (id, comment) = doc.add_comment("Ann & Co");
+        comment.set_initials("AC").set_date("2024-05-01T10:00:00Z");
+        comment.add_paragraph_with_text("First");
+        comment.add_paragraph_with_text("Second");
+        let para = doc.add_paragraph();
+        para.add_run_with_text("Some ");
+        para.add_commented_text("text", id);
+        let (reply_id, reply) = doc.add_reply(id, "Bob").unwrap();
+        reply.set_resolved(true);
+        assert_eq!(reply_id, 1);
+        assert!(doc.add_reply(7, "Bob").is_err());
+
+        let xml = doc.to_xml().unwrap();
+        assert!(xml.contains(
+            r#"<w:commentRangeStart w:id="0"/><w:commentRangeStart w:id="1"/><w:r><w:t xml:space="preserve">text</w:t></w:r><w:commentRangeEnd w:id="0"/><w:r><w:commentReference w:id="0"/></w:r><w:commentRangeEnd w:id="1"/><w:r><w:commentReference w:id="1"/></w:r>"#
+        ));
+
+        let comments = doc.generate_comments_xml(None).unwrap().unwrap();
+        assert!(comments.contains(
+            r#"<w:comment w:id="0" w:author="Ann &amp; Co" w:initials="AC" w:date="2024-05-01T10:00:00Z"><w:p>"#
+        ));
+        assert!(
+            comments
+                .contains(r#"<w:p w14:paraId="00000001"><w:r><w:t xml:space="preserve">Second"#)
+        );
+        assert!(
+            comments.contains(
+                r#"<w:comment w:id="1" w:author="Bob"><w:p w14:paraId="00000002"></w:p>"#
+            )
+        );
+
+        let extended = doc.generate_comments_extended_xml(None).unwrap().unwrap();
+        assert!(extended.contains(r#"<w15:commentEx w15:paraId="00000001" w15:done="0"/>"#));
+        assert!(extended.contains(
+            r#"<w15:commentEx w15:paraId="00000002" w15:paraIdParent="00000001" w15:done="1"/>"#
+        ));
+    }
+
     #[test]
     fn test_run_formatting() {
         let mut doc = MutableDocument::new();
@@ -2,19 +2,25 @@
 //!
 //! This module provides the writer API for creating and modifying Word documents.
 
+pub mod comment;
 pub mod doc;
 pub mod hyperlink;
 pub mod image;
 pub mod note;
+pub mod numbering;
 pub mod paragraph;
 pub(crate) mod relmap;
 pub mod run;
 pub mod section;
+pub mod style;
 pub mod table;
 
 // Re-export main document type
 pub use doc::MutableDocument;
 
+// Re-export comment types
+pub use comment::MutableComment;
+
 // Re-export note types
 pub use note::Note;
 
@@ -27,11 +33,20 @@ pub use hyperlink::MutableHyperlink;
 // Re-export image types
 pub use image::{ImageFormat, MutableInlineImage};
 
+// Re-export numbering types
+pub use numbering::{MutableNumbering, NumberingDefinition, NumberingLevel};
+
 // Re-export paragraph types
 pub use paragraph::{ListType, MutableParagraph};
 
 // Re-export run types
 pub use run::{MutableRun, RunContent};
 
+// Re-export style types
+pub use style::{
+    MutableStyle, MutableStyles, ParagraphFormat, RunFormat, TableFormat, TableStyleCondition,
+    TableStyleRegion,
+};
+
 // Re-export table types
 pub use table::{CellProperties, MutableCell, MutableRow, MutableTable, TableBorder, TableBorders};
@@ -0,0 +1,444 @@
+/// List definitions for DOCX documents.
+///
+/// Lists defined here are written to `numbering.xml` after the lists
+/// already there (or the built-in lists of new documents), with IDs above
+/// theirs.
+use crate::common::ListFormat;
+use crate::ooxml::common::xml::attribute;
+use crate::ooxml::docx::template;
+use crate::ooxml::error::{OoxmlError, Result};
+use quick_xml::Reader;
+use quick_xml::events::Event;
+use std::fmt::Write as FmtWrite;
+
+/// First abstract definition ID after the built-in ones.
+const FIRST_ABSTRACT_NUM_ID: u32 = 9;
+/// First numbering instance ID after the built-in ones.
+const FIRST_NUM_ID: u32 = 10;
+/// Number of levels in a list.
+const LEVEL_COUNT: usize = 9;
+
+/// Escape XML special characters.
+fn escape_xml(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&apos;")
+}
+
+/// The format of one level of a list.
+#[derive(Debug, Clone)]
+pub struct NumberingLevel {
+    pub(crate) format: ListFormat,
+    pub(crate) text: String,
+    pub(crate) start: u32,
+    pub(crate) indent_left: u32,
+    pub(crate) hanging: u32,
+    pub(crate) font: Option<String>,
+    pub(crate) style: Option<String>,
+}
+
+impl NumberingLevel {
+    fn new(level: usize, format: ListFormat, text: String) -> Self {
+        Self {
+            format,
+            text,
+            start: 1,
+            indent_left: 720 * (level as u32 + 1),
+            hanging: 360,
+            font: None,
+            style: None,
+        }
+    }
+
+    /// Set the number format.
+    pub fn format(&mut self, format: ListFormat) -> &mut Self {
+        self.format = format;
+        self
+    }
+
+    /// Set the marker text: the bullet character, or a pattern where `%1`
+    /// to `%9` stand for the numbers of the levels (e.g., "%1.%2.").
+    pub fn text(&mut self, text: &str) -> &mut Self {
+        self.text = text.to_string();
+        self
+    }
+
+    /// Set the first number.
+    pub fn start(&mut self, start: u32) -> &mut Self {
+        self.start = start;
+        self
+    }
+
+    /// Set the left indentation and how far the marker hangs into it (in
+    /// inches).
+    pub fn indent(&mut self, left: f64, hanging: f64) -> &mut Self {
+        self.indent_left = (left * 1440.0) as u32;
+        self.hanging = (hanging * 1440.0) as u32;
+        self
+    }
+
+    /// Set the font of the marker, for bullets from symbol fonts.
+    pub fn font(&mut self, name: &str) -> &mut Self {
+        self.font = Some(name.to_string());
+        self
+    }
+
+    /// Link the level to a paragraph style, so paragraphs in that style are
+    /// numbered at this level.
+    pub fn paragraph_style(&mut self, style_id: &str) -> &mut Self {
+        self.style = Some(style_id.to_string());
+        self
+    }
+
+    fn to_xml(&self, xml: &mut String, level: usize) -> Result<()> {
+        write!(
+            xml,
+            "<w:lvl w:ilvl=\"{}\"><w:start w:val=\"{}\"/><w:numFmt w:val=\"{}\"/>",
+            level,
+            self.start,
+            self.format.to_ooxml()
+        )
+        .map_err(|e| OoxmlError::Xml(e.to_string()))?;
+        if let Some(ref style) = self.style {
+            write!(xml, "<w:pStyle w:val=\"{}\"/>", escape_xml(style))
+                .map_err(|e| OoxmlError::Xml(e.to_string()))?;
+        }
+        write!(
+            xml,
+            "<w:lvlText w:val=\"{}\"/><w:lvlJc w:val=\"left\"/><w:pPr><w:ind w:left=\"{}\" w:hanging=\"{}\"/></w:pPr>",
+            escape_xml(&self.text),
+            self.indent_left,
+            self.hanging
+        )
+        .map_err(|e| OoxmlError::Xml(e.to_string()))?;
+        if let Some(ref font) = self.font {
+            let font = escape_xml(font);
+            write!(
+                xml,
+                "<w:rPr><w:rFonts w:ascii=\"{}\" w:hAnsi=\"{}\" w:hint=\"default\"/></w:rPr>",
+                font, font
+            )
+            .map_err(|e| OoxmlError::Xml(e.to_string()))?;
+        }
+        xml.push_str("</w:lvl>");
+        Ok(())
+    }
+}
+
+/// A list definition with nine levels.
+///
+/// Paragraphs join the list through
+/// [`MutableParagraph::set_numbering`](super::MutableParagraph::set_numbering)
+/// or a paragraph style's
+/// [`ParagraphFormat::numbering`](super::ParagraphFormat::numbering) with
+/// its [`num_id`](Self::num_id).
+#[derive(Debug, Clone)]
+pub struct NumberingDefinition {
+    pub(crate) abstract_num_id: u32,
+    pub(crate) num_id: u32,
+    pub(crate) name: Option<String>,
+    pub(crate) style_link: Option<String>,
+    pub(crate) levels: Vec<NumberingLevel>,
+}
+
+impl NumberingDefinition {
+    /// Get the ID paragraphs use to join the list.
+    pub fn num_id(&self) -> u32 {
+        self.num_id
+    }
+
+    /// Set the name of the list.
+    pub fn set_name(&mut self, name: &str) -> &mut Self {
+        self.name = Some(name.to_string());
+        self
+    }
+
+    /// Make this the definition of a numbering style added with
+    /// [`MutableStyles::add_numbering_style`](super::MutableStyles::add_numbering_style).
+    pub fn set_style_link(&mut self, style_id: &str) -> &mut Self {
+        self.style_link = Some(style_id.to_string());
+        self
+    }
+
+    /// Get a level (0 to 8).
+    pub fn level(&mut self, level: usize) -> Option<&mut NumberingLevel> {
+        self.levels.get_mut(level)
+    }
+
+    fn to_xml(&self, xml: &mut String) -> Result<()> {
+        write!(
+            xml,
+            "<w:abstractNum w:abstractNumId=\"{}\"><w:multiLevelType w:val=\"hybridMultilevel\"/>",
+            self.abstract_num_id
+        )
+        .map_err(|e| OoxmlError::Xml(e.to_string()))?;
+        if let Some(ref name) = self.name {
+            write!(xml, "<w:name w:val=\"{}\"/>", escape_xml(name))
+                .map_err(|e| OoxmlError::Xml(e.to_string()))?;
+        }
+        if let Some(ref style) = self.style_link {
+            write!(xml, "<w:styleLink w:val=\"{}\"/>", escape_xml(style))
+                .map_err(|e| OoxmlError::Xml(e.to_string()))?;
+        }
+        for (i, level) in self.levels.iter().enumerate() {
+            level.to_xml(xml, i)?;
+        }
+        xml.push_str("</w:abstractNum>");
+        Ok(())
+    }
+}
+
+/// The list definitions of a document being written.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// use litchi::ooxml::docx::Package;
+///
+/// let mut pkg = Package::new()?;
+/// let doc = pkg.document_mut()?;
+///
+/// let steps = doc.numbering_mut().add_numbered_list().num_id();
+/// doc.add_paragraph_with_text("Open the package").set_numbering(steps, 0);
+/// doc.add_paragraph_with_text("Read the document").set_numbering(steps, 0);
+/// pkg.save("steps.docx")?;
+/// # Ok::<(), Box<dyn std::error::Error>>(())
+/// ```
+#[derive(Debug)]
+pub struct MutableNumbering {
+    pub(crate) definitions: Vec<NumberingDefinition>,
+    /// Instances restarting a definition: (num ID, abstract definition ID)
+    pub(crate) restarts: Vec<(u32, u32)>,
+    /// ID of the first abstract definition added
+    first_abstract_num_id: u32,
+    /// ID of the first numbering instance added
+    first_num_id: u32,
+}
+
+impl MutableNumbering {
+    pub(crate) fn new() -> Self {
+        Self {
+            definitions: Vec::new(),
+            restarts: Vec::new(),
+            first_abstract_num_id: FIRST_ABSTRACT_NUM_ID,
+            first_num_id: FIRST_NUM_ID,
+        }
+    }
+
+    /// Create definitions for a document whose `numbering.xml` already
+    /// holds lists, numbering the new ones after the highest IDs in use.
+    pub(crate) fn following(xml: &str) -> Result<Self> {
+        let mut numbering = Self {
+            first_abstract_num_id: 0,
+            // Instance 0 means no numbering
+            first_num_id: 1,
+            ..Self::new()
+        };
+        let mut reader = Reader::from_str(xml);
+        loop {
+            match reader
+                .read_event()
+                .map_err(|e| OoxmlError::Xml(e.to_string()))?
+            {
+                Event::Start(ref e) | Event::Empty(ref e) => {
+                    let (id, first) = match e.local_name().as_ref() {
+                        b"abstractNum" => (
+                            attribute(e, b"abstractNumId"),
+                            &mut numbering.first_abstract_num_id,
+                        ),
+                        b"num" => (attribute(e, b"numId"), &mut numbering.first_num_id),
+                        _ => continue,
+                    };
+                    if let Some(id) = id.and_then(|id| id.parse::<u32>().ok()) {
+                        *first = (*first).max(id + 1);
+                    }
+                },
+                Event::Eof => break,
+                _ => {},
+            }
+        }
+        Ok(numbering)
+    }
+
+    fn next_num_id(&self) -> u32 {
+        self.first_num_id + (self.definitions.len() + self.restarts.len()) as u32
+    }
+
+    /// Add a list with the given level formats, repeated to fill nine
+    /// levels.
+    fn add_definition(&mut self, formats: &[(ListFormat, &str)]) -> &mut NumberingDefinition {
+        let levels = (0..LEVEL_COUNT)
+            .map(|i| {
+                let (format, text) = formats[i % formats.len()];
+                let text = text.replace("%", &format!("%{}", i + 1));
+                NumberingLevel::new(i, format, text)
+            })
+            .collect();
+        let definition = NumberingDefinition {
+            abstract_num_id: self.first_abstract_num_id + self.definitions.len() as u32,
+            num_id: self.next_num_id(),
+            name: None,
+            style_link: None,
+            levels,
+        };
+        self.definitions.push(definition);
+        self.definitions.last_mut().unwrap()
+    }
+
+    /// Add a bulleted list.
+    pub fn add_bullet_list(&mut self) -> &mut NumberingDefinition {
+        self.add_definition(&[
+            (ListFormat::Bullet, "\u{2022}"),
+            (ListFormat::Bullet, "\u{25E6}"),
+            (ListFormat::Bullet, "\u{25AA}"),
+        ])
+    }
+
+    /// Add a numbered list (1., a., i., ...).
+    pub fn add_numbered_list(&mut self) -> &mut NumberingDefinition {
+        self.add_definition(&[
+            (ListFormat::Decimal, "%."),
+            (ListFormat::LowerLetter, "%."),
+            (ListFormat::LowerRoman, "%."),
+        ])
+    }
+
+    /// Add a list numbered with one format at every level.
+    pub fn add_list(&mut self, format: ListFormat) -> &mut NumberingDefinition {
+        match format {
+            ListFormat::Bullet => self.add_bullet_list(),
+            ListFormat::None => self.add_definition(&[(format, "")]),
+            _ => self.add_definition(&[(format, "%.")]),
+        }
+    }
+
+    /// Start a list over: returns the ID of a new instance of the list
+    /// `num_id` that numbers from the start again.
+    pub fn restart(&mut self, num_id: u32) -> Option<u32> {
+        let abstract_num_id = self
+            .definitions
+            .iter()
+            .find(|d| d.num_id == num_id)
+            .map(|d| d.abstract_num_id)
+            .or_else(|| {
+                self.restarts
+                    .iter()
+                    .find(|(id, _)| *id == num_id)
+                    .map(|(_, abstract_id)| *abstract_id)
+            })?;
+        let new_id = self.next_num_id();
+        self.restarts.push((new_id, abstract_num_id));
+        Some(new_id)
+    }
+
+    /// Check if no lists are defined.
+    pub fn is_empty(&self) -> bool {
+        self.definitions.is_empty()
+    }
+
+    /// Generate `numbering.xml`: the lists of `base`, the document's
+    /// current `numbering.xml`, or else the built-in lists of new documents,
+    /// followed by these.
+    pub(crate) fn to_xml(&self, base: Option<&str>) -> Result<String> {
+        let base = base.unwrap_or(template::default_numbering_xml());
+        // Instances go after the existing ones but before the cleanup list
+        let end = base
+            .find("<w:numIdMacAtCleanup")
+            .or_else(|| base.rfind("</w:numbering>"))
+            .ok_or_else(|| OoxmlError::Xml("Unterminated numbering part".to_string()))?;
+        // Abstract definitions must all come before the instances
+        let nums_start = base.find("<w:num ").unwrap_or(end);
+
+        let mut xml = String::with_capacity(base.len() + 4096 * self.definitions.len());
+        xml.push_str(&base[..nums_start]);
+        for definition in &self.definitions {
+            definition.to_xml(&mut xml)?;
+        }
+        xml.push_str(&base[nums_start..end]);
+
+        for definition in &self.definitions {
+            write!(
+                xml,
+                "<w:num w:numId=\"{}\"><w:abstractNumId w:val=\"{}\"/></w:num>",
+                definition.num_id, definition.abstract_num_id
+            )
+            .map_err(|e| OoxmlError::Xml(e.to_string()))?;
+        }
+        for &(num_id, abstract_num_id) in &self.restarts {
+            write!(
+                xml,
+                "<w:num w:numId=\"{}\"><w:abstractNumId w:val=\"{}\"/>",
+                num_id, abstract_num_id
+            )
+            .map_err(|e| OoxmlError::Xml(e.to_string()))?;
+            let definition = self
+                .definitions
+                .iter()
+                .find(|d| d.abstract_num_id == abstract_num_id);
+            for (i, level) in definition.iter().flat_map(|d| d.levels.iter().enumerate()) {
+                write!(
+                    xml,
+                    "<w:lvlOverride w:ilvl=\"{}\"><w:startOverride w:val=\"{}\"/></w:lvlOverride>",
+                    i, level.start
+                )
+                .map_err(|e| OoxmlError::Xml(e.to_string()))?;
+            }
+            xml.push_str("</w:num>");
+        }
+
+        xml.push_str(&base[end..]);
+        Ok(xml)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ooxml::docx::numbering::Numbering;
+
+    #[test]
+    fn test_numbering_xml() {
+        let mut numbering = MutableNumbering::new();
+        let bullets = numbering.add_bullet_list().num_id();
+        let steps = numbering.add_numbered_list();
+        steps.level(0).unwrap().start(3);
+        let steps = steps.num_id();
+        let again = numbering.restart(steps).unwrap();
+        assert_eq!((bullets, steps, again), (10, 11, 12));
+        assert_eq!(numbering.restart(99), None);
+
+        let xml = numbering.to_xml(None).unwrap();
+        assert!(xml.contains("<w:startOverride w:val=\"3\"/>"));
+        // Abstract definitions stay ahead of the instances
+        assert!(xml.find("w:abstractNumId=\"10\"").unwrap() < xml.find("<w:num ").unwrap());
+
+        let parsed = Numbering::parse(xml.as_bytes()).unwrap();
+        assert_eq!(parsed.level_format(bullets, 0), Some(ListFormat::Bullet));
+        assert_eq!(parsed.level_format(steps, 1), Some(ListFormat::LowerLetter));
+        assert_eq!(parsed.level_format(again, 2), Some(ListFormat::LowerRoman));
+        // Built-in lists are kept
+        assert_eq!(parsed.level_format(1, 0), Some(ListFormat::Bullet));
+    }
+
+    #[test]
+    fn test_numbering_after_existing_lists() {
+        let existing = "<w:numbering xmlns:w=\"http://schemas.openxmlformats.org/wordprocessingml/2006/main\">\
+                        <w:abstractNum w:abstractNumId=\"4\"><w:lvl w:ilvl=\"0\"><w:numFmt w:val=\"upperRoman\"/></w:lvl></w:abstractNum>\
+                        <w:num w:numId=\"7\"><w:abstractNumId w:val=\"4\"/></w:num>\
+                        <w:numIdMacAtCleanup w:val=\"7\"/></w:numbering>";
+        let mut numbering = MutableNumbering::following(existing).unwrap();
+        let bullets = numbering.add_bullet_list().num_id();
+        assert_eq!(bullets, 8);
+        assert_eq!(numbering.definitions[0].abstract_num_id, 5);
+
+        let xml = numbering.to_xml(Some(existing)).unwrap();
+        assert!(
+            xml.find("<w:num w:numId=\"8\">").unwrap() < xml.find("<w:numIdMacAtCleanup").unwrap()
+        );
+        let parsed = Numbering::parse(xml.as_bytes()).unwrap();
+        assert_eq!(parsed.level_format(7, 0), Some(ListFormat::UpperRoman));
+        assert_eq!(parsed.level_format(bullets, 0), Some(ListFormat::Bullet));
+    }
+}
@@ -24,6 +24,12 @@ pub(crate) enum ParagraphElement {
     Run(MutableRun),
     Hyperlink(MutableHyperlink),
     InlineImage(MutableInlineImage),
+    /// OMML markup (`m:oMath` or `m:oMathPara`)
+    Math(String),
+    /// Start of the text a comment is on
+    CommentStart(u32),
+    /// End of the text a comment is on, followed by its reference mark
+    CommentEnd(u32),
 }
 
 /// A mutable paragraph in a document.
@@ -107,6 +113,85 @@ impl MutableParagraph {
         }
     }
 
+    /// Add an equation to the paragraph.
+    ///
+    /// `omml` is Office Math markup: an `m:oMath` element for an inline
+    /// equation, or an `m:oMathPara` element for a display equation on its
+    /// own line. The `m` prefix is declared on the document element.
+    pub fn add_math(&mut self, omml: &str) {
+        self.elements.push(ParagraphElement::Math(omml.to_string()));
+    }
+
+    /// Start the text commented on by a comment.
+    ///
+    /// The range may end in a later paragraph. `id` comes from
+    /// [`MutableDocument::add_comment`](super::MutableDocument::add_comment).
+    pub fn start_comment(&mut self, id: u32) {
+        self.elements.push(ParagraphElement::CommentStart(id));
+    }
+
+    /// End the text commented on by a comment, and place its reference mark.
+    pub fn end_comment(&mut self, id: u32) {
+        self.elements.push(ParagraphElement::CommentEnd(id));
+    }
+
+    /// Add a run with text that a comment is on.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use litchi::ooxml::docx::Package;
+    ///
+    /// let mut pkg = Package::new()?;
+    /// let doc = pkg.document_mut()?;
+    /// let (id, comment) = doc.add_comment("Ann Lee");
+    /// comment.set_initials("AL");
+    /// comment.add_paragraph_with_text("Is this figure current?");
+    ///
+    /// let para = doc.add_paragraph();
+    /// para.add_run_with_text("Revenue grew ");
+    /// para.add_commented_text("12%", id).bold(true);
+    ///
+    /// let (_, reply) = doc.add_reply(id, "Bob")?;
+    /// reply.add_paragraph_with_text("Yes, from the Q3 report.");
+    /// pkg.save("commented.docx")?;
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn add_commented_text(&mut self, text: &str, id: u32) -> &mut MutableRun {
+        self.start_comment(id);
+        self.elements.push(ParagraphElement::Run(MutableRun::new()));
+        self.end_comment(id);
+        let index = self.elements.len() - 2;
+        match &mut self.elements[index] {
+            ParagraphElement::Run(run) => {
+                run.set_text(text);
+                run
+            },
+            _ => unreachable!(),
+        }
+    }
+
+    /// Anchor a reply next to every range of the comment it answers.
+    pub(crate) fn anchor_reply(&mut self, parent: u32, reply: u32) {
+        let mut index = 0;
+        while index < self.elements.len() {
+            let marker = match self.elements[index] {
+                ParagraphElement::CommentStart(id) if id == parent => {
+                    Some(ParagraphElement::CommentStart(reply))
+                },
+                ParagraphElement::CommentEnd(id) if id == parent => {
+                    Some(ParagraphElement::CommentEnd(reply))
+                },
+                _ => None,
+            };
+            if let Some(marker) = marker {
+                index += 1;
+                self.elements.insert(index, marker);
+            }
+            index += 1;
+        }
+    }
+
     /// Set the paragraph style.
     pub fn set_style(&mut self, style_id: &str) {
         self.style = Some(style_id.to_string());
@@ -164,6 +249,15 @@ impl MutableParagraph {
         });
     }
 
+    /// Number this paragraph with a list defined through
+    /// [`MutableNumbering`](super::MutableNumbering), at a level from 0.
+    pub fn set_numbering(&mut self, num_id: u32, level: u32) {
+        self.properties.numbering = Some(NumberingProperties {
+            num_id,
+            ilvl: level,
+        });
+    }
+
     /// Get the number of elements (runs and hyperlinks).
     pub fn element_count(&self) -> usize {
         self.elements.len()
@@ -297,6 +391,9 @@ impl MutableParagraph {
                     xml.push_str("</w:r>");
                     image_idx += 1;
                 },
+                ParagraphElement::Math(omml) => xml.push_str(omml),
+                ParagraphElement::CommentStart(id) => write_comment_start(xml, *id)?,
+                ParagraphElement::CommentEnd(id) => write_comment_end(xml, *id)?,
             }
         }
 
@@ -445,6 +542,9 @@ impl MutableParagraph {
                     xml.push_str("</w:r>");
                     *image_counter += 1;
                 },
+                ParagraphElement::Math(omml) => xml.push_str(omml),
+                ParagraphElement::CommentStart(id) => write_comment_start(xml, *id)?,
+                ParagraphElement::CommentEnd(id) => write_comment_end(xml, *id)?,
             }
         }
 
@@ -453,6 +553,19 @@ impl MutableParagraph {
     }
 }
 
+fn write_comment_start(xml: &mut String, id: u32) -> Result<()> {
+    write!(xml, "<w:commentRangeStart w:id=\"{}\"/>", id)
+        .map_err(|e| OoxmlError::Xml(e.to_string()))
+}
+
+fn write_comment_end(xml: &mut String, id: u32) -> Result<()> {
+    write!(xml, "<w:commentRangeEnd w:id=\"{}\"/>", id)
+        .map_err(|e| OoxmlError::Xml(e.to_string()))?;
+    let mut reference = MutableRun::new();
+    reference.add_comment_reference(id);
+    reference.to_xml(xml)
+}
+
 /// Paragraph properties.
 #[derive(Debug, Default)]
 pub(crate) struct ParagraphProperties {
@@ -33,6 +33,8 @@ pub enum RunContent {
     FootnoteReference(u32),
     /// Endnote reference
     EndnoteReference(u32),
+    /// Comment reference
+    CommentReference(u32),
 }
 
 /// A mutable run.
@@ -59,6 +61,12 @@ impl MutableRun {
         self.content = RunContent::Text(text.to_string());
     }
 
+    /// Set the character style by ID.
+    pub fn style(&mut self, style_id: &str) -> &mut Self {
+        self.properties.style = Some(style_id.to_string());
+        self
+    }
+
     /// Make the text bold.
     pub fn bold(&mut self, bold: bool) -> &mut Self {
         self.properties.bold = Some(bold);
@@ -71,6 +79,12 @@ impl MutableRun {
         self
     }
 
+    /// Strike through the text.
+    pub fn strikethrough(&mut self, strike: bool) -> &mut Self {
+        self.properties.strike = Some(strike);
+        self
+    }
+
     /// Set underline style.
     pub fn underline(&mut self, style: UnderlineStyle) -> &mut Self {
         self.properties.underline = Some(style);
@@ -143,6 +157,12 @@ impl MutableRun {
         self
     }
 
+    /// Add a comment reference.
+    pub fn add_comment_reference(&mut self, id: u32) -> &mut Self {
+        self.content = RunContent::CommentReference(id);
+        self
+    }
+
     pub(crate) fn to_xml(&self, xml: &mut String) -> Result<()> {
         xml.push_str("<w:r>");
 
@@ -150,6 +170,11 @@ impl MutableRun {
         if self.properties.has_properties() {
             xml.push_str("<w:rPr>");
 
+            if let Some(ref style) = self.properties.style {
+                write!(xml, "<w:rStyle w:val=\"{}\"/>", escape_xml(style))
+                    .map_err(|e| OoxmlError::Xml(e.to_string()))?;
+            }
+
             if let Some(bold) = self.properties.bold
                 && bold
             {
@@ -162,6 +187,12 @@ impl MutableRun {
                 xml.push_str("<w:i/>");
             }
 
+            if let Some(strike) = self.properties.strike
+                && strike
+            {
+                xml.push_str("<w:strike/>");
+            }
+
             if let Some(underline_style) = self.properties.underline {
                 write!(xml, "<w:u w:val=\"{}\"/>", underline_style.as_str())
                     .map_err(|e| OoxmlError::Xml(e.to_string()))?;
@@ -281,6 +312,10 @@ impl MutableRun {
                 write!(xml, "<w:endnoteReference w:id=\"{}\"/>", id)
                     .map_err(|e| OoxmlError::Xml(e.to_string()))?;
             },
+            RunContent::CommentReference(id) => {
+                write!(xml, "<w:commentReference w:id=\"{}\"/>", id)
+                    .map_err(|e| OoxmlError::Xml(e.to_string()))?;
+            },
             _ => {},
         }
 
@@ -298,8 +333,10 @@ impl MutableRun {
 /// Run properties.
 #[derive(Debug, Default)]
 pub(crate) struct RunProperties {
+    pub(crate) style: Option<String>,
     pub(crate) bold: Option<bool>,
     pub(crate) italic: Option<bool>,
+    pub(crate) strike: Option<bool>,
     pub(crate) underline: Option<UnderlineStyle>,
     pub(crate) font_size: Option<u32>,
     pub(crate) font_name: Option<String>,
@@ -310,8 +347,10 @@ pub(crate) struct RunProperties {
 
 impl RunProperties {
     pub(crate) fn has_properties(&self) -> bool {
-        self.bold.is_some()
+        self.style.is_some()
+            || self.bold.is_some()
             || self.italic.is_some()
+            || self.strike.is_some()
             || self.underline.is_some()
             || self.font_size.is_some()
             || self.font_name.is_some()
@@ -0,0 +1,1090 @@
+/// Style definitions for DOCX documents.
+///
+/// Styles defined here are written to `styles.xml` together with the
+/// styles already there (or the built-in styles of new documents); a style
+/// with the ID of an existing one replaces it.
+use crate::ooxml::common::xml::attribute;
+use crate::ooxml::docx::enums::WdStyleType;
+use crate::ooxml::docx::template;
+use crate::ooxml::error::{OoxmlError, Result};
+use quick_xml::Reader;
+use quick_xml::events::Event;
+use std::collections::HashSet;
+use std::fmt::Write as FmtWrite;
+
+// Import shared format types
+pub use super::super::format::{LineSpacing, ParagraphAlignment, UnderlineStyle};
+// Import table types
+use super::table::{TableBorders, write_borders};
+
+/// Escape XML special characters.
+fn escape_xml(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&apos;")
+}
+
+/// Write an on/off property, spelling out `false` so it overrides an
+/// inherited `true`.
+fn write_toggle(xml: &mut String, name: &str, value: Option<bool>) {
+    match value {
+        Some(true) => {
+            let _ = write!(xml, "<w:{}/>", name);
+        },
+        Some(false) => {
+            let _ = write!(xml, "<w:{} w:val=\"0\"/>", name);
+        },
+        None => {},
+    }
+}
+
+/// Character formatting of a style.
+#[derive(Debug, Clone, Default)]
+pub struct RunFormat {
+    pub(crate) bold: Option<bool>,
+    pub(crate) italic: Option<bool>,
+    pub(crate) all_caps: Option<bool>,
+    pub(crate) small_caps: Option<bool>,
+    pub(crate) strike: Option<bool>,
+    pub(crate) underline: Option<UnderlineStyle>,
+    pub(crate) font_size: Option<u32>,
+    pub(crate) font_name: Option<String>,
+    pub(crate) color: Option<String>,
+    pub(crate) highlight: Option<String>,
+}
+
+impl RunFormat {
+    /// Make the text bold, or not bold.
+    pub fn bold(&mut self, bold: bool) -> &mut Self {
+        self.bold = Some(bold);
+        self
+    }
+
+    /// Make the text italic, or not italic.
+    pub fn italic(&mut self, italic: bool) -> &mut Self {
+        self.italic = Some(italic);
+        self
+    }
+
+    /// Show the text in capitals.
+    pub fn all_caps(&mut self, caps: bool) -> &mut Self {
+        self.all_caps = Some(caps);
+        self
+    }
+
+    /// Show lowercase letters as small capitals.
+    pub fn small_caps(&mut self, caps: bool) -> &mut Self {
+        self.small_caps = Some(caps);
+        self
+    }
+
+    /// Strike through the text.
+    pub fn strikethrough(&mut self, strike: bool) -> &mut Self {
+        self.strike = Some(strike);
+        self
+    }
+
+    /// Set underline style.
+    pub fn underline(&mut self, style: UnderlineStyle) -> &mut Self {
+        self.underline = Some(style);
+        self
+    }
+
+    /// Set font size in half-points (e.g., 24 = 12pt).
+    pub fn font_size(&mut self, size: u32) -> &mut Self {
+        self.font_size = Some(size);
+        self
+    }
+
+    /// Set font name.
+    pub fn font_name(&mut self, name: &str) -> &mut Self {
+        self.font_name = Some(name.to_string());
+        self
+    }
+
+    /// Set text color using hex RGB (e.g., "FF0000" for red).
+    pub fn color(&mut self, color: &str) -> &mut Self {
+        self.color = Some(color.to_string());
+        self
+    }
+
+    /// Set text highlight color (e.g., "yellow").
+    pub fn highlight(&mut self, color: &str) -> &mut Self {
+        self.highlight = Some(color.to_string());
+        self
+    }
+
+    fn is_empty(&self) -> bool {
+        self.bold.is_none()
+            && self.italic.is_none()
+            && self.all_caps.is_none()
+            && self.small_caps.is_none()
+            && self.strike.is_none()
+            && self.underline.is_none()
+            && self.font_size.is_none()
+            && self.font_name.is_none()
+            && self.color.is_none()
+            && self.highlight.is_none()
+    }
+
+    /// Write `<w:rPr>`, in schema order.
+    pub(crate) fn to_xml(&self, xml: &mut String) -> Result<()> {
+        if self.is_empty() {
+            return Ok(());
+        }
+
+        xml.push_str("<w:rPr>");
+        if let Some(ref font_name) = self.font_name {
+            let font_name = escape_xml(font_name);
+            write!(
+                xml,
+                "<w:rFonts w:ascii=\"{}\" w:hAnsi=\"{}\" w:cs=\"{}\"/>",
+                font_name, font_name, font_name
+            )
+            .map_err(|e| OoxmlError::Xml(e.to_string()))?;
+        }
+        write_toggle(xml, "b", self.bold);
+        write_toggle(xml, "i", self.italic);
+        write_toggle(xml, "caps", self.all_caps);
+        write_toggle(xml, "smallCaps", self.small_caps);
+        write_toggle(xml, "strike", self.strike);
+        if let Some(ref color) = self.color {
+            write!(xml, "<w:color w:val=\"{}\"/>", escape_xml(color))
+                .map_err(|e| OoxmlError::Xml(e.to_string()))?;
+        }
+        if let Some(size) = self.font_size {
+            write!(
+                xml,
+                "<w:sz w:val=\"{}\"/><w:szCs w:val=\"{}\"/>",
+                size, size
+            )
+            .map_err(|e| OoxmlError::Xml(e.to_string()))?;
+        }
+        if let Some(ref highlight) = self.highlight {
+            write!(xml, "<w:highlight w:val=\"{}\"/>", escape_xml(highlight))
+                .map_err(|e| OoxmlError::Xml(e.to_string()))?;
+        }
+        if let Some(underline) = self.underline {
+            write!(xml, "<w:u w:val=\"{}\"/>", underline.as_str())
+                .map_err(|e| OoxmlError::Xml(e.to_string()))?;
+        }
+        xml.push_str("</w:rPr>");
+        Ok(())
+    }
+}
+
+/// Paragraph formatting of a style.
+#[derive(Debug, Clone, Default)]
+pub struct ParagraphFormat {
+    pub(crate) keep_with_next: Option<bool>,
+    pub(crate) keep_lines_together: Option<bool>,
+    pub(crate) page_break_before: Option<bool>,
+    pub(crate) numbering: Option<(u32, u32)>,
+    pub(crate) space_before: Option<u32>,
+    pub(crate) space_after: Option<u32>,
+    pub(crate) line_spacing: Option<LineSpacing>,
+    pub(crate) indent_left: Option<u32>,
+    pub(crate) indent_right: Option<u32>,
+    pub(crate) indent_first_line: Option<i32>,
+    pub(crate) alignment: Option<ParagraphAlignment>,
+    pub(crate) outline_level: Option<u8>,
+}
+
+impl ParagraphFormat {
+    /// Keep the paragraph on the same page as the next one.
+    pub fn keep_with_next(&mut self, keep: bool) -> &mut Self {
+        self.keep_with_next = Some(keep);
+        self
+    }
+
+    /// Keep all lines of the paragraph on the same page.
+    pub fn keep_lines_together(&mut self, keep: bool) -> &mut Self {
+        self.keep_lines_together = Some(keep);
+        self
+    }
+
+    /// Start the paragraph on a new page.
+    pub fn page_break_before(&mut self, page_break: bool) -> &mut Self {
+        self.page_break_before = Some(page_break);
+        self
+    }
+
+    /// Number the paragraph with a list from
+    /// [`MutableNumbering`](super::MutableNumbering), at a level from 0.
+    pub fn numbering(&mut self, num_id: u32, level: u32) -> &mut Self {
+        self.numbering = Some((num_id, level));
+        self
+    }
+
+    /// Set spacing before the paragraph (in points).
+    pub fn space_before(&mut self, points: f64) -> &mut Self {
+        self.space_before = Some((points * 20.0) as u32);
+        self
+    }
+
+    /// Set spacing after the paragraph (in points).
+    pub fn space_after(&mut self, points: f64) -> &mut Self {
+        self.space_after = Some((points * 20.0) as u32);
+        self
+    }
+
+    /// Set line spacing.
+    pub fn line_spacing(&mut self, spacing: LineSpacing) -> &mut Self {
+        self.line_spacing = Some(spacing);
+        self
+    }
+
+    /// Set left indentation (in inches).
+    pub fn indent_left(&mut self, inches: f64) -> &mut Self {
+        self.indent_left = Some((inches * 1440.0) as u32);
+        self
+    }
+
+    /// Set right indentation (in inches).
+    pub fn indent_right(&mut self, inches: f64) -> &mut Self {
+        self.indent_right = Some((inches * 1440.0) as u32);
+        self
+    }
+
+    /// Set first line indentation (in inches); negative for a hanging
+    /// indent.
+    pub fn indent_first_line(&mut self, inches: f64) -> &mut Self {
+        self.indent_first_line = Some((inches * 1440.0) as i32);
+        self
+    }
+
+    /// Set paragraph alignment.
+    pub fn alignment(&mut self, alignment: ParagraphAlignment) -> &mut Self {
+        self.alignment = Some(alignment);
+        self
+    }
+
+    /// Set the outline level (0 for headings at level 1, up to 8), which
+    /// puts the paragraph in the navigation pane and tables of contents.
+    pub fn outline_level(&mut self, level: u8) -> &mut Self {
+        self.outline_level = Some(level.min(8));
+        self
+    }
+
+    fn is_empty(&self) -> bool {
+        self.keep_with_next.is_none()
+            && self.keep_lines_together.is_none()
+            && self.page_break_before.is_none()
+            && self.numbering.is_none()
+            && self.space_before.is_none()
+            && self.space_after.is_none()
+            && self.line_spacing.is_none()
+            && self.indent_left.is_none()
+            && self.indent_right.is_none()
+            && self.indent_first_line.is_none()
+            && self.alignment.is_none()
+            && self.outline_level.is_none()
+    }
+
+    /// Write `<w:pPr>`, in schema order.
+    pub(crate) fn to_xml(&self, xml: &mut String) -> Result<()> {
+        if self.is_empty() {
+            return Ok(());
+        }
+
+        xml.push_str("<w:pPr>");
+        write_toggle(xml, "keepNext", self.keep_with_next);
+        write_toggle(xml, "keepLines", self.keep_lines_together);
+        write_toggle(xml, "pageBreakBefore", self.page_break_before);
+
+        if let Some((num_id, level)) = self.numbering {
+            write!(
+                xml,
+                "<w:numPr><w:ilvl w:val=\"{}\"/><w:numId w:val=\"{}\"/></w:numPr>",
+                level, num_id
+            )
+            .map_err(|e| OoxmlError::Xml(e.to_string()))?;
+        }
+
+        if self.space_before.is_some() || self.space_after.is_some() || self.line_spacing.is_some()
+        {
+            xml.push_str("<w:spacing");
+            if let Some(before) = self.space_before {
+                write!(xml, " w:before=\"{}\"", before)
+                    .map_err(|e| OoxmlError::Xml(e.to_string()))?;
+            }
+            if let Some(after) = self.space_after {
+                write!(xml, " w:after=\"{}\"", after)
+                    .map_err(|e| OoxmlError::Xml(e.to_string()))?;
+            }
+            if let Some(ref line_spacing) = self.line_spacing {
+                let (line, rule) = match line_spacing {
+                    LineSpacing::Single => (240, "auto"),
+                    LineSpacing::OneAndHalf => (360, "auto"),
+                    LineSpacing::Double => (480, "auto"),
+                    LineSpacing::Multiple(factor) => ((factor * 240.0) as u32, "auto"),
+                    LineSpacing::Exact(points) => ((points * 20.0) as u32, "exact"),
+                    LineSpacing::AtLeast(points) => ((points * 20.0) as u32, "atLeast"),
+                };
+                write!(xml, " w:line=\"{}\" w:lineRule=\"{}\"", line, rule)
+                    .map_err(|e| OoxmlError::Xml(e.to_string()))?;
+            }
+            xml.push_str("/>");
+        }
+
+        if self.indent_left.is_some()
+            || self.indent_right.is_some()
+            || self.indent_first_line.is_some()
+        {
+            xml.push_str("<w:ind");
+            if let Some(left) = self.indent_left {
+                write!(xml, " w:left=\"{}\"", left).map_err(|e| OoxmlError::Xml(e.to_string()))?;
+            }
+            if let Some(right) = self.indent_right {
+                write!(xml, " w:right=\"{}\"", right)
+                    .map_err(|e| OoxmlError::Xml(e.to_string()))?;
+            }
+            if let Some(first_line) = self.indent_first_line {
+                if first_line >= 0 {
+                    write!(xml, " w:firstLine=\"{}\"", first_line)
+                        .map_err(|e| OoxmlError::Xml(e.to_string()))?;
+                } else {
+                    write!(xml, " w:hanging=\"{}\"", -first_line)
+                        .map_err(|e| OoxmlError::Xml(e.to_string()))?;
+                }
+            }
+            xml.push_str("/>");
+        }
+
+        if let Some(alignment) = self.alignment {
+            write!(xml, "<w:jc w:val=\"{}\"/>", alignment.as_str())
+                .map_err(|e| OoxmlError::Xml(e.to_string()))?;
+        }
+        if let Some(level) = self.outline_level {
+            write!(xml, "<w:outlineLvl w:val=\"{}\"/>", level)
+                .map_err(|e| OoxmlError::Xml(e.to_string()))?;
+        }
+        xml.push_str("</w:pPr>");
+        Ok(())
+    }
+}
+
+/// Table formatting of a table style.
+#[derive(Debug, Clone, Default)]
+pub struct TableFormat {
+    pub(crate) borders: Option<TableBorders>,
+    pub(crate) shading: Option<String>,
+    pub(crate) cell_margin: Option<u32>,
+    pub(crate) row_band_size: Option<u32>,
+    pub(crate) column_band_size: Option<u32>,
+}
+
+impl TableFormat {
+    /// Set the table borders.
+    pub fn borders(&mut self, borders: TableBorders) -> &mut Self {
+        self.borders = Some(borders);
+        self
+    }
+
+    /// Set the cell background color in hex RGB format.
+    pub fn shading(&mut self, color: &str) -> &mut Self {
+        self.shading = Some(color.to_string());
+        self
+    }
+
+    /// Set the left and right cell margins in DXA units (twentieth of a point).
+    pub fn cell_margin(&mut self, dxa: u32) -> &mut Self {
+        self.cell_margin = Some(dxa);
+        self
+    }
+
+    /// Set how many rows each horizontal band covers.
+    pub fn row_band_size(&mut self, rows: u32) -> &mut Self {
+        self.row_band_size = Some(rows);
+        self
+    }
+
+    /// Set how many columns each vertical band covers.
+    pub fn column_band_size(&mut self, columns: u32) -> &mut Self {
+        self.column_band_size = Some(columns);
+        self
+    }
+
+    /// Write `<w:tblPr>` and `<w:tcPr>`.
+    fn to_xml(&self, xml: &mut String) -> Result<()> {
+        if self.row_band_size.is_some()
+            || self.column_band_size.is_some()
+            || self.borders.is_some()
+            || self.cell_margin.is_some()
+        {
+            xml.push_str("<w:tblPr>");
+            if let Some(rows) = self.row_band_size {
+                write!(xml, "<w:tblStyleRowBandSize w:val=\"{}\"/>", rows)
+                    .map_err(|e| OoxmlError::Xml(e.to_string()))?;
+            }
+            if let Some(columns) = self.column_band_size {
+                write!(xml, "<w:tblStyleColBandSize w:val=\"{}\"/>", columns)
+                    .map_err(|e| OoxmlError::Xml(e.to_string()))?;
+            }
+            if let Some(ref borders) = self.borders {
+                write_borders(xml, "tblBorders", borders)?;
+            }
+            if let Some(margin) = self.cell_margin {
+                write!(
+                    xml,
+                    "<w:tblCellMar><w:left w:w=\"{}\" w:type=\"dxa\"/><w:right w:w=\"{}\" w:type=\"dxa\"/></w:tblCellMar>",
+                    margin, margin
+                )
+                .map_err(|e| OoxmlError::Xml(e.to_string()))?;
+            }
+            xml.push_str("</w:tblPr>");
+        }
+        write_cell_shading(xml, self.shading.as_deref())
+    }
+}
+
+/// Write `<w:tcPr>` with a cell background color.
+fn write_cell_shading(xml: &mut String, shading: Option<&str>) -> Result<()> {
+    if let Some(color) = shading {
+        write!(
+            xml,
+            "<w:tcPr><w:shd w:val=\"clear\" w:color=\"auto\" w:fill=\"{}\"/></w:tcPr>",
+            escape_xml(color)
+        )
+        .map_err(|e| OoxmlError::Xml(e.to_string()))?;
+    }
+    Ok(())
+}
+
+/// Part of a table that a table style can format differently.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TableStyleRegion {
+    /// Header row
+    FirstRow,
+    /// Total row
+    LastRow,
+    /// First column
+    FirstColumn,
+    /// Last column
+    LastColumn,
+    /// Odd row bands
+    OddRows,
+    /// Even row bands
+    EvenRows,
+    /// Odd column bands
+    OddColumns,
+    /// Even column bands
+    EvenColumns,
+    /// Top left cell
+    TopLeftCell,
+    /// Top right cell
+    TopRightCell,
+    /// Bottom left cell
+    BottomLeftCell,
+    /// Bottom right cell
+    BottomRightCell,
+}
+
+impl TableStyleRegion {
+    /// Get the `w:tblStylePr` type.
+    pub(crate) fn as_str(&self) -> &'static str {
+        match self {
+            Self::FirstRow => "firstRow",
+            Self::LastRow => "lastRow",
+            Self::FirstColumn => "firstCol",
+            Self::LastColumn => "lastCol",
+            Self::OddRows => "band1Horz",
+            Self::EvenRows => "band2Horz",
+            Self::OddColumns => "band1Vert",
+            Self::EvenColumns => "band2Vert",
+            Self::TopLeftCell => "nwCell",
+            Self::TopRightCell => "neCell",
+            Self::BottomLeftCell => "swCell",
+            Self::BottomRightCell => "seCell",
+        }
+    }
+}
+
+/// Formatting a table style applies to one region of a table.
+#[derive(Debug, Clone)]
+pub struct TableStyleCondition {
+    pub(crate) region: TableStyleRegion,
+    pub(crate) run: RunFormat,
+    pub(crate) paragraph: ParagraphFormat,
+    pub(crate) shading: Option<String>,
+}
+
+impl TableStyleCondition {
+    /// Get the character formatting of the region.
+    pub fn run(&mut self) -> &mut RunFormat {
+        &mut self.run
+    }
+
+    /// Get the paragraph formatting of the region.
+    pub fn paragraph(&mut self) -> &mut ParagraphFormat {
+        &mut self.paragraph
+    }
+
+    /// Set the background color of the region's cells in hex RGB format.
+    pub fn shading(&mut self, color: &str) -> &mut Self {
+        self.shading = Some(color.to_string());
+        self
+    }
+
+    fn to_xml(&self, xml: &mut String) -> Result<()> {
+        write!(xml, "<w:tblStylePr w:type=\"{}\">", self.region.as_str())
+            .map_err(|e| OoxmlError::Xml(e.to_string()))?;
+        self.paragraph.to_xml(xml)?;
+        self.run.to_xml(xml)?;
+        write_cell_shading(xml, self.shading.as_deref())?;
+        xml.push_str("</w:tblStylePr>");
+        Ok(())
+    }
+}
+
+/// A style definition.
+///
+/// Created through [`MutableStyles`]; paragraphs, runs and tables refer to
+/// it by its ID.
+#[derive(Debug, Clone)]
+pub struct MutableStyle {
+    pub(crate) style_type: WdStyleType,
+    pub(crate) style_id: String,
+    pub(crate) name: String,
+    pub(crate) based_on: Option<String>,
+    pub(crate) next: Option<String>,
+    pub(crate) link: Option<String>,
+    pub(crate) ui_priority: Option<u32>,
+    pub(crate) quick_format: bool,
+    pub(crate) hidden: bool,
+    pub(crate) default: bool,
+    pub(crate) run: RunFormat,
+    pub(crate) paragraph: ParagraphFormat,
+    pub(crate) table: TableFormat,
+    pub(crate) conditions: Vec<TableStyleCondition>,
+}
+
+impl MutableStyle {
+    pub(crate) fn new(style_type: WdStyleType, style_id: &str, name: &str) -> Self {
+        Self {
+            style_type,
+            style_id: style_id.to_string(),
+            name: name.to_string(),
+            based_on: None,
+            next: None,
+            link: None,
+            ui_priority: None,
+            quick_format: false,
+            hidden: false,
+            default: false,
+            run: RunFormat::default(),
+            paragraph: ParagraphFormat::default(),
+            table: TableFormat::default(),
+            conditions: Vec::new(),
+        }
+    }
+
+    /// Get the style ID.
+    pub fn style_id(&self) -> &str {
+        &self.style_id
+    }
+
+    /// Inherit the formatting of another style of the same type.
+    pub fn based_on(&mut self, style_id: &str) -> &mut Self {
+        self.based_on = Some(style_id.to_string());
+        self
+    }
+
+    /// Set the style of the paragraph Word creates after one in this style.
+    pub fn next(&mut self, style_id: &str) -> &mut Self {
+        self.next = Some(style_id.to_string());
+        self
+    }
+
+    /// Link a paragraph style with a character style, so the paragraph
+    /// style's character formatting can be applied to part of a paragraph.
+    pub fn link(&mut self, style_id: &str) -> &mut Self {
+        self.link = Some(style_id.to_string());
+        self
+    }
+
+    /// Set the sort order in the style gallery (lower comes first).
+    pub fn ui_priority(&mut self, priority: u32) -> &mut Self {
+        self.ui_priority = Some(priority);
+        self
+    }
+
+    /// Show the style in the quick style gallery.
+    pub fn quick_format(&mut self, quick: bool) -> &mut Self {
+        self.quick_format = quick;
+        self
+    }
+
+    /// Hide the style from the user interface.
+    pub fn hidden(&mut self, hidden: bool) -> &mut Self {
+        self.hidden = hidden;
+        self
+    }
+
+    /// Make this the default style of its type.
+    pub fn set_default(&mut self, default: bool) -> &mut Self {
+        self.default = default;
+        self
+    }
+
+    /// Get the character formatting.
+    pub fn run(&mut self) -> &mut RunFormat {
+        &mut self.run
+    }
+
+    /// Get the paragraph formatting.
+    pub fn paragraph(&mut self) -> &mut ParagraphFormat {
+        &mut self.paragraph
+    }
+
+    /// Get the table formatting, for table styles.
+    pub fn table(&mut self) -> &mut TableFormat {
+        &mut self.table
+    }
+
+    /// Get the formatting for a region of the table, for table styles.
+    pub fn condition(&mut self, region: TableStyleRegion) -> &mut TableStyleCondition {
+        let index = match self.conditions.iter().position(|c| c.region == region) {
+            Some(index) => index,
+            None => {
+                self.conditions.push(TableStyleCondition {
+                    region,
+                    run: RunFormat::default(),
+                    paragraph: ParagraphFormat::default(),
+                    shading: None,
+                });
+                self.conditions.len() - 1
+            },
+        };
+        &mut self.conditions[index]
+    }
+
+    pub(crate) fn to_xml(&self, xml: &mut String, custom: bool) -> Result<()> {
+        write!(
+            xml,
+            "<w:style w:type=\"{}\" w:styleId=\"{}\"",
+            self.style_type.to_xml(),
+            escape_xml(&self.style_id)
+        )
+        .map_err(|e| OoxmlError::Xml(e.to_string()))?;
+        if self.default {
+            xml.push_str(" w:default=\"1\"");
+        }
+        if custom {
+            xml.push_str(" w:customStyle=\"1\"");
+        }
+        xml.push('>');
+
+        write!(xml, "<w:name w:val=\"{}\"/>", escape_xml(&self.name))
+            .map_err(|e| OoxmlError::Xml(e.to_string()))?;
+        for (element, value) in [
+            ("basedOn", &self.based_on),
+            ("next", &self.next),
+            ("link", &self.link),
+        ] {
+            if let Some(value) = value {
+                write!(xml, "<w:{} w:val=\"{}\"/>", element, escape_xml(value))
+                    .map_err(|e| OoxmlError::Xml(e.to_string()))?;
+            }
+        }
+        if self.hidden {
+            xml.push_str("<w:hidden/>");
+        }
+        if let Some(priority) = self.ui_priority {
+            write!(xml, "<w:uiPriority w:val=\"{}\"/>", priority)
+                .map_err(|e| OoxmlError::Xml(e.to_string()))?;
+        }
+        if self.quick_format {
+            xml.push_str("<w:qFormat/>");
+        }
+
+        self.paragraph.to_xml(xml)?;
+        self.run.to_xml(xml)?;
+        self.table.to_xml(xml)?;
+        for condition in &self.conditions {
+            condition.to_xml(xml)?;
+        }
+
+        xml.push_str("</w:style>");
+        Ok(())
+    }
+}
+
+/// The style definitions of a document being written.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// use litchi::ooxml::docx::{Package, TableStyleRegion};
+///
+/// let mut pkg = Package::new()?;
+/// let doc = pkg.document_mut()?;
+///
+/// let styles = doc.styles_mut();
+/// styles.default_run().font_name("Arial").font_size(20);
+/// styles
+///     .add_paragraph_style("CorpHeading", "Corporate Heading")
+///     .based_on("Normal")
+///     .next("Normal")
+///     .quick_format(true)
+///     .run()
+///     .bold(true)
+///     .color("1F3864");
+/// let table = styles.add_table_style("CorpTable", "Corporate Table");
+/// table.condition(TableStyleRegion::FirstRow).shading("1F3864").run().color("FFFFFF");
+/// table.condition(TableStyleRegion::OddRows).shading("D9E2F3");
+///
+/// doc.add_paragraph_with_text("Quarterly report").set_style("CorpHeading");
+/// doc.add_table(3, 2).set_style("CorpTable");
+/// pkg.save("styled.docx")?;
+/// # Ok::<(), Box<dyn std::error::Error>>(())
+/// ```
+#[derive(Debug, Default)]
+pub struct MutableStyles {
+    pub(crate) styles: Vec<MutableStyle>,
+    pub(crate) default_run: RunFormat,
+    pub(crate) default_paragraph: ParagraphFormat,
+}
+
+impl MutableStyles {
+    pub(crate) fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a style, replacing any earlier one with the same ID.
+    pub fn add_style(
+        &mut self,
+        style_type: WdStyleType,
+        style_id: &str,
+        name: &str,
+    ) -> &mut MutableStyle {
+        self.styles.retain(|style| style.style_id != style_id);
+        self.styles
+            .push(MutableStyle::new(style_type, style_id, name));
+        self.styles.last_mut().unwrap()
+    }
+
+    /// Add a paragraph style.
+    pub fn add_paragraph_style(&mut self, style_id: &str, name: &str) -> &mut MutableStyle {
+        self.add_style(WdStyleType::Paragraph, style_id, name)
+    }
+
+    /// Add a character style.
+    pub fn add_character_style(&mut self, style_id: &str, name: &str) -> &mut MutableStyle {
+        self.add_style(WdStyleType::Character, style_id, name)
+    }
+
+    /// Add a table style.
+    pub fn add_table_style(&mut self, style_id: &str, name: &str) -> &mut MutableStyle {
+        self.add_style(WdStyleType::Table, style_id, name)
+    }
+
+    /// Add a numbering style for a list from
+    /// [`MutableNumbering`](super::MutableNumbering).
+    pub fn add_numbering_style(
+        &mut self,
+        style_id: &str,
+        name: &str,
+        num_id: u32,
+    ) -> &mut MutableStyle {
+        let style = self.add_style(WdStyleType::List, style_id, name);
+        style.paragraph.numbering = Some((num_id, 0));
+        style
+    }
+
+    /// Get a style by ID.
+    pub fn style_mut(&mut self, style_id: &str) -> Option<&mut MutableStyle> {
+        self.styles
+            .iter_mut()
+            .find(|style| style.style_id == style_id)
+    }
+
+    /// Get the document-wide default character formatting.
+    pub fn default_run(&mut self) -> &mut RunFormat {
+        &mut self.default_run
+    }
+
+    /// Get the document-wide default paragraph formatting.
+    pub fn default_paragraph(&mut self) -> &mut ParagraphFormat {
+        &mut self.default_paragraph
+    }
+
+    /// Get the number of styles defined.
+    pub fn len(&self) -> usize {
+        self.styles.len()
+    }
+
+    /// Check if no styles are defined.
+    pub fn is_empty(&self) -> bool {
+        self.styles.is_empty()
+    }
+
+    /// Check if any styles or defaults are defined.
+    pub(crate) fn has_definitions(&self) -> bool {
+        !self.styles.is_empty()
+            || !self.default_run.is_empty()
+            || !self.default_paragraph.is_empty()
+    }
+
+    /// Generate `styles.xml`: the styles of `base`, the document's current
+    /// `styles.xml`, or else the built-in styles of new documents, with
+    /// these styles added or replacing them.
+    pub(crate) fn to_xml(&self, base: Option<&str>) -> Result<String> {
+        let base = base.unwrap_or(template::default_styles_xml());
+        let defined: HashSet<&str> = self.styles.iter().map(|s| s.style_id.as_str()).collect();
+        let has_defaults = base.contains("docDefaults>");
+        // IDs of the styles already there that aren't custom ones
+        let mut builtin = HashSet::new();
+        let mut xml = String::with_capacity(base.len() + 1024 * self.styles.len());
+
+        let mut reader = Reader::from_str(base);
+        // Start of the markup not yet copied
+        let mut copied = 0;
+        // Start of the element being skipped and whether it is kept
+        let mut skipping: Option<(usize, bool)> = None;
+        let mut depth = 0usize;
+
+        loop {
+            let start = reader.buffer_position() as usize;
+            let event = reader
+                .read_event()
+                .map_err(|e| OoxmlError::Xml(e.to_string()))?;
+            let end = reader.buffer_position() as usize;
+            match event {
+                Event::Start(ref e) => {
+                    depth += 1;
+                    if depth == 1 && !has_defaults {
+                        // Defaults go first in the part
+                        xml.push_str(&base[copied..end]);
+                        copied = end;
+                        self.write_doc_defaults(&mut xml, "")?;
+                    }
+                    if depth != 2 {
+                        continue;
+                    }
+                    match e.local_name().as_ref() {
+                        b"docDefaults" => skipping = Some((start, false)),
+                        b"style" => {
+                            let id = attribute(e, b"styleId").unwrap_or_default();
+                            let replaced = defined.contains(id.as_str());
+                            let custom = attribute(e, b"customStyle")
+                                .is_some_and(|v| v == "1" || v == "true");
+                            if !custom {
+                                builtin.insert(id);
+                            }
+                            if replaced {
+                                skipping = Some((start, false));
+                            }
+                        },
+                        _ => {},
+                    }
+                    if let Some((skip_start, _)) = skipping {
+                        xml.push_str(&base[copied..skip_start]);
+                        copied = skip_start;
+                    }
+                },
+                Event::End(ref e) => {
+                    depth = depth.saturating_sub(1);
+                    if depth == 1
+                        && let Some((_, _)) = skipping.take()
+                    {
+                        if e.local_name().as_ref() == b"docDefaults" {
+                            self.write_doc_defaults(&mut xml, &base[copied..end])?;
+                        }
+                        copied = end;
+                    } else if depth == 0 {
+                        // Add the styles before </w:styles>
+                        xml.push_str(&base[copied..start]);
+                        for style in &self.styles {
+                            style.to_xml(&mut xml, !builtin.contains(&style.style_id))?;
+                        }
+                        xml.push_str(&base[start..]);
+                        return Ok(xml);
+                    }
+                },
+                Event::Eof => break,
+                _ => {},
+            }
+        }
+        Err(OoxmlError::Xml("Unterminated styles part".to_string()))
+    }
+
+    /// Write `<w:docDefaults>`, keeping the current defaults unless
+    /// defaults were set.
+    fn write_doc_defaults(&self, xml: &mut String, template: &str) -> Result<()> {
+        if self.default_run.is_empty() && self.default_paragraph.is_empty() {
+            xml.push_str(template);
+            return Ok(());
+        }
+        xml.push_str("<w:docDefaults><w:rPrDefault>");
+        if self.default_run.is_empty() {
+            xml.push_str("<w:rPr/>");
+        } else {
+            self.default_run.to_xml(xml)?;
+        }
+        xml.push_str("</w:rPrDefault><w:pPrDefault>");
+        if self.default_paragraph.is_empty() {
+            xml.push_str("<w:pPr/>");
+        } else {
+            self.default_paragraph.to_xml(xml)?;
+        }
+        xml.push_str("</w:pPrDefault></w:docDefaults>");
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ooxml::docx::styles::Styles;
+    use crate::ooxml::opc::packuri::PackURI;
+    use crate::ooxml::opc::part::BlobPart;
+    use std::io::Cursor;
+
+    #[test]
+    fn test_styles_xml() {
+        let mut styles = MutableStyles::new();
+        styles.default_run().font_name("Arial").font_size(20);
+        styles
+            .add_paragraph_style("Heading1", "heading 1")
+            .based_on("Normal")
+            .next("Normal")
+            .link("CorpChar")
+            .quick_format(true)
+            .paragraph()
+            .keep_with_next(true)
+            .outline_level(0);
+        styles
+            .add_character_style("CorpChar", "Corporate Emphasis")
+            .run()
+            .italic(true)
+            .bold(false);
+        let table = styles.add_table_style("CorpTable", "Corporate Table");
+        table.table().row_band_size(1);
+        table
+            .condition(TableStyleRegion::FirstRow)
+            .shading("1F3864")
+            .run()
+            .color("FFFFFF");
+        table.condition(TableStyleRegion::FirstRow).run().bold(true);
+        assert_eq!(table.conditions.len(), 1);
+
+        let xml = styles.to_xml(None).unwrap();
+        assert_eq!(xml.matches("w:styleId=\"Heading1\"").count(), 1);
+        assert!(xml.contains("<w:b w:val=\"0\"/>"));
+        assert!(xml.contains("<w:tblStylePr w:type=\"firstRow\"><w:rPr><w:b/><w:color w:val=\"FFFFFF\"/></w:rPr><w:tcPr>"));
+        assert!(xml.contains("<w:docDefaults><w:rPrDefault><w:rPr><w:rFonts w:ascii=\"Arial\""));
+
+        let part = BlobPart::new(
+            PackURI::new("/word/styles.xml").unwrap(),
+            "application/xml".to_string(),
+            xml.into_bytes(),
+        );
+        let mut parsed = Styles::from_part(&part);
+        let heading = parsed.get_by_id("Heading1").unwrap().unwrap();
+        assert_eq!(heading.based_on(), Some("Normal"));
+        assert_eq!(heading.outline_level(), Some(0));
+        assert!(heading.is_builtin());
+        let table = parsed.get_by_id("CorpTable").unwrap().unwrap();
+        assert_eq!(table.style_type(), WdStyleType::Table);
+        assert!(table.is_custom());
+        // Built-in styles not redefined are kept
+        assert!(parsed.get_by_id("Title").unwrap().is_some());
+    }
+
+    #[test]
+    fn test_styles_round_trip() {
+        use crate::common::ListFormat;
+        use crate::ooxml::docx::Package;
+
+        let mut pkg = Package::new().unwrap();
+        {
+            let doc = pkg.document_mut().unwrap();
+            let steps = doc.numbering_mut().add_numbered_list().num_id();
+            doc.styles_mut()
+                .add_paragraph_style("CorpStep", "Corporate Step")
+                .based_on("Normal")
+                .paragraph()
+                .numbering(steps, 1);
+            doc.add_paragraph_with_text("First").set_style("CorpStep");
+            doc.add_table(2, 2).set_style("TableGrid");
+        }
+        let mut buffer = Cursor::new(Vec::new());
+        pkg.save_to_writer(&mut buffer).unwrap();
+
+        let pkg = Package::from_reader(buffer).unwrap();
+        let doc = pkg.document().unwrap();
+        let paragraphs = doc.paragraphs().unwrap();
+        let info = paragraphs[0].list_info().unwrap().unwrap();
+        assert_eq!(
+            paragraphs[0].style_name().unwrap().as_deref(),
+            Some("Corporate Step")
+        );
+        assert_eq!((info.level, info.format), (1, ListFormat::LowerLetter));
+        // Built-in styles remain linked to the document
+        assert!(
+            doc.styles()
+                .unwrap()
+                .get_by_id("TableGrid")
+                .unwrap()
+                .is_some()
+        );
+    }
+
+    #[test]
+    fn test_styles_merge_into_existing_parts() {
+        use crate::common::ListFormat;
+        use crate::ooxml::docx::Package;
+
+        let save = |pkg: &mut Package| {
+            let mut buffer = Cursor::new(Vec::new());
+            pkg.save_to_writer(&mut buffer).unwrap();
+            Package::from_reader(buffer).unwrap()
+        };
+
+        let mut pkg = Package::new().unwrap();
+        let steps = {
+            let doc = pkg.document_mut().unwrap();
+            let steps = doc.numbering_mut().add_numbered_list().num_id();
+            doc.styles_mut()
+                .add_paragraph_style("CorpBody", "Corporate Body")
+                .run()
+                .italic(true);
+            doc.add_paragraph_with_text("Body").set_style("CorpBody");
+            doc.add_paragraph_with_text("Step").set_numbering(steps, 0);
+            steps
+        };
+        let mut pkg = save(&mut pkg);
+
+        // A second editing session uses and adds to the saved definitions
+        let bullets = {
+            let doc = pkg.document_mut().unwrap();
+            let bullets = doc.numbering_mut().add_bullet_list().num_id();
+            doc.add_paragraph_with_text("Body").set_style("CorpBody");
+            doc.add_paragraph_with_text("Step").set_numbering(steps, 0);
+            doc.styles_mut()
+                .add_paragraph_style("CorpNote", "Corporate Note")
+                .based_on("CorpBody");
+            let note = doc.add_paragraph_with_text("Note");
+            note.set_style("CorpNote");
+            note.set_numbering(bullets, 0);
+            bullets
+        };
+        assert_ne!(bullets, steps);
+        let pkg = save(&mut pkg);
+
+        let doc = pkg.document().unwrap();
+        let mut styles = doc.styles().unwrap();
+        assert!(styles.get_by_id("CorpBody").unwrap().is_some());
+        assert!(styles.get_by_id("CorpNote").unwrap().is_some());
+        let paragraphs = doc.paragraphs().unwrap();
+        let formats: Vec<_> = paragraphs
+            .iter()
+            .filter_map(|p| p.list_info().unwrap())
+            .map(|info| info.format)
+            .collect();
+        assert_eq!(formats, vec![ListFormat::Decimal, ListFormat::Bullet]);
+    }
+}
@@ -2,6 +2,15 @@
 use crate::ooxml::error::{OoxmlError, Result};
 use std::fmt::Write as FmtWrite;
 
+/// Escape XML special characters.
+fn escape_xml(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&apos;")
+}
+
 // Import shared format types
 pub use super::super::format::TableBorderStyle;
 // Import paragraph types
@@ -39,9 +48,40 @@ pub struct TableBorders {
     pub inside_v: Option<TableBorder>,
 }
 
+impl TableBorders {
+    /// Check if any side is set.
+    pub(crate) fn is_set(&self) -> bool {
+        self.top.is_some()
+            || self.left.is_some()
+            || self.bottom.is_some()
+            || self.right.is_some()
+            || self.inside_h.is_some()
+            || self.inside_v.is_some()
+    }
+}
+
+/// Write the sides that are set as `<w:{element}>`.
+pub(crate) fn write_borders(xml: &mut String, element: &str, borders: &TableBorders) -> Result<()> {
+    write!(xml, "<w:{}>", element).map_err(|e| OoxmlError::Xml(e.to_string()))?;
+    for (name, border) in [
+        ("top", &borders.top),
+        ("left", &borders.left),
+        ("bottom", &borders.bottom),
+        ("right", &borders.right),
+        ("insideH", &borders.inside_h),
+        ("insideV", &borders.inside_v),
+    ] {
+        if let Some(border) = border {
+            MutableTable::write_border(xml, name, border)?;
+        }
+    }
+    write!(xml, "</w:{}>", element).map_err(|e| OoxmlError::Xml(e.to_string()))
+}
+
 /// Table properties.
 #[derive(Debug, Default)]
 pub(crate) struct TableProperties {
+    pub(crate) style: Option<String>,
     pub(crate) borders: TableBorders,
     pub(crate) width_pct: Option<u32>,
 }
@@ -89,6 +129,13 @@ impl MutableTable {
         self.properties.width_pct = Some(percent * 50);
     }
 
+    /// Set the table style by ID.
+    ///
+    /// Borders are then taken from the style unless set on the table.
+    pub fn set_style(&mut self, style_id: &str) {
+        self.properties.style = Some(style_id.to_string());
+    }
+
     /// Set all table borders at once.
     pub fn set_borders(&mut self, border: TableBorder) {
         self.properties.borders.top = Some(border.clone());
@@ -126,18 +173,8 @@ impl MutableTable {
         .map_err(|e| OoxmlError::Xml(e.to_string()))
     }
 
-    pub(crate) fn to_xml(&self, xml: &mut String) -> Result<()> {
-        xml.push_str("<w:tbl>");
-
-        // Write table properties
-        xml.push_str("<w:tblPr>");
-
-        // Table width
-        let width = self.properties.width_pct.unwrap_or(5000);
-        write!(xml, "<w:tblW w:w=\"{}\" w:type=\"pct\"/>", width)
-            .map_err(|e| OoxmlError::Xml(e.to_string()))?;
-
-        // Table borders
+    /// Write the table borders, with single lines for sides not set.
+    fn write_default_borders(&self, xml: &mut String) -> Result<()> {
         xml.push_str("<w:tblBorders>");
         if let Some(ref border) = self.properties.borders.top {
             Self::write_border(xml, "top", border)?;
@@ -176,6 +213,35 @@ impl MutableTable {
             );
         }
         xml.push_str("</w:tblBorders>");
+        Ok(())
+    }
+
+    pub(crate) fn to_xml(&self, xml: &mut String) -> Result<()> {
+        xml.push_str("<w:tbl>");
+
+        // Write table properties
+        xml.push_str("<w:tblPr>");
+
+        // Table style
+        if let Some(ref style) = self.properties.style {
+            write!(xml, "<w:tblStyle w:val=\"{}\"/>", escape_xml(style))
+                .map_err(|e| OoxmlError::Xml(e.to_string()))?;
+        }
+
+        // Table width
+        let width = self.properties.width_pct.unwrap_or(5000);
+        write!(xml, "<w:tblW w:w=\"{}\" w:type=\"pct\"/>", width)
+            .map_err(|e| OoxmlError::Xml(e.to_string()))?;
+
+        // Table borders, defaulting to single lines unless a style provides them
+        let borders = &self.properties.borders;
+        if self.properties.style.is_some() {
+            if borders.is_set() {
+                write_borders(xml, "tblBorders", borders)?;
+            }
+        } else {
+            self.write_default_borders(xml)?;
+        }
         xml.push_str("</w:tblPr>");
 
         // Write grid
@@ -52,7 +52,7 @@ pub use opc::{OpcPackage, PackURI};
 pub use shared::{Length, RGBColor};
 
 // Re-export common utilities
-pub use common::DocumentProperties;
+pub use common::{DocumentProperties, TemplateData, TemplateValue};
 
 // Re-export error types
 pub use error::{OoxmlError, Result};
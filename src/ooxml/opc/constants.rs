@@ -56,6 +56,10 @@ pub mod content_type {
     // WordprocessingML content types
     pub const WML_COMMENTS: &str =
         "application/vnd.openxmlformats-officedocument.wordprocessingml.comments+xml";
+    pub const WML_COMMENTS_EXTENDED: &str =
+        "application/vnd.openxmlformats-officedocument.wordprocessingml.commentsExtended+xml";
+    pub const WML_COMMENTS_IDS: &str =
+        "application/vnd.openxmlformats-officedocument.wordprocessingml.commentsIds+xml";
     pub const WML_DOCUMENT: &str =
         "application/vnd.openxmlformats-officedocument.wordprocessingml.document";
     pub const WML_DOCUMENT_GLOSSARY: &str =
@@ -91,6 +95,18 @@ pub mod content_type {
         "application/vnd.openxmlformats-officedocument.spreadsheetml.styles+xml";
     pub const SML_SHARED_STRINGS: &str =
         "application/vnd.openxmlformats-officedocument.spreadsheetml.sharedStrings+xml";
+    pub const SML_TABLE: &str =
+        "application/vnd.openxmlformats-officedocument.spreadsheetml.table+xml";
+    pub const SML_PIVOT_TABLE: &str =
+        "application/vnd.openxmlformats-officedocument.spreadsheetml.pivotTable+xml";
+    pub const SML_PIVOT_CACHE_DEFINITION: &str =
+        "application/vnd.openxmlformats-officedocument.spreadsheetml.pivotCacheDefinition+xml";
+    pub const SML_PIVOT_CACHE_RECORDS: &str =
+        "application/vnd.openxmlformats-officedocument.spreadsheetml.pivotCacheRecords+xml";
+    pub const SML_COMMENTS: &str =
+        "application/vnd.openxmlformats-officedocument.spreadsheetml.comments+xml";
+    pub const SML_THREADED_COMMENTS: &str = "application/vnd.ms-excel.threadedcomments+xml";
+    pub const SML_PERSON: &str = "application/vnd.ms-excel.person+xml";
 
     // PresentationML content types
     pub const PML_PRESENTATION_MAIN: &str =
@@ -170,6 +186,14 @@ pub mod relationship_type {
     // Document parts
     pub const COMMENTS: &str =
         "http://schemas.openxmlformats.org/officeDocument/2006/relationships/comments";
+    pub const COMMENTS_EXTENDED: &str =
+        "http://schemas.microsoft.com/office/2011/relationships/commentsExtended";
+    pub const COMMENTS_IDS: &str =
+        "http://schemas.microsoft.com/office/2016/09/relationships/commentsIds";
+    pub const CUSTOM_XML: &str =
+        "http://schemas.openxmlformats.org/officeDocument/2006/relationships/customXml";
+    pub const CUSTOM_XML_PROPS: &str =
+        "http://schemas.openxmlformats.org/officeDocument/2006/relationships/customXmlProps";
     pub const ENDNOTES: &str =
         "http://schemas.openxmlformats.org/officeDocument/2006/relationships/endnotes";
     pub const FONT_TABLE: &str =
@@ -219,6 +243,25 @@ pub mod relationship_type {
     pub const PACKAGE: &str =
         "http://schemas.openxmlformats.org/officeDocument/2006/relationships/package";
 
+    // SpreadsheetML relationships
+    pub const WORKSHEET: &str =
+        "http://schemas.openxmlformats.org/officeDocument/2006/relationships/worksheet";
+    pub const SHARED_STRINGS: &str =
+        "http://schemas.openxmlformats.org/officeDocument/2006/relationships/sharedStrings";
+    pub const CALC_CHAIN: &str =
+        "http://schemas.openxmlformats.org/officeDocument/2006/relationships/calcChain";
+    pub const TABLE: &str =
+        "http://schemas.openxmlformats.org/officeDocument/2006/relationships/table";
+    pub const PIVOT_TABLE: &str =
+        "http://schemas.openxmlformats.org/officeDocument/2006/relationships/pivotTable";
+    pub const PIVOT_CACHE_DEFINITION: &str =
+        "http://schemas.openxmlformats.org/officeDocument/2006/relationships/pivotCacheDefinition";
+    pub const PIVOT_CACHE_RECORDS: &str =
+        "http://schemas.openxmlformats.org/officeDocument/2006/relationships/pivotCacheRecords";
+    pub const THREADED_COMMENT: &str =
+        "http://schemas.microsoft.com/office/2017/10/relationships/threadedComment";
+    pub const PERSON: &str = "http://schemas.microsoft.com/office/2017/10/relationships/person";
+
     // PresentationML relationships
     pub const SLIDE: &str =
         "http://schemas.openxmlformats.org/officeDocument/2006/relationships/slide";
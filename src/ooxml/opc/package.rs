@@ -11,7 +11,7 @@ use crate::ooxml::opc::rel::Relationships;
 /// Convention package in memory. It manages parts, relationships, and provides
 /// high-level operations for working with office documents.
 use std::collections::HashMap;
-use std::io::{Read, Seek};
+use std::io::{Read, Seek, Write};
 use std::path::Path;
 
 /// Main API class for working with OPC packages.
@@ -291,6 +291,14 @@ impl OpcPackage {
         }
     }
 
+    /// Remove a part from the package.
+    ///
+    /// Relationships pointing at the part are left untouched; callers should
+    /// drop them separately.
+    pub fn remove_part(&mut self, partname: &PackURI) -> Option<Box<dyn Part>> {
+        self.parts.remove(partname)
+    }
+
     /// Check if a part exists in the package.
     pub fn contains_part(&self, partname: &PackURI) -> bool {
         self.parts.contains_key(partname)
@@ -316,6 +324,14 @@ impl OpcPackage {
     pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<()> {
         crate::ooxml::opc::pkgwriter::PackageWriter::write(path, self)
     }
+
+    /// Save the package to a writer.
+    ///
+    /// # Arguments
+    /// * `writer` - A writer that implements Write + Seek
+    pub fn save_to_writer<W: Write + Seek>(&self, writer: W) -> Result<()> {
+        crate::ooxml::opc::pkgwriter::PackageWriter::write_to_writer(writer, self)
+    }
 }
 
 impl Default for OpcPackage {
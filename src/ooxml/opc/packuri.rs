@@ -34,11 +34,15 @@ impl PackURI {
     ///
     /// This translates a relative reference (like "../styles.xml") onto a base URI
     /// (like "/word") to produce an absolute PackURI (like "/styles.xml").
+    /// References starting with `/` are already absolute and ignore the base.
     ///
     /// # Arguments
     /// * `base_uri` - The base URI to resolve from
     /// * `relative_ref` - The relative reference to resolve
     pub fn from_rel_ref(base_uri: &str, relative_ref: &str) -> Result<Self, String> {
+        if relative_ref.starts_with('/') {
+            return Self::new(Self::normalize_path(relative_ref));
+        }
         // Join the paths using POSIX-style path manipulation
         let joined = Self::join_paths(base_uri, relative_ref);
         let normalized = Self::normalize_path(&joined);
@@ -275,6 +279,14 @@ mod tests {
         assert_eq!(root.base_uri(), "/");
     }
 
+    #[test]
+    fn test_from_rel_ref() {
+        let uri = PackURI::from_rel_ref("/word", "../customXml/item1.xml").unwrap();
+        assert_eq!(uri.as_str(), "/customXml/item1.xml");
+        let uri = PackURI::from_rel_ref("/word", "/word/media/image1.png").unwrap();
+        assert_eq!(uri.as_str(), "/word/media/image1.png");
+    }
+
     #[test]
     fn test_filename() {
         let uri = PackURI::new("/ppt/slides/slide1.xml").unwrap();
@@ -6,7 +6,7 @@
 use crate::ooxml::opc::error::{OpcError, Result};
 use crate::ooxml::opc::packuri::PackURI;
 use std::fs::File;
-use std::io::{BufReader, Read, Seek};
+use std::io::{BufReader, Read, Seek, Write};
 use std::path::Path;
 use zip::ZipArchive;
 
@@ -154,20 +154,30 @@ impl<R: Read + Seek> PhysPkgReader<R> {
 /// Physical package writer for creating OPC packages.
 ///
 /// Handles the low-level writing of parts to a ZIP archive with optimal compression.
-pub struct PhysPkgWriter {
+pub struct PhysPkgWriter<W: Write + Seek = File> {
     /// The underlying ZIP archive writer
-    archive: zip::ZipWriter<File>,
+    archive: zip::ZipWriter<W>,
 }
 
-impl PhysPkgWriter {
+impl PhysPkgWriter<File> {
     /// Create a new package writer for a file path.
     ///
     /// # Arguments
     /// * `path` - Path where the package should be written
     pub fn create<P: AsRef<Path>>(path: P) -> Result<Self> {
         let file = File::create(path)?;
-        let archive = zip::ZipWriter::new(file);
-        Ok(Self { archive })
+        Ok(Self::new(file))
+    }
+}
+
+impl<W: Write + Seek> PhysPkgWriter<W> {
+    /// Create a new package writer for a writer.
+    ///
+    /// # Arguments
+    /// * `writer` - A writer that implements Write + Seek
+    pub fn new(writer: W) -> Self {
+        let archive = zip::ZipWriter::new(writer);
+        Self { archive }
     }
 
     /// Write a part to the package.
@@ -8,6 +8,7 @@ use crate::ooxml::opc::package::OpcPackage;
 use crate::ooxml::opc::packuri::{CONTENT_TYPES_URI, PACKAGE_URI, PackURI};
 use crate::ooxml::opc::phys_pkg::PhysPkgWriter;
 use std::collections::HashMap;
+use std::io::{Seek, Write};
 use std::path::Path;
 
 /// Package writer that serializes an OPC package to a ZIP file.
@@ -37,8 +38,22 @@ impl PackageWriter {
     /// * `path` - Path where the package should be written
     /// * `package` - The OPC package to write
     pub fn write<P: AsRef<Path>>(path: P, package: &OpcPackage) -> Result<()> {
-        let mut phys_writer = PhysPkgWriter::create(path)?;
+        Self::write_package(PhysPkgWriter::create(path)?, package)
+    }
+
+    /// Write an OPC package to a writer.
+    ///
+    /// # Arguments
+    /// * `writer` - A writer that implements Write + Seek
+    /// * `package` - The OPC package to write
+    pub fn write_to_writer<W: Write + Seek>(writer: W, package: &OpcPackage) -> Result<()> {
+        Self::write_package(PhysPkgWriter::new(writer), package)
+    }
 
+    fn write_package<W: Write + Seek>(
+        mut phys_writer: PhysPkgWriter<W>,
+        package: &OpcPackage,
+    ) -> Result<()> {
         // Write [Content_Types].xml
         Self::write_content_types(&mut phys_writer, package)?;
 
@@ -57,7 +72,10 @@ impl PackageWriter {
     /// Write the [Content_Types].xml part.
     ///
     /// This file maps file extensions and part names to content types.
-    fn write_content_types(phys_writer: &mut PhysPkgWriter, package: &OpcPackage) -> Result<()> {
+    fn write_content_types<W: Write + Seek>(
+        phys_writer: &mut PhysPkgWriter<W>,
+        package: &OpcPackage,
+    ) -> Result<()> {
         let cti = ContentTypesItem::from_package(package);
         let blob = cti.to_xml();
 
@@ -69,7 +87,10 @@ impl PackageWriter {
     }
 
     /// Write package-level relationships.
-    fn write_pkg_rels(phys_writer: &mut PhysPkgWriter, package: &OpcPackage) -> Result<()> {
+    fn write_pkg_rels<W: Write + Seek>(
+        phys_writer: &mut PhysPkgWriter<W>,
+        package: &OpcPackage,
+    ) -> Result<()> {
         let package_uri = PackURI::new(PACKAGE_URI)
             .map_err(crate::ooxml::opc::error::OpcError::InvalidPackUri)?;
         let rels_uri = package_uri
@@ -82,7 +103,10 @@ impl PackageWriter {
     }
 
     /// Write all parts and their relationships.
-    fn write_parts(phys_writer: &mut PhysPkgWriter, package: &OpcPackage) -> Result<()> {
+    fn write_parts<W: Write + Seek>(
+        phys_writer: &mut PhysPkgWriter<W>,
+        package: &OpcPackage,
+    ) -> Result<()> {
         for part in package.iter_parts() {
             // Write the part itself
             let blob = part.blob();
@@ -169,6 +193,7 @@ impl ContentTypesItem {
                 | ("gif", "image/gif")
                 | ("emf", "image/x-emf")
                 | ("wmf", "image/x-wmf")
+                | ("vml", ct::OFC_VML_DRAWING)
         )
     }
 
@@ -1,14 +1,16 @@
 /// Package implementation for PowerPoint presentations.
-use crate::ooxml::common::DocumentProperties;
+use crate::ooxml::common::{DocumentProperties, TemplateData};
 use crate::ooxml::error::{OoxmlError, Result};
 use crate::ooxml::opc::OpcPackage;
 use crate::ooxml::opc::constants::content_type as ct;
 use crate::ooxml::opc::packuri::PackURI;
 use crate::ooxml::opc::part::Part;
-use crate::ooxml::pptx::parts::PresentationPart;
+use crate::ooxml::pptx::parts::{PresentationPart, SlideMasterPart};
 use crate::ooxml::pptx::presentation::Presentation;
-use crate::ooxml::pptx::writer::MutablePresentation;
-use std::io::{Read, Seek};
+use crate::ooxml::pptx::template;
+use crate::ooxml::pptx::writer::loader::{ExistingSlide, notes_text, replace_notes_text};
+use crate::ooxml::pptx::writer::{MutablePresentation, MutableSlide};
+use std::io::{Read, Seek, Write};
 use std::path::Path;
 
 /// A PowerPoint (.pptx) package.
@@ -38,6 +40,8 @@ pub struct Package {
     mutable_pres: Option<MutablePresentation>,
     /// Document properties (metadata)
     properties: DocumentProperties,
+    /// Whether the document properties were changed since opening
+    properties_modified: bool,
 }
 
 impl Package {
@@ -217,6 +221,7 @@ impl Package {
             opc,
             mutable_pres,
             properties,
+            properties_modified: false,
         })
     }
 
@@ -255,10 +260,12 @@ impl Package {
             });
         }
 
+        let properties = load_properties(&opc);
         Ok(Self {
             opc,
             mutable_pres: None,
-            properties: DocumentProperties::new(),
+            properties,
+            properties_modified: false,
         })
     }
 
@@ -301,10 +308,12 @@ impl Package {
             });
         }
 
+        let properties = load_properties(&opc);
         Ok(Self {
             opc,
             mutable_pres: None,
-            properties: DocumentProperties::new(),
+            properties,
+            properties_modified: false,
         })
     }
 
@@ -346,10 +355,12 @@ impl Package {
             });
         }
 
+        let properties = load_properties(&opc);
         Ok(Self {
             opc,
             mutable_pres: None,
-            properties: DocumentProperties::new(),
+            properties,
+            properties_modified: false,
         })
     }
 
@@ -404,7 +415,9 @@ impl Package {
     /// Get a mutable presentation for writing and modification.
     ///
     /// This returns a `MutablePresentation` that allows you to add and modify
-    /// slides, shapes, and other presentation elements.
+    /// slides, shapes, and other presentation elements. For an opened
+    /// presentation the existing slides are loaded first, so they can be
+    /// edited, removed, duplicated and reordered.
     ///
     /// # Examples
     ///
@@ -420,17 +433,114 @@ impl Package {
     /// slide.add_text_box("Hello, World!", 914400, 914400, 2743200, 914400);
     ///
     /// pkg.save("output.pptx")?;
+    ///
+    /// // Edit an existing deck
+    /// let mut pkg = Package::open("output.pptx")?;
+    /// let pres = pkg.presentation_mut()?;
+    /// pres.duplicate_slide(0);
+    /// pres.slide_mut(1).unwrap().set_title("Copy");
+    /// pkg.save("output.pptx")?;
     /// # Ok::<(), Box<dyn std::error::Error>>(())
     /// ```
     pub fn presentation_mut(&mut self) -> Result<&mut MutablePresentation> {
-        // If we don't have a mutable presentation, create one
+        // If we don't have a mutable presentation, mirror the existing one
         if self.mutable_pres.is_none() {
-            self.mutable_pres = Some(MutablePresentation::new());
+            self.mutable_pres = Some(self.load_mutable_presentation()?);
         }
 
         Ok(self.mutable_pres.as_mut().unwrap())
     }
 
+    /// Fill `{{name}}` placeholders in slides and notes with data.
+    ///
+    /// Placeholders split over several runs are joined first and keep the
+    /// formatting of their first character; `{{#name}}...{{/name}}`
+    /// sections repeat paragraphs or table rows over lists. See
+    /// [`merge`](crate::ooxml::common::merge) for the details.
+    ///
+    /// This works on the stored slides. Pending edits made through
+    /// [`presentation_mut`](Self::presentation_mut) are written to the
+    /// slides first, and the mutable presentation starts over afterwards.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use litchi::ooxml::common::TemplateData;
+    /// use litchi::ooxml::pptx::Package;
+    ///
+    /// let mut pkg = Package::open("pitch-template.pptx")?;
+    /// let mut data = TemplateData::new();
+    /// data.set("customer", "ACME Corp.");
+    /// pkg.render(&data)?;
+    /// pkg.save("pitch-acme.pptx")?;
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn render(&mut self, data: &TemplateData) -> Result<()> {
+        if let Some(mut mutable_pres) = self.mutable_pres.take()
+            && mutable_pres.is_modified()
+        {
+            self.update_presentation_parts(&mut mutable_pres)?;
+        }
+
+        let uris: Vec<PackURI> = self
+            .opc
+            .iter_parts()
+            .filter(|part| matches!(part.content_type(), ct::PML_SLIDE | ct::PML_NOTES_SLIDE))
+            .map(|part| part.partname().clone())
+            .collect();
+
+        for uri in uris {
+            let part = self.opc.get_part_mut(&uri)?;
+            let xml = std::str::from_utf8(part.blob())
+                .map_err(|e| OoxmlError::InvalidFormat(format!("Invalid UTF-8: {}", e)))?;
+            if let Some(xml) = template::render_part(xml, data)? {
+                part.set_blob(xml.into_bytes());
+            }
+        }
+        Ok(())
+    }
+
+    /// Load the slides of the package into a mutable presentation.
+    fn load_mutable_presentation(&self) -> Result<MutablePresentation> {
+        use crate::ooxml::opc::constants::relationship_type as rt;
+
+        let main_part = self
+            .opc
+            .main_document_part()
+            .map_err(|e| OoxmlError::PartNotFound(format!("main presentation part: {}", e)))?;
+        let xml = std::str::from_utf8(main_part.blob())
+            .map_err(|e| OoxmlError::Xml(format!("Invalid UTF-8 in presentation: {}", e)))?;
+
+        MutablePresentation::from_existing(xml, |r_id| {
+            let Some(rel) = main_part.rels().get(r_id).filter(|rel| !rel.is_external()) else {
+                return Ok(None);
+            };
+            let partname = rel.target_partname()?;
+            let Ok(slide_part) = self.opc.get_part(&partname) else {
+                return Ok(None);
+            };
+            let xml = std::str::from_utf8(slide_part.blob())
+                .map_err(|e| OoxmlError::Xml(format!("Invalid UTF-8 in slide: {}", e)))?;
+
+            let notes_part = slide_part
+                .rels()
+                .iter()
+                .find(|rel| rel.reltype() == rt::NOTES_SLIDE && !rel.is_external())
+                .and_then(|rel| rel.target_partname().ok())
+                .and_then(|uri| self.opc.get_part(&uri).ok());
+            let notes = match notes_part {
+                Some(part) => notes_text(&String::from_utf8_lossy(part.blob()))?,
+                None => None,
+            };
+
+            Ok(Some(ExistingSlide {
+                partname: partname.to_string(),
+                xml: xml.to_string(),
+                notes,
+            }))
+        })
+    }
+
     /// Get a reference to the presentation properties.
     ///
     /// # Examples
@@ -460,13 +570,15 @@ impl Package {
     /// # Ok::<(), Box<dyn std::error::Error>>(())
     /// ```
     pub fn properties_mut(&mut self) -> &mut DocumentProperties {
+        self.properties_modified = true;
         &mut self.properties
     }
 
     /// Save the package to a file.
     ///
     /// Writes the complete PowerPoint presentation including all parts, relationships,
-    /// and content types to a .pptx file.
+    /// and content types to a .pptx file. Only edited slides are regenerated;
+    /// layouts, masters, themes and any other parts are written back unchanged.
     ///
     /// # Arguments
     /// * `path` - Path where the .pptx file should be written
@@ -482,6 +594,40 @@ impl Package {
     /// # Ok::<(), Box<dyn std::error::Error>>(())
     /// ```
     pub fn save<P: AsRef<Path>>(&mut self, path: P) -> Result<()> {
+        self.write_pending_changes()?;
+        self.opc.save(path).map_err(|e| {
+            OoxmlError::IoError(std::io::Error::other(format!(
+                "Failed to save package: {}",
+                e
+            )))
+        })
+    }
+
+    /// Save the package to a writer.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use litchi::ooxml::pptx::Package;
+    /// use std::io::Cursor;
+    ///
+    /// let mut pkg = Package::new()?;
+    /// let mut buffer = Cursor::new(Vec::new());
+    /// pkg.save_to_writer(&mut buffer)?;
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn save_to_writer<W: Write + Seek>(&mut self, writer: W) -> Result<()> {
+        self.write_pending_changes()?;
+        self.opc.save_to_writer(writer).map_err(|e| {
+            OoxmlError::IoError(std::io::Error::other(format!(
+                "Failed to save package: {}",
+                e
+            )))
+        })
+    }
+
+    /// Write the mutable presentation and core properties into the parts.
+    fn write_pending_changes(&mut self) -> Result<()> {
         // If we have a mutable presentation, update the presentation parts
         let should_update = self
             .mutable_pres
@@ -491,173 +637,335 @@ impl Package {
 
         if should_update {
             // Take mutable_pres temporarily to avoid borrow issues
-            if let Some(mutable_pres) = self.mutable_pres.take() {
-                self.update_presentation_parts(&mutable_pres)?;
+            if let Some(mut mutable_pres) = self.mutable_pres.take() {
+                let result = self.update_presentation_parts(&mut mutable_pres);
                 self.mutable_pres = Some(mutable_pres);
+                result?;
             }
         }
 
         // Update core properties
-        self.update_core_properties()?;
-
-        self.opc.save(path).map_err(|e| {
-            OoxmlError::IoError(std::io::Error::other(format!(
-                "Failed to save package: {}",
-                e
-            )))
-        })
+        if self.properties_modified {
+            self.update_core_properties()?;
+        }
+        Ok(())
     }
 
     /// Update presentation parts with modified data.
-    fn update_presentation_parts(&mut self, pres: &MutablePresentation) -> Result<()> {
-        use crate::ooxml::opc::constants::content_type as ct;
+    ///
+    /// Existing slide parts are updated in place so they keep their
+    /// relationships; written slides are reloaded so that saving again does
+    /// not duplicate their new images.
+    fn update_presentation_parts(&mut self, pres: &mut MutablePresentation) -> Result<()> {
         use crate::ooxml::opc::constants::relationship_type as rt;
-        use crate::ooxml::opc::part::{BlobPart, Part};
+        use crate::ooxml::opc::part::BlobPart;
         use crate::ooxml::pptx::writer::relmap::RelationshipMapper;
 
+        let pres_uri = self
+            .opc
+            .main_document_part()
+            .map_err(|e| OoxmlError::PartNotFound(format!("main presentation part: {}", e)))?
+            .partname()
+            .clone();
+        let default_layout = self.default_slide_layout();
+
         // Initialize relationship mapper
         let mut rel_mapper = RelationshipMapper::new();
 
-        // Collect all images from all slides
-        let all_images = pres.collect_all_images();
-
-        // Create image parts first and add to package
-        for (img_index, (_slide_index, image_data, image_format)) in all_images.iter().enumerate() {
-            let img_num = img_index + 1;
-            let ext = image_format.extension();
-
-            // Create image part URI
-            let image_partname = format!("/ppt/media/image{}.{}", img_num, ext);
-            let image_uri = PackURI::new(&image_partname)
-                .map_err(|e| OoxmlError::InvalidUri(format!("image URI: {}", e)))?;
-
-            // Create image part
-            let image_part = BlobPart::new(
-                image_uri,
-                image_format.mime_type().to_string(),
-                image_data.to_vec(),
-            );
-
-            // Add image part to package
-            self.opc.add_part(Box::new(image_part));
-        }
-
-        // Create presentation part and add relationships
-        let pres_uri = PackURI::new("/ppt/presentation.xml")
-            .map_err(|e| OoxmlError::InvalidUri(format!("presentation URI: {}", e)))?;
-
-        // Create a temporary presentation part to manage relationships
-        let mut temp_pres_part = BlobPart::new(
-            pres_uri.clone(),
-            ct::PML_PRESENTATION_MAIN.to_string(),
-            Vec::new(),
-        );
-
-        // Add relationship to slideMaster (this should be rId1)
-        temp_pres_part.relate_to("slideMasters/slideMaster1.xml", rt::SLIDE_MASTER);
-
-        // Add other required relationships (in the order they were created in Package::new())
-        // These relationships should be added even if not modified, as they're required for a valid PPTX
-        temp_pres_part.relate_to("tableStyles.xml", rt::TABLE_STYLES);
-        temp_pres_part.relate_to("viewProps.xml", rt::VIEW_PROPS);
-        temp_pres_part.relate_to("presProps.xml", rt::PRES_PROPS);
-
         // Track slide relationship IDs for presentation.xml generation
         let mut slide_rel_ids: Vec<String> = Vec::new();
 
-        // Process each slide: create relationships first, then generate XML
-        for (slide_index, slide) in pres.slides.iter().enumerate() {
-            if slide.is_modified() {
-                let slide_num = slide_index + 1;
-                let slide_uri = PackURI::new(format!("/ppt/slides/slide{}.xml", slide_num))
-                    .map_err(|e| {
-                        OoxmlError::InvalidUri(format!("slide{} URI: {}", slide_num, e))
-                    })?;
-
-                // Create a temporary slide part to manage relationships
-                let mut temp_slide_part =
-                    BlobPart::new(slide_uri.clone(), ct::PML_SLIDE.to_string(), Vec::new());
-
-                // Add relationship from slide to slide layout (always first relationship)
-                temp_slide_part.relate_to("../slideLayouts/slideLayout1.xml", rt::SLIDE_LAYOUT);
-
-                // Collect images for this slide and create relationships
-                let slide_images = slide.collect_images();
-                for (img_index_in_slide, (_, image_format)) in slide_images.iter().enumerate() {
-                    // Find the global image index for this slide's image
-                    let mut global_img_idx = 0;
-                    for (global_idx, (s_idx, _, _)) in all_images.iter().enumerate() {
-                        if *s_idx == slide_index {
-                            if global_img_idx == img_index_in_slide {
-                                let img_num = global_idx + 1;
-                                let ext = image_format.extension();
-                                let image_rel_target = format!("../media/image{}.{}", img_num, ext);
-                                let rid = temp_slide_part.relate_to(&image_rel_target, rt::IMAGE);
-                                rel_mapper.add_image(slide_index, img_index_in_slide, rid);
-                                break;
-                            }
-                            global_img_idx += 1;
+        for (slide_index, slide) in pres.slides.iter_mut().enumerate() {
+            let source = slide.source.as_ref();
+            let notes_changed = slide.notes != source.and_then(|s| s.notes.clone());
+
+            // Reuse the part of a loaded slide
+            let existing = source
+                .and_then(|s| s.relationship_id.as_deref())
+                .and_then(|rid| {
+                    let rel = self.opc.get_part(&pres_uri).ok()?.rels().get(rid)?;
+                    (rel.reltype() == rt::SLIDE).then_some(())?;
+                    Some((rel.target_partname().ok()?, rid.to_string()))
+                });
+
+            let (slide_uri, slide_rid) = match existing {
+                Some(_) if !slide.is_modified() && !notes_changed => {
+                    let (_, rid) = existing.unwrap();
+                    slide_rel_ids.push(rid);
+                    continue;
+                },
+                Some(existing) => existing,
+                None => {
+                    let slide_uri = self.opc.next_partname("/ppt/slides/slide%d.xml")?;
+                    let mut slide_part =
+                        BlobPart::new(slide_uri.clone(), ct::PML_SLIDE.to_string(), Vec::new());
+
+                    let original = source
+                        .and_then(|s| PackURI::new(s.partname.as_str()).ok())
+                        .and_then(|uri| self.opc.get_part(&uri).ok());
+                    if let Some(original) = original {
+                        // A duplicate shares the original's layout, images and
+                        // other related parts, but gets its own charts
+                        let rels: Vec<_> = original
+                            .rels()
+                            .iter()
+                            .filter(|rel| rel.reltype() != rt::NOTES_SLIDE)
+                            .map(|rel| {
+                                (
+                                    rel.reltype().to_string(),
+                                    rel.target_ref().to_string(),
+                                    rel.target_partname().ok().filter(|_| !rel.is_external()),
+                                    rel.r_id().to_string(),
+                                    rel.is_external(),
+                                )
+                            })
+                            .collect();
+                        for (reltype, target_ref, target, r_id, external) in rels {
+                            let target_ref = match target {
+                                Some(target) if reltype == rt::CHART => {
+                                    self.copy_part(&target)?.relative_ref(slide_uri.base_uri())
+                                },
+                                Some(target) => target.relative_ref(slide_uri.base_uri()),
+                                None => target_ref,
+                            };
+                            slide_part
+                                .rels_mut()
+                                .add_relationship(reltype, target_ref, r_id, external);
                         }
+                    } else if let Some(layout) = &default_layout {
+                        // Add relationship from slide to slide layout (always first relationship)
+                        slide_part.relate_to(
+                            &layout.relative_ref(slide_uri.base_uri()),
+                            rt::SLIDE_LAYOUT,
+                        );
                     }
-                }
+                    self.opc.add_part(Box::new(slide_part));
+
+                    let rid = self
+                        .opc
+                        .get_part_mut(&pres_uri)?
+                        .relate_to(&slide_uri.relative_ref(pres_uri.base_uri()), rt::SLIDE);
+                    (slide_uri, rid)
+                },
+            };
+
+            // Create image parts for new pictures and relate them to the slide
+            for (image_index, (image_data, image_format)) in
+                slide.collect_images().into_iter().enumerate()
+            {
+                let image_uri = self
+                    .opc
+                    .next_partname(&format!("/ppt/media/image%d.{}", image_format.extension()))?;
+                self.opc.add_part(Box::new(BlobPart::new(
+                    image_uri.clone(),
+                    image_format.mime_type().to_string(),
+                    image_data.to_vec(),
+                )));
+                let rid = self
+                    .opc
+                    .get_part_mut(&slide_uri)?
+                    .relate_to(&image_uri.relative_ref(slide_uri.base_uri()), rt::IMAGE);
+                rel_mapper.add_image(slide_index, image_index, rid);
+            }
 
-                // Add relationship from slide to notes slide if notes exist
-                if slide.has_notes() {
-                    let notes_rel_target = format!("../notesSlides/notesSlide{}.xml", slide_num);
-                    let rid = temp_slide_part.relate_to(&notes_rel_target, "http://schemas.openxmlformats.org/officeDocument/2006/relationships/notesSlide");
-                    rel_mapper.add_notes(slide_index, rid);
-                }
+            if notes_changed {
+                self.update_notes(slide, slide_index, &slide_uri, &mut rel_mapper)?;
+            }
 
-                // Now generate slide XML with actual relationship IDs
-                let slide_xml = slide.to_xml_with_rels(Some(slide_index), Some(&rel_mapper))?;
-
-                // Update the temp part with the actual XML content
-                temp_slide_part.set_blob(slide_xml.into_bytes());
-
-                // Add the slide part to the package
-                self.opc.add_part(Box::new(temp_slide_part));
-
-                // Create notes slide if notes exist
-                if let Some(notes_xml_result) = slide.generate_notes_xml() {
-                    let notes_xml = notes_xml_result?;
-                    let notes_uri =
-                        PackURI::new(format!("/ppt/notesSlides/notesSlide{}.xml", slide_num))
-                            .map_err(|e| {
-                                OoxmlError::InvalidUri(format!(
-                                    "notesSlide{} URI: {}",
-                                    slide_num, e
-                                ))
-                            })?;
-
-                    let mut notes_part = BlobPart::new(
-                        notes_uri,
-                        "application/vnd.openxmlformats-officedocument.presentationml.notesSlide+xml".to_string(),
-                        notes_xml.into_bytes(),
-                    );
-
-                    // Add relationship from notes to slide
-                    notes_part.relate_to(&format!("../slides/slide{}.xml", slide_num), rt::SLIDE);
-
-                    self.opc.add_part(Box::new(notes_part));
-                }
+            // Now generate slide XML with actual relationship IDs
+            let slide_xml = slide.to_xml_with_rels(Some(slide_index), Some(&rel_mapper))?;
+            self.opc
+                .get_part_mut(&slide_uri)?
+                .set_blob(slide_xml.clone().into_bytes());
+
+            *slide = MutableSlide::from_existing(
+                slide.slide_id(),
+                slide_rid.clone(),
+                ExistingSlide {
+                    partname: slide_uri.to_string(),
+                    xml: slide_xml,
+                    notes: slide.notes.clone(),
+                },
+            )?;
+            slide_rel_ids.push(slide_rid);
+        }
 
-                // Add relationship from presentation to this slide and track the ID
-                let rel_target = format!("slides/slide{}.xml", slide_num);
-                let slide_rid = temp_pres_part.relate_to(&rel_target, rt::SLIDE);
-                slide_rel_ids.push(slide_rid);
+        // Remove slides that were deleted, along with their notes
+        let stale: Vec<(String, Option<PackURI>)> = self
+            .opc
+            .get_part(&pres_uri)?
+            .rels()
+            .iter()
+            .filter(|rel| {
+                rel.reltype() == rt::SLIDE && !slide_rel_ids.iter().any(|id| id == rel.r_id())
+            })
+            .map(|rel| (rel.r_id().to_string(), rel.target_partname().ok()))
+            .collect();
+        for (r_id, partname) in stale {
+            self.opc.get_part_mut(&pres_uri)?.rels_mut().remove(&r_id);
+            let Some(slide_part) = partname.and_then(|uri| self.opc.remove_part(&uri)) else {
+                continue;
+            };
+            for rel in slide_part.rels().iter() {
+                if rel.reltype() == rt::NOTES_SLIDE
+                    && let Ok(notes_uri) = rel.target_partname()
+                {
+                    self.opc.remove_part(&notes_uri);
+                }
             }
         }
 
-        // Now generate presentation XML with actual relationship IDs
-        let pres_xml = pres.generate_presentation_xml_with_rels(Some(&slide_rel_ids))?;
-        temp_pres_part.set_blob(pres_xml.into_bytes());
+        // Now update presentation XML with actual relationship IDs
+        let pres_part = self.opc.get_part_mut(&pres_uri)?;
+        let current = String::from_utf8_lossy(pres_part.blob()).into_owned();
+        let pres_xml = if current.trim().is_empty() {
+            pres.generate_presentation_xml_with_rels(Some(&slide_rel_ids))?
+        } else {
+            pres.splice_presentation_xml(&current, &slide_rel_ids)?
+        };
+        pres_part.set_blob(pres_xml.into_bytes());
+        pres.modified = false;
+
+        Ok(())
+    }
+
+    /// Copy a part to a new part with the next free number, along with the
+    /// parts it relates to (such as a chart's embedded workbook, style and
+    /// colors). Images stay shared.
+    fn copy_part(&mut self, uri: &PackURI) -> Result<PackURI> {
+        use crate::ooxml::opc::constants::relationship_type as rt;
+        use crate::ooxml::opc::part::BlobPart;
+
+        let original = self.opc.get_part(uri)?;
+        let content_type = original.content_type().to_string();
+        let blob = original.blob().to_vec();
+        let rels: Vec<_> = original
+            .rels()
+            .iter()
+            .map(|rel| {
+                (
+                    rel.reltype().to_string(),
+                    rel.target_ref().to_string(),
+                    rel.target_partname().ok().filter(|_| !rel.is_external()),
+                    rel.r_id().to_string(),
+                    rel.is_external(),
+                )
+            })
+            .collect();
+
+        // chart3.xml becomes chart%d.xml
+        let name = uri.as_str();
+        let stem_end = name
+            .rfind('.')
+            .filter(|&dot| dot > name.rfind('/').unwrap_or(0));
+        let (stem, extension) = name.split_at(stem_end.unwrap_or(name.len()));
+        let template = format!(
+            "{}%d{}",
+            stem.trim_end_matches(|c: char| c.is_ascii_digit()),
+            extension
+        );
+        let copy_uri = self.opc.next_partname(&template)?;
+        let mut copy = BlobPart::new(copy_uri.clone(), content_type, blob);
+
+        for (reltype, target_ref, target, r_id, external) in rels {
+            let target_ref = match target {
+                Some(target) if reltype == rt::IMAGE => target.relative_ref(copy_uri.base_uri()),
+                Some(target) => self.copy_part(&target)?.relative_ref(copy_uri.base_uri()),
+                None => target_ref,
+            };
+            copy.rels_mut()
+                .add_relationship(reltype, target_ref, r_id, external);
+        }
+        self.opc.add_part(Box::new(copy));
+        Ok(copy_uri)
+    }
+
+    /// Write, replace or remove the notes slide of a slide.
+    fn update_notes(
+        &mut self,
+        slide: &MutableSlide,
+        slide_index: usize,
+        slide_uri: &PackURI,
+        rel_mapper: &mut crate::ooxml::pptx::writer::relmap::RelationshipMapper,
+    ) -> Result<()> {
+        use crate::ooxml::opc::constants::relationship_type as rt;
+        use crate::ooxml::opc::part::BlobPart;
 
-        // Add the presentation part to the package
-        self.opc.add_part(Box::new(temp_pres_part));
+        let existing = self
+            .opc
+            .get_part(slide_uri)?
+            .rels()
+            .iter()
+            .find(|rel| rel.reltype() == rt::NOTES_SLIDE && !rel.is_external())
+            .and_then(|rel| Some((rel.r_id().to_string(), rel.target_partname().ok()?)));
+
+        match (slide.notes(), existing) {
+            (Some(text), Some((rid, notes_uri))) => {
+                let notes_part = self.opc.get_part_mut(&notes_uri)?;
+                let current = String::from_utf8_lossy(notes_part.blob()).into_owned();
+                let notes_xml = match replace_notes_text(&current, text)? {
+                    Some(xml) => xml,
+                    None => match slide.generate_notes_xml() {
+                        Some(xml) => xml?,
+                        None => return Ok(()),
+                    },
+                };
+                notes_part.set_blob(notes_xml.into_bytes());
+                rel_mapper.add_notes(slide_index, rid);
+            },
+            (Some(_), None) => {
+                let Some(notes_xml) = slide.generate_notes_xml() else {
+                    return Ok(());
+                };
+                let notes_uri = self
+                    .opc
+                    .next_partname("/ppt/notesSlides/notesSlide%d.xml")?;
+                let mut notes_part = BlobPart::new(
+                    notes_uri.clone(),
+                    ct::PML_NOTES_SLIDE.to_string(),
+                    notes_xml?.into_bytes(),
+                );
+
+                // Add relationship from notes to slide
+                notes_part.relate_to(&slide_uri.relative_ref(notes_uri.base_uri()), rt::SLIDE);
+                self.opc.add_part(Box::new(notes_part));
+
+                let rid = self.opc.get_part_mut(slide_uri)?.relate_to(
+                    &notes_uri.relative_ref(slide_uri.base_uri()),
+                    rt::NOTES_SLIDE,
+                );
+                rel_mapper.add_notes(slide_index, rid);
+            },
+            (None, Some((rid, notes_uri))) => {
+                self.opc.get_part_mut(slide_uri)?.rels_mut().remove(&rid);
+                self.opc.remove_part(&notes_uri);
+            },
+            (None, None) => {},
+        }
 
         Ok(())
     }
 
+    /// Find the layout new slides use: the first layout of the first slide master.
+    fn default_slide_layout(&self) -> Option<PackURI> {
+        let pres_part = self.opc.main_document_part().ok()?;
+        let master_rid = PresentationPart::from_part(pres_part)
+            .ok()?
+            .slide_master_rids()
+            .ok()?
+            .into_iter()
+            .next()?;
+        let master_uri = pres_part.rels().get(&master_rid)?.target_partname().ok()?;
+        let master_part = self.opc.get_part(&master_uri).ok()?;
+        let layout_rid = SlideMasterPart::from_part(master_part)
+            .ok()?
+            .slide_layout_rids()
+            .ok()?
+            .into_iter()
+            .next()?;
+        master_part.rels().get(&layout_rid)?.target_partname().ok()
+    }
+
     /// Update the core.xml properties part.
     fn update_core_properties(&mut self) -> Result<()> {
         use crate::ooxml::opc::part::BlobPart;
@@ -681,9 +989,35 @@ impl Package {
     }
 }
 
+/// Read the document properties from docProps/core.xml.
+fn load_properties(opc: &OpcPackage) -> DocumentProperties {
+    match crate::ooxml::metadata::extract_metadata(opc) {
+        Ok(metadata) => DocumentProperties {
+            title: metadata.title,
+            subject: metadata.subject,
+            creator: metadata.author,
+            keywords: metadata.keywords,
+            description: metadata.description,
+            last_modified_by: metadata.last_modified_by,
+            created: metadata.created,
+            modified: metadata.modified,
+            ..DocumentProperties::new()
+        },
+        Err(_) => DocumentProperties::new(),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use std::io::Cursor;
+
+    /// Save the package to memory.
+    fn save(pkg: &mut Package) -> Vec<u8> {
+        let mut buffer = Cursor::new(Vec::new());
+        pkg.save_to_writer(&mut buffer).unwrap();
+        buffer.into_inner()
+    }
 
     #[test]
     #[ignore] // Requires test file
@@ -691,4 +1025,159 @@ mod tests {
         let result = Package::open("test.pptx");
         assert!(result.is_ok());
     }
+
+    #[test]
+    fn test_render_keeps_pending_edits() {
+        let mut pkg = Package::new().unwrap();
+        pkg.presentation_mut()
+            .unwrap()
+            .add_slide()
+            .unwrap()
+            .set_title("Hi {{name}}");
+        pkg.render(&TemplateData::new().with("name", "Ann"))
+            .unwrap();
+        assert!(pkg.mutable_pres.is_none());
+
+        let slides = pkg.presentation().unwrap().slides().unwrap();
+        assert_eq!(slides.len(), 1);
+        assert!(slides[0].text().unwrap().contains("Hi Ann"));
+    }
+
+    #[test]
+    fn test_duplicate_slide_copies_charts() {
+        use crate::ooxml::opc::constants::relationship_type as rt;
+        use crate::ooxml::opc::part::BlobPart;
+
+        let mut pkg = Package::new().unwrap();
+        pkg.presentation_mut()
+            .unwrap()
+            .add_slide()
+            .unwrap()
+            .set_title("Sales");
+        let saved = save(&mut pkg);
+        let mut pkg = Package::from_reader(Cursor::new(&saved)).unwrap();
+
+        let uri = |name: &str| PackURI::new(name).unwrap();
+        let workbook = BlobPart::new(
+            uri("/ppt/embeddings/Microsoft_Excel_Worksheet1.xlsx"),
+            ct::SML_SHEET.to_string(),
+            b"PK".to_vec(),
+        );
+        let mut chart = BlobPart::new(
+            uri("/ppt/charts/chart1.xml"),
+            ct::DML_CHART.to_string(),
+            b"<c:chartSpace/>".to_vec(),
+        );
+        chart.relate_to("../embeddings/Microsoft_Excel_Worksheet1.xlsx", rt::PACKAGE);
+        chart.relate_to("../media/image1.png", rt::IMAGE);
+        pkg.opc.add_part(Box::new(BlobPart::new(
+            uri("/ppt/media/image1.png"),
+            "image/png".to_string(),
+            b"\x89PNG".to_vec(),
+        )));
+        pkg.opc.add_part(Box::new(workbook));
+        pkg.opc.add_part(Box::new(chart));
+        pkg.opc
+            .get_part_mut(&uri("/ppt/slides/slide1.xml"))
+            .unwrap()
+            .relate_to("../charts/chart1.xml", rt::CHART);
+
+        pkg.presentation_mut().unwrap().duplicate_slide(0).unwrap();
+        let saved = save(&mut pkg);
+        let pkg = Package::from_reader(Cursor::new(&saved)).unwrap();
+
+        let opc = pkg.opc_package();
+        let target = |part: &str, reltype: &str| {
+            opc.get_part(&uri(part))
+                .unwrap()
+                .rels()
+                .iter()
+                .find(|rel| rel.reltype() == reltype)
+                .map(|rel| rel.target_partname().unwrap().to_string())
+        };
+        assert_eq!(
+            target("/ppt/slides/slide1.xml", rt::CHART).as_deref(),
+            Some("/ppt/charts/chart1.xml")
+        );
+        assert_eq!(
+            target("/ppt/slides/slide2.xml", rt::CHART).as_deref(),
+            Some("/ppt/charts/chart2.xml")
+        );
+        assert_eq!(
+            target("/ppt/charts/chart2.xml", rt::PACKAGE).as_deref(),
+            Some("/ppt/embeddings/Microsoft_Excel_Worksheet2.xlsx")
+        );
+        assert_eq!(
+            target("/ppt/charts/chart2.xml", rt::IMAGE).as_deref(),
+            Some("/ppt/media/image1.png")
+        );
+        assert_eq!(
+            target("/ppt/slides/slide1.xml", rt::SLIDE_LAYOUT),
+            target("/ppt/slides/slide2.xml", rt::SLIDE_LAYOUT)
+        );
+        assert_eq!(
+            opc.get_part(&uri("/ppt/charts/chart2.xml")).unwrap().blob(),
+            b"<c:chartSpace/>"
+        );
+    }
+
+    #[test]
+    fn test_edit_saved_presentation() {
+        let png = b"\x89PNG\r\n\x1a\n\0\0\0\rIHDR".to_vec();
+
+        let mut pkg = Package::new().unwrap();
+        {
+            let pres = pkg.presentation_mut().unwrap();
+            let first = pres.add_slide().unwrap();
+            first.set_title("First");
+            first.set_notes("First notes");
+            first
+                .add_picture_from_bytes(png, 0, 0, 100, 100, None)
+                .unwrap();
+            pres.add_slide().unwrap().set_title("Second");
+            pres.add_slide().unwrap().set_title("Third");
+        }
+        let saved = save(&mut pkg);
+
+        let mut pkg = Package::from_reader(Cursor::new(&saved)).unwrap();
+        {
+            let pres = pkg.presentation_mut().unwrap();
+            assert_eq!(pres.slide_count(), 3);
+            assert_eq!(pres.slide_mut(0).unwrap().notes(), Some("First notes"));
+
+            let copy = pres.duplicate_slide(0).unwrap();
+            copy.set_title("Copy");
+            copy.set_notes("Copy notes");
+            assert!(pres.remove_slide(2));
+            assert!(pres.move_slide(2, 0));
+        }
+        let saved = save(&mut pkg);
+
+        let pkg = Package::from_reader(Cursor::new(&saved)).unwrap();
+        let slides = pkg.presentation().unwrap().slides().unwrap();
+        let texts: Vec<String> = slides.iter().map(|slide| slide.text().unwrap()).collect();
+        assert_eq!(texts.len(), 3);
+        assert!(texts[0].contains("Third"));
+        assert!(texts[1].contains("First"));
+        assert!(texts[2].contains("Copy"));
+        assert!(!slides[0].has_pictures().unwrap());
+        assert!(slides[1].has_pictures().unwrap());
+        assert!(slides[2].has_pictures().unwrap());
+
+        let opc = pkg.opc_package();
+        let count = |prefix: &str| {
+            opc.iter_parts()
+                .filter(|part| part.partname().as_str().starts_with(prefix))
+                .count()
+        };
+        assert_eq!(count("/ppt/slides/"), 3);
+        assert_eq!(count("/ppt/notesSlides/"), 2);
+        assert_eq!(count("/ppt/media/"), 1);
+        assert_eq!(count("/ppt/slideLayouts/"), 11);
+
+        let mut pkg = Package::from_reader(Cursor::new(&saved)).unwrap();
+        let pres = pkg.presentation_mut().unwrap();
+        assert_eq!(pres.slide_mut(2).unwrap().notes(), Some("Copy notes"));
+        assert_eq!(pres.slide_mut(1).unwrap().title(), Some("First"));
+    }
 }
@@ -1,4 +1,6 @@
 /// Main presentation object - the high-level API for working with presentations.
+use crate::common::{EmbeddedImage, ImageAnchor};
+use crate::ooxml::common::{ChartData, collect_charts, collect_pictures};
 use crate::ooxml::error::Result;
 use crate::ooxml::opc::OpcPackage;
 use crate::ooxml::opc::packuri::PackURI;
@@ -202,9 +204,68 @@ impl<'a> Presentation<'a> {
         self.package
     }
 
+    /// Get all pictures placed on the slides.
+    ///
+    /// Each image is anchored to the 0-based index of its slide and carries
+    /// the description (or title) of its `p:pic` shape as alt text.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use litchi::ooxml::pptx::Package;
+    ///
+    /// let pkg = Package::open("presentation.pptx")?;
+    /// let pres = pkg.presentation()?;
+    ///
+    /// for image in pres.images()? {
+    ///     std::fs::write(&image.name, &image.data)?;
+    /// }
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn images(&self) -> Result<Vec<EmbeddedImage>> {
+        let mut images = Vec::new();
+        for (index, slide) in self.slides()?.iter().enumerate() {
+            images.extend(collect_pictures(slide.part().part(), self.package, |_| {
+                ImageAnchor::Slide(index)
+            })?);
+        }
+        Ok(images)
+    }
+
+    /// Get all charts placed on the slides.
+    ///
+    /// Charts are read from the graphic frames of each slide and anchored to
+    /// the 0-based index of their slide.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use litchi::ooxml::pptx::Package;
+    /// use litchi::ImageAnchor;
+    ///
+    /// let pkg = Package::open("presentation.pptx")?;
+    /// let pres = pkg.presentation()?;
+    ///
+    /// for chart in pres.charts()? {
+    ///     if let ImageAnchor::Slide(index) = chart.anchor {
+    ///         println!("slide {}: {:?}", index + 1, chart.title);
+    ///     }
+    /// }
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn charts(&self) -> Result<Vec<ChartData>> {
+        let mut charts = Vec::new();
+        for (index, slide) in self.slides()?.iter().enumerate() {
+            charts.extend(collect_charts(slide.part().part(), self.package, |_| {
+                ImageAnchor::Slide(index)
+            })?);
+        }
+        Ok(charts)
+    }
+
     // TODO: Apache POI features not yet implemented:
     // - Slide manipulation: delete_slide(), duplicate_slide(), move_slide()
-    // - Charts: add_chart(), get_charts(), update_chart()
+    // - Charts: add_chart(), update_chart()
     // - Tables (reading/writing): get_tables(), add_table(), modify_table()
     // - SmartArt: add_smartart(), get_smartart()
     // - Audio/Video: add_audio(), add_video(), get_media()
@@ -3,9 +3,29 @@
 //! Provides minimal valid templates for creating new PowerPoint presentations.
 //! These templates contain the bare minimum structure required for a valid .pptx file.
 //! Generate valid presentation.xml content (based on python-pptx template).
+//!
+//! Also fills `{{name}}` placeholders in slides and notes as described in
+//! [`merge`](crate::ooxml::common::merge).
 
+use crate::ooxml::common::TemplateData;
+use crate::ooxml::common::merge::{self, Dialect};
+use crate::ooxml::error::Result;
 use xml_minifier::minified_xml;
 
+/// DrawingML element names for filling templates.
+const DIALECT: Dialect = Dialect {
+    paragraphs: &["p"],
+    run: "r",
+    text: "t",
+    row: Some("tr"),
+    // A graphic frame cannot be left without its table
+    table: None,
+    paragraph_container: Some(("txBody", "<a:p/>")),
+    line_break: None,
+    preserve_space: false,
+    paragraph_blocks: true,
+};
+
 /// Creates an empty presentation with no slides but complete text styling.
 pub fn default_presentation_xml() -> &'static str {
     minified_xml!("resources/presentation.xml")
@@ -123,3 +143,12 @@ pub fn default_core_props_xml() -> &'static str {
 pub fn default_app_props_xml() -> &'static str {
     minified_xml!("resources/docProps/app.xml")
 }
+
+/// Fill the placeholders of a slide or notes part. Returns `None` when
+/// the part has nothing to fill.
+pub(crate) fn render_part(xml: &str, data: &TemplateData) -> Result<Option<String>> {
+    if !xml.contains('{') {
+        return Ok(None);
+    }
+    merge::render(xml, data, &DIALECT, |_| {})
+}
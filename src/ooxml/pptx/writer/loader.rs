@@ -0,0 +1,589 @@
+//! Loading existing slides into the mutable presentation model.
+//!
+//! Slides are mirrored as [`MutableSlide`]s whose shapes keep their original
+//! markup. Only what is edited through the mutable API is regenerated on
+//! save; backgrounds, transitions, animations and shape kinds the writer does
+//! not model (tables, charts, groups, media, ...) are written back verbatim,
+//! and the slide parts keep their relationships to layouts and media.
+
+use std::ops::Range;
+
+use quick_xml::Reader;
+use quick_xml::events::{BytesStart, Event};
+
+use super::pres::MutablePresentation;
+use super::shape::{MutableShape, ShapeType};
+use super::slide::MutableSlide;
+use crate::ooxml::common::xml::{attribute, local_name, push_text, split_children};
+use crate::ooxml::error::{OoxmlError, Result};
+
+/// Markup of a loaded slide that lives outside its shapes.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct SlideSource {
+    /// Part holding the slide; for unsaved duplicates, the part copied from
+    pub(crate) partname: String,
+    /// Relationship ID from presentation.xml, `None` for unsaved duplicates
+    pub(crate) relationship_id: Option<String>,
+    /// Raw attributes of `p:sld` (namespaces, `show`, ...)
+    pub(crate) root_attributes: String,
+    /// Raw attributes of `p:cSld`
+    pub(crate) c_sld_attributes: String,
+    /// Markup in `p:cSld` before the shape tree (`p:bg`)
+    pub(crate) before_tree: String,
+    /// `p:nvGrpSpPr` and `p:grpSpPr` of the shape tree
+    pub(crate) tree_properties: String,
+    /// Markup in `p:cSld` after the shape tree
+    pub(crate) after_tree: String,
+    /// Markup after `p:cSld` (`p:clrMapOvr`, `p:transition`, `p:timing`, ...)
+    pub(crate) after_c_sld: String,
+    /// Speaker notes as last loaded or saved
+    pub(crate) notes: Option<String>,
+}
+
+/// An existing slide part to mirror.
+pub(crate) struct ExistingSlide {
+    /// Partname of the slide
+    pub(crate) partname: String,
+    /// Slide XML
+    pub(crate) xml: String,
+    /// Text of the slide's notes page, if any
+    pub(crate) notes: Option<String>,
+}
+
+fn number(e: &BytesStart, name: &[u8]) -> Option<i64> {
+    attribute(e, name).and_then(|value| value.parse().ok())
+}
+
+/// List the `(id, r:id)` pairs of a presentation's `p:sldIdLst`.
+pub(crate) fn slide_id_list(presentation_xml: &str) -> Result<Vec<(u32, String)>> {
+    let mut reader = Reader::from_str(presentation_xml);
+    let mut slides = Vec::new();
+
+    loop {
+        match reader.read_event() {
+            Ok(Event::Start(e)) | Ok(Event::Empty(e)) if e.local_name().as_ref() == b"sldId" => {
+                let id = attribute(&e, b"id").and_then(|id| id.parse().ok());
+                let r_id = e
+                    .attributes()
+                    .flatten()
+                    .find(|attr| {
+                        attr.key.prefix().is_some() && attr.key.local_name().as_ref() == b"id"
+                    })
+                    .and_then(|attr| attr.unescape_value().ok().map(|v| v.into_owned()));
+                if let (Some(id), Some(r_id)) = (id, r_id) {
+                    slides.push((id, r_id));
+                }
+            },
+            Ok(Event::End(e)) if e.local_name().as_ref() == b"sldIdLst" => break,
+            Ok(Event::Eof) => break,
+            Err(e) => return Err(OoxmlError::Xml(e.to_string())),
+            _ => {},
+        }
+    }
+
+    Ok(slides)
+}
+
+/// Get the `(cx, cy)` of a presentation's `p:sldSz`.
+pub(crate) fn slide_size(presentation_xml: &str) -> Result<Option<(i64, i64)>> {
+    let mut reader = Reader::from_str(presentation_xml);
+
+    loop {
+        match reader.read_event() {
+            Ok(Event::Start(e)) | Ok(Event::Empty(e)) if e.local_name().as_ref() == b"sldSz" => {
+                return Ok(number(&e, b"cx").zip(number(&e, b"cy")));
+            },
+            Ok(Event::Eof) => return Ok(None),
+            Err(e) => return Err(OoxmlError::Xml(e.to_string())),
+            _ => {},
+        }
+    }
+}
+
+/// Find the body placeholder of a notes slide.
+///
+/// Returns the shape's byte range in `notes_xml` along with the shape.
+fn notes_body(notes_xml: &str) -> Result<Option<(Range<usize>, MutableShape)>> {
+    let (_, children) = split_children(notes_xml)?;
+    let Some(c_sld) = children.iter().find(|child| child.name == "cSld") else {
+        return Ok(None);
+    };
+    let c_sld_xml = &notes_xml[c_sld.range.clone()];
+    let (_, c_sld_children) = split_children(c_sld_xml)?;
+    let Some(tree) = c_sld_children.iter().find(|child| child.name == "spTree") else {
+        return Ok(None);
+    };
+    let tree_start = c_sld.range.start + tree.range.start;
+    let tree_xml = &c_sld_xml[tree.range.clone()];
+    let (_, shapes) = split_children(tree_xml)?;
+
+    for child in shapes.iter().filter(|child| child.name == "sp") {
+        let shape = MutableShape::from_xml(&tree_xml[child.range.clone()])?;
+        if shape.placeholder_type() == Some("body") {
+            let range = tree_start + child.range.start..tree_start + child.range.end;
+            return Ok(Some((range, shape)));
+        }
+    }
+
+    Ok(None)
+}
+
+/// Get the text of the body placeholder of a notes slide.
+pub(crate) fn notes_text(notes_xml: &str) -> Result<Option<String>> {
+    Ok(notes_body(notes_xml)?.and_then(|(_, shape)| shape.text().map(str::to_string)))
+}
+
+/// Replace the text of the body placeholder of a notes slide.
+///
+/// The rest of the notes page (slide image, header and footer
+/// placeholders, ...) is kept. Returns `None` when there is no body
+/// placeholder to update.
+pub(crate) fn replace_notes_text(notes_xml: &str, text: &str) -> Result<Option<String>> {
+    let Some((range, mut shape)) = notes_body(notes_xml)? else {
+        return Ok(None);
+    };
+    if shape.set_text(text).text() != Some(text) {
+        return Ok(None);
+    }
+    let ShapeType::Existing { xml: markup, .. } = &shape.shape_type else {
+        return Ok(None);
+    };
+
+    Ok(Some(format!(
+        "{}{}{}",
+        &notes_xml[..range.start],
+        markup,
+        &notes_xml[range.end..]
+    )))
+}
+
+impl MutablePresentation {
+    /// Mirror an existing presentation.xml.
+    ///
+    /// `slide` resolves a slide relationship ID to its part; slides it
+    /// cannot resolve are dropped from the slide list.
+    pub(crate) fn from_existing(
+        presentation_xml: &str,
+        mut slide: impl FnMut(&str) -> Result<Option<ExistingSlide>>,
+    ) -> Result<Self> {
+        let mut presentation = MutablePresentation::new();
+        if let Some((width, height)) = slide_size(presentation_xml)? {
+            presentation.set_slide_width(width);
+            presentation.set_slide_height(height);
+        }
+
+        for (slide_id, relationship_id) in slide_id_list(presentation_xml)? {
+            if let Some(existing) = slide(&relationship_id)? {
+                presentation.slides.push(MutableSlide::from_existing(
+                    slide_id,
+                    relationship_id,
+                    existing,
+                )?);
+            }
+        }
+
+        presentation.modified = false;
+        Ok(presentation)
+    }
+}
+
+impl MutableSlide {
+    /// Mirror an existing slide part.
+    pub(crate) fn from_existing(
+        slide_id: u32,
+        relationship_id: String,
+        existing: ExistingSlide,
+    ) -> Result<Self> {
+        let xml = existing.xml.as_str();
+        let mut slide = MutableSlide::new(slide_id);
+        let mut source = SlideSource {
+            partname: existing.partname,
+            relationship_id: Some(relationship_id),
+            notes: existing.notes.clone(),
+            ..SlideSource::default()
+        };
+
+        let (root_attributes, children) = split_children(xml)?;
+        source.root_attributes = root_attributes;
+
+        for child in &children {
+            let markup = &xml[child.range.clone()];
+            if child.name != "cSld" {
+                source.after_c_sld.push_str(markup);
+                continue;
+            }
+
+            let (c_sld_attributes, c_sld_children) = split_children(markup)?;
+            source.c_sld_attributes = c_sld_attributes;
+            let mut seen_tree = false;
+
+            for c_sld_child in &c_sld_children {
+                let c_sld_markup = &markup[c_sld_child.range.clone()];
+                if c_sld_child.name != "spTree" {
+                    if seen_tree {
+                        source.after_tree.push_str(c_sld_markup);
+                    } else {
+                        source.before_tree.push_str(c_sld_markup);
+                    }
+                    continue;
+                }
+                seen_tree = true;
+
+                let (_, shapes) = split_children(c_sld_markup)?;
+                for shape in &shapes {
+                    let shape_markup = &c_sld_markup[shape.range.clone()];
+                    match shape.name.as_str() {
+                        "nvGrpSpPr" | "grpSpPr" => source.tree_properties.push_str(shape_markup),
+                        _ => slide.shapes.push(MutableShape::from_xml(shape_markup)?),
+                    }
+                }
+            }
+        }
+
+        slide.title = slide
+            .shapes
+            .iter()
+            .find(|shape| shape.is_title())
+            .and_then(|shape| shape.text().map(str::to_string));
+        slide.notes = existing.notes;
+        slide.source = Some(source);
+        slide.modified = false;
+
+        Ok(slide)
+    }
+}
+
+impl MutableShape {
+    /// Mirror a shape tree child (`p:sp`, `p:pic`, `p:graphicFrame`, ...).
+    pub(crate) fn from_xml(markup: &str) -> Result<Self> {
+        let mut reader = Reader::from_str(markup);
+        let mut kind = String::new();
+        let mut shape_id = 0;
+        let mut name = String::new();
+        let mut placeholder = None;
+        let mut offset = None;
+        let mut extent = None;
+        let mut paragraphs: Vec<String> = Vec::new();
+        let mut in_paragraph = false;
+        let mut in_text = false;
+        let mut seen_properties = false;
+
+        loop {
+            match reader.read_event() {
+                Ok(Event::Start(e)) | Ok(Event::Empty(e)) if kind.is_empty() => {
+                    kind = local_name(&e);
+                },
+                Ok(Event::Start(e)) => match e.local_name().as_ref() {
+                    b"p" => {
+                        paragraphs.push(String::new());
+                        in_paragraph = true;
+                    },
+                    b"t" if in_paragraph => in_text = true,
+                    _ => visit_shape_element(
+                        &e,
+                        &mut seen_properties,
+                        &mut shape_id,
+                        &mut name,
+                        &mut placeholder,
+                        &mut offset,
+                        &mut extent,
+                    ),
+                },
+                Ok(Event::Empty(e)) => match e.local_name().as_ref() {
+                    b"p" => paragraphs.push(String::new()),
+                    b"br" if in_paragraph => {
+                        if let Some(paragraph) = paragraphs.last_mut() {
+                            paragraph.push('\n');
+                        }
+                    },
+                    _ => visit_shape_element(
+                        &e,
+                        &mut seen_properties,
+                        &mut shape_id,
+                        &mut name,
+                        &mut placeholder,
+                        &mut offset,
+                        &mut extent,
+                    ),
+                },
+                Ok(event @ (Event::Text(_) | Event::CData(_) | Event::GeneralRef(_)))
+                    if in_text =>
+                {
+                    if let Some(paragraph) = paragraphs.last_mut() {
+                        push_text(&event, paragraph)?;
+                    }
+                },
+                Ok(Event::End(e)) => match e.local_name().as_ref() {
+                    b"p" => in_paragraph = false,
+                    b"t" => in_text = false,
+                    _ => {},
+                },
+                Ok(Event::Eof) => break,
+                Err(e) => return Err(OoxmlError::Xml(e.to_string())),
+                _ => {},
+            }
+        }
+
+        let text = (!paragraphs.is_empty()).then(|| paragraphs.join("\n"));
+        let position = offset
+            .zip(extent)
+            .map(|((x, y), (width, height))| (x, y, width, height));
+
+        Ok(Self {
+            shape_id,
+            shape_type: ShapeType::Existing {
+                xml: markup.to_string(),
+                kind,
+                name,
+                placeholder,
+                text,
+                position,
+            },
+        })
+    }
+}
+
+/// Record the identity, placeholder and position of a shape from one element.
+///
+/// Only the first `cNvPr`, `ph`, `a:off` and `a:ext` count, so the
+/// properties of a group's children do not override the group's own.
+fn visit_shape_element(
+    e: &BytesStart,
+    seen_properties: &mut bool,
+    shape_id: &mut u32,
+    name: &mut String,
+    placeholder: &mut Option<String>,
+    offset: &mut Option<(i64, i64)>,
+    extent: &mut Option<(i64, i64)>,
+) {
+    match e.local_name().as_ref() {
+        b"cNvPr" if !*seen_properties => {
+            *seen_properties = true;
+            *shape_id = attribute(e, b"id")
+                .and_then(|id| id.parse().ok())
+                .unwrap_or(0);
+            *name = attribute(e, b"name").unwrap_or_default();
+        },
+        b"ph" if placeholder.is_none() => {
+            // An untyped placeholder is an object placeholder
+            *placeholder = Some(attribute(e, b"type").unwrap_or_else(|| "obj".to_string()));
+        },
+        b"off" if offset.is_none() => *offset = number(e, b"x").zip(number(e, b"y")),
+        // `a:ext` is also the element name of extension list entries
+        b"ext" if extent.is_none() => *extent = number(e, b"cx").zip(number(e, b"cy")),
+        _ => {},
+    }
+}
+
+/// Replace the paragraphs of a shape's text body with `text`, one per line.
+///
+/// Body properties and list styles are kept, and every new paragraph
+/// reuses the paragraph and run properties of the first existing one.
+/// Returns `None` when the shape has no text body.
+pub(crate) fn replace_text_body(markup: &str, text: &str) -> Result<Option<String>> {
+    let mut reader = Reader::from_str(markup);
+    let mut body_depth = 0usize;
+    let mut first_paragraph = None;
+    let mut body_end = None;
+    let mut properties = TextProperties::default();
+    let mut paragraphs_seen = 0usize;
+
+    loop {
+        let start = reader.buffer_position() as usize;
+        match reader.read_event() {
+            Ok(Event::Start(e)) => match e.local_name().as_ref() {
+                b"txBody" if body_depth == 0 && body_end.is_none() => body_depth = 1,
+                b"p" if body_depth == 1 => {
+                    paragraphs_seen += 1;
+                    first_paragraph.get_or_insert(start);
+                    body_depth += 1;
+                },
+                b"pPr" | b"rPr" | b"endParaRPr" if body_depth > 1 && paragraphs_seen == 1 => {
+                    reader
+                        .read_to_end(e.name())
+                        .map_err(|e| OoxmlError::Xml(e.to_string()))?;
+                    let range = start..reader.buffer_position() as usize;
+                    properties.record(e.local_name().as_ref(), range);
+                },
+                _ if body_depth > 0 => body_depth += 1,
+                _ => {},
+            },
+            Ok(Event::Empty(e)) => match e.local_name().as_ref() {
+                b"p" if body_depth == 1 => {
+                    paragraphs_seen += 1;
+                    first_paragraph.get_or_insert(start);
+                },
+                b"pPr" | b"rPr" | b"endParaRPr" if body_depth > 1 && paragraphs_seen == 1 => {
+                    let range = start..reader.buffer_position() as usize;
+                    properties.record(e.local_name().as_ref(), range);
+                },
+                _ => {},
+            },
+            Ok(Event::End(_)) if body_depth == 1 => {
+                body_depth = 0;
+                body_end = Some(start);
+            },
+            Ok(Event::End(_)) if body_depth > 1 => body_depth -= 1,
+            Ok(Event::Eof) => break,
+            Err(e) => return Err(OoxmlError::Xml(e.to_string())),
+            _ => {},
+        }
+    }
+
+    let Some(body_end) = body_end else {
+        return Ok(None);
+    };
+    let first_paragraph = first_paragraph.unwrap_or(body_end);
+    let paragraph_properties = properties.paragraph.map_or("", |range| &markup[range]);
+    let run_properties = match (properties.run, properties.end) {
+        (Some(range), _) => markup[range].to_string(),
+        (None, Some(range)) => end_to_run_properties(&markup[range]),
+        (None, None) => String::new(),
+    };
+
+    let mut result = String::with_capacity(markup.len() + text.len());
+    result.push_str(&markup[..first_paragraph]);
+    for line in text.split('\n') {
+        result.push_str("<a:p>");
+        result.push_str(paragraph_properties);
+        if !line.is_empty() {
+            result.push_str("<a:r>");
+            result.push_str(&run_properties);
+            result.push_str("<a:t>");
+            result.push_str(&quick_xml::escape::escape(line));
+            result.push_str("</a:t></a:r>");
+        }
+        result.push_str("</a:p>");
+    }
+    result.push_str(&markup[body_end..]);
+
+    Ok(Some(result))
+}
+
+/// Byte ranges of the properties in the first paragraph of a text body.
+#[derive(Default)]
+struct TextProperties {
+    /// `a:pPr`
+    paragraph: Option<Range<usize>>,
+    /// First `a:rPr`
+    run: Option<Range<usize>>,
+    /// `a:endParaRPr`
+    end: Option<Range<usize>>,
+}
+
+impl TextProperties {
+    fn record(&mut self, name: &[u8], range: Range<usize>) {
+        let slot = match name {
+            b"pPr" => &mut self.paragraph,
+            b"rPr" => &mut self.run,
+            _ => &mut self.end,
+        };
+        slot.get_or_insert(range);
+    }
+}
+
+/// Turn `a:endParaRPr` markup into the equivalent `a:rPr`.
+fn end_to_run_properties(markup: &str) -> String {
+    match markup.strip_prefix("<a:endParaRPr") {
+        Some(rest) => format!("<a:rPr{}", rest.replace("</a:endParaRPr>", "</a:rPr>")),
+        None => String::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SLIDE_XML: &str = r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<p:sld xmlns:a="http://schemas.openxmlformats.org/drawingml/2006/main" xmlns:r="http://schemas.openxmlformats.org/officeDocument/2006/relationships" xmlns:p="http://schemas.openxmlformats.org/presentationml/2006/main" show="0"><p:cSld name="Intro"><p:bg><p:bgRef idx="1001"><a:schemeClr val="bg1"/></p:bgRef></p:bg><p:spTree><p:nvGrpSpPr><p:cNvPr id="1" name=""/><p:cNvGrpSpPr/><p:nvPr/></p:nvGrpSpPr><p:grpSpPr/><p:sp><p:nvSpPr><p:cNvPr id="2" name="Title 1"/><p:cNvSpPr><a:spLocks noGrp="1"/></p:cNvSpPr><p:nvPr><p:ph type="title"/></p:nvPr></p:nvSpPr><p:spPr/><p:txBody><a:bodyPr/><a:lstStyle/><a:p><a:pPr algn="ctr"/><a:r><a:rPr lang="en-US" b="1"/><a:t>Quarterly &amp; Annual</a:t></a:r></a:p></p:txBody></p:sp><p:pic><p:nvPicPr><p:cNvPr id="4" name="Picture 3" descr="Logo"/><p:cNvPicPr/><p:nvPr/></p:nvPicPr><p:blipFill><a:blip r:embed="rId2"/></p:blipFill><p:spPr><a:xfrm><a:off x="100" y="200"/><a:ext cx="300" cy="400"/></a:xfrm></p:spPr></p:pic><p:graphicFrame><p:nvGraphicFramePr><p:cNvPr id="5" name="Table 4"/><p:cNvGraphicFramePr/><p:nvPr/></p:nvGraphicFramePr><p:xfrm><a:off x="1" y="2"/><a:ext cx="3" cy="4"/></p:xfrm><a:graphic><a:graphicData uri="http://schemas.openxmlformats.org/drawingml/2006/table"><a:tbl/></a:graphicData></a:graphic></p:graphicFrame></p:spTree></p:cSld><p:clrMapOvr><a:masterClrMapping/></p:clrMapOvr><p:transition spd="slow"/></p:sld>"#;
+
+    fn load() -> MutableSlide {
+        MutableSlide::from_existing(
+            257,
+            "rId3".to_string(),
+            ExistingSlide {
+                partname: "/ppt/slides/slide1.xml".to_string(),
+                xml: SLIDE_XML.to_string(),
+                notes: Some("Speak slowly".to_string()),
+            },
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_load_slide() {
+        let slide = load();
+
+        assert!(!slide.is_modified());
+        assert_eq!(slide.slide_id(), 257);
+        assert_eq!(slide.title(), Some("Quarterly & Annual"));
+        assert_eq!(slide.notes(), Some("Speak slowly"));
+        assert_eq!(slide.shape_count(), 3);
+
+        let picture = slide.shape(1).unwrap();
+        assert_eq!(picture.name(), Some("Picture 3"));
+        assert_eq!(picture.position(), Some((100, 200, 300, 400)));
+        assert_eq!(slide.shape(2).unwrap().position(), Some((1, 2, 3, 4)));
+
+        let source = slide.source.as_ref().unwrap();
+        assert!(source.root_attributes.contains("show=\"0\""));
+        assert!(source.before_tree.starts_with("<p:bg>"));
+        assert!(
+            source
+                .after_c_sld
+                .ends_with(r#"<p:transition spd="slow"/>"#)
+        );
+    }
+
+    #[test]
+    fn test_edit_loaded_slide() {
+        let mut slide = load();
+        slide.set_title("Yearly <Review>");
+        slide.add_text_box("New", 0, 0, 10, 10);
+
+        let xml = slide.to_xml().unwrap();
+        assert!(xml.contains(r#"<p:cSld name="Intro"><p:bg>"#));
+        assert!(xml.contains(
+            r#"<a:p><a:pPr algn="ctr"/><a:r><a:rPr lang="en-US" b="1"/><a:t>Yearly &lt;Review&gt;</a:t></a:r></a:p>"#
+        ));
+        assert!(!xml.contains("ctrTitle"));
+        assert!(xml.contains(r#"<a:blip r:embed="rId2"/>"#));
+        assert!(xml.contains("<a:tbl/>"));
+        assert!(xml.contains(r#"<p:cNvPr id="6" name="Text Box 6"/>"#));
+        assert!(xml.ends_with(r#"<p:transition spd="slow"/></p:sld>"#));
+
+        let reloaded = MutableSlide::from_existing(
+            257,
+            "rId3".to_string(),
+            ExistingSlide {
+                partname: "/ppt/slides/slide1.xml".to_string(),
+                xml,
+                notes: None,
+            },
+        )
+        .unwrap();
+        assert_eq!(reloaded.title(), Some("Yearly <Review>"));
+        assert_eq!(reloaded.shape_count(), 4);
+    }
+
+    #[test]
+    fn test_replace_text_body_lines() {
+        let markup = r#"<p:sp><p:txBody><a:bodyPr/><a:p><a:endParaRPr lang="en-US" sz="1800"/></a:p></p:txBody></p:sp>"#;
+        let replaced = replace_text_body(markup, "one\n\ntwo").unwrap().unwrap();
+        assert_eq!(
+            replaced,
+            r#"<p:sp><p:txBody><a:bodyPr/><a:p><a:r><a:rPr lang="en-US" sz="1800"/><a:t>one</a:t></a:r></a:p><a:p></a:p><a:p><a:r><a:rPr lang="en-US" sz="1800"/><a:t>two</a:t></a:r></a:p></p:txBody></p:sp>"#
+        );
+        assert_eq!(replace_text_body("<p:pic/>", "x").unwrap(), None);
+    }
+
+    #[test]
+    fn test_presentation_lists() {
+        let xml = r#"<p:presentation xmlns:p="p" xmlns:r="r"><p:sldMasterIdLst><p:sldMasterId id="2147483648" r:id="rId1"/></p:sldMasterIdLst><p:sldIdLst><p:sldId id="256" r:id="rId7"/><p:sldId id="300" r:id="rId8"/></p:sldIdLst><p:sldSz cx="12192000" cy="6858000"/></p:presentation>"#;
+        assert_eq!(
+            slide_id_list(xml).unwrap(),
+            vec![(256, "rId7".to_string()), (300, "rId8".to_string())]
+        );
+        assert_eq!(slide_size(xml).unwrap(), Some((12192000, 6858000)));
+    }
+}
@@ -1,5 +1,6 @@
 //! Mutable presentation writer components for PPTX.
 
+pub(crate) mod loader;
 pub mod pres;
 pub(crate) mod relmap;
 pub mod shape;
@@ -1,9 +1,12 @@
 /// Presentation writer for PPTX.
+use crate::ooxml::common::xml::{attribute, split_children};
 use crate::ooxml::error::{OoxmlError, Result};
+use quick_xml::Reader;
+use quick_xml::events::Event;
 use std::fmt::Write as FmtWrite;
+use std::ops::Range;
 
-// Import shared format types
-use super::super::format::ImageFormat;
+use super::loader::slide_size;
 use super::slide::MutableSlide;
 
 /// A mutable PowerPoint presentation for writing and modification.
@@ -18,7 +21,7 @@ pub struct MutablePresentation {
     /// Slide height in EMUs
     slide_height: i64,
     /// Whether the presentation has been modified
-    modified: bool,
+    pub(crate) modified: bool,
 }
 
 impl MutablePresentation {
@@ -36,13 +39,71 @@ impl MutablePresentation {
 
     /// Add a new slide to the presentation.
     pub fn add_slide(&mut self) -> Result<&mut MutableSlide> {
-        let slide_id = (self.slides.len() + 256) as u32;
-        let slide = MutableSlide::new(slide_id);
+        let slide = MutableSlide::new(self.next_slide_id());
         self.slides.push(slide);
         self.modified = true;
         Ok(self.slides.last_mut().unwrap())
     }
 
+    /// Remove a slide by index (0-based).
+    ///
+    /// The slide's part and notes are removed from the package on save.
+    /// Returns `false` if there is no slide at `index`.
+    pub fn remove_slide(&mut self, index: usize) -> bool {
+        if index >= self.slides.len() {
+            return false;
+        }
+        self.slides.remove(index);
+        self.modified = true;
+        true
+    }
+
+    /// Duplicate a slide and insert the copy right after it.
+    ///
+    /// The copy shares the original's layout and images; its charts and
+    /// their embedded workbooks are copied so that editing one slide's
+    /// chart doesn't change the other. Returns `None` if there is no slide
+    /// at `index`.
+    pub fn duplicate_slide(&mut self, index: usize) -> Option<&mut MutableSlide> {
+        let mut copy = self.slides.get(index)?.clone();
+        copy.slide_id = self.next_slide_id();
+        if let Some(source) = copy.source.as_mut() {
+            // Saved as a new part that copies the original's relationships
+            source.relationship_id = None;
+            source.notes = None;
+        }
+        copy.modified = true;
+
+        self.slides.insert(index + 1, copy);
+        self.modified = true;
+        self.slides.get_mut(index + 1)
+    }
+
+    /// Move a slide from one position to another (both 0-based).
+    ///
+    /// Returns `false` if either index is out of range.
+    pub fn move_slide(&mut self, from: usize, to: usize) -> bool {
+        if from >= self.slides.len() || to >= self.slides.len() {
+            return false;
+        }
+        let slide = self.slides.remove(from);
+        self.slides.insert(to, slide);
+        self.modified = true;
+        true
+    }
+
+    /// Get an unused slide ID.
+    ///
+    /// Slide IDs start at 256 as required by the specification.
+    fn next_slide_id(&self) -> u32 {
+        self.slides
+            .iter()
+            .map(|slide| slide.slide_id + 1)
+            .max()
+            .unwrap_or(256)
+            .max(256)
+    }
+
     /// Get the number of slides.
     pub fn slide_count(&self) -> usize {
         self.slides.len()
@@ -80,17 +141,93 @@ impl MutablePresentation {
         self.modified || self.slides.iter().any(|s| s.is_modified())
     }
 
-    /// Collect all images from all slides in the presentation.
-    pub(crate) fn collect_all_images(&self) -> Vec<(usize, &[u8], ImageFormat)> {
-        let mut all_images = Vec::new();
+    /// Update an existing presentation.xml with the slide list and size.
+    ///
+    /// Sections and custom shows are updated to match the slide list;
+    /// everything else in the part (masters, notes master, default text
+    /// styles, other extensions, ...) is kept as is.
+    pub(crate) fn splice_presentation_xml(
+        &self,
+        presentation_xml: &str,
+        slide_rel_ids: &[String],
+    ) -> Result<String> {
+        let mut slide_list = String::new();
+        if !self.slides.is_empty() {
+            slide_list.push_str("<p:sldIdLst>");
+            for (slide, rel_id) in self.slides.iter().zip(slide_rel_ids) {
+                write!(
+                    slide_list,
+                    r#"<p:sldId id="{}" r:id="{}"/>"#,
+                    slide.slide_id(),
+                    rel_id
+                )
+                .map_err(|e| OoxmlError::Xml(e.to_string()))?;
+            }
+            slide_list.push_str("</p:sldIdLst>");
+        }
 
-        for (slide_index, slide) in self.slides.iter().enumerate() {
-            for (image_data, image_format) in slide.collect_images() {
-                all_images.push((slide_index, image_data, image_format));
+        // Keep the size element (and its `type`) untouched unless it changed
+        let size = if slide_size(presentation_xml)? == Some((self.slide_width, self.slide_height)) {
+            None
+        } else {
+            Some(format!(
+                r#"<p:sldSz cx="{}" cy="{}"/>"#,
+                self.slide_width, self.slide_height
+            ))
+        };
+
+        let slide_ids: Vec<u32> = self.slides.iter().map(|slide| slide.slide_id()).collect();
+        let (_, children) = split_children(presentation_xml)?;
+        let mut xml = String::with_capacity(presentation_xml.len() + slide_list.len());
+        let mut position = children
+            .first()
+            .map_or(presentation_xml.len(), |child| child.range.start);
+        xml.push_str(&presentation_xml[..position]);
+
+        let mut slide_list = Some(slide_list);
+        for child in &children {
+            xml.push_str(&presentation_xml[position..child.range.start]);
+            position = child.range.end;
+            let markup = &presentation_xml[child.range.clone()];
+            let updated;
+            let markup = match child.name.as_str() {
+                "custShowLst" => {
+                    updated = prune_custom_shows(markup, slide_rel_ids)?;
+                    updated.as_str()
+                },
+                "extLst" => {
+                    updated = splice_sections(markup, &slide_ids)?;
+                    updated.as_str()
+                },
+                _ => markup,
+            };
+
+            match child.name.as_str() {
+                "sldIdLst" => xml.push_str(&slide_list.take().unwrap_or_default()),
+                "sldSz" => {
+                    if let Some(list) = slide_list.take() {
+                        xml.push_str(&list);
+                    }
+                    xml.push_str(size.as_deref().unwrap_or(markup));
+                },
+                "sldMasterIdLst" | "notesMasterIdLst" | "handoutMasterIdLst" => {
+                    xml.push_str(markup)
+                },
+                _ => {
+                    // The slide list goes right after the master lists
+                    if let Some(list) = slide_list.take() {
+                        xml.push_str(&list);
+                    }
+                    xml.push_str(markup);
+                },
             }
         }
+        if let Some(list) = slide_list.take() {
+            xml.push_str(&list);
+        }
+        xml.push_str(&presentation_xml[position..]);
 
-        all_images
+        Ok(xml)
     }
 
     /// Generate presentation.xml content.
@@ -162,6 +299,204 @@ impl Default for MutablePresentation {
     }
 }
 
+/// Drop the slides of custom shows (`p:custShowLst`) that are no longer in
+/// the presentation.
+fn prune_custom_shows(markup: &str, slide_rel_ids: &[String]) -> Result<String> {
+    let mut reader = Reader::from_str(markup);
+    let mut stale: Vec<Range<usize>> = Vec::new();
+
+    loop {
+        let start = reader.buffer_position() as usize;
+        match reader
+            .read_event()
+            .map_err(|e| OoxmlError::Xml(e.to_string()))?
+        {
+            Event::Empty(ref e)
+                if e.local_name().as_ref() == b"sld"
+                    && !attribute(e, b"id").is_some_and(|id| slide_rel_ids.contains(&id)) =>
+            {
+                stale.push(start..reader.buffer_position() as usize);
+            },
+            Event::Start(ref e) if e.local_name().as_ref() == b"sld" => {
+                let keep = attribute(e, b"id").is_some_and(|id| slide_rel_ids.contains(&id));
+                reader
+                    .read_to_end(e.name())
+                    .map_err(|e| OoxmlError::Xml(e.to_string()))?;
+                if !keep {
+                    stale.push(start..reader.buffer_position() as usize);
+                }
+            },
+            Event::Eof => break,
+            _ => {},
+        }
+    }
+
+    let mut xml = String::with_capacity(markup.len());
+    let mut position = 0;
+    for range in stale {
+        xml.push_str(&markup[position..range.start]);
+        position = range.end;
+    }
+    xml.push_str(&markup[position..]);
+    Ok(xml)
+}
+
+/// A section of the `p14:sectionLst` extension.
+struct Section {
+    /// Namespace prefix of the section elements
+    prefix: String,
+    /// Slide IDs in the section
+    slide_ids: Vec<u32>,
+    /// Markup of the section's slide list, or where it goes if it has none
+    list: Range<usize>,
+}
+
+/// Update the sections of the `p14:sectionLst` extension to the slide list.
+///
+/// Deleted slides leave their section, and the sections must follow one
+/// another in slide order, so new slides and slides moved out of order join
+/// the section of the slide before them (or after them, at the start).
+fn splice_sections(markup: &str, slide_ids: &[u32]) -> Result<String> {
+    let mut reader = Reader::from_str(markup);
+    let mut sections: Vec<Section> = Vec::new();
+    let mut in_section = false;
+    let mut list_start = None;
+
+    loop {
+        let start = reader.buffer_position() as usize;
+        let event = reader
+            .read_event()
+            .map_err(|e| OoxmlError::Xml(e.to_string()))?;
+        let end = reader.buffer_position() as usize;
+        match event {
+            Event::Start(ref e) if e.local_name().as_ref() == b"section" => {
+                let prefix = e
+                    .name()
+                    .prefix()
+                    .map(|prefix| String::from_utf8_lossy(prefix.as_ref()).into_owned())
+                    .unwrap_or_default();
+                sections.push(Section {
+                    prefix,
+                    slide_ids: Vec::new(),
+                    list: end..end,
+                });
+                in_section = true;
+            },
+            Event::End(ref e) if e.local_name().as_ref() == b"section" => in_section = false,
+            Event::Start(ref e) if in_section && e.local_name().as_ref() == b"sldIdLst" => {
+                list_start = Some(start);
+            },
+            Event::End(ref e) if in_section && e.local_name().as_ref() == b"sldIdLst" => {
+                if let (Some(section), Some(list_start)) = (sections.last_mut(), list_start.take())
+                {
+                    section.list = list_start..end;
+                }
+            },
+            Event::Empty(ref e) if in_section && e.local_name().as_ref() == b"sldIdLst" => {
+                if let Some(section) = sections.last_mut() {
+                    section.list = start..end;
+                }
+            },
+            Event::Start(ref e) | Event::Empty(ref e)
+                if in_section && e.local_name().as_ref() == b"sldId" =>
+            {
+                if let (Some(section), Some(id)) = (
+                    sections.last_mut(),
+                    attribute(e, b"id").and_then(|id| id.parse().ok()),
+                ) {
+                    section.slide_ids.push(id);
+                }
+            },
+            Event::Eof => break,
+            _ => {},
+        }
+    }
+    if sections.is_empty() {
+        return Ok(markup.to_string());
+    }
+
+    let assigned = assign_sections(&sections, slide_ids);
+    let mut xml = String::with_capacity(markup.len());
+    let mut position = 0;
+    for (index, section) in sections.iter().enumerate() {
+        xml.push_str(&markup[position..section.list.start]);
+        position = section.list.end;
+
+        let prefix = match section.prefix.as_str() {
+            "" => String::new(),
+            prefix => format!("{}:", prefix),
+        };
+        let ids: Vec<u32> = slide_ids
+            .iter()
+            .zip(&assigned)
+            .filter(|&(_, &section)| section == index)
+            .map(|(&id, _)| id)
+            .collect();
+        if ids.is_empty() {
+            write!(xml, "<{}sldIdLst/>", prefix).map_err(|e| OoxmlError::Xml(e.to_string()))?;
+            continue;
+        }
+        write!(xml, "<{}sldIdLst>", prefix).map_err(|e| OoxmlError::Xml(e.to_string()))?;
+        for id in ids {
+            write!(xml, r#"<{}sldId id="{}"/>"#, prefix, id)
+                .map_err(|e| OoxmlError::Xml(e.to_string()))?;
+        }
+        write!(xml, "</{}sldIdLst>", prefix).map_err(|e| OoxmlError::Xml(e.to_string()))?;
+    }
+    xml.push_str(&markup[position..]);
+    Ok(xml)
+}
+
+/// Pick the section of each slide, keeping as many slides as possible in
+/// their own section while the sections stay in slide order.
+fn assign_sections(sections: &[Section], slide_ids: &[u32]) -> Vec<usize> {
+    let wanted: Vec<Option<usize>> = slide_ids
+        .iter()
+        .map(|id| {
+            sections
+                .iter()
+                .position(|section| section.slide_ids.contains(id))
+        })
+        .collect();
+
+    // Longest run of slides whose sections don't decrease
+    let mut length = vec![0usize; wanted.len()];
+    let mut previous = vec![None; wanted.len()];
+    for i in 0..wanted.len() {
+        let Some(section) = wanted[i] else { continue };
+        length[i] = 1;
+        for j in 0..i {
+            if wanted[j].is_some_and(|other| other <= section) && length[j] + 1 > length[i] {
+                length[i] = length[j] + 1;
+                previous[i] = Some(j);
+            }
+        }
+    }
+    let mut kept = vec![false; wanted.len()];
+    let mut next = (0..wanted.len())
+        .filter(|&i| length[i] > 0)
+        .max_by_key(|&i| length[i]);
+    while let Some(i) = next {
+        kept[i] = true;
+        next = previous[i];
+    }
+
+    let mut assigned: Vec<Option<usize>> = Vec::with_capacity(wanted.len());
+    for i in 0..wanted.len() {
+        let section = if kept[i] {
+            wanted[i]
+        } else {
+            assigned.last().copied().flatten()
+        };
+        assigned.push(section);
+    }
+    let first = assigned.iter().copied().flatten().next().unwrap_or(0);
+    assigned
+        .into_iter()
+        .map(|section| section.unwrap_or(first))
+        .collect()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -198,6 +533,98 @@ mod tests {
         assert_eq!(slide.shape_count(), 1);
     }
 
+    #[test]
+    fn test_splice_presentation_xml() {
+        let xml = r#"<p:presentation xmlns:p="p" xmlns:r="r"><p:sldMasterIdLst><p:sldMasterId id="2147483648" r:id="rId1"/></p:sldMasterIdLst><p:sldIdLst><p:sldId id="256" r:id="rId7"/></p:sldIdLst><p:sldSz cx="9144000" cy="6858000" type="screen4x3"/><p:notesSz cx="6858000" cy="9144000"/><p:extLst/></p:presentation>"#;
+        let mut pres = MutablePresentation::new();
+        pres.add_slide().unwrap();
+        pres.add_slide().unwrap();
+
+        let spliced = pres
+            .splice_presentation_xml(xml, &["rId7".to_string(), "rId9".to_string()])
+            .unwrap();
+        assert_eq!(
+            spliced,
+            r#"<p:presentation xmlns:p="p" xmlns:r="r"><p:sldMasterIdLst><p:sldMasterId id="2147483648" r:id="rId1"/></p:sldMasterIdLst><p:sldIdLst><p:sldId id="256" r:id="rId7"/><p:sldId id="257" r:id="rId9"/></p:sldIdLst><p:sldSz cx="9144000" cy="6858000" type="screen4x3"/><p:notesSz cx="6858000" cy="9144000"/><p:extLst/></p:presentation>"#
+        );
+
+        pres.set_slide_width(12192000);
+        assert!(pres.remove_slide(0));
+        assert!(pres.remove_slide(0));
+        let spliced = pres.splice_presentation_xml(xml, &[]).unwrap();
+        assert!(!spliced.contains("sldIdLst"));
+        assert!(spliced.contains(r#"<p:sldSz cx="12192000" cy="6858000"/>"#));
+    }
+
+    #[test]
+    fn test_duplicate_and_move_slides() {
+        let mut pres = MutablePresentation::new();
+        pres.add_slide().unwrap().set_title("A");
+        pres.add_slide().unwrap().set_title("B");
+
+        let copy = pres.duplicate_slide(0).unwrap();
+        assert_eq!(copy.slide_id(), 258);
+        assert_eq!(copy.title(), Some("A"));
+        assert!(pres.move_slide(0, 2));
+        let titles: Vec<_> = pres.slides.iter().map(|slide| slide.title()).collect();
+        assert_eq!(titles, vec![Some("A"), Some("B"), Some("A")]);
+        assert_eq!(pres.slides[2].slide_id(), 256);
+        assert!(!pres.move_slide(0, 3));
+        assert!(pres.duplicate_slide(5).is_none());
+    }
+
+    #[test]
+    fn test_splice_sections_and_custom_shows() {
+        let xml = concat!(
+            r#"<p:presentation xmlns:p="p" xmlns:r="r"><p:sldIdLst><p:sldId id="256" r:id="rId7"/>"#,
+            r#"<p:sldId id="257" r:id="rId8"/><p:sldId id="258" r:id="rId9"/></p:sldIdLst>"#,
+            r#"<p:sldSz cx="9144000" cy="6858000"/><p:custShowLst><p:custShow name="Short" id="0">"#,
+            r#"<p:sldLst><p:sld r:id="rId9"/><p:sld r:id="rId8"/></p:sldLst></p:custShow></p:custShowLst>"#,
+            r#"<p:extLst><p:ext uri="{521415D9-36F7-43E2-AB2F-B90AF26B5E84}"><p14:sectionLst xmlns:p14="p14">"#,
+            r#"<p14:section name="Intro" id="{1}"><p14:sldIdLst><p14:sldId id="256"/><p14:sldId id="257"/></p14:sldIdLst></p14:section>"#,
+            r#"<p14:section name="End" id="{2}"><p14:sldIdLst><p14:sldId id="258"/></p14:sldIdLst></p14:section>"#,
+            r#"</p14:sectionLst></p:ext></p:extLst></p:presentation>"#
+        );
+        let mut pres = MutablePresentation::new();
+        for _ in 0..3 {
+            pres.add_slide().unwrap();
+        }
+
+        // Delete the second slide and duplicate the last one
+        assert!(pres.remove_slide(1));
+        assert_eq!(pres.duplicate_slide(1).unwrap().slide_id(), 259);
+        let rel_ids = ["rId7", "rId9", "rId10"].map(String::from);
+        let spliced = pres.splice_presentation_xml(xml, &rel_ids).unwrap();
+        assert!(spliced.contains(r#"<p:sldLst><p:sld r:id="rId9"/></p:sldLst>"#));
+        assert!(spliced.contains(
+            r#"<p14:section name="Intro" id="{1}"><p14:sldIdLst><p14:sldId id="256"/></p14:sldIdLst></p14:section>"#
+        ));
+        assert!(spliced.contains(
+            r#"<p14:section name="End" id="{2}"><p14:sldIdLst><p14:sldId id="258"/><p14:sldId id="259"/></p14:sldIdLst></p14:section>"#
+        ));
+
+        // A slide moved to the front joins the first section
+        assert!(pres.move_slide(2, 0));
+        let rel_ids = ["rId10", "rId7", "rId9"].map(String::from);
+        let spliced = pres.splice_presentation_xml(xml, &rel_ids).unwrap();
+        assert!(spliced.contains(
+            r#"<p14:sldIdLst><p14:sldId id="259"/><p14:sldId id="256"/></p14:sldIdLst>"#
+        ));
+        assert!(spliced.contains(
+            r#"<p14:section name="End" id="{2}"><p14:sldIdLst><p14:sldId id="258"/></p14:sldIdLst>"#
+        ));
+
+        // Removing every slide of a section leaves it empty
+        assert!(pres.remove_slide(2));
+        let spliced = pres
+            .splice_presentation_xml(xml, &["rId10", "rId7"].map(String::from))
+            .unwrap();
+        assert!(
+            spliced.contains(r#"<p14:section name="End" id="{2}"><p14:sldIdLst/></p14:section>"#)
+        );
+        assert!(spliced.contains(r#"<p:sldLst></p:sldLst>"#));
+    }
+
     #[test]
     fn test_xml_generation() {
         let mut pres = MutablePresentation::new();
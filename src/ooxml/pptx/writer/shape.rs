@@ -2,6 +2,8 @@
 use crate::ooxml::error::{OoxmlError, Result};
 use std::fmt::Write as FmtWrite;
 
+use super::loader::replace_text_body;
+
 // Import shared format types
 pub use super::super::format::{ImageFormat, TextFormat};
 
@@ -15,7 +17,7 @@ fn escape_xml(s: &str) -> String {
 }
 
 /// A shape on a slide (text box, image, etc.).
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct MutableShape {
     /// Shape ID
     pub(crate) shape_id: u32,
@@ -23,7 +25,7 @@ pub struct MutableShape {
     pub(crate) shape_type: ShapeType,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub(crate) enum ShapeType {
     TextBox {
         text: String,
@@ -58,6 +60,17 @@ pub(crate) enum ShapeType {
         height: i64,
         description: String,
     },
+    /// A shape loaded from an existing slide, written back as its original markup
+    Existing {
+        xml: String,
+        /// Local name of the element (`sp`, `pic`, `graphicFrame`, `grpSp`, ...)
+        kind: String,
+        name: String,
+        /// Placeholder type (`title`, `body`, ...)
+        placeholder: Option<String>,
+        text: Option<String>,
+        position: Option<(i64, i64, i64, i64)>,
+    },
 }
 
 impl MutableShape {
@@ -228,6 +241,92 @@ impl MutableShape {
         })
     }
 
+    /// Get the name of a shape loaded from an existing slide.
+    pub fn name(&self) -> Option<&str> {
+        match &self.shape_type {
+            ShapeType::Existing { name, .. } => Some(name.as_str()),
+            _ => None,
+        }
+    }
+
+    /// Get the placeholder type (`title`, `body`, ...) of a loaded placeholder shape.
+    pub fn placeholder_type(&self) -> Option<&str> {
+        match &self.shape_type {
+            ShapeType::Existing { placeholder, .. } => placeholder.as_deref(),
+            _ => None,
+        }
+    }
+
+    /// Get the text of the shape, with paragraphs separated by newlines.
+    pub fn text(&self) -> Option<&str> {
+        match &self.shape_type {
+            ShapeType::TextBox { text, .. } => Some(text.as_str()),
+            ShapeType::Existing { text, .. } => text.as_deref(),
+            _ => None,
+        }
+    }
+
+    /// Replace the text of the shape.
+    ///
+    /// Each line becomes a paragraph. Shapes loaded from an existing slide
+    /// keep their body properties and the formatting of their first
+    /// paragraph and run; shapes without a text body are left unchanged.
+    pub fn set_text(&mut self, new_text: &str) -> &mut Self {
+        match &mut self.shape_type {
+            ShapeType::TextBox { text, .. } => *text = new_text.to_string(),
+            ShapeType::Existing {
+                xml, kind, text, ..
+            } if kind == "sp" => {
+                if let Ok(Some(replaced)) = replace_text_body(xml, new_text) {
+                    *xml = replaced;
+                    *text = Some(new_text.to_string());
+                }
+            },
+            _ => {},
+        }
+        self
+    }
+
+    /// Get the position and size of the shape as `(x, y, width, height)` in EMUs.
+    pub fn position(&self) -> Option<(i64, i64, i64, i64)> {
+        match &self.shape_type {
+            ShapeType::TextBox {
+                x,
+                y,
+                width,
+                height,
+                ..
+            }
+            | ShapeType::Rectangle {
+                x,
+                y,
+                width,
+                height,
+                ..
+            }
+            | ShapeType::Ellipse {
+                x,
+                y,
+                width,
+                height,
+                ..
+            }
+            | ShapeType::Picture {
+                x,
+                y,
+                width,
+                height,
+                ..
+            } => Some((*x, *y, *width, *height)),
+            ShapeType::Existing { position, .. } => *position,
+        }
+    }
+
+    /// Check if this is a title placeholder.
+    pub(crate) fn is_title(&self) -> bool {
+        matches!(self.placeholder_type(), Some("title" | "ctrTitle"))
+    }
+
     /// Get image data if this shape is a picture.
     pub(crate) fn get_image_data(&self) -> Option<(&[u8], ImageFormat)> {
         match &self.shape_type {
@@ -441,6 +540,7 @@ impl MutableShape {
                 xml.push_str("</p:spPr>");
                 xml.push_str("</p:pic>");
             },
+            ShapeType::Existing { xml: markup, .. } => xml.push_str(markup),
         }
 
         Ok(())
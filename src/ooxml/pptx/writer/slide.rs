@@ -4,6 +4,7 @@ use std::fmt::Write as FmtWrite;
 
 // Import shared format types
 use super::super::format::ImageFormat;
+use super::loader::SlideSource;
 use super::shape::MutableShape;
 
 /// Escape XML special characters.
@@ -16,7 +17,7 @@ fn escape_xml(s: &str) -> String {
 }
 
 /// A mutable slide in a presentation.
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct MutableSlide {
     /// Slide ID (unique identifier)
     pub(crate) slide_id: u32,
@@ -28,6 +29,8 @@ pub struct MutableSlide {
     pub(crate) notes: Option<String>,
     /// Whether the slide has been modified
     pub(crate) modified: bool,
+    /// Original markup for slides loaded from an existing presentation
+    pub(crate) source: Option<SlideSource>,
 }
 
 impl MutableSlide {
@@ -39,6 +42,7 @@ impl MutableSlide {
             shapes: Vec::new(),
             notes: None,
             modified: false,
+            source: None,
         }
     }
 
@@ -48,7 +52,13 @@ impl MutableSlide {
     }
 
     /// Set the slide title.
+    ///
+    /// On slides loaded from an existing presentation the title placeholder
+    /// keeps its formatting.
     pub fn set_title(&mut self, title: &str) {
+        if let Some(shape) = self.shapes.iter_mut().find(|shape| shape.is_title()) {
+            shape.set_text(title);
+        }
         self.title = Some(title.to_string());
         self.modified = true;
     }
@@ -76,7 +86,7 @@ impl MutableSlide {
 
     /// Add a text box to the slide.
     pub fn add_text_box(&mut self, text: &str, x: i64, y: i64, width: i64, height: i64) {
-        let shape_id = (self.shapes.len() + 2) as u32;
+        let shape_id = self.next_shape_id();
         let shape = MutableShape::new_text_box(shape_id, text.to_string(), x, y, width, height);
         self.shapes.push(shape);
         self.modified = true;
@@ -91,7 +101,7 @@ impl MutableSlide {
         height: i64,
         fill_color: Option<String>,
     ) {
-        let shape_id = (self.shapes.len() + 2) as u32;
+        let shape_id = self.next_shape_id();
         let shape = MutableShape::new_rectangle(shape_id, x, y, width, height, fill_color);
         self.shapes.push(shape);
         self.modified = true;
@@ -106,7 +116,7 @@ impl MutableSlide {
         height: i64,
         fill_color: Option<String>,
     ) {
-        let shape_id = (self.shapes.len() + 2) as u32;
+        let shape_id = self.next_shape_id();
         let shape = MutableShape::new_ellipse(shape_id, x, y, width, height, fill_color);
         self.shapes.push(shape);
         self.modified = true;
@@ -128,7 +138,7 @@ impl MutableSlide {
         let format = ImageFormat::detect_from_bytes(&data)
             .ok_or_else(|| OoxmlError::InvalidFormat("Unknown image format".to_string()))?;
 
-        let shape_id = (self.shapes.len() + 2) as u32;
+        let shape_id = self.next_shape_id();
         let description = format!("Picture from {}", image_path);
         let shape =
             MutableShape::new_picture(shape_id, data, format, x, y, width, height, description)?;
@@ -151,7 +161,7 @@ impl MutableSlide {
         let format = ImageFormat::detect_from_bytes(&data)
             .ok_or_else(|| OoxmlError::InvalidFormat("Unknown image format".to_string()))?;
 
-        let shape_id = (self.shapes.len() + 2) as u32;
+        let shape_id = self.next_shape_id();
         let desc = description.unwrap_or_else(|| "Picture".to_string());
         let shape = MutableShape::new_picture(shape_id, data, format, x, y, width, height, desc)?;
         self.shapes.push(shape);
@@ -165,6 +175,43 @@ impl MutableSlide {
         self.shapes.len()
     }
 
+    /// Get a shape by index (0-based).
+    pub fn shape(&self, index: usize) -> Option<&MutableShape> {
+        self.shapes.get(index)
+    }
+
+    /// Get a mutable reference to a shape by index (0-based).
+    pub fn shape_mut(&mut self, index: usize) -> Option<&mut MutableShape> {
+        let shape = self.shapes.get_mut(index)?;
+        self.modified = true;
+        Some(shape)
+    }
+
+    /// Remove a shape by index (0-based).
+    ///
+    /// Returns `false` if there is no shape at `index`.
+    pub fn remove_shape(&mut self, index: usize) -> bool {
+        if index >= self.shapes.len() {
+            return false;
+        }
+        let shape = self.shapes.remove(index);
+        if shape.is_title() {
+            self.title = None;
+        }
+        self.modified = true;
+        true
+    }
+
+    /// Get an unused shape ID.
+    fn next_shape_id(&self) -> u32 {
+        self.shapes
+            .iter()
+            .map(|shape| shape.shape_id + 1)
+            .max()
+            .unwrap_or(2)
+            .max(2)
+    }
+
     /// Check if the slide has been modified.
     pub fn is_modified(&self) -> bool {
         self.modified
@@ -203,34 +250,48 @@ impl MutableSlide {
 
         xml.push_str(r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>"#);
 
-        xml.push_str(
-            r#"<p:sld xmlns:p="http://schemas.openxmlformats.org/presentationml/2006/main" "#,
-        );
-        xml.push_str(r#"xmlns:a="http://schemas.openxmlformats.org/drawingml/2006/main" "#);
-        xml.push_str(
-            r#"xmlns:r="http://schemas.openxmlformats.org/officeDocument/2006/relationships">"#,
-        );
-
-        xml.push_str("<p:cSld>");
-        xml.push_str("<p:spTree>");
-
-        // Write group shape properties (required)
-        xml.push_str("<p:nvGrpSpPr>");
-        xml.push_str(r#"<p:cNvPr id="1" name=""/>"#);
-        xml.push_str("<p:cNvGrpSpPr/>");
-        xml.push_str("<p:nvPr/>");
-        xml.push_str("</p:nvGrpSpPr>");
-        xml.push_str("<p:grpSpPr>");
-        xml.push_str("<a:xfrm>");
-        xml.push_str(r#"<a:off x="0" y="0"/>"#);
-        xml.push_str(r#"<a:ext cx="0" cy="0"/>"#);
-        xml.push_str(r#"<a:chOff x="0" y="0"/>"#);
-        xml.push_str(r#"<a:chExt cx="0" cy="0"/>"#);
-        xml.push_str("</a:xfrm>");
-        xml.push_str("</p:grpSpPr>");
+        if let Some(source) = &self.source {
+            write!(
+                xml,
+                "<p:sld{}><p:cSld{}>",
+                source.root_attributes, source.c_sld_attributes
+            )
+            .map_err(|e| OoxmlError::Xml(e.to_string()))?;
+            xml.push_str(&source.before_tree);
+            xml.push_str("<p:spTree>");
+            xml.push_str(&source.tree_properties);
+        } else {
+            xml.push_str(
+                r#"<p:sld xmlns:p="http://schemas.openxmlformats.org/presentationml/2006/main" "#,
+            );
+            xml.push_str(r#"xmlns:a="http://schemas.openxmlformats.org/drawingml/2006/main" "#);
+            xml.push_str(
+                r#"xmlns:r="http://schemas.openxmlformats.org/officeDocument/2006/relationships">"#,
+            );
+
+            xml.push_str("<p:cSld>");
+            xml.push_str("<p:spTree>");
+
+            // Write group shape properties (required)
+            xml.push_str("<p:nvGrpSpPr>");
+            xml.push_str(r#"<p:cNvPr id="1" name=""/>"#);
+            xml.push_str("<p:cNvGrpSpPr/>");
+            xml.push_str("<p:nvPr/>");
+            xml.push_str("</p:nvGrpSpPr>");
+            xml.push_str("<p:grpSpPr>");
+            xml.push_str("<a:xfrm>");
+            xml.push_str(r#"<a:off x="0" y="0"/>"#);
+            xml.push_str(r#"<a:ext cx="0" cy="0"/>"#);
+            xml.push_str(r#"<a:chOff x="0" y="0"/>"#);
+            xml.push_str(r#"<a:chExt cx="0" cy="0"/>"#);
+            xml.push_str("</a:xfrm>");
+            xml.push_str("</p:grpSpPr>");
+        }
 
-        // Write title placeholder if title is set
-        if let Some(ref title) = self.title {
+        // Write title placeholder if title is set and the slide has none yet
+        if let Some(ref title) = self.title
+            && !self.shapes.iter().any(|shape| shape.is_title())
+        {
             self.write_title_shape(&mut xml, title)?;
         }
 
@@ -250,9 +311,14 @@ impl MutableSlide {
         }
 
         xml.push_str("</p:spTree>");
-        xml.push_str("</p:cSld>");
-
-        xml.push_str(r#"<p:clrMapOvr><a:masterClrMapping/></p:clrMapOvr>"#);
+        if let Some(source) = &self.source {
+            xml.push_str(&source.after_tree);
+            xml.push_str("</p:cSld>");
+            xml.push_str(&source.after_c_sld);
+        } else {
+            xml.push_str("</p:cSld>");
+            xml.push_str(r#"<p:clrMapOvr><a:masterClrMapping/></p:clrMapOvr>"#);
+        }
         xml.push_str("</p:sld>");
 
         Ok(xml)
@@ -4,6 +4,7 @@ use crate::common::binary;
 use crate::ooxml::xlsb::cell::XlsbCell;
 use crate::ooxml::xlsb::error::XlsbResult;
 use crate::ooxml::xlsb::records::RecordIter;
+use crate::ooxml::xlsb::utils::error_text;
 use crate::sheet::CellValue;
 use std::io::{Read, Seek};
 
@@ -117,17 +118,7 @@ where
                     if self.buf.len() >= 9 {
                         let col = binary::read_u32_le_at(&self.buf, 0)?;
                         let error_code = self.buf[8];
-                        let error_msg = match error_code {
-                            0x00 => "#NULL!",
-                            0x07 => "#DIV/0!",
-                            0x0F => "#VALUE!",
-                            0x17 => "#REF!",
-                            0x1D => "#NAME?",
-                            0x24 => "#NUM!",
-                            0x2A => "#N/A",
-                            0x2B => "#GETTING_DATA",
-                            _ => "#ERR!",
-                        };
+                        let error_msg = error_text(error_code);
                         return Ok(Some(XlsbCell::new(
                             self.current_row,
                             col,
@@ -22,6 +22,9 @@ mod cell;
 /// XLSB cells reader
 mod cells_reader;
 
+/// Pivot cache and pivot table parsing
+mod pivot;
+
 /// Shared parsing utilities
 mod utils;
 
@@ -0,0 +1,852 @@
+//! Pivot caches and pivot tables stored as BIFF12 records.
+//!
+//! XLSB packages hold the same pivot parts as XLSX ones
+//! (`xl/pivotCache/pivotCacheDefinitionN.bin`, `pivotCacheRecordsN.bin` and
+//! `xl/pivotTables/pivotTableN.bin`), so they are read into the
+//! [`crate::ooxml::xlsx::pivot`] model. Calculated field formulas are stored
+//! as parsed formulas and are not decoded; pivot filters are not read.
+
+use crate::common::binary;
+use crate::ooxml::xlsb::error::{XlsbError, XlsbResult};
+use crate::ooxml::xlsb::records::{XlsbRecordIter, record_types, wide_str_with_len};
+use crate::ooxml::xlsb::utils::{cell_reference, error_text};
+use crate::ooxml::xlsx::parsers::pivot_parser::date_time_serial;
+use crate::ooxml::xlsx::pivot::{
+    CacheField, DataField, PageField, PivotAxis, PivotCache, PivotField, PivotFunction, PivotItem,
+    PivotSource, PivotTable,
+};
+use crate::sheet::CellValue;
+
+/// `BrtBeginPCDField` flag: the field is a column of the source data
+const PCD_FIELD_DATABASE_FIELD: u16 = 0x0004;
+/// `BrtBeginPCDField` flag: a caption follows the field name
+const PCD_FIELD_HAS_CAPTION: u16 = 0x0008;
+/// `BrtBeginPCDFSharedItems` flags describing the field's values
+const SHARED_ITEMS_HAS_DATE: u16 = 0x0004;
+const SHARED_ITEMS_HAS_STRING: u16 = 0x0008;
+const SHARED_ITEMS_IS_NUMERIC: u16 = 0x0040;
+/// `BrtBeginPCDSheetSource` flags
+const SHEET_SOURCE_HAS_SHEET: u8 = 0x01;
+const SHEET_SOURCE_HAS_REL_ID: u8 = 0x02;
+/// `BrtBeginSXVD` flags
+const SXVD_ROW_AXIS: u32 = 0x0001;
+const SXVD_COLUMN_AXIS: u32 = 0x0002;
+const SXVD_PAGE_AXIS: u32 = 0x0004;
+const SXVD_DATA_FIELD: u32 = 0x0008;
+/// `BrtBeginSXVI` flag: the item is filtered out
+const SXVI_HIDDEN: u16 = 0x0001;
+/// `BrtBeginSXPI` item selecting all (or several) items of a page field
+const SXPI_ALL_ITEMS: i32 = 0x0010_00FE;
+
+/// How a compact cache record (`BrtPCRecord`) stores the value of a field.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum RecordValue {
+    /// Index into the field's shared items
+    Index,
+    Number,
+    Date,
+    String,
+}
+
+/// Sequential reader over the fields of a record.
+struct Fields<'a> {
+    data: &'a [u8],
+    offset: usize,
+}
+
+impl<'a> Fields<'a> {
+    fn new(data: &'a [u8]) -> Self {
+        Fields { data, offset: 0 }
+    }
+
+    fn bytes(&mut self, len: usize) -> XlsbResult<&'a [u8]> {
+        let bytes =
+            self.data
+                .get(self.offset..self.offset + len)
+                .ok_or(XlsbError::InvalidLength {
+                    expected: self.offset + len,
+                    found: self.data.len(),
+                })?;
+        self.offset += len;
+        Ok(bytes)
+    }
+
+    fn skip(&mut self, len: usize) -> XlsbResult<()> {
+        self.bytes(len).map(|_| ())
+    }
+
+    fn u8(&mut self) -> XlsbResult<u8> {
+        Ok(self.bytes(1)?[0])
+    }
+
+    fn u16(&mut self) -> XlsbResult<u16> {
+        Ok(binary::read_u16_le_at(self.bytes(2)?, 0)?)
+    }
+
+    fn u32(&mut self) -> XlsbResult<u32> {
+        Ok(binary::read_u32_le_at(self.bytes(4)?, 0)?)
+    }
+
+    fn i32(&mut self) -> XlsbResult<i32> {
+        Ok(self.u32()? as i32)
+    }
+
+    fn f64(&mut self) -> XlsbResult<f64> {
+        Ok(binary::read_f64_le_at(self.bytes(8)?, 0)?)
+    }
+
+    /// Read an `XLWideString`.
+    fn string(&mut self) -> XlsbResult<String> {
+        let rest = self.data.get(self.offset..).unwrap_or_default();
+        let (string, consumed) = wide_str_with_len(rest)?;
+        self.offset += consumed;
+        Ok(string)
+    }
+
+    /// Read an `XLNullableWideString`, whose length is 0xFFFFFFFF when null.
+    fn nullable_string(&mut self) -> XlsbResult<Option<String>> {
+        let rest = self.data.get(self.offset..).unwrap_or_default();
+        if rest.len() >= 4 && binary::read_u32_le_at(rest, 0)? == u32::MAX {
+            self.offset += 4;
+            return Ok(None);
+        }
+        self.string().map(Some)
+    }
+
+    /// Read an `RfX` cell range as an A1 reference (e.g. "A1:D100").
+    fn range(&mut self) -> XlsbResult<String> {
+        let first_row = self.u32()?;
+        let last_row = self.u32()?;
+        let first_col = self.u32()?;
+        let last_col = self.u32()?;
+        Ok(format!(
+            "{}:{}",
+            cell_reference(first_row, first_col),
+            cell_reference(last_row, last_col)
+        ))
+    }
+
+    /// Read a cache item date (`PCDIDateTime`).
+    fn date(&mut self) -> XlsbResult<CellValue> {
+        let year = self.u16()?;
+        let month = self.u16()?;
+        let day = self.u8()?;
+        let (hour, minute, second) = (self.u8()?, self.u8()?, self.u8()?);
+        let serial = chrono::NaiveDate::from_ymd_opt(year.into(), month.into(), day.into())
+            .and_then(|date| date.and_hms_opt(hour.into(), minute.into(), second.into()))
+            .and_then(date_time_serial);
+        Ok(serial.map_or(CellValue::Empty, CellValue::DateTime))
+    }
+}
+
+/// Parse the pivot cache list of `workbook.bin` into (cache ID, relationship ID) pairs.
+pub(crate) fn parse_pivot_cache_ids(workbook: &[u8]) -> XlsbResult<Vec<(u32, String)>> {
+    let mut caches = Vec::new();
+    for record in XlsbRecordIter::new(workbook) {
+        let record = record?;
+        if record.header.record_type != record_types::BEGIN_PIVOT_CACHE_ID {
+            continue;
+        }
+        let mut fields = Fields::new(&record.data);
+        let id = fields.u32()?;
+        if let Some(r_id) = fields.nullable_string()? {
+            caches.push((id, r_id));
+        }
+    }
+    Ok(caches)
+}
+
+/// Parse a pivot cache definition part and, when present, its records part.
+///
+/// Records hold values for the source fields only; indexed values are
+/// resolved against the fields' shared items.
+pub(crate) fn parse_pivot_cache(
+    definition: &[u8],
+    records: Option<&[u8]>,
+    id: u32,
+) -> XlsbResult<PivotCache> {
+    let mut cache = PivotCache {
+        id,
+        source: PivotSource::default(),
+        fields: Vec::new(),
+        records: Vec::new(),
+    };
+    let mut values = Vec::new();
+    let mut in_shared_items = false;
+
+    for record in XlsbRecordIter::new(definition) {
+        let record = record?;
+        let mut fields = Fields::new(&record.data);
+        match record.header.record_type {
+            record_types::BEGIN_PCD_SOURCE => {
+                cache.source.source_type = match fields.i32()? {
+                    1 => "external",
+                    2 => "consolidation",
+                    3 => "scenario",
+                    _ => "worksheet",
+                }
+                .to_string();
+            },
+            record_types::BEGIN_PCD_SHEET_SOURCE => {
+                let is_name = fields.u8()? != 0;
+                let is_builtin_name = fields.u8()? != 0;
+                let flags = fields.u8()?;
+                if flags & SHEET_SOURCE_HAS_SHEET != 0 {
+                    cache.source.sheet = Some(fields.string()?);
+                }
+                if flags & SHEET_SOURCE_HAS_REL_ID != 0 {
+                    fields.string()?;
+                }
+                if is_name {
+                    let name = fields.string()?;
+                    cache.source.name = Some(if is_builtin_name {
+                        format!("_xlnm.{}", name)
+                    } else {
+                        name
+                    });
+                } else {
+                    cache.source.range = Some(fields.range()?);
+                }
+            },
+            record_types::BEGIN_PCD_FIELD => {
+                let flags = fields.u16()?;
+                let number_format_id = fields.u32()?;
+                // SQL type, hierarchy, level and mapping count
+                fields.skip(14)?;
+                let name = fields.string()?;
+                if flags & PCD_FIELD_HAS_CAPTION != 0 {
+                    fields.string()?;
+                }
+                cache.fields.push(CacheField {
+                    name,
+                    shared_items: Vec::new(),
+                    database_field: flags & PCD_FIELD_DATABASE_FIELD != 0,
+                    formula: None,
+                    number_format_id: Some(number_format_id),
+                });
+                values.push(RecordValue::String);
+            },
+            record_types::BEGIN_PCDF_SHARED_ITEMS => {
+                let flags = fields.u16()?;
+                if let Some(value) = values.last_mut() {
+                    *value = if flags & SHARED_ITEMS_IS_NUMERIC != 0 {
+                        RecordValue::Number
+                    } else if flags & SHARED_ITEMS_HAS_DATE != 0
+                        && flags & SHARED_ITEMS_HAS_STRING == 0
+                    {
+                        RecordValue::Date
+                    } else {
+                        RecordValue::String
+                    };
+                }
+                in_shared_items = true;
+            },
+            record_types::END_PCDF_SHARED_ITEMS => in_shared_items = false,
+            record_type if in_shared_items => {
+                if let (Some(item), Some(field)) = (
+                    item_value(record_type, &mut fields)?,
+                    cache.fields.last_mut(),
+                ) {
+                    field.shared_items.push(item);
+                }
+            },
+            _ => {},
+        }
+    }
+
+    // Fields with shared items store indices into them
+    for (value, field) in values.iter_mut().zip(&cache.fields) {
+        if !field.shared_items.is_empty() {
+            *value = RecordValue::Index;
+        }
+    }
+    if let Some(records) = records {
+        cache.records = parse_pivot_cache_records(records, &cache, &values)?;
+    }
+    Ok(cache)
+}
+
+/// Parse a pivot cache records part into rows with one value per field of `cache`.
+///
+/// Records are either compact (`BrtPCRecord`), storing each value as laid
+/// out by `values`, or typed (`BrtPCRecordDt`), followed by one item record
+/// per value.
+fn parse_pivot_cache_records(
+    data: &[u8],
+    cache: &PivotCache,
+    values: &[RecordValue],
+) -> XlsbResult<Vec<Vec<CellValue>>> {
+    let columns: Vec<usize> = (0..cache.fields.len())
+        .filter(|&index| cache.fields[index].database_field)
+        .collect();
+    let shared_item = |field: usize, index: i32| {
+        usize::try_from(index)
+            .ok()
+            .and_then(|index| cache.fields[field].shared_items.get(index))
+            .cloned()
+            .unwrap_or(CellValue::Empty)
+    };
+    let mut records = Vec::new();
+    let mut typed: Option<Vec<CellValue>> = None;
+    let mut column = 0usize;
+
+    for record in XlsbRecordIter::new(data) {
+        let record = record?;
+        let mut fields = Fields::new(&record.data);
+        match record.header.record_type {
+            record_types::PC_RECORD => {
+                records.extend(typed.take());
+                let mut row = vec![CellValue::Empty; cache.fields.len()];
+                for &field in &columns {
+                    row[field] = match values[field] {
+                        RecordValue::Index => shared_item(field, fields.i32()?),
+                        RecordValue::Number => CellValue::Float(fields.f64()?),
+                        RecordValue::Date => fields.date()?,
+                        RecordValue::String => CellValue::String(fields.string()?),
+                    };
+                }
+                records.push(row);
+            },
+            record_types::PC_RECORD_DT => {
+                records.extend(typed.take());
+                typed = Some(vec![CellValue::Empty; cache.fields.len()]);
+                column = 0;
+            },
+            record_type => {
+                let (Some(row), Some(&field)) = (typed.as_mut(), columns.get(column)) else {
+                    continue;
+                };
+                let value = if record_type == record_types::PC_ITEM_INDEX {
+                    Some(shared_item(field, fields.i32()?))
+                } else {
+                    item_value(record_type, &mut fields)?
+                };
+                if let Some(value) = value {
+                    row[field] = value;
+                    column += 1;
+                }
+            },
+        }
+    }
+    records.extend(typed.take());
+
+    Ok(records)
+}
+
+/// Parse a pivot table part.
+pub(crate) fn parse_pivot_table(data: &[u8]) -> XlsbResult<PivotTable> {
+    let mut table = PivotTable {
+        name: String::new(),
+        cache_id: 0,
+        location: String::new(),
+        fields: Vec::new(),
+        row_fields: Vec::new(),
+        column_fields: Vec::new(),
+        data_on_rows: false,
+        page_fields: Vec::new(),
+        data_fields: Vec::new(),
+        filters: Vec::new(),
+    };
+
+    for record in XlsbRecordIter::new(data) {
+        let record = record?;
+        let mut fields = Fields::new(&record.data);
+        match record.header.record_type {
+            record_types::BEGIN_SX_VIEW => {
+                fields.skip(12)?;
+                table.data_on_rows = fields.u8()? == 1;
+                // Page wrap, versions, data position, auto format and chart format
+                fields.skip(15)?;
+                table.cache_id = fields.u32()?;
+                table.name = fields.string()?;
+            },
+            record_types::BEGIN_SX_LOCATION => table.location = fields.range()?,
+            record_types::BEGIN_SXVD => {
+                let flags = fields.u32()?;
+                let axis = if flags & SXVD_ROW_AXIS != 0 {
+                    Some(PivotAxis::Row)
+                } else if flags & SXVD_COLUMN_AXIS != 0 {
+                    Some(PivotAxis::Column)
+                } else if flags & SXVD_PAGE_AXIS != 0 {
+                    Some(PivotAxis::Page)
+                } else {
+                    None
+                };
+                table.fields.push(PivotField {
+                    name: None,
+                    axis,
+                    data_field: flags & SXVD_DATA_FIELD != 0,
+                    items: Vec::new(),
+                });
+            },
+            record_types::BEGIN_SXVI => {
+                let item_type = fields.u8()?;
+                let flags = fields.u16()?;
+                let index = fields.i32()?;
+                if let Some(field) = table.fields.last_mut() {
+                    field.items.push(PivotItem {
+                        index: usize::try_from(index).ok().filter(|_| item_type == 0),
+                        hidden: flags & SXVI_HIDDEN != 0,
+                        item_type: item_type_name(item_type).map(str::to_string),
+                    });
+                }
+            },
+            record_type @ (record_types::BEGIN_ISXVD_RWS | record_types::BEGIN_ISXVD_COLS) => {
+                let count = fields.u32()?;
+                let axis = if record_type == record_types::BEGIN_ISXVD_RWS {
+                    &mut table.row_fields
+                } else {
+                    &mut table.column_fields
+                };
+                for _ in 0..count {
+                    // Field -2 is the values pseudo-field
+                    if let Ok(field) = usize::try_from(fields.i32()?) {
+                        axis.push(field);
+                    }
+                }
+            },
+            record_types::BEGIN_SXPI => {
+                let field = fields.i32()?;
+                let item = fields.i32()?;
+                // Hierarchy
+                fields.skip(4)?;
+                let name = if fields.u8()? == 1 {
+                    Some(fields.string()?)
+                } else {
+                    None
+                };
+                table.page_fields.push(PageField {
+                    field: usize::try_from(field).unwrap_or(0),
+                    item: usize::try_from(item)
+                        .ok()
+                        .filter(|_| item != SXPI_ALL_ITEMS),
+                    name,
+                });
+            },
+            record_types::BEGIN_SXDI => {
+                let field = fields.i32()?;
+                let function = fields.i32()?;
+                let show_data_as = fields.i32()?;
+                // Base field and base item
+                fields.skip(8)?;
+                let number_format_id = fields.u32()?;
+                let name = if fields.u8()? == 1 {
+                    Some(fields.string()?)
+                } else {
+                    None
+                };
+                table.data_fields.push(DataField {
+                    name,
+                    field: usize::try_from(field).unwrap_or(0),
+                    function: function_from_code(function),
+                    show_data_as: show_data_as_name(show_data_as).map(str::to_string),
+                    number_format_id: Some(number_format_id),
+                });
+            },
+            _ => {},
+        }
+    }
+
+    Ok(table)
+}
+
+/// Read the value of a cache item record; `None` for other records.
+fn item_value(record_type: u16, fields: &mut Fields) -> XlsbResult<Option<CellValue>> {
+    Ok(Some(match record_type {
+        record_types::PC_ITEM_MISSING | record_types::PC_ITEMA_MISSING => CellValue::Empty,
+        record_types::PC_ITEM_DOUBLE | record_types::PC_ITEMA_DOUBLE => {
+            CellValue::Float(fields.f64()?)
+        },
+        record_types::PC_ITEM_BOOL | record_types::PC_ITEMA_BOOL => {
+            CellValue::Bool(fields.u8()? != 0)
+        },
+        record_types::PC_ITEM_ERROR | record_types::PC_ITEMA_ERROR => {
+            CellValue::Error(error_text(fields.u8()?).to_string())
+        },
+        record_types::PC_ITEM_STRING | record_types::PC_ITEMA_STRING => {
+            CellValue::String(fields.string()?)
+        },
+        record_types::PC_ITEM_DATE | record_types::PC_ITEMA_DATE => fields.date()?,
+        _ => return Ok(None),
+    }))
+}
+
+/// Subtotal kind of a `BrtBeginSXVI` item type; `None` for items holding a value.
+fn item_type_name(item_type: u8) -> Option<&'static str> {
+    Some(match item_type {
+        0x01 => "default",
+        0x02 => "sum",
+        0x03 => "countA",
+        0x04 => "avg",
+        0x05 => "max",
+        0x06 => "min",
+        0x07 => "product",
+        0x08 => "count",
+        0x09 => "stdDev",
+        0x0A => "stdDevP",
+        0x0B => "var",
+        0x0C => "varP",
+        0x0D => "grand",
+        0x0E => "blank",
+        _ => return None,
+    })
+}
+
+/// Aggregation of a `BrtBeginSXDI` data field.
+fn function_from_code(code: i32) -> PivotFunction {
+    match code {
+        1 => PivotFunction::Count,
+        2 => PivotFunction::Average,
+        3 => PivotFunction::Max,
+        4 => PivotFunction::Min,
+        5 => PivotFunction::Product,
+        6 => PivotFunction::CountNums,
+        7 => PivotFunction::StdDev,
+        8 => PivotFunction::StdDevP,
+        9 => PivotFunction::Var,
+        10 => PivotFunction::VarP,
+        _ => PivotFunction::Sum,
+    }
+}
+
+/// Calculation of a `BrtBeginSXDI` data field; `None` for plain values.
+fn show_data_as_name(code: i32) -> Option<&'static str> {
+    Some(match code {
+        1 => "difference",
+        2 => "percent",
+        3 => "percentDiff",
+        4 => "runTotal",
+        5 => "percentOfRow",
+        6 => "percentOfCol",
+        7 => "percentOfTotal",
+        8 => "index",
+        _ => return None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Encode a record with its variable-length header.
+    fn record(record_type: u16, data: &[u8]) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        if record_type < 0x80 {
+            bytes.push(record_type as u8);
+        } else {
+            bytes.push((record_type & 0x7F) as u8 | 0x80);
+            bytes.push((record_type >> 7) as u8);
+        }
+        let mut len = data.len();
+        loop {
+            let byte = (len & 0x7F) as u8;
+            len >>= 7;
+            if len == 0 {
+                bytes.push(byte);
+                break;
+            }
+            bytes.push(byte | 0x80);
+        }
+        bytes.extend_from_slice(data);
+        bytes
+    }
+
+    fn wide(text: &str) -> Vec<u8> {
+        let units: Vec<u16> = text.encode_utf16().collect();
+        let mut bytes = (units.len() as u32).to_le_bytes().to_vec();
+        bytes.extend(units.iter().flat_map(|unit| unit.to_le_bytes()));
+        bytes
+    }
+
+    fn range(first_row: u32, last_row: u32, first_col: u32, last_col: u32) -> Vec<u8> {
+        [first_row, last_row, first_col, last_col]
+            .iter()
+            .flat_map(|value| value.to_le_bytes())
+            .collect()
+    }
+
+    fn cache_field(name: &str, flags: u16) -> Vec<u8> {
+        let mut data = flags.to_le_bytes().to_vec();
+        data.extend([0; 18]);
+        data.extend(wide(name));
+        record(record_types::BEGIN_PCD_FIELD, &data)
+    }
+
+    /// A cache of Region (shared strings), Units (numbers), Date (dates) and
+    /// a calculated field, sourced from Data!A1:C4.
+    fn definition() -> Vec<u8> {
+        let mut sheet_source = vec![0, 0, SHEET_SOURCE_HAS_SHEET];
+        sheet_source.extend(wide("Data"));
+        sheet_source.extend(range(0, 3, 0, 2));
+
+        [
+            record(record_types::BEGIN_PCD_SOURCE, &0i32.to_le_bytes()),
+            record(record_types::BEGIN_PCD_SHEET_SOURCE, &sheet_source),
+            cache_field("Region", PCD_FIELD_DATABASE_FIELD),
+            record(
+                record_types::BEGIN_PCDF_SHARED_ITEMS,
+                &SHARED_ITEMS_HAS_STRING.to_le_bytes(),
+            ),
+            record(record_types::PC_ITEM_STRING, &wide("East")),
+            record(record_types::PC_ITEM_STRING, &wide("West")),
+            record(record_types::END_PCDF_SHARED_ITEMS, &[]),
+            cache_field("Units", PCD_FIELD_DATABASE_FIELD),
+            record(
+                record_types::BEGIN_PCDF_SHARED_ITEMS,
+                &SHARED_ITEMS_IS_NUMERIC.to_le_bytes(),
+            ),
+            record(record_types::END_PCDF_SHARED_ITEMS, &[]),
+            cache_field("Date", PCD_FIELD_DATABASE_FIELD),
+            record(
+                record_types::BEGIN_PCDF_SHARED_ITEMS,
+                &SHARED_ITEMS_HAS_DATE.to_le_bytes(),
+            ),
+            record(record_types::END_PCDF_SHARED_ITEMS, &[]),
+            cache_field("Double", 0),
+        ]
+        .concat()
+    }
+
+    /// Three records: two compact ones and a typed one.
+    fn records() -> Vec<u8> {
+        // Year, month, day, hour, minute and second
+        let date = |day: u8| {
+            [
+                &2024u16.to_le_bytes()[..],
+                &3u16.to_le_bytes(),
+                &[day, 0, 0, 0],
+            ]
+            .concat()
+        };
+        let compact = |region: i32, units: f64, day: u8| {
+            let mut data = region.to_le_bytes().to_vec();
+            data.extend(units.to_le_bytes());
+            data.extend(date(day));
+            record(record_types::PC_RECORD, &data)
+        };
+        [
+            compact(0, 12.0, 1),
+            compact(1, 3.0, 2),
+            record(record_types::PC_RECORD_DT, &[]),
+            record(record_types::PC_ITEM_INDEX, &0i32.to_le_bytes()),
+            record(record_types::PC_ITEM_DOUBLE, &5.0f64.to_le_bytes()),
+            record(record_types::PC_ITEM_MISSING, &[]),
+        ]
+        .concat()
+    }
+
+    #[test]
+    fn test_parse_pivot_cache() {
+        let cache = parse_pivot_cache(&definition(), Some(&records()), 5).unwrap();
+        assert_eq!(cache.id, 5);
+        assert_eq!(cache.source.source_type, "worksheet");
+        assert_eq!(cache.source.sheet.as_deref(), Some("Data"));
+        assert_eq!(cache.source.range.as_deref(), Some("A1:C4"));
+        assert_eq!(cache.fields.len(), 4);
+        assert_eq!(
+            cache.fields[0].shared_items,
+            [
+                CellValue::String("East".to_string()),
+                CellValue::String("West".to_string())
+            ]
+        );
+        assert!(cache.fields[2].database_field && !cache.fields[3].database_field);
+
+        assert_eq!(cache.records.len(), 3);
+        assert_eq!(
+            cache.records[1],
+            [
+                CellValue::String("West".to_string()),
+                CellValue::Float(3.0),
+                CellValue::DateTime(45353.0),
+                CellValue::Empty
+            ]
+        );
+        assert_eq!(cache.records[0][2], CellValue::DateTime(45352.0));
+        assert_eq!(
+            cache.records[2][..3],
+            [
+                CellValue::String("East".to_string()),
+                CellValue::Float(5.0),
+                CellValue::Empty
+            ]
+        );
+        assert_eq!(cache.to_rows()[0].len(), 3);
+    }
+
+    #[test]
+    fn test_parse_pivot_cache_ids() {
+        let mut cache_id = 7u32.to_le_bytes().to_vec();
+        cache_id.extend(wide("rId3"));
+        let workbook = [
+            record(record_types::BEGIN_BUNDLE_SHS, &[]),
+            record(record_types::END_BUNDLE_SHS, &[]),
+            record(record_types::BEGIN_PIVOT_CACHE_ID, &cache_id),
+        ]
+        .concat();
+        assert_eq!(
+            parse_pivot_cache_ids(&workbook).unwrap(),
+            [(7, "rId3".to_string())]
+        );
+    }
+
+    /// A table with Region on rows (East hidden), Units summed and averaged,
+    /// and Date as a report filter.
+    fn table() -> Vec<u8> {
+        let mut view = vec![0; 12];
+        view.push(2);
+        view.extend([0; 15]);
+        view.extend(5u32.to_le_bytes());
+        view.extend(wide("PivotTable1"));
+        let field = |flags: u32| {
+            let mut data = flags.to_le_bytes().to_vec();
+            data.extend([0; 16]);
+            record(record_types::BEGIN_SXVD, &data)
+        };
+        let item = |item_type: u8, flags: u16, index: i32| {
+            let mut data = vec![item_type];
+            data.extend(flags.to_le_bytes());
+            data.extend(index.to_le_bytes());
+            record(record_types::BEGIN_SXVI, &data)
+        };
+        let data_field = |function: i32, show_data_as: i32, name: &str| {
+            let mut data: Vec<u8> = [1, function, show_data_as, 0, 0, 10]
+                .iter()
+                .flat_map(|value| value.to_le_bytes())
+                .collect();
+            data.push(1);
+            data.extend(wide(name));
+            record(record_types::BEGIN_SXDI, &data)
+        };
+        let mut page_field: Vec<u8> = [2, 0, -1]
+            .iter()
+            .flat_map(|value: &i32| value.to_le_bytes())
+            .collect();
+        page_field.push(0);
+        let fields = |indices: &[i32]| {
+            let mut data = (indices.len() as u32).to_le_bytes().to_vec();
+            data.extend(indices.iter().flat_map(|index| index.to_le_bytes()));
+            data
+        };
+
+        [
+            record(record_types::BEGIN_SX_VIEW, &view),
+            record(record_types::BEGIN_SX_LOCATION, &range(2, 5, 0, 1)),
+            field(SXVD_ROW_AXIS),
+            item(0, 0, 1),
+            item(0, SXVI_HIDDEN, 0),
+            item(1, 0, 0),
+            field(SXVD_DATA_FIELD),
+            field(SXVD_PAGE_AXIS),
+            item(0, 0, 0),
+            field(0),
+            record(record_types::BEGIN_ISXVD_RWS, &fields(&[0])),
+            record(record_types::BEGIN_ISXVD_COLS, &fields(&[-2])),
+            record(record_types::BEGIN_SXPI, &page_field),
+            data_field(0, 0, "Sum of Units"),
+            data_field(2, 7, "Average of Units"),
+        ]
+        .concat()
+    }
+
+    #[test]
+    fn test_parse_pivot_table() {
+        let table = parse_pivot_table(&table()).unwrap();
+        assert_eq!((table.name.as_str(), table.cache_id), ("PivotTable1", 5));
+        assert_eq!(table.location, "A3:B6");
+        assert_eq!(table.fields.len(), 4);
+        assert_eq!(table.fields[0].axis, Some(PivotAxis::Row));
+        assert!(table.fields[0].items[1].hidden);
+        assert_eq!(
+            table.fields[0].items[2],
+            PivotItem {
+                index: None,
+                hidden: false,
+                item_type: Some("default".to_string())
+            }
+        );
+        assert!(table.fields[1].data_field);
+        assert_eq!(table.fields[2].axis, Some(PivotAxis::Page));
+        assert_eq!(table.row_fields, [0]);
+        assert!(table.column_fields.is_empty() && !table.data_on_rows);
+        assert_eq!(
+            table.page_fields,
+            [PageField {
+                field: 2,
+                item: Some(0),
+                name: None
+            }]
+        );
+        assert_eq!(table.data_fields.len(), 2);
+        assert_eq!(table.data_fields[0].function, PivotFunction::Sum);
+        assert_eq!(table.data_fields[0].name.as_deref(), Some("Sum of Units"));
+        assert_eq!(table.data_fields[1].function, PivotFunction::Average);
+        assert_eq!(
+            table.data_fields[1].show_data_as.as_deref(),
+            Some("percentOfTotal")
+        );
+        assert_eq!(table.data_fields[1].number_format_id, Some(10));
+
+        let cache = parse_pivot_cache(&definition(), None, 5).unwrap();
+        assert_eq!(
+            table.item_value(&cache, 0, 0),
+            Some(&CellValue::String("West".to_string()))
+        );
+        assert_eq!(table.item_value(&cache, 0, 2), None);
+    }
+
+    #[test]
+    fn test_workbook_pivot_tables() {
+        use crate::ooxml::opc::constants::relationship_type as rt;
+        use crate::ooxml::opc::{BlobPart, OpcPackage, PackURI, Part};
+        use crate::ooxml::xlsb::XlsbWorkbook;
+
+        let part = |name: &str, blob: Vec<u8>| {
+            BlobPart::new(PackURI::new(name).unwrap(), String::new(), blob)
+        };
+        let mut sheet = [1u32.to_le_bytes(), 0u32.to_le_bytes()].concat();
+        sheet.extend(wide("rId1"));
+        sheet.extend(wide("Summary"));
+        let mut cache_id = 5u32.to_le_bytes().to_vec();
+        cache_id.extend(wide("rId2"));
+
+        let mut workbook = part(
+            "/xl/workbook.bin",
+            [
+                record(record_types::BUNDLE_SH, &sheet),
+                record(record_types::END_BUNDLE_SHS, &[]),
+                record(record_types::BEGIN_PIVOT_CACHE_ID, &cache_id),
+            ]
+            .concat(),
+        );
+        workbook.relate_to("worksheets/sheet1.bin", rt::WORKSHEET);
+        workbook.relate_to(
+            "pivotCache/pivotCacheDefinition1.bin",
+            rt::PIVOT_CACHE_DEFINITION,
+        );
+        let mut worksheet = part("/xl/worksheets/sheet1.bin", Vec::new());
+        worksheet.relate_to("../pivotTables/pivotTable1.bin", rt::PIVOT_TABLE);
+        let mut cache_definition = part("/xl/pivotCache/pivotCacheDefinition1.bin", definition());
+        cache_definition.relate_to("pivotCacheRecords1.bin", rt::PIVOT_CACHE_RECORDS);
+
+        let mut package = OpcPackage::new();
+        package.add_part(Box::new(workbook));
+        package.add_part(Box::new(worksheet));
+        package.add_part(Box::new(cache_definition));
+        package.add_part(Box::new(part(
+            "/xl/pivotCache/pivotCacheRecords1.bin",
+            records(),
+        )));
+        package.add_part(Box::new(part("/xl/pivotTables/pivotTable1.bin", table())));
+        let workbook = XlsbWorkbook::from_opc_package(package).unwrap();
+
+        let caches = workbook.pivot_caches().unwrap();
+        assert_eq!(caches.len(), 1);
+        assert_eq!(caches[0].id, 5);
+        assert_eq!(caches[0].to_rows().len(), 4);
+        let tables = workbook.pivot_tables().unwrap();
+        assert_eq!(tables.len(), 1);
+        assert_eq!(tables[0].0, "Summary");
+        assert_eq!(tables[0].1.cache_id, caches[0].id);
+    }
+}
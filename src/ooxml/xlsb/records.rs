@@ -130,6 +130,39 @@ pub mod record_types {
     pub const END_BUNDLE_SHS: u16 = 144; // 0x90
     pub const BEGIN_SHEET_DATA: u16 = 145; // 0x91
     pub const END_SHEET_DATA: u16 = 146; // 0x92
+
+    // Pivot caches (workbook, pivotCacheDefinitionN.bin, pivotCacheRecordsN.bin)
+    pub const BEGIN_PIVOT_CACHE_ID: u16 = 386; // 0x182
+    pub const BEGIN_PCD_SOURCE: u16 = 185; // 0xB9
+    pub const BEGIN_PCD_SHEET_SOURCE: u16 = 187; // 0xBB
+    pub const BEGIN_PCD_FIELD: u16 = 183; // 0xB7
+    pub const BEGIN_PCDF_SHARED_ITEMS: u16 = 189; // 0xBD
+    pub const END_PCDF_SHARED_ITEMS: u16 = 190; // 0xBE
+    pub const PC_ITEM_MISSING: u16 = 20; // 0x14
+    pub const PC_ITEM_DOUBLE: u16 = 21; // 0x15
+    pub const PC_ITEM_BOOL: u16 = 22; // 0x16
+    pub const PC_ITEM_ERROR: u16 = 23; // 0x17
+    pub const PC_ITEM_STRING: u16 = 24; // 0x18
+    pub const PC_ITEM_DATE: u16 = 25; // 0x19
+    pub const PC_ITEM_INDEX: u16 = 26; // 0x1A
+    pub const PC_ITEMA_MISSING: u16 = 27; // 0x1B
+    pub const PC_ITEMA_DOUBLE: u16 = 28; // 0x1C
+    pub const PC_ITEMA_BOOL: u16 = 29; // 0x1D
+    pub const PC_ITEMA_ERROR: u16 = 30; // 0x1E
+    pub const PC_ITEMA_STRING: u16 = 31; // 0x1F
+    pub const PC_ITEMA_DATE: u16 = 32; // 0x20
+    pub const PC_RECORD: u16 = 33; // 0x21
+    pub const PC_RECORD_DT: u16 = 34; // 0x22
+
+    // Pivot tables (pivotTableN.bin)
+    pub const BEGIN_SX_VIEW: u16 = 207; // 0xCF
+    pub const BEGIN_SXVD: u16 = 211; // 0xD3
+    pub const BEGIN_SXVI: u16 = 218; // 0xDA
+    pub const BEGIN_SXPI: u16 = 289; // 0x121
+    pub const BEGIN_SXDI: u16 = 293; // 0x125
+    pub const BEGIN_ISXVD_RWS: u16 = 309; // 0x135
+    pub const BEGIN_ISXVD_COLS: u16 = 311; // 0x137
+    pub const BEGIN_SX_LOCATION: u16 = 314; // 0x13A
 }
 
 /// Decode wide string (UTF-16LE) from XLSB format
@@ -65,6 +65,21 @@ pub fn parse_cell_reference(ref_str: &str) -> XlsbResult<(u32, u32)> {
     Ok((row - 1, col)) // Make 0-based
 }
 
+/// Text of a BIFF12 error code (e.g. 0x07 is "#DIV/0!")
+pub fn error_text(code: u8) -> &'static str {
+    match code {
+        0x00 => "#NULL!",
+        0x07 => "#DIV/0!",
+        0x0F => "#VALUE!",
+        0x17 => "#REF!",
+        0x1D => "#NAME?",
+        0x24 => "#NUM!",
+        0x2A => "#N/A",
+        0x2B => "#GETTING_DATA",
+        _ => "#ERR!",
+    }
+}
+
 /// Convert serial date to datetime
 #[allow(dead_code)]
 pub fn excel_date_to_datetime(serial: f64, is_1904: bool) -> Option<chrono::NaiveDateTime> {
@@ -1,9 +1,14 @@
 //! Workbook implementation for XLSB files
 
+use crate::common::{EmbeddedImage, ImageAnchor};
+use crate::ooxml::common::collect_pictures;
 use crate::ooxml::opc::OpcPackage;
+use crate::ooxml::opc::constants::relationship_type;
 use crate::ooxml::xlsb::error::XlsbResult;
+use crate::ooxml::xlsb::pivot::{parse_pivot_cache, parse_pivot_cache_ids, parse_pivot_table};
 use crate::ooxml::xlsb::records::{XlsbRecordIter, record_types};
 use crate::ooxml::xlsb::worksheet::XlsbWorksheet;
+use crate::ooxml::xlsx::pivot::{PivotCache, PivotTable};
 use crate::sheet::{Worksheet as SheetTrait, WorksheetIterator};
 use std::io::{BufReader, Cursor, Read, Seek};
 
@@ -113,6 +118,106 @@ impl XlsbWorkbook {
         Self::read_worksheet(cursor, name.clone(), &self.shared_strings)
     }
 
+    /// Get all pictures placed on the worksheets.
+    ///
+    /// Drawings in XLSB packages are DrawingML parts just like in XLSX, so
+    /// pictures are anchored to the top-left cell of their `xdr:from` marker.
+    pub fn images(&self) -> XlsbResult<Vec<EmbeddedImage>> {
+        let mut images = Vec::new();
+
+        for (index, name) in self.worksheet_names.iter().enumerate() {
+            // Same sheet part naming assumption as get_worksheet()
+            let sheet_uri =
+                crate::ooxml::opc::PackURI::new(format!("/xl/worksheets/sheet{}.bin", index + 1))?;
+            let Ok(sheet_part) = self.package.get_part(&sheet_uri) else {
+                continue;
+            };
+
+            for rel in sheet_part.rels().iter() {
+                if rel.reltype() != relationship_type::DRAWING || rel.is_external() {
+                    continue;
+                }
+                let Ok(drawing_part) = self.package.get_part(&rel.target_partname()?) else {
+                    continue;
+                };
+                images.extend(collect_pictures(drawing_part, &self.package, |picture| {
+                    let (row, column) = picture.cell.unwrap_or_default();
+                    ImageAnchor::Cell {
+                        sheet: name.clone(),
+                        row,
+                        column,
+                    }
+                })?);
+            }
+        }
+
+        Ok(images)
+    }
+
+    /// Get the pivot caches with their fields and cached records.
+    ///
+    /// The records are a snapshot of the source data taken when the cache
+    /// was last refreshed, so the dataset behind a pivot table can be
+    /// recovered even if its source sheet was removed.
+    pub fn pivot_caches(&self) -> XlsbResult<Vec<PivotCache>> {
+        let workbook_uri = crate::ooxml::opc::PackURI::new("/xl/workbook.bin")?;
+        let workbook_part = self.package.get_part(&workbook_uri)?;
+
+        let mut caches = Vec::new();
+        for (id, r_id) in parse_pivot_cache_ids(workbook_part.blob())? {
+            let Some(rel) = workbook_part
+                .rels()
+                .get(&r_id)
+                .filter(|rel| rel.reltype() == relationship_type::PIVOT_CACHE_DEFINITION)
+            else {
+                continue;
+            };
+            let Ok(definition_part) = self.package.get_part(&rel.target_partname()?) else {
+                continue;
+            };
+            let records_part = definition_part
+                .rels()
+                .iter()
+                .find(|rel| rel.reltype() == relationship_type::PIVOT_CACHE_RECORDS)
+                .and_then(|rel| self.package.get_part(&rel.target_partname().ok()?).ok());
+            caches.push(parse_pivot_cache(
+                definition_part.blob(),
+                records_part.map(|part| part.blob()),
+                id,
+            )?);
+        }
+        Ok(caches)
+    }
+
+    /// Get the pivot tables of all worksheets, each paired with its sheet name.
+    ///
+    /// Each table refers to its data by [`PivotTable::cache_id`]; see
+    /// [`XlsbWorkbook::pivot_caches`].
+    pub fn pivot_tables(&self) -> XlsbResult<Vec<(String, PivotTable)>> {
+        let mut tables = Vec::new();
+
+        for (index, name) in self.worksheet_names.iter().enumerate() {
+            // Same sheet part naming assumption as get_worksheet()
+            let sheet_uri =
+                crate::ooxml::opc::PackURI::new(format!("/xl/worksheets/sheet{}.bin", index + 1))?;
+            let Ok(sheet_part) = self.package.get_part(&sheet_uri) else {
+                continue;
+            };
+
+            for rel in sheet_part.rels().iter() {
+                if rel.reltype() != relationship_type::PIVOT_TABLE || rel.is_external() {
+                    continue;
+                }
+                let Ok(table_part) = self.package.get_part(&rel.target_partname()?) else {
+                    continue;
+                };
+                tables.push((name.clone(), parse_pivot_table(table_part.blob())?));
+            }
+        }
+
+        Ok(tables)
+    }
+
     /// Read shared strings from SST
     fn read_shared_strings(
         iter: &mut XlsbRecordIter<impl Read>,
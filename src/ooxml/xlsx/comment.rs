@@ -0,0 +1,29 @@
+//! Cell comments: notes and threaded comments.
+//!
+//! Notes are stored in a `xl/commentsN.xml` part and drawn by the sheet's
+//! VML part (`xl/drawings/vmlDrawingN.vml`). Threaded comments add a
+//! `xl/threadedComments/threadedCommentN.xml` part whose authors are listed
+//! in the workbook's `xl/persons/person.xml`; each thread is also written as
+//! a note, which is what versions of Excel without threaded comments show.
+
+/// A comment on a cell.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Comment {
+    /// 1-based row of the cell
+    pub row: u32,
+    /// 1-based column of the cell
+    pub col: u32,
+    pub author: String,
+    pub text: String,
+    /// Whether the comment starts a thread rather than being a note
+    pub threaded: bool,
+    /// Replies to a threaded comment, oldest first
+    pub replies: Vec<CommentReply>,
+}
+
+/// A reply in a comment thread.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CommentReply {
+    pub author: String,
+    pub text: String,
+}
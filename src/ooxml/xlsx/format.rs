@@ -1,12 +1,167 @@
 //! Shared formatting types for XLSX (used in both reading and writing).
 
+use super::styles::{Alignment, Border, BorderStyle, Fill, Font, Protection};
+
 /// Cell format information.
+///
+/// Colors are given as `AARRGGBB` hex strings, or as `theme:N` / `indexed:N`
+/// to refer to a theme color or a legacy palette entry.
 #[derive(Debug, Clone, Default)]
 pub struct CellFormat {
     pub font: Option<CellFont>,
     pub fill: Option<CellFill>,
     pub border: Option<CellBorder>,
     pub number_format: Option<String>,
+    pub alignment: Option<CellAlignment>,
+    pub protection: Option<CellProtection>,
+    /// Named style the format is based on; properties left unset here are
+    /// taken from the style
+    pub style_name: Option<String>,
+}
+
+impl CellFormat {
+    /// Fill in properties left unset from the format of a named style.
+    pub(crate) fn based_on(&self, style: &CellFormat) -> CellFormat {
+        CellFormat {
+            font: self.font.clone().or_else(|| style.font.clone()),
+            fill: self.fill.clone().or_else(|| style.fill.clone()),
+            border: self.border.clone().or_else(|| style.border.clone()),
+            number_format: self
+                .number_format
+                .clone()
+                .or_else(|| style.number_format.clone()),
+            alignment: self.alignment.clone().or_else(|| style.alignment.clone()),
+            protection: self.protection.or(style.protection),
+            style_name: self.style_name.clone(),
+        }
+    }
+}
+
+/// A reusable cell style that cells refer to by name.
+#[derive(Debug, Clone)]
+pub struct NamedStyle {
+    pub name: String,
+    pub format: CellFormat,
+    /// Index of the built-in style this one stands for (e.g. 3 for "Comma")
+    pub builtin_id: Option<u32>,
+}
+
+impl NamedStyle {
+    /// Create a custom named style.
+    pub fn new(name: &str, format: CellFormat) -> Self {
+        Self {
+            name: name.to_string(),
+            format,
+            builtin_id: None,
+        }
+    }
+}
+
+/// Text alignment, wrapping and rotation within a cell.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct CellAlignment {
+    pub horizontal: Option<HorizontalAlignment>,
+    pub vertical: Option<VerticalAlignment>,
+    pub wrap_text: bool,
+    pub shrink_to_fit: bool,
+    /// Indent level, in steps of about three spaces
+    pub indent: Option<u32>,
+    /// Degrees 0-90 rotate counterclockwise, 91-180 clockwise (90 + angle),
+    /// and 255 stacks the letters vertically
+    pub text_rotation: Option<u32>,
+}
+
+/// Horizontal alignment of cell content.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum HorizontalAlignment {
+    General,
+    Left,
+    Center,
+    Right,
+    Fill,
+    Justify,
+    CenterContinuous,
+    Distributed,
+}
+
+impl HorizontalAlignment {
+    pub(crate) fn as_str(&self) -> &'static str {
+        match self {
+            Self::General => "general",
+            Self::Left => "left",
+            Self::Center => "center",
+            Self::Right => "right",
+            Self::Fill => "fill",
+            Self::Justify => "justify",
+            Self::CenterContinuous => "centerContinuous",
+            Self::Distributed => "distributed",
+        }
+    }
+
+    pub(crate) fn parse(value: &str) -> Option<Self> {
+        Some(match value {
+            "general" => Self::General,
+            "left" => Self::Left,
+            "center" => Self::Center,
+            "right" => Self::Right,
+            "fill" => Self::Fill,
+            "justify" => Self::Justify,
+            "centerContinuous" => Self::CenterContinuous,
+            "distributed" => Self::Distributed,
+            _ => return None,
+        })
+    }
+}
+
+/// Vertical alignment of cell content.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum VerticalAlignment {
+    Top,
+    Center,
+    Bottom,
+    Justify,
+    Distributed,
+}
+
+impl VerticalAlignment {
+    pub(crate) fn as_str(&self) -> &'static str {
+        match self {
+            Self::Top => "top",
+            Self::Center => "center",
+            Self::Bottom => "bottom",
+            Self::Justify => "justify",
+            Self::Distributed => "distributed",
+        }
+    }
+
+    pub(crate) fn parse(value: &str) -> Option<Self> {
+        Some(match value {
+            "top" => Self::Top,
+            "center" => Self::Center,
+            "bottom" => Self::Bottom,
+            "justify" => Self::Justify,
+            "distributed" => Self::Distributed,
+            _ => return None,
+        })
+    }
+}
+
+/// Cell protection, enforced once the worksheet is protected.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct CellProtection {
+    /// Whether the cell can't be edited (Excel's default)
+    pub locked: bool,
+    /// Whether the formula is hidden from the formula bar
+    pub hidden: bool,
+}
+
+impl Default for CellProtection {
+    fn default() -> Self {
+        Self {
+            locked: true,
+            hidden: false,
+        }
+    }
 }
 
 /// Font properties for a cell.
@@ -35,16 +190,49 @@ impl Default for CellFont {
 }
 
 /// Fill properties for a cell.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Default)]
 pub struct CellFill {
     pub pattern_type: CellFillPatternType,
     pub fg_color: Option<String>,
     pub bg_color: Option<String>,
+    /// Gradient drawn instead of the pattern
+    pub gradient: Option<CellGradientFill>,
+}
+
+impl CellFill {
+    /// Create a solid fill.
+    pub fn solid(color: &str) -> Self {
+        Self {
+            pattern_type: CellFillPatternType::Solid,
+            fg_color: Some(color.to_string()),
+            ..Default::default()
+        }
+    }
+
+    /// Create a gradient fill.
+    pub fn gradient(gradient: CellGradientFill) -> Self {
+        Self {
+            gradient: Some(gradient),
+            ..Default::default()
+        }
+    }
+}
+
+/// A linear or path gradient fill.
+#[derive(Debug, Clone, Default)]
+pub struct CellGradientFill {
+    /// Path gradients spread out from the center instead of along a line
+    pub path: bool,
+    /// Angle of a linear gradient in degrees, clockwise from left-to-right
+    pub degree: f64,
+    /// Color stops as (position from 0.0 to 1.0, color)
+    pub stops: Vec<(f64, String)>,
 }
 
 /// Cell fill pattern types.
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, Default)]
 pub enum CellFillPatternType {
+    #[default]
     None,
     Solid,
     Gray125,
@@ -88,7 +276,12 @@ pub struct CellBorder {
     pub right: Option<CellBorderSide>,
     pub top: Option<CellBorderSide>,
     pub bottom: Option<CellBorderSide>,
+    /// Line drawn by `diagonal_up` and/or `diagonal_down`
     pub diagonal: Option<CellBorderSide>,
+    /// Draw the diagonal from bottom-left to top-right
+    pub diagonal_up: bool,
+    /// Draw the diagonal from top-left to bottom-right
+    pub diagonal_down: bool,
 }
 
 /// Border side properties.
@@ -139,6 +332,155 @@ impl CellBorderLineStyle {
     }
 }
 
+/// Convert a parsed color to the form used by the writer.
+///
+/// RGB colors lose the parser's `#` prefix, theme and indexed colors are kept,
+/// and automatic colors are dropped.
+pub(crate) fn writer_color(color: &Option<String>) -> Option<String> {
+    let color = color.as_deref()?;
+    match color.strip_prefix('#') {
+        Some(rgb) => Some(rgb.to_string()),
+        None if color.starts_with("theme:") || color.starts_with("indexed:") => {
+            Some(color.to_string())
+        },
+        None => None,
+    }
+}
+
+impl From<&Font> for CellFont {
+    fn from(font: &Font) -> Self {
+        CellFont {
+            name: font.name.clone(),
+            size: font.size,
+            bold: font.bold,
+            italic: font.italic,
+            underline: font.underline.as_deref().is_some_and(|u| u != "none"),
+            color: writer_color(&font.color),
+        }
+    }
+}
+
+impl CellFill {
+    /// Convert a parsed fill; `none` gives `None`.
+    pub(crate) fn from_fill(fill: &Fill) -> Option<Self> {
+        let (pattern_type, fg_color, bg_color) = match fill {
+            Fill::None => return None,
+            Fill::Pattern {
+                pattern_type,
+                fg_color,
+                bg_color,
+            } => (pattern_type, fg_color, bg_color),
+            Fill::Gradient {
+                gradient_type,
+                degree,
+                stops,
+            } => {
+                return Some(CellFill::gradient(CellGradientFill {
+                    path: gradient_type.as_deref() == Some("path"),
+                    degree: degree.unwrap_or_default(),
+                    stops: stops
+                        .iter()
+                        .filter_map(|(position, color)| {
+                            Some((*position, writer_color(&Some(color.clone()))?))
+                        })
+                        .collect(),
+                }));
+            },
+        };
+
+        let pattern_type = match pattern_type.as_str() {
+            "solid" => CellFillPatternType::Solid,
+            "gray125" => CellFillPatternType::Gray125,
+            "darkGray" => CellFillPatternType::DarkGray,
+            "mediumGray" => CellFillPatternType::MediumGray,
+            "lightGray" => CellFillPatternType::LightGray,
+            "gray0625" => CellFillPatternType::Gray0625,
+            "darkHorizontal" => CellFillPatternType::DarkHorizontal,
+            "darkVertical" => CellFillPatternType::DarkVertical,
+            "darkDown" => CellFillPatternType::DarkDown,
+            "darkUp" => CellFillPatternType::DarkUp,
+            "darkGrid" => CellFillPatternType::DarkGrid,
+            "darkTrellis" => CellFillPatternType::DarkTrellis,
+            _ => return None,
+        };
+
+        Some(CellFill {
+            pattern_type,
+            fg_color: writer_color(fg_color),
+            bg_color: writer_color(bg_color),
+            gradient: None,
+        })
+    }
+}
+
+impl From<&BorderStyle> for CellBorderSide {
+    fn from(side: &BorderStyle) -> Self {
+        let style = match side.style.as_str() {
+            "thin" => CellBorderLineStyle::Thin,
+            "medium" => CellBorderLineStyle::Medium,
+            "dashed" => CellBorderLineStyle::Dashed,
+            "dotted" => CellBorderLineStyle::Dotted,
+            "thick" => CellBorderLineStyle::Thick,
+            "double" => CellBorderLineStyle::Double,
+            "hair" => CellBorderLineStyle::Hair,
+            "mediumDashed" => CellBorderLineStyle::MediumDashed,
+            "dashDot" => CellBorderLineStyle::DashDot,
+            "mediumDashDot" => CellBorderLineStyle::MediumDashDot,
+            "dashDotDot" => CellBorderLineStyle::DashDotDot,
+            "mediumDashDotDot" => CellBorderLineStyle::MediumDashDotDot,
+            "slantDashDot" => CellBorderLineStyle::SlantDashDot,
+            _ => CellBorderLineStyle::None,
+        };
+
+        CellBorderSide {
+            style,
+            color: writer_color(&side.color),
+        }
+    }
+}
+
+impl From<&Border> for CellBorder {
+    fn from(border: &Border) -> Self {
+        CellBorder {
+            left: border.left.as_ref().map(CellBorderSide::from),
+            right: border.right.as_ref().map(CellBorderSide::from),
+            top: border.top.as_ref().map(CellBorderSide::from),
+            bottom: border.bottom.as_ref().map(CellBorderSide::from),
+            diagonal: border.diagonal.as_ref().map(CellBorderSide::from),
+            diagonal_up: matches!(border.diagonal_direction, Some(1 | 3)),
+            diagonal_down: matches!(border.diagonal_direction, Some(2 | 3)),
+        }
+    }
+}
+
+impl From<&Alignment> for CellAlignment {
+    fn from(alignment: &Alignment) -> Self {
+        CellAlignment {
+            horizontal: alignment
+                .horizontal
+                .as_deref()
+                .and_then(HorizontalAlignment::parse),
+            vertical: alignment
+                .vertical
+                .as_deref()
+                .and_then(VerticalAlignment::parse),
+            wrap_text: alignment.wrap_text,
+            shrink_to_fit: alignment.shrink_to_fit,
+            indent: alignment.indent,
+            text_rotation: alignment.text_rotation,
+        }
+    }
+}
+
+impl From<&Protection> for CellProtection {
+    fn from(protection: &Protection) -> Self {
+        CellProtection {
+            locked: protection.locked,
+            hidden: protection.hidden,
+        }
+    }
+}
+
 /// Chart types supported in Excel.
 #[derive(Debug, Clone, Copy)]
 pub enum ChartType {
@@ -205,7 +547,6 @@ pub enum DataValidationOperator {
 }
 
 impl DataValidationOperator {
-    #[allow(dead_code)]
     pub(crate) fn as_str(&self) -> &'static str {
         match self {
             Self::Between => "between",
@@ -218,6 +559,21 @@ impl DataValidationOperator {
             Self::LessThanOrEqual => "lessThanOrEqual",
         }
     }
+
+    /// Parse an `operator` attribute value.
+    pub(crate) fn parse(value: &str) -> Option<Self> {
+        Some(match value {
+            "between" => Self::Between,
+            "notBetween" => Self::NotBetween,
+            "equal" => Self::Equal,
+            "notEqual" => Self::NotEqual,
+            "greaterThan" => Self::GreaterThan,
+            "lessThan" => Self::LessThan,
+            "greaterThanOrEqual" => Self::GreaterThanOrEqual,
+            "lessThanOrEqual" => Self::LessThanOrEqual,
+            _ => return None,
+        })
+    }
 }
 
 /// Data validation rule.
@@ -232,3 +588,122 @@ pub struct DataValidation {
     pub error_title: Option<String>,
     pub error_message: Option<String>,
 }
+
+/// Conditional formatting rules applied to a set of ranges.
+#[derive(Debug, Clone)]
+pub struct ConditionalFormatting {
+    /// Space-separated cell ranges (e.g. "A1:A10 C1:C10")
+    pub range: String,
+    /// Rules, evaluated by priority
+    pub rules: Vec<ConditionalFormatRule>,
+}
+
+/// A single conditional formatting rule (`cfRule`).
+#[derive(Debug, Clone)]
+pub struct ConditionalFormatRule {
+    pub rule_type: ConditionalFormatType,
+    /// Differential format applied when the rule matches
+    pub format: Option<CellFormat>,
+    /// Evaluation order across the sheet, lowest first
+    pub priority: u32,
+    /// Whether lower-priority rules are skipped when this one matches
+    pub stop_if_true: bool,
+}
+
+/// Conditional formatting rule types.
+#[derive(Debug, Clone)]
+pub enum ConditionalFormatType {
+    /// Compare the cell value with one or two formulas
+    CellIs {
+        operator: DataValidationOperator,
+        formulas: Vec<String>,
+    },
+    /// Apply when the formula evaluates to true
+    Expression {
+        formula: String,
+    },
+    /// Two- or three-color gradient; `colors` pairs up with `points`
+    ColorScale {
+        points: Vec<ConditionalFormatValue>,
+        colors: Vec<String>,
+    },
+    /// Bar proportional to the value between `min` and `max`
+    DataBar {
+        min: ConditionalFormatValue,
+        max: ConditionalFormatValue,
+        color: String,
+    },
+    /// Icons from a named set (e.g. "3TrafficLights1") split at `points`
+    IconSet {
+        icon_set: String,
+        points: Vec<ConditionalFormatValue>,
+        reverse: bool,
+        show_value: bool,
+    },
+    /// Top or bottom `rank` values, or percent of values
+    Top10 {
+        rank: u32,
+        percent: bool,
+        bottom: bool,
+    },
+    DuplicateValues,
+    UniqueValues,
+    /// Cells containing `text`
+    ContainsText {
+        text: String,
+    },
+}
+
+/// A threshold of a color scale, data bar or icon set (`cfvo`).
+#[derive(Debug, Clone)]
+pub struct ConditionalFormatValue {
+    pub value_type: ConditionalFormatValueType,
+    /// Number, percent or formula, depending on the type
+    pub value: Option<String>,
+}
+
+impl ConditionalFormatValue {
+    /// Create a threshold.
+    pub fn new(value_type: ConditionalFormatValueType, value: Option<&str>) -> Self {
+        Self {
+            value_type,
+            value: value.map(str::to_string),
+        }
+    }
+}
+
+/// Threshold types.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConditionalFormatValueType {
+    Min,
+    Max,
+    Number,
+    Percent,
+    Percentile,
+    Formula,
+}
+
+impl ConditionalFormatValueType {
+    pub(crate) fn as_str(&self) -> &'static str {
+        match self {
+            Self::Min => "min",
+            Self::Max => "max",
+            Self::Number => "num",
+            Self::Percent => "percent",
+            Self::Percentile => "percentile",
+            Self::Formula => "formula",
+        }
+    }
+
+    pub(crate) fn parse(value: &str) -> Option<Self> {
+        Some(match value {
+            "min" => Self::Min,
+            "max" => Self::Max,
+            "num" => Self::Number,
+            "percent" => Self::Percent,
+            "percentile" => Self::Percentile,
+            "formula" => Self::Formula,
+            _ => return None,
+        })
+    }
+}
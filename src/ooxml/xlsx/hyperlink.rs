@@ -0,0 +1,59 @@
+//! Cell hyperlinks.
+//!
+//! Links to a location in the workbook are stored on the `hyperlink`
+//! element; links to a URL, file or e-mail address are stored as external
+//! relationships of the worksheet part.
+
+/// A hyperlink on a cell or range.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Hyperlink {
+    /// Cell or range the link covers (e.g. "A1" or "A1:C1")
+    pub range: String,
+    pub target: HyperlinkTarget,
+    /// Text shown when hovering over the link
+    pub tooltip: Option<String>,
+    /// Text of the link; written to the cell when it is empty
+    pub display: Option<String>,
+    /// Relationship ID of an external target in the worksheet part
+    pub(crate) relationship_id: Option<String>,
+}
+
+impl Hyperlink {
+    /// Link a cell or range to a URL, file path or `mailto:` address.
+    pub fn external(range: &str, url: &str) -> Self {
+        Self::new(range, HyperlinkTarget::External(url.to_string()))
+    }
+
+    /// Link a cell or range to a location in the workbook, such as
+    /// `Sheet2!A1` or a defined name.
+    pub fn internal(range: &str, location: &str) -> Self {
+        Self::new(range, HyperlinkTarget::Internal(location.to_string()))
+    }
+
+    fn new(range: &str, target: HyperlinkTarget) -> Self {
+        Self {
+            range: range.to_string(),
+            target,
+            tooltip: None,
+            display: None,
+            relationship_id: None,
+        }
+    }
+
+    /// The link target as shown to users.
+    pub fn address(&self) -> &str {
+        match &self.target {
+            HyperlinkTarget::External(url) => url,
+            HyperlinkTarget::Internal(location) => location,
+        }
+    }
+}
+
+/// Where a hyperlink points.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum HyperlinkTarget {
+    /// URL, file path or `mailto:` address
+    External(String),
+    /// Location in the workbook (e.g. "Sheet2!A1" or a defined name)
+    Internal(String),
+}
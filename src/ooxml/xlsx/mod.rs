@@ -31,10 +31,17 @@
 //! ```
 
 pub mod cell;
+pub mod comment;
 pub mod format;
+pub mod hyperlink;
+pub mod page_setup;
 pub mod parsers;
+pub mod picture;
+pub mod pivot;
+pub mod protection;
 pub mod shared_strings;
 pub mod styles;
+pub mod table;
 pub mod template;
 pub mod workbook;
 pub mod worksheet;
@@ -42,13 +49,34 @@ pub mod writer;
 
 // Re-export main types for convenience
 pub use cell::Cell;
+pub use comment::{Comment, CommentReply};
 // Re-export shared formatting types
 pub use format::{
-    CellBorder, CellBorderLineStyle, CellBorderSide, CellFill, CellFillPatternType, CellFont,
-    CellFormat, Chart, ChartType, DataValidation, DataValidationOperator, DataValidationType,
+    CellAlignment, CellBorder, CellBorderLineStyle, CellBorderSide, CellFill, CellFillPatternType,
+    CellFont, CellFormat, CellGradientFill, CellProtection, Chart, ChartType,
+    ConditionalFormatRule, ConditionalFormatType, ConditionalFormatValue,
+    ConditionalFormatValueType, ConditionalFormatting, DataValidation, DataValidationOperator,
+    DataValidationType, HorizontalAlignment, NamedStyle, VerticalAlignment,
 };
+pub use hyperlink::{Hyperlink, HyperlinkTarget};
+pub use page_setup::{
+    HeaderFooter, PageLayout, PageMargins, PageOrientation, PageSetup, PrintOptions, PrintTitles,
+};
+pub use picture::{Picture, PictureAnchor};
+pub use pivot::{
+    CacheField, DataField, PageField, PivotAxis, PivotCache, PivotField, PivotFilter,
+    PivotFunction, PivotItem, PivotSource, PivotTable,
+};
+pub use protection::{PasswordHash, SheetProtection, WorkbookProtection};
 pub use shared_strings::SharedStrings;
-pub use styles::{Alignment, Border, BorderStyle, CellStyle, Fill, Font, NumberFormat, Styles};
+pub use styles::{
+    Alignment, Border, BorderStyle, CellStyle, Fill, Font, NamedCellStyle, NumberFormat,
+    Protection, Styles,
+};
+pub use table::{
+    AutoFilter, ColumnFilter, CustomFilter, FilterColumn, FilterOperator, Table, TableColumn,
+    TableStyle, TotalsFunction, resolve_structured_references,
+};
 pub use workbook::Workbook;
 pub use worksheet::Worksheet;
 // Re-export writer types
@@ -0,0 +1,273 @@
+//! Page layout and print settings for Excel worksheets.
+//!
+//! These types mirror the worksheet's `pageSetup`, `pageMargins`,
+//! `printOptions` and `headerFooter` elements and are used both when reading
+//! and when writing. Print areas and print titles are stored by Excel as the
+//! sheet-scoped defined names `_xlnm.Print_Area` and `_xlnm.Print_Titles`.
+
+/// Defined name holding a sheet's print area.
+pub(crate) const PRINT_AREA: &str = "_xlnm.Print_Area";
+/// Defined name holding a sheet's repeating print rows and columns.
+pub(crate) const PRINT_TITLES: &str = "_xlnm.Print_Titles";
+
+/// Page layout settings of a worksheet.
+///
+/// Settings left as `None` are not written, so Excel uses its defaults.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct PageLayout {
+    pub setup: Option<PageSetup>,
+    pub margins: Option<PageMargins>,
+    pub print_options: Option<PrintOptions>,
+    pub header_footer: Option<HeaderFooter>,
+}
+
+/// Page orientation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PageOrientation {
+    Portrait,
+    Landscape,
+}
+
+impl PageOrientation {
+    pub(crate) fn as_str(&self) -> &'static str {
+        match self {
+            Self::Portrait => "portrait",
+            Self::Landscape => "landscape",
+        }
+    }
+
+    pub(crate) fn parse(value: &str) -> Option<Self> {
+        match value {
+            "portrait" => Some(Self::Portrait),
+            "landscape" => Some(Self::Landscape),
+            _ => None,
+        }
+    }
+}
+
+/// Paper, orientation and scaling (`pageSetup`).
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PageSetup {
+    pub orientation: Option<PageOrientation>,
+    /// Paper size code (1 = Letter, 9 = A4, ...)
+    pub paper_size: Option<u32>,
+    /// Print scaling in percent (10-400), used unless `fit_to_page` is set
+    pub scale: Option<u32>,
+    /// Scale the sheet to `fit_to_width` by `fit_to_height` pages
+    pub fit_to_page: bool,
+    /// Number of pages wide; 0 means as many as needed
+    pub fit_to_width: Option<u32>,
+    /// Number of pages tall; 0 means as many as needed
+    pub fit_to_height: Option<u32>,
+    /// Number of the first printed page, instead of 1
+    pub first_page_number: Option<u32>,
+}
+
+impl PageSetup {
+    /// Scale the sheet to fit `width` pages wide by `height` pages tall.
+    ///
+    /// Pass 0 for a dimension that may use as many pages as needed, e.g.
+    /// `PageSetup::fit_to(1, 0)` for one page wide.
+    pub fn fit_to(width: u32, height: u32) -> Self {
+        Self {
+            fit_to_page: true,
+            fit_to_width: Some(width),
+            fit_to_height: Some(height),
+            ..Default::default()
+        }
+    }
+}
+
+/// Page margins in inches (`pageMargins`).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PageMargins {
+    pub left: f64,
+    pub right: f64,
+    pub top: f64,
+    pub bottom: f64,
+    pub header: f64,
+    pub footer: f64,
+}
+
+impl Default for PageMargins {
+    /// Excel's "Normal" margins.
+    fn default() -> Self {
+        Self {
+            left: 0.7,
+            right: 0.7,
+            top: 0.75,
+            bottom: 0.75,
+            header: 0.3,
+            footer: 0.3,
+        }
+    }
+}
+
+/// Printing of grid lines and headings, and page centering (`printOptions`).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct PrintOptions {
+    pub grid_lines: bool,
+    /// Print row numbers and column letters
+    pub headings: bool,
+    pub horizontal_centered: bool,
+    pub vertical_centered: bool,
+}
+
+/// Page headers and footers (`headerFooter`).
+///
+/// Texts use Excel's formatting codes, e.g. `&LQuarterly report&RPage &P of &N`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HeaderFooter {
+    /// Header of every page, or of odd pages if `different_odd_even` is set
+    pub odd_header: Option<String>,
+    pub odd_footer: Option<String>,
+    pub even_header: Option<String>,
+    pub even_footer: Option<String>,
+    /// Header of the first page if `different_first` is set
+    pub first_header: Option<String>,
+    pub first_footer: Option<String>,
+    pub different_odd_even: bool,
+    pub different_first: bool,
+    pub scale_with_document: bool,
+    pub align_with_margins: bool,
+}
+
+impl Default for HeaderFooter {
+    fn default() -> Self {
+        Self {
+            odd_header: None,
+            odd_footer: None,
+            even_header: None,
+            even_footer: None,
+            first_header: None,
+            first_footer: None,
+            different_odd_even: false,
+            different_first: false,
+            scale_with_document: true,
+            align_with_margins: true,
+        }
+    }
+}
+
+impl HeaderFooter {
+    /// Use the same header and footer on every page.
+    pub fn new(header: Option<&str>, footer: Option<&str>) -> Self {
+        Self {
+            odd_header: header.map(str::to_string),
+            odd_footer: footer.map(str::to_string),
+            ..Default::default()
+        }
+    }
+}
+
+/// Rows and columns repeated on every printed page.
+///
+/// Rows and columns are 1-based and inclusive.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct PrintTitles {
+    /// First and last row repeated at the top of each page
+    pub rows: Option<(u32, u32)>,
+    /// First and last column repeated at the left of each page
+    pub columns: Option<(u32, u32)>,
+}
+
+impl PrintTitles {
+    /// Read the areas of a `_xlnm.Print_Titles` formula, such as `$1:$2,$A:$A`.
+    pub(crate) fn from_areas<'a>(areas: impl IntoIterator<Item = &'a str>) -> Self {
+        let mut titles = Self::default();
+        for area in areas {
+            let Some((start, end)) = area.split_once(':') else {
+                continue;
+            };
+            let (start, end) = (start.trim_start_matches('$'), end.trim_start_matches('$'));
+            if let (Ok(start), Ok(end)) = (start.parse(), end.parse()) {
+                titles.rows = Some((start, end));
+            } else if let (Some(start), Some(end)) = (column_number(start), column_number(end)) {
+                titles.columns = Some((start, end));
+            }
+        }
+        titles
+    }
+
+    /// The areas of the `_xlnm.Print_Titles` formula, rows first.
+    pub(crate) fn areas(&self) -> Vec<String> {
+        let mut areas = Vec::new();
+        if let Some((start, end)) = self.rows {
+            areas.push(format!("${}:${}", start, end));
+        }
+        if let Some((start, end)) = self.columns {
+            areas.push(format!(
+                "${}:${}",
+                column_letters(start),
+                column_letters(end)
+            ));
+        }
+        areas
+    }
+}
+
+/// Split a defined name formula into its areas without sheet names.
+///
+/// `'My Sheet'!$A$1:$B$2,'My Sheet'!$D$1` gives `$A$1:$B$2` and `$D$1`.
+pub(crate) fn formula_areas(formula: &str) -> Vec<&str> {
+    let mut areas = Vec::new();
+    let mut quoted = false;
+    let mut start = 0;
+    for (index, c) in formula.char_indices() {
+        match c {
+            '\'' => quoted = !quoted,
+            ',' if !quoted => {
+                areas.push(&formula[start..index]);
+                start = index + 1;
+            },
+            _ => {},
+        }
+    }
+    areas.push(&formula[start..]);
+
+    areas
+        .into_iter()
+        .map(|area| area.rsplit_once('!').map_or(area, |(_, area)| area).trim())
+        .filter(|area| !area.is_empty())
+        .collect()
+}
+
+/// Convert column letters to a 1-based column number.
+fn column_number(letters: &str) -> Option<u32> {
+    if letters.is_empty() || !letters.bytes().all(|b| b.is_ascii_alphabetic()) {
+        return None;
+    }
+    Some(letters.bytes().fold(0, |number, b| {
+        number * 26 + u32::from(b.to_ascii_uppercase() - b'A' + 1)
+    }))
+}
+
+/// Convert a 1-based column number to letters.
+fn column_letters(mut col: u32) -> String {
+    let mut letters = Vec::new();
+    while col > 0 {
+        col -= 1;
+        letters.push(b'A' + (col % 26) as u8);
+        col /= 26;
+    }
+    letters.reverse();
+    String::from_utf8(letters).unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_print_titles_areas() {
+        let areas = formula_areas("'Q1, 2024'!$1:$3,'Q1, 2024'!$A:$B");
+        assert_eq!(areas, vec!["$1:$3", "$A:$B"]);
+
+        let titles = PrintTitles::from_areas(areas);
+        assert_eq!(titles.rows, Some((1, 3)));
+        assert_eq!(titles.columns, Some((1, 2)));
+        assert_eq!(titles.areas(), vec!["$1:$3", "$A:$B"]);
+
+        assert_eq!(formula_areas("Data!$A$1:$D$20"), vec!["$A$1:$D$20"]);
+    }
+}
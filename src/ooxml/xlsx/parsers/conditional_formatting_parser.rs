@@ -0,0 +1,313 @@
+//! Parser for `conditionalFormatting` elements of worksheet XML.
+//!
+//! Rules that reference a differential format (`dxfId`) are resolved against
+//! the workbook's `dxfs`. Rules kept in the `x14` extension list are not read.
+
+use quick_xml::Reader;
+use quick_xml::events::{BytesStart, Event};
+
+use crate::ooxml::common::xml::{attribute, push_text};
+use crate::ooxml::xlsx::format::{
+    CellFormat, ConditionalFormatRule, ConditionalFormatType, ConditionalFormatValue,
+    ConditionalFormatValueType, ConditionalFormatting, DataValidationOperator,
+};
+use crate::sheet::Result;
+
+/// A rule while its child elements are read.
+struct PendingRule {
+    kind: String,
+    attributes: BytesStart<'static>,
+    /// The `iconSet` child, which carries the icon set's attributes
+    icon_set: Option<BytesStart<'static>>,
+    formulas: Vec<String>,
+    points: Vec<ConditionalFormatValue>,
+    colors: Vec<String>,
+}
+
+/// Parse every `conditionalFormatting` element in worksheet XML.
+///
+/// `xml` may be a whole worksheet or a fragment holding only the
+/// `conditionalFormatting` elements.
+pub fn parse_conditional_formatting(
+    xml: &str,
+    dxfs: &[CellFormat],
+) -> Result<Vec<ConditionalFormatting>> {
+    let mut reader = Reader::from_str(xml);
+    let mut formattings = Vec::new();
+    let mut current: Option<ConditionalFormatting> = None;
+    let mut rule: Option<PendingRule> = None;
+    let mut ext_depth = 0usize;
+    let mut in_formula = false;
+    let mut text = String::new();
+
+    loop {
+        let event = reader
+            .read_event()
+            .map_err(|e| format!("XML parse error: {}", e))?;
+        match &event {
+            Event::Start(e) | Event::Empty(e) => {
+                let empty = matches!(event, Event::Empty(_));
+                let name = e.local_name();
+                if name.as_ref() == b"extLst" {
+                    ext_depth += usize::from(!empty);
+                    continue;
+                }
+                if ext_depth > 0 {
+                    continue;
+                }
+
+                match name.as_ref() {
+                    b"conditionalFormatting" if !empty => {
+                        current = Some(ConditionalFormatting {
+                            range: attribute(e, b"sqref").unwrap_or_default(),
+                            rules: Vec::new(),
+                        });
+                    },
+                    b"cfRule" if current.is_some() => {
+                        let pending = PendingRule {
+                            kind: attribute(e, b"type").unwrap_or_default(),
+                            attributes: e.to_owned().into_owned(),
+                            icon_set: None,
+                            formulas: Vec::new(),
+                            points: Vec::new(),
+                            colors: Vec::new(),
+                        };
+                        if empty {
+                            push_rule(current.as_mut(), pending, dxfs);
+                        } else {
+                            rule = Some(pending);
+                        }
+                    },
+                    b"formula" if rule.is_some() && !empty => {
+                        in_formula = true;
+                        text.clear();
+                    },
+                    b"cfvo" => {
+                        if let Some(rule) = rule.as_mut() {
+                            let value_type = attribute(e, b"type")
+                                .and_then(|t| ConditionalFormatValueType::parse(&t))
+                                .unwrap_or(ConditionalFormatValueType::Number);
+                            rule.points.push(ConditionalFormatValue {
+                                value_type,
+                                value: attribute(e, b"val"),
+                            });
+                        }
+                    },
+                    b"iconSet" => {
+                        if let Some(rule) = rule.as_mut() {
+                            rule.icon_set = Some(e.to_owned().into_owned());
+                        }
+                    },
+                    b"color" => {
+                        if let Some(rule) = rule.as_mut() {
+                            rule.colors.push(color(e));
+                        }
+                    },
+                    _ => {},
+                }
+            },
+            Event::Text(_) | Event::CData(_) | Event::GeneralRef(_) if in_formula => {
+                push_text(&event, &mut text)?;
+            },
+            Event::End(e) => match e.local_name().as_ref() {
+                b"extLst" => ext_depth = ext_depth.saturating_sub(1),
+                _ if ext_depth > 0 => {},
+                b"formula" if in_formula => {
+                    in_formula = false;
+                    if let Some(rule) = rule.as_mut() {
+                        rule.formulas.push(std::mem::take(&mut text));
+                    }
+                },
+                b"cfRule" => {
+                    if let Some(pending) = rule.take() {
+                        push_rule(current.as_mut(), pending, dxfs);
+                    }
+                },
+                b"conditionalFormatting" => formattings.extend(current.take()),
+                _ => {},
+            },
+            Event::Eof => break,
+            _ => {},
+        }
+    }
+
+    Ok(formattings)
+}
+
+/// Convert a finished rule and add it to its `conditionalFormatting`.
+///
+/// Rule types the model does not cover are skipped.
+fn push_rule(
+    formatting: Option<&mut ConditionalFormatting>,
+    rule: PendingRule,
+    dxfs: &[CellFormat],
+) {
+    let Some(formatting) = formatting else {
+        return;
+    };
+    let e = &rule.attributes;
+    let flag = |e: &BytesStart, name: &[u8], default: bool| {
+        attribute(e, name).map_or(default, |v| v == "1" || v == "true")
+    };
+    let min_max = |points: &[ConditionalFormatValue], index: usize, fallback| {
+        points
+            .get(index)
+            .cloned()
+            .unwrap_or(ConditionalFormatValue::new(fallback, None))
+    };
+
+    let rule_type = match rule.kind.as_str() {
+        "cellIs" => ConditionalFormatType::CellIs {
+            operator: attribute(e, b"operator")
+                .and_then(|op| DataValidationOperator::parse(&op))
+                .unwrap_or(DataValidationOperator::Equal),
+            formulas: rule.formulas,
+        },
+        "expression" => ConditionalFormatType::Expression {
+            formula: rule.formulas.into_iter().next().unwrap_or_default(),
+        },
+        "colorScale" => ConditionalFormatType::ColorScale {
+            points: rule.points,
+            colors: rule.colors,
+        },
+        "dataBar" => ConditionalFormatType::DataBar {
+            min: min_max(&rule.points, 0, ConditionalFormatValueType::Min),
+            max: min_max(&rule.points, 1, ConditionalFormatValueType::Max),
+            color: rule.colors.into_iter().next().unwrap_or_default(),
+        },
+        "iconSet" => {
+            let icon_set = rule.icon_set.as_ref().unwrap_or(e);
+            ConditionalFormatType::IconSet {
+                icon_set: attribute(icon_set, b"iconSet")
+                    .unwrap_or_else(|| "3TrafficLights1".to_string()),
+                reverse: flag(icon_set, b"reverse", false),
+                show_value: flag(icon_set, b"showValue", true),
+                points: rule.points,
+            }
+        },
+        "top10" => ConditionalFormatType::Top10 {
+            rank: attribute(e, b"rank")
+                .and_then(|r| r.parse().ok())
+                .unwrap_or(10),
+            percent: flag(e, b"percent", false),
+            bottom: flag(e, b"bottom", false),
+        },
+        "duplicateValues" => ConditionalFormatType::DuplicateValues,
+        "uniqueValues" => ConditionalFormatType::UniqueValues,
+        "containsText" => ConditionalFormatType::ContainsText {
+            text: attribute(e, b"text").unwrap_or_default(),
+        },
+        _ => return,
+    };
+
+    formatting.rules.push(ConditionalFormatRule {
+        rule_type,
+        format: attribute(e, b"dxfId")
+            .and_then(|id| id.parse::<usize>().ok())
+            .and_then(|id| dxfs.get(id))
+            .cloned(),
+        priority: attribute(e, b"priority")
+            .and_then(|p| p.parse().ok())
+            .unwrap_or(1),
+        stop_if_true: flag(e, b"stopIfTrue", false),
+    });
+}
+
+/// Read a `color` element as `AARRGGBB`, `theme:N` or `indexed:N`.
+fn color(e: &BytesStart) -> String {
+    if let Some(rgb) = attribute(e, b"rgb") {
+        rgb
+    } else if let Some(theme) = attribute(e, b"theme") {
+        format!("theme:{}", theme)
+    } else if let Some(indexed) = attribute(e, b"indexed") {
+        format!("indexed:{}", indexed)
+    } else {
+        String::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ooxml::xlsx::format::CellFont;
+
+    #[test]
+    fn test_parse_rules() {
+        let xml = r#"<worksheet><sheetData/>
+            <conditionalFormatting sqref="A1:A10">
+                <cfRule type="cellIs" dxfId="0" priority="2" operator="between" stopIfTrue="1"><formula>1</formula><formula>5</formula></cfRule>
+                <cfRule type="expression" priority="1"><formula>AND(A1&gt;0,A1&lt;3)</formula></cfRule>
+            </conditionalFormatting>
+            <conditionalFormatting sqref="B1:B10 D1:D10">
+                <cfRule type="colorScale" priority="3"><colorScale><cfvo type="min"/><cfvo type="percentile" val="50"/><cfvo type="max"/><color rgb="FFF8696B"/><color theme="4"/><color rgb="FF63BE7B"/></colorScale></cfRule>
+                <cfRule type="dataBar" priority="4"><dataBar><cfvo type="min"/><cfvo type="max"/><color rgb="FF638EC6"/></dataBar></cfRule>
+                <cfRule type="iconSet" priority="5"><iconSet iconSet="3Arrows" showValue="0"><cfvo type="percent" val="0"/><cfvo type="percent" val="33"/><cfvo type="percent" val="67"/></iconSet></cfRule>
+                <cfRule type="top10" dxfId="0" priority="6" rank="5" bottom="1"/>
+                <cfRule type="duplicateValues" priority="7"/>
+                <cfRule type="containsText" priority="8" operator="containsText" text="late"><formula>NOT(ISERROR(SEARCH("late",B1)))</formula></cfRule>
+            </conditionalFormatting>
+            <extLst><ext><x14:conditionalFormattings xmlns:x14="x14"><x14:conditionalFormatting><x14:cfRule type="dataBar"/></x14:conditionalFormatting></x14:conditionalFormattings></ext></extLst>
+        </worksheet>"#;
+        let dxfs = vec![CellFormat {
+            font: Some(CellFont {
+                bold: true,
+                ..Default::default()
+            }),
+            ..Default::default()
+        }];
+
+        let formattings = parse_conditional_formatting(xml, &dxfs).unwrap();
+        assert_eq!(formattings.len(), 2);
+        assert_eq!(formattings[0].range, "A1:A10");
+
+        let between = &formattings[0].rules[0];
+        assert!(matches!(
+            &between.rule_type,
+            ConditionalFormatType::CellIs { operator: DataValidationOperator::Between, formulas } if formulas == &["1", "5"]
+        ));
+        assert!(between.format.as_ref().unwrap().font.as_ref().unwrap().bold);
+        assert_eq!(between.priority, 2);
+        assert!(between.stop_if_true);
+        assert!(matches!(
+            &formattings[0].rules[1].rule_type,
+            ConditionalFormatType::Expression { formula } if formula == "AND(A1>0,A1<3)"
+        ));
+
+        let rules = &formattings[1].rules;
+        assert_eq!(rules.len(), 6);
+        match &rules[0].rule_type {
+            ConditionalFormatType::ColorScale { points, colors } => {
+                assert_eq!(points.len(), 3);
+                assert_eq!(points[1].value_type, ConditionalFormatValueType::Percentile);
+                assert_eq!(points[1].value.as_deref(), Some("50"));
+                assert_eq!(colors, &["FFF8696B", "theme:4", "FF63BE7B"]);
+            },
+            other => panic!("unexpected rule {:?}", other),
+        }
+        assert!(matches!(
+            &rules[1].rule_type,
+            ConditionalFormatType::DataBar { color, .. } if color == "FF638EC6"
+        ));
+        assert!(matches!(
+            &rules[2].rule_type,
+            ConditionalFormatType::IconSet { icon_set, points, show_value: false, reverse: false } if icon_set == "3Arrows" && points.len() == 3
+        ));
+        assert!(matches!(
+            rules[3].rule_type,
+            ConditionalFormatType::Top10 {
+                rank: 5,
+                percent: false,
+                bottom: true
+            }
+        ));
+        assert!(rules[3].format.is_some());
+        assert!(matches!(
+            rules[4].rule_type,
+            ConditionalFormatType::DuplicateValues
+        ));
+        assert!(matches!(
+            &rules[5].rule_type,
+            ConditionalFormatType::ContainsText { text } if text == "late"
+        ));
+    }
+}
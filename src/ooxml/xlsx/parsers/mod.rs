@@ -3,5 +3,10 @@
 //! This module contains specialized parsers for different XML files
 //! within an Excel workbook (.xlsx).
 
+pub mod conditional_formatting_parser;
+pub mod page_setup_parser;
+pub mod pivot_parser;
+pub mod protection_parser;
+pub mod table_parser;
 pub mod workbook_parser;
 pub mod worksheet_parser;
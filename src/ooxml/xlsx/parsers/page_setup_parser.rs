@@ -0,0 +1,196 @@
+//! Parser for the page layout elements of worksheet XML.
+//!
+//! Reads `pageSetup`, `pageMargins`, `printOptions` and `headerFooter`, and
+//! the `fitToPage` flag of `sheetPr`. The copies kept in custom sheet views
+//! are skipped.
+
+use quick_xml::Reader;
+use quick_xml::events::{BytesStart, Event};
+
+use crate::ooxml::common::xml::{attribute, push_text};
+use crate::ooxml::xlsx::page_setup::{
+    HeaderFooter, PageLayout, PageMargins, PageOrientation, PageSetup, PrintOptions,
+};
+use crate::sheet::Result;
+
+/// Attributes of `pageSetup` read into [`PageSetup`].
+pub(crate) const PAGE_SETUP_ATTRIBUTES: &[&str] = &[
+    "orientation",
+    "paperSize",
+    "scale",
+    "fitToWidth",
+    "fitToHeight",
+    "firstPageNumber",
+    "useFirstPageNumber",
+];
+
+/// Parse the page layout of worksheet XML.
+///
+/// `xml` may be a whole worksheet or a fragment holding only the page
+/// layout elements.
+pub fn parse_page_layout(xml: &str) -> Result<PageLayout> {
+    let mut reader = Reader::from_str(xml);
+    let mut layout = PageLayout::default();
+    let mut fit_to_page = false;
+    let mut skip_depth = 0usize;
+    let mut header_footer: Option<HeaderFooter> = None;
+    let mut text: Option<(Vec<u8>, String)> = None;
+
+    loop {
+        let event = reader
+            .read_event()
+            .map_err(|e| format!("XML parse error: {}", e))?;
+        match &event {
+            Event::Start(e) | Event::Empty(e) => {
+                let empty = matches!(event, Event::Empty(_));
+                let name = e.local_name();
+                if matches!(name.as_ref(), b"customSheetViews" | b"extLst") {
+                    skip_depth += usize::from(!empty);
+                    continue;
+                }
+                if skip_depth > 0 {
+                    continue;
+                }
+
+                match name.as_ref() {
+                    b"pageSetUpPr" => fit_to_page = flag(e, b"fitToPage", false),
+                    b"printOptions" => {
+                        layout.print_options = Some(PrintOptions {
+                            grid_lines: flag(e, b"gridLines", false),
+                            headings: flag(e, b"headings", false),
+                            horizontal_centered: flag(e, b"horizontalCentered", false),
+                            vertical_centered: flag(e, b"verticalCentered", false),
+                        });
+                    },
+                    b"pageMargins" => {
+                        let defaults = PageMargins::default();
+                        let margin = |name: &[u8], default: f64| {
+                            attribute(e, name)
+                                .and_then(|v| v.parse().ok())
+                                .unwrap_or(default)
+                        };
+                        layout.margins = Some(PageMargins {
+                            left: margin(b"left", defaults.left),
+                            right: margin(b"right", defaults.right),
+                            top: margin(b"top", defaults.top),
+                            bottom: margin(b"bottom", defaults.bottom),
+                            header: margin(b"header", defaults.header),
+                            footer: margin(b"footer", defaults.footer),
+                        });
+                    },
+                    b"pageSetup" => {
+                        let number = |name: &[u8]| attribute(e, name).and_then(|v| v.parse().ok());
+                        layout.setup = Some(PageSetup {
+                            orientation: attribute(e, b"orientation")
+                                .and_then(|o| PageOrientation::parse(&o)),
+                            paper_size: number(b"paperSize"),
+                            scale: number(b"scale"),
+                            fit_to_page: false,
+                            fit_to_width: number(b"fitToWidth"),
+                            fit_to_height: number(b"fitToHeight"),
+                            first_page_number: number(b"firstPageNumber")
+                                .filter(|_| flag(e, b"useFirstPageNumber", false)),
+                        });
+                    },
+                    b"headerFooter" => {
+                        let hf = HeaderFooter {
+                            different_odd_even: flag(e, b"differentOddEven", false),
+                            different_first: flag(e, b"differentFirst", false),
+                            scale_with_document: flag(e, b"scaleWithDoc", true),
+                            align_with_margins: flag(e, b"alignWithMargins", true),
+                            ..Default::default()
+                        };
+                        if empty {
+                            layout.header_footer = Some(hf);
+                        } else {
+                            header_footer = Some(hf);
+                        }
+                    },
+                    b"oddHeader" | b"oddFooter" | b"evenHeader" | b"evenFooter"
+                    | b"firstHeader" | b"firstFooter"
+                        if header_footer.is_some() && !empty =>
+                    {
+                        text = Some((name.as_ref().to_vec(), String::new()));
+                    },
+                    _ => {},
+                }
+            },
+            Event::Text(_) | Event::CData(_) | Event::GeneralRef(_) => {
+                if let Some((_, value)) = text.as_mut() {
+                    push_text(&event, value)?;
+                }
+            },
+            Event::End(e) => match e.local_name().as_ref() {
+                b"customSheetViews" | b"extLst" => skip_depth = skip_depth.saturating_sub(1),
+                _ if skip_depth > 0 => {},
+                b"headerFooter" => {
+                    if let Some(hf) = header_footer.take() {
+                        layout.header_footer = Some(hf);
+                    }
+                },
+                _ => {
+                    if let (Some((name, value)), Some(hf)) = (text.take(), header_footer.as_mut()) {
+                        let slot = match name.as_slice() {
+                            b"oddHeader" => &mut hf.odd_header,
+                            b"oddFooter" => &mut hf.odd_footer,
+                            b"evenHeader" => &mut hf.even_header,
+                            b"evenFooter" => &mut hf.even_footer,
+                            b"firstHeader" => &mut hf.first_header,
+                            _ => &mut hf.first_footer,
+                        };
+                        *slot = Some(value);
+                    }
+                },
+            },
+            Event::Eof => break,
+            _ => {},
+        }
+    }
+
+    if fit_to_page {
+        layout
+            .setup
+            .get_or_insert_with(PageSetup::default)
+            .fit_to_page = true;
+    }
+    Ok(layout)
+}
+
+fn flag(e: &BytesStart, name: &[u8], default: bool) -> bool {
+    attribute(e, name).map_or(default, |v| v == "1" || v == "true")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_page_layout() {
+        let xml = r#"<worksheet xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main"><sheetPr><pageSetUpPr fitToPage="1"/></sheetPr><sheetData/><customSheetViews><customSheetView guid="{0}"><pageSetup orientation="portrait"/></customSheetView></customSheetViews><printOptions gridLines="1" horizontalCentered="1"/><pageMargins left="0.5" right="0.5" top="1" bottom="1" header="0.25" footer="0.25"/><pageSetup paperSize="9" orientation="landscape" fitToWidth="1" fitToHeight="0" r:id="rId1"/><headerFooter differentFirst="1"><oddHeader>&amp;CReport</oddHeader><firstFooter>Page &amp;P</firstFooter></headerFooter></worksheet>"#;
+        let layout = parse_page_layout(xml).unwrap();
+
+        let setup = layout.setup.unwrap();
+        assert_eq!(setup.orientation, Some(PageOrientation::Landscape));
+        assert_eq!(setup.paper_size, Some(9));
+        assert!(setup.fit_to_page);
+        assert_eq!(
+            (setup.fit_to_width, setup.fit_to_height),
+            (Some(1), Some(0))
+        );
+
+        let margins = layout.margins.unwrap();
+        assert_eq!(
+            (margins.left, margins.top, margins.footer),
+            (0.5, 1.0, 0.25)
+        );
+
+        let options = layout.print_options.unwrap();
+        assert!(options.grid_lines && options.horizontal_centered && !options.headings);
+
+        let hf = layout.header_footer.unwrap();
+        assert!(hf.different_first);
+        assert_eq!(hf.odd_header.as_deref(), Some("&CReport"));
+        assert_eq!(hf.first_footer.as_deref(), Some("Page &P"));
+        assert_eq!(hf.even_header, None);
+    }
+}
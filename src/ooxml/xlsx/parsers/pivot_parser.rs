@@ -0,0 +1,362 @@
+//! Parsers for pivot cache definition, pivot cache records and pivot table parts.
+
+use quick_xml::Reader;
+use quick_xml::events::{BytesStart, Event};
+
+use crate::ooxml::common::xml::attribute;
+use crate::ooxml::xlsx::pivot::{
+    CacheField, DataField, PageField, PivotAxis, PivotCache, PivotField, PivotFilter,
+    PivotFunction, PivotItem, PivotSource, PivotTable,
+};
+use crate::sheet::{CellValue, Result};
+
+/// Parse a pivot cache definition part.
+///
+/// The returned cache has no records; read them with
+/// [`parse_pivot_cache_records`]. Grouped field items are not read.
+pub fn parse_pivot_cache_definition(xml: &str, id: u32) -> Result<PivotCache> {
+    let mut reader = Reader::from_str(xml);
+    let mut cache = PivotCache {
+        id,
+        source: PivotSource::default(),
+        fields: Vec::new(),
+        records: Vec::new(),
+    };
+    let mut in_shared_items = false;
+
+    loop {
+        let event = reader
+            .read_event()
+            .map_err(|e| format!("XML parse error: {}", e))?;
+        match &event {
+            Event::Start(e) | Event::Empty(e) => {
+                let empty = matches!(event, Event::Empty(_));
+                match e.local_name().as_ref() {
+                    b"cacheSource" => {
+                        cache.source.source_type =
+                            attribute(e, b"type").unwrap_or_else(|| "worksheet".to_string());
+                    },
+                    b"worksheetSource" => {
+                        cache.source.sheet = attribute(e, b"sheet");
+                        cache.source.range = attribute(e, b"ref");
+                        cache.source.name = attribute(e, b"name");
+                    },
+                    b"cacheField" => {
+                        cache.fields.push(CacheField {
+                            name: attribute(e, b"name").unwrap_or_default(),
+                            shared_items: Vec::new(),
+                            database_field: attribute(e, b"databaseField").as_deref() != Some("0"),
+                            formula: attribute(e, b"formula"),
+                            number_format_id: attribute(e, b"numFmtId")
+                                .and_then(|v| v.parse().ok()),
+                        });
+                    },
+                    b"sharedItems" => in_shared_items = !empty,
+                    name if in_shared_items => {
+                        if let (Some(value), Some(field)) =
+                            (item_value(name, e), cache.fields.last_mut())
+                        {
+                            field.shared_items.push(value);
+                        }
+                    },
+                    _ => {},
+                }
+            },
+            Event::End(e) if e.local_name().as_ref() == b"sharedItems" => in_shared_items = false,
+            Event::Eof => break,
+            _ => {},
+        }
+    }
+
+    Ok(cache)
+}
+
+/// Parse a pivot cache records part into rows with one value per field of `cache`.
+///
+/// Indexed values (`x`) are resolved against the fields' shared items.
+pub fn parse_pivot_cache_records(xml: &str, cache: &PivotCache) -> Result<Vec<Vec<CellValue>>> {
+    // Records hold values for the source fields only
+    let columns: Vec<usize> = (0..cache.fields.len())
+        .filter(|&index| cache.fields[index].database_field)
+        .collect();
+    let mut reader = Reader::from_str(xml);
+    let mut records = Vec::new();
+    let mut record: Option<Vec<CellValue>> = None;
+    let mut column = 0usize;
+
+    loop {
+        let event = reader
+            .read_event()
+            .map_err(|e| format!("XML parse error: {}", e))?;
+        match &event {
+            Event::Start(e) | Event::Empty(e) => {
+                let name = e.local_name();
+                if name.as_ref() == b"r" {
+                    record = Some(vec![CellValue::Empty; cache.fields.len()]);
+                    column = 0;
+                    if matches!(event, Event::Empty(_)) {
+                        records.extend(record.take());
+                    }
+                    continue;
+                }
+                let Some(values) = record.as_mut() else {
+                    continue;
+                };
+                let value = if name.as_ref() == b"x" {
+                    let index: Option<usize> = attribute(e, b"v").and_then(|v| v.parse().ok());
+                    columns
+                        .get(column)
+                        .and_then(|&field| cache.fields[field].shared_items.get(index?))
+                        .cloned()
+                } else {
+                    item_value(name.as_ref(), e)
+                };
+                if let (Some(value), Some(&field)) = (value, columns.get(column)) {
+                    values[field] = value;
+                }
+                column += 1;
+            },
+            Event::End(e) if e.local_name().as_ref() == b"r" => {
+                records.extend(record.take());
+            },
+            Event::Eof => break,
+            _ => {},
+        }
+    }
+
+    Ok(records)
+}
+
+/// Parse a pivot table part.
+pub fn parse_pivot_table(xml: &str) -> Result<PivotTable> {
+    let mut reader = Reader::from_str(xml);
+    let mut table = PivotTable {
+        name: String::new(),
+        cache_id: 0,
+        location: String::new(),
+        fields: Vec::new(),
+        row_fields: Vec::new(),
+        column_fields: Vec::new(),
+        data_on_rows: false,
+        page_fields: Vec::new(),
+        data_fields: Vec::new(),
+        filters: Vec::new(),
+    };
+    let mut section: Vec<u8> = Vec::new();
+
+    loop {
+        let event = reader
+            .read_event()
+            .map_err(|e| format!("XML parse error: {}", e))?;
+        match &event {
+            Event::Start(e) | Event::Empty(e) => {
+                let empty = matches!(event, Event::Empty(_));
+                let index = |name: &[u8]| attribute(e, name).and_then(|v| v.parse::<usize>().ok());
+                match e.local_name().as_ref() {
+                    b"pivotTableDefinition" => {
+                        table.name = attribute(e, b"name").unwrap_or_default();
+                        table.cache_id = attribute(e, b"cacheId")
+                            .and_then(|v| v.parse().ok())
+                            .unwrap_or(0);
+                        table.data_on_rows = flag(e, b"dataOnRows");
+                    },
+                    b"location" => table.location = attribute(e, b"ref").unwrap_or_default(),
+                    name @ (b"pivotFields" | b"rowFields" | b"colFields" | b"filters")
+                        if !empty =>
+                    {
+                        section = name.to_vec();
+                    },
+                    b"pivotField" => table.fields.push(PivotField {
+                        name: attribute(e, b"name"),
+                        axis: attribute(e, b"axis").and_then(|axis| PivotAxis::parse(&axis)),
+                        data_field: flag(e, b"dataField"),
+                        items: Vec::new(),
+                    }),
+                    b"item" if section == b"pivotFields" => {
+                        if let Some(field) = table.fields.last_mut() {
+                            field.items.push(PivotItem {
+                                index: index(b"x"),
+                                hidden: flag(e, b"h"),
+                                item_type: attribute(e, b"t").filter(|t| t != "data"),
+                            });
+                        }
+                    },
+                    b"field" => {
+                        // Field -2 is the values pseudo-field
+                        let field = attribute(e, b"x").and_then(|v| v.parse::<i64>().ok());
+                        let Some(field) = field.and_then(|f| usize::try_from(f).ok()) else {
+                            continue;
+                        };
+                        match section.as_slice() {
+                            b"rowFields" => table.row_fields.push(field),
+                            b"colFields" => table.column_fields.push(field),
+                            _ => {},
+                        }
+                    },
+                    b"pageField" => table.page_fields.push(PageField {
+                        field: index(b"fld").unwrap_or(0),
+                        item: index(b"item"),
+                        name: attribute(e, b"name"),
+                    }),
+                    b"dataField" => table.data_fields.push(DataField {
+                        name: attribute(e, b"name"),
+                        field: index(b"fld").unwrap_or(0),
+                        function: attribute(e, b"subtotal")
+                            .and_then(|f| PivotFunction::parse(&f))
+                            .unwrap_or_default(),
+                        show_data_as: attribute(e, b"showDataAs").filter(|v| v != "normal"),
+                        number_format_id: attribute(e, b"numFmtId").and_then(|v| v.parse().ok()),
+                    }),
+                    b"filter" if section == b"filters" => table.filters.push(PivotFilter {
+                        field: index(b"fld").unwrap_or(0),
+                        filter_type: attribute(e, b"type").unwrap_or_default(),
+                        data_field: index(b"iMeasureFld"),
+                        value1: attribute(e, b"stringValue1"),
+                        value2: attribute(e, b"stringValue2"),
+                    }),
+                    // Value filters keep their operand in the nested autoFilter
+                    b"top10" | b"customFilter" if section == b"filters" => {
+                        if let Some(filter) = table.filters.last_mut()
+                            && filter.value1.is_none()
+                        {
+                            filter.value1 = attribute(e, b"val");
+                        }
+                    },
+                    _ => {},
+                }
+            },
+            Event::End(e) if e.local_name().as_ref() == section.as_slice() => section.clear(),
+            Event::Eof => break,
+            _ => {},
+        }
+    }
+
+    Ok(table)
+}
+
+/// Read a cache item element (`s`, `n`, `b`, `e`, `d` or `m`).
+fn item_value(name: &[u8], e: &BytesStart) -> Option<CellValue> {
+    let value = attribute(e, b"v");
+    Some(match name {
+        b"m" => CellValue::Empty,
+        b"s" => CellValue::String(value.unwrap_or_default()),
+        b"n" => CellValue::Float(value?.parse().ok()?),
+        b"b" => CellValue::Bool(matches!(value.as_deref(), Some("1") | Some("true"))),
+        b"e" => CellValue::Error(value.unwrap_or_default()),
+        b"d" => {
+            let value = value?;
+            match date_serial(&value) {
+                Some(serial) => CellValue::DateTime(serial),
+                None => CellValue::String(value),
+            }
+        },
+        _ => return None,
+    })
+}
+
+/// Convert an `xsd:dateTime` value to a serial date in the 1900 date system.
+fn date_serial(value: &str) -> Option<f64> {
+    let value = value.trim_end_matches('Z');
+    let date_time = chrono::NaiveDateTime::parse_from_str(value, "%Y-%m-%dT%H:%M:%S%.f")
+        .or_else(|_| chrono::NaiveDateTime::parse_from_str(value, "%Y-%m-%dT%H:%M:%S"))
+        .ok()?;
+    date_time_serial(date_time)
+}
+
+/// Convert a date and time to a serial date in the 1900 date system.
+pub(crate) fn date_time_serial(date_time: chrono::NaiveDateTime) -> Option<f64> {
+    let epoch = chrono::NaiveDate::from_ymd_opt(1899, 12, 30)?.and_hms_opt(0, 0, 0)?;
+    let serial = (date_time - epoch).num_milliseconds() as f64 / 86_400_000.0;
+    // Serials before March 1900 skip Excel's phantom 29 February 1900
+    Some(if serial < 61.0 { serial - 1.0 } else { serial })
+}
+
+fn flag(e: &BytesStart, name: &[u8]) -> bool {
+    matches!(attribute(e, name).as_deref(), Some("1") | Some("true"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DEFINITION: &str = r#"<pivotCacheDefinition xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main" xmlns:r="http://schemas.openxmlformats.org/officeDocument/2006/relationships" r:id="rId1" recordCount="3"><cacheSource type="worksheet"><worksheetSource ref="A1:C4" sheet="Data"/></cacheSource><cacheFields count="4"><cacheField name="Region" numFmtId="0"><sharedItems count="2"><s v="East"/><s v="West"/></sharedItems></cacheField><cacheField name="Units" numFmtId="0"><sharedItems containsSemiMixedTypes="0" containsNumber="1" minValue="3" maxValue="12"/></cacheField><cacheField name="Date" numFmtId="14"><sharedItems containsDate="1"><d v="2024-03-01T00:00:00"/></sharedItems><fieldGroup base="2"><groupItems count="1"><s v="Mar"/></groupItems></fieldGroup></cacheField><cacheField name="Double" numFmtId="0" formula="Units*2" databaseField="0"/></cacheFields></pivotCacheDefinition>"#;
+
+    #[test]
+    fn test_parse_pivot_cache() {
+        let cache = parse_pivot_cache_definition(DEFINITION, 5).unwrap();
+        assert_eq!(cache.id, 5);
+        assert_eq!(cache.source.sheet.as_deref(), Some("Data"));
+        assert_eq!(cache.source.range.as_deref(), Some("A1:C4"));
+        assert_eq!(cache.fields.len(), 4);
+        assert_eq!(
+            cache.fields[0].shared_items,
+            [
+                CellValue::String("East".to_string()),
+                CellValue::String("West".to_string())
+            ]
+        );
+        assert_eq!(cache.fields[2].shared_items, [CellValue::DateTime(45352.0)]);
+        assert_eq!(cache.fields[3].formula.as_deref(), Some("Units*2"));
+        assert!(cache.fields[2].database_field && !cache.fields[3].database_field);
+
+        let records = r#"<pivotCacheRecords xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main" count="3"><r><x v="0"/><n v="12"/><x v="0"/></r><r><x v="1"/><n v="3"/><m/></r><r><x v="0"/><n v="5"/><d v="1900-01-01T00:00:00"/></r></pivotCacheRecords>"#;
+        let records = parse_pivot_cache_records(records, &cache).unwrap();
+        assert_eq!(records.len(), 3);
+        assert_eq!(
+            records[1],
+            [
+                CellValue::String("West".to_string()),
+                CellValue::Float(3.0),
+                CellValue::Empty,
+                CellValue::Empty
+            ]
+        );
+        assert_eq!(records[0][2], CellValue::DateTime(45352.0));
+        assert_eq!(records[2][2], CellValue::DateTime(1.0));
+    }
+
+    #[test]
+    fn test_parse_pivot_table() {
+        let xml = r#"<pivotTableDefinition xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main" name="PivotTable1" cacheId="5" dataCaption="Values"><location ref="A3:B6" firstHeaderRow="1" firstDataRow="1" firstDataCol="1" rowPageCount="1" colPageCount="1"/><pivotFields count="4"><pivotField axis="axisRow" showAll="0"><items count="3"><item x="1"/><item x="0" h="1"/><item t="default"/></items></pivotField><pivotField dataField="1" showAll="0"/><pivotField axis="axisPage" showAll="0"><items count="2"><item x="0"/><item t="default"/></items></pivotField><pivotField showAll="0"/></pivotFields><rowFields count="1"><field x="0"/></rowFields><colFields count="1"><field x="-2"/></colFields><pageFields count="1"><pageField fld="2" item="0" hier="-1"/></pageFields><dataFields count="2"><dataField name="Sum of Units" fld="1" baseField="0" baseItem="0"/><dataField name="Average of Units" fld="1" subtotal="average" showDataAs="percentOfTotal" numFmtId="10"/></dataFields><filters count="1"><filter fld="0" type="valueGreaterThan" evalOrder="-1" id="1" iMeasureFld="0"><autoFilter ref="A1"><filterColumn colId="0"><customFilters><customFilter operator="greaterThan" val="4"/></customFilters></filterColumn></autoFilter></filter></filters></pivotTableDefinition>"#;
+        let table = parse_pivot_table(xml).unwrap();
+
+        assert_eq!((table.name.as_str(), table.cache_id), ("PivotTable1", 5));
+        assert_eq!(table.location, "A3:B6");
+        assert_eq!(table.fields.len(), 4);
+        assert_eq!(table.fields[0].axis, Some(PivotAxis::Row));
+        assert_eq!(table.fields[0].items.len(), 3);
+        assert!(table.fields[0].items[1].hidden);
+        assert_eq!(
+            table.fields[0].items[2].item_type.as_deref(),
+            Some("default")
+        );
+        assert!(table.fields[1].data_field);
+        assert_eq!(table.row_fields, [0]);
+        assert!(table.column_fields.is_empty() && !table.data_on_rows);
+        assert_eq!(
+            table.page_fields,
+            [PageField {
+                field: 2,
+                item: Some(0),
+                name: None
+            }]
+        );
+        assert_eq!(table.data_fields.len(), 2);
+        assert_eq!(table.data_fields[0].function, PivotFunction::Sum);
+        assert_eq!(table.data_fields[1].function, PivotFunction::Average);
+        assert_eq!(
+            table.data_fields[1].show_data_as.as_deref(),
+            Some("percentOfTotal")
+        );
+        assert_eq!(table.filters[0].filter_type, "valueGreaterThan");
+        assert_eq!(table.filters[0].data_field, Some(0));
+        assert_eq!(table.filters[0].value1.as_deref(), Some("4"));
+
+        let cache = parse_pivot_cache_definition(DEFINITION, 5).unwrap();
+        assert_eq!(
+            table.item_value(&cache, 0, 0),
+            Some(&CellValue::String("West".to_string()))
+        );
+        assert_eq!(table.item_value(&cache, 0, 2), None);
+    }
+}
@@ -0,0 +1,108 @@
+//! Parser for `sheetProtection` and `workbookProtection` elements.
+
+use quick_xml::Reader;
+use quick_xml::events::{BytesStart, Event};
+
+use crate::ooxml::common::xml::attribute;
+use crate::ooxml::xlsx::protection::{PasswordHash, SheetProtection, WorkbookProtection};
+use crate::sheet::Result;
+
+/// Parse the `sheetProtection` element of worksheet XML.
+///
+/// Returns `None` if the sheet is not protected, including when the element
+/// is present with `sheet="0"`.
+pub fn parse_sheet_protection(xml: &str) -> Result<Option<SheetProtection>> {
+    let Some(e) = find_element(xml, b"sheetProtection")? else {
+        return Ok(None);
+    };
+    if !flag(&e, b"sheet") {
+        return Ok(None);
+    }
+
+    let mut protection = SheetProtection {
+        password: PasswordHash::from_attributes(|name| attribute(&e, name.as_bytes()), ""),
+        ..Default::default()
+    };
+    for attr in e.attributes().flatten() {
+        let name = String::from_utf8_lossy(attr.key.local_name().as_ref()).into_owned();
+        let value = attr
+            .unescape_value()
+            .map(|v| v.into_owned())
+            .unwrap_or_default();
+        protection.set_flag(&name, value == "1" || value == "true");
+    }
+    Ok(Some(protection))
+}
+
+/// Parse the `workbookProtection` element of workbook XML.
+pub fn parse_workbook_protection(xml: &str) -> Result<Option<WorkbookProtection>> {
+    Ok(
+        find_element(xml, b"workbookProtection")?.map(|e| WorkbookProtection {
+            password: PasswordHash::from_attributes(
+                |name| attribute(&e, name.as_bytes()),
+                "workbook",
+            ),
+            lock_structure: flag(&e, b"lockStructure"),
+            lock_windows: flag(&e, b"lockWindows"),
+        }),
+    )
+}
+
+/// Find the first element with the given local name.
+fn find_element(xml: &str, name: &[u8]) -> Result<Option<BytesStart<'static>>> {
+    let mut reader = Reader::from_str(xml);
+    loop {
+        match reader
+            .read_event()
+            .map_err(|e| format!("XML parse error: {}", e))?
+        {
+            Event::Start(e) | Event::Empty(e) if e.local_name().as_ref() == name => {
+                return Ok(Some(e.into_owned()));
+            },
+            Event::Eof => return Ok(None),
+            _ => {},
+        }
+    }
+}
+
+fn flag(e: &BytesStart, name: &[u8]) -> bool {
+    matches!(attribute(e, name).as_deref(), Some("1") | Some("true"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_protection() {
+        let xml = r#"<worksheet><sheetData/><sheetProtection algorithmName="SHA-512" hashValue="abc=" saltValue="def=" spinCount="100000" sheet="1" objects="1" scenarios="1" formatColumns="0" selectLockedCells="1"/></worksheet>"#;
+        let protection = parse_sheet_protection(xml).unwrap().unwrap();
+        assert!(protection.allow_format_columns);
+        assert!(!protection.allow_format_cells);
+        assert!(!protection.allow_select_locked_cells);
+        assert!(protection.allow_select_unlocked_cells);
+        assert_eq!(
+            protection.password,
+            Some(PasswordHash::Hashed {
+                algorithm: "SHA-512".to_string(),
+                hash_value: "abc=".to_string(),
+                salt_value: "def=".to_string(),
+                spin_count: 100_000,
+            })
+        );
+
+        assert!(
+            parse_sheet_protection(r#"<sheetProtection sheet="0"/>"#)
+                .unwrap()
+                .is_none()
+        );
+
+        let xml = r#"<workbook><workbookProtection workbookPassword="CBEB" lockStructure="1"/><sheets/></workbook>"#;
+        let protection = parse_workbook_protection(xml).unwrap().unwrap();
+        assert!(protection.lock_structure && !protection.lock_windows);
+        assert_eq!(
+            protection.password,
+            Some(PasswordHash::Legacy("CBEB".to_string()))
+        );
+    }
+}
@@ -0,0 +1,316 @@
+//! Parser for table parts and `autoFilter` elements.
+//!
+//! Filter columns using color, icon or date-group criteria are not read.
+
+use quick_xml::Reader;
+use quick_xml::events::{BytesStart, Event};
+
+use crate::ooxml::common::xml::{attribute, push_text};
+use crate::ooxml::xlsx::table::{
+    AutoFilter, ColumnFilter, CustomFilter, FilterColumn, FilterOperator, Table, TableColumn,
+    TableStyle, TotalsFunction,
+};
+use crate::sheet::Result;
+
+/// Parse a table part (`xl/tables/tableN.xml`).
+pub fn parse_table(xml: &str) -> Result<Table> {
+    let mut reader = Reader::from_str(xml);
+    let mut table = Table {
+        name: String::new(),
+        range: String::new(),
+        header_row: true,
+        totals_row: false,
+        columns: Vec::new(),
+        style: None,
+        auto_filter: None,
+    };
+    // Column being read with its totals function and totals formula
+    let mut column: Option<(TableColumn, Option<String>, Option<String>)> = None;
+    let mut text: Option<(Vec<u8>, String)> = None;
+
+    loop {
+        let event = reader
+            .read_event()
+            .map_err(|e| format!("XML parse error: {}", e))?;
+        match &event {
+            Event::Start(e) | Event::Empty(e) => {
+                let empty = matches!(event, Event::Empty(_));
+                match e.local_name().as_ref() {
+                    b"table" => {
+                        table.name = attribute(e, b"displayName")
+                            .or_else(|| attribute(e, b"name"))
+                            .unwrap_or_default();
+                        table.range = attribute(e, b"ref").unwrap_or_default();
+                        table.header_row = attribute(e, b"headerRowCount").as_deref() != Some("0");
+                        table.totals_row =
+                            attribute(e, b"totalsRowCount").is_some_and(|count| count != "0");
+                    },
+                    b"tableColumn" => {
+                        let mut pending =
+                            TableColumn::new(&attribute(e, b"name").unwrap_or_default());
+                        pending.totals_label = attribute(e, b"totalsRowLabel");
+                        let function = attribute(e, b"totalsRowFunction");
+                        if empty {
+                            pending.totals_function =
+                                function.and_then(|f| TotalsFunction::parse(&f, None));
+                            table.columns.push(pending);
+                        } else {
+                            column = Some((pending, function, None));
+                        }
+                    },
+                    name @ (b"calculatedColumnFormula" | b"totalsRowFormula")
+                        if column.is_some() && !empty =>
+                    {
+                        text = Some((name.to_vec(), String::new()));
+                    },
+                    b"tableStyleInfo" => {
+                        let flag = |name: &[u8]| {
+                            attribute(e, name).is_some_and(|v| v == "1" || v == "true")
+                        };
+                        table.style = Some(TableStyle {
+                            name: attribute(e, b"name").unwrap_or_default(),
+                            show_first_column: flag(b"showFirstColumn"),
+                            show_last_column: flag(b"showLastColumn"),
+                            show_row_stripes: flag(b"showRowStripes"),
+                            show_column_stripes: flag(b"showColumnStripes"),
+                        });
+                    },
+                    _ => {},
+                }
+            },
+            Event::Text(_) | Event::CData(_) | Event::GeneralRef(_) => {
+                if let Some((_, value)) = text.as_mut() {
+                    push_text(&event, value)?;
+                }
+            },
+            Event::End(e) => match e.local_name().as_ref() {
+                b"tableColumn" => {
+                    if let Some((mut pending, function, formula)) = column.take() {
+                        pending.totals_function =
+                            function.and_then(|f| TotalsFunction::parse(&f, formula));
+                        table.columns.push(pending);
+                    }
+                },
+                b"calculatedColumnFormula" | b"totalsRowFormula" => {
+                    if let (Some((name, value)), Some((pending, _, formula))) =
+                        (text.take(), column.as_mut())
+                    {
+                        if name == b"calculatedColumnFormula" {
+                            pending.calculated_formula = Some(value);
+                        } else {
+                            *formula = Some(value);
+                        }
+                    }
+                },
+                _ => {},
+            },
+            Event::Eof => break,
+            _ => {},
+        }
+    }
+
+    table.auto_filter = parse_auto_filter(xml)?.map(|mut filter| {
+        // A table's filter always covers the table
+        filter.range.clear();
+        filter
+    });
+    Ok(table)
+}
+
+/// Parse the `autoFilter` element of worksheet or table XML.
+///
+/// The filters of custom sheet views are skipped.
+pub fn parse_auto_filter(xml: &str) -> Result<Option<AutoFilter>> {
+    let mut reader = Reader::from_str(xml);
+    let mut filter: Option<AutoFilter> = None;
+    let mut column: Option<(u32, Option<ColumnFilter>)> = None;
+    let mut skip_depth = 0usize;
+
+    loop {
+        let event = reader
+            .read_event()
+            .map_err(|e| format!("XML parse error: {}", e))?;
+        match &event {
+            Event::Start(e) | Event::Empty(e) => {
+                let empty = matches!(event, Event::Empty(_));
+                let name = e.local_name();
+                if matches!(name.as_ref(), b"customSheetViews" | b"extLst") {
+                    skip_depth += usize::from(!empty);
+                    continue;
+                }
+                if skip_depth > 0 {
+                    continue;
+                }
+
+                match name.as_ref() {
+                    b"autoFilter" if filter.is_none() => {
+                        filter = Some(AutoFilter::new(&attribute(e, b"ref").unwrap_or_default()));
+                        if empty {
+                            break;
+                        }
+                    },
+                    b"filterColumn" if filter.is_some() => {
+                        let id = attribute(e, b"colId")
+                            .and_then(|id| id.parse().ok())
+                            .unwrap_or(0);
+                        column = (!empty).then_some((id, None));
+                    },
+                    b"filters" => {
+                        if let Some((_, criteria)) = column.as_mut() {
+                            *criteria = Some(ColumnFilter::Values {
+                                values: Vec::new(),
+                                blank: flag(e, b"blank"),
+                            });
+                        }
+                    },
+                    b"filter" => {
+                        if let Some((_, Some(ColumnFilter::Values { values, .. }))) =
+                            column.as_mut()
+                        {
+                            values.extend(attribute(e, b"val"));
+                        }
+                    },
+                    b"dateGroupItem" => {
+                        // Date groups are not modeled; drop the column's criteria
+                        if let Some((_, criteria)) = column.as_mut() {
+                            *criteria = None;
+                        }
+                    },
+                    b"customFilters" => {
+                        if let Some((_, criteria)) = column.as_mut() {
+                            *criteria = Some(ColumnFilter::Custom {
+                                and: flag(e, b"and"),
+                                conditions: Vec::new(),
+                            });
+                        }
+                    },
+                    b"customFilter" => {
+                        if let Some((_, Some(ColumnFilter::Custom { conditions, .. }))) =
+                            column.as_mut()
+                        {
+                            conditions.push(CustomFilter {
+                                operator: attribute(e, b"operator")
+                                    .and_then(|op| FilterOperator::parse(&op))
+                                    .unwrap_or(FilterOperator::Equal),
+                                value: attribute(e, b"val").unwrap_or_default(),
+                            });
+                        }
+                    },
+                    b"top10" => {
+                        if let Some((_, criteria)) = column.as_mut() {
+                            *criteria = Some(ColumnFilter::Top10 {
+                                top: attribute(e, b"top").is_none_or(|v| v == "1" || v == "true"),
+                                percent: flag(e, b"percent"),
+                                value: attribute(e, b"val")
+                                    .and_then(|v| v.parse().ok())
+                                    .unwrap_or(10.0),
+                            });
+                        }
+                    },
+                    b"dynamicFilter" => {
+                        if let Some((_, criteria)) = column.as_mut() {
+                            *criteria = Some(ColumnFilter::Dynamic {
+                                filter_type: attribute(e, b"type").unwrap_or_default(),
+                            });
+                        }
+                    },
+                    _ => {},
+                }
+            },
+            Event::End(e) => match e.local_name().as_ref() {
+                b"customSheetViews" | b"extLst" => skip_depth = skip_depth.saturating_sub(1),
+                _ if skip_depth > 0 => {},
+                b"filterColumn" => {
+                    if let (Some((id, Some(criteria))), Some(filter)) =
+                        (column.take(), filter.as_mut())
+                    {
+                        filter.columns.push(FilterColumn {
+                            column: id,
+                            filter: criteria,
+                        });
+                    }
+                },
+                b"autoFilter" if filter.is_some() => break,
+                _ => {},
+            },
+            Event::Eof => break,
+            _ => {},
+        }
+    }
+
+    Ok(filter)
+}
+
+fn flag(e: &BytesStart, name: &[u8]) -> bool {
+    matches!(attribute(e, name).as_deref(), Some("1") | Some("true"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_table() {
+        let xml = r#"<table xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main" id="3" name="Table3" displayName="Sales" ref="A1:C6" totalsRowCount="1"><autoFilter ref="A1:C5"><filterColumn colId="0"><filters blank="1"><filter val="East"/><filter val="West"/></filters></filterColumn></autoFilter><tableColumns count="3"><tableColumn id="1" name="Region" totalsRowLabel="Total"/><tableColumn id="2" name="Units" totalsRowFunction="sum"/><tableColumn id="3" name="Amount" totalsRowFunction="custom"><calculatedColumnFormula>Sales[[#This Row],[Units]]*2</calculatedColumnFormula><totalsRowFormula>SUM(Sales[Amount])/2</totalsRowFormula></tableColumn></tableColumns><tableStyleInfo name="TableStyleLight9" showFirstColumn="0" showLastColumn="0" showRowStripes="1" showColumnStripes="0"/></table>"#;
+        let table = parse_table(xml).unwrap();
+
+        assert_eq!(table.name, "Sales");
+        assert_eq!(table.range, "A1:C6");
+        assert!(table.header_row && table.totals_row);
+        assert_eq!(table.columns.len(), 3);
+        assert_eq!(table.columns[0].totals_label.as_deref(), Some("Total"));
+        assert_eq!(table.columns[1].totals_function, Some(TotalsFunction::Sum));
+        assert_eq!(
+            table.columns[2].calculated_formula.as_deref(),
+            Some("Sales[[#This Row],[Units]]*2")
+        );
+        assert_eq!(
+            table.columns[2].totals_function,
+            Some(TotalsFunction::Custom("SUM(Sales[Amount])/2".to_string()))
+        );
+        assert_eq!(table.style.unwrap().name, "TableStyleLight9");
+
+        let filter = table.auto_filter.unwrap();
+        assert_eq!(
+            filter.columns[0].filter,
+            ColumnFilter::Values {
+                values: vec!["East".to_string(), "West".to_string()],
+                blank: true
+            }
+        );
+    }
+
+    #[test]
+    fn test_parse_auto_filter() {
+        let xml = r#"<worksheet><sheetData/><autoFilter ref="A1:D20"><filterColumn colId="1"><customFilters and="1"><customFilter operator="greaterThanOrEqual" val="10"/><customFilter operator="lessThan" val="50"/></customFilters></filterColumn><filterColumn colId="2"><top10 percent="1" val="25"/></filterColumn><filterColumn colId="3"><colorFilter dxfId="0"/></filterColumn></autoFilter></worksheet>"#;
+        let filter = parse_auto_filter(xml).unwrap().unwrap();
+
+        assert_eq!(filter.range, "A1:D20");
+        assert_eq!(filter.columns.len(), 2);
+        assert_eq!(
+            filter.columns[0].filter,
+            ColumnFilter::Custom {
+                and: true,
+                conditions: vec![
+                    CustomFilter {
+                        operator: FilterOperator::GreaterThanOrEqual,
+                        value: "10".to_string()
+                    },
+                    CustomFilter {
+                        operator: FilterOperator::LessThan,
+                        value: "50".to_string()
+                    },
+                ],
+            }
+        );
+        assert_eq!(
+            filter.columns[1].filter,
+            ColumnFilter::Top10 {
+                top: true,
+                percent: true,
+                value: 25.0
+            }
+        );
+        assert!(parse_auto_filter("<worksheet/>").unwrap().is_none());
+    }
+}
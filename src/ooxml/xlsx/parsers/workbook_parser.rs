@@ -8,7 +8,12 @@
 //! - Uses atoi_simd for fast integer parsing
 //! - Pre-allocates vectors with reasonable capacities
 
+use quick_xml::Reader;
+use quick_xml::events::Event;
+
+use crate::ooxml::common::xml::{attribute, push_text};
 use crate::ooxml::xlsx::worksheet::WorksheetInfo;
+use crate::ooxml::xlsx::writer::NamedRange;
 use crate::sheet::Result;
 
 // Performance: Pre-allocate typical capacity for worksheets
@@ -124,3 +129,69 @@ pub fn parse_sheet_xml(sheet_xml: &str) -> Result<Option<WorksheetInfo>> {
         _ => Ok(None),
     }
 }
+
+/// Parse the `definedName` elements of workbook.xml.
+///
+/// `local_sheet_id` of sheet-scoped names is the 1-based position of the
+/// sheet in the workbook.
+pub fn parse_defined_names(content: &str) -> Result<Vec<NamedRange>> {
+    let mut reader = Reader::from_str(content);
+    let mut names = Vec::new();
+    let mut current: Option<NamedRange> = None;
+
+    loop {
+        let event = reader
+            .read_event()
+            .map_err(|e| format!("XML parse error: {}", e))?;
+        match &event {
+            Event::Start(e) if e.local_name().as_ref() == b"definedName" => {
+                current = Some(NamedRange {
+                    name: attribute(e, b"name").unwrap_or_default(),
+                    reference: String::new(),
+                    comment: attribute(e, b"comment"),
+                    local_sheet_id: attribute(e, b"localSheetId")
+                        .and_then(|id| id.parse::<u32>().ok())
+                        .map(|index| index + 1),
+                });
+            },
+            Event::End(e) if e.local_name().as_ref() == b"definedName" => {
+                if let Some(named_range) = current.take()
+                    && !named_range.name.is_empty()
+                {
+                    names.push(named_range);
+                }
+            },
+            Event::Eof => break,
+            _ => {
+                if let Some(named_range) = current.as_mut() {
+                    push_text(&event, &mut named_range.reference)?;
+                }
+            },
+        }
+    }
+    Ok(names)
+}
+
+/// Parse the `pivotCaches` list of workbook.xml into (cache ID, relationship ID) pairs.
+pub fn parse_pivot_caches(content: &str) -> Result<Vec<(u32, String)>> {
+    let mut reader = Reader::from_str(content);
+    let mut caches = Vec::new();
+    loop {
+        match reader
+            .read_event()
+            .map_err(|e| format!("XML parse error: {}", e))?
+        {
+            Event::Start(e) | Event::Empty(e) if e.local_name().as_ref() == b"pivotCache" => {
+                if let (Some(id), Some(r_id)) = (
+                    attribute(&e, b"cacheId").and_then(|id| id.parse().ok()),
+                    attribute(&e, b"id"),
+                ) {
+                    caches.push((id, r_id));
+                }
+            },
+            Event::Eof => break,
+            _ => {},
+        }
+    }
+    Ok(caches)
+}
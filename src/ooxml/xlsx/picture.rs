@@ -0,0 +1,98 @@
+//! Pictures placed on worksheets.
+//!
+//! Each picture is stored as a media part (`xl/media/imageN.png`) and
+//! anchored to the sheet by its `xl/drawings/drawingN.xml` part, which it
+//! shares with the sheet's charts.
+
+use crate::common::image::sniff_dimensions;
+use crate::ooxml::docx::ImageFormat;
+use crate::sheet::Result as SheetResult;
+
+/// EMUs per pixel at 96 DPI.
+const EMU_PER_PIXEL: i64 = 9525;
+
+/// Size used when the image header gives no dimensions (one inch).
+const DEFAULT_SIZE_EMU: i64 = 914_400;
+
+/// A picture to place on a worksheet.
+#[derive(Debug, Clone)]
+pub struct Picture {
+    /// Encoded image bytes
+    pub data: Vec<u8>,
+    pub format: ImageFormat,
+    pub anchor: PictureAnchor,
+    /// Shape name; defaults to "Picture N"
+    pub name: Option<String>,
+    /// Alternative text
+    pub description: Option<String>,
+}
+
+impl Picture {
+    /// Create a picture with its top-left corner at a cell (1-based), at
+    /// its natural size at 96 DPI.
+    pub fn new(data: Vec<u8>, row: u32, col: u32) -> SheetResult<Self> {
+        let (width, height) = sniff_dimensions(&data)
+            .map_or((DEFAULT_SIZE_EMU, DEFAULT_SIZE_EMU), |(width, height)| {
+                (width as i64 * EMU_PER_PIXEL, height as i64 * EMU_PER_PIXEL)
+            });
+        Self::with_anchor(
+            data,
+            PictureAnchor::OneCell {
+                row,
+                col,
+                width,
+                height,
+            },
+        )
+    }
+
+    /// Create a picture stretched over cells.
+    ///
+    /// `position` is `(start_row, start_col, end_row, end_col)`, 1-based and
+    /// inclusive, like chart positions.
+    pub fn over_cells(data: Vec<u8>, position: (u32, u32, u32, u32)) -> SheetResult<Self> {
+        let (start_row, start_col, end_row, end_col) = position;
+        Self::with_anchor(
+            data,
+            PictureAnchor::TwoCell {
+                start_row,
+                start_col,
+                end_row,
+                end_col,
+            },
+        )
+    }
+
+    /// Create a picture with the given anchor, detecting its format.
+    pub fn with_anchor(data: Vec<u8>, anchor: PictureAnchor) -> SheetResult<Self> {
+        let format = ImageFormat::detect_from_bytes(&data).ok_or("Unknown image format")?;
+        Ok(Self {
+            data,
+            format,
+            anchor,
+            name: None,
+            description: None,
+        })
+    }
+}
+
+/// How a picture is attached to the cells of its sheet.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PictureAnchor {
+    /// Top-left corner at a cell (1-based), with a size in EMUs; the picture
+    /// moves with the cell but keeps its size
+    OneCell {
+        row: u32,
+        col: u32,
+        width: i64,
+        height: i64,
+    },
+    /// Stretched over cells (1-based, inclusive); the picture moves and
+    /// resizes with them
+    TwoCell {
+        start_row: u32,
+        start_col: u32,
+        end_row: u32,
+        end_col: u32,
+    },
+}
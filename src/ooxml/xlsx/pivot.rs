@@ -0,0 +1,240 @@
+//! Pivot tables and pivot caches.
+//!
+//! A pivot cache (`xl/pivotCache/pivotCacheDefinitionN.xml`) describes the
+//! source data and its fields, and its records part keeps a snapshot of the
+//! source rows. Pivot tables (`xl/pivotTables/pivotTableN.xml`) summarize a
+//! cache on a worksheet. The cached records remain readable after the source
+//! sheet has been deleted.
+//!
+//! XLSB workbooks store these parts as BIFF12 records; see
+//! [`XlsbWorkbook::pivot_caches`](crate::ooxml::xlsb::XlsbWorkbook::pivot_caches).
+
+use crate::sheet::CellValue;
+
+/// The source data of pivot tables: its fields and cached records.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PivotCache {
+    /// ID that pivot tables refer to the cache by
+    pub id: u32,
+    pub source: PivotSource,
+    pub fields: Vec<CacheField>,
+    /// Snapshot of the source rows with one value per field; empty when the
+    /// workbook was saved without records
+    pub records: Vec<Vec<CellValue>>,
+}
+
+impl PivotCache {
+    /// Find a field by name, ignoring case.
+    pub fn field_index(&self, name: &str) -> Option<usize> {
+        self.fields
+            .iter()
+            .position(|field| field.name.eq_ignore_ascii_case(name))
+    }
+
+    /// The cached dataset as rows, starting with a row of field names.
+    ///
+    /// Calculated and grouping fields are left out, as they have no cached values.
+    pub fn to_rows(&self) -> Vec<Vec<CellValue>> {
+        let columns: Vec<usize> = (0..self.fields.len())
+            .filter(|&index| self.fields[index].database_field)
+            .collect();
+        let header = columns
+            .iter()
+            .map(|&index| CellValue::String(self.fields[index].name.clone()))
+            .collect();
+
+        std::iter::once(header)
+            .chain(self.records.iter().map(|record| {
+                columns
+                    .iter()
+                    .map(|&index| record.get(index).cloned().unwrap_or(CellValue::Empty))
+                    .collect()
+            }))
+            .collect()
+    }
+}
+
+/// Where a pivot cache's data comes from (`cacheSource`).
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PivotSource {
+    /// "worksheet", "external", "consolidation" or "scenario"
+    pub source_type: String,
+    /// Source sheet name
+    pub sheet: Option<String>,
+    /// Source range on `sheet` (e.g. "A1:D100")
+    pub range: Option<String>,
+    /// Defined name or table used as the source
+    pub name: Option<String>,
+}
+
+/// A field (column) of a pivot cache.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CacheField {
+    pub name: String,
+    /// Distinct values of the field; records and pivot items refer to them by index
+    pub shared_items: Vec<CellValue>,
+    /// Whether the records hold values for the field; false for calculated
+    /// fields and fields added by grouping
+    pub database_field: bool,
+    /// Formula of a calculated field
+    pub formula: Option<String>,
+    pub number_format_id: Option<u32>,
+}
+
+/// A pivot table on a worksheet.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PivotTable {
+    pub name: String,
+    /// ID of the [`PivotCache`] the table summarizes
+    pub cache_id: u32,
+    /// Cells the table covers, without page fields (e.g. "A3:E20")
+    pub location: String,
+    /// Pivot fields, one per cache field in the same order
+    pub fields: Vec<PivotField>,
+    /// Cache field indices shown on rows, outermost first
+    pub row_fields: Vec<usize>,
+    /// Cache field indices shown on columns, outermost first
+    pub column_fields: Vec<usize>,
+    /// Whether multiple data fields are listed down the rows rather than across columns
+    pub data_on_rows: bool,
+    /// Report filters shown above the table
+    pub page_fields: Vec<PageField>,
+    pub data_fields: Vec<DataField>,
+    /// Label, value and date filters on row and column fields
+    pub filters: Vec<PivotFilter>,
+}
+
+impl PivotTable {
+    /// The cache value of an item of a pivot field, e.g. the selected item
+    /// of a [`PageField`].
+    pub fn item_value<'a>(
+        &self,
+        cache: &'a PivotCache,
+        field: usize,
+        item: usize,
+    ) -> Option<&'a CellValue> {
+        let index = self.fields.get(field)?.items.get(item)?.index?;
+        cache.fields.get(field)?.shared_items.get(index)
+    }
+}
+
+/// Axis a pivot field is placed on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PivotAxis {
+    Row,
+    Column,
+    /// Report filter
+    Page,
+    Values,
+}
+
+impl PivotAxis {
+    pub(crate) fn parse(value: &str) -> Option<Self> {
+        match value {
+            "axisRow" => Some(Self::Row),
+            "axisCol" => Some(Self::Column),
+            "axisPage" => Some(Self::Page),
+            "axisValues" => Some(Self::Values),
+            _ => None,
+        }
+    }
+}
+
+/// How a pivot table shows a cache field.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct PivotField {
+    /// Custom caption replacing the cache field name
+    pub name: Option<String>,
+    pub axis: Option<PivotAxis>,
+    /// Whether the field is summarized in the values area
+    pub data_field: bool,
+    /// Items in display order
+    pub items: Vec<PivotItem>,
+}
+
+/// An item of a pivot field.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PivotItem {
+    /// Index of the cache field's shared item; `None` for subtotal items
+    pub index: Option<usize>,
+    /// Whether the item is filtered out
+    pub hidden: bool,
+    /// Subtotal kind (e.g. "default", "sum"); `None` for items holding a value
+    pub item_type: Option<String>,
+}
+
+/// A report filter of a pivot table.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PageField {
+    /// Cache field index
+    pub field: usize,
+    /// Selected item, as an index into the pivot field's items; `None` shows all
+    pub item: Option<usize>,
+    pub name: Option<String>,
+}
+
+/// A summarized field in the values area of a pivot table.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DataField {
+    /// Caption (e.g. "Sum of Units")
+    pub name: Option<String>,
+    /// Cache field index
+    pub field: usize,
+    pub function: PivotFunction,
+    /// Calculation shown instead of the plain value (e.g. "percentOfTotal")
+    pub show_data_as: Option<String>,
+    pub number_format_id: Option<u32>,
+}
+
+/// Aggregation of a data field.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum PivotFunction {
+    #[default]
+    Sum,
+    /// Count of non-empty values
+    Count,
+    Average,
+    Max,
+    Min,
+    Product,
+    /// Count of numbers
+    CountNums,
+    StdDev,
+    StdDevP,
+    Var,
+    VarP,
+}
+
+impl PivotFunction {
+    pub(crate) fn parse(value: &str) -> Option<Self> {
+        match value {
+            "sum" => Some(Self::Sum),
+            "count" => Some(Self::Count),
+            "average" => Some(Self::Average),
+            "max" => Some(Self::Max),
+            "min" => Some(Self::Min),
+            "product" => Some(Self::Product),
+            "countNums" => Some(Self::CountNums),
+            "stdDev" => Some(Self::StdDev),
+            "stdDevp" => Some(Self::StdDevP),
+            "var" => Some(Self::Var),
+            "varp" => Some(Self::VarP),
+            _ => None,
+        }
+    }
+}
+
+/// A label, value or date filter on a row or column field.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PivotFilter {
+    /// Cache field index of the filtered field
+    pub field: usize,
+    /// Filter kind (e.g. "captionBeginsWith", "valueGreaterThan", "count")
+    pub filter_type: String,
+    /// Data field a value filter compares, as an index into `data_fields`
+    pub data_field: Option<usize>,
+    /// First comparison value
+    pub value1: Option<String>,
+    /// Second comparison value of "between" filters
+    pub value2: Option<String>,
+}
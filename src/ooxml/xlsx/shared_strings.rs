@@ -82,16 +82,33 @@ impl SharedStrings {
     }
 
     /// Extract text content from <si> element - optimized version.
+    ///
+    /// The text of every run is joined, phonetic runs (`<rPh>`) are
+    /// skipped and entities are unescaped.
     fn extract_text_from_si(si_content: &str) -> Option<String> {
         let bytes = si_content.as_bytes();
+        let mut text = String::new();
+        let mut pos = 0;
 
-        if let Some(t_start) = memchr::memmem::find(bytes, b"<t>") {
-            let t_start_pos = t_start + 3;
-            if let Some(t_end) = memchr::memmem::find(&bytes[t_start_pos..], b"</t>") {
-                let text = &si_content[t_start_pos..t_start_pos + t_end];
-                return Some(text.to_string());
+        while let Some(offset) = memchr::memchr(b'<', &bytes[pos..]) {
+            let tag_start = pos + offset;
+            let tag_end = tag_start + memchr::memchr(b'>', &bytes[tag_start..])?;
+            let tag = &si_content[tag_start + 1..tag_end];
+            pos = tag_end + 1;
+
+            if tag.starts_with("rPh") && !tag.ends_with('/') {
+                let close = memchr::memmem::find(&bytes[pos..], b"</rPh>")?;
+                pos += close + 6;
+            } else if tag == "t" || tag.starts_with("t ") {
+                let t_end = memchr::memmem::find(&bytes[pos..], b"</t>")?;
+                let raw = &si_content[pos..pos + t_end];
+                match quick_xml::escape::unescape(raw) {
+                    Ok(unescaped) => text.push_str(&unescaped),
+                    Err(_) => text.push_str(raw),
+                }
+                pos += t_end + 4;
             }
         }
-        None
+        Some(text)
     }
 }
@@ -1,6 +1,6 @@
 //! Cell style format records.
 
-use super::Alignment;
+use super::{Alignment, Protection};
 
 /// Cell style information.
 ///
@@ -20,6 +20,8 @@ pub struct CellStyle {
     pub xf_id: Option<u32>,
     /// Alignment information
     pub alignment: Option<Alignment>,
+    /// Protection information
+    pub protection: Option<Protection>,
     /// Apply number format flag
     pub apply_number_format: bool,
     /// Apply font flag
@@ -30,6 +32,8 @@ pub struct CellStyle {
     pub apply_border: bool,
     /// Apply alignment flag
     pub apply_alignment: bool,
+    /// Apply protection flag
+    pub apply_protection: bool,
     /// Quote prefix flag (for preserving leading apostrophe)
     pub quote_prefix: bool,
 }
@@ -49,5 +53,17 @@ impl CellStyle {
             || self.fill_id.is_some()
             || self.border_id.is_some()
             || self.alignment.is_some()
+            || self.protection.is_some()
     }
 }
+
+/// A named cell style (an entry of `cellStyles`).
+#[derive(Debug, Clone)]
+pub struct NamedCellStyle {
+    /// Style name shown in Excel (e.g., "Normal", "Heading 1")
+    pub name: String,
+    /// Index into the cell style formats (cellStyleXfs)
+    pub xf_id: u32,
+    /// Built-in style ID (0 = Normal)
+    pub builtin_id: Option<u32>,
+}
@@ -18,10 +18,12 @@ pub enum Fill {
         /// Background color (RGB hex or theme color reference)
         bg_color: Option<String>,
     },
-    /// Gradient fill (simplified representation)
+    /// Gradient fill
     Gradient {
         /// Gradient type (linear or path)
         gradient_type: Option<String>,
+        /// Angle of a linear gradient in degrees
+        degree: Option<f64>,
         /// Gradient stops (position, color pairs)
         stops: Vec<(f64, String)>,
     },
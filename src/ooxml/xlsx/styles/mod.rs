@@ -14,6 +14,7 @@
 //! - `fill`: Fill patterns and colors
 //! - `border`: Border styles
 //! - `alignment`: Cell alignment information
+//! - `protection`: Cell protection flags
 //! - `cell_style`: Cell style format records
 //!
 //! # Example
@@ -38,17 +39,21 @@ mod fill;
 mod font;
 mod number_format;
 mod parser;
+mod protection;
 
 pub use alignment::Alignment;
 pub use border::{Border, BorderStyle};
-pub use cell_style::CellStyle;
+pub use cell_style::{CellStyle, NamedCellStyle};
 pub use fill::Fill;
 pub use font::Font;
 pub use number_format::NumberFormat;
+pub(crate) use number_format::builtin_format_code;
+pub use protection::Protection;
 
 use std::collections::HashMap;
 
 use crate::ooxml::error::Result;
+use crate::ooxml::xlsx::format::CellFormat;
 
 /// Styles collection for an Excel workbook.
 ///
@@ -68,6 +73,10 @@ pub struct Styles {
     pub cell_styles: Vec<CellStyle>,
     /// Cell format records (cellXfs - the actual styles applied to cells)
     pub cell_xfs: Vec<CellStyle>,
+    /// Named cell styles (cellStyles), each pointing into `cell_styles`
+    pub named_styles: Vec<NamedCellStyle>,
+    /// Differential formats (dxfs) used by conditional formatting and tables
+    pub dxfs: Vec<CellFormat>,
 }
 
 impl Styles {
@@ -78,7 +78,6 @@ pub fn is_date_format(format: &str) -> bool {
 ///
 /// Returns `None` if the ID is not a recognized built-in format.
 /// Built-in formats are Excel's standard formats (0-163).
-#[allow(dead_code)] // Reserved for future use
 pub(crate) fn builtin_format_code(id: u32) -> Option<&'static str> {
     match id {
         0 => Some("General"),
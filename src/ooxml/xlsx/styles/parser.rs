@@ -8,8 +8,12 @@ use std::collections::HashMap;
 use quick_xml::Reader;
 use quick_xml::events::Event;
 
-use super::{Alignment, Border, BorderStyle, CellStyle, Fill, Font, NumberFormat, Styles};
+use super::{
+    Alignment, Border, BorderStyle, CellStyle, Fill, Font, NamedCellStyle, NumberFormat,
+    Protection, Styles,
+};
 use crate::ooxml::error::{OoxmlError, Result};
+use crate::ooxml::xlsx::format::{CellBorder, CellFill, CellFillPatternType, CellFont, CellFormat};
 
 /// Parse styles from xl/styles.xml XML content.
 ///
@@ -24,7 +28,8 @@ pub fn parse_styles(content: &str) -> Result<Styles> {
 
     loop {
         buf.clear();
-        match reader.read_event_into(&mut buf) {
+        let event = reader.read_event_into(&mut buf);
+        match &event {
             Ok(Event::Start(e)) | Ok(Event::Empty(e)) => match e.local_name().as_ref() {
                 b"numFmts" => {
                     parse_number_formats(&mut reader, &mut styles.number_formats)?;
@@ -44,6 +49,14 @@ pub fn parse_styles(content: &str) -> Result<Styles> {
                 b"cellXfs" => {
                     parse_cell_xfs(&mut reader, &mut styles.cell_xfs)?;
                 },
+                b"cellStyle" => {
+                    if let Some(style) = parse_named_style(&reader, e) {
+                        styles.named_styles.push(style);
+                    }
+                },
+                b"dxfs" if matches!(event, Ok(Event::Start(_))) => {
+                    parse_dxfs(&mut reader, &mut styles.dxfs)?;
+                },
                 _ => {},
             },
             Ok(Event::Eof) => break,
@@ -308,30 +321,44 @@ fn parse_gradient_fill(
     start: &quick_xml::events::BytesStart,
 ) -> Result<Fill> {
     let mut gradient_type = None;
-    let stops = Vec::new();
+    let mut degree = None;
+    let mut stops = Vec::new();
 
-    // Get gradient type from attributes
     for attr in start.attributes().flatten() {
-        if attr.key.local_name().as_ref() == b"type"
-            && let Ok(value) = attr.decode_and_unescape_value(reader.decoder())
-        {
-            gradient_type = Some(value.to_string());
+        match attr.key.local_name().as_ref() {
+            b"type" => {
+                if let Ok(value) = attr.decode_and_unescape_value(reader.decoder()) {
+                    gradient_type = Some(value.to_string());
+                }
+            },
+            b"degree" => {
+                if let Ok(value) = attr.decode_and_unescape_value(reader.decoder()) {
+                    degree = value.parse::<f64>().ok();
+                }
+            },
+            _ => {},
         }
     }
 
-    // Skip to end of gradientFill (full gradient parsing can be added later)
     let mut buf = Vec::with_capacity(128);
-    let mut depth = 1;
+    let mut position = None;
     loop {
         buf.clear();
         match reader.read_event_into(&mut buf) {
-            Ok(Event::Start(e)) if e.local_name().as_ref() == b"gradientFill" => depth += 1,
-            Ok(Event::End(e)) if e.local_name().as_ref() == b"gradientFill" => {
-                depth -= 1;
-                if depth == 0 {
-                    break;
+            Ok(Event::Start(e)) if e.local_name().as_ref() == b"stop" => {
+                position = e
+                    .attributes()
+                    .flatten()
+                    .find(|attr| attr.key.local_name().as_ref() == b"position")
+                    .and_then(|attr| attr.decode_and_unescape_value(reader.decoder()).ok())
+                    .and_then(|value| value.parse::<f64>().ok());
+            },
+            Ok(Event::Start(e)) | Ok(Event::Empty(e)) if e.local_name().as_ref() == b"color" => {
+                if let (Some(position), Some(color)) = (position.take(), parse_color(reader, &e)?) {
+                    stops.push((position, color));
                 }
             },
+            Ok(Event::End(e)) if e.local_name().as_ref() == b"gradientFill" => break,
             Ok(Event::Eof) => break,
             Err(e) => return Err(OoxmlError::Xml(format!("XML error in gradientFill: {}", e))),
             _ => {},
@@ -340,6 +367,7 @@ fn parse_gradient_fill(
 
     Ok(Fill::Gradient {
         gradient_type,
+        degree,
         stops,
     })
 }
@@ -462,6 +490,122 @@ fn parse_border_side(
     }
 }
 
+/// Parse differential formats (dxfs).
+///
+/// Solid fills in a differential format carry their color in `bgColor`, and
+/// `patternType` may be left out; both are mapped to a solid [`CellFill`]
+/// whose `fg_color` is the fill color.
+fn parse_dxfs(reader: &mut Reader<&[u8]>, dxfs: &mut Vec<CellFormat>) -> Result<()> {
+    let mut buf = Vec::with_capacity(256);
+    let mut current: Option<CellFormat> = None;
+
+    loop {
+        buf.clear();
+        match reader.read_event_into(&mut buf) {
+            Ok(Event::Start(e)) => match e.local_name().as_ref() {
+                b"dxf" => current = Some(CellFormat::default()),
+                b"font" => {
+                    let font = parse_font(reader)?;
+                    if let Some(format) = current.as_mut() {
+                        format.font = Some(CellFont::from(&font));
+                    }
+                },
+                b"fill" => {
+                    let fill = parse_dxf_fill(reader)?;
+                    if let Some(format) = current.as_mut() {
+                        format.fill = fill;
+                    }
+                },
+                b"border" => {
+                    let border = parse_border(reader, &e)?;
+                    if let Some(format) = current.as_mut() {
+                        format.border = Some(CellBorder::from(&border));
+                    }
+                },
+                _ => {},
+            },
+            Ok(Event::Empty(e)) => match e.local_name().as_ref() {
+                b"dxf" => dxfs.push(CellFormat::default()),
+                b"numFmt" => {
+                    if let Some(format) = current.as_mut() {
+                        format.number_format = e
+                            .attributes()
+                            .flatten()
+                            .find(|attr| attr.key.local_name().as_ref() == b"formatCode")
+                            .and_then(|attr| {
+                                attr.decode_and_unescape_value(reader.decoder())
+                                    .ok()
+                                    .map(|v| v.into_owned())
+                            });
+                    }
+                },
+                _ => {},
+            },
+            Ok(Event::End(e)) => match e.local_name().as_ref() {
+                b"dxf" => dxfs.extend(current.take()),
+                b"dxfs" => break,
+                _ => {},
+            },
+            Ok(Event::Eof) => break,
+            Err(e) => return Err(OoxmlError::Xml(format!("XML error in dxfs: {}", e))),
+            _ => {},
+        }
+    }
+
+    Ok(())
+}
+
+/// Parse the fill of a differential format.
+fn parse_dxf_fill(reader: &mut Reader<&[u8]>) -> Result<Option<CellFill>> {
+    let mut buf = Vec::with_capacity(128);
+    let mut pattern_type = None;
+    let mut fg_color = None;
+    let mut bg_color = None;
+
+    loop {
+        buf.clear();
+        match reader.read_event_into(&mut buf) {
+            Ok(Event::Start(e)) | Ok(Event::Empty(e)) => match e.local_name().as_ref() {
+                b"patternFill" => {
+                    pattern_type = e
+                        .attributes()
+                        .flatten()
+                        .find(|attr| attr.key.local_name().as_ref() == b"patternType")
+                        .and_then(|attr| {
+                            attr.decode_and_unescape_value(reader.decoder())
+                                .ok()
+                                .map(|v| v.into_owned())
+                        });
+                },
+                b"fgColor" => fg_color = parse_color(reader, &e)?,
+                b"bgColor" => bg_color = parse_color(reader, &e)?,
+                _ => {},
+            },
+            Ok(Event::End(e)) if e.local_name().as_ref() == b"fill" => break,
+            Ok(Event::Eof) => break,
+            Err(e) => return Err(OoxmlError::Xml(format!("XML error in dxf fill: {}", e))),
+            _ => {},
+        }
+    }
+
+    match pattern_type.as_deref() {
+        None | Some("solid") => {
+            let color = bg_color.or(fg_color);
+            Ok(color.is_some().then(|| CellFill {
+                pattern_type: CellFillPatternType::Solid,
+                fg_color: crate::ooxml::xlsx::format::writer_color(&color),
+                bg_color: None,
+                gradient: None,
+            }))
+        },
+        Some(pattern_type) => Ok(CellFill::from_fill(&Fill::pattern(
+            pattern_type.to_string(),
+            fg_color,
+            bg_color,
+        ))),
+    }
+}
+
 /// Parse cell XFs (cell format records).
 fn parse_cell_xfs(reader: &mut Reader<&[u8]>, cell_xfs: &mut Vec<CellStyle>) -> Result<()> {
     let mut buf = Vec::with_capacity(512);
@@ -470,7 +614,11 @@ fn parse_cell_xfs(reader: &mut Reader<&[u8]>, cell_xfs: &mut Vec<CellStyle>) ->
         buf.clear();
         match reader.read_event_into(&mut buf) {
             Ok(Event::Start(e)) if e.local_name().as_ref() == b"xf" => {
-                let style = parse_xf(reader, &e)?;
+                let style = parse_xf(reader, &e, true)?;
+                cell_xfs.push(style);
+            },
+            Ok(Event::Empty(e)) if e.local_name().as_ref() == b"xf" => {
+                let style = parse_xf(reader, &e, false)?;
                 cell_xfs.push(style);
             },
             Ok(Event::End(e))
@@ -489,9 +637,12 @@ fn parse_cell_xfs(reader: &mut Reader<&[u8]>, cell_xfs: &mut Vec<CellStyle>) ->
 }
 
 /// Parse a single xf (format) element.
+///
+/// `has_children` is false for self-closing `<xf/>` elements.
 fn parse_xf(
     reader: &mut Reader<&[u8]>,
     start: &quick_xml::events::BytesStart,
+    has_children: bool,
 ) -> Result<CellStyle> {
     let mut style = CellStyle::new();
 
@@ -548,6 +699,11 @@ fn parse_xf(
                     style.apply_alignment = value == "1" || value == "true";
                 }
             },
+            b"applyProtection" => {
+                if let Ok(value) = attr.decode_and_unescape_value(reader.decoder()) {
+                    style.apply_protection = value == "1" || value == "true";
+                }
+            },
             b"quotePrefix" => {
                 if let Ok(value) = attr.decode_and_unescape_value(reader.decoder()) {
                     style.quote_prefix = value == "1" || value == "true";
@@ -557,6 +713,10 @@ fn parse_xf(
         }
     }
 
+    if !has_children {
+        return Ok(style);
+    }
+
     // Parse child elements
     let mut buf = Vec::with_capacity(256);
     loop {
@@ -567,6 +727,11 @@ fn parse_xf(
             {
                 style.alignment = Some(parse_alignment(reader, &e)?);
             },
+            Ok(Event::Start(e)) | Ok(Event::Empty(e))
+                if e.local_name().as_ref() == b"protection" =>
+            {
+                style.protection = Some(parse_protection(reader, &e));
+            },
             Ok(Event::End(e)) if e.local_name().as_ref() == b"xf" => break,
             Ok(Event::Eof) => break,
             Err(e) => return Err(OoxmlError::Xml(format!("XML error in xf: {}", e))),
@@ -628,6 +793,52 @@ fn parse_alignment(
     Ok(alignment)
 }
 
+/// Parse protection element.
+fn parse_protection(reader: &Reader<&[u8]>, start: &quick_xml::events::BytesStart) -> Protection {
+    let mut protection = Protection::default();
+
+    for attr in start.attributes().flatten() {
+        let Ok(value) = attr.decode_and_unescape_value(reader.decoder()) else {
+            continue;
+        };
+        match attr.key.local_name().as_ref() {
+            b"locked" => protection.locked = value == "1" || value == "true",
+            b"hidden" => protection.hidden = value == "1" || value == "true",
+            _ => {},
+        }
+    }
+
+    protection
+}
+
+/// Parse a `cellStyle` element (a named style).
+fn parse_named_style(
+    reader: &Reader<&[u8]>,
+    start: &quick_xml::events::BytesStart,
+) -> Option<NamedCellStyle> {
+    let mut name = None;
+    let mut xf_id = 0;
+    let mut builtin_id = None;
+
+    for attr in start.attributes().flatten() {
+        let Ok(value) = attr.decode_and_unescape_value(reader.decoder()) else {
+            continue;
+        };
+        match attr.key.local_name().as_ref() {
+            b"name" => name = Some(value.to_string()),
+            b"xfId" => xf_id = value.parse::<u32>().unwrap_or(0),
+            b"builtinId" => builtin_id = value.parse::<u32>().ok(),
+            _ => {},
+        }
+    }
+
+    Some(NamedCellStyle {
+        name: name?,
+        xf_id,
+        builtin_id,
+    })
+}
+
 /// Parse color from a color element.
 ///
 /// Colors can be specified as:
@@ -0,0 +1,21 @@
+//! Cell protection information.
+
+/// Protection settings for a cell.
+///
+/// These only take effect when the worksheet is protected.
+#[derive(Debug, Clone)]
+pub struct Protection {
+    /// Locked flag (cells are locked unless stated otherwise)
+    pub locked: bool,
+    /// Hidden flag (hides the formula in the formula bar)
+    pub hidden: bool,
+}
+
+impl Default for Protection {
+    fn default() -> Self {
+        Self {
+            locked: true,
+            hidden: false,
+        }
+    }
+}
@@ -0,0 +1,562 @@
+//! Tables (ListObjects), autofilters and structured references.
+//!
+//! A table is stored in its own `xl/tables/tableN.xml` part related to the
+//! worksheet. Formulas may refer to table areas with structured references
+//! such as `Table1[Sales]` or `Table1[[#Headers],[Region]:[Sales]]`, which
+//! [`resolve_structured_references`] turns into plain cell references.
+
+use crate::ooxml::xlsx::parsers::worksheet_parser::reference_to_coords;
+use crate::ooxml::xlsx::writer::MutableWorksheet;
+use crate::ooxml::xlsx::writer::chart::quote_sheet_name;
+
+/// An Excel table over a block of cells.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Table {
+    /// Table name used in structured references (e.g. "Sales")
+    pub name: String,
+    /// Cell range including the header and totals rows (e.g. "A1:D10")
+    pub range: String,
+    /// Whether the first row holds the column names
+    pub header_row: bool,
+    /// Whether the last row holds column totals
+    pub totals_row: bool,
+    pub columns: Vec<TableColumn>,
+    pub style: Option<TableStyle>,
+    /// Filter buttons and criteria; `None` hides the filter buttons
+    pub auto_filter: Option<AutoFilter>,
+}
+
+impl Table {
+    /// Create a table with a header row, filter buttons and the default style.
+    pub fn new(name: &str, range: &str, columns: &[&str]) -> Self {
+        Self {
+            name: name.to_string(),
+            range: range.to_string(),
+            header_row: true,
+            totals_row: false,
+            columns: columns.iter().map(|name| TableColumn::new(name)).collect(),
+            style: Some(TableStyle::default()),
+            auto_filter: Some(AutoFilter::default()),
+        }
+    }
+
+    /// The table range as (start_row, start_col, end_row, end_col), 1-based.
+    pub fn bounds(&self) -> Option<(u32, u32, u32, u32)> {
+        parse_range(&self.range)
+    }
+
+    /// The range covered by the filter: the table without its totals row.
+    pub(crate) fn filter_range(&self) -> Option<String> {
+        let (start_row, start_col, end_row, end_col) = self.bounds()?;
+        let end_row = end_row - u32::from(self.totals_row && end_row > start_row);
+        Some(format!(
+            "{}{}:{}{}",
+            MutableWorksheet::column_to_letters(start_col),
+            start_row,
+            MutableWorksheet::column_to_letters(end_col),
+            end_row
+        ))
+    }
+}
+
+/// A column of a table.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TableColumn {
+    /// Column name, which matches the header cell text
+    pub name: String,
+    /// Function computing the column's totals row cell
+    pub totals_function: Option<TotalsFunction>,
+    /// Text shown in the totals row instead of a function result
+    pub totals_label: Option<String>,
+    /// Formula filled into every data cell of the column
+    pub calculated_formula: Option<String>,
+}
+
+impl TableColumn {
+    pub fn new(name: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            totals_function: None,
+            totals_label: None,
+            calculated_formula: None,
+        }
+    }
+}
+
+/// Function of a totals row cell.
+#[derive(Debug, Clone, PartialEq)]
+pub enum TotalsFunction {
+    Sum,
+    Min,
+    Max,
+    Average,
+    /// Count of non-empty cells
+    Count,
+    /// Count of numeric cells
+    CountNums,
+    StdDev,
+    Var,
+    /// A custom totals formula
+    Custom(String),
+}
+
+impl TotalsFunction {
+    pub(crate) fn as_str(&self) -> &'static str {
+        match self {
+            Self::Sum => "sum",
+            Self::Min => "min",
+            Self::Max => "max",
+            Self::Average => "average",
+            Self::Count => "count",
+            Self::CountNums => "countNums",
+            Self::StdDev => "stdDev",
+            Self::Var => "var",
+            Self::Custom(_) => "custom",
+        }
+    }
+
+    /// Parse a `totalsRowFunction` value; `custom` takes the totals formula.
+    pub(crate) fn parse(value: &str, formula: Option<String>) -> Option<Self> {
+        Some(match value {
+            "sum" => Self::Sum,
+            "min" => Self::Min,
+            "max" => Self::Max,
+            "average" => Self::Average,
+            "count" => Self::Count,
+            "countNums" => Self::CountNums,
+            "stdDev" => Self::StdDev,
+            "var" => Self::Var,
+            "custom" => Self::Custom(formula.unwrap_or_default()),
+            _ => return None,
+        })
+    }
+
+    /// The formula Excel puts in the totals row cell of `column`.
+    pub(crate) fn cell_formula(&self, table: &str, column: &str) -> String {
+        let code = match self {
+            Self::Average => 101,
+            Self::CountNums => 102,
+            Self::Count => 103,
+            Self::Max => 104,
+            Self::Min => 105,
+            Self::StdDev => 107,
+            Self::Sum => 109,
+            Self::Var => 110,
+            Self::Custom(formula) => return formula.clone(),
+        };
+        format!("SUBTOTAL({},{}[{}])", code, table, escape_column(column))
+    }
+}
+
+/// Table style (`tableStyleInfo`).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TableStyle {
+    /// Built-in or custom table style name (e.g. "TableStyleMedium2")
+    pub name: String,
+    pub show_first_column: bool,
+    pub show_last_column: bool,
+    pub show_row_stripes: bool,
+    pub show_column_stripes: bool,
+}
+
+impl Default for TableStyle {
+    fn default() -> Self {
+        Self::new("TableStyleMedium2")
+    }
+}
+
+impl TableStyle {
+    /// Use a style with banded rows.
+    pub fn new(name: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            show_first_column: false,
+            show_last_column: false,
+            show_row_stripes: true,
+            show_column_stripes: false,
+        }
+    }
+}
+
+/// Filter buttons over a range, with the criteria of filtered columns.
+///
+/// Excel hides the rows that don't match when the filter is reapplied; rows
+/// are not hidden when the file is written.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct AutoFilter {
+    /// Filtered range including the header row; empty for a table's filter,
+    /// which always covers the table
+    pub range: String,
+    pub columns: Vec<FilterColumn>,
+}
+
+impl AutoFilter {
+    /// Add filter buttons to a range without filtering any column.
+    pub fn new(range: &str) -> Self {
+        Self {
+            range: range.to_string(),
+            columns: Vec::new(),
+        }
+    }
+
+    /// Filter a column, given as a 0-based offset within the range.
+    pub fn filter_column(mut self, column: u32, filter: ColumnFilter) -> Self {
+        self.columns.retain(|c| c.column != column);
+        self.columns.push(FilterColumn { column, filter });
+        self
+    }
+}
+
+/// Criteria applied to one column of an autofilter.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FilterColumn {
+    /// 0-based offset of the column within the filter range
+    pub column: u32,
+    pub filter: ColumnFilter,
+}
+
+/// How a column is filtered.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ColumnFilter {
+    /// Show rows whose cell text is one of `values`, and blank cells if `blank`
+    Values { values: Vec<String>, blank: bool },
+    /// Show rows matching one or two comparisons
+    Custom {
+        /// Whether both comparisons must match rather than either
+        and: bool,
+        conditions: Vec<CustomFilter>,
+    },
+    /// Show the top or bottom items or percent
+    Top10 {
+        top: bool,
+        percent: bool,
+        value: f64,
+    },
+    /// Show rows matching a dynamic criterion (e.g. "aboveAverage", "today")
+    Dynamic { filter_type: String },
+}
+
+/// A comparison of a custom filter; `value` may use `*` and `?` wildcards.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CustomFilter {
+    pub operator: FilterOperator,
+    pub value: String,
+}
+
+/// Comparison operator of a custom filter.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FilterOperator {
+    Equal,
+    NotEqual,
+    GreaterThan,
+    GreaterThanOrEqual,
+    LessThan,
+    LessThanOrEqual,
+}
+
+impl FilterOperator {
+    pub(crate) fn as_str(&self) -> &'static str {
+        match self {
+            Self::Equal => "equal",
+            Self::NotEqual => "notEqual",
+            Self::GreaterThan => "greaterThan",
+            Self::GreaterThanOrEqual => "greaterThanOrEqual",
+            Self::LessThan => "lessThan",
+            Self::LessThanOrEqual => "lessThanOrEqual",
+        }
+    }
+
+    pub(crate) fn parse(value: &str) -> Option<Self> {
+        match value {
+            "equal" => Some(Self::Equal),
+            "notEqual" => Some(Self::NotEqual),
+            "greaterThan" => Some(Self::GreaterThan),
+            "greaterThanOrEqual" => Some(Self::GreaterThanOrEqual),
+            "lessThan" => Some(Self::LessThan),
+            "lessThanOrEqual" => Some(Self::LessThanOrEqual),
+            _ => None,
+        }
+    }
+}
+
+/// Replace the structured references in a formula with cell references.
+///
+/// `tables` pairs each table with the name of its sheet. References to
+/// unknown tables or columns, `[#This Row]` references and unqualified
+/// references such as `[@Sales]` are left unchanged.
+///
+/// # Examples
+///
+/// ```rust
+/// use litchi::ooxml::xlsx::{Table, resolve_structured_references};
+///
+/// let table = Table::new("Sales", "B2:D6", &["Region", "Units", "Price"]);
+/// let tables = [("Data".to_string(), table)];
+/// assert_eq!(
+///     resolve_structured_references("SUM(Sales[Units])", &tables),
+///     "SUM(Data!$C$3:$C$6)"
+/// );
+/// ```
+pub fn resolve_structured_references(formula: &str, tables: &[(String, Table)]) -> String {
+    let mut resolved = String::with_capacity(formula.len());
+    let mut rest = formula;
+
+    while let Some(c) = rest.chars().next() {
+        // Copy string literals and quoted sheet names unchanged
+        if c == '"' || c == '\'' {
+            let end = rest[1..].find(c).map_or(rest.len(), |i| i + 2);
+            resolved.push_str(&rest[..end]);
+            rest = &rest[end..];
+            continue;
+        }
+
+        let name_len = rest
+            .find(|c: char| !(c.is_alphanumeric() || c == '_' || c == '.' || c == '\\'))
+            .unwrap_or(rest.len());
+        if name_len == 0 {
+            resolved.push(c);
+            rest = &rest[c.len_utf8()..];
+            continue;
+        }
+
+        let (name, after) = rest.split_at(name_len);
+        let reference = after
+            .starts_with('[')
+            .then(|| specifier_len(after))
+            .flatten()
+            .and_then(|len| {
+                let (sheet, table) = tables
+                    .iter()
+                    .find(|(_, table)| table.name.eq_ignore_ascii_case(name))?;
+                let area = resolve_specifier(table, &after[1..len - 1])?;
+                Some((format!("{}!{}", quote_sheet_name(sheet), area), len))
+            });
+        match reference {
+            Some((area, len)) => {
+                resolved.push_str(&area);
+                rest = &after[len..];
+            },
+            None => {
+                resolved.push_str(name);
+                rest = after;
+            },
+        }
+    }
+
+    resolved
+}
+
+/// Length of the bracketed specifier at the start of `s`, brackets included.
+fn specifier_len(s: &str) -> Option<usize> {
+    let mut depth = 0usize;
+    let mut escaped = false;
+    for (index, c) in s.char_indices() {
+        match c {
+            _ if escaped => escaped = false,
+            '\'' => escaped = true,
+            '[' => depth += 1,
+            ']' => {
+                depth -= 1;
+                if depth == 0 {
+                    return Some(index + 1);
+                }
+            },
+            _ => {},
+        }
+    }
+    None
+}
+
+/// Resolve the inside of a table specifier to an absolute cell range.
+fn resolve_specifier(table: &Table, specifier: &str) -> Option<String> {
+    let specifier = specifier.trim();
+    let items = if specifier.starts_with('[') {
+        split_items(specifier)?
+    } else if specifier.is_empty() {
+        Vec::new()
+    } else {
+        vec![(specifier, None)]
+    };
+
+    let (mut headers, mut data, mut totals) = (false, false, false);
+    let mut columns: Vec<usize> = Vec::new();
+    for (item, last) in items {
+        match item.to_ascii_lowercase().as_str() {
+            "#all" => (headers, data, totals) = (true, true, true),
+            "#data" => data = true,
+            "#headers" => headers = true,
+            "#totals" => totals = true,
+            special if special.starts_with('#') || special.starts_with('@') => return None,
+            _ => {
+                for name in [item, last.unwrap_or(item)] {
+                    let name = unescape_column(name);
+                    let index = table
+                        .columns
+                        .iter()
+                        .position(|c| c.name.eq_ignore_ascii_case(&name))?;
+                    columns.push(index);
+                }
+            },
+        }
+    }
+    if !headers && !totals {
+        data = true;
+    }
+
+    let (start_row, start_col, end_row, end_col) = table.bounds()?;
+    let header_row = table.header_row.then_some(start_row);
+    let totals_row = (table.totals_row && end_row > start_row).then_some(end_row);
+    let first_data = start_row + u32::from(header_row.is_some());
+    let last_data = end_row - u32::from(totals_row.is_some());
+
+    let mut rows: Vec<u32> = Vec::new();
+    if headers {
+        rows.push(header_row?);
+    }
+    if data {
+        if first_data > last_data {
+            return None;
+        }
+        rows.extend([first_data, last_data]);
+    }
+    if totals {
+        rows.push(totals_row?);
+    }
+    let (first_row, last_row) = (*rows.iter().min()?, *rows.iter().max()?);
+
+    let (first_col, last_col) = match (columns.iter().min(), columns.iter().max()) {
+        (Some(&first), Some(&last)) => (start_col + first as u32, start_col + last as u32),
+        _ => (start_col, end_col),
+    };
+    if last_col > end_col {
+        return None;
+    }
+
+    let start = format!(
+        "${}${}",
+        MutableWorksheet::column_to_letters(first_col),
+        first_row
+    );
+    let end = format!(
+        "${}${}",
+        MutableWorksheet::column_to_letters(last_col),
+        last_row
+    );
+    Some(if start == end {
+        start
+    } else {
+        format!("{}:{}", start, end)
+    })
+}
+
+/// Split `[#Headers],[Region]:[Sales]` into its items without brackets.
+///
+/// A column range gives its first and last column.
+fn split_items(specifier: &str) -> Option<Vec<(&str, Option<&str>)>> {
+    let mut items = Vec::new();
+    let mut rest = specifier.trim();
+    while !rest.is_empty() {
+        let len = specifier_len(rest)?;
+        let first = &rest[1..len - 1];
+        rest = &rest[len..];
+        let mut last = None;
+        if let Some(range) = rest.strip_prefix(':') {
+            let len = specifier_len(range)?;
+            last = Some(&range[1..len - 1]);
+            rest = &range[len..];
+        }
+        items.push((first, last));
+        rest = rest.trim_start();
+        rest = rest.strip_prefix(',').unwrap_or(rest).trim_start();
+    }
+    Some(items)
+}
+
+/// Remove the `'` escapes from a column name in a structured reference.
+fn unescape_column(name: &str) -> String {
+    let mut unescaped = String::with_capacity(name.len());
+    let mut chars = name.chars();
+    while let Some(c) = chars.next() {
+        if c == '\'' {
+            unescaped.extend(chars.next());
+        } else {
+            unescaped.push(c);
+        }
+    }
+    unescaped
+}
+
+/// Escape the characters of a column name that are special in structured references.
+fn escape_column(name: &str) -> String {
+    let mut escaped = String::with_capacity(name.len());
+    for c in name.chars() {
+        if matches!(c, '[' | ']' | '#' | '\'') {
+            escaped.push('\'');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
+
+/// Parse `A1:D10` into (start_row, start_col, end_row, end_col).
+fn parse_range(range: &str) -> Option<(u32, u32, u32, u32)> {
+    let range = range.replace('$', "");
+    let (start, end) = range.split_once(':').unwrap_or((&range, &range));
+    let (start_col, start_row) = reference_to_coords(start).ok()?;
+    let (end_col, end_row) = reference_to_coords(end).ok()?;
+    Some((
+        start_row.min(end_row),
+        start_col.min(end_col),
+        start_row.max(end_row),
+        start_col.max(end_col),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tables() -> Vec<(String, Table)> {
+        let mut table = Table::new("Sales", "A1:C5", &["Region", "Units [k]", "Price"]);
+        table.totals_row = true;
+        vec![("Q1 Data".to_string(), table)]
+    }
+
+    #[test]
+    fn test_resolve_structured_references() {
+        let tables = tables();
+        let resolve = |formula| resolve_structured_references(formula, &tables);
+
+        assert_eq!(resolve("SUM(Sales[Price])"), "SUM('Q1 Data'!$C$2:$C$4)");
+        assert_eq!(resolve("sales[[#Headers],[Region]]"), "'Q1 Data'!$A$1");
+        assert_eq!(resolve("Sales[#All]"), "'Q1 Data'!$A$1:$C$5");
+        assert_eq!(resolve("Sales[]"), "'Q1 Data'!$A$2:$C$4");
+        assert_eq!(
+            resolve("Sales[[#Data],[#Totals],[Units '[k']]:[Price]]"),
+            "'Q1 Data'!$B$2:$C$5"
+        );
+
+        // Left alone: unknown names, this-row references and string literals
+        assert_eq!(resolve("Other[Price]"), "Other[Price]");
+        assert_eq!(resolve("Sales[@Price]"), "Sales[@Price]");
+        assert_eq!(
+            resolve("Sales[[#This Row],[Price]]"),
+            "Sales[[#This Row],[Price]]"
+        );
+        assert_eq!(resolve(r#""Sales[Price]""#), r#""Sales[Price]""#);
+    }
+
+    #[test]
+    fn test_totals_formula() {
+        assert_eq!(
+            TotalsFunction::Sum.cell_formula("Sales", "Units [k]"),
+            "SUBTOTAL(109,Sales[Units '[k']])"
+        );
+        assert_eq!(
+            Table::new("T", "A1:B3", &["a", "b"])
+                .filter_range()
+                .unwrap(),
+            "A1:B3"
+        );
+    }
+}
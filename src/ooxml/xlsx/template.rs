@@ -3,9 +3,31 @@
 //! Provides minimal valid templates for creating new Excel workbooks.
 //! These templates contain the bare minimum structure required for a valid .xlsx file.
 //! Generate a minimal valid workbook.xml content.
+//!
+//! Also fills `{{name}}` placeholders in cell text as described in
+//! [`merge`](crate::ooxml::common::merge). Sections only work within a
+//! cell, since shared strings are not tied to rows.
 
+use crate::ooxml::common::TemplateData;
+use crate::ooxml::common::markup::{Element, Node};
+use crate::ooxml::common::merge::{self, Dialect};
+use crate::ooxml::error::Result;
 use xml_minifier::minified_xml;
 
+/// SpreadsheetML element names for filling templates.
+const DIALECT: Dialect = Dialect {
+    // Shared strings and inline strings
+    paragraphs: &["si", "is"],
+    run: "r",
+    text: "t",
+    row: None,
+    table: None,
+    paragraph_container: None,
+    line_break: None,
+    preserve_space: true,
+    paragraph_blocks: false,
+};
+
 /// Creates an empty workbook with one default sheet reference.
 pub fn default_workbook_xml() -> &'static str {
     minified_xml!("resources/workbook.xml")
@@ -46,3 +68,66 @@ pub fn default_core_props_xml() -> &'static str {
 pub fn default_app_props_xml() -> &'static str {
     minified_xml!("resources/docProps/app.xml")
 }
+
+/// Fill the placeholders of a shared strings or worksheet part. Returns
+/// `None` when the part has nothing to fill.
+pub(crate) fn render_part(xml: &str, data: &TemplateData) -> Result<Option<String>> {
+    if !xml.contains('{') {
+        return Ok(None);
+    }
+    merge::render(xml, data, &DIALECT, |nodes| wrap_plain_text(nodes))
+}
+
+/// Put the plain text of a string holding a section into a run, so the
+/// section can repeat part of it.
+fn wrap_plain_text(nodes: &mut [Node]) {
+    for node in nodes.iter_mut() {
+        let Node::Element(element) = node else {
+            continue;
+        };
+        if !DIALECT.paragraphs.contains(&element.local_name()) {
+            wrap_plain_text(&mut element.children);
+            continue;
+        }
+        for child in element.children.iter_mut() {
+            let Node::Element(text) = child else {
+                continue;
+            };
+            let content = text.text();
+            if text.local_name() != DIALECT.text
+                || !(content.contains("{{#") || content.contains("{{^"))
+            {
+                continue;
+            }
+            let prefix = text.name.strip_suffix(DIALECT.text).unwrap_or_default();
+            let name = format!("{prefix}{}", DIALECT.run);
+            let text = std::mem::replace(child, Node::Raw(String::new()));
+            *child = Node::Element(Element {
+                start: format!("<{name}>"),
+                end: format!("</{name}>"),
+                name,
+                children: vec![text],
+            });
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render_shared_strings() {
+        let xml = "<sst><si><t>Hello {{name}}</t></si>\
+            <si><t>{{#items}}{{.}};{{/items}}</t></si><si><t>Total</t></si></sst>";
+        let mut data = TemplateData::new();
+        data.set("name", "World").set("items", vec!["a", "b"]);
+        let out = render_part(xml, &data).unwrap().unwrap();
+        assert_eq!(
+            out,
+            "<sst><si><t xml:space=\"preserve\">Hello World</t></si>\
+            <si><r><t xml:space=\"preserve\">a;</t></r><r><t xml:space=\"preserve\">b;</t></r></si>\
+            <si><t>Total</t></si></sst>"
+        );
+    }
+}
@@ -3,10 +3,23 @@
 //! This module provides the concrete implementation of the Workbook trait
 //! for Excel (.xlsx) files using the Office Open XML format.
 
-use crate::ooxml::common::DocumentProperties;
+use crate::common::{EmbeddedImage, ImageAnchor};
+use crate::ooxml::common::{ChartData, DocumentProperties, TemplateData, collect_pictures};
+use crate::ooxml::opc::constants::relationship_type;
 use crate::ooxml::opc::{OpcPackage, PackURI};
-use crate::ooxml::xlsx::writer::{MutableWorkbookData, MutableWorksheet};
-use crate::ooxml::xlsx::{SharedStrings, Styles};
+use crate::ooxml::xlsx::parsers::pivot_parser::{
+    parse_pivot_cache_definition, parse_pivot_cache_records,
+};
+use crate::ooxml::xlsx::parsers::protection_parser::parse_workbook_protection;
+use crate::ooxml::xlsx::parsers::table_parser::parse_table;
+use crate::ooxml::xlsx::writer::loader::rich_shared_strings;
+use crate::ooxml::xlsx::writer::{
+    MutableWorkbookData, MutableWorksheet, chart, comment, picture, table,
+};
+use crate::ooxml::xlsx::{
+    HyperlinkTarget, NamedStyle, PivotCache, PivotTable, SharedStrings, Styles, Table,
+    WorkbookProtection, template,
+};
 use crate::sheet::{
     Result as SheetResult, WorkbookTrait, Worksheet as WorksheetTrait, WorksheetIterator,
 };
@@ -33,6 +46,8 @@ pub struct Workbook {
     mutable_data: Option<MutableWorkbookData>,
     /// Document properties (metadata)
     properties: DocumentProperties,
+    /// Whether the document properties were changed since opening
+    properties_modified: bool,
 }
 
 impl Workbook {
@@ -155,17 +170,133 @@ impl Workbook {
             active_sheet_index: 0,
             shared_strings: SharedStrings::new(),
             styles: Styles::new(),
-            mutable_data: Some(MutableWorkbookData::new()),
+            mutable_data: None,
             properties: DocumentProperties::new(),
+            properties_modified: false,
         };
 
         workbook.load_workbook_info()?;
         workbook.load_shared_strings()?;
         workbook.load_styles()?;
+        workbook.load_properties();
+        workbook.mutable_data = Some(workbook.load_mutable_data()?);
 
         Ok(workbook)
     }
 
+    /// Load document properties from docProps/core.xml.
+    fn load_properties(&mut self) {
+        if let Ok(metadata) = crate::ooxml::metadata::extract_metadata(&self.package) {
+            self.properties = DocumentProperties {
+                title: metadata.title,
+                subject: metadata.subject,
+                creator: metadata.author,
+                keywords: metadata.keywords,
+                description: metadata.description,
+                last_modified_by: metadata.last_modified_by,
+                created: metadata.created,
+                modified: metadata.modified,
+                ..DocumentProperties::new()
+            };
+        }
+    }
+
+    /// Mirror the package's workbook into mutable data.
+    ///
+    /// Only the sheet list, named ranges and styles are read here; worksheet
+    /// contents are loaded on first access through [`Self::worksheet_mut`] or
+    /// when the workbook is saved.
+    fn load_mutable_data(&self) -> SheetResult<MutableWorkbookData> {
+        let workbook_uri = PackURI::new("/xl/workbook.xml")?;
+        let workbook_part = self.package.get_part(&workbook_uri)?;
+        let workbook_xml = std::str::from_utf8(workbook_part.blob())?;
+
+        let styles_uri = PackURI::new("/xl/styles.xml")?;
+        let styles_xml = match self.package.get_part(&styles_uri) {
+            Ok(part) => Some(std::str::from_utf8(part.blob())?),
+            Err(_) => None,
+        };
+
+        let mut data = MutableWorkbookData::from_existing(
+            workbook_xml,
+            styles_xml,
+            &self.styles,
+            |relationship_id, sheet_id| {
+                workbook_part
+                    .rels()
+                    .get(relationship_id)
+                    .and_then(|rel| rel.target_partname().ok())
+                    .map(|uri| uri.to_string())
+                    .unwrap_or_else(|| format!("/xl/worksheets/sheet{}.xml", sheet_id))
+            },
+        )?;
+
+        // Rich-text strings are kept as written for the cells nobody edits
+        let shared_strings_part = workbook_part
+            .rels()
+            .iter()
+            .find(|rel| rel.reltype() == relationship_type::SHARED_STRINGS && !rel.is_external())
+            .and_then(|rel| rel.target_partname().ok())
+            .and_then(|uri| self.package.get_part(&uri).ok());
+        if let Some(part) = shared_strings_part {
+            data.rich_strings = rich_shared_strings(std::str::from_utf8(part.blob())?)?;
+        }
+        Ok(data)
+    }
+
+    /// Read a worksheet's existing part into its mutable counterpart, once.
+    fn load_mutable_worksheet(&mut self, index: usize) -> SheetResult<()> {
+        let Some(data) = self.mutable_data.as_mut() else {
+            return Ok(());
+        };
+        let rich_strings = &data.rich_strings;
+        let Some(worksheet) = data.worksheets.get_mut(index) else {
+            return Ok(());
+        };
+        let Some(source) = worksheet.source.as_ref().filter(|source| !source.loaded) else {
+            return Ok(());
+        };
+
+        let uri = PackURI::new(source.partname.as_str())?;
+        let Ok(part) = self.package.get_part(&uri) else {
+            return worksheet.load_xml("", &self.shared_strings, rich_strings, &self.styles);
+        };
+        worksheet.load_xml(
+            std::str::from_utf8(part.blob())?,
+            &self.shared_strings,
+            rich_strings,
+            &self.styles,
+        )?;
+
+        // External hyperlink targets are kept in the worksheet relationships
+        for hyperlink in &mut worksheet.hyperlinks {
+            if let (Some(r_id), HyperlinkTarget::External(url)) =
+                (&hyperlink.relationship_id, &mut hyperlink.target)
+                && let Some(rel) = part.rels().get(r_id)
+            {
+                url.insert_str(0, rel.target_ref());
+            }
+        }
+        Ok(())
+    }
+
+    /// Resolve the partname of a worksheet through the workbook relationships.
+    pub(crate) fn worksheet_partname(&self, info: &WorksheetInfo) -> SheetResult<PackURI> {
+        let workbook_uri = PackURI::new("/xl/workbook.xml")?;
+        let rel = self
+            .package
+            .get_part(&workbook_uri)
+            .ok()
+            .and_then(|part| part.rels().get(&info.relationship_id));
+        match rel {
+            Some(rel) => Ok(rel.target_partname()?),
+            None => Ok(PackURI::new(format!(
+                "/xl/worksheets/sheet{}.xml",
+                info.sheet_id
+            ))?),
+        }
+    }
+
     /// Load workbook information from workbook.xml
     fn load_workbook_info(&mut self) -> SheetResult<()> {
         let workbook_uri = PackURI::new("/xl/workbook.xml")?;
@@ -231,6 +362,32 @@ impl Workbook {
     pub(crate) fn shared_strings(&self) -> &SharedStrings {
         &self.shared_strings
     }
+
+    /// Get the parsed styles (for internal use by worksheet)
+    pub(crate) fn styles(&self) -> &Styles {
+        &self.styles
+    }
+
+    /// Get the formula of a sheet-scoped defined name (for internal use by worksheet)
+    pub(crate) fn sheet_defined_name(
+        &self,
+        info: &WorksheetInfo,
+        name: &str,
+    ) -> SheetResult<Option<String>> {
+        let Some(index) = self
+            .worksheets
+            .iter()
+            .position(|ws| ws.relationship_id == info.relationship_id)
+        else {
+            return Ok(None);
+        };
+        let workbook_part = self.package.get_part(&PackURI::new("/xl/workbook.xml")?)?;
+        let content = std::str::from_utf8(workbook_part.blob())?;
+        Ok(workbook_parser::parse_defined_names(content)?
+            .into_iter()
+            .find(|named| named.name == name && named.local_sheet_id == Some(index as u32 + 1))
+            .map(|named| named.reference))
+    }
 }
 
 impl WorkbookTrait for Workbook {
@@ -279,8 +436,193 @@ impl Workbook {
         Self::new(package)
     }
 
+    /// Open a workbook from a reader.
+    pub fn from_reader<R: std::io::Read + std::io::Seek>(reader: R) -> SheetResult<Self> {
+        let package = OpcPackage::from_reader(reader)?;
+        Self::new(package)
+    }
+
+    /// Get all pictures placed on the worksheets.
+    ///
+    /// Pictures are read from each worksheet's drawing part and anchored to
+    /// the top-left cell of their `xdr:from` marker (0-based row and column).
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use litchi::ooxml::xlsx::Workbook;
+    /// use litchi::ImageAnchor;
+    ///
+    /// let wb = Workbook::open("report.xlsx")?;
+    /// for image in wb.images()? {
+    ///     if let ImageAnchor::Cell { sheet, row, column } = &image.anchor {
+    ///         println!("{} on {} at ({}, {})", image.name, sheet, row, column);
+    ///     }
+    /// }
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn images(&self) -> SheetResult<Vec<EmbeddedImage>> {
+        let mut images = Vec::new();
+
+        for info in &self.worksheets {
+            let worksheet_uri = self.worksheet_partname(info)?;
+            let Ok(worksheet_part) = self.package.get_part(&worksheet_uri) else {
+                continue;
+            };
+
+            for rel in worksheet_part.rels().iter() {
+                if rel.reltype() != relationship_type::DRAWING || rel.is_external() {
+                    continue;
+                }
+                let Ok(drawing_part) = self.package.get_part(&rel.target_partname()?) else {
+                    continue;
+                };
+                images.extend(collect_pictures(drawing_part, &self.package, |picture| {
+                    let (row, column) = picture.cell.unwrap_or_default();
+                    ImageAnchor::Cell {
+                        sheet: info.name.clone(),
+                        row,
+                        column,
+                    }
+                })?);
+            }
+        }
+
+        Ok(images)
+    }
+
+    /// Get the charts drawn on all worksheets.
+    ///
+    /// Each chart is anchored at the 0-based cell of its top-left corner on
+    /// its worksheet, and carries its title, series names, category and value
+    /// references and the values cached in the chart part.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use litchi::ooxml::xlsx::Workbook;
+    ///
+    /// let wb = Workbook::open("report.xlsx")?;
+    /// for chart in wb.charts()? {
+    ///     println!("{:?} {:?}: {:?}", chart.kind, chart.title, chart.series_names());
+    /// }
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn charts(&self) -> SheetResult<Vec<ChartData>> {
+        let mut charts = Vec::new();
+        for info in &self.worksheets {
+            charts.extend(Worksheet::new(self, info.clone()).charts()?);
+        }
+        Ok(charts)
+    }
+
+    /// Get the tables of all worksheets, each paired with its sheet name.
+    pub fn tables(&self) -> SheetResult<Vec<(String, Table)>> {
+        let mut tables = Vec::new();
+        for info in &self.worksheets {
+            let sheet = info.name.clone();
+            tables.extend(
+                Worksheet::new(self, info.clone())
+                    .tables()?
+                    .into_iter()
+                    .map(|table| (sheet.clone(), table)),
+            );
+        }
+        Ok(tables)
+    }
+
+    /// Get the pivot caches with their fields and cached records.
+    ///
+    /// The records are a snapshot of the source data taken when the cache
+    /// was last refreshed, so the dataset behind a pivot table can be
+    /// recovered even if its source sheet was removed.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use litchi::ooxml::xlsx::Workbook;
+    ///
+    /// let wb = Workbook::open("report.xlsx")?;
+    /// for cache in wb.pivot_caches()? {
+    ///     for row in cache.to_rows() {
+    ///         println!("{:?}", row);
+    ///     }
+    /// }
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn pivot_caches(&self) -> SheetResult<Vec<PivotCache>> {
+        let workbook_part = self.package.get_part(&PackURI::new("/xl/workbook.xml")?)?;
+        let workbook_xml = std::str::from_utf8(workbook_part.blob())?;
+
+        let mut caches = Vec::new();
+        for (id, r_id) in workbook_parser::parse_pivot_caches(workbook_xml)? {
+            let Some(rel) = workbook_part
+                .rels()
+                .get(&r_id)
+                .filter(|rel| rel.reltype() == relationship_type::PIVOT_CACHE_DEFINITION)
+            else {
+                continue;
+            };
+            let Ok(definition_part) = self.package.get_part(&rel.target_partname()?) else {
+                continue;
+            };
+            let mut cache =
+                parse_pivot_cache_definition(std::str::from_utf8(definition_part.blob())?, id)?;
+
+            let records_part = definition_part
+                .rels()
+                .iter()
+                .find(|rel| rel.reltype() == relationship_type::PIVOT_CACHE_RECORDS)
+                .and_then(|rel| self.package.get_part(&rel.target_partname().ok()?).ok());
+            if let Some(records_part) = records_part {
+                cache.records =
+                    parse_pivot_cache_records(std::str::from_utf8(records_part.blob())?, &cache)?;
+            }
+            caches.push(cache);
+        }
+        Ok(caches)
+    }
+
+    /// Get the pivot tables of all worksheets, each paired with its sheet name.
+    pub fn pivot_tables(&self) -> SheetResult<Vec<(String, PivotTable)>> {
+        let mut tables = Vec::new();
+        for info in &self.worksheets {
+            let sheet = info.name.clone();
+            tables.extend(
+                Worksheet::new(self, info.clone())
+                    .pivot_tables()?
+                    .into_iter()
+                    .map(|table| (sheet.clone(), table)),
+            );
+        }
+        Ok(tables)
+    }
+
+    /// Replace the structured references to this workbook's tables in a
+    /// formula with cell references.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use litchi::ooxml::xlsx::Workbook;
+    ///
+    /// let wb = Workbook::open("sales.xlsx")?;
+    /// // e.g. "SUM(Data!$C$2:$C$100)"
+    /// println!("{}", wb.resolve_structured_references("SUM(Sales[Units])")?);
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn resolve_structured_references(&self, formula: &str) -> SheetResult<String> {
+        Ok(super::table::resolve_structured_references(
+            formula,
+            &self.tables()?,
+        ))
+    }
+
     /// Get a mutable worksheet for writing and modification.
     ///
+    /// For an opened workbook the worksheet starts out with the existing
+    /// cells, formulas, formats, merged ranges and column widths.
+    ///
     /// # Arguments
     ///
     /// * `index` - Worksheet index (0-based)
@@ -290,19 +632,20 @@ impl Workbook {
     /// ```rust,no_run
     /// use litchi::ooxml::xlsx::Workbook;
     ///
-    /// let mut wb = Workbook::create()?;
+    /// let mut wb = Workbook::open("report.xlsx")?;
     /// let mut ws = wb.worksheet_mut(0)?;
     ///
     /// ws.set_cell_value(1, 1, "Hello");
     /// ws.set_cell_value(1, 2, "World");
     ///
-    /// wb.save("output.xlsx")?;
+    /// wb.save("report.xlsx")?;
     /// # Ok::<(), Box<dyn std::error::Error>>(())
     /// ```
     pub fn worksheet_mut(&mut self, index: usize) -> SheetResult<&mut MutableWorksheet> {
         if self.mutable_data.is_none() {
-            self.mutable_data = Some(MutableWorkbookData::new());
+            self.mutable_data = Some(self.load_mutable_data()?);
         }
+        self.load_mutable_worksheet(index)?;
 
         self.mutable_data.as_mut().unwrap().worksheet_mut(index)
     }
@@ -336,6 +679,67 @@ impl Workbook {
             .add_worksheet(name.to_string())
     }
 
+    /// Add a named cell style that cells can refer to by name.
+    ///
+    /// A style with the same name replaces the earlier one.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use litchi::ooxml::xlsx::{CellFont, CellFormat, NamedStyle, Workbook};
+    ///
+    /// let mut wb = Workbook::create()?;
+    /// let heading = CellFormat {
+    ///     font: Some(CellFont { bold: true, size: Some(14.0), ..Default::default() }),
+    ///     ..Default::default()
+    /// };
+    /// wb.add_named_style(NamedStyle::new("Heading", heading))?;
+    /// wb.worksheet_mut(0)?.set_cell_style(1, 1, "Heading");
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn add_named_style(&mut self, style: NamedStyle) -> SheetResult<()> {
+        if self.mutable_data.is_none() {
+            self.mutable_data = Some(self.load_mutable_data()?);
+        }
+        self.mutable_data.as_mut().unwrap().add_named_style(style);
+        Ok(())
+    }
+
+    /// Get the workbook protection, if the workbook is protected.
+    pub fn protection(&self) -> SheetResult<Option<WorkbookProtection>> {
+        let workbook_part = self.package.get_part(&PackURI::new("/xl/workbook.xml")?)?;
+        parse_workbook_protection(std::str::from_utf8(workbook_part.blob())?)
+    }
+
+    /// Protect the workbook structure or windows.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use litchi::ooxml::xlsx::{Workbook, WorkbookProtection};
+    ///
+    /// let mut wb = Workbook::open("report.xlsx")?;
+    /// wb.protect_workbook(WorkbookProtection::with_password("secret"))?;
+    /// wb.save("report.xlsx")?;
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn protect_workbook(&mut self, protection: WorkbookProtection) -> SheetResult<()> {
+        if self.mutable_data.is_none() {
+            self.mutable_data = Some(self.load_mutable_data()?);
+        }
+        self.mutable_data.as_mut().unwrap().protect(protection);
+        Ok(())
+    }
+
+    /// Remove the workbook protection.
+    pub fn unprotect_workbook(&mut self) -> SheetResult<()> {
+        if self.mutable_data.is_none() {
+            self.mutable_data = Some(self.load_mutable_data()?);
+        }
+        self.mutable_data.as_mut().unwrap().unprotect();
+        Ok(())
+    }
+
     /// Define a named range.
     ///
     /// Named ranges allow you to refer to cells or ranges by meaningful names.
@@ -431,13 +835,69 @@ impl Workbook {
     /// # Ok::<(), Box<dyn std::error::Error>>(())
     /// ```
     pub fn properties_mut(&mut self) -> &mut DocumentProperties {
+        self.properties_modified = true;
         &mut self.properties
     }
 
+    /// Fill `{{placeholder}}` tags in the cell texts of the workbook.
+    ///
+    /// Shared strings and inline strings are rendered in place, keeping
+    /// the formatting of rich text runs. `{{#name}}...{{/name}}` sections
+    /// work within a single cell; rows are not repeated. See
+    /// [`merge`](crate::ooxml::common::merge) for the details.
+    ///
+    /// This works on the stored parts. Pending cell edits are written to
+    /// the parts first and reloaded from them afterwards.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use litchi::ooxml::common::TemplateData;
+    /// use litchi::ooxml::xlsx::Workbook;
+    ///
+    /// let mut workbook = Workbook::open("invoice-template.xlsx")?;
+    /// let mut data = TemplateData::new();
+    /// data.set("customer", "ACME Corp.");
+    /// workbook.render(&data)?;
+    /// workbook.save("invoice-acme.xlsx")?;
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn render(&mut self, data: &TemplateData) -> SheetResult<()> {
+        use crate::ooxml::opc::constants::content_type as ct;
+
+        self.write_mutable_data()?;
+
+        let uris: Vec<PackURI> = self
+            .package
+            .iter_parts()
+            .filter(|part| {
+                matches!(
+                    part.content_type(),
+                    ct::SML_SHARED_STRINGS | ct::SML_WORKSHEET
+                )
+            })
+            .map(|part| part.partname().clone())
+            .collect();
+
+        for uri in uris {
+            let part = self.package.get_part_mut(&uri)?;
+            let xml = std::str::from_utf8(part.blob())?;
+            if let Some(xml) = template::render_part(xml, data)? {
+                part.set_blob(xml.into_bytes());
+            }
+        }
+        self.load_shared_strings()?;
+        self.mutable_data = Some(self.load_mutable_data()?);
+        Ok(())
+    }
+
     /// Save the workbook to a file.
     ///
     /// Writes the complete Excel workbook including all worksheets, styles,
-    /// and shared strings to an .xlsx file.
+    /// and shared strings to an .xlsx file. Worksheets, styles, shared strings
+    /// and workbook.xml are only rewritten when something was modified; parts
+    /// the writer does not generate (charts, pivot tables, drawings, VBA
+    /// projects, ...) are copied unchanged.
     ///
     /// # Arguments
     /// * `path` - Path where the .xlsx file should be written
@@ -453,7 +913,46 @@ impl Workbook {
     /// # Ok::<(), Box<dyn std::error::Error>>(())
     /// ```
     pub fn save<P: AsRef<std::path::Path>>(&mut self, path: P) -> SheetResult<()> {
-        // If we have mutable data, update the workbook parts
+        self.write_pending_changes()?;
+        self.package.save(path)?;
+        Ok(())
+    }
+
+    /// Save the workbook to a writer.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use litchi::ooxml::xlsx::Workbook;
+    /// use std::io::Cursor;
+    ///
+    /// let mut workbook = Workbook::create()?;
+    /// let mut buffer = Cursor::new(Vec::new());
+    /// workbook.save_to_writer(&mut buffer)?;
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn save_to_writer<W: std::io::Write + std::io::Seek>(
+        &mut self,
+        writer: W,
+    ) -> SheetResult<()> {
+        self.write_pending_changes()?;
+        self.package.save_to_writer(writer)?;
+        Ok(())
+    }
+
+    /// Write modified mutable data and core properties into the parts.
+    fn write_pending_changes(&mut self) -> SheetResult<()> {
+        self.write_mutable_data()?;
+
+        // Update core properties
+        if self.properties_modified {
+            self.update_core_properties()?;
+        }
+        Ok(())
+    }
+
+    /// Write modified mutable data into the workbook parts.
+    fn write_mutable_data(&mut self) -> SheetResult<()> {
         let should_update = self
             .mutable_data
             .as_ref()
@@ -461,57 +960,138 @@ impl Workbook {
             .unwrap_or(false);
 
         if should_update {
+            // Every sheet is rewritten against the new shared strings and styles
+            let count = self
+                .mutable_data
+                .as_ref()
+                .map_or(0, |d| d.worksheet_count());
+            for index in 0..count {
+                self.load_mutable_worksheet(index)?;
+            }
+
             // Take mutable_data temporarily to avoid borrow issues
             if let Some(mut mutable_data) = self.mutable_data.take() {
-                self.update_workbook_parts(&mut mutable_data)?;
+                let result = self.update_workbook_parts(&mut mutable_data);
                 self.mutable_data = Some(mutable_data);
+                result?;
             }
-        }
 
-        // Update core properties
-        self.update_core_properties()?;
-
-        self.package.save(path)?;
+            // Refresh the read-only view of the workbook
+            self.load_workbook_info()?;
+            self.load_shared_strings()?;
+            self.load_styles()?;
+        }
         Ok(())
     }
 
     /// Update workbook parts with modified data.
+    ///
+    /// Existing parts are updated in place so their relationships (drawings,
+    /// tables, pivot caches, ...) are kept.
     fn update_workbook_parts(&mut self, data: &mut MutableWorkbookData) -> SheetResult<()> {
         use crate::ooxml::opc::constants::content_type as ct;
         use crate::ooxml::opc::constants::relationship_type as rt;
-        use crate::ooxml::opc::part::{BlobPart, Part};
+        use crate::ooxml::opc::part::BlobPart;
+        use crate::ooxml::xlsx::writer::loader::SheetSource;
 
         let workbook_uri = PackURI::new("/xl/workbook.xml")?;
-
-        // Create temporary workbook part to manage relationships
-        let mut temp_wb_part = BlobPart::new(
-            workbook_uri.clone(),
-            ct::SML_SHEET_MAIN.to_string(),
-            Vec::new(),
-        );
+        if !self.package.contains_part(&workbook_uri) {
+            self.package.add_part(Box::new(BlobPart::new(
+                workbook_uri.clone(),
+                ct::SML_SHEET_MAIN.to_string(),
+                Vec::new(),
+            )));
+            self.package
+                .relate_to("xl/workbook.xml", rt::OFFICE_DOCUMENT);
+        }
 
         // Build styles from all worksheets FIRST
         let (styles_builder, worksheet_style_indices) = data.build_styles()?;
 
         // Generate and write styles.xml
         let styles_xml = styles_builder.to_xml()?;
-        let styles_uri = PackURI::new("/xl/styles.xml")?;
-        let styles_part = BlobPart::new(
-            styles_uri,
-            ct::SML_STYLES.to_string(),
-            styles_xml.into_bytes(),
-        );
-        self.package.add_part(Box::new(styles_part));
-
-        // Create styles relationship
-        temp_wb_part.relate_to("styles.xml", rt::STYLES);
+        let styles_uri = self.related_partname(&workbook_uri, rt::STYLES, "/xl/styles.xml")?;
+        self.write_part(&styles_uri, ct::SML_STYLES, styles_xml.into_bytes());
+
+        // Charts, conditional formats and validations can't hold structured
+        // references, so resolve them against every table in the workbook
+        let tables = self.collect_tables(data)?;
+        let mut names: Vec<String> = tables.iter().map(|(_, t)| t.name.to_lowercase()).collect();
+        names.sort();
+        if let Some(pair) = names.windows(2).find(|pair| pair[0] == pair[1]) {
+            return Err(format!("Duplicate table name: {}", pair[0]).into());
+        }
+        for ws in &mut data.worksheets {
+            ws.resolve_structured_references(&tables);
+        }
+        let mut next_table_id = self
+            .package
+            .iter_parts()
+            .filter(|part| part.content_type() == ct::SML_TABLE)
+            .filter_map(|part| table::table_id(&String::from_utf8_lossy(part.blob())).ok()?)
+            .max()
+            .unwrap_or(0)
+            + 1;
+
+        // Chart parts read cell values from any sheet, so build them up front
+        let charts = data
+            .worksheets
+            .iter()
+            .map(|ws| {
+                ws.charts
+                    .iter()
+                    .map(|c| chart::chart_xml(c, ws, &data.worksheets))
+                    .collect::<SheetResult<Vec<_>>>()
+            })
+            .collect::<SheetResult<Vec<_>>>()?;
 
         // Track worksheet relationship IDs for workbook.xml generation
         let mut worksheet_rel_ids: Vec<String> = Vec::new();
 
         // Update worksheet parts and create relationships
         // IMPORTANT: Create relationships for ALL worksheets, not just modified ones
-        for (index, ws) in data.worksheets.iter().enumerate() {
+        data.shared_strings = Default::default();
+        for (index, (ws, charts)) in data.worksheets.iter_mut().zip(charts).enumerate() {
+            // Reuse the part a loaded sheet came from
+            let existing = ws.source.as_ref().and_then(|source| {
+                self.package
+                    .get_part(&workbook_uri)
+                    .ok()?
+                    .rels()
+                    .get(&source.relationship_id)
+                    .filter(|rel| rel.reltype() == rt::WORKSHEET)?;
+                Some((
+                    PackURI::new(source.partname.as_str()).ok()?,
+                    source.relationship_id.clone(),
+                ))
+            });
+            let (ws_uri, rid) = match existing {
+                Some(existing) => existing,
+                None => {
+                    let preferred =
+                        PackURI::new(format!("/xl/worksheets/sheet{}.xml", ws.sheet_id()))?;
+                    let ws_uri = if self.package.contains_part(&preferred) {
+                        self.package.next_partname("/xl/worksheets/sheet%d.xml")?
+                    } else {
+                        preferred
+                    };
+                    let rid = self
+                        .package
+                        .get_part_mut(&workbook_uri)?
+                        .relate_to(&ws_uri.relative_ref(workbook_uri.base_uri()), rt::WORKSHEET);
+                    let source = ws.source.get_or_insert_with(SheetSource::default);
+                    source.partname = ws_uri.to_string();
+                    source.relationship_id = rid.clone();
+                    source.loaded = true;
+                    (ws_uri, rid)
+                },
+            };
+
+            self.write_drawing(&ws_uri, ws, charts)?;
+            self.write_comments(&workbook_uri, &ws_uri, ws)?;
+            self.write_hyperlinks(&ws_uri, ws)?;
+            self.write_tables(&ws_uri, ws, &mut next_table_id)?;
+
             // Get style indices for this worksheet
             let style_indices = worksheet_style_indices
                 .get(index)
@@ -520,45 +1100,430 @@ impl Workbook {
 
             // Generate XML with proper style indices
             let ws_xml = ws.to_xml(&mut data.shared_strings, &style_indices)?;
-            let ws_uri = PackURI::new(format!("/xl/worksheets/sheet{}.xml", ws.sheet_id()))?;
-            let ws_part = BlobPart::new(ws_uri, ct::SML_WORKSHEET.to_string(), ws_xml.into_bytes());
-            self.package.add_part(Box::new(ws_part));
-
-            // Create relationship and track the ID (for ALL sheets)
-            let rel_target = format!("worksheets/sheet{}.xml", ws.sheet_id());
-            let rid = temp_wb_part.relate_to(
-                &rel_target,
-                "http://schemas.openxmlformats.org/officeDocument/2006/relationships/worksheet",
-            );
+            self.write_part(&ws_uri, ct::SML_WORKSHEET, ws_xml.into_bytes());
             worksheet_rel_ids.push(rid);
         }
 
+        // Drop worksheets that were removed, and the calculation chain, which
+        // may list cells that no longer hold formulas
+        let stale: Vec<(String, Option<PackURI>)> = self
+            .package
+            .get_part(&workbook_uri)?
+            .rels()
+            .iter()
+            .filter(|rel| {
+                (rel.reltype() == rt::WORKSHEET
+                    && !worksheet_rel_ids.iter().any(|id| id == rel.r_id()))
+                    || rel.reltype() == rt::CALC_CHAIN
+            })
+            .map(|rel| (rel.r_id().to_string(), rel.target_partname().ok()))
+            .collect();
+        for (r_id, partname) in stale {
+            self.package
+                .get_part_mut(&workbook_uri)?
+                .rels_mut()
+                .remove(&r_id);
+            if let Some(partname) = partname {
+                self.package.remove_part(&partname);
+            }
+        }
+
         // Update shared strings
         let ss_xml = data.shared_strings.to_xml()?;
-        let ss_uri = PackURI::new("/xl/sharedStrings.xml")?;
-        let ss_part = BlobPart::new(
-            ss_uri,
-            ct::SML_SHARED_STRINGS.to_string(),
-            ss_xml.into_bytes(),
-        );
-        self.package.add_part(Box::new(ss_part));
-
-        // Create shared strings relationship
-        temp_wb_part.relate_to(
-            "sharedStrings.xml",
-            "http://schemas.openxmlformats.org/officeDocument/2006/relationships/sharedStrings",
-        );
+        let ss_uri =
+            self.related_partname(&workbook_uri, rt::SHARED_STRINGS, "/xl/sharedStrings.xml")?;
+        self.write_part(&ss_uri, ct::SML_SHARED_STRINGS, ss_xml.into_bytes());
 
         // Now generate workbook XML with actual relationship IDs
         let workbook_xml = data.generate_workbook_xml_with_rels(&worksheet_rel_ids)?;
-        temp_wb_part.set_blob(workbook_xml.into_bytes());
+        self.package
+            .get_part_mut(&workbook_uri)?
+            .set_blob(workbook_xml.into_bytes());
 
-        // Add the workbook part to the package
-        self.package.add_part(Box::new(temp_wb_part));
+        Ok(())
+    }
+
+    /// Write a sheet's pending charts and pictures and anchor them in its
+    /// drawing part.
+    ///
+    /// They are added to the drawing the sheet already has; otherwise a new
+    /// drawing part is created and related to the sheet.
+    fn write_drawing(
+        &mut self,
+        sheet_uri: &PackURI,
+        ws: &mut MutableWorksheet,
+        charts: Vec<String>,
+    ) -> SheetResult<()> {
+        use crate::ooxml::opc::constants::content_type as ct;
+        use crate::ooxml::opc::constants::relationship_type as rt;
+
+        if charts.is_empty() && ws.pictures.is_empty() {
+            return Ok(());
+        }
+        if !self.package.contains_part(sheet_uri) {
+            self.write_part(sheet_uri, ct::SML_WORKSHEET, Vec::new());
+        }
+
+        let existing = self
+            .package
+            .get_part(sheet_uri)?
+            .rels()
+            .iter()
+            .find(|rel| rel.reltype() == rt::DRAWING && !rel.is_external())
+            .and_then(|rel| rel.target_partname().ok());
+        let drawing_uri = match existing {
+            Some(partname) => partname,
+            None => {
+                let partname = self.package.next_partname("/xl/drawings/drawing%d.xml")?;
+                let rid = self
+                    .package
+                    .get_part_mut(sheet_uri)?
+                    .relate_to(&partname.relative_ref(sheet_uri.base_uri()), rt::DRAWING);
+                ws.drawing = Some(rid);
+                partname
+            },
+        };
+        let drawing = match self.package.get_part(&drawing_uri) {
+            Ok(part) => String::from_utf8_lossy(part.blob()).into_owned(),
+            Err(_) => {
+                self.write_part(&drawing_uri, ct::OFC_DRAWING, Vec::new());
+                String::new()
+            },
+        };
+
+        let mut object_ids = chart::next_object_id(&drawing)?..;
+        let mut anchors = String::new();
+        for ((chart, chart_xml), object_id) in ws.charts.iter().zip(charts).zip(&mut object_ids) {
+            let chart_uri = self.package.next_partname("/xl/charts/chart%d.xml")?;
+            self.write_part(&chart_uri, ct::DML_CHART, chart_xml.into_bytes());
+            let rid = self
+                .package
+                .get_part_mut(&drawing_uri)?
+                .relate_to(&chart_uri.relative_ref(drawing_uri.base_uri()), rt::CHART);
+            anchors.push_str(&chart::anchor_xml(chart, object_id, &rid));
+        }
+        for (picture, object_id) in ws.pictures.iter().zip(&mut object_ids) {
+            let image_uri = self
+                .package
+                .next_partname(&format!("/xl/media/image%d.{}", picture.format.extension()))?;
+            self.write_part(&image_uri, picture.format.mime_type(), picture.data.clone());
+            let rid = self
+                .package
+                .get_part_mut(&drawing_uri)?
+                .relate_to(&image_uri.relative_ref(drawing_uri.base_uri()), rt::IMAGE);
+            anchors.push_str(&picture::anchor_xml(picture, object_id, &rid));
+        }
+
+        let drawing = if drawing.trim().is_empty() {
+            chart::drawing_xml(&anchors)
+        } else {
+            chart::append_anchors(&drawing, &anchors)?
+        };
+        self.package
+            .get_part_mut(&drawing_uri)?
+            .set_blob(drawing.into_bytes());
+        ws.charts.clear();
+        ws.pictures.clear();
+
+        Ok(())
+    }
+
+    /// Write a sheet's pending comments to its comments, VML and threaded
+    /// comments parts, creating the parts it doesn't have yet.
+    fn write_comments(
+        &mut self,
+        workbook_uri: &PackURI,
+        sheet_uri: &PackURI,
+        ws: &mut MutableWorksheet,
+    ) -> SheetResult<()> {
+        use crate::ooxml::opc::constants::content_type as ct;
+        use crate::ooxml::opc::constants::relationship_type as rt;
+
+        if ws.comments.is_empty() {
+            return Ok(());
+        }
+        if !self.package.contains_part(sheet_uri) {
+            self.write_part(sheet_uri, ct::SML_WORKSHEET, Vec::new());
+        }
+        let comments = std::mem::take(&mut ws.comments);
+
+        let mut thread_ids = vec![None; comments.len()];
+        let threads = self.related_part(sheet_uri, rt::THREADED_COMMENT)?;
+        if threads.is_some() || comments.iter().any(|comment| comment.threaded) {
+            let mut authors: Vec<&str> = Vec::new();
+            for comment in comments.iter().filter(|comment| comment.threaded) {
+                authors.push(&comment.author);
+                authors.extend(comment.replies.iter().map(|reply| reply.author.as_str()));
+            }
+            let persons_uri =
+                self.related_partname(workbook_uri, rt::PERSON, "/xl/persons/person.xml")?;
+            let (persons_xml, persons) =
+                comment::persons_xml(&self.part_text(&persons_uri), &authors)?;
+            self.write_part(&persons_uri, ct::SML_PERSON, persons_xml.into_bytes());
+
+            let threads_uri = match threads {
+                Some(partname) => partname,
+                None => self.relate_new_part(
+                    sheet_uri,
+                    "/xl/threadedComments/threadedComment%d.xml",
+                    rt::THREADED_COMMENT,
+                )?,
+            };
+            let timestamp = chrono::Utc::now()
+                .format("%Y-%m-%dT%H:%M:%S%.3f")
+                .to_string();
+            let (threads_xml, ids) = comment::threaded_comments_xml(
+                &self.part_text(&threads_uri),
+                &comments,
+                &persons,
+                threads_uri.as_str(),
+                &timestamp,
+            )?;
+            self.write_part(
+                &threads_uri,
+                ct::SML_THREADED_COMMENTS,
+                threads_xml.into_bytes(),
+            );
+            thread_ids = ids;
+        }
+
+        let comments_uri = match self.related_part(sheet_uri, rt::COMMENTS)? {
+            Some(partname) => partname,
+            None => self.relate_new_part(sheet_uri, "/xl/comments%d.xml", rt::COMMENTS)?,
+        };
+        let comments_xml =
+            comment::comments_xml(&self.part_text(&comments_uri), &comments, &thread_ids)?;
+        self.write_part(&comments_uri, ct::SML_COMMENTS, comments_xml.into_bytes());
+
+        // The legacyDrawing element names the VML part holding note shapes
+        let existing = ws
+            .source
+            .as_ref()
+            .and_then(|source| source.legacy_drawing.as_ref())
+            .or(ws.legacy_drawing.as_ref())
+            .and_then(|r_id| self.package.get_part(sheet_uri).ok()?.rels().get(r_id))
+            .and_then(|rel| rel.target_partname().ok());
+        let vml_uri = match existing {
+            Some(partname) => partname,
+            None => {
+                let partname = self
+                    .package
+                    .next_partname("/xl/drawings/vmlDrawing%d.vml")?;
+                let rid = self.package.get_part_mut(sheet_uri)?.relate_to(
+                    &partname.relative_ref(sheet_uri.base_uri()),
+                    rt::VML_DRAWING,
+                );
+                ws.legacy_drawing = Some(rid);
+                partname
+            },
+        };
+        let vml = comment::vml_xml(
+            &self.part_text(&vml_uri),
+            &comments,
+            vml_uri.idx().unwrap_or(1),
+        )?;
+        self.write_part(&vml_uri, ct::OFC_VML_DRAWING, vml.into_bytes());
+
+        Ok(())
+    }
+
+    /// Relate external hyperlink targets to the sheet and drop the
+    /// relationships of removed hyperlinks.
+    fn write_hyperlinks(
+        &mut self,
+        sheet_uri: &PackURI,
+        ws: &mut MutableWorksheet,
+    ) -> SheetResult<()> {
+        use crate::ooxml::opc::constants::content_type as ct;
+        use crate::ooxml::opc::constants::relationship_type as rt;
+
+        if !self.package.contains_part(sheet_uri) {
+            if ws.hyperlinks.is_empty() {
+                return Ok(());
+            }
+            self.write_part(sheet_uri, ct::SML_WORKSHEET, Vec::new());
+        }
+        let part = self.package.get_part_mut(sheet_uri)?;
+        for hyperlink in &mut ws.hyperlinks {
+            let HyperlinkTarget::External(url) = &hyperlink.target else {
+                hyperlink.relationship_id = None;
+                continue;
+            };
+            let current = hyperlink
+                .relationship_id
+                .as_ref()
+                .and_then(|r_id| part.rels().get(r_id))
+                .is_some_and(|rel| {
+                    rel.is_external() && rel.reltype() == rt::HYPERLINK && rel.target_ref() == url
+                });
+            if !current {
+                hyperlink.relationship_id = Some(part.relate_to_ext(url, rt::HYPERLINK));
+            }
+        }
+
+        let stale: Vec<String> = part
+            .rels()
+            .iter()
+            .filter(|rel| {
+                rel.reltype() == rt::HYPERLINK
+                    && !ws
+                        .hyperlinks
+                        .iter()
+                        .any(|link| link.relationship_id.as_deref() == Some(rel.r_id()))
+            })
+            .map(|rel| rel.r_id().to_string())
+            .collect();
+        for r_id in stale {
+            part.rels_mut().remove(&r_id);
+        }
+        Ok(())
+    }
+
+    /// The partname of the first internal part related to `source_uri` by `reltype`.
+    fn related_part(&self, source_uri: &PackURI, reltype: &str) -> SheetResult<Option<PackURI>> {
+        let Ok(source) = self.package.get_part(source_uri) else {
+            return Ok(None);
+        };
+        Ok(source
+            .rels()
+            .iter()
+            .find(|rel| rel.reltype() == reltype && !rel.is_external())
+            .map(|rel| rel.target_partname())
+            .transpose()?)
+    }
+
+    /// Relate a new part named after `template` to `source_uri`.
+    fn relate_new_part(
+        &mut self,
+        source_uri: &PackURI,
+        template: &str,
+        reltype: &str,
+    ) -> SheetResult<PackURI> {
+        let partname = self.package.next_partname(template)?;
+        self.package
+            .get_part_mut(source_uri)?
+            .relate_to(&partname.relative_ref(source_uri.base_uri()), reltype);
+        Ok(partname)
+    }
+
+    /// The content of a part as text, or an empty string if it doesn't exist.
+    fn part_text(&self, partname: &PackURI) -> String {
+        self.package
+            .get_part(partname)
+            .map(|part| String::from_utf8_lossy(part.blob()).into_owned())
+            .unwrap_or_default()
+    }
+
+    /// Write the sheet's pending tables to new table parts.
+    fn write_tables(
+        &mut self,
+        sheet_uri: &PackURI,
+        ws: &mut MutableWorksheet,
+        next_id: &mut u32,
+    ) -> SheetResult<()> {
+        use crate::ooxml::opc::constants::content_type as ct;
+        use crate::ooxml::opc::constants::relationship_type as rt;
+        use crate::ooxml::xlsx::writer::loader::SheetSource;
+
+        if ws.tables.is_empty() {
+            return Ok(());
+        }
+        if !self.package.contains_part(sheet_uri) {
+            self.write_part(sheet_uri, ct::SML_WORKSHEET, Vec::new());
+        }
+
+        for table in std::mem::take(&mut ws.tables) {
+            let table_uri = self.package.next_partname("/xl/tables/table%d.xml")?;
+            self.write_part(
+                &table_uri,
+                ct::SML_TABLE,
+                table::table_xml(&table, *next_id)?.into_bytes(),
+            );
+            *next_id += 1;
+            let rid = self
+                .package
+                .get_part_mut(sheet_uri)?
+                .relate_to(&table_uri.relative_ref(sheet_uri.base_uri()), rt::TABLE);
+            ws.source
+                .get_or_insert_with(SheetSource::default)
+                .table_parts
+                .push(rid);
+        }
 
         Ok(())
     }
 
+    /// Collect the tables of all sheets with their sheet names: those saved
+    /// in the package and those added since.
+    fn collect_tables(&self, data: &MutableWorkbookData) -> SheetResult<Vec<(String, Table)>> {
+        use crate::ooxml::opc::constants::relationship_type as rt;
+
+        let mut tables = Vec::new();
+        for ws in &data.worksheets {
+            let sheet_part = ws
+                .source
+                .as_ref()
+                .and_then(|source| PackURI::new(source.partname.as_str()).ok())
+                .and_then(|partname| self.package.get_part(&partname).ok());
+            if let Some(sheet_part) = sheet_part {
+                for rel in sheet_part.rels().iter() {
+                    if rel.reltype() != rt::TABLE || rel.is_external() {
+                        continue;
+                    }
+                    let Ok(part) = self.package.get_part(&rel.target_partname()?) else {
+                        continue;
+                    };
+                    let table = parse_table(std::str::from_utf8(part.blob())?)?;
+                    tables.push((ws.name().to_string(), table));
+                }
+            }
+            tables.extend(
+                ws.tables
+                    .iter()
+                    .map(|table| (ws.name().to_string(), table.clone())),
+            );
+        }
+        Ok(tables)
+    }
+
+    /// Find the part related to `source_uri` by `reltype`, relating `default` if there is none.
+    fn related_partname(
+        &mut self,
+        source_uri: &PackURI,
+        reltype: &str,
+        default: &str,
+    ) -> SheetResult<PackURI> {
+        let source = self.package.get_part_mut(source_uri)?;
+        if let Some(partname) = source
+            .rels()
+            .iter()
+            .find(|rel| rel.reltype() == reltype && !rel.is_external())
+            .and_then(|rel| rel.target_partname().ok())
+        {
+            return Ok(partname);
+        }
+
+        let partname = PackURI::new(default)?;
+        source.relate_to(&partname.relative_ref(source_uri.base_uri()), reltype);
+        Ok(partname)
+    }
+
+    /// Replace the content of a part, creating it if needed.
+    ///
+    /// Existing parts keep their relationships.
+    fn write_part(&mut self, partname: &PackURI, content_type: &str, blob: Vec<u8>) {
+        use crate::ooxml::opc::part::BlobPart;
+
+        match self.package.get_part_mut(partname) {
+            Ok(part) => part.set_blob(blob),
+            Err(_) => self.package.add_part(Box::new(BlobPart::new(
+                partname.clone(),
+                content_type.to_string(),
+                blob,
+            ))),
+        }
+    }
+
     /// Update the core.xml properties part.
     fn update_core_properties(&mut self) -> SheetResult<()> {
         use crate::ooxml::opc::constants::content_type as ct;
@@ -582,20 +1547,15 @@ impl Workbook {
     }
 
     // TODO: Apache POI features not yet implemented:
-    // - Charts: add_chart(), get_charts(), create_chart(), update_chart()
-    // - Pivot tables: add_pivot_table(), get_pivot_tables(), refresh_pivot_table()
+    // - Charts: update_chart()
+    // - Pivot tables: add_pivot_table(), refresh_pivot_table()
     // - Data validation (reading): get_data_validation(), add_data_validation()
-    // - Conditional formatting: add_conditional_formatting(), get_conditional_formatting()
-    // - Comments: add_comment(), get_comments(), delete_comment()
-    // - Images/Pictures: add_picture(), get_pictures(), delete_picture()
-    // - Page setup: set_page_setup(), get_page_setup(), set_print_area()
-    // - Protection: protect_sheet(), unprotect_sheet(), protect_workbook(), unprotect_workbook()
+    // - Comments: get_comments(), delete_comment()
+    // - Images/Pictures: delete_picture()
     // - Hidden sheets: hide_sheet(), unhide_sheet(), is_sheet_hidden()
     // - Sheet ordering: move_sheet(), reorder_sheets()
-    // - Cell styles (advanced): create_cell_style(), clone_cell_style()
-    // - Hyperlinks: add_hyperlink(), get_hyperlinks(), remove_hyperlink()
+    // - Cell styles (advanced): clone_cell_style()
     // - Merged cells (reading): get_merged_regions(), merge_cells(), unmerge_cells()
-    // - Auto-filter: set_auto_filter(), get_auto_filter()
     // - Column width/Row height: set_column_width(), get_column_width(), set_row_height()
     // - Sheet visibility: set_sheet_visibility(), get_sheet_visibility()
     // - Workbook calculation mode: set_force_formula_recalculation(), get_calculation_mode()
@@ -610,3 +1570,892 @@ impl Workbook {
     // - Timeline: add_timeline(), get_timelines()
     // - Power Query: get_power_query_connections()
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sheet::CellValue;
+    use std::io::Cursor;
+
+    /// Save the workbook to memory and open the saved copy.
+    fn reopen(workbook: &mut Workbook) -> Workbook {
+        let mut buffer = Cursor::new(Vec::new());
+        workbook.save_to_writer(&mut buffer).unwrap();
+        buffer.set_position(0);
+        Workbook::from_reader(buffer).unwrap()
+    }
+
+    #[test]
+    fn test_render_keeps_pending_edits() {
+        let mut workbook = Workbook::create().unwrap();
+        workbook
+            .worksheet_mut(0)
+            .unwrap()
+            .set_cell_value(1, 1, "Hi {{name}}");
+        workbook
+            .render(&TemplateData::new().with("name", "Ann"))
+            .unwrap();
+        assert!(!workbook.mutable_data.as_ref().unwrap().is_modified());
+
+        let sheet = workbook.worksheet_by_index(0).unwrap();
+        assert_eq!(
+            sheet.cell_value(1, 1).unwrap().as_ref(),
+            &CellValue::String("Hi Ann".to_string())
+        );
+    }
+
+    #[test]
+    fn test_edit_saved_workbook() {
+        let mut workbook = Workbook::create().unwrap();
+        {
+            let sheet = workbook.worksheet_mut(0).unwrap();
+            sheet.set_cell_value(1, 1, "Name");
+            sheet.set_cell_value(2, 1, 42.0);
+            sheet.set_cell_formula(3, 1, "A2*2");
+            sheet.set_column_width(1, 20.0);
+        }
+        workbook.add_worksheet("Notes").set_cell_value(1, 1, "kept");
+
+        let mut workbook = reopen(&mut workbook);
+        workbook
+            .worksheet_mut(0)
+            .unwrap()
+            .set_cell_value(2, 1, 43.0);
+
+        let workbook = reopen(&mut workbook);
+        assert_eq!(workbook.worksheet_names(), &["Sheet1", "Notes"]);
+        let sheet = workbook.worksheet_by_index(0).unwrap();
+        assert_eq!(
+            sheet.cell_value(1, 1).unwrap().as_ref(),
+            &CellValue::String("Name".to_string())
+        );
+        assert_eq!(
+            sheet.cell_value(2, 1).unwrap().as_ref(),
+            &CellValue::Int(43)
+        );
+        let notes = workbook.worksheet_by_name("Notes").unwrap();
+        assert_eq!(
+            notes.cell_value(1, 1).unwrap().as_ref(),
+            &CellValue::String("kept".to_string())
+        );
+        assert!(
+            workbook
+                .package()
+                .contains_part(&PackURI::new("/xl/theme/theme1.xml").unwrap())
+        );
+        let sheet_xml = workbook
+            .package()
+            .get_part(&PackURI::new("/xl/worksheets/sheet1.xml").unwrap())
+            .unwrap()
+            .blob()
+            .to_vec();
+        let sheet_xml = String::from_utf8(sheet_xml).unwrap();
+        assert!(sheet_xml.contains("<f>A2*2</f>"));
+        assert!(sheet_xml.contains("width=\"20\""));
+    }
+
+    #[test]
+    fn test_edit_keeps_rich_text_and_row_markup() {
+        use crate::ooxml::xlsx::{CellFont, CellFormat};
+
+        let part_xml = |workbook: &Workbook, partname: &str| {
+            let part = workbook
+                .package()
+                .get_part(&PackURI::new(partname).unwrap())
+                .unwrap();
+            String::from_utf8(part.blob().to_vec()).unwrap()
+        };
+
+        let mut workbook = Workbook::create().unwrap();
+        workbook.worksheet_mut(0).unwrap().set_cell_format(
+            5,
+            2,
+            CellFormat {
+                font: Some(CellFont {
+                    bold: true,
+                    ..Default::default()
+                }),
+                ..Default::default()
+            },
+        );
+        let mut workbook = reopen(&mut workbook);
+
+        // A rich-text cell, a grouped and styled row and a grouped column,
+        // as Excel writes them
+        let sheet_xml = part_xml(&workbook, "/xl/worksheets/sheet1.xml");
+        let start = sheet_xml.find("<sheetData").unwrap();
+        let end = sheet_xml.find("</sheetData>").unwrap() + "</sheetData>".len();
+        let sheet_xml = format!(
+            "{}<cols><col min=\"2\" max=\"3\" width=\"12\" customWidth=\"1\" style=\"1\" \
+             outlineLevel=\"1\" bestFit=\"1\"/></cols><sheetData><row r=\"1\"><c r=\"A1\" t=\"s\">\
+             <v>0</v></c></row><row r=\"2\" s=\"1\" customFormat=\"1\" outlineLevel=\"1\" \
+             collapsed=\"1\"><c r=\"A2\" t=\"s\"><v>1</v></c></row></sheetData>{}",
+            &sheet_xml[..start],
+            &sheet_xml[end..]
+        );
+        let rich =
+            "<si><r><rPr><b/></rPr><t>Bold</t></r><r><t xml:space=\"preserve\"> text</t></r></si>";
+        let strings_xml = format!(
+            "<sst xmlns=\"http://schemas.openxmlformats.org/spreadsheetml/2006/main\">{}\
+             <si><t>plain</t></si></sst>",
+            rich
+        );
+        for (partname, xml) in [
+            ("/xl/worksheets/sheet1.xml", sheet_xml),
+            ("/xl/sharedStrings.xml", strings_xml),
+        ] {
+            workbook
+                .package
+                .get_part_mut(&PackURI::new(partname).unwrap())
+                .unwrap()
+                .set_blob(xml.into_bytes());
+        }
+        let mut workbook = reopen(&mut workbook);
+
+        // Edit another cell, so the whole sheet is written again
+        workbook
+            .worksheet_mut(0)
+            .unwrap()
+            .set_cell_value(10, 1, "edited");
+        let workbook = reopen(&mut workbook);
+
+        let sheet = workbook.worksheet_by_index(0).unwrap();
+        assert_eq!(
+            sheet.cell_value(1, 1).unwrap().as_ref(),
+            &CellValue::String("Bold text".to_string())
+        );
+        assert!(part_xml(&workbook, "/xl/sharedStrings.xml").contains(rich));
+
+        let sheet_xml = part_xml(&workbook, "/xl/worksheets/sheet1.xml");
+        let row = &sheet_xml[sheet_xml.find("<row r=\"2\"").unwrap()..];
+        let row = &row[..row.find('>').unwrap()];
+        assert!(row.contains(" customFormat=\"1\""));
+        assert!(row.contains(" outlineLevel=\"1\" collapsed=\"1\""));
+        let col = &sheet_xml[sheet_xml.find("<col ").unwrap()..];
+        let col = &col[..col.find("/>").unwrap()];
+        assert!(col.contains(" style="));
+        assert!(col.contains(" outlineLevel=\"1\" bestFit=\"1\""));
+
+        // The row and column keep the bold format
+        let styles = &workbook.styles;
+        for style in [row, col].map(|markup| {
+            let start = markup.find(" s=\"").or(markup.find(" style=\"")).unwrap();
+            let value = &markup[start..];
+            let value = &value[value.find('"').unwrap() + 1..];
+            value[..value.find('"').unwrap()].parse::<usize>().unwrap()
+        }) {
+            let font = styles.get_cell_style(style).unwrap().font_id.unwrap();
+            assert!(styles.fonts[font as usize].bold);
+        }
+    }
+
+    #[test]
+    fn test_save_worksheet_charts() {
+        use crate::ooxml::opc::constants::relationship_type as rt;
+        use crate::ooxml::xlsx::ChartType;
+
+        let mut workbook = Workbook::create().unwrap();
+        {
+            let sheet = workbook.worksheet_mut(0).unwrap();
+            sheet.set_cell_value(1, 1, "Month");
+            sheet.set_cell_value(1, 2, "Sales");
+            sheet.set_cell_value(2, 1, "Jan");
+            sheet.set_cell_value(2, 2, 10.0);
+            sheet.set_cell_value(3, 1, "Feb");
+            sheet.set_cell_value(3, 2, 12.0);
+            sheet.merge_cells(5, 1, 5, 2);
+            sheet.add_chart(ChartType::Column, "Sales", "A1:B3", (6, 1, 20, 8), true);
+        }
+
+        // A second chart added after reopening joins the existing drawing
+        let mut workbook = reopen(&mut workbook);
+        workbook.worksheet_mut(0).unwrap().add_chart(
+            ChartType::Pie,
+            "Share",
+            "Sheet1!A2:B3",
+            (6, 10, 20, 16),
+            false,
+        );
+
+        let workbook = reopen(&mut workbook);
+        let package = workbook.package();
+        let sheet_uri = PackURI::new("/xl/worksheets/sheet1.xml").unwrap();
+        let sheet = package.get_part(&sheet_uri).unwrap();
+        let sheet_xml = String::from_utf8(sheet.blob().to_vec()).unwrap();
+        let drawing_rel = sheet
+            .rels()
+            .iter()
+            .find(|rel| rel.reltype() == rt::DRAWING)
+            .unwrap();
+        assert_eq!(sheet_xml.matches("<drawing ").count(), 1);
+        assert!(sheet_xml.contains(&format!(r#"<drawing r:id="{}"/>"#, drawing_rel.r_id())));
+        assert!(sheet_xml.find("</mergeCells>") < sheet_xml.find("<drawing "));
+
+        let drawing = package
+            .get_part(&drawing_rel.target_partname().unwrap())
+            .unwrap();
+        let drawing_xml = String::from_utf8(drawing.blob().to_vec()).unwrap();
+        assert_eq!(drawing_xml.matches("<xdr:twoCellAnchor>").count(), 2);
+        assert!(drawing_xml.contains(r#"<xdr:cNvPr id="3" name="Chart 2"/>"#));
+
+        let charts: Vec<String> = drawing
+            .rels()
+            .iter()
+            .filter(|rel| rel.reltype() == rt::CHART)
+            .map(|rel| {
+                let part = package.get_part(&rel.target_partname().unwrap()).unwrap();
+                String::from_utf8(part.blob().to_vec()).unwrap()
+            })
+            .collect();
+        assert_eq!(charts.len(), 2);
+        assert!(
+            charts
+                .iter()
+                .any(|xml| xml.contains("<c:barChart>")
+                    && xml.contains("<c:f>Sheet1!$B$2:$B$3</c:f>"))
+        );
+        assert!(charts.iter().any(|xml| xml.contains("<c:pieChart>")));
+
+        let charts = workbook.charts().unwrap();
+        assert_eq!(charts.len(), 2);
+        assert_eq!(charts[0].title.as_deref(), Some("Sales"));
+        assert_eq!(charts[0].series_names(), vec!["Sales"]);
+        assert_eq!(charts[0].category_names(), vec!["Jan", "Feb"]);
+        assert_eq!(
+            charts[0].anchor,
+            ImageAnchor::Cell {
+                sheet: "Sheet1".to_string(),
+                row: 5,
+                column: 0
+            }
+        );
+        assert_eq!(
+            charts[1].series[0].values.as_ref().unwrap().numbers(),
+            vec![Some(10.0), Some(12.0)]
+        );
+    }
+
+    #[test]
+    fn test_save_pictures_comments_and_hyperlinks() {
+        use crate::ooxml::opc::constants::relationship_type as rt;
+        use crate::ooxml::xlsx::{Hyperlink, Picture};
+
+        let mut png = vec![0x89, b'P', b'N', b'G', 0x0D, 0x0A, 0x1A, 0x0A, 0, 0, 0, 13];
+        png.extend_from_slice(b"IHDR");
+        png.extend_from_slice(&[0, 0, 0, 4, 0, 0, 0, 2, 8, 6, 0, 0, 0]);
+
+        let mut workbook = Workbook::create().unwrap();
+        {
+            let sheet = workbook.worksheet_mut(0).unwrap();
+            sheet.set_cell_value(1, 1, "Total");
+            let mut picture = Picture::new(png.clone(), 3, 2).unwrap();
+            picture.description = Some("Logo".to_string());
+            sheet.add_picture(picture);
+            sheet.add_comment(1, 1, "Ann", "Checked");
+            sheet.add_threaded_comment(2, 2, "Ann", "Why?");
+            sheet.reply_to_comment(2, 2, "Bob", "Rounding").unwrap();
+            assert!(sheet.reply_to_comment(9, 9, "Bob", "Nobody").is_err());
+            let mut link = Hyperlink::external("A5", "https://example.com/a?b=1&c=2");
+            link.tooltip = Some("Example".to_string());
+            sheet.add_hyperlink(link).unwrap();
+            sheet
+                .add_hyperlink(Hyperlink::internal("A6:B6", "Sheet1!A1"))
+                .unwrap();
+            sheet
+                .add_hyperlink(Hyperlink::external("A7", "mailto:x@example.com"))
+                .unwrap();
+            assert_eq!(
+                sheet.cell_value(5, 1),
+                Some(&CellValue::String("https://example.com/a?b=1&c=2".into()))
+            );
+            assert!(
+                sheet
+                    .add_hyperlink(Hyperlink::external("not a cell", "x"))
+                    .is_err()
+            );
+        }
+
+        // Reopen, add to the existing parts and drop one hyperlink
+        let mut workbook = reopen(&mut workbook);
+        {
+            let sheet = workbook.worksheet_mut(0).unwrap();
+            assert_eq!(sheet.hyperlinks().len(), 3);
+            assert_eq!(
+                sheet.hyperlinks()[0].address(),
+                "https://example.com/a?b=1&c=2"
+            );
+            sheet.remove_hyperlink(7, 1);
+            sheet.add_comment(1, 1, "Cy", "Replaced");
+            sheet.add_comment(4, 4, "Ann", "Second note");
+        }
+
+        let workbook = reopen(&mut workbook);
+        let package = workbook.package();
+        let sheet = package
+            .get_part(&PackURI::new("/xl/worksheets/sheet1.xml").unwrap())
+            .unwrap();
+        let sheet_xml = String::from_utf8(sheet.blob().to_vec()).unwrap();
+        let text = |reltype: &str| {
+            let rel = sheet
+                .rels()
+                .iter()
+                .find(|rel| rel.reltype() == reltype)
+                .unwrap();
+            let part = package.get_part(&rel.target_partname().unwrap()).unwrap();
+            String::from_utf8(part.blob().to_vec()).unwrap()
+        };
+
+        let links: Vec<_> = sheet
+            .rels()
+            .iter()
+            .filter(|rel| rel.reltype() == rt::HYPERLINK)
+            .collect();
+        assert_eq!(links.len(), 1);
+        assert!(links[0].is_external());
+        assert_eq!(links[0].target_ref(), "https://example.com/a?b=1&c=2");
+        assert!(sheet_xml.contains(&format!(
+            r#"<hyperlinks><hyperlink ref="A5" r:id="{}" tooltip="Example"/><hyperlink ref="A6:B6" location="Sheet1!A1"/></hyperlinks>"#,
+            links[0].r_id()
+        )));
+        assert_eq!(sheet_xml.matches("<legacyDrawing ").count(), 1);
+        assert!(sheet_xml.find("<hyperlinks>") < sheet_xml.find("<drawing "));
+        assert!(sheet_xml.find("<drawing ") < sheet_xml.find("<legacyDrawing "));
+
+        let comments = text(rt::COMMENTS);
+        assert_eq!(comments.matches("<comment ").count(), 3);
+        assert!(comments.contains("Replaced") && !comments.contains("Checked"));
+        assert!(comments.contains("Comment:\n    Why?\nReply:\n    Rounding"));
+        let vml = text(rt::VML_DRAWING);
+        assert_eq!(vml.matches("<v:shape ").count(), 3);
+        let threads = text(rt::THREADED_COMMENT);
+        assert_eq!(threads.matches("<threadedComment ").count(), 2);
+        assert!(threads.contains("parentId="));
+
+        let persons = package
+            .get_part(&PackURI::new("/xl/persons/person.xml").unwrap())
+            .unwrap();
+        let persons = String::from_utf8(persons.blob().to_vec()).unwrap();
+        assert!(
+            persons.contains(r#"displayName="Ann""#) && persons.contains(r#"displayName="Bob""#)
+        );
+
+        let images = workbook.images().unwrap();
+        assert_eq!(images.len(), 1);
+        assert_eq!(images[0].data, png);
+        assert_eq!(images[0].alt_text.as_deref(), Some("Logo"));
+        assert_eq!(
+            images[0].anchor,
+            ImageAnchor::Cell {
+                sheet: "Sheet1".to_string(),
+                row: 2,
+                column: 1
+            }
+        );
+    }
+
+    #[test]
+    fn test_save_conditional_formatting() {
+        use crate::ooxml::xlsx::{
+            CellFill, CellFillPatternType, CellFont, CellFormat, ConditionalFormatType,
+            ConditionalFormatValue, ConditionalFormatValueType, DataValidationOperator,
+        };
+
+        let highlight = CellFormat {
+            font: Some(CellFont {
+                bold: true,
+                color: Some("FF9C0006".to_string()),
+                ..Default::default()
+            }),
+            fill: Some(CellFill {
+                pattern_type: CellFillPatternType::Solid,
+                fg_color: Some("FFFFC7CE".to_string()),
+                bg_color: None,
+                gradient: None,
+            }),
+            ..Default::default()
+        };
+
+        let mut workbook = Workbook::create().unwrap();
+        {
+            let sheet = workbook.worksheet_mut(0).unwrap();
+            for row in 1..=5 {
+                sheet.set_cell_value(row, 1, row as f64);
+            }
+            sheet.add_conditional_format(
+                "A1:A5",
+                ConditionalFormatType::CellIs {
+                    operator: DataValidationOperator::GreaterThan,
+                    formulas: vec!["3".to_string()],
+                },
+                Some(highlight.clone()),
+            );
+            sheet.add_conditional_format(
+                "A1:A5",
+                ConditionalFormatType::DataBar {
+                    min: ConditionalFormatValue::new(ConditionalFormatValueType::Min, None),
+                    max: ConditionalFormatValue::new(ConditionalFormatValueType::Max, None),
+                    color: "FF638EC6".to_string(),
+                },
+                None,
+            );
+        }
+
+        // Rules survive editing, and new ones reuse the existing dxf
+        let mut workbook = reopen(&mut workbook);
+        {
+            let sheet = workbook.worksheet_mut(0).unwrap();
+            assert_eq!(sheet.conditional_formatting()[0].rules.len(), 2);
+            sheet.add_conditional_format(
+                "B1:B5",
+                ConditionalFormatType::ContainsText {
+                    text: "late".to_string(),
+                },
+                Some(highlight),
+            );
+        }
+
+        let workbook = reopen(&mut workbook);
+        let styles_xml = String::from_utf8(
+            workbook
+                .package()
+                .get_part(&PackURI::new("/xl/styles.xml").unwrap())
+                .unwrap()
+                .blob()
+                .to_vec(),
+        )
+        .unwrap();
+        assert!(styles_xml.contains(r#"<dxfs count="1">"#));
+
+        let formatting = Worksheet::new(&workbook, workbook.worksheets[0].clone())
+            .conditional_formatting()
+            .unwrap();
+        assert_eq!(formatting.len(), 2);
+        assert_eq!(formatting[0].range, "A1:A5");
+        let rule = &formatting[0].rules[0];
+        assert!(matches!(
+            &rule.rule_type,
+            ConditionalFormatType::CellIs { operator: DataValidationOperator::GreaterThan, formulas } if formulas == &["3"]
+        ));
+        let format = rule.format.as_ref().unwrap();
+        assert!(format.font.as_ref().unwrap().bold);
+        assert_eq!(
+            format.fill.as_ref().unwrap().fg_color.as_deref(),
+            Some("FFFFC7CE")
+        );
+        assert!(formatting[0].rules[1].format.is_none());
+        assert_eq!(formatting[1].rules[0].priority, 3);
+        assert!(matches!(
+            &formatting[1].rules[0].rule_type,
+            ConditionalFormatType::ContainsText { text } if text == "late"
+        ));
+    }
+
+    #[test]
+    fn test_save_cell_styles() {
+        use crate::ooxml::xlsx::{
+            CellAlignment, CellBorder, CellFill, CellFont, CellFormat, CellGradientFill,
+            CellProtection, HorizontalAlignment, NamedStyle, VerticalAlignment,
+        };
+
+        let mut workbook = Workbook::create().unwrap();
+        workbook
+            .add_named_style(NamedStyle::new(
+                "Total",
+                CellFormat {
+                    font: Some(CellFont {
+                        bold: true,
+                        ..Default::default()
+                    }),
+                    number_format: Some("#,##0.00".to_string()),
+                    ..Default::default()
+                },
+            ))
+            .unwrap();
+        {
+            let sheet = workbook.worksheet_mut(0).unwrap();
+            sheet.set_cell_value(1, 1, "Wrapped heading");
+            sheet.set_cell_format(
+                1,
+                1,
+                CellFormat {
+                    alignment: Some(CellAlignment {
+                        horizontal: Some(HorizontalAlignment::Center),
+                        vertical: Some(VerticalAlignment::Top),
+                        wrap_text: true,
+                        indent: Some(1),
+                        ..Default::default()
+                    }),
+                    protection: Some(CellProtection {
+                        locked: false,
+                        hidden: false,
+                    }),
+                    fill: Some(CellFill::gradient(CellGradientFill {
+                        path: true,
+                        stops: vec![(0.0, "FFFFFFFF".to_string()), (1.0, "theme:4".to_string())],
+                        ..Default::default()
+                    })),
+                    border: Some(CellBorder {
+                        diagonal_up: true,
+                        ..Default::default()
+                    }),
+                    ..Default::default()
+                },
+            );
+            sheet.set_cell_value(2, 1, 1234.5);
+            sheet.set_cell_style(2, 1, "Total");
+        }
+
+        let mut workbook = reopen(&mut workbook);
+        let named = workbook.styles().named_styles.clone();
+        assert!(named.iter().any(|style| style.name == "Total"));
+
+        let sheet = workbook.worksheet_mut(0).unwrap();
+        let heading = &sheet.cell_formats()[&(1, 1)];
+        let alignment = heading.alignment.as_ref().unwrap();
+        assert_eq!(alignment.horizontal, Some(HorizontalAlignment::Center));
+        assert_eq!(alignment.vertical, Some(VerticalAlignment::Top));
+        assert!(alignment.wrap_text);
+        assert_eq!(alignment.indent, Some(1));
+        assert!(!heading.protection.unwrap().locked);
+        let gradient = heading.fill.as_ref().unwrap().gradient.as_ref().unwrap();
+        assert!(gradient.path);
+        assert_eq!(gradient.stops[1], (1.0, "theme:4".to_string()));
+        assert!(heading.border.as_ref().unwrap().diagonal_up);
+
+        let total = &sheet.cell_formats()[&(2, 1)];
+        assert_eq!(total.style_name.as_deref(), Some("Total"));
+        assert!(total.font.as_ref().unwrap().bold);
+        assert_eq!(total.number_format.as_deref(), Some("#,##0.00"));
+
+        // Saving again keeps the named style
+        let workbook = reopen(&mut workbook);
+        assert!(
+            workbook
+                .styles()
+                .named_styles
+                .iter()
+                .any(|style| style.name == "Total")
+        );
+    }
+
+    #[test]
+    fn test_save_page_layout_and_protection() {
+        use crate::ooxml::xlsx::{
+            HeaderFooter, PageOrientation, PageSetup, PasswordHash, SheetProtection,
+            WorkbookProtection,
+        };
+
+        let mut workbook = Workbook::create().unwrap();
+        {
+            let sheet = workbook.worksheet_mut(0).unwrap();
+            sheet.set_name("Q1 Report".to_string());
+            sheet.set_cell_value(1, 1, "Account");
+            sheet.set_page_setup(PageSetup {
+                orientation: Some(PageOrientation::Landscape),
+                paper_size: Some(9),
+                ..PageSetup::fit_to(1, 0)
+            });
+            sheet.set_header_footer(HeaderFooter::new(Some("&CFinance"), Some("Page &P of &N")));
+            sheet.set_print_area(1, 1, 40, 6);
+            sheet.set_print_titles(Some((1, 2)), None);
+            sheet.protect(SheetProtection {
+                password: Some(PasswordHash::sha512("secret", &[7; 16], 1000)),
+                allow_format_columns: true,
+                ..Default::default()
+            });
+        }
+        workbook
+            .protect_workbook(WorkbookProtection {
+                password: Some(PasswordHash::legacy("secret")),
+                lock_structure: true,
+                lock_windows: false,
+            })
+            .unwrap();
+
+        let workbook = reopen(&mut workbook);
+        let sheet = workbook.get_worksheet(0).unwrap();
+        let layout = sheet.page_layout().unwrap();
+        let setup = layout.setup.unwrap();
+        assert_eq!(setup.orientation, Some(PageOrientation::Landscape));
+        assert!(setup.fit_to_page);
+        assert_eq!(
+            (setup.fit_to_width, setup.fit_to_height),
+            (Some(1), Some(0))
+        );
+        assert_eq!(
+            layout.header_footer.unwrap().odd_footer.as_deref(),
+            Some("Page &P of &N")
+        );
+        assert_eq!(sheet.print_area().unwrap().as_deref(), Some("$A$1:$F$40"));
+        assert_eq!(sheet.print_titles().unwrap().rows, Some((1, 2)));
+        let protection = sheet.protection().unwrap().unwrap();
+        assert!(protection.verify_password("secret"));
+        assert!(protection.allow_format_columns && !protection.allow_format_cells);
+        let book_protection = workbook.protection().unwrap().unwrap();
+        assert!(book_protection.lock_structure);
+        assert!(book_protection.verify_password("secret"));
+
+        // Editing the loaded workbook keeps the settings
+        let mut workbook = workbook;
+        workbook
+            .worksheet_mut(0)
+            .unwrap()
+            .set_cell_value(2, 1, "Cash");
+        let mut workbook = reopen(&mut workbook);
+        let sheet = workbook.worksheet_mut(0).unwrap();
+        assert_eq!(sheet.print_area(), Some("$A$1:$F$40"));
+        assert_eq!(sheet.print_titles().rows, Some((1, 2)));
+        assert!(sheet.page_layout().setup.as_ref().unwrap().fit_to_page);
+        assert!(sheet.protection().unwrap().verify_password("secret"));
+        sheet.unprotect();
+        workbook.unprotect_workbook().unwrap();
+
+        let workbook = reopen(&mut workbook);
+        assert!(workbook.protection().unwrap().is_none());
+        let sheet = workbook.get_worksheet(0).unwrap();
+        assert!(sheet.protection().unwrap().is_none());
+        assert_eq!(sheet.print_titles().unwrap().rows, Some((1, 2)));
+    }
+
+    #[test]
+    fn test_save_tables_and_auto_filter() {
+        use crate::ooxml::xlsx::writer::sheet::ChartType;
+        use crate::ooxml::xlsx::{
+            AutoFilter, ColumnFilter, CustomFilter, FilterOperator, TotalsFunction,
+        };
+
+        let mut workbook = Workbook::create().unwrap();
+        {
+            let sheet = workbook.worksheet_mut(0).unwrap();
+            sheet.set_name("Data".to_string());
+            for (row, (region, units)) in [("East", 10.0), ("West", 12.0), ("North", 7.0)]
+                .into_iter()
+                .enumerate()
+            {
+                sheet.set_cell_value(row as u32 + 2, 1, region);
+                sheet.set_cell_value(row as u32 + 2, 2, units);
+            }
+            let mut table = Table::new("Sales", "A1:B5", &["Region", "Units"]);
+            table.totals_row = true;
+            table.columns[0].totals_label = Some("Total".to_string());
+            table.columns[1].totals_function = Some(TotalsFunction::Sum);
+            table.auto_filter = Some(AutoFilter::default().filter_column(
+                0,
+                ColumnFilter::Values {
+                    values: vec!["East".to_string()],
+                    blank: false,
+                },
+            ));
+            sheet.add_table(table).unwrap();
+            assert!(
+                sheet
+                    .add_table(Table::new("Other", "A1:C3", &["A", "B"]))
+                    .is_err()
+            );
+            assert_eq!(
+                sheet.cell_value(1, 2),
+                Some(&CellValue::String("Units".to_string()))
+            );
+            sheet.add_chart(
+                ChartType::Column,
+                "Units",
+                "Sales[[#Headers],[#Data]]",
+                (1, 4, 15, 10),
+                false,
+            );
+        }
+        {
+            let log = workbook.add_worksheet("Log");
+            log.set_cell_value(1, 1, "Level");
+            log.set_auto_filter(AutoFilter::new("A1:C10").filter_column(
+                1,
+                ColumnFilter::Custom {
+                    and: true,
+                    conditions: vec![CustomFilter {
+                        operator: FilterOperator::GreaterThan,
+                        value: "5".to_string(),
+                    }],
+                },
+            ));
+        }
+
+        let workbook = reopen(&mut workbook);
+        let tables = workbook.tables().unwrap();
+        assert_eq!(tables.len(), 1);
+        let (sheet_name, table) = &tables[0];
+        assert_eq!(
+            (sheet_name.as_str(), table.name.as_str()),
+            ("Data", "Sales")
+        );
+        assert_eq!(table.range, "A1:B5");
+        assert!(table.header_row && table.totals_row);
+        assert_eq!(table.columns[1].totals_function, Some(TotalsFunction::Sum));
+        assert_eq!(table.columns[0].totals_label.as_deref(), Some("Total"));
+        assert_eq!(table.auto_filter.as_ref().unwrap().columns.len(), 1);
+        assert_eq!(
+            workbook
+                .resolve_structured_references("SUM(Sales[Units])")
+                .unwrap(),
+            "SUM(Data!$B$2:$B$4)"
+        );
+
+        let data = workbook.get_worksheet(0).unwrap();
+        assert_eq!(
+            *data.cell(5, 1).unwrap().value(),
+            CellValue::String("Total".to_string())
+        );
+        let charts = workbook.charts().unwrap();
+        let values = charts[0].series[0].values.as_ref().unwrap();
+        assert_eq!(values.reference.as_deref(), Some("Data!$B$2:$B$4"));
+
+        let log = workbook.get_worksheet(1).unwrap();
+        let filter = log.auto_filter().unwrap().unwrap();
+        assert_eq!(filter.range, "A1:C10");
+        assert_eq!(filter.columns[0].column, 1);
+
+        // Editing keeps the existing table, and new tables get their own ids
+        let mut workbook = workbook;
+        assert_eq!(
+            workbook.worksheet_mut(0).unwrap().cell_value(5, 2),
+            Some(&CellValue::Formula {
+                formula: "SUBTOTAL(109,Sales[Units])".to_string(),
+                cached_value: None,
+            })
+        );
+        {
+            let log = workbook.worksheet_mut(1).unwrap();
+            assert_eq!(log.auto_filter().unwrap().range, "A1:C10");
+            log.add_table(Table::new("sales", "E1:E3", &["Code"]))
+                .unwrap();
+        }
+        assert!(workbook.save_to_writer(Cursor::new(Vec::new())).is_err());
+        workbook.worksheet_mut(1).unwrap().tables.clear();
+        workbook
+            .worksheet_mut(1)
+            .unwrap()
+            .add_table(Table::new("Codes", "E1:E3", &["Code"]))
+            .unwrap();
+
+        let workbook = reopen(&mut workbook);
+        let names: Vec<_> = workbook
+            .tables()
+            .unwrap()
+            .into_iter()
+            .map(|(_, table)| table.name)
+            .collect();
+        assert_eq!(names, ["Sales", "Codes"]);
+        let ids: Vec<_> = workbook
+            .package
+            .iter_parts()
+            .filter(|part| {
+                part.content_type() == crate::ooxml::opc::constants::content_type::SML_TABLE
+            })
+            .filter_map(|part| table::table_id(&String::from_utf8_lossy(part.blob())).unwrap())
+            .collect();
+        assert_eq!(ids.len(), 2);
+        assert_ne!(ids[0], ids[1]);
+        assert!(
+            workbook
+                .get_worksheet(1)
+                .unwrap()
+                .auto_filter()
+                .unwrap()
+                .is_some()
+        );
+    }
+
+    #[test]
+    fn test_read_pivot_tables() {
+        use crate::ooxml::opc::constants::content_type as ct;
+        use crate::ooxml::opc::constants::relationship_type as rt;
+        use crate::ooxml::xlsx::PivotFunction;
+
+        let mut workbook = Workbook::create().unwrap();
+        workbook
+            .worksheet_mut(0)
+            .unwrap()
+            .set_name("Summary".to_string());
+
+        // Add a cache whose source sheet no longer exists, and a pivot table on it
+        let mut workbook = reopen(&mut workbook);
+        let workbook_uri = PackURI::new("/xl/workbook.xml").unwrap();
+        let definition_uri = PackURI::new("/xl/pivotCache/pivotCacheDefinition1.xml").unwrap();
+        let records_uri = PackURI::new("/xl/pivotCache/pivotCacheRecords1.xml").unwrap();
+        let table_uri = PackURI::new("/xl/pivotTables/pivotTable1.xml").unwrap();
+        workbook.write_part(
+            &definition_uri,
+            ct::SML_PIVOT_CACHE_DEFINITION,
+            br#"<pivotCacheDefinition xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main" xmlns:r="http://schemas.openxmlformats.org/officeDocument/2006/relationships" r:id="rId1" recordCount="3"><cacheSource type="worksheet"><worksheetSource ref="A1:B4" sheet="Deleted"/></cacheSource><cacheFields count="2"><cacheField name="Region" numFmtId="0"><sharedItems count="2"><s v="East"/><s v="West"/></sharedItems></cacheField><cacheField name="Units" numFmtId="0"><sharedItems containsNumber="1"/></cacheField></cacheFields></pivotCacheDefinition>"#.to_vec(),
+        );
+        workbook.write_part(
+            &records_uri,
+            ct::SML_PIVOT_CACHE_RECORDS,
+            br#"<pivotCacheRecords xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main" count="3"><r><x v="0"/><n v="10"/></r><r><x v="1"/><n v="4"/></r><r><x v="0"/><n v="6"/></r></pivotCacheRecords>"#.to_vec(),
+        );
+        workbook.write_part(
+            &table_uri,
+            ct::SML_PIVOT_TABLE,
+            br#"<pivotTableDefinition xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main" name="UnitsByRegion" cacheId="7"><location ref="A1:B4" firstHeaderRow="1" firstDataRow="1" firstDataCol="1"/><pivotFields count="2"><pivotField axis="axisRow"><items count="3"><item x="0"/><item x="1"/><item t="default"/></items></pivotField><pivotField dataField="1"/></pivotFields><rowFields count="1"><field x="0"/></rowFields><dataFields count="1"><dataField name="Max of Units" fld="1" subtotal="max"/></dataFields></pivotTableDefinition>"#.to_vec(),
+        );
+        let package = &mut workbook.package;
+        package
+            .get_part_mut(&definition_uri)
+            .unwrap()
+            .relate_to("pivotCacheRecords1.xml", rt::PIVOT_CACHE_RECORDS);
+        package.get_part_mut(&table_uri).unwrap().relate_to(
+            "../pivotCache/pivotCacheDefinition1.xml",
+            rt::PIVOT_CACHE_DEFINITION,
+        );
+        package
+            .get_part_mut(&PackURI::new("/xl/worksheets/sheet1.xml").unwrap())
+            .unwrap()
+            .relate_to("../pivotTables/pivotTable1.xml", rt::PIVOT_TABLE);
+        let workbook_part = package.get_part_mut(&workbook_uri).unwrap();
+        let r_id = workbook_part.relate_to(
+            "pivotCache/pivotCacheDefinition1.xml",
+            rt::PIVOT_CACHE_DEFINITION,
+        );
+        let xml = String::from_utf8_lossy(workbook_part.blob()).replace(
+            "</workbook>",
+            &format!(
+                r#"<pivotCaches><pivotCache cacheId="7" r:id="{}"/></pivotCaches></workbook>"#,
+                r_id
+            ),
+        );
+        workbook_part.set_blob(xml.into_bytes());
+        let workbook = reopen(&mut workbook);
+        let caches = workbook.pivot_caches().unwrap();
+        assert_eq!(caches.len(), 1);
+        let cache = &caches[0];
+        assert_eq!(cache.id, 7);
+        assert_eq!(cache.source.sheet.as_deref(), Some("Deleted"));
+        let rows = cache.to_rows();
+        assert_eq!(rows.len(), 4);
+        assert_eq!(
+            rows[0],
+            [
+                CellValue::String("Region".to_string()),
+                CellValue::String("Units".to_string())
+            ]
+        );
+        assert_eq!(
+            rows[2],
+            [CellValue::String("West".to_string()), CellValue::Float(4.0)]
+        );
+
+        let tables = workbook.pivot_tables().unwrap();
+        assert_eq!(tables.len(), 1);
+        let (sheet, table) = &tables[0];
+        assert_eq!(
+            (sheet.as_str(), table.name.as_str()),
+            ("Summary", "UnitsByRegion")
+        );
+        assert_eq!(table.cache_id, cache.id);
+        assert_eq!(table.row_fields, [0]);
+        assert_eq!(table.data_fields[0].function, PivotFunction::Max);
+        assert_eq!(
+            table.item_value(cache, 0, 1),
+            Some(&CellValue::String("West".to_string()))
+        );
+    }
+}
@@ -6,7 +6,21 @@
 use std::borrow::Cow;
 use std::collections::HashMap;
 
-use crate::ooxml::opc::PackURI;
+use crate::common::ImageAnchor;
+use crate::ooxml::common::{ChartData, collect_charts};
+use crate::ooxml::opc::constants::relationship_type;
+use crate::ooxml::xlsx::format::ConditionalFormatting;
+use crate::ooxml::xlsx::page_setup::{
+    PRINT_AREA, PRINT_TITLES, PageLayout, PrintTitles, formula_areas,
+};
+use crate::ooxml::xlsx::parsers::conditional_formatting_parser::parse_conditional_formatting;
+use crate::ooxml::xlsx::parsers::page_setup_parser::parse_page_layout;
+use crate::ooxml::xlsx::parsers::pivot_parser::parse_pivot_table;
+use crate::ooxml::xlsx::parsers::protection_parser::parse_sheet_protection;
+use crate::ooxml::xlsx::parsers::table_parser::{parse_auto_filter, parse_table};
+use crate::ooxml::xlsx::pivot::PivotTable;
+use crate::ooxml::xlsx::protection::SheetProtection;
+use crate::ooxml::xlsx::table::{AutoFilter, Table};
 use crate::sheet::{
     Cell as CellTrait, CellIterator, CellValue, Result as SheetResult, RowIterator,
     Worksheet as WorksheetTrait,
@@ -53,8 +67,7 @@ impl<'a> Worksheet<'a> {
     /// Load worksheet data from the XML.
     pub fn load_data(&mut self) -> SheetResult<()> {
         // Get the worksheet part using the relationship ID
-        let worksheet_uri =
-            PackURI::new(format!("/xl/worksheets/sheet{}.xml", self.info.sheet_id))?;
+        let worksheet_uri = self.workbook.worksheet_partname(&self.info)?;
 
         let worksheet_part = self.workbook.package().get_part(&worksheet_uri)?;
         let content = std::str::from_utf8(worksheet_part.blob())?;
@@ -444,6 +457,139 @@ impl<'a> Worksheet<'a> {
         &self.info
     }
 
+    /// Get the charts drawn on this worksheet.
+    ///
+    /// Each chart is anchored at the 0-based cell of its top-left corner and
+    /// carries the series references and cached values stored in its part.
+    ///
+    /// [`Workbook::charts`] collects the charts of every worksheet.
+    pub fn charts(&self) -> SheetResult<Vec<ChartData>> {
+        let package = self.workbook.package();
+        let worksheet_uri = self.workbook.worksheet_partname(&self.info)?;
+        let Ok(worksheet_part) = package.get_part(&worksheet_uri) else {
+            return Ok(Vec::new());
+        };
+
+        let mut charts = Vec::new();
+        for rel in worksheet_part.rels().iter() {
+            if rel.reltype() != relationship_type::DRAWING || rel.is_external() {
+                continue;
+            }
+            let Ok(drawing_part) = package.get_part(&rel.target_partname()?) else {
+                continue;
+            };
+            charts.extend(collect_charts(drawing_part, package, |frame| {
+                let (row, column) = frame.cell.unwrap_or_default();
+                ImageAnchor::Cell {
+                    sheet: self.info.name.clone(),
+                    row,
+                    column,
+                }
+            })?);
+        }
+
+        Ok(charts)
+    }
+
+    /// Get the conditional formatting rules of this worksheet.
+    ///
+    /// Rules that apply a differential format carry it as a [`CellFormat`]
+    /// resolved from the workbook styles. Rules stored only in the `x14`
+    /// extension list are not read.
+    ///
+    /// [`CellFormat`]: crate::ooxml::xlsx::CellFormat
+    pub fn conditional_formatting(&self) -> SheetResult<Vec<ConditionalFormatting>> {
+        let worksheet_uri = self.workbook.worksheet_partname(&self.info)?;
+        let worksheet_part = self.workbook.package().get_part(&worksheet_uri)?;
+        let content = std::str::from_utf8(worksheet_part.blob())?;
+        parse_conditional_formatting(content, &self.workbook.styles().dxfs)
+    }
+
+    /// Get the page setup, margins, print options and headers/footers.
+    pub fn page_layout(&self) -> SheetResult<PageLayout> {
+        parse_page_layout(&self.part_xml()?)
+    }
+
+    /// Get the worksheet protection, if the sheet is protected.
+    pub fn protection(&self) -> SheetResult<Option<SheetProtection>> {
+        parse_sheet_protection(&self.part_xml()?)
+    }
+
+    /// Get the print area as absolute references without the sheet name
+    /// (e.g. `$A$1:$D$20`), with multiple areas separated by commas.
+    pub fn print_area(&self) -> SheetResult<Option<String>> {
+        Ok(self
+            .workbook
+            .sheet_defined_name(&self.info, PRINT_AREA)?
+            .map(|formula| formula_areas(&formula).join(",")))
+    }
+
+    /// Get the rows and columns repeated on every printed page.
+    pub fn print_titles(&self) -> SheetResult<PrintTitles> {
+        Ok(self
+            .workbook
+            .sheet_defined_name(&self.info, PRINT_TITLES)?
+            .map(|formula| PrintTitles::from_areas(formula_areas(&formula)))
+            .unwrap_or_default())
+    }
+
+    /// Get the tables (ListObjects) on this worksheet.
+    pub fn tables(&self) -> SheetResult<Vec<Table>> {
+        let package = self.workbook.package();
+        let worksheet_uri = self.workbook.worksheet_partname(&self.info)?;
+        let Ok(worksheet_part) = package.get_part(&worksheet_uri) else {
+            return Ok(Vec::new());
+        };
+
+        let mut tables = Vec::new();
+        for rel in worksheet_part.rels().iter() {
+            if rel.reltype() != relationship_type::TABLE || rel.is_external() {
+                continue;
+            }
+            let Ok(table_part) = package.get_part(&rel.target_partname()?) else {
+                continue;
+            };
+            tables.push(parse_table(std::str::from_utf8(table_part.blob())?)?);
+        }
+        Ok(tables)
+    }
+
+    /// Get the pivot tables on this worksheet.
+    ///
+    /// Each table refers to its data by [`PivotTable::cache_id`]; see
+    /// [`Workbook::pivot_caches`].
+    pub fn pivot_tables(&self) -> SheetResult<Vec<PivotTable>> {
+        let package = self.workbook.package();
+        let worksheet_uri = self.workbook.worksheet_partname(&self.info)?;
+        let Ok(worksheet_part) = package.get_part(&worksheet_uri) else {
+            return Ok(Vec::new());
+        };
+
+        let mut tables = Vec::new();
+        for rel in worksheet_part.rels().iter() {
+            if rel.reltype() != relationship_type::PIVOT_TABLE || rel.is_external() {
+                continue;
+            }
+            let Ok(part) = package.get_part(&rel.target_partname()?) else {
+                continue;
+            };
+            tables.push(parse_pivot_table(std::str::from_utf8(part.blob())?)?);
+        }
+        Ok(tables)
+    }
+
+    /// Get the sheet-level autofilter; table filters belong to [`Table`]s.
+    pub fn auto_filter(&self) -> SheetResult<Option<AutoFilter>> {
+        parse_auto_filter(&self.part_xml()?)
+    }
+
+    /// Read the worksheet part.
+    fn part_xml(&self) -> SheetResult<String> {
+        let worksheet_uri = self.workbook.worksheet_partname(&self.info)?;
+        let worksheet_part = self.workbook.package().get_part(&worksheet_uri)?;
+        Ok(std::str::from_utf8(worksheet_part.blob())?.to_string())
+    }
+
     // TODO: Apache POI worksheet-level features not yet implemented:
     // - Cell formatting (reading): get_cell_style(), get_cell_format()
     // - Formula evaluation: evaluate_formula(), get_formula_evaluator()
@@ -456,13 +602,7 @@ impl<'a> Worksheet<'a> {
     // - Merged regions: get_merged_regions(), is_merged_cell(), get_merge_region()
     // - Column operations: auto_size_column(), set_column_hidden(), is_column_hidden()
     // - Row operations: set_row_hidden(), is_row_hidden(), get_row_height(), set_row_height()
-    // - Sheet protection: protect_sheet(), is_protected(), get_protection_info()
-    // - Auto-filter: set_auto_filter(), get_auto_filter_range()
     // - Data validation: add_validation_data(), get_data_validations()
-    // - Conditional formatting: get_sheet_conditional_formatting()
-    // - Page setup: get_print_setup(), set_fit_to_page()
-    // - Headers/Footers: get_header(), get_footer(), set_header(), set_footer()
-    // - Repeating rows/columns: set_repeating_rows(), set_repeating_columns()
 }
 
 impl<'a> WorksheetTrait for Worksheet<'a> {
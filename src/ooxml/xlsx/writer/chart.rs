@@ -0,0 +1,717 @@
+//! DrawingML chart and drawing parts for worksheet charts.
+//!
+//! Each chart added with [`MutableWorksheet::add_chart`] becomes a
+//! `xl/charts/chartN.xml` part, anchored to the sheet by a `twoCellAnchor`
+//! in the sheet's `xl/drawings/drawingN.xml` part.
+
+use std::fmt::Write as FmtWrite;
+
+use quick_xml::Reader;
+use quick_xml::events::Event;
+
+use crate::ooxml::xlsx::parsers::worksheet_parser::reference_to_coords;
+use crate::sheet::{CellValue, Result as SheetResult};
+
+use super::sheet::{Chart, ChartType, MutableWorksheet, escape_xml};
+
+const CHART_NS: &str = "http://schemas.openxmlformats.org/drawingml/2006/chart";
+const DRAWING_NS: &str = "http://schemas.openxmlformats.org/drawingml/2006/main";
+const SPREADSHEET_DRAWING_NS: &str =
+    "http://schemas.openxmlformats.org/drawingml/2006/spreadsheetDrawing";
+const RELATIONSHIPS_NS: &str =
+    "http://schemas.openxmlformats.org/officeDocument/2006/relationships";
+
+/// Axis IDs shared by the plot and its axes.
+const CATEGORY_AXIS_ID: u32 = 500_000_001;
+const VALUE_AXIS_ID: u32 = 500_000_002;
+
+/// A cell range referenced by a chart, 1-based and inclusive.
+#[derive(Debug, Clone, PartialEq)]
+struct SourceRange {
+    sheet: Option<String>,
+    start_row: u32,
+    start_col: u32,
+    end_row: u32,
+    end_col: u32,
+}
+
+impl SourceRange {
+    /// Parse `A1:C5`, `Sheet1!$A$1:$C$5` or `'My Sheet'!A1:C5`.
+    fn parse(range: &str) -> SheetResult<Self> {
+        let range = range.trim().trim_start_matches('=');
+        let (sheet, cells) = match range.rfind('!') {
+            Some(pos) => {
+                let name = &range[..pos];
+                let name = match name.strip_prefix('\'').and_then(|n| n.strip_suffix('\'')) {
+                    Some(quoted) => quoted.replace("''", "'"),
+                    None => name.to_string(),
+                };
+                (Some(name), &range[pos + 1..])
+            },
+            None => (None, range),
+        };
+
+        let cells = cells.replace('$', "");
+        let (start, end) = cells.split_once(':').unwrap_or((&cells, &cells));
+        let (start_col, start_row) = reference_to_coords(start)?;
+        let (end_col, end_row) = reference_to_coords(end)?;
+
+        Ok(Self {
+            sheet,
+            start_row: start_row.min(end_row),
+            start_col: start_col.min(end_col),
+            end_row: start_row.max(end_row),
+            end_col: start_col.max(end_col),
+        })
+    }
+
+    /// Absolute formula reference to a block of cells in this range's sheet.
+    fn formula(&self, sheet: &str, rows: (u32, u32), cols: (u32, u32)) -> String {
+        let start = format!(
+            "${}${}",
+            MutableWorksheet::column_to_letters(cols.0),
+            rows.0
+        );
+        let end = format!(
+            "${}${}",
+            MutableWorksheet::column_to_letters(cols.1),
+            rows.1
+        );
+        let sheet = quote_sheet_name(self.sheet.as_deref().unwrap_or(sheet));
+        if start == end {
+            format!("{}!{}", sheet, start)
+        } else {
+            format!("{}!{}:{}", sheet, start, end)
+        }
+    }
+}
+
+/// Quote a sheet name for use in a formula when needed.
+pub(crate) fn quote_sheet_name(name: &str) -> String {
+    let plain = !name.is_empty()
+        && !name.starts_with(|c: char| c.is_ascii_digit())
+        && name
+            .chars()
+            .all(|c| c.is_alphanumeric() || c == '_' || c == '.');
+    if plain {
+        name.to_string()
+    } else {
+        format!("'{}'", name.replace('\'', "''"))
+    }
+}
+
+/// The value of a cell as a chart cache entry.
+enum CachedValue {
+    Number(f64),
+    Text(String),
+}
+
+fn cached_value(value: &CellValue) -> Option<CachedValue> {
+    match value {
+        CellValue::Empty => None,
+        CellValue::Bool(b) => Some(CachedValue::Number(if *b { 1.0 } else { 0.0 })),
+        CellValue::Int(i) => Some(CachedValue::Number(*i as f64)),
+        CellValue::Float(f) | CellValue::DateTime(f) => Some(CachedValue::Number(*f)),
+        CellValue::String(s) | CellValue::Error(s) => Some(CachedValue::Text(s.clone())),
+        CellValue::Formula {
+            cached_value: cached,
+            ..
+        } => cached.as_deref().and_then(cached_value),
+    }
+}
+
+/// One series of a chart.
+struct Series {
+    /// Reference to the series name cell
+    name: Option<String>,
+    /// Reference to the category (or X) values
+    categories: Option<String>,
+    /// Reference to the values (or Y values)
+    values: String,
+    /// Cached category values, if any
+    category_cache: Vec<Option<CachedValue>>,
+    /// Cached series name
+    name_cache: Option<String>,
+    /// Cached values
+    value_cache: Vec<Option<CachedValue>>,
+}
+
+/// Split a data range into series.
+///
+/// The first row holds series names when it contains only text. With more
+/// than one column, the first column holds the categories (X values for
+/// scatter charts) and every other column is a series; a single column is
+/// one series without categories.
+fn build_series(
+    range: &SourceRange,
+    sheet_name: &str,
+    data: Option<&MutableWorksheet>,
+) -> Vec<Series> {
+    let value = |row: u32, col: u32| {
+        data.and_then(|sheet| sheet.cell_value(row, col))
+            .and_then(cached_value)
+    };
+
+    let has_header = range.end_row > range.start_row
+        && (range.start_col..=range.end_col).any(|col| value(range.start_row, col).is_some())
+        && (range.start_col..=range.end_col)
+            .all(|col| !matches!(value(range.start_row, col), Some(CachedValue::Number(_))));
+    let first_row = if has_header {
+        range.start_row + 1
+    } else {
+        range.start_row
+    };
+    let has_categories = range.end_col > range.start_col;
+    let first_col = if has_categories {
+        range.start_col + 1
+    } else {
+        range.start_col
+    };
+
+    let rows = (first_row, range.end_row);
+    let column = |col: u32| {
+        (first_row..=range.end_row)
+            .map(|row| value(row, col))
+            .collect()
+    };
+
+    (first_col..=range.end_col)
+        .map(|col| Series {
+            name: has_header
+                .then(|| range.formula(sheet_name, (range.start_row, range.start_row), (col, col))),
+            name_cache: has_header
+                .then(|| match value(range.start_row, col) {
+                    Some(CachedValue::Text(text)) => Some(text),
+                    _ => None,
+                })
+                .flatten(),
+            categories: has_categories
+                .then(|| range.formula(sheet_name, rows, (range.start_col, range.start_col))),
+            category_cache: if has_categories {
+                column(range.start_col)
+            } else {
+                Vec::new()
+            },
+            values: range.formula(sheet_name, rows, (col, col)),
+            value_cache: column(col),
+        })
+        .collect()
+}
+
+/// Generate the `c:chartSpace` part for a chart on `sheet`.
+///
+/// `worksheets` resolves data ranges that name another sheet; the values
+/// found there are written as the chart's caches.
+pub(crate) fn chart_xml(
+    chart: &Chart,
+    sheet: &MutableWorksheet,
+    worksheets: &[MutableWorksheet],
+) -> SheetResult<String> {
+    let range = SourceRange::parse(&chart.data_range)?;
+    let data = match &range.sheet {
+        Some(name) => worksheets.iter().find(|ws| ws.name() == name),
+        None => Some(sheet),
+    };
+    let series = build_series(&range, sheet.name(), data);
+
+    let mut xml = String::with_capacity(4096);
+    xml.push_str(r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>"#);
+    write!(
+        xml,
+        r#"<c:chartSpace xmlns:c="{}" xmlns:a="{}" xmlns:r="{}">"#,
+        CHART_NS, DRAWING_NS, RELATIONSHIPS_NS
+    )
+    .map_err(|e| format!("XML write error: {}", e))?;
+    xml.push_str(r#"<c:roundedCorners val="0"/><c:chart>"#);
+
+    match chart.title.as_deref().filter(|title| !title.is_empty()) {
+        Some(title) => write!(
+            xml,
+            r#"<c:title><c:tx><c:rich><a:bodyPr/><a:lstStyle/><a:p><a:r><a:t>{}</a:t></a:r></a:p></c:rich></c:tx><c:overlay val="0"/></c:title><c:autoTitleDeleted val="0"/>"#,
+            escape_xml(title)
+        )
+        .map_err(|e| format!("XML write error: {}", e))?,
+        None => xml.push_str(r#"<c:autoTitleDeleted val="1"/>"#),
+    }
+
+    xml.push_str("<c:plotArea><c:layout/>");
+    write_plot(&mut xml, chart.chart_type, &series)?;
+    xml.push_str("</c:plotArea>");
+
+    if chart.show_legend {
+        xml.push_str(r#"<c:legend><c:legendPos val="r"/><c:overlay val="0"/></c:legend>"#);
+    }
+    xml.push_str(r#"<c:plotVisOnly val="1"/><c:dispBlanksAs val="gap"/></c:chart></c:chartSpace>"#);
+
+    Ok(xml)
+}
+
+/// Write the chart-type element and its axes.
+fn write_plot(xml: &mut String, chart_type: ChartType, series: &[Series]) -> SheetResult<()> {
+    let axis_ids = format!(
+        r#"<c:axId val="{}"/><c:axId val="{}"/>"#,
+        CATEGORY_AXIS_ID, VALUE_AXIS_ID
+    );
+
+    match chart_type {
+        ChartType::Bar | ChartType::Column => {
+            let horizontal = matches!(chart_type, ChartType::Bar);
+            write!(
+                xml,
+                r#"<c:barChart><c:barDir val="{}"/><c:grouping val="clustered"/><c:varyColors val="0"/>"#,
+                if horizontal { "bar" } else { "col" }
+            )
+            .map_err(|e| format!("XML write error: {}", e))?;
+            write_series(xml, chart_type, series)?;
+            xml.push_str(r#"<c:gapWidth val="150"/>"#);
+            xml.push_str(&axis_ids);
+            xml.push_str("</c:barChart>");
+            let (category_pos, value_pos) = if horizontal { ("l", "b") } else { ("b", "l") };
+            write_category_axis(xml, category_pos)?;
+            write_value_axis(
+                xml,
+                VALUE_AXIS_ID,
+                CATEGORY_AXIS_ID,
+                value_pos,
+                "between",
+                true,
+            )?;
+        },
+        ChartType::Line => {
+            xml.push_str(r#"<c:lineChart><c:grouping val="standard"/><c:varyColors val="0"/>"#);
+            write_series(xml, chart_type, series)?;
+            xml.push_str(r#"<c:marker val="1"/>"#);
+            xml.push_str(&axis_ids);
+            xml.push_str("</c:lineChart>");
+            write_category_axis(xml, "b")?;
+            write_value_axis(xml, VALUE_AXIS_ID, CATEGORY_AXIS_ID, "l", "between", true)?;
+        },
+        ChartType::Area => {
+            xml.push_str(r#"<c:areaChart><c:grouping val="standard"/><c:varyColors val="0"/>"#);
+            write_series(xml, chart_type, series)?;
+            xml.push_str(&axis_ids);
+            xml.push_str("</c:areaChart>");
+            write_category_axis(xml, "b")?;
+            write_value_axis(xml, VALUE_AXIS_ID, CATEGORY_AXIS_ID, "l", "midCat", true)?;
+        },
+        ChartType::Pie => {
+            xml.push_str(r#"<c:pieChart><c:varyColors val="1"/>"#);
+            write_series(xml, chart_type, series)?;
+            xml.push_str(r#"<c:firstSliceAng val="0"/></c:pieChart>"#);
+        },
+        ChartType::Scatter => {
+            xml.push_str(
+                r#"<c:scatterChart><c:scatterStyle val="lineMarker"/><c:varyColors val="0"/>"#,
+            );
+            write_series(xml, chart_type, series)?;
+            xml.push_str(&axis_ids);
+            xml.push_str("</c:scatterChart>");
+            write_value_axis(xml, CATEGORY_AXIS_ID, VALUE_AXIS_ID, "b", "midCat", false)?;
+            write_value_axis(xml, VALUE_AXIS_ID, CATEGORY_AXIS_ID, "l", "midCat", true)?;
+        },
+    }
+
+    Ok(())
+}
+
+/// Write the `c:ser` elements in the order each chart type's schema expects.
+fn write_series(xml: &mut String, chart_type: ChartType, series: &[Series]) -> SheetResult<()> {
+    let scatter = matches!(chart_type, ChartType::Scatter);
+
+    for (index, ser) in series.iter().enumerate() {
+        write!(
+            xml,
+            r#"<c:ser><c:idx val="{0}"/><c:order val="{0}"/>"#,
+            index
+        )
+        .map_err(|e| format!("XML write error: {}", e))?;
+
+        if let Some(name) = &ser.name {
+            write!(xml, "<c:tx><c:strRef><c:f>{}</c:f>", escape_xml(name))
+                .map_err(|e| format!("XML write error: {}", e))?;
+            if let Some(cache) = &ser.name_cache {
+                write!(
+                    xml,
+                    r#"<c:strCache><c:ptCount val="1"/><c:pt idx="0"><c:v>{}</c:v></c:pt></c:strCache>"#,
+                    escape_xml(cache)
+                )
+                .map_err(|e| format!("XML write error: {}", e))?;
+            }
+            xml.push_str("</c:strRef></c:tx>");
+        }
+
+        match chart_type {
+            ChartType::Bar | ChartType::Column => xml.push_str(r#"<c:invertIfNegative val="0"/>"#),
+            ChartType::Line | ChartType::Scatter => {
+                xml.push_str(r#"<c:marker><c:symbol val="none"/></c:marker>"#)
+            },
+            ChartType::Area | ChartType::Pie => {},
+        }
+
+        let (category_tag, value_tag) = if scatter {
+            ("c:xVal", "c:yVal")
+        } else {
+            ("c:cat", "c:val")
+        };
+        if let Some(categories) = &ser.categories {
+            write!(xml, "<{}>", category_tag).map_err(|e| format!("XML write error: {}", e))?;
+            write_data_reference(xml, categories, &ser.category_cache)?;
+            write!(xml, "</{}>", category_tag).map_err(|e| format!("XML write error: {}", e))?;
+        }
+        write!(xml, "<{}>", value_tag).map_err(|e| format!("XML write error: {}", e))?;
+        write_number_reference(xml, &ser.values, &ser.value_cache)?;
+        write!(xml, "</{}>", value_tag).map_err(|e| format!("XML write error: {}", e))?;
+
+        if matches!(chart_type, ChartType::Line | ChartType::Scatter) {
+            xml.push_str(r#"<c:smooth val="0"/>"#);
+        }
+        xml.push_str("</c:ser>");
+    }
+
+    Ok(())
+}
+
+/// Write categories as a string reference unless every cached value is a number.
+fn write_data_reference(
+    xml: &mut String,
+    formula: &str,
+    cache: &[Option<CachedValue>],
+) -> SheetResult<()> {
+    if cache
+        .iter()
+        .any(|value| matches!(value, Some(CachedValue::Text(_))))
+    {
+        write!(xml, "<c:strRef><c:f>{}</c:f>", escape_xml(formula))
+            .map_err(|e| format!("XML write error: {}", e))?;
+        write!(xml, r#"<c:strCache><c:ptCount val="{}"/>"#, cache.len())
+            .map_err(|e| format!("XML write error: {}", e))?;
+        for (index, value) in cache.iter().enumerate() {
+            let text = match value {
+                Some(CachedValue::Text(text)) => text.clone(),
+                Some(CachedValue::Number(number)) => number.to_string(),
+                None => continue,
+            };
+            write!(
+                xml,
+                r#"<c:pt idx="{}"><c:v>{}</c:v></c:pt>"#,
+                index,
+                escape_xml(&text)
+            )
+            .map_err(|e| format!("XML write error: {}", e))?;
+        }
+        xml.push_str("</c:strCache></c:strRef>");
+        Ok(())
+    } else {
+        write_number_reference(xml, formula, cache)
+    }
+}
+
+/// Write a `c:numRef` with the numbers found in `cache`.
+fn write_number_reference(
+    xml: &mut String,
+    formula: &str,
+    cache: &[Option<CachedValue>],
+) -> SheetResult<()> {
+    write!(
+        xml,
+        r#"<c:numRef><c:f>{}</c:f><c:numCache><c:formatCode>General</c:formatCode><c:ptCount val="{}"/>"#,
+        escape_xml(formula),
+        cache.len()
+    )
+    .map_err(|e| format!("XML write error: {}", e))?;
+    for (index, value) in cache.iter().enumerate() {
+        if let Some(CachedValue::Number(number)) = value {
+            write!(xml, r#"<c:pt idx="{}"><c:v>{}</c:v></c:pt>"#, index, number)
+                .map_err(|e| format!("XML write error: {}", e))?;
+        }
+    }
+    xml.push_str("</c:numCache></c:numRef>");
+    Ok(())
+}
+
+fn write_category_axis(xml: &mut String, position: &str) -> SheetResult<()> {
+    write!(
+        xml,
+        r#"<c:catAx><c:axId val="{}"/><c:scaling><c:orientation val="minMax"/></c:scaling><c:delete val="0"/><c:axPos val="{}"/><c:numFmt formatCode="General" sourceLinked="1"/><c:majorTickMark val="out"/><c:minorTickMark val="none"/><c:tickLblPos val="nextTo"/><c:crossAx val="{}"/><c:crosses val="autoZero"/><c:auto val="1"/><c:lblAlgn val="ctr"/><c:lblOffset val="100"/><c:noMultiLvlLbl val="0"/></c:catAx>"#,
+        CATEGORY_AXIS_ID, position, VALUE_AXIS_ID
+    )
+    .map_err(|e| format!("XML write error: {}", e).into())
+}
+
+fn write_value_axis(
+    xml: &mut String,
+    axis_id: u32,
+    cross_axis_id: u32,
+    position: &str,
+    cross_between: &str,
+    gridlines: bool,
+) -> SheetResult<()> {
+    write!(
+        xml,
+        r#"<c:valAx><c:axId val="{}"/><c:scaling><c:orientation val="minMax"/></c:scaling><c:delete val="0"/><c:axPos val="{}"/>{}<c:numFmt formatCode="General" sourceLinked="1"/><c:majorTickMark val="out"/><c:minorTickMark val="none"/><c:tickLblPos val="nextTo"/><c:crossAx val="{}"/><c:crosses val="autoZero"/><c:crossBetween val="{}"/></c:valAx>"#,
+        axis_id,
+        position,
+        if gridlines { "<c:majorGridlines/>" } else { "" },
+        cross_axis_id,
+        cross_between
+    )
+    .map_err(|e| format!("XML write error: {}", e).into())
+}
+
+/// Generate the `xdr:twoCellAnchor` placing a chart on the sheet.
+///
+/// `position` is `(start_row, start_col, end_row, end_col)`, 1-based like
+/// cell coordinates; the chart covers both corner cells.
+pub(crate) fn anchor_xml(chart: &Chart, object_id: u32, r_id: &str) -> String {
+    let (start_row, start_col, end_row, end_col) = chart.position;
+    let marker = |tag: &str, row: u32, col: u32| {
+        format!(
+            "<xdr:{0}><xdr:col>{1}</xdr:col><xdr:colOff>0</xdr:colOff><xdr:row>{2}</xdr:row><xdr:rowOff>0</xdr:rowOff></xdr:{0}>",
+            tag, col, row
+        )
+    };
+    let from_row = start_row.saturating_sub(1);
+    let from_col = start_col.saturating_sub(1);
+
+    format!(
+        r#"<xdr:twoCellAnchor>{}{}<xdr:graphicFrame macro=""><xdr:nvGraphicFramePr><xdr:cNvPr id="{}" name="Chart {}"/><xdr:cNvGraphicFramePr/></xdr:nvGraphicFramePr><xdr:xfrm><a:off x="0" y="0"/><a:ext cx="0" cy="0"/></xdr:xfrm><a:graphic><a:graphicData uri="{}"><c:chart xmlns:c="{}" xmlns:r="{}" r:id="{}"/></a:graphicData></a:graphic></xdr:graphicFrame><xdr:clientData/></xdr:twoCellAnchor>"#,
+        marker("from", from_row, from_col),
+        marker("to", end_row.max(from_row + 1), end_col.max(from_col + 1)),
+        object_id,
+        object_id.saturating_sub(1),
+        CHART_NS,
+        CHART_NS,
+        RELATIONSHIPS_NS,
+        escape_xml(r_id)
+    )
+}
+
+/// Generate a drawing part holding `anchors`.
+pub(crate) fn drawing_xml(anchors: &str) -> String {
+    format!(
+        r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?><xdr:wsDr xmlns:xdr="{}" xmlns:a="{}">{}</xdr:wsDr>"#,
+        SPREADSHEET_DRAWING_NS, DRAWING_NS, anchors
+    )
+}
+
+/// Add `anchors` to the end of an existing drawing part.
+///
+/// The `xdr` and `a` prefixes are declared on the root if it lacks them.
+pub(crate) fn append_anchors(drawing: &str, anchors: &str) -> SheetResult<String> {
+    let mut reader = Reader::from_str(drawing);
+    loop {
+        match reader.read_event() {
+            Ok(Event::Start(e)) | Ok(Event::Empty(e)) => {
+                let empty = drawing[..reader.buffer_position() as usize].ends_with("/>");
+                let tag_end = reader.buffer_position() as usize - if empty { 2 } else { 1 };
+                let name = String::from_utf8_lossy(e.name().as_ref()).into_owned();
+
+                let mut declarations = String::new();
+                for (prefix, namespace) in [("xdr", SPREADSHEET_DRAWING_NS), ("a", DRAWING_NS)] {
+                    let declared = e
+                        .attributes()
+                        .flatten()
+                        .any(|attr| attr.key.as_ref() == format!("xmlns:{}", prefix).as_bytes());
+                    if !declared {
+                        write!(declarations, r#" xmlns:{}="{}""#, prefix, namespace)
+                            .map_err(|e| format!("XML write error: {}", e))?;
+                    }
+                }
+
+                let mut xml = String::with_capacity(drawing.len() + anchors.len() + 128);
+                xml.push_str(&drawing[..tag_end]);
+                xml.push_str(&declarations);
+                if empty {
+                    write!(xml, ">{}</{}>", anchors, name)
+                        .map_err(|e| format!("XML write error: {}", e))?;
+                    xml.push_str(&drawing[tag_end + 2..]);
+                } else {
+                    let close = drawing
+                        .rfind("</")
+                        .filter(|&pos| pos >= tag_end)
+                        .ok_or("Drawing part has no closing root tag")?;
+                    xml.push_str(&drawing[tag_end..close]);
+                    xml.push_str(anchors);
+                    xml.push_str(&drawing[close..]);
+                }
+                return Ok(xml);
+            },
+            Ok(Event::Eof) => return Ok(drawing_xml(anchors)),
+            Err(e) => return Err(format!("XML parse error: {}", e).into()),
+            _ => {},
+        }
+    }
+}
+
+/// The next free drawing object ID in a drawing part.
+pub(crate) fn next_object_id(drawing: &str) -> SheetResult<u32> {
+    let mut reader = Reader::from_str(drawing);
+    let mut max_id = 1;
+    loop {
+        match reader.read_event() {
+            Ok(Event::Start(e)) | Ok(Event::Empty(e)) if e.local_name().as_ref() == b"cNvPr" => {
+                if let Some(id) = e
+                    .attributes()
+                    .flatten()
+                    .find(|attr| attr.key.as_ref() == b"id")
+                    .and_then(|attr| String::from_utf8_lossy(&attr.value).parse::<u32>().ok())
+                {
+                    max_id = max_id.max(id);
+                }
+            },
+            Ok(Event::Eof) => break,
+            Err(e) => return Err(format!("XML parse error: {}", e).into()),
+            _ => {},
+        }
+    }
+    Ok(max_id + 1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sales_sheet() -> MutableWorksheet {
+        let mut sheet = MutableWorksheet::new("Sales Data".to_string(), 1);
+        sheet.set_cell_value(1, 1, "Month");
+        sheet.set_cell_value(1, 2, "North");
+        sheet.set_cell_value(1, 3, "South");
+        for (row, (month, north, south)) in
+            [("Jan", 10.0, 7.5), ("Feb", 12.0, 9.0), ("Mar", 15.0, 11.0)]
+                .into_iter()
+                .enumerate()
+        {
+            let row = row as u32 + 2;
+            sheet.set_cell_value(row, 1, month);
+            sheet.set_cell_value(row, 2, north);
+            sheet.set_cell_value(row, 3, south);
+        }
+        sheet
+    }
+
+    fn chart(chart_type: ChartType, data_range: &str) -> Chart {
+        Chart {
+            chart_type,
+            title: Some("Quarterly <Sales>".to_string()),
+            data_range: data_range.to_string(),
+            position: (6, 1, 20, 8),
+            show_legend: true,
+        }
+    }
+
+    #[test]
+    fn test_parse_source_range() {
+        let range = SourceRange::parse("'Bob''s Sheet'!$C$5:A1").unwrap();
+        assert_eq!(range.sheet.as_deref(), Some("Bob's Sheet"));
+        assert_eq!(
+            (
+                range.start_row,
+                range.start_col,
+                range.end_row,
+                range.end_col
+            ),
+            (1, 1, 5, 3)
+        );
+        assert_eq!(
+            range.formula("Other", (2, 5), (1, 1)),
+            "'Bob''s Sheet'!$A$2:$A$5"
+        );
+        assert_eq!(
+            SourceRange::parse("B2")
+                .unwrap()
+                .formula("Data", (2, 2), (2, 2)),
+            "Data!$B$2"
+        );
+    }
+
+    #[test]
+    fn test_column_chart_series() {
+        let sheet = sales_sheet();
+        let xml = chart_xml(&chart(ChartType::Column, "A1:C4"), &sheet, &[]).unwrap();
+
+        assert!(xml.contains(r#"<c:barDir val="col"/>"#));
+        assert!(xml.contains("<a:t>Quarterly &lt;Sales&gt;</a:t>"));
+        assert!(xml.contains("<c:f>&apos;Sales Data&apos;!$B$1</c:f>"));
+        assert!(xml.contains("<c:f>&apos;Sales Data&apos;!$A$2:$A$4</c:f>"));
+        assert!(xml.contains("<c:f>&apos;Sales Data&apos;!$C$2:$C$4</c:f>"));
+        assert!(xml.contains(r#"<c:pt idx="1"><c:v>Feb</c:v></c:pt>"#));
+        assert!(xml.contains(r#"<c:pt idx="0"><c:v>7.5</c:v></c:pt>"#));
+        assert_eq!(xml.matches("<c:ser>").count(), 2);
+        assert!(xml.contains("<c:catAx>") && xml.contains("<c:valAx>"));
+        assert!(xml.contains(r#"<c:legendPos val="r"/>"#));
+    }
+
+    #[test]
+    fn test_chart_types() {
+        let sheet = sales_sheet();
+        let cases = [
+            (ChartType::Bar, r#"<c:barDir val="bar"/>"#),
+            (ChartType::Line, "<c:lineChart>"),
+            (ChartType::Area, "<c:areaChart>"),
+            (ChartType::Pie, "<c:pieChart>"),
+            (ChartType::Scatter, "<c:scatterChart>"),
+        ];
+        for (chart_type, expected) in cases {
+            let xml = chart_xml(&chart(chart_type, "A1:B4"), &sheet, &[]).unwrap();
+            assert!(xml.contains(expected), "{:?}", chart_type);
+        }
+
+        let pie = chart_xml(&chart(ChartType::Pie, "A1:B4"), &sheet, &[]).unwrap();
+        assert!(!pie.contains("<c:catAx>") && !pie.contains("<c:valAx>"));
+
+        let scatter = chart_xml(&chart(ChartType::Scatter, "A1:B4"), &sheet, &[]).unwrap();
+        assert!(scatter.contains("<c:xVal>") && scatter.contains("<c:yVal>"));
+        assert_eq!(scatter.matches("<c:valAx>").count(), 2);
+    }
+
+    #[test]
+    fn test_chart_without_title_or_legend() {
+        let sheet = sales_sheet();
+        let mut untitled = chart(ChartType::Line, "B2:B4");
+        untitled.title = None;
+        untitled.show_legend = false;
+        let xml = chart_xml(&untitled, &sheet, &[]).unwrap();
+
+        assert!(xml.contains(r#"<c:autoTitleDeleted val="1"/>"#));
+        assert!(!xml.contains("<c:legend>"));
+        assert!(!xml.contains("<c:tx>") && !xml.contains("<c:cat>"));
+        assert!(xml.contains("<c:f>&apos;Sales Data&apos;!$B$2:$B$4</c:f>"));
+    }
+
+    #[test]
+    fn test_chart_data_from_other_sheet() {
+        let data = sales_sheet();
+        let summary = MutableWorksheet::new("Summary".to_string(), 2);
+        let xml = chart_xml(
+            &chart(ChartType::Column, "'Sales Data'!A1:B4"),
+            &summary,
+            std::slice::from_ref(&data),
+        )
+        .unwrap();
+        assert!(xml.contains(r#"<c:pt idx="2"><c:v>15</c:v></c:pt>"#));
+    }
+
+    #[test]
+    fn test_anchor_and_drawing() {
+        let anchor = anchor_xml(&chart(ChartType::Pie, "A1:B4"), 2, "rId1");
+        assert!(anchor.contains(
+            "<xdr:from><xdr:col>0</xdr:col><xdr:colOff>0</xdr:colOff><xdr:row>5</xdr:row>"
+        ));
+        assert!(anchor.contains(
+            "<xdr:to><xdr:col>8</xdr:col><xdr:colOff>0</xdr:colOff><xdr:row>20</xdr:row>"
+        ));
+        assert!(anchor.contains(r#"r:id="rId1""#));
+
+        let drawing = drawing_xml(&anchor);
+        assert_eq!(next_object_id(&drawing).unwrap(), 3);
+
+        let existing = r#"<?xml version="1.0"?><xdr:wsDr xmlns:xdr="http://schemas.openxmlformats.org/drawingml/2006/spreadsheetDrawing"><xdr:oneCellAnchor/></xdr:wsDr>"#;
+        let appended = append_anchors(existing, "<xdr:twoCellAnchor/>").unwrap();
+        assert!(
+            appended
+                .contains(r#"xmlns:a="http://schemas.openxmlformats.org/drawingml/2006/main">"#)
+        );
+        assert!(appended.ends_with("<xdr:oneCellAnchor/><xdr:twoCellAnchor/></xdr:wsDr>"));
+        assert_eq!(appended.matches("xmlns:xdr").count(), 1);
+    }
+}
@@ -0,0 +1,466 @@
+//! Comments, VML note shapes and threaded comment parts.
+//!
+//! Comments added with [`MutableWorksheet::add_comment`] are merged into the
+//! sheet's comments part and VML part, replacing any comment on the same
+//! cell. Threaded comments are also merged into the sheet's threaded
+//! comments part, with their authors added to the workbook's persons part.
+//!
+//! [`MutableWorksheet::add_comment`]: super::MutableWorksheet::add_comment
+
+use std::collections::HashMap;
+use std::fmt::Write as FmtWrite;
+
+use quick_xml::Reader;
+use quick_xml::escape::unescape;
+use quick_xml::events::Event;
+
+use crate::ooxml::common::guid;
+use crate::ooxml::common::xml::split_children;
+use crate::ooxml::xlsx::comment::Comment;
+use crate::sheet::Result as SheetResult;
+
+use super::sheet::{MutableWorksheet, escape_xml};
+
+const MAIN_NS: &str = "http://schemas.openxmlformats.org/spreadsheetml/2006/main";
+const THREADED_COMMENTS_NS: &str =
+    "http://schemas.microsoft.com/office/spreadsheetml/2018/threadedcomments";
+
+/// Text of the note written for a threaded comment, followed by the thread.
+const THREAD_NOTE: &str = "[Threaded comment]\n\nYour version of Excel allows you to read this threaded comment; however, any edits to it will get removed if the file is opened in a newer version of Excel. Learn more: https://go.microsoft.com/fwlink/?linkid=870924\n\nComment:\n    ";
+
+fn cell_ref(comment: &Comment) -> String {
+    format!(
+        "{}{}",
+        MutableWorksheet::column_to_letters(comment.col),
+        comment.row
+    )
+}
+
+/// Get an attribute of the first element of a fragment by local name.
+fn first_attribute(xml: &str, name: &[u8]) -> SheetResult<Option<String>> {
+    let mut reader = Reader::from_str(xml);
+    loop {
+        match reader
+            .read_event()
+            .map_err(|e| format!("XML parse error: {}", e))?
+        {
+            Event::Start(e) | Event::Empty(e) => {
+                return Ok(e
+                    .attributes()
+                    .flatten()
+                    .find(|attr| attr.key.local_name().as_ref() == name)
+                    .and_then(|attr| attr.unescape_value().ok().map(|v| v.into_owned())));
+            },
+            Event::Eof => return Ok(None),
+            _ => {},
+        }
+    }
+}
+
+/// Merge persons into a persons part, returning it with the ID of every
+/// person by display name.
+pub(crate) fn persons_xml(
+    existing: &str,
+    authors: &[&str],
+) -> SheetResult<(String, HashMap<String, String>)> {
+    let (root_attributes, children) = split_children(existing)?;
+    let mut ids = HashMap::new();
+    let mut xml = String::with_capacity(existing.len() + 256);
+    xml.push_str(r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>"#);
+    if root_attributes.trim().is_empty() {
+        write!(
+            xml,
+            r#"<personList xmlns="{}" xmlns:x="{}">"#,
+            THREADED_COMMENTS_NS, MAIN_NS
+        )
+        .map_err(|e| format!("XML write error: {}", e))?;
+    } else {
+        write!(xml, "<personList{}>", root_attributes)
+            .map_err(|e| format!("XML write error: {}", e))?;
+    }
+
+    for child in &children {
+        let fragment = &existing[child.range.clone()];
+        if child.name == "person"
+            && let (Some(name), Some(id)) = (
+                first_attribute(fragment, b"displayName")?,
+                first_attribute(fragment, b"id")?,
+            )
+        {
+            ids.entry(name).or_insert(id);
+        }
+        xml.push_str(fragment);
+    }
+    for author in authors {
+        if ids.contains_key(*author) {
+            continue;
+        }
+        let id = guid(&format!("person|{}", author));
+        write!(
+            xml,
+            r#"<person displayName="{0}" id="{1}" userId="{0}" providerId="None"/>"#,
+            escape_xml(author),
+            id
+        )
+        .map_err(|e| format!("XML write error: {}", e))?;
+        ids.insert(author.to_string(), id);
+    }
+
+    xml.push_str("</personList>");
+    Ok((xml, ids))
+}
+
+/// Merge comments into a threaded comments part.
+///
+/// Threads on cells that get a new comment are removed. Returns the part
+/// with the ID of each comment's thread (`None` for notes). `seed` makes
+/// the IDs unique to the part and `timestamp` dates the new comments.
+pub(crate) fn threaded_comments_xml(
+    existing: &str,
+    comments: &[Comment],
+    persons: &HashMap<String, String>,
+    seed: &str,
+    timestamp: &str,
+) -> SheetResult<(String, Vec<Option<String>>)> {
+    let refs: Vec<String> = comments.iter().map(cell_ref).collect();
+    let (root_attributes, children) = split_children(existing)?;
+    let mut xml = String::with_capacity(existing.len() + 512);
+    xml.push_str(r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>"#);
+    if root_attributes.trim().is_empty() {
+        write!(
+            xml,
+            r#"<ThreadedComments xmlns="{}" xmlns:x="{}">"#,
+            THREADED_COMMENTS_NS, MAIN_NS
+        )
+        .map_err(|e| format!("XML write error: {}", e))?;
+    } else {
+        write!(xml, "<ThreadedComments{}>", root_attributes)
+            .map_err(|e| format!("XML write error: {}", e))?;
+    }
+
+    for child in &children {
+        let fragment = &existing[child.range.clone()];
+        if child.name == "threadedComment"
+            && first_attribute(fragment, b"ref")?.is_some_and(|r| refs.contains(&r))
+        {
+            continue;
+        }
+        xml.push_str(fragment);
+    }
+
+    let mut ids = Vec::with_capacity(comments.len());
+    for (index, (comment, cell)) in comments.iter().zip(&refs).enumerate() {
+        if !comment.threaded {
+            ids.push(None);
+            continue;
+        }
+        let thread_id = guid(&format!("{}|{}|{}|{}", seed, cell, index, comment.text));
+        let entries = std::iter::once((&comment.author, &comment.text)).chain(
+            comment
+                .replies
+                .iter()
+                .map(|reply| (&reply.author, &reply.text)),
+        );
+        for (position, (author, text)) in entries.enumerate() {
+            let person_id = persons
+                .get(author)
+                .ok_or_else(|| format!("No person for comment author {}", author))?;
+            let (id, parent) = if position == 0 {
+                (thread_id.clone(), String::new())
+            } else {
+                (
+                    guid(&format!("{}|{}|{}", thread_id, position, text)),
+                    format!(r#" parentId="{}""#, thread_id),
+                )
+            };
+            write!(
+                xml,
+                r#"<threadedComment ref="{}" dT="{}" personId="{}" id="{}"{}><text>{}</text></threadedComment>"#,
+                cell,
+                timestamp,
+                person_id,
+                id,
+                parent,
+                escape_xml(text)
+            )
+            .map_err(|e| format!("XML write error: {}", e))?;
+        }
+        ids.push(Some(thread_id));
+    }
+
+    xml.push_str("</ThreadedComments>");
+    Ok((xml, ids))
+}
+
+/// Merge comments into a comments part, replacing comments on the same cells.
+///
+/// `thread_ids` holds the thread ID of each threaded comment, which is
+/// written as a note by the author `tc={id}` as Excel does.
+pub(crate) fn comments_xml(
+    existing: &str,
+    comments: &[Comment],
+    thread_ids: &[Option<String>],
+) -> SheetResult<String> {
+    let refs: Vec<String> = comments.iter().map(cell_ref).collect();
+    let (root_attributes, children) = split_children(existing)?;
+
+    let mut authors = Vec::new();
+    let mut kept = String::new();
+    let mut rest = String::new();
+    for child in &children {
+        let fragment = &existing[child.range.clone()];
+        match child.name.as_str() {
+            "authors" => authors = author_names(fragment)?,
+            "commentList" => {
+                let (_, list) = split_children(fragment)?;
+                for comment in &list {
+                    let markup = &fragment[comment.range.clone()];
+                    if !first_attribute(markup, b"ref")?.is_some_and(|r| refs.contains(&r)) {
+                        kept.push_str(markup);
+                    }
+                }
+            },
+            _ => rest.push_str(fragment),
+        }
+    }
+
+    let mut list = kept;
+    for ((comment, cell), thread_id) in comments.iter().zip(&refs).zip(thread_ids) {
+        let (author, text) = match thread_id {
+            Some(id) => {
+                let mut text = format!("{}{}", THREAD_NOTE, comment.text);
+                for reply in &comment.replies {
+                    write!(text, "\nReply:\n    {}", reply.text)
+                        .map_err(|e| format!("XML write error: {}", e))?;
+                }
+                (format!("tc={}", id), text)
+            },
+            None => (comment.author.clone(), comment.text.clone()),
+        };
+        let author_id = match authors.iter().position(|name| *name == author) {
+            Some(index) => index,
+            None => {
+                authors.push(author);
+                authors.len() - 1
+            },
+        };
+        write!(
+            list,
+            r#"<comment ref="{}" authorId="{}" shapeId="0"><text><t xml:space="preserve">{}</t></text></comment>"#,
+            cell,
+            author_id,
+            escape_xml(&text)
+        )
+        .map_err(|e| format!("XML write error: {}", e))?;
+    }
+
+    let mut xml = String::with_capacity(existing.len() + list.len() + 256);
+    xml.push_str(r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>"#);
+    if root_attributes.trim().is_empty() {
+        write!(xml, r#"<comments xmlns="{}">"#, MAIN_NS)
+            .map_err(|e| format!("XML write error: {}", e))?;
+    } else {
+        write!(xml, "<comments{}>", root_attributes)
+            .map_err(|e| format!("XML write error: {}", e))?;
+    }
+    xml.push_str("<authors>");
+    for author in &authors {
+        write!(xml, "<author>{}</author>", escape_xml(author))
+            .map_err(|e| format!("XML write error: {}", e))?;
+    }
+    write!(xml, "</authors><commentList>{}</commentList>", list)
+        .map_err(|e| format!("XML write error: {}", e))?;
+    xml.push_str(&rest);
+    xml.push_str("</comments>");
+    Ok(xml)
+}
+
+/// Read the names of an `authors` element, in `authorId` order.
+fn author_names(xml: &str) -> SheetResult<Vec<String>> {
+    let mut reader = Reader::from_str(xml);
+    let mut names = Vec::new();
+    loop {
+        match reader
+            .read_event()
+            .map_err(|e| format!("XML parse error: {}", e))?
+        {
+            Event::Start(e) if e.local_name().as_ref() == b"author" => {
+                let text = reader
+                    .read_text(e.name())
+                    .map_err(|e| format!("XML parse error: {}", e))?;
+                names.push(
+                    unescape(&text)
+                        .map_err(|e| format!("XML parse error: {}", e))?
+                        .into_owned(),
+                );
+            },
+            Event::Empty(e) if e.local_name().as_ref() == b"author" => names.push(String::new()),
+            Event::Eof => break,
+            _ => {},
+        }
+    }
+    Ok(names)
+}
+
+/// Add note shapes for comments to a VML part.
+///
+/// Cells that already have a note shape keep it. New parts take shape IDs
+/// from `block` (1024 per block), which must differ between the sheets.
+pub(crate) fn vml_xml(existing: &str, comments: &[Comment], block: u32) -> SheetResult<String> {
+    let close = existing.rfind("</xml>");
+    let noted = noted_cells(existing);
+    let mut next_id = shape_ids(existing)
+        .max()
+        .map_or(block * 1024 + 1, |id| id + 1);
+
+    let mut z_index = existing.matches("<v:shape ").count() + 1;
+    let mut shapes = String::new();
+    if close.is_none() || !existing.contains(r##"id="_x0000_t202""##) {
+        shapes.push_str(r#"<v:shapetype id="_x0000_t202" coordsize="21600,21600" o:spt="202" path="m,l,21600r21600,l21600,xe"><v:stroke joinstyle="miter"/><v:path gradientshapeok="t" o:connecttype="rect"/></v:shapetype>"#);
+    }
+    for comment in comments {
+        let (row, col) = (comment.row.saturating_sub(1), comment.col.saturating_sub(1));
+        if noted.contains(&(row, col)) {
+            continue;
+        }
+        // Columns and rows with offsets: left, top, right, bottom
+        write!(
+            shapes,
+            r##"<v:shape id="_x0000_s{}" type="#_x0000_t202" style="position:absolute;margin-left:59.25pt;margin-top:1.5pt;width:108pt;height:59.25pt;z-index:{};visibility:hidden" fillcolor="#ffffe1" o:insetmode="auto"><v:fill color2="#ffffe1"/><v:shadow on="t" color="black" obscured="t"/><v:path o:connecttype="none"/><v:textbox style="mso-direction-alt:auto"><div style="text-align:left"></div></v:textbox><x:ClientData ObjectType="Note"><x:MoveWithCells/><x:SizeWithCells/><x:Anchor>{}, 15, {}, 10, {}, 15, {}, 4</x:Anchor><x:AutoFill>False</x:AutoFill><x:Row>{}</x:Row><x:Column>{}</x:Column></x:ClientData></v:shape>"##,
+            next_id,
+            z_index,
+            col + 1,
+            row.saturating_sub(1),
+            col + 3,
+            row + 3,
+            row,
+            col
+        )
+        .map_err(|e| format!("XML write error: {}", e))?;
+        next_id += 1;
+        z_index += 1;
+    }
+
+    Ok(match close {
+        Some(close) => format!("{}{}{}", &existing[..close], shapes, &existing[close..]),
+        None => format!(
+            r#"<xml xmlns:v="urn:schemas-microsoft-com:vml" xmlns:o="urn:schemas-microsoft-com:office:office" xmlns:x="urn:schemas-microsoft-com:office:excel"><o:shapelayout v:ext="edit"><o:idmap v:ext="edit" data="{}"/></o:shapelayout>{}</xml>"#,
+            block, shapes
+        ),
+    })
+}
+
+/// Numbers of the `_x0000_sN` shape IDs in a VML part.
+fn shape_ids(vml: &str) -> impl Iterator<Item = u32> + '_ {
+    vml.split("_x0000_s").skip(1).filter_map(|rest| {
+        let end = rest
+            .find(|c: char| !c.is_ascii_digit())
+            .unwrap_or(rest.len());
+        rest[..end].parse().ok()
+    })
+}
+
+/// The 0-based (row, column) of each note shape in a VML part.
+///
+/// VML parts are not always well-formed XML, so they are scanned as text.
+fn noted_cells(vml: &str) -> Vec<(u32, u32)> {
+    let value = |data: &str, tag: &str| -> Option<u32> {
+        let start = data.find(&format!("<x:{}>", tag))? + tag.len() + 4;
+        let end = data[start..].find('<')? + start;
+        data[start..end].trim().parse().ok()
+    };
+    vml.split("<x:ClientData")
+        .skip(1)
+        .filter(|data| data.trim_start().starts_with(r#"ObjectType="Note""#))
+        .filter_map(|data| {
+            let data = &data[..data.find("</x:ClientData>").unwrap_or(data.len())];
+            Some((value(data, "Row")?, value(data, "Column")?))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ooxml::xlsx::comment::CommentReply;
+
+    fn comment(row: u32, col: u32, text: &str, threaded: bool) -> Comment {
+        Comment {
+            row,
+            col,
+            author: "Ann".to_string(),
+            text: text.to_string(),
+            threaded,
+            replies: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_merge_comments() {
+        let existing = r#"<?xml version="1.0"?><comments xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main"><authors><author>Bob</author><author>Ann</author></authors><commentList><comment ref="A1" authorId="0"><text><t>Old</t></text></comment><comment ref="C3" authorId="0"><text><t>Kept</t></text></comment></commentList></comments>"#;
+        let comments = [comment(1, 1, "New & improved", false)];
+        let xml = comments_xml(existing, &comments, &[None]).unwrap();
+        assert!(xml.contains("<authors><author>Bob</author><author>Ann</author></authors>"));
+        assert!(!xml.contains("Old"));
+        assert!(xml.contains(r#"<comment ref="C3" authorId="0"><text><t>Kept</t></text></comment><comment ref="A1" authorId="1" shapeId="0"><text><t xml:space="preserve">New &amp; improved</t></text></comment>"#));
+
+        let xml = comments_xml("", &comments, &[Some("{ID}".to_string())]).unwrap();
+        assert!(xml.contains("<authors><author>tc={ID}</author></authors>"));
+        assert!(xml.contains("Comment:\n    New &amp; improved"));
+    }
+
+    #[test]
+    fn test_vml_shapes() {
+        let comments = [comment(2, 3, "Note", false)];
+        let vml = vml_xml("", &comments, 1).unwrap();
+        assert!(vml.contains(r#"<o:idmap v:ext="edit" data="1"/>"#));
+        assert!(vml.contains(r#"<v:shape id="_x0000_s1025""#));
+        assert!(vml.contains("<x:Row>1</x:Row><x:Column>2</x:Column>"));
+        assert_eq!(noted_cells(&vml), vec![(1, 2)]);
+
+        // Existing shapes are kept and new IDs follow them
+        let comments = [comment(2, 3, "Again", false), comment(5, 1, "Other", false)];
+        let merged = vml_xml(&vml, &comments, 4).unwrap();
+        assert_eq!(merged.matches("<v:shapetype").count(), 1);
+        assert_eq!(merged.matches("<v:shape ").count(), 2);
+        assert!(merged.contains(r#"<v:shape id="_x0000_s1026""#));
+        assert_eq!(noted_cells(&merged), vec![(1, 2), (4, 0)]);
+    }
+
+    #[test]
+    fn test_threaded_comments_and_persons() {
+        let mut thread = comment(1, 2, "Check this", true);
+        thread.replies.push(CommentReply {
+            author: "Bob".to_string(),
+            text: "Done".to_string(),
+        });
+        let comments = [thread, comment(4, 4, "Plain note", false)];
+
+        let existing = r#"<personList xmlns="http://schemas.microsoft.com/office/spreadsheetml/2018/threadedcomments"><person displayName="Ann" id="{ANN}" userId="ann" providerId="AD"/></personList>"#;
+        let (persons, ids) = persons_xml(existing, &["Ann", "Bob"]).unwrap();
+        assert_eq!(ids["Ann"], "{ANN}");
+        assert_eq!(persons.matches("<person ").count(), 2);
+        assert!(persons.contains(&format!(r#"<person displayName="Bob" id="{}""#, ids["Bob"])));
+
+        let existing = r#"<ThreadedComments xmlns="http://schemas.microsoft.com/office/spreadsheetml/2018/threadedcomments"><threadedComment ref="B1" personId="{ANN}" id="{OLD}"><text>Old</text></threadedComment><threadedComment ref="Z9" personId="{ANN}" id="{KEEP}"><text>Keep</text></threadedComment></ThreadedComments>"#;
+        let (xml, thread_ids) = threaded_comments_xml(
+            existing,
+            &comments,
+            &ids,
+            "sheet1",
+            "2024-01-02T03:04:05.000",
+        )
+        .unwrap();
+        assert!(!xml.contains("{OLD}"));
+        assert!(xml.contains("{KEEP}"));
+        let id = thread_ids[0].clone().unwrap();
+        assert_eq!(thread_ids[1], None);
+        assert!(xml.contains(&format!(
+            r#"<threadedComment ref="B1" dT="2024-01-02T03:04:05.000" personId="{{ANN}}" id="{}"><text>Check this</text>"#,
+            id
+        )));
+        assert!(xml.contains(&format!(r#"parentId="{}"><text>Done</text>"#, id)));
+        assert_eq!(id.len(), 38);
+        assert_eq!(&id[15..16], "4");
+    }
+}
@@ -0,0 +1,1123 @@
+//! Loading existing workbook parts into the mutable writer model.
+//!
+//! Opening a workbook for editing mirrors `workbook.xml` into
+//! [`MutableWorkbookData`] and loads each [`MutableWorksheet`] from its
+//! worksheet part on first use. Markup the writer model does not cover
+//! (sheet properties and views, hyperlinks, drawings, extension lists, ...)
+//! is kept verbatim and written back in
+//! schema order, so the worksheet's relationships stay valid.
+
+use std::collections::HashMap;
+
+use quick_xml::Reader;
+use quick_xml::events::{BytesStart, Event};
+
+use crate::ooxml::common::xml::{attribute, push_text, raw_attributes, split_children};
+use crate::ooxml::xlsx::SharedStrings;
+use crate::ooxml::xlsx::format::{
+    CellAlignment, CellBorder, CellFill, CellFont, CellFormat, CellProtection, NamedStyle,
+};
+use crate::ooxml::xlsx::page_setup::{PRINT_AREA, PRINT_TITLES, PrintTitles, formula_areas};
+use crate::ooxml::xlsx::parsers::conditional_formatting_parser::parse_conditional_formatting;
+use crate::ooxml::xlsx::parsers::page_setup_parser::{PAGE_SETUP_ATTRIBUTES, parse_page_layout};
+use crate::ooxml::xlsx::parsers::protection_parser::{
+    parse_sheet_protection, parse_workbook_protection,
+};
+use crate::ooxml::xlsx::parsers::table_parser::parse_auto_filter;
+use crate::ooxml::xlsx::parsers::workbook_parser::parse_defined_names;
+use crate::ooxml::xlsx::parsers::worksheet_parser::reference_to_coords;
+use crate::ooxml::xlsx::styles::{CellStyle, Styles, builtin_format_code};
+use crate::sheet::{CellValue, Result as SheetResult};
+
+use super::sheet::{FreezePanes, Hyperlink, HyperlinkTarget, MutableWorksheet};
+use super::workbook::MutableWorkbookData;
+
+/// Worksheet children that follow `mergeCells` in `CT_Worksheet`.
+const AFTER_MERGE_CELLS: &[&str] = &[
+    "phoneticPr",
+    "conditionalFormatting",
+    "dataValidations",
+    "hyperlinks",
+    "printOptions",
+    "pageMargins",
+    "pageSetup",
+    "headerFooter",
+    "rowBreaks",
+    "colBreaks",
+    "customProperties",
+    "cellWatches",
+    "ignoredErrors",
+    "smartTags",
+    "drawing",
+    "legacyDrawing",
+    "legacyDrawingHF",
+    "drawingHF",
+    "picture",
+    "oleObjects",
+    "controls",
+    "webPublishItems",
+    "tableParts",
+    "extLst",
+];
+
+/// `row` attributes the writer regenerates.
+const ROW_ATTRIBUTES: &[&str] = &[
+    "r",
+    "spans",
+    "ht",
+    "customHeight",
+    "hidden",
+    "s",
+    "customFormat",
+];
+
+/// `col` attributes the writer regenerates.
+const COL_ATTRIBUTES: &[&str] = &["min", "max", "width", "customWidth", "hidden", "style"];
+
+/// Stylesheet children that follow `dxfs` and are kept verbatim.
+const STYLES_TAIL: &[&str] = &["tableStyles", "colors", "extLst"];
+
+/// Where a worksheet came from and the markup kept from it.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct SheetSource {
+    /// Partname of the worksheet part (e.g. `/xl/worksheets/sheet1.xml`)
+    pub(crate) partname: String,
+    /// Relationship ID from the workbook part
+    pub(crate) relationship_id: String,
+    /// Sheet visibility (`hidden` or `veryHidden`)
+    pub(crate) state: Option<String>,
+    /// Whether the worksheet part has been read into the sheet
+    pub(crate) loaded: bool,
+    /// Attributes of the root `worksheet` element, namespace declarations included
+    pub(crate) root_attributes: String,
+    /// Raw `sheetPr` element
+    pub(crate) sheet_pr: Option<String>,
+    /// Raw `sheetViews` element, dropped when freeze panes change
+    pub(crate) sheet_views: Option<String>,
+    /// Raw `sheetFormatPr` element
+    pub(crate) sheet_format_pr: Option<String>,
+    /// Raw elements between `sheetData` and `mergeCells`
+    pub(crate) before_merges: String,
+    /// Raw elements after `mergeCells`
+    pub(crate) after_merges: String,
+    /// Raw `conditionalFormatting` elements with rules the writer does not model
+    pub(crate) conditional_formatting: String,
+    /// Raw `pageSetup` attributes the writer does not model (printer
+    /// settings relationship, print quality, ...)
+    pub(crate) page_setup_attributes: String,
+    /// Relationship IDs of the sheet's table parts
+    pub(crate) table_parts: Vec<String>,
+    /// Relationship ID of the VML part drawing the sheet's comments
+    pub(crate) legacy_drawing: Option<String>,
+}
+
+/// Parts of an existing styles.xml kept when styles are rebuilt.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct StylesSource {
+    /// Attributes of the root `styleSheet` element
+    pub(crate) root_attributes: String,
+    /// The workbook's default font (font 0)
+    pub(crate) default_font: Option<CellFont>,
+    /// Raw `dxf` elements with the formats read from them, in `dxfId` order
+    pub(crate) dxfs: Vec<(String, CellFormat)>,
+    /// Raw `tableStyles`, `colors` and `extLst` elements
+    pub(crate) tail: String,
+}
+
+/// Worksheet children between `sheetData` and `mergeCells` in `CT_Worksheet`.
+const BEFORE_MERGE_CELLS: &[&str] = &[
+    "sheetCalcPr",
+    "sheetProtection",
+    "protectedRanges",
+    "scenarios",
+    "autoFilter",
+    "sortState",
+    "dataConsolidate",
+    "customSheetViews",
+];
+
+/// Insert generated worksheet children into the raw markup after `mergeCells`.
+///
+/// Each element goes before the first kept child that follows it in
+/// `CT_Worksheet`. Elements whose name is already present are left out, so
+/// markup kept from the loaded part wins.
+pub(super) fn insert_after_merges(
+    markup: &str,
+    elements: &[(&str, String)],
+) -> SheetResult<String> {
+    insert_children(markup, AFTER_MERGE_CELLS, elements)
+}
+
+/// Insert generated worksheet children into the raw markup between
+/// `sheetData` and `mergeCells`, like [`insert_after_merges`].
+pub(super) fn insert_before_merges(
+    markup: &str,
+    elements: &[(&str, String)],
+) -> SheetResult<String> {
+    insert_children(markup, BEFORE_MERGE_CELLS, elements)
+}
+
+/// Insert elements into a run of sibling elements kept in `order`.
+fn insert_children(
+    markup: &str,
+    order: &[&str],
+    elements: &[(&str, String)],
+) -> SheetResult<String> {
+    let position = |name: &str| {
+        order
+            .iter()
+            .position(|known| *known == name)
+            .unwrap_or(order.len())
+    };
+
+    let wrapped = format!("<root>{}</root>", markup);
+    let (_, children) = split_children(&wrapped)?;
+    let mut pending: Vec<(usize, &str)> = elements
+        .iter()
+        .filter(|(name, _)| !children.iter().any(|child| child.name == *name))
+        .map(|(name, xml)| (position(name), xml.as_str()))
+        .collect();
+    pending.sort_by_key(|(index, _)| *index);
+    let mut pending = pending.into_iter().peekable();
+
+    let body = "<root>".len()..wrapped.len() - "</root>".len();
+    let mut xml = String::with_capacity(markup.len() + 128);
+    let mut copied = body.start;
+    for child in &children {
+        if let Some(index) = order.iter().position(|known| *known == child.name) {
+            while let Some((_, element)) = pending.next_if(|(pending, _)| *pending < index) {
+                xml.push_str(element);
+            }
+        }
+        xml.push_str(&wrapped[copied..child.range.end]);
+        copied = child.range.end;
+    }
+    for (_, element) in pending {
+        xml.push_str(element);
+    }
+    xml.push_str(&wrapped[copied..body.end]);
+
+    Ok(xml)
+}
+
+/// Get the raw attributes of an element, leaving out the given local names.
+pub(super) fn attributes_except(element: &str, names: &[&str]) -> SheetResult<String> {
+    let mut reader = Reader::from_str(element);
+    loop {
+        match reader
+            .read_event()
+            .map_err(|e| format!("XML parse error: {}", e))?
+        {
+            Event::Start(e) | Event::Empty(e) => return Ok(start_attributes_except(&e, names)),
+            Event::Eof => return Ok(String::new()),
+            _ => {},
+        }
+    }
+}
+
+/// Get the raw attributes of a start tag, leaving out the given local names.
+fn start_attributes_except(e: &BytesStart, names: &[&str]) -> String {
+    let mut attributes = String::new();
+    for attr in e.attributes().flatten() {
+        let local = String::from_utf8_lossy(attr.key.local_name().as_ref()).into_owned();
+        if !names.contains(&local.as_str()) {
+            attributes.push(' ');
+            attributes.push_str(&String::from_utf8_lossy(attr.key.as_ref()));
+            attributes.push_str("=\"");
+            attributes.push_str(&String::from_utf8_lossy(&attr.value));
+            attributes.push('"');
+        }
+    }
+    attributes
+}
+
+/// Read the raw `si` elements of a shared strings part that hold rich text
+/// or phonetic runs, by index.
+pub(crate) fn rich_shared_strings(xml: &str) -> SheetResult<HashMap<usize, String>> {
+    let (_, children) = split_children(xml)?;
+    let mut rich = HashMap::new();
+    for (index, child) in children
+        .iter()
+        .filter(|child| child.name == "si")
+        .enumerate()
+    {
+        let markup = &xml[child.range.clone()];
+        let (_, runs) = split_children(markup)?;
+        if runs.iter().any(|run| run.name == "r" || run.name == "rPh") {
+            rich.insert(index, markup.to_string());
+        }
+    }
+    Ok(rich)
+}
+
+/// Get a prefixed attribute (such as `r:id`) by local name.
+fn prefixed_attribute(e: &BytesStart, name: &[u8]) -> Option<String> {
+    e.attributes()
+        .flatten()
+        .find(|attr| attr.key.prefix().is_some() && attr.key.local_name().as_ref() == name)
+        .and_then(|attr| attr.unescape_value().ok().map(|v| v.into_owned()))
+}
+
+fn is_true(value: Option<String>) -> bool {
+    matches!(value.as_deref(), Some("1") | Some("true"))
+}
+
+/// Read the relationship IDs of a `tableParts` element.
+fn table_part_ids(xml: &str) -> SheetResult<Vec<String>> {
+    let mut reader = Reader::from_str(xml);
+    let mut ids = Vec::new();
+    loop {
+        match reader.read_event() {
+            Ok(Event::Start(e)) | Ok(Event::Empty(e))
+                if e.local_name().as_ref() == b"tablePart" =>
+            {
+                ids.extend(prefixed_attribute(&e, b"id"));
+            },
+            Ok(Event::Eof) => break,
+            Err(e) => return Err(format!("XML parse error: {}", e).into()),
+            _ => {},
+        }
+    }
+    Ok(ids)
+}
+
+/// Read the `hyperlink` elements of a `hyperlinks` element.
+///
+/// External targets are left for the caller to resolve from the worksheet
+/// relationships; their URL holds only the `#location` fragment, if any.
+fn parse_hyperlinks(xml: &str) -> SheetResult<Vec<Hyperlink>> {
+    let mut reader = Reader::from_str(xml);
+    let mut hyperlinks = Vec::new();
+    loop {
+        match reader.read_event() {
+            Ok(Event::Start(e)) | Ok(Event::Empty(e))
+                if e.local_name().as_ref() == b"hyperlink" =>
+            {
+                let Some(range) = attribute(&e, b"ref") else {
+                    continue;
+                };
+                let location = attribute(&e, b"location");
+                let relationship_id = prefixed_attribute(&e, b"id");
+                let target = if relationship_id.is_some() {
+                    HyperlinkTarget::External(
+                        location.map(|l| format!("#{}", l)).unwrap_or_default(),
+                    )
+                } else {
+                    HyperlinkTarget::Internal(location.unwrap_or_default())
+                };
+                hyperlinks.push(Hyperlink {
+                    range,
+                    target,
+                    tooltip: attribute(&e, b"tooltip"),
+                    display: attribute(&e, b"display"),
+                    relationship_id,
+                });
+            },
+            Ok(Event::Eof) => break,
+            Err(e) => return Err(format!("XML parse error: {}", e).into()),
+            _ => {},
+        }
+    }
+    Ok(hyperlinks)
+}
+
+/// Read the `r:id` of an element such as `legacyDrawing`.
+fn first_relationship_id(xml: &str) -> SheetResult<Option<String>> {
+    let mut reader = Reader::from_str(xml);
+    match reader
+        .read_event()
+        .map_err(|e| format!("XML parse error: {}", e))?
+    {
+        Event::Start(e) | Event::Empty(e) => Ok(prefixed_attribute(&e, b"id")),
+        _ => Ok(None),
+    }
+}
+
+impl MutableWorkbookData {
+    /// Mirror an existing `workbook.xml` without reading any worksheet parts.
+    ///
+    /// `partname` resolves a sheet's relationship ID and sheet ID to the
+    /// partname of its worksheet part.
+    pub(crate) fn from_existing(
+        workbook_xml: &str,
+        styles_xml: Option<&str>,
+        styles: &Styles,
+        partname: impl Fn(&str, u32) -> String,
+    ) -> SheetResult<Self> {
+        let mut data = Self {
+            worksheets: Vec::new(),
+            shared_strings: Default::default(),
+            named_ranges: Vec::new(),
+            named_styles: Vec::new(),
+            protection: None,
+            modified: false,
+            source_xml: Some(workbook_xml.to_string()),
+            styles_source: None,
+            rich_strings: HashMap::new(),
+        };
+
+        let (_, children) = split_children(workbook_xml)?;
+        for child in &children {
+            let xml = &workbook_xml[child.range.clone()];
+            match child.name.as_str() {
+                "sheets" => data.load_sheets(xml, &partname)?,
+                "definedNames" => data.named_ranges = parse_defined_names(xml)?,
+                "workbookProtection" => data.protection = parse_workbook_protection(xml)?,
+                _ => {},
+            }
+        }
+
+        // Print areas and titles are kept with their sheets
+        let worksheets = &mut data.worksheets;
+        data.named_ranges.retain(|named| {
+            let Some(worksheet) = named
+                .local_sheet_id
+                .and_then(|id| id.checked_sub(1))
+                .and_then(|index| worksheets.get_mut(index as usize))
+            else {
+                return true;
+            };
+            let areas = formula_areas(&named.reference);
+            match named.name.as_str() {
+                PRINT_AREA => worksheet.print_area = Some(areas.join(",")),
+                PRINT_TITLES => worksheet.print_titles = PrintTitles::from_areas(areas),
+                _ => return true,
+            }
+            false
+        });
+
+        if let Some(styles_xml) = styles_xml {
+            let (root_attributes, children) = split_children(styles_xml)?;
+            let tail = children
+                .iter()
+                .filter(|child| STYLES_TAIL.contains(&child.name.as_str()))
+                .map(|child| &styles_xml[child.range.clone()])
+                .collect();
+            let mut dxfs = Vec::new();
+            if let Some(child) = children.iter().find(|child| child.name == "dxfs") {
+                let xml = &styles_xml[child.range.clone()];
+                let (_, markup) = split_children(xml)?;
+                dxfs = markup
+                    .iter()
+                    .filter(|dxf| dxf.name == "dxf")
+                    .map(|dxf| xml[dxf.range.clone()].to_string())
+                    .zip(styles.dxfs.iter().cloned())
+                    .collect();
+            }
+            data.named_styles = styles
+                .named_styles
+                .iter()
+                .filter(|style| style.builtin_id != Some(0) && style.name != "Normal")
+                .map(|style| NamedStyle {
+                    name: style.name.clone(),
+                    format: styles
+                        .cell_styles
+                        .get(style.xf_id as usize)
+                        .and_then(|xf| xf_format(styles, xf))
+                        .unwrap_or_default(),
+                    builtin_id: style.builtin_id,
+                })
+                .collect();
+            data.styles_source = Some(StylesSource {
+                root_attributes,
+                default_font: styles.fonts.first().map(CellFont::from),
+                dxfs,
+                tail,
+            });
+        }
+
+        Ok(data)
+    }
+
+    fn load_sheets(
+        &mut self,
+        xml: &str,
+        partname: &impl Fn(&str, u32) -> String,
+    ) -> SheetResult<()> {
+        let mut reader = Reader::from_str(xml);
+        loop {
+            match reader.read_event() {
+                Ok(Event::Start(e)) | Ok(Event::Empty(e))
+                    if e.local_name().as_ref() == b"sheet" =>
+                {
+                    let (Some(name), Some(sheet_id), Some(relationship_id)) = (
+                        attribute(&e, b"name"),
+                        attribute(&e, b"sheetId").and_then(|id| id.parse().ok()),
+                        prefixed_attribute(&e, b"id"),
+                    ) else {
+                        continue;
+                    };
+
+                    let mut worksheet = MutableWorksheet::new(name, sheet_id);
+                    worksheet.source = Some(SheetSource {
+                        partname: partname(&relationship_id, sheet_id),
+                        relationship_id,
+                        state: attribute(&e, b"state").filter(|state| state != "visible"),
+                        ..SheetSource::default()
+                    });
+                    self.worksheets.push(worksheet);
+                },
+                Ok(Event::Eof) => break,
+                Err(e) => return Err(format!("XML parse error: {}", e).into()),
+                _ => {},
+            }
+        }
+        Ok(())
+    }
+}
+
+/// A `c` element being read.
+#[derive(Default)]
+struct PendingCell {
+    position: (u32, u32),
+    style: usize,
+    cell_type: Option<String>,
+    formula: Option<String>,
+    formula_attributes: String,
+    value: Option<String>,
+    inline: Option<String>,
+}
+
+/// Which cell child text is being collected.
+#[derive(Clone, Copy, PartialEq)]
+enum Capture {
+    None,
+    Formula,
+    Value,
+    Inline,
+}
+
+impl MutableWorksheet {
+    /// Load cells, formats, merges, sizes and preserved markup from worksheet XML.
+    ///
+    /// Shared string indices are resolved against `shared_strings`, keeping
+    /// the markup of the `rich_strings` entries, and style indices are
+    /// converted to [`CellFormat`]s through `styles`. The sheet is not marked
+    /// as modified.
+    pub(crate) fn load_xml(
+        &mut self,
+        xml: &str,
+        shared_strings: &SharedStrings,
+        rich_strings: &HashMap<usize, String>,
+        styles: &Styles,
+    ) -> SheetResult<()> {
+        let mut source = self.source.take().unwrap_or_default();
+        let (root_attributes, children) = split_children(xml)?;
+        source.root_attributes = root_attributes;
+        let mut formats = HashMap::new();
+        let mut after_merges = false;
+        let mut page_layout = String::new();
+
+        for child in &children {
+            let fragment = &xml[child.range.clone()];
+            match child.name.as_str() {
+                "sheetPr" => {
+                    page_layout.push_str(fragment);
+                    source.sheet_pr = Some(fragment.to_string());
+                },
+                "sheetViews" => {
+                    self.load_sheet_views(fragment)?;
+                    source.sheet_views = Some(fragment.to_string());
+                },
+                "sheetFormatPr" => source.sheet_format_pr = Some(fragment.to_string()),
+                "cols" => self.load_cols(fragment, styles, &mut formats)?,
+                "sheetData" => self.load_sheet_data(
+                    fragment,
+                    shared_strings,
+                    rich_strings,
+                    styles,
+                    &mut formats,
+                )?,
+                "mergeCells" => {
+                    self.load_merge_cells(fragment)?;
+                    after_merges = true;
+                },
+                "conditionalFormatting" => {
+                    after_merges = true;
+                    let formatting = parse_conditional_formatting(fragment, &styles.dxfs)?;
+                    // Keep elements with rule types the model doesn't cover verbatim
+                    let rules = formatting.iter().map(|f| f.rules.len()).sum::<usize>();
+                    if rules == fragment.matches("<cfRule").count() {
+                        self.conditional_formats.extend(formatting);
+                    } else {
+                        source.conditional_formatting.push_str(fragment);
+                    }
+                },
+                "autoFilter" => {
+                    let filter = parse_auto_filter(fragment)?;
+                    // Keep filters with criteria the model doesn't cover verbatim
+                    match filter {
+                        Some(filter)
+                            if filter.columns.len()
+                                == fragment.matches("<filterColumn").count() =>
+                        {
+                            self.auto_filter = Some(filter)
+                        },
+                        _ => source.before_merges.push_str(fragment),
+                    }
+                },
+                "hyperlinks" => {
+                    after_merges = true;
+                    self.hyperlinks = parse_hyperlinks(fragment)?;
+                },
+                "legacyDrawing" => {
+                    after_merges = true;
+                    source.legacy_drawing = first_relationship_id(fragment)?;
+                    source.after_merges.push_str(fragment);
+                },
+                "tableParts" => {
+                    after_merges = true;
+                    source.table_parts = table_part_ids(fragment)?;
+                },
+                "sheetProtection" => match parse_sheet_protection(fragment)? {
+                    Some(protection) => self.protection = Some(protection),
+                    None => source.before_merges.push_str(fragment),
+                },
+                "printOptions" | "pageMargins" | "pageSetup" | "headerFooter" => {
+                    after_merges = true;
+                    if child.name == "pageSetup" {
+                        source.page_setup_attributes =
+                            attributes_except(fragment, PAGE_SETUP_ATTRIBUTES)?;
+                    }
+                    page_layout.push_str(fragment);
+                },
+                // Regenerated from the cells
+                "dimension" => {},
+                // Refers to a font index that is not kept when styles are rebuilt
+                "phoneticPr" => after_merges = true,
+                name => {
+                    after_merges |= AFTER_MERGE_CELLS.contains(&name);
+                    if after_merges {
+                        source.after_merges.push_str(fragment);
+                    } else {
+                        source.before_merges.push_str(fragment);
+                    }
+                },
+            }
+        }
+
+        self.page_layout = parse_page_layout(&page_layout)?;
+        source.loaded = true;
+        self.source = Some(source);
+        Ok(())
+    }
+
+    fn load_sheet_views(&mut self, xml: &str) -> SheetResult<()> {
+        let mut reader = Reader::from_str(xml);
+        loop {
+            match reader.read_event() {
+                Ok(Event::Start(e)) | Ok(Event::Empty(e)) if e.local_name().as_ref() == b"pane" => {
+                    if attribute(&e, b"state").is_some_and(|state| state.starts_with("frozen")) {
+                        let split = |name: &[u8]| {
+                            attribute(&e, name)
+                                .and_then(|v| v.parse::<f64>().ok())
+                                .map_or(0, |v| v as u32)
+                        };
+                        self.freeze_panes = Some(FreezePanes {
+                            freeze_cols: split(b"xSplit"),
+                            freeze_rows: split(b"ySplit"),
+                        });
+                    }
+                    // Only the first sheet view is used
+                    break;
+                },
+                Ok(Event::Eof) => break,
+                Err(e) => return Err(format!("XML parse error: {}", e).into()),
+                _ => {},
+            }
+        }
+        Ok(())
+    }
+
+    fn load_cols(
+        &mut self,
+        xml: &str,
+        styles: &Styles,
+        formats: &mut HashMap<usize, Option<CellFormat>>,
+    ) -> SheetResult<()> {
+        let mut reader = Reader::from_str(xml);
+        loop {
+            match reader.read_event() {
+                Ok(Event::Start(e)) | Ok(Event::Empty(e)) if e.local_name().as_ref() == b"col" => {
+                    let bound =
+                        |name: &[u8]| attribute(&e, name).and_then(|v| v.parse::<u32>().ok());
+                    let (Some(min), Some(max)) = (bound(b"min"), bound(b"max")) else {
+                        continue;
+                    };
+                    let width = attribute(&e, b"width").and_then(|v| v.parse::<f64>().ok());
+                    let hidden = is_true(attribute(&e, b"hidden"));
+                    let format = attribute(&e, b"style")
+                        .and_then(|v| v.parse::<usize>().ok())
+                        .and_then(|style| load_format(styles, style, formats));
+                    let attributes = start_attributes_except(&e, COL_ATTRIBUTES);
+
+                    for col in min..=max.min(MAX_COLUMNS) {
+                        if let Some(width) = width {
+                            self.column_widths.insert(col, width);
+                        }
+                        if hidden {
+                            self.hidden_columns.insert(col);
+                        }
+                        if let Some(format) = &format {
+                            self.column_formats.insert(col, format.clone());
+                        }
+                        if !attributes.is_empty() {
+                            self.column_attributes.insert(col, attributes.clone());
+                        }
+                    }
+                },
+                Ok(Event::Eof) => break,
+                Err(e) => return Err(format!("XML parse error: {}", e).into()),
+                _ => {},
+            }
+        }
+        Ok(())
+    }
+
+    fn load_merge_cells(&mut self, xml: &str) -> SheetResult<()> {
+        let mut reader = Reader::from_str(xml);
+        loop {
+            match reader.read_event() {
+                Ok(Event::Start(e)) | Ok(Event::Empty(e))
+                    if e.local_name().as_ref() == b"mergeCell" =>
+                {
+                    if let Some(range) = attribute(&e, b"ref")
+                        && let Some((start, end)) = range.split_once(':')
+                    {
+                        let (start_col, start_row) = reference_to_coords(&start.replace('$', ""))?;
+                        let (end_col, end_row) = reference_to_coords(&end.replace('$', ""))?;
+                        self.merged_cells
+                            .push((start_row, start_col, end_row, end_col));
+                    }
+                },
+                Ok(Event::Eof) => break,
+                Err(e) => return Err(format!("XML parse error: {}", e).into()),
+                _ => {},
+            }
+        }
+        Ok(())
+    }
+
+    fn load_sheet_data(
+        &mut self,
+        xml: &str,
+        shared_strings: &SharedStrings,
+        rich_strings: &HashMap<usize, String>,
+        styles: &Styles,
+        formats: &mut HashMap<usize, Option<CellFormat>>,
+    ) -> SheetResult<()> {
+        let mut reader = Reader::from_str(xml);
+        let mut row = 0u32;
+        let mut column = 0u32;
+        let mut cell: Option<PendingCell> = None;
+        let mut capture = Capture::None;
+        let mut in_phonetic = false;
+
+        loop {
+            let event = reader
+                .read_event()
+                .map_err(|e| format!("XML parse error: {}", e))?;
+            match &event {
+                Event::Start(e) | Event::Empty(e) => {
+                    let empty = matches!(event, Event::Empty(_));
+                    match e.local_name().as_ref() {
+                        b"row" => {
+                            row = attribute(e, b"r")
+                                .and_then(|r| r.parse().ok())
+                                .unwrap_or(row + 1);
+                            column = 0;
+                            if is_true(attribute(e, b"customHeight"))
+                                && let Some(height) =
+                                    attribute(e, b"ht").and_then(|ht| ht.parse::<f64>().ok())
+                            {
+                                self.row_heights.insert(row, height);
+                            }
+                            if is_true(attribute(e, b"hidden")) {
+                                self.hidden_rows.insert(row);
+                            }
+                            if is_true(attribute(e, b"customFormat"))
+                                && let Some(format) = attribute(e, b"s")
+                                    .and_then(|s| s.parse::<usize>().ok())
+                                    .and_then(|style| load_format(styles, style, formats))
+                            {
+                                self.row_formats.insert(row, format);
+                            }
+                            let attributes = start_attributes_except(e, ROW_ATTRIBUTES);
+                            if !attributes.is_empty() {
+                                self.row_attributes.insert(row, attributes);
+                            }
+                        },
+                        b"c" => {
+                            let (col, cell_row) = match attribute(e, b"r") {
+                                Some(reference) => reference_to_coords(&reference)?,
+                                None => (column + 1, row),
+                            };
+                            column = col;
+                            let pending = PendingCell {
+                                position: (cell_row, col),
+                                style: attribute(e, b"s").and_then(|s| s.parse().ok()).unwrap_or(0),
+                                cell_type: attribute(e, b"t"),
+                                ..PendingCell::default()
+                            };
+                            if empty {
+                                self.store_cell(
+                                    pending,
+                                    shared_strings,
+                                    rich_strings,
+                                    styles,
+                                    formats,
+                                );
+                            } else {
+                                cell = Some(pending);
+                            }
+                        },
+                        b"f" => {
+                            if let Some(cell) = cell.as_mut() {
+                                cell.formula = Some(String::new());
+                                cell.formula_attributes = raw_attributes(e);
+                                if !empty {
+                                    capture = Capture::Formula;
+                                }
+                            }
+                        },
+                        b"v" if !empty => capture = Capture::Value,
+                        b"rPh" => in_phonetic = !empty,
+                        b"t" if !empty && !in_phonetic => {
+                            if let Some(cell) = cell.as_mut() {
+                                cell.inline.get_or_insert_with(String::new);
+                                capture = Capture::Inline;
+                            }
+                        },
+                        _ => {},
+                    }
+                },
+                Event::End(e) => match e.local_name().as_ref() {
+                    b"c" => {
+                        if let Some(pending) = cell.take() {
+                            self.store_cell(pending, shared_strings, rich_strings, styles, formats);
+                        }
+                    },
+                    b"rPh" => in_phonetic = false,
+                    b"f" | b"v" | b"t" => capture = Capture::None,
+                    _ => {},
+                },
+                Event::Eof => break,
+                _ => {
+                    if let Some(cell) = cell.as_mut() {
+                        let target = match capture {
+                            Capture::None => continue,
+                            Capture::Formula => cell.formula.get_or_insert_with(String::new),
+                            Capture::Value => cell.value.get_or_insert_with(String::new),
+                            Capture::Inline => cell.inline.get_or_insert_with(String::new),
+                        };
+                        push_text(&event, target)?;
+                    }
+                },
+            }
+        }
+        Ok(())
+    }
+
+    /// Store a fully read cell.
+    fn store_cell(
+        &mut self,
+        cell: PendingCell,
+        shared_strings: &SharedStrings,
+        rich_strings: &HashMap<usize, String>,
+        styles: &Styles,
+        formats: &mut HashMap<usize, Option<CellFormat>>,
+    ) {
+        let value = match (cell.cell_type.as_deref(), cell.value) {
+            (Some("inlineStr"), _) => cell.inline.map_or(CellValue::Empty, CellValue::String),
+            (_, None) => CellValue::Empty,
+            (Some("s"), Some(v)) => {
+                let index = v.trim().parse::<usize>().ok();
+                if let Some(markup) = index.and_then(|index| rich_strings.get(&index)) {
+                    self.rich_text.insert(cell.position, markup.clone());
+                }
+                index
+                    .and_then(|index| shared_strings.get(index))
+                    .map_or(CellValue::Empty, |s| CellValue::String(s.to_string()))
+            },
+            (Some("str") | Some("d"), Some(v)) => CellValue::String(v),
+            (Some("b"), Some(v)) => CellValue::Bool(matches!(v.trim(), "1" | "true")),
+            (Some("e"), Some(v)) => CellValue::Error(v),
+            (_, Some(v)) => {
+                let v = v.trim();
+                if let Ok(int_val) = v.parse::<i64>() {
+                    CellValue::Int(int_val)
+                } else if let Ok(float_val) = v.parse::<f64>() {
+                    CellValue::Float(float_val)
+                } else {
+                    CellValue::String(v.to_string())
+                }
+            },
+        };
+
+        let value = match cell.formula {
+            Some(formula) => {
+                if !cell.formula_attributes.trim().is_empty() {
+                    self.formula_attributes
+                        .insert(cell.position, cell.formula_attributes);
+                }
+                CellValue::Formula {
+                    formula,
+                    cached_value: (!matches!(value, CellValue::Empty)).then(|| Box::new(value)),
+                }
+            },
+            None => value,
+        };
+
+        let format = load_format(styles, cell.style, formats);
+
+        if matches!(value, CellValue::Empty) && format.is_none() {
+            return;
+        }
+        if let Some(format) = format {
+            self.cell_formats.insert(cell.position, format);
+        }
+        self.cells.insert(cell.position, value);
+    }
+}
+
+/// Highest column number in a worksheet (XFD).
+const MAX_COLUMNS: u32 = 16_384;
+
+/// Convert a `cellXfs` index to a [`CellFormat`], caching the result.
+///
+/// The default format (index 0) gives `None`.
+fn load_format(
+    styles: &Styles,
+    index: usize,
+    formats: &mut HashMap<usize, Option<CellFormat>>,
+) -> Option<CellFormat> {
+    if index == 0 {
+        return None;
+    }
+    formats
+        .entry(index)
+        .or_insert_with(|| cell_format(styles, index))
+        .clone()
+}
+
+/// Convert a `cellXfs` entry to a [`CellFormat`].
+///
+/// Returns `None` when the entry carries nothing the writer model can express.
+fn cell_format(styles: &Styles, index: usize) -> Option<CellFormat> {
+    let style = styles.get_cell_style(index)?;
+    let style_name = style
+        .xf_id
+        .filter(|&id| id != 0)
+        .and_then(|id| styles.named_styles.iter().find(|named| named.xf_id == id))
+        .filter(|named| named.builtin_id != Some(0))
+        .map(|named| named.name.clone());
+
+    match xf_format(styles, style) {
+        Some(format) => Some(CellFormat {
+            style_name,
+            ..format
+        }),
+        None => style_name.map(|style_name| CellFormat {
+            style_name: Some(style_name),
+            ..Default::default()
+        }),
+    }
+}
+
+/// Convert an `xf` record to a [`CellFormat`], leaving out the style name.
+fn xf_format(styles: &Styles, style: &CellStyle) -> Option<CellFormat> {
+    let font = style
+        .font_id
+        .filter(|&id| id != 0)
+        .and_then(|id| styles.get_font(id as usize))
+        .map(CellFont::from);
+    let fill = style
+        .fill_id
+        .filter(|&id| id > 1)
+        .and_then(|id| styles.get_fill(id as usize))
+        .and_then(CellFill::from_fill);
+    let border = style
+        .border_id
+        .filter(|&id| id != 0)
+        .and_then(|id| styles.get_border(id as usize))
+        .map(CellBorder::from);
+    let number_format = style.num_fmt_id.filter(|&id| id != 0).and_then(|id| {
+        styles
+            .get_number_format(id)
+            .map(|format| format.code.clone())
+            .or_else(|| builtin_format_code(id).map(str::to_string))
+    });
+    let alignment = style
+        .alignment
+        .as_ref()
+        .filter(|alignment| alignment.has_settings())
+        .map(CellAlignment::from);
+    let protection = style.protection.as_ref().map(CellProtection::from);
+
+    if font.is_none()
+        && fill.is_none()
+        && border.is_none()
+        && number_format.is_none()
+        && alignment.is_none()
+        && protection.is_none()
+    {
+        return None;
+    }
+
+    Some(CellFormat {
+        font,
+        fill,
+        border,
+        number_format,
+        alignment,
+        protection,
+        style_name: None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_insert_after_merges() {
+        let kept = r#"<pageMargins left="0.7"/><tableParts count="0"/>"#;
+        let xml =
+            insert_after_merges(kept, &[("drawing", r#"<drawing r:id="rId1"/>"#.into())]).unwrap();
+        assert_eq!(
+            xml,
+            r#"<pageMargins left="0.7"/><drawing r:id="rId1"/><tableParts count="0"/>"#
+        );
+
+        let xml = insert_after_merges("", &[("drawing", "<drawing/>".into())]).unwrap();
+        assert_eq!(xml, "<drawing/>");
+
+        let kept = r#"<drawing r:id="rId2"/>"#;
+        let xml = insert_after_merges(kept, &[("drawing", "<drawing/>".into())]).unwrap();
+        assert_eq!(xml, kept);
+    }
+
+    const SHEET_XML: &str = r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<worksheet xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main" xmlns:r="http://schemas.openxmlformats.org/officeDocument/2006/relationships"><sheetPr><tabColor rgb="FFFF0000"/></sheetPr><dimension ref="A1:C3"/><sheetViews><sheetView workbookViewId="0"><pane ySplit="1" topLeftCell="A2" activePane="bottomLeft" state="frozen"/></sheetView></sheetViews><sheetFormatPr defaultRowHeight="15"/><cols><col min="1" max="2" width="20.5" customWidth="1"/></cols><sheetData><row r="1" ht="30" customHeight="1"><c r="A1" t="s"><v>0</v></c><c r="B1" s="1"><v>2.5</v></c><c r="C1" t="inlineStr"><is><t>a &amp; b</t></is></c></row><row r="2"><c r="A2"><f>SUM(B1:B3)</f><v>10</v></c><c r="B2"><f t="shared" ref="B2:B3" si="0">B1*2</f><v>5</v></c></row><row r="3"><c r="B3"><f t="shared" si="0"/><v>10</v></c><c r="C3" s="1"/></row></sheetData><mergeCells count="1"><mergeCell ref="A3:A4"/></mergeCells><conditionalFormatting sqref="A1"><cfRule type="expression" dxfId="0" priority="1"><formula>TRUE</formula></cfRule></conditionalFormatting><drawing r:id="rId1"/></worksheet>"#;
+
+    const STYLES_XML: &str = r#"<styleSheet xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main"><numFmts count="1"><numFmt numFmtId="164" formatCode="0.0%"/></numFmts><fonts count="2"><font><sz val="11"/><name val="Aptos"/></font><font><b/><sz val="11"/><name val="Aptos"/></font></fonts><fills count="2"><fill><patternFill patternType="none"/></fill><fill><patternFill patternType="gray125"/></fill></fills><borders count="1"><border/></borders><cellXfs count="2"><xf numFmtId="0" fontId="0" fillId="0" borderId="0"/><xf numFmtId="164" fontId="1" fillId="0" borderId="0"/></cellXfs><dxfs count="1"><dxf><font><b/></font></dxf></dxfs></styleSheet>"#;
+
+    fn shared_strings() -> SharedStrings {
+        SharedStrings::parse(r#"<sst><si><t>Name</t></si></sst>"#).unwrap()
+    }
+
+    #[test]
+    fn test_load_worksheet_xml() {
+        let styles = Styles::parse(STYLES_XML).unwrap();
+        let mut ws = MutableWorksheet::new("Data".to_string(), 1);
+        ws.load_xml(SHEET_XML, &shared_strings(), &HashMap::new(), &styles)
+            .unwrap();
+
+        assert!(!ws.is_modified());
+        assert!(matches!(ws.cell_value(1, 1), Some(CellValue::String(s)) if s == "Name"));
+        assert!(matches!(ws.cell_value(1, 2), Some(CellValue::Float(f)) if *f == 2.5));
+        assert!(matches!(ws.cell_value(1, 3), Some(CellValue::String(s)) if s == "a & b"));
+        assert!(matches!(
+            ws.cell_value(2, 1),
+            Some(CellValue::Formula { formula, cached_value: Some(_) }) if formula == "SUM(B1:B3)"
+        ));
+
+        let format = &ws.cell_formats()[&(1, 2)];
+        assert!(format.font.as_ref().is_some_and(|font| font.bold));
+        assert_eq!(format.number_format.as_deref(), Some("0.0%"));
+        assert!(ws.cell_formats().contains_key(&(3, 3)));
+
+        assert_eq!(ws.merged_cells, vec![(3, 1, 4, 1)]);
+        assert_eq!(ws.column_widths.get(&2), Some(&20.5));
+        assert_eq!(ws.row_heights.get(&1), Some(&30.0));
+        assert_eq!(ws.freeze_panes.as_ref().map(|f| f.freeze_rows), Some(1));
+
+        let source = ws.source.as_ref().unwrap();
+        assert!(source.sheet_pr.as_deref().unwrap().contains("tabColor"));
+        assert_eq!(source.after_merges, r#"<drawing r:id="rId1"/>"#);
+
+        let formatting = ws.conditional_formatting();
+        assert_eq!(formatting[0].range, "A1");
+        assert!(
+            formatting[0].rules[0]
+                .format
+                .as_ref()
+                .and_then(|format| format.font.as_ref())
+                .is_some_and(|font| font.bold)
+        );
+    }
+
+    #[test]
+    fn test_loaded_worksheet_round_trip() {
+        let styles = Styles::parse(STYLES_XML).unwrap();
+        let mut ws = MutableWorksheet::new("Data".to_string(), 1);
+        ws.load_xml(SHEET_XML, &shared_strings(), &HashMap::new(), &styles)
+            .unwrap();
+
+        let mut strings = Default::default();
+        let xml = ws.to_xml(&mut strings, &HashMap::new()).unwrap();
+        assert!(xml.contains(r#"<f t="shared" ref="B2:B3" si="0">B1*2</f>"#));
+        assert!(xml.contains(r#"<c r="B3"><f t="shared" si="0"/><v>10</v></c>"#));
+        assert!(xml.contains(r#"<col min="1" max="2" width="20.5" customWidth="1"/>"#));
+        assert!(xml.contains(r#"<row r="1" ht="30" customHeight="1">"#));
+
+        let merge = xml.find("<mergeCells").unwrap();
+        let conditional = xml.find("<conditionalFormatting").unwrap();
+        let drawing = xml.find("<drawing").unwrap();
+        assert!(xml.find("</sheetData>").unwrap() < merge);
+        assert!(merge < conditional && conditional < drawing);
+
+        // The output must be well-formed
+        let mut reloaded = MutableWorksheet::new("Data".to_string(), 1);
+        reloaded
+            .load_xml(&xml, &strings_table(&strings), &HashMap::new(), &styles)
+            .unwrap();
+        assert_eq!(reloaded.cell_count(), ws.cell_count());
+    }
+
+    fn strings_table(strings: &super::super::MutableSharedStrings) -> SharedStrings {
+        SharedStrings::parse(&strings.to_xml().unwrap()).unwrap()
+    }
+
+    #[test]
+    fn test_from_existing_workbook() {
+        let workbook_xml = r#"<workbook xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main" xmlns:r="http://schemas.openxmlformats.org/officeDocument/2006/relationships"><bookViews><workbookView/></bookViews><sheets><sheet name="Data" sheetId="3" r:id="rId2"/><sheet name="Hidden" sheetId="4" state="hidden" r:id="rId5"/></sheets><definedNames><definedName name="Rate" localSheetId="0">Data!$A$1</definedName></definedNames><pivotCaches><pivotCache cacheId="1" r:id="rId9"/></pivotCaches></workbook>"#;
+        let styles = Styles::parse(STYLES_XML).unwrap();
+        let mut data = MutableWorkbookData::from_existing(
+            workbook_xml,
+            Some(STYLES_XML),
+            &styles,
+            |rel_id, _| format!("/xl/worksheets/{}.xml", rel_id),
+        )
+        .unwrap();
+
+        assert!(!data.is_modified());
+        assert_eq!(data.worksheet_count(), 2);
+        let source = data.worksheets[1].source.as_ref().unwrap();
+        assert_eq!(source.partname, "/xl/worksheets/rId5.xml");
+        assert_eq!(source.state.as_deref(), Some("hidden"));
+        assert_eq!(data.named_ranges()[0].reference, "Data!$A$1");
+        assert_eq!(data.named_ranges()[0].local_sheet_id, Some(1));
+        let styles_source = data.styles_source.as_ref().unwrap();
+        assert_eq!(styles_source.dxfs.len(), 1);
+        assert!(styles_source.dxfs[0].0.starts_with("<dxf>"));
+        assert!(styles_source.tail.is_empty());
+
+        // New sheets get an unused sheet ID
+        assert_eq!(data.add_worksheet("New".to_string()).sheet_id(), 5);
+
+        let xml = data
+            .generate_workbook_xml_with_rels(&[
+                "rId2".to_string(),
+                "rId5".to_string(),
+                "rId10".to_string(),
+            ])
+            .unwrap();
+        assert!(xml.contains(r#"<sheet name="Hidden" sheetId="4" state="hidden" r:id="rId5"/>"#));
+        assert!(xml.contains(r#"<sheet name="New" sheetId="5" r:id="rId10"/>"#));
+        assert!(xml.contains(r#"<pivotCache cacheId="1" r:id="rId9"/>"#));
+        assert!(
+            xml.contains(r#"<definedName name="Rate" localSheetId="0">Data!$A$1</definedName>"#)
+        );
+    }
+}
@@ -1,8 +1,13 @@
 //! Mutable worksheet and workbook writer components for XLSX.
 
+pub(crate) mod chart;
+pub(crate) mod comment;
+pub(crate) mod loader;
+pub(crate) mod picture;
 pub mod sheet;
 pub mod strings;
 pub mod styles;
+pub(crate) mod table;
 pub mod workbook;
 
 // Re-export main types
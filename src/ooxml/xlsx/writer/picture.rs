@@ -0,0 +1,125 @@
+//! Drawing anchors for worksheet pictures.
+//!
+//! Each picture added with [`MutableWorksheet::add_picture`] becomes a media
+//! part related to the sheet's drawing part, and is placed by a
+//! `oneCellAnchor` or `twoCellAnchor` next to the sheet's charts.
+//!
+//! [`MutableWorksheet::add_picture`]: super::MutableWorksheet::add_picture
+
+use crate::ooxml::xlsx::picture::{Picture, PictureAnchor};
+
+use super::sheet::escape_xml;
+
+const RELATIONSHIPS_NS: &str =
+    "http://schemas.openxmlformats.org/officeDocument/2006/relationships";
+
+/// Generate the anchor placing a picture on the sheet.
+///
+/// `r_id` is the drawing part's relationship to the image part.
+pub(crate) fn anchor_xml(picture: &Picture, object_id: u32, r_id: &str) -> String {
+    let marker = |tag: &str, row: u32, col: u32| {
+        format!(
+            "<xdr:{0}><xdr:col>{1}</xdr:col><xdr:colOff>0</xdr:colOff><xdr:row>{2}</xdr:row><xdr:rowOff>0</xdr:rowOff></xdr:{0}>",
+            tag, col, row
+        )
+    };
+    let name = picture
+        .name
+        .clone()
+        .unwrap_or_else(|| format!("Picture {}", object_id - 1));
+    let description = picture
+        .description
+        .as_deref()
+        .map(|text| format!(r#" descr="{}""#, escape_xml(text)))
+        .unwrap_or_default();
+
+    let (open, placement, size, close) = match picture.anchor {
+        PictureAnchor::OneCell {
+            row,
+            col,
+            width,
+            height,
+        } => (
+            "<xdr:oneCellAnchor>",
+            format!(
+                r#"{}<xdr:ext cx="{}" cy="{}"/>"#,
+                marker("from", row.saturating_sub(1), col.saturating_sub(1)),
+                width,
+                height
+            ),
+            (width, height),
+            "</xdr:oneCellAnchor>",
+        ),
+        PictureAnchor::TwoCell {
+            start_row,
+            start_col,
+            end_row,
+            end_col,
+        } => {
+            let from_row = start_row.saturating_sub(1);
+            let from_col = start_col.saturating_sub(1);
+            (
+                r#"<xdr:twoCellAnchor editAs="oneCell">"#,
+                format!(
+                    "{}{}",
+                    marker("from", from_row, from_col),
+                    marker("to", end_row.max(from_row + 1), end_col.max(from_col + 1))
+                ),
+                (0, 0),
+                "</xdr:twoCellAnchor>",
+            )
+        },
+    };
+
+    format!(
+        r#"{}{}<xdr:pic><xdr:nvPicPr><xdr:cNvPr id="{}" name="{}"{}/><xdr:cNvPicPr><a:picLocks noChangeAspect="1"/></xdr:cNvPicPr></xdr:nvPicPr><xdr:blipFill><a:blip xmlns:r="{}" r:embed="{}"/><a:stretch><a:fillRect/></a:stretch></xdr:blipFill><xdr:spPr><a:xfrm><a:off x="0" y="0"/><a:ext cx="{}" cy="{}"/></a:xfrm><a:prstGeom prst="rect"><a:avLst/></a:prstGeom></xdr:spPr></xdr:pic><xdr:clientData/>{}"#,
+        open,
+        placement,
+        object_id,
+        escape_xml(&name),
+        description,
+        RELATIONSHIPS_NS,
+        escape_xml(r_id),
+        size.0,
+        size.1,
+        close
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A 2x3 PNG header; only the signature and IHDR size are read.
+    fn png() -> Vec<u8> {
+        let mut data = vec![0x89, b'P', b'N', b'G', 0x0D, 0x0A, 0x1A, 0x0A];
+        data.extend_from_slice(&[0, 0, 0, 13]);
+        data.extend_from_slice(b"IHDR");
+        data.extend_from_slice(&2u32.to_be_bytes());
+        data.extend_from_slice(&3u32.to_be_bytes());
+        data.extend_from_slice(&[8, 6, 0, 0, 0]);
+        data
+    }
+
+    #[test]
+    fn test_picture_anchors() {
+        let mut picture = Picture::new(png(), 2, 3).unwrap();
+        picture.description = Some("Logo & name".to_string());
+        let anchor = anchor_xml(&picture, 3, "rId2");
+        assert!(anchor.starts_with("<xdr:oneCellAnchor><xdr:from><xdr:col>2</xdr:col>"));
+        assert!(anchor.contains("<xdr:row>1</xdr:row>"));
+        assert!(anchor.contains(r#"<xdr:ext cx="19050" cy="28575"/>"#));
+        assert!(anchor.contains(r#"<xdr:cNvPr id="3" name="Picture 2" descr="Logo &amp; name"/>"#));
+        assert!(anchor.contains(r#"r:embed="rId2""#));
+        assert!(anchor.ends_with("<xdr:clientData/></xdr:oneCellAnchor>"));
+
+        let picture = Picture::over_cells(png(), (1, 1, 4, 2)).unwrap();
+        let anchor = anchor_xml(&picture, 2, "rId1");
+        assert!(anchor.starts_with(r#"<xdr:twoCellAnchor editAs="oneCell">"#));
+        assert!(anchor.contains(
+            "<xdr:to><xdr:col>2</xdr:col><xdr:colOff>0</xdr:colOff><xdr:row>4</xdr:row>"
+        ));
+
+        assert!(Picture::new(b"not an image".to_vec(), 1, 1).is_err());
+    }
+}
@@ -1,18 +1,37 @@
 /// Writer module for creating and modifying Excel worksheets.
+use crate::ooxml::common::xml::split_children;
+use crate::ooxml::xlsx::parsers::worksheet_parser::reference_to_coords;
 use crate::sheet::{CellValue, Result as SheetResult};
 use std::collections::HashMap;
 use std::fmt::Write as FmtWrite;
 
 // Import shared formatting types
 pub use super::super::format::{
-    CellBorder, CellBorderLineStyle, CellBorderSide, CellFill, CellFillPatternType, CellFont,
-    CellFormat, Chart, ChartType, DataValidation, DataValidationOperator, DataValidationType,
+    CellAlignment, CellBorder, CellBorderLineStyle, CellBorderSide, CellFill, CellFillPatternType,
+    CellFont, CellFormat, CellGradientFill, CellProtection, Chart, ChartType,
+    ConditionalFormatRule, ConditionalFormatType, ConditionalFormatValue,
+    ConditionalFormatValueType, ConditionalFormatting, DataValidation, DataValidationOperator,
+    DataValidationType, HorizontalAlignment, NamedStyle, VerticalAlignment,
+};
+pub use super::super::page_setup::{
+    HeaderFooter, PageLayout, PageMargins, PageOrientation, PageSetup, PrintOptions, PrintTitles,
+};
+pub use super::super::protection::{PasswordHash, SheetProtection};
+use super::super::table::resolve_structured_references;
+pub use super::super::table::{AutoFilter, Table};
+pub use super::super::{
+    comment::{Comment, CommentReply},
+    hyperlink::{Hyperlink, HyperlinkTarget},
+    picture::Picture,
 };
 // Import from other writer modules
+use super::loader::{SheetSource, attributes_except, insert_after_merges, insert_before_merges};
 use super::strings::MutableSharedStrings;
+use super::styles::color_attribute;
+use super::table::write_auto_filter;
 
 /// Escape XML special characters.
-fn escape_xml(s: &str) -> String {
+pub(super) fn escape_xml(s: &str) -> String {
     s.replace('&', "&amp;")
         .replace('<', "&lt;")
         .replace('>', "&gt;")
@@ -57,25 +76,66 @@ pub struct MutableWorksheet {
     /// Sheet ID
     sheet_id: u32,
     /// Cell data (row, col) -> value
-    cells: HashMap<(u32, u32), CellValue>,
+    pub(crate) cells: HashMap<(u32, u32), CellValue>,
     /// Cell formatting
-    cell_formats: HashMap<(u32, u32), CellFormat>,
+    pub(crate) cell_formats: HashMap<(u32, u32), CellFormat>,
+    /// Extra `f` attributes (shared/array formulas) kept from a loaded sheet
+    pub(crate) formula_attributes: HashMap<(u32, u32), String>,
+    /// Raw `si` markup of rich-text strings kept from a loaded sheet
+    pub(crate) rich_text: HashMap<(u32, u32), String>,
     /// Merged cell ranges (start_row, start_col, end_row, end_col)
-    merged_cells: Vec<(u32, u32, u32, u32)>,
-    /// Charts in this worksheet
-    charts: Vec<Chart>,
+    pub(crate) merged_cells: Vec<(u32, u32, u32, u32)>,
+    /// Charts in this worksheet not yet written to a drawing part
+    pub(crate) charts: Vec<Chart>,
+    /// Pictures in this worksheet not yet written to a drawing part
+    pub(crate) pictures: Vec<Picture>,
+    /// Comments in this worksheet not yet written to a comments part
+    pub(crate) comments: Vec<Comment>,
+    /// Cell hyperlinks
+    pub(crate) hyperlinks: Vec<Hyperlink>,
     /// Data validation rules
     validations: Vec<DataValidation>,
+    /// Conditional formatting rules
+    pub(crate) conditional_formats: Vec<ConditionalFormatting>,
+    /// `dxfId` of each formatted rule, keyed by (formatting index, rule index),
+    /// assigned when the workbook styles are built
+    pub(crate) conditional_format_dxfs: HashMap<(usize, usize), usize>,
     /// Column widths (col -> width in characters)
-    column_widths: HashMap<u32, f64>,
+    pub(crate) column_widths: HashMap<u32, f64>,
     /// Hidden columns
-    hidden_columns: std::collections::HashSet<u32>,
+    pub(crate) hidden_columns: std::collections::HashSet<u32>,
+    /// Column formats kept from a loaded sheet
+    pub(crate) column_formats: HashMap<u32, CellFormat>,
+    /// Extra `col` attributes (outline level, collapsed, ...) kept from a loaded sheet
+    pub(crate) column_attributes: HashMap<u32, String>,
     /// Row heights (row -> height in points)
-    row_heights: HashMap<u32, f64>,
+    pub(crate) row_heights: HashMap<u32, f64>,
     /// Hidden rows
-    hidden_rows: std::collections::HashSet<u32>,
+    pub(crate) hidden_rows: std::collections::HashSet<u32>,
+    /// Row formats kept from a loaded sheet
+    pub(crate) row_formats: HashMap<u32, CellFormat>,
+    /// Extra `row` attributes (outline level, collapsed, ...) kept from a loaded sheet
+    pub(crate) row_attributes: HashMap<u32, String>,
     /// Freeze panes configuration
-    freeze_panes: Option<FreezePanes>,
+    pub(crate) freeze_panes: Option<FreezePanes>,
+    /// Page setup, margins, print options and headers/footers
+    pub(crate) page_layout: PageLayout,
+    /// Print area as absolute references without the sheet name (e.g. `$A$1:$D$20`)
+    pub(crate) print_area: Option<String>,
+    /// Rows and columns repeated on every printed page
+    pub(crate) print_titles: PrintTitles,
+    /// Sheet protection
+    pub(crate) protection: Option<SheetProtection>,
+    /// Tables in this worksheet not yet written to a table part
+    pub(crate) tables: Vec<Table>,
+    /// Sheet-level filter buttons and criteria
+    pub(crate) auto_filter: Option<AutoFilter>,
+    /// Worksheet part this sheet was loaded from, if any
+    pub(crate) source: Option<SheetSource>,
+    /// Relationship ID of a drawing part created for this sheet's charts
+    pub(crate) drawing: Option<String>,
+    /// Relationship ID of a VML part created for this sheet's comments
+    pub(crate) legacy_drawing: Option<String>,
     /// Whether the worksheet has been modified
     modified: bool,
 }
@@ -88,14 +148,34 @@ impl MutableWorksheet {
             sheet_id,
             cells: HashMap::new(),
             cell_formats: HashMap::new(),
+            formula_attributes: HashMap::new(),
+            rich_text: HashMap::new(),
             merged_cells: Vec::new(),
             charts: Vec::new(),
+            pictures: Vec::new(),
+            comments: Vec::new(),
+            hyperlinks: Vec::new(),
             validations: Vec::new(),
+            conditional_formats: Vec::new(),
+            conditional_format_dxfs: HashMap::new(),
             column_widths: HashMap::new(),
             hidden_columns: std::collections::HashSet::new(),
+            column_formats: HashMap::new(),
+            column_attributes: HashMap::new(),
             row_heights: HashMap::new(),
             hidden_rows: std::collections::HashSet::new(),
+            row_formats: HashMap::new(),
+            row_attributes: HashMap::new(),
             freeze_panes: None,
+            page_layout: PageLayout::default(),
+            print_area: None,
+            print_titles: PrintTitles::default(),
+            protection: None,
+            tables: Vec::new(),
+            auto_filter: None,
+            source: None,
+            drawing: None,
+            legacy_drawing: None,
             modified: false,
         }
     }
@@ -119,11 +199,15 @@ impl MutableWorksheet {
     /// Set a cell value.
     pub fn set_cell_value<V: Into<CellValue>>(&mut self, row: u32, col: u32, value: V) {
         self.cells.insert((row, col), value.into());
+        self.formula_attributes.remove(&(row, col));
+        self.rich_text.remove(&(row, col));
         self.modified = true;
     }
 
     /// Set a cell formula.
     pub fn set_cell_formula(&mut self, row: u32, col: u32, formula: &str) {
+        self.formula_attributes.remove(&(row, col));
+        self.rich_text.remove(&(row, col));
         self.cells.insert(
             (row, col),
             CellValue::Formula {
@@ -142,6 +226,8 @@ impl MutableWorksheet {
         formula: &str,
         cached_value: V,
     ) {
+        self.formula_attributes.remove(&(row, col));
+        self.rich_text.remove(&(row, col));
         self.cells.insert(
             (row, col),
             CellValue::Formula {
@@ -158,6 +244,16 @@ impl MutableWorksheet {
         self.modified = true;
     }
 
+    /// Apply a named style to a cell, keeping any formatting set directly on it.
+    ///
+    /// The style must be added to the workbook (see
+    /// [`Workbook::add_named_style`](crate::ooxml::xlsx::Workbook::add_named_style))
+    /// before saving; unknown names fall back to "Normal".
+    pub fn set_cell_style(&mut self, row: u32, col: u32, style_name: &str) {
+        self.cell_formats.entry((row, col)).or_default().style_name = Some(style_name.to_string());
+        self.modified = true;
+    }
+
     /// Merge cells in a rectangular range.
     pub fn merge_cells(&mut self, start_row: u32, start_col: u32, end_row: u32, end_col: u32) {
         self.merged_cells
@@ -166,6 +262,12 @@ impl MutableWorksheet {
     }
 
     /// Add a chart to the worksheet.
+    ///
+    /// `data_range` may name another sheet (e.g. `Sheet1!A1:C5`). Its first
+    /// row holds series names when it contains only text, and with more than
+    /// one column the first column holds the categories (or X values).
+    /// `position` is `(start_row, start_col, end_row, end_col)`, 1-based and
+    /// inclusive, giving the cells the chart covers.
     pub fn add_chart(
         &mut self,
         chart_type: ChartType,
@@ -210,6 +312,473 @@ impl MutableWorksheet {
         self.modified = true;
     }
 
+    /// Add a conditional formatting rule to a cell range.
+    ///
+    /// `range` may list several ranges separated by spaces (e.g. `"A1:A10 C1:C10"`).
+    /// `format` is the differential format applied when the rule matches; color
+    /// scales, data bars and icon sets don't need one. Rules are evaluated in
+    /// the order they are added, after any rules loaded with the sheet.
+    pub fn add_conditional_format(
+        &mut self,
+        range: &str,
+        rule_type: ConditionalFormatType,
+        format: Option<CellFormat>,
+    ) {
+        let priority = self
+            .conditional_formats
+            .iter()
+            .flat_map(|formatting| &formatting.rules)
+            .map(|rule| rule.priority)
+            .max()
+            .unwrap_or(0)
+            + 1;
+        let rule = ConditionalFormatRule {
+            rule_type,
+            format,
+            priority,
+            stop_if_true: false,
+        };
+
+        match self
+            .conditional_formats
+            .iter_mut()
+            .find(|formatting| formatting.range == range)
+        {
+            Some(formatting) => formatting.rules.push(rule),
+            None => self.conditional_formats.push(ConditionalFormatting {
+                range: range.to_string(),
+                rules: vec![rule],
+            }),
+        }
+        self.modified = true;
+    }
+
+    /// Get the conditional formatting of the worksheet.
+    pub fn conditional_formatting(&self) -> &[ConditionalFormatting] {
+        &self.conditional_formats
+    }
+
+    /// Remove all conditional formatting from the worksheet.
+    pub fn clear_conditional_formatting(&mut self) {
+        self.conditional_formats.clear();
+        self.conditional_format_dxfs.clear();
+        if let Some(source) = self.source.as_mut() {
+            source.conditional_formatting.clear();
+        }
+        self.modified = true;
+    }
+
+    /// Set paper size, orientation and scaling.
+    pub fn set_page_setup(&mut self, setup: PageSetup) {
+        self.page_layout.setup = Some(setup);
+        self.modified = true;
+    }
+
+    /// Set the page margins.
+    pub fn set_page_margins(&mut self, margins: PageMargins) {
+        self.page_layout.margins = Some(margins);
+        self.modified = true;
+    }
+
+    /// Set whether grid lines and headings are printed and how pages are centered.
+    pub fn set_print_options(&mut self, options: PrintOptions) {
+        self.page_layout.print_options = Some(options);
+        self.modified = true;
+    }
+
+    /// Set the page headers and footers.
+    pub fn set_header_footer(&mut self, header_footer: HeaderFooter) {
+        self.page_layout.header_footer = Some(header_footer);
+        self.modified = true;
+    }
+
+    /// Get the page layout of the worksheet.
+    pub fn page_layout(&self) -> &PageLayout {
+        &self.page_layout
+    }
+
+    /// Set the range printed by default (1-based, inclusive).
+    pub fn set_print_area(&mut self, start_row: u32, start_col: u32, end_row: u32, end_col: u32) {
+        self.print_area = Some(format!(
+            "${}${}:${}${}",
+            Self::column_to_letters(start_col),
+            start_row,
+            Self::column_to_letters(end_col),
+            end_row
+        ));
+        self.modified = true;
+    }
+
+    /// Print the whole used range again.
+    pub fn clear_print_area(&mut self) {
+        self.print_area = None;
+        self.modified = true;
+    }
+
+    /// Get the print area as absolute references without the sheet name
+    /// (e.g. `$A$1:$D$20`).
+    pub fn print_area(&self) -> Option<&str> {
+        self.print_area.as_deref()
+    }
+
+    /// Set the rows and columns repeated on every printed page.
+    ///
+    /// Rows and columns are 1-based, inclusive ranges; pass `None` for neither.
+    pub fn set_print_titles(&mut self, rows: Option<(u32, u32)>, columns: Option<(u32, u32)>) {
+        self.print_titles = PrintTitles { rows, columns };
+        self.modified = true;
+    }
+
+    /// Get the rows and columns repeated on every printed page.
+    pub fn print_titles(&self) -> PrintTitles {
+        self.print_titles
+    }
+
+    /// Protect the worksheet.
+    ///
+    /// Cells stay editable only if their format unlocks them through
+    /// [`CellProtection`].
+    pub fn protect(&mut self, protection: SheetProtection) {
+        self.protection = Some(protection);
+        self.modified = true;
+    }
+
+    /// Remove the worksheet protection.
+    pub fn unprotect(&mut self) {
+        self.protection = None;
+        self.modified = true;
+    }
+
+    /// Get the worksheet protection, if the sheet is protected.
+    pub fn protection(&self) -> Option<&SheetProtection> {
+        self.protection.as_ref()
+    }
+
+    /// Add a table over a block of cells.
+    ///
+    /// The header cells are set to the column names, totals row cells to
+    /// their label or totals formula, and the data cells of calculated
+    /// columns to the column formula. The table part is written when the
+    /// workbook is saved; table names must be unique in the workbook.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use litchi::ooxml::xlsx::{Table, TotalsFunction, Workbook};
+    ///
+    /// let mut wb = Workbook::create()?;
+    /// let ws = wb.worksheet_mut(0)?;
+    /// // Header row, three data rows and a totals row
+    /// let mut table = Table::new("Sales", "A1:B5", &["Region", "Units"]);
+    /// table.totals_row = true;
+    /// table.columns[1].totals_function = Some(TotalsFunction::Sum);
+    /// ws.add_table(table)?;
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn add_table(&mut self, table: Table) -> SheetResult<()> {
+        let (start_row, start_col, end_row, end_col) = table
+            .bounds()
+            .ok_or_else(|| format!("Invalid table range: {}", table.range))?;
+        let valid_name = table
+            .name
+            .starts_with(|c: char| c.is_alphabetic() || c == '_' || c == '\\')
+            && table
+                .name
+                .chars()
+                .all(|c| c.is_alphanumeric() || c == '_' || c == '.' || c == '\\')
+            && reference_to_coords(&table.name).is_err();
+        if !valid_name {
+            return Err(format!("Invalid table name: {}", table.name).into());
+        }
+        if self
+            .tables
+            .iter()
+            .any(|other| other.name.eq_ignore_ascii_case(&table.name))
+        {
+            return Err(format!("Duplicate table name: {}", table.name).into());
+        }
+        if table.columns.len() != (end_col - start_col + 1) as usize {
+            return Err(format!(
+                "Table {} has {} columns but its range is {} columns wide",
+                table.name,
+                table.columns.len(),
+                end_col - start_col + 1
+            )
+            .into());
+        }
+        for (index, column) in table.columns.iter().enumerate() {
+            if column.name.is_empty()
+                || table.columns[..index]
+                    .iter()
+                    .any(|other| other.name.eq_ignore_ascii_case(&column.name))
+            {
+                return Err(format!("Invalid or duplicate table column: {:?}", column.name).into());
+            }
+        }
+        let first_data = start_row + u32::from(table.header_row);
+        let last_data = end_row - u32::from(table.totals_row);
+        if first_data > last_data {
+            return Err(format!("Table {} has no data rows", table.name).into());
+        }
+        let overlaps = self.tables.iter().filter_map(Table::bounds).any(|other| {
+            start_row <= other.2 && other.0 <= end_row && start_col <= other.3 && other.1 <= end_col
+        });
+        if overlaps {
+            return Err(format!("Table {} overlaps another table", table.name).into());
+        }
+
+        for (col, column) in (start_col..).zip(&table.columns) {
+            if table.header_row {
+                self.set_cell_value(start_row, col, column.name.as_str());
+            }
+            if let Some(formula) = &column.calculated_formula {
+                for row in first_data..=last_data {
+                    self.set_cell_formula(row, col, formula);
+                }
+            }
+            if table.totals_row {
+                if let Some(function) = &column.totals_function {
+                    self.set_cell_formula(
+                        end_row,
+                        col,
+                        &function.cell_formula(&table.name, &column.name),
+                    );
+                } else if let Some(label) = &column.totals_label {
+                    self.set_cell_value(end_row, col, label.as_str());
+                }
+            }
+        }
+
+        self.tables.push(table);
+        self.modified = true;
+        Ok(())
+    }
+
+    /// Get the tables added to this worksheet that have not been saved yet.
+    ///
+    /// Use [`Worksheet::tables`](crate::ooxml::xlsx::Worksheet::tables) to
+    /// read the tables of a saved or opened workbook.
+    pub fn tables(&self) -> &[Table] {
+        &self.tables
+    }
+
+    /// Add filter buttons to a range, with optional column criteria.
+    ///
+    /// Rows that don't match the criteria are hidden by Excel when the
+    /// filter is reapplied.
+    pub fn set_auto_filter(&mut self, filter: AutoFilter) {
+        self.auto_filter = Some(filter);
+        self.modified = true;
+    }
+
+    /// Get the sheet's autofilter.
+    pub fn auto_filter(&self) -> Option<&AutoFilter> {
+        self.auto_filter.as_ref()
+    }
+
+    /// Remove the sheet's autofilter.
+    pub fn clear_auto_filter(&mut self) {
+        self.auto_filter = None;
+        self.modified = true;
+    }
+
+    /// Place a picture on the worksheet.
+    ///
+    /// The picture is written to the sheet's drawing part when the workbook
+    /// is saved.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use litchi::ooxml::xlsx::{Picture, Workbook};
+    ///
+    /// let mut wb = Workbook::create()?;
+    /// let mut logo = Picture::new(std::fs::read("logo.png")?, 1, 1)?;
+    /// logo.description = Some("Company logo".to_string());
+    /// wb.worksheet_mut(0)?.add_picture(logo);
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn add_picture(&mut self, picture: Picture) {
+        self.pictures.push(picture);
+        self.modified = true;
+    }
+
+    /// Get the pictures added to this worksheet that have not been saved yet.
+    pub fn pictures(&self) -> &[Picture] {
+        &self.pictures
+    }
+
+    /// Add a note to a cell, replacing any comment the cell already has.
+    pub fn add_comment(&mut self, row: u32, col: u32, author: &str, text: &str) {
+        self.push_comment(row, col, author, text, false);
+    }
+
+    /// Start a comment thread on a cell, replacing any comment the cell
+    /// already has.
+    ///
+    /// Versions of Excel without threaded comments show the thread as a note.
+    pub fn add_threaded_comment(&mut self, row: u32, col: u32, author: &str, text: &str) {
+        self.push_comment(row, col, author, text, true);
+    }
+
+    /// Reply to a comment thread started with [`add_threaded_comment`].
+    ///
+    /// [`add_threaded_comment`]: Self::add_threaded_comment
+    pub fn reply_to_comment(
+        &mut self,
+        row: u32,
+        col: u32,
+        author: &str,
+        text: &str,
+    ) -> SheetResult<()> {
+        let comment = self
+            .comments
+            .iter_mut()
+            .find(|comment| comment.threaded && comment.row == row && comment.col == col)
+            .ok_or_else(|| {
+                format!(
+                    "No comment thread at {}{}",
+                    Self::column_to_letters(col),
+                    row
+                )
+            })?;
+        comment.replies.push(CommentReply {
+            author: author.to_string(),
+            text: text.to_string(),
+        });
+        self.modified = true;
+        Ok(())
+    }
+
+    fn push_comment(&mut self, row: u32, col: u32, author: &str, text: &str, threaded: bool) {
+        self.comments
+            .retain(|comment| comment.row != row || comment.col != col);
+        self.comments.push(Comment {
+            row,
+            col,
+            author: author.to_string(),
+            text: text.to_string(),
+            threaded,
+            replies: Vec::new(),
+        });
+        self.modified = true;
+    }
+
+    /// Get the comments added to this worksheet that have not been saved yet.
+    pub fn comments(&self) -> &[Comment] {
+        &self.comments
+    }
+
+    /// Add a hyperlink, replacing any link on the same range.
+    ///
+    /// An empty top-left cell is set to the link's display text, or to its
+    /// address.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use litchi::ooxml::xlsx::{Hyperlink, Workbook};
+    ///
+    /// let mut wb = Workbook::create()?;
+    /// let ws = wb.worksheet_mut(0)?;
+    /// ws.add_hyperlink(Hyperlink::external("A1", "https://example.com"))?;
+    /// let mut link = Hyperlink::internal("A2", "'Q1 Sales'!B4");
+    /// link.display = Some("Q1 totals".to_string());
+    /// ws.add_hyperlink(link)?;
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn add_hyperlink(&mut self, hyperlink: Hyperlink) -> SheetResult<()> {
+        let start = hyperlink
+            .range
+            .split(':')
+            .next()
+            .unwrap_or_default()
+            .replace('$', "");
+        let (col, row) = reference_to_coords(&start)
+            .map_err(|_| format!("Invalid hyperlink range: {}", hyperlink.range))?;
+        if let Some(end) = hyperlink.range.split(':').nth(1) {
+            reference_to_coords(&end.replace('$', ""))
+                .map_err(|_| format!("Invalid hyperlink range: {}", hyperlink.range))?;
+        }
+
+        if self
+            .cells
+            .get(&(row, col))
+            .is_none_or(|value| matches!(value, CellValue::Empty))
+        {
+            let text = hyperlink
+                .display
+                .clone()
+                .unwrap_or_else(|| hyperlink.address().to_string());
+            self.set_cell_value(row, col, text);
+        }
+        self.hyperlinks
+            .retain(|other| !other.range.eq_ignore_ascii_case(&hyperlink.range));
+        self.hyperlinks.push(hyperlink);
+        self.modified = true;
+        Ok(())
+    }
+
+    /// Get the hyperlinks of the worksheet.
+    pub fn hyperlinks(&self) -> &[Hyperlink] {
+        &self.hyperlinks
+    }
+
+    /// Remove the hyperlinks covering a cell; the cell keeps its value.
+    pub fn remove_hyperlink(&mut self, row: u32, col: u32) {
+        self.hyperlinks.retain(|hyperlink| {
+            let mut bounds = hyperlink
+                .range
+                .split(':')
+                .map(|reference| reference_to_coords(&reference.replace('$', "")));
+            let (Some(Ok((start_col, start_row))), end) = (bounds.next(), bounds.next()) else {
+                return true;
+            };
+            let (end_col, end_row) = match end {
+                Some(Ok(end)) => end,
+                _ => (start_col, start_row),
+            };
+            !(start_row..=end_row).contains(&row) || !(start_col..=end_col).contains(&col)
+        });
+        self.modified = true;
+    }
+
+    /// Replace structured references in chart ranges, conditional format
+    /// formulas and custom validation formulas, where Excel only accepts
+    /// cell references.
+    pub(crate) fn resolve_structured_references(&mut self, tables: &[(String, Table)]) {
+        if tables.is_empty() {
+            return;
+        }
+        let resolve = |formula: &mut String| {
+            if formula.contains('[') {
+                *formula = resolve_structured_references(formula, tables);
+            }
+        };
+
+        for chart in &mut self.charts {
+            resolve(&mut chart.data_range);
+        }
+        for rule in self
+            .conditional_formats
+            .iter_mut()
+            .flat_map(|formatting| &mut formatting.rules)
+        {
+            match &mut rule.rule_type {
+                ConditionalFormatType::CellIs { formulas, .. } => {
+                    formulas.iter_mut().for_each(resolve)
+                },
+                ConditionalFormatType::Expression { formula } => resolve(formula),
+                _ => {},
+            }
+        }
+        for validation in &mut self.validations {
+            if let DataValidationType::Custom { formula } = &mut validation.validation_type {
+                resolve(formula);
+            }
+        }
+    }
+
     /// Get a cell value.
     pub fn cell_value(&self, row: u32, col: u32) -> Option<&CellValue> {
         self.cells.get(&(row, col))
@@ -218,18 +787,25 @@ impl MutableWorksheet {
     /// Clear a cell.
     pub fn clear_cell(&mut self, row: u32, col: u32) {
         self.cells.remove(&(row, col));
+        self.formula_attributes.remove(&(row, col));
+        self.rich_text.remove(&(row, col));
         self.modified = true;
     }
 
     /// Clear all cells in the worksheet.
     pub fn clear_all(&mut self) {
         self.cells.clear();
+        self.formula_attributes.clear();
+        self.rich_text.clear();
         self.modified = true;
     }
 
     /// Get the number of non-empty cells.
     pub fn cell_count(&self) -> usize {
-        self.cells.len()
+        self.cells
+            .values()
+            .filter(|value| !matches!(value, CellValue::Empty))
+            .count()
     }
 
     /// Set column width in characters (Excel default is 8.43).
@@ -275,6 +851,7 @@ impl MutableWorksheet {
                 freeze_rows,
                 freeze_cols,
             });
+            self.discard_sheet_views();
             self.modified = true;
         }
     }
@@ -282,9 +859,17 @@ impl MutableWorksheet {
     /// Remove freeze panes.
     pub fn unfreeze_panes(&mut self) {
         self.freeze_panes = None;
+        self.discard_sheet_views();
         self.modified = true;
     }
 
+    /// Drop sheet views kept from a loaded sheet so they are regenerated.
+    fn discard_sheet_views(&mut self) {
+        if let Some(source) = self.source.as_mut() {
+            source.sheet_views = None;
+        }
+    }
+
     /// Check if the worksheet has been modified.
     pub fn is_modified(&self) -> bool {
         self.modified
@@ -313,6 +898,9 @@ impl MutableWorksheet {
 
     /// Serialize the worksheet to XML.
     ///
+    /// Sheets loaded from an existing part keep their root namespace
+    /// declarations and any markup the writer does not model, in schema order.
+    ///
     /// # Arguments
     /// * `shared_strings` - Mutable shared strings table
     /// * `style_indices` - Optional map of cell positions to style indices
@@ -321,9 +909,17 @@ impl MutableWorksheet {
         shared_strings: &mut MutableSharedStrings,
         style_indices: &HashMap<(u32, u32), usize>,
     ) -> SheetResult<String> {
+        let source = self.source.as_ref();
         let mut xml = String::with_capacity(4096);
         xml.push_str(r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>"#);
-        xml.push_str(r#"<worksheet xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main" xmlns:r="http://schemas.openxmlformats.org/officeDocument/2006/relationships">"#);
+
+        match source.filter(|s| !s.root_attributes.trim().is_empty()) {
+            Some(source) => write!(xml, "<worksheet{}>", source.root_attributes)
+                .map_err(|e| format!("XML write error: {}", e))?,
+            None => xml.push_str(r#"<worksheet xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main" xmlns:r="http://schemas.openxmlformats.org/officeDocument/2006/relationships">"#),
+        }
+
+        self.write_sheet_pr(&mut xml, source.and_then(|s| s.sheet_pr.as_deref()))?;
 
         // Write sheet dimensions
         if let Some((min_row, min_col, max_row, max_col)) = self.used_range() {
@@ -339,6 +935,473 @@ impl MutableWorksheet {
         }
 
         // Write sheet views (including freeze panes if set)
+        if let Some(sheet_views) = source.and_then(|s| s.sheet_views.as_deref()) {
+            xml.push_str(sheet_views);
+        } else {
+            self.write_sheet_views(&mut xml)?;
+        }
+
+        match source.and_then(|s| s.sheet_format_pr.as_deref()) {
+            Some(sheet_format_pr) => xml.push_str(sheet_format_pr),
+            None => xml.push_str("<sheetFormatPr defaultRowHeight=\"15\"/>\n"),
+        }
+
+        self.write_cols(&mut xml, style_indices)?;
+
+        // Write sheet data
+        xml.push_str("<sheetData>");
+        self.write_sheet_data(&mut xml, shared_strings, style_indices)?;
+        xml.push_str("</sheetData>");
+
+        let before_merges = source.map_or("", |s| s.before_merges.as_str());
+        let mut elements = Vec::new();
+        if let Some(protection) = &self.protection {
+            let mut element = String::new();
+            Self::write_sheet_protection(&mut element, protection)?;
+            elements.push(("sheetProtection", element));
+        }
+        if let Some(filter) = &self.auto_filter {
+            let mut element = String::new();
+            write_auto_filter(&mut element, &filter.range, filter)?;
+            elements.push(("autoFilter", element));
+        }
+        if elements.is_empty() {
+            xml.push_str(before_merges);
+        } else {
+            xml.push_str(&insert_before_merges(before_merges, &elements)?);
+        }
+
+        // Write merged cells
+        if !self.merged_cells.is_empty() {
+            write!(xml, r#"<mergeCells count="{}">"#, self.merged_cells.len())
+                .map_err(|e| format!("XML write error: {}", e))?;
+
+            for (start_row, start_col, end_row, end_col) in &self.merged_cells {
+                let start_ref = format!("{}{}", Self::column_to_letters(*start_col), start_row);
+                let end_ref = format!("{}{}", Self::column_to_letters(*end_col), end_row);
+                write!(xml, r#"<mergeCell ref="{}:{}"/>"#, start_ref, end_ref)
+                    .map_err(|e| format!("XML write error: {}", e))?;
+            }
+
+            xml.push_str("</mergeCells>");
+        }
+
+        let mut elements = Vec::new();
+        let mut conditional_formatting = source
+            .map(|s| s.conditional_formatting.clone())
+            .unwrap_or_default();
+        self.write_conditional_formatting(&mut conditional_formatting)?;
+        if !conditional_formatting.is_empty() {
+            elements.push(("conditionalFormatting", conditional_formatting));
+        }
+        if !self.hyperlinks.is_empty() {
+            let mut element = String::new();
+            self.write_hyperlinks(&mut element)?;
+            elements.push(("hyperlinks", element));
+        }
+        self.write_page_layout(
+            &mut elements,
+            source.map_or("", |s| s.page_setup_attributes.as_str()),
+        )?;
+        if let Some(r_id) = &self.drawing {
+            elements.push((
+                "drawing",
+                format!(r#"<drawing r:id="{}"/>"#, escape_xml(r_id)),
+            ));
+        }
+        if let Some(r_id) = &self.legacy_drawing {
+            elements.push((
+                "legacyDrawing",
+                format!(r#"<legacyDrawing r:id="{}"/>"#, escape_xml(r_id)),
+            ));
+        }
+        if let Some(table_parts) = source.map(|s| &s.table_parts).filter(|t| !t.is_empty()) {
+            let mut element = format!(r#"<tableParts count="{}">"#, table_parts.len());
+            for r_id in table_parts {
+                write!(element, r#"<tablePart r:id="{}"/>"#, escape_xml(r_id))
+                    .map_err(|e| format!("XML write error: {}", e))?;
+            }
+            element.push_str("</tableParts>");
+            elements.push(("tableParts", element));
+        }
+        let after_merges = source.map_or("", |s| s.after_merges.as_str());
+        if elements.is_empty() {
+            xml.push_str(after_merges);
+        } else {
+            xml.push_str(&insert_after_merges(after_merges, &elements)?);
+        }
+
+        xml.push_str("</worksheet>");
+
+        Ok(xml)
+    }
+
+    /// Write `sheetPr`, setting the `fitToPage` flag from the page setup.
+    fn write_sheet_pr(&self, xml: &mut String, sheet_pr: Option<&str>) -> SheetResult<()> {
+        let fit_to_page = self
+            .page_layout
+            .setup
+            .as_ref()
+            .is_some_and(|setup| setup.fit_to_page);
+        let Some(sheet_pr) = sheet_pr else {
+            if fit_to_page {
+                xml.push_str(r#"<sheetPr><pageSetUpPr fitToPage="1"/></sheetPr>"#);
+            }
+            return Ok(());
+        };
+        if !fit_to_page && !sheet_pr.contains("fitToPage") {
+            xml.push_str(sheet_pr);
+            return Ok(());
+        }
+
+        // pageSetUpPr is the last child of sheetPr
+        let (attributes, children) = split_children(sheet_pr)?;
+        let mut page_setup_pr = String::new();
+        write!(xml, "<sheetPr{}>", attributes).map_err(|e| format!("XML write error: {}", e))?;
+        for child in &children {
+            let fragment = &sheet_pr[child.range.clone()];
+            if child.name == "pageSetUpPr" {
+                page_setup_pr = attributes_except(fragment, &["fitToPage"])?;
+            } else {
+                xml.push_str(fragment);
+            }
+        }
+        if fit_to_page {
+            page_setup_pr.push_str(r#" fitToPage="1""#);
+        }
+        if !page_setup_pr.is_empty() {
+            write!(xml, "<pageSetUpPr{}/>", page_setup_pr)
+                .map_err(|e| format!("XML write error: {}", e))?;
+        }
+        xml.push_str("</sheetPr>");
+        Ok(())
+    }
+
+    /// Write the `hyperlinks` element.
+    ///
+    /// External targets are written by relationship ID, which is assigned
+    /// when the workbook is saved.
+    fn write_hyperlinks(&self, xml: &mut String) -> SheetResult<()> {
+        xml.push_str("<hyperlinks>");
+        for hyperlink in &self.hyperlinks {
+            write!(xml, r#"<hyperlink ref="{}""#, escape_xml(&hyperlink.range))
+                .map_err(|e| format!("XML write error: {}", e))?;
+            match &hyperlink.target {
+                HyperlinkTarget::External(_) => {
+                    if let Some(r_id) = &hyperlink.relationship_id {
+                        write!(xml, r#" r:id="{}""#, escape_xml(r_id))
+                            .map_err(|e| format!("XML write error: {}", e))?;
+                    }
+                },
+                HyperlinkTarget::Internal(location) => {
+                    write!(xml, r#" location="{}""#, escape_xml(location))
+                        .map_err(|e| format!("XML write error: {}", e))?;
+                },
+            }
+            for (name, value) in [
+                ("tooltip", &hyperlink.tooltip),
+                ("display", &hyperlink.display),
+            ] {
+                if let Some(value) = value {
+                    write!(xml, r#" {}="{}""#, name, escape_xml(value))
+                        .map_err(|e| format!("XML write error: {}", e))?;
+                }
+            }
+            xml.push_str("/>");
+        }
+        xml.push_str("</hyperlinks>");
+        Ok(())
+    }
+
+    /// Write the `sheetProtection` element.
+    fn write_sheet_protection(xml: &mut String, protection: &SheetProtection) -> SheetResult<()> {
+        xml.push_str("<sheetProtection");
+        if let Some(password) = &protection.password {
+            for (name, value) in password.attributes("") {
+                write!(xml, r#" {}="{}""#, name, escape_xml(&value))
+                    .map_err(|e| format!("XML write error: {}", e))?;
+            }
+        }
+        xml.push_str(r#" sheet="1""#);
+        for (name, allowed) in protection.flags() {
+            // These four are unprotected unless set; all others are protected
+            let protected_by_default = !matches!(
+                name,
+                "objects" | "scenarios" | "selectLockedCells" | "selectUnlockedCells"
+            );
+            if allowed == protected_by_default {
+                write!(xml, r#" {}="{}""#, name, u8::from(!allowed))
+                    .map_err(|e| format!("XML write error: {}", e))?;
+            }
+        }
+        xml.push_str("/>");
+        Ok(())
+    }
+
+    /// Add the `printOptions`, `pageMargins`, `pageSetup` and `headerFooter`
+    /// elements that are set.
+    ///
+    /// `page_setup_attributes` are raw attributes kept from a loaded `pageSetup`.
+    fn write_page_layout(
+        &self,
+        elements: &mut Vec<(&str, String)>,
+        page_setup_attributes: &str,
+    ) -> SheetResult<()> {
+        let layout = &self.page_layout;
+        if let Some(options) = &layout.print_options {
+            let mut xml = String::from("<printOptions");
+            for (name, value) in [
+                ("horizontalCentered", options.horizontal_centered),
+                ("verticalCentered", options.vertical_centered),
+                ("headings", options.headings),
+                ("gridLines", options.grid_lines),
+            ] {
+                if value {
+                    write!(xml, r#" {}="1""#, name)
+                        .map_err(|e| format!("XML write error: {}", e))?;
+                }
+            }
+            xml.push_str("/>");
+            elements.push(("printOptions", xml));
+        }
+
+        if let Some(margins) = &layout.margins {
+            elements.push((
+                "pageMargins",
+                format!(
+                    r#"<pageMargins left="{}" right="{}" top="{}" bottom="{}" header="{}" footer="{}"/>"#,
+                    margins.left,
+                    margins.right,
+                    margins.top,
+                    margins.bottom,
+                    margins.header,
+                    margins.footer
+                ),
+            ));
+        }
+
+        if let Some(setup) = &layout.setup {
+            let mut xml = String::from("<pageSetup");
+            for (name, value) in [
+                ("paperSize", setup.paper_size),
+                ("scale", setup.scale),
+                ("firstPageNumber", setup.first_page_number),
+                ("fitToWidth", setup.fit_to_width),
+                ("fitToHeight", setup.fit_to_height),
+            ] {
+                if let Some(value) = value {
+                    write!(xml, r#" {}="{}""#, name, value)
+                        .map_err(|e| format!("XML write error: {}", e))?;
+                }
+            }
+            if let Some(orientation) = setup.orientation {
+                write!(xml, r#" orientation="{}""#, orientation.as_str())
+                    .map_err(|e| format!("XML write error: {}", e))?;
+            }
+            if setup.first_page_number.is_some() {
+                xml.push_str(r#" useFirstPageNumber="1""#);
+            }
+            xml.push_str(page_setup_attributes);
+            xml.push_str("/>");
+            elements.push(("pageSetup", xml));
+        }
+
+        if let Some(hf) = &layout.header_footer {
+            let mut xml = String::from("<headerFooter");
+            for (name, value, default) in [
+                ("differentOddEven", hf.different_odd_even, false),
+                ("differentFirst", hf.different_first, false),
+                ("scaleWithDoc", hf.scale_with_document, true),
+                ("alignWithMargins", hf.align_with_margins, true),
+            ] {
+                if value != default {
+                    write!(xml, r#" {}="{}""#, name, u8::from(value))
+                        .map_err(|e| format!("XML write error: {}", e))?;
+                }
+            }
+            xml.push('>');
+            for (name, text) in [
+                ("oddHeader", &hf.odd_header),
+                ("oddFooter", &hf.odd_footer),
+                ("evenHeader", &hf.even_header),
+                ("evenFooter", &hf.even_footer),
+                ("firstHeader", &hf.first_header),
+                ("firstFooter", &hf.first_footer),
+            ] {
+                if let Some(text) = text {
+                    write!(xml, "<{0}>{1}</{0}>", name, escape_xml(text))
+                        .map_err(|e| format!("XML write error: {}", e))?;
+                }
+            }
+            xml.push_str("</headerFooter>");
+            elements.push(("headerFooter", xml));
+        }
+
+        Ok(())
+    }
+
+    /// Write the `conditionalFormatting` elements.
+    fn write_conditional_formatting(&self, xml: &mut String) -> SheetResult<()> {
+        for (index, formatting) in self.conditional_formats.iter().enumerate() {
+            if formatting.rules.is_empty() {
+                continue;
+            }
+            write!(
+                xml,
+                r#"<conditionalFormatting sqref="{}">"#,
+                escape_xml(&formatting.range)
+            )
+            .map_err(|e| format!("XML write error: {}", e))?;
+
+            for (rule_index, rule) in formatting.rules.iter().enumerate() {
+                let dxf_id = self.conditional_format_dxfs.get(&(index, rule_index));
+                Self::write_conditional_rule(xml, &formatting.range, rule, dxf_id)?;
+            }
+
+            xml.push_str("</conditionalFormatting>");
+        }
+        Ok(())
+    }
+
+    /// Write a single `cfRule` element.
+    fn write_conditional_rule(
+        xml: &mut String,
+        range: &str,
+        rule: &ConditionalFormatRule,
+        dxf_id: Option<&usize>,
+    ) -> SheetResult<()> {
+        let rule_type = match &rule.rule_type {
+            ConditionalFormatType::CellIs { .. } => "cellIs",
+            ConditionalFormatType::Expression { .. } => "expression",
+            ConditionalFormatType::ColorScale { .. } => "colorScale",
+            ConditionalFormatType::DataBar { .. } => "dataBar",
+            ConditionalFormatType::IconSet { .. } => "iconSet",
+            ConditionalFormatType::Top10 { .. } => "top10",
+            ConditionalFormatType::DuplicateValues => "duplicateValues",
+            ConditionalFormatType::UniqueValues => "uniqueValues",
+            ConditionalFormatType::ContainsText { .. } => "containsText",
+        };
+        write!(xml, r#"<cfRule type="{}""#, rule_type)
+            .map_err(|e| format!("XML write error: {}", e))?;
+        if let Some(dxf_id) = dxf_id {
+            write!(xml, r#" dxfId="{}""#, dxf_id).map_err(|e| format!("XML write error: {}", e))?;
+        }
+        write!(xml, r#" priority="{}""#, rule.priority)
+            .map_err(|e| format!("XML write error: {}", e))?;
+        if rule.stop_if_true {
+            xml.push_str(r#" stopIfTrue="1""#);
+        }
+
+        match &rule.rule_type {
+            ConditionalFormatType::CellIs { operator, formulas } => {
+                write!(xml, r#" operator="{}">"#, operator.as_str())
+                    .map_err(|e| format!("XML write error: {}", e))?;
+                for formula in formulas {
+                    write!(xml, "<formula>{}</formula>", escape_xml(formula))
+                        .map_err(|e| format!("XML write error: {}", e))?;
+                }
+            },
+            ConditionalFormatType::Expression { formula } => {
+                write!(xml, "><formula>{}</formula>", escape_xml(formula))
+                    .map_err(|e| format!("XML write error: {}", e))?;
+            },
+            ConditionalFormatType::ColorScale { points, colors } => {
+                xml.push_str("><colorScale>");
+                for point in points {
+                    Self::write_cfvo(xml, point)?;
+                }
+                for color in colors {
+                    write!(xml, "<color {}/>", color_attribute(color))
+                        .map_err(|e| format!("XML write error: {}", e))?;
+                }
+                xml.push_str("</colorScale>");
+            },
+            ConditionalFormatType::DataBar { min, max, color } => {
+                xml.push_str("><dataBar>");
+                Self::write_cfvo(xml, min)?;
+                Self::write_cfvo(xml, max)?;
+                write!(xml, "<color {}/>", color_attribute(color))
+                    .map_err(|e| format!("XML write error: {}", e))?;
+                xml.push_str("</dataBar>");
+            },
+            ConditionalFormatType::IconSet {
+                icon_set,
+                points,
+                reverse,
+                show_value,
+            } => {
+                write!(xml, r#"><iconSet iconSet="{}""#, escape_xml(icon_set))
+                    .map_err(|e| format!("XML write error: {}", e))?;
+                if !show_value {
+                    xml.push_str(r#" showValue="0""#);
+                }
+                if *reverse {
+                    xml.push_str(r#" reverse="1""#);
+                }
+                xml.push('>');
+                for point in points {
+                    Self::write_cfvo(xml, point)?;
+                }
+                xml.push_str("</iconSet>");
+            },
+            ConditionalFormatType::Top10 {
+                rank,
+                percent,
+                bottom,
+            } => {
+                write!(xml, r#" rank="{}""#, rank)
+                    .map_err(|e| format!("XML write error: {}", e))?;
+                if *percent {
+                    xml.push_str(r#" percent="1""#);
+                }
+                if *bottom {
+                    xml.push_str(r#" bottom="1""#);
+                }
+                xml.push('>');
+            },
+            ConditionalFormatType::DuplicateValues | ConditionalFormatType::UniqueValues => {
+                xml.push('>');
+            },
+            ConditionalFormatType::ContainsText { text } => {
+                // Excel evaluates the rule through a formula on the top-left cell
+                let top_left = range
+                    .split([' ', ':'])
+                    .next()
+                    .unwrap_or_default()
+                    .replace('$', "");
+                let formula = format!(
+                    r#"NOT(ISERROR(SEARCH("{}",{})))"#,
+                    text.replace('"', "\"\""),
+                    top_left
+                );
+                write!(
+                    xml,
+                    r#" operator="containsText" text="{}"><formula>{}</formula>"#,
+                    escape_xml(text),
+                    escape_xml(&formula)
+                )
+                .map_err(|e| format!("XML write error: {}", e))?;
+            },
+        }
+
+        xml.push_str("</cfRule>");
+        Ok(())
+    }
+
+    /// Write a `cfvo` threshold.
+    fn write_cfvo(xml: &mut String, point: &ConditionalFormatValue) -> SheetResult<()> {
+        write!(xml, r#"<cfvo type="{}""#, point.value_type.as_str())
+            .map_err(|e| format!("XML write error: {}", e))?;
+        if let Some(value) = &point.value {
+            write!(xml, r#" val="{}""#, escape_xml(value))
+                .map_err(|e| format!("XML write error: {}", e))?;
+        }
+        xml.push_str("/>");
+        Ok(())
+    }
+
+    /// Write the `sheetViews` element.
+    fn write_sheet_views(&self, xml: &mut String) -> SheetResult<()> {
         xml.push_str("<sheetViews><sheetView workbookViewId=\"0\"");
 
         // Add freeze panes if configured
@@ -379,31 +1442,72 @@ impl MutableWorksheet {
         }
 
         xml.push_str("</sheetViews>");
-        xml.push_str("<sheetFormatPr defaultRowHeight=\"15\"/>\n");
+        Ok(())
+    }
 
-        // Write sheet data
-        xml.push_str("<sheetData>");
-        self.write_sheet_data(&mut xml, shared_strings, style_indices)?;
-        xml.push_str("</sheetData>");
+    /// Write column widths, hidden columns and kept column formats and
+    /// attributes, merging adjacent equal columns.
+    fn write_cols(
+        &self,
+        xml: &mut String,
+        style_indices: &HashMap<(u32, u32), usize>,
+    ) -> SheetResult<()> {
+        let mut columns: Vec<u32> = self
+            .column_widths
+            .keys()
+            .chain(self.hidden_columns.iter())
+            .chain(self.column_formats.keys())
+            .chain(self.column_attributes.keys())
+            .copied()
+            .collect();
+        if columns.is_empty() {
+            return Ok(());
+        }
+        columns.sort_unstable();
+        columns.dedup();
+
+        let props = |col: u32| {
+            (
+                self.column_widths.get(&col).map(|w| w.to_bits()),
+                self.hidden_columns.contains(&col),
+                style_indices.get(&(0, col)).copied(),
+                self.column_attributes.get(&col).map_or("", String::as_str),
+            )
+        };
 
-        // Write merged cells
-        if !self.merged_cells.is_empty() {
-            write!(xml, r#"<mergeCells count="{}">"#, self.merged_cells.len())
-                .map_err(|e| format!("XML write error: {}", e))?;
+        xml.push_str("<cols>");
+        let mut index = 0;
+        while index < columns.len() {
+            let min = columns[index];
+            let current = props(min);
+            let mut max = min;
+            while index + 1 < columns.len()
+                && columns[index + 1] == max + 1
+                && props(columns[index + 1]) == current
+            {
+                index += 1;
+                max = columns[index];
+            }
 
-            for (start_row, start_col, end_row, end_col) in &self.merged_cells {
-                let start_ref = format!("{}{}", Self::column_to_letters(*start_col), start_row);
-                let end_ref = format!("{}{}", Self::column_to_letters(*end_col), end_row);
-                write!(xml, r#"<mergeCell ref="{}:{}"/>"#, start_ref, end_ref)
+            write!(xml, r#"<col min="{}" max="{}""#, min, max)
+                .map_err(|e| format!("XML write error: {}", e))?;
+            if let Some(width) = self.column_widths.get(&min) {
+                write!(xml, r#" width="{}" customWidth="1""#, width)
                     .map_err(|e| format!("XML write error: {}", e))?;
             }
-
-            xml.push_str("</mergeCells>");
+            if current.1 {
+                xml.push_str(r#" hidden="1""#);
+            }
+            if let Some(style) = current.2 {
+                write!(xml, r#" style="{}""#, style)
+                    .map_err(|e| format!("XML write error: {}", e))?;
+            }
+            xml.push_str(current.3);
+            xml.push_str("/>");
+            index += 1;
         }
-
-        xml.push_str("</worksheet>");
-
-        Ok(xml)
+        xml.push_str("</cols>");
+        Ok(())
     }
 
     /// Get cell formats for all cells (used by workbook to build styles).
@@ -418,15 +1522,21 @@ impl MutableWorksheet {
         shared_strings: &mut MutableSharedStrings,
         style_indices: &HashMap<(u32, u32), usize>,
     ) -> SheetResult<()> {
-        if self.cells.is_empty() {
-            return Ok(());
-        }
-
-        // Group cells by row
+        // Group cells by row; rows with only a height, hidden flag, format or
+        // kept attributes are written too
         let mut rows: HashMap<u32, Vec<(u32, &CellValue)>> = HashMap::new();
         for (&(row, col), value) in &self.cells {
             rows.entry(row).or_default().push((col, value));
         }
+        for &row in self
+            .row_heights
+            .keys()
+            .chain(self.hidden_rows.iter())
+            .chain(self.row_formats.keys())
+            .chain(self.row_attributes.keys())
+        {
+            rows.entry(row).or_default();
+        }
 
         // Sort rows
         let mut row_nums: Vec<u32> = rows.keys().copied().collect();
@@ -436,14 +1546,55 @@ impl MutableWorksheet {
             let mut cells = rows[&row_num].clone();
             cells.sort_unstable_by_key(|(col, _)| *col);
 
-            write!(xml, r#"<row r="{}">"#, row_num)
+            write!(xml, r#"<row r="{}""#, row_num)
                 .map_err(|e| format!("XML write error: {}", e))?;
+            if let Some(height) = self.row_heights.get(&row_num) {
+                write!(xml, r#" ht="{}" customHeight="1""#, height)
+                    .map_err(|e| format!("XML write error: {}", e))?;
+            }
+            if self.hidden_rows.contains(&row_num) {
+                xml.push_str(r#" hidden="1""#);
+            }
+            if let Some(style) = style_indices.get(&(row_num, 0)) {
+                write!(xml, r#" s="{}" customFormat="1""#, style)
+                    .map_err(|e| format!("XML write error: {}", e))?;
+            }
+            if let Some(attributes) = self.row_attributes.get(&row_num) {
+                xml.push_str(attributes);
+            }
+            xml.push('>');
 
             for (col_num, value) in cells {
                 let cell_ref = format!("{}{}", Self::column_to_letters(col_num), row_num);
                 // Get the style index for this cell (if any)
                 let style_index = style_indices.get(&(row_num, col_num)).copied();
-                self.write_cell(xml, &cell_ref, value, shared_strings, style_index)?;
+                // Unchanged rich-text strings keep their runs
+                if let (CellValue::String(text), Some(markup)) =
+                    (value, self.rich_text.get(&(row_num, col_num)))
+                {
+                    let string_index = shared_strings.add_rich_string(text, markup);
+                    let style_attr =
+                        style_index.map_or(String::new(), |idx| format!(r#" s="{}""#, idx));
+                    write!(
+                        xml,
+                        r#"<c r="{}"{} t="s"><v>{}</v></c>"#,
+                        cell_ref, style_attr, string_index
+                    )
+                    .map_err(|e| format!("XML write error: {}", e))?;
+                    continue;
+                }
+                let formula_attributes = self
+                    .formula_attributes
+                    .get(&(row_num, col_num))
+                    .map_or("", |attrs| attrs.as_str());
+                self.write_cell(
+                    xml,
+                    &cell_ref,
+                    value,
+                    formula_attributes,
+                    shared_strings,
+                    style_index,
+                )?;
             }
 
             xml.push_str("</row>");
@@ -458,6 +1609,7 @@ impl MutableWorksheet {
         xml: &mut String,
         cell_ref: &str,
         value: &CellValue,
+        formula_attributes: &str,
         shared_strings: &mut MutableSharedStrings,
         style_index: Option<usize>,
     ) -> SheetResult<()> {
@@ -469,7 +1621,13 @@ impl MutableWorksheet {
         };
 
         match value {
-            CellValue::Empty => {},
+            CellValue::Empty => {
+                // Keep formatting on cells without a value
+                if style_index.is_some() {
+                    write!(xml, r#"<c r="{}"{}/>"#, cell_ref, style_attr)
+                        .map_err(|e| format!("XML write error: {}", e))?;
+                }
+            },
             CellValue::String(s) => {
                 let string_index = shared_strings.add_string(s);
                 write!(
@@ -480,20 +1638,12 @@ impl MutableWorksheet {
                 .map_err(|e| format!("XML write error: {}", e))?;
             },
             CellValue::Int(i) => {
-                write!(
-                    xml,
-                    r#"<c r="{}"{}>  <v>{}</v></c>"#,
-                    cell_ref, style_attr, i
-                )
-                .map_err(|e| format!("XML write error: {}", e))?;
+                write!(xml, r#"<c r="{}"{}><v>{}</v></c>"#, cell_ref, style_attr, i)
+                    .map_err(|e| format!("XML write error: {}", e))?;
             },
             CellValue::Float(f) => {
-                write!(
-                    xml,
-                    r#"<c r="{}"{}>  <v>{}</v></c>"#,
-                    cell_ref, style_attr, f
-                )
-                .map_err(|e| format!("XML write error: {}", e))?;
+                write!(xml, r#"<c r="{}"{}><v>{}</v></c>"#, cell_ref, style_attr, f)
+                    .map_err(|e| format!("XML write error: {}", e))?;
             },
             CellValue::Bool(b) => {
                 write!(
@@ -506,12 +1656,8 @@ impl MutableWorksheet {
                 .map_err(|e| format!("XML write error: {}", e))?;
             },
             CellValue::DateTime(d) => {
-                write!(
-                    xml,
-                    r#"<c r="{}"{}>  <v>{}</v></c>"#,
-                    cell_ref, style_attr, d
-                )
-                .map_err(|e| format!("XML write error: {}", e))?;
+                write!(xml, r#"<c r="{}"{}><v>{}</v></c>"#, cell_ref, style_attr, d)
+                    .map_err(|e| format!("XML write error: {}", e))?;
             },
             CellValue::Error(e) => {
                 write!(
@@ -527,22 +1673,36 @@ impl MutableWorksheet {
                 formula,
                 cached_value,
             } => {
-                xml.push_str(&format!(r#"<c r="{}"{}">"#, cell_ref, style_attr));
-                write!(xml, "<f>{}</f>", escape_xml(formula))
+                // The cell type describes the cached result
+                let type_attr = match cached_value.as_deref() {
+                    Some(CellValue::String(_)) => r#" t="str""#,
+                    Some(CellValue::Bool(_)) => r#" t="b""#,
+                    Some(CellValue::Error(_)) => r#" t="e""#,
+                    _ => "",
+                };
+                write!(xml, r#"<c r="{}"{}{}>"#, cell_ref, style_attr, type_attr)
                     .map_err(|e| format!("XML write error: {}", e))?;
 
+                // Cells sharing a formula only carry the shared index
+                if formula.is_empty() && !formula_attributes.is_empty() {
+                    write!(xml, "<f{}/>", formula_attributes)
+                        .map_err(|e| format!("XML write error: {}", e))?;
+                } else {
+                    write!(xml, "<f{}>{}</f>", formula_attributes, escape_xml(formula))
+                        .map_err(|e| format!("XML write error: {}", e))?;
+                }
+
                 if let Some(cached) = cached_value {
                     match &**cached {
-                        CellValue::String(s) => {
-                            let string_index = shared_strings.add_string(s);
-                            write!(xml, "<v>{}</v>", string_index)
+                        CellValue::String(s) | CellValue::Error(s) => {
+                            write!(xml, "<v>{}</v>", escape_xml(s))
                                 .map_err(|e| format!("XML write error: {}", e))?;
                         },
                         CellValue::Int(i) => {
                             write!(xml, "<v>{}</v>", i)
                                 .map_err(|e| format!("XML write error: {}", e))?;
                         },
-                        CellValue::Float(f) => {
+                        CellValue::Float(f) | CellValue::DateTime(f) => {
                             write!(xml, "<v>{}</v>", f)
                                 .map_err(|e| format!("XML write error: {}", e))?;
                         },
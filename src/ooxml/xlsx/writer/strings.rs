@@ -22,6 +22,10 @@ pub struct MutableSharedStrings {
     pub(crate) strings: Vec<String>,
     /// Map from string to index for fast lookup
     pub(crate) string_to_index: HashMap<String, usize>,
+    /// Raw `si` markup of rich-text strings, by index
+    pub(crate) rich_markup: HashMap<usize, String>,
+    /// Map from rich-text markup to index
+    rich_to_index: HashMap<String, usize>,
 }
 
 impl MutableSharedStrings {
@@ -30,6 +34,8 @@ impl MutableSharedStrings {
         Self {
             strings: Vec::new(),
             string_to_index: HashMap::new(),
+            rich_markup: HashMap::new(),
+            rich_to_index: HashMap::new(),
         }
     }
 
@@ -47,6 +53,21 @@ impl MutableSharedStrings {
         }
     }
 
+    /// Add a rich-text string, given its plain text and its raw `si`
+    /// markup, and return its index.
+    ///
+    /// If the same markup was already added, returns the existing index.
+    pub(crate) fn add_rich_string(&mut self, text: &str, markup: &str) -> usize {
+        if let Some(&index) = self.rich_to_index.get(markup) {
+            return index;
+        }
+        let index = self.strings.len();
+        self.strings.push(text.to_string());
+        self.rich_markup.insert(index, markup.to_string());
+        self.rich_to_index.insert(markup.to_string(), index);
+        index
+    }
+
     /// Get the number of unique strings.
     pub fn count(&self) -> usize {
         self.strings.len()
@@ -66,9 +87,12 @@ impl MutableSharedStrings {
         )
         .map_err(|e| format!("XML write error: {}", e))?;
 
-        for s in &self.strings {
-            write!(xml, "<si><t>{}</t></si>", escape_xml(s))
-                .map_err(|e| format!("XML write error: {}", e))?;
+        for (index, s) in self.strings.iter().enumerate() {
+            match self.rich_markup.get(&index) {
+                Some(markup) => xml.push_str(markup),
+                None => write!(xml, "<si><t>{}</t></si>", escape_xml(s))
+                    .map_err(|e| format!("XML write error: {}", e))?,
+            }
         }
 
         xml.push_str("</sst>");
@@ -1,14 +1,17 @@
 //! Styles.xml generator for XLSX files.
 //!
 //! This module handles the generation of the styles.xml file, which defines
-//! all the formatting information (fonts, fills, borders, number formats, and
-//! cell formats) used in an Excel workbook.
+//! all the formatting information (fonts, fills, borders, number formats,
+//! cell formats and named styles) used in an Excel workbook.
 
 use crate::ooxml::xlsx::format::{
-    CellBorder, CellBorderSide, CellFill, CellFillPatternType, CellFont, CellFormat,
+    CellAlignment, CellBorder, CellBorderSide, CellFill, CellFillPatternType, CellFont, CellFormat,
+    CellProtection, NamedStyle,
 };
 use crate::sheet::Result as SheetResult;
 use std::collections::HashMap;
+
+use super::loader::StylesSource;
 use std::fmt::Write as FmtWrite;
 
 /// Escape XML special characters.
@@ -20,6 +23,30 @@ fn escape_xml(s: &str) -> String {
         .replace('\'', "&apos;")
 }
 
+/// Attribute selecting a color given as `AARRGGBB`, `theme:N` or `indexed:N`.
+pub(super) fn color_attribute(color: &str) -> String {
+    if let Some(theme) = color.strip_prefix("theme:") {
+        format!(r#"theme="{}""#, escape_xml(theme))
+    } else if let Some(indexed) = color.strip_prefix("indexed:") {
+        format!(r#"indexed="{}""#, escape_xml(indexed))
+    } else {
+        format!(r#"rgb="{}""#, escape_xml(color.trim_start_matches('#')))
+    }
+}
+
+/// A format record (`xf`) referring to fonts, fills, borders and number formats.
+#[derive(Debug, Clone, Default)]
+struct XfRecord {
+    font_id: usize,
+    fill_id: usize,
+    border_id: usize,
+    num_fmt_id: usize,
+    /// Named style record (`cellStyleXfs` index) a cell format is based on
+    xf_id: usize,
+    alignment: Option<CellAlignment>,
+    protection: Option<CellProtection>,
+}
+
 /// Builder for generating styles.xml content.
 ///
 /// This struct collects all unique fonts, fills, borders, and cell formats,
@@ -42,10 +69,24 @@ pub struct StylesBuilder {
     number_formats: Vec<String>,
     /// Number format lookup (format string -> index)
     number_format_map: HashMap<String, usize>,
-    /// Cell formats (XF records) - index -> (font_id, fill_id, border_id, num_fmt_id)
-    cell_formats: Vec<(usize, usize, usize, usize)>,
+    /// Cell formats (cellXfs records)
+    cell_formats: Vec<XfRecord>,
     /// Cell format lookup (format hash -> index)
     cell_format_map: HashMap<u64, usize>,
+    /// Named style formats (cellStyleXfs records); record 0 is "Normal"
+    style_formats: Vec<XfRecord>,
+    /// Named styles after "Normal" as (name, built-in ID, cellStyleXfs index)
+    named_styles: Vec<(String, Option<u32>, usize)>,
+    /// Named style lookup (name -> (cellStyleXfs index, format))
+    named_style_map: HashMap<String, (usize, CellFormat)>,
+    /// Differential formats used by conditional formatting (index -> `dxf` markup)
+    dxfs: Vec<String>,
+    /// Differential format lookup (format hash -> index)
+    dxf_map: HashMap<u64, usize>,
+    /// Root element attributes kept from an existing styles.xml
+    root_attributes: Option<String>,
+    /// Raw elements after `cellStyles` kept from an existing styles.xml
+    preserved_tail: Option<String>,
 }
 
 impl StylesBuilder {
@@ -62,6 +103,13 @@ impl StylesBuilder {
             number_format_map: HashMap::new(),
             cell_formats: Vec::new(),
             cell_format_map: HashMap::new(),
+            style_formats: vec![XfRecord::default()],
+            named_styles: Vec::new(),
+            named_style_map: HashMap::new(),
+            dxfs: Vec::new(),
+            dxf_map: HashMap::new(),
+            root_attributes: None,
+            preserved_tail: None,
         };
 
         // Add default font (required by Excel)
@@ -72,34 +120,18 @@ impl StylesBuilder {
 
         // Add default fills (required by Excel - must be first two)
         // Fill 0: no fill
-        builder.fills.push(CellFill {
-            pattern_type: CellFillPatternType::None,
-            fg_color: None,
-            bg_color: None,
-        });
-        builder.fill_map.insert(
-            Self::hash_fill(&CellFill {
-                pattern_type: CellFillPatternType::None,
-                fg_color: None,
-                bg_color: None,
-            }),
-            0,
-        );
+        builder.fills.push(CellFill::default());
+        builder
+            .fill_map
+            .insert(Self::hash_fill(&CellFill::default()), 0);
 
         // Fill 1: gray125 (Excel default)
-        builder.fills.push(CellFill {
+        let gray125 = CellFill {
             pattern_type: CellFillPatternType::Gray125,
-            fg_color: None,
-            bg_color: None,
-        });
-        builder.fill_map.insert(
-            Self::hash_fill(&CellFill {
-                pattern_type: CellFillPatternType::Gray125,
-                fg_color: None,
-                bg_color: None,
-            }),
-            1,
-        );
+            ..Default::default()
+        };
+        builder.fill_map.insert(Self::hash_fill(&gray125), 1);
+        builder.fills.push(gray125);
 
         // Add default border (required by Excel)
         builder.borders.push(CellBorder::default());
@@ -108,14 +140,38 @@ impl StylesBuilder {
             .insert(Self::hash_border(&CellBorder::default()), 0);
 
         // Add default cell format (style index 0)
-        builder.cell_formats.push((0, 0, 0, 0)); // font=0, fill=0, border=0, numFmt=0
+        builder.cell_formats.push(XfRecord::default()); // font=0, fill=0, border=0, numFmt=0
 
         builder
     }
 
+    /// Keep the parts of an existing styles.xml that the builder does not model.
+    ///
+    /// The original default font stays font 0, existing differential formats
+    /// keep their indices, and table styles, color overrides and extensions
+    /// are written back as-is.
+    pub(crate) fn preserve(&mut self, source: &StylesSource) {
+        if let Some(font) = &source.default_font {
+            self.font_map.remove(&Self::hash_font(&self.fonts[0]));
+            self.fonts[0] = font.clone();
+            self.font_map.insert(Self::hash_font(font), 0);
+        }
+        for (markup, format) in &source.dxfs {
+            self.dxf_map
+                .entry(Self::hash_cell_format(format))
+                .or_insert(self.dxfs.len());
+            self.dxfs.push(markup.clone());
+        }
+        self.root_attributes =
+            Some(source.root_attributes.clone()).filter(|attributes| !attributes.trim().is_empty());
+        self.preserved_tail = Some(source.tail.clone());
+    }
+
     /// Add a cell format and return its style index.
     ///
-    /// If the format has already been added, returns the existing index.
+    /// A format naming a style added with [`add_named_style`](Self::add_named_style)
+    /// is based on that style. If the format has already been added, returns
+    /// the existing index.
     pub fn add_cell_format(&mut self, format: &CellFormat) -> usize {
         let format_hash = Self::hash_cell_format(format);
 
@@ -124,43 +180,110 @@ impl StylesBuilder {
             return index;
         }
 
-        // Add font if present
-        let font_id = if let Some(ref font) = format.font {
-            self.add_font(font)
-        } else {
-            0 // Default font
-        };
-
-        // Add fill if present
-        let fill_id = if let Some(ref fill) = format.fill {
-            self.add_fill(fill)
-        } else {
-            0 // Default fill
-        };
-
-        // Add border if present
-        let border_id = if let Some(ref border) = format.border {
-            self.add_border(border)
-        } else {
-            0 // Default border
+        let named = format
+            .style_name
+            .as_ref()
+            .and_then(|name| self.named_style_map.get(name))
+            .map(|(xf_id, style)| (*xf_id, format.based_on(style)));
+        let record = match named {
+            Some((xf_id, format)) => XfRecord {
+                xf_id,
+                ..self.add_xf_components(&format)
+            },
+            None => self.add_xf_components(format),
         };
-
-        // Add number format if present
-        let num_fmt_id = if let Some(ref num_fmt) = format.number_format {
-            self.add_number_format(num_fmt)
-        } else {
-            0 // General format
-        };
-
         // Add the cell format
         let index = self.cell_formats.len();
-        self.cell_formats
-            .push((font_id, fill_id, border_id, num_fmt_id));
+        self.cell_formats.push(record);
         self.cell_format_map.insert(format_hash, index);
 
         index
     }
 
+    /// Add a named style and return its index among the style formats.
+    ///
+    /// "Normal" (built-in style 0) always uses the default font and formats;
+    /// a style whose name was already added keeps its first definition.
+    pub fn add_named_style(&mut self, style: &NamedStyle) -> usize {
+        if style.builtin_id == Some(0) || style.name == "Normal" {
+            return 0;
+        }
+        if let Some((index, _)) = self.named_style_map.get(&style.name) {
+            return *index;
+        }
+
+        let record = self.add_xf_components(&style.format);
+        let index = self.style_formats.len();
+        self.style_formats.push(record);
+        self.named_styles
+            .push((style.name.clone(), style.builtin_id, index));
+        self.named_style_map
+            .insert(style.name.clone(), (index, style.format.clone()));
+        index
+    }
+
+    /// Add the font, fill, border and number format of a format.
+    fn add_xf_components(&mut self, format: &CellFormat) -> XfRecord {
+        XfRecord {
+            font_id: format.font.as_ref().map_or(0, |font| self.add_font(font)),
+            fill_id: format.fill.as_ref().map_or(0, |fill| self.add_fill(fill)),
+            border_id: format
+                .border
+                .as_ref()
+                .map_or(0, |border| self.add_border(border)),
+            num_fmt_id: format
+                .number_format
+                .as_ref()
+                .map_or(0, |num_fmt| self.add_number_format(num_fmt)),
+            xf_id: 0,
+            alignment: format.alignment.clone(),
+            protection: format.protection,
+        }
+    }
+
+    /// Add a differential format for conditional formatting and return its `dxfId`.
+    ///
+    /// If the format has already been added, returns the existing index.
+    pub fn add_differential_format(&mut self, format: &CellFormat) -> SheetResult<usize> {
+        let format_hash = Self::hash_cell_format(format);
+        if let Some(&index) = self.dxf_map.get(&format_hash) {
+            return Ok(index);
+        }
+
+        let mut xml = String::from("<dxf>");
+        if let Some(ref font) = format.font {
+            self.write_dxf_font(&mut xml, font)?;
+        }
+        if let Some(ref num_fmt) = format.number_format {
+            let num_fmt_id = self.add_number_format(num_fmt);
+            write!(
+                xml,
+                r#"<numFmt numFmtId="{}" formatCode="{}"/>"#,
+                num_fmt_id,
+                escape_xml(num_fmt)
+            )
+            .map_err(|e| format!("XML write error: {}", e))?;
+        }
+        if let Some(ref fill) = format.fill {
+            self.write_dxf_fill(&mut xml, fill)?;
+        }
+        if let Some(ref alignment) = format.alignment {
+            Self::write_alignment(&mut xml, alignment)?;
+        }
+        if let Some(ref protection) = format.protection {
+            Self::write_protection(&mut xml, protection);
+        }
+        if let Some(ref border) = format.border {
+            self.write_border(&mut xml, border)?;
+        }
+        xml.push_str("</dxf>");
+
+        let index = self.dxfs.len();
+        self.dxfs.push(xml);
+        self.dxf_map.insert(format_hash, index);
+        Ok(index)
+    }
+
     /// Add a font and return its index.
     fn add_font(&mut self, font: &CellFont) -> usize {
         let hash = Self::hash_font(font);
@@ -218,9 +341,13 @@ impl StylesBuilder {
         let mut xml = String::with_capacity(4096);
 
         xml.push_str(r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>"#);
-        xml.push_str(
-            r#"<styleSheet xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main">"#,
-        );
+        match &self.root_attributes {
+            Some(attributes) => write!(xml, "<styleSheet{}>", attributes)
+                .map_err(|e| format!("XML write error: {}", e))?,
+            None => xml.push_str(
+                r#"<styleSheet xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main">"#,
+            ),
+        }
 
         // Write number formats (if any custom ones exist)
         if !self.number_formats.is_empty() {
@@ -271,46 +398,163 @@ impl StylesBuilder {
 
         xml.push_str("</borders>");
 
-        // Write cell style XFs (required, even if empty)
-        xml.push_str(r#"<cellStyleXfs count="1"><xf numFmtId="0" fontId="0" fillId="0" borderId="0"/></cellStyleXfs>"#);
+        // Write named style XFs (required, "Normal" comes first)
+        write!(
+            xml,
+            r#"<cellStyleXfs count="{}">"#,
+            self.style_formats.len()
+        )
+        .map_err(|e| format!("XML write error: {}", e))?;
+        for record in &self.style_formats {
+            Self::write_xf(&mut xml, record, false)?;
+        }
+        xml.push_str("</cellStyleXfs>");
 
         // Write cell XFs (the actual cell formats)
         write!(xml, r#"<cellXfs count="{}">"#, self.cell_formats.len())
             .map_err(|e| format!("XML write error: {}", e))?;
+        for record in &self.cell_formats {
+            Self::write_xf(&mut xml, record, true)?;
+        }
+        xml.push_str("</cellXfs>");
 
-        for (font_id, fill_id, border_id, num_fmt_id) in &self.cell_formats {
+        // Write cell styles (required, even if minimal)
+        write!(
+            xml,
+            r#"<cellStyles count="{}">"#,
+            self.named_styles.len() + 1
+        )
+        .map_err(|e| format!("XML write error: {}", e))?;
+        xml.push_str(r#"<cellStyle name="Normal" xfId="0" builtinId="0"/>"#);
+        for (name, builtin_id, xf_id) in &self.named_styles {
             write!(
                 xml,
-                r#"<xf numFmtId="{}" fontId="{}" fillId="{}" borderId="{}""#,
-                num_fmt_id, font_id, fill_id, border_id
+                r#"<cellStyle name="{}" xfId="{}""#,
+                escape_xml(name),
+                xf_id
             )
             .map_err(|e| format!("XML write error: {}", e))?;
+            if let Some(builtin_id) = builtin_id {
+                write!(xml, r#" builtinId="{}""#, builtin_id)
+                    .map_err(|e| format!("XML write error: {}", e))?;
+            }
+            xml.push_str("/>");
+        }
+        xml.push_str("</cellStyles>");
+
+        // Write differential formats (conditional formatting)
+        if !self.dxfs.is_empty() {
+            write!(xml, r#"<dxfs count="{}">"#, self.dxfs.len())
+                .map_err(|e| format!("XML write error: {}", e))?;
+            for dxf in &self.dxfs {
+                xml.push_str(dxf);
+            }
+            xml.push_str("</dxfs>");
+        }
+
+        if let Some(tail) = &self.preserved_tail {
+            xml.push_str(tail);
+        }
+
+        xml.push_str("</styleSheet>");
+
+        Ok(xml)
+    }
+
+    /// Write an `xf` record.
+    ///
+    /// Cell formats (`cell` is true) refer to their named style and flag the
+    /// properties they apply.
+    fn write_xf(xml: &mut String, record: &XfRecord, cell: bool) -> SheetResult<()> {
+        write!(
+            xml,
+            r#"<xf numFmtId="{}" fontId="{}" fillId="{}" borderId="{}""#,
+            record.num_fmt_id, record.font_id, record.fill_id, record.border_id
+        )
+        .map_err(|e| format!("XML write error: {}", e))?;
+
+        if cell {
+            if record.xf_id != 0 {
+                write!(xml, r#" xfId="{}""#, record.xf_id)
+                    .map_err(|e| format!("XML write error: {}", e))?;
+            }
 
             // Add applyXXX attributes if non-default
-            if *font_id != 0 {
+            if record.font_id != 0 {
                 xml.push_str(r#" applyFont="1""#);
             }
-            if *fill_id != 0 {
+            if record.fill_id != 0 {
                 xml.push_str(r#" applyFill="1""#);
             }
-            if *border_id != 0 {
+            if record.border_id != 0 {
                 xml.push_str(r#" applyBorder="1""#);
             }
-            if *num_fmt_id != 0 {
+            if record.num_fmt_id != 0 {
                 xml.push_str(r#" applyNumberFormat="1""#);
             }
+            if record.alignment.is_some() {
+                xml.push_str(r#" applyAlignment="1""#);
+            }
+            if record.protection.is_some() {
+                xml.push_str(r#" applyProtection="1""#);
+            }
+        }
 
+        if record.alignment.is_none() && record.protection.is_none() {
             xml.push_str("/>");
+            return Ok(());
         }
 
-        xml.push_str("</cellXfs>");
-
-        // Write cell styles (required, even if minimal)
-        xml.push_str(r#"<cellStyles count="1"><cellStyle name="Normal" xfId="0" builtinId="0"/></cellStyles>"#);
+        xml.push('>');
+        if let Some(alignment) = &record.alignment {
+            Self::write_alignment(xml, alignment)?;
+        }
+        if let Some(protection) = &record.protection {
+            Self::write_protection(xml, protection);
+        }
+        xml.push_str("</xf>");
+        Ok(())
+    }
 
-        xml.push_str("</styleSheet>");
+    /// Write an `alignment` element.
+    fn write_alignment(xml: &mut String, alignment: &CellAlignment) -> SheetResult<()> {
+        xml.push_str("<alignment");
+        if let Some(horizontal) = alignment.horizontal {
+            write!(xml, r#" horizontal="{}""#, horizontal.as_str())
+                .map_err(|e| format!("XML write error: {}", e))?;
+        }
+        if let Some(vertical) = alignment.vertical {
+            write!(xml, r#" vertical="{}""#, vertical.as_str())
+                .map_err(|e| format!("XML write error: {}", e))?;
+        }
+        if let Some(rotation) = alignment.text_rotation {
+            write!(xml, r#" textRotation="{}""#, rotation)
+                .map_err(|e| format!("XML write error: {}", e))?;
+        }
+        if alignment.wrap_text {
+            xml.push_str(r#" wrapText="1""#);
+        }
+        if let Some(indent) = alignment.indent {
+            write!(xml, r#" indent="{}""#, indent)
+                .map_err(|e| format!("XML write error: {}", e))?;
+        }
+        if alignment.shrink_to_fit {
+            xml.push_str(r#" shrinkToFit="1""#);
+        }
+        xml.push_str("/>");
+        Ok(())
+    }
 
-        Ok(xml)
+    /// Write a `protection` element.
+    fn write_protection(xml: &mut String, protection: &CellProtection) {
+        xml.push_str("<protection");
+        if !protection.locked {
+            xml.push_str(r#" locked="0""#);
+        }
+        if protection.hidden {
+            xml.push_str(r#" hidden="1""#);
+        }
+        xml.push_str("/>");
     }
 
     /// Write a font element to XML.
@@ -333,7 +577,7 @@ impl StylesBuilder {
         }
 
         if let Some(ref color) = font.color {
-            write!(xml, r#"<color rgb="{}"/>"#, escape_xml(color))
+            write!(xml, "<color {}/>", color_attribute(color))
                 .map_err(|e| format!("XML write error: {}", e))?;
         }
 
@@ -349,10 +593,76 @@ impl StylesBuilder {
         Ok(())
     }
 
+    /// Write the font of a differential format, leaving unset properties out.
+    fn write_dxf_font(&self, xml: &mut String, font: &CellFont) -> SheetResult<()> {
+        xml.push_str("<font>");
+        if font.bold {
+            xml.push_str("<b/>");
+        }
+        if font.italic {
+            xml.push_str("<i/>");
+        }
+        if font.underline {
+            xml.push_str("<u/>");
+        }
+        if let Some(ref color) = font.color {
+            write!(xml, "<color {}/>", color_attribute(color))
+                .map_err(|e| format!("XML write error: {}", e))?;
+        }
+        if let Some(ref size) = font.size {
+            write!(xml, r#"<sz val="{}"/>"#, size)
+                .map_err(|e| format!("XML write error: {}", e))?;
+        }
+        if let Some(ref name) = font.name {
+            write!(xml, r#"<name val="{}"/>"#, escape_xml(name))
+                .map_err(|e| format!("XML write error: {}", e))?;
+        }
+        xml.push_str("</font>");
+        Ok(())
+    }
+
+    /// Write the fill of a differential format.
+    ///
+    /// A solid fill in a `dxf` takes its color from `bgColor`.
+    fn write_dxf_fill(&self, xml: &mut String, fill: &CellFill) -> SheetResult<()> {
+        if !matches!(fill.pattern_type, CellFillPatternType::Solid) {
+            return self.write_fill(xml, fill);
+        }
+        xml.push_str("<fill><patternFill>");
+        if let Some(color) = fill.fg_color.as_ref().or(fill.bg_color.as_ref()) {
+            write!(xml, "<bgColor {}/>", color_attribute(color))
+                .map_err(|e| format!("XML write error: {}", e))?;
+        }
+        xml.push_str("</patternFill></fill>");
+        Ok(())
+    }
+
     /// Write a fill element to XML.
     fn write_fill(&self, xml: &mut String, fill: &CellFill) -> SheetResult<()> {
         xml.push_str("<fill>");
 
+        if let Some(gradient) = &fill.gradient {
+            xml.push_str("<gradientFill");
+            if gradient.path {
+                xml.push_str(r#" type="path" left="0.5" right="0.5" top="0.5" bottom="0.5""#);
+            } else if gradient.degree != 0.0 {
+                write!(xml, r#" degree="{}""#, gradient.degree)
+                    .map_err(|e| format!("XML write error: {}", e))?;
+            }
+            xml.push('>');
+            for (position, color) in &gradient.stops {
+                write!(
+                    xml,
+                    r#"<stop position="{}"><color {}/></stop>"#,
+                    position,
+                    color_attribute(color)
+                )
+                .map_err(|e| format!("XML write error: {}", e))?;
+            }
+            xml.push_str("</gradientFill></fill>");
+            return Ok(());
+        }
+
         write!(
             xml,
             r#"<patternFill patternType="{}">"#,
@@ -361,12 +671,12 @@ impl StylesBuilder {
         .map_err(|e| format!("XML write error: {}", e))?;
 
         if let Some(ref fg_color) = fill.fg_color {
-            write!(xml, r#"<fgColor rgb="{}"/>"#, escape_xml(fg_color))
+            write!(xml, "<fgColor {}/>", color_attribute(fg_color))
                 .map_err(|e| format!("XML write error: {}", e))?;
         }
 
         if let Some(ref bg_color) = fill.bg_color {
-            write!(xml, r#"<bgColor rgb="{}"/>"#, escape_xml(bg_color))
+            write!(xml, "<bgColor {}/>", color_attribute(bg_color))
                 .map_err(|e| format!("XML write error: {}", e))?;
         }
 
@@ -376,7 +686,14 @@ impl StylesBuilder {
 
     /// Write a border element to XML.
     fn write_border(&self, xml: &mut String, border: &CellBorder) -> SheetResult<()> {
-        xml.push_str("<border>");
+        xml.push_str("<border");
+        if border.diagonal_up {
+            xml.push_str(r#" diagonalUp="1""#);
+        }
+        if border.diagonal_down {
+            xml.push_str(r#" diagonalDown="1""#);
+        }
+        xml.push('>');
 
         self.write_border_side(xml, "left", border.left.as_ref())?;
         self.write_border_side(xml, "right", border.right.as_ref())?;
@@ -400,7 +717,7 @@ impl StylesBuilder {
                 .map_err(|e| format!("XML write error: {}", e))?;
 
             if let Some(ref color) = bs.color {
-                write!(xml, r#"<color rgb="{}"/>"#, escape_xml(color))
+                write!(xml, "<color {}/>", color_attribute(color))
                     .map_err(|e| format!("XML write error: {}", e))?;
             }
 
@@ -447,13 +764,21 @@ impl StylesBuilder {
         if let Some(ref bg) = fill.bg_color {
             bg.hash(&mut hasher);
         }
+        if let Some(ref gradient) = fill.gradient {
+            gradient.path.hash(&mut hasher);
+            gradient.degree.to_bits().hash(&mut hasher);
+            for (position, color) in &gradient.stops {
+                position.to_bits().hash(&mut hasher);
+                color.hash(&mut hasher);
+            }
+        }
         hasher.finish()
     }
 
     /// Hash a border for deduplication.
     fn hash_border(border: &CellBorder) -> u64 {
         use std::collections::hash_map::DefaultHasher;
-        use std::hash::Hasher;
+        use std::hash::{Hash, Hasher};
 
         let mut hasher = DefaultHasher::new();
         Self::hash_border_side(&border.left, &mut hasher);
@@ -461,6 +786,8 @@ impl StylesBuilder {
         Self::hash_border_side(&border.top, &mut hasher);
         Self::hash_border_side(&border.bottom, &mut hasher);
         Self::hash_border_side(&border.diagonal, &mut hasher);
+        border.diagonal_up.hash(&mut hasher);
+        border.diagonal_down.hash(&mut hasher);
         hasher.finish()
     }
 
@@ -494,6 +821,9 @@ impl StylesBuilder {
         if let Some(ref num_fmt) = format.number_format {
             num_fmt.hash(&mut hasher);
         }
+        format.alignment.hash(&mut hasher);
+        format.protection.hash(&mut hasher);
+        format.style_name.hash(&mut hasher);
         hasher.finish()
     }
 }
@@ -552,6 +882,7 @@ mod tests {
                 pattern_type: CellFillPatternType::Solid,
                 fg_color: Some("FFFF0000".to_string()),
                 bg_color: None,
+                gradient: None,
             }),
             ..Default::default()
         };
@@ -565,4 +896,97 @@ mod tests {
         assert!(xml.contains("<borders"));
         assert!(xml.contains("<cellXfs"));
     }
+
+    #[test]
+    fn test_add_differential_format() {
+        let mut builder = StylesBuilder::new();
+        let format = CellFormat {
+            font: Some(CellFont {
+                bold: true,
+                color: Some("FF9C0006".to_string()),
+                ..Default::default()
+            }),
+            fill: Some(CellFill {
+                pattern_type: CellFillPatternType::Solid,
+                fg_color: Some("FFFFC7CE".to_string()),
+                bg_color: None,
+                gradient: None,
+            }),
+            number_format: Some("0.0%".to_string()),
+            ..Default::default()
+        };
+
+        assert_eq!(builder.add_differential_format(&format).unwrap(), 0);
+        assert_eq!(builder.add_differential_format(&format).unwrap(), 0);
+        assert_eq!(
+            builder
+                .add_differential_format(&CellFormat::default())
+                .unwrap(),
+            1
+        );
+
+        let xml = builder.to_xml().unwrap();
+        assert!(xml.contains(r#"<dxfs count="2"><dxf><font><b/><color rgb="FF9C0006"/></font><numFmt numFmtId="164" formatCode="0.0%"/><fill><patternFill><bgColor rgb="FFFFC7CE"/></patternFill></fill></dxf><dxf></dxf></dxfs>"#));
+        assert!(xml.find("<cellStyles").unwrap() < xml.find("<dxfs").unwrap());
+    }
+
+    #[test]
+    fn test_alignment_protection_and_named_styles() {
+        use crate::ooxml::xlsx::format::{CellGradientFill, HorizontalAlignment};
+
+        let mut builder = StylesBuilder::new();
+        let heading = NamedStyle::new(
+            "Heading",
+            CellFormat {
+                font: Some(CellFont {
+                    bold: true,
+                    color: Some("theme:4".to_string()),
+                    ..Default::default()
+                }),
+                ..Default::default()
+            },
+        );
+        assert_eq!(builder.add_named_style(&heading), 1);
+        assert_eq!(builder.add_named_style(&heading), 1);
+
+        let index = builder.add_cell_format(&CellFormat {
+            alignment: Some(CellAlignment {
+                horizontal: Some(HorizontalAlignment::Center),
+                wrap_text: true,
+                text_rotation: Some(45),
+                ..Default::default()
+            }),
+            protection: Some(CellProtection {
+                locked: false,
+                hidden: true,
+            }),
+            fill: Some(CellFill::gradient(CellGradientFill {
+                degree: 90.0,
+                stops: vec![
+                    (0.0, "FFFFFFFF".to_string()),
+                    (1.0, "indexed:12".to_string()),
+                ],
+                ..Default::default()
+            })),
+            border: Some(CellBorder {
+                diagonal: Some(CellBorderSide {
+                    style: crate::ooxml::xlsx::format::CellBorderLineStyle::Thin,
+                    color: None,
+                }),
+                diagonal_down: true,
+                ..Default::default()
+            }),
+            style_name: Some("Heading".to_string()),
+            ..Default::default()
+        });
+        assert_eq!(index, 1);
+
+        let xml = builder.to_xml().unwrap();
+        assert!(xml.contains(r#"<font><b/><color theme="4"/><name val="Calibri"/></font>"#));
+        assert!(xml.contains(r#"<gradientFill degree="90"><stop position="0"><color rgb="FFFFFFFF"/></stop><stop position="1"><color indexed="12"/></stop></gradientFill>"#));
+        assert!(xml.contains(r#"<border diagonalDown="1">"#));
+        assert!(xml.contains(r#"<cellStyleXfs count="2">"#));
+        assert!(xml.contains(r#"<cellStyle name="Heading" xfId="1"/>"#));
+        assert!(xml.contains(r#"xfId="1" applyFont="1" applyFill="1" applyBorder="1" applyAlignment="1" applyProtection="1"><alignment horizontal="center" textRotation="45" wrapText="1"/><protection locked="0" hidden="1"/></xf>"#));
+    }
 }
@@ -0,0 +1,212 @@
+//! Table parts and `autoFilter` elements.
+//!
+//! Each table added with [`MutableWorksheet::add_table`] becomes a
+//! `xl/tables/tableN.xml` part listed in the sheet's `tableParts`.
+//!
+//! [`MutableWorksheet::add_table`]: super::MutableWorksheet::add_table
+
+use std::fmt::Write as FmtWrite;
+
+use quick_xml::Reader;
+use quick_xml::events::Event;
+
+use crate::ooxml::xlsx::table::{AutoFilter, ColumnFilter, Table, TotalsFunction};
+use crate::sheet::Result as SheetResult;
+
+use super::sheet::escape_xml;
+
+/// Generate the XML of a table part; `id` must be unique in the workbook.
+pub(crate) fn table_xml(table: &Table, id: u32) -> SheetResult<String> {
+    let mut xml = String::with_capacity(1024);
+    xml.push_str(r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>"#);
+    write!(
+        xml,
+        r#"<table xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main" id="{}" name="{}" displayName="{}" ref="{}""#,
+        id,
+        escape_xml(&table.name),
+        escape_xml(&table.name),
+        escape_xml(&table.range)
+    )
+    .map_err(|e| format!("XML write error: {}", e))?;
+    if !table.header_row {
+        xml.push_str(r#" headerRowCount="0""#);
+    }
+    if table.totals_row {
+        xml.push_str(r#" totalsRowCount="1""#);
+    } else {
+        xml.push_str(r#" totalsRowShown="0""#);
+    }
+    xml.push('>');
+
+    // A table without a header row has no filter buttons
+    if let (Some(filter), true) = (&table.auto_filter, table.header_row) {
+        let range = table.filter_range().unwrap_or_else(|| table.range.clone());
+        write_auto_filter(&mut xml, &range, filter)?;
+    }
+
+    write!(xml, r#"<tableColumns count="{}">"#, table.columns.len())
+        .map_err(|e| format!("XML write error: {}", e))?;
+    for (index, column) in table.columns.iter().enumerate() {
+        write!(
+            xml,
+            r#"<tableColumn id="{}" name="{}""#,
+            index + 1,
+            escape_xml(&column.name)
+        )
+        .map_err(|e| format!("XML write error: {}", e))?;
+        if let Some(function) = &column.totals_function {
+            write!(xml, r#" totalsRowFunction="{}""#, function.as_str())
+                .map_err(|e| format!("XML write error: {}", e))?;
+        } else if let Some(label) = &column.totals_label {
+            write!(xml, r#" totalsRowLabel="{}""#, escape_xml(label))
+                .map_err(|e| format!("XML write error: {}", e))?;
+        }
+
+        let custom = match &column.totals_function {
+            Some(TotalsFunction::Custom(formula)) => Some(formula),
+            _ => None,
+        };
+        if column.calculated_formula.is_none() && custom.is_none() {
+            xml.push_str("/>");
+            continue;
+        }
+        xml.push('>');
+        if let Some(formula) = &column.calculated_formula {
+            write!(
+                xml,
+                "<calculatedColumnFormula>{}</calculatedColumnFormula>",
+                escape_xml(formula)
+            )
+            .map_err(|e| format!("XML write error: {}", e))?;
+        }
+        if let Some(formula) = custom {
+            write!(
+                xml,
+                "<totalsRowFormula>{}</totalsRowFormula>",
+                escape_xml(formula)
+            )
+            .map_err(|e| format!("XML write error: {}", e))?;
+        }
+        xml.push_str("</tableColumn>");
+    }
+    xml.push_str("</tableColumns>");
+
+    if let Some(style) = &table.style {
+        write!(
+            xml,
+            r#"<tableStyleInfo name="{}" showFirstColumn="{}" showLastColumn="{}" showRowStripes="{}" showColumnStripes="{}"/>"#,
+            escape_xml(&style.name),
+            u8::from(style.show_first_column),
+            u8::from(style.show_last_column),
+            u8::from(style.show_row_stripes),
+            u8::from(style.show_column_stripes)
+        )
+        .map_err(|e| format!("XML write error: {}", e))?;
+    }
+
+    xml.push_str("</table>");
+    Ok(xml)
+}
+
+/// The `id` of a table part, which is unique in the workbook.
+pub(crate) fn table_id(xml: &str) -> SheetResult<Option<u32>> {
+    let mut reader = Reader::from_str(xml);
+    loop {
+        match reader.read_event() {
+            Ok(Event::Start(e)) | Ok(Event::Empty(e)) if e.local_name().as_ref() == b"table" => {
+                return Ok(e
+                    .attributes()
+                    .flatten()
+                    .find(|attr| attr.key.as_ref() == b"id")
+                    .and_then(|attr| String::from_utf8_lossy(&attr.value).parse().ok()));
+            },
+            Ok(Event::Eof) => return Ok(None),
+            Err(e) => return Err(format!("XML parse error: {}", e).into()),
+            _ => {},
+        }
+    }
+}
+
+/// Write an `autoFilter` element over `range` with the filter's criteria.
+pub(crate) fn write_auto_filter(
+    xml: &mut String,
+    range: &str,
+    filter: &AutoFilter,
+) -> SheetResult<()> {
+    write!(xml, r#"<autoFilter ref="{}""#, escape_xml(range))
+        .map_err(|e| format!("XML write error: {}", e))?;
+    if filter.columns.is_empty() {
+        xml.push_str("/>");
+        return Ok(());
+    }
+    xml.push('>');
+
+    let mut columns: Vec<_> = filter.columns.iter().collect();
+    columns.sort_by_key(|column| column.column);
+    for column in columns {
+        write!(xml, r#"<filterColumn colId="{}">"#, column.column)
+            .map_err(|e| format!("XML write error: {}", e))?;
+        match &column.filter {
+            ColumnFilter::Values { values, blank } => {
+                xml.push_str("<filters");
+                if *blank {
+                    xml.push_str(r#" blank="1""#);
+                }
+                if values.is_empty() {
+                    xml.push_str("/>");
+                } else {
+                    xml.push('>');
+                    for value in values {
+                        write!(xml, r#"<filter val="{}"/>"#, escape_xml(value))
+                            .map_err(|e| format!("XML write error: {}", e))?;
+                    }
+                    xml.push_str("</filters>");
+                }
+            },
+            ColumnFilter::Custom { and, conditions } => {
+                xml.push_str("<customFilters");
+                if *and {
+                    xml.push_str(r#" and="1""#);
+                }
+                xml.push('>');
+                for condition in conditions {
+                    write!(
+                        xml,
+                        r#"<customFilter operator="{}" val="{}"/>"#,
+                        condition.operator.as_str(),
+                        escape_xml(&condition.value)
+                    )
+                    .map_err(|e| format!("XML write error: {}", e))?;
+                }
+                xml.push_str("</customFilters>");
+            },
+            ColumnFilter::Top10 {
+                top,
+                percent,
+                value,
+            } => {
+                xml.push_str("<top10");
+                if !*top {
+                    xml.push_str(r#" top="0""#);
+                }
+                if *percent {
+                    xml.push_str(r#" percent="1""#);
+                }
+                write!(xml, r#" val="{}"/>"#, value)
+                    .map_err(|e| format!("XML write error: {}", e))?;
+            },
+            ColumnFilter::Dynamic { filter_type } => {
+                write!(
+                    xml,
+                    r#"<dynamicFilter type="{}"/>"#,
+                    escape_xml(filter_type)
+                )
+                .map_err(|e| format!("XML write error: {}", e))?;
+            },
+        }
+        xml.push_str("</filterColumn>");
+    }
+
+    xml.push_str("</autoFilter>");
+    Ok(())
+}
@@ -1,13 +1,21 @@
 /// Workbook data structure for XLSX.
+use crate::ooxml::common::xml::split_children;
 use crate::sheet::Result as SheetResult;
 use std::collections::HashMap;
 use std::fmt::Write as FmtWrite;
 
-use super::sheet::{MutableWorksheet, NamedRange};
+use super::super::page_setup::{PRINT_AREA, PRINT_TITLES};
+use super::super::protection::WorkbookProtection;
+use super::chart::quote_sheet_name;
+use super::loader::StylesSource;
+use super::sheet::{MutableWorksheet, NamedRange, NamedStyle};
 use super::strings::MutableSharedStrings;
 use super::styles::StylesBuilder;
 
 /// Type alias for cell position to style index mapping.
+///
+/// Row formats are keyed by `(row, 0)` and column formats by `(0, col)`,
+/// since rows and columns are numbered from 1.
 type CellStyleMap = HashMap<(u32, u32), usize>;
 
 /// Escape XML special characters.
@@ -30,8 +38,18 @@ pub struct MutableWorkbookData {
     pub shared_strings: MutableSharedStrings,
     /// Named ranges
     pub named_ranges: Vec<NamedRange>,
+    /// Named cell styles
+    pub named_styles: Vec<NamedStyle>,
+    /// Workbook structure and window protection
+    pub protection: Option<WorkbookProtection>,
     /// Whether the workbook has been modified
     pub modified: bool,
+    /// Original workbook.xml of a loaded workbook
+    pub(crate) source_xml: Option<String>,
+    /// Parts of the original styles.xml kept when styles are rebuilt
+    pub(crate) styles_source: Option<StylesSource>,
+    /// Raw `si` markup of the original shared strings with rich text, by index
+    pub(crate) rich_strings: HashMap<usize, String>,
 }
 
 impl MutableWorkbookData {
@@ -41,7 +59,12 @@ impl MutableWorkbookData {
             worksheets: Vec::new(),
             shared_strings: MutableSharedStrings::new(),
             named_ranges: Vec::new(),
+            named_styles: Vec::new(),
+            protection: None,
             modified: false,
+            source_xml: None,
+            styles_source: None,
+            rich_strings: HashMap::new(),
         };
 
         // Add a default worksheet
@@ -52,7 +75,13 @@ impl MutableWorkbookData {
 
     /// Add a new worksheet.
     pub fn add_worksheet(&mut self, name: String) -> &mut MutableWorksheet {
-        let sheet_id = (self.worksheets.len() + 1) as u32;
+        let sheet_id = self
+            .worksheets
+            .iter()
+            .map(|ws| ws.sheet_id())
+            .max()
+            .unwrap_or(0)
+            + 1;
         let worksheet = MutableWorksheet::new(name, sheet_id);
         self.worksheets.push(worksheet);
         self.modified = true;
@@ -124,6 +153,33 @@ impl MutableWorkbookData {
         &self.named_ranges
     }
 
+    /// Add a named cell style, replacing any style with the same name.
+    ///
+    /// Cells use the style through [`MutableWorksheet::set_cell_style`] or
+    /// [`CellFormat::style_name`](crate::ooxml::xlsx::CellFormat::style_name).
+    pub fn add_named_style(&mut self, style: NamedStyle) {
+        self.named_styles.retain(|s| s.name != style.name);
+        self.named_styles.push(style);
+        self.modified = true;
+    }
+
+    /// Get all named cell styles.
+    pub fn named_styles(&self) -> &[NamedStyle] {
+        &self.named_styles
+    }
+
+    /// Protect the workbook structure or windows.
+    pub fn protect(&mut self, protection: WorkbookProtection) {
+        self.protection = Some(protection);
+        self.modified = true;
+    }
+
+    /// Remove the workbook protection.
+    pub fn unprotect(&mut self) {
+        self.protection = None;
+        self.modified = true;
+    }
+
     /// Check if the workbook has been modified.
     pub fn is_modified(&self) -> bool {
         self.modified || self.worksheets.iter().any(|ws| ws.is_modified())
@@ -132,12 +188,21 @@ impl MutableWorkbookData {
     /// Build styles from all worksheets and return a StylesBuilder and cell position -> style index mappings.
     ///
     /// Returns a tuple of (StylesBuilder, Vec of per-worksheet CellStyleMap).
-    pub fn build_styles(&self) -> SheetResult<(StylesBuilder, Vec<CellStyleMap>)> {
+    ///
+    /// Differential formats of conditional formatting rules are added too, and
+    /// each sheet records the `dxfId`s its rules were given.
+    pub fn build_styles(&mut self) -> SheetResult<(StylesBuilder, Vec<CellStyleMap>)> {
         let mut builder = StylesBuilder::new();
+        if let Some(source) = &self.styles_source {
+            builder.preserve(source);
+        }
+        for style in &self.named_styles {
+            builder.add_named_style(style);
+        }
         let mut worksheet_style_indices = Vec::new();
 
         // For each worksheet, collect cell formats and build style indices
-        for ws in &self.worksheets {
+        for ws in &mut self.worksheets {
             let mut style_map = CellStyleMap::new();
 
             // Iterate through all cells with formats
@@ -146,6 +211,23 @@ impl MutableWorkbookData {
                 let style_index = builder.add_cell_format(format);
                 style_map.insert(*pos, style_index);
             }
+            for (&row, format) in &ws.row_formats {
+                style_map.insert((row, 0), builder.add_cell_format(format));
+            }
+            for (&col, format) in &ws.column_formats {
+                style_map.insert((0, col), builder.add_cell_format(format));
+            }
+
+            ws.conditional_format_dxfs.clear();
+            for (index, formatting) in ws.conditional_formats.iter().enumerate() {
+                for (rule_index, rule) in formatting.rules.iter().enumerate() {
+                    if let Some(format) = &rule.format {
+                        let dxf_id = builder.add_differential_format(format)?;
+                        ws.conditional_format_dxfs
+                            .insert((index, rule_index), dxf_id);
+                    }
+                }
+            }
 
             worksheet_style_indices.push(style_map);
         }
@@ -164,12 +246,29 @@ impl MutableWorkbookData {
 
     /// Generate workbook.xml content with actual relationship IDs.
     ///
+    /// For a loaded workbook only `workbookProtection`, `sheets` and
+    /// `definedNames` are replaced; every other element of the original
+    /// workbook.xml is kept.
+    ///
     /// # Arguments
     /// * `worksheet_rel_ids` - Vector of relationship IDs for worksheets (e.g., ["rId1", "rId2", ...])
     pub(crate) fn generate_workbook_xml_with_rels(
         &self,
         worksheet_rel_ids: &[String],
     ) -> SheetResult<String> {
+        let mut sheets = String::with_capacity(512);
+        self.write_sheets(&mut sheets, worksheet_rel_ids)?;
+        let mut defined_names = String::new();
+        self.write_defined_names(&mut defined_names)?;
+        let mut protection = String::new();
+        self.write_protection(&mut protection)?;
+
+        if let Some(source) = &self.source_xml
+            && let Some(xml) = splice_workbook_xml(source, &protection, &sheets, &defined_names)?
+        {
+            return Ok(xml);
+        }
+
         let mut xml = String::with_capacity(2048);
 
         xml.push_str(r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>"#);
@@ -181,6 +280,17 @@ impl MutableWorkbookData {
             r#"xmlns:r="http://schemas.openxmlformats.org/officeDocument/2006/relationships">"#,
         );
 
+        xml.push_str(&protection);
+        xml.push_str(&sheets);
+        xml.push_str(&defined_names);
+
+        xml.push_str("</workbook>");
+
+        Ok(xml)
+    }
+
+    /// Write the `sheets` element.
+    fn write_sheets(&self, xml: &mut String, worksheet_rel_ids: &[String]) -> SheetResult<()> {
         xml.push_str("<sheets>");
         for (index, ws) in self.worksheets.iter().enumerate() {
             let sheet_id = ws.sheet_id();
@@ -190,48 +300,176 @@ impl MutableWorkbookData {
                 .unwrap_or("rId1"); // Fallback, shouldn't happen
             write!(
                 xml,
-                r#"<sheet name="{}" sheetId="{}" r:id="{}"/>"#,
+                r#"<sheet name="{}" sheetId="{}""#,
                 escape_xml(ws.name()),
-                sheet_id,
-                rel_id
+                sheet_id
             )
             .map_err(|e| format!("XML write error: {}", e))?;
+            if let Some(state) = ws.source.as_ref().and_then(|s| s.state.as_deref()) {
+                write!(xml, r#" state="{}""#, escape_xml(state))
+                    .map_err(|e| format!("XML write error: {}", e))?;
+            }
+            write!(xml, r#" r:id="{}"/>"#, rel_id)
+                .map_err(|e| format!("XML write error: {}", e))?;
         }
         xml.push_str("</sheets>");
+        Ok(())
+    }
 
-        // Write defined names (named ranges)
-        if !self.named_ranges.is_empty() {
-            xml.push_str("<definedNames>");
-            for named_range in &self.named_ranges {
-                xml.push_str("<definedName name=\"");
-                xml.push_str(&escape_xml(&named_range.name));
-                xml.push('"');
-
-                // Add localSheetId if it's a sheet-scoped name
-                if let Some(sheet_id) = named_range.local_sheet_id {
-                    write!(xml, " localSheetId=\"{}\"", sheet_id - 1)
-                        .map_err(|e| format!("XML write error: {}", e))?;
-                }
-
-                // Add comment if present
-                if let Some(ref comment) = named_range.comment {
-                    write!(xml, " comment=\"{}\"", escape_xml(comment))
-                        .map_err(|e| format!("XML write error: {}", e))?;
-                }
+    /// Write the `workbookProtection` element, if the workbook is protected.
+    fn write_protection(&self, xml: &mut String) -> SheetResult<()> {
+        let Some(protection) = &self.protection else {
+            return Ok(());
+        };
+        xml.push_str("<workbookProtection");
+        if let Some(password) = &protection.password {
+            for (name, value) in password.attributes("workbook") {
+                write!(xml, r#" {}="{}""#, name, escape_xml(&value))
+                    .map_err(|e| format!("XML write error: {}", e))?;
+            }
+        }
+        if protection.lock_structure {
+            xml.push_str(r#" lockStructure="1""#);
+        }
+        if protection.lock_windows {
+            xml.push_str(r#" lockWindows="1""#);
+        }
+        xml.push_str("/>");
+        Ok(())
+    }
 
-                xml.push('>');
-                xml.push_str(&escape_xml(&named_range.reference));
-                xml.push_str("</definedName>");
+    /// Write the `definedNames` element, if there are any named ranges.
+    ///
+    /// Print areas and print titles of the worksheets are written as the
+    /// sheet-scoped `_xlnm.Print_Area` and `_xlnm.Print_Titles` names.
+    fn write_defined_names(&self, xml: &mut String) -> SheetResult<()> {
+        let mut print_names = Vec::new();
+        for (index, ws) in self.worksheets.iter().enumerate() {
+            let sheet = quote_sheet_name(ws.name());
+            let formula = |areas: Vec<&str>| {
+                areas
+                    .iter()
+                    .map(|area| format!("{}!{}", sheet, area))
+                    .collect::<Vec<_>>()
+                    .join(",")
+            };
+            if let Some(area) = &ws.print_area {
+                print_names.push(NamedRange {
+                    name: PRINT_AREA.to_string(),
+                    reference: formula(area.split(',').collect()),
+                    comment: None,
+                    local_sheet_id: Some(index as u32 + 1),
+                });
+            }
+            let titles = ws.print_titles.areas();
+            if !titles.is_empty() {
+                print_names.push(NamedRange {
+                    name: PRINT_TITLES.to_string(),
+                    reference: formula(titles.iter().map(String::as_str).collect()),
+                    comment: None,
+                    local_sheet_id: Some(index as u32 + 1),
+                });
             }
-            xml.push_str("</definedNames>");
         }
 
-        xml.push_str("</workbook>");
+        if self.named_ranges.is_empty() && print_names.is_empty() {
+            return Ok(());
+        }
 
-        Ok(xml)
+        xml.push_str("<definedNames>");
+        for named_range in print_names.iter().chain(&self.named_ranges) {
+            xml.push_str("<definedName name=\"");
+            xml.push_str(&escape_xml(&named_range.name));
+            xml.push('"');
+
+            // Add localSheetId if it's a sheet-scoped name
+            if let Some(sheet_id) = named_range.local_sheet_id {
+                write!(xml, " localSheetId=\"{}\"", sheet_id - 1)
+                    .map_err(|e| format!("XML write error: {}", e))?;
+            }
+
+            // Add comment if present
+            if let Some(ref comment) = named_range.comment {
+                write!(xml, " comment=\"{}\"", escape_xml(comment))
+                    .map_err(|e| format!("XML write error: {}", e))?;
+            }
+
+            xml.push('>');
+            xml.push_str(&escape_xml(&named_range.reference));
+            xml.push_str("</definedName>");
+        }
+        xml.push_str("</definedNames>");
+        Ok(())
     }
 }
 
+/// Replace the `workbookProtection`, `sheets` and `definedNames` elements
+/// of an existing workbook.xml.
+///
+/// Returns `None` if the source has no `sheets` element to replace.
+fn splice_workbook_xml(
+    source: &str,
+    protection: &str,
+    sheets: &str,
+    defined_names: &str,
+) -> SheetResult<Option<String>> {
+    let (_, children) = split_children(source)?;
+    let Some(sheets_range) = children
+        .iter()
+        .find(|child| child.name == "sheets")
+        .map(|child| child.range.clone())
+    else {
+        return Ok(None);
+    };
+
+    // Where a missing element goes: after the last of the children before it
+    let after = |names: &[&str], fallback: usize| {
+        children
+            .iter()
+            .filter(|child| names.contains(&child.name.as_str()))
+            .map(|child| child.range.end)
+            .max()
+            .unwrap_or(fallback)
+    };
+
+    let protection_range = match children
+        .iter()
+        .find(|child| child.name == "workbookProtection")
+    {
+        Some(child) => child.range.clone(),
+        None => {
+            let first = children
+                .first()
+                .map_or(sheets_range.start, |c| c.range.start);
+            let end = after(&["fileVersion", "fileSharing", "workbookPr"], first);
+            end..end
+        },
+    };
+
+    // definedNames follows sheets, functionGroups and externalReferences
+    let names_range = match children.iter().find(|child| child.name == "definedNames") {
+        Some(child) => child.range.clone(),
+        None => {
+            let end = after(
+                &["sheets", "functionGroups", "externalReferences"],
+                sheets_range.end,
+            );
+            end..end
+        },
+    };
+
+    let mut xml =
+        String::with_capacity(source.len() + protection.len() + sheets.len() + defined_names.len());
+    xml.push_str(&source[..protection_range.start]);
+    xml.push_str(protection);
+    xml.push_str(&source[protection_range.end..sheets_range.start]);
+    xml.push_str(sheets);
+    xml.push_str(&source[sheets_range.end..names_range.start]);
+    xml.push_str(defined_names);
+    xml.push_str(&source[names_range.end..]);
+    Ok(Some(xml))
+}
+
 impl Default for MutableWorkbookData {
     fn default() -> Self {
         Self::new()
@@ -473,6 +473,46 @@ impl Presentation {
         Ok(self.cached_metadata.clone())
     }
 
+    /// Get all images embedded in the presentation.
+    ///
+    /// Images come back in a common [`EmbeddedImage`](crate::EmbeddedImage)
+    /// form whatever the source format, anchored to their 0-based slide index
+    /// where the format records placement:
+    ///
+    /// - **PPTX / ODP**: anchored to the slide, with the picture's alt text
+    /// - **PPT**: anchored to the first slide showing the picture
+    /// - **Keynote**: unanchored
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use litchi::{ImageAnchor, Presentation};
+    ///
+    /// let pres = Presentation::open("presentation.pptx")?;
+    /// for image in pres.images()? {
+    ///     if let ImageAnchor::Slide(index) = image.anchor {
+    ///         println!("Slide {}: {} {:?}", index + 1, image.name, image.alt_text);
+    ///     }
+    /// }
+    /// # Ok::<(), litchi::common::Error>(())
+    /// ```
+    pub fn images(&self) -> Result<Vec<crate::common::EmbeddedImage>> {
+        match &self.inner {
+            #[cfg(feature = "ole")]
+            PresentationImpl::Ppt(pres) => pres.images().map_err(Error::from),
+            #[cfg(feature = "ooxml")]
+            PresentationImpl::Pptx(pres) => pres.images().map_err(Error::from),
+            #[cfg(feature = "iwa")]
+            PresentationImpl::Keynote(doc) => doc.bundle().media().embedded_images().map_err(|e| {
+                Error::ParseError(format!("Failed to extract images from Keynote: {}", e))
+            }),
+            #[cfg(feature = "odf")]
+            PresentationImpl::Odp(doc) => doc.images().map_err(|e| {
+                Error::ParseError(format!("Failed to extract images from ODP: {}", e))
+            }),
+        }
+    }
+
     /// Fast text extraction for markdown conversion (internal use).
     ///
     /// This method is optimized for PPT files by skipping shape parsing.
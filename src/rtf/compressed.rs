@@ -242,66 +242,39 @@ pub fn compress(data: &[u8], compress: bool) -> RtfResult<Vec<u8>> {
 
 /// Compress data using LZFu algorithm
 fn compress_lzfu(data: &[u8]) -> RtfResult<Vec<u8>> {
-    // Initialize dictionary
-    let mut dict = vec![0u8; MAX_DICT_SIZE];
-    dict[..INIT_DICT_SIZE].copy_from_slice(INIT_DICT);
-    dict[INIT_DICT_SIZE..].fill(b' ');
-
-    let mut write_offset = INIT_DICT_SIZE;
-    let mut output = Vec::new();
-    let mut cursor = Cursor::new(data);
+    let mut matcher = MatchFinder::new();
+    let mut output = Vec::with_capacity(data.len() / 2 + 16);
+    let mut pos = 0;
 
-    let mut control_byte: u8 = 0;
-    let mut control_bit = 0;
-    let mut token_buffer = Vec::new();
+    // Tokens are emitted in runs of eight, each run preceded by a control
+    // byte whose bits (LSB first) mark dictionary references.
+    'runs: loop {
+        let control_index = output.len();
+        output.push(0u8);
 
-    loop {
-        // Find longest match
-        let (dict_offset, longest_match) = find_longest_match(&mut dict, &mut cursor, write_offset);
-
-        let mut read_buf = vec![0u8; if longest_match > 1 { longest_match } else { 1 }];
-        let bytes_read = cursor.read(&mut read_buf).unwrap_or(0);
-
-        // EOF
-        if bytes_read == 0 {
-            // Add end marker
-            control_byte |= 1 << control_bit;
-
-            let dict_ref = ((write_offset & 0xFFF) << 4) as u16;
-            token_buffer.write_all(&dict_ref.to_be_bytes()).unwrap();
-
-            // Flush final control byte and tokens
-            output.push(control_byte);
-            output.write_all(&token_buffer).unwrap();
-            break;
-        }
-
-        if longest_match > 1 {
-            // Dictionary reference
-            control_byte |= 1 << control_bit;
-            control_bit += 1;
-
-            let dict_ref = (((dict_offset & 0xFFF) << 4) | ((longest_match - 2) & 0xF)) as u16;
-            token_buffer.write_all(&dict_ref.to_be_bytes()).unwrap();
-        } else {
-            // Literal
-            if longest_match == 0 {
-                dict[write_offset] = read_buf[0];
-                write_offset = (write_offset + 1) % MAX_DICT_SIZE;
+        for bit in 0..8 {
+            if pos >= data.len() {
+                // A reference to the current write offset marks the end
+                output[control_index] |= 1 << bit;
+                let end_ref = ((matcher.write_offset & 0xFFF) << 4) as u16;
+                output.extend_from_slice(&end_ref.to_be_bytes());
+                break 'runs;
             }
 
-            control_byte |= 0 << control_bit;
-            control_bit += 1;
-            token_buffer.push(read_buf[0]);
-        }
+            let (offset, length) = matcher.find_longest_match(&data[pos..]);
+            if length >= 2 {
+                output[control_index] |= 1 << bit;
+                let dict_ref = (((offset & 0xFFF) << 4) | ((length - 2) & 0xF)) as u16;
+                output.extend_from_slice(&dict_ref.to_be_bytes());
+            } else {
+                output.push(data[pos]);
+            }
 
-        // Flush when control byte is full
-        if control_bit >= 8 {
-            output.push(control_byte);
-            output.write_all(&token_buffer).unwrap();
-            control_byte = 0;
-            control_bit = 0;
-            token_buffer.clear();
+            let length = length.max(1);
+            for &byte in &data[pos..pos + length] {
+                matcher.push(byte);
+            }
+            pos += length;
         }
     }
 
@@ -317,7 +290,7 @@ fn compress_lzfu(data: &[u8]) -> RtfResult<Vec<u8>> {
     );
 
     // Combine header and compressed data
-    let mut result = Vec::new();
+    let mut result = Vec::with_capacity(output.len() + 16);
     result.write_all(IntoBytes::as_bytes(&header)).unwrap();
     result.write_all(&output).unwrap();
 
@@ -340,67 +313,112 @@ fn compress_uncompressed(data: &[u8]) -> RtfResult<Vec<u8>> {
     Ok(result)
 }
 
-/// Find the longest match in the dictionary
-fn find_longest_match(
-    dict: &mut [u8],
-    stream: &mut Cursor<&[u8]>,
+/// Longest match a dictionary reference can encode (4-bit length + 2).
+const MAX_MATCH_LEN: usize = 17;
+
+/// Maximum number of hash chain entries examined per input position.
+const MAX_CHAIN: usize = 128;
+
+/// Marker for empty hash chain slots.
+const NO_POSITION: usize = usize::MAX;
+
+/// LZFu compression dictionary with hash chains for match lookup.
+///
+/// Positions are indexed by the two bytes starting there. Entries go stale as
+/// the circular dictionary is overwritten, so candidates are always verified
+/// against the current dictionary contents.
+struct MatchFinder {
+    dict: Vec<u8>,
     write_offset: usize,
-) -> (usize, usize) {
-    let start_pos = stream.position();
-
-    // Read first byte
-    let mut byte_buf = [0u8; 1];
-    if stream.read_exact(&mut byte_buf).is_err() {
-        stream.set_position(start_pos);
-        return (0, 0);
+    head: Vec<usize>,
+    prev: Vec<usize>,
+}
+
+impl MatchFinder {
+    fn new() -> Self {
+        let mut dict = vec![b' '; MAX_DICT_SIZE];
+        dict[..INIT_DICT_SIZE].copy_from_slice(INIT_DICT);
+
+        let mut finder = Self {
+            dict,
+            write_offset: INIT_DICT_SIZE,
+            head: vec![NO_POSITION; 1 << 16],
+            prev: vec![NO_POSITION; MAX_DICT_SIZE],
+        };
+        for position in 0..MAX_DICT_SIZE {
+            finder.insert(position);
+        }
+        finder
     }
 
-    let first_byte = byte_buf[0];
-    let prev_write_offset = write_offset;
-    let mut dict_index = 0;
-    let mut match_len = 0;
-    let mut longest_match_len = 0;
-    let mut dict_offset = 0;
+    /// Index the dictionary position `position` under its current two bytes.
+    fn insert(&mut self, position: usize) {
+        let key = ((self.dict[position] as usize) << 8)
+            | self.dict[(position + 1) % MAX_DICT_SIZE] as usize;
+        self.prev[position] = self.head[key];
+        self.head[key] = position;
+    }
 
-    // Search dictionary
-    loop {
-        if dict[dict_index % MAX_DICT_SIZE] == first_byte {
-            match_len += 1;
+    /// Append a byte to the dictionary.
+    fn push(&mut self, byte: u8) {
+        self.dict[self.write_offset] = byte;
+        // The pair starting one byte back now ends with the new byte
+        self.insert((self.write_offset + MAX_DICT_SIZE - 1) % MAX_DICT_SIZE);
+        self.write_offset = (self.write_offset + 1) % MAX_DICT_SIZE;
+    }
 
-            if longest_match_len < match_len && match_len <= 17 {
-                dict_offset = dict_index - match_len + 1;
-                dict[write_offset + match_len - 1] = first_byte;
-                longest_match_len = match_len;
-            }
+    /// Find the longest dictionary match for the start of `input`.
+    ///
+    /// Returns `(offset, length)`; lengths below 2 mean no usable match.
+    fn find_longest_match(&self, input: &[u8]) -> (usize, usize) {
+        let input = &input[..input.len().min(MAX_MATCH_LEN)];
+        if input.len() < 2 {
+            return (0, 0);
+        }
 
-            // Try to extend match
-            if stream.read_exact(&mut byte_buf).is_ok()
-                && dict[(dict_index + 1) % MAX_DICT_SIZE] == byte_buf[0]
-            {
-                dict_index += 1;
-                continue;
-            }
+        let key = ((input[0] as usize) << 8) | input[1] as usize;
+        let mut candidate = self.head[key];
+        let mut best = (0, 0);
 
-            // No more bytes or no match
-            stream.set_position(start_pos + longest_match_len as u64);
-            return (dict_offset, longest_match_len);
+        for _ in 0..MAX_CHAIN {
+            if candidate == NO_POSITION {
+                break;
+            }
+            // A reference to the write offset would be read as the end marker
+            if candidate != self.write_offset {
+                let length = self.match_length(candidate, input);
+                if length > best.1 {
+                    best = (candidate, length);
+                    if length == input.len() {
+                        break;
+                    }
+                }
+            }
+            candidate = self.prev[candidate];
         }
 
-        // Reset match and try next position
-        stream.set_position(start_pos);
-        if stream.read_exact(&mut byte_buf).is_err() {
-            break;
-        }
-        match_len = 0;
+        best
+    }
 
-        dict_index += 1;
-        if dict_index >= prev_write_offset + longest_match_len {
-            break;
+    /// Length of the match at `offset`, accounting for bytes the copy itself
+    /// writes when the source overlaps the write offset.
+    fn match_length(&self, offset: usize, input: &[u8]) -> usize {
+        let mut length = 0;
+        while length < input.len() {
+            let index = (offset + length) % MAX_DICT_SIZE;
+            let distance = (index + MAX_DICT_SIZE - self.write_offset) % MAX_DICT_SIZE;
+            let byte = if distance < length {
+                input[distance]
+            } else {
+                self.dict[index]
+            };
+            if byte != input[length] {
+                break;
+            }
+            length += 1;
         }
+        length
     }
-
-    stream.set_position(start_pos + longest_match_len as u64);
-    (dict_offset, longest_match_len)
 }
 
 #[cfg(test)]
@@ -435,4 +453,26 @@ mod tests {
         let decompressed = decompress(&compressed).unwrap();
         assert_eq!(original, decompressed.as_slice());
     }
+
+    #[test]
+    fn test_round_trip_lzfu() {
+        let mut original = b"{\\rtf1\\ansi\\deff0{\\fonttbl{\\f0 Arial;}}\\f0\\fs20 ".to_vec();
+        for i in 0..500 {
+            original
+                .extend_from_slice(format!("Line {} of the message body\\par\r\n", i).as_bytes());
+        }
+        original.push(b'}');
+
+        let compressed = compress(&original, true).unwrap();
+        assert!(compressed.len() < original.len() / 2);
+        assert_eq!(decompress(&compressed).unwrap(), original);
+    }
+
+    #[test]
+    fn test_lzfu_empty_and_short_input() {
+        for original in [&b""[..], b"a", b"{\\rtf1}"] {
+            let compressed = compress(original, true).unwrap();
+            assert_eq!(decompress(&compressed).unwrap(), original);
+        }
+    }
 }
@@ -94,6 +94,7 @@ impl<'a> RtfDocument<'a> {
                 text: Cow::Owned(block.text.into_owned()),
                 formatting: block.formatting,
                 paragraph: block.paragraph,
+                hyperlink: block.hyperlink.map(|url| Cow::Owned(url.into_owned())),
             })
             .collect();
 
@@ -286,7 +287,10 @@ impl<'a> RtfDocument<'a> {
                     has_content = true;
 
                     // Add run for this part
-                    current_runs.push(Run::new(Cow::Borrowed(part), block.formatting));
+                    current_runs.push(
+                        Run::new(Cow::Borrowed(part), block.formatting)
+                            .with_hyperlink(block.hyperlink.clone()),
+                    );
                 }
 
                 // If this is not the last part, we have a paragraph break
@@ -319,7 +323,10 @@ impl<'a> RtfDocument<'a> {
     pub fn runs(&self) -> Vec<Run<'_>> {
         self.blocks
             .iter()
-            .map(|block| Run::new(block.text.clone(), block.formatting))
+            .map(|block| {
+                Run::new(block.text.clone(), block.formatting)
+                    .with_hyperlink(block.hyperlink.clone())
+            })
             .collect()
     }
 
@@ -431,6 +438,7 @@ impl<'a> RtfDocument<'a> {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::common::{ListFormat, ListInfo};
 
     #[test]
     fn test_simple_document() {
@@ -447,4 +455,49 @@ mod tests {
         let runs = doc.runs();
         assert!(!runs.is_empty());
     }
+
+    #[test]
+    fn test_headings_lists_and_links() {
+        let rtf = r#"{\rtf1\ansi
+{\stylesheet{\ql Normal;}{\s1\ql heading 1;}{\s2\ql\outlinelevel1 Subtitle;}}
+{\*\listtable{\list\listtemplateid7{\listlevel\levelnfc0\levelstartat3{\leveltext\'02\'00.;}}{\listlevel\levelnfc23{\leveltext\'01-;}}\listid42}}
+{\*\listoverridetable{\listoverride\listid42\listoverridecount0\ls1}}
+\pard\s1 Title\par
+\pard\s2 Sub\par
+\pard\ls1 {\listtext 1.\tab}First\par
+\pard\ls1\ilvl1 Nested\par
+\pard See {\field{\*\fldinst {HYPERLINK "https://example.com"}}{\fldrslt {\ul site}}} now\par
+}"#;
+        let doc = RtfDocument::parse(rtf).unwrap();
+        let paragraphs = doc.paragraphs_with_content();
+        assert_eq!(paragraphs.len(), 5);
+
+        assert_eq!(paragraphs[0].properties.heading_level(), Some(1));
+        assert_eq!(paragraphs[1].properties.heading_level(), Some(2));
+        assert_eq!(paragraphs[4].properties.heading_level(), None);
+
+        assert_eq!(paragraphs[2].text(), "First");
+        assert_eq!(
+            paragraphs[2].properties.list_info(),
+            Some(ListInfo::new(0, ListFormat::Decimal).with_start(3))
+        );
+        assert_eq!(
+            paragraphs[3].properties.list_info(),
+            Some(ListInfo::new(1, ListFormat::Bullet))
+        );
+        assert_eq!(paragraphs[4].properties.list_info(), None);
+
+        let runs = paragraphs[4].runs();
+        assert_eq!(paragraphs[4].text(), "See site now");
+        let link = runs.iter().find(|run| run.text() == "site").unwrap();
+        assert_eq!(link.hyperlink(), Some("https://example.com"));
+        assert!(runs[0].hyperlink().is_none());
+    }
+
+    #[test]
+    fn test_spaces_between_groups() {
+        let rtf = r#"{\rtf1\ansi{\b Bold} {\i Italic}  two\par}"#;
+        let doc = RtfDocument::parse(rtf).unwrap();
+        assert_eq!(doc.text().trim_end(), "Bold Italic  two");
+    }
 }
@@ -202,8 +202,6 @@ impl<'a> Lexer<'a> {
 
     /// Get the next token.
     fn next_token(&mut self) -> RtfResult<Token<'a>> {
-        self.skip_whitespace();
-
         if self.pos >= self.input.len() {
             return Err(RtfError::UnexpectedEof);
         }
@@ -597,19 +595,6 @@ impl<'a> Lexer<'a> {
             self.pos += ch.len_utf8();
         }
     }
-
-    /// Skip whitespace (but not newlines, they might be significant).
-    #[inline]
-    fn skip_whitespace(&mut self) {
-        while self.pos < self.input.len() {
-            let ch = self.current_char();
-            if ch == ' ' || ch == '\t' {
-                self.advance();
-            } else {
-                break;
-            }
-        }
-    }
 }
 
 #[cfg(test)]
@@ -628,4 +613,18 @@ mod tests {
         assert!(matches!(tokens[1], Token::Control(ControlWord::Rtf(1))));
         assert!(matches!(tokens[2], Token::Control(ControlWord::Ansi)));
     }
+
+    #[test]
+    fn test_spaces_between_groups_are_text() {
+        let arena = Bump::new();
+        let input = r"{\b Bold} {\i Italic}";
+        let mut lexer = Lexer::new(input, &arena);
+        let tokens = lexer.tokenize().unwrap();
+
+        // The space after a control word is its delimiter; the one between
+        // the groups is content
+        assert!(matches!(tokens[3], Token::CloseBrace));
+        assert!(matches!(&tokens[4], Token::Text(text) if text == " "));
+        assert!(matches!(tokens[5], Token::OpenBrace));
+    }
 }
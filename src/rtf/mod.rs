@@ -1,4 +1,4 @@
-//! RTF (Rich Text Format) parser module.
+//! RTF (Rich Text Format) parser and writer module.
 //!
 //! This module provides high-performance parsing of RTF documents with support
 //! for the RTF 1.9.1 specification. It uses arena allocation (bumpalo) for efficient
@@ -10,6 +10,7 @@
 //! - **Lexer**: Tokenizes RTF input into control words, symbols, and text
 //! - **Parser**: Builds a structured document from tokens
 //! - **Document**: High-level document representation with paragraphs, runs, and tables
+//! - **Writer**: Serializes [`MutableDocument`] or any unified document back to RTF
 //!
 //! # Example
 //!
@@ -31,6 +32,7 @@ mod parser;
 mod picture;
 mod table;
 mod types;
+pub mod writer;
 
 // Re-exports
 pub use compressed::{compress, decompress, is_compressed_rtf};
@@ -44,3 +46,7 @@ pub use types::{
     Alignment, Color, ColorTable, DocumentElement, Font, FontFamily, FontRef, FontTable,
     Formatting, Indentation, Paragraph, ParagraphContent, Run, Spacing, StyleBlock,
 };
+pub use writer::{
+    MutableCell, MutableDocument, MutableParagraph, MutableRow, MutableRun, MutableTable,
+    ParagraphAlignment, ToRtf, VerticalMerge,
+};
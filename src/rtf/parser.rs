@@ -3,12 +3,15 @@
 use super::error::{RtfError, RtfResult};
 use super::lexer::{ControlWord, Token};
 use super::types::*;
+use crate::common::ListFormat;
 use crate::common::encoding::codepage_to_encoding;
+use crate::common::field::hyperlink_target;
 use bumpalo::Bump;
 use encoding_rs::Encoding;
 use smallvec::SmallVec;
 use std::borrow::Cow;
 use std::cell::RefCell;
+use std::collections::HashMap;
 use std::num::NonZeroU16;
 
 /// RTF destination type - determines if we're in document body or header
@@ -99,6 +102,12 @@ pub struct Parser<'a> {
     pictures: Vec<super::picture::Picture<'a>>,
     /// Extracted fields
     fields: Vec<super::field::Field<'a>>,
+    /// Heading level of each heading paragraph style, by `\s` index
+    heading_styles: HashMap<u16, u8>,
+    /// Format and start value of each level of each list, by `\listid`
+    lists: HashMap<i32, Vec<(ListFormat, u32)>>,
+    /// List of each list override, by `\ls` index
+    list_overrides: HashMap<u16, i32>,
 }
 
 impl<'a> Parser<'a> {
@@ -118,6 +127,9 @@ impl<'a> Parser<'a> {
             current_cell_text: SmallVec::new(),
             pictures: Vec::new(),
             fields: Vec::new(),
+            heading_styles: HashMap::new(),
+            lists: HashMap::new(),
+            list_overrides: HashMap::new(),
         }
     }
 
@@ -192,15 +204,35 @@ impl<'a> Parser<'a> {
                     if let Some(state) = self.states.last_mut() {
                         state.destination = Destination::Other;
                     }
-                    self.skip_until_close_brace()?;
+                    // List definitions are needed to resolve list paragraphs
+                    match self.tokens.get(self.pos + 1) {
+                        Some(Token::Control(ControlWord::Unknown("listtable", _))) => {
+                            self.parse_list_table();
+                        },
+                        Some(Token::Control(ControlWord::Unknown("listoverridetable", _))) => {
+                            self.parse_list_override_table();
+                        },
+                        _ => self.skip_until_close_brace()?,
+                    }
                     self.states.pop();
                     return Ok(());
                 },
                 Token::Control(ControlWord::StyleSheet) => {
-                    // Mark as stylesheet destination and skip
+                    // Mark as stylesheet destination; only heading styles are kept
                     if let Some(state) = self.states.last_mut() {
                         state.destination = Destination::StyleSheet;
                     }
+                    self.parse_stylesheet()?;
+                    self.skip_until_close_brace()?;
+                    self.states.pop();
+                    return Ok(());
+                },
+                Token::Control(ControlWord::Unknown("listtext" | "pntext", _)) => {
+                    // Rendered list number; the paragraph's list properties
+                    // describe it instead
+                    if let Some(state) = self.states.last_mut() {
+                        state.destination = Destination::Other;
+                    }
                     self.skip_until_close_brace()?;
                     self.states.pop();
                     return Ok(());
@@ -365,27 +397,9 @@ impl<'a> Parser<'a> {
         // Only create blocks for text in the document body
         // Skip text from font tables, color tables, stylesheets, etc.
         if state.destination == Destination::DocumentBody {
-            // The bytes in the buffer came from a string that was decoded with Windows-1252.
-            // Each character in that string represents a byte value (0x00-0xFF).
-            // We need to recover the original bytes, then decode with the correct encoding.
-            //
-            // Since Windows-1252 characters U+0000-U+00FF map 1:1 to byte values 0x00-0xFF
-            // (with some exceptions in the 0x80-0x9F range), we can reconstruct the
-            // original bytes by taking the lower 8 bits of each character's code point.
-            //
-            // Note: buffer contains UTF-8 bytes of the string. We need to decode to chars first.
-            let original_bytes: SmallVec<[u8; 256]> = std::str::from_utf8(buffer)
-                .unwrap_or("")
-                .chars()
-                .map(|c| c as u8) // Take lower 8 bits
-                .collect();
-
-            // Now decode using the correct encoding
-            let (decoded_str, _, _) = state.encoding.decode(&original_bytes);
-
-            // Allocate in arena and create block
-            let text = self.arena.alloc_str(&decoded_str);
-            let block = StyleBlock::new(Cow::Borrowed(text), state.formatting, state.paragraph);
+            let text = self.decode_text(buffer)?;
+            let paragraph = self.resolve_list(state.paragraph);
+            let block = StyleBlock::new(Cow::Borrowed(text), state.formatting, paragraph);
             self.blocks.push(block);
         }
 
@@ -393,8 +407,54 @@ impl<'a> Parser<'a> {
         Ok(())
     }
 
+    /// Decode buffered text with the current encoding, allocating it in the arena.
+    fn decode_text(&self, buffer: &[u8]) -> RtfResult<&'a str> {
+        // The bytes in the buffer came from a string that was decoded with Windows-1252.
+        // Each character in that string represents a byte value (0x00-0xFF).
+        // We need to recover the original bytes, then decode with the correct encoding.
+        //
+        // Since Windows-1252 characters U+0000-U+00FF map 1:1 to byte values 0x00-0xFF
+        // (with some exceptions in the 0x80-0x9F range), we can reconstruct the
+        // original bytes by taking the lower 8 bits of each character's code point.
+        //
+        // Note: buffer contains UTF-8 bytes of the string. We need to decode to chars first.
+        let original_bytes: SmallVec<[u8; 256]> = std::str::from_utf8(buffer)
+            .unwrap_or("")
+            .chars()
+            .map(|c| c as u8) // Take lower 8 bits
+            .collect();
+
+        // Now decode using the correct encoding
+        let (decoded_str, _, _) = self.current_state()?.encoding.decode(&original_bytes);
+        Ok(self.arena.alloc_str(&decoded_str))
+    }
+
+    /// Resolve the list level format and start value of a paragraph from
+    /// the list tables.
+    fn resolve_list(&self, mut paragraph: Paragraph) -> Paragraph {
+        let level = paragraph
+            .list_override
+            .and_then(|ls| self.list_overrides.get(&ls))
+            .and_then(|list_id| self.lists.get(list_id))
+            .and_then(|levels| {
+                levels
+                    .get(paragraph.list_level as usize)
+                    .or_else(|| levels.first())
+            })
+            .copied();
+        paragraph.list_format = level.map(|(format, _)| format);
+        paragraph.list_start = level.map(|(_, start)| start);
+        paragraph
+    }
+
     /// Apply a control word to the current state.
     fn apply_control_word(&mut self, control: &ControlWord) -> RtfResult<()> {
+        let style_heading = match control {
+            ControlWord::Unknown("s", Some(index)) => {
+                self.heading_styles.get(&(*index as u16)).copied()
+            },
+            _ => None,
+        };
         let state = self.current_state_mut()?;
 
         match control {
@@ -446,6 +506,23 @@ impl<'a> Parser<'a> {
             ControlWord::RightIndent(n) => state.paragraph.indentation.right = *n,
             ControlWord::FirstLineIndent(n) => state.paragraph.indentation.first_line = *n,
 
+            // Paragraph structure
+            ControlWord::Unknown("s", Some(index)) => {
+                state.paragraph.style = Some(*index as u16);
+                if let Some(level) = style_heading {
+                    state.paragraph.outline_level = Some(level - 1);
+                }
+            },
+            ControlWord::Unknown("outlinelevel", Some(level)) => {
+                state.paragraph.outline_level = Some((*level).clamp(0, 9) as u8);
+            },
+            ControlWord::Unknown("ls", Some(index)) => {
+                state.paragraph.list_override = Some(*index as u16);
+            },
+            ControlWord::Unknown("ilvl", Some(level)) => {
+                state.paragraph.list_level = (*level).clamp(0, 8) as u8;
+            },
+
             // Unicode
             ControlWord::UnicodeSkip(n) => state.unicode_skip = *n,
             ControlWord::Unicode(code) => {
@@ -582,6 +659,157 @@ impl<'a> Parser<'a> {
         Ok(())
     }
 
+    /// Parse the stylesheet, keeping the heading level of heading styles.
+    ///
+    /// A style is a heading if it is named "heading 1" to "heading 9" or
+    /// sets `\outlinelevel`.
+    fn parse_stylesheet(&mut self) -> RtfResult<()> {
+        self.pos += 1; // Skip \stylesheet
+
+        while self.pos < self.tokens.len() {
+            match &self.tokens[self.pos] {
+                Token::CloseBrace => return Ok(()),
+                Token::OpenBrace => self.parse_style_entry()?,
+                _ => self.pos += 1,
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Parse a single stylesheet entry.
+    fn parse_style_entry(&mut self) -> RtfResult<()> {
+        self.pos += 1; // Skip {
+
+        // Character, section and table styles are ignorable destinations
+        if matches!(
+            self.tokens.get(self.pos),
+            Some(Token::Control(ControlWord::IgnorableDestination))
+        ) {
+            return self.skip_until_close_brace();
+        }
+
+        let mut index = 0;
+        let mut outline_level = None;
+        let mut name = String::new();
+
+        while self.pos < self.tokens.len() {
+            match &self.tokens[self.pos] {
+                Token::CloseBrace => {
+                    self.pos += 1;
+                    break;
+                },
+                Token::OpenBrace => self.skip_group()?,
+                Token::Control(ControlWord::Unknown("s", Some(n))) => {
+                    index = *n as u16;
+                    self.pos += 1;
+                },
+                Token::Control(ControlWord::Unknown("outlinelevel", Some(level))) => {
+                    outline_level = Some(*level);
+                    self.pos += 1;
+                },
+                Token::Text(text) => {
+                    name.push_str(text);
+                    self.pos += 1;
+                },
+                _ => self.pos += 1,
+            }
+        }
+
+        let name = name
+            .trim()
+            .trim_end_matches(';')
+            .trim()
+            .to_ascii_lowercase();
+        let heading_level = name
+            .strip_prefix("heading ")
+            .and_then(|level| level.parse::<u8>().ok())
+            .filter(|level| (1..=9).contains(level))
+            .or_else(|| {
+                outline_level
+                    .filter(|level| (0..9).contains(level))
+                    .map(|level| level as u8 + 1)
+            });
+        if let Some(level) = heading_level {
+            self.heading_styles.insert(index, level);
+        }
+
+        Ok(())
+    }
+
+    /// Parse the list table (`{\*\listtable ...}`), keeping each list's
+    /// level formats and start values.
+    fn parse_list_table(&mut self) {
+        let mut depth = 1;
+        let mut list_id = None;
+        let mut levels = Vec::new();
+
+        while self.pos < self.tokens.len() && depth > 0 {
+            match &self.tokens[self.pos] {
+                Token::OpenBrace => depth += 1,
+                Token::CloseBrace => {
+                    depth -= 1;
+                    // End of a \list group
+                    if depth == 1 {
+                        let levels = std::mem::take(&mut levels);
+                        if let Some(id) = list_id.take() {
+                            self.lists.insert(id, levels);
+                        }
+                    }
+                },
+                Token::Control(ControlWord::Unknown("listlevel", _)) => {
+                    levels.push((ListFormat::Decimal, 1));
+                },
+                Token::Control(ControlWord::Unknown("levelnfc", Some(nfc))) => {
+                    if let Some((format, _)) = levels.last_mut() {
+                        *format = ListFormat::from_nfc((*nfc).clamp(0, 255) as u8);
+                    }
+                },
+                Token::Control(ControlWord::Unknown("levelstartat", Some(start))) => {
+                    if let Some((_, level_start)) = levels.last_mut() {
+                        *level_start = (*start).max(0) as u32;
+                    }
+                },
+                Token::Control(ControlWord::Unknown("listid", Some(id))) if depth == 2 => {
+                    list_id = Some(*id);
+                },
+                _ => {},
+            }
+            self.pos += 1;
+        }
+    }
+
+    /// Parse the list override table (`{\*\listoverridetable ...}`),
+    /// mapping each `\ls` index to its list.
+    fn parse_list_override_table(&mut self) {
+        let mut depth = 1;
+        let mut list_id = None;
+        let mut index = None;
+
+        while self.pos < self.tokens.len() && depth > 0 {
+            match &self.tokens[self.pos] {
+                Token::OpenBrace => depth += 1,
+                Token::CloseBrace => {
+                    depth -= 1;
+                    // End of a \listoverride group
+                    if depth == 1
+                        && let (Some(id), Some(ls)) = (list_id.take(), index.take())
+                    {
+                        self.list_overrides.insert(ls, id);
+                    }
+                },
+                Token::Control(ControlWord::Unknown("listid", Some(id))) if depth == 2 => {
+                    list_id = Some(*id);
+                },
+                Token::Control(ControlWord::Unknown("ls", Some(ls))) if depth == 2 => {
+                    index = Some(*ls as u16);
+                },
+                _ => {},
+            }
+            self.pos += 1;
+        }
+    }
+
     /// Parse color table.
     fn parse_color_table(&mut self) -> RtfResult<()> {
         self.pos += 1; // Skip \colortbl
@@ -760,7 +988,8 @@ impl<'a> Parser<'a> {
         // Add to document
         let allocated = self.arena.alloc_str(&unicode_str);
         let state = self.current_state()?;
-        let block = StyleBlock::new(Cow::Borrowed(allocated), state.formatting, state.paragraph);
+        let paragraph = self.resolve_list(state.paragraph);
+        let block = StyleBlock::new(Cow::Borrowed(allocated), state.formatting, paragraph);
         self.blocks.push(block);
 
         Ok(())
@@ -919,6 +1148,17 @@ impl<'a> Parser<'a> {
     fn parse_field(&mut self) -> RtfResult<()> {
         self.pos += 1; // Skip \field
 
+        // Where the field sits, before its groups change the destination
+        let (formatting, paragraph, in_body, in_table) = {
+            let state = self.current_state()?;
+            (
+                state.formatting,
+                state.paragraph,
+                state.destination == Destination::DocumentBody,
+                state.in_table,
+            )
+        };
+
         let mut instruction = SmallVec::<[u8; 128]>::new();
         let mut result = SmallVec::<[u8; 128]>::new();
         let mut in_instruction;
@@ -968,12 +1208,17 @@ impl<'a> Parser<'a> {
                             continue;
                         }
 
-                        // Collect text until closing brace
+                        // Collect text until closing brace, including the text
+                        // of nested formatting groups
+                        let mut depth = 0;
                         while self.pos < self.tokens.len() {
                             match &self.tokens[self.pos] {
                                 Token::CloseBrace => {
                                     self.pos += 1;
-                                    break;
+                                    if depth == 0 {
+                                        break;
+                                    }
+                                    depth -= 1;
                                 },
                                 Token::Text(text) => {
                                     if in_instruction {
@@ -984,8 +1229,16 @@ impl<'a> Parser<'a> {
                                     self.pos += 1;
                                 },
                                 Token::OpenBrace => {
-                                    // Skip nested groups
-                                    self.skip_group()?;
+                                    // Skip nested destinations such as \datafield
+                                    if matches!(
+                                        self.tokens.get(self.pos + 1),
+                                        Some(Token::Control(ControlWord::IgnorableDestination))
+                                    ) {
+                                        self.skip_group()?;
+                                    } else {
+                                        depth += 1;
+                                        self.pos += 1;
+                                    }
                                 },
                                 _ => {
                                     self.pos += 1;
@@ -1019,6 +1272,26 @@ impl<'a> Parser<'a> {
                 field.result = Cow::Borrowed(res_alloc);
             }
 
+            // Hyperlink results are part of the text, carrying their target
+            if in_body
+                && !field.result.is_empty()
+                && let Some(url) = hyperlink_target(inst_alloc)
+            {
+                if in_table {
+                    self.current_cell_text
+                        .extend_from_slice(field.result.as_bytes());
+                } else {
+                    let text = self.decode_text(field.result.as_bytes())?;
+                    let mut block = StyleBlock::new(
+                        Cow::Borrowed(text),
+                        formatting,
+                        self.resolve_list(paragraph),
+                    );
+                    block.hyperlink = Some(Cow::Borrowed(self.arena.alloc_str(&url)));
+                    self.blocks.push(block);
+                }
+            }
+
             self.fields.push(field);
         }
 
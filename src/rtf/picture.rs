@@ -9,6 +9,7 @@
 //! - DIB (Device Independent Bitmap)
 //! - BMP
 
+use crate::common::{EmbeddedImage, ImageAnchor};
 use std::borrow::Cow;
 
 /// Image type in RTF documents.
@@ -115,6 +116,69 @@ impl<'a> Picture<'a> {
     pub fn height_pixels(&self, dpi: u32) -> Option<u32> {
         self.computed_height().map(|tw| (tw as u32 * dpi) / 1440)
     }
+
+    /// Convert into a format-independent [`EmbeddedImage`].
+    ///
+    /// The image is named `pictureN.ext` from its 1-based position in the
+    /// document. DIB data gets a `BITMAPFILEHEADER` so it is a valid `.bmp`
+    /// file, and the goal size at 96 DPI is used when the data itself does
+    /// not reveal the dimensions.
+    pub fn to_embedded_image(&self, index: usize) -> EmbeddedImage {
+        let data = match self.image_type {
+            ImageType::Dib => dib_to_bmp(&self.data),
+            _ => self.data.to_vec(),
+        };
+
+        let mut image = EmbeddedImage::new(String::new(), data, ImageAnchor::Unanchored);
+        if self.image_type == ImageType::Pict {
+            image.mime_type = "image/x-pict";
+        }
+        if image.width.is_none() {
+            image.width = self.width_pixels(96);
+            image.height = self.height_pixels(96);
+        }
+        image.name = format!("picture{}.{}", index + 1, image.extension());
+        image
+    }
+}
+
+/// Prefix a packed DIB with a `BITMAPFILEHEADER`.
+fn dib_to_bmp(dib: &[u8]) -> Vec<u8> {
+    let le32 = |at: usize| {
+        dib.get(at..at + 4)
+            .map(|b| u32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+            .unwrap_or(0)
+    };
+    let header_size = le32(0);
+    let bit_count = dib
+        .get(14..16)
+        .map(|b| u16::from_le_bytes([b[0], b[1]]))
+        .unwrap_or(0);
+    let compression = le32(16);
+    let colors_used = le32(32);
+
+    let palette_entries = if colors_used > 0 {
+        colors_used
+    } else if bit_count <= 8 {
+        1 << bit_count
+    } else {
+        0
+    };
+    // BI_BITFIELDS masks follow a plain BITMAPINFOHEADER
+    let masks = if compression == 3 && header_size == 40 {
+        12
+    } else {
+        0
+    };
+    let pixel_offset = 14 + header_size + palette_entries * 4 + masks;
+
+    let mut bmp = Vec::with_capacity(14 + dib.len());
+    bmp.extend_from_slice(b"BM");
+    bmp.extend_from_slice(&(14 + dib.len() as u32).to_le_bytes());
+    bmp.extend_from_slice(&[0; 4]);
+    bmp.extend_from_slice(&pixel_offset.to_le_bytes());
+    bmp.extend_from_slice(dib);
+    bmp
 }
 
 /// Detect image type from binary signature.
@@ -181,6 +245,26 @@ mod tests {
         assert_eq!(detect_image_type(&jpeg_sig), ImageType::Jpeg);
     }
 
+    #[test]
+    fn test_dib_to_embedded_bmp() {
+        // 2x1 24-bit DIB: 40-byte header followed by one padded row
+        let mut dib = Vec::new();
+        for value in [40u32, 2, 1] {
+            dib.extend_from_slice(&value.to_le_bytes());
+        }
+        dib.extend_from_slice(&1u16.to_le_bytes());
+        dib.extend_from_slice(&24u16.to_le_bytes());
+        dib.extend_from_slice(&[0; 24]);
+        dib.extend_from_slice(&[0xFF; 8]);
+
+        let picture = Picture::new(ImageType::Dib, Cow::Owned(dib));
+        let image = picture.to_embedded_image(0);
+        assert_eq!(image.mime_type, "image/bmp");
+        assert_eq!(image.name, "picture1.bmp");
+        assert_eq!((image.width, image.height), (Some(2), Some(1)));
+        assert_eq!(&image.data[10..14], &54u32.to_le_bytes());
+    }
+
     #[test]
     fn test_picture_dimensions() {
         let pic = Picture {
@@ -1,5 +1,6 @@
 //! RTF document type definitions.
 
+use crate::common::{ListFormat, ListInfo};
 use std::borrow::Cow;
 use std::num::NonZeroU16;
 
@@ -215,6 +216,42 @@ pub struct Paragraph {
     pub spacing: Spacing,
     /// Indentation
     pub indentation: Indentation,
+    /// Paragraph style index (`\s`)
+    pub style: Option<u16>,
+    /// Outline level (`\outlinelevel`, 0-based), or the heading level of
+    /// the paragraph style minus one
+    pub outline_level: Option<u8>,
+    /// List override index (`\ls`)
+    pub list_override: Option<u16>,
+    /// List level (`\ilvl`)
+    pub list_level: u8,
+    /// Number format of the list level, resolved from the list tables
+    pub list_format: Option<ListFormat>,
+    /// Start value of the list level (`\levelstartat`), resolved from the
+    /// list tables
+    pub list_start: Option<u32>,
+}
+
+impl Paragraph {
+    /// Get the heading level (1-9) of this paragraph.
+    ///
+    /// Returns `None` for body text.
+    #[inline]
+    pub fn heading_level(&self) -> Option<u8> {
+        self.outline_level
+            .filter(|level| *level < 9)
+            .map(|level| level + 1)
+    }
+
+    /// Get the list membership of this paragraph.
+    ///
+    /// Returns `None` if the paragraph is not in a list.
+    #[inline]
+    pub fn list_info(&self) -> Option<ListInfo> {
+        self.list_format.map(|format| {
+            ListInfo::new(self.list_level, format).with_start(self.list_start.unwrap_or(1))
+        })
+    }
 }
 
 /// Character formatting properties.
@@ -267,13 +304,35 @@ pub struct Run<'a> {
     pub text: Cow<'a, str>,
     /// Character formatting
     pub formatting: Formatting,
+    /// Target of the HYPERLINK field this run is the result of
+    pub hyperlink: Option<Cow<'a, str>>,
 }
 
 impl<'a> Run<'a> {
     /// Create a new run.
     #[inline]
     pub fn new(text: Cow<'a, str>, formatting: Formatting) -> Self {
-        Self { text, formatting }
+        Self {
+            text,
+            formatting,
+            hyperlink: None,
+        }
+    }
+
+    /// Set the hyperlink target of this run.
+    #[inline]
+    pub fn with_hyperlink(mut self, hyperlink: Option<Cow<'a, str>>) -> Self {
+        self.hyperlink = hyperlink;
+        self
+    }
+
+    /// Convert into a run that owns its text.
+    pub fn into_owned(self) -> Run<'static> {
+        Run {
+            text: Cow::Owned(self.text.into_owned()),
+            formatting: self.formatting,
+            hyperlink: self.hyperlink.map(|url| Cow::Owned(url.into_owned())),
+        }
     }
 
     /// Get the text content.
@@ -300,6 +359,18 @@ impl<'a> Run<'a> {
         Some(self.formatting.strike)
     }
 
+    /// Check if this run is underlined.
+    #[inline]
+    pub fn underline(&self) -> Option<bool> {
+        Some(self.formatting.underline)
+    }
+
+    /// Get the font size of this run in half-points.
+    #[inline]
+    pub fn font_size(&self) -> u16 {
+        self.formatting.font_size.get()
+    }
+
     /// Get the vertical position of this run (superscript/subscript).
     #[inline]
     pub fn vertical_position(&self) -> Option<crate::common::style::text::pos::VerticalPosition> {
@@ -311,6 +382,12 @@ impl<'a> Run<'a> {
             None
         }
     }
+
+    /// Get the hyperlink target (URL or `#bookmark`) of this run.
+    #[inline]
+    pub fn hyperlink(&self) -> Option<&str> {
+        self.hyperlink.as_deref()
+    }
 }
 
 /// A styled block of text with paragraph and character formatting.
@@ -322,6 +399,8 @@ pub struct StyleBlock<'a> {
     pub formatting: Formatting,
     /// Text content
     pub text: Cow<'a, str>,
+    /// Target of the HYPERLINK field this block is the result of
+    pub hyperlink: Option<Cow<'a, str>>,
 }
 
 impl<'a> StyleBlock<'a> {
@@ -332,6 +411,7 @@ impl<'a> StyleBlock<'a> {
             text,
             formatting,
             paragraph,
+            hyperlink: None,
         }
     }
 
@@ -0,0 +1,295 @@
+//! Conversion from the unified [`Document`] API to RTF.
+//!
+//! The unified API exposes paragraphs, runs, tables and images for every
+//! supported word processing format. Formatting beyond bold/italic/
+//! strikethrough and vertical position is taken from the format-specific run
+//! where available. Images are placed at the end of the paragraph they are
+//! anchored to, or after the body when the format does not record placement.
+use super::ToRtf;
+use super::document::{MutableDocument, MutableParagraph, MutableRun, ParagraphAlignment};
+use crate::common::{EmbeddedImage, ImageAnchor, Result, TextAlignment};
+use crate::document::{Document, DocumentElement, Paragraph, Run, Table};
+use crate::rtf::picture::{ImageType, detect_image_type};
+use std::collections::HashMap;
+
+/// EMUs per pixel at 96 DPI.
+const EMU_PER_PIXEL: i64 = 9525;
+
+impl MutableDocument {
+    /// Build an RTF document from any unified [`Document`].
+    ///
+    /// The result can be edited further before serialization, e.g. to append
+    /// a signature to an email body.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use litchi::Document;
+    /// use litchi::rtf::MutableDocument;
+    ///
+    /// # fn main() -> litchi::Result<()> {
+    /// let doc = Document::open("letter.doc")?;
+    /// let mut rtf = MutableDocument::from_document(&doc)?;
+    /// rtf.add_paragraph_with_text("Sent from Litchi");
+    /// rtf.save("letter.rtf")?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn from_document(doc: &Document) -> Result<Self> {
+        let mut out = Self::new();
+        out.metadata = doc.metadata()?;
+
+        // Images by the index of their paragraph, counted like paragraphs()
+        let mut anchored: HashMap<usize, Vec<EmbeddedImage>> = HashMap::new();
+        let mut trailing = Vec::new();
+        for image in doc.images()? {
+            if detect_image_type(&image.data) == ImageType::Unknown {
+                continue;
+            }
+            match image.anchor {
+                ImageAnchor::Paragraph(index) => anchored.entry(index).or_default().push(image),
+                _ => trailing.push(image),
+            }
+        }
+
+        let mut next_paragraph = 0;
+        for element in doc.elements()? {
+            match element {
+                DocumentElement::Paragraph(para) => {
+                    let target = out.add_paragraph();
+                    convert_paragraph(&para, target)?;
+                    for image in anchored.remove(&next_paragraph).unwrap_or_default() {
+                        add_image(target, image)?;
+                    }
+                    next_paragraph += 1;
+                },
+                DocumentElement::Table(table) => {
+                    convert_table(&table, &mut out)?;
+                    // Cell paragraphs only need counting when images are anchored
+                    if !anchored.is_empty() {
+                        let end = next_paragraph + table.paragraph_count()?;
+                        let images: Vec<_> = (next_paragraph..end)
+                            .flat_map(|index| anchored.remove(&index).unwrap_or_default())
+                            .collect();
+                        if !images.is_empty() {
+                            let target = out.add_paragraph();
+                            for image in images {
+                                add_image(target, image)?;
+                            }
+                        }
+                        next_paragraph = end;
+                    }
+                },
+            }
+        }
+
+        // Images anchored past the last paragraph go after the body as well
+        let mut rest: Vec<_> = anchored.into_iter().collect();
+        rest.sort_by_key(|(index, _)| *index);
+        trailing.extend(rest.into_iter().flat_map(|(_, images)| images));
+        for image in trailing {
+            add_image(out.add_paragraph(), image)?;
+        }
+
+        Ok(out)
+    }
+}
+
+impl ToRtf for Document {
+    fn to_rtf(&self) -> Result<String> {
+        Ok(MutableDocument::from_document(self)?.to_rtf_string())
+    }
+}
+
+/// Copy the runs, heading level and alignment of a unified paragraph.
+///
+/// Runs with a hyperlink target become `HYPERLINK` fields.
+fn convert_paragraph(para: &Paragraph, target: &mut MutableParagraph) -> Result<()> {
+    if let Some(level) = para.heading_level()? {
+        target.heading_level = Some(level.min(9));
+    }
+    if let Some(alignment) = para.alignment()? {
+        target.set_alignment(match alignment {
+            TextAlignment::Left => ParagraphAlignment::Left,
+            TextAlignment::Center => ParagraphAlignment::Center,
+            TextAlignment::Right => ParagraphAlignment::Right,
+            TextAlignment::Justify => ParagraphAlignment::Justify,
+        });
+    }
+    for run in para.runs()? {
+        let target_run = match run.hyperlink()? {
+            Some(url) => target.add_hyperlink("", &url),
+            None => target.add_run(),
+        };
+        convert_run(&run, target_run)?;
+    }
+    Ok(())
+}
+
+/// Add an image as an inline picture, sized from its pixel dimensions.
+fn add_image(target: &mut MutableParagraph, image: EmbeddedImage) -> Result<()> {
+    let width = image.width.map(|w| w as i64 * EMU_PER_PIXEL);
+    let height = image.height.map(|h| h as i64 * EMU_PER_PIXEL);
+    target.add_picture_from_bytes(image.data, width, height, image.alt_text)
+}
+
+/// Copy a unified table, preserving column spans and vertical merges.
+fn convert_table(table: &Table, doc: &mut MutableDocument) -> Result<()> {
+    let target = doc.add_table(0, 0);
+    for row in table.rows()? {
+        let target_row = target.add_row(0);
+        for cell in row.cells()? {
+            let target_cell = target_row.add_cell();
+            target_cell.set_column_span(cell.grid_span()? as u32);
+
+            #[cfg(feature = "ooxml")]
+            {
+                use super::table::VerticalMerge;
+                use crate::ooxml::docx::VMergeState;
+                target_cell.set_vertical_merge(cell.v_merge()?.map(|state| match state {
+                    VMergeState::Restart => VerticalMerge::Restart,
+                    VMergeState::Continue => VerticalMerge::Continue,
+                }));
+            }
+
+            // Cell text is the only content the unified API exposes for all formats
+            let text = cell.text()?;
+            for line in text.lines() {
+                target_cell.add_paragraph().add_run_with_text(line);
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Copy the text and character formatting of a unified run.
+fn convert_run(run: &Run, target: &mut MutableRun) -> Result<()> {
+    target.set_text(&run.text()?);
+    target
+        .bold(run.bold()?.unwrap_or(false))
+        .italic(run.italic()?.unwrap_or(false))
+        .strikethrough(run.strikethrough()?.unwrap_or(false));
+
+    #[cfg(any(feature = "ole", feature = "ooxml", feature = "iwa"))]
+    {
+        use crate::common::VerticalPosition;
+        match run.vertical_position()? {
+            Some(VerticalPosition::Superscript) => {
+                target.superscript(true);
+            },
+            Some(VerticalPosition::Subscript) => {
+                target.subscript(true);
+            },
+            _ => {},
+        }
+    }
+
+    #[allow(unreachable_patterns)]
+    match run {
+        #[cfg(feature = "ooxml")]
+        Run::Docx(r) => {
+            target.underline(r.underline()?.unwrap_or(false));
+            if let Some(name) = r.font_name()? {
+                target.font_name(&name);
+            }
+            if let Some(half_points) = r.font_size()? {
+                target.font_size(half_points as f64 / 2.0);
+            }
+        },
+        #[cfg(feature = "ole")]
+        Run::Doc(r) => {
+            use crate::ole::doc::parts::chp::UnderlineStyle;
+            let props = r.properties();
+            target
+                .underline(!matches!(props.underline, UnderlineStyle::None))
+                .small_caps(props.is_small_caps.unwrap_or(false));
+            if let Some(half_points) = props.font_size {
+                target.font_size(half_points as f64 / 2.0);
+            }
+            if let Some((red, green, blue)) = props.color {
+                target.color(&format!("{:02X}{:02X}{:02X}", red, green, blue));
+            }
+        },
+        Run::Rtf(r) => {
+            let formatting = &r.formatting;
+            target
+                .underline(formatting.underline)
+                .small_caps(formatting.smallcaps)
+                .superscript(formatting.superscript)
+                .subscript(formatting.subscript);
+            target.font_size(formatting.font_size.get() as f64 / 2.0);
+        },
+        _ => {},
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rtf::RtfDocument;
+
+    #[test]
+    fn test_unified_document_to_rtf() {
+        let source =
+            r"{\rtf1\ansi{\fonttbl\f0\fswiss Helvetica;}\f0\pard Hello {\b bold} {\ul world}\par}";
+        let doc = Document::from_bytes(source.as_bytes().to_vec()).unwrap();
+
+        let rtf = doc.to_rtf().unwrap();
+        let parsed = RtfDocument::parse(&rtf).unwrap();
+        assert_eq!(parsed.text().trim(), "Hello bold world");
+
+        let runs = parsed.runs();
+        assert!(runs.iter().any(|r| r.text() == "bold" && r.formatting.bold));
+        assert!(
+            runs.iter()
+                .any(|r| r.text() == "world" && r.formatting.underline)
+        );
+
+        let compressed = doc.to_compressed_rtf().unwrap();
+        assert!(crate::rtf::is_compressed_rtf(&compressed));
+    }
+
+    #[cfg(feature = "ooxml")]
+    #[test]
+    fn test_headings_links_and_pictures() {
+        use crate::ooxml::docx::{Package, ParagraphAlignment as DocxAlignment};
+        use std::io::Cursor;
+
+        const PNG: &[u8] = &[
+            0x89, 0x50, 0x4E, 0x47, 0x0D, 0x0A, 0x1A, 0x0A, 0x00, 0x00, 0x00, 0x0D, 0x49, 0x48,
+            0x44, 0x52, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x00, 0x01, 0x08, 0x06, 0x00, 0x00,
+            0x00, 0x1F, 0x15, 0xC4, 0x89,
+        ];
+
+        let mut pkg = Package::new().unwrap();
+        {
+            let doc = pkg.document_mut().unwrap();
+            doc.add_paragraph_with_text("Report").set_style("Heading1");
+            let para = doc.add_paragraph();
+            para.set_alignment(DocxAlignment::Center);
+            para.add_run_with_text("See");
+            para.add_hyperlink("site", "https://example.com");
+            para.add_picture_from_bytes(PNG.to_vec(), None, None)
+                .unwrap();
+            doc.add_paragraph_with_text("After");
+        }
+        let mut buffer = Cursor::new(Vec::new());
+        pkg.save_to_writer(&mut buffer).unwrap();
+        let doc = Document::from_bytes(buffer.into_inner()).unwrap();
+
+        let rtf = doc.to_rtf().unwrap();
+        assert!(rtf.contains("\\qc"));
+        // The picture stays in the paragraph it was anchored to
+        assert!(rtf.find("\\pngblip").unwrap() < rtf.find("After").unwrap());
+
+        let parsed = RtfDocument::parse(&rtf).unwrap();
+        assert_eq!(parsed.pictures().len(), 1);
+        let paragraphs = parsed.paragraphs_with_content();
+        assert_eq!(paragraphs[0].properties.heading_level(), Some(1));
+        let runs = paragraphs[1].runs();
+        let link = runs.iter().find(|run| run.text() == "site").unwrap();
+        assert_eq!(link.hyperlink(), Some("https://example.com"));
+    }
+}
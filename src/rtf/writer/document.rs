@@ -0,0 +1,1031 @@
+//! RTF document builder and serializer.
+use super::table::MutableTable;
+use super::{ToRtf, escape_text, write_hex};
+use crate::common::{Error, Metadata, Result};
+use crate::rtf::picture::{ImageType, detect_image_type};
+use chrono::{DateTime, Datelike, Timelike, Utc};
+use std::fmt::Write as FmtWrite;
+use std::path::Path;
+
+/// Font used for `\f0` and unformatted text.
+const DEFAULT_FONT: &str = "Calibri";
+
+/// Default font size in half-points (11pt).
+const DEFAULT_FONT_SIZE: u32 = 22;
+
+/// Color used for hyperlink text without an explicit color.
+const HYPERLINK_COLOR: &str = "0563C1";
+
+/// Heading font sizes in half-points for outline levels 1-9.
+const HEADING_SIZES: [u32; 9] = [32, 28, 26, 24, 24, 24, 22, 22, 22];
+
+/// Stylesheet index of the "Title" style (headings use 1-9).
+const TITLE_STYLE: u8 = 10;
+
+/// Twips per EMU divisor (914400 EMU = 1440 twips).
+const EMU_PER_TWIP: i64 = 635;
+
+/// Generator string written into the header.
+const GENERATOR: &str = concat!("Litchi/", env!("CARGO_PKG_VERSION"));
+
+/// Paragraph alignment.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ParagraphAlignment {
+    Left,
+    Center,
+    Right,
+    Justify,
+}
+
+impl ParagraphAlignment {
+    /// Get the RTF control word.
+    fn as_rtf(&self) -> &'static str {
+        match self {
+            Self::Left => "\\ql",
+            Self::Center => "\\qc",
+            Self::Right => "\\qr",
+            Self::Justify => "\\qj",
+        }
+    }
+}
+
+/// Font and color tables collected while serializing the body.
+///
+/// Font 0 is always the default font and color 0 is the "auto" color, so
+/// explicit colors start at index 1.
+#[derive(Debug)]
+pub(crate) struct ResourceTables {
+    fonts: Vec<String>,
+    colors: Vec<(u8, u8, u8)>,
+}
+
+impl ResourceTables {
+    pub(crate) fn new() -> Self {
+        Self {
+            fonts: vec![DEFAULT_FONT.to_string()],
+            colors: Vec::new(),
+        }
+    }
+
+    /// Get the index of a font, adding it to the table if needed.
+    pub(crate) fn font(&mut self, name: &str) -> usize {
+        if let Some(index) = self.fonts.iter().position(|f| f.eq_ignore_ascii_case(name)) {
+            return index;
+        }
+        self.fonts.push(name.to_string());
+        self.fonts.len() - 1
+    }
+
+    /// Get the index of a hex RGB color, adding it to the table if needed.
+    ///
+    /// Returns `None` for strings that are not 6-digit hex colors.
+    pub(crate) fn color(&mut self, hex: &str) -> Option<usize> {
+        let hex = hex.trim_start_matches('#');
+        if hex.len() != 6 || !hex.is_ascii() {
+            return None;
+        }
+        let component = |i: usize| u8::from_str_radix(&hex[i..i + 2], 16).ok();
+        let rgb = (component(0)?, component(2)?, component(4)?);
+        let index = match self.colors.iter().position(|c| *c == rgb) {
+            Some(index) => index,
+            None => {
+                self.colors.push(rgb);
+                self.colors.len() - 1
+            },
+        };
+        Some(index + 1)
+    }
+
+    /// Write the `\fonttbl` and `\colortbl` groups.
+    fn write_to(&self, out: &mut String) {
+        out.push_str("{\\fonttbl");
+        for (index, name) in self.fonts.iter().enumerate() {
+            write!(out, "{{\\f{}\\{}\\fcharset0 ", index, font_family(name)).unwrap();
+            escape_text(out, name);
+            out.push_str(";}");
+        }
+        out.push('}');
+
+        out.push_str("{\\colortbl;");
+        for (r, g, b) in &self.colors {
+            write!(out, "\\red{}\\green{}\\blue{};", r, g, b).unwrap();
+        }
+        out.push('}');
+    }
+}
+
+/// Guess the RTF font family keyword from a font name.
+fn font_family(name: &str) -> &'static str {
+    let lower = name.to_ascii_lowercase();
+    if ["times", "georgia", "cambria", "garamond", "serif"]
+        .iter()
+        .any(|k| lower.contains(k))
+        && !lower.contains("sans")
+    {
+        "froman"
+    } else if ["courier", "consolas", "mono", "menlo"]
+        .iter()
+        .any(|k| lower.contains(k))
+    {
+        "fmodern"
+    } else if ["symbol", "wingdings", "webdings"]
+        .iter()
+        .any(|k| lower.contains(k))
+    {
+        "ftech"
+    } else {
+        "fswiss"
+    }
+}
+
+/// Character formatting of a run.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub(crate) struct RunProperties {
+    pub bold: bool,
+    pub italic: bool,
+    pub underline: bool,
+    pub strikethrough: bool,
+    pub superscript: bool,
+    pub subscript: bool,
+    pub small_caps: bool,
+    /// Font size in points
+    pub font_size: Option<f64>,
+    pub font_name: Option<String>,
+    /// Text color (hex RGB)
+    pub color: Option<String>,
+    /// Highlight color (hex RGB)
+    pub highlight: Option<String>,
+}
+
+impl RunProperties {
+    /// Check whether any formatting is set.
+    pub(crate) fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// Write the character formatting control words.
+    fn write_to(&self, out: &mut String, tables: &mut ResourceTables) {
+        if self.bold {
+            out.push_str("\\b");
+        }
+        if self.italic {
+            out.push_str("\\i");
+        }
+        if self.underline {
+            out.push_str("\\ul");
+        }
+        if self.strikethrough {
+            out.push_str("\\strike");
+        }
+        if self.superscript {
+            out.push_str("\\super");
+        } else if self.subscript {
+            out.push_str("\\sub");
+        }
+        if self.small_caps {
+            out.push_str("\\scaps");
+        }
+        if let Some(name) = &self.font_name {
+            write!(out, "\\f{}", tables.font(name)).unwrap();
+        }
+        if let Some(size) = self.font_size {
+            write!(out, "\\fs{}", (size * 2.0).round().max(1.0) as u32).unwrap();
+        }
+        if let Some(index) = self.color.as_deref().and_then(|c| tables.color(c)) {
+            write!(out, "\\cf{}", index).unwrap();
+        }
+        if let Some(index) = self.highlight.as_deref().and_then(|c| tables.color(c)) {
+            write!(out, "\\highlight{}", index).unwrap();
+        }
+    }
+}
+
+/// A mutable run of text with character formatting.
+#[derive(Debug, Clone, Default)]
+pub struct MutableRun {
+    /// Run text
+    pub(crate) text: String,
+    /// Character formatting
+    pub(crate) properties: RunProperties,
+}
+
+impl MutableRun {
+    pub(crate) fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the text content.
+    pub fn set_text(&mut self, text: &str) {
+        self.text = text.to_string();
+    }
+
+    /// Get the text content.
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    /// Set bold formatting.
+    pub fn bold(&mut self, bold: bool) -> &mut Self {
+        self.properties.bold = bold;
+        self
+    }
+
+    /// Set italic formatting.
+    pub fn italic(&mut self, italic: bool) -> &mut Self {
+        self.properties.italic = italic;
+        self
+    }
+
+    /// Set single underline.
+    pub fn underline(&mut self, underline: bool) -> &mut Self {
+        self.properties.underline = underline;
+        self
+    }
+
+    /// Set strikethrough.
+    pub fn strikethrough(&mut self, strikethrough: bool) -> &mut Self {
+        self.properties.strikethrough = strikethrough;
+        self
+    }
+
+    /// Set superscript.
+    pub fn superscript(&mut self, superscript: bool) -> &mut Self {
+        self.properties.superscript = superscript;
+        if superscript {
+            self.properties.subscript = false;
+        }
+        self
+    }
+
+    /// Set subscript.
+    pub fn subscript(&mut self, subscript: bool) -> &mut Self {
+        self.properties.subscript = subscript;
+        if subscript {
+            self.properties.superscript = false;
+        }
+        self
+    }
+
+    /// Set small caps.
+    pub fn small_caps(&mut self, small_caps: bool) -> &mut Self {
+        self.properties.small_caps = small_caps;
+        self
+    }
+
+    /// Set font size in points.
+    pub fn font_size(&mut self, size: f64) -> &mut Self {
+        self.properties.font_size = Some(size);
+        self
+    }
+
+    /// Set font family name.
+    pub fn font_name(&mut self, name: &str) -> &mut Self {
+        self.properties.font_name = Some(name.to_string());
+        self
+    }
+
+    /// Set text color (hex RGB, e.g., "FF0000").
+    pub fn color(&mut self, color: &str) -> &mut Self {
+        self.properties.color = Some(color.to_string());
+        self
+    }
+
+    /// Set highlight (background) color (hex RGB, e.g., "FFFF00").
+    pub fn highlight(&mut self, color: &str) -> &mut Self {
+        self.properties.highlight = Some(color.to_string());
+        self
+    }
+
+    /// Serialize the run as a group (or bare text if unformatted).
+    fn write_to(&self, out: &mut String, tables: &mut ResourceTables) {
+        if self.properties.is_empty() {
+            escape_text(out, &self.text);
+        } else {
+            out.push('{');
+            self.properties.write_to(out, tables);
+            out.push(' ');
+            escape_text(out, &self.text);
+            out.push('}');
+        }
+    }
+}
+
+/// A picture embedded in a paragraph.
+#[derive(Debug, Clone)]
+pub(crate) struct InlinePicture {
+    pub(crate) data: Vec<u8>,
+    pub(crate) image_type: ImageType,
+    /// Width in EMUs
+    pub(crate) width_emu: i64,
+    /// Height in EMUs
+    pub(crate) height_emu: i64,
+    pub(crate) description: String,
+}
+
+impl InlinePicture {
+    /// Serialize as a `\pict` group.
+    fn write_to(&self, out: &mut String) {
+        let goal_width = (self.width_emu / EMU_PER_TWIP).max(1);
+        let goal_height = (self.height_emu / EMU_PER_TWIP).max(1);
+
+        out.push_str("{\\pict");
+        if !self.description.is_empty() {
+            out.push_str("{\\*\\picprop{\\sp{\\sn wzDescription}{\\sv ");
+            escape_text(out, &self.description);
+            out.push_str("}}}");
+        }
+
+        // Metafiles measure \picw/\pich in 0.01 mm, bitmaps in pixels (96 DPI)
+        let (keyword, data, width, height) = match self.image_type {
+            ImageType::Png => (
+                "\\pngblip",
+                &self.data[..],
+                goal_width / 15,
+                goal_height / 15,
+            ),
+            ImageType::Jpeg => (
+                "\\jpegblip",
+                &self.data[..],
+                goal_width / 15,
+                goal_height / 15,
+            ),
+            ImageType::Emf => (
+                "\\emfblip",
+                &self.data[..],
+                self.width_emu / 360,
+                self.height_emu / 360,
+            ),
+            // RTF embeds the metafile without its 22-byte placeable header
+            ImageType::Wmf => (
+                "\\wmetafile8",
+                self.data.get(22..).unwrap_or_default(),
+                self.width_emu / 360,
+                self.height_emu / 360,
+            ),
+            // RTF embeds the DIB without its 14-byte BITMAPFILEHEADER
+            ImageType::Dib => (
+                "\\dibitmap0",
+                self.data.get(14..).unwrap_or_default(),
+                goal_width / 15,
+                goal_height / 15,
+            ),
+            ImageType::Pict => (
+                "\\macpict",
+                &self.data[..],
+                goal_width / 15,
+                goal_height / 15,
+            ),
+            ImageType::Unknown => unreachable!("unknown images are rejected when added"),
+        };
+        write!(
+            out,
+            "{}\\picw{}\\pich{}\\picwgoal{}\\pichgoal{}",
+            keyword,
+            width.max(1),
+            height.max(1),
+            goal_width,
+            goal_height
+        )
+        .unwrap();
+        write_hex(out, data);
+        out.push('}');
+    }
+}
+
+/// Elements that can appear in a paragraph.
+#[derive(Debug, Clone)]
+pub(crate) enum ParagraphElement {
+    Run(MutableRun),
+    Hyperlink { url: String, run: MutableRun },
+    Picture(InlinePicture),
+}
+
+/// A mutable paragraph or heading.
+#[derive(Debug, Clone, Default)]
+pub struct MutableParagraph {
+    /// Elements (runs, hyperlinks, pictures) in this paragraph
+    pub(crate) elements: Vec<ParagraphElement>,
+    /// Outline level for headings (0 = title, 1-9 = headings)
+    pub(crate) heading_level: Option<u8>,
+    /// Paragraph alignment
+    pub(crate) alignment: Option<ParagraphAlignment>,
+    /// Space before in points
+    pub(crate) space_before: Option<f64>,
+    /// Space after in points
+    pub(crate) space_after: Option<f64>,
+    /// Left indent in points
+    pub(crate) indent_left: Option<f64>,
+    /// First line indent in points (negative for a hanging indent)
+    pub(crate) indent_first_line: Option<f64>,
+    /// Whether a page break precedes this paragraph
+    pub(crate) page_break_before: bool,
+}
+
+impl MutableParagraph {
+    pub(crate) fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a new run to the paragraph.
+    pub fn add_run(&mut self) -> &mut MutableRun {
+        self.elements.push(ParagraphElement::Run(MutableRun::new()));
+        match self.elements.last_mut().unwrap() {
+            ParagraphElement::Run(r) => r,
+            _ => unreachable!(),
+        }
+    }
+
+    /// Add a run with text.
+    pub fn add_run_with_text(&mut self, text: &str) -> &mut MutableRun {
+        let run = self.add_run();
+        run.set_text(text);
+        run
+    }
+
+    /// Add a hyperlink field and return its run for formatting.
+    ///
+    /// Hyperlinks are underlined and blue unless the run sets its own color.
+    /// A URL of the form `#name` links to a bookmark in the document.
+    pub fn add_hyperlink(&mut self, text: &str, url: &str) -> &mut MutableRun {
+        let mut run = MutableRun::new();
+        run.set_text(text);
+        self.elements.push(ParagraphElement::Hyperlink {
+            url: url.to_string(),
+            run,
+        });
+        match self.elements.last_mut().unwrap() {
+            ParagraphElement::Hyperlink { run, .. } => run,
+            _ => unreachable!(),
+        }
+    }
+
+    /// Add an inline picture from a file.
+    pub fn add_picture(
+        &mut self,
+        image_path: &str,
+        width_emu: Option<i64>,
+        height_emu: Option<i64>,
+    ) -> Result<()> {
+        let data = std::fs::read(image_path)?;
+        self.add_picture_from_bytes(data, width_emu, height_emu, None)
+    }
+
+    /// Add an inline picture from bytes.
+    ///
+    /// PNG, JPEG, EMF, placeable WMF and BMP data are supported. Dimensions
+    /// are in EMUs and default to one inch when not given.
+    pub fn add_picture_from_bytes(
+        &mut self,
+        data: Vec<u8>,
+        width_emu: Option<i64>,
+        height_emu: Option<i64>,
+        description: Option<String>,
+    ) -> Result<()> {
+        let image_type = detect_image_type(&data);
+        if image_type == ImageType::Unknown {
+            return Err(Error::InvalidFormat("Unknown image format".to_string()));
+        }
+        self.elements.push(ParagraphElement::Picture(InlinePicture {
+            data,
+            image_type,
+            width_emu: width_emu.unwrap_or(914400),
+            height_emu: height_emu.unwrap_or(914400),
+            description: description.unwrap_or_default(),
+        }));
+        Ok(())
+    }
+
+    /// Set paragraph alignment.
+    pub fn set_alignment(&mut self, alignment: ParagraphAlignment) {
+        self.alignment = Some(alignment);
+    }
+
+    /// Set space before and after the paragraph in points.
+    pub fn set_spacing(&mut self, before: f64, after: f64) {
+        self.space_before = Some(before);
+        self.space_after = Some(after);
+    }
+
+    /// Set the left and first line indents in points.
+    ///
+    /// A negative first line indent produces a hanging indent.
+    pub fn set_indent(&mut self, left: f64, first_line: f64) {
+        self.indent_left = Some(left);
+        self.indent_first_line = Some(first_line);
+    }
+
+    /// Insert a page break before this paragraph.
+    pub fn set_page_break_before(&mut self, page_break: bool) {
+        self.page_break_before = page_break;
+    }
+
+    /// Get the heading level, if this paragraph is a heading.
+    ///
+    /// Level 0 is a title.
+    pub fn heading_level(&self) -> Option<u8> {
+        self.heading_level
+    }
+
+    /// Get the number of elements in this paragraph.
+    pub fn element_count(&self) -> usize {
+        self.elements.len()
+    }
+
+    /// Get the plain text of the paragraph.
+    pub fn text(&self) -> String {
+        let mut text = String::new();
+        for element in &self.elements {
+            match element {
+                ParagraphElement::Run(run) | ParagraphElement::Hyperlink { run, .. } => {
+                    text.push_str(&run.text)
+                },
+                ParagraphElement::Picture(_) => {},
+            }
+        }
+        text
+    }
+
+    /// Serialize the paragraph, ending it with `terminator` (`\par` or `\cell`).
+    pub(crate) fn write_to(
+        &self,
+        out: &mut String,
+        tables: &mut ResourceTables,
+        in_table: bool,
+        terminator: &str,
+    ) {
+        out.push_str("\\pard\\plain");
+        if in_table {
+            out.push_str("\\intbl");
+        }
+        match self.heading_level {
+            Some(0) => write!(out, "\\s{}", TITLE_STYLE).unwrap(),
+            Some(level) => write!(out, "\\s{}\\outlinelevel{}", level, level - 1).unwrap(),
+            None => {},
+        }
+        if self.page_break_before {
+            out.push_str("\\pagebb");
+        }
+        if let Some(alignment) = self.alignment {
+            out.push_str(alignment.as_rtf());
+        } else if self.heading_level == Some(0) {
+            out.push_str("\\qc");
+        }
+        let (default_before, default_after) = match self.heading_level {
+            Some(_) => (Some(12.0), Some(3.0)),
+            None => (None, None),
+        };
+        if let Some(before) = self.space_before.or(default_before) {
+            write!(out, "\\sb{}", points_to_twips(before)).unwrap();
+        }
+        if let Some(after) = self.space_after.or(default_after) {
+            write!(out, "\\sa{}", points_to_twips(after)).unwrap();
+        }
+        if let Some(left) = self.indent_left {
+            write!(out, "\\li{}", points_to_twips(left)).unwrap();
+        }
+        if let Some(first_line) = self.indent_first_line {
+            write!(out, "\\fi{}", points_to_twips(first_line)).unwrap();
+        }
+
+        // Heading formatting is part of the paragraph's base character formatting
+        match self.heading_level {
+            Some(level) => write!(out, "\\b\\fs{}", heading_size(level)).unwrap(),
+            None => write!(out, "\\fs{}", DEFAULT_FONT_SIZE).unwrap(),
+        }
+        out.push(' ');
+
+        for element in &self.elements {
+            match element {
+                ParagraphElement::Run(run) => run.write_to(out, tables),
+                ParagraphElement::Hyperlink { url, run } => write_hyperlink(out, tables, url, run),
+                ParagraphElement::Picture(picture) => picture.write_to(out),
+            }
+        }
+        out.push_str(terminator);
+        out.push('\n');
+    }
+}
+
+/// Serialize a hyperlink as a `HYPERLINK` field.
+fn write_hyperlink(out: &mut String, tables: &mut ResourceTables, url: &str, run: &MutableRun) {
+    out.push_str("{\\field{\\*\\fldinst HYPERLINK ");
+    // Links to bookmarks in the same document use the \l switch
+    match url.strip_prefix('#') {
+        Some(bookmark) => {
+            out.push_str("\\\\l \"");
+            escape_text(out, &bookmark.replace('"', ""));
+        },
+        None => {
+            out.push('"');
+            escape_text(out, &url.replace('"', "%22"));
+        },
+    }
+    out.push_str("\"}{\\fldrslt ");
+
+    let mut properties = run.properties.clone();
+    properties.underline = true;
+    if properties.color.is_none() {
+        properties.color = Some(HYPERLINK_COLOR.to_string());
+    }
+    properties.write_to(out, tables);
+    out.push(' ');
+    escape_text(out, &run.text);
+    out.push_str("}}");
+}
+
+/// Convert points to twips.
+fn points_to_twips(points: f64) -> i32 {
+    (points * 20.0).round() as i32
+}
+
+/// Get the font size of a heading level in half-points.
+fn heading_size(level: u8) -> u32 {
+    match level {
+        0 => 56,
+        level => HEADING_SIZES[(level as usize - 1).min(HEADING_SIZES.len() - 1)],
+    }
+}
+
+/// Elements that can appear in the document body.
+#[derive(Debug, Clone)]
+pub(crate) enum BodyElement {
+    Paragraph(MutableParagraph),
+    Table(MutableTable),
+}
+
+/// A mutable RTF document.
+///
+/// # Examples
+///
+/// ```no_run
+/// use litchi::rtf::{MutableDocument, ParagraphAlignment};
+///
+/// # fn main() -> litchi::Result<()> {
+/// let mut doc = MutableDocument::new();
+/// doc.add_heading("Quarterly Report", 0);
+///
+/// let para = doc.add_paragraph();
+/// para.set_alignment(ParagraphAlignment::Justify);
+/// para.add_run_with_text("Revenue grew ");
+/// para.add_run_with_text("12%").bold(true);
+///
+/// let table = doc.add_table(2, 2);
+/// table.cell(0, 0).unwrap().set_text("Region");
+/// table.cell(0, 1).unwrap().set_text("Sales");
+///
+/// doc.save("report.rtf")?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct MutableDocument {
+    /// Body content in document order
+    pub(crate) body: Vec<BodyElement>,
+    /// Document metadata written to the `\info` group
+    pub(crate) metadata: Metadata,
+}
+
+impl MutableDocument {
+    /// Create a new empty document.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a new paragraph to the end of the document.
+    pub fn add_paragraph(&mut self) -> &mut MutableParagraph {
+        self.body
+            .push(BodyElement::Paragraph(MutableParagraph::new()));
+        match self.body.last_mut().unwrap() {
+            BodyElement::Paragraph(p) => p,
+            _ => unreachable!(),
+        }
+    }
+
+    /// Add a paragraph with text.
+    pub fn add_paragraph_with_text(&mut self, text: &str) -> &mut MutableParagraph {
+        let para = self.add_paragraph();
+        para.add_run_with_text(text);
+        para
+    }
+
+    /// Add a heading paragraph.
+    ///
+    /// Level 0 produces a title; levels 1-9 produce outline headings.
+    pub fn add_heading(&mut self, text: &str, level: u8) -> &mut MutableParagraph {
+        let para = self.add_paragraph();
+        para.heading_level = Some(level.min(9));
+        para.add_run_with_text(text);
+        para
+    }
+
+    /// Add a table with the specified rows and columns.
+    pub fn add_table(&mut self, rows: usize, cols: usize) -> &mut MutableTable {
+        self.body
+            .push(BodyElement::Table(MutableTable::new(rows, cols)));
+        match self.body.last_mut().unwrap() {
+            BodyElement::Table(t) => t,
+            _ => unreachable!(),
+        }
+    }
+
+    /// Add an empty paragraph that starts on a new page.
+    pub fn add_page_break(&mut self) -> &mut MutableParagraph {
+        let para = self.add_paragraph();
+        para.set_page_break_before(true);
+        para
+    }
+
+    /// Get the number of paragraphs (including headings) in the document body.
+    pub fn paragraph_count(&self) -> usize {
+        self.body
+            .iter()
+            .filter(|e| matches!(e, BodyElement::Paragraph(_)))
+            .count()
+    }
+
+    /// Get the number of tables in the document body.
+    pub fn table_count(&self) -> usize {
+        self.body
+            .iter()
+            .filter(|e| matches!(e, BodyElement::Table(_)))
+            .count()
+    }
+
+    /// Get a reference to the document metadata.
+    pub fn metadata(&self) -> &Metadata {
+        &self.metadata
+    }
+
+    /// Get a mutable reference to the document metadata.
+    pub fn metadata_mut(&mut self) -> &mut Metadata {
+        &mut self.metadata
+    }
+
+    /// Serialize the document as an RTF string.
+    pub fn to_rtf_string(&self) -> String {
+        // The body is written first so that the font and color tables are
+        // complete when the header is assembled.
+        let mut tables = ResourceTables::new();
+        let mut body = String::with_capacity(self.body.len() * 128);
+        for element in &self.body {
+            match element {
+                BodyElement::Paragraph(para) => {
+                    para.write_to(&mut body, &mut tables, false, "\\par")
+                },
+                BodyElement::Table(table) => table.write_to(&mut body, &mut tables),
+            }
+        }
+        // A document must not end inside a table row
+        if matches!(self.body.last(), Some(BodyElement::Table(_))) {
+            writeln!(body, "\\pard\\plain\\fs{} \\par", DEFAULT_FONT_SIZE).unwrap();
+        }
+
+        let mut out = String::with_capacity(body.len() + 2048);
+        out.push_str("{\\rtf1\\ansi\\ansicpg1252\\deff0\\uc1");
+        tables.write_to(&mut out);
+        write_stylesheet(&mut out);
+        write!(out, "{{\\*\\generator {};}}", GENERATOR).unwrap();
+        write_info(&mut out, &self.metadata);
+        out.push_str("\\paperw12240\\paperh15840\\margl1440\\margr1440\\margt1440\\margb1440");
+        out.push_str("\\viewkind4\n");
+        out.push_str(&body);
+        out.push('}');
+        out
+    }
+
+    /// Serialize the document to RTF bytes.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        self.to_rtf_string().into_bytes()
+    }
+
+    /// Save the document to an RTF file.
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<()> {
+        std::fs::write(path, self.to_bytes())?;
+        Ok(())
+    }
+}
+
+impl ToRtf for MutableDocument {
+    fn to_rtf(&self) -> Result<String> {
+        Ok(self.to_rtf_string())
+    }
+}
+
+/// Write the `\stylesheet` group defining Normal, headings and Title.
+fn write_stylesheet(out: &mut String) {
+    write!(
+        out,
+        "{{\\stylesheet{{\\s0\\snext0\\f0\\fs{} Normal;}}",
+        DEFAULT_FONT_SIZE
+    )
+    .unwrap();
+    for level in 1..=9u8 {
+        write!(
+            out,
+            "{{\\s{level}\\sbasedon0\\snext0\\outlinelevel{}\\sb240\\sa60\\b\\fs{} heading {level};}}",
+            level - 1,
+            heading_size(level)
+        )
+        .unwrap();
+    }
+    write!(
+        out,
+        "{{\\s{}\\sbasedon0\\snext0\\qc\\sb240\\sa60\\b\\fs{} Title;}}}}",
+        TITLE_STYLE,
+        heading_size(0)
+    )
+    .unwrap();
+}
+
+/// Write the `\info` group from document metadata.
+fn write_info(out: &mut String, metadata: &Metadata) {
+    let fields = [
+        ("title", &metadata.title),
+        ("subject", &metadata.subject),
+        ("author", &metadata.author),
+        ("manager", &metadata.manager),
+        ("company", &metadata.company),
+        ("operator", &metadata.last_modified_by),
+        ("category", &metadata.category),
+        ("keywords", &metadata.keywords),
+        ("doccomm", &metadata.description),
+    ];
+    let has_dates = metadata.created.is_some() || metadata.modified.is_some();
+    if !has_dates && fields.iter().all(|(_, value)| value.is_none()) {
+        return;
+    }
+
+    out.push_str("{\\info");
+    for (keyword, value) in fields {
+        if let Some(value) = value {
+            write!(out, "{{\\{} ", keyword).unwrap();
+            escape_text(out, value);
+            out.push('}');
+        }
+    }
+    if let Some(created) = &metadata.created {
+        write_time(out, "creatim", created);
+    }
+    if let Some(modified) = &metadata.modified {
+        write_time(out, "revtim", modified);
+    }
+    out.push('}');
+}
+
+/// Write an `\info` date group such as `{\creatim\yr2024\mo1\dy2\hr3\min4}`.
+fn write_time(out: &mut String, keyword: &str, time: &DateTime<Utc>) {
+    write!(
+        out,
+        "{{\\{}\\yr{}\\mo{}\\dy{}\\hr{}\\min{}}}",
+        keyword,
+        time.year(),
+        time.month(),
+        time.day(),
+        time.hour(),
+        time.minute()
+    )
+    .unwrap();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rtf::RtfDocument;
+
+    /// Minimal 1x1 PNG.
+    const PNG: &[u8] = &[
+        0x89, 0x50, 0x4E, 0x47, 0x0D, 0x0A, 0x1A, 0x0A, 0x00, 0x00, 0x00, 0x0D, 0x49, 0x48, 0x44,
+        0x52, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x00, 0x01, 0x08, 0x06, 0x00, 0x00, 0x00, 0x1F,
+        0x15, 0xC4, 0x89,
+    ];
+
+    #[test]
+    fn test_round_trip_text_and_formatting() {
+        let mut doc = MutableDocument::new();
+        doc.add_heading("Title", 1);
+        let para = doc.add_paragraph();
+        para.add_run_with_text("plain ");
+        para.add_run_with_text("bold").bold(true).color("#FF0000");
+        para.add_run_with_text(" caf\u{e9} {x}");
+
+        let rtf = doc.to_rtf_string();
+        assert!(rtf.starts_with("{\\rtf1\\ansi"));
+        assert!(rtf.contains("{\\colortbl;\\red255\\green0\\blue0;}"));
+        assert!(rtf.contains("{\\b\\cf1 bold}"));
+        assert!(rtf.is_ascii());
+
+        let parsed = RtfDocument::parse(&rtf).unwrap();
+        let text = parsed.text();
+        assert!(text.contains("Title"));
+        assert!(text.contains("plain bold caf\u{e9} {x}"));
+        let bold = parsed
+            .runs()
+            .into_iter()
+            .find(|r| r.text() == "bold")
+            .unwrap();
+        assert!(bold.formatting.bold);
+        assert_eq!(
+            parsed.color_table().get(bold.formatting.color_ref),
+            Some(&crate::rtf::Color::new(255, 0, 0))
+        );
+    }
+
+    #[test]
+    fn test_font_table_deduplicates() {
+        let mut doc = MutableDocument::new();
+        let para = doc.add_paragraph();
+        para.add_run_with_text("a").font_name("Courier New");
+        para.add_run_with_text("b").font_name("Courier New");
+        para.add_run_with_text("c").font_name("Calibri");
+
+        let rtf = doc.to_rtf_string();
+        assert!(rtf.contains(
+            "{\\f0\\fswiss\\fcharset0 Calibri;}{\\f1\\fmodern\\fcharset0 Courier New;}}"
+        ));
+        assert_eq!(rtf.matches("{\\f1 a}").count(), 1);
+        assert!(rtf.contains("{\\f0 c}"));
+    }
+
+    #[test]
+    fn test_hyperlink_field() {
+        let mut doc = MutableDocument::new();
+        doc.add_paragraph()
+            .add_hyperlink("docs", "https://example.com/a\"b");
+
+        let rtf = doc.to_rtf_string();
+        assert!(rtf.contains(
+            "{\\field{\\*\\fldinst HYPERLINK \"https://example.com/a%22b\"}{\\fldrslt \\ul\\cf1 docs}}"
+        ));
+
+        let parsed = RtfDocument::parse(&rtf).unwrap();
+        let field = &parsed.fields()[0];
+        assert_eq!(
+            field.extract_url().as_deref(),
+            Some("https://example.com/a%22b")
+        );
+        assert_eq!(field.display_text(), "docs");
+    }
+
+    #[test]
+    fn test_bookmark_hyperlink_field() {
+        let mut doc = MutableDocument::new();
+        doc.add_paragraph().add_hyperlink("intro", "#Intro");
+
+        let rtf = doc.to_rtf_string();
+        assert!(rtf.contains("{\\*\\fldinst HYPERLINK \\\\l \"Intro\"}"));
+
+        let parsed = RtfDocument::parse(&rtf).unwrap();
+        let runs = parsed.runs();
+        let link = runs.iter().find(|run| run.text() == "intro").unwrap();
+        assert_eq!(link.hyperlink(), Some("#Intro"));
+    }
+
+    #[test]
+    fn test_picture_round_trip() {
+        let mut doc = MutableDocument::new();
+        doc.add_paragraph()
+            .add_picture_from_bytes(PNG.to_vec(), Some(914400), Some(457200), None)
+            .unwrap();
+
+        let rtf = doc.to_rtf_string();
+        assert!(rtf.contains("\\pngblip\\picw96\\pich48\\picwgoal1440\\pichgoal720"));
+
+        let parsed = RtfDocument::parse(&rtf).unwrap();
+        let picture = &parsed.pictures()[0];
+        assert_eq!(picture.image_type, ImageType::Png);
+        assert_eq!(picture.data(), PNG);
+        assert_eq!(picture.computed_width(), Some(1440));
+    }
+
+    #[test]
+    fn test_unknown_picture_rejected() {
+        let mut doc = MutableDocument::new();
+        let result =
+            doc.add_paragraph()
+                .add_picture_from_bytes(b"not an image".to_vec(), None, None, None);
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_info_group() {
+        let mut doc = MutableDocument::new();
+        doc.metadata_mut().title = Some("Q3 {Report}".to_string());
+        doc.metadata_mut().author = Some("Finance".to_string());
+        doc.add_paragraph_with_text("body");
+
+        let rtf = doc.to_rtf_string();
+        assert!(rtf.contains("{\\info{\\title Q3 \\{Report\\}}{\\author Finance}}"));
+        assert_eq!(RtfDocument::parse(&rtf).unwrap().text().trim(), "body");
+    }
+
+    #[test]
+    fn test_compressed_rtf_round_trip() {
+        let mut doc = MutableDocument::new();
+        doc.add_paragraph_with_text("Hello from the mail body");
+
+        let compressed = doc.to_compressed_rtf().unwrap();
+        assert!(crate::rtf::is_compressed_rtf(&compressed));
+        let decompressed = crate::rtf::decompress(&compressed).unwrap();
+        assert_eq!(decompressed, doc.to_bytes());
+    }
+}
@@ -0,0 +1,146 @@
+//! RTF serialization.
+//!
+//! This module emits RTF 1.9 documents. Content is built up through
+//! [`MutableDocument`] (mirroring the OOXML and ODF writers) or converted from
+//! any unified [`Document`](crate::Document) through the [`ToRtf`] trait.
+//!
+//! The writer collects fonts and colors while serializing the body and emits
+//! the matching `\fonttbl` and `\colortbl` groups in the header. All non-ASCII
+//! text is written as `\uN` escapes so the output is plain 7-bit ASCII.
+//!
+//! # Examples
+//!
+//! ```no_run
+//! use litchi::rtf::{MutableDocument, ToRtf};
+//!
+//! # fn main() -> litchi::Result<()> {
+//! let mut doc = MutableDocument::new();
+//! doc.add_heading("Status", 1);
+//! let para = doc.add_paragraph();
+//! para.add_run_with_text("Build is ");
+//! para.add_run_with_text("green").bold(true).color("00A000");
+//! para.add_hyperlink("details", "https://example.com/ci");
+//!
+//! // Plain RTF for a message body
+//! let rtf = doc.to_rtf()?;
+//!
+//! // Compressed RTF for PR_RTF_COMPRESSED
+//! let compressed = doc.to_compressed_rtf()?;
+//! # Ok(())
+//! # }
+//! ```
+
+#[cfg(any(feature = "ole", feature = "ooxml"))]
+mod convert;
+mod document;
+mod table;
+
+pub use document::{MutableDocument, MutableParagraph, MutableRun, ParagraphAlignment};
+pub use table::{MutableCell, MutableRow, MutableTable, VerticalMerge};
+
+use super::compressed::compress;
+use crate::common::{Error, Result};
+
+/// Types that can be serialized as RTF.
+///
+/// Implemented for [`MutableDocument`] and, with the `ole` or `ooxml`
+/// feature, the unified [`Document`](crate::Document) API, so any supported
+/// word processing format can be re-emitted as RTF.
+///
+/// # Examples
+///
+/// ```no_run
+/// use litchi::Document;
+/// use litchi::rtf::ToRtf;
+///
+/// # fn main() -> litchi::Result<()> {
+/// let doc = Document::open("report.docx")?;
+/// std::fs::write("report.rtf", doc.to_rtf()?)?;
+/// # Ok(())
+/// # }
+/// ```
+pub trait ToRtf {
+    /// Serialize as an RTF document.
+    fn to_rtf(&self) -> Result<String>;
+
+    /// Serialize as compressed RTF (LZFu), as stored in `PR_RTF_COMPRESSED`.
+    fn to_compressed_rtf(&self) -> Result<Vec<u8>> {
+        let rtf = self.to_rtf()?;
+        compress(rtf.as_bytes(), true)
+            .map_err(|e| Error::Other(format!("Failed to compress RTF: {}", e)))
+    }
+}
+
+/// Append text to RTF output, escaping special and non-ASCII characters.
+///
+/// Tabs and newlines become `\tab` and `\line`; characters outside ASCII are
+/// written as `\uN` with a `?` fallback (UTF-16 surrogate pairs for
+/// characters outside the BMP). Other control characters are dropped.
+pub(crate) fn escape_text(out: &mut String, text: &str) {
+    for ch in text.chars() {
+        match ch {
+            '\\' => out.push_str("\\\\"),
+            '{' => out.push_str("\\{"),
+            '}' => out.push_str("\\}"),
+            '\t' => out.push_str("\\tab "),
+            '\n' => out.push_str("\\line "),
+            '\u{00A0}' => out.push_str("\\~"),
+            '\u{00AD}' => out.push_str("\\-"),
+            '\u{2011}' => out.push_str("\\_"),
+            ' '..='~' => out.push(ch),
+            c if c.is_control() => {},
+            c => {
+                let mut units = [0u16; 2];
+                for unit in c.encode_utf16(&mut units) {
+                    // \u takes a signed 16-bit value
+                    out.push_str("\\u");
+                    out.push_str(&(*unit as i16).to_string());
+                    out.push_str("\\'3f");
+                }
+            },
+        }
+    }
+}
+
+/// Append binary data as lowercase hex, wrapped at 128 characters per line.
+pub(crate) fn write_hex(out: &mut String, data: &[u8]) {
+    const HEX: &[u8; 16] = b"0123456789abcdef";
+    out.reserve(data.len() * 2 + data.len() / 64 + 1);
+    for chunk in data.chunks(64) {
+        out.push('\n');
+        for byte in chunk {
+            out.push(HEX[(byte >> 4) as usize] as char);
+            out.push(HEX[(byte & 0x0F) as usize] as char);
+        }
+    }
+    out.push('\n');
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_escape_text() {
+        let mut out = String::new();
+        escape_text(&mut out, "a\\b{c}\td\ne");
+        assert_eq!(out, "a\\\\b\\{c\\}\\tab d\\line e");
+    }
+
+    #[test]
+    fn test_escape_unicode() {
+        let mut out = String::new();
+        escape_text(&mut out, "caf\u{e9} \u{4e2d} \u{1F600}");
+        assert_eq!(
+            out,
+            "caf\\u233\\'3f \\u20013\\'3f \\u-10179\\'3f\\u-8704\\'3f"
+        );
+    }
+
+    #[test]
+    fn test_write_hex() {
+        let mut out = String::new();
+        write_hex(&mut out, &[0x00, 0xAB, 0xFF]);
+        assert_eq!(out, "\n00abff\n");
+    }
+}
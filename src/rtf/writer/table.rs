@@ -0,0 +1,295 @@
+//! RTF table builder.
+use super::document::{MutableParagraph, ResourceTables};
+use std::fmt::Write as FmtWrite;
+
+/// Default table width in twips (6.5", the text width of a Letter page).
+const DEFAULT_TABLE_WIDTH: i32 = 9360;
+
+/// Vertical merge state of a table cell.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VerticalMerge {
+    /// First cell of a vertically merged range
+    Restart,
+    /// Cell merged into the cell above
+    Continue,
+}
+
+/// A mutable table cell.
+#[derive(Debug, Clone, Default)]
+pub struct MutableCell {
+    /// Paragraphs in this cell
+    pub(crate) paragraphs: Vec<MutableParagraph>,
+    /// Background color (hex RGB)
+    pub(crate) background_color: Option<String>,
+    /// Number of columns this cell spans
+    pub(crate) column_span: u32,
+    /// Vertical merge state
+    pub(crate) vertical_merge: Option<VerticalMerge>,
+}
+
+impl MutableCell {
+    pub(crate) fn new() -> Self {
+        Self {
+            column_span: 1,
+            ..Default::default()
+        }
+    }
+
+    /// Add a paragraph to the cell.
+    pub fn add_paragraph(&mut self) -> &mut MutableParagraph {
+        self.paragraphs.push(MutableParagraph::new());
+        self.paragraphs.last_mut().unwrap()
+    }
+
+    /// Get the number of paragraphs in the cell.
+    pub fn paragraph_count(&self) -> usize {
+        self.paragraphs.len()
+    }
+
+    /// Get a paragraph by index.
+    pub fn paragraph(&mut self, index: usize) -> Option<&mut MutableParagraph> {
+        self.paragraphs.get_mut(index)
+    }
+
+    /// Replace the cell content with a single paragraph of text.
+    pub fn set_text(&mut self, text: &str) {
+        self.paragraphs.clear();
+        self.add_paragraph().add_run_with_text(text);
+    }
+
+    /// Set the cell background color (hex RGB).
+    pub fn set_background_color(&mut self, color: &str) {
+        self.background_color = Some(color.to_string());
+    }
+
+    /// Make this cell span multiple grid columns.
+    pub fn set_column_span(&mut self, span: u32) {
+        self.column_span = span.max(1);
+    }
+
+    /// Set the vertical merge state of this cell.
+    pub fn set_vertical_merge(&mut self, merge: Option<VerticalMerge>) {
+        self.vertical_merge = merge;
+    }
+}
+
+/// A mutable table row.
+#[derive(Debug, Clone, Default)]
+pub struct MutableRow {
+    pub(crate) cells: Vec<MutableCell>,
+    /// Whether this row repeats as a header on each page
+    pub(crate) is_header: bool,
+}
+
+impl MutableRow {
+    pub(crate) fn new(cols: usize) -> Self {
+        Self {
+            cells: (0..cols).map(|_| MutableCell::new()).collect(),
+            is_header: false,
+        }
+    }
+
+    /// Get a cell by index.
+    pub fn cell(&mut self, index: usize) -> Option<&mut MutableCell> {
+        self.cells.get_mut(index)
+    }
+
+    /// Add a cell to the row.
+    pub fn add_cell(&mut self) -> &mut MutableCell {
+        self.cells.push(MutableCell::new());
+        self.cells.last_mut().unwrap()
+    }
+
+    /// Get the number of cells in the row.
+    pub fn cell_count(&self) -> usize {
+        self.cells.len()
+    }
+
+    /// Mark this row as a repeating header row.
+    pub fn set_header(&mut self, is_header: bool) {
+        self.is_header = is_header;
+    }
+}
+
+/// A mutable table.
+#[derive(Debug, Clone, Default)]
+pub struct MutableTable {
+    pub(crate) rows: Vec<MutableRow>,
+    /// Column widths in twips; empty means equal widths
+    pub(crate) column_widths: Vec<i32>,
+}
+
+impl MutableTable {
+    pub(crate) fn new(rows: usize, cols: usize) -> Self {
+        Self {
+            rows: (0..rows).map(|_| MutableRow::new(cols)).collect(),
+            column_widths: Vec::new(),
+        }
+    }
+
+    /// Add a row with the given number of cells.
+    pub fn add_row(&mut self, cols: usize) -> &mut MutableRow {
+        self.rows.push(MutableRow::new(cols));
+        self.rows.last_mut().unwrap()
+    }
+
+    /// Get a cell by row and column index.
+    pub fn cell(&mut self, row: usize, col: usize) -> Option<&mut MutableCell> {
+        self.rows.get_mut(row).and_then(|r| r.cell(col))
+    }
+
+    /// Get the number of rows.
+    pub fn row_count(&self) -> usize {
+        self.rows.len()
+    }
+
+    /// Get a row by index.
+    pub fn row(&mut self, index: usize) -> Option<&mut MutableRow> {
+        self.rows.get_mut(index)
+    }
+
+    /// Get the number of grid columns (widest row, including spans).
+    pub fn column_count(&self) -> usize {
+        self.rows
+            .iter()
+            .map(|r| {
+                r.cells
+                    .iter()
+                    .map(|c| c.column_span as usize)
+                    .sum::<usize>()
+            })
+            .max()
+            .unwrap_or(0)
+    }
+
+    /// Set grid column widths in twips (1/1440 inch).
+    ///
+    /// Columns without an explicit width share the default table width.
+    pub fn set_column_widths(&mut self, widths: Vec<i32>) {
+        self.column_widths = widths;
+    }
+
+    /// Get the right boundary of each grid column in twips.
+    fn column_boundaries(&self) -> Vec<i32> {
+        let cols = self.column_count().max(1);
+        let default_width = DEFAULT_TABLE_WIDTH / cols as i32;
+        let mut right = 0;
+        (0..cols)
+            .map(|i| {
+                right += self
+                    .column_widths
+                    .get(i)
+                    .copied()
+                    .filter(|w| *w > 0)
+                    .unwrap_or(default_width);
+                right
+            })
+            .collect()
+    }
+
+    /// Serialize the table as a sequence of `\trowd ... \row` groups.
+    pub(crate) fn write_to(&self, out: &mut String, tables: &mut ResourceTables) {
+        let boundaries = self.column_boundaries();
+
+        for row in &self.rows {
+            if row.cells.is_empty() {
+                continue;
+            }
+
+            out.push_str("\\trowd\\trgaph108\\trleft0");
+            if row.is_header {
+                out.push_str("\\trhdr");
+            }
+
+            // Cell definitions: spans are expressed by a wider \cellx boundary
+            let mut col = 0usize;
+            for cell in &row.cells {
+                match cell.vertical_merge {
+                    Some(VerticalMerge::Restart) => out.push_str("\\clvmgf"),
+                    Some(VerticalMerge::Continue) => out.push_str("\\clvmrg"),
+                    None => {},
+                }
+                if let Some(index) = cell
+                    .background_color
+                    .as_deref()
+                    .and_then(|c| tables.color(c))
+                {
+                    write!(out, "\\clcbpat{}", index).unwrap();
+                }
+                col += cell.column_span as usize;
+                let right = boundaries
+                    .get(col - 1)
+                    .copied()
+                    .unwrap_or_else(|| boundaries[boundaries.len() - 1]);
+                write!(out, "\\cellx{}", right).unwrap();
+            }
+            out.push('\n');
+
+            // Cell contents
+            for cell in &row.cells {
+                if cell.paragraphs.is_empty() {
+                    out.push_str("\\pard\\plain\\intbl \\cell\n");
+                    continue;
+                }
+                let last = cell.paragraphs.len() - 1;
+                for (i, para) in cell.paragraphs.iter().enumerate() {
+                    let terminator = if i == last { "\\cell" } else { "\\par" };
+                    para.write_to(out, tables, true, terminator);
+                }
+            }
+            out.push_str("\\row\n");
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rtf::RtfDocument;
+    use crate::rtf::writer::MutableDocument;
+
+    #[test]
+    fn test_table_round_trip() {
+        let mut doc = MutableDocument::new();
+        doc.add_paragraph_with_text("Before");
+        let table = doc.add_table(2, 2);
+        table.cell(0, 0).unwrap().set_text("Region");
+        table.cell(0, 1).unwrap().set_text("Sales");
+        table.cell(1, 0).unwrap().set_text("North");
+        table.cell(1, 1).unwrap().set_text("42");
+        table.row(0).unwrap().set_header(true);
+
+        let rtf = doc.to_rtf_string();
+        assert!(rtf.contains("\\trowd\\trgaph108\\trleft0\\trhdr\\cellx4680\\cellx9360"));
+
+        let parsed = RtfDocument::parse(&rtf).unwrap();
+        let tables = parsed.tables();
+        assert_eq!(tables.len(), 1);
+        assert_eq!(tables[0].row_count(), 2);
+        let cells: Vec<&str> = tables[0].rows()[1]
+            .cells()
+            .iter()
+            .map(|c| c.text())
+            .collect();
+        assert_eq!(cells, ["North", "42"]);
+    }
+
+    #[test]
+    fn test_spans_and_shading() {
+        let mut table = MutableTable::new(2, 3);
+        table.set_column_widths(vec![1000, 2000, 3000]);
+        table.rows[0].cells.truncate(2);
+        table.cell(0, 0).unwrap().set_column_span(2);
+        table.cell(0, 1).unwrap().set_background_color("FFFF00");
+        table
+            .cell(1, 2)
+            .unwrap()
+            .set_vertical_merge(Some(VerticalMerge::Continue));
+
+        let mut tables = ResourceTables::new();
+        let mut out = String::new();
+        table.write_to(&mut out, &mut tables);
+        assert!(out.contains("\\cellx3000\\clcbpat1\\cellx6000\n"));
+        assert!(out.contains("\\cellx1000\\cellx3000\\clvmrg\\cellx6000\n"));
+    }
+}
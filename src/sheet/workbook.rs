@@ -2,7 +2,7 @@
 
 use super::types::Result;
 use super::workbook_types::WorkbookImpl;
-use crate::common::{Error, Metadata};
+use crate::common::{EmbeddedImage, Error, Metadata};
 use crate::sheet::WorkbookTrait;
 use std::path::Path;
 
@@ -541,6 +541,142 @@ impl Workbook {
         Ok(self.cached_metadata.clone())
     }
 
+    /// Get all images placed in the workbook.
+    ///
+    /// Images come back in a common [`EmbeddedImage`](crate::EmbeddedImage)
+    /// form whatever the source format. Where the format records placement,
+    /// images are anchored to a worksheet cell (0-based row and column):
+    ///
+    /// - **XLSX / XLSB / ODS**: anchored to the top-left cell of the picture
+    /// - **XLS**: pictures from the drawing group, unanchored
+    /// - **Numbers**: unanchored
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use litchi::sheet::Workbook;
+    /// use litchi::ImageAnchor;
+    ///
+    /// let workbook = Workbook::open("report.xlsx")?;
+    /// for image in workbook.images()? {
+    ///     if let ImageAnchor::Cell { sheet, row, column } = &image.anchor {
+    ///         println!("{}!R{}C{}: {}", sheet, row + 1, column + 1, image.name);
+    ///     }
+    /// }
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn images(&self) -> Result<Vec<EmbeddedImage>> {
+        match &self.inner {
+            #[cfg(feature = "iwa")]
+            WorkbookImpl::Numbers(doc) => doc.bundle().media().embedded_images().map_err(|e| {
+                Box::new(Error::ParseError(format!(
+                    "Failed to extract images from Numbers: {}",
+                    e
+                ))) as Box<dyn std::error::Error>
+            }),
+            #[cfg(feature = "ooxml")]
+            WorkbookImpl::Xlsx(xlsx) => xlsx.images(),
+            #[cfg(feature = "ooxml")]
+            WorkbookImpl::Xlsb(xlsb) => xlsb
+                .images()
+                .map_err(|e| Box::new(e) as Box<dyn std::error::Error>),
+            #[cfg(feature = "ole")]
+            WorkbookImpl::XlsFile(xls) => xls
+                .images()
+                .map_err(|e| Box::new(e) as Box<dyn std::error::Error>),
+            #[cfg(feature = "ole")]
+            WorkbookImpl::XlsMem(xls) => xls
+                .images()
+                .map_err(|e| Box::new(e) as Box<dyn std::error::Error>),
+            #[cfg(feature = "odf")]
+            WorkbookImpl::Ods(ods_ref) => ods_ref
+                .borrow()
+                .images()
+                .map_err(|e| Box::new(e) as Box<dyn std::error::Error>),
+            #[cfg(any(feature = "ole", feature = "ooxml"))]
+            WorkbookImpl::Other => Ok(Vec::new()),
+        }
+    }
+
+    /// Get the displayed text of every worksheet, as the sheet name and its
+    /// rows of cell text.
+    ///
+    /// Formula cells show their cached value when there is one. The rows of
+    /// the tables on a Numbers sheet follow one another.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use litchi::sheet::Workbook;
+    ///
+    /// let workbook = Workbook::open("data.xlsx")?;
+    /// for (name, rows) in workbook.worksheet_rows()? {
+    ///     println!("{}: {} rows", name, rows.len());
+    /// }
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn worksheet_rows(&self) -> Result<Vec<(String, Vec<Vec<String>>)>> {
+        match &self.inner {
+            #[cfg(feature = "iwa")]
+            WorkbookImpl::Numbers(doc) => {
+                let sheets = doc
+                    .sheets()
+                    .map_err(|e| Box::new(e) as Box<dyn std::error::Error>)?;
+                Ok(sheets
+                    .into_iter()
+                    .map(|sheet| {
+                        let rows = sheet
+                            .tables
+                            .iter()
+                            .flat_map(|table| {
+                                (0..table.row_count).map(|row| {
+                                    table
+                                        .get_row(row)
+                                        .iter()
+                                        .map(|cell| cell.as_text())
+                                        .collect()
+                                })
+                            })
+                            .collect();
+                        (sheet.name, rows)
+                    })
+                    .collect())
+            },
+            #[cfg(feature = "ooxml")]
+            WorkbookImpl::Xlsx(xlsx) => trait_worksheet_rows(xlsx),
+            #[cfg(feature = "ooxml")]
+            WorkbookImpl::Xlsb(xlsb) => trait_worksheet_rows(xlsb),
+            #[cfg(feature = "ole")]
+            WorkbookImpl::XlsFile(xls) => trait_worksheet_rows(xls),
+            #[cfg(feature = "ole")]
+            WorkbookImpl::XlsMem(xls) => trait_worksheet_rows(xls),
+            #[cfg(feature = "odf")]
+            WorkbookImpl::Ods(ods_ref) => {
+                let mut ods = ods_ref.borrow_mut();
+                let sheets = ods
+                    .sheets()
+                    .map_err(|e| Box::new(e) as Box<dyn std::error::Error>)?;
+                let mut result = Vec::with_capacity(sheets.len());
+                for sheet in &sheets {
+                    let mut rows = Vec::new();
+                    for row in sheet.rows()? {
+                        let mut cells = Vec::new();
+                        for cell in row.cells()? {
+                            cells.push(cell.text()?.to_string());
+                        }
+                        rows.push(cells);
+                    }
+                    result.push((sheet.name()?.to_string(), rows));
+                }
+                Ok(result)
+            },
+            #[cfg(any(feature = "ole", feature = "ooxml"))]
+            WorkbookImpl::Other => Err(Box::new(Error::ParseError(
+                "Unsupported workbook type in this build".to_string(),
+            )) as Box<dyn std::error::Error>),
+        }
+    }
+
     /// Extract metadata from a Numbers document.
     ///
     /// This extracts metadata from the Numbers bundle, similar to how
@@ -603,3 +739,47 @@ impl Workbook {
         metadata
     }
 }
+
+/// Read the rows of every worksheet of a workbook implementing [`WorkbookTrait`].
+#[cfg(any(feature = "ole", feature = "ooxml"))]
+fn trait_worksheet_rows(workbook: &dyn WorkbookTrait) -> Result<Vec<(String, Vec<Vec<String>>)>> {
+    let mut result = Vec::with_capacity(workbook.worksheet_count());
+    for i in 0..workbook.worksheet_count() {
+        let ws = workbook.worksheet_by_index(i)?;
+        let mut rows = Vec::new();
+        let mut iter = ws.rows();
+        while let Some(row) = iter.next() {
+            rows.push(row?.iter().map(cell_text).collect());
+        }
+        result.push((ws.name().to_string(), rows));
+    }
+    Ok(result)
+}
+
+/// Get the displayed text of a cell, preferring a formula's cached value.
+#[cfg(any(feature = "ole", feature = "ooxml"))]
+fn cell_text(value: &crate::sheet::CellValue) -> String {
+    use crate::sheet::CellValue;
+
+    match value {
+        CellValue::Empty => String::new(),
+        CellValue::Bool(b) => if *b { "TRUE" } else { "FALSE" }.to_string(),
+        CellValue::Int(n) => n.to_string(),
+        CellValue::Float(f) => f.to_string(),
+        CellValue::String(s) => s.clone(),
+        CellValue::DateTime(dt) => dt.to_string(),
+        CellValue::Error(e) => e.clone(),
+        CellValue::Formula {
+            formula,
+            cached_value,
+        } => match cached_value.as_deref() {
+            Some(
+                cached @ (CellValue::String(_)
+                | CellValue::Int(_)
+                | CellValue::Float(_)
+                | CellValue::Bool(_)),
+            ) => cell_text(cached),
+            _ => format!("={}", formula),
+        },
+    }
+}